@@ -102,7 +102,13 @@ fn main() {
         println!("cargo:rerun-if-changed=proto/dd_trace.proto");
         println!("cargo:rerun-if-changed=proto/dnstap.proto");
         println!("cargo:rerun-if-changed=proto/ddsketch.proto");
+        println!("cargo:rerun-if-changed=proto/ddsketch_full.proto");
+        println!("cargo:rerun-if-changed=proto/google/cloud/bigquery/storage/v1");
         println!("cargo:rerun-if-changed=proto/google/pubsub/v1/pubsub.proto");
+        println!("cargo:rerun-if-changed=proto/google/rpc/status.proto");
+        println!("cargo:rerun-if-changed=proto/jaeger/api_v2");
+        println!("cargo:rerun-if-changed=proto/opentelemetry");
+        println!("cargo:rerun-if-changed=proto/pprof/profile.proto");
         println!("cargo:rerun-if-changed=proto/vector.proto");
 
         let mut prost_build = prost_build::Config::new();
@@ -115,8 +121,15 @@ fn main() {
                     "lib/vector-core/proto/event.proto",
                     "proto/dnstap.proto",
                     "proto/ddsketch.proto",
+                    "proto/ddsketch_full.proto",
                     "proto/dd_trace.proto",
+                    "proto/google/cloud/bigquery/storage/v1/storage.proto",
                     "proto/google/pubsub/v1/pubsub.proto",
+                    "proto/jaeger/api_v2/collector.proto",
+                    "proto/opentelemetry/proto/collector/logs/v1/logs_service.proto",
+                    "proto/opentelemetry/proto/collector/metrics/v1/metrics_service.proto",
+                    "proto/opentelemetry/proto/collector/trace/v1/trace_service.proto",
+                    "proto/pprof/profile.proto",
                     "proto/vector.proto",
                 ],
                 &["proto/", "lib/vector-core/proto/"],
@@ -124,6 +137,18 @@ fn main() {
             .unwrap();
     }
 
+    // The eBPF programs are only built for Linux, and require clang.
+    #[cfg(feature = "sources-ebpf")]
+    if env::var("CARGO_CFG_TARGET_OS").as_deref() == Ok("linux") {
+        println!("cargo:rerun-if-changed=src/sources/ebpf/bpf");
+
+        let out_dir = env::var("OUT_DIR").expect("OUT_DIR not present in build script!");
+        libbpf_cargo::SkeletonBuilder::new()
+            .source("src/sources/ebpf/bpf/telemetry.bpf.c")
+            .build_and_generate(Path::new(&out_dir).join("telemetry.skel.rs"))
+            .expect("Failed to build the eBPF programs, is clang installed?");
+    }
+
     // We keep track of which environment variables we slurp in, and then emit stanzas at the end to
     // inform Cargo when it needs to rerun this build script.  This allows us to avoid rerunning it
     // every single time unless something _actually_ changes.
@@ -0,0 +1,188 @@
+use std::convert::TryFrom;
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use smallvec::{smallvec, SmallVec};
+use value::Kind;
+use vector_core::{
+    config::{log_schema, DataType},
+    event::{Event, LogEvent},
+    schema,
+};
+
+use super::Deserializer;
+
+/// Config used to build an `AwsCloudtrailDeserializer`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct AwsCloudtrailDeserializerConfig;
+
+impl AwsCloudtrailDeserializerConfig {
+    /// Build the `AwsCloudtrailDeserializer` from this configuration.
+    pub const fn build(&self) -> AwsCloudtrailDeserializer {
+        AwsCloudtrailDeserializer
+    }
+
+    /// Return the type of event build by this deserializer.
+    pub fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    /// The schema produced by the deserializer.
+    pub fn schema_definition(&self) -> schema::Definition {
+        schema::Definition::empty()
+            .required_field(
+                log_schema().timestamp_key(),
+                Kind::timestamp(),
+                Some("timestamp"),
+            )
+            // Fields of log records.
+            .optional_field("eventVersion", Kind::bytes(), None)
+            .optional_field("eventTime", Kind::bytes(), None)
+            .optional_field("eventSource", Kind::bytes(), Some("service"))
+            .optional_field("eventName", Kind::bytes(), None)
+            .optional_field("awsRegion", Kind::bytes(), None)
+            .optional_field("sourceIPAddress", Kind::bytes(), None)
+            .optional_field("userAgent", Kind::bytes(), None)
+            // Fields of digest files.
+            .optional_field("digestStartTime", Kind::bytes(), None)
+            .optional_field("digestEndTime", Kind::bytes(), None)
+            .optional_field("digestS3Bucket", Kind::bytes(), None)
+            .optional_field("digestS3Object", Kind::bytes(), None)
+            .unknown_fields(Kind::json())
+    }
+}
+
+/// Deserializer that builds `Event`s from an AWS CloudTrail log file or digest
+/// file.
+///
+/// A log file holds its events in a `Records` array, and each of them becomes
+/// an event timestamped by its `eventTime`. A digest file becomes a single
+/// event, timestamped by its `digestEndTime`.
+#[derive(Debug, Clone)]
+pub struct AwsCloudtrailDeserializer;
+
+impl Deserializer for AwsCloudtrailDeserializer {
+    fn parse(&self, bytes: Bytes) -> vector_core::Result<SmallVec<[Event; 1]>> {
+        // Files are framed as a whole, but trailing newlines can still make for
+        // empty frames.
+        if bytes.iter().all(u8::is_ascii_whitespace) {
+            return Ok(smallvec![]);
+        }
+
+        let mut file: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_slice(&bytes)
+                .map_err(|error| format!("Error parsing CloudTrail file: {:?}", error))?;
+
+        match file.remove("Records") {
+            Some(serde_json::Value::Array(records)) => records
+                .into_iter()
+                .map(|record| to_event(record, "eventTime"))
+                .collect(),
+            Some(_) => Err("CloudTrail `Records` is not an array.".into()),
+            None if file.contains_key("digestEndTime") => {
+                Ok(smallvec![to_event(file.into(), "digestEndTime")?])
+            }
+            None => Err("Not a CloudTrail log file or digest file.".into()),
+        }
+    }
+}
+
+fn to_event(value: serde_json::Value, time_field: &str) -> vector_core::Result<Event> {
+    let mut log = LogEvent::try_from(value)?;
+
+    let timestamp = log
+        .get(time_field)
+        .and_then(|time| time.as_bytes())
+        .and_then(|time| std::str::from_utf8(time).ok())
+        .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+        .map(|time| time.with_timezone(&Utc))
+        .ok_or_else(|| format!("CloudTrail `{}` is missing or invalid.", time_field))?;
+    log.insert(log_schema().timestamp_key(), timestamp);
+
+    Ok(log.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use indoc::indoc;
+    use vector_core::event::Value;
+
+    use super::*;
+
+    #[test]
+    fn deserialize_log_file() {
+        let input = Bytes::from(indoc! {r#"
+            {"Records": [
+                {
+                    "eventVersion": "1.08",
+                    "eventTime": "2022-06-01T12:30:15Z",
+                    "eventSource": "s3.amazonaws.com",
+                    "eventName": "GetObject",
+                    "requestParameters": { "bucketName": "my-bucket" }
+                },
+                {
+                    "eventVersion": "1.08",
+                    "eventTime": "2022-06-01T12:30:16Z",
+                    "eventSource": "ec2.amazonaws.com",
+                    "eventName": "DescribeInstances"
+                }
+            ]}
+        "#});
+
+        let events = AwsCloudtrailDeserializer.parse(input).unwrap();
+        assert_eq!(events.len(), 2);
+
+        let log = events[0].as_log();
+        assert_eq!(log["eventName"], "GetObject".into());
+        assert_eq!(log["requestParameters.bucketName"], "my-bucket".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Value::Timestamp(Utc.ymd(2022, 6, 1).and_hms(12, 30, 15))
+        );
+        assert_eq!(events[1].as_log()["eventName"], "DescribeInstances".into());
+    }
+
+    #[test]
+    fn deserialize_digest_file() {
+        let input = Bytes::from(indoc! {r#"
+            {
+                "awsAccountId": "111122223333",
+                "digestStartTime": "2022-06-01T11:27:05Z",
+                "digestEndTime": "2022-06-01T12:27:05Z",
+                "digestS3Bucket": "my-bucket",
+                "digestS3Object": "AWSLogs/111122223333/CloudTrail-Digest/us-east-1/2022/06/01/111122223333_CloudTrail-Digest_us-east-1_trail_us-east-1_20220601T122705Z.json.gz",
+                "logFiles": []
+            }
+        "#});
+
+        let events = AwsCloudtrailDeserializer.parse(input).unwrap();
+        assert_eq!(events.len(), 1);
+
+        let log = events[0].as_log();
+        assert_eq!(log["digestS3Bucket"], "my-bucket".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Value::Timestamp(Utc.ymd(2022, 6, 1).and_hms(12, 27, 5))
+        );
+    }
+
+    #[test]
+    fn deserialize_empty_frame() {
+        let events = AwsCloudtrailDeserializer.parse(Bytes::from("\n")).unwrap();
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn deserialize_other_json() {
+        assert!(AwsCloudtrailDeserializer
+            .parse(Bytes::from(r#"{ "foo": "bar" }"#))
+            .is_err());
+        assert!(AwsCloudtrailDeserializer
+            .parse(Bytes::from(
+                r#"{ "Records": [{ "eventName": "GetObject" }] }"#
+            ))
+            .is_err());
+    }
+}
@@ -0,0 +1,262 @@
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use smallvec::{smallvec, SmallVec};
+use value::Kind;
+use vector_core::{
+    config::{log_schema, DataType},
+    event::{Event, LogEvent, Value},
+    schema,
+};
+
+use super::Deserializer;
+
+/// The fields of a log record, in the order they're written in.
+///
+/// See: https://docs.aws.amazon.com/AmazonS3/latest/userguide/LogFormat.html
+const FIELDS: &[(&str, FieldType)] = &[
+    ("bucket_owner", FieldType::String),
+    ("bucket", FieldType::String),
+    ("time", FieldType::Timestamp),
+    ("remote_ip", FieldType::String),
+    ("requester", FieldType::String),
+    ("request_id", FieldType::String),
+    ("operation", FieldType::String),
+    ("key", FieldType::String),
+    ("request_uri", FieldType::String),
+    ("http_status", FieldType::Integer),
+    ("error_code", FieldType::String),
+    ("bytes_sent", FieldType::Integer),
+    ("object_size", FieldType::Integer),
+    ("total_time", FieldType::Integer),
+    ("turn_around_time", FieldType::Integer),
+    ("referer", FieldType::String),
+    ("user_agent", FieldType::String),
+    ("version_id", FieldType::String),
+    ("host_id", FieldType::String),
+    ("signature_version", FieldType::String),
+    ("cipher_suite", FieldType::String),
+    ("authentication_type", FieldType::String),
+    ("host_header", FieldType::String),
+    ("tls_version", FieldType::String),
+    ("access_point_arn", FieldType::String),
+    ("acl_required", FieldType::String),
+];
+
+/// Records written before the later fields were introduced end after the
+/// user agent.
+const MIN_FIELDS: usize = 17;
+
+const TIME_FORMAT: &str = "%d/%b/%Y:%H:%M:%S %z";
+
+#[derive(Debug, Clone, Copy)]
+enum FieldType {
+    String,
+    Integer,
+    Timestamp,
+}
+
+/// Config used to build an `AwsS3AccessLogDeserializer`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct AwsS3AccessLogDeserializerConfig;
+
+impl AwsS3AccessLogDeserializerConfig {
+    /// Build the `AwsS3AccessLogDeserializer` from this configuration.
+    pub const fn build(&self) -> AwsS3AccessLogDeserializer {
+        AwsS3AccessLogDeserializer
+    }
+
+    /// Return the type of event build by this deserializer.
+    pub fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    /// The schema produced by the deserializer.
+    pub fn schema_definition(&self) -> schema::Definition {
+        FIELDS.iter().fold(
+            schema::Definition::empty(),
+            |definition, (name, field_type)| match field_type {
+                // The time of the request is the timestamp of the event.
+                FieldType::Timestamp => definition.required_field(
+                    log_schema().timestamp_key(),
+                    Kind::timestamp(),
+                    Some("timestamp"),
+                ),
+                FieldType::String => definition.optional_field(*name, Kind::bytes(), None),
+                FieldType::Integer => definition.optional_field(*name, Kind::integer(), None),
+            },
+        )
+    }
+}
+
+/// Deserializer that builds an `Event` from a byte frame containing a record of
+/// an AWS S3 server access log.
+///
+/// Fields whose value is `-` are left out of the event, and the fields AWS may
+/// add to the format in the future are ignored.
+#[derive(Debug, Clone)]
+pub struct AwsS3AccessLogDeserializer;
+
+impl Deserializer for AwsS3AccessLogDeserializer {
+    fn parse(&self, bytes: Bytes) -> vector_core::Result<SmallVec<[Event; 1]>> {
+        let line = std::str::from_utf8(&bytes)?.trim();
+        if line.is_empty() {
+            return Ok(smallvec![]);
+        }
+
+        let values = split(line)?;
+        if values.len() < MIN_FIELDS {
+            return Err(format!(
+                "S3 access log record has {} fields, expected at least {}.",
+                values.len(),
+                MIN_FIELDS
+            )
+            .into());
+        }
+
+        let mut log = LogEvent::default();
+        for ((name, field_type), value) in FIELDS.iter().zip(values) {
+            if value == "-" {
+                continue;
+            }
+            let value = match field_type {
+                FieldType::String => Value::from(value.to_owned()),
+                FieldType::Integer => Value::from(value.parse::<i64>().map_err(|error| {
+                    format!("S3 access log field `{}` is invalid: {}", name, error)
+                })?),
+                FieldType::Timestamp => {
+                    let timestamp = DateTime::parse_from_str(value, TIME_FORMAT)
+                        .map_err(|error| {
+                            format!("S3 access log field `{}` is invalid: {}", name, error)
+                        })?
+                        .with_timezone(&Utc);
+                    log.insert(log_schema().timestamp_key(), timestamp);
+                    continue;
+                }
+            };
+            log.insert(*name, value);
+        }
+
+        Ok(smallvec![log.into()])
+    }
+}
+
+/// Splits a record into its values, which are separated by spaces, unless
+/// they're enclosed in brackets or double quotes.
+fn split(line: &str) -> vector_core::Result<Vec<&str>> {
+    let mut values = Vec::new();
+    let mut rest = line.trim_start();
+
+    while !rest.is_empty() {
+        let (value, remainder) = match rest.as_bytes()[0] {
+            b'[' => enclosed(rest, ']')?,
+            b'"' => enclosed(rest, '"')?,
+            _ => rest.split_at(rest.find(' ').unwrap_or(rest.len())),
+        };
+        values.push(value);
+        rest = remainder.trim_start();
+    }
+
+    Ok(values)
+}
+
+/// Splits off a value enclosed by its first character and `close`, without
+/// them. The value ends at the first `close` followed by a space, since quoted
+/// values such as user agents can contain quotes themselves.
+fn enclosed(rest: &str, close: char) -> vector_core::Result<(&str, &str)> {
+    let inner = &rest[1..];
+    let mut offset = 0;
+    loop {
+        match inner[offset..].find(close) {
+            Some(index) => {
+                let end = offset + index;
+                let remainder = &inner[end + 1..];
+                if remainder.is_empty() || remainder.starts_with(' ') {
+                    return Ok((&inner[..end], remainder));
+                }
+                offset = end + 1;
+            }
+            None => {
+                return Err(format!("S3 access log value is missing a closing `{}`.", close).into())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn deserialize_record() {
+        let input = Bytes::from(
+            r#"79a59df900b949e55d96a1e698fbacedfd6e09d98eacf8f8d5218e7cd47ef2be awsexamplebucket1 [06/Feb/2019:00:00:38 +0000] 192.0.2.3 79a59df900b949e55d96a1e698fbacedfd6e09d98eacf8f8d5218e7cd47ef2be 3E57427F3EXAMPLE REST.GET.VERSIONING - "GET /awsexamplebucket1?versioning HTTP/1.1" 200 - 113 - 7 - "-" "S3Console/0.4 (compatible; "quoted")" - s9lzHYrFp76ZVxRcpX9+5cjAnEH2ROuNkd2BHfIa6UkFVdtjf5mKR3/eTPFvsiP/XV/VLi31234= SigV2 ECDHE-RSA-AES128-GCM-SHA256 AuthHeader awsexamplebucket1.s3.us-west-1.amazonaws.com TLSV1.1 - Yes"#,
+        );
+
+        let events = AwsS3AccessLogDeserializer.parse(input).unwrap();
+        assert_eq!(events.len(), 1);
+
+        let log = events[0].as_log();
+        assert_eq!(log["bucket"], "awsexamplebucket1".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Value::Timestamp(Utc.ymd(2019, 2, 6).and_hms(0, 0, 38))
+        );
+        assert_eq!(log["operation"], "REST.GET.VERSIONING".into());
+        assert_eq!(
+            log["request_uri"],
+            "GET /awsexamplebucket1?versioning HTTP/1.1".into()
+        );
+        assert_eq!(log["http_status"], 200.into());
+        assert_eq!(log["bytes_sent"], 113.into());
+        assert_eq!(log["total_time"], 7.into());
+        assert_eq!(
+            log["user_agent"],
+            r#"S3Console/0.4 (compatible; "quoted")"#.into()
+        );
+        assert_eq!(log["tls_version"], "TLSV1.1".into());
+        assert_eq!(log["acl_required"], "Yes".into());
+        assert!(log.get("key").is_none());
+        assert!(log.get("error_code").is_none());
+        assert!(log.get("object_size").is_none());
+        assert!(log.get("referer").is_none());
+        assert!(log.get("access_point_arn").is_none());
+    }
+
+    #[test]
+    fn deserialize_legacy_record() {
+        let input = Bytes::from(
+            r#"owner bucket [06/Feb/2019:01:00:38 +0100] 192.0.2.3 - 3E57427F3EXAMPLE REST.GET.OBJECT photos/2019/08/puppy.jpg "GET /bucket/photos/2019/08/puppy.jpg HTTP/1.1" 404 NoSuchKey 291 - 11 - "https://example.com/" "curl/7.15.1""#,
+        );
+
+        let events = AwsS3AccessLogDeserializer.parse(input).unwrap();
+        let log = events[0].as_log();
+        assert_eq!(log["key"], "photos/2019/08/puppy.jpg".into());
+        assert_eq!(log["error_code"], "NoSuchKey".into());
+        assert_eq!(log["referer"], "https://example.com/".into());
+        assert_eq!(log["user_agent"], "curl/7.15.1".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Value::Timestamp(Utc.ymd(2019, 2, 6).and_hms(0, 0, 38))
+        );
+    }
+
+    #[test]
+    fn deserialize_invalid_record() {
+        let deserializer = AwsS3AccessLogDeserializer;
+        assert!(deserializer.parse(Bytes::from("")).unwrap().is_empty());
+        assert!(deserializer.parse(Bytes::from("owner bucket")).is_err());
+        assert!(deserializer
+            .parse(Bytes::from(
+                r#"owner bucket [06/Feb/2019:00:00:38 +0000 192.0.2.3"#
+            ))
+            .is_err());
+        assert!(deserializer
+            .parse(Bytes::from(
+                r#"owner bucket [06/Feb/2019:00:00:38 +0000] 192.0.2.3 - 3E57427F3EXAMPLE REST.GET.OBJECT key "GET /key HTTP/1.1" OK - 291 - 11 - "-" "curl/7.15.1""#
+            ))
+            .is_err());
+    }
+}
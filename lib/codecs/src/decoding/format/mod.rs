@@ -3,6 +3,8 @@
 
 #![deny(missing_docs)]
 
+mod aws_cloudtrail;
+mod aws_s3_access_log;
 mod bytes;
 mod json;
 mod native;
@@ -13,6 +15,8 @@ mod syslog;
 use std::fmt::Debug;
 
 use ::bytes::Bytes;
+pub use aws_cloudtrail::{AwsCloudtrailDeserializer, AwsCloudtrailDeserializerConfig};
+pub use aws_s3_access_log::{AwsS3AccessLogDeserializer, AwsS3AccessLogDeserializerConfig};
 use dyn_clone::DynClone;
 pub use json::{JsonDeserializer, JsonDeserializerConfig};
 pub use native::{NativeDeserializer, NativeDeserializerConfig};
@@ -40,10 +40,24 @@ pub struct OctetCountingDecoderOptions {
 
 /// Codec using the `Octet Counting` format as specified in
 /// https://tools.ietf.org/html/rfc6587#section-3.4.1.
+///
+/// By default, each frame starting with a non-zero digit is decoded as octet counted, and the
+/// other ones as newline delimited.
 #[derive(Clone, Debug)]
 pub struct OctetCountingDecoder {
     other: LinesCodec,
     octet_decoding: Option<State>,
+    detection: Detection,
+}
+
+/// How octet counted frames are told apart from newline delimited ones.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Detection {
+    /// Each frame is octet counted when it starts with a non-zero digit.
+    PerFrame,
+    /// The frames of the stream are octet counted when its first frame starts with a non-zero
+    /// digit, which is `None` until the first byte is received.
+    PerStream(Option<bool>),
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -59,6 +73,7 @@ impl OctetCountingDecoder {
         Self {
             other: LinesCodec::new(),
             octet_decoding: None,
+            detection: Detection::PerFrame,
         }
     }
 
@@ -67,9 +82,23 @@ impl OctetCountingDecoder {
         Self {
             other: LinesCodec::new_with_max_length(max_length),
             octet_decoding: None,
+            detection: Detection::PerFrame,
         }
     }
 
+    /// Detects the framing once for the whole stream, from its first frame, so that the newline
+    /// delimited frames starting with a digit aren't mistaken for octet counted ones.
+    pub const fn detect_per_stream(mut self) -> Self {
+        self.detection = Detection::PerStream(None);
+        self
+    }
+
+    /// Decodes all the frames of the stream as octet counted.
+    pub const fn octet_counted_only(mut self) -> Self {
+        self.detection = Detection::PerStream(Some(true));
+        self
+    }
+
     /// Decode a frame.
     fn octet_decode(
         &mut self,
@@ -235,13 +264,38 @@ impl OctetCountingDecoder {
         &mut self,
         src: &mut BytesMut,
     ) -> Option<Result<Option<Bytes>, LinesCodecError>> {
-        if let Some(&first_byte) = src.get(0) {
-            if (49..=57).contains(&first_byte) {
-                // First character is non zero number so we can assume that
-                // octet count framing is used.
-                trace!("Octet counting encoded event detected.");
-                self.octet_decoding = Some(State::NotDiscarding);
+        match self.detection {
+            Detection::PerFrame => {
+                if let Some(&first_byte) = src.get(0) {
+                    if (49..=57).contains(&first_byte) {
+                        // First character is non zero number so we can assume that
+                        // octet count framing is used.
+                        trace!("Octet counting encoded event detected.");
+                        self.octet_decoding = Some(State::NotDiscarding);
+                    }
+                }
             }
+            Detection::PerStream(None) => {
+                if let Some(&first_byte) = src.get(0) {
+                    let octet_counting = (49..=57).contains(&first_byte);
+                    trace!(message = "Framing of the stream detected.", octet_counting);
+                    self.detection = Detection::PerStream(Some(octet_counting));
+                    return self.checked_decode(src);
+                }
+            }
+            Detection::PerStream(Some(true)) => {
+                if self.octet_decoding.is_none() {
+                    // Some senders terminate octet counted frames with a newline, which is
+                    // skipped between frames.
+                    let skipped = src
+                        .iter()
+                        .take_while(|&&b| b == b'\n' || b == b'\r')
+                        .count();
+                    src.advance(skipped);
+                    self.octet_decoding = Some(State::NotDiscarding);
+                }
+            }
+            Detection::PerStream(Some(false)) => {}
         }
 
         self.octet_decoding
@@ -397,6 +451,48 @@ mod tests {
         assert_eq!(b"and here we are"[..], buffer);
     }
 
+    #[test]
+    fn per_stream_detection_keeps_newline_framing() {
+        let mut decoder = OctetCountingDecoder::new_with_max_length(128).detect_per_stream();
+        let mut buffer = BytesMut::with_capacity(64);
+
+        buffer.put(&b"<13>first message\n2022-06-01 second message\n"[..]);
+        assert_eq!(
+            decoder.decode(&mut buffer).map_err(|_| ()),
+            Ok(Some("<13>first message".into()))
+        );
+        assert_eq!(
+            decoder.decode(&mut buffer).map_err(|_| ()),
+            Ok(Some("2022-06-01 second message".into()))
+        );
+    }
+
+    #[test]
+    fn per_stream_detection_keeps_octet_counting() {
+        let mut decoder = OctetCountingDecoder::new_with_max_length(128).detect_per_stream();
+        let mut buffer = BytesMut::with_capacity(64);
+
+        buffer.put(&b"5 first\n6 second"[..]);
+        assert_eq!(
+            decoder.decode(&mut buffer).map_err(|_| ()),
+            Ok(Some("first".into()))
+        );
+        assert_eq!(
+            decoder.decode(&mut buffer).map_err(|_| ()),
+            Ok(Some("second".into()))
+        );
+        assert_eq!(decoder.decode(&mut buffer).map_err(|_| ()), Ok(None));
+    }
+
+    #[test]
+    fn octet_counted_only_rejects_newline_framing() {
+        let mut decoder = OctetCountingDecoder::new_with_max_length(128).octet_counted_only();
+        let mut buffer = BytesMut::with_capacity(64);
+
+        buffer.put(&b"<13>message\n"[..]);
+        assert!(decoder.decode(&mut buffer).is_err());
+    }
+
     #[test]
     fn octet_decode_moves_past_exceeded_frame_length_multiple_frames() {
         let mut decoder = OctetCountingDecoder::new_with_max_length(16);
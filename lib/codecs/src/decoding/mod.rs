@@ -10,9 +10,10 @@ use std::fmt::Debug;
 use bytes::{Bytes, BytesMut};
 pub use error::StreamDecodingError;
 pub use format::{
-    BoxedDeserializer, BytesDeserializer, BytesDeserializerConfig, JsonDeserializer,
-    JsonDeserializerConfig, NativeDeserializer, NativeDeserializerConfig, NativeJsonDeserializer,
-    NativeJsonDeserializerConfig,
+    AwsCloudtrailDeserializer, AwsCloudtrailDeserializerConfig, AwsS3AccessLogDeserializer,
+    AwsS3AccessLogDeserializerConfig, BoxedDeserializer, BytesDeserializer,
+    BytesDeserializerConfig, JsonDeserializer, JsonDeserializerConfig, NativeDeserializer,
+    NativeDeserializerConfig, NativeJsonDeserializer, NativeJsonDeserializerConfig,
 };
 #[cfg(feature = "syslog")]
 pub use format::{SyslogDeserializer, SyslogDeserializerConfig};
@@ -230,6 +231,10 @@ pub enum DeserializerConfig {
     Native,
     /// Configures the `NativeJsonDeserializer`.
     NativeJson,
+    /// Configures the `AwsCloudtrailDeserializer`.
+    AwsCloudtrail,
+    /// Configures the `AwsS3AccessLogDeserializer`.
+    AwsS3AccessLog,
 }
 
 impl From<BytesDeserializerConfig> for DeserializerConfig {
@@ -251,6 +256,18 @@ impl From<SyslogDeserializerConfig> for DeserializerConfig {
     }
 }
 
+impl From<AwsCloudtrailDeserializerConfig> for DeserializerConfig {
+    fn from(_: AwsCloudtrailDeserializerConfig) -> Self {
+        Self::AwsCloudtrail
+    }
+}
+
+impl From<AwsS3AccessLogDeserializerConfig> for DeserializerConfig {
+    fn from(_: AwsS3AccessLogDeserializerConfig) -> Self {
+        Self::AwsS3AccessLog
+    }
+}
+
 impl DeserializerConfig {
     /// Build the `Deserializer` from this configuration.
     pub fn build(&self) -> Deserializer {
@@ -263,6 +280,12 @@ impl DeserializerConfig {
             DeserializerConfig::NativeJson => {
                 Deserializer::NativeJson(NativeJsonDeserializerConfig.build())
             }
+            DeserializerConfig::AwsCloudtrail => {
+                Deserializer::AwsCloudtrail(AwsCloudtrailDeserializerConfig.build())
+            }
+            DeserializerConfig::AwsS3AccessLog => {
+                Deserializer::AwsS3AccessLog(AwsS3AccessLogDeserializerConfig.build())
+            }
         }
     }
 
@@ -270,9 +293,12 @@ impl DeserializerConfig {
     pub fn default_stream_framing(&self) -> FramingConfig {
         match self {
             DeserializerConfig::Native => FramingConfig::LengthDelimited,
+            // CloudTrail files are JSON documents which aren't split into lines.
+            DeserializerConfig::AwsCloudtrail => FramingConfig::Bytes,
             DeserializerConfig::Bytes
             | DeserializerConfig::Json
-            | DeserializerConfig::NativeJson => FramingConfig::NewlineDelimited {
+            | DeserializerConfig::NativeJson
+            | DeserializerConfig::AwsS3AccessLog => FramingConfig::NewlineDelimited {
                 newline_delimited: Default::default(),
             },
             #[cfg(feature = "syslog")]
@@ -291,6 +317,8 @@ impl DeserializerConfig {
             DeserializerConfig::Syslog => SyslogDeserializerConfig.output_type(),
             DeserializerConfig::Native => NativeDeserializerConfig.output_type(),
             DeserializerConfig::NativeJson => NativeJsonDeserializerConfig.output_type(),
+            DeserializerConfig::AwsCloudtrail => AwsCloudtrailDeserializerConfig.output_type(),
+            DeserializerConfig::AwsS3AccessLog => AwsS3AccessLogDeserializerConfig.output_type(),
         }
     }
 
@@ -303,6 +331,12 @@ impl DeserializerConfig {
             DeserializerConfig::Syslog => SyslogDeserializerConfig.schema_definition(),
             DeserializerConfig::Native => NativeDeserializerConfig.schema_definition(),
             DeserializerConfig::NativeJson => NativeJsonDeserializerConfig.schema_definition(),
+            DeserializerConfig::AwsCloudtrail => {
+                AwsCloudtrailDeserializerConfig.schema_definition()
+            }
+            DeserializerConfig::AwsS3AccessLog => {
+                AwsS3AccessLogDeserializerConfig.schema_definition()
+            }
         }
     }
 }
@@ -321,6 +355,10 @@ pub enum Deserializer {
     Native(NativeDeserializer),
     /// Uses a `NativeDeserializer` for deserialization.
     NativeJson(NativeJsonDeserializer),
+    /// Uses an `AwsCloudtrailDeserializer` for deserialization.
+    AwsCloudtrail(AwsCloudtrailDeserializer),
+    /// Uses an `AwsS3AccessLogDeserializer` for deserialization.
+    AwsS3AccessLog(AwsS3AccessLogDeserializer),
     /// Uses an opaque `Deserializer` implementation for deserialization.
     Boxed(BoxedDeserializer),
 }
@@ -334,6 +372,8 @@ impl format::Deserializer for Deserializer {
             Deserializer::Syslog(deserializer) => deserializer.parse(bytes),
             Deserializer::Native(deserializer) => deserializer.parse(bytes),
             Deserializer::NativeJson(deserializer) => deserializer.parse(bytes),
+            Deserializer::AwsCloudtrail(deserializer) => deserializer.parse(bytes),
+            Deserializer::AwsS3AccessLog(deserializer) => deserializer.parse(bytes),
             Deserializer::Boxed(deserializer) => deserializer.parse(bytes),
         }
     }
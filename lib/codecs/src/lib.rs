@@ -7,9 +7,10 @@ pub mod decoding;
 pub mod encoding;
 
 pub use decoding::{
-    BytesDecoder, BytesDecoderConfig, BytesDeserializer, BytesDeserializerConfig,
-    CharacterDelimitedDecoder, CharacterDelimitedDecoderConfig, JsonDeserializer,
-    JsonDeserializerConfig, LengthDelimitedDecoder, LengthDelimitedDecoderConfig,
+    AwsCloudtrailDeserializer, AwsCloudtrailDeserializerConfig, AwsS3AccessLogDeserializer,
+    AwsS3AccessLogDeserializerConfig, BytesDecoder, BytesDecoderConfig, BytesDeserializer,
+    BytesDeserializerConfig, CharacterDelimitedDecoder, CharacterDelimitedDecoderConfig,
+    JsonDeserializer, JsonDeserializerConfig, LengthDelimitedDecoder, LengthDelimitedDecoderConfig,
     NativeDeserializer, NativeDeserializerConfig, NativeJsonDeserializer,
     NativeJsonDeserializerConfig, NewlineDelimitedDecoder, NewlineDelimitedDecoderConfig,
     OctetCountingDecoder, OctetCountingDecoderConfig, StreamDecodingError,
@@ -71,6 +71,11 @@ pub trait Table: DynClone {
 
     /// Returns true if the underlying data has changed and the table needs reloading.
     fn needs_reload(&self) -> bool;
+
+    /// Returns the number of rows in the table, for tables that hold their data in memory.
+    fn row_count(&self) -> Option<usize> {
+        None
+    }
 }
 
 dyn_clone::clone_trait_object!(Table);
@@ -43,10 +43,14 @@ use crate::Case;
 /// A hashmap of name => implementation of an enrichment table.
 type TableMap = HashMap<String, Box<dyn Table + Send + Sync>>;
 
+/// The read only tables. Each table is shared so that a single table can be replaced without
+/// copying the others.
+type SharedTableMap = HashMap<String, Arc<dyn Table + Send + Sync>>;
+
 #[derive(Clone, Default)]
 pub struct TableRegistry {
     loading: Arc<Mutex<Option<TableMap>>>,
-    tables: Arc<ArcSwap<Option<TableMap>>>,
+    tables: Arc<ArcSwap<Option<SharedTableMap>>>,
 }
 
 impl TableRegistry {
@@ -86,7 +90,7 @@ impl TableRegistry {
             let extend = existing
                 .iter()
                 .filter(|(key, _)| !tables.contains_key(*key))
-                .map(|(key, value)| (key.clone(), value.clone()))
+                .map(|(key, value)| (key.clone(), dyn_clone::clone_box(&**value)))
                 .collect::<HashMap<_, _>>();
 
             tables.extend(extend);
@@ -107,10 +111,43 @@ impl TableRegistry {
     /// Panics if the Mutex is poisoned.
     pub fn finish_load(&self) {
         let mut tables_lock = self.loading.lock().unwrap();
-        let tables = tables_lock.take();
+        let tables = tables_lock.take().map(|tables| {
+            tables
+                .into_iter()
+                .map(|(key, table)| (key, Arc::from(table)))
+                .collect()
+        });
         self.tables.swap(Arc::new(tables));
     }
 
+    /// Replace a single table while in the reading stage, without going through a full load.
+    ///
+    /// This is used to pick up changes to a table's underlying data without reloading the
+    /// config. The new table must already have the indexes of the table it replaces applied,
+    /// in the same order, so that the `IndexHandle`s held by transforms remain valid.
+    ///
+    /// Only the replaced table is swapped out, the other tables are shared with the previous
+    /// list of tables.
+    ///
+    /// Returns `false` if the tables are still being loaded, in which case nothing is replaced.
+    pub fn replace_table(&self, name: &str, table: Box<dyn Table + Send + Sync>) -> bool {
+        let table: Arc<dyn Table + Send + Sync> = Arc::from(table);
+        let mut replaced = false;
+        self.tables.rcu(|tables| match &**tables {
+            Some(tables) => {
+                let mut tables = tables.clone();
+                tables.insert(name.to_string(), Arc::clone(&table));
+                replaced = true;
+                Some(tables)
+            }
+            None => {
+                replaced = false;
+                None
+            }
+        });
+        replaced
+    }
+
     /// Return a list of the available tables that we can write to.
     ///
     /// This only works in the writing stage and will acquire a lock to retrieve
@@ -192,7 +229,7 @@ impl std::fmt::Debug for TableRegistry {
 /// `vrl::EnrichmentTableSearch` trait. Cloning this object is designed to be
 /// cheap. The underlying data will be shared by all clones.
 #[derive(Clone, Default)]
-pub struct TableSearch(Arc<ArcSwap<Option<TableMap>>>);
+pub struct TableSearch(Arc<ArcSwap<Option<SharedTableMap>>>);
 
 impl TableSearch {
     /// Search the given table to find the data.
@@ -250,7 +287,7 @@ impl std::fmt::Debug for TableSearch {
 fn fmt_enrichment_table(
     f: &mut std::fmt::Formatter<'_>,
     name: &'static str,
-    tables: &Arc<ArcSwap<Option<TableMap>>>,
+    tables: &Arc<ArcSwap<Option<SharedTableMap>>>,
 ) -> std::fmt::Result {
     let tables = tables.load();
     match **tables {
@@ -451,4 +488,50 @@ mod tests {
                 .unwrap()
         );
     }
+
+    #[test]
+    fn replaces_table_after_finish() {
+        let mut tables: TableMap = HashMap::new();
+        tables.insert("dummy1".to_string(), Box::new(DummyEnrichmentTable::new()));
+
+        let registry = super::TableRegistry::default();
+        let tables_search = registry.as_readonly();
+
+        // Tables can't be replaced while they are being loaded.
+        assert!(!registry.replace_table("dummy1", Box::new(DummyEnrichmentTable::new())));
+
+        registry.load(tables);
+        registry.finish_load();
+
+        let new_data = BTreeMap::from([("field".to_string(), Value::from("replaced"))]);
+        assert!(registry.replace_table(
+            "dummy1",
+            Box::new(DummyEnrichmentTable::new_with_data(new_data))
+        ));
+
+        assert_eq!(
+            Ok(BTreeMap::from([("field".into(), Value::from("replaced"))])),
+            tables_search.find_table_row("dummy1", Case::Sensitive, &[], None, None)
+        );
+    }
+
+    #[test]
+    fn replace_table_shares_other_tables() {
+        let mut tables: TableMap = HashMap::new();
+        tables.insert("dummy1".to_string(), Box::new(DummyEnrichmentTable::new()));
+        tables.insert("dummy2".to_string(), Box::new(DummyEnrichmentTable::new()));
+
+        let registry = super::TableRegistry::default();
+        registry.load(tables);
+        registry.finish_load();
+
+        let before = registry.tables.load_full();
+        assert!(registry.replace_table("dummy1", Box::new(DummyEnrichmentTable::new())));
+        let after = registry.tables.load_full();
+
+        let before = (*before).as_ref().unwrap();
+        let after = (*after).as_ref().unwrap();
+        assert!(!Arc::ptr_eq(&before["dummy1"], &after["dummy1"]));
+        assert!(Arc::ptr_eq(&before["dummy2"], &after["dummy2"]));
+    }
 }
@@ -47,6 +47,19 @@ pub struct Checkpointer {
     glob_string: String,
     checkpoints: Arc<CheckpointsView>,
     last: Mutex<Option<State>>,
+    max_checkpoints: Option<usize>,
+}
+
+/// The checkpoints persisted by `Checkpointer::write_checkpoints`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CheckpointsWritten {
+    /// The number of checkpoints.
+    pub count: usize,
+    /// The number of checkpoints removed before writing them, because their
+    /// files were removed or because there were more than the maximum.
+    pub removed: usize,
+    /// The size of the checkpoints file, in bytes.
+    pub file_size: u64,
 }
 
 /// A thread-safe handle for reading and writing checkpoints in-memory across
@@ -73,6 +86,28 @@ impl CheckpointsView {
         self.removed_times.insert(fng, Utc::now());
     }
 
+    /// Mark the checkpoints of the files that aren't watched as dead, such as
+    /// the ones of the files removed or rotated away while Vector wasn't
+    /// running, so they expire unless their files are found again.
+    pub fn set_dead_unless_watched(&self, is_watched: impl Fn(&FileFingerprint) -> bool) {
+        let now = Utc::now();
+        let unwatched = self
+            .checkpoints
+            .iter()
+            .map(|entry| *entry.key())
+            .filter(|fng| !is_watched(fng))
+            .collect::<Vec<FileFingerprint>>();
+
+        for fng in unwatched {
+            self.removed_times.entry(fng).or_insert(now);
+        }
+    }
+
+    /// Mark the checkpoint as alive again, once its file is found.
+    pub fn set_alive(&self, fng: FileFingerprint) {
+        self.removed_times.remove(&fng);
+    }
+
     pub fn update_key(&self, old: FileFingerprint, new: FileFingerprint) {
         if let Some((_, value)) = self.checkpoints.remove(&old) {
             self.checkpoints.insert(new, value);
@@ -93,7 +128,7 @@ impl CheckpointsView {
             .any(|entry| matches!(entry.key(), FileFingerprint::BytesChecksum(_)))
     }
 
-    pub fn remove_expired(&self) {
+    pub fn remove_expired(&self) -> usize {
         let now = Utc::now();
 
         // Collect all of the expired keys. Removing them while iterating can
@@ -110,11 +145,46 @@ impl CheckpointsView {
             .map(|entry| *entry.key())
             .collect::<Vec<FileFingerprint>>();
 
+        self.remove_all(to_remove)
+    }
+
+    /// Remove checkpoints until there are at most `max` of them, starting with
+    /// the ones of removed files, then the least recently modified ones.
+    pub fn remove_excess(&self, max: usize) -> usize {
+        let excess = self.checkpoints.len().saturating_sub(max);
+        if excess == 0 {
+            return 0;
+        }
+
+        let mut candidates = self
+            .checkpoints
+            .iter()
+            .map(|entry| {
+                let fng = *entry.key();
+                let alive = !self.removed_times.contains_key(&fng);
+                let modified = self.modified_times.get(&fng).map(|r| *r.value());
+                (alive, modified, fng)
+            })
+            .collect::<Vec<_>>();
+        candidates.sort_by_key(|(alive, modified, _)| (*alive, *modified));
+
+        self.remove_all(
+            candidates
+                .into_iter()
+                .take(excess)
+                .map(|(_, _, fng)| fng)
+                .collect(),
+        )
+    }
+
+    fn remove_all(&self, to_remove: Vec<FileFingerprint>) -> usize {
+        let count = to_remove.len();
         for fng in to_remove {
             self.checkpoints.remove(&fng);
             self.modified_times.remove(&fng);
             self.removed_times.remove(&fng);
         }
+        count
     }
 
     fn load(&self, checkpoint: Checkpoint) {
@@ -205,9 +275,16 @@ impl Checkpointer {
             stable_file_path,
             checkpoints: Arc::new(CheckpointsView::default()),
             last: Mutex::new(None),
+            max_checkpoints: None,
         }
     }
 
+    /// Limit the number of checkpoints persisted, removing the ones of removed
+    /// files first, then the least recently modified ones.
+    pub fn set_max_checkpoints(&mut self, max_checkpoints: Option<usize>) {
+        self.max_checkpoints = max_checkpoints;
+    }
+
     pub fn view(&self) -> Arc<CheckpointsView> {
         Arc::clone(&self.checkpoints)
     }
@@ -289,12 +366,18 @@ impl Checkpointer {
     /// Persist the current checkpoints state to disk, making our best effort to
     /// do so in an atomic way that allow for recovering the previous state in
     /// the event of a crash.
-    pub fn write_checkpoints(&self) -> Result<usize, io::Error> {
+    pub fn write_checkpoints(&self) -> Result<CheckpointsWritten, io::Error> {
         // First drop any checkpoints for files that were removed more than 60
         // seconds ago. This keeps our working set as small as possible and
         // makes sure we don't spend time and IO writing checkpoints that don't
         // matter anymore.
-        self.checkpoints.remove_expired();
+        let mut removed = self.checkpoints.remove_expired();
+
+        // Then drop the checkpoints over the limit, if any, so the checkpoints
+        // file doesn't grow unbounded when files churn faster than they expire.
+        if let Some(max_checkpoints) = self.max_checkpoints {
+            removed += self.checkpoints.remove_excess(max_checkpoints);
+        }
 
         let current = self.checkpoints.get_state();
 
@@ -319,7 +402,13 @@ impl Checkpointer {
             *last = Some(current);
         }
 
-        Ok(self.checkpoints.checkpoints.len())
+        Ok(CheckpointsWritten {
+            count: self.checkpoints.checkpoints.len(),
+            removed,
+            file_size: fs::metadata(&self.stable_file_path)
+                .map(|metadata| metadata.len())
+                .unwrap_or(0),
+        })
     }
 
     /// Write checkpoints to disk in the legacy format. Used for compatibility
@@ -683,6 +772,63 @@ mod test {
         assert_eq!(chkptr.get_checkpoint(cases[3].0), None);
     }
 
+    #[test]
+    fn test_checkpointer_unwatched_expiration() {
+        let watched = FileFingerprint::DevInode(1, 2);
+        let unwatched = FileFingerprint::DevInode(3, 4);
+
+        let data_dir = tempdir().unwrap();
+        let mut chkptr = Checkpointer::new(data_dir.path());
+        chkptr.update_checkpoint(watched, 1);
+        chkptr.update_checkpoint(unwatched, 2);
+
+        chkptr
+            .checkpoints
+            .set_dead_unless_watched(|fng| *fng == watched);
+        assert!(!chkptr.checkpoints.removed_times.contains_key(&watched));
+
+        // slide the removal back so we don't have to sleep for a long time
+        chkptr
+            .checkpoints
+            .removed_times
+            .insert(unwatched, Utc::now() - chrono::Duration::seconds(60));
+
+        let written = chkptr.write_checkpoints().unwrap();
+        assert_eq!(written.count, 1);
+        assert_eq!(written.removed, 1);
+        assert!(written.file_size > 0);
+        assert_eq!(chkptr.get_checkpoint(watched), Some(1));
+        assert_eq!(chkptr.get_checkpoint(unwatched), None);
+    }
+
+    #[test]
+    fn test_checkpointer_max_checkpoints() {
+        let data_dir = tempdir().unwrap();
+        let mut chkptr = Checkpointer::new(data_dir.path());
+        chkptr.set_max_checkpoints(Some(2));
+
+        let removed = FileFingerprint::DevInode(1, 1);
+        let oldest = FileFingerprint::DevInode(2, 2);
+        let older = FileFingerprint::DevInode(3, 3);
+        let newer = FileFingerprint::DevInode(4, 4);
+        for (fingerprint, age) in &[(removed, 10), (oldest, 30), (older, 20), (newer, 0)] {
+            chkptr.checkpoints.load(Checkpoint {
+                fingerprint: *fingerprint,
+                position: 1,
+                modified: Utc::now() - Duration::seconds(*age),
+            });
+        }
+        chkptr.checkpoints.set_dead(removed);
+
+        let written = chkptr.write_checkpoints().unwrap();
+        assert_eq!(written.count, 2);
+        assert_eq!(written.removed, 2);
+        assert_eq!(chkptr.get_checkpoint(removed), None);
+        assert_eq!(chkptr.get_checkpoint(oldest), None);
+        assert_eq!(chkptr.get_checkpoint(older), Some(1));
+        assert_eq!(chkptr.get_checkpoint(newer), Some(1));
+    }
+
     #[test]
     fn test_checkpointer_checksum_updates() {
         let data_dir = tempdir().unwrap();
@@ -50,6 +50,7 @@ where
     pub fingerprinter: Fingerprinter,
     pub oldest_first: bool,
     pub remove_after: Option<Duration>,
+    pub max_checkpoints: Option<usize>,
     pub emitter: E,
     pub handle: tokio::runtime::Handle,
 }
@@ -97,6 +98,7 @@ where
         let mut backoff_cap: usize = 1;
         let mut lines = Vec::new();
 
+        checkpointer.set_max_checkpoints(self.max_checkpoints);
         checkpointer.read_checkpoints(self.ignore_before);
 
         let mut known_small_files = HashSet::new();
@@ -134,6 +136,10 @@ where
         }
         self.emitter.emit_files_open(fp_map.len());
 
+        // Checkpoints of files that were removed or rotated away while we
+        // weren't running would otherwise be kept forever.
+        checkpoints.set_dead_unless_watched(|file_id| fp_map.contains_key(file_id));
+
         let mut stats = TimingStats::default();
 
         // Spawn the checkpoint writer task
@@ -157,7 +163,16 @@ where
                 tokio::task::spawn_blocking(move || {
                     let start = time::Instant::now();
                     match checkpointer.write_checkpoints() {
-                        Ok(count) => emitter.emit_file_checkpointed(count, start.elapsed()),
+                        Ok(written) => {
+                            if written.removed > 0 {
+                                emitter.emit_file_checkpoints_removed(written.removed);
+                            }
+                            emitter.emit_file_checkpointed(
+                                written.count,
+                                written.file_size,
+                                start.elapsed(),
+                            );
+                        }
                         Err(error) => emitter.emit_file_checkpoint_write_error(error),
                     }
                 })
@@ -429,6 +444,7 @@ where
                     self.emitter.emit_file_added(&path);
                 }
                 watcher.set_file_findable(true);
+                checkpoints.set_alive(file_id);
                 fp_map.insert(file_id, watcher);
             }
             Err(error) => self.emitter.emit_file_watch_error(&path, error),
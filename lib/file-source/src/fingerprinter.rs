@@ -483,7 +483,9 @@ mod test {
             panic!();
         }
 
-        fn emit_file_checkpointed(&self, _: usize, _: Duration) {}
+        fn emit_file_checkpointed(&self, _: usize, _: u64, _: Duration) {}
+
+        fn emit_file_checkpoints_removed(&self, _: usize) {}
 
         fn emit_file_checksum_failed(&self, _: &Path) {
             panic!();
@@ -17,7 +17,9 @@ pub trait FileSourceInternalEvents: Send + Sync + Clone + 'static {
 
     fn emit_file_fingerprint_read_error(&self, path: &Path, error: Error);
 
-    fn emit_file_checkpointed(&self, count: usize, duration: Duration);
+    fn emit_file_checkpointed(&self, count: usize, file_size: u64, duration: Duration);
+
+    fn emit_file_checkpoints_removed(&self, count: usize);
 
     fn emit_file_checksum_failed(&self, path: &Path);
 
@@ -13,7 +13,7 @@ mod metadata_ext;
 pub mod paths_provider;
 
 pub use self::{
-    checkpointer::{Checkpointer, CheckpointsView},
+    checkpointer::{Checkpointer, CheckpointsView, CheckpointsWritten},
     file_server::{FileServer, Line, Shutdown as FileServerShutdown},
     fingerprinter::{FileFingerprint, FingerprintStrategy, Fingerprinter},
     internal_events::FileSourceInternalEvents,
@@ -1,5 +1,6 @@
 fn main() {
     println!("cargo:rerun-if-changed=proto/prometheus-remote.proto");
+    println!("cargo:rerun-if-changed=proto/prometheus-remote-v2.proto");
     println!("cargo:rerun-if-changed=proto/prometheus-types.proto");
     let mut prost_build = prost_build::Config::new();
     prost_build.btree_map(&["."]);
@@ -8,7 +9,17 @@ fn main() {
     // extra derives to conflict with itself.
     prost_build.type_attribute("Label", "#[derive(Eq, Hash, Ord, PartialOrd)]");
     prost_build.type_attribute("MetricType", "#[derive(num_enum::TryFromPrimitive)]");
+    // The samples and native histograms of both versions of the protocol are wire compatible.
+    prost_build.extern_path(".io.prometheus.write.v2.Sample", "crate::proto::Sample");
+    prost_build.extern_path(".io.prometheus.write.v2.Histogram", "crate::proto::Histogram");
+    prost_build.extern_path(".io.prometheus.write.v2.BucketSpan", "crate::proto::BucketSpan");
     prost_build
-        .compile_protos(&["proto/prometheus-remote.proto"], &["proto/"])
+        .compile_protos(
+            &[
+                "proto/prometheus-remote.proto",
+                "proto/prometheus-remote-v2.proto",
+            ],
+            &["proto/"],
+        )
         .unwrap();
 }
@@ -10,11 +10,19 @@ use line::{Line, Metric, MetricKind};
 
 pub const METRIC_NAME_LABEL: &str = "__name__";
 
+/// The suffix of the names of the gauges holding the exemplars of a time series.
+pub const EXEMPLAR_SUFFIX: &str = "_exemplar";
+
 pub mod proto {
     include!(concat!(env!("OUT_DIR"), "/prometheus.rs"));
 
     pub use metric_metadata::MetricType;
 
+    /// The messages of the 2.0 version of the remote write protocol.
+    pub mod v2 {
+        include!(concat!(env!("OUT_DIR"), "/io.prometheus.write.v2.rs"));
+    }
+
     impl MetricType {
         pub fn as_str(&self) -> &'static str {
             match self {
@@ -370,6 +378,73 @@ impl MetricGroupSet {
         Ok(())
     }
 
+    /// Inserts the exemplar of a time series as a gauge, whose tags are the labels of the time
+    /// series along with the labels of the exemplar.
+    fn insert_exemplar(
+        &mut self,
+        name: &str,
+        labels: &BTreeMap<String, String>,
+        exemplar: proto::Exemplar,
+    ) -> Result<(), ParserError> {
+        let name = format!("{}{}", name, EXEMPLAR_SUFFIX);
+        let mut labels = labels.clone();
+        labels.extend(
+            exemplar
+                .labels
+                .into_iter()
+                .map(|label| (label.name, label.value)),
+        );
+        let key = GroupKey {
+            timestamp: Some(exemplar.timestamp),
+            labels,
+        };
+
+        match self
+            .0
+            .entry(name.clone())
+            .or_insert_with(|| GroupKind::new(MetricKind::Gauge))
+        {
+            GroupKind::Gauge(metrics) | GroupKind::Untyped(metrics) => {
+                metrics.insert(
+                    key,
+                    SimpleMetric {
+                        value: exemplar.value,
+                    },
+                );
+                Ok(())
+            }
+            _ => Err(ParserError::MultipleMetricKinds { name }),
+        }
+    }
+
+    /// Inserts a native histogram as a histogram with the buckets of its schema.
+    fn insert_histogram(
+        &mut self,
+        name: &str,
+        labels: &BTreeMap<String, String>,
+        histogram: &proto::Histogram,
+    ) -> Result<(), ParserError> {
+        let group = self
+            .0
+            .entry(name.into())
+            .or_insert_with(|| GroupKind::new(MetricKind::Histogram));
+        if matches!(group, GroupKind::Untyped(metrics) if metrics.is_empty()) {
+            *group = GroupKind::new(MetricKind::Histogram);
+        }
+
+        match group {
+            GroupKind::Histogram(metrics) => {
+                let key = GroupKey {
+                    timestamp: Some(histogram.timestamp),
+                    labels: labels.clone(),
+                };
+                metrics.insert(key, native_histogram(histogram)?);
+                Ok(())
+            }
+            _ => Err(ParserError::MultipleMetricKinds { name: name.into() }),
+        }
+    }
+
     fn finish(self) -> Vec<MetricGroup> {
         self.0
             .into_iter()
@@ -405,11 +480,106 @@ pub fn parse_request(request: proto::WriteRequest) -> Result<Vec<MetricGroup>, P
         for sample in timeseries.samples {
             groups.insert_sample(&name, &labels, sample)?;
         }
+        for exemplar in timeseries.exemplars {
+            groups.insert_exemplar(&name, &labels, exemplar)?;
+        }
+        for histogram in &timeseries.histograms {
+            groups.insert_histogram(&name, &labels, histogram)?;
+        }
     }
 
     Ok(groups.finish())
 }
 
+/// Converts a native histogram to the cumulative buckets of the text format, bounded by the
+/// powers of the base of its schema, in ascending order.
+fn native_histogram(histogram: &proto::Histogram) -> Result<HistogramMetric, ParserError> {
+    use proto::histogram::{Count, ZeroCount};
+
+    let count = match histogram.count {
+        Some(Count::CountInt(count)) => count as f64,
+        Some(Count::CountFloat(count)) => count,
+        None => 0.0,
+    };
+    let zero_count = match histogram.zero_count {
+        Some(ZeroCount::ZeroCountInt(count)) => count as f64,
+        Some(ZeroCount::ZeroCountFloat(count)) => count,
+        None => 0.0,
+    };
+
+    let schema = histogram.schema;
+    let mut buckets = Vec::new();
+    // The negative bucket of index `i` is bounded by `-base^i` and `-base^(i - 1)`.
+    let negative = native_buckets(
+        &histogram.negative_spans,
+        &histogram.negative_deltas,
+        &histogram.negative_counts,
+    );
+    for (index, count) in negative.into_iter().rev() {
+        buckets.push((-native_bound(schema, index - 1), count));
+    }
+    if zero_count > 0.0 || histogram.zero_threshold > 0.0 {
+        buckets.push((histogram.zero_threshold, zero_count));
+    }
+    let positive = native_buckets(
+        &histogram.positive_spans,
+        &histogram.positive_deltas,
+        &histogram.positive_counts,
+    );
+    for (index, count) in positive {
+        buckets.push((native_bound(schema, index), count));
+    }
+
+    let mut cumulative = 0.0;
+    let buckets = buckets
+        .into_iter()
+        .map(|(bucket, count)| {
+            cumulative += count;
+            try_f64_to_u32(cumulative).map(|count| HistogramBucket { bucket, count })
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(HistogramMetric {
+        buckets,
+        sum: histogram.sum,
+        count: try_f64_to_u32(count)?,
+    })
+}
+
+/// The indexes and counts of the buckets of a native histogram, whose counts are either the
+/// deltas to the previous bucket, for integer histograms, or absolute, for float histograms.
+fn native_buckets(spans: &[proto::BucketSpan], deltas: &[i64], counts: &[f64]) -> Vec<(i32, f64)> {
+    let mut counts: Box<dyn Iterator<Item = f64>> = if deltas.is_empty() {
+        Box::new(counts.iter().copied())
+    } else {
+        Box::new(deltas.iter().scan(0, |count, delta| {
+            *count += delta;
+            Some(*count as f64)
+        }))
+    };
+
+    let mut buckets = Vec::new();
+    let mut index = 0;
+    for span in spans {
+        // The offset of a span is the gap to the previous one, or the index of the first one.
+        index += span.offset;
+        for _ in 0..span.length {
+            match counts.next() {
+                Some(count) => buckets.push((index, count)),
+                None => return buckets,
+            }
+            index += 1;
+        }
+    }
+    buckets
+}
+
+/// The upper bound of the positive bucket of the given index, `base^index`, with a base of
+/// `2^(2^-schema)`.
+fn native_bound(schema: i32, index: i32) -> f64 {
+    2f64.powf(f64::from(index) * 2f64.powi(-schema))
+}
+
 impl From<proto::MetricType> for MetricKind {
     fn from(kind: proto::MetricType) -> Self {
         use proto::MetricType::*;
@@ -688,6 +858,7 @@ mod test {
                     samples: vec![
                         $( proto::Sample { value: $sample as f64, timestamp: $timestamp as i64 }, )*
                     ],
+                    ..Default::default()
                 }, )* ],
             }
         };
@@ -838,4 +1009,122 @@ mod test {
             assert_eq!(metrics.get_index(0).unwrap(), simple_metric!(Some(1395066367700), labels!(), 24.0));
         });
     }
+
+    #[test]
+    fn parse_request_native_histogram() {
+        use proto::histogram::{Count, ZeroCount};
+
+        let mut request = write_request!(["one" = Histogram], [[__name__ => "one"] => []]);
+        request.timeseries[0].histograms.push(proto::Histogram {
+            count: Some(Count::CountInt(9)),
+            sum: 20.0,
+            schema: 0,
+            zero_threshold: 0.001,
+            zero_count: Some(ZeroCount::ZeroCountInt(1)),
+            negative_spans: vec![proto::BucketSpan {
+                offset: 1,
+                length: 1,
+            }],
+            negative_deltas: vec![1],
+            positive_spans: vec![
+                proto::BucketSpan {
+                    offset: 0,
+                    length: 2,
+                },
+                proto::BucketSpan {
+                    offset: 1,
+                    length: 1,
+                },
+            ],
+            positive_deltas: vec![2, -1, 3],
+            timestamp: 1395066367700,
+            ..Default::default()
+        });
+        let parsed = parse_request(request).unwrap();
+
+        assert_eq!(parsed.len(), 1);
+        match_group!(parsed[0], "one", Histogram => |metrics: &MetricMap<HistogramMetric>| {
+            assert_eq!(metrics.len(), 1);
+            assert_eq!(
+                metrics.get_index(0).unwrap(), (
+                    &GroupKey {
+                        timestamp: Some(1395066367700),
+                        labels: labels!(),
+                    },
+                    &HistogramMetric {
+                        buckets: vec![
+                            HistogramBucket { bucket: -1.0, count: 1 },
+                            HistogramBucket { bucket: 0.001, count: 2 },
+                            HistogramBucket { bucket: 1.0, count: 4 },
+                            HistogramBucket { bucket: 2.0, count: 5 },
+                            HistogramBucket { bucket: 8.0, count: 9 },
+                        ],
+                        count: 9,
+                        sum: 20.0,
+                    })
+            );
+        });
+    }
+
+    #[test]
+    fn parse_request_float_native_histogram() {
+        use proto::histogram::Count;
+
+        let mut request = write_request!([], [[__name__ => "one"] => []]);
+        request.timeseries[0].histograms.push(proto::Histogram {
+            count: Some(Count::CountFloat(3.0)),
+            sum: 3.5,
+            schema: 1,
+            positive_spans: vec![proto::BucketSpan {
+                offset: 1,
+                length: 2,
+            }],
+            positive_counts: vec![1.0, 2.0],
+            timestamp: 1395066367700,
+            ..Default::default()
+        });
+        let parsed = parse_request(request).unwrap();
+
+        assert_eq!(parsed.len(), 1);
+        match_group!(parsed[0], "one", Histogram => |metrics: &MetricMap<HistogramMetric>| {
+            let (_, metric) = metrics.get_index(0).unwrap();
+            assert_eq!(
+                metric.buckets,
+                vec![
+                    HistogramBucket { bucket: 2f64.powf(0.5), count: 1 },
+                    HistogramBucket { bucket: 2.0, count: 3 },
+                ]
+            );
+            assert_eq!(metric.count, 3);
+        });
+    }
+
+    #[test]
+    fn parse_request_exemplars() {
+        let mut request = write_request!(
+            ["one" = Counter],
+            [[__name__ => "one", code => "200"] => [ 15 @ 1395066367700 ]]
+        );
+        request.timeseries[0].exemplars.push(proto::Exemplar {
+            labels: vec![proto::Label {
+                name: "trace_id".into(),
+                value: "abc".into(),
+            }],
+            value: 0.5,
+            timestamp: 1395066367600,
+        });
+        let parsed = parse_request(request).unwrap();
+
+        assert_eq!(parsed.len(), 2);
+        match_group!(parsed[0], "one", Counter => |metrics: &MetricMap<SimpleMetric>| {
+            assert_eq!(metrics.len(), 1);
+        });
+        match_group!(parsed[1], "one_exemplar", Gauge => |metrics: &MetricMap<SimpleMetric>| {
+            assert_eq!(metrics.len(), 1);
+            assert_eq!(
+                metrics.get_index(0).unwrap(),
+                simple_metric!(Some(1395066367600), labels!(code => "200", trace_id => "abc"), 0.5)
+            );
+        });
+    }
 }
@@ -8,6 +8,7 @@ mod debug;
 pub mod find;
 pub mod insert;
 pub mod merge;
+mod narrow;
 pub mod nest;
 pub mod remove;
 
@@ -0,0 +1,82 @@
+//! Narrowing a [`Kind`] down to a subset of its type states.
+
+use super::Kind;
+
+impl Kind {
+    /// Keep only the type states of `self` that are also present in `other`.
+    ///
+    /// Only the top-level states are compared, the array and object collections of `self` are kept
+    /// as-is if `other` contains an array or object. The returned `Kind` is empty if `self` and
+    /// `other` don't intersect.
+    #[must_use]
+    pub fn keep_states(&self, other: &Self) -> Self {
+        Self {
+            bytes: self.bytes.and(other.bytes),
+            integer: self.integer.and(other.integer),
+            float: self.float.and(other.float),
+            boolean: self.boolean.and(other.boolean),
+            timestamp: self.timestamp.and(other.timestamp),
+            regex: self.regex.and(other.regex),
+            null: self.null.and(other.null),
+            array: self.array.clone().filter(|_| other.array.is_some()),
+            object: self.object.clone().filter(|_| other.object.is_some()),
+        }
+    }
+
+    /// Remove the type states of `self` that are present in `other`.
+    ///
+    /// Only the top-level states are compared, arrays and objects are removed regardless of the
+    /// collections `other` contains. The returned `Kind` is empty if `other` is a superset of the
+    /// states of `self`.
+    #[must_use]
+    pub fn without_states(&self, other: &Self) -> Self {
+        Self {
+            bytes: self.bytes.filter(|_| other.bytes.is_none()),
+            integer: self.integer.filter(|_| other.integer.is_none()),
+            float: self.float.filter(|_| other.float.is_none()),
+            boolean: self.boolean.filter(|_| other.boolean.is_none()),
+            timestamp: self.timestamp.filter(|_| other.timestamp.is_none()),
+            regex: self.regex.filter(|_| other.regex.is_none()),
+            null: self.null.filter(|_| other.null.is_none()),
+            array: self.array.clone().filter(|_| other.array.is_none()),
+            object: self.object.clone().filter(|_| other.object.is_none()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kind::Collection;
+
+    #[test]
+    fn test_keep_states() {
+        let kind = Kind::bytes()
+            .or_null()
+            .or_object(Collection::from_unknown(Kind::integer()));
+
+        assert_eq!(kind.keep_states(&Kind::bytes()), Kind::bytes());
+        assert_eq!(
+            kind.keep_states(&Kind::object(Collection::any()).or_integer()),
+            Kind::object(Collection::from_unknown(Kind::integer()))
+        );
+        assert!(kind.keep_states(&Kind::integer()).is_empty());
+    }
+
+    #[test]
+    fn test_without_states() {
+        let kind = Kind::bytes()
+            .or_null()
+            .or_object(Collection::from_unknown(Kind::integer()));
+
+        assert_eq!(
+            kind.without_states(&Kind::null()),
+            Kind::bytes().or_object(Collection::from_unknown(Kind::integer()))
+        );
+        assert_eq!(
+            kind.without_states(&Kind::object(Collection::empty()).or_null()),
+            Kind::bytes()
+        );
+        assert!(kind.without_states(&Kind::any()).is_empty());
+    }
+}
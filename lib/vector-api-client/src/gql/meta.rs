@@ -10,11 +10,24 @@ use graphql_client::GraphQLQuery;
 )]
 pub struct MetaVersionStringQuery;
 
+/// MetaBundleQuery returns the bundle the configuration of the queried Vector instance was
+/// loaded from, if any.
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/queries/meta_bundle.graphql",
+    response_derives = "Debug"
+)]
+pub struct MetaBundleQuery;
+
 /// Extension methods for meta queries.
 #[async_trait]
 pub trait MetaQueryExt {
     /// Executes a meta version string query.
     async fn meta_version_string(&self) -> crate::QueryResult<MetaVersionStringQuery>;
+
+    /// Executes a meta bundle query.
+    async fn meta_bundle(&self) -> crate::QueryResult<MetaBundleQuery>;
 }
 
 #[async_trait]
@@ -26,4 +39,10 @@ impl MetaQueryExt for crate::Client {
         ))
         .await
     }
+
+    /// Executes a meta bundle query.
+    async fn meta_bundle(&self) -> crate::QueryResult<MetaBundleQuery> {
+        self.query::<MetaBundleQuery>(&MetaBundleQuery::build_query(meta_bundle_query::Variables))
+            .await
+    }
 }
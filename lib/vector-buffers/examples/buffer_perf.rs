@@ -261,8 +261,9 @@ where
                 max_size_bytes
             );
             BufferType::DiskV2 {
-                max_size: max_size_bytes,
+                max_size: Some(max_size_bytes),
                 when_full,
+                encryption: None,
             }
         }
         s => panic!(
@@ -13,7 +13,10 @@ use crate::{
         builder::{TopologyBuilder, TopologyError},
         channel::{BufferReceiver, BufferSender},
     },
-    variants::{DiskV1Buffer, DiskV2Buffer, MemoryBuffer},
+    variants::{
+        disk_v2::DEFAULT_MAX_DATA_FILE_SIZE, DiskV1Buffer, DiskV2Buffer, EncryptionConfig,
+        MemoryBuffer,
+    },
     Acker, Bufferable, WhenFull,
 };
 
@@ -25,6 +28,8 @@ pub enum BufferBuildError {
     FailedToBuildTopology { source: TopologyError },
     #[snafu(display("`max_events` must be greater than zero"))]
     InvalidMaxEvents,
+    #[snafu(display("the disk buffer type requires `max_size` be specified"))]
+    RequiresMaxSize,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -37,7 +42,7 @@ enum BufferTypeKind {
     DiskV2,
 }
 
-const ALL_FIELDS: [&str; 4] = ["type", "max_events", "max_size", "when_full"];
+const ALL_FIELDS: [&str; 5] = ["type", "max_events", "max_size", "when_full", "encryption"];
 
 struct BufferTypeVisitor;
 
@@ -50,6 +55,7 @@ impl BufferTypeVisitor {
         let mut max_events: Option<NonZeroUsize> = None;
         let mut max_size: Option<NonZeroU64> = None;
         let mut when_full: Option<WhenFull> = None;
+        let mut encryption: Option<EncryptionConfig> = None;
         while let Some(key) = map.next_key::<String>()? {
             match key.as_str() {
                 "type" => {
@@ -76,6 +82,12 @@ impl BufferTypeVisitor {
                     }
                     when_full = Some(map.next_value()?);
                 }
+                "encryption" => {
+                    if encryption.is_some() {
+                        return Err(de::Error::duplicate_field("encryption"));
+                    }
+                    encryption = Some(map.next_value()?);
+                }
                 other => {
                     return Err(de::Error::unknown_field(other, &ALL_FIELDS));
                 }
@@ -91,6 +103,12 @@ impl BufferTypeVisitor {
                         &["type", "max_events", "when_full"],
                     ));
                 }
+                if encryption.is_some() {
+                    return Err(de::Error::unknown_field(
+                        "encryption",
+                        &["type", "max_events", "when_full"],
+                    ));
+                }
                 Ok(BufferType::Memory {
                     max_events: max_events.unwrap_or_else(memory_buffer_default_max_events),
                     when_full,
@@ -103,6 +121,12 @@ impl BufferTypeVisitor {
                         &["type", "max_size", "when_full"],
                     ));
                 }
+                if encryption.is_some() {
+                    return Err(de::Error::unknown_field(
+                        "encryption",
+                        &["type", "max_size", "when_full"],
+                    ));
+                }
                 Ok(BufferType::DiskV1 {
                     max_size: max_size.ok_or_else(|| de::Error::missing_field("max_size"))?,
                     when_full,
@@ -112,12 +136,13 @@ impl BufferTypeVisitor {
                 if max_events.is_some() {
                     return Err(de::Error::unknown_field(
                         "max_events",
-                        &["type", "max_size", "when_full"],
+                        &["type", "max_size", "when_full", "encryption"],
                     ));
                 }
                 Ok(BufferType::DiskV2 {
-                    max_size: max_size.ok_or_else(|| de::Error::missing_field("max_size"))?,
+                    max_size,
                     when_full,
+                    encryption,
                 })
             }
         }
@@ -208,7 +233,7 @@ pub const fn memory_buffer_default_max_events() -> NonZeroUsize {
 }
 
 /// A specific type of buffer stage.
-#[derive(Copy, Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize)]
 #[serde(tag = "type")]
 #[serde(rename_all = "snake_case")]
 pub enum BufferType {
@@ -228,15 +253,36 @@ pub enum BufferType {
         when_full: WhenFull,
     },
     /// A buffer stage backed by disk.
+    ///
+    /// The maximum size can be left out when a global disk buffer budget is configured, in which
+    /// case it's allocated a share of the budget of its sink.
     #[serde(rename = "disk")]
     DiskV2 {
-        max_size: NonZeroU64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_size: Option<NonZeroU64>,
         #[serde(default)]
         when_full: WhenFull,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        encryption: Option<EncryptionConfig>,
     },
 }
 
 impl BufferType {
+    /// The smallest size of a disk buffer stage.
+    ///
+    /// Disk buffers store their records in data files of a fixed maximum size, so they take at
+    /// least the size of a data file, whatever their `max_size`.
+    pub const DISK_V2_MIN_SIZE: u64 = DEFAULT_MAX_DATA_FILE_SIZE;
+
+    /// Gets the behavior of this buffer stage when it is full.
+    pub const fn when_full(&self) -> WhenFull {
+        match self {
+            BufferType::Memory { when_full, .. }
+            | BufferType::DiskV1 { when_full, .. }
+            | BufferType::DiskV2 { when_full, .. } => *when_full,
+        }
+    }
+
     /// Adds this buffer type as a stage to an existing [`TopologyBuilder`].
     ///
     /// # Errors
@@ -252,26 +298,31 @@ impl BufferType {
     where
         T: Bufferable + Clone,
     {
-        match *self {
+        match self {
             BufferType::Memory {
                 when_full,
                 max_events,
             } => {
-                builder.stage(MemoryBuffer::new(max_events), when_full);
+                builder.stage(MemoryBuffer::new(*max_events), *when_full);
             }
             BufferType::DiskV1 {
                 when_full,
                 max_size,
             } => {
                 let data_dir = data_dir.ok_or(BufferBuildError::RequiresDataDir)?;
-                builder.stage(DiskV1Buffer::new(id, data_dir, max_size), when_full);
+                builder.stage(DiskV1Buffer::new(id, data_dir, *max_size), *when_full);
             }
             BufferType::DiskV2 {
                 when_full,
                 max_size,
+                encryption,
+                ..
             } => {
                 let data_dir = data_dir.ok_or(BufferBuildError::RequiresDataDir)?;
-                builder.stage(DiskV2Buffer::new(id, data_dir, max_size), when_full);
+                let max_size = max_size.ok_or(BufferBuildError::RequiresMaxSize)?;
+                let buffer =
+                    DiskV2Buffer::new(id, data_dir, max_size).with_encryption(encryption.clone());
+                builder.stage(buffer, *when_full);
             }
         };
 
@@ -333,12 +384,16 @@ impl BufferConfig {
     ///
     /// If a disk buffer stage is configured and the data directory provided is `None`, an error
     /// variant will be thrown.
+    ///
+    /// If `overflow` is given, the last stage can be set to overflow mode, in which case the items it
+    /// can't hold are sent to `overflow` rather than being read from the returned receiver.
     #[allow(clippy::needless_pass_by_value)]
     pub async fn build<T>(
         &self,
         data_dir: Option<PathBuf>,
         buffer_id: String,
         span: Span,
+        overflow: Option<BufferSender<T>>,
     ) -> Result<(BufferSender<T>, BufferReceiver<T>, Acker), BufferBuildError>
     where
         T: Bufferable + Clone,
@@ -349,6 +404,10 @@ impl BufferConfig {
             stage.add_to_builder(&mut builder, data_dir.clone(), buffer_id.clone())?;
         }
 
+        if let Some(overflow) = overflow {
+            builder.overflow_to(overflow);
+        }
+
         builder
             .build(buffer_id, span)
             .await
@@ -360,7 +419,7 @@ impl BufferConfig {
 mod test {
     use std::num::{NonZeroU64, NonZeroUsize};
 
-    use crate::{BufferConfig, BufferType, WhenFull};
+    use crate::{variants::EncryptionConfig, BufferConfig, BufferType, WhenFull};
 
     fn check_single_stage(source: &str, expected: BufferType) {
         let config: BufferConfig = serde_yaml::from_str(source).unwrap();
@@ -390,7 +449,7 @@ mod test {
         let error = serde_yaml::from_str::<BufferConfig>(source).unwrap_err();
         assert_eq!(
             error.to_string(),
-            "unknown field `foo`, expected one of `type`, `max_events`, `max_size`, `when_full` at line 1 column 4"
+            "unknown field `foo`, expected one of `type`, `max_events`, `max_size`, `when_full`, `encryption` at line 1 column 4"
         );
     }
 
@@ -502,9 +561,51 @@ max_events: 42
           max_size: 1024
           "#,
             BufferType::DiskV2 {
-                max_size: NonZeroU64::new(1024).unwrap(),
+                max_size: NonZeroU64::new(1024),
                 when_full: WhenFull::Block,
+                encryption: None,
             },
         );
+
+        check_single_stage(
+            r#"
+          type: disk
+          "#,
+            BufferType::DiskV2 {
+                max_size: None,
+                when_full: WhenFull::Block,
+                encryption: None,
+            },
+        );
+    }
+
+    #[test]
+    fn parse_disk_encryption() {
+        check_single_stage(
+            r#"
+          type: disk
+          max_size: 1024
+          encryption:
+            key: new-key
+            previous_keys:
+              - old-key
+          "#,
+            BufferType::DiskV2 {
+                max_size: NonZeroU64::new(1024),
+                when_full: WhenFull::Block,
+                encryption: Some(EncryptionConfig {
+                    key: "new-key".to_string(),
+                    previous_keys: vec!["old-key".to_string()],
+                }),
+            },
+        );
+
+        let source = r#"
+          type: memory
+          encryption:
+            key: new-key
+          "#;
+        let error = serde_yaml::from_str::<BufferConfig>(source).unwrap_err();
+        assert!(error.to_string().starts_with("unknown field `encryption`"));
     }
 }
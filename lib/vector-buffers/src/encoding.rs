@@ -2,9 +2,17 @@ use std::error;
 
 use bytes::{Buf, BufMut};
 
+/// Bits of the integer representation of metadata that are reserved for the buffers themselves.
+///
+/// Buffers use these bits to describe how records are stored, such as whether their payload is
+/// encrypted, so metadata types must never set them.
+pub const RESERVED_METADATA_BITS: u32 = 1 << 31;
+
 /// Converts back and forth between user-friendly metadata types and the on-disk integer representation.
 pub trait AsMetadata: Sized {
     /// Converts this metadata value into its integer representation.
+    ///
+    /// The integer representation must not set any of the [`RESERVED_METADATA_BITS`].
     fn into_u32(self) -> u32;
 
     /// Converts an integer repentation of metadata into its real type, if possible.
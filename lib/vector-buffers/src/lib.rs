@@ -30,6 +30,7 @@ pub mod test;
 pub mod topology;
 
 pub(crate) mod variants;
+pub use variants::EncryptionConfig;
 
 use std::fmt::Debug;
 
@@ -44,7 +44,9 @@ pub enum TopologyError {
         stage_idx
     ))]
     NextStageNotUsed { stage_idx: usize },
-    #[snafu(display("last stage in buffer topology cannot be set to overflow mode"))]
+    #[snafu(display(
+        "last stage in buffer topology cannot be set to overflow mode without an overflow sender"
+    ))]
     OverflowWhenLast,
     #[snafu(display("failed to build individual stage {}: {}", stage_idx, source))]
     FailedToBuildStage {
@@ -65,6 +67,7 @@ struct TopologyStage<T: Bufferable> {
 /// Builder for constructing buffer topologies.
 pub struct TopologyBuilder<T: Bufferable> {
     stages: Vec<TopologyStage<T>>,
+    overflow: Option<BufferSender<T>>,
 }
 
 impl<T: Bufferable> TopologyBuilder<T> {
@@ -98,6 +101,16 @@ impl<T: Bufferable> TopologyBuilder<T> {
         self
     }
 
+    /// Sets the sender that the innermost stage overflows to.
+    ///
+    /// This allows the innermost stage to be set to "overflow" mode, sending the items it can't hold
+    /// to a sender outside of the buffer topology, such as the input of another component.  Items
+    /// sent to it are never seen by the receiver of this buffer topology.
+    pub fn overflow_to(&mut self, overflow: BufferSender<T>) -> &mut Self {
+        self.overflow = Some(overflow);
+        self
+    }
+
     /// Consumes this builder, returning the sender and receiver that can be used by components.
     ///
     /// # Errors
@@ -113,13 +126,15 @@ impl<T: Bufferable> TopologyBuilder<T> {
         let mut buffer_usage = BufferUsage::from_span(span);
         let mut current_acker = None;
         let mut current_stage = None;
+        let mut overflow = self.overflow;
 
         for (stage_idx, stage) in self.stages.into_iter().enumerate().rev() {
             // Make sure the stage is valid for our current builder state.
             match stage.when_full {
-                // The innermost stage can't be set to overflow, there's nothing else to overflow _to_.
+                // The innermost stage can't be set to overflow, there's nothing else to overflow _to_,
+                // unless we were given a sender outside of the topology to overflow to.
                 WhenFull::Overflow => {
-                    if current_stage.is_none() {
+                    if current_stage.is_none() && overflow.is_none() {
                         return Err(TopologyError::OverflowWhenLast);
                     }
                 }
@@ -167,10 +182,15 @@ impl<T: Bufferable> TopologyBuilder<T> {
             current_acker = acker;
 
             let (mut sender, mut receiver) = match current_stage.take() {
-                None => (
-                    BufferSender::new(sender, stage.when_full),
-                    BufferReceiver::new(receiver),
-                ),
+                None => {
+                    let sender = match (stage.when_full, overflow.take()) {
+                        (WhenFull::Overflow, Some(overflow)) => {
+                            BufferSender::with_overflow(sender, overflow)
+                        }
+                        (when_full, _) => BufferSender::new(sender, when_full),
+                    };
+                    (sender, BufferReceiver::new(receiver))
+                }
                 Some((current_sender, current_receiver)) => (
                     BufferSender::with_overflow(sender, current_sender),
                     BufferReceiver::with_overflow(receiver, current_receiver),
@@ -270,7 +290,10 @@ impl<T: Bufferable> TopologyBuilder<T> {
 
 impl<T: Bufferable> Default for TopologyBuilder<T> {
     fn default() -> Self {
-        Self { stages: Vec::new() }
+        Self {
+            stages: Vec::new(),
+            overflow: None,
+        }
     }
 }
 
@@ -329,6 +352,36 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn single_stage_topology_overflow_to_sender() {
+        let (overflow, mut overflow_rx) = TopologyBuilder::<u64>::standalone_memory(
+            NonZeroUsize::new(1).unwrap(),
+            WhenFull::Block,
+        )
+        .await;
+
+        let mut builder = TopologyBuilder::<u64>::default();
+        builder.stage(
+            MemoryBuffer::new(NonZeroUsize::new(1).unwrap()),
+            WhenFull::Overflow,
+        );
+        builder.overflow_to(overflow);
+        let result = builder.build(String::from("test"), Span::none()).await;
+        assert!(result.is_ok());
+
+        let (mut sender, mut receiver, _) = result.unwrap();
+        assert_current_send_capacity(&mut sender, Some(1), Some(1));
+
+        sender.send(1).await.unwrap();
+        sender.send(2).await.unwrap();
+        drop(sender);
+
+        // The item the buffer couldn't hold only ever reaches the overflow sender.
+        assert_eq!(receiver.next().await, Some(1));
+        assert_eq!(receiver.next().await, None);
+        assert_eq!(overflow_rx.next().await, Some(2));
+    }
+
     #[tokio::test]
     async fn two_stage_topology_block() {
         let mut builder = TopologyBuilder::<u64>::default();
@@ -7,7 +7,10 @@ use std::{
 use crc32fast::Hasher;
 use snafu::Snafu;
 
-use super::io::{Filesystem, ProductionFilesystem};
+use super::{
+    encryption::Encryption,
+    io::{Filesystem, ProductionFilesystem},
+};
 
 // We don't want data files to be bigger than 128MB, but we might end up overshooting slightly.
 pub const DEFAULT_MAX_DATA_FILE_SIZE: u64 = 128 * 1024 * 1024;
@@ -89,6 +92,14 @@ pub struct DiskBufferConfig<FS> {
     /// amount of data written since the last flush would be lost.
     pub(crate) flush_interval: Duration,
 
+    /// Encryption of records at rest.
+    ///
+    /// When set, the payload of each record is encrypted before being written to a data file.
+    /// Records which were encrypted are decrypted when read, regardless of whether or not new
+    /// records are being encrypted, as long as the key they were encrypted with is still
+    /// configured.  Otherwise, they are skipped like corrupted records.
+    pub(crate) encryption: Option<Encryption>,
+
     /// Filesystem implementation for opening data files.
     ///
     /// We allow parameterizing the filesystem implementation for ease of testing.  The "filesystem"
@@ -109,6 +120,7 @@ where
     pub(crate) max_record_size: Option<usize>,
     pub(crate) write_buffer_size: Option<usize>,
     pub(crate) flush_interval: Option<Duration>,
+    pub(crate) encryption: Option<Encryption>,
     pub(crate) filesystem: FS,
 }
 
@@ -124,6 +136,7 @@ impl DiskBufferConfigBuilder {
             max_record_size: None,
             write_buffer_size: None,
             flush_interval: None,
+            encryption: None,
             filesystem: ProductionFilesystem,
         }
     }
@@ -204,6 +217,17 @@ where
         self
     }
 
+    /// Sets the encryption of records at rest.
+    ///
+    /// When set, the payload of each record is encrypted before being written to a data file.
+    ///
+    /// Defaults to no encryption.
+    #[allow(dead_code)]
+    pub fn encryption(mut self, encryption: Option<Encryption>) -> Self {
+        self.encryption = encryption;
+        self
+    }
+
     /// Filesystem implementation for opening data files.
     ///
     /// We allow parameterizing the filesystem implementation for ease of testing.  The "filesystem"
@@ -223,6 +247,7 @@ where
             max_record_size: self.max_record_size,
             write_buffer_size: self.write_buffer_size,
             flush_interval: self.flush_interval,
+            encryption: self.encryption,
             filesystem,
         }
     }
@@ -238,6 +263,7 @@ where
         let flush_interval = self
             .flush_interval
             .unwrap_or_else(|| Duration::from_millis(500));
+        let encryption = self.encryption;
         let filesystem = self.filesystem;
 
         // Validate the input parameters.
@@ -287,6 +313,7 @@ where
             max_record_size,
             write_buffer_size,
             flush_interval,
+            encryption,
             filesystem,
         })
     }
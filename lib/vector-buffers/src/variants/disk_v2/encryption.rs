@@ -0,0 +1,300 @@
+use std::{fmt, sync::Arc};
+
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN},
+    digest::{digest, SHA256},
+    rand::{SecureRandom, SystemRandom},
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+
+use crate::encoding::RESERVED_METADATA_BITS;
+
+/// Flag set in the metadata of records whose payload is encrypted.
+///
+/// Encodings can't use the bits of their metadata reserved for buffers, so we use one of them to
+/// mark encrypted records.  This lets a buffer hold both plaintext and encrypted records, such as
+/// when encryption is enabled on a buffer that still has records written before it was.
+pub(crate) const ENCRYPTED_RECORD_FLAG: u32 = RESERVED_METADATA_BITS;
+
+/// Length, in bytes, of the key ID prefixed to encrypted payloads.
+const KEY_ID_LEN: usize = 4;
+
+/// Length, in bytes, of the event count prefixed to encrypted payloads.
+const EVENT_COUNT_LEN: usize = 8;
+
+/// Length, in bytes, of an AES-256 key.
+const KEY_LEN: usize = 32;
+
+#[derive(Debug, Snafu)]
+pub enum EncryptionConfigError {
+    #[snafu(display("encryption key is not valid base64: {}", source))]
+    InvalidKeyEncoding { source: base64::DecodeError },
+    #[snafu(display(
+        "encryption key must be {} bytes long, but was {} bytes long",
+        KEY_LEN,
+        length
+    ))]
+    InvalidKeyLength { length: usize },
+}
+
+/// Encryption configuration for disk buffers.
+///
+/// Keys are base64-encoded 256-bit AES keys, which are typically sourced from a secrets backend via
+/// `SECRET[backend.key]` rather than being written into the configuration in plaintext.
+#[derive(Clone, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct EncryptionConfig {
+    /// Key used to encrypt new records, and to decrypt records encrypted with it.
+    pub key: String,
+
+    /// Keys that records were previously encrypted with.
+    ///
+    /// When rotating keys, the old key is moved here so that records written before the rotation can
+    /// still be read.  It can be removed once those records have been read out of the buffer.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub previous_keys: Vec<String>,
+}
+
+// The keys are secrets, so they never end up in logs.
+impl fmt::Debug for EncryptionConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncryptionConfig")
+            .field("key", &"**REDACTED**")
+            .field("previous_keys", &self.previous_keys.len())
+            .finish()
+    }
+}
+
+struct Key {
+    /// Truncated SHA-256 digest of the key, identifying which key a record was encrypted with.
+    id: [u8; KEY_ID_LEN],
+    key: LessSafeKey,
+}
+
+impl Key {
+    fn from_encoded(encoded: &str) -> Result<Self, EncryptionConfigError> {
+        let raw = base64::decode(encoded.trim()).context(InvalidKeyEncodingSnafu)?;
+        if raw.len() != KEY_LEN {
+            return Err(EncryptionConfigError::InvalidKeyLength { length: raw.len() });
+        }
+
+        let mut id = [0; KEY_ID_LEN];
+        id.copy_from_slice(&digest(&SHA256, &raw).as_ref()[..KEY_ID_LEN]);
+        let key = UnboundKey::new(&AES_256_GCM, &raw).expect("key length already validated");
+
+        Ok(Self {
+            id,
+            key: LessSafeKey::new(key),
+        })
+    }
+}
+
+struct Keys {
+    current: Key,
+    previous: Vec<Key>,
+    rng: SystemRandom,
+}
+
+/// Encrypts and decrypts record payloads with AES-256-GCM.
+///
+/// Encrypted payloads are laid out as the ID of the key used, the number of events in the record, the
+/// nonce, and the ciphertext followed by the authentication tag.  The event count is left in the
+/// clear so that the writer can validate the last written record without being able to decrypt it.
+/// It is authenticated along with the record ID, so that payloads can't be swapped between records,
+/// nor their event count changed, without being detected.
+///
+/// Nonces are generated randomly for each record.
+#[derive(Clone)]
+pub struct Encryption {
+    keys: Arc<Keys>,
+}
+
+impl Encryption {
+    /// Creates an [`Encryption`] from the given configuration.
+    ///
+    /// # Errors
+    ///
+    /// If any of the configured keys are not valid base64-encoded 256-bit keys, an error variant
+    /// will be returned describing the error.
+    pub fn from_config(config: &EncryptionConfig) -> Result<Self, EncryptionConfigError> {
+        let current = Key::from_encoded(&config.key)?;
+        let previous = config
+            .previous_keys
+            .iter()
+            .map(|key| Key::from_encoded(key))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            keys: Arc::new(Keys {
+                current,
+                previous,
+                rng: SystemRandom::new(),
+            }),
+        })
+    }
+
+    /// Encrypts `plaintext` with the current key, writing the encrypted payload to `dst`.
+    ///
+    /// # Errors
+    ///
+    /// If a nonce could not be generated, or the encryption itself failed, an error is returned.
+    pub(crate) fn seal(
+        &self,
+        record_id: u64,
+        event_count: u64,
+        plaintext: &[u8],
+        dst: &mut Vec<u8>,
+    ) -> Result<(), ring::error::Unspecified> {
+        let key = &self.keys.current;
+        let mut nonce = [0; NONCE_LEN];
+        self.keys.rng.fill(&mut nonce)?;
+
+        dst.clear();
+        dst.extend_from_slice(&key.id);
+        dst.extend_from_slice(&event_count.to_be_bytes());
+        dst.extend_from_slice(&nonce);
+        let header_len = dst.len();
+        dst.extend_from_slice(plaintext);
+
+        let tag = key.key.seal_in_place_separate_tag(
+            Nonce::assume_unique_for_key(nonce),
+            additional_data(record_id, event_count),
+            &mut dst[header_len..],
+        )?;
+        dst.extend_from_slice(tag.as_ref());
+
+        Ok(())
+    }
+
+    /// Decrypts the encrypted payload of the record with the given ID.
+    ///
+    /// # Errors
+    ///
+    /// If the payload was encrypted with a key that is not configured, or it fails to be
+    /// authenticated, a message describing the error is returned.
+    pub(crate) fn open(&self, record_id: u64, payload: &[u8]) -> Result<Vec<u8>, String> {
+        let event_count = Self::event_count(payload)?;
+        if payload.len() < KEY_ID_LEN + EVENT_COUNT_LEN + NONCE_LEN + AES_256_GCM.tag_len() {
+            return Err("encrypted payload is too short".to_string());
+        }
+
+        let (key_id, rest) = payload.split_at(KEY_ID_LEN);
+        let (nonce, ciphertext) = rest[EVENT_COUNT_LEN..].split_at(NONCE_LEN);
+        let key = std::iter::once(&self.keys.current)
+            .chain(self.keys.previous.iter())
+            .find(|key| key.id == key_id)
+            .ok_or_else(|| {
+                "record was encrypted with a key that is not configured for this buffer".to_string()
+            })?;
+
+        let nonce = Nonce::try_assume_unique_for_key(nonce).expect("nonce length already checked");
+        let mut plaintext = ciphertext.to_vec();
+        let plaintext_len = key
+            .key
+            .open_in_place(
+                nonce,
+                additional_data(record_id, event_count),
+                &mut plaintext,
+            )
+            .map_err(|_| "encrypted payload failed authentication".to_string())?
+            .len();
+        plaintext.truncate(plaintext_len);
+
+        Ok(plaintext)
+    }
+
+    /// Gets the number of events in the record with the given encrypted payload, without
+    /// decrypting it.
+    ///
+    /// The event count is only authenticated once the payload is decrypted, so this is only as
+    /// trustworthy as the record checksum.
+    ///
+    /// # Errors
+    ///
+    /// If the payload is too short to hold an event count, a message describing the error is
+    /// returned.
+    pub(crate) fn event_count(payload: &[u8]) -> Result<u64, String> {
+        payload
+            .get(KEY_ID_LEN..KEY_ID_LEN + EVENT_COUNT_LEN)
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(u64::from_be_bytes)
+            .ok_or_else(|| "encrypted payload is too short".to_string())
+    }
+}
+
+fn additional_data(record_id: u64, event_count: u64) -> Aad<[u8; 16]> {
+    let mut aad = [0; 16];
+    aad[..8].copy_from_slice(&record_id.to_be_bytes());
+    aad[8..].copy_from_slice(&event_count.to_be_bytes());
+    Aad::from(aad)
+}
+
+impl fmt::Debug for Encryption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Encryption")
+            .field("previous_keys", &self.keys.previous.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(key: u8, previous_keys: &[u8]) -> EncryptionConfig {
+        EncryptionConfig {
+            key: base64::encode([key; KEY_LEN]),
+            previous_keys: previous_keys
+                .iter()
+                .map(|key| base64::encode([*key; KEY_LEN]))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn roundtrip_and_rotation() {
+        let old = Encryption::from_config(&config(1, &[])).unwrap();
+        let rotated = Encryption::from_config(&config(2, &[1])).unwrap();
+        let other = Encryption::from_config(&config(3, &[])).unwrap();
+
+        let mut sealed = Vec::new();
+        old.seal(42, 3, b"hello world", &mut sealed).unwrap();
+        assert!(!sealed
+            .windows(b"hello world".len())
+            .any(|window| window == b"hello world"));
+
+        assert_eq!(old.open(42, &sealed).unwrap(), b"hello world");
+        // Records written with a previous key can still be read after rotating it.
+        assert_eq!(rotated.open(42, &sealed).unwrap(), b"hello world");
+        assert!(other.open(42, &sealed).is_err());
+        // The payload is bound to its record.
+        assert!(old.open(43, &sealed).is_err());
+
+        // The event count can be read without the key, but not tampered with.
+        assert_eq!(Encryption::event_count(&sealed), Ok(3));
+        let mut tampered = sealed.clone();
+        tampered[KEY_ID_LEN + EVENT_COUNT_LEN - 1] ^= 1;
+        assert!(old.open(42, &tampered).is_err());
+
+        let last = sealed.len() - 1;
+        sealed[last] ^= 1;
+        assert!(old.open(42, &sealed).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_keys() {
+        let mut config = config(1, &[]);
+        config.key = base64::encode([1; 16]);
+        assert!(matches!(
+            Encryption::from_config(&config),
+            Err(EncryptionConfigError::InvalidKeyLength { length: 16 })
+        ));
+
+        config.key = "not base64!".to_string();
+        assert!(matches!(
+            Encryption::from_config(&config),
+            Err(EncryptionConfigError::InvalidKeyEncoding { .. })
+        ));
+    }
+}
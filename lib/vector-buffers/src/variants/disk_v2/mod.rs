@@ -149,6 +149,7 @@ use snafu::{ResultExt, Snafu};
 mod acknowledgements;
 mod backed_archive;
 mod common;
+mod encryption;
 mod io;
 mod ledger;
 mod reader;
@@ -164,7 +165,8 @@ use self::{
     acknowledgements::create_disk_v2_acker, ledger::Ledger, v1_migration::try_disk_v1_migration,
 };
 pub use self::{
-    common::{DiskBufferConfig, DiskBufferConfigBuilder},
+    common::{DiskBufferConfig, DiskBufferConfigBuilder, DEFAULT_MAX_DATA_FILE_SIZE},
+    encryption::{Encryption, EncryptionConfig, EncryptionConfigError},
     io::{Filesystem, ProductionFilesystem},
     ledger::LedgerLoadCreateError,
     reader::{Reader, ReaderError},
@@ -270,6 +272,7 @@ pub struct DiskV2Buffer {
     id: String,
     data_dir: PathBuf,
     max_size: NonZeroU64,
+    encryption: Option<EncryptionConfig>,
 }
 
 impl DiskV2Buffer {
@@ -278,8 +281,16 @@ impl DiskV2Buffer {
             id,
             data_dir,
             max_size,
+            encryption: None,
         }
     }
+
+    /// Sets the encryption configuration for records written to the buffer.
+    #[must_use]
+    pub fn with_encryption(mut self, encryption: Option<EncryptionConfig>) -> Self {
+        self.encryption = encryption;
+        self
+    }
 }
 
 #[async_trait]
@@ -298,7 +309,12 @@ where
     {
         // Attempt to migrate a disk v1 buffer based on the same data directory and buffer ID if one
         // exists. If one doesn't exist, then this method does nothing.
-        try_disk_v1_migration::<T>(self.data_dir.as_path(), self.id.as_str()).await?;
+        try_disk_v1_migration::<T>(
+            self.data_dir.as_path(),
+            self.id.as_str(),
+            self.encryption.as_ref(),
+        )
+        .await?;
 
         // Now that we've handled any necessary migrations, go ahead and build the buffer.
         let (writer, reader, acker) = build_disk_v2_buffer(
@@ -306,6 +322,7 @@ where
             &self.data_dir,
             self.id.as_str(),
             self.max_size,
+            self.encryption.as_ref(),
         )
         .await?;
 
@@ -318,6 +335,7 @@ async fn build_disk_v2_buffer<T>(
     data_dir: &Path,
     id: &str,
     max_size: NonZeroU64,
+    encryption: Option<&EncryptionConfig>,
 ) -> Result<
     (
         Writer<T, ProductionFilesystem>,
@@ -331,9 +349,12 @@ where
 {
     usage_handle.set_buffer_limits(Some(max_size.get()), None);
 
+    let encryption = encryption.map(Encryption::from_config).transpose()?;
+
     let buffer_path = get_disk_v2_data_dir_path(data_dir, id);
     let config = DiskBufferConfigBuilder::from_path(buffer_path)
         .max_buffer_size(max_size.get())
+        .encryption(encryption)
         .build()?;
     Buffer::from_config(config, usage_handle)
         .await
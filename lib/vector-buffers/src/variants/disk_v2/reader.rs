@@ -15,6 +15,7 @@ use vector_common::internal_event::emit;
 
 use super::{
     common::create_crc32c_hasher,
+    encryption::{Encryption, ENCRYPTED_RECORD_FLAG},
     ledger::Ledger,
     record::{validate_record_archive, ArchivedRecord, Record, RecordStatus},
     Filesystem,
@@ -108,6 +109,15 @@ where
     #[snafu(display("record version not compatible: {}", reason))]
     Incompatible { reason: String },
 
+    /// The record could not be decrypted.
+    ///
+    /// This can occur when the record was encrypted with a key that is no longer configured, such
+    /// as when a key was rotated without keeping the previous key, or when the record was encrypted
+    /// but encryption is no longer configured at all.  It can also indicate that the encrypted
+    /// payload was tampered with, as it failed to be authenticated.
+    #[snafu(display("failed to decrypt record: {}", reason))]
+    Decryption { reason: String },
+
     /// The reader detected that a data file contains a partially-written record.
     ///
     /// Records should never be partially written to a data file (we don't split records across data
@@ -160,7 +170,8 @@ impl<T: Bufferable> PartialEq for ReaderError<T> {
                 },
             ) => l_calculated == r_calculated && l_actual == r_actual,
             (Self::Decode { .. }, Self::Decode { .. }) => true,
-            (Self::Incompatible { reason: l_reason }, Self::Incompatible { reason: r_reason }) => {
+            (Self::Incompatible { reason: l_reason }, Self::Incompatible { reason: r_reason })
+            | (Self::Decryption { reason: l_reason }, Self::Decryption { reason: r_reason }) => {
                 l_reason == r_reason
             }
             _ => core::mem::discriminant(self) == core::mem::discriminant(other),
@@ -173,6 +184,7 @@ pub(super) struct RecordReader<R, T> {
     reader: BufReader<R>,
     aligned_buf: AlignedVec,
     checksummer: Hasher,
+    encryption: Option<Encryption>,
     current_record_id: u64,
     _t: PhantomData<T>,
 }
@@ -186,11 +198,12 @@ where
     ///
     /// Internally, the reader is wrapped in a [`BufReader`], so callers should not pass in an
     /// already buffered reader.
-    pub fn new(reader: R) -> Self {
+    pub fn new(reader: R, encryption: Option<Encryption>) -> Self {
         Self {
             reader: BufReader::with_capacity(256 * 1024, reader),
             aligned_buf: AlignedVec::new(),
             checksummer: create_crc32c_hasher(),
+            encryption,
             current_record_id: 0,
             _t: PhantomData,
         }
@@ -350,7 +363,7 @@ where
         // - `try_next_record` does all the archive checks, checksum validation, etc
         let record = unsafe { archived_root::<Record<'_>>(&self.aligned_buf) };
 
-        decode_record_payload(record)
+        decode_record_payload(record, self.encryption.as_ref())
     }
 }
 
@@ -770,7 +783,8 @@ where
                 "Opened data file for reading."
             );
 
-            self.reader = Some(RecordReader::new(data_file));
+            let encryption = self.ledger.config().encryption.clone();
+            self.reader = Some(RecordReader::new(data_file, encryption));
             return Ok(());
         }
     }
@@ -835,8 +849,8 @@ where
                 } => {
                     let record = try_as_record_archive(data_file_mmap.as_ref())
                         .expect("record was already validated");
-                    let item = match decode_record_payload::<T>(record) {
-                        Ok(item) => item,
+                    let record_events = match record_event_count::<T>(record) {
+                        Ok(record_events) => record_events,
                         // If there's an error decoding the item, just fall back to the slow path,
                         // because this file might actually be where we left off, so we don't want
                         // to incorrectly skip ahead or anything.
@@ -849,8 +863,6 @@ where
                     // the number of events in the record, which is how we can determine the event
                     // count from the record IDs alone, without having to read every record in the
                     // buffer during startup.)
-                    let record_events = u64::try_from(item.event_count())
-                        .expect("event count should never exceed u64");
                     let last_record_id_in_data_file =
                         last_record_id.wrapping_add(record_events.saturating_sub(1));
 
@@ -909,12 +921,32 @@ where
     /// If the writer is closed and there is no more data in the buffer, `None` is returned.
     /// Otherwise, reads the next record or waits until the next record is available.
     ///
+    /// Records that can't be decrypted are skipped, as they can no more be acted upon than corrupted
+    /// records.
+    ///
     /// # Errors
     ///
     /// If an error occurred while reading a record, an error variant will be returned describing
     /// the error.
     #[cfg_attr(test, instrument(skip(self), level = "trace"))]
     pub async fn next(&mut self) -> Result<Option<T>, ReaderError<T>> {
+        loop {
+            match self.try_next().await {
+                // The record was encrypted with a key that is no longer configured, or was tampered
+                // with.  Skipping it leaves a gap in the record IDs, which gets reported as corrupted
+                // events once the records after it are acknowledged, like skipped corrupted records.
+                Err(ReaderError::Decryption { reason }) => {
+                    error!(
+                        %reason,
+                        "Skipping record that could not be decrypted.  Buffer data loss has occurred."
+                    );
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn try_next(&mut self) -> Result<Option<T>, ReaderError<T>> {
         let mut force_check_pending_data_files = false;
 
         let token = loop {
@@ -1081,12 +1113,35 @@ where
     }
 }
 
+/// Gets the number of events in a record.
+///
+/// Encrypted records hold their event count in the clear, so this works even when the key the
+/// record was encrypted with is no longer configured.
+pub(crate) fn record_event_count<T: Bufferable>(
+    record: &ArchivedRecord<'_>,
+) -> Result<u64, ReaderError<T>> {
+    if record.metadata() & ENCRYPTED_RECORD_FLAG != 0 {
+        Encryption::event_count(record.payload())
+            .map_err(|reason| ReaderError::Decryption { reason })
+    } else {
+        decode_record_payload::<T>(record, None).map(|item| {
+            u64::try_from(item.event_count()).expect("event count should never exceed u64")
+        })
+    }
+}
+
 pub(crate) fn decode_record_payload<T: Bufferable>(
     record: &ArchivedRecord<'_>,
+    encryption: Option<&Encryption>,
 ) -> Result<T, ReaderError<T>> {
+    // Records with an encrypted payload are flagged as such in their metadata, so strip that flag
+    // before handing the metadata to `T`, which knows nothing about it.
+    let is_encrypted = record.metadata() & ENCRYPTED_RECORD_FLAG != 0;
+    let raw_metadata = record.metadata() & !ENCRYPTED_RECORD_FLAG;
+
     // Try and convert the raw record metadata into the true metadata type used by `T`, and then
     // also verify that `T` is able to decode records with the metadata used for this record in particular.
-    let metadata = T::Metadata::from_u32(record.metadata()).ok_or(ReaderError::Incompatible {
+    let metadata = T::Metadata::from_u32(raw_metadata).ok_or(ReaderError::Incompatible {
         reason: format!("invalid metadata for {}", std::any::type_name::<T>()),
     })?;
 
@@ -1099,6 +1154,16 @@ pub(crate) fn decode_record_payload<T: Bufferable>(
         });
     }
 
-    // Now we can finally try decoding.
-    T::decode(metadata, record.payload()).context(DecodeSnafu)
+    // Now we can finally try decoding, decrypting the payload first if need be.
+    if is_encrypted {
+        let encryption = encryption.ok_or_else(|| ReaderError::Decryption {
+            reason: "record is encrypted but encryption is not configured".to_string(),
+        })?;
+        let payload = encryption
+            .open(record.id(), record.payload())
+            .map_err(|reason| ReaderError::Decryption { reason })?;
+        T::decode(metadata, &payload[..]).context(DecodeSnafu)
+    } else {
+        T::decode(metadata, record.payload()).context(DecodeSnafu)
+    }
 }
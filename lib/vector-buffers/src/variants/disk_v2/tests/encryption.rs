@@ -0,0 +1,139 @@
+use super::create_buffer_v2_with_encryption;
+use crate::{
+    test::common::{with_temp_dir, SizedRecord},
+    variants::disk_v2::{Encryption, EncryptionConfig},
+};
+
+fn encryption(key: u8, previous_keys: &[u8]) -> Encryption {
+    let config = EncryptionConfig {
+        key: base64::encode([key; 32]),
+        previous_keys: previous_keys
+            .iter()
+            .map(|key| base64::encode([*key; 32]))
+            .collect(),
+    };
+    Encryption::from_config(&config).expect("key should be valid")
+}
+
+#[tokio::test]
+async fn reads_records_across_encryption_changes() {
+    with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            // Write a plaintext record before encryption is enabled.
+            let (mut writer, _, _, ledger) =
+                create_buffer_v2_with_encryption(&data_dir, None).await;
+            writer
+                .write_record(SizedRecord(32))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("flush should not fail");
+            drop(writer);
+            drop(ledger);
+
+            // Enable encryption and write another record.
+            let (mut writer, _, _, ledger) =
+                create_buffer_v2_with_encryption(&data_dir, Some(encryption(1, &[]))).await;
+            writer
+                .write_record(SizedRecord(64))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("flush should not fail");
+            drop(writer);
+            drop(ledger);
+
+            // Rotate the key, keeping the old one around so that existing records can be read.
+            let (mut writer, mut reader, acker, _ledger) =
+                create_buffer_v2_with_encryption(&data_dir, Some(encryption(2, &[1]))).await;
+            writer
+                .write_record(SizedRecord(128))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("flush should not fail");
+            writer.close();
+
+            for expected in [32, 64, 128] {
+                let record = reader.next().await.expect("read should not fail");
+                assert_eq!(record, Some(SizedRecord(expected)));
+                acker.ack(1);
+            }
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn opens_buffer_after_encryption_is_disabled() {
+    with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            let (mut writer, _, _, ledger) =
+                create_buffer_v2_with_encryption(&data_dir, Some(encryption(1, &[]))).await;
+            writer
+                .write_record(SizedRecord(32))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("flush should not fail");
+            drop(writer);
+            drop(ledger);
+
+            // The last written record is validated without being decrypted, so the buffer opens,
+            // and the record which can no longer be decrypted is skipped.
+            let (mut writer, mut reader, acker, _ledger) =
+                create_buffer_v2_with_encryption(&data_dir, None).await;
+            writer
+                .write_record(SizedRecord(64))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("flush should not fail");
+            writer.close();
+
+            let record = reader.next().await.expect("read should not fail");
+            assert_eq!(record, Some(SizedRecord(64)));
+            acker.ack(1);
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn skips_records_encrypted_with_removed_key() {
+    with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            let (mut writer, _, _, ledger) =
+                create_buffer_v2_with_encryption(&data_dir, Some(encryption(1, &[]))).await;
+            writer
+                .write_record(SizedRecord(32))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("flush should not fail");
+            drop(writer);
+            drop(ledger);
+
+            // Rotate the key, and write a record with the new one.
+            let (mut writer, _, _, ledger) =
+                create_buffer_v2_with_encryption(&data_dir, Some(encryption(2, &[1]))).await;
+            writer
+                .write_record(SizedRecord(64))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("flush should not fail");
+            drop(writer);
+            drop(ledger);
+
+            // Remove the old key before the record encrypted with it was read.
+            let (mut writer, mut reader, acker, _ledger) =
+                create_buffer_v2_with_encryption(&data_dir, Some(encryption(2, &[]))).await;
+            writer.close();
+
+            let record = reader.next().await.expect("read should not fail");
+            assert_eq!(record, Some(SizedRecord(64)));
+            acker.ack(1);
+        }
+    })
+    .await;
+}
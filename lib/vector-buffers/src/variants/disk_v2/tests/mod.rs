@@ -8,6 +8,7 @@ use async_trait::async_trait;
 use tokio::io::DuplexStream;
 
 use super::{
+    encryption::Encryption,
     io::{AsyncFile, Metadata, ProductionFilesystem, ReadableMemoryMap, WritableMemoryMap},
     Buffer, DiskBufferConfigBuilder, Ledger, Reader, Writer,
 };
@@ -17,6 +18,7 @@ type FilesystemUnderTest = ProductionFilesystem;
 
 mod acknowledgements;
 mod basic;
+mod encryption;
 mod invariants;
 mod known_errors;
 mod model;
@@ -307,3 +309,27 @@ where
         .await
         .expect("should not fail to create buffer")
 }
+
+pub(crate) async fn create_buffer_v2_with_encryption<P, R>(
+    data_dir: P,
+    encryption: Option<Encryption>,
+) -> (
+    Writer<R, FilesystemUnderTest>,
+    Reader<R, FilesystemUnderTest>,
+    Acker,
+    Arc<Ledger<FilesystemUnderTest>>,
+)
+where
+    P: AsRef<Path>,
+    R: Bufferable,
+{
+    let config = DiskBufferConfigBuilder::from_path(data_dir)
+        .encryption(encryption)
+        .build()
+        .expect("creating buffer should not fail");
+    let usage_handle = BufferUsageHandle::noop(WhenFull::Block);
+
+    Buffer::from_config_inner(config, usage_handle)
+        .await
+        .expect("should not fail to create buffer")
+}
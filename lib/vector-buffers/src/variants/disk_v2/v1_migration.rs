@@ -4,13 +4,17 @@ use crate::{
     buffer_usage_data::BufferUsageHandle,
     topology::{builder::IntoBuffer, channel::ReceiverAdapter},
     variants::{
-        disk_v2::{build_disk_v2_buffer, get_disk_v2_data_dir_path},
+        disk_v2::{build_disk_v2_buffer, get_disk_v2_data_dir_path, EncryptionConfig},
         DiskV1Buffer,
     },
     Acker, Bufferable, WhenFull,
 };
 
-pub async fn try_disk_v1_migration<T>(base_data_dir: &Path, id: &str) -> Result<(), String>
+pub async fn try_disk_v1_migration<T>(
+    base_data_dir: &Path,
+    id: &str,
+    encryption: Option<&EncryptionConfig>,
+) -> Result<(), String>
 where
     T: Bufferable + Clone,
 {
@@ -62,7 +66,7 @@ where
     let dst_buffer_dir = get_disk_v2_data_dir_path(base_data_dir, id);
 
     let (mut dst_writer, _, _) =
-        build_disk_v2_buffer(usage_handle, base_data_dir, id, buffer_max_size)
+        build_disk_v2_buffer(usage_handle, base_data_dir, id, buffer_max_size, encryption)
             .await
             .map_err(|e| format!("Failed to build `disk_v2` buffer: {}", e))?;
 
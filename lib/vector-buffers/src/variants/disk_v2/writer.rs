@@ -25,15 +25,14 @@ use tokio::io::{AsyncWrite, AsyncWriteExt};
 
 use super::{
     common::{create_crc32c_hasher, DiskBufferConfig},
+    encryption::{Encryption, ENCRYPTED_RECORD_FLAG},
     io::Filesystem,
     ledger::Ledger,
     record::{validate_record_archive, Record, RecordStatus},
 };
 use crate::{
-    encoding::{AsMetadata, Encodable},
-    variants::disk_v2::{
-        io::AsyncFile, reader::decode_record_payload, record::try_as_record_archive,
-    },
+    encoding::{AsMetadata, Encodable, RESERVED_METADATA_BITS},
+    variants::disk_v2::{io::AsyncFile, reader::record_event_count, record::try_as_record_archive},
     Bufferable,
 };
 
@@ -109,6 +108,13 @@ where
     #[snafu(display("failed to serialize encoded record to buffer: {}", reason))]
     FailedToSerialize { reason: String },
 
+    /// The writer failed to encrypt the record.
+    ///
+    /// This can only occur when encryption is configured, and in practice, only if the system is
+    /// unable to provide the random bytes needed to generate a nonce.
+    #[snafu(display("failed to encrypt encoded record"))]
+    FailedToEncrypt,
+
     /// The writer failed to validate the last written record.
     ///
     /// Specifically, for `Writer`, this can only ever be returned when creating the buffer, during
@@ -346,9 +352,11 @@ impl<W: fmt::Debug> fmt::Debug for TrackingBufWriter<W> {
 pub(super) struct RecordWriter<W, T> {
     writer: TrackingBufWriter<W>,
     encode_buf: Vec<u8>,
+    encrypt_buf: Vec<u8>,
     ser_buf: AlignedVec,
     ser_scratch: AlignedVec,
     checksummer: Hasher,
+    encryption: Option<Encryption>,
     max_record_size: usize,
     current_data_file_size: u64,
     max_data_file_size: u64,
@@ -364,19 +372,33 @@ where
     ///
     /// Internally, the writer is wrapped in a [`BufWriter`], so callers should not pass in an
     /// already buffered writer.
+    ///
+    /// # Panics
+    ///
+    /// If the metadata of `T` sets any of the bits reserved for the buffer, this method will panic,
+    /// as its records could not be told apart from encrypted records.
     pub fn new(
         writer: W,
         current_data_file_size: u64,
         write_buffer_size: usize,
         max_data_file_size: u64,
         max_record_size: usize,
+        encryption: Option<Encryption>,
     ) -> Self {
+        assert_eq!(
+            T::get_metadata().into_u32() & RESERVED_METADATA_BITS,
+            0,
+            "record metadata must not use the bits reserved for the buffer"
+        );
+
         Self {
             writer: TrackingBufWriter::with_capacity(write_buffer_size, writer),
             encode_buf: Vec::with_capacity(16_384),
+            encrypt_buf: Vec::new(),
             ser_buf: AlignedVec::with_capacity(16_384),
             ser_scratch: AlignedVec::with_capacity(16_384),
             checksummer: create_crc32c_hasher(),
+            encryption,
             max_record_size,
             current_data_file_size,
             max_data_file_size,
@@ -430,6 +452,8 @@ where
         // the actual encoded size and then check it against the limit.
         //
         // C'est la vie.
+        let event_count =
+            u64::try_from(record.event_count()).expect("event count should never exceed u64");
         let encode_result = {
             let mut encode_buf = (&mut self.encode_buf).limit(self.max_record_size);
             record.encode(&mut encode_buf)
@@ -443,9 +467,19 @@ where
             });
         }
 
+        // When encryption is configured, we encrypt the encoded record, and flag the record as
+        // encrypted so that the reader knows to decrypt it before decoding it.
         let metadata = T::get_metadata().into_u32();
-        let wrapped_record =
-            Record::with_checksum(id, metadata, &self.encode_buf, &self.checksummer);
+        let (metadata, payload) = match &self.encryption {
+            None => (metadata, &self.encode_buf),
+            Some(encryption) => {
+                encryption
+                    .seal(id, event_count, &self.encode_buf, &mut self.encrypt_buf)
+                    .map_err(|_| WriterError::FailedToEncrypt)?;
+                (metadata | ENCRYPTED_RECORD_FLAG, &self.encrypt_buf)
+            }
+        };
+        let wrapped_record = Record::with_checksum(id, metadata, payload, &self.checksummer);
 
         // Push 8 dummy bytes where our length delimiter will sit.  We'll fix this up after
         // serialization.  Notably, `AlignedSerializer` will report the serializer position as
@@ -751,23 +785,21 @@ where
                 id: last_record_id, ..
             } => {
                 // We now know the record is valid from the perspective of being framed correctly,
-                // and the checksum matching, etc.  We'll attempt to actually decode it now so we
-                // can get the actual item that was written, which we need to understand where the
-                // next writer record ID should be.
+                // and the checksum matching, etc.  We'll get the number of events it holds now,
+                // which we need to understand where the next writer record ID should be.  This
+                // doesn't require decrypting the record, so the buffer can still be opened after
+                // the key it was encrypted with was removed, and the reader skips it.
                 let record = try_as_record_archive(data_file_mmap.as_ref())
                     .expect("record was already validated");
-                let item = decode_record_payload::<T>(record).map_err(|e| {
-                    WriterError::FailedToValidate {
+                let record_events =
+                    record_event_count::<T>(record).map_err(|e| WriterError::FailedToValidate {
                         reason: e.to_string(),
-                    }
-                })?;
+                    })?;
 
                 // Since we have a valid record, checksum and all, see if the writer record ID
                 // in the ledger lines up with the record ID we have here.  Specifically, the record
                 // ID plus the number of events in the record should be the next record ID that gets used.
                 let ledger_next = self.ledger.state().get_next_writer_record_id();
-                let record_events =
-                    u64::try_from(item.event_count()).expect("event count should never exceed u64");
                 let record_next = last_record_id.wrapping_add(record_events);
 
                 match ledger_next.cmp(&record_next) {
@@ -1000,6 +1032,7 @@ where
                     self.config.write_buffer_size,
                     self.config.max_data_file_size,
                     self.config.max_record_size,
+                    self.config.encryption.clone(),
                 ));
                 self.data_file_size = data_file_size;
 
@@ -1,4 +1,4 @@
-use std::{fs::DirBuilder, path::PathBuf};
+use std::{fs::DirBuilder, num::NonZeroU64, path::PathBuf};
 
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
@@ -45,6 +45,17 @@ pub struct GlobalOptions {
         skip_serializing_if = "crate::serde::skip_serializing_if_default"
     )]
     pub acknowledgements: AcknowledgementsConfig,
+    /// Fuse chains of adjacent `remap` transforms into a single component when the topology is
+    /// built, so events don't have to cross a channel between each of them.
+    #[serde(skip_serializing_if = "crate::serde::skip_serializing_if_default")]
+    pub fuse_transforms: bool,
+    /// The maximum number of bytes per second sent over HTTP by all the sinks together.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub egress_bytes_per_sec: Option<NonZeroU64>,
+    /// The maximum number of bytes taken by all the disk buffers together, which is allocated to
+    /// each of them in proportion to their weight.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disk_buffer_max_size: Option<NonZeroU64>,
 }
 
 impl GlobalOptions {
@@ -1,4 +1,4 @@
-use std::{fmt, num::NonZeroUsize};
+use std::{fmt, num::NonZeroUsize, path::PathBuf};
 
 use bitmask_enum::bitmask;
 use serde::{Deserialize, Serialize};
@@ -141,6 +141,62 @@ impl Output {
     }
 }
 
+/// Something a component needs access to, which its sandbox must allow.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Capability {
+    /// Reading or writing the files under a path.
+    Path(PathBuf),
+    /// Connecting to, or listening on, a host, on any port when `port` isn't known.
+    Host { host: String, port: Option<u16> },
+}
+
+impl Capability {
+    pub fn path(path: impl Into<PathBuf>) -> Self {
+        Self::Path(path.into())
+    }
+
+    pub fn host(host: impl Into<String>, port: Option<u16>) -> Self {
+        Self::Host {
+            host: host.into(),
+            port,
+        }
+    }
+
+    /// The host of an URI, on its explicit port or the default one of its scheme.
+    pub fn uri(uri: &http::Uri) -> Option<Self> {
+        let port = uri.port_u16().or_else(|| match uri.scheme_str() {
+            Some("https") => Some(443),
+            Some("http") => Some(80),
+            _ => None,
+        });
+        uri.host().map(|host| Self::host(host, port))
+    }
+
+    /// The directory a glob pattern matches files in, up to its first wildcard.
+    pub fn glob(pattern: &str) -> Self {
+        match pattern.find(|c| matches!(c, '*' | '?' | '[' | '{')) {
+            Some(index) => {
+                let prefix = &pattern[..index];
+                Self::path(prefix.rfind('/').map_or("", |slash| &prefix[..=slash]))
+            }
+            None => Self::path(pattern),
+        }
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Path(path) => write!(f, "path \"{}\"", path.display()),
+            Self::Host { host, port: None } => write!(f, "host \"{}\"", host),
+            Self::Host {
+                host,
+                port: Some(port),
+            } => write!(f, "host \"{}:{}\"", host, port),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct AcknowledgementsConfig {
     enabled: Option<bool>,
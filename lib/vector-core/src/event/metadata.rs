@@ -1,11 +1,11 @@
 #![deny(missing_docs)]
 
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 use serde::{Deserialize, Serialize};
 use vector_common::EventDataEq;
 
-use super::{BatchNotifier, EventFinalizer, EventFinalizers, EventStatus};
+use super::{BatchNotifier, EventFinalizer, EventFinalizers, EventStatus, Value};
 use crate::{schema, ByteSizeOf};
 
 /// The top-level metadata structure contained by both `struct Metric`
@@ -18,6 +18,18 @@ pub struct EventMetadata {
     /// Used to store the Splunk HEC auth token from sources to sinks
     #[serde(default, skip)]
     splunk_hec_token: Option<Arc<str>>,
+    /// Used to store the W3C `traceparent` of the request an event was received in
+    #[serde(default, skip)]
+    trace_parent: Option<Arc<str>>,
+    /// Used to store the headers of the request an event was received in
+    #[serde(default, skip)]
+    http_headers: Option<Arc<BTreeMap<String, String>>>,
+    /// Used to store the position of an event in the events of its source
+    #[serde(default, skip)]
+    sequence_number: Option<SequenceNumber>,
+    /// Used to store the OpenTelemetry resource and instrumentation scope that produced an event
+    #[serde(default, skip)]
+    opentelemetry_scope: Option<Arc<OpenTelemetryScope>>,
     #[serde(default, skip)]
     finalizers: EventFinalizers,
 
@@ -49,6 +61,64 @@ impl EventMetadata {
     pub fn set_splunk_hec_token(&mut self, token: Option<Arc<str>>) {
         self.splunk_hec_token = token;
     }
+
+    /// Return the W3C `traceparent`, if it exists
+    pub fn trace_parent(&self) -> &Option<Arc<str>> {
+        &self.trace_parent
+    }
+
+    /// Set the W3C `traceparent` to passed value
+    pub fn set_trace_parent(&mut self, trace_parent: Option<Arc<str>>) {
+        self.trace_parent = trace_parent;
+    }
+
+    /// Return the headers of the request, if they exist
+    pub fn http_headers(&self) -> &Option<Arc<BTreeMap<String, String>>> {
+        &self.http_headers
+    }
+
+    /// Set the headers of the request to passed value
+    pub fn set_http_headers(&mut self, http_headers: Option<Arc<BTreeMap<String, String>>>) {
+        self.http_headers = http_headers;
+    }
+
+    /// Return the sequence number, if it exists
+    pub fn sequence_number(&self) -> &Option<SequenceNumber> {
+        &self.sequence_number
+    }
+
+    /// Set the sequence number to passed value
+    pub fn set_sequence_number(&mut self, sequence_number: Option<SequenceNumber>) {
+        self.sequence_number = sequence_number;
+    }
+
+    /// Return the OpenTelemetry resource and instrumentation scope, if they exist
+    pub fn opentelemetry_scope(&self) -> &Option<Arc<OpenTelemetryScope>> {
+        &self.opentelemetry_scope
+    }
+
+    /// Set the OpenTelemetry resource and instrumentation scope to passed value
+    pub fn set_opentelemetry_scope(&mut self, scope: Option<Arc<OpenTelemetryScope>>) {
+        self.opentelemetry_scope = scope;
+    }
+}
+
+/// The OpenTelemetry resource and instrumentation scope that produced an event.
+#[derive(Clone, Debug, Default, PartialEq, PartialOrd)]
+pub struct OpenTelemetryScope {
+    /// The attributes of the resource.
+    pub resource: Value,
+    /// The name, version and attributes of the instrumentation scope.
+    pub scope: Value,
+}
+
+/// The position of an event in the stream of events sent by a source output, numbered from zero.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SequenceNumber {
+    /// The source output that numbered the event.
+    pub stream: Arc<str>,
+    /// The number of events the source output sent before this one.
+    pub number: u64,
 }
 
 impl Default for EventMetadata {
@@ -56,6 +126,10 @@ impl Default for EventMetadata {
         Self {
             datadog_api_key: Default::default(),
             splunk_hec_token: Default::default(),
+            trace_parent: Default::default(),
+            http_headers: Default::default(),
+            sequence_number: Default::default(),
+            opentelemetry_scope: Default::default(),
             finalizers: Default::default(),
             schema_definition: default_schema_definition(),
         }
@@ -115,6 +189,10 @@ impl EventMetadata {
     /// Merge the other `EventMetadata` into this.
     /// If a Datadog API key is not set in `self`, the one from `other` will be used.
     /// If a Splunk HEC token is not set in `self`, the one from `other` will be used.
+    /// If a `traceparent` is not set in `self`, the one from `other` will be used.
+    /// If request headers are not set in `self`, the ones from `other` will be used.
+    /// If a sequence number is not set in `self`, the one from `other` will be used.
+    /// If an OpenTelemetry scope is not set in `self`, the one from `other` will be used.
     pub fn merge(&mut self, other: Self) {
         self.finalizers.merge(other.finalizers);
         if self.datadog_api_key.is_none() {
@@ -123,6 +201,18 @@ impl EventMetadata {
         if self.splunk_hec_token.is_none() {
             self.splunk_hec_token = other.splunk_hec_token;
         }
+        if self.trace_parent.is_none() {
+            self.trace_parent = other.trace_parent;
+        }
+        if self.http_headers.is_none() {
+            self.http_headers = other.http_headers;
+        }
+        if self.sequence_number.is_none() {
+            self.sequence_number = other.sequence_number;
+        }
+        if self.opentelemetry_scope.is_none() {
+            self.opentelemetry_scope = other.opentelemetry_scope;
+        }
     }
 
     /// Update the finalizer(s) status.
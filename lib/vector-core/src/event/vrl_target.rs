@@ -297,6 +297,14 @@ impl vrl_lib::Target for VrlTarget {
                 .splunk_hec_token()
                 .as_ref()
                 .map(|token| ::value::Value::from(token.to_string()))),
+            "http_headers" => Ok(metadata.http_headers().as_ref().map(|headers| {
+                ::value::Value::Object(
+                    headers
+                        .iter()
+                        .map(|(name, value)| (name.clone(), ::value::Value::from(value.as_str())))
+                        .collect(),
+                )
+            })),
             _ => Err(format!("key {} not available", key)),
         }
     }
@@ -316,6 +324,7 @@ impl vrl_lib::Target for VrlTarget {
                 metadata.set_splunk_hec_token(Some(Arc::from(value.as_str())));
                 Ok(())
             }
+            "http_headers" => Err(format!("key {} is read-only", key)),
             _ => Err(format!("key {} not available", key)),
         }
     }
@@ -335,6 +344,10 @@ impl vrl_lib::Target for VrlTarget {
                 metadata.set_splunk_hec_token(None);
                 Ok(())
             }
+            "http_headers" => {
+                metadata.set_http_headers(None);
+                Ok(())
+            }
             _ => Err(format!("key {} not available", key)),
         }
     }
@@ -111,6 +111,17 @@ impl Config {
         (self.gamma_v - 1.0) / 2.0
     }
 
+    /// Gets the ratio between the upper and lower bounds of a bin.
+    pub fn gamma(&self) -> f64 {
+        self.gamma_v
+    }
+
+    /// Gets the bias of the bin keys, such that the bin at key `k` represents values close to
+    /// γ^(k - bias).
+    pub fn norm_bias(&self) -> i32 {
+        self.norm_bias
+    }
+
     /// Gets the value lower bound of the bin at the given key.
     pub fn bin_lower_bound(&self, k: i16) -> f64 {
         lower_bound(self.gamma_v, self.norm_bias, k)
@@ -1,6 +1,10 @@
 //! Time utilities for vector-core
 
-use std::task::{Context, Poll};
+use std::{
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
 
 /// A trait for representing a timer which holds multiple subtimers, mapped by an arbitrary key, `K`.
 ///
@@ -32,3 +36,91 @@ pub trait KeyedTimer<K> {
     /// Used primarily for property testing vis-á-vis `vector_core::stream::batcher::Batcher`.
     fn poll_expired(&mut self, cx: &mut Context) -> Poll<Option<K>>;
 }
+
+/// A source of the current time, for components whose behavior depends on the passage of time
+/// (throttling, windowed aggregation, expiring caches, etc).
+///
+/// Components should read the current time through the clock handed to them in their context
+/// instead of calling `Instant::now` directly, so that tests can substitute a [`MockClock`] and
+/// advance time deterministically.
+#[derive(Clone, Debug)]
+pub enum Clock {
+    /// The system's monotonic clock.
+    System,
+    /// A virtual clock that only moves when advanced explicitly.
+    Mock(MockClock),
+}
+
+impl Clock {
+    /// Returns the current time according to this clock.
+    pub fn now(&self) -> Instant {
+        match self {
+            Self::System => Instant::now(),
+            Self::Mock(clock) => clock.now(),
+        }
+    }
+
+    /// Returns the amount of time elapsed since `earlier`, according to this clock.
+    pub fn elapsed(&self, earlier: Instant) -> Duration {
+        self.now().saturating_duration_since(earlier)
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::System
+    }
+}
+
+/// A virtual clock that only moves when advanced explicitly.
+///
+/// Clones share the same underlying time, so a test can keep a handle to the clock given to a
+/// component and advance it from the outside.
+#[derive(Clone, Debug)]
+pub struct MockClock {
+    now: Arc<Mutex<Instant>>,
+}
+
+impl MockClock {
+    /// Creates a new `MockClock`, starting at the current system time.
+    pub fn new() -> Self {
+        Self {
+            now: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// Returns the current virtual time.
+    pub fn now(&self) -> Instant {
+        *self.now.lock().expect("mock clock lock poisoned")
+    }
+
+    /// Moves the virtual time forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().expect("mock clock lock poisoned") += duration;
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Clock, MockClock};
+
+    #[test]
+    fn mock_clock_only_moves_when_advanced() {
+        let mock = MockClock::new();
+        let clock = Clock::Mock(mock.clone());
+
+        let start = clock.now();
+        assert_eq!(clock.now(), start);
+
+        mock.advance(Duration::from_secs(5));
+        assert_eq!(clock.elapsed(start), Duration::from_secs(5));
+    }
+}
@@ -1,11 +1,13 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 use async_trait::async_trait;
 use indexmap::IndexMap;
+use lookup::LookupBuf;
 
 use crate::{
-    config::{ComponentKey, GlobalOptions, Input, Output},
+    config::{Capability, ComponentKey, GlobalOptions, Input, Output},
     schema,
+    time::Clock,
 };
 
 #[derive(Debug, serde::Serialize)]
@@ -56,6 +58,12 @@ pub struct TransformContext {
     /// information, such as the `remap` transform, which passes this information along to the VRL
     /// compiler such that type coercion becomes less of a need for operators writing VRL programs.
     pub merged_schema_definition: schema::Definition,
+
+    /// The clock the transform should read the current time from.
+    ///
+    /// This is the system clock, except in unit tests run by `vector test` and in tests that need
+    /// to control the passage of time.
+    pub clock: Clock,
 }
 
 impl Default for TransformContext {
@@ -67,6 +75,7 @@ impl Default for TransformContext {
             enrichment_tables: Default::default(),
             schema_definitions: HashMap::from([(None, schema::Definition::empty())]),
             merged_schema_definition: schema::Definition::empty(),
+            clock: Clock::default(),
         }
     }
 }
@@ -112,12 +121,31 @@ pub trait TransformConfig: core::fmt::Debug + Send + Sync + dyn_clone::DynClone
 
     fn transform_type(&self) -> &'static str;
 
+    /// Returns the event fields the transform reads, if they can be determined from its
+    /// configuration.
+    ///
+    /// This is used to warn about fields that none of the transform's inputs produce.
+    fn queried_fields(&self) -> Option<BTreeSet<LookupBuf>> {
+        None
+    }
+
     /// Return true if the transform is able to be run across multiple tasks simultaneously with no
     /// concerns around statefulness, ordering, etc.
     fn enable_concurrency(&self) -> bool {
         false
     }
 
+    /// The paths and hosts the transform needs access to, which its sandbox must allow.
+    fn capabilities(&self) -> Vec<Capability> {
+        Vec::new()
+    }
+
+    /// Whether the transform is confined to its sandbox at runtime. A sandbox can only be given to
+    /// the transforms which are, as it wouldn't restrict the others.
+    fn supports_sandbox(&self) -> bool {
+        false
+    }
+
     /// Allows to detect if a transform can be embedded in another transform.
     /// It's used by the pipelines transform for now.
     fn nestable(&self, _parents: &HashSet<&'static str>) -> bool {
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, pin::Pin};
+use std::{collections::HashMap, pin::Pin, time::Duration};
 
 use futures::{Stream, StreamExt};
 use vector_common::internal_event::{emit, EventsSent, DEFAULT_OUTPUT};
@@ -187,6 +187,20 @@ pub trait SyncTransform: Send + dyn_clone::DynClone + Sync {
             self.transform(event, output);
         }
     }
+
+    /// How often `flush` is called, for transforms holding on to events that have to be sent
+    /// even if no new events arrive.
+    ///
+    /// This is only honored for transforms that don't enable concurrency.
+    fn flush_period(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Sends the events held by the transform that are due.
+    fn flush(&mut self, _output: &mut TransformOutputsBuf) {}
+
+    /// Sends every event still held by the transform once its input has ended.
+    fn flush_all(&mut self, _output: &mut TransformOutputsBuf) {}
 }
 
 dyn_clone::clone_trait_object!(SyncTransform);
@@ -86,6 +86,11 @@ impl Expression for GetMetadataFieldFn {
     }
 
     fn type_def(&self, _: (&state::LocalEnv, &state::ExternalEnv)) -> TypeDef {
-        TypeDef::bytes().add_null().infallible()
+        match self.key.as_str() {
+            "http_headers" => TypeDef::object(Collection::from_unknown(Kind::bytes()))
+                .add_null()
+                .infallible(),
+            _ => TypeDef::bytes().add_null().infallible(),
+        }
     }
 }
@@ -1,7 +1,14 @@
 use diagnostic::{DiagnosticList, DiagnosticMessage, Severity, Span};
 use lookup::LookupBuf;
 use parser::ast::{self, Node};
+#[cfg(feature = "expr-if_statement")]
+use value::{
+    kind::{insert, merge, Collection},
+    Kind,
+};
 
+#[cfg(feature = "expr-if_statement")]
+use crate::type_def::Details;
 use crate::{
     expression::*,
     program::ProgramInfo,
@@ -280,8 +287,20 @@ impl<'a> Compiler<'a> {
             }
         };
 
+        // Each branch knows the types the predicate checked, so that it can use
+        // the checked values without having to coerce them.
+        let narrowings = predicate.narrowings();
+
+        let widenings = self.narrow_types(&narrowings.when_true, external);
         let consequent = self.compile_block(consequent, external);
-        let alternative = alternative.map(|block| self.compile_block(block, external));
+        self.widen_types(widenings, external);
+
+        let alternative = alternative.map(|block| {
+            let widenings = self.narrow_types(&narrowings.when_false, external);
+            let block = self.compile_block(block, external);
+            self.widen_types(widenings, external);
+            block
+        });
 
         IfStatement {
             predicate,
@@ -290,6 +309,127 @@ impl<'a> Compiler<'a> {
         }
     }
 
+    /// Narrow the types of the given variables and external paths, returning
+    /// the types they had before.
+    #[cfg(feature = "expr-if_statement")]
+    fn narrow_types(
+        &mut self,
+        narrowings: &[predicate::Narrowing],
+        external: &mut ExternalEnv,
+    ) -> Vec<(predicate::Narrowing, Kind)> {
+        use predicate::Restriction;
+
+        let mut widenings = vec![];
+
+        for narrowing in narrowings {
+            let kind = match self.kind_at(narrowing, external) {
+                Some(kind) => kind,
+                None => continue,
+            };
+
+            let narrowed = match &narrowing.restriction {
+                Restriction::Only(only) => kind.keep_states(only),
+                Restriction::Not(not) => kind.without_states(not),
+            };
+
+            // An empty kind means the branch is never taken, in which case the
+            // type is left as-is.
+            if narrowed.is_empty() || narrowed == kind {
+                continue;
+            }
+
+            if self.set_kind_at(narrowing, narrowed, external) {
+                widenings.push((narrowing.clone(), kind));
+            }
+        }
+
+        widenings
+    }
+
+    /// Restore the types narrowed by `narrow_types`.
+    ///
+    /// The branch could have assigned a new value to a narrowed variable or
+    /// path, so its type after the branch is merged into the type it had
+    /// before.
+    #[cfg(feature = "expr-if_statement")]
+    fn widen_types(
+        &mut self,
+        widenings: Vec<(predicate::Narrowing, Kind)>,
+        external: &mut ExternalEnv,
+    ) {
+        for (narrowing, mut kind) in widenings.into_iter().rev() {
+            if let Some(current) = self.kind_at(&narrowing, external) {
+                kind.merge(
+                    current,
+                    merge::Strategy {
+                        depth: merge::Depth::Deep,
+                        indices: merge::Indices::Keep,
+                    },
+                );
+            }
+
+            self.set_kind_at(&narrowing, kind, external);
+        }
+    }
+
+    #[cfg(feature = "expr-if_statement")]
+    fn narrowed_details(
+        &self,
+        narrowing: &predicate::Narrowing,
+        external: &ExternalEnv,
+    ) -> Option<Details> {
+        match &narrowing.variable {
+            Some(ident) => self.local.variable(ident).cloned(),
+            None => Some(external.target().cloned().unwrap_or_else(|| Details {
+                type_def: Kind::object(Collection::any()).into(),
+                value: None,
+            })),
+        }
+    }
+
+    #[cfg(feature = "expr-if_statement")]
+    fn kind_at(&self, narrowing: &predicate::Narrowing, external: &ExternalEnv) -> Option<Kind> {
+        let details = self.narrowed_details(narrowing, external)?;
+
+        Some(details.type_def.at_path(&narrowing.path.to_lookup()).into())
+    }
+
+    #[cfg(feature = "expr-if_statement")]
+    fn set_kind_at(
+        &mut self,
+        narrowing: &predicate::Narrowing,
+        kind: Kind,
+        external: &mut ExternalEnv,
+    ) -> bool {
+        let mut details = match self.narrowed_details(narrowing, external) {
+            Some(details) => details,
+            None => return false,
+        };
+
+        let inserted = details.type_def.insert_at_path(
+            &narrowing.path.to_lookup(),
+            kind,
+            insert::Strategy {
+                inner_conflict: insert::InnerConflict::Merge(merge::Strategy {
+                    depth: merge::Depth::Deep,
+                    indices: merge::Indices::Keep,
+                }),
+                leaf_conflict: insert::LeafConflict::Replace,
+                coalesced_path: insert::CoalescedPath::Reject,
+            },
+        );
+        if inserted.is_err() {
+            return false;
+        }
+
+        match &narrowing.variable {
+            Some(ident) => self.local.insert_variable(ident.clone(), details),
+            None => external.update_target(details),
+        }
+
+        true
+    }
+
     #[cfg(not(feature = "expr-if_statement"))]
     fn compile_if_statement(&mut self, node: Node<ast::IfStatement>, _: &mut ExternalEnv) -> Noop {
         self.handle_missing_feature_error(node.span(), "expr-if_statement")
@@ -574,9 +714,18 @@ impl<'a> Compiler<'a> {
         node: Node<ast::FunctionArgument>,
         external: &mut ExternalEnv,
     ) -> FunctionArgument {
-        let ast::FunctionArgument { ident, expr } = node.into_inner();
+        let ast::FunctionArgument {
+            ident,
+            expr,
+            propagate,
+        } = node.into_inner();
         let expr = Node::new(expr.span(), self.compile_expr(expr, external));
-        FunctionArgument::new(ident, expr)
+        let argument = FunctionArgument::new(ident, expr);
+
+        match propagate {
+            Some(propagate) => argument.with_propagation(propagate.span()),
+            None => argument,
+        }
     }
 
     #[cfg(not(feature = "expr-function_call"))]
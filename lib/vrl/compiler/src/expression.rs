@@ -39,6 +39,7 @@ pub(crate) mod literal;
 pub(crate) mod predicate;
 #[cfg(feature = "expr-query")]
 pub(crate) mod query;
+pub mod visit;
 
 pub use core::{ExpressionError, Resolved};
 
@@ -46,7 +47,7 @@ pub use core::{ExpressionError, Resolved};
 pub use abort::Abort;
 pub use array::Array;
 #[cfg(feature = "expr-assignment")]
-pub use assignment::Assignment;
+pub use assignment::{Assignment, Target as AssignmentTarget};
 pub use block::Block;
 pub use container::{Container, Variant};
 pub use function_argument::FunctionArgument;
@@ -68,7 +69,7 @@ pub use predicate::Predicate;
 #[cfg(feature = "expr-query")]
 pub use query::{Query, Target};
 #[cfg(feature = "expr-unary")]
-pub use unary::Unary;
+pub use unary::{Unary, Variant as UnaryVariant};
 pub use variable::Variable;
 
 pub trait Expression: Send + Sync + fmt::Debug + DynClone {
@@ -56,6 +56,11 @@ impl Abort {
             message: None,
         }
     }
+
+    /// The expression producing the abort message, if any.
+    pub fn message(&self) -> Option<&Expr> {
+        self.message.as_deref()
+    }
 }
 
 impl Expression for Abort {
@@ -146,7 +146,7 @@ impl Assignment {
     ///
     /// For regular assignments, this contains a single target, for infallible
     /// assignments, it'll contain both the `ok` and `err` target.
-    pub(crate) fn targets(&self) -> Vec<Target> {
+    pub fn targets(&self) -> Vec<Target> {
         let mut targets = Vec::with_capacity(2);
 
         match &self.variant {
@@ -159,6 +159,13 @@ impl Assignment {
 
         targets
     }
+
+    /// The expression whose result is assigned.
+    pub fn expr(&self) -> &Expr {
+        match &self.variant {
+            Variant::Single { expr, .. } | Variant::Infallible { expr, .. } => expr,
+        }
+    }
 }
 
 impl Expression for Assignment {
@@ -29,6 +29,11 @@ impl Block {
     pub fn into_inner(self) -> Vec<Expr> {
         self.inner
     }
+
+    /// The expressions of the block, in evaluation order.
+    pub fn exprs(&self) -> &[Expr] {
+        &self.inner
+    }
 }
 
 impl Expression for Block {
@@ -3,7 +3,7 @@ use std::{fmt, ops::Deref};
 use crate::{
     expression::Expr,
     parser::{Ident, Node},
-    Parameter,
+    Parameter, Span,
 };
 
 #[derive(Clone, Debug, PartialEq)]
@@ -11,6 +11,7 @@ pub struct FunctionArgument {
     ident: Option<Node<Ident>>,
     parameter: Option<Parameter>,
     expr: Node<Expr>,
+    propagate: Option<Span>,
 }
 
 impl FunctionArgument {
@@ -19,11 +20,31 @@ impl FunctionArgument {
             ident,
             parameter: None,
             expr,
+            propagate: None,
         }
     }
 
     #[cfg(feature = "expr-function_call")]
-    pub(crate) fn keyword(&self) -> Option<&str> {
+    /// Propagate the error of the argument to the function call, as marked by
+    /// the `?` operator at the given span.
+    pub(crate) fn with_propagation(mut self, span: Span) -> Self {
+        self.propagate = Some(span);
+        self
+    }
+
+    /// Whether the argument propagates its error to the function call.
+    pub fn propagates(&self) -> bool {
+        self.propagate.is_some()
+    }
+
+    #[cfg(feature = "expr-function_call")]
+    pub(crate) fn propagate_span(&self) -> Option<Span> {
+        self.propagate
+    }
+
+    #[cfg(feature = "expr-function_call")]
+    /// The keyword the argument was passed with, if it was passed by keyword.
+    pub fn keyword(&self) -> Option<&str> {
         self.ident.as_ref().map(|node| node.as_ref().as_ref())
     }
 
@@ -47,7 +68,13 @@ impl FunctionArgument {
 
 impl fmt::Display for FunctionArgument {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.expr.fmt(f)
+        self.expr.fmt(f)?;
+
+        if self.propagates() {
+            f.write_str("?")?;
+        }
+
+        Ok(())
     }
 }
 
@@ -5,10 +5,10 @@ use diagnostic::{DiagnosticMessage, Label, Note, Urls};
 
 use super::Block;
 use crate::{
-    expression::{levenstein, ExpressionError, FunctionArgument, Noop},
+    expression::{levenstein, Expr, ExpressionError, FunctionArgument, Noop},
     function::{
         closure::{self, VariableKind},
-        ArgumentList, Example, FunctionClosure, FunctionCompileContext, Parameter,
+        ArgumentList, Example, FunctionClosure, FunctionCompileContext, Parameter, TypeGuard,
     },
     parser::{Ident, Node},
     state::{ExternalEnv, LocalEnv},
@@ -141,11 +141,23 @@ impl<'a> Builder<'a> {
                 maybe_fallible_arguments = true;
             }
 
-            // Check if the argument is infallible.
-            if argument_type_def.is_fallible() {
-                return Err(Error::FallibleArgument {
-                    expr_span: argument.span(),
-                });
+            // Check if the argument is infallible, or explicitly propagates its
+            // error to the function call using `?`, in which case the function
+            // call becomes fallible.
+            match (argument_type_def.is_fallible(), argument.propagate_span()) {
+                (true, Some(_)) => maybe_fallible_arguments = true,
+                (true, None) => {
+                    return Err(Error::FallibleArgument {
+                        expr_span: argument.span(),
+                    })
+                }
+                (false, Some(propagate_span)) => {
+                    return Err(Error::UnnecessaryPropagation {
+                        expr_span: argument.span(),
+                        propagate_span,
+                    })
+                }
+                (false, None) => {}
             }
 
             list.insert(parameter.keyword, argument.into_inner());
@@ -456,6 +468,7 @@ impl<'a> Builder<'a> {
             maybe_fallible_arguments: self.maybe_fallible_arguments,
             closure_fallible,
             closure,
+            type_guard: self.function.type_guard(),
             span: call_span,
             ident: self.function.identifier(),
             function_id: self.function_id,
@@ -471,6 +484,7 @@ pub struct FunctionCall {
     maybe_fallible_arguments: bool,
     closure_fallible: bool,
     closure: Option<FunctionClosure>,
+    type_guard: Option<TypeGuard>,
 
     // used for enhancing runtime error messages (using abort-instruction).
     //
@@ -547,6 +561,7 @@ impl FunctionCall {
             maybe_fallible_arguments: false,
             closure_fallible: false,
             closure: None,
+            type_guard: None,
             span: Span::default(),
             ident: "noop",
             arguments: Arc::new(Vec::new()),
@@ -554,6 +569,30 @@ impl FunctionCall {
         }
     }
 
+    /// The name of the called function.
+    pub fn ident(&self) -> &'static str {
+        self.ident
+    }
+
+    /// The type guard of the called function, along with the argument whose
+    /// type it checks.
+    pub(crate) fn type_guard(&self) -> Option<(&TypeGuard, &Expr)> {
+        let type_guard = self.type_guard.as_ref()?;
+        let argument = self.arguments.first()?;
+
+        Some((type_guard, argument.inner().expr()))
+    }
+
+    /// The arguments as they were passed to the function, in call order.
+    pub fn arguments(&self) -> impl Iterator<Item = &FunctionArgument> {
+        self.arguments.iter().map(|arg| arg.inner())
+    }
+
+    /// The closure passed to the function, if any.
+    pub fn closure(&self) -> Option<&FunctionClosure> {
+        self.closure.as_ref()
+    }
+
     pub fn arguments_fmt(&self) -> Vec<String> {
         self.arguments
             .iter()
@@ -657,6 +696,10 @@ impl Expression for FunctionCall {
         // For the second event, only the `slice` function succeeds.
         // For the third event, both functions fail.
         //
+        // The same applies to arguments propagating their error using `?`, as
+        // in `upcase(to_string(.foo)?)`, which fail the function call when
+        // they fail themselves.
+        //
         if self.maybe_fallible_arguments {
             type_def = type_def.with_fallibility(true);
         }
@@ -703,8 +746,14 @@ impl Expression for FunctionCall {
         let mut compile_ctx =
             FunctionCompileContext::new(self.span).with_external_context(external_context);
 
+        // Whether an argument compiled so far propagates its error using `?`.
+        let mut propagated = false;
+
         for (keyword, argument) in &args {
             let fun = vm.function(self.function_id).unwrap();
+            let propagates = argument
+                .as_ref()
+                .map_or(false, |argument| argument.inner().propagates());
             let argument = argument.as_ref().map(|argument| argument.inner());
 
             // Call `compile_argument` for functions that need to perform any compile time processing
@@ -721,9 +770,17 @@ impl Expression for FunctionCall {
                 }
                 None => match argument {
                     Some(argument) => {
+                        // Once a previous argument failed, the remaining arguments aren't resolved, and
+                        // `MoveParameter` moves an empty parameter in their place, leaving the stack as it
+                        // was.
+                        let err_jump = propagated.then(|| vm.emit_jump(OpCode::JumpIfErr));
+
                         // Compile the argument, `MoveParameter` will move the result of the expression onto the
                         // parameter stack to be passed into the function.
                         argument.compile_to_vm(vm, (local, external))?;
+                        if let Some(err_jump) = err_jump {
+                            vm.patch_jump(err_jump);
+                        }
                         vm.write_opcode(OpCode::MoveParameter);
                     }
                     None => {
@@ -733,6 +790,8 @@ impl Expression for FunctionCall {
                     }
                 },
             }
+
+            propagated |= propagates;
         }
 
         if let Some(FunctionClosure { variables, block }) = self.closure.as_ref().cloned() {
@@ -862,6 +921,12 @@ pub(crate) enum Error {
     #[error("fallible argument")]
     FallibleArgument { expr_span: Span },
 
+    #[error("unnecessary error propagation")]
+    UnnecessaryPropagation {
+        expr_span: Span,
+        propagate_span: Span,
+    },
+
     #[error("error updating state {}", error)]
     UpdateState { call_span: Span, error: String },
 
@@ -904,6 +969,7 @@ impl DiagnosticMessage for Error {
             AbortInfallible { .. } => 620,
             InvalidArgumentKind { .. } => 110,
             FallibleArgument { .. } => 630,
+            UnnecessaryPropagation { .. } => 631,
             UpdateState { .. } => 640,
             UnexpectedClosure { .. } => 109,
             MissingClosure { .. } => 111,
@@ -1057,6 +1123,18 @@ impl DiagnosticMessage for Error {
                     "handle the error before passing it in as an argument",
                     expr_span,
                 ),
+                Label::context(
+                    "or propagate it to the function call using `?`",
+                    expr_span,
+                ),
+            ],
+
+            UnnecessaryPropagation {
+                expr_span,
+                propagate_span,
+            } => vec![
+                Label::primary("this expression can't fail", expr_span),
+                Label::context("remove this error propagation", propagate_span),
             ],
 
             UpdateState { call_span, error } => vec![Label::primary(
@@ -1097,7 +1175,9 @@ impl DiagnosticMessage for Error {
                 "function arguments".to_owned(),
                 Urls::expression_docs_url("#arguments"),
             )],
-            AbortInfallible { .. } | FallibleArgument { .. } => vec![Note::SeeErrorDocs],
+            AbortInfallible { .. } | FallibleArgument { .. } | UnnecessaryPropagation { .. } => {
+                vec![Note::SeeErrorDocs]
+            }
             InvalidArgumentKind {
                 function_ident,
                 abort_on_error,
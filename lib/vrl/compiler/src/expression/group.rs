@@ -17,6 +17,10 @@ impl Group {
             inner: Box::new(inner),
         }
     }
+
+    pub fn inner(&self) -> &Expr {
+        &self.inner
+    }
 }
 
 impl Expression for Group {
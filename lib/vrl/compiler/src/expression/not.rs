@@ -42,6 +42,11 @@ impl Not {
             inner: Box::new(Noop.into()),
         }
     }
+
+    /// The negated expression.
+    pub fn inner(&self) -> &Expr {
+        &self.inner
+    }
 }
 
 impl Expression for Not {
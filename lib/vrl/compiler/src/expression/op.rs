@@ -77,6 +77,18 @@ impl Op {
         })
     }
 
+    pub fn lhs(&self) -> &Expr {
+        &self.lhs
+    }
+
+    pub fn rhs(&self) -> &Expr {
+        &self.rhs
+    }
+
+    pub fn opcode(&self) -> ast::Opcode {
+        self.opcode
+    }
+
     pub fn noop() -> Self {
         let lhs = Box::new(Noop.into());
         let rhs = Box::new(Noop.into());
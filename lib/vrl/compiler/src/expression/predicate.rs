@@ -1,11 +1,12 @@
 use std::fmt;
 
 use diagnostic::{DiagnosticMessage, Label, Note, Urls};
+use lookup::LookupBuf;
 use value::Value;
 
 use crate::{
-    expression::{Expr, Resolved},
-    parser::Node,
+    expression::{Container, Expr, Resolved, Variant},
+    parser::{ast::Ident, Node},
     state::{ExternalEnv, LocalEnv},
     value::Kind,
     Context, Expression, Span, TypeDef,
@@ -46,6 +47,206 @@ impl Predicate {
     pub fn new_unchecked(inner: Vec<Expr>) -> Self {
         Self { inner }
     }
+
+    /// The expressions of the predicate, the last of which determines its result.
+    pub fn exprs(&self) -> &[Expr] {
+        &self.inner
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+/// The types of the variables and external paths checked by a predicate, when
+/// it resolves to `true` and when it resolves to `false`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct Narrowings {
+    pub(crate) when_true: Vec<Narrowing>,
+    pub(crate) when_false: Vec<Narrowing>,
+}
+
+impl Narrowings {
+    fn swap(self) -> Self {
+        Self {
+            when_true: self.when_false,
+            when_false: self.when_true,
+        }
+    }
+}
+
+/// A type known for a variable or external path.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Narrowing {
+    /// The variable holding the value, or `None` for the external target.
+    pub(crate) variable: Option<Ident>,
+    pub(crate) path: LookupBuf,
+    pub(crate) restriction: Restriction,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Restriction {
+    /// The value has one of the type states of the kind.
+    Only(Kind),
+
+    /// The value has none of the type states of the kind.
+    Not(Kind),
+}
+
+impl Predicate {
+    /// The type narrowings implied by the result of the predicate.
+    pub(crate) fn narrowings(&self) -> Narrowings {
+        // Only the last expression determines the result of the predicate.
+        self.inner.last().map(narrowings).unwrap_or_default()
+    }
+}
+
+fn narrowings(expr: &Expr) -> Narrowings {
+    match expr {
+        #[cfg(feature = "expr-function_call")]
+        Expr::FunctionCall(call) => call
+            .type_guard()
+            .and_then(|(type_guard, argument)| {
+                let (variable, path) = checked_value(argument)?;
+
+                Some(Narrowings {
+                    when_true: vec![Narrowing {
+                        variable: variable.clone(),
+                        path: path.clone(),
+                        restriction: Restriction::Only(type_guard.when_true.clone()),
+                    }],
+                    when_false: vec![Narrowing {
+                        variable,
+                        path,
+                        restriction: Restriction::Not(type_guard.excluded_when_false.clone()),
+                    }],
+                })
+            })
+            .unwrap_or_default(),
+
+        #[cfg(feature = "expr-unary")]
+        Expr::Unary(unary) => match unary.variant() {
+            crate::expression::UnaryVariant::Not(not) => narrowings(not.inner()).swap(),
+        },
+
+        // Both sides are known to be `true` for a conjunction to be `true`, and
+        // both sides are known to be `false` for a disjunction to be `false`.
+        // The right-hand side runs after the left-hand side was checked, so
+        // the values it assigns to lose the type the left-hand side checked.
+        #[cfg(feature = "expr-op")]
+        Expr::Op(op) => {
+            use crate::parser::ast::Opcode;
+
+            let assigned = Assigned::by(&op.rhs);
+
+            match op.opcode {
+                Opcode::And => Narrowings {
+                    when_true: narrowings(&op.lhs)
+                        .when_true
+                        .into_iter()
+                        .filter(|narrowing| !assigned.overwrites(narrowing))
+                        .chain(narrowings(&op.rhs).when_true)
+                        .collect(),
+                    when_false: vec![],
+                },
+                Opcode::Or => Narrowings {
+                    when_true: vec![],
+                    when_false: narrowings(&op.lhs)
+                        .when_false
+                        .into_iter()
+                        .filter(|narrowing| !assigned.overwrites(narrowing))
+                        .chain(narrowings(&op.rhs).when_false)
+                        .collect(),
+                },
+                _ => Narrowings::default(),
+            }
+        }
+
+        Expr::Container(Container {
+            variant: Variant::Group(group),
+        }) => narrowings(group.inner()),
+
+        _ => Narrowings::default(),
+    }
+}
+
+/// The variable and path of an expression reading a value whose type can be
+/// narrowed.
+fn checked_value(expr: &Expr) -> Option<(Option<Ident>, LookupBuf)> {
+    match expr {
+        Expr::Variable(variable) => Some((Some(variable.ident().clone()), LookupBuf::root())),
+
+        // A coalesced path could be read from another field than the one it's
+        // assigned to.
+        #[cfg(feature = "expr-query")]
+        Expr::Query(query) if query.path().iter().any(|segment| segment.is_coalesce()) => None,
+
+        #[cfg(feature = "expr-query")]
+        Expr::Query(query) => match query.target() {
+            crate::expression::Target::External => Some((None, query.path().clone())),
+            crate::expression::Target::Internal(variable) => {
+                Some((Some(variable.ident().clone()), query.path().clone()))
+            }
+            _ => None,
+        },
+
+        _ => None,
+    }
+}
+
+/// The variables and external paths an expression assigns to, or deletes.
+#[cfg(feature = "expr-op")]
+#[derive(Debug, Default)]
+struct Assigned {
+    targets: Vec<(Option<Ident>, LookupBuf)>,
+}
+
+#[cfg(feature = "expr-op")]
+impl Assigned {
+    fn by(expr: &Expr) -> Self {
+        use crate::expression::visit::Visitor;
+
+        let mut assigned = Self::default();
+        assigned.visit_expr(expr);
+        assigned
+    }
+
+    /// Whether a value the narrowing applies to, or one of its parents or
+    /// children, is assigned to.
+    fn overwrites(&self, narrowing: &Narrowing) -> bool {
+        self.targets.iter().any(|(variable, path)| {
+            *variable == narrowing.variable
+                && (path.starts_with(&narrowing.path) || narrowing.path.starts_with(path))
+        })
+    }
+}
+
+#[cfg(feature = "expr-op")]
+impl crate::expression::visit::Visitor for Assigned {
+    #[cfg(feature = "expr-assignment")]
+    fn visit_assignment(&mut self, assignment: &crate::expression::Assignment) {
+        use crate::expression::AssignmentTarget;
+
+        for target in assignment.targets() {
+            match target {
+                AssignmentTarget::Noop => {}
+                AssignmentTarget::Internal(ident, path) => self.targets.push((Some(ident), path)),
+                AssignmentTarget::External(path) => self.targets.push((None, path)),
+            }
+        }
+        self.visit_expr(assignment.expr());
+    }
+
+    // `del` is the only function changing the values it's given.
+    #[cfg(feature = "expr-function_call")]
+    fn visit_function_call(&mut self, function_call: &crate::expression::FunctionCall) {
+        if function_call.ident() == "del" {
+            self.targets.extend(
+                function_call
+                    .arguments()
+                    .filter_map(|argument| checked_value(argument.expr())),
+            );
+        }
+        crate::expression::visit::walk_function_call(self, function_call);
+    }
 }
 
 impl Expression for Predicate {
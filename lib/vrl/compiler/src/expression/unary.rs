@@ -16,6 +16,10 @@ impl Unary {
     pub fn new(variant: Variant) -> Self {
         Self { variant }
     }
+
+    pub fn variant(&self) -> &Variant {
+        &self.variant
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
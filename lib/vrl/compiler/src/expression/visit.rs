@@ -0,0 +1,271 @@
+//! A visitor API over compiled expressions.
+//!
+//! Implement [`Visitor`] and override the methods for the expressions you are
+//! interested in, then pass the visitor to [`Program::visit`] or
+//! [`Visitor::visit_expr`]. The default implementation of each method walks
+//! into the children of the expression using the matching `walk_*` function,
+//! so an overriding method has to call that function itself to keep walking
+//! into the expression's children.
+//!
+//! [`Program::visit`]: crate::Program::visit
+
+#[cfg(feature = "expr-abort")]
+use super::Abort;
+#[cfg(feature = "expr-assignment")]
+use super::Assignment;
+#[cfg(feature = "expr-function_call")]
+use super::FunctionCall;
+#[cfg(feature = "expr-if_statement")]
+use super::IfStatement;
+#[cfg(feature = "expr-literal")]
+use super::Literal;
+#[cfg(feature = "expr-unary")]
+use super::Not;
+#[cfg(feature = "expr-op")]
+use super::Op;
+#[cfg(feature = "expr-if_statement")]
+use super::Predicate;
+#[cfg(feature = "expr-unary")]
+use super::Unary;
+#[cfg(feature = "expr-query")]
+use super::{query, Query};
+use super::{
+    Array, Block, Container, Expr, FunctionArgument, Group, Noop, Object, Variable, Variant,
+};
+use crate::function::FunctionClosure;
+
+pub trait Visitor {
+    fn visit_expr(&mut self, expr: &Expr) {
+        walk_expr(self, expr);
+    }
+
+    fn visit_block(&mut self, block: &Block) {
+        walk_block(self, block);
+    }
+
+    fn visit_container(&mut self, container: &Container) {
+        walk_container(self, container);
+    }
+
+    fn visit_group(&mut self, group: &Group) {
+        self.visit_expr(group.inner());
+    }
+
+    fn visit_array(&mut self, array: &Array) {
+        walk_array(self, array);
+    }
+
+    fn visit_object(&mut self, object: &Object) {
+        walk_object(self, object);
+    }
+
+    #[cfg(feature = "expr-literal")]
+    fn visit_literal(&mut self, _literal: &Literal) {}
+
+    #[cfg(feature = "expr-if_statement")]
+    fn visit_if_statement(&mut self, if_statement: &IfStatement) {
+        walk_if_statement(self, if_statement);
+    }
+
+    #[cfg(feature = "expr-if_statement")]
+    fn visit_predicate(&mut self, predicate: &Predicate) {
+        walk_predicate(self, predicate);
+    }
+
+    #[cfg(feature = "expr-op")]
+    fn visit_op(&mut self, op: &Op) {
+        walk_op(self, op);
+    }
+
+    #[cfg(feature = "expr-assignment")]
+    fn visit_assignment(&mut self, assignment: &Assignment) {
+        self.visit_expr(assignment.expr());
+    }
+
+    #[cfg(feature = "expr-query")]
+    fn visit_query(&mut self, query: &Query) {
+        walk_query(self, query);
+    }
+
+    #[cfg(feature = "expr-function_call")]
+    fn visit_function_call(&mut self, function_call: &FunctionCall) {
+        walk_function_call(self, function_call);
+    }
+
+    fn visit_function_argument(&mut self, argument: &FunctionArgument) {
+        self.visit_expr(argument.expr());
+    }
+
+    fn visit_function_closure(&mut self, closure: &FunctionClosure) {
+        self.visit_block(&closure.block);
+    }
+
+    fn visit_variable(&mut self, _variable: &Variable) {}
+
+    fn visit_noop(&mut self, _noop: &Noop) {}
+
+    #[cfg(feature = "expr-unary")]
+    fn visit_unary(&mut self, unary: &Unary) {
+        walk_unary(self, unary);
+    }
+
+    #[cfg(feature = "expr-unary")]
+    fn visit_not(&mut self, not: &Not) {
+        self.visit_expr(not.inner());
+    }
+
+    #[cfg(feature = "expr-abort")]
+    fn visit_abort(&mut self, abort: &Abort) {
+        if let Some(message) = abort.message() {
+            self.visit_expr(message);
+        }
+    }
+}
+
+pub fn walk_expr<V: Visitor + ?Sized>(visitor: &mut V, expr: &Expr) {
+    match expr {
+        #[cfg(feature = "expr-literal")]
+        Expr::Literal(v) => visitor.visit_literal(v),
+        Expr::Container(v) => visitor.visit_container(v),
+        #[cfg(feature = "expr-if_statement")]
+        Expr::IfStatement(v) => visitor.visit_if_statement(v),
+        #[cfg(feature = "expr-op")]
+        Expr::Op(v) => visitor.visit_op(v),
+        #[cfg(feature = "expr-assignment")]
+        Expr::Assignment(v) => visitor.visit_assignment(v),
+        #[cfg(feature = "expr-query")]
+        Expr::Query(v) => visitor.visit_query(v),
+        #[cfg(feature = "expr-function_call")]
+        Expr::FunctionCall(v) => visitor.visit_function_call(v),
+        Expr::Variable(v) => visitor.visit_variable(v),
+        Expr::Noop(v) => visitor.visit_noop(v),
+        #[cfg(feature = "expr-unary")]
+        Expr::Unary(v) => visitor.visit_unary(v),
+        #[cfg(feature = "expr-abort")]
+        Expr::Abort(v) => visitor.visit_abort(v),
+    }
+}
+
+pub fn walk_block<V: Visitor + ?Sized>(visitor: &mut V, block: &Block) {
+    for expr in block.exprs() {
+        visitor.visit_expr(expr);
+    }
+}
+
+pub fn walk_container<V: Visitor + ?Sized>(visitor: &mut V, container: &Container) {
+    match &container.variant {
+        Variant::Group(v) => visitor.visit_group(v),
+        Variant::Block(v) => visitor.visit_block(v),
+        Variant::Array(v) => visitor.visit_array(v),
+        Variant::Object(v) => visitor.visit_object(v),
+    }
+}
+
+pub fn walk_array<V: Visitor + ?Sized>(visitor: &mut V, array: &Array) {
+    for expr in array.iter() {
+        visitor.visit_expr(expr);
+    }
+}
+
+pub fn walk_object<V: Visitor + ?Sized>(visitor: &mut V, object: &Object) {
+    for expr in object.values() {
+        visitor.visit_expr(expr);
+    }
+}
+
+#[cfg(feature = "expr-if_statement")]
+pub fn walk_if_statement<V: Visitor + ?Sized>(visitor: &mut V, if_statement: &IfStatement) {
+    visitor.visit_predicate(&if_statement.predicate);
+    visitor.visit_block(&if_statement.consequent);
+    if let Some(alternative) = &if_statement.alternative {
+        visitor.visit_block(alternative);
+    }
+}
+
+#[cfg(feature = "expr-if_statement")]
+pub fn walk_predicate<V: Visitor + ?Sized>(visitor: &mut V, predicate: &Predicate) {
+    for expr in predicate.exprs() {
+        visitor.visit_expr(expr);
+    }
+}
+
+#[cfg(feature = "expr-op")]
+pub fn walk_op<V: Visitor + ?Sized>(visitor: &mut V, op: &Op) {
+    visitor.visit_expr(op.lhs());
+    visitor.visit_expr(op.rhs());
+}
+
+#[cfg(feature = "expr-query")]
+pub fn walk_query<V: Visitor + ?Sized>(visitor: &mut V, query: &Query) {
+    match query.target() {
+        query::Target::Internal(v) => visitor.visit_variable(v),
+        query::Target::External => {}
+        #[cfg(feature = "expr-function_call")]
+        query::Target::FunctionCall(v) => visitor.visit_function_call(v),
+        #[cfg(not(feature = "expr-function_call"))]
+        query::Target::FunctionCall(v) => visitor.visit_noop(v),
+        query::Target::Container(v) => visitor.visit_container(v),
+    }
+}
+
+#[cfg(feature = "expr-function_call")]
+pub fn walk_function_call<V: Visitor + ?Sized>(visitor: &mut V, function_call: &FunctionCall) {
+    for argument in function_call.arguments() {
+        visitor.visit_function_argument(argument);
+    }
+    if let Some(closure) = function_call.closure() {
+        visitor.visit_function_closure(closure);
+    }
+}
+
+#[cfg(feature = "expr-unary")]
+pub fn walk_unary<V: Visitor + ?Sized>(visitor: &mut V, unary: &Unary) {
+    match unary.variant() {
+        super::UnaryVariant::Not(v) => visitor.visit_not(v),
+    }
+}
+
+#[cfg(all(test, feature = "expressions"))]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Counter {
+        queries: Vec<String>,
+        assignments: usize,
+        literals: usize,
+    }
+
+    impl Visitor for Counter {
+        fn visit_query(&mut self, query: &Query) {
+            self.queries.push(query.path().to_string());
+            walk_query(self, query);
+        }
+
+        fn visit_assignment(&mut self, assignment: &Assignment) {
+            self.assignments += 1;
+            self.visit_expr(assignment.expr());
+        }
+
+        fn visit_literal(&mut self, _literal: &Literal) {
+            self.literals += 1;
+        }
+    }
+
+    #[test]
+    fn walks_nested_expressions() {
+        let source = r#"
+            .a = [.b, { "c": .d }]
+            if !(.e == 1) { .f = "g" } else { abort }
+        "#;
+        let ast = parser::parse(source).unwrap();
+        let (program, _) = crate::compile(ast, &[]).unwrap();
+
+        let mut counter = Counter::default();
+        program.visit(&mut counter);
+
+        assert_eq!(counter.queries, vec!["b", "d", "e"]);
+        assert_eq!(counter.assignments, 2);
+        assert_eq!(counter.literals, 2);
+    }
+}
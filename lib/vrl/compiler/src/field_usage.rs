@@ -0,0 +1,146 @@
+use std::collections::BTreeSet;
+
+use lookup::LookupBuf;
+
+use crate::expression::visit::Visitor;
+#[cfg(feature = "expr-query")]
+use crate::expression::{visit::walk_query, Query};
+#[cfg(feature = "expr-assignment")]
+use crate::expression::{Assignment, AssignmentTarget};
+
+/// The event paths a program reads from and writes to.
+///
+/// Paths are collected from queries and assignments on the external target
+/// (the event), so paths only passed to functions through a dynamic value,
+/// such as `get(., path)`, are not included. Paths removed through functions
+/// such as `del` are reported as reads.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FieldUsage {
+    /// The paths queried by the program.
+    pub reads: BTreeSet<LookupBuf>,
+
+    /// The paths assigned to by the program.
+    pub writes: BTreeSet<LookupBuf>,
+}
+
+impl FieldUsage {
+    /// Returns whether the program reads the event as a whole (e.g. `encode_json(.)`), in which
+    /// case every field of the event has to be considered as read.
+    pub fn reads_root(&self) -> bool {
+        self.reads.iter().any(LookupBuf::is_root)
+    }
+
+    /// Returns whether the program overwrites the event as a whole (e.g. `. = {}`).
+    pub fn writes_root(&self) -> bool {
+        self.writes.iter().any(LookupBuf::is_root)
+    }
+
+    /// Returns the paths the program reads without assigning to them (or to one of their
+    /// parents or children) itself, i.e. the paths the program expects to be set on the event
+    /// it is given.
+    pub fn unassigned_reads(&self) -> BTreeSet<LookupBuf> {
+        self.reads
+            .iter()
+            .filter(|read| {
+                !self
+                    .writes
+                    .iter()
+                    .any(|write| read.starts_with(write) || write.starts_with(read))
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+impl Visitor for FieldUsage {
+    #[cfg(feature = "expr-query")]
+    fn visit_query(&mut self, query: &Query) {
+        if query.is_external() {
+            self.reads.insert(query.path().clone());
+        }
+        walk_query(self, query);
+    }
+
+    #[cfg(feature = "expr-assignment")]
+    fn visit_assignment(&mut self, assignment: &Assignment) {
+        for target in assignment.targets() {
+            if let AssignmentTarget::External(path) = target {
+                self.writes.insert(path);
+            }
+        }
+        self.visit_expr(assignment.expr());
+    }
+}
+
+#[cfg(all(test, feature = "expressions"))]
+mod tests {
+    use super::*;
+
+    fn field_usage(source: &str) -> FieldUsage {
+        let ast = parser::parse(source).unwrap();
+        let (program, _) = crate::compile(ast, &[]).unwrap();
+        program.field_usage()
+    }
+
+    fn paths(paths: &[&str]) -> BTreeSet<LookupBuf> {
+        paths
+            .iter()
+            .map(|path| parser::parse_path(path).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn collects_reads_and_writes() {
+        let usage = field_usage(
+            r#"
+            .a = .b
+            .c.d = [.e, { "f": .g.h }]
+            foo = .i
+            if foo == "j" { .k = 1 }
+            "#,
+        );
+
+        assert_eq!(usage.reads, paths(&[".b", ".e", ".g.h", ".i"]));
+        assert_eq!(usage.writes, paths(&[".a", ".c.d", ".k"]));
+        assert!(!usage.reads_root());
+        assert!(!usage.writes_root());
+    }
+
+    #[test]
+    fn ignores_variable_paths() {
+        let usage = field_usage(
+            r#"
+            foo = {}
+            foo.bar = 1
+            .baz = foo.bar
+            "#,
+        );
+
+        assert!(usage.reads.is_empty());
+        assert_eq!(usage.writes, paths(&[".baz"]));
+    }
+
+    #[test]
+    fn unassigned_reads_exclude_assignment_targets() {
+        let usage = field_usage(
+            r#"
+            .a = 1
+            .b.c = .a
+            .d = .b
+            .e = .b.c.f
+            .g = .h
+            "#,
+        );
+
+        assert_eq!(usage.reads, paths(&[".a", ".b", ".b.c.f", ".h"]));
+        assert_eq!(usage.unassigned_reads(), paths(&[".h"]));
+    }
+
+    #[test]
+    fn root_paths() {
+        let usage = field_usage(". = { \"a\": .b, \"c\": . }");
+
+        assert!(usage.reads_root());
+        assert!(usage.writes_root());
+    }
+}
@@ -93,6 +93,39 @@ pub trait Function: Send + Sync + fmt::Debug {
     fn closure(&self) -> Option<closure::Definition> {
         None
     }
+
+    /// An optional type guard for functions checking the type of their first
+    /// argument, such as `is_string`.
+    ///
+    /// The compiler uses it to narrow the type of the checked variable or path
+    /// in the branches of an `if` statement using the function as predicate.
+    fn type_guard(&self) -> Option<TypeGuard> {
+        None
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+/// What the result of a type checking function tells about the type of the
+/// value it checked.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeGuard {
+    /// The type states the value can have if the function returns `true`.
+    pub when_true: Kind,
+
+    /// The type states the value can't have if the function returns `false`.
+    pub excluded_when_false: Kind,
+}
+
+impl TypeGuard {
+    /// A guard for functions returning `true` exactly when the value is of the
+    /// given kind.
+    pub fn exact(kind: Kind) -> Self {
+        Self {
+            when_true: kind.clone(),
+            excluded_when_false: kind,
+        }
+    }
 }
 
 // -----------------------------------------------------------------------------
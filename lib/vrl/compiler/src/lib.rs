@@ -7,6 +7,7 @@
 
 mod compiler;
 mod context;
+mod field_usage;
 mod program;
 mod test_util;
 
@@ -25,6 +26,7 @@ pub use context::Context;
 use diagnostic::DiagnosticList;
 pub(crate) use diagnostic::Span;
 pub use expression::Expression;
+pub use field_usage::FieldUsage;
 pub use function::{Function, Parameter};
 pub use paste::paste;
 pub use program::{Program, ProgramInfo};
@@ -1,9 +1,9 @@
 use lookup::LookupBuf;
 
 use crate::{
-    expression::{Block, Resolved},
+    expression::{visit::Visitor, Block, Resolved},
     state::{ExternalEnv, LocalEnv},
-    Context, Expression,
+    Context, Expression, FieldUsage,
 };
 
 #[derive(Debug, Clone)]
@@ -31,6 +31,18 @@ impl Program {
         &self.info
     }
 
+    /// Walk the compiled expressions of the program with the given visitor.
+    pub fn visit<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        visitor.visit_block(&self.expressions);
+    }
+
+    /// Get the event paths the program reads from and writes to.
+    pub fn field_usage(&self) -> FieldUsage {
+        let mut usage = FieldUsage::default();
+        self.visit(&mut usage);
+        usage
+    }
+
     /// Resolve the program to its final [`Value`].
     pub fn resolve(&self, ctx: &mut Context) -> Resolved {
         self.expressions.resolve(ctx)
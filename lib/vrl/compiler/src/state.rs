@@ -20,7 +20,11 @@ impl LocalEnv {
         self.bindings.get(ident)
     }
 
-    #[cfg(any(feature = "expr-assignment", feature = "expr-function_call"))]
+    #[cfg(any(
+        feature = "expr-assignment",
+        feature = "expr-function_call",
+        feature = "expr-if_statement"
+    ))]
     pub(crate) fn insert_variable(&mut self, ident: Ident, details: Details) {
         self.bindings.insert(ident, details);
     }
@@ -82,7 +86,11 @@ impl ExternalEnv {
         self.target().map(|details| details.type_def.kind())
     }
 
-    #[cfg(any(feature = "expr-assignment", feature = "expr-query"))]
+    #[cfg(any(
+        feature = "expr-assignment",
+        feature = "expr-if_statement",
+        feature = "expr-query"
+    ))]
     pub(crate) fn update_target(&mut self, details: Details) {
         self.target = Some(details);
     }
@@ -585,9 +585,14 @@ impl Vm {
                         argumentlist.set_closure(state.pop_closure()?);
                     }
 
-                    let result = argumentlist
-                        .check_arguments()
-                        .and_then(|_| function.call_by_vm(ctx, &mut argumentlist));
+                    let result = match state.error.take() {
+                        // An argument propagated its error using `?`, which is
+                        // then the error of the function call.
+                        Some(err) => Err(err),
+                        None => argumentlist
+                            .check_arguments()
+                            .and_then(|_| function.call_by_vm(ctx, &mut argumentlist)),
+                    };
 
                     match result {
                         Ok(result) => state.stack.push(result),
@@ -622,9 +627,14 @@ impl Vm {
                 }
                 OpCode::MoveParameter => {
                     // Moves the top value from the stack onto the parameter stack.
-                    state
-                        .parameter_stack
-                        .push(state.stack.pop().map(VmArgument::Value))
+                    // A failed argument, or one skipped after a previous
+                    // argument failed, didn't push a value onto the stack, so
+                    // an empty parameter stands in for it.
+                    let value = match state.error {
+                        None => state.stack.pop().map(VmArgument::Value),
+                        Some(_) => None,
+                    };
+                    state.parameter_stack.push(value)
                 }
                 OpCode::MoveStaticParameter => {
                     // Moves a static parameter onto the parameter stack.
@@ -257,6 +257,7 @@ impl<'a> ArbitraryDepth<'a> for FunctionCall {
         let arguments = vec![node(FunctionArgument {
             ident: None,
             expr: node(Expr::arbitrary_depth(u, depth - 1)?),
+            propagate: None,
         })];
 
         Ok(Self {
@@ -1034,6 +1034,10 @@ impl fmt::Debug for FunctionCall {
 pub struct FunctionArgument {
     pub ident: Option<Node<Ident>>,
     pub expr: Node<Expr>,
+
+    /// The `?` operator, propagating the error of a fallible argument to the
+    /// enclosing function call.
+    pub propagate: Option<Node<()>>,
 }
 
 impl fmt::Display for FunctionArgument {
@@ -1042,16 +1046,24 @@ impl fmt::Display for FunctionArgument {
             write!(f, "{}: ", ident)?;
         }
 
-        self.expr.fmt(f)
+        self.expr.fmt(f)?;
+
+        if self.propagate.is_some() {
+            f.write_str("?")?;
+        }
+
+        Ok(())
     }
 }
 
 impl fmt::Debug for FunctionArgument {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let propagate = if self.propagate.is_some() { "?" } else { "" };
+
         if let Some(ident) = &self.ident {
-            write!(f, "Argument({:?}: {:?})", ident, self.expr)
+            write!(f, "Argument({:?}: {:?}{})", ident, self.expr, propagate)
         } else {
-            write!(f, "Argument({:?})", self.expr)
+            write!(f, "Argument({:?}{})", self.expr, propagate)
         }
     }
 }
@@ -143,7 +143,8 @@ prop_compose! {
             abort_on_error: abort,
             arguments: params.into_iter().map(|p| node(FunctionArgument {
                 ident: None,
-                expr: node(Expr::Variable(node(p)))
+                expr: node(Expr::Variable(node(p))),
+                propagate: None,
             })).collect(),
             closure: None,
         }
@@ -260,6 +261,7 @@ fn expr() -> impl Strategy<Value = Expr> {
                                 node(FunctionArgument {
                                     ident: None,
                                     expr: node(p),
+                                    propagate: None,
                                 })
                             })
                             .collect(),
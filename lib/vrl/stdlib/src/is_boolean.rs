@@ -50,6 +50,10 @@ impl Function for IsBoolean {
     fn call_by_vm(&self, _ctx: &mut Context, args: &mut VmArgumentList) -> Resolved {
         Ok(value!(args.required("value").is_boolean()))
     }
+
+    fn type_guard(&self) -> Option<TypeGuard> {
+        Some(TypeGuard::exact(Kind::boolean()))
+    }
 }
 
 #[derive(Clone, Debug)]
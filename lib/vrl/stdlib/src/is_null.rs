@@ -50,6 +50,10 @@ impl Function for IsNull {
     fn call_by_vm(&self, _ctx: &mut Context, args: &mut VmArgumentList) -> Resolved {
         Ok(value!(args.required("value").is_null()))
     }
+
+    fn type_guard(&self) -> Option<TypeGuard> {
+        Some(TypeGuard::exact(Kind::null()))
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -45,6 +45,14 @@ impl Function for IsNullish {
         let value = args.required("value");
         is_nullish(value)
     }
+
+    fn type_guard(&self) -> Option<TypeGuard> {
+        // Strings can be nullish or not, depending on their content.
+        Some(TypeGuard {
+            when_true: Kind::bytes().or_null(),
+            excluded_when_false: Kind::null(),
+        })
+    }
 }
 
 #[derive(Clone, Debug)]
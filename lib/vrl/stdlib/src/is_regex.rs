@@ -50,6 +50,10 @@ impl Function for IsRegex {
     fn call_by_vm(&self, _ctx: &mut Context, args: &mut VmArgumentList) -> Resolved {
         Ok(value!(args.required("value").is_regex()))
     }
+
+    fn type_guard(&self) -> Option<TypeGuard> {
+        Some(TypeGuard::exact(Kind::regex()))
+    }
 }
 
 #[derive(Clone, Debug)]
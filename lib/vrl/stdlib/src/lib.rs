@@ -269,6 +269,8 @@ mod to_timestamp;
 mod to_unix_timestamp;
 #[cfg(feature = "truncate")]
 mod truncate;
+#[cfg(feature = "type")]
+mod r#type;
 #[cfg(feature = "type_def")]
 mod type_def;
 #[cfg(feature = "unique")]
@@ -532,6 +534,8 @@ pub use to_timestamp::ToTimestamp;
 pub use to_unix_timestamp::ToUnixTimestamp;
 #[cfg(feature = "truncate")]
 pub use truncate::Truncate;
+#[cfg(feature = "type")]
+pub use r#type::Type;
 #[cfg(feature = "type_def")]
 pub use type_def::TypeDef;
 #[cfg(feature = "unique")]
@@ -810,6 +814,8 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(ToUnixTimestamp),
         #[cfg(feature = "truncate")]
         Box::new(Truncate),
+        #[cfg(feature = "type")]
+        Box::new(Type),
         #[cfg(feature = "type_def")]
         Box::new(TypeDef),
         #[cfg(feature = "unique")]
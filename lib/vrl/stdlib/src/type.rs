@@ -0,0 +1,115 @@
+use vrl::prelude::*;
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Bytes(_) => "string",
+        Value::Integer(_) => "integer",
+        Value::Float(_) => "float",
+        Value::Boolean(_) => "boolean",
+        Value::Timestamp(_) => "timestamp",
+        Value::Regex(_) => "regex",
+        Value::Object(_) => "object",
+        Value::Array(_) => "array",
+        Value::Null => "null",
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Type;
+
+impl Function for Type {
+    fn identifier(&self) -> &'static str {
+        "type"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::ANY,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "string",
+                source: r#"type("foobar")"#,
+                result: Ok(r#""string""#),
+            },
+            Example {
+                title: "object",
+                source: r#"type({"foo": 1})"#,
+                result: Ok(r#""object""#),
+            },
+            Example {
+                title: "null",
+                source: r#"type(null)"#,
+                result: Ok(r#""null""#),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: (&mut state::LocalEnv, &mut state::ExternalEnv),
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(TypeFn { value }))
+    }
+
+    fn call_by_vm(&self, _ctx: &mut Context, args: &mut VmArgumentList) -> Resolved {
+        Ok(type_name(&args.required("value")).into())
+    }
+}
+
+#[derive(Clone, Debug)]
+struct TypeFn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for TypeFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        self.value.resolve(ctx).map(|v| type_name(&v).into())
+    }
+
+    fn type_def(&self, _: (&state::LocalEnv, &state::ExternalEnv)) -> TypeDef {
+        TypeDef::bytes().infallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        type_name => Type;
+
+        bytes {
+            args: func_args![value: value!("foobar")],
+            want: Ok(value!("string")),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        integer {
+            args: func_args![value: value!(1789)],
+            want: Ok(value!("integer")),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        array {
+            args: func_args![value: value!([1, 2])],
+            want: Ok(value!("array")),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        null {
+            args: func_args![value: value!(null)],
+            want: Ok(value!("null")),
+            tdef: TypeDef::bytes().infallible(),
+        }
+    ];
+}
@@ -13,7 +13,257 @@ struct Source {
     program: &'static str,
 }
 
-static SOURCES: &[Source] = &[
+/// A log event, as targeted by the function benchmarks.
+const LOG: &str = r#"{
+    "message": "{\"status\": 200, \"method\": \"GET\", \"path\": \"/index.html\"}",
+    "syslog": "<13>1 2020-03-13T20:45:38.119Z dynamicwireless.name non 2426 ID931 [exampleSDID@32473 iut=\"3\" eventSource= \"Application\" eventID=\"1011\"] Try to override the THX port, maybe it will reboot the neural interface!",
+    "key_value": "level=info msg=\"Stopping all fetchers\" tag=stopping_fetchers id=ConsumerFetcherManager-1382721708341 module=kafka.consumer.ConsumerFetcherManager",
+    "access": "5.86.210.12 - zieme4647 5667 [19/06/2019:17:20:49 -0400] \"GET /embrace/supply-chains/dynamic/vertical\" 201 20574",
+    "timestamp": "2022-05-10T10:43:15Z",
+    "count": "42",
+    "hostname": "vector",
+    "labels": {
+        "app": "vector",
+        "environment": "production",
+        "team": "observability"
+    }
+}"#;
+
+/// One program per expression type, to track the cost of each in isolation.
+static EXPRESSIONS: &[Source] = &[
+    Source {
+        name: "literal",
+        target: "{}",
+        program: indoc! {r#"
+            "vector"
+        "#},
+    },
+    Source {
+        name: "container",
+        target: "{}",
+        program: indoc! {r#"
+            { "name": "vector", "tags": ["one", "two", "three"], "enabled": true }
+        "#},
+    },
+    Source {
+        name: "variable",
+        target: "{}",
+        program: indoc! {r#"
+            name = "vector"
+            name
+        "#},
+    },
+    Source {
+        name: "query",
+        target: LOG,
+        program: indoc! {r#"
+            .labels.environment
+        "#},
+    },
+    Source {
+        name: "index",
+        target: r#"{ "tags": ["one", "two", "three"] }"#,
+        program: indoc! {r#"
+            .tags[1]
+        "#},
+    },
+    Source {
+        name: "assignment",
+        target: "{}",
+        program: indoc! {r#"
+            .name = "vector"
+        "#},
+    },
+    Source {
+        name: "error_assignment",
+        target: r#"{ "divisor": 0 }"#,
+        program: indoc! {r#"
+            result, err = 10 / .divisor
+        "#},
+    },
+    Source {
+        name: "if_statement",
+        target: r#"{ "status": 200 }"#,
+        program: indoc! {r#"
+            if .status == 200 { .success = true } else { .success = false }
+        "#},
+    },
+    Source {
+        name: "arithmetic",
+        target: "{}",
+        program: indoc! {r#"
+            a = 3
+            b = 4
+            a * b + a - b / 2
+        "#},
+    },
+    Source {
+        name: "comparison",
+        target: "{}",
+        program: indoc! {r#"
+            a = 3
+            b = 4
+            a < b
+        "#},
+    },
+    Source {
+        name: "logical",
+        target: "{}",
+        program: indoc! {r#"
+            a = true
+            b = false
+            a && b || a
+        "#},
+    },
+    Source {
+        name: "not",
+        target: "{}",
+        program: indoc! {r#"
+            a = true
+            !a
+        "#},
+    },
+    Source {
+        name: "coalesce",
+        target: r#"{ "count": "not a number" }"#,
+        program: indoc! {r#"
+            to_int(.count) ?? 0
+        "#},
+    },
+    Source {
+        name: "merge",
+        target: "{}",
+        program: indoc! {r#"
+            { "a": 1 } | { "b": 2 }
+        "#},
+    },
+    Source {
+        name: "function_call",
+        target: "{}",
+        program: indoc! {r#"
+            length([1, 2, 3])
+        "#},
+    },
+    Source {
+        name: "closure",
+        target: LOG,
+        program: indoc! {r#"
+            map_values(object!(.labels)) -> |value| { upcase(value) ?? value }
+        "#},
+    },
+];
+
+/// The functions most commonly used in remap programs.
+static FUNCTIONS: &[Source] = &[
+    Source {
+        name: "contains",
+        target: LOG,
+        program: indoc! {r#"
+            contains(string!(.key_value), "fetchers")
+        "#},
+    },
+    Source {
+        name: "del",
+        target: LOG,
+        program: indoc! {r#"
+            del(.labels.team)
+        "#},
+    },
+    Source {
+        name: "downcase",
+        target: LOG,
+        program: indoc! {r#"
+            downcase(string!(.key_value))
+        "#},
+    },
+    Source {
+        name: "encode_json",
+        target: LOG,
+        program: indoc! {r#"
+            encode_json(.labels)
+        "#},
+    },
+    Source {
+        name: "exists",
+        target: LOG,
+        program: indoc! {r#"
+            exists(.labels.app)
+        "#},
+    },
+    Source {
+        name: "format_timestamp",
+        target: LOG,
+        program: indoc! {r#"
+            format_timestamp!(t'2022-05-10T10:43:15Z', "%d %B %Y %H:%M")
+        "#},
+    },
+    Source {
+        name: "parse_json",
+        target: LOG,
+        program: indoc! {r#"
+            parse_json!(string!(.message))
+        "#},
+    },
+    Source {
+        name: "parse_key_value",
+        target: LOG,
+        program: indoc! {r#"
+            parse_key_value!(.key_value)
+        "#},
+    },
+    Source {
+        name: "parse_regex",
+        target: LOG,
+        program: indoc! {r#"
+            parse_regex!(.access, r'^(?P<host>[\w\.]+) - (?P<user>[\w]+) (?P<bytes_in>[\d]+) \[(?P<timestamp>.*)\] "(?P<method>[\w]+) (?P<path>.*)" (?P<status>[\d]+) (?P<bytes_out>[\d]+)$')
+        "#},
+    },
+    Source {
+        name: "parse_syslog",
+        target: LOG,
+        program: indoc! {r#"
+            parse_syslog!(.syslog)
+        "#},
+    },
+    Source {
+        name: "parse_timestamp",
+        target: LOG,
+        program: indoc! {r#"
+            parse_timestamp!(.timestamp, "%+")
+        "#},
+    },
+    Source {
+        name: "replace",
+        target: LOG,
+        program: indoc! {r#"
+            replace(string!(.key_value), "fetchers", "consumers")
+        "#},
+    },
+    Source {
+        name: "split",
+        target: LOG,
+        program: indoc! {r#"
+            split(string!(.key_value), " ")
+        "#},
+    },
+    Source {
+        name: "to_int",
+        target: LOG,
+        program: indoc! {r#"
+            to_int!(.count)
+        "#},
+    },
+    Source {
+        name: "upcase",
+        target: LOG,
+        program: indoc! {r#"
+            upcase(string!(.hostname))
+        "#},
+    },
+];
+
+/// Complete programs, as found in remap transforms.
+static PROGRAMS: &[Source] = &[
     Source {
         name: "parse_json",
         target: r#"
@@ -92,9 +342,23 @@ static SOURCES: &[Source] = &[
     },
 ];
 
-fn benchmark_vrl_runtimes(c: &mut Criterion) {
-    let mut group = c.benchmark_group("vrl/runtime");
-    for source in SOURCES {
+fn benchmark_expressions(c: &mut Criterion) {
+    bench_runtimes(c, "vrl/runtime/expression", EXPRESSIONS);
+}
+
+fn benchmark_functions(c: &mut Criterion) {
+    bench_runtimes(c, "vrl/runtime/function", FUNCTIONS);
+}
+
+fn benchmark_programs(c: &mut Criterion) {
+    bench_runtimes(c, "vrl/runtime", PROGRAMS);
+}
+
+/// Benchmarks each source on every runtime, after checking they all agree on
+/// its result.
+fn bench_runtimes(c: &mut Criterion, group_name: &str, sources: &[Source]) {
+    let mut group = c.benchmark_group(group_name);
+    for source in sources {
         let state = state::Runtime::default();
         let runtime = Runtime::new(state);
         let tz = TimeZone::default();
@@ -104,11 +368,23 @@ fn benchmark_vrl_runtimes(c: &mut Criterion) {
         let vm = runtime
             .compile(functions, &program, &mut external_env)
             .unwrap();
+        let target: Value = serde_json::from_str(source.target).expect("valid json");
+
+        {
+            let mut runtime = Runtime::new(state::Runtime::default());
+            let ast_result = runtime.resolve(&mut target.clone(), &program, &tz);
+            runtime.clear();
+            let vm_result = runtime.run_vm(&vm, &mut target.clone(), &tz);
+            assert_eq!(
+                ast_result, vm_result,
+                "runtimes disagree on the result of {}",
+                source.name
+            );
+        }
 
         group.bench_with_input(BenchmarkId::new(source.name, "vm"), &vm, |b, vm| {
             let state = state::Runtime::default();
             let mut runtime = Runtime::new(state);
-            let target: Value = serde_json::from_str(source.target).expect("valid json");
 
             b.iter_with_setup(
                 || target.clone(),
@@ -123,7 +399,6 @@ fn benchmark_vrl_runtimes(c: &mut Criterion) {
         group.bench_with_input(BenchmarkId::new(source.name, "ast"), &(), |b, _| {
             let state = state::Runtime::default();
             let mut runtime = Runtime::new(state);
-            let target: Value = serde_json::from_str(source.target).expect("valid json");
 
             b.iter_with_setup(
                 || target.clone(),
@@ -151,5 +426,17 @@ criterion_group!(name = vrl_runtime;
                     .nresamples(100_000)
                     // total samples to collect within the set measurement time
                     .sample_size(150);
-                 targets = benchmark_vrl_runtimes);
-criterion_main!(vrl_runtime);
+                 targets = benchmark_programs);
+// The expression and function cases are much cheaper to run, so they can do
+// with a shorter measurement time, which keeps the whole suite practical to run.
+criterion_group!(name = vrl_runtime_cases;
+                config = Criterion::default()
+                    .warm_up_time(Duration::from_secs(2))
+                    .measurement_time(Duration::from_secs(10))
+                    .noise_threshold(0.01)
+                    .significance_level(0.05)
+                    .confidence_level(0.95)
+                    .nresamples(100_000)
+                    .sample_size(150);
+                 targets = benchmark_expressions, benchmark_functions);
+criterion_main!(vrl_runtime, vrl_runtime_cases);
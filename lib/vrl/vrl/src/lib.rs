@@ -10,8 +10,8 @@ pub mod prelude;
 mod runtime;
 
 pub use compiler::{
-    function, state, value, vm::Vm, Context, Expression, Function, Program, ProgramInfo, Target,
-    VrlRuntime,
+    function, state, value, vm::Vm, Context, Expression, FieldUsage, Function, Program,
+    ProgramInfo, Target, VrlRuntime,
 };
 pub use diagnostic;
 pub use runtime::{Runtime, RuntimeResult, Terminate};
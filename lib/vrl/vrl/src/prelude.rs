@@ -23,7 +23,7 @@ pub use bytes::Bytes;
 
 // commonly used function types
 pub use compiler::function::{
-    ArgumentList, Compiled, CompiledArgument, Example, FunctionCompileContext, Parameter,
+    ArgumentList, Compiled, CompiledArgument, Example, FunctionCompileContext, Parameter, TypeGuard,
 };
 pub use compiler::value::{VrlValueArithmetic, VrlValueConvert};
 // commonly used macros
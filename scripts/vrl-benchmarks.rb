@@ -0,0 +1,124 @@
+#!/usr/bin/env ruby
+
+# vrl-benchmarks.rb
+#
+# SUMMARY
+#
+#   Exports the results of the VRL runtime benchmarks (`lib/vrl/vrl/benches/runtime.rs`)
+#   as JSON, and compares two exported runs to track regressions of each runtime.
+#
+# USAGE
+#
+#   scripts/vrl-benchmarks.rb export [CRITERION_HOME] > current.json
+#   scripts/vrl-benchmarks.rb diff [--threshold PERCENT] baseline.json current.json
+#
+#   `export` reads the criterion output directory, `target/criterion` by default.
+#   `diff` exits with a non-zero status when a benchmark regressed by more than
+#   the threshold, 5% by default.
+
+require "json"
+require "optparse"
+
+BENCHMARK_PREFIX = "vrl/runtime"
+DEFAULT_THRESHOLD = 5.0
+
+def export(criterion_home)
+  benchmarks = {}
+
+  Dir.glob(File.join(criterion_home, "**", "new", "benchmark.json")).each do |path|
+    id = JSON.parse(File.read(path)).fetch("full_id")
+    next unless id.start_with?(BENCHMARK_PREFIX)
+
+    estimates = JSON.parse(File.read(File.join(File.dirname(path), "estimates.json")))
+    benchmarks[id] = {
+      "mean_ns" => estimates.dig("mean", "point_estimate"),
+      "median_ns" => estimates.dig("median", "point_estimate"),
+      "std_dev_ns" => estimates.dig("std_dev", "point_estimate")
+    }
+  end
+
+  if benchmarks.empty?
+    abort "No VRL runtime benchmarks found in #{criterion_home}, run them with `make bench-vrl-runtime` first."
+  end
+
+  commit = `git rev-parse HEAD 2>/dev/null`.strip
+  puts JSON.pretty_generate(
+    "commit" => commit.empty? ? nil : commit,
+    "benchmarks" => benchmarks.sort.to_h
+  )
+end
+
+def format_duration(nanoseconds)
+  return "-" if nanoseconds.nil?
+
+  if nanoseconds >= 1_000_000
+    format("%.2f ms", nanoseconds / 1_000_000)
+  elsif nanoseconds >= 1_000
+    format("%.2f µs", nanoseconds / 1_000)
+  else
+    format("%.2f ns", nanoseconds)
+  end
+end
+
+def diff(baseline_path, current_path, threshold)
+  baseline = JSON.parse(File.read(baseline_path)).fetch("benchmarks")
+  current = JSON.parse(File.read(current_path)).fetch("benchmarks")
+
+  rows = (baseline.keys | current.keys).sort.map do |id|
+    before = baseline.dig(id, "median_ns")
+    after = current.dig(id, "median_ns")
+
+    change, status =
+      if before.nil?
+        [nil, "added"]
+      elsif after.nil?
+        [nil, "removed"]
+      else
+        change = (after - before) / before * 100
+        status =
+          if change > threshold
+            "regressed"
+          elsif change < -threshold
+            "improved"
+          else
+            "unchanged"
+          end
+        [change, status]
+      end
+
+    [id, format_duration(before), format_duration(after), change.nil? ? "-" : format("%+.2f%%", change), status]
+  end
+
+  header = ["benchmark", "baseline", "current", "change", "status"]
+  widths = header.each_index.map { |i| ([header] + rows).map { |row| row[i].length }.max }
+  ([header] + rows).each do |row|
+    puts row.each_with_index.map { |cell, i| cell.ljust(widths[i]) }.join("  ").rstrip
+  end
+
+  regressions = rows.count { |row| row.last == "regressed" }
+  if regressions > 0
+    puts "\n#{regressions} benchmark(s) regressed by more than #{threshold}%. Note that any regressions should be verified."
+    exit 1
+  end
+end
+
+command = ARGV.shift
+
+case command
+when "export"
+  export(ARGV.fetch(0, File.join("target", "criterion")))
+when "diff"
+  threshold = DEFAULT_THRESHOLD
+  OptionParser.new do |opts|
+    opts.banner = "Usage: #{$0} diff [--threshold PERCENT] baseline.json current.json"
+    opts.on("--threshold PERCENT", Float, "Change in percent above which a benchmark regressed") do |value|
+      threshold = value
+    end
+  end.parse!
+
+  abort "Usage: #{$0} diff [--threshold PERCENT] baseline.json current.json" unless ARGV.length == 2
+
+  diff(ARGV[0], ARGV[1], threshold)
+else
+  abort "Usage: #{$0} (export [CRITERION_HOME] | diff [--threshold PERCENT] baseline.json current.json)"
+end
@@ -21,3 +21,72 @@ pub(super) async fn health(running: Arc<AtomicBool>) -> Result<impl Reply, Rejec
         ))
     }
 }
+
+/// How long CPU profiles are collected for, unless the request asks otherwise.
+#[cfg(all(unix, feature = "api-profiling"))]
+const DEFAULT_PROFILE_SECONDS: u64 = 30;
+
+/// The longest CPU profiles can be collected for, bounding the memory used by the samples.
+#[cfg(all(unix, feature = "api-profiling"))]
+const MAX_PROFILE_SECONDS: u64 = 300;
+
+#[cfg(all(unix, feature = "api-profiling"))]
+#[derive(Debug, serde::Deserialize)]
+pub(super) struct ProfileParams {
+    seconds: Option<u64>,
+}
+
+// CPU profile handler, responds with a pprof profile of the CPU time spent by the process over
+// the requested number of seconds. Only one profile can be collected at a time.
+#[cfg(all(unix, feature = "api-profiling"))]
+pub(super) async fn cpu_profile(
+    enabled: bool,
+    params: ProfileParams,
+) -> Result<impl Reply, Rejection> {
+    if !enabled {
+        return Err(warp::reject::not_found());
+    }
+
+    let seconds = params
+        .seconds
+        .unwrap_or(DEFAULT_PROFILE_SECONDS)
+        .clamp(1, MAX_PROFILE_SECONDS);
+    match super::profiling::cpu_profile(std::time::Duration::from_secs(seconds)).await {
+        Ok(profile) => Ok(profile_reply(profile)),
+        Err(error) => {
+            let status = match error {
+                super::profiling::ProfileError::InProgress => warp::http::StatusCode::CONFLICT,
+                super::profiling::ProfileError::Signal(_) => {
+                    warp::http::StatusCode::INTERNAL_SERVER_ERROR
+                }
+            };
+            Ok(warp::reply::with_status(
+                warp::reply::with_header(
+                    error.to_string().into_bytes(),
+                    "content-type",
+                    "text/plain",
+                ),
+                status,
+            ))
+        }
+    }
+}
+
+// Heap profile handler, responds with a pprof profile of the allocations sampled since the API
+// started.
+#[cfg(all(unix, feature = "api-profiling"))]
+pub(super) async fn heap_profile(enabled: bool) -> Result<impl Reply, Rejection> {
+    if !enabled {
+        return Err(warp::reject::not_found());
+    }
+
+    Ok(profile_reply(super::profiling::heap_profile()))
+}
+
+#[cfg(all(unix, feature = "api-profiling"))]
+fn profile_reply(profile: Vec<u8>) -> warp::reply::WithStatus<warp::reply::WithHeader<Vec<u8>>> {
+    warp::reply::with_status(
+        warp::reply::with_header(profile, "content-type", "application/octet-stream"),
+        warp::http::StatusCode::OK,
+    )
+}
@@ -1,4 +1,6 @@
 mod handler;
+#[cfg(all(unix, feature = "api-profiling"))]
+pub mod profiling;
 mod schema;
 mod server;
 pub mod tap;
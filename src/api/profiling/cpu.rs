@@ -0,0 +1,307 @@
+use std::{
+    ffi::c_void,
+    io, mem, ptr,
+    sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use super::pprof::ProfileBuilder;
+
+/// How often the process is sampled, per second of CPU time it consumes.
+const FREQUENCY: u64 = 99;
+
+/// Stack traces deeper than this are truncated to their innermost frames.
+const MAX_DEPTH: usize = 64;
+
+/// Room for every sample of a profile when all of the cores are busy, past which samples are
+/// discarded.
+const SAMPLES_PER_CORE_SECOND: usize = FREQUENCY as usize + 1;
+
+/// A stack trace captured by the signal handler, which can't allocate.
+#[derive(Clone, Copy)]
+struct Frames {
+    depth: usize,
+    addresses: [usize; MAX_DEPTH],
+}
+
+impl Frames {
+    const EMPTY: Self = Self {
+        depth: 0,
+        addresses: [0; MAX_DEPTH],
+    };
+}
+
+/// Only one profile can be collected at a time, as the timer and the handler are process-wide.
+static PROFILING: AtomicBool = AtomicBool::new(false);
+
+/// The buffer the signal handler writes samples into, allocated up front for each profile.
+static SAMPLES: AtomicPtr<Frames> = AtomicPtr::new(ptr::null_mut());
+static CAPACITY: AtomicUsize = AtomicUsize::new(0);
+static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+/// The number of signal handlers currently running, which have to complete before the buffer
+/// can be freed.
+static IN_HANDLER: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug)]
+pub(crate) enum ProfileError {
+    InProgress,
+    Signal(io::Error),
+}
+
+impl std::fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProfileError::InProgress => write!(f, "a CPU profile is already being collected"),
+            ProfileError::Signal(error) => write!(f, "unable to set up sampling: {}", error),
+        }
+    }
+}
+
+/// Samples the stack of the threads running on the CPU for `duration`, returning the resulting
+/// profile in the pprof format.
+///
+/// Sampling relies on `SIGPROF`, delivered by the kernel to whichever thread is consuming CPU
+/// time, so idle threads don't show up in the profile.
+pub(crate) async fn profile(duration: Duration) -> Result<Vec<u8>, ProfileError> {
+    if PROFILING
+        .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+        .is_err()
+    {
+        return Err(ProfileError::InProgress);
+    }
+
+    let capacity = SAMPLES_PER_CORE_SECOND * num_cpus::get() * (duration.as_secs() as usize + 1);
+    let mut samples = vec![Frames::EMPTY; capacity];
+    NEXT.store(0, Ordering::SeqCst);
+    CAPACITY.store(capacity, Ordering::SeqCst);
+    SAMPLES.store(samples.as_mut_ptr(), Ordering::SeqCst);
+
+    // Stops sampling even if the request is cancelled while it's in progress. It's dropped before
+    // the samples, and only returns once no handler can write to them anymore.
+    let session = Session;
+    let started = Instant::now();
+    start().map_err(ProfileError::Signal)?;
+    tokio::time::sleep(duration).await;
+    let elapsed = started.elapsed();
+    let collected = NEXT.load(Ordering::SeqCst).min(capacity);
+    drop(session);
+
+    let period = 1_000_000_000 / FREQUENCY as i64;
+    let mut builder = ProfileBuilder::new(
+        &[("samples", "count"), ("cpu", "nanoseconds")],
+        ("cpu", "nanoseconds"),
+        period,
+    )
+    .duration_nanos(elapsed.as_nanos() as i64);
+    // Samples can be left empty by handlers interrupted when sampling stopped.
+    for frames in samples[..collected]
+        .iter()
+        .filter(|frames| frames.depth > 0)
+    {
+        builder.add_sample(&frames.addresses[..frames.depth], &[1, period]);
+    }
+    Ok(builder.encode())
+}
+
+struct Session;
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        if let Err(error) = stop() {
+            error!(message = "Failed to stop CPU profiling.", %error);
+        }
+
+        // Handlers already running may still be writing to the buffer. They increment
+        // `IN_HANDLER` before loading the buffer, so once it's cleared and no handler is running,
+        // none can access it anymore.
+        SAMPLES.store(ptr::null_mut(), Ordering::SeqCst);
+        while IN_HANDLER.load(Ordering::SeqCst) > 0 {
+            std::thread::yield_now();
+        }
+        PROFILING.store(false, Ordering::Release);
+    }
+}
+
+fn start() -> io::Result<()> {
+    unwind_safety::find_unsafe_ranges();
+
+    unsafe {
+        let mut action: libc::sigaction = mem::zeroed();
+        action.sa_sigaction = handler as usize;
+        action.sa_flags = libc::SA_RESTART | libc::SA_SIGINFO;
+        libc::sigemptyset(&mut action.sa_mask);
+        if libc::sigaction(libc::SIGPROF, &action, ptr::null_mut()) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+
+    let interval = libc::timeval {
+        tv_sec: 0,
+        tv_usec: (1_000_000 / FREQUENCY) as libc::suseconds_t,
+    };
+    set_timer(interval)
+}
+
+fn stop() -> io::Result<()> {
+    set_timer(libc::timeval {
+        tv_sec: 0,
+        tv_usec: 0,
+    })?;
+
+    // A signal may still be pending, and the default disposition of `SIGPROF` terminates the
+    // process, so ignore it rather than restoring the default. This discards pending signals,
+    // so no handler starts past this point.
+    unsafe {
+        if libc::signal(libc::SIGPROF, libc::SIG_IGN) == libc::SIG_ERR {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+extern "C" fn handler(_: libc::c_int, _: *mut libc::siginfo_t, context: *mut c_void) {
+    IN_HANDLER.fetch_add(1, Ordering::SeqCst);
+    sample(context);
+    IN_HANDLER.fetch_sub(1, Ordering::SeqCst);
+}
+
+fn sample(context: *mut c_void) {
+    let samples = SAMPLES.load(Ordering::SeqCst);
+    if samples.is_null() || unwind_safety::interrupted_in_unsafe_code(context) {
+        return;
+    }
+    let index = NEXT.fetch_add(1, Ordering::SeqCst);
+    if index >= CAPACITY.load(Ordering::SeqCst) {
+        return;
+    }
+
+    // Safety: each index is handed out to a single invocation of the handler, and the buffer
+    // isn't freed while a handler is running.
+    let frames = unsafe { &mut *samples.add(index) };
+    let mut depth = 0;
+    // Safety: the unwinder of the platforms this is built for is thread-safe, and doesn't
+    // allocate or lock anything other than what `unwind_safety` excludes.
+    unsafe {
+        backtrace::trace_unsynchronized(|frame| {
+            frames.addresses[depth] = frame.ip() as usize;
+            depth += 1;
+            depth < MAX_DEPTH
+        });
+    }
+    frames.depth = depth;
+}
+
+/// Unwinding a thread interrupted within the libraries the unwinder relies on could deadlock on
+/// a lock it holds, or observe their state half updated, so such threads aren't sampled.
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+mod unwind_safety {
+    use std::{
+        ffi::{c_void, CStr},
+        ptr,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    const UNSAFE_LIBRARIES: &[&str] = &[
+        "libgcc_s",
+        "libunwind",
+        "libc.so",
+        "libc-",
+        "libpthread",
+        "libdl",
+        "ld-linux",
+        "ld-musl",
+    ];
+
+    const MAX_RANGES: usize = 32;
+
+    #[allow(clippy::declare_interior_mutable_const)]
+    const EMPTY_RANGE: (AtomicUsize, AtomicUsize) = (AtomicUsize::new(0), AtomicUsize::new(0));
+
+    /// The code of `UNSAFE_LIBRARIES`, as start and end addresses.
+    static RANGES: [(AtomicUsize, AtomicUsize); MAX_RANGES] = [EMPTY_RANGE; MAX_RANGES];
+    static RANGE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    /// Records the code of `UNSAFE_LIBRARIES`. This has to be done before sampling starts, as
+    /// walking the loaded libraries isn't async-signal-safe.
+    pub(super) fn find_unsafe_ranges() {
+        unsafe extern "C" fn callback(
+            info: *mut libc::dl_phdr_info,
+            _: libc::size_t,
+            _: *mut c_void,
+        ) -> libc::c_int {
+            let info = &*info;
+            if info.dlpi_name.is_null() {
+                return 0;
+            }
+            let name = CStr::from_ptr(info.dlpi_name).to_string_lossy();
+            let file_name = name.rsplit('/').next().unwrap_or_default();
+            if !UNSAFE_LIBRARIES
+                .iter()
+                .any(|library| file_name.starts_with(library))
+            {
+                return 0;
+            }
+
+            for index in 0..info.dlpi_phnum as usize {
+                let header = &*info.dlpi_phdr.add(index);
+                if header.p_type != libc::PT_LOAD || header.p_flags & libc::PF_X == 0 {
+                    continue;
+                }
+                let count = RANGE_COUNT.load(Ordering::SeqCst);
+                if count == MAX_RANGES {
+                    return 1;
+                }
+                let start = info.dlpi_addr as usize + header.p_vaddr as usize;
+                RANGES[count].0.store(start, Ordering::SeqCst);
+                RANGES[count]
+                    .1
+                    .store(start + header.p_memsz as usize, Ordering::SeqCst);
+                RANGE_COUNT.store(count + 1, Ordering::SeqCst);
+            }
+            0
+        }
+
+        RANGE_COUNT.store(0, Ordering::SeqCst);
+        unsafe {
+            libc::dl_iterate_phdr(Some(callback), ptr::null_mut());
+        }
+    }
+
+    /// Returns whether the thread whose signal context is `context` was interrupted while
+    /// running code of `UNSAFE_LIBRARIES`.
+    pub(super) fn interrupted_in_unsafe_code(context: *mut c_void) -> bool {
+        if context.is_null() {
+            return true;
+        }
+        let context = unsafe { &*(context as *const libc::ucontext_t) };
+        #[cfg(target_arch = "x86_64")]
+        let ip = context.uc_mcontext.gregs[libc::REG_RIP as usize] as usize;
+        #[cfg(target_arch = "aarch64")]
+        let ip = context.uc_mcontext.pc as usize;
+
+        let count = RANGE_COUNT.load(Ordering::SeqCst);
+        RANGES[..count].iter().any(|(start, end)| {
+            (start.load(Ordering::Relaxed)..end.load(Ordering::Relaxed)).contains(&ip)
+        })
+    }
+}
+
+/// Where the interrupted instruction can't be determined, threads are assumed to be safe to
+/// unwind.
+#[cfg(not(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+)))]
+mod unwind_safety {
+    use std::ffi::c_void;
+
+    pub(super) const fn find_unsafe_ranges() {}
+
+    pub(super) const fn interrupted_in_unsafe_code(_: *mut c_void) -> bool {
+        false
+    }
+}
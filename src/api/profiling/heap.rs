@@ -0,0 +1,246 @@
+use std::{
+    alloc::{GlobalAlloc, Layout},
+    cell::{Cell, UnsafeCell},
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering},
+        Mutex, Once,
+    },
+    time::Duration,
+};
+
+use once_cell::sync::Lazy;
+
+use super::pprof::ProfileBuilder;
+
+/// On average, one allocation is sampled for every this many bytes allocated by a thread.
+const SAMPLE_INTERVAL: usize = 512 * 1024;
+
+/// Stack traces deeper than this are truncated to their innermost frames.
+const MAX_DEPTH: usize = 64;
+
+/// How many samples can be waiting to be collected, past which samples are discarded.
+const PENDING_CAPACITY: usize = 1024;
+
+/// How often the pending samples are collected.
+const COLLECT_INTERVAL: Duration = Duration::from_secs(1);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// The sampled allocations, as the number of allocations and bytes they stand for, by stack trace.
+///
+/// The allocator can't take a lock, which may be held by a thread waiting for memory, nor
+/// allocate, so it writes samples into `PENDING` instead, from which they're collected here.
+static ALLOCATIONS: Lazy<Mutex<HashMap<Vec<usize>, (i64, i64)>>> = Lazy::new(Default::default);
+
+static PENDING: [Slot; PENDING_CAPACITY] = [Slot::EMPTY; PENDING_CAPACITY];
+static NEXT_SLOT: AtomicUsize = AtomicUsize::new(0);
+
+const SLOT_FREE: u8 = 0;
+const SLOT_WRITING: u8 = 1;
+const SLOT_READY: u8 = 2;
+const SLOT_READING: u8 = 3;
+
+/// A sample waiting to be collected. A slot is claimed by atomically moving it out of its free or
+/// ready state, so that it's never accessed by two threads at once.
+struct Slot {
+    state: AtomicU8,
+    sample: UnsafeCell<Sample>,
+}
+
+// Safety: the sample is only accessed by the thread that moved the slot to `SLOT_WRITING` or
+// `SLOT_READING`.
+unsafe impl Sync for Slot {}
+
+impl Slot {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const EMPTY: Self = Self {
+        state: AtomicU8::new(SLOT_FREE),
+        sample: UnsafeCell::new(Sample {
+            size: 0,
+            depth: 0,
+            addresses: [0; MAX_DEPTH],
+        }),
+    };
+}
+
+#[derive(Clone, Copy)]
+struct Sample {
+    size: usize,
+    depth: usize,
+    addresses: [usize; MAX_DEPTH],
+}
+
+thread_local! {
+    /// How many more bytes the thread has to allocate before its next allocation is sampled.
+    static COUNTDOWN: Cell<usize> = Cell::new(SAMPLE_INTERVAL);
+    /// Set while the thread is sampling an allocation, so the allocations made to record it
+    /// aren't sampled in turn.
+    static SAMPLING: Cell<bool> = Cell::new(false);
+}
+
+/// A global allocator sampling the allocations made through the allocator it wraps, once
+/// allocation profiling has been enabled.
+pub struct Profiled<A>(pub A);
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for Profiled<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        sample(layout.size());
+        self.0.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.0.dealloc(ptr, layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        sample(layout.size());
+        self.0.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        sample(new_size);
+        self.0.realloc(ptr, layout, new_size)
+    }
+}
+
+/// Starts sampling allocations. Does nothing unless `Profiled` is the global allocator.
+pub(crate) fn enable() {
+    static COLLECTOR: Once = Once::new();
+    COLLECTOR.call_once(|| {
+        let spawned = std::thread::Builder::new()
+            .name("allocation-sampler".to_string())
+            .spawn(|| loop {
+                std::thread::sleep(COLLECT_INTERVAL);
+                collect();
+            });
+        if let Err(error) = spawned {
+            error!(message = "Failed to start collecting allocation samples.", %error);
+        }
+    });
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+fn sample(size: usize) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+
+    // Thread locals can't be accessed while the thread is being torn down, in which case its
+    // allocations go unsampled.
+    let due = COUNTDOWN
+        .try_with(|countdown| match countdown.get().checked_sub(size) {
+            Some(remaining) if remaining > 0 => {
+                countdown.set(remaining);
+                false
+            }
+            _ => {
+                countdown.set(SAMPLE_INTERVAL);
+                true
+            }
+        })
+        .unwrap_or(false);
+    if !due
+        || !matches!(
+            SAMPLING.try_with(|sampling| sampling.replace(true)),
+            Ok(false)
+        )
+    {
+        return;
+    }
+
+    // The sample is discarded if the slot it would be written to hasn't been collected yet.
+    let slot = &PENDING[NEXT_SLOT.fetch_add(1, Ordering::Relaxed) % PENDING_CAPACITY];
+    if slot
+        .state
+        .compare_exchange(
+            SLOT_FREE,
+            SLOT_WRITING,
+            Ordering::Acquire,
+            Ordering::Relaxed,
+        )
+        .is_ok()
+    {
+        // Safety: the slot was claimed above.
+        let sample = unsafe { &mut *slot.sample.get() };
+        sample.size = size;
+        sample.depth = 0;
+        // Safety: the unwinder of the platforms this is built for is thread-safe. Unlike
+        // `backtrace::trace`, this doesn't take a lock that a thread allocating while holding it
+        // would deadlock on.
+        unsafe {
+            backtrace::trace_unsynchronized(|frame| {
+                sample.addresses[sample.depth] = frame.ip() as usize;
+                sample.depth += 1;
+                sample.depth < MAX_DEPTH
+            });
+        }
+        slot.state.store(SLOT_READY, Ordering::Release);
+    }
+
+    let _ = SAMPLING.try_with(|sampling| sampling.set(false));
+}
+
+/// Moves the pending samples into `ALLOCATIONS`.
+fn collect() {
+    // Recording the samples allocates, which mustn't sample while the lock is held.
+    let _ = SAMPLING.try_with(|sampling| sampling.set(true));
+    if let Ok(mut allocations) = ALLOCATIONS.lock() {
+        for slot in PENDING.iter() {
+            if slot
+                .state
+                .compare_exchange(
+                    SLOT_READY,
+                    SLOT_READING,
+                    Ordering::Acquire,
+                    Ordering::Relaxed,
+                )
+                .is_err()
+            {
+                continue;
+            }
+            // Safety: the slot was claimed above.
+            let sample = unsafe { *slot.sample.get() };
+            slot.state.store(SLOT_FREE, Ordering::Release);
+
+            // Each sample stands for the allocations made since the previous one.
+            let count = (SAMPLE_INTERVAL / sample.size.max(1)).max(1) as i64;
+            let bytes = sample.size.max(SAMPLE_INTERVAL) as i64;
+            let totals = allocations
+                .entry(sample.addresses[..sample.depth].to_vec())
+                .or_default();
+            totals.0 += count;
+            totals.1 += bytes;
+        }
+    }
+    let _ = SAMPLING.try_with(|sampling| sampling.set(false));
+}
+
+/// Returns a profile of the allocations sampled since profiling was enabled, in the pprof
+/// format.
+pub(crate) fn profile() -> Vec<u8> {
+    collect();
+
+    // Copying the samples allocates, which mustn't sample while the lock is held.
+    let _ = SAMPLING.try_with(|sampling| sampling.set(true));
+    let allocations = ALLOCATIONS
+        .lock()
+        .map(|allocations| {
+            allocations
+                .iter()
+                .map(|(frames, totals)| (frames.clone(), *totals))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let _ = SAMPLING.try_with(|sampling| sampling.set(false));
+
+    let mut builder = ProfileBuilder::new(
+        &[("alloc_objects", "count"), ("alloc_space", "bytes")],
+        ("space", "bytes"),
+        SAMPLE_INTERVAL as i64,
+    );
+    for (frames, (count, bytes)) in allocations {
+        builder.add_sample(&frames, &[count, bytes]);
+    }
+    builder.encode()
+}
@@ -0,0 +1,10 @@
+//! CPU and allocation profiles of the running process, served by the API in the format consumed
+//! by `go tool pprof`.
+
+mod cpu;
+mod heap;
+mod pprof;
+
+pub(crate) use cpu::{profile as cpu_profile, ProfileError};
+pub use heap::Profiled;
+pub(crate) use heap::{enable as enable_allocation_sampling, profile as heap_profile};
@@ -0,0 +1,212 @@
+use std::{collections::HashMap, ffi::c_void, io::Write, time::SystemTime};
+
+use flate2::{write::GzEncoder, Compression};
+use prost::Message;
+
+mod proto {
+    include!(concat!(env!("OUT_DIR"), "/perftools.profiles.rs"));
+}
+
+/// Incrementally builds a profile in the [pprof] format out of raw stack traces, symbolizing the
+/// addresses they're made of along the way.
+///
+/// [pprof]: https://github.com/google/pprof/blob/main/proto/README.md
+pub(super) struct ProfileBuilder {
+    profile: proto::Profile,
+    strings: HashMap<String, i64>,
+    locations: HashMap<usize, u64>,
+    functions: HashMap<(i64, i64), u64>,
+    samples: HashMap<Vec<u64>, usize>,
+}
+
+impl ProfileBuilder {
+    /// Creates a builder for a profile whose samples hold one value of each of `sample_types`,
+    /// given as `(type, unit)` pairs, and which were taken every `period` occurrences of
+    /// `period_type`.
+    pub(super) fn new(
+        sample_types: &[(&str, &str)],
+        period_type: (&str, &str),
+        period: i64,
+    ) -> Self {
+        let mut builder = Self {
+            profile: proto::Profile::default(),
+            strings: HashMap::new(),
+            locations: HashMap::new(),
+            functions: HashMap::new(),
+            samples: HashMap::new(),
+        };
+        // The string table always starts with the empty string.
+        builder.string("");
+
+        let sample_type = sample_types
+            .iter()
+            .map(|(kind, unit)| builder.value_type(kind, unit))
+            .collect();
+        builder.profile.sample_type = sample_type;
+        builder.profile.period_type = Some(builder.value_type(period_type.0, period_type.1));
+        builder.profile.period = period;
+        builder.profile.time_nanos = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as i64);
+        builder
+    }
+
+    pub(super) fn duration_nanos(mut self, duration_nanos: i64) -> Self {
+        self.profile.duration_nanos = duration_nanos;
+        self
+    }
+
+    /// Adds a sample for the given stack trace, leaf frame first. Samples of identical stack
+    /// traces are merged by summing their values.
+    pub(super) fn add_sample(&mut self, frames: &[usize], values: &[i64]) {
+        let location_ids = frames
+            .iter()
+            .map(|address| self.location(*address))
+            .collect::<Vec<_>>();
+
+        match self.samples.get(&location_ids) {
+            Some(index) => {
+                let sample = &mut self.profile.sample[*index];
+                for (total, value) in sample.value.iter_mut().zip(values) {
+                    *total += value;
+                }
+            }
+            None => {
+                self.samples
+                    .insert(location_ids.clone(), self.profile.sample.len());
+                self.profile.sample.push(proto::Sample {
+                    location_id: location_ids,
+                    value: values.to_vec(),
+                    label: Vec::new(),
+                });
+            }
+        }
+    }
+
+    /// Encodes the profile, gzip compressed as `go tool pprof` expects it.
+    pub(super) fn encode(self) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(&self.profile.encode_to_vec())
+            .and_then(|_| encoder.finish())
+            .expect("writing to a Vec can't fail")
+    }
+
+    fn string(&mut self, value: &str) -> i64 {
+        if let Some(index) = self.strings.get(value) {
+            return *index;
+        }
+        let index = self.profile.string_table.len() as i64;
+        self.profile.string_table.push(value.to_owned());
+        self.strings.insert(value.to_owned(), index);
+        index
+    }
+
+    fn value_type(&mut self, kind: &str, unit: &str) -> proto::ValueType {
+        proto::ValueType {
+            r#type: self.string(kind),
+            unit: self.string(unit),
+        }
+    }
+
+    fn location(&mut self, address: usize) -> u64 {
+        if let Some(id) = self.locations.get(&address) {
+            return *id;
+        }
+
+        // Return addresses point past the call instruction, so resolve the one before it to
+        // attribute the frame to the line making the call.
+        let mut symbols = Vec::new();
+        backtrace::resolve(address.saturating_sub(1) as *mut c_void, |symbol| {
+            symbols.push((
+                symbol.name().map(|name| format!("{:#}", name)),
+                symbol
+                    .filename()
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_default(),
+                symbol.lineno().unwrap_or(0),
+            ));
+        });
+
+        // Inlined functions are resolved innermost first, which is the order pprof expects.
+        let lines = symbols
+            .into_iter()
+            .map(|(name, filename, line)| {
+                let name = name.unwrap_or_else(|| format!("{:#x}", address));
+                proto::Line {
+                    function_id: self.function(&name, &filename),
+                    line: line.into(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let id = self.profile.location.len() as u64 + 1;
+        self.profile.location.push(proto::Location {
+            id,
+            mapping_id: 0,
+            address: address as u64,
+            line: lines,
+            is_folded: false,
+        });
+        self.locations.insert(address, id);
+        id
+    }
+
+    fn function(&mut self, name: &str, filename: &str) -> u64 {
+        let name = self.string(name);
+        let filename = self.string(filename);
+        if let Some(id) = self.functions.get(&(name, filename)) {
+            return *id;
+        }
+
+        let id = self.profile.function.len() as u64 + 1;
+        self.profile.function.push(proto::Function {
+            id,
+            name,
+            system_name: name,
+            filename,
+            start_line: 0,
+        });
+        self.functions.insert((name, filename), id);
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use flate2::read::GzDecoder;
+
+    use super::*;
+
+    #[test]
+    fn merges_identical_stacks() {
+        let mut frames = Vec::new();
+        backtrace::trace(|frame| {
+            frames.push(frame.ip() as usize);
+            true
+        });
+
+        let mut builder = ProfileBuilder::new(&[("samples", "count")], ("cpu", "nanoseconds"), 10);
+        builder.add_sample(&frames, &[1]);
+        builder.add_sample(&frames, &[2]);
+        builder.add_sample(&frames[1..], &[1]);
+
+        let mut encoded = Vec::new();
+        GzDecoder::new(builder.encode().as_slice())
+            .read_to_end(&mut encoded)
+            .unwrap();
+        let profile = proto::Profile::decode(encoded.as_slice()).unwrap();
+
+        assert_eq!(profile.string_table[0], "");
+        assert_eq!(profile.period, 10);
+        assert_eq!(profile.sample.len(), 2);
+        assert_eq!(profile.sample[0].value, vec![3]);
+        assert_eq!(profile.location.len(), frames.len());
+        assert!(profile
+            .string_table
+            .iter()
+            .any(|name| name.contains("merges_identical_stacks")));
+    }
+}
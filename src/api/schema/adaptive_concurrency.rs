@@ -0,0 +1,75 @@
+use async_graphql::Object;
+
+use crate::{config::ComponentKey, sinks::util::adaptive_concurrency};
+
+pub struct ConcurrencyLimit(adaptive_concurrency::ConcurrencyLimitStatus);
+
+#[Object]
+impl ConcurrencyLimit {
+    /// Sink id
+    async fn component_id(&self) -> String {
+        self.0.component.to_string()
+    }
+
+    /// Partition of the requests the limit applies to, such as an API key or a tenant, for sinks
+    /// limiting the requests of each partition separately
+    async fn partition(&self) -> Option<&str> {
+        self.0.partition.as_deref()
+    }
+
+    /// Maximum number of requests in flight
+    async fn limit(&self) -> i64 {
+        self.0.limit as i64
+    }
+
+    /// Number of requests in flight
+    async fn in_flight(&self) -> i64 {
+        self.0.in_flight as i64
+    }
+
+    /// Whether the limit was overridden with `overrideConcurrencyLimit`
+    async fn overridden(&self) -> bool {
+        self.0.overridden
+    }
+}
+
+#[derive(Default)]
+pub struct AdaptiveConcurrencyQuery;
+
+#[Object]
+impl AdaptiveConcurrencyQuery {
+    /// Request concurrency limits of the running sinks, optionally filtered by sink id
+    async fn concurrency_limits(&self, component_id: Option<String>) -> Vec<ConcurrencyLimit> {
+        adaptive_concurrency::limits()
+            .into_iter()
+            .filter(|status| {
+                component_id
+                    .as_ref()
+                    .map_or(true, |id| status.component.id() == id)
+            })
+            .map(ConcurrencyLimit)
+            .collect()
+    }
+}
+
+#[derive(Default)]
+pub struct AdaptiveConcurrencyMutation;
+
+#[Object]
+impl AdaptiveConcurrencyMutation {
+    /// Overrides the request concurrency limit of a running sink, or of one of its partitions,
+    /// until the sink is reloaded. Omitting the limit lifts the override. Returns the number of
+    /// limits changed
+    async fn override_concurrency_limit(
+        &self,
+        component_id: String,
+        partition: Option<String>,
+        #[graphql(validator(minimum = 1))] limit: Option<i32>,
+    ) -> i64 {
+        adaptive_concurrency::override_limit(
+            &ComponentKey::from(component_id),
+            partition.as_deref(),
+            limit.map(|limit| limit as usize),
+        ) as i64
+    }
+}
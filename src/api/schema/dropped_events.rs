@@ -0,0 +1,71 @@
+use async_graphql::Object;
+use chrono::{DateTime, Utc};
+
+use crate::{config::ComponentKey, dropped_events};
+
+pub struct DroppedEvents {
+    component_id: String,
+    dropped: u64,
+    samples: Vec<dropped_events::DroppedEventSample>,
+}
+
+#[Object]
+impl DroppedEvents {
+    /// Component id
+    async fn component_id(&self) -> &str {
+        &self.component_id
+    }
+
+    /// Number of events the component dropped since sampling was enabled
+    async fn dropped_events_total(&self) -> f64 {
+        self.dropped as f64
+    }
+
+    /// Samples of the events the component dropped
+    async fn samples(&self) -> Vec<DroppedEventSample> {
+        self.samples
+            .iter()
+            .cloned()
+            .map(DroppedEventSample)
+            .collect()
+    }
+}
+
+pub struct DroppedEventSample(dropped_events::DroppedEventSample);
+
+#[Object]
+impl DroppedEventSample {
+    /// Time the event was dropped
+    async fn timestamp(&self) -> DateTime<Utc> {
+        self.0.timestamp
+    }
+
+    /// Reason the event was dropped
+    async fn reason(&self) -> &str {
+        &self.0.reason
+    }
+
+    /// Redacted event, encoded as JSON
+    async fn event(&self) -> String {
+        serde_json::to_string(&self.0.event)
+            .expect("JSON serialization of dropped event failed. Please report.")
+    }
+}
+
+#[derive(Default)]
+pub struct DroppedEventsQuery;
+
+#[Object]
+impl DroppedEventsQuery {
+    /// Samples of the events a component dropped, when enabled with
+    /// `api.dropped_event_samples`
+    async fn dropped_events(&self, component_id: String) -> DroppedEvents {
+        let (dropped, samples) =
+            dropped_events::samples(&ComponentKey::from(component_id.as_str()));
+        DroppedEvents {
+            component_id,
+            dropped,
+            samples,
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use async_graphql::Object;
 
+use crate::config::bundle::BundleManifest;
+
 #[derive(Default)]
 pub struct Meta;
 
@@ -14,6 +16,31 @@ impl Meta {
     async fn hostname(&self) -> Option<String> {
         crate::get_hostname().ok()
     }
+
+    /// Bundle the running configuration was loaded from, if any
+    async fn bundle(&self) -> Option<Bundle> {
+        crate::config::bundle::loaded().map(Bundle)
+    }
+}
+
+pub struct Bundle(BundleManifest);
+
+#[Object]
+impl Bundle {
+    /// Bundle name
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    /// Bundle version
+    async fn version(&self) -> &str {
+        &self.0.version
+    }
+
+    /// Bundle description
+    async fn description(&self) -> Option<&str> {
+        self.0.description.as_deref()
+    }
 }
 
 #[derive(Default)]
@@ -1,4 +1,6 @@
+mod adaptive_concurrency;
 pub mod components;
+mod dropped_events;
 pub mod events;
 pub mod filter;
 mod health;
@@ -7,7 +9,7 @@ mod metrics;
 mod relay;
 pub mod sort;
 
-use async_graphql::{EmptyMutation, MergedObject, MergedSubscription, Schema, SchemaBuilder};
+use async_graphql::{MergedObject, MergedSubscription, Schema, SchemaBuilder};
 
 #[derive(MergedObject, Default)]
 pub struct Query(
@@ -15,8 +17,13 @@ pub struct Query(
     components::ComponentsQuery,
     metrics::MetricsQuery,
     meta::MetaQuery,
+    dropped_events::DroppedEventsQuery,
+    adaptive_concurrency::AdaptiveConcurrencyQuery,
 );
 
+#[derive(MergedObject, Default)]
+pub struct Mutation(adaptive_concurrency::AdaptiveConcurrencyMutation);
+
 #[derive(MergedSubscription, Default)]
 pub struct Subscription(
     health::HealthSubscription,
@@ -26,6 +33,6 @@ pub struct Subscription(
 );
 
 /// Build a new GraphQL schema, comprised of Query, Mutation and Subscription types
-pub fn build_schema() -> SchemaBuilder<Query, EmptyMutation, Subscription> {
-    Schema::build(Query::default(), EmptyMutation, Subscription::default())
+pub fn build_schema() -> SchemaBuilder<Query, Mutation, Subscription> {
+    Schema::build(Query::default(), Mutation::default(), Subscription::default())
 }
@@ -13,7 +13,7 @@ use tokio::sync::oneshot;
 use warp::{filters::BoxedFilter, http::Response, ws::Ws, Filter, Reply};
 
 use super::{handler, schema, ShutdownTx};
-use crate::{config, topology};
+use crate::{config, dropped_events, topology};
 
 pub struct Server {
     _shutdown: ShutdownTx,
@@ -28,7 +28,22 @@ impl Server {
         watch_rx: topology::WatchRx,
         running: Arc<AtomicBool>,
     ) -> Self {
-        let routes = make_routes(config.api.playground, watch_rx, running);
+        if config.api.profiling {
+            #[cfg(all(unix, feature = "api-profiling"))]
+            super::profiling::enable_allocation_sampling();
+            #[cfg(not(all(unix, feature = "api-profiling")))]
+            warn!(
+                message =
+                    "Profiling is enabled in the API, but isn't supported by this build of Vector."
+            );
+        }
+
+        let routes = make_routes(
+            config.api.playground,
+            config.api.profiling,
+            watch_rx,
+            running,
+        );
 
         let (_shutdown, rx) = oneshot::channel();
         let (addr, server) = warp::serve(routes).bind_with_graceful_shutdown(
@@ -40,6 +55,7 @@ impl Server {
 
         // Update component schema with the config before starting the server.
         schema::components::update_config(config);
+        configure_dropped_event_samples(config);
 
         // Spawn the server in the background.
         tokio::spawn(server);
@@ -56,12 +72,22 @@ impl Server {
     /// directly involve `self`, it provides a neater API to expose an internal implementation
     /// detail than exposing the function of the sub-mod directly.
     pub fn update_config(&self, config: &config::Config) {
-        schema::components::update_config(config)
+        schema::components::update_config(config);
+        configure_dropped_event_samples(config);
     }
 }
 
+fn configure_dropped_event_samples(config: &config::Config) {
+    // The redaction program was validated along with the config.
+    if let Err(error) = dropped_events::configure(&config.api.dropped_event_samples) {
+        error!(message = "Failed to configure the dropped event samples.", %error);
+    }
+}
+
+#[cfg_attr(not(all(unix, feature = "api-profiling")), allow(unused_variables))]
 fn make_routes(
     playground: bool,
+    profiling: bool,
     watch_tx: topology::WatchRx,
     running: Arc<AtomicBool>,
 ) -> BoxedFilter<(impl Reply,)> {
@@ -128,11 +154,25 @@ fn make_routes(
         not_found.boxed()
     };
 
-    // Wire up the health + GraphQL endpoints. Provides a permissive CORS policy to allow for
-    // cross-origin interaction with the Vector API.
-    health
-        .or(graphql_handler)
-        .or(graphql_playground)
+    // Wire up the health + GraphQL endpoints.
+    let routes = health.or(graphql_handler).or(graphql_playground);
+
+    // CPU and allocation profiles, in the pprof format.
+    #[cfg(all(unix, feature = "api-profiling"))]
+    let routes = {
+        let enabled = warp::any().map(move || profiling);
+        let cpu_profile = warp::path!("debug" / "pprof" / "profile")
+            .and(enabled)
+            .and(warp::query())
+            .and_then(handler::cpu_profile);
+        let heap_profile = warp::path!("debug" / "pprof" / "heap")
+            .and(enabled)
+            .and_then(handler::heap_profile);
+        routes.or(cpu_profile).or(heap_profile)
+    };
+
+    // Provides a permissive CORS policy to allow for cross-origin interaction with the Vector API.
+    routes
         .or(not_found)
         .with(
             warp::cors()
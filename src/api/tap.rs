@@ -625,6 +625,8 @@ mod tests {
                     namespace: None,
                     tags: None,
                 })],
+                all_metrics: false,
+                host_tag: None,
             },
         );
         config.add_sink(
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, num::NonZeroUsize, path::PathBuf};
+use std::{collections::HashMap, fs::OpenOptions, num::NonZeroUsize, path::PathBuf};
 
 use futures::StreamExt;
 #[cfg(feature = "enterprise")]
@@ -47,6 +47,8 @@ pub struct ApplicationConfig {
     pub api: config::api::Options,
     #[cfg(feature = "enterprise")]
     pub enterprise: Option<EnterpriseReporter<BoxFuture<'static, ()>>>,
+    /// The bundle running, kept for its files to stay around.
+    pub bundle: Option<config::bundle::Bundle>,
     pub signal_handler: signal::SignalHandler,
     pub signal_rx: signal::SignalRx,
 }
@@ -109,6 +111,20 @@ impl Application {
             LogFormat::Json => true,
         };
 
+        let internal_log_file = match &root_opts.internal_log_file {
+            Some(path) => match OpenOptions::new().create(true).append(true).open(path) {
+                Ok(file) => Some(file),
+                Err(error) => {
+                    #[allow(clippy::print_stderr)]
+                    {
+                        eprintln!("Unable to open internal log file {:?}: {}", path, error);
+                    }
+                    return Err(exitcode::CANTCREAT);
+                }
+            },
+            None => None,
+        };
+
         #[cfg(not(feature = "enterprise-tests"))]
         metrics::init_global().expect("metrics initialization failed");
 
@@ -130,12 +146,12 @@ impl Application {
         let rt = rt_builder.build().expect("Unable to create async runtime");
 
         let config = {
-            let config_paths = root_opts.config_paths_with_formats();
+            let opts = &root_opts;
             let watch_config = root_opts.watch_config;
             let require_healthy = root_opts.require_healthy;
 
             rt.block_on(async move {
-                trace::init(color, json, &level);
+                trace::init(color, json, &level, internal_log_file);
                 // Signal handler for OS and provider messages.
                 let (mut signal_handler, signal_rx) = signal::SignalHandler::new();
                 signal_handler.forever(signal::os_signals());
@@ -164,15 +180,26 @@ impl Application {
 
                 info!(message = "Log level is enabled.", level = ?level);
 
+                let (config_paths, bundle) = opts
+                    .config_paths_with_bundle()
+                    .map_err(handle_config_errors)?;
                 let config_paths = config::process_paths(&config_paths).ok_or(exitcode::CONFIG)?;
 
                 if watch_config {
+                    // The bundle is watched rather than the directory it's unpacked to, which is
+                    // rewritten on each reload.
+                    let bundle_config_path = bundle.as_ref().map(|bundle| bundle.config_path());
+                    let watched_paths = config_paths
+                        .iter()
+                        .filter(|path| Some(*path) != bundle_config_path.as_ref())
+                        .map(Into::into)
+                        .chain(opts.bundle.as_ref());
+
                     // Start listening for config changes immediately.
-                    config::watcher::spawn_thread(config_paths.iter().map(Into::into), None)
-                        .map_err(|error| {
-                            error!(message = "Unable to start config watcher.", %error);
-                            exitcode::CONFIG
-                        })?;
+                    config::watcher::spawn_thread(watched_paths, None).map_err(|error| {
+                        error!(message = "Unable to start config watcher.", %error);
+                        exitcode::CONFIG
+                    })?;
                 }
 
                 info!(
@@ -219,11 +246,20 @@ impl Application {
                     .ok_or(exitcode::CONFIG)?;
 
                 #[cfg(feature = "api")]
-                let api = config.api;
+                let api = config.api.clone();
 
                 let result = topology::start_validated(config, diff, pieces).await;
                 let (topology, graceful_crash) = result.ok_or(exitcode::CONFIG)?;
 
+                if let Some(bundle) = &bundle {
+                    info!(
+                        message = "Loaded bundle.",
+                        name = %bundle.manifest.name,
+                        version = %bundle.manifest.version,
+                    );
+                    config::bundle::set_loaded(Some(bundle.manifest.clone()));
+                }
+
                 Ok(ApplicationConfig {
                     config_paths,
                     topology,
@@ -232,6 +268,7 @@ impl Application {
                     api,
                     #[cfg(feature = "enterprise")]
                     enterprise,
+                    bundle,
                     signal_handler,
                     signal_rx,
                 })
@@ -261,6 +298,9 @@ impl Application {
         #[cfg(feature = "enterprise")]
         let mut enterprise = self.config.enterprise;
 
+        // The running bundle is only held for its files to stay around.
+        let mut _bundle = self.config.bundle;
+
         let mut signal_handler = self.config.signal_handler;
         let mut signal_rx = self.config.signal_rx;
 
@@ -344,8 +384,19 @@ impl Application {
                                 }
                             }
                             SignalTo::ReloadFromDisk => {
-                                // Reload paths
-                                config_paths = config::process_paths(&opts.config_paths_with_formats()).unwrap_or(config_paths);
+                                // Reload paths, unpacking the bundle again in case it was updated. The
+                                // running bundle is only dropped once the new one is running.
+                                let new_bundle = match opts.config_paths_with_bundle() {
+                                    Ok((paths, bundle)) => {
+                                        config_paths = config::process_paths(&paths).unwrap_or(config_paths);
+                                        bundle
+                                    }
+                                    Err(errors) => {
+                                        handle_config_errors(errors);
+                                        emit!(VectorConfigLoadError);
+                                        continue;
+                                    }
+                                };
 
                                 // Reload config
                                 let new_config = config::load_from_paths_with_provider_and_secrets(&config_paths, &mut signal_handler)
@@ -381,6 +432,8 @@ impl Application {
                                                 api_server.update_config(topology.config());
                                             }
 
+                                            config::bundle::set_loaded(new_bundle.as_ref().map(|bundle| bundle.manifest.clone()));
+                                            _bundle = new_bundle;
                                             emit!(VectorReloaded { config_paths: &config_paths })
                                         },
                                         Ok(false) => emit!(VectorReloadError),
@@ -116,6 +116,27 @@ pub struct RootOpts {
     )]
     pub config_paths_yaml: Vec<PathBuf>,
 
+    /// Load a pipeline bundle: a gzipped tarball packaging configuration files along with the
+    /// VRL programs and enrichment tables they use, described by a versioned manifest.
+    /// The bundle is verified and unpacked on startup and on each reload, and its configuration
+    /// is loaded along with the other configuration files.
+    #[clap(long, env = "VECTOR_BUNDLE")]
+    pub bundle: Option<PathBuf>,
+
+    /// Require the bundle to be signed with the private key matching the given Ed25519 public
+    /// key, stored in the file encoded in base64.
+    #[clap(long, env = "VECTOR_BUNDLE_PUBLIC_KEY", requires = "bundle")]
+    pub bundle_public_key: Option<PathBuf>,
+
+    /// Load the bundle without verifying its signature, when no public key is given.
+    #[clap(
+        long,
+        env = "VECTOR_BUNDLE_ALLOW_UNSIGNED",
+        requires = "bundle",
+        conflicts_with = "bundle-public-key"
+    )]
+    pub bundle_allow_unsigned: bool,
+
     /// Exit on startup if any sinks fail healthchecks
     #[clap(short, long, env = "VECTOR_REQUIRE_HEALTHY")]
     pub require_healthy: Option<bool>,
@@ -136,6 +157,14 @@ pub struct RootOpts {
     #[clap(long, default_value = "text", env = "VECTOR_LOG_FORMAT", possible_values = &["text", "json"])]
     pub log_format: LogFormat,
 
+    /// Additionally write internal logs, formatted as JSON, to the given file.
+    ///
+    /// Each line carries the fields of the component that emitted it (`component_kind`,
+    /// `component_id`, `component_type`) in its `spans` list, and the file is written separately from the console
+    /// output, so it can be safely ingested by Vector itself (e.g. with the `file` source).
+    #[clap(long, env = "VECTOR_INTERNAL_LOG_FILE")]
+    pub internal_log_file: Option<PathBuf>,
+
     /// Control when ANSI terminal formatting is used.
     ///
     /// By default `vector` will try and detect if `stdout` is a terminal, if it is
@@ -168,6 +197,32 @@ impl RootOpts {
         )
         .collect()
     }
+
+    /// Return the list of config paths including the configuration of the bundle, if any,
+    /// along with the bundle once verified and unpacked.
+    pub fn config_paths_with_bundle(
+        &self,
+    ) -> Result<(Vec<config::ConfigPath>, Option<config::bundle::Bundle>), Vec<String>> {
+        let mut config_paths = self.config_paths_with_formats();
+        let bundle = self
+            .bundle
+            .as_deref()
+            .map(|path| {
+                config::bundle::load(
+                    path,
+                    self.bundle_public_key.as_deref(),
+                    self.bundle_allow_unsigned,
+                )
+            })
+            .transpose()
+            .map_err(|error| vec![error.to_string()])?;
+
+        if let Some(bundle) = &bundle {
+            config_paths.push(bundle.config_path());
+        }
+
+        Ok((config_paths, bundle))
+    }
 }
 
 #[derive(Parser, Debug)]
@@ -0,0 +1,35 @@
+//! The component whose task is running.
+//!
+//! The topology runs the tasks of each component within its scope, with [`scope`], so that what
+//! they do, such as dropping events or building adaptive concurrency controllers, can be
+//! attributed to the component.
+
+use std::future::Future;
+
+use crate::config::ComponentKey;
+
+tokio::task_local! {
+    static COMPONENT: ComponentKey;
+}
+
+/// Runs `future` as a task of the component.
+pub async fn scope<F: Future>(component: ComponentKey, future: F) -> F::Output {
+    COMPONENT.scope(component, future).await
+}
+
+/// Returns the component whose task is running, if any.
+pub fn current() -> Option<ComponentKey> {
+    COMPONENT.try_with(Clone::clone).ok()
+}
+
+/// Returns the future running within the component of the current task, for tasks spawned by
+/// components.
+pub fn in_current_scope<F: Future>(future: F) -> impl Future<Output = F::Output> {
+    let component = current();
+    async move {
+        match component {
+            Some(component) => scope(component, future).await,
+            None => future.await,
+        }
+    }
+}
@@ -2,7 +2,9 @@ use std::net::{Ipv4Addr, SocketAddr};
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, Copy, Clone)]
+use crate::dropped_events::DroppedEventSamplesOptions;
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
 #[serde(default, deny_unknown_fields)]
 pub struct Options {
     #[serde(default = "default_enabled")]
@@ -13,6 +15,15 @@ pub struct Options {
 
     #[serde(default = "default_playground")]
     pub playground: bool,
+
+    /// Serve CPU and allocation profiles of the process under `/debug/pprof`. Only takes effect
+    /// when built with the `api-profiling` feature.
+    #[serde(default)]
+    pub profiling: bool,
+
+    /// Sample the events components drop, to inspect them through the API.
+    #[serde(default)]
+    pub dropped_event_samples: DroppedEventSamplesOptions,
 }
 
 impl Default for Options {
@@ -21,6 +32,8 @@ impl Default for Options {
             enabled: default_enabled(),
             playground: default_playground(),
             address: default_address(),
+            profiling: false,
+            dropped_event_samples: DroppedEventSamplesOptions::default(),
         }
     }
 }
@@ -61,10 +74,23 @@ impl Options {
             }
         };
 
+        // Prefer non default dropped event samples
+        let default_samples = DroppedEventSamplesOptions::default();
+        let dropped_event_samples = match (
+            self.dropped_event_samples.clone(),
+            other.dropped_event_samples,
+        ) {
+            (a, b) if a == b || b == default_samples => a,
+            (a, b) if a == default_samples => b,
+            _ => return Err("Conflicting `api` dropped event samples.".to_owned()),
+        };
+
         let options = Options {
             address,
             enabled: self.enabled | other.enabled,
             playground: self.playground & other.playground,
+            profiling: self.profiling | other.profiling,
+            dropped_event_samples,
         };
 
         *self = options;
@@ -78,6 +104,8 @@ fn bool_merge() {
         enabled: true,
         address: None,
         playground: false,
+        profiling: false,
+        dropped_event_samples: DroppedEventSamplesOptions::default(),
     };
 
     a.merge(Options::default()).unwrap();
@@ -88,6 +116,8 @@ fn bool_merge() {
             enabled: true,
             address: default_address(),
             playground: false,
+            profiling: false,
+            dropped_event_samples: DroppedEventSamplesOptions::default(),
         }
     );
 }
@@ -99,6 +129,8 @@ fn bind_merge() {
         enabled: true,
         address: Some(address),
         playground: true,
+        profiling: true,
+        dropped_event_samples: DroppedEventSamplesOptions::default(),
     };
 
     a.merge(Options::default()).unwrap();
@@ -109,6 +141,8 @@ fn bind_merge() {
             enabled: true,
             address: Some(address),
             playground: true,
+            profiling: true,
+            dropped_event_samples: DroppedEventSamplesOptions::default(),
         }
     );
 }
@@ -127,3 +161,27 @@ fn bind_conflict() {
 
     assert!(a.merge(b).is_err());
 }
+
+#[test]
+fn dropped_event_samples_merge() {
+    let samples = DroppedEventSamplesOptions {
+        enabled: true,
+        ..DroppedEventSamplesOptions::default()
+    };
+    let mut a = Options::default();
+    a.merge(Options {
+        dropped_event_samples: samples.clone(),
+        ..Options::default()
+    })
+    .unwrap();
+    assert_eq!(a.dropped_event_samples, samples);
+
+    let b = Options {
+        dropped_event_samples: DroppedEventSamplesOptions {
+            size: 100,
+            ..samples
+        },
+        ..Options::default()
+    };
+    assert!(a.merge(b).is_err());
+}
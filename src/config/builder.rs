@@ -187,6 +187,7 @@ impl ConfigBuilder {
         let transform = TransformOuter {
             inner: Box::new(transform),
             inputs,
+            sandbox: Default::default(),
         };
 
         self.transforms
@@ -244,6 +245,24 @@ impl ConfigBuilder {
             errors.extend(merge_errors);
         }
 
+        self.global.fuse_transforms |= with.global.fuse_transforms;
+
+        if self.global.egress_bytes_per_sec.is_none() {
+            self.global.egress_bytes_per_sec = with.global.egress_bytes_per_sec;
+        } else if with.global.egress_bytes_per_sec.is_some()
+            && self.global.egress_bytes_per_sec != with.global.egress_bytes_per_sec
+        {
+            errors.push("conflicting values for 'egress_bytes_per_sec' found".to_owned());
+        }
+
+        if self.global.disk_buffer_max_size.is_none() {
+            self.global.disk_buffer_max_size = with.global.disk_buffer_max_size;
+        } else if with.global.disk_buffer_max_size.is_some()
+            && self.global.disk_buffer_max_size != with.global.disk_buffer_max_size
+        {
+            errors.push("conflicting values for 'disk_buffer_max_size' found".to_owned());
+        }
+
         self.healthchecks.merge(with.healthchecks);
 
         with.enrichment_tables.keys().for_each(|k| {
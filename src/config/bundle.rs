@@ -0,0 +1,174 @@
+//! Pipeline bundles.
+//!
+//! A bundle packages the configuration of a pipeline along with the files it depends on, such as
+//! VRL programs and enrichment tables, as a single versioned artifact that can be distributed to a
+//! fleet of Vector instances. Bundles are gzipped tarballs laid out as follows:
+//!
+//! - `manifest.toml`: the name and version of the bundle, along with the SHA-256 checksum of each
+//!   of its other files.
+//! - `manifest.toml.sig`: the Ed25519 signature of the manifest, encoded in base64. As the
+//!   manifest lists the checksums of all the files, this signature covers the whole bundle.
+//! - `config/`: the configuration of the pipeline, loaded as a config directory.
+//! - any other file used by the configuration, e.g. under `vrl/` or `enrichment/`, which the
+//!   configuration refers to with the `VECTOR_BUNDLE_DIR` variable, set to the directory the
+//!   bundle is unpacked to.
+//!
+//! Unpacking bundles requires the `bundles` feature.
+
+use std::{
+    collections::BTreeMap,
+    io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+
+use super::ConfigPath;
+
+#[cfg(feature = "bundles")]
+mod archive;
+
+#[cfg(feature = "bundles")]
+pub use archive::unpack;
+
+/// The variable the configuration refers to the directory the bundle is unpacked to with.
+pub const BUNDLE_DIR_VAR: &str = "VECTOR_BUNDLE_DIR";
+
+const CONFIG_DIR: &str = "config";
+
+/// The manifest of the bundle currently running, as reported by the API.
+static LOADED_BUNDLE: Lazy<Mutex<Option<BundleManifest>>> = Lazy::new(Mutex::default);
+
+/// The directory the last bundle loaded was unpacked to, which `VECTOR_BUNDLE_DIR` is interpolated
+/// with in the configuration.
+static BUNDLE_DIR: Lazy<Mutex<Option<PathBuf>>> = Lazy::new(Mutex::default);
+
+#[derive(Debug, Snafu)]
+pub enum BundleError {
+    #[snafu(display("Could not read bundle {:?}: {}", path, source))]
+    Read { path: PathBuf, source: io::Error },
+    #[snafu(display("Could not read bundle public key {:?}: {}", path, source))]
+    ReadPublicKey { path: PathBuf, source: io::Error },
+    #[snafu(display("Invalid bundle public key: {}", source))]
+    InvalidPublicKey { source: openssl::error::ErrorStack },
+    #[snafu(display(
+        "No public key to verify the bundle with, set `--bundle-public-key` or allow unsigned bundles with `--bundle-allow-unsigned`"
+    ))]
+    MissingPublicKey,
+    #[snafu(display(
+        "Unsupported entry {:?} in bundle, only files and directories are allowed",
+        path
+    ))]
+    UnsupportedEntry { path: PathBuf },
+    #[snafu(display(
+        "Invalid path {:?} in bundle, paths must be relative and within the bundle",
+        path
+    ))]
+    InvalidPath { path: PathBuf },
+    #[snafu(display("The bundle has no manifest"))]
+    MissingManifest,
+    #[snafu(display("Invalid bundle manifest: {}", source))]
+    InvalidManifest { source: toml::de::Error },
+    #[snafu(display(
+        "Invalid bundle {}: {:?}, it must be a single path segment",
+        field,
+        value
+    ))]
+    InvalidName { field: &'static str, value: String },
+    #[snafu(display("The bundle is not signed"))]
+    MissingSignature,
+    #[snafu(display("The signature of the bundle does not match its public key"))]
+    InvalidSignature,
+    #[snafu(display("File {:?} of the bundle is not listed in its manifest", path))]
+    UnlistedFile { path: String },
+    #[snafu(display("File {:?} listed in the bundle manifest is missing", path))]
+    MissingFile { path: String },
+    #[snafu(display("Checksum mismatch for file {:?} of the bundle", path))]
+    ChecksumMismatch { path: String },
+    #[snafu(display("Could not unpack bundle to {:?}: {}", path, source))]
+    Unpack { path: PathBuf, source: io::Error },
+    #[snafu(display("Vector was built without support for bundles"))]
+    Unsupported,
+}
+
+/// The manifest of a bundle, describing its content.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct BundleManifest {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// The SHA-256 checksum of each file of the bundle, hex encoded, by path.
+    #[serde(default)]
+    pub files: BTreeMap<String, String>,
+}
+
+/// A bundle, verified and unpacked.
+///
+/// The temporary directory the bundle was unpacked to by [`load`] is removed once the bundle and
+/// its clones are dropped, so the bundle must be kept for as long as its configuration runs.
+#[derive(Clone, Debug)]
+pub struct Bundle {
+    pub manifest: BundleManifest,
+    pub dir: PathBuf,
+    #[cfg(feature = "bundles")]
+    temp_dir: Option<std::sync::Arc<tempfile::TempDir>>,
+}
+
+impl Bundle {
+    /// Returns the path of the configuration of the bundle.
+    pub fn config_path(&self) -> ConfigPath {
+        ConfigPath::Dir(self.dir.join(CONFIG_DIR))
+    }
+}
+
+/// Verifies the bundle at `path` and unpacks it into a new temporary directory, which only Vector
+/// can access and which the configuration refers to with the `VECTOR_BUNDLE_DIR` variable.
+///
+/// The bundle must be signed with the private key matching the public key, unless unsigned bundles
+/// are explicitly allowed.
+#[cfg(feature = "bundles")]
+pub fn load(
+    path: &Path,
+    public_key: Option<&Path>,
+    allow_unsigned: bool,
+) -> Result<Bundle, BundleError> {
+    let bundle = archive::load(path, public_key, allow_unsigned)?;
+    *BUNDLE_DIR.lock().expect("bundle dir lock poisoned") = Some(bundle.dir.clone());
+    Ok(bundle)
+}
+
+#[cfg(not(feature = "bundles"))]
+pub fn load(
+    _path: &Path,
+    _public_key: Option<&Path>,
+    _allow_unsigned: bool,
+) -> Result<Bundle, BundleError> {
+    Err(BundleError::Unsupported)
+}
+
+/// Returns the directory the last bundle loaded was unpacked to, if any.
+pub fn dir() -> Option<PathBuf> {
+    BUNDLE_DIR
+        .lock()
+        .map(|guard| guard.clone())
+        .unwrap_or_default()
+}
+
+/// Records the manifest of the bundle currently running.
+pub fn set_loaded(manifest: Option<BundleManifest>) {
+    // Ignore poison error and let the current main thread continue running to do the cleanup.
+    std::mem::drop(LOADED_BUNDLE.lock().map(|mut guard| *guard = manifest));
+}
+
+/// Returns the manifest of the bundle currently running, if any.
+pub fn loaded() -> Option<BundleManifest> {
+    LOADED_BUNDLE
+        .lock()
+        .map(|guard| guard.clone())
+        .unwrap_or_default()
+}
@@ -0,0 +1,451 @@
+//! Verifying and unpacking bundles.
+
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, Read, Write},
+    path::{Component, Path},
+    sync::Arc,
+};
+
+use flate2::read::GzDecoder;
+use openssl::{
+    pkey::{Id, PKey},
+    sign::Verifier,
+};
+use snafu::{OptionExt, ResultExt};
+use tar::{Archive, EntryType};
+
+use super::*;
+
+const MANIFEST: &str = "manifest.toml";
+const SIGNATURE: &str = "manifest.toml.sig";
+
+pub(super) fn load(
+    path: &Path,
+    public_key: Option<&Path>,
+    allow_unsigned: bool,
+) -> Result<Bundle, BundleError> {
+    let public_key = public_key.map(read_public_key).transpose()?;
+    if public_key.is_none() && !allow_unsigned {
+        return Err(BundleError::MissingPublicKey);
+    }
+
+    // Each bundle is unpacked to a new directory, which is only removed once the bundle is
+    // dropped, so that the files of the running configuration are left untouched until the
+    // configuration of a new bundle replaces it.
+    let temp_dir = tempfile::Builder::new()
+        .prefix("vector-bundle-")
+        .tempdir()
+        .context(UnpackSnafu {
+            path: std::env::temp_dir(),
+        })?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(temp_dir.path(), fs::Permissions::from_mode(0o700)).context(
+            UnpackSnafu {
+                path: temp_dir.path(),
+            },
+        )?;
+    }
+
+    let mut bundle = unpack(path, temp_dir.path(), public_key.as_deref())?;
+    bundle.temp_dir = Some(Arc::new(temp_dir));
+    if public_key.is_none() {
+        warn!(
+            message = "Bundle signature not verified, unsigned bundles are allowed.",
+            bundle = %bundle.manifest.name,
+        );
+    }
+
+    Ok(bundle)
+}
+
+/// Verifies the bundle at `path` and unpacks it into its own directory under `target`, which must
+/// not contain another version of the bundle.
+pub fn unpack(
+    path: &Path,
+    target: &Path,
+    public_key: Option<&[u8]>,
+) -> Result<Bundle, BundleError> {
+    let mut files = read_archive(path)?;
+
+    let manifest = files.remove(MANIFEST).context(MissingManifestSnafu)?;
+    let signature = files.remove(SIGNATURE);
+    if let Some(public_key) = public_key {
+        let signature = signature.context(MissingSignatureSnafu)?;
+        verify_signature(public_key, &manifest, &signature)?;
+    }
+
+    let manifest: BundleManifest = toml::from_slice(&manifest).context(InvalidManifestSnafu)?;
+    check_path_segment("name", &manifest.name)?;
+    check_path_segment("version", &manifest.version)?;
+
+    for (path, contents) in &files {
+        let checksum = manifest
+            .files
+            .get(path)
+            .context(UnlistedFileSnafu { path })?;
+        if !checksum.eq_ignore_ascii_case(&sha256_hex(contents)) {
+            return Err(BundleError::ChecksumMismatch { path: path.clone() });
+        }
+    }
+    if let Some(path) = manifest
+        .files
+        .keys()
+        .find(|path| !files.contains_key(*path))
+    {
+        return Err(BundleError::MissingFile { path: path.clone() });
+    }
+
+    let dir = target.join(&manifest.name).join(&manifest.version);
+    write_files(&dir, files).context(UnpackSnafu { path: &dir })?;
+
+    Ok(Bundle {
+        manifest,
+        dir,
+        temp_dir: None,
+    })
+}
+
+/// Reads the public key at `path`, a raw Ed25519 public key encoded in base64.
+fn read_public_key(path: &Path) -> Result<Vec<u8>, BundleError> {
+    let encoded = fs::read_to_string(path).context(ReadPublicKeySnafu { path })?;
+    openssl::base64::decode_block(encoded.trim()).context(InvalidPublicKeySnafu)
+}
+
+/// Reads all the files of the archive at `path`, by their normalized path.
+fn read_archive(path: &Path) -> Result<HashMap<String, Vec<u8>>, BundleError> {
+    let read_error = |source| BundleError::Read {
+        path: path.to_owned(),
+        source,
+    };
+
+    let file = fs::File::open(path).map_err(read_error)?;
+    let mut archive = Archive::new(GzDecoder::new(file));
+    let mut files = HashMap::new();
+
+    for entry in archive.entries().map_err(read_error)? {
+        let mut entry = entry.map_err(read_error)?;
+        let entry_path = entry.path().map_err(read_error)?.into_owned();
+        match entry.header().entry_type() {
+            EntryType::Directory => continue,
+            EntryType::Regular => {}
+            _ => return Err(BundleError::UnsupportedEntry { path: entry_path }),
+        }
+
+        let normalized = normalize_path(&entry_path)?;
+        let mut contents = Vec::new();
+        entry.read_to_end(&mut contents).map_err(read_error)?;
+        files.insert(normalized, contents);
+    }
+
+    Ok(files)
+}
+
+/// Normalizes the path of an entry of the archive, refusing any path that could point outside of
+/// the directory the bundle is unpacked to.
+fn normalize_path(path: &Path) -> Result<String, BundleError> {
+    let mut segments = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(segment) => match segment.to_str() {
+                Some(segment) => segments.push(segment),
+                None => return Err(BundleError::InvalidPath { path: path.into() }),
+            },
+            Component::CurDir => {}
+            _ => return Err(BundleError::InvalidPath { path: path.into() }),
+        }
+    }
+
+    if segments.is_empty() {
+        Err(BundleError::InvalidPath { path: path.into() })
+    } else {
+        Ok(segments.join("/"))
+    }
+}
+
+fn check_path_segment(field: &'static str, value: &str) -> Result<(), BundleError> {
+    let mut components = Path::new(value).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) => Ok(()),
+        _ => Err(BundleError::InvalidName {
+            field,
+            value: value.to_owned(),
+        }),
+    }
+}
+
+fn verify_signature(
+    public_key: &[u8],
+    manifest: &[u8],
+    signature: &[u8],
+) -> Result<(), BundleError> {
+    let public_key =
+        PKey::public_key_from_raw_bytes(public_key, Id::ED25519).context(InvalidPublicKeySnafu)?;
+    let signature = std::str::from_utf8(signature)
+        .ok()
+        .and_then(|signature| openssl::base64::decode_block(signature.trim()).ok())
+        .context(InvalidSignatureSnafu)?;
+
+    let valid = Verifier::new_without_digest(&public_key)
+        .and_then(|mut verifier| verifier.verify_oneshot(&signature, manifest))
+        .unwrap_or(false);
+    if valid {
+        Ok(())
+    } else {
+        Err(BundleError::InvalidSignature)
+    }
+}
+
+fn sha256_hex(contents: &[u8]) -> String {
+    openssl::sha::sha256(contents)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn write_files(dir: &Path, files: HashMap<String, Vec<u8>>) -> io::Result<()> {
+    // Files are never written over, nor through links, left in the directory.
+    fs::create_dir_all(dir)?;
+    if fs::read_dir(dir)?.next().is_some() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "the directory is not empty",
+        ));
+    }
+
+    for (path, contents) in files {
+        let path = dir.join(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)?
+            .write_all(&contents)?;
+    }
+
+    // Bundles without any configuration are still loaded as such.
+    fs::create_dir_all(dir.join(CONFIG_DIR))
+}
+
+#[cfg(test)]
+mod tests {
+    use flate2::{write::GzEncoder, Compression};
+    use openssl::{pkey::Private, sign::Signer};
+
+    use super::*;
+
+    const CONFIG: &str = r#"
+        [transforms.parse]
+        type = "remap"
+        inputs = ["in"]
+        file = "${VECTOR_BUNDLE_DIR}/vrl/parse.vrl"
+    "#;
+
+    const PROGRAM: &str = ". = parse_json!(.message)";
+
+    fn manifest(files: &[(&str, &str)]) -> String {
+        let mut manifest = "name = \"web\"\nversion = \"1.2.0\"\n[files]\n".to_owned();
+        for (path, contents) in files {
+            manifest.push_str(&format!(
+                "\"{}\" = \"{}\"\n",
+                path,
+                sha256_hex(contents.as_bytes())
+            ));
+        }
+        manifest
+    }
+
+    fn sign(key: &PKey<Private>, manifest: &str) -> String {
+        let signature = Signer::new_without_digest(key)
+            .unwrap()
+            .sign_oneshot_to_vec(manifest.as_bytes())
+            .unwrap();
+        openssl::base64::encode_block(&signature)
+    }
+
+    fn write_archive(path: &Path, files: &[(&str, &str)]) {
+        let mut builder = tar::Builder::new(GzEncoder::new(
+            fs::File::create(path).unwrap(),
+            Compression::default(),
+        ));
+        for (path, contents) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, path, contents.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    fn bundle_files() -> Vec<(&'static str, &'static str)> {
+        vec![("config/vector.toml", CONFIG), ("vrl/parse.vrl", PROGRAM)]
+    }
+
+    #[test]
+    fn unpacks_signed_bundle() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = PKey::generate_ed25519().unwrap();
+        let files = bundle_files();
+        let manifest = manifest(&files);
+        let signature = sign(&key, &manifest);
+
+        let archive = dir.path().join("bundle.tar.gz");
+        let mut entries = files.clone();
+        entries.push((MANIFEST, &manifest));
+        entries.push((SIGNATURE, &signature));
+        write_archive(&archive, &entries);
+
+        let public_key = key.raw_public_key().unwrap();
+        let target = dir.path().join("unpacked");
+        let bundle = unpack(&archive, &target, Some(&public_key)).unwrap();
+
+        assert_eq!(bundle.manifest.name, "web");
+        assert_eq!(bundle.manifest.version, "1.2.0");
+        assert_eq!(bundle.dir, target.join("web").join("1.2.0"));
+        assert_eq!(
+            bundle.config_path(),
+            ConfigPath::Dir(bundle.dir.join("config"))
+        );
+        assert_eq!(
+            fs::read_to_string(bundle.dir.join("vrl/parse.vrl")).unwrap(),
+            PROGRAM
+        );
+    }
+
+    #[test]
+    fn loads_bundle_into_private_temporary_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = bundle_files();
+        let manifest = manifest(&files);
+
+        let archive = dir.path().join("bundle.tar.gz");
+        let mut entries = files.clone();
+        entries.push((MANIFEST, &manifest));
+        write_archive(&archive, &entries);
+
+        let error = load(&archive, None, false).unwrap_err();
+        assert!(matches!(error, BundleError::MissingPublicKey));
+
+        let bundle = load(&archive, None, true).unwrap();
+        let other = load(&archive, None, true).unwrap();
+        assert_ne!(bundle.dir, other.dir);
+        assert_eq!(
+            fs::read_to_string(bundle.dir.join("vrl/parse.vrl")).unwrap(),
+            PROGRAM
+        );
+
+        let temp_dir = bundle.temp_dir.as_ref().unwrap().path().to_owned();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&temp_dir).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o700);
+        }
+
+        // The files of a bundle are only removed once it's dropped.
+        drop(other);
+        assert!(temp_dir.exists());
+        drop(bundle);
+        assert!(!temp_dir.exists());
+    }
+
+    #[test]
+    fn refuses_bundle_signed_with_another_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = PKey::generate_ed25519().unwrap();
+        let other_key = PKey::generate_ed25519().unwrap();
+        let files = bundle_files();
+        let manifest = manifest(&files);
+        let signature = sign(&other_key, &manifest);
+
+        let archive = dir.path().join("bundle.tar.gz");
+        let mut entries = files.clone();
+        entries.push((MANIFEST, &manifest));
+        entries.push((SIGNATURE, &signature));
+        write_archive(&archive, &entries);
+
+        let public_key = key.raw_public_key().unwrap();
+        let error = unpack(&archive, dir.path(), Some(&public_key)).unwrap_err();
+        assert!(matches!(error, BundleError::InvalidSignature));
+    }
+
+    #[test]
+    fn refuses_unsigned_bundle_when_key_provided() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = PKey::generate_ed25519().unwrap();
+        let files = bundle_files();
+        let manifest = manifest(&files);
+
+        let archive = dir.path().join("bundle.tar.gz");
+        let mut entries = files.clone();
+        entries.push((MANIFEST, &manifest));
+        write_archive(&archive, &entries);
+
+        let public_key = key.raw_public_key().unwrap();
+        let error = unpack(&archive, dir.path(), Some(&public_key)).unwrap_err();
+        assert!(matches!(error, BundleError::MissingSignature));
+    }
+
+    #[test]
+    fn refuses_tampered_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = manifest(&bundle_files());
+
+        let archive = dir.path().join("bundle.tar.gz");
+        write_archive(
+            &archive,
+            &[
+                ("config/vector.toml", CONFIG),
+                ("vrl/parse.vrl", ". = {}"),
+                (MANIFEST, &manifest),
+            ],
+        );
+
+        let error = unpack(&archive, dir.path(), None).unwrap_err();
+        assert!(
+            matches!(error, BundleError::ChecksumMismatch { ref path } if path == "vrl/parse.vrl")
+        );
+    }
+
+    #[test]
+    fn refuses_unlisted_and_missing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = manifest(&bundle_files());
+
+        let archive = dir.path().join("extra.tar.gz");
+        let mut entries = bundle_files();
+        entries.push(("enrichment/hosts.csv", "host\n"));
+        entries.push((MANIFEST, &manifest));
+        write_archive(&archive, &entries);
+        let error = unpack(&archive, dir.path(), None).unwrap_err();
+        assert!(matches!(error, BundleError::UnlistedFile { .. }));
+
+        let archive = dir.path().join("missing.tar.gz");
+        write_archive(
+            &archive,
+            &[("config/vector.toml", CONFIG), (MANIFEST, &manifest)],
+        );
+        let error = unpack(&archive, dir.path(), None).unwrap_err();
+        assert!(matches!(error, BundleError::MissingFile { .. }));
+    }
+
+    #[test]
+    fn normalizes_paths() {
+        assert_eq!(
+            normalize_path(Path::new("./config/vector.toml")).unwrap(),
+            "config/vector.toml"
+        );
+        assert!(normalize_path(Path::new("../vector.toml")).is_err());
+        assert!(normalize_path(Path::new("/etc/vector/vector.toml")).is_err());
+        assert!(check_path_segment("version", "1.2.0").is_ok());
+        assert!(check_path_segment("version", "../1.2.0").is_err());
+    }
+}
@@ -1,6 +1,7 @@
-use std::collections::HashSet;
+use std::{cmp::Reverse, collections::HashSet, num::NonZeroU64};
 
 use indexmap::{IndexMap, IndexSet};
+use vector_buffers::BufferType;
 
 use super::{
     builder::ConfigBuilder, graph::Graph, schema, validation, ComponentKey, Config, OutputId,
@@ -49,10 +50,32 @@ pub fn compile(mut builder: ConfigBuilder) -> Result<(Config, Vec<String>), Vec<
         errors.extend(type_errors);
     }
 
+    if let Err(sandbox_errors) = validation::check_sandboxes(&builder) {
+        errors.extend(sandbox_errors);
+    }
+
+    #[cfg(feature = "api")]
+    if let Err(error) = builder.api.dropped_event_samples.compile_redact() {
+        errors.push(format!(
+            "Invalid redaction program for the dropped event samples:\n{}",
+            error
+        ));
+    }
+
     if let Err(output_errors) = validation::check_outputs(&builder) {
         errors.extend(output_errors);
     }
 
+    if let Err(budget_errors) = allocate_disk_buffer_budget(&mut builder) {
+        errors.extend(budget_errors);
+    }
+
+    // Unit tests refer to transforms individually, so they need to keep their own identity.
+    #[cfg(feature = "transforms-remap")]
+    if builder.global.fuse_transforms && builder.tests.is_empty() {
+        fuse_transforms(&mut builder, &expansions);
+    }
+
     #[cfg(feature = "enterprise")]
     let version = Some(builder.sha256_hash());
 
@@ -172,6 +195,206 @@ pub(super) fn expand_macros(
     }
 }
 
+/// Allocates the global disk buffer budget to the sinks with disk buffers, in proportion to their
+/// `disk_buffer_weight`. The share of each sink is split evenly between its disk buffer stages, and
+/// stages with a `max_size` of their own are capped to their share, so that together they never take
+/// more than the budget. Each stage then applies its `when_full` behavior once it fills its share.
+///
+/// The shares are fixed when the configuration is loaded: the space a sink leaves unused isn't lent
+/// to the other sinks.
+fn allocate_disk_buffer_budget(config: &mut ConfigBuilder) -> Result<(), Vec<String>> {
+    fn is_disk(stage: &BufferType) -> bool {
+        !matches!(stage, BufferType::Memory { .. })
+    }
+
+    let mut errors = Vec::new();
+    for (key, sink) in &config.sinks {
+        let stages = sink.buffer.stages();
+        if sink.disk_buffer_weight.is_some() && !stages.iter().any(is_disk) {
+            errors.push(format!(
+                "Sink \"{}\" has `disk_buffer_weight` set, but no disk buffer.",
+                key
+            ));
+        }
+        if config.global.disk_buffer_max_size.is_none() {
+            if stages
+                .iter()
+                .any(|stage| matches!(stage, BufferType::DiskV2 { max_size: None, .. }))
+            {
+                errors.push(format!(
+                    "Sink \"{}\" has a disk buffer without `max_size`, which is required unless `disk_buffer_max_size` is set.",
+                    key
+                ));
+            }
+            if sink.disk_buffer_weight.is_some() {
+                errors.push(format!(
+                    "Sink \"{}\" has `disk_buffer_weight` set, which requires `disk_buffer_max_size` to be set.",
+                    key
+                ));
+            }
+        }
+    }
+    let budget = match config.global.disk_buffer_max_size {
+        Some(budget) if errors.is_empty() => budget.get(),
+        _ => {
+            return if errors.is_empty() {
+                Ok(())
+            } else {
+                Err(errors)
+            }
+        }
+    };
+
+    let weights = config
+        .sinks
+        .values()
+        .map(|sink| {
+            if sink.buffer.stages().iter().any(is_disk) {
+                sink.disk_buffer_weight.map_or(1, NonZeroU64::get)
+            } else {
+                0
+            }
+        })
+        .collect::<Vec<_>>();
+    let shares = apportion(budget, &weights);
+
+    for ((key, sink), share) in config.sinks.iter_mut().zip(shares) {
+        let stages = sink
+            .buffer
+            .stages
+            .iter_mut()
+            .filter(|stage| is_disk(stage))
+            .collect::<Vec<_>>();
+        let stage_shares = apportion(share, &vec![1; stages.len()]);
+        for (stage, share) in stages.into_iter().zip(stage_shares) {
+            let share = match NonZeroU64::new(share) {
+                Some(share) => share,
+                None => {
+                    errors.push(format!(
+                        "Sink \"{}\" has no share of the disk buffer budget, as `disk_buffer_max_size` is too small.",
+                        key
+                    ));
+                    continue;
+                }
+            };
+            match stage {
+                BufferType::DiskV2 { max_size, .. } => {
+                    // Smaller disk buffers would still take that much, beyond their share.
+                    if share.get() < BufferType::DISK_V2_MIN_SIZE {
+                        errors.push(format!(
+                            "Sink \"{}\" has a share of {} bytes of the disk buffer budget, less than the {} bytes a disk buffer takes at least, as `disk_buffer_max_size` is too small.",
+                            key,
+                            share,
+                            BufferType::DISK_V2_MIN_SIZE
+                        ));
+                        continue;
+                    }
+                    *max_size = Some(max_size.map_or(share, |max_size| max_size.min(share)));
+                }
+                BufferType::DiskV1 { max_size, .. } => *max_size = (*max_size).min(share),
+                BufferType::Memory { .. } => {}
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Splits `total` in proportion to the weights. The remainder of the integer division is handed out
+/// one by one to the largest fractional parts first, so that the shares add up to `total` exactly.
+fn apportion(total: u64, weights: &[u64]) -> Vec<u64> {
+    let total_weight = weights.iter().copied().map(u128::from).sum::<u128>();
+    if total_weight == 0 {
+        return vec![0; weights.len()];
+    }
+
+    let mut shares = Vec::with_capacity(weights.len());
+    let mut remainders = Vec::with_capacity(weights.len());
+    for (index, weight) in weights.iter().copied().enumerate() {
+        let product = u128::from(total) * u128::from(weight);
+        // The share is at most the total, so it always fits.
+        shares.push((product / total_weight) as u64);
+        remainders.push((product % total_weight, index));
+    }
+
+    let left = total - shares.iter().sum::<u64>();
+    remainders.sort_by_key(|&(remainder, index)| (Reverse(remainder), index));
+    for (_, index) in remainders.into_iter().take(left as usize) {
+        shares[index] += 1;
+    }
+    shares
+}
+
+/// Fuses chains of `remap` transforms, where each transform is the only consumer of the previous
+/// one, into a single transform that takes the key of the last transform of the chain.
+#[cfg(feature = "transforms-remap")]
+fn fuse_transforms(
+    config: &mut ConfigBuilder,
+    expansions: &IndexMap<ComponentKey, Vec<ComponentKey>>,
+) {
+    use crate::transforms::remap::FusedRemapConfig;
+
+    loop {
+        let fused = config.transforms.iter().find_map(|(key, transform)| {
+            let upstream_key = match transform.inputs.as_slice() {
+                [input] => ComponentKey::from(input.as_str()),
+                _ => return None,
+            };
+            // Transforms created by expanding another one are referred to through their parent.
+            if upstream_key == *key
+                || expansions
+                    .values()
+                    .any(|children| children.contains(&upstream_key))
+            {
+                return None;
+            }
+            let upstream = config.transforms.get(&upstream_key)?;
+            if upstream.sandbox != transform.sandbox {
+                return None;
+            }
+
+            let consumers = config
+                .transforms
+                .values()
+                .map(|transform| &transform.inputs)
+                .chain(config.sinks.values().map(|sink| &sink.inputs))
+                .flatten()
+                .filter(|input| *input == upstream_key.id())
+                .count();
+            let dead_letter_of_sink = config
+                .sinks
+                .values()
+                .any(|sink| sink.dead_letter.as_ref() == Some(&upstream_key));
+            if consumers != 1 || dead_letter_of_sink {
+                return None;
+            }
+
+            FusedRemapConfig::fuse(&upstream_key, &*upstream.inner, key, &*transform.inner)
+                .map(|fused| (upstream_key, key.clone(), fused))
+        });
+
+        let (upstream_key, key, fused) = match fused {
+            Some(fused) => fused,
+            None => break,
+        };
+        let upstream = config
+            .transforms
+            .shift_remove(&upstream_key)
+            .expect("upstream transform exists");
+        let transform = config
+            .transforms
+            .get_mut(&key)
+            .expect("downstream transform exists");
+        transform.inputs = upstream.inputs;
+        transform.inner = Box::new(fused);
+        debug!(message = "Fused transforms.", upstream = %upstream_key, component = %key);
+    }
+}
+
 /// Expand globs in input lists
 pub(crate) fn expand_globs(config: &mut ConfigBuilder) {
     let candidates = config
@@ -246,8 +469,14 @@ fn expand_globs_inner(inputs: &mut Vec<String>, id: &str, candidates: &IndexSet<
 
 #[cfg(test)]
 mod test {
+    use std::collections::BTreeSet;
+
     use async_trait::async_trait;
+    use lookup::LookupBuf;
     use serde::{Deserialize, Serialize};
+    use value::Kind;
+
+    use vector_buffers::{BufferConfig, WhenFull};
 
     use super::*;
     use crate::{
@@ -269,6 +498,12 @@ mod test {
     #[derive(Debug, Serialize, Deserialize)]
     struct MockSinkConfig;
 
+    #[derive(Debug, Serialize, Deserialize)]
+    struct MockSchemaSourceConfig;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct MockReaderTransformConfig;
+
     #[async_trait]
     #[typetag::serde(name = "mock")]
     impl SourceConfig for MockSourceConfig {
@@ -329,6 +564,220 @@ mod test {
         }
     }
 
+    #[async_trait]
+    #[typetag::serde(name = "mock_schema")]
+    impl SourceConfig for MockSchemaSourceConfig {
+        async fn build(&self, _cx: SourceContext) -> crate::Result<Source> {
+            unimplemented!()
+        }
+
+        fn source_type(&self) -> &'static str {
+            "mock_schema"
+        }
+
+        fn outputs(&self) -> Vec<Output> {
+            vec![Output::default(DataType::all()).with_schema_definition(
+                schema::Definition::empty().required_field("message", Kind::bytes(), None),
+            )]
+        }
+
+        fn can_acknowledge(&self) -> bool {
+            false
+        }
+    }
+
+    #[async_trait]
+    #[typetag::serde(name = "mock_reader")]
+    impl TransformConfig for MockReaderTransformConfig {
+        async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+            unimplemented!()
+        }
+
+        fn transform_type(&self) -> &'static str {
+            "mock_reader"
+        }
+
+        fn input(&self) -> Input {
+            Input::all()
+        }
+
+        fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+            vec![Output::default(DataType::all())]
+        }
+
+        fn queried_fields(&self) -> Option<BTreeSet<LookupBuf>> {
+            Some(BTreeSet::from([
+                LookupBuf::from("message"),
+                LookupBuf::from("missing"),
+            ]))
+        }
+    }
+
+    #[test]
+    fn warns_on_unproduced_fields() {
+        let mut builder = ConfigBuilder::default();
+        builder.schema.enabled = true;
+        builder.add_source("closed", MockSchemaSourceConfig);
+        builder.add_source("open", MockSourceConfig);
+        builder.add_transform("reader", &["closed"], MockReaderTransformConfig);
+        builder.add_transform("open_reader", &["open"], MockReaderTransformConfig);
+        builder.add_sink("out", &["reader", "open_reader"], MockSinkConfig);
+
+        let (_, warnings) = builder.build_with_warnings().expect("build should succeed");
+
+        assert_eq!(
+            warnings,
+            vec![r#"Transform "reader" reads field ".missing", which none of its inputs produce"#]
+        );
+    }
+
+    #[cfg(feature = "transforms-remap")]
+    #[test]
+    fn fuses_remap_chains() {
+        use crate::transforms::remap::RemapConfig;
+
+        let remap = |source: &str| RemapConfig {
+            source: Some(source.to_owned()),
+            ..Default::default()
+        };
+
+        let mut builder = ConfigBuilder::default();
+        builder.global.fuse_transforms = true;
+        builder.add_source("in", MockSourceConfig);
+        builder.add_transform("a", &["in"], remap(".a = 1"));
+        builder.add_transform("b", &["a"], remap(".b = 2"));
+        builder.add_transform("c", &["b"], remap(".c = 3"));
+        builder.add_transform("fanned_out", &["in"], remap(".d = 4"));
+        builder.add_transform("d", &["fanned_out"], remap(".e = 5"));
+        builder.add_transform("other", &["in"], MockTransformConfig);
+        builder.add_transform("e", &["other"], remap(".f = 6"));
+        builder.add_sink("out", &["c", "d", "e", "fanned_out"], MockSinkConfig);
+
+        let config = builder.build().expect("build should succeed");
+
+        assert_eq!(
+            config.transforms.keys().collect::<Vec<_>>(),
+            vec![
+                &ComponentKey::from("c"),
+                &ComponentKey::from("fanned_out"),
+                &ComponentKey::from("d"),
+                &ComponentKey::from("other"),
+                &ComponentKey::from("e"),
+            ]
+        );
+        assert_eq!(
+            config
+                .transforms
+                .get(&ComponentKey::from("c"))
+                .map(|item| without_ports(item.inputs.clone()))
+                .unwrap(),
+            vec![ComponentKey::from("in")]
+        );
+        assert_eq!(
+            config
+                .transform(&ComponentKey::from("c"))
+                .unwrap()
+                .inner
+                .transform_type(),
+            "remap"
+        );
+    }
+
+    #[test]
+    fn allocates_disk_buffer_budget() {
+        fn disk(max_size: Option<u64>) -> BufferType {
+            BufferType::DiskV2 {
+                max_size: max_size.and_then(NonZeroU64::new),
+                when_full: WhenFull::Block,
+                encryption: None,
+            }
+        }
+
+        fn builder(disk_buffer_max_size: Option<u64>) -> ConfigBuilder {
+            let mut builder = ConfigBuilder::default();
+            builder.global.disk_buffer_max_size = disk_buffer_max_size.and_then(NonZeroU64::new);
+            builder.add_source("in", MockSourceConfig);
+            let buffers = [
+                ("light", vec![disk(None)], None),
+                ("heavy", vec![disk(None)], Some(2)),
+                ("capped", vec![disk(Some(200_000_000))], None),
+                (
+                    "v1",
+                    vec![BufferType::DiskV1 {
+                        max_size: NonZeroU64::new(1_000_000_000).unwrap(),
+                        when_full: WhenFull::Block,
+                    }],
+                    None,
+                ),
+                ("split", vec![disk(None), disk(None)], Some(3)),
+                ("memory", BufferConfig::default().stages, None),
+            ];
+            for (key, stages, weight) in buffers {
+                builder.add_sink(key, &["in"], MockSinkConfig);
+                let sink = &mut builder.sinks[&ComponentKey::from(key)];
+                sink.buffer = BufferConfig { stages };
+                sink.disk_buffer_weight = weight.and_then(NonZeroU64::new);
+            }
+            builder
+        }
+
+        let errors = builder(None).build().unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                "Sink \"light\" has a disk buffer without `max_size`, which is required unless `disk_buffer_max_size` is set.",
+                "Sink \"heavy\" has a disk buffer without `max_size`, which is required unless `disk_buffer_max_size` is set.",
+                "Sink \"heavy\" has `disk_buffer_weight` set, which requires `disk_buffer_max_size` to be set.",
+                "Sink \"split\" has a disk buffer without `max_size`, which is required unless `disk_buffer_max_size` is set.",
+                "Sink \"split\" has `disk_buffer_weight` set, which requires `disk_buffer_max_size` to be set.",
+            ]
+        );
+
+        let errors = builder(Some(400_000)).build().unwrap_err();
+        assert_eq!(errors.len(), 5);
+        assert_eq!(
+            errors[0],
+            "Sink \"light\" has a share of 50000 bytes of the disk buffer budget, less than the 134217728 bytes a disk buffer takes at least, as `disk_buffer_max_size` is too small."
+        );
+
+        // The bytes left over by the division go to the largest fractional parts of the shares.
+        let config = builder(Some(2_400_000_005))
+            .build()
+            .expect("build should succeed");
+        let max_sizes = |key: &str| {
+            config
+                .sink(&ComponentKey::from(key))
+                .unwrap()
+                .buffer
+                .stages()
+                .iter()
+                .map(|stage| match stage {
+                    BufferType::DiskV2 { max_size, .. } => max_size.map(NonZeroU64::get),
+                    BufferType::DiskV1 { max_size, .. } => Some(max_size.get()),
+                    BufferType::Memory { .. } => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(max_sizes("light"), vec![Some(300_000_001)]);
+        assert_eq!(max_sizes("heavy"), vec![Some(600_000_001)]);
+        assert_eq!(max_sizes("capped"), vec![Some(200_000_000)]);
+        assert_eq!(max_sizes("v1"), vec![Some(300_000_000)]);
+        // A sink splits its share evenly between its disk buffer stages.
+        assert_eq!(
+            max_sizes("split"),
+            vec![Some(450_000_001), Some(450_000_001)]
+        );
+        assert_eq!(max_sizes("memory"), vec![None]);
+
+        let mut builder = builder(Some(2_400_000_005));
+        builder.sinks[&ComponentKey::from("memory")].disk_buffer_weight = NonZeroU64::new(2);
+        assert_eq!(
+            builder.build().unwrap_err(),
+            vec!["Sink \"memory\" has `disk_buffer_weight` set, but no disk buffer."]
+        );
+    }
+
     #[test]
     fn glob_expansion() {
         let mut builder = ConfigBuilder::default();
@@ -2,7 +2,7 @@ use std::collections::HashSet;
 
 use indexmap::IndexMap;
 
-use super::{ComponentKey, Config};
+use super::{ComponentKey, Config, OutputId, DROPPED_OUTPUT};
 
 #[derive(Debug)]
 pub struct ConfigDiff {
@@ -18,10 +18,28 @@ impl ConfigDiff {
     }
 
     pub fn new(old: &Config, new: &Config) -> Self {
+        let mut sinks = Difference::new(&old.sinks, &new.sinks);
+        // A sink only has a dropped output while components have it as an input, so it's rebuilt
+        // when they start or stop doing so.
+        let dropped_consumers_changed = old
+            .sinks
+            .keys()
+            .filter(|key| new.sinks.contains_key(*key))
+            .filter(|key| {
+                let output = OutputId {
+                    component: (*key).clone(),
+                    port: Some(DROPPED_OUTPUT.to_owned()),
+                };
+                old.is_consumed(&output) != new.is_consumed(&output)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        sinks.to_change.extend(dropped_consumers_changed);
+
         ConfigDiff {
             sources: Difference::new(&old.sources, &new.sources),
             transforms: Difference::new(&old.transforms, &new.transforms),
-            sinks: Difference::new(&old.sinks, &new.sinks),
+            sinks,
             enrichment_tables: Difference::new(&old.enrichment_tables, &new.enrichment_tables),
         }
     }
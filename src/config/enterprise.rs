@@ -804,11 +804,15 @@ mod test {
             config_paths_toml: vec![],
             config_paths_json: vec![],
             config_paths_yaml: vec![],
+            bundle: None,
+            bundle_public_key: None,
+            bundle_allow_unsigned: false,
             require_healthy: None,
             threads: None,
             verbose: 0,
             quiet: 3,
             log_format: LogFormat::from_str("text").unwrap(),
+            internal_log_file: None,
             color: Color::from_str("auto").unwrap(),
             watch_config: false,
         }
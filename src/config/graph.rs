@@ -3,7 +3,8 @@ use std::collections::{HashMap, HashSet, VecDeque};
 use indexmap::{set::IndexSet, IndexMap};
 
 use super::{
-    schema, ComponentKey, DataType, Output, OutputId, SinkOuter, SourceOuter, TransformOuter,
+    dropped_data_type, schema, ComponentKey, DataType, Output, OutputId, SinkOuter, SourceOuter,
+    TransformOuter, DROPPED_OUTPUT, OVERFLOW_OUTPUT,
 };
 
 #[derive(Debug, Clone)]
@@ -110,6 +111,26 @@ impl Graph {
             }
         }
 
+        // The events a sink fails to deliver are sent to its dead letter component as if the sink
+        // was one of its inputs.
+        for (id, config) in sinks.iter() {
+            if let Some(dead_letter) = &config.dead_letter {
+                if let Err(e) = graph.add_dead_letter(id, dead_letter) {
+                    errors.push(e);
+                }
+            }
+        }
+
+        // The events that don't fit in the buffer of a sink are sent to its overflow sink through a
+        // separate output of the sink.
+        for (id, config) in sinks.iter() {
+            if let Some(overflow_sink) = &config.overflow_sink {
+                if let Err(e) = graph.add_overflow_sink(id, overflow_sink) {
+                    errors.push(e);
+                }
+            }
+        }
+
         if ignore_errors || errors.is_empty() {
             Ok(graph)
         } else {
@@ -148,6 +169,49 @@ impl Graph {
         }
     }
 
+    fn add_dead_letter(&mut self, from: &ComponentKey, to: &ComponentKey) -> Result<(), String> {
+        match self.nodes.get(to) {
+            Some(Node::Transform { .. } | Node::Sink { .. }) => {
+                self.edges.push(Edge {
+                    from: OutputId::from(from),
+                    to: to.clone(),
+                });
+                Ok(())
+            }
+            Some(Node::Source { .. }) => Err(format!(
+                "Dead letter \"{}\" for sink \"{}\" is a source, which can't receive events.",
+                to, from
+            )),
+            None => Err(format!(
+                "Dead letter \"{}\" for sink \"{}\" doesn't match any components.",
+                to, from
+            )),
+        }
+    }
+
+    fn add_overflow_sink(&mut self, from: &ComponentKey, to: &ComponentKey) -> Result<(), String> {
+        match self.nodes.get(to) {
+            Some(Node::Sink { .. }) => {
+                self.edges.push(Edge {
+                    from: OutputId {
+                        component: from.clone(),
+                        port: Some(OVERFLOW_OUTPUT.to_owned()),
+                    },
+                    to: to.clone(),
+                });
+                Ok(())
+            }
+            Some(Node::Source { .. } | Node::Transform { .. }) => Err(format!(
+                "Overflow sink \"{}\" for sink \"{}\" isn't a sink.",
+                to, from
+            )),
+            None => Err(format!(
+                "Overflow sink \"{}\" for sink \"{}\" doesn't match any components.",
+                to, from
+            )),
+        }
+    }
+
     /// Return the input type of a given component.
     ///
     /// # Panics
@@ -166,8 +230,9 @@ impl Graph {
     ///
     /// # Panics
     ///
-    /// Will panic if the given id is not present in the graph or identifies a sink, which can't
-    /// have inputs.
+    /// Will panic if the given id is not present in the graph. The outputs of a sink are the ones of
+    /// its dead letter and overflow sink, which send the events the sink received, and its dropped
+    /// output, which sends the events of the types it doesn't accept.
     fn get_output_type(&self, id: &OutputId) -> DataType {
         match &self.nodes[&id.component] {
             Node::Source { outputs } | Node::Transform { outputs, .. } => outputs
@@ -175,7 +240,10 @@ impl Graph {
                 .find(|output| output.port == id.port)
                 .map(|output| output.ty)
                 .expect("output didn't exist"),
-            Node::Sink { .. } => panic!("no outputs on sinks"),
+            Node::Sink { ty } if id.port.as_deref() == Some(DROPPED_OUTPUT) => {
+                dropped_data_type(*ty).expect("output didn't exist")
+            }
+            Node::Sink { ty } => *ty,
         }
     }
 
@@ -260,7 +328,14 @@ impl Graph {
         self.nodes
             .iter()
             .flat_map(|(key, node)| match node {
-                Node::Sink { .. } => vec![],
+                // The dropped output is the only output of a sink components can have as an input.
+                Node::Sink { ty } => dropped_data_type(*ty)
+                    .map(|_| OutputId {
+                        component: key.clone(),
+                        port: Some(DROPPED_OUTPUT.to_owned()),
+                    })
+                    .into_iter()
+                    .collect(),
                 Node::Source { outputs } | Node::Transform { outputs, .. } => outputs
                     .iter()
                     .map(|output| OutputId {
@@ -738,4 +813,120 @@ mod test {
         assert_eq!(paths[0], vec!["source", "t1", "t3", "sink1"]);
         assert_eq!(paths[1], vec!["source", "t1", "t2", "sink1"]);
     }
+
+    #[test]
+    fn dead_letters_are_outputs_of_their_sink() {
+        let mut graph = Graph::default();
+        graph.add_source("in", DataType::Log);
+        graph.add_sink("out", DataType::Log, vec!["in"]);
+        graph.add_sink("failed", DataType::Log, vec![]);
+        graph
+            .add_dead_letter(&"out".into(), &"failed".into())
+            .unwrap();
+
+        assert_eq!(
+            graph.inputs_for(&"failed".into()),
+            vec![OutputId::from("out")]
+        );
+        assert_eq!(Ok(()), graph.typecheck());
+        graph.check_for_cycles().unwrap();
+
+        // The output of a sink can only be wired through its dead letter.
+        assert_eq!(
+            Err("Input \"out\" for sink \"failed\" doesn't match any components.".into()),
+            graph.test_add_input("failed", "out")
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_dead_letters() {
+        let mut graph = Graph::default();
+        graph.add_source("in", DataType::Log);
+        graph.add_sink("out", DataType::Log, vec!["in"]);
+
+        assert_eq!(
+            Err(
+                "Dead letter \"in\" for sink \"out\" is a source, which can't receive events."
+                    .into()
+            ),
+            graph.add_dead_letter(&"out".into(), &"in".into())
+        );
+        assert_eq!(
+            Err("Dead letter \"missing\" for sink \"out\" doesn't match any components.".into()),
+            graph.add_dead_letter(&"out".into(), &"missing".into())
+        );
+    }
+
+    #[test]
+    fn detects_dead_letter_cycles() {
+        let mut graph = Graph::default();
+        graph.add_source("in", DataType::Log);
+        graph.add_transform("retry", DataType::Log, DataType::Log, vec!["in"]);
+        graph.add_sink("out", DataType::Log, vec!["retry"]);
+        graph
+            .add_dead_letter(&"out".into(), &"retry".into())
+            .unwrap();
+
+        assert_eq!(
+            Err("Cyclic dependency detected in the chain [ out -> retry ]".into()),
+            graph.check_for_cycles()
+        );
+    }
+
+    #[test]
+    fn sinks_send_unsupported_events_through_their_dropped_output() {
+        let mut graph = Graph::default();
+        graph.add_source("in", DataType::all());
+        graph.add_sink("out", DataType::Trace, vec!["in"]);
+        graph.add_sink("any", DataType::all(), vec!["in"]);
+        graph.add_sink("logs", DataType::Log, vec![]);
+        graph.add_sink("traces", DataType::Trace, vec![]);
+
+        graph.test_add_input("logs", "out.dropped").unwrap();
+        assert_eq!(Ok(()), graph.typecheck());
+
+        graph.test_add_input("traces", "out.dropped").unwrap();
+        assert_eq!(
+            Err(vec![
+                "Data type mismatch between out.dropped (Log,Metric) and traces (Trace)".into()
+            ]),
+            graph.typecheck()
+        );
+
+        // A sink accepting all the types of events has no dropped output.
+        assert_eq!(
+            Err("Input \"any.dropped\" for sink \"logs\" doesn't match any components.".into()),
+            graph.test_add_input("logs", "any.dropped")
+        );
+    }
+
+    #[test]
+    fn overflow_sinks_are_outputs_of_their_sink() {
+        let mut graph = Graph::default();
+        graph.add_source("in", DataType::Log);
+        graph.add_sink("out", DataType::Log, vec!["in"]);
+        graph.add_sink("archive", DataType::Log, vec![]);
+        graph
+            .add_overflow_sink(&"out".into(), &"archive".into())
+            .unwrap();
+
+        assert_eq!(
+            graph.inputs_for(&"archive".into()),
+            vec![OutputId {
+                component: "out".into(),
+                port: Some(OVERFLOW_OUTPUT.to_owned()),
+            }]
+        );
+        assert_eq!(Ok(()), graph.typecheck());
+        graph.check_for_cycles().unwrap();
+
+        assert_eq!(
+            Err("Overflow sink \"in\" for sink \"out\" isn't a sink.".into()),
+            graph.add_overflow_sink(&"out".into(), &"in".into())
+        );
+        assert_eq!(
+            Err("Overflow sink \"missing\" for sink \"out\" doesn't match any components.".into()),
+            graph.add_overflow_sink(&"out".into(), &"missing".into())
+        );
+    }
 }
@@ -285,6 +285,12 @@ pub fn prepare_input<R: std::io::Read>(mut input: R) -> Result<(String, Vec<Stri
         .map_err(|e| vec![e.to_string()])?;
 
     let mut vars = std::env::vars().collect::<HashMap<_, _>>();
+    if let Some(dir) = super::bundle::dir() {
+        vars.insert(
+            super::bundle::BUNDLE_DIR_VAR.into(),
+            dir.to_string_lossy().into_owned(),
+        );
+    }
     if !vars.contains_key("HOSTNAME") {
         if let Ok(hostname) = crate::get_hostname() {
             vars.insert("HOSTNAME".into(), hostname);
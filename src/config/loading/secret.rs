@@ -39,6 +39,8 @@ pub trait SecretBackend: core::fmt::Debug + Send + Sync + dyn_clone::DynClone {
     ) -> crate::Result<HashMap<String, String>>;
 }
 
+dyn_clone::clone_trait_object!(SecretBackend);
+
 #[derive(Deserialize, Serialize, Debug, Default)]
 pub struct SecretBackendLoader {
     backends: IndexMap<ComponentKey, Box<dyn SecretBackend>>,
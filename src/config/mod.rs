@@ -10,13 +10,16 @@ use async_trait::async_trait;
 use component::ComponentDescription;
 use indexmap::IndexMap; // IndexMap preserves insertion order, allowing us to output errors in the same order they are present in the file
 use serde::{Deserialize, Serialize};
-pub use vector_core::config::{AcknowledgementsConfig, DataType, GlobalOptions, Input, Output};
+pub use vector_core::config::{
+    AcknowledgementsConfig, Capability, DataType, GlobalOptions, Input, Output,
+};
 pub use vector_core::transform::{TransformConfig, TransformContext};
 
 use crate::{conditions, event::Metric, serde::OneOrMany};
 
 pub mod api;
 mod builder;
+pub mod bundle;
 mod cmd;
 mod compiler;
 pub mod component;
@@ -28,6 +31,7 @@ mod graph;
 mod id;
 mod loading;
 pub mod provider;
+pub mod sandbox;
 mod schema;
 mod sink;
 mod source;
@@ -47,7 +51,11 @@ pub use loading::{
     load_from_str, load_source_from_paths, merge_path_lists, process_paths, SecretBackend,
     CONFIG_PATHS,
 };
-pub use sink::{SinkConfig, SinkContext, SinkDescription, SinkHealthcheckOptions, SinkOuter};
+pub use sandbox::{Sandbox, SandboxConfig};
+pub use sink::{
+    dropped_data_type, SinkConfig, SinkContext, SinkDescription, SinkHealthcheckOptions, SinkOuter,
+    DROPPED_OUTPUT, OVERFLOW_OUTPUT,
+};
 pub use source::{SourceConfig, SourceContext, SourceDescription, SourceOuter};
 pub use transform::{TransformDescription, TransformOuter};
 pub use unit_test::{build_unit_tests, build_unit_tests_main, UnitTestResult};
@@ -140,6 +148,10 @@ impl Config {
         self.sinks.get(id)
     }
 
+    pub const fn secret_backends(&self) -> &IndexMap<ComponentKey, Box<dyn SecretBackend>> {
+        &self.secret
+    }
+
     pub fn inputs_for_node(&self, id: &ComponentKey) -> Option<&[OutputId]> {
         self.transforms
             .get(id)
@@ -147,6 +159,14 @@ impl Config {
             .or_else(|| self.sinks.get(id).map(|s| s.inputs.as_slice()))
     }
 
+    /// Checks whether any component has the given output as an input.
+    pub fn is_consumed(&self, output: &OutputId) -> bool {
+        self.transforms
+            .values()
+            .any(|transform| transform.inputs.contains(output))
+            || self.sinks.values().any(|sink| sink.inputs.contains(output))
+    }
+
     /// Expand a logical component id (i.e. from the config file) into the ids of the
     /// components it was expanded to as part of the macro process. Does not check that the
     /// identifier is otherwise valid.
@@ -158,17 +178,6 @@ impl Config {
     }
 
     pub fn propagate_acknowledgements(&mut self) -> Result<(), Vec<String>> {
-        if self.global.acknowledgements.enabled() {
-            for (name, sink) in &self.sinks {
-                if sink.inner.acknowledgements().is_none() {
-                    warn!(
-                        message = "Acknowledgements are globally enabled but sink does not support them.",
-                        sink = %name,
-                    );
-                }
-            }
-        }
-
         let inputs: Vec<_> = self
             .sinks
             .iter()
@@ -179,35 +188,22 @@ impl Config {
                     .merge_default(&self.global.acknowledgements)
                     .enabled()
             })
-            .flat_map(|(name, sink)| {
-                sink.inputs
-                    .iter()
-                    .map(|input| (name.clone(), input.clone()))
-            })
+            .flat_map(|(_, sink)| sink.inputs.iter().cloned())
             .collect();
         self.propagate_acks_rec(inputs);
         Ok(())
     }
 
-    fn propagate_acks_rec(&mut self, sink_inputs: Vec<(ComponentKey, OutputId)>) {
-        for (sink, input) in sink_inputs {
+    fn propagate_acks_rec(&mut self, sink_inputs: Vec<OutputId>) {
+        for input in sink_inputs {
             let component = &input.component;
+            // Sources that can't acknowledge are reported by `validation::warnings`.
             if let Some(source) = self.sources.get_mut(component) {
                 if source.inner.can_acknowledge() {
                     source.sink_acknowledgements = true;
-                } else {
-                    warn!(
-                        message = "Source has acknowledgements enabled by a sink, but acknowledgements are not supported by this source. Silent data loss could occur.",
-                        source = component.id(),
-                        sink = sink.id(),
-                    );
                 }
             } else if let Some(transform) = self.transforms.get(component) {
-                let inputs = transform
-                    .inputs
-                    .iter()
-                    .map(|input| (sink.clone(), input.clone()))
-                    .collect();
+                let inputs = transform.inputs.clone();
                 self.propagate_acks_rec(inputs);
             }
         }
@@ -258,7 +254,7 @@ macro_rules! impl_generate_config_from_default {
     };
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Clone, Deserialize, Serialize, Debug)]
 pub struct EnrichmentTableOuter {
     #[serde(flatten)]
     pub inner: Box<dyn EnrichmentTableConfig>,
@@ -279,6 +275,8 @@ pub trait EnrichmentTableConfig: core::fmt::Debug + Send + Sync + dyn_clone::Dyn
     ) -> crate::Result<Box<dyn enrichment::Table + Send + Sync>>;
 }
 
+dyn_clone::clone_trait_object!(EnrichmentTableConfig);
+
 pub type EnrichmentTableDescription = ComponentDescription<Box<dyn EnrichmentTableConfig>>;
 
 inventory::collect!(EnrichmentTableDescription);
@@ -528,6 +526,9 @@ pub struct TestInput {
     pub value: Option<String>,
     pub log_fields: Option<IndexMap<String, TestInputValue>>,
     pub metric: Option<Metric>,
+    /// Moves the clock of the test forward by this many seconds before the transform the input is
+    /// inserted at receives it.
+    pub advance_secs: Option<u64>,
 }
 
 fn default_test_input_type() -> String {
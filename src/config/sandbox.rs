@@ -0,0 +1,373 @@
+//! Sandboxes restricting the paths and hosts components can access.
+//!
+//! Each component can be given a sandbox, listing the paths it can read and write and the hosts
+//! it can connect to or listen on. The capabilities components declare are checked against their
+//! sandbox when the configuration is loaded, and the sandbox is then enforced by the I/O shared by
+//! components: the HTTP client, the TCP and UDP sinks, and the file sink. Only the components
+//! confined to their sandbox this way, which tell so through `supports_sandbox`, can be given one.
+//!
+//! Components are built and run within their sandbox, which is captured by the clients and
+//! connectors they build, so that it also applies to the tasks they spawn. Paths are resolved,
+//! following symbolic links, before they are checked.
+//!
+//! The sandbox is advisory beyond that: it restricts the I/O going through the clients and
+//! connectors above, not the system calls of the process, and code running outside of the tasks of
+//! components, such as the API, is unrestricted.
+
+use std::{
+    convert::TryFrom,
+    fmt,
+    future::Future,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+
+use super::{Capability, Resource};
+use crate::internal_events::SandboxViolation;
+
+tokio::task_local! {
+    static CURRENT: Sandbox;
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct SandboxConfig {
+    /// The paths the component can access, including everything under them. Any path is allowed
+    /// when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paths: Option<Vec<PathBuf>>,
+    /// The hosts the component can connect to or listen on. Any host is allowed when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hosts: Option<Vec<HostRule>>,
+}
+
+/// A host a component can access, written as `host` to allow any of its ports or `host:port`.
+///
+/// The host can be `*` to allow any host, on the given port, or start with `*.` to allow its
+/// subdomains.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct HostRule {
+    host: String,
+    port: Option<u16>,
+}
+
+impl HostRule {
+    fn allows(&self, host: &str, port: Option<u16>) -> bool {
+        let host = normalize_host(host);
+        let host_allowed = match self.host.strip_prefix("*.") {
+            Some(domain) => host.strip_suffix(domain).map_or(false, |subdomain| {
+                subdomain.len() > 1 && subdomain.ends_with('.')
+            }),
+            None => self.host == "*" || self.host == host,
+        };
+        host_allowed && (self.port.is_none() || self.port == port)
+    }
+}
+
+impl TryFrom<String> for HostRule {
+    type Error = String;
+
+    fn try_from(rule: String) -> Result<Self, Self::Error> {
+        // IPv6 addresses have to be bracketed to be given a port.
+        let (host, port) = match rule.rsplit_once(':') {
+            Some((host, port)) if !host.contains(':') || host.ends_with(']') => {
+                let port = port
+                    .parse()
+                    .map_err(|_| format!("Invalid port in sandbox host `{}`.", rule))?;
+                (host, Some(port))
+            }
+            _ => (rule.as_str(), None),
+        };
+
+        let host = normalize_host(host);
+        if host.is_empty() {
+            return Err(format!("Missing host in sandbox host `{}`.", rule));
+        }
+        Ok(Self { host, port })
+    }
+}
+
+impl From<HostRule> for String {
+    fn from(rule: HostRule) -> Self {
+        rule.to_string()
+    }
+}
+
+impl fmt::Display for HostRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.port {
+            Some(port) if self.host.contains(':') => write!(f, "[{}]:{}", self.host, port),
+            Some(port) => write!(f, "{}:{}", self.host, port),
+            None => f.write_str(&self.host),
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum SandboxError {
+    #[snafu(display("The sandbox doesn't allow access to {}.", capability))]
+    NotAllowed { capability: Capability },
+}
+
+/// The sandbox of a component, enforced at runtime.
+#[derive(Clone, Debug, Default)]
+pub struct Sandbox {
+    /// Unrestricted when unset.
+    config: Option<Arc<SandboxConfig>>,
+}
+
+impl Sandbox {
+    pub fn new(config: &SandboxConfig) -> Self {
+        Self {
+            config: (*config != SandboxConfig::default()).then(|| Arc::new(config.clone())),
+        }
+    }
+
+    /// The sandbox of the component being built, which is unrestricted outside of components.
+    pub fn current() -> Self {
+        CURRENT.try_with(Clone::clone).unwrap_or_default()
+    }
+
+    /// Runs `future` within this sandbox.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT.scope(self, future).await
+    }
+
+    /// Returns the future running within the sandbox of the current task, for the tasks spawned by
+    /// components.
+    pub fn in_current_scope<F: Future>(future: F) -> impl Future<Output = F::Output> {
+        Self::current().scope(future)
+    }
+
+    /// Checks that the sandbox allows the capability.
+    pub fn check(&self, capability: &Capability) -> Result<(), SandboxError> {
+        let config = match &self.config {
+            Some(config) => config,
+            None => return Ok(()),
+        };
+
+        let allowed = match capability {
+            Capability::Path(path) => config.paths.as_ref().map_or(true, |paths| {
+                let path = normalize_path(path);
+                paths
+                    .iter()
+                    .any(|allowed| path.starts_with(normalize_path(allowed)))
+            }),
+            Capability::Host { host, port } => config.hosts.as_ref().map_or(true, |hosts| {
+                hosts.iter().any(|rule| rule.allows(host, *port))
+            }),
+        };
+
+        if allowed {
+            Ok(())
+        } else {
+            Err(SandboxError::NotAllowed {
+                capability: capability.clone(),
+            })
+        }
+    }
+
+    /// Checks that the sandbox allows the capability at runtime, reporting it otherwise.
+    pub fn enforce(&self, capability: &Capability) -> Result<(), SandboxError> {
+        self.check(capability).map_err(|error| {
+            emit!(SandboxViolation { capability });
+            error
+        })
+    }
+
+    /// Checks that the sandbox allows access to the host of the URI at runtime, reporting it
+    /// otherwise.
+    pub fn enforce_uri(&self, uri: &http::Uri) -> Result<(), SandboxError> {
+        match Capability::uri(uri) {
+            Some(capability) => self.enforce(&capability),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The capability needed to use a resource, for those the sandbox restricts.
+pub fn resource_capability(resource: &Resource) -> Option<Capability> {
+    match resource {
+        Resource::Port(address, _) => Some(Capability::host(
+            address.ip().to_string(),
+            Some(address.port()),
+        )),
+        Resource::SystemFdOffset(_) | Resource::Stdin | Resource::DiskBuffer(_) => None,
+    }
+}
+
+fn normalize_host(host: &str) -> String {
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_ascii_lowercase()
+}
+
+/// Makes the path absolute and resolves it, following symbolic links, so that a path can't escape
+/// the sandbox through them.
+///
+/// Only the part of the path that exists can be resolved by the file system, the `.` and `..`
+/// components of the rest of it are resolved lexically.
+fn normalize_path(path: &Path) -> PathBuf {
+    let path = if path.is_relative() {
+        std::env::current_dir().unwrap_or_default().join(path)
+    } else {
+        path.to_owned()
+    };
+
+    let mut normalized = PathBuf::new();
+    let mut components = path.components().peekable();
+    while let Some(component) = components.next() {
+        match component {
+            Component::CurDir => continue,
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+
+        // Resolving the path as it's built resolves the links it goes through before any `..`
+        // following them.
+        match std::fs::canonicalize(&normalized) {
+            Ok(canonical) => normalized = canonical,
+            Err(_) => {
+                for component in components.by_ref() {
+                    match component {
+                        Component::CurDir => {}
+                        Component::ParentDir => {
+                            normalized.pop();
+                        }
+                        component => normalized.push(component),
+                    }
+                }
+            }
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sandbox(config: &str) -> Sandbox {
+        Sandbox::new(&toml::from_str(config).unwrap())
+    }
+
+    #[test]
+    fn unrestricted_by_default() {
+        let sandbox = sandbox("");
+        assert!(sandbox.check(&Capability::path("/etc/passwd")).is_ok());
+        assert!(sandbox
+            .check(&Capability::host("example.com", Some(443)))
+            .is_ok());
+    }
+
+    #[test]
+    fn allows_paths_under_allowed_ones() {
+        let sandbox = sandbox(r#"paths = ["/var/log/app"]"#);
+        assert!(sandbox.check(&Capability::path("/var/log/app")).is_ok());
+        assert!(sandbox
+            .check(&Capability::path("/var/log/app/today.log"))
+            .is_ok());
+        assert!(sandbox.check(&Capability::path("/var/log/apps")).is_err());
+        assert!(sandbox
+            .check(&Capability::path("/var/log/app/../../../etc/passwd"))
+            .is_err());
+        // Hosts aren't restricted when only paths are.
+        assert!(sandbox
+            .check(&Capability::host("example.com", None))
+            .is_ok());
+    }
+
+    #[test]
+    fn allows_hosts_and_ports() {
+        let sandbox =
+            sandbox(r#"hosts = ["api.example.com:443", "*.datadoghq.com", "*:514", "[::1]:9000"]"#);
+        assert!(sandbox
+            .check(&Capability::host("API.example.com", Some(443)))
+            .is_ok());
+        assert!(sandbox
+            .check(&Capability::host("api.example.com", Some(80)))
+            .is_err());
+        assert!(sandbox
+            .check(&Capability::host("api.example.com", None))
+            .is_err());
+        assert!(sandbox
+            .check(&Capability::host(
+                "http-intake.logs.datadoghq.com",
+                Some(443)
+            ))
+            .is_ok());
+        assert!(sandbox
+            .check(&Capability::host("datadoghq.com", Some(443)))
+            .is_err());
+        assert!(sandbox
+            .check(&Capability::host("evil-datadoghq.com", Some(443)))
+            .is_err());
+        assert!(sandbox
+            .check(&Capability::host("10.0.0.1", Some(514)))
+            .is_ok());
+        assert!(sandbox.check(&Capability::host("::1", Some(9000))).is_ok());
+        // Paths aren't restricted when only hosts are.
+        assert!(sandbox.check(&Capability::path("/tmp")).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resolves_symbolic_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let allowed = dir.path().join("allowed");
+        let secret = dir.path().join("secret");
+        std::fs::create_dir(&allowed).unwrap();
+        std::fs::create_dir(&secret).unwrap();
+        std::os::unix::fs::symlink(&secret, allowed.join("link")).unwrap();
+
+        let sandbox = Sandbox::new(&SandboxConfig {
+            paths: Some(vec![allowed.clone()]),
+            hosts: None,
+        });
+        assert!(sandbox
+            .check(&Capability::path(allowed.join("file")))
+            .is_ok());
+        assert!(sandbox
+            .check(&Capability::path(allowed.join("link/file")))
+            .is_err());
+        assert!(sandbox
+            .check(&Capability::path(allowed.join("link/../file")))
+            .is_err());
+        assert!(sandbox
+            .check(&Capability::path(allowed.join("missing/../file")))
+            .is_ok());
+    }
+
+    #[test]
+    fn denies_everything_when_empty() {
+        let sandbox = sandbox("paths = []\nhosts = []");
+        assert!(sandbox.check(&Capability::path("/tmp")).is_err());
+        assert!(sandbox
+            .check(&Capability::host("localhost", Some(80)))
+            .is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_hosts() {
+        assert!(toml::from_str::<SandboxConfig>(r#"hosts = ["example.com:https"]"#).is_err());
+        assert!(toml::from_str::<SandboxConfig>(r#"hosts = [":80"]"#).is_err());
+    }
+
+    #[tokio::test]
+    async fn current_sandbox_is_scoped() {
+        let restricted = sandbox("hosts = []");
+        let capability = Capability::host("example.com", Some(80));
+
+        assert!(Sandbox::current().check(&capability).is_ok());
+        restricted
+            .scope(async { assert!(Sandbox::current().check(&capability).is_err()) })
+            .await;
+        assert!(Sandbox::current().check(&capability).is_ok());
+    }
+}
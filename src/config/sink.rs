@@ -1,12 +1,33 @@
+use std::num::NonZeroU64;
+
 use async_trait::async_trait;
 use component::ComponentDescription;
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use vector_buffers::{Acker, BufferConfig, BufferType};
-use vector_core::config::{AcknowledgementsConfig, GlobalOptions, Input};
+use vector_core::config::{AcknowledgementsConfig, DataType, GlobalOptions, Input};
 
-use super::{component, schema, ComponentKey, ProxyConfig, Resource};
+use super::{
+    component, schema, Capability, ComponentKey, ProxyConfig, Resource, SandboxConfig,
+    SecretBackend,
+};
 use crate::sinks::{self, util::UriSerde};
 
+/// The name of the output of a sink sending the events that don't fit in its buffer to its
+/// overflow sink.
+pub const OVERFLOW_OUTPUT: &str = "overflow";
+
+/// The name of the output of a sink sending the events of the types it doesn't accept, which
+/// components can have as an input.
+pub const DROPPED_OUTPUT: &str = "dropped";
+
+/// Returns the types of the events sent through the dropped output of a sink accepting `ty`, if
+/// there are any it doesn't accept.
+pub fn dropped_data_type(ty: DataType) -> Option<DataType> {
+    let dropped = DataType::all() & !ty;
+    (!dropped.is_none()).then(|| dropped)
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 pub struct SinkOuter<T> {
     #[serde(default = "Default::default")] // https://github.com/serde-rs/serde/issues/1541
@@ -22,12 +43,46 @@ pub struct SinkOuter<T> {
     #[serde(default)]
     pub buffer: BufferConfig,
 
+    /// The share of the global disk buffer budget the disk buffer of the sink gets, relative to
+    /// the other sinks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disk_buffer_weight: Option<NonZeroU64>,
+
     #[serde(
         default,
         skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
     )]
     proxy: ProxyConfig,
 
+    /// Whether to check the sequence numbers of the events received, reporting the events
+    /// missing or out of order.
+    #[serde(
+        default,
+        skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
+    )]
+    pub check_sequence_numbers: bool,
+
+    /// The component the events the sink failed to deliver are routed to, instead of being
+    /// dropped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dead_letter: Option<ComponentKey>,
+
+    /// The sink the events that don't fit in the buffer are routed to, when the last stage of the
+    /// buffer is set to overflow.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overflow_sink: Option<ComponentKey>,
+
+    /// The maximum number of bytes per second the sink sends, over HTTP.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub egress_bytes_per_sec: Option<NonZeroU64>,
+
+    /// The paths and hosts the sink can access.
+    #[serde(
+        default,
+        skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
+    )]
+    pub sandbox: SandboxConfig,
+
     #[serde(flatten)]
     pub inner: Box<dyn SinkConfig>,
 }
@@ -37,10 +92,16 @@ impl<T> SinkOuter<T> {
         SinkOuter {
             inputs,
             buffer: Default::default(),
+            disk_buffer_weight: None,
             healthcheck: SinkHealthcheckOptions::default(),
             healthcheck_uri: None,
             inner,
             proxy: Default::default(),
+            check_sequence_numbers: false,
+            dead_letter: None,
+            overflow_sink: None,
+            egress_bytes_per_sec: None,
+            sandbox: Default::default(),
         }
     }
 
@@ -89,9 +150,15 @@ impl<T> SinkOuter<T> {
             inputs,
             inner: self.inner,
             buffer: self.buffer,
+            disk_buffer_weight: self.disk_buffer_weight,
             healthcheck: self.healthcheck,
             healthcheck_uri: self.healthcheck_uri,
             proxy: self.proxy,
+            check_sequence_numbers: self.check_sequence_numbers,
+            dead_letter: self.dead_letter,
+            overflow_sink: self.overflow_sink,
+            egress_bytes_per_sec: self.egress_bytes_per_sec,
+            sandbox: self.sandbox,
         }
     }
 }
@@ -144,6 +211,18 @@ pub trait SinkConfig: core::fmt::Debug + Send + Sync {
         Vec::new()
     }
 
+    /// The paths and hosts the sink needs access to, which its sandbox must allow. The ports of
+    /// its resources are checked as well.
+    fn capabilities(&self) -> Vec<Capability> {
+        Vec::new()
+    }
+
+    /// Whether the sink is confined to its sandbox at runtime. A sandbox can only be given to the
+    /// sinks which are, as it wouldn't restrict the others.
+    fn supports_sandbox(&self) -> bool {
+        false
+    }
+
     fn acknowledgements(&self) -> Option<&AcknowledgementsConfig>;
 }
 
@@ -154,6 +233,15 @@ pub struct SinkContext {
     pub globals: GlobalOptions,
     pub proxy: ProxyConfig,
     pub schema: schema::Options,
+    /// The schema definition of the events the sink receives, merged from its inputs. It's empty
+    /// unless schema support is enabled.
+    pub input_definition: crate::schema::Definition,
+    /// The secret backends of the configuration, for the sinks refreshing their secrets while
+    /// running.
+    pub secret_backends: IndexMap<ComponentKey, Box<dyn SecretBackend>>,
+    /// Whether the events the sink fails to deliver are routed to a dead letter, in which case the
+    /// sink may fail them rather than retry them indefinitely.
+    pub dead_letter: bool,
 }
 
 impl SinkContext {
@@ -165,6 +253,9 @@ impl SinkContext {
             globals: GlobalOptions::default(),
             proxy: ProxyConfig::default(),
             schema: schema::Options::default(),
+            input_definition: crate::schema::Definition::empty(),
+            secret_backends: IndexMap::new(),
+            dead_letter: false,
         }
     }
 
@@ -179,6 +270,10 @@ impl SinkContext {
     pub const fn proxy(&self) -> &ProxyConfig {
         &self.proxy
     }
+
+    pub fn secret_backend(&self, name: &str) -> Option<Box<dyn SecretBackend>> {
+        self.secret_backends.get(&ComponentKey::from(name)).cloned()
+    }
 }
 
 pub type SinkDescription = ComponentDescription<Box<dyn SinkConfig>>;
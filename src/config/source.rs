@@ -5,7 +5,7 @@ use component::ComponentDescription;
 use serde::{Deserialize, Serialize};
 use vector_core::config::{AcknowledgementsConfig, GlobalOptions, Output};
 
-use super::{component, schema, ComponentKey, ProxyConfig, Resource};
+use super::{component, schema, Capability, ComponentKey, ProxyConfig, Resource, SandboxConfig};
 use crate::{shutdown::ShutdownSignal, sources, SourceSender};
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -15,6 +15,18 @@ pub struct SourceOuter {
         skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
     )]
     pub proxy: ProxyConfig,
+    /// Whether to number the events sent by each output, so sinks can check none are missing.
+    #[serde(
+        default,
+        skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
+    )]
+    pub sequence_numbers: bool,
+    /// The paths and hosts the source can access.
+    #[serde(
+        default,
+        skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
+    )]
+    pub sandbox: SandboxConfig,
     #[serde(flatten)]
     pub(crate) inner: Box<dyn SourceConfig>,
     #[serde(default, skip)]
@@ -26,6 +38,8 @@ impl SourceOuter {
         Self {
             inner: Box::new(source),
             proxy: Default::default(),
+            sequence_numbers: false,
+            sandbox: Default::default(),
             sink_acknowledgements: false,
         }
     }
@@ -45,6 +59,18 @@ pub trait SourceConfig: core::fmt::Debug + Send + Sync {
         Vec::new()
     }
 
+    /// The paths and hosts the source needs access to, which its sandbox must allow. The ports
+    /// of its resources are checked as well.
+    fn capabilities(&self) -> Vec<Capability> {
+        Vec::new()
+    }
+
+    /// Whether the source is confined to its sandbox at runtime. A sandbox can only be given to
+    /// the sources which are, as it wouldn't restrict the others.
+    fn supports_sandbox(&self) -> bool {
+        false
+    }
+
     fn can_acknowledge(&self) -> bool;
 }
 
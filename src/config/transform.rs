@@ -5,12 +5,18 @@ use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use vector_core::transform::TransformConfig;
 
-use super::{component, ComponentKey};
+use super::{component, ComponentKey, SandboxConfig};
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct TransformOuter<T> {
     #[serde(default = "Default::default")] // https://github.com/serde-rs/serde/issues/1541
     pub inputs: Vec<T>,
+    /// The paths and hosts the transform can access.
+    #[serde(
+        default,
+        skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
+    )]
+    pub sandbox: SandboxConfig,
     #[serde(flatten)]
     pub inner: Box<dyn TransformConfig>,
 }
@@ -20,6 +26,7 @@ impl<T> TransformOuter<T> {
     pub(super) fn new(inputs: Vec<T>, transform: impl TransformConfig + 'static) -> Self {
         TransformOuter {
             inputs,
+            sandbox: Default::default(),
             inner: Box::new(transform),
         }
     }
@@ -32,6 +39,7 @@ impl<T> TransformOuter<T> {
     pub(crate) fn with_inputs<U>(self, inputs: Vec<U>) -> TransformOuter<U> {
         TransformOuter {
             inputs,
+            sandbox: self.sandbox,
             inner: self.inner,
         }
     }
@@ -74,8 +82,10 @@ impl TransformOuter<String> {
             );
 
             for (inner_name, inner_transform) in inner_topology.inner {
+                // The transforms a transform expands into are confined to its sandbox.
                 let child = TransformOuter {
                     inputs: inner_transform.inputs,
+                    sandbox: self.sandbox.clone(),
                     inner: inner_transform.inner,
                 };
                 children.push(inner_name.clone());
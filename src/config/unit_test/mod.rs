@@ -5,6 +5,7 @@ mod unit_test_components;
 use std::{
     collections::{HashMap, HashSet},
     sync::Arc,
+    time::Duration,
 };
 
 use futures_util::{stream::FuturesUnordered, StreamExt};
@@ -15,6 +16,7 @@ use tokio::sync::{
     Mutex,
 };
 use uuid::Uuid;
+use vector_core::time::{Clock, MockClock};
 
 use self::unit_test_components::{
     UnitTestSinkCheck, UnitTestSinkConfig, UnitTestSinkResult, UnitTestSourceConfig,
@@ -369,6 +371,7 @@ async fn build_unit_test(
     let test = test.resolve_outputs(&transform_only_graph, &expansions)?;
 
     let sources = metadata.hydrate_into_sources(&test.inputs)?;
+    let clock_advances = build_clock_advances(&test.inputs);
     let (test_result_rxs, sinks) =
         metadata.hydrate_into_sinks(&test.name, &test.outputs, &test.no_outputs_from)?;
 
@@ -435,7 +438,12 @@ async fn build_unit_test(
     }
     let config = config_builder.build()?;
     let diff = config::ConfigDiff::initial(&config);
-    let pieces = builder::build_pieces(&config, &diff, HashMap::new()).await?;
+    // Time only passes when the inputs say so while a test runs, so that time-based transforms
+    // (e.g. `throttle` or `dedupe` with a TTL) behave the same way every time the test is run.
+    let clock = Clock::Mock(MockClock::new());
+    let pieces =
+        builder::build_pieces_with_clock(&config, &diff, HashMap::new(), clock, clock_advances)
+            .await?;
 
     Ok(UnitTest {
         name: test.name,
@@ -536,6 +544,26 @@ fn build_and_validate_inputs(
     }
 }
 
+/// For each transform inputs are inserted at, the number of events it receives before the clock of
+/// the test is moved forward, and by how much.
+fn build_clock_advances(
+    test_inputs: &[TestInput],
+) -> HashMap<ComponentKey, Vec<(usize, Duration)>> {
+    let mut received = HashMap::new();
+    let mut advances = HashMap::new();
+    for input in test_inputs {
+        let count: &mut usize = received.entry(input.insert_at.clone()).or_default();
+        if let Some(secs) = input.advance_secs.filter(|secs| *secs > 0) {
+            advances
+                .entry(input.insert_at.clone())
+                .or_insert_with(Vec::new)
+                .push((*count, Duration::from_secs(secs)));
+        }
+        *count += 1;
+    }
+    advances
+}
+
 fn build_outputs(
     test_outputs: &[TestOutput],
 ) -> Result<IndexMap<OneOrMany<OutputId>, Vec<Vec<Condition>>>, Vec<String>> {
@@ -1198,3 +1198,57 @@ async fn test_glob_input() {
     let mut tests = build_unit_tests(config).await.unwrap();
     assert!(tests.remove(0).run().await.errors.is_empty());
 }
+
+#[tokio::test]
+async fn test_advance_time() {
+    let config: ConfigBuilder = toml::from_str(indoc! {r#"
+        [transforms.dedupe]
+          inputs = ["ignored"]
+          type = "dedupe"
+          fields.match = ["message"]
+          cache.num_events = 10
+          cache.ttl_secs = 10
+
+        [[tests]]
+          name = "advance time"
+
+          [[tests.inputs]]
+            insert_at = "dedupe"
+            type = "log"
+            [tests.inputs.log_fields]
+              message = "duplicate"
+              count = 1
+
+          [[tests.inputs]]
+            insert_at = "dedupe"
+            type = "log"
+            [tests.inputs.log_fields]
+              message = "duplicate"
+              count = 2
+
+          [[tests.inputs]]
+            insert_at = "dedupe"
+            type = "log"
+            advance_secs = 20
+            [tests.inputs.log_fields]
+              message = "duplicate"
+              count = 3
+
+          [[tests.outputs]]
+            extract_from = "dedupe"
+            [[tests.outputs.conditions]]
+              type = "vrl"
+              source = """
+                assert_eq!(.count, 1)
+              """
+            [[tests.outputs.conditions]]
+              type = "vrl"
+              source = """
+                assert_eq!(.count, 3)
+              """
+    "#})
+    .unwrap();
+
+    let mut tests = build_unit_tests(config).await.unwrap();
+    assert!(tests.remove(0).run().await.errors.is_empty());
+}
@@ -1,8 +1,12 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
+use vector_buffers::WhenFull;
 use vector_core::internal_event::DEFAULT_OUTPUT;
 
-use super::{builder::ConfigBuilder, schema, ComponentKey, Config, OutputId, Resource};
+use super::{
+    builder::ConfigBuilder, sandbox, schema, ComponentKey, Config, OutputId, Resource, Sandbox,
+    SandboxConfig,
+};
 
 /// Check that provide + topology config aren't present in the same builder, which is an error.
 pub fn check_provider(config: &ConfigBuilder) -> Result<(), Vec<String>> {
@@ -73,6 +77,13 @@ pub fn check_shape(config: &ConfigBuilder) -> Result<(), Vec<String>> {
     }
 
     // Warnings and errors
+    // Dead letter and overflow components receive the events of their sinks, and may have no
+    // inputs otherwise.
+    let routed_to = config
+        .sinks
+        .values()
+        .flat_map(|sink| sink.dead_letter.iter().chain(sink.overflow_sink.iter()))
+        .collect::<HashSet<_>>();
     let sink_inputs = config
         .sinks
         .iter()
@@ -82,7 +93,7 @@ pub fn check_shape(config: &ConfigBuilder) -> Result<(), Vec<String>> {
         .iter()
         .map(|(key, transform)| ("transform", key.clone(), transform.inputs.clone()));
     for (output_type, key, inputs) in sink_inputs.chain(transform_inputs) {
-        if inputs.is_empty() {
+        if inputs.is_empty() && !routed_to.contains(&key) {
             errors.push(format!(
                 "{} \"{}\" has no inputs",
                 capitalize(output_type),
@@ -107,6 +118,27 @@ pub fn check_shape(config: &ConfigBuilder) -> Result<(), Vec<String>> {
         }
     }
 
+    // Only the last stage of a buffer can overflow to the overflow sink, and it can't overflow
+    // anywhere else.
+    for (key, sink) in &config.sinks {
+        let overflows = sink
+            .buffer
+            .stages()
+            .last()
+            .map_or(false, |stage| stage.when_full() == WhenFull::Overflow);
+        match (overflows, &sink.overflow_sink) {
+            (true, None) => errors.push(format!(
+                "Sink \"{}\" has the last stage of its buffer set to overflow, but no `overflow_sink`.",
+                key
+            )),
+            (false, Some(_)) => errors.push(format!(
+                "Sink \"{}\" has an `overflow_sink`, but the last stage of its buffer isn't set to overflow.",
+                key
+            )),
+            _ => {}
+        }
+    }
+
     if errors.is_empty() {
         Ok(())
     } else {
@@ -141,6 +173,86 @@ pub fn check_resources(config: &ConfigBuilder) -> Result<(), Vec<String>> {
     }
 }
 
+/// Check that the sandbox of each component allows the capabilities it declares, and the ports it
+/// listens on. Only the components confined to their sandbox at runtime can be given one.
+pub fn check_sandboxes(config: &ConfigBuilder) -> Result<(), Vec<String>> {
+    let sources = config.sources.iter().map(|(key, source)| {
+        let mut capabilities = source.inner.capabilities();
+        capabilities.extend(
+            source
+                .inner
+                .resources()
+                .iter()
+                .filter_map(sandbox::resource_capability),
+        );
+        (
+            "Source",
+            key,
+            source.inner.source_type(),
+            &source.sandbox,
+            source.inner.supports_sandbox(),
+            capabilities,
+        )
+    });
+    let transforms = config.transforms.iter().map(|(key, transform)| {
+        (
+            "Transform",
+            key,
+            transform.inner.transform_type(),
+            &transform.sandbox,
+            transform.inner.supports_sandbox(),
+            transform.inner.capabilities(),
+        )
+    });
+    let sinks = config.sinks.iter().map(|(key, sink)| {
+        let mut capabilities = sink.inner.capabilities();
+        capabilities.extend(
+            sink.resources(key)
+                .iter()
+                .filter_map(sandbox::resource_capability),
+        );
+        (
+            "Sink",
+            key,
+            sink.inner.sink_type(),
+            &sink.sandbox,
+            sink.inner.supports_sandbox(),
+            capabilities,
+        )
+    });
+
+    let errors = sources
+        .chain(transforms)
+        .chain(sinks)
+        .flat_map(
+            |(kind, key, component_type, sandbox, supported, capabilities)| {
+                if !supported && *sandbox != SandboxConfig::default() {
+                    return vec![format!(
+                        "{} \"{}\": The `sandbox` option isn't supported by `{}` components, which aren't confined to it.",
+                        kind, key, component_type
+                    )];
+                }
+                let sandbox = Sandbox::new(sandbox);
+                capabilities
+                    .into_iter()
+                    .filter_map(|capability| {
+                        sandbox
+                            .check(&capability)
+                            .err()
+                            .map(|error| format!("{} \"{}\": {}", kind, key, error))
+                    })
+                    .collect()
+            },
+        )
+        .collect::<Vec<_>>();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
 /// To avoid collisions between `output` metric tags, check that a component
 /// does not have a named output with the name [`DEFAULT_OUTPUT`]
 pub fn check_outputs(config: &ConfigBuilder) -> Result<(), Vec<String>> {
@@ -232,9 +344,69 @@ pub fn warnings(config: &Config) -> Vec<String> {
         }
     }
 
+    if config.schema.enabled {
+        warnings.extend(crate::topology::unproduced_field_warnings(config));
+    }
+
+    warnings.extend(acknowledgement_warnings(config));
+
     warnings
 }
 
+/// Lists the components breaking the chain of end-to-end acknowledgements: the sinks that don't
+/// support acknowledgements while they are globally enabled, and the sources that can't
+/// acknowledge the events they send to sinks with acknowledgements enabled.
+fn acknowledgement_warnings(config: &Config) -> Option<String> {
+    let mut components = Vec::new();
+
+    if config.global.acknowledgements.enabled() {
+        components.extend(
+            config
+                .sinks
+                .iter()
+                .filter(|(_, sink)| sink.inner.acknowledgements().is_none())
+                .map(|(key, _)| format!("sink \"{}\"", key)),
+        );
+    }
+
+    let mut inputs: Vec<&OutputId> = config
+        .sinks
+        .values()
+        .filter(|sink| {
+            sink.inner
+                .acknowledgements()
+                .unwrap_or(&config.global.acknowledgements)
+                .merge_default(&config.global.acknowledgements)
+                .enabled()
+        })
+        .flat_map(|sink| sink.inputs.iter())
+        .collect();
+    let mut visited = HashSet::new();
+    let mut sources = Vec::new();
+    while let Some(input) = inputs.pop() {
+        let component = &input.component;
+        if !visited.insert(component) {
+            continue;
+        }
+        if let Some(source) = config.sources.get(component) {
+            if !source.inner.can_acknowledge() {
+                sources.push(format!("source \"{}\"", component));
+            }
+        } else if let Some(transform) = config.transforms.get(component) {
+            inputs.extend(transform.inputs.iter());
+        }
+    }
+    sources.sort();
+    components.extend(sources);
+
+    (!components.is_empty()).then(|| {
+        format!(
+            "Acknowledgements are enabled but not supported by {}. Silent data loss could occur.",
+            components.join(", ")
+        )
+    })
+}
+
 fn capitalize(s: &str) -> String {
     let mut s = s.to_owned();
     if let Some(r) = s.get_mut(0..1) {
@@ -0,0 +1,249 @@
+//! Samples of the events components drop.
+//!
+//! When enabled with the `api.dropped_event_samples` option, the events components drop, for
+//! instance because they didn't match a filter, were throttled, or failed to be processed or
+//! encoded, are sampled so that they can be inspected through the API, without having to
+//! reproduce the issue. A bounded reservoir of samples is kept for each component, so that the
+//! samples are representative of all the events the component dropped, and the sampled events can
+//! be redacted with a VRL program before they're kept.
+//!
+//! Components report the events they drop with [`sample`], which attributes them to the component
+//! whose task is running. Tasks are scoped to their component by the topology, with
+//! [`component_scope::scope`].
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex, RwLock},
+};
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use vector_common::TimeZone;
+use vector_core::event::{Event, Finalizable, TargetEvents, VrlTarget};
+use vrl::{diagnostic::Formatter, Program, Runtime};
+
+use crate::{component_scope, config::ComponentKey, internal_events::DroppedEventRedactionError};
+
+static SAMPLER: Lazy<RwLock<Option<Arc<Sampler>>>> = Lazy::new(Default::default);
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct DroppedEventSamplesOptions {
+    pub enabled: bool,
+    /// The number of events sampled for each component.
+    pub size: usize,
+    /// A VRL program redacting the sampled events before they're kept. Events the program fails
+    /// on aren't kept.
+    pub redact: Option<String>,
+}
+
+impl Default for DroppedEventSamplesOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            size: 10,
+            redact: None,
+        }
+    }
+}
+
+impl DroppedEventSamplesOptions {
+    /// Compiles the redaction program, which is used to validate the options.
+    pub fn compile_redact(&self) -> Result<Option<Program>, String> {
+        let source = match &self.redact {
+            Some(source) => source,
+            None => return Ok(None),
+        };
+
+        let functions = vrl_stdlib::all()
+            .into_iter()
+            .chain(vector_vrl_functions::vrl_functions())
+            .collect::<Vec<_>>();
+        let mut state = vrl::state::ExternalEnv::default();
+        vrl::compile_with_state(source, &functions, &mut state)
+            .map(|(program, _warnings)| Some(program))
+            .map_err(|diagnostics| Formatter::new(source, diagnostics).to_string())
+    }
+}
+
+/// A sample of the events dropped by a component.
+#[derive(Clone, Debug)]
+pub struct DroppedEventSample {
+    pub timestamp: DateTime<Utc>,
+    /// Why the event was dropped.
+    pub reason: String,
+    /// The event, once redacted.
+    pub event: Event,
+}
+
+struct Sampler {
+    options: DroppedEventSamplesOptions,
+    redact: Option<Program>,
+    reservoirs: Mutex<HashMap<ComponentKey, Reservoir>>,
+}
+
+#[derive(Default)]
+struct Reservoir {
+    /// The number of events the component dropped.
+    dropped: u64,
+    samples: Vec<DroppedEventSample>,
+}
+
+impl Sampler {
+    fn record(&self, component: &ComponentKey, event: &Event, reason: &dyn fmt::Display) {
+        // The slot of the sample is picked under the lock, while the event is copied and redacted
+        // outside of it, for components dropping events not to wait on each other.
+        let index = {
+            let mut reservoirs = self.reservoirs.lock().expect("reservoirs lock poisoned");
+            let reservoir = reservoirs.entry(component.clone()).or_default();
+            reservoir.dropped += 1;
+
+            // Each of the events dropped so far has the same chance of being sampled.
+            let dropped = reservoir.dropped;
+            if dropped <= self.options.size as u64 {
+                dropped as usize - 1
+            } else {
+                match rand::thread_rng().gen_range(0..dropped) as usize {
+                    index if index < self.options.size => index,
+                    _ => return,
+                }
+            }
+        };
+
+        if let Some(event) = self.redact(event) {
+            let sample = DroppedEventSample {
+                timestamp: Utc::now(),
+                reason: reason.to_string(),
+                event,
+            };
+            let mut reservoirs = self.reservoirs.lock().expect("reservoirs lock poisoned");
+            if let Some(reservoir) = reservoirs.get_mut(component) {
+                // The samples of the slots before this one may still be redacted, or may have
+                // failed to be.
+                if index < reservoir.samples.len() {
+                    reservoir.samples[index] = sample;
+                } else if reservoir.samples.len() < self.options.size {
+                    reservoir.samples.push(sample);
+                }
+            }
+        }
+    }
+
+    fn redact(&self, event: &Event) -> Option<Event> {
+        let mut event = event.clone();
+        // The sample mustn't hold up the acknowledgement of the event.
+        drop(event.take_finalizers());
+
+        let program = match &self.redact {
+            Some(program) => program,
+            None => return Some(event),
+        };
+
+        let mut target = VrlTarget::new(event, program.info());
+        match Runtime::default().resolve(&mut target, program, &TimeZone::default()) {
+            Ok(_) => match target.into_events() {
+                TargetEvents::One(event) => Some(event),
+                TargetEvents::Logs(mut events) => events.next(),
+                TargetEvents::Traces(mut events) => events.next(),
+            },
+            Err(error) => {
+                emit!(DroppedEventRedactionError { error });
+                None
+            }
+        }
+    }
+}
+
+/// Applies the options, keeping the samples taken so far unless the options changed.
+pub fn configure(options: &DroppedEventSamplesOptions) -> Result<(), String> {
+    let mut sampler = SAMPLER.write().expect("sampler lock poisoned");
+    if !options.enabled || options.size == 0 {
+        *sampler = None;
+    } else if sampler
+        .as_ref()
+        .map_or(true, |sampler| sampler.options != *options)
+    {
+        *sampler = Some(Arc::new(Sampler {
+            options: options.clone(),
+            redact: options.compile_redact()?,
+            reservoirs: Mutex::default(),
+        }));
+    }
+    Ok(())
+}
+
+/// Samples an event the component whose task is running dropped.
+pub fn sample(event: &Event, reason: impl fmt::Display) {
+    let sampler = match &*SAMPLER.read().expect("sampler lock poisoned") {
+        Some(sampler) => Arc::clone(sampler),
+        None => return,
+    };
+    // Events dropped outside of components, such as in tests, aren't sampled.
+    if let Some(component) = component_scope::current() {
+        sampler.record(&component, event, &reason);
+    }
+}
+
+/// Returns the samples of the events the component dropped, along with the number of events it
+/// dropped.
+pub fn samples(component: &ComponentKey) -> (u64, Vec<DroppedEventSample>) {
+    SAMPLER
+        .read()
+        .expect("sampler lock poisoned")
+        .as_ref()
+        .and_then(|sampler| {
+            let reservoirs = sampler.reservoirs.lock().expect("reservoirs lock poisoned");
+            reservoirs
+                .get(component)
+                .map(|reservoir| (reservoir.dropped, reservoir.samples.clone()))
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::LogEvent;
+
+    // The sampler is global, so the tests share a single one.
+    #[tokio::test]
+    async fn samples_and_redacts_dropped_events() {
+        configure(&DroppedEventSamplesOptions {
+            enabled: true,
+            size: 3,
+            redact: Some("del(.password)".into()),
+        })
+        .unwrap();
+
+        let component = ComponentKey::from("filter");
+        component_scope::scope(component.clone(), async {
+            for index in 0..10 {
+                let mut log = LogEvent::from(format!("event {}", index));
+                log.insert("password", "hunter2");
+                sample(&log.into(), "filtered");
+            }
+        })
+        .await;
+        // Events dropped outside of components aren't sampled.
+        sample(&LogEvent::from("outside").into(), "filtered");
+
+        let (dropped, samples) = samples(&component);
+        assert_eq!(dropped, 10);
+        assert_eq!(samples.len(), 3);
+        for sample in samples {
+            assert_eq!(sample.reason, "filtered");
+            assert!(sample.event.as_log().get("password").is_none());
+            assert!(sample.event.as_log().get("message").is_some());
+        }
+
+        assert!(configure(&DroppedEventSamplesOptions {
+            enabled: true,
+            size: 3,
+            redact: Some("del(.password".into()),
+        })
+        .is_err());
+    }
+}
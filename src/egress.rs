@@ -0,0 +1,337 @@
+//! Egress rate limits, in bytes per second.
+//!
+//! The bytes sinks send can be limited per sink, with the `egress_bytes_per_sec` option of the
+//! sink, and across all the sinks of the process, with the global `egress_bytes_per_sec` option,
+//! for users on metered links. The limits apply to the requests sent with the HTTP client, which
+//! counts the bytes of their bodies against the budget of its sink and the global one as they are
+//! sent, streaming bodies included.
+//!
+//! Once the bytes sent run ahead of the budget, the requests of sinks are held back by the
+//! [`EgressLayer`] set above their rate and concurrency limits, retries and timeout, so that the
+//! time requests wait for the budget doesn't count against their timeout nor their round-trip
+//! time. The HTTP clients of sinks without this layer hold their requests back themselves.
+//!
+//! Clients are built along with their sink, so they take the limit of the sink being built, set by
+//! the topology with [`EgressLimit::scope`]. Sinks that don't build any HTTP client can't honour
+//! their limit, which the topology rejects, nor the global one, which it warns about. The global
+//! limit is set by [`configure`] when the topology starts, and again by the reloads changing it.
+
+use std::{
+    fmt,
+    future::Future,
+    num::NonZeroU64,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, RwLock,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures::ready;
+use once_cell::sync::Lazy;
+use tokio::time::{sleep, Instant, Sleep};
+use tower::{Layer, Service};
+
+use crate::internal_events::EgressRateLimited;
+
+tokio::task_local! {
+    static CURRENT: EgressLimit;
+}
+
+static GLOBAL: Lazy<RwLock<Option<Arc<Bucket>>>> = Lazy::new(Default::default);
+
+/// Bytes can be sent up to a second ahead of the rate, in a burst.
+const BURST: Duration = Duration::from_secs(1);
+
+/// A budget of bytes per second, shared by the requests it limits.
+///
+/// The bytes sent are recorded as they are, delaying the requests after them once the bytes sent
+/// run ahead of the rate by more than a burst. A request larger than a burst is sent on its own,
+/// and the requests after it wait for the rate to catch up.
+#[derive(Debug)]
+struct Bucket {
+    bytes_per_sec: NonZeroU64,
+    /// The time at which the bytes recorded so far are sent, at the rate.
+    reserved_until: Mutex<Instant>,
+}
+
+impl Bucket {
+    fn new(bytes_per_sec: NonZeroU64) -> Self {
+        Self {
+            bytes_per_sec,
+            reserved_until: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Records the bytes sent.
+    fn record(&self, bytes: u64) {
+        let cost = Duration::from_secs_f64(bytes as f64 / self.bytes_per_sec.get() as f64);
+        let now = Instant::now();
+        let mut reserved_until = self.reserved_until.lock().expect("bucket lock poisoned");
+        *reserved_until = (*reserved_until).max(now) + cost;
+    }
+
+    /// Returns how long to wait before sending more bytes.
+    fn delay(&self) -> Duration {
+        let reserved_until = *self.reserved_until.lock().expect("bucket lock poisoned");
+        reserved_until
+            .saturating_duration_since(Instant::now())
+            .saturating_sub(BURST)
+    }
+}
+
+/// The egress limit of a sink, enforced along with the global one.
+///
+/// The default limit, taken outside of sinks, such as by sources scraping HTTP endpoints, doesn't
+/// limit requests, nor count them against the global limit.
+#[derive(Clone, Debug, Default)]
+pub struct EgressLimit {
+    /// Unlimited when unset.
+    bucket: Option<Arc<Bucket>>,
+    global: bool,
+    /// Whether an HTTP client sends the bytes of the sink, counting them against the limit.
+    enforced: Arc<AtomicBool>,
+    /// Whether the requests of the sink are held back by an [`EgressLayer`], rather than by the
+    /// HTTP clients.
+    layered: Arc<AtomicBool>,
+}
+
+impl EgressLimit {
+    pub fn new(bytes_per_sec: Option<NonZeroU64>) -> Self {
+        Self {
+            bucket: bytes_per_sec.map(|bytes_per_sec| Arc::new(Bucket::new(bytes_per_sec))),
+            global: true,
+            ..Self::default()
+        }
+    }
+
+    /// The limit of the sink being built.
+    pub fn current() -> Self {
+        CURRENT.try_with(Clone::clone).unwrap_or_default()
+    }
+
+    /// The limit of the sink being built, for the HTTP client sending its bytes.
+    pub fn capture() -> Self {
+        let limit = Self::current();
+        limit.enforced.store(true, Ordering::Relaxed);
+        limit
+    }
+
+    /// The layer holding back the requests of the sink being built until its limit allows for
+    /// sending more bytes.
+    pub fn layer() -> EgressLayer {
+        let limit = Self::current();
+        limit.layered.store(true, Ordering::Relaxed);
+        EgressLayer { limit }
+    }
+
+    /// Runs `future` within this limit.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT.scope(self, future).await
+    }
+
+    /// Whether the sink limits the bytes it sends on top of the global limit.
+    pub const fn is_limited(&self) -> bool {
+        self.bucket.is_some()
+    }
+
+    /// Whether the bytes of the sink are sent with an HTTP client, which counts them against this
+    /// limit.
+    pub fn is_enforced(&self) -> bool {
+        self.enforced.load(Ordering::Relaxed)
+    }
+
+    /// Whether the requests of the sink are held back by an [`EgressLayer`].
+    pub fn is_layered(&self) -> bool {
+        self.layered.load(Ordering::Relaxed)
+    }
+
+    /// Records the bytes sent against both this limit and the global one.
+    pub fn record(&self, bytes: u64) {
+        if let Some(bucket) = &self.bucket {
+            bucket.record(bytes);
+        }
+        if let Some(bucket) = self.global() {
+            bucket.record(bytes);
+        }
+    }
+
+    /// Waits until both this limit and the global one allow for sending more bytes.
+    pub async fn ready(&self) {
+        while let Some((scope, delay)) = self.delay() {
+            emit!(EgressRateLimited { scope, delay });
+            sleep(delay).await;
+        }
+    }
+
+    /// Returns the longest of the delays of this limit and the global one, if any.
+    fn delay(&self) -> Option<(&'static str, Duration)> {
+        let component = self
+            .bucket
+            .as_ref()
+            .map(|bucket| ("component", bucket.delay()));
+        let global = self.global().map(|bucket| ("global", bucket.delay()));
+        component
+            .into_iter()
+            .chain(global)
+            .filter(|(_, delay)| !delay.is_zero())
+            .max_by_key(|(_, delay)| *delay)
+    }
+
+    fn global(&self) -> Option<Arc<Bucket>> {
+        if self.global {
+            GLOBAL.read().expect("global limit lock poisoned").clone()
+        } else {
+            None
+        }
+    }
+}
+
+/// Holds back the requests of a sink until its egress limit allows for sending more bytes.
+///
+/// This sits above the concurrency limit, retries and timeout of the sink, so that waiting for the
+/// budget neither times requests out nor skews the round-trip time they are measured with.
+#[derive(Clone, Debug)]
+pub struct EgressLayer {
+    limit: EgressLimit,
+}
+
+impl<S> Layer<S> for EgressLayer {
+    type Service = Egress<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Egress {
+            inner,
+            limit: self.limit.clone(),
+            sleep: None,
+        }
+    }
+}
+
+pub struct Egress<S> {
+    inner: S,
+    limit: EgressLimit,
+    sleep: Option<Pin<Box<Sleep>>>,
+}
+
+impl<S, Request> Service<Request> for Egress<S>
+where
+    S: Service<Request>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        loop {
+            if let Some(sleep) = &mut self.sleep {
+                ready!(sleep.as_mut().poll(cx));
+                self.sleep = None;
+            }
+            match self.limit.delay() {
+                Some((scope, delay)) => {
+                    emit!(EgressRateLimited { scope, delay });
+                    self.sleep = Some(Box::pin(sleep(delay)));
+                }
+                None => break,
+            }
+        }
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        self.inner.call(request)
+    }
+}
+
+impl<S: Clone> Clone for Egress<S> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            limit: self.limit.clone(),
+            sleep: None,
+        }
+    }
+}
+
+impl<S: fmt::Debug> fmt::Debug for Egress<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Egress")
+            .field("inner", &self.inner)
+            .field("limit", &self.limit)
+            .finish()
+    }
+}
+
+/// Sets the global limit, shared by all the sinks. The budget is kept unless the rate changed.
+pub fn configure(bytes_per_sec: Option<NonZeroU64>) {
+    let mut global = GLOBAL.write().expect("global limit lock poisoned");
+    if global.as_ref().map(|bucket| bucket.bytes_per_sec) != bytes_per_sec {
+        *global = bytes_per_sec.map(|bytes_per_sec| Arc::new(Bucket::new(bytes_per_sec)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn delays_bytes_beyond_burst() {
+        tokio::time::pause();
+        let bucket = Bucket::new(NonZeroU64::new(1000).unwrap());
+
+        // Bytes sent up to a second ahead of the rate don't delay the ones after them.
+        bucket.record(500);
+        assert_eq!(bucket.delay(), Duration::ZERO);
+        bucket.record(500);
+        assert_eq!(bucket.delay(), Duration::ZERO);
+        bucket.record(1000);
+        assert_eq!(bucket.delay(), Duration::from_secs(1));
+
+        // The budget recovers at the rate.
+        tokio::time::advance(Duration::from_secs(2)).await;
+        assert_eq!(bucket.delay(), Duration::ZERO);
+
+        // Requests larger than a burst delay the ones after them.
+        bucket.record(3000);
+        assert_eq!(bucket.delay(), Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn layer_holds_requests_back() {
+        tokio::time::pause();
+        let limit = EgressLimit::new(NonZeroU64::new(1000));
+        let mut service = limit
+            .clone()
+            .scope(async { EgressLimit::layer() })
+            .await
+            .layer(tower::service_fn(|()| async { Ok::<_, ()>(()) }));
+        assert!(limit.is_layered());
+
+        let before = Instant::now();
+        tower::ServiceExt::ready(&mut service).await.unwrap();
+        assert_eq!(before.elapsed(), Duration::ZERO);
+
+        limit.record(3000);
+        tower::ServiceExt::ready(&mut service).await.unwrap();
+        assert_eq!(before.elapsed(), Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn limit_is_scoped_to_sink() {
+        let limit = EgressLimit::new(NonZeroU64::new(1000));
+        assert!(EgressLimit::current().bucket.is_none());
+        assert!(!EgressLimit::current().global);
+        limit
+            .clone()
+            .scope(async {
+                assert!(EgressLimit::current().bucket.is_some());
+                assert!(EgressLimit::current().global);
+                EgressLimit::capture();
+            })
+            .await;
+        assert!(limit.is_enforced());
+    }
+}
@@ -119,6 +119,10 @@ impl FileConfig {
             delimiter,
         } = self.file.encoding;
 
+        // The modification time is taken before reading, so that if the file is
+        // written to while it's read, the table is reloaded again.
+        let modified = fs::metadata(&self.file.path)?.modified()?;
+
         let mut reader = csv::ReaderBuilder::new()
             .has_headers(include_headers)
             .delimiter(delimiter as u8)
@@ -156,8 +160,6 @@ impl FileConfig {
             headers
         );
 
-        let modified = fs::metadata(&self.file.path)?.modified()?;
-
         Ok((headers, data, modified))
     }
 }
@@ -169,7 +171,12 @@ impl EnrichmentTableConfig for FileConfig {
         &self,
         globals: &crate::config::GlobalOptions,
     ) -> crate::Result<Box<dyn Table + Send + Sync>> {
-        let (headers, data, modified) = self.load_file(globals.timezone)?;
+        // Parsing a large file takes a while, so it's kept off the runtime's
+        // worker threads.
+        let config = self.clone();
+        let timezone = globals.timezone;
+        let (headers, data, modified) =
+            tokio::task::spawn_blocking(move || config.load_file(timezone)).await??;
 
         Ok(Box::new(File::new(self.clone(), modified, data, headers)))
     }
@@ -505,6 +512,10 @@ impl Table for File {
             .and_then(|metadata| metadata.modified()),
             Ok(modified) if modified > self.last_modified)
     }
+
+    fn row_count(&self) -> Option<usize> {
+        Some(self.data.len())
+    }
 }
 
 impl std::fmt::Debug for File {
@@ -945,4 +956,38 @@ mod tests {
             file.find_table_row(Case::Sensitive, &[condition], None, Some(handle))
         );
     }
+
+    #[tokio::test]
+    async fn reloads_modified_file() {
+        let path = crate::test_util::temp_file();
+        fs::write(&path, "field1,field2\nzip,zup\n").unwrap();
+        let config = FileConfig {
+            file: FileC {
+                path: path.clone(),
+                encoding: Default::default(),
+            },
+            schema: HashMap::new(),
+        };
+
+        let table = config.build(&Default::default()).await.unwrap();
+        assert_eq!(table.row_count(), Some(1));
+        assert!(!table.needs_reload());
+
+        // The table is taken to be loaded a while before the file is written,
+        // rather than waiting for the modification time to change on file
+        // systems with a coarse resolution.
+        let (headers, data, modified) = config.load_file(Default::default()).unwrap();
+        let table = File::new(
+            config.clone(),
+            modified - std::time::Duration::from_secs(2),
+            data,
+            headers,
+        );
+        fs::write(&path, "field1,field2\nzip,zup\nzirp,zurp\n").unwrap();
+        assert!(table.needs_reload());
+
+        let table = config.build(&Default::default()).await.unwrap();
+        assert_eq!(table.row_count(), Some(2));
+        assert!(!table.needs_reload());
+    }
 }
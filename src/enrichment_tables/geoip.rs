@@ -0,0 +1,406 @@
+use std::{collections::BTreeMap, fs, net::IpAddr, sync::Arc, time::SystemTime};
+
+use enrichment::{Case, Condition, IndexHandle, Table};
+use maxminddb::{
+    geoip2::{City, Isp},
+    MaxMindDBError, Reader,
+};
+use serde::{Deserialize, Serialize};
+use value::Value;
+
+use crate::config::{EnrichmentTableConfig, EnrichmentTableDescription, GenerateConfig};
+
+// MaxMind GeoIP database files have a type field we can use to recognize specific
+// products. If we encounter one of these two types, we look for ASN/ISP information;
+// otherwise we expect to be working with a City database.
+const ASN_DATABASE_TYPE: &str = "GeoLite2-ASN";
+const ISP_DATABASE_TYPE: &str = "GeoIP2-ISP";
+
+/// The only field the table can be searched by.
+const IP_FIELD: &str = "ip";
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct GeoipConfig {
+    /// The path of a MaxMind GeoIP2 or GeoLite2 database, of the City, ISP or ASN type.
+    pub path: String,
+    #[serde(default = "default_locale")]
+    pub locale: String,
+}
+
+// valid locales are: “de”, "en", “es”, “fr”, “ja”, “pt-BR”, “ru”, and “zh-CN”
+//
+// https://dev.maxmind.com/geoip/docs/databases/city-and-country?lang=en
+fn default_locale() -> String {
+    "en".to_string()
+}
+
+impl GenerateConfig for GeoipConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            path: "/path/to/GeoLite2-City.mmdb".to_string(),
+            locale: default_locale(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "geoip")]
+impl EnrichmentTableConfig for GeoipConfig {
+    async fn build(
+        &self,
+        _globals: &crate::config::GlobalOptions,
+    ) -> crate::Result<Box<dyn Table + Send + Sync>> {
+        Ok(Box::new(Geoip::new(self.clone())?))
+    }
+}
+
+inventory::submit! {
+    EnrichmentTableDescription::new::<GeoipConfig>("geoip")
+}
+
+#[derive(Clone)]
+pub struct Geoip {
+    config: GeoipConfig,
+    dbreader: Arc<Reader<Vec<u8>>>,
+    last_modified: SystemTime,
+}
+
+impl Geoip {
+    pub fn new(config: GeoipConfig) -> crate::Result<Self> {
+        // The modification time is read first, so a database replaced while it's being read
+        // is loaded again.
+        let last_modified = fs::metadata(&config.path)?.modified()?;
+        let dbreader = Arc::new(Reader::open_readfile(&config.path)?);
+
+        Ok(Geoip {
+            config,
+            dbreader,
+            last_modified,
+        })
+    }
+
+    fn has_isp_db(&self) -> bool {
+        self.dbreader.metadata.database_type == ASN_DATABASE_TYPE
+            || self.dbreader.metadata.database_type == ISP_DATABASE_TYPE
+    }
+
+    /// Looks up the data of the address, with the fields of the database that aren't known
+    /// for it set to null. Addresses which aren't in the database find nothing.
+    fn lookup(
+        &self,
+        ip: IpAddr,
+        select: Option<&[String]>,
+    ) -> Result<Option<BTreeMap<String, Value>>, String> {
+        let mut map = BTreeMap::new();
+        let mut add_field = |key: &str, value: Option<Value>| {
+            if select
+                .map(|select| select.iter().any(|field| field == key))
+                // If no select is passed, we assume all fields are included
+                .unwrap_or(true)
+            {
+                map.insert(key.to_string(), value.unwrap_or(Value::Null));
+            }
+        };
+
+        if self.has_isp_db() {
+            let data = match self.dbreader.lookup::<Isp>(ip) {
+                Ok(data) => data,
+                Err(MaxMindDBError::AddressNotFoundError(_)) => return Ok(None),
+                Err(error) => return Err(format!("unable to look up IP {}: {}", ip, error)),
+            };
+
+            add_field(
+                "autonomous_system_number",
+                data.autonomous_system_number
+                    .map(|number| (number as i64).into()),
+            );
+            add_field(
+                "autonomous_system_organization",
+                data.autonomous_system_organization.map(Into::into),
+            );
+            add_field("isp", data.isp.map(Into::into));
+            add_field("organization", data.organization.map(Into::into));
+        } else {
+            let data = match self.dbreader.lookup::<City>(ip) {
+                Ok(data) => data,
+                Err(MaxMindDBError::AddressNotFoundError(_)) => return Ok(None),
+                Err(error) => return Err(format!("unable to look up IP {}: {}", ip, error)),
+            };
+            let locale = self.config.locale.as_str();
+
+            add_field(
+                "city_name",
+                data.city
+                    .and_then(|city| city.names)
+                    .and_then(|names| names.get(locale).map(|&name| name.into())),
+            );
+
+            add_field(
+                "continent_code",
+                data.continent
+                    .and_then(|continent| continent.code.map(Into::into)),
+            );
+
+            let country = data.country;
+            add_field(
+                "country_code",
+                country
+                    .as_ref()
+                    .and_then(|country| country.iso_code.map(Into::into)),
+            );
+            add_field(
+                "country_name",
+                country
+                    .as_ref()
+                    .and_then(|country| country.names.as_ref())
+                    .and_then(|names| names.get(locale).map(|&name| name.into())),
+            );
+
+            let location = data.location;
+            add_field(
+                "timezone",
+                location
+                    .as_ref()
+                    .and_then(|location| location.time_zone.map(Into::into)),
+            );
+            add_field(
+                "latitude",
+                location
+                    .as_ref()
+                    .and_then(|location| location.latitude.map(Into::into)),
+            );
+            add_field(
+                "longitude",
+                location
+                    .as_ref()
+                    .and_then(|location| location.longitude.map(Into::into)),
+            );
+            add_field(
+                "metro_code",
+                location
+                    .as_ref()
+                    .and_then(|location| location.metro_code.map(|code| (code as i64).into())),
+            );
+
+            // last subdivision is most specific per https://github.com/maxmind/GeoIP2-java/blob/39385c6ce645374039450f57208b886cf87ade47/src/main/java/com/maxmind/geoip2/model/AbstractCityResponse.java#L96-L107
+            let subdivision = data.subdivisions.as_ref().and_then(|s| s.last());
+            add_field(
+                "region_code",
+                subdivision.and_then(|subdivision| subdivision.iso_code.map(Into::into)),
+            );
+            add_field(
+                "region_name",
+                subdivision
+                    .and_then(|subdivision| subdivision.names.as_ref())
+                    .and_then(|names| names.get(locale).map(|&name| name.into())),
+            );
+
+            add_field(
+                "postal_code",
+                data.postal.and_then(|postal| postal.code.map(Into::into)),
+            );
+        }
+
+        Ok(Some(map))
+    }
+}
+
+/// Reads the address to look up from the condition, which can only be an exact match of the
+/// `ip` field.
+fn condition_ip(condition: &[Condition]) -> Result<IpAddr, String> {
+    match condition {
+        [Condition::Equals { field, value }] if *field == IP_FIELD => {
+            let ip = value
+                .as_bytes()
+                .and_then(|bytes| std::str::from_utf8(bytes).ok())
+                .ok_or_else(|| format!("{} must be a string", IP_FIELD))?;
+            ip.parse()
+                .map_err(|error| format!("invalid IP {:?}: {}", ip, error))
+        }
+        _ => Err(format!(
+            "only an exact match of the {} field is supported",
+            IP_FIELD
+        )),
+    }
+}
+
+impl Table for Geoip {
+    fn find_table_row<'a>(
+        &self,
+        _case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
+        _index: Option<IndexHandle>,
+    ) -> Result<BTreeMap<String, Value>, String> {
+        let ip = condition_ip(condition)?;
+        self.lookup(ip, select)?
+            .ok_or_else(|| format!("no data found for IP {}", ip))
+    }
+
+    fn find_table_rows<'a>(
+        &self,
+        _case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
+        _index: Option<IndexHandle>,
+    ) -> Result<Vec<BTreeMap<String, Value>>, String> {
+        Ok(self
+            .lookup(condition_ip(condition)?, select)?
+            .into_iter()
+            .collect())
+    }
+
+    /// The database is indexed by address already, so this only checks the table is searched
+    /// by the `ip` field.
+    fn add_index(&mut self, _case: Case, fields: &[&str]) -> Result<IndexHandle, String> {
+        match fields {
+            [IP_FIELD] => Ok(IndexHandle(0)),
+            _ => Err(format!(
+                "only the {} field can be searched, found: {}",
+                IP_FIELD,
+                fields.join(", ")
+            )),
+        }
+    }
+
+    fn index_fields(&self) -> Vec<(Case, Vec<String>)> {
+        Vec::new()
+    }
+
+    /// Checks the modified timestamp of the database to see if it has been updated.
+    fn needs_reload(&self) -> bool {
+        matches!(fs::metadata(&self.config.path)
+            .and_then(|metadata| metadata.modified()),
+            Ok(modified) if modified > self.last_modified)
+    }
+}
+
+impl std::fmt::Debug for Geoip {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Geoip {} database {}",
+            self.dbreader.metadata.database_type, self.config.path
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<GeoipConfig>();
+    }
+
+    fn geoip(path: &str) -> Geoip {
+        Geoip::new(GeoipConfig {
+            path: path.to_string(),
+            locale: default_locale(),
+        })
+        .unwrap()
+    }
+
+    fn find(geoip: &Geoip, ip: &str) -> Result<BTreeMap<String, Value>, String> {
+        geoip.find_table_row(
+            Case::Insensitive,
+            &[Condition::Equals {
+                field: "ip",
+                value: ip.into(),
+            }],
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn finds_city() {
+        let geoip = geoip("tests/data/GeoIP2-City-Test.mmdb");
+        let row = find(&geoip, "2.125.160.216").unwrap();
+
+        assert_eq!(row["city_name"], "Boxford".into());
+        assert_eq!(row["country_code"], "GB".into());
+        assert_eq!(row["continent_code"], "EU".into());
+        assert_eq!(row["country_name"], "United Kingdom".into());
+        assert_eq!(row["region_code"], "WBK".into());
+        assert_eq!(row["region_name"], "West Berkshire".into());
+        assert_eq!(row["timezone"], "Europe/London".into());
+        assert_eq!(row["latitude"], 51.75.into());
+        assert_eq!(row["longitude"], (-1.25).into());
+        assert_eq!(row["postal_code"], "OX1".into());
+        assert_eq!(row["metro_code"], Value::Null);
+    }
+
+    #[test]
+    fn finds_isp() {
+        let geoip = geoip("tests/data/GeoIP2-ISP-Test.mmdb");
+        let row = find(&geoip, "208.192.1.2").unwrap();
+
+        assert_eq!(row["autonomous_system_number"], 701.into());
+        assert_eq!(
+            row["autonomous_system_organization"],
+            "MCI Communications Services, Inc. d/b/a Verizon Business".into()
+        );
+        assert_eq!(row["isp"], "Verizon Business".into());
+        assert_eq!(row["organization"], "Verizon Business".into());
+    }
+
+    #[test]
+    fn finds_asn_with_select() {
+        let geoip = geoip("tests/data/GeoLite2-ASN-Test.mmdb");
+        let row = geoip
+            .find_table_row(
+                Case::Sensitive,
+                &[Condition::Equals {
+                    field: "ip",
+                    value: "2600:7000::1".into(),
+                }],
+                Some(&["autonomous_system_number".to_string()]),
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(
+            row,
+            BTreeMap::from([("autonomous_system_number".to_string(), 6939.into())])
+        );
+    }
+
+    #[test]
+    fn doesnt_find_address() {
+        let geoip = geoip("tests/data/GeoIP2-City-Test.mmdb");
+
+        assert!(find(&geoip, "10.1.12.1").is_err());
+        assert!(find(&geoip, "not an address").is_err());
+        assert_eq!(
+            geoip.find_table_rows(
+                Case::Sensitive,
+                &[Condition::Equals {
+                    field: "ip",
+                    value: "10.1.12.1".into(),
+                }],
+                None,
+                None,
+            ),
+            Ok(Vec::new())
+        );
+    }
+
+    #[test]
+    fn only_indexes_ip() {
+        let mut geoip = geoip("tests/data/GeoIP2-City-Test.mmdb");
+
+        assert_eq!(
+            geoip.add_index(Case::Sensitive, &["ip"]),
+            Ok(IndexHandle(0))
+        );
+        assert!(geoip.add_index(Case::Sensitive, &["city_name"]).is_err());
+        assert!(geoip
+            .add_index(Case::Sensitive, &["ip", "city_name"])
+            .is_err());
+        assert!(!geoip.needs_reload());
+    }
+}
@@ -2,3 +2,12 @@ pub use enrichment::{Condition, IndexHandle, Table};
 
 #[cfg(feature = "enrichment-tables-file")]
 pub mod file;
+
+#[cfg(feature = "enrichment-tables-geoip")]
+pub mod geoip;
+
+#[cfg(feature = "enrichment-tables-redis")]
+pub mod redis;
+
+#[cfg(feature = "enrichment-tables-sqlite")]
+pub mod sqlite;
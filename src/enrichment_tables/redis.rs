@@ -0,0 +1,620 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::{Arc, Mutex, Weak},
+    time::{Duration, Instant},
+};
+
+use bytes::Bytes;
+use enrichment::{Case, Condition, IndexHandle, Table};
+use lru::LruCache;
+use redis::{aio::ConnectionManager, Client, RedisResult};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use tokio::sync::mpsc;
+use value::Value;
+
+use crate::{
+    config::{EnrichmentTableConfig, EnrichmentTableDescription, GenerateConfig},
+    internal_events::EnrichmentTableFetchFailed,
+};
+
+/// The only field the table can be searched by.
+const KEY_FIELD: &str = "key";
+
+/// The maximum number of keys fetched from Redis in a single round trip.
+const FETCH_BATCH_SIZE: usize = 100;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Failed to build redis client: {}", source))]
+    Client { source: redis::RedisError },
+    #[snafu(display("The cache must hold at least one entry"))]
+    EmptyCache,
+}
+
+#[derive(Copy, Clone, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DataType {
+    /// Rows are stored as hashes, whose fields are the columns of the row.
+    #[derivative(Default)]
+    Hash,
+    /// Rows are stored as strings holding a JSON object.
+    Json,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RedisConfig {
+    /// The URL of the Redis server, such as `redis://127.0.0.1:6379/0`.
+    pub url: String,
+    /// A prefix added to the searched key, to find the Redis key of the row.
+    #[serde(default)]
+    pub key_prefix: String,
+    #[serde(default)]
+    pub data_type: DataType,
+    #[serde(default = "default_cache_ttl_secs")]
+    pub cache_ttl_secs: u64,
+    #[serde(default = "default_cache_max_entries")]
+    pub cache_max_entries: usize,
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+const fn default_cache_ttl_secs() -> u64 {
+    60
+}
+
+const fn default_cache_max_entries() -> usize {
+    10_000
+}
+
+const fn default_timeout_ms() -> u64 {
+    100
+}
+
+impl GenerateConfig for RedisConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            url: "redis://127.0.0.1:6379/0".to_owned(),
+            key_prefix: "user:".to_owned(),
+            data_type: DataType::Hash,
+            cache_ttl_secs: default_cache_ttl_secs(),
+            cache_max_entries: default_cache_max_entries(),
+            timeout_ms: default_timeout_ms(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "redis")]
+impl EnrichmentTableConfig for RedisConfig {
+    async fn build(
+        &self,
+        _globals: &crate::config::GlobalOptions,
+    ) -> crate::Result<Box<dyn Table + Send + Sync>> {
+        if self.cache_max_entries == 0 {
+            return Err(BuildError::EmptyCache.into());
+        }
+        // The server is only connected to once the table is searched, so Vector can start while
+        // it's unavailable.
+        let client = Client::open(self.url.as_str()).context(ClientSnafu)?;
+
+        let (requests, requested) = mpsc::unbounded_channel();
+        let inner = Arc::new(Inner {
+            cache: Mutex::new(Cache::new(
+                self.cache_max_entries,
+                Duration::from_secs(self.cache_ttl_secs),
+            )),
+            fetching: Mutex::new(HashSet::new()),
+            requests,
+        });
+        tokio::spawn(fetch_rows(
+            client,
+            self.clone(),
+            Arc::downgrade(&inner),
+            requested,
+        ));
+
+        Ok(Box::new(Redis {
+            config: self.clone(),
+            inner,
+        }))
+    }
+}
+
+inventory::submit! {
+    EnrichmentTableDescription::new::<RedisConfig>("redis")
+}
+
+type Row = BTreeMap<String, Value>;
+
+/// The result of looking a key up in the cache.
+#[derive(Debug, PartialEq)]
+enum Lookup {
+    /// The row was fetched within the TTL.
+    Fresh(Option<Row>),
+    /// The row was fetched longer than the TTL ago, and should be fetched again.
+    Stale(Option<Row>),
+    /// The key isn't cached.
+    Missing,
+}
+
+/// The rows fetched recently, including the keys that had no row. Rows are considered fresh for
+/// the TTL after they were fetched, and are kept until they're fetched again or evicted.
+struct Cache {
+    entries: LruCache<String, (Instant, Option<Row>)>,
+    ttl: Duration,
+}
+
+impl Cache {
+    fn new(max_entries: usize, ttl: Duration) -> Self {
+        Self {
+            entries: LruCache::new(max_entries),
+            ttl,
+        }
+    }
+
+    fn get(&mut self, key: &str, now: Instant) -> Lookup {
+        match self.entries.get(&key.to_owned()) {
+            Some((fetched, row)) if now.saturating_duration_since(*fetched) < self.ttl => {
+                Lookup::Fresh(row.clone())
+            }
+            Some((_, row)) => Lookup::Stale(row.clone()),
+            None => Lookup::Missing,
+        }
+    }
+
+    fn insert(&mut self, key: String, row: Option<Row>, now: Instant) {
+        self.entries.put(key, (now, row));
+    }
+
+    fn remove(&mut self, key: &str) {
+        self.entries.pop(&key.to_owned());
+    }
+}
+
+/// Searching the table never waits for Redis, which would block the transform searching it.
+/// Instead, the keys that aren't cached, or whose rows are stale, are requested from a task
+/// fetching them in the background.
+struct Inner {
+    cache: Mutex<Cache>,
+    /// The keys requested and not fetched yet, so that each key is only requested once.
+    fetching: Mutex<HashSet<String>>,
+    requests: mpsc::UnboundedSender<String>,
+}
+
+#[derive(Clone)]
+pub struct Redis {
+    config: RedisConfig,
+    inner: Arc<Inner>,
+}
+
+impl Redis {
+    /// Finds the row of the key in the cache, requesting it from Redis if it isn't cached or is
+    /// stale. Stale rows are still returned until they're fetched again.
+    fn find(&self, key: &str, select: Option<&[String]>) -> Result<Option<Row>, String> {
+        let key = format!("{}{}", self.config.key_prefix, key);
+
+        let cached = self
+            .inner
+            .cache
+            .lock()
+            .expect("cache mutex poisoned")
+            .get(&key, Instant::now());
+        let row = match cached {
+            Lookup::Fresh(row) => row,
+            Lookup::Stale(row) => {
+                self.request(key);
+                row
+            }
+            Lookup::Missing => {
+                let error = format!("the row of {:?} is being fetched from redis", key);
+                self.request(key);
+                return Err(error);
+            }
+        };
+
+        Ok(row.map(|row| match select {
+            Some(select) => row
+                .into_iter()
+                .filter(|(field, _)| select.contains(field))
+                .collect(),
+            // If no select is passed, we assume all fields are included
+            None => row,
+        }))
+    }
+
+    fn request(&self, key: String) {
+        let mut fetching = self.inner.fetching.lock().expect("fetching mutex poisoned");
+        if fetching.insert(key.clone()) && self.inner.requests.send(key.clone()).is_err() {
+            fetching.remove(&key);
+        }
+    }
+}
+
+/// Fetches the requested keys into the cache, until the table is dropped.
+async fn fetch_rows(
+    client: Client,
+    config: RedisConfig,
+    inner: Weak<Inner>,
+    mut requested: mpsc::UnboundedReceiver<String>,
+) {
+    let timeout = Duration::from_millis(config.timeout_ms);
+    let mut connection = None;
+
+    while let Some(key) = requested.recv().await {
+        let mut keys = vec![key];
+        while keys.len() < FETCH_BATCH_SIZE {
+            match requested.try_recv() {
+                Ok(key) => keys.push(key),
+                Err(_) => break,
+            }
+        }
+
+        let result = tokio::time::timeout(
+            timeout,
+            fetch(&client, &mut connection, config.data_type, &keys),
+        )
+        .await
+        .unwrap_or_else(|_| {
+            Err(redis::RedisError::from((
+                redis::ErrorKind::IoError,
+                "timed out",
+            )))
+        });
+
+        let inner = match inner.upgrade() {
+            Some(inner) => inner,
+            None => break,
+        };
+        {
+            let mut cache = inner.cache.lock().expect("cache mutex poisoned");
+            match result {
+                Ok(rows) => {
+                    let now = Instant::now();
+                    for (key, row) in keys.iter().zip(rows) {
+                        let row = row.unwrap_or_else(|error| {
+                            emit!(EnrichmentTableFetchFailed {
+                                error: format!("the row of {:?} is invalid: {}", key, error),
+                            });
+                            None
+                        });
+                        cache.insert(key.clone(), row, now);
+                    }
+                }
+                Err(error) => {
+                    emit!(EnrichmentTableFetchFailed {
+                        error: format!("unable to fetch rows from redis: {}", error),
+                    });
+                    // Rows which can't be fetched again aren't served anymore.
+                    for key in &keys {
+                        cache.remove(key);
+                    }
+                }
+            }
+        }
+        let mut fetching = inner.fetching.lock().expect("fetching mutex poisoned");
+        for key in &keys {
+            fetching.remove(key);
+        }
+    }
+}
+
+/// Fetches the rows of the keys in a single round trip, connecting to Redis first if needed.
+async fn fetch(
+    client: &Client,
+    connection: &mut Option<ConnectionManager>,
+    data_type: DataType,
+    keys: &[String],
+) -> RedisResult<Vec<Result<Option<Row>, String>>> {
+    if connection.is_none() {
+        // The connection manager reconnects on its own once connected.
+        *connection = Some(client.get_tokio_connection_manager().await?);
+    }
+    let connection = connection.as_mut().expect("connection is set");
+
+    let mut pipeline = redis::pipe();
+    for key in keys {
+        match data_type {
+            DataType::Hash => pipeline.cmd("HGETALL").arg(key),
+            DataType::Json => pipeline.cmd("GET").arg(key),
+        };
+    }
+
+    Ok(match data_type {
+        DataType::Hash => {
+            let rows: Vec<HashMap<String, Vec<u8>>> = pipeline.query_async(connection).await?;
+            rows.into_iter()
+                .map(|fields| {
+                    // Redis doesn't store empty hashes, so a hash without fields doesn't exist.
+                    Ok((!fields.is_empty()).then(|| {
+                        fields
+                            .into_iter()
+                            .map(|(field, value)| (field, Value::from(Bytes::from(value))))
+                            .collect()
+                    }))
+                })
+                .collect()
+        }
+        DataType::Json => {
+            let rows: Vec<Option<Vec<u8>>> = pipeline.query_async(connection).await?;
+            rows.into_iter()
+                .map(|json| json.map(|json| parse_json(&json)).transpose())
+                .collect()
+        }
+    })
+}
+
+fn parse_json(json: &[u8]) -> Result<Row, String> {
+    match serde_json::from_slice::<serde_json::Value>(json) {
+        Ok(json @ serde_json::Value::Object(_)) => match Value::from(json) {
+            Value::Object(row) => Ok(row),
+            _ => unreachable!("objects are converted to objects"),
+        },
+        Ok(_) => Err("found another JSON type".to_owned()),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+/// Reads the key to search from the condition, which can only be an exact match of the `key`
+/// field.
+fn condition_key(condition: &[Condition]) -> Result<String, String> {
+    match condition {
+        [Condition::Equals { field, value }] if *field == KEY_FIELD => match value {
+            Value::Bytes(_) | Value::Integer(_) => Ok(value.to_string_lossy()),
+            _ => Err(format!("{} must be a string or an integer", KEY_FIELD)),
+        },
+        _ => Err(format!(
+            "only an exact match of the {} field is supported",
+            KEY_FIELD
+        )),
+    }
+}
+
+impl Table for Redis {
+    fn find_table_row<'a>(
+        &self,
+        _case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
+        _index: Option<IndexHandle>,
+    ) -> Result<BTreeMap<String, Value>, String> {
+        let key = condition_key(condition)?;
+        self.find(&key, select)?
+            .ok_or_else(|| format!("no row found for key {:?}", key))
+    }
+
+    fn find_table_rows<'a>(
+        &self,
+        _case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
+        _index: Option<IndexHandle>,
+    ) -> Result<Vec<BTreeMap<String, Value>>, String> {
+        Ok(self
+            .find(&condition_key(condition)?, select)?
+            .into_iter()
+            .collect())
+    }
+
+    /// Rows are found by their key already, so this only checks the table is searched by the
+    /// `key` field.
+    fn add_index(&mut self, _case: Case, fields: &[&str]) -> Result<IndexHandle, String> {
+        match fields {
+            [KEY_FIELD] => Ok(IndexHandle(0)),
+            _ => Err(format!(
+                "only the {} field can be searched, found: {}",
+                KEY_FIELD,
+                fields.join(", ")
+            )),
+        }
+    }
+
+    fn index_fields(&self) -> Vec<(Case, Vec<String>)> {
+        Vec::new()
+    }
+
+    /// The data is searched live, with the cache expiring on its own.
+    fn needs_reload(&self) -> bool {
+        false
+    }
+}
+
+impl std::fmt::Debug for Redis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Redis {:?} {}", self.config.data_type, self.config.url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<RedisConfig>();
+    }
+
+    #[test]
+    fn cache_expires_entries() {
+        let now = Instant::now();
+        let mut cache = Cache::new(2, Duration::from_secs(10));
+        let row = Row::from([("name".to_owned(), "Bob".into())]);
+
+        cache.insert("user:1".to_owned(), Some(row.clone()), now);
+        cache.insert("user:2".to_owned(), None, now);
+        assert_eq!(cache.get("user:1", now), Lookup::Fresh(Some(row.clone())));
+        assert_eq!(
+            cache.get("user:2", now + Duration::from_secs(5)),
+            Lookup::Fresh(None),
+            "keys without a row are cached too"
+        );
+        assert_eq!(
+            cache.get("user:1", now + Duration::from_secs(10)),
+            Lookup::Stale(Some(row.clone()))
+        );
+
+        cache.insert("user:1".to_owned(), Some(row), now);
+        cache.insert("user:3".to_owned(), None, now);
+        assert_eq!(
+            cache.get("user:2", now),
+            Lookup::Missing,
+            "the least recently used entry is evicted"
+        );
+
+        cache.remove("user:3");
+        assert_eq!(cache.get("user:3", now), Lookup::Missing);
+    }
+
+    #[test]
+    fn parses_json_rows() {
+        assert_eq!(
+            parse_json(br#"{ "name": "Bob", "age": 42 }"#),
+            Ok(Row::from([
+                ("age".to_owned(), 42.into()),
+                ("name".to_owned(), "Bob".into())
+            ]))
+        );
+        assert!(parse_json(b"[42]").is_err());
+        assert!(parse_json(b"{").is_err());
+    }
+
+    #[test]
+    fn only_searches_key() {
+        assert_eq!(
+            condition_key(&[Condition::Equals {
+                field: "key",
+                value: 42.into(),
+            }]),
+            Ok("42".to_owned())
+        );
+        assert!(condition_key(&[Condition::Equals {
+            field: "name",
+            value: "Bob".into(),
+        }])
+        .is_err());
+        assert!(condition_key(&[
+            Condition::Equals {
+                field: "key",
+                value: "1".into(),
+            },
+            Condition::Equals {
+                field: "name",
+                value: "Bob".into(),
+            }
+        ])
+        .is_err());
+    }
+}
+
+#[cfg(feature = "redis-integration-tests")]
+#[cfg(test)]
+mod integration_tests {
+    use super::*;
+    use crate::{config::GlobalOptions, test_util::random_string};
+
+    fn redis_server() -> String {
+        std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379/0".to_owned())
+    }
+
+    async fn build(data_type: DataType, key_prefix: String) -> Box<dyn Table + Send + Sync> {
+        let config: RedisConfig = toml::from_str(&format!(
+            r#"url = "{}"
+            key_prefix = "{}"
+            data_type = "{}""#,
+            redis_server(),
+            key_prefix,
+            if data_type == DataType::Hash {
+                "hash"
+            } else {
+                "json"
+            },
+        ))
+        .unwrap();
+        config.build(&GlobalOptions::default()).await.unwrap()
+    }
+
+    fn find(table: &dyn Table, key: &str) -> Result<BTreeMap<String, Value>, String> {
+        table.find_table_row(
+            Case::Sensitive,
+            &[Condition::Equals {
+                field: "key",
+                value: key.into(),
+            }],
+            None,
+            None,
+        )
+    }
+
+    /// Searches the key, waiting for its row to be fetched if it isn't cached.
+    async fn find_fetched(table: &dyn Table, key: &str) -> Result<BTreeMap<String, Value>, String> {
+        for _ in 0..100 {
+            match find(table, key) {
+                Err(error) if error.contains("is being fetched") => {
+                    tokio::time::sleep(Duration::from_millis(10)).await
+                }
+                result => return result,
+            }
+        }
+        panic!("the row of {:?} wasn't fetched", key);
+    }
+
+    #[tokio::test]
+    async fn redis_enrichment_table_hash() {
+        let prefix = format!("test-{}:", random_string(10));
+        let mut connection = Client::open(redis_server())
+            .unwrap()
+            .get_connection()
+            .unwrap();
+        redis::cmd("HSET")
+            .arg(format!("{}1", prefix))
+            .arg("name")
+            .arg("Bob")
+            .arg("team")
+            .arg("core")
+            .query::<()>(&mut connection)
+            .unwrap();
+
+        let table = build(DataType::Hash, prefix.clone()).await;
+        assert!(find(&*table, "1").is_err(), "rows aren't fetched in place");
+        assert_eq!(
+            find_fetched(&*table, "1").await,
+            Ok(BTreeMap::from([
+                ("name".to_owned(), "Bob".into()),
+                ("team".to_owned(), "core".into())
+            ]))
+        );
+        assert_eq!(
+            find_fetched(&*table, "2").await,
+            Err(r#"no row found for key "2""#.to_owned())
+        );
+
+        // Rows are served from the cache until they expire.
+        redis::cmd("DEL")
+            .arg(format!("{}1", prefix))
+            .query::<()>(&mut connection)
+            .unwrap();
+        assert!(find(&*table, "1").is_ok());
+    }
+
+    #[tokio::test]
+    async fn redis_enrichment_table_json() {
+        let prefix = format!("test-{}:", random_string(10));
+        let mut connection = Client::open(redis_server())
+            .unwrap()
+            .get_connection()
+            .unwrap();
+        redis::cmd("SET")
+            .arg(format!("{}1", prefix))
+            .arg(r#"{ "name": "Bob", "teams": ["core"] }"#)
+            .query::<()>(&mut connection)
+            .unwrap();
+
+        let table = build(DataType::Json, prefix).await;
+        let row = find_fetched(&*table, "1").await.unwrap();
+        assert_eq!(row["name"], "Bob".into());
+        assert_eq!(row["teams"], Value::Array(vec!["core".into()]));
+    }
+}
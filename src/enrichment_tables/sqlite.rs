@@ -0,0 +1,609 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    fs,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    time::SystemTime,
+};
+
+use bytes::Bytes;
+use enrichment::{Case, Condition, IndexHandle, Table};
+use ordered_float::NotNan;
+use rusqlite::{
+    types::{Value as SqlValue, ValueRef},
+    Connection, OpenFlags,
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use value::Value;
+
+use crate::config::{EnrichmentTableConfig, EnrichmentTableDescription, GenerateConfig};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Exactly one of `table` and `query` must be set"))]
+    TableOrQuery,
+    #[snafu(display("Indexes can only be created on a `table`"))]
+    IndexesOnQuery,
+    #[snafu(display("Failed to open SQLite database {:?}: {}", path, source))]
+    Open {
+        path: String,
+        source: rusqlite::Error,
+    },
+    #[snafu(display("Failed to read the columns of the SQLite rows: {}", source))]
+    Columns { source: rusqlite::Error },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SqliteConfig {
+    /// The path of the SQLite database file.
+    pub path: String,
+    /// The table, or view, holding the rows.
+    pub table: Option<String>,
+    /// A `SELECT` statement whose results are the rows, used instead of a table.
+    pub query: Option<String>,
+    /// Whether to create the indexes needed by the searches of the table in the database, which
+    /// requires write access to it.
+    #[serde(default)]
+    pub create_indexes: bool,
+}
+
+impl GenerateConfig for SqliteConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            path: "/path/to/data.db".to_owned(),
+            table: Some("users".to_owned()),
+            query: None,
+            create_indexes: false,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "sqlite")]
+impl EnrichmentTableConfig for SqliteConfig {
+    async fn build(
+        &self,
+        _globals: &crate::config::GlobalOptions,
+    ) -> crate::Result<Box<dyn Table + Send + Sync>> {
+        let config = self.clone();
+        Ok(Box::new(
+            tokio::task::spawn_blocking(move || Sqlite::new(config)).await??,
+        ))
+    }
+}
+
+inventory::submit! {
+    EnrichmentTableDescription::new::<SqliteConfig>("sqlite")
+}
+
+type Row = BTreeMap<String, Value>;
+
+/// A table whose rows are searched in a SQLite database, rather than being loaded in memory.
+#[derive(Clone)]
+pub struct Sqlite {
+    config: SqliteConfig,
+    /// The rows are selected from, either the quoted table or the parenthesized query.
+    source: String,
+    columns: Vec<String>,
+    connections: Arc<Connections>,
+    indexes: Vec<(Case, Vec<String>)>,
+    last_modified: SystemTime,
+}
+
+impl Sqlite {
+    fn new(config: SqliteConfig) -> crate::Result<Self> {
+        let source = match (&config.table, &config.query) {
+            (Some(table), None) => quote(table),
+            (None, Some(_)) if config.create_indexes => {
+                return Err(BuildError::IndexesOnQuery.into())
+            }
+            (None, Some(query)) => format!("({})", query.trim().trim_end_matches(';')),
+            _ => return Err(BuildError::TableOrQuery.into()),
+        };
+
+        // The modification time is read first, so a database modified while it's being opened
+        // is opened again.
+        let last_modified = fs::metadata(&config.path)?.modified()?;
+        let flags = if config.create_indexes {
+            OpenFlags::SQLITE_OPEN_READ_WRITE
+        } else {
+            OpenFlags::SQLITE_OPEN_READ_ONLY
+        };
+        let connections = (0..num_cpus::get().max(1))
+            .map(|_| {
+                Connection::open_with_flags(
+                    &config.path,
+                    flags | OpenFlags::SQLITE_OPEN_NO_MUTEX | OpenFlags::SQLITE_OPEN_URI,
+                )
+                .map(Mutex::new)
+                .with_context(|_| OpenSnafu {
+                    path: config.path.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let connections = Connections {
+            connections,
+            next: AtomicUsize::new(0),
+        };
+
+        let columns = connections
+            .get()
+            .prepare(&format!("SELECT * FROM {} LIMIT 0", source))
+            .map(|statement| {
+                statement
+                    .column_names()
+                    .into_iter()
+                    .map(Into::into)
+                    .collect()
+            })
+            .context(ColumnsSnafu)?;
+
+        Ok(Self {
+            config,
+            source,
+            columns,
+            connections: Arc::new(connections),
+            indexes: Vec::new(),
+            last_modified,
+        })
+    }
+
+    /// Finds the rows matching all the conditions, stopping after `limit` rows.
+    fn find(
+        &self,
+        case: Case,
+        condition: &[Condition],
+        select: Option<&[String]>,
+        limit: Option<usize>,
+    ) -> Result<Vec<Row>, String> {
+        let (sql, params) = self.statement(case, condition, limit)?;
+
+        let connection = self.connections.get();
+        let mut statement = connection
+            .prepare_cached(&sql)
+            .map_err(|error| format!("unable to prepare SQLite query: {}", error))?;
+        let names = statement
+            .column_names()
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        let mut rows = statement
+            .query(rusqlite::params_from_iter(params))
+            .map_err(|error| format!("unable to query SQLite: {}", error))?;
+
+        let mut found = Vec::new();
+        while let Some(row) = rows
+            .next()
+            .map_err(|error| format!("unable to query SQLite: {}", error))?
+        {
+            let mut fields = Row::new();
+            for (index, name) in names.iter().enumerate() {
+                if select
+                    .map(|select| select.contains(name))
+                    // If no select is passed, we assume all fields are included
+                    .unwrap_or(true)
+                {
+                    let value = row
+                        .get_ref(index)
+                        .map_err(|error| format!("unable to read {:?}: {}", name, error))?;
+                    fields.insert(name.clone(), to_value(value));
+                }
+            }
+            found.push(fields);
+        }
+
+        Ok(found)
+    }
+
+    /// Builds the statement selecting the rows matching the conditions, along with its parameters.
+    fn statement(
+        &self,
+        case: Case,
+        condition: &[Condition],
+        limit: Option<usize>,
+    ) -> Result<(String, Vec<SqlValue>), String> {
+        let mut sql = format!("SELECT * FROM {}", self.source);
+        let mut params = Vec::new();
+
+        for (position, condition) in condition.iter().enumerate() {
+            sql.push_str(if position == 0 { " WHERE " } else { " AND " });
+            match condition {
+                Condition::Equals { field, value } => {
+                    self.check_column(field)?;
+                    params.push(to_sql(value).ok_or_else(|| {
+                        format!("unable to search {:?} by a {}", field, value.kind_str())
+                    })?);
+                    // `IS` also matches null values.
+                    let _ = write!(sql, "{} IS ?{}", quote(field), params.len());
+                    // `NOCASE` only folds ASCII letters, so other letters only match in the
+                    // same case.
+                    if case == Case::Insensitive {
+                        sql.push_str(" COLLATE NOCASE");
+                    }
+                }
+                Condition::BetweenDates { field, from, to } => {
+                    self.check_column(field)?;
+                    params.push(SqlValue::Text(from.format(DATE_FORMAT).to_string()));
+                    params.push(SqlValue::Text(to.format(DATE_FORMAT).to_string()));
+                    let _ = write!(
+                        sql,
+                        "julianday({}) BETWEEN julianday(?{}) AND julianday(?{})",
+                        quote(field),
+                        params.len() - 1,
+                        params.len()
+                    );
+                }
+            }
+        }
+
+        if let Some(limit) = limit {
+            let _ = write!(sql, " LIMIT {}", limit);
+        }
+
+        Ok((sql, params))
+    }
+
+    fn check_column(&self, field: &str) -> Result<(), String> {
+        if self.columns.iter().any(|column| column == field) {
+            Ok(())
+        } else {
+            Err(format!("field {:?} is not in the table", field))
+        }
+    }
+
+    fn create_index(&self, case: Case, fields: &[String]) -> Result<(), String> {
+        let table = self
+            .config
+            .table
+            .as_deref()
+            .expect("indexes are created on tables");
+        let name = format!(
+            "vector_{}_{}{}",
+            table,
+            fields.join("_"),
+            if case == Case::Insensitive {
+                "_nocase"
+            } else {
+                ""
+            }
+        );
+        let columns = fields
+            .iter()
+            .map(|field| match case {
+                Case::Sensitive => quote(field),
+                Case::Insensitive => format!("{} COLLATE NOCASE", quote(field)),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        self.connections
+            .get()
+            .execute_batch(&format!(
+                "CREATE INDEX IF NOT EXISTS {} ON {} ({})",
+                quote(&name),
+                self.source,
+                columns
+            ))
+            .map_err(|error| format!("unable to create index {:?}: {}", name, error))
+    }
+}
+
+/// Connections to the database, shared by the transforms searching the table. Searches run on
+/// the thread of the transform, so there's one connection per CPU for them not to wait on each
+/// other, and each search takes whichever connection is free.
+struct Connections {
+    connections: Vec<Mutex<Connection>>,
+    next: AtomicUsize,
+}
+
+impl Connections {
+    fn get(&self) -> MutexGuard<'_, Connection> {
+        for connection in &self.connections {
+            if let Ok(connection) = connection.try_lock() {
+                return connection;
+            }
+        }
+        // All the connections are busy, so the searches queue up evenly.
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.connections.len();
+        self.connections[index]
+            .lock()
+            .expect("connection mutex poisoned")
+    }
+}
+
+/// The format dates are compared in, which is understood by the SQLite date functions.
+const DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f";
+
+/// Quotes an identifier, so that any table or column name can be used.
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+fn to_value(value: ValueRef) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(integer) => integer.into(),
+        ValueRef::Real(real) => NotNan::new(real).map(Value::Float).unwrap_or(Value::Null),
+        ValueRef::Text(bytes) | ValueRef::Blob(bytes) => Bytes::copy_from_slice(bytes).into(),
+    }
+}
+
+fn to_sql(value: &Value) -> Option<SqlValue> {
+    Some(match value {
+        Value::Null => SqlValue::Null,
+        Value::Bytes(bytes) => SqlValue::Text(String::from_utf8_lossy(bytes).into_owned()),
+        Value::Integer(integer) => SqlValue::Integer(*integer),
+        Value::Float(float) => SqlValue::Real(float.into_inner()),
+        Value::Boolean(boolean) => SqlValue::Integer(i64::from(*boolean)),
+        Value::Timestamp(timestamp) => SqlValue::Text(timestamp.format(DATE_FORMAT).to_string()),
+        _ => return None,
+    })
+}
+
+impl Table for Sqlite {
+    fn find_table_row<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
+        _index: Option<IndexHandle>,
+    ) -> Result<BTreeMap<String, Value>, String> {
+        let mut rows = self.find(case, condition, select, Some(2))?;
+        match rows.len() {
+            0 => Err("no rows found".to_string()),
+            1 => Ok(rows.remove(0)),
+            _ => Err("more than one row found".to_string()),
+        }
+    }
+
+    fn find_table_rows<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
+        _index: Option<IndexHandle>,
+    ) -> Result<Vec<BTreeMap<String, Value>>, String> {
+        self.find(case, condition, select, None)
+    }
+
+    /// The searches are planned by SQLite, so this only checks the fields are columns of the
+    /// rows, and creates an index on them if configured to.
+    fn add_index(&mut self, case: Case, fields: &[&str]) -> Result<IndexHandle, String> {
+        for field in fields {
+            self.check_column(field)?;
+        }
+
+        let mut normalized = fields.iter().map(ToString::to_string).collect::<Vec<_>>();
+        normalized.sort();
+        match self
+            .indexes
+            .iter()
+            .position(|index| index.0 == case && index.1 == normalized)
+        {
+            Some(position) => Ok(IndexHandle(position)),
+            None => {
+                if self.config.create_indexes {
+                    self.create_index(case, &normalized)?;
+                }
+                self.indexes.push((case, normalized));
+                Ok(IndexHandle(self.indexes.len() - 1))
+            }
+        }
+    }
+
+    fn index_fields(&self) -> Vec<(Case, Vec<String>)> {
+        self.indexes.clone()
+    }
+
+    /// The rows are searched live, but the database is opened again when its file is modified,
+    /// in case it was replaced.
+    fn needs_reload(&self) -> bool {
+        matches!(
+            fs::metadata(&self.config.path).and_then(|metadata| metadata.modified()),
+            Ok(modified) if modified > self.last_modified
+        )
+    }
+}
+
+impl std::fmt::Debug for Sqlite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Sqlite {} from {}", self.config.path, self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::test_util::temp_file;
+
+    fn database() -> String {
+        let path = temp_file();
+        let connection = Connection::open(&path).unwrap();
+        connection
+            .execute_batch(
+                r#"
+                CREATE TABLE users (id INTEGER, name TEXT, team TEXT, score REAL, joined TEXT);
+                INSERT INTO users VALUES (1, 'Bob', 'core', 4.5, '2020-01-15 10:00:00');
+                INSERT INTO users VALUES (2, 'Alice', 'core', NULL, '2021-06-01T08:30:00Z');
+                INSERT INTO users VALUES (3, 'Carol', 'web', 3.0, '2022-03-10 12:00:00');
+                "#,
+            )
+            .unwrap();
+        path.to_str().unwrap().to_owned()
+    }
+
+    fn table(path: String, table: Option<&str>, query: Option<&str>) -> Sqlite {
+        Sqlite::new(SqliteConfig {
+            path,
+            table: table.map(Into::into),
+            query: query.map(Into::into),
+            create_indexes: false,
+        })
+        .unwrap()
+    }
+
+    fn equals<'a>(field: &'a str, value: Value) -> Condition<'a> {
+        Condition::Equals { field, value }
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SqliteConfig>();
+    }
+
+    #[test]
+    fn finds_row() {
+        let table = table(database(), Some("users"), None);
+
+        assert_eq!(
+            table.find_table_row(Case::Sensitive, &[equals("name", "Bob".into())], None, None),
+            Ok(BTreeMap::from([
+                ("id".to_owned(), 1.into()),
+                ("joined".to_owned(), "2020-01-15 10:00:00".into()),
+                ("name".to_owned(), "Bob".into()),
+                ("score".to_owned(), 4.5.into()),
+                ("team".to_owned(), "core".into()),
+            ]))
+        );
+        assert_eq!(
+            table.find_table_row(
+                Case::Insensitive,
+                &[equals("name", "alice".into())],
+                Some(&["id".to_owned(), "score".to_owned()]),
+                None
+            ),
+            Ok(BTreeMap::from([
+                ("id".to_owned(), 2.into()),
+                ("score".to_owned(), Value::Null),
+            ]))
+        );
+        assert!(table
+            .find_table_row(
+                Case::Sensitive,
+                &[equals("name", "alice".into())],
+                None,
+                None
+            )
+            .is_err());
+        assert_eq!(
+            table.find_table_row(
+                Case::Sensitive,
+                &[equals("team", "core".into())],
+                None,
+                None
+            ),
+            Err("more than one row found".to_owned())
+        );
+    }
+
+    #[test]
+    fn finds_rows_between_dates() {
+        let table = table(database(), Some("users"), None);
+
+        let rows = table
+            .find_table_rows(
+                Case::Sensitive,
+                &[
+                    equals("team", "core".into()),
+                    Condition::BetweenDates {
+                        field: "joined",
+                        from: Utc.ymd(2021, 1, 1).and_hms(0, 0, 0),
+                        to: Utc.ymd(2022, 1, 1).and_hms(0, 0, 0),
+                    },
+                ],
+                Some(&["name".to_owned()]),
+                None,
+            )
+            .unwrap();
+        assert_eq!(
+            rows,
+            vec![BTreeMap::from([("name".to_owned(), "Alice".into())])]
+        );
+    }
+
+    #[test]
+    fn finds_rows_of_query() {
+        let table = table(
+            database(),
+            None,
+            Some("SELECT name, team = 'core' AS core FROM users;"),
+        );
+
+        assert_eq!(
+            table.find_table_rows(Case::Sensitive, &[equals("core", 1.into())], None, None),
+            Ok(vec![
+                BTreeMap::from([
+                    ("core".to_owned(), 1.into()),
+                    ("name".to_owned(), "Bob".into())
+                ]),
+                BTreeMap::from([
+                    ("core".to_owned(), 1.into()),
+                    ("name".to_owned(), "Alice".into())
+                ]),
+            ])
+        );
+        assert!(table
+            .find_table_rows(Case::Sensitive, &[equals("team", "web".into())], None, None)
+            .is_err());
+    }
+
+    #[test]
+    fn adds_index() {
+        let path = database();
+        let mut table = Sqlite::new(SqliteConfig {
+            path: path.clone(),
+            table: Some("users".to_owned()),
+            query: None,
+            create_indexes: true,
+        })
+        .unwrap();
+
+        assert_eq!(
+            table.add_index(Case::Insensitive, &["team", "name"]),
+            Ok(IndexHandle(0))
+        );
+        assert_eq!(
+            table.add_index(Case::Insensitive, &["name", "team"]),
+            Ok(IndexHandle(0))
+        );
+        assert_eq!(
+            table.add_index(Case::Sensitive, &["id"]),
+            Ok(IndexHandle(1))
+        );
+        assert!(table.add_index(Case::Sensitive, &["email"]).is_err());
+
+        let indexes: i64 = Connection::open(&path)
+            .unwrap()
+            .query_row(
+                "SELECT count(*) FROM sqlite_master WHERE type = 'index' AND tbl_name = 'users'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(indexes, 2);
+    }
+
+    #[test]
+    fn requires_table_or_query() {
+        let path = database();
+        let config = |table: Option<&str>, query: Option<&str>, create_indexes| SqliteConfig {
+            path: path.clone(),
+            table: table.map(Into::into),
+            query: query.map(Into::into),
+            create_indexes,
+        };
+
+        assert!(Sqlite::new(config(None, None, false)).is_err());
+        assert!(Sqlite::new(config(Some("users"), Some("SELECT 1"), false)).is_err());
+        assert!(Sqlite::new(config(None, Some("SELECT 1"), true)).is_err());
+        assert!(Sqlite::new(config(Some("missing"), None, false)).is_err());
+    }
+}
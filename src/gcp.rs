@@ -21,6 +21,8 @@ use crate::{config::ProxyConfig, http::HttpClient, http::HttpError};
 const SERVICE_ACCOUNT_TOKEN_URL: &str =
     "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
 
+pub const BIGQUERY_URL: &str = "https://bigquery.googleapis.com";
+pub const BIGQUERY_STORAGE_URL: &str = "https://bigquerystorage.googleapis.com";
 pub const PUBSUB_URL: &str = "https://pubsub.googleapis.com";
 
 pub static PUBSUB_ADDRESS: Lazy<String> = Lazy::new(|| {
@@ -32,6 +34,8 @@ pub static PUBSUB_ADDRESS: Lazy<String> = Lazy::new(|| {
 pub enum GcpError {
     #[snafu(display("This requires one of api_key or credentials_path to be defined"))]
     MissingAuth,
+    #[snafu(display("This requires credentials_path to be defined"))]
+    MissingCredentials,
     #[snafu(display("Invalid GCP credentials: {}", source))]
     InvalidCredentials { source: GoErr },
     #[snafu(display("Healthcheck endpoint forbidden"))]
@@ -44,6 +48,8 @@ pub enum GcpError {
     GetTokenBytes { source: hyper::Error },
     #[snafu(display("Failed to get implicit GCP token: {}", source))]
     GetImplicitToken { source: HttpError },
+    #[snafu(display("Failed to get delegated GCP token: {}", source))]
+    GetDelegatedToken { source: HttpError },
     #[snafu(display("Failed to parse OAuth token JSON: {}", source))]
     TokenFromJson { source: TokenErr },
     #[snafu(display("Failed to parse OAuth token JSON text: {}", source))]
@@ -68,6 +74,30 @@ impl GcpAuthConfig {
             (None, None) => Some(GcpCredentials::new_implicit(scope).await?),
         })
     }
+
+    /// Makes credentials impersonating `subject`, a user of the Google Workspace domain, through
+    /// the domain-wide delegation granted to the service account. Unlike `make_credentials`, this
+    /// requires a service account key file.
+    pub async fn make_delegated_credentials(
+        &self,
+        scope: Scope,
+        subject: String,
+    ) -> crate::Result<GcpCredentials> {
+        let gap = std::env::var("GOOGLE_APPLICATION_CREDENTIALS").ok();
+        let creds_path = self
+            .credentials_path
+            .as_ref()
+            .or_else(|| gap.as_ref())
+            .ok_or(GcpError::MissingCredentials)?;
+        let creds = Credentials::from_file(creds_path).context(InvalidCredentialsSnafu)?;
+        let token = fetch_delegated_token(&creds, &scope, &subject).await?;
+        Ok(GcpCredentials(Arc::new(Inner {
+            creds: Some(creds),
+            scope,
+            subject: Some(subject),
+            token: RwLock::new(token),
+        })))
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -77,6 +107,7 @@ pub struct GcpCredentials(Arc<Inner>);
 struct Inner {
     creds: Option<Credentials>,
     scope: Scope,
+    subject: Option<String>,
     token: RwLock<Token>,
 }
 
@@ -87,6 +118,7 @@ impl GcpCredentials {
         Ok(Self(Arc::new(Inner {
             creds: Some(creds),
             scope,
+            subject: None,
             token: RwLock::new(token),
         })))
     }
@@ -96,6 +128,7 @@ impl GcpCredentials {
         Ok(Self(Arc::new(Inner {
             creds: None,
             scope,
+            subject: None,
             token: RwLock::new(token),
         })))
     }
@@ -112,9 +145,12 @@ impl GcpCredentials {
     }
 
     async fn regenerate_token(&self) -> crate::Result<()> {
-        let token = match &self.0.creds {
-            Some(creds) => fetch_token(creds, &self.0.scope).await?,
-            None => get_token_implicit().await?,
+        let token = match (&self.0.creds, &self.0.subject) {
+            (Some(creds), Some(subject)) => {
+                fetch_delegated_token(creds, &self.0.scope, subject).await?
+            }
+            (Some(creds), None) => fetch_token(creds, &self.0.scope).await?,
+            (None, _) => get_token_implicit().await?,
         };
         *self.0.token.write().unwrap() = token;
         Ok(())
@@ -162,6 +198,47 @@ async fn fetch_token(creds: &Credentials, scope: &Scope) -> crate::Result<Token>
         .map_err(Into::into)
 }
 
+async fn fetch_delegated_token(
+    creds: &Credentials,
+    scope: &Scope,
+    subject: &str,
+) -> crate::Result<Token> {
+    // The claims of `goauth` have no subject, so it's added to their serialized form.
+    let claims = JwtClaims::new(creds.iss(), scope, creds.token_uri(), None, None);
+    let mut claims = serde_json::to_value(claims).context(TokenJsonFromStrSnafu)?;
+    claims["sub"] = subject.into();
+    let rsa_key = creds.rsa_key().context(InvalidRsaKeySnafu)?;
+    let assertion = Jwt::new(claims, rsa_key, None)
+        .finalize()
+        .map_err(GoErr::from)
+        .context(InvalidRsaKeySnafu)?;
+
+    debug!(
+        message = "Fetching delegated GCP authentication token.",
+        iss = ?creds.iss(),
+        subject = %subject,
+        token_uri = ?creds.token_uri(),
+    );
+    let body = url::form_urlencoded::Serializer::new(String::new())
+        .append_pair("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer")
+        .append_pair("assertion", &assertion)
+        .finish();
+    let req = http::Request::post(creds.token_uri())
+        .header("Content-Type", "application/x-www-form-urlencoded")
+        .body(hyper::Body::from(body))?;
+
+    let proxy = ProxyConfig::from_env();
+    let res = HttpClient::new(None, &proxy)
+        .context(BuildHttpClientSnafu)?
+        .send(req)
+        .await
+        .context(GetDelegatedTokenSnafu)?;
+    let bytes = hyper::body::to_bytes(res.into_body())
+        .await
+        .context(GetTokenBytesSnafu)?;
+    parse_token(&bytes).map_err(Into::into)
+}
+
 async fn get_token_implicit() -> Result<Token, GcpError> {
     debug!("Fetching implicit GCP authentication token.");
     let req = http::Request::get(SERVICE_ACCOUNT_TOKEN_URL)
@@ -180,11 +257,14 @@ async fn get_token_implicit() -> Result<Token, GcpError> {
     let bytes = hyper::body::to_bytes(body)
         .await
         .context(GetTokenBytesSnafu)?;
+    parse_token(&bytes)
+}
 
+fn parse_token(bytes: &[u8]) -> Result<Token, GcpError> {
     // Token::from_str is irresponsible and may panic!
-    match serde_json::from_slice::<Token>(&bytes) {
+    match serde_json::from_slice::<Token>(bytes) {
         Ok(token) => Ok(token),
-        Err(error) => Err(match serde_json::from_slice::<TokenErr>(&bytes) {
+        Err(error) => Err(match serde_json::from_slice::<TokenErr>(bytes) {
             Ok(error) => GcpError::TokenFromJson { source: error },
             Err(_) => GcpError::TokenJsonFromStr { source: error },
         }),
@@ -0,0 +1,229 @@
+//! The methods described by user-provided file descriptor sets, as generated by
+//! `protoc --include_imports --descriptor_set_out`, whose messages are only known at runtime.
+
+use std::path::{Path, PathBuf};
+
+use bytes::{Buf, BufMut, Bytes};
+use prost::Message;
+use prost_reflect::{DescriptorPool, MethodDescriptor};
+use prost_types::FileDescriptorSet;
+use snafu::{ResultExt, Snafu};
+use tonic::{
+    codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder},
+    Status,
+};
+
+#[derive(Debug, Snafu)]
+pub(crate) enum MethodError {
+    #[snafu(display("Unable to read the descriptor set {:?}: {}", path, source))]
+    ReadDescriptorSet {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("Invalid descriptor set {:?}: {}", path, message))]
+    InvalidDescriptorSet { path: PathBuf, message: String },
+    #[snafu(display(
+        "Invalid method {:?}, expected the form `package.Service/Method`",
+        method
+    ))]
+    InvalidMethod { method: String },
+    #[snafu(display("The service {:?} is not in the descriptor set", service))]
+    UnknownService { service: String },
+    #[snafu(display("The service {:?} has no method {:?}", service, method))]
+    UnknownMethod { service: String, method: String },
+    #[snafu(display(
+        "The method {:?} streams its responses, which is not supported",
+        method
+    ))]
+    ServerStreaming { method: String },
+}
+
+/// Reads the descriptor set at `path` and finds the method in it.
+pub(crate) async fn load_method(
+    path: &Path,
+    method: &str,
+) -> Result<MethodDescriptor, MethodError> {
+    let bytes = tokio::fs::read(path)
+        .await
+        .context(ReadDescriptorSetSnafu { path })?;
+    resolve_method(path, &bytes, method)
+}
+
+/// Finds the method, as in `package.Service/Method`, in the encoded file descriptor set.
+pub(crate) fn resolve_method(
+    path: &Path,
+    bytes: &[u8],
+    method: &str,
+) -> Result<MethodDescriptor, MethodError> {
+    let invalid = |message: String| MethodError::InvalidDescriptorSet {
+        path: path.to_owned(),
+        message,
+    };
+    let descriptor_set =
+        FileDescriptorSet::decode(bytes).map_err(|error| invalid(error.to_string()))?;
+    let pool = DescriptorPool::from_file_descriptor_set(descriptor_set)
+        .map_err(|error| invalid(error.to_string()))?;
+
+    let (service_name, method_name) = method
+        .trim_start_matches('/')
+        .split_once('/')
+        .filter(|(service, method)| !service.is_empty() && !method.is_empty())
+        .ok_or_else(|| MethodError::InvalidMethod {
+            method: method.to_owned(),
+        })?;
+    let service =
+        pool.get_service_by_name(service_name)
+            .ok_or_else(|| MethodError::UnknownService {
+                service: service_name.to_owned(),
+            })?;
+    let method = service
+        .methods()
+        .find(|method| method.name() == method_name)
+        .ok_or_else(|| MethodError::UnknownMethod {
+            service: service_name.to_owned(),
+            method: method_name.to_owned(),
+        })?;
+    if method.is_server_streaming() {
+        return Err(MethodError::ServerStreaming {
+            method: method.full_name().to_owned(),
+        });
+    }
+    Ok(method)
+}
+
+/// The path of the method in the requests, as in `/package.Service/Method`.
+pub(crate) fn method_path(method: &MethodDescriptor) -> String {
+    format!("/{}/{}", method.parent_service().full_name(), method.name())
+}
+
+/// Passes the encoded messages through, as they are only known at runtime.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct BytesCodec;
+
+impl Codec for BytesCodec {
+    type Encode = Bytes;
+    type Decode = Bytes;
+    type Encoder = Self;
+    type Decoder = Self;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        *self
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        *self
+    }
+}
+
+impl Encoder for BytesCodec {
+    type Item = Bytes;
+    type Error = Status;
+
+    fn encode(&mut self, item: Self::Item, dst: &mut EncodeBuf<'_>) -> Result<(), Self::Error> {
+        dst.put(item);
+        Ok(())
+    }
+}
+
+impl Decoder for BytesCodec {
+    type Item = Bytes;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
+        Ok(Some(src.copy_to_bytes(src.remaining())))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use prost_types::{
+        DescriptorProto, FieldDescriptorProto, FileDescriptorProto, MethodDescriptorProto,
+        ServiceDescriptorProto,
+    };
+
+    use super::*;
+
+    /// The descriptor set of `test.proto`, with the `Log` and `Ack` messages and the
+    /// `LogService` service.
+    pub(crate) fn descriptor_set() -> FileDescriptorSet {
+        let field = |name: &str, number, r#type: i32| FieldDescriptorProto {
+            name: Some(name.into()),
+            number: Some(number),
+            r#type: Some(r#type),
+            label: Some(1),
+            ..Default::default()
+        };
+        let method = |name: &str, client_streaming, server_streaming| MethodDescriptorProto {
+            name: Some(name.into()),
+            input_type: Some(".test.v1.Log".into()),
+            output_type: Some(".test.v1.Ack".into()),
+            client_streaming: Some(client_streaming),
+            server_streaming: Some(server_streaming),
+            ..Default::default()
+        };
+        FileDescriptorSet {
+            file: vec![FileDescriptorProto {
+                name: Some("test.proto".into()),
+                package: Some("test.v1".into()),
+                syntax: Some("proto3".into()),
+                message_type: vec![
+                    DescriptorProto {
+                        name: Some("Log".into()),
+                        // string and int32.
+                        field: vec![field("message", 1, 9), field("severity", 2, 5)],
+                        ..Default::default()
+                    },
+                    DescriptorProto {
+                        name: Some("Ack".into()),
+                        ..Default::default()
+                    },
+                ],
+                service: vec![ServiceDescriptorProto {
+                    name: Some("LogService".into()),
+                    method: vec![
+                        method("Push", false, false),
+                        method("PushStream", true, false),
+                        method("Watch", false, true),
+                    ],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        }
+    }
+
+    #[test]
+    fn resolves_methods() {
+        let bytes = descriptor_set().encode_to_vec();
+        let resolve = |method| resolve_method(Path::new("test.desc"), &bytes, method);
+
+        let method = resolve("test.v1.LogService/Push").unwrap();
+        assert_eq!(method.full_name(), "test.v1.LogService.Push");
+        assert_eq!(method_path(&method), "/test.v1.LogService/Push");
+        assert!(!method.is_client_streaming());
+        assert!(resolve("/test.v1.LogService/PushStream")
+            .unwrap()
+            .is_client_streaming());
+
+        assert!(matches!(
+            resolve("test.v1.LogService"),
+            Err(MethodError::InvalidMethod { .. })
+        ));
+        assert!(matches!(
+            resolve("test.v1.Other/Push"),
+            Err(MethodError::UnknownService { .. })
+        ));
+        assert!(matches!(
+            resolve("test.v1.LogService/Pull"),
+            Err(MethodError::UnknownMethod { .. })
+        ));
+        assert!(matches!(
+            resolve("test.v1.LogService/Watch"),
+            Err(MethodError::ServerStreaming { .. })
+        ));
+        assert!(matches!(
+            resolve_method(Path::new("test.desc"), b"\xff", "test.v1.LogService/Push"),
+            Err(MethodError::InvalidDescriptorSet { .. })
+        ));
+    }
+}
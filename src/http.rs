@@ -1,25 +1,37 @@
 use std::{
+    collections::hash_map::DefaultHasher,
     fmt,
+    hash::{Hash, Hasher},
+    pin::Pin,
     task::{Context, Poll},
 };
 
-use futures::future::BoxFuture;
+use bytes::Buf;
+use futures::{
+    future::{self, BoxFuture},
+    ready,
+};
 use headers::{Authorization, HeaderMapExt};
 use http::{header::HeaderValue, request::Builder, uri::InvalidUri, HeaderMap, Request, Uri};
 use hyper::{
-    body::{Body, HttpBody},
+    body::{Body, HttpBody, SizeHint},
     client,
     client::{Client, HttpConnector},
 };
 use hyper_openssl::HttpsConnector;
 use hyper_proxy::ProxyConnector;
+use pin_project::pin_project;
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 use tower::Service;
 use tracing::Instrument;
 
 use crate::{
-    config::ProxyConfig,
+    config::{
+        sandbox::{Sandbox, SandboxError},
+        ProxyConfig,
+    },
+    egress::EgressLimit,
     internal_events::http_client,
     tls::{tls_connector_builder, MaybeTlsSettings, TlsError},
 };
@@ -37,13 +49,18 @@ pub enum HttpError {
     CallRequest { source: hyper::Error },
     #[snafu(display("Failed to build HTTP request: {}", source))]
     BuildRequest { source: http::Error },
+    #[snafu(display("Failed to make HTTP(S) request: {}", source))]
+    Sandbox { source: SandboxError },
 }
 
 pub type HttpClientFuture = <HttpClient as Service<http::Request<Body>>>::Future;
 
 pub struct HttpClient<B = Body> {
-    client: Client<ProxyConnector<HttpsConnector<HttpConnector>>, B>,
+    client: Client<ProxyConnector<HttpsConnector<HttpConnector>>, EgressBody<B>>,
     user_agent: HeaderValue,
+    propagate_trace_context: bool,
+    sandbox: Sandbox,
+    egress: EgressLimit,
 }
 
 impl<B> HttpClient<B>
@@ -71,23 +88,67 @@ where
         let user_agent = HeaderValue::from_str(&format!("Vector/{}", version))
             .expect("Invalid header value for version!");
 
-        Ok(HttpClient { client, user_agent })
+        Ok(HttpClient {
+            client,
+            user_agent,
+            propagate_trace_context: false,
+            // Clients are built along with the component using them, so they enforce its sandbox.
+            sandbox: Sandbox::current(),
+            egress: EgressLimit::capture(),
+        })
+    }
+
+    /// Sets whether requests carry a W3C `traceparent` header.
+    ///
+    /// Each request carrying a `traceparent` header, which the sink sets from
+    /// its events, becomes a span of its own within that trace. The trace and
+    /// span IDs are recorded on the `http` span, so that Vector's own logs about
+    /// the request can be correlated with those of the server.
+    #[must_use]
+    pub const fn with_trace_context(mut self, propagate: bool) -> Self {
+        self.propagate_trace_context = propagate;
+        self
     }
 
     pub fn send(
         &self,
         mut request: Request<B>,
     ) -> BoxFuture<'static, Result<http::Response<Body>, HttpError>> {
-        let span = tracing::info_span!("http");
+        let span = tracing::info_span!(
+            "http",
+            trace_id = tracing::field::Empty,
+            span_id = tracing::field::Empty
+        );
         let _enter = span.enter();
 
+        if let Err(error) = self.sandbox.enforce_uri(request.uri()) {
+            return Box::pin(future::ready(Err(error).context(SandboxSnafu)));
+        }
+
         default_request_headers(&mut request, &self.user_agent);
+        if self.propagate_trace_context {
+            if let Some(trace_parent) = trace_context_header(&mut request) {
+                span.record("trace_id", &trace_parent.trace_id().as_str());
+                span.record("span_id", &trace_parent.span_id().as_str());
+            }
+        }
 
         emit!(http_client::AboutToSendHttpRequest { request: &request });
 
+        let egress = self.egress.clone();
+        let request = request.map(|body| EgressBody {
+            inner: body,
+            egress: egress.clone(),
+        });
         let response = self.client.request(request);
 
         let fut = async move {
+            // The request isn't sent until the response is polled. Sinks whose requests aren't held
+            // back by the egress layer are held back here instead.
+            if !egress.is_layered() {
+                egress.ready().await;
+            }
+
             // Capture the time right before we issue the request.
             // Request doesn't start the processing until we start polling it.
             let before = std::time::Instant::now();
@@ -124,6 +185,46 @@ where
     }
 }
 
+/// A request body counting the bytes sent against the egress limit, as they are sent.
+#[pin_project]
+struct EgressBody<B> {
+    #[pin]
+    inner: B,
+    egress: EgressLimit,
+}
+
+impl<B: HttpBody> HttpBody for EgressBody<B> {
+    type Data = B::Data;
+    type Error = B::Error;
+
+    fn poll_data(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        let this = self.project();
+        let data = ready!(this.inner.poll_data(cx));
+        if let Some(Ok(data)) = &data {
+            this.egress.record(data.remaining() as u64);
+        }
+        Poll::Ready(data)
+    }
+
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        self.project().inner.poll_trailers(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
 pub fn build_proxy_connector(
     tls_settings: MaybeTlsSettings,
     proxy_config: &ProxyConfig,
@@ -172,6 +273,124 @@ fn default_request_headers<B>(request: &mut Request<B>, user_agent: &HeaderValue
     }
 }
 
+/// Replaces the `traceparent` header of the request with a child span of it,
+/// returning the child. Requests without a valid `traceparent` are left as is.
+fn trace_context_header<B>(request: &mut Request<B>) -> Option<TraceParent> {
+    let trace_parent = request
+        .headers()
+        .get(TRACEPARENT)
+        .and_then(|value| value.to_str().ok())
+        .and_then(TraceParent::parse)?
+        .child();
+    request.headers_mut().insert(
+        TRACEPARENT,
+        HeaderValue::from_str(&trace_parent.to_string())
+            .expect("traceparent is a valid header value"),
+    );
+    Some(trace_parent)
+}
+
+/// The name of the header carrying the W3C trace context.
+pub const TRACEPARENT: &str = "traceparent";
+
+/// The value of a W3C Trace Context `traceparent` header, identifying a trace
+/// and the span within it that a request belongs to.
+///
+/// See: https://www.w3.org/TR/trace-context/#traceparent-header
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TraceParent {
+    trace_id: u128,
+    parent_id: u64,
+    flags: u8,
+}
+
+impl TraceParent {
+    const VERSION: u8 = 0;
+    const SAMPLED: u8 = 1;
+
+    /// Starts a new trace, whose IDs are derived from the seed, so that the
+    /// same events always start the same trace, even when their request is
+    /// retried.
+    pub fn derive(seed: &[u8]) -> Self {
+        let high = hash_id(&(0_u8, seed));
+        let low = hash_id(&(1_u8, seed));
+        Self {
+            trace_id: (u128::from(high) << 64 | u128::from(low)).max(1),
+            parent_id: hash_id(&(2_u8, seed)).max(1),
+            flags: Self::SAMPLED,
+        }
+    }
+
+    /// Starts a new span within the same trace, whose ID is derived from its
+    /// parent's.
+    #[must_use]
+    pub fn child(&self) -> Self {
+        Self {
+            parent_id: hash_id(&(self.trace_id, self.parent_id)).max(1),
+            ..*self
+        }
+    }
+
+    /// Parses the value of a `traceparent` header, returning `None` if it's
+    /// invalid.
+    ///
+    /// Values of later versions of the format are accepted as long as they
+    /// start like version `00` does, as the specification requires.
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.trim().split('-');
+        let mut next = |len| parts.next().filter(|part| is_lower_hex(part, len));
+        let version = u8::from_str_radix(next(2)?, 16).ok()?;
+        let trace_id = u128::from_str_radix(next(32)?, 16).ok()?;
+        let parent_id = u64::from_str_radix(next(16)?, 16).ok()?;
+        let flags = u8::from_str_radix(next(2)?, 16).ok()?;
+        if version == 0xff || (version == Self::VERSION && parts.next().is_some()) {
+            return None;
+        }
+
+        (trace_id != 0 && parent_id != 0).then(|| Self {
+            trace_id,
+            parent_id,
+            flags,
+        })
+    }
+
+    pub fn trace_id(&self) -> String {
+        format!("{:032x}", self.trace_id)
+    }
+
+    pub fn span_id(&self) -> String {
+        format!("{:016x}", self.parent_id)
+    }
+}
+
+fn hash_id(value: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Checks for `len` lowercase hexadecimal digits, which are the only ones
+/// allowed in a `traceparent`.
+fn is_lower_hex(value: &str, len: usize) -> bool {
+    value.len() == len
+        && value
+            .bytes()
+            .all(|byte| matches!(byte, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+impl fmt::Display for TraceParent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:02x}-{:032x}-{:016x}-{:02x}",
+            Self::VERSION,
+            self.trace_id,
+            self.parent_id,
+            self.flags
+        )
+    }
+}
+
 impl<B> Service<Request<B>> for HttpClient<B>
 where
     B: fmt::Debug + HttpBody + Send + 'static,
@@ -196,6 +415,9 @@ impl<B> Clone for HttpClient<B> {
         Self {
             client: self.client.clone(),
             user_agent: self.user_agent.clone(),
+            propagate_trace_context: self.propagate_trace_context,
+            sandbox: self.sandbox.clone(),
+            egress: self.egress.clone(),
         }
     }
 }
@@ -303,4 +525,68 @@ mod tests {
             Some(&HeaderValue::from_static("foo"))
         );
     }
+
+    #[test]
+    fn parses_trace_parent() {
+        let value = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let trace_parent = TraceParent::parse(value).unwrap();
+        assert_eq!(trace_parent.trace_id(), "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(trace_parent.span_id(), "00f067aa0ba902b7");
+        assert_eq!(trace_parent.to_string(), value);
+
+        // Later versions may add fields.
+        assert!(TraceParent::parse(
+            "01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra"
+        )
+        .is_some());
+
+        for invalid in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e473-00f067aa0ba902b7-01",
+        ] {
+            assert_eq!(TraceParent::parse(invalid), None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn trace_context_header_continues_trace() {
+        let value = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let mut request = Request::post("http://example.com")
+            .header(TRACEPARENT, value)
+            .body(())
+            .unwrap();
+        let trace_parent = trace_context_header(&mut request).unwrap();
+        assert_eq!(trace_parent.trace_id(), "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_ne!(trace_parent.span_id(), "00f067aa0ba902b7");
+        assert_eq!(
+            request.headers().get(TRACEPARENT).unwrap(),
+            &trace_parent.to_string()
+        );
+
+        let mut request = Request::post("http://example.com").body(()).unwrap();
+        assert_eq!(trace_context_header(&mut request), None);
+        assert!(request.headers().get(TRACEPARENT).is_none());
+    }
+
+    #[test]
+    fn derives_trace_parent() {
+        let trace_parent = TraceParent::derive(b"hello world");
+        assert_eq!(trace_parent, TraceParent::derive(b"hello world"));
+        assert_ne!(trace_parent, TraceParent::derive(b"goodbye world"));
+        assert_eq!(
+            TraceParent::parse(&trace_parent.to_string()),
+            Some(trace_parent)
+        );
+
+        let child = trace_parent.child();
+        assert_eq!(child, trace_parent.child());
+        assert_eq!(child.trace_id(), trace_parent.trace_id());
+        assert_ne!(child.span_id(), trace_parent.span_id());
+    }
 }
@@ -3,6 +3,8 @@ use std::time::Duration;
 use metrics::histogram;
 use vector_core::internal_event::InternalEvent;
 
+use crate::config::ComponentKey;
+
 #[derive(Debug)]
 pub struct AdaptiveConcurrencyLimit {
     pub concurrency: u64,
@@ -68,3 +70,28 @@ impl InternalEvent for AdaptiveConcurrencyAveragedRtt {
         histogram!("adaptive_concurrency_averaged_rtt", self.rtt);
     }
 }
+
+#[derive(Debug)]
+pub struct AdaptiveConcurrencyOverridden<'a> {
+    pub component: &'a ComponentKey,
+    pub partition: Option<&'a str>,
+    pub limit: Option<usize>,
+}
+
+impl<'a> InternalEvent for AdaptiveConcurrencyOverridden<'a> {
+    fn emit(self) {
+        match self.limit {
+            Some(limit) => info!(
+                message = "Concurrency limit overridden.",
+                component_id = %self.component,
+                partition = ?self.partition,
+                limit = %limit,
+            ),
+            None => info!(
+                message = "Concurrency limit override lifted.",
+                component_id = %self.component,
+                partition = ?self.partition,
+            ),
+        }
+    }
+}
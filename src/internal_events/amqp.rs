@@ -0,0 +1,156 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub(crate) struct AmqpEventsReceived<'a> {
+    pub byte_size: usize,
+    pub count: usize,
+    pub queue: &'a str,
+}
+
+impl<'a> InternalEvent for AmqpEventsReceived<'a> {
+    fn emit(self) {
+        trace!(
+            message = "Events received.",
+            count = %self.count,
+            byte_size = %self.byte_size,
+            queue = self.queue,
+        );
+        counter!(
+            "component_received_events_total", self.count as u64,
+            "queue" => self.queue.to_string(),
+        );
+        counter!(
+            "component_received_event_bytes_total", self.byte_size as u64,
+            "queue" => self.queue.to_string(),
+        );
+        // deprecated
+        counter!("events_in_total", self.count as u64);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct AmqpConnectionError<E> {
+    pub error: E,
+    pub stage: &'static str,
+}
+
+impl<E: std::fmt::Display> InternalEvent for AmqpConnectionError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to connect to the server.",
+            error = %self.error,
+            error_code = "failed_connecting",
+            error_type = error_type::CONNECTION_FAILED,
+            stage = self.stage,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_connecting",
+            "error_type" => error_type::CONNECTION_FAILED,
+            "stage" => self.stage,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct AmqpConsumeError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for AmqpConsumeError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to consume message.",
+            error = %self.error,
+            error_code = "consuming_message",
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "consuming_message",
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct AmqpAcknowledgementError<E> {
+    pub error: E,
+    pub negative: bool,
+}
+
+impl<E: std::fmt::Display> InternalEvent for AmqpAcknowledgementError<E> {
+    fn emit(self) {
+        let error_code = if self.negative {
+            "negative_acknowledgement_failed"
+        } else {
+            "acknowledgement_failed"
+        };
+        error!(
+            message = "Failed to acknowledge message.",
+            error = %self.error,
+            error_code = error_code,
+            error_type = error_type::ACKNOWLEDGMENT_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => error_code,
+            "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct AmqpPublishError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for AmqpPublishError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to publish message.",
+            error = %self.error,
+            error_code = "publishing_message",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::SENDING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "publishing_message",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct AmqpNegativeConfirmation;
+
+impl InternalEvent for AmqpNegativeConfirmation {
+    fn emit(self) {
+        error!(
+            message = "Message negatively acknowledged by the server.",
+            error_code = "negative_confirmation",
+            error_type = error_type::ACKNOWLEDGMENT_FAILED,
+            stage = error_stage::SENDING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "negative_confirmation",
+            "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use std::{path::Path, time::Duration};
+
+use http::StatusCode;
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type, http_error_code};
+
+#[derive(Debug)]
+pub struct AuditLogRequestError {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for AuditLogRequestError {
+    fn emit(self) {
+        error!(
+            message = "Failed to fetch audit logs.",
+            error = %self.error,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct AuditLogRateLimited {
+    pub status: StatusCode,
+    pub delay: Duration,
+}
+
+impl InternalEvent for AuditLogRateLimited {
+    fn emit(self) {
+        warn!(
+            message = "Audit log API is limiting requests, retrying later.",
+            status = %self.status,
+            delay_secs = self.delay.as_secs_f64(),
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "http_client_retries_total", 1,
+            "error_code" => http_error_code(self.status.as_u16()),
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct AuditLogCheckpointError<'a> {
+    pub error: std::io::Error,
+    pub path: &'a Path,
+}
+
+impl InternalEvent for AuditLogCheckpointError<'_> {
+    fn emit(self) {
+        error!(
+            message = "Failed to access the audit log checkpoint.",
+            error = %self.error,
+            path = ?self.path,
+            error_type = error_type::IO_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::IO_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
@@ -0,0 +1,32 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+/// Some messages of a batch were rejected by SNS or SQS, which reports the errors per message.
+#[derive(Debug)]
+pub struct AwsSSMessagesRejected<'a> {
+    pub count: usize,
+    pub code: &'a str,
+    pub message: &'a str,
+}
+
+impl<'a> InternalEvent for AwsSSMessagesRejected<'a> {
+    fn emit(self) {
+        error!(
+            message = "Messages rejected.",
+            count = %self.count,
+            error = %self.message,
+            error_code = %self.code,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::SENDING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => self.code.to_owned(),
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
@@ -197,6 +197,36 @@ impl<'a, E: std::fmt::Display> InternalEvent for SqsMessageDeleteError<'a, E> {
     }
 }
 
+#[cfg(feature = "sources-aws_sqs")]
+#[derive(Debug)]
+pub struct SqsMessagePayloadFetchError<'a, E> {
+    pub bucket: &'a str,
+    pub key: &'a str,
+    pub error: &'a E,
+}
+
+#[cfg(feature = "sources-aws_sqs")]
+impl<'a, E: std::fmt::Display> InternalEvent for SqsMessagePayloadFetchError<'a, E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to fetch SQS message payload from S3.",
+            bucket = %self.bucket,
+            key = %self.key,
+            error = %self.error,
+            error_code = "failed_fetching_sqs_message_payload",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_fetching_sqs_message_payload",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
 // AWS s3 source
 
 #[derive(Debug)]
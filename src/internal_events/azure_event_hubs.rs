@@ -0,0 +1,106 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub(crate) struct AzureEventHubsEventsReceived<'a> {
+    pub byte_size: usize,
+    pub count: usize,
+    pub partition_id: &'a str,
+}
+
+impl<'a> InternalEvent for AzureEventHubsEventsReceived<'a> {
+    fn emit(self) {
+        trace!(
+            message = "Events received.",
+            count = %self.count,
+            byte_size = %self.byte_size,
+            partition_id = self.partition_id,
+        );
+        counter!(
+            "component_received_events_total", self.count as u64,
+            "partition_id" => self.partition_id.to_string(),
+        );
+        counter!(
+            "component_received_event_bytes_total", self.byte_size as u64,
+            "partition_id" => self.partition_id.to_string(),
+        );
+        // deprecated
+        counter!("events_in_total", self.count as u64);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct AzureEventHubsConnectionError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for AzureEventHubsConnectionError<E> {
+    fn emit(self) {
+        error!(
+            message = "Connection to the event hub failed; reconnecting.",
+            error = %self.error,
+            error_code = "failed_connecting",
+            error_type = error_type::CONNECTION_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_connecting",
+            "error_type" => error_type::CONNECTION_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct AzureEventHubsLoadBalancingError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for AzureEventHubsLoadBalancingError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to balance the partitions among the consumers.",
+            error = %self.error,
+            error_code = "load_balancing_failed",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "load_balancing_failed",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct AzureEventHubsCheckpointError<'a, E> {
+    pub error: E,
+    pub partition_id: &'a str,
+}
+
+impl<'a, E: std::fmt::Display> InternalEvent for AzureEventHubsCheckpointError<'a, E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to update the checkpoint of the partition.",
+            error = %self.error,
+            partition_id = self.partition_id,
+            error_code = "checkpoint_update_failed",
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "checkpoint_update_failed",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
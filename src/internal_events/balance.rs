@@ -0,0 +1,34 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct BalanceTargetUnhealthy<'a> {
+    pub target: usize,
+    pub reason: &'a str,
+}
+
+impl<'a> InternalEvent for BalanceTargetUnhealthy<'a> {
+    fn emit(self) {
+        warn!(
+            message = "Excluding unhealthy target.",
+            target = self.target,
+            reason = %self.reason,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "balance_target_unhealthy_total", 1,
+            "target" => self.target.to_string(),
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct BalanceTargetRecovered {
+    pub target: usize,
+}
+
+impl InternalEvent for BalanceTargetRecovered {
+    fn emit(self) {
+        info!(message = "Target recovered.", target = self.target);
+    }
+}
@@ -0,0 +1,57 @@
+use std::fmt;
+
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct CassandraEncodingError<E> {
+    pub error: E,
+}
+
+impl<E: fmt::Display> InternalEvent for CassandraEncodingError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to encode event as a row of the table; dropping event.",
+            error = %self.error,
+            error_type = error_type::ENCODER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::ENCODER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+        counter!(
+            "component_discarded_events_total", 1,
+            "error_type" => error_type::ENCODER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct CassandraPrepareError<'a, E> {
+    pub error: E,
+    pub table: &'a str,
+}
+
+impl<'a, E: fmt::Display> InternalEvent for CassandraPrepareError<'a, E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to prepare the statement inserting into the table; retrying.",
+            error = %self.error,
+            table = %self.table,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::SENDING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+use metrics::{counter, gauge};
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct CircuitBreakerOpened {
+    pub failures: usize,
+    pub open: Duration,
+}
+
+impl InternalEvent for CircuitBreakerOpened {
+    fn emit(self) {
+        warn!(
+            message = "Circuit breaker opened, holding back requests.",
+            failures = %self.failures,
+            open_secs = %self.open.as_secs(),
+        );
+        counter!("circuit_breaker_opened_total", 1);
+        gauge!("circuit_breaker_state", 2.0);
+    }
+}
+
+#[derive(Debug)]
+pub struct CircuitBreakerHalfOpened;
+
+impl InternalEvent for CircuitBreakerHalfOpened {
+    fn emit(self) {
+        info!(message = "Circuit breaker half opened, probing the service.");
+        gauge!("circuit_breaker_state", 1.0);
+    }
+}
+
+#[derive(Debug)]
+pub struct CircuitBreakerClosed;
+
+impl InternalEvent for CircuitBreakerClosed {
+    fn emit(self) {
+        info!(message = "Circuit breaker closed, the service recovered.");
+        gauge!("circuit_breaker_state", 0.0);
+    }
+}
@@ -0,0 +1,33 @@
+use std::fmt;
+
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct ClickhouseEncodingError<E> {
+    pub error: E,
+}
+
+impl<E: fmt::Display> InternalEvent for ClickhouseEncodingError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to encode event as a row of the table; dropping event.",
+            error = %self.error,
+            error_type = error_type::ENCODER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::ENCODER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+        counter!(
+            "component_discarded_events_total", 1,
+            "error_type" => error_type::ENCODER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
@@ -0,0 +1,36 @@
+use metrics::{counter, gauge};
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct DatadogLogsQuotaExceeded;
+
+impl InternalEvent for DatadogLogsQuotaExceeded {
+    fn emit(self) {
+        warn!(
+            message = "Datadog API quota exceeded, the request was throttled.",
+            error_code = "http_response_429",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::SENDING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "http_response_429",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+        counter!("datadog_logs_throttled_requests_total", 1);
+        gauge!("datadog_logs_quota_exceeded", 1.0);
+    }
+}
+
+#[derive(Debug)]
+pub struct DatadogLogsRequestAccepted;
+
+impl InternalEvent for DatadogLogsRequestAccepted {
+    fn emit(self) {
+        gauge!("datadog_logs_quota_exceeded", 0.0);
+    }
+}
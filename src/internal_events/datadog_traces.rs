@@ -34,3 +34,24 @@ impl InternalEvent for DatadogTracesEncodingError {
         }
     }
 }
+
+#[derive(Debug)]
+pub struct DatadogTracesApmStatsError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for DatadogTracesApmStatsError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed sending APM stats payload.",
+            error = %self.error,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::SENDING,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
@@ -0,0 +1,36 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct DeadLetterEventsRouted {
+    pub count: usize,
+    pub status: &'static str,
+}
+
+impl InternalEvent for DeadLetterEventsRouted {
+    fn emit(self) {
+        debug!(
+            message = "Routing events the sink failed to deliver to its dead letter.",
+            count = self.count,
+            status = %self.status,
+            internal_log_rate_secs = 10,
+        );
+        counter!("dead_letter_events_total", self.count as u64, "status" => self.status);
+    }
+}
+
+#[derive(Debug)]
+pub struct DeadLetterEventsUntracked {
+    pub count: usize,
+}
+
+impl InternalEvent for DeadLetterEventsUntracked {
+    fn emit(self) {
+        warn!(
+            message = "Too many events are tracked for the dead letter, handing events to the sink untracked.",
+            count = self.count,
+            internal_log_rate_secs = 10,
+        );
+        counter!("dead_letter_untracked_events_total", self.count as u64);
+    }
+}
@@ -0,0 +1,20 @@
+use std::fmt;
+
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct DroppedEventRedactionError<E> {
+    pub error: E,
+}
+
+impl<E: fmt::Display> InternalEvent for DroppedEventRedactionError<E> {
+    fn emit(self) {
+        warn!(
+            message = "Failed to redact a sample of the dropped events; discarding sample.",
+            error = %self.error,
+            internal_log_rate_secs = 10,
+        );
+        counter!("dropped_event_redaction_errors_total", 1);
+    }
+}
@@ -0,0 +1,50 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+use crate::sources::ebpf::ParseError;
+
+#[derive(Debug)]
+pub struct EbpfParseError {
+    pub error: ParseError,
+}
+
+impl InternalEvent for EbpfParseError {
+    fn emit(self) {
+        error!(
+            message = "Failed to parse eBPF event.",
+            error = %self.error,
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct EbpfProbeError {
+    pub error: libbpf_rs::Error,
+}
+
+impl InternalEvent for EbpfProbeError {
+    fn emit(self) {
+        error!(
+            message = "Failed to load, attach or poll the eBPF probes.",
+            error = %self.error,
+            error_code = "ebpf_probe",
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "ebpf_probe",
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
@@ -0,0 +1,23 @@
+use std::time::Duration;
+
+use metrics::{counter, histogram};
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct EgressRateLimited {
+    pub scope: &'static str,
+    pub delay: Duration,
+}
+
+impl InternalEvent for EgressRateLimited {
+    fn emit(self) {
+        debug!(
+            message = "Delaying request to stay within the egress rate limit.",
+            scope = %self.scope,
+            delay = ?self.delay,
+            internal_log_rate_secs = 10,
+        );
+        counter!("egress_rate_limited_total", 1, "scope" => self.scope);
+        histogram!("egress_rate_limit_delay_seconds", self.delay, "scope" => self.scope);
+    }
+}
@@ -40,3 +40,23 @@ impl<'a> InternalEvent for ElasticsearchResponseError<'a> {
         );
     }
 }
+
+#[derive(Debug)]
+pub struct ElasticsearchMissingIdError;
+
+impl InternalEvent for ElasticsearchMissingIdError {
+    fn emit(self) {
+        error!(
+            message = "Update actions require a document id; discarding event.",
+            error_type = error_type::ENCODER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::ENCODER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+        counter!("events_discarded_total", 1);
+    }
+}
@@ -0,0 +1,71 @@
+use metrics::{counter, gauge};
+use vector_core::internal_event::InternalEvent;
+
+/// Records the row count of an enrichment table, for tables that hold their
+/// rows in memory.
+fn set_rows(table: &str, rows: Option<usize>) {
+    if let Some(rows) = rows {
+        gauge!("enrichment_table_rows", rows as f64, "table" => table.to_owned());
+    }
+}
+
+#[derive(Debug)]
+pub struct EnrichmentTableLoaded<'a> {
+    pub table: &'a str,
+    pub rows: Option<usize>,
+}
+
+impl<'a> InternalEvent for EnrichmentTableLoaded<'a> {
+    fn emit(self) {
+        debug!(message = "Loaded enrichment table.", table = %self.table, rows = ?self.rows);
+        set_rows(self.table, self.rows);
+    }
+}
+
+#[derive(Debug)]
+pub struct EnrichmentTableReloaded<'a> {
+    pub table: &'a str,
+    pub rows: Option<usize>,
+}
+
+impl<'a> InternalEvent for EnrichmentTableReloaded<'a> {
+    fn emit(self) {
+        info!(message = "Reloaded enrichment table.", table = %self.table, rows = ?self.rows);
+        counter!("enrichment_table_reloads_total", 1, "table" => self.table.to_owned());
+        set_rows(self.table, self.rows);
+    }
+}
+
+#[derive(Debug)]
+pub struct EnrichmentTableReloadFailed<'a> {
+    pub table: &'a str,
+    pub error: String,
+}
+
+impl<'a> InternalEvent for EnrichmentTableReloadFailed<'a> {
+    fn emit(self) {
+        error!(
+            message = "Unable to reload enrichment table; keeping the previously loaded data.",
+            table = %self.table,
+            error = %self.error,
+            internal_log_rate_secs = 30,
+        );
+        counter!("enrichment_table_reload_errors_total", 1, "table" => self.table.to_owned());
+    }
+}
+
+#[derive(Debug)]
+pub struct EnrichmentTableFetchFailed {
+    pub error: String,
+}
+
+impl InternalEvent for EnrichmentTableFetchFailed {
+    fn emit(self) {
+        error!(
+            message = "Unable to fetch enrichment table rows.",
+            error = %self.error,
+            internal_log_rate_secs = 30,
+        );
+        counter!("enrichment_table_fetch_errors_total", 1);
+    }
+}
@@ -0,0 +1,40 @@
+use metrics::{counter, gauge};
+use vector_core::internal_event::InternalEvent;
+
+const TARGETS: [&str; 2] = ["primary", "secondary"];
+
+fn set_active_target(target: &str) {
+    for other in TARGETS {
+        let active = if other == target { 1.0 } else { 0.0 };
+        gauge!("failover_active_target", active, "target" => other);
+    }
+}
+
+#[derive(Debug)]
+pub struct FailoverActiveTarget {
+    pub target: &'static str,
+}
+
+impl InternalEvent for FailoverActiveTarget {
+    fn emit(self) {
+        set_active_target(self.target);
+    }
+}
+
+#[derive(Debug)]
+pub struct FailoverSwitched<'a> {
+    pub target: &'static str,
+    pub reason: &'a str,
+}
+
+impl<'a> InternalEvent for FailoverSwitched<'a> {
+    fn emit(self) {
+        warn!(
+            message = "Switched target.",
+            target = self.target,
+            reason = %self.reason,
+        );
+        counter!("failover_switches_total", 1, "target" => self.target);
+        set_active_target(self.target);
+    }
+}
@@ -72,7 +72,7 @@ mod source {
     use std::{io::Error, path::Path, time::Duration};
 
     use file_source::FileSourceInternalEvents;
-    use metrics::counter;
+    use metrics::{counter, gauge};
 
     use super::{FileOpen, InternalEvent};
     use crate::emit;
@@ -321,6 +321,7 @@ mod source {
     #[derive(Debug)]
     pub struct FileCheckpointed {
         pub count: usize,
+        pub file_size: u64,
         pub duration: Duration,
     }
 
@@ -329,9 +330,24 @@ mod source {
             debug!(
                 message = "Files checkpointed.",
                 count = %self.count,
+                file_size = %self.file_size,
                 duration_ms = self.duration.as_millis() as u64,
             );
             counter!("checkpoints_total", self.count as u64);
+            gauge!("checkpoint_entries", self.count as f64);
+            gauge!("checkpoint_file_size_bytes", self.file_size as f64);
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct FileCheckpointsRemoved {
+        pub count: usize,
+    }
+
+    impl InternalEvent for FileCheckpointsRemoved {
+        fn emit(self) {
+            debug!(message = "Checkpoints removed.", count = %self.count);
+            counter!("checkpoints_removed_total", self.count as u64);
         }
     }
 
@@ -429,8 +445,16 @@ mod source {
             emit!(FileChecksumFailed { file });
         }
 
-        fn emit_file_checkpointed(&self, count: usize, duration: Duration) {
-            emit!(FileCheckpointed { count, duration });
+        fn emit_file_checkpointed(&self, count: usize, file_size: u64, duration: Duration) {
+            emit!(FileCheckpointed {
+                count,
+                file_size,
+                duration
+            });
+        }
+
+        fn emit_file_checkpoints_removed(&self, count: usize) {
+            emit!(FileCheckpointsRemoved { count });
         }
 
         fn emit_file_checkpoint_write_error(&self, error: Error) {
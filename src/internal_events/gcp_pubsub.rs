@@ -71,3 +71,27 @@ impl InternalEvent for GcpPubsubReceiveError {
         );
     }
 }
+
+pub struct GcpPubsubAcknowledgementError {
+    pub count: usize,
+}
+
+impl InternalEvent for GcpPubsubAcknowledgementError {
+    fn emit(self) {
+        error!(
+            message = "Acknowledgements were rejected by the subscription; their messages will be redelivered.",
+            count = %self.count,
+            error_code = "acknowledgement_rejected",
+            error_type = error_type::ACKNOWLEDGMENT_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "acknowledgement_rejected",
+            "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
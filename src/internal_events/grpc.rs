@@ -0,0 +1,59 @@
+use std::fmt;
+
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct GrpcMessageDecodeError<'a> {
+    pub error: prost::DecodeError,
+    pub message_type: &'a str,
+}
+
+impl<'a> InternalEvent for GrpcMessageDecodeError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to decode gRPC message.",
+            error = %self.error,
+            message_type = %self.message_type,
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct GrpcMessageEncodeError<'a, E> {
+    pub error: E,
+    pub message_type: &'a str,
+}
+
+impl<'a, E: fmt::Display> InternalEvent for GrpcMessageEncodeError<'a, E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to encode event as gRPC message; dropping event.",
+            error = %self.error,
+            message_type = %self.message_type,
+            error_type = error_type::ENCODER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::ENCODER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+        counter!(
+            "component_discarded_events_total", 1,
+            "error_type" => error_type::ENCODER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
@@ -0,0 +1,19 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub(crate) struct JoinEventDiscarded {
+    pub reason: &'static str,
+}
+
+impl InternalEvent for JoinEventDiscarded {
+    fn emit(self) {
+        warn!(
+            message = "Event discarded without a match.",
+            reason = %self.reason,
+            internal_log_rate_secs = 10,
+        );
+        counter!("events_discarded_total", 1);
+        counter!("component_discarded_events_total", 1);
+    }
+}
@@ -109,6 +109,24 @@ impl InternalEvent for KafkaReadError {
     }
 }
 
+#[derive(Debug)]
+pub struct KafkaRevokedPartitionsDrainTimeout {
+    pub partitions: usize,
+    pub timeout: std::time::Duration,
+}
+
+impl InternalEvent for KafkaRevokedPartitionsDrainTimeout {
+    fn emit(self) {
+        warn!(
+            message = "Timed out waiting for the events of the revoked partitions to be acknowledged; they may be consumed again.",
+            partitions = %self.partitions,
+            timeout_ms = %self.timeout.as_millis(),
+            internal_log_rate_secs = 10,
+        );
+        counter!("kafka_revoked_partitions_drain_timeouts_total", 1);
+    }
+}
+
 #[derive(Debug)]
 pub struct KafkaStatisticsReceived<'a> {
     pub statistics: &'a rdkafka::Statistics,
@@ -173,3 +191,63 @@ impl InternalEvent for KafkaHeaderExtractionError<'_> {
         counter!("kafka_header_extraction_failures_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct KafkaTransactionAborted<'a> {
+    pub error: &'a rdkafka::error::KafkaError,
+    pub retry: bool,
+}
+
+impl InternalEvent for KafkaTransactionAborted<'_> {
+    fn emit(self) {
+        if self.retry {
+            error!(
+                message = "Failed to produce the transaction; aborting and producing it again.",
+                error = %self.error,
+                error_code = "transaction_aborted",
+                error_type = error_type::REQUEST_FAILED,
+                stage = error_stage::SENDING,
+                internal_log_rate_secs = 10,
+            );
+        } else {
+            error!(
+                message = "Failed to produce the transaction with a permanent error; aborting it and rejecting its events.",
+                error = %self.error,
+                error_code = "transaction_aborted",
+                error_type = error_type::REQUEST_FAILED,
+                stage = error_stage::SENDING,
+                internal_log_rate_secs = 10,
+            );
+        }
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "transaction_aborted",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct KafkaSchemaRegistryError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for KafkaSchemaRegistryError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to resolve the schema of the message.",
+            error = %self.error,
+            error_code = "schema_resolution_failed",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "schema_resolution_failed",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
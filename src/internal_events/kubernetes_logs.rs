@@ -186,3 +186,27 @@ impl<E: std::fmt::Display> InternalEvent for KubernetesLifecycleError<E> {
         );
     }
 }
+
+#[derive(Debug)]
+pub struct KubernetesMetadataCacheError {
+    pub error: std::io::Error,
+}
+
+impl InternalEvent for KubernetesMetadataCacheError {
+    fn emit(self) {
+        error!(
+            message = "Failed to write the Kubernetes metadata cache.",
+            error = %self.error,
+            error_code = "writing_metadata_cache",
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::PROCESSING,
+            rate_limit_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "writing_metadata_cache",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
@@ -93,3 +93,51 @@ impl InternalEvent for LogToMetricTemplateParseError {
         );
     }
 }
+
+pub struct LogToMetricVrlError<'a> {
+    pub expression: &'a str,
+    pub error: String,
+}
+
+impl<'a> InternalEvent for LogToMetricVrlError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to compute metric from VRL expression.",
+            error = %self.error,
+            expression = %self.expression,
+            error_code = "failed_vrl_expression",
+            error_type = error_type::SCRIPT_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 30,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_vrl_expression",
+            "error_type" => error_type::SCRIPT_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+pub struct LogToMetricDecodeError {
+    pub error: serde_json::Error,
+}
+
+impl InternalEvent for LogToMetricDecodeError {
+    fn emit(self) {
+        error!(
+            message = "Failed to decode metric from log.",
+            error = %self.error,
+            error_code = "failed_decoding_metric",
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 30
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_decoding_metric",
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
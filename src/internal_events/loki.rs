@@ -49,3 +49,35 @@ impl InternalEvent for LokiOutOfOrderEventRewritten {
         counter!("rewritten_timestamp_events_total", self.count as u64);
     }
 }
+
+#[derive(Debug)]
+pub struct LokiOutOfOrderEventRestreamed {
+    pub count: usize,
+}
+
+impl InternalEvent for LokiOutOfOrderEventRestreamed {
+    fn emit(self) {
+        debug!(
+            message = "Received out-of-order events, sending them to sibling streams.",
+            count = %self.count,
+            internal_log_rate_secs = 10,
+        );
+        counter!("restreamed_events_total", self.count as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct LokiOutOfOrderEventAccepted {
+    pub count: usize,
+}
+
+impl InternalEvent for LokiOutOfOrderEventAccepted {
+    fn emit(self) {
+        trace!(
+            message = "Received out-of-order events, sending them as they are.",
+            count = %self.count,
+            internal_log_rate_secs = 10,
+        );
+        counter!("out_of_order_accepted_events_total", self.count as u64);
+    }
+}
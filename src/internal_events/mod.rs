@@ -4,11 +4,15 @@ mod adaptive_concurrency;
 mod add_fields;
 mod add_tags;
 mod aggregate;
+#[cfg(any(feature = "sources-amqp", feature = "sinks-amqp"))]
+mod amqp;
 mod ansi_stripper;
 #[cfg(feature = "sources-apache_metrics")]
 mod apache_metrics;
 #[cfg(feature = "api")]
 mod api;
+#[cfg(feature = "sources-utils-audit-log")]
+mod audit_log;
 #[cfg(feature = "aws-core")]
 mod aws;
 #[cfg(any(
@@ -22,21 +26,35 @@ mod aws_ec2_metadata;
 mod aws_ecs_metrics;
 #[cfg(feature = "sources-aws_kinesis_firehose")]
 mod aws_kinesis_firehose;
+#[cfg(any(feature = "sinks-aws_sns", feature = "sinks-aws_sqs"))]
+mod aws_s_s;
 #[cfg(any(feature = "sources-aws_s3", feature = "sources-aws_sqs",))]
 mod aws_sqs;
 #[cfg(any(feature = "sinks-azure_blob", feature = "sinks-datadog_archives"))]
 pub(crate) mod azure_blob;
+#[cfg(feature = "sources-azure_event_hubs")]
+mod azure_event_hubs;
+#[cfg(feature = "sinks-balance")]
+mod balance;
 mod batch;
+#[cfg(feature = "sinks-cassandra")]
+mod cassandra;
+mod circuit_breaker;
+#[cfg(feature = "sinks-clickhouse")]
+mod clickhouse;
 #[cfg(feature = "transforms-coercer")]
 mod coercer;
 mod common;
 #[cfg(feature = "transforms-concat")]
 mod concat;
 mod conditions;
+#[cfg(feature = "sinks-datadog_logs")]
+mod datadog_logs;
 #[cfg(feature = "sinks-datadog_metrics")]
 mod datadog_metrics;
 #[cfg(feature = "sinks-datadog_traces")]
 mod datadog_traces;
+mod dead_letter;
 mod decoder;
 #[cfg(feature = "transforms-dedupe")]
 mod dedupe;
@@ -44,14 +62,23 @@ mod dedupe;
 mod demo_logs;
 #[cfg(feature = "sources-dnstap")]
 mod dnstap;
+#[cfg(feature = "sinks-doris")]
+mod doris;
 #[cfg(feature = "sources-docker_logs")]
 mod docker_logs;
+#[cfg(all(target_os = "linux", feature = "sources-ebpf"))]
+mod ebpf;
+mod dropped_events;
+mod egress;
 mod elasticsearch;
 mod encoding_transcode;
+mod enrichment_tables;
 #[cfg(feature = "sources-eventstoredb_metrics")]
 mod eventstoredb_metrics;
 #[cfg(feature = "sources-exec")]
 mod exec;
+#[cfg(feature = "sinks-failover")]
+mod failover;
 #[cfg(feature = "transforms-filter")]
 mod filter;
 #[cfg(feature = "sources-fluent")]
@@ -60,11 +87,15 @@ mod fluent;
 mod gcp_pubsub;
 #[cfg(feature = "transforms-geoip")]
 mod geoip;
+#[cfg(any(feature = "sources-grpc", feature = "sinks-grpc"))]
+mod grpc;
 mod heartbeat;
 mod http;
 pub mod http_client;
 #[cfg(feature = "sources-internal_logs")]
 mod internal_logs;
+#[cfg(feature = "transforms-join")]
+mod join;
 #[cfg(all(unix, feature = "sources-journald"))]
 mod journald;
 #[cfg(feature = "transforms-json_parser")]
@@ -84,13 +115,28 @@ mod loki;
 mod lua;
 #[cfg(feature = "transforms-metric_to_log")]
 mod metric_to_log;
+#[cfg(feature = "sources-mongodb_change_stream")]
+mod mongodb_change_stream;
 #[cfg(feature = "sources-mongodb_metrics")]
 mod mongodb_metrics;
-#[cfg(feature = "sinks-nats")]
+#[cfg(any(feature = "sources-mqtt", feature = "sinks-mqtt"))]
+mod mqtt;
+#[cfg(any(feature = "sources-nats", feature = "sinks-nats"))]
 mod nats;
+#[cfg(feature = "sources-netflow")]
+mod netflow;
 #[cfg(feature = "sources-nginx_metrics")]
 mod nginx_metrics;
+#[cfg(any(
+    feature = "sinks-aws_s3",
+    feature = "sinks-azure_blob",
+    feature = "sinks-gcp"
+))]
+mod object_manifest;
 mod open;
+#[cfg(feature = "sinks-opentelemetry")]
+mod opentelemetry_sink;
+mod overflow_sink;
 #[cfg(any(
     feature = "sinks-datadog_events",
     feature = "transforms-geoip",
@@ -104,6 +150,8 @@ mod open;
     feature = "transforms-tokenizer",
 ))]
 mod parser;
+#[cfg(feature = "sources-postgresql_cdc")]
+mod postgresql_cdc;
 #[cfg(feature = "sources-postgresql_metrics")]
 mod postgresql_metrics;
 mod process;
@@ -120,8 +168,12 @@ mod remove_fields;
 #[cfg(feature = "transforms-rename_fields")]
 mod rename_fields;
 mod sample;
+mod sandbox;
 #[cfg(feature = "sinks-sematext")]
 mod sematext_metrics;
+mod sequence;
+#[cfg(feature = "sources-snmp")]
+mod snmp;
 mod socket;
 #[cfg(any(feature = "sources-splunk_hec", feature = "sinks-splunk_hec"))]
 mod splunk_hec;
@@ -137,11 +189,20 @@ mod tcp;
 mod template;
 #[cfg(feature = "transforms-throttle")]
 mod throttle;
+#[cfg(any(feature = "sinks-jaeger", feature = "sinks-zipkin"))]
+mod trace_spans;
 mod udp;
 mod unix;
+mod unsupported_events;
+#[cfg(feature = "transforms-validate")]
+mod validate;
 mod vector;
-#[cfg(feature = "sinks-websocket")]
+#[cfg(any(feature = "sources-websocket", feature = "sinks-websocket"))]
 mod websocket;
+#[cfg(all(windows, feature = "sources-windows_event_log"))]
+mod windows_event_log;
+#[cfg(all(windows, feature = "sources-windows_perf_counters"))]
+mod windows_perf_counters;
 
 #[cfg(any(
     feature = "sources-file",
@@ -151,6 +212,8 @@ mod websocket;
 mod file;
 mod windows;
 
+#[cfg(feature = "sources-mongodb_change_stream")]
+pub(crate) use mongodb_change_stream::*;
 #[cfg(feature = "sources-mongodb_metrics")]
 pub(crate) use mongodb_metrics::*;
 
@@ -160,12 +223,16 @@ pub(crate) use self::add_fields::*;
 pub(crate) use self::add_tags::*;
 #[cfg(feature = "transforms-aggregate")]
 pub(crate) use self::aggregate::*;
+#[cfg(any(feature = "sources-amqp", feature = "sinks-amqp"))]
+pub(crate) use self::amqp::*;
 #[cfg(feature = "transforms-ansi_stripper")]
 pub(crate) use self::ansi_stripper::*;
 #[cfg(feature = "sources-apache_metrics")]
 pub(crate) use self::apache_metrics::*;
 #[cfg(feature = "api")]
 pub(crate) use self::api::*;
+#[cfg(feature = "sources-utils-audit-log")]
+pub(crate) use self::audit_log::*;
 #[cfg(feature = "aws-core")]
 pub(crate) use self::aws::*;
 #[cfg(any(
@@ -179,12 +246,24 @@ pub(crate) use self::aws_ec2_metadata::*;
 pub(crate) use self::aws_ecs_metrics::*;
 #[cfg(feature = "sources-aws_kinesis_firehose")]
 pub(crate) use self::aws_kinesis_firehose::*;
+#[cfg(any(feature = "sinks-aws_sns", feature = "sinks-aws_sqs"))]
+pub(crate) use self::aws_s_s::*;
 #[cfg(any(feature = "sources-aws_s3", feature = "sources-aws_sqs",))]
 pub(crate) use self::aws_sqs::*;
+#[cfg(feature = "sources-azure_event_hubs")]
+pub(crate) use self::azure_event_hubs::*;
+#[cfg(feature = "sinks-balance")]
+pub(crate) use self::balance::*;
+#[cfg(feature = "sinks-cassandra")]
+pub(crate) use self::cassandra::*;
+#[cfg(feature = "sinks-clickhouse")]
+pub(crate) use self::clickhouse::*;
 #[cfg(feature = "transforms-coercer")]
 pub(crate) use self::coercer::*;
 #[cfg(feature = "transforms-concat")]
 pub(crate) use self::concat::*;
+#[cfg(feature = "sinks-datadog_logs")]
+pub(crate) use self::datadog_logs::*;
 #[cfg(feature = "sinks-datadog_metrics")]
 pub(crate) use self::datadog_metrics::*;
 #[cfg(feature = "sinks-datadog_traces")]
@@ -196,14 +275,20 @@ pub(crate) use self::dedupe::*;
 pub(crate) use self::demo_logs::*;
 #[cfg(feature = "sources-dnstap")]
 pub(crate) use self::dnstap::*;
+#[cfg(feature = "sinks-doris")]
+pub(crate) use self::doris::*;
 #[cfg(feature = "sources-docker_logs")]
 pub(crate) use self::docker_logs::*;
+#[cfg(all(target_os = "linux", feature = "sources-ebpf"))]
+pub(crate) use self::ebpf::*;
 #[cfg(feature = "sinks-elasticsearch")]
 pub(crate) use self::elasticsearch::*;
 #[cfg(feature = "sources-eventstoredb_metrics")]
 pub(crate) use self::eventstoredb_metrics::*;
 #[cfg(feature = "sources-exec")]
 pub(crate) use self::exec::*;
+#[cfg(feature = "sinks-failover")]
+pub(crate) use self::failover::*;
 #[cfg(any(
     feature = "sources-file",
     feature = "sources-kubernetes_logs",
@@ -218,6 +303,8 @@ pub(crate) use self::fluent::*;
 pub(crate) use self::gcp_pubsub::*;
 #[cfg(feature = "transforms-geoip")]
 pub(crate) use self::geoip::*;
+#[cfg(any(feature = "sources-grpc", feature = "sinks-grpc"))]
+pub(crate) use self::grpc::*;
 #[cfg(any(
     feature = "sources-utils-http",
     feature = "sources-utils-http-encoding",
@@ -227,6 +314,8 @@ pub(crate) use self::geoip::*;
 pub(crate) use self::http::*;
 #[cfg(feature = "sources-internal_logs")]
 pub(crate) use self::internal_logs::*;
+#[cfg(feature = "transforms-join")]
+pub(crate) use self::join::*;
 #[cfg(all(unix, feature = "sources-journald"))]
 pub(crate) use self::journald::*;
 #[cfg(feature = "transforms-json_parser")]
@@ -247,10 +336,22 @@ pub(crate) use self::loki::*;
 pub(crate) use self::lua::*;
 #[cfg(feature = "transforms-metric_to_log")]
 pub(crate) use self::metric_to_log::*;
-#[cfg(feature = "sinks-nats")]
+#[cfg(any(feature = "sources-mqtt", feature = "sinks-mqtt"))]
+pub(crate) use self::mqtt::*;
+#[cfg(any(feature = "sources-nats", feature = "sinks-nats"))]
 pub(crate) use self::nats::*;
+#[cfg(feature = "sources-netflow")]
+pub(crate) use self::netflow::*;
 #[cfg(feature = "sources-nginx_metrics")]
 pub(crate) use self::nginx_metrics::*;
+#[cfg(any(
+    feature = "sinks-aws_s3",
+    feature = "sinks-azure_blob",
+    feature = "sinks-gcp"
+))]
+pub(crate) use self::object_manifest::*;
+#[cfg(feature = "sinks-opentelemetry")]
+pub(crate) use self::opentelemetry_sink::*;
 #[cfg(any(
     feature = "sinks-datadog_events",
     feature = "transforms-geoip",
@@ -264,11 +365,13 @@ pub(crate) use self::nginx_metrics::*;
     feature = "transforms-tokenizer",
 ))]
 pub(crate) use self::parser::*;
+#[cfg(feature = "sources-postgresql_cdc")]
+pub(crate) use self::postgresql_cdc::*;
 #[cfg(feature = "sources-postgresql_metrics")]
 pub(crate) use self::postgresql_metrics::*;
 #[cfg(any(feature = "sources-prometheus", feature = "sinks-prometheus"))]
 pub(crate) use self::prometheus::*;
-#[cfg(feature = "sinks-pulsar")]
+#[cfg(any(feature = "sources-pulsar", feature = "sinks-pulsar"))]
 pub(crate) use self::pulsar::*;
 #[cfg(any(feature = "sources-redis", feature = "sinks-redis"))]
 pub(crate) use self::redis::*;
@@ -284,6 +387,8 @@ pub(crate) use self::rename_fields::*;
 pub(crate) use self::sample::*;
 #[cfg(feature = "sinks-sematext")]
 pub(crate) use self::sematext_metrics::*;
+#[cfg(feature = "sources-snmp")]
+pub(crate) use self::snmp::*;
 #[cfg(any(feature = "sources-splunk_hec", feature = "sinks-splunk_hec"))]
 pub(crate) use self::splunk_hec::*;
 #[cfg(feature = "sinks-statsd")]
@@ -296,6 +401,8 @@ pub(crate) use self::syslog::*;
 pub(crate) use self::tag_cardinality_limit::*;
 #[cfg(feature = "transforms-throttle")]
 pub(crate) use self::throttle::*;
+#[cfg(any(feature = "sinks-jaeger", feature = "sinks-zipkin"))]
+pub(crate) use self::trace_spans::*;
 #[cfg(all(
     any(
         feature = "sinks-socket",
@@ -309,15 +416,23 @@ pub(crate) use self::throttle::*;
     unix
 ))]
 pub(crate) use self::unix::*;
-#[cfg(feature = "sources-vector")]
+#[cfg(feature = "transforms-validate")]
+pub(crate) use self::validate::*;
+#[cfg(any(feature = "sources-vector", feature = "sinks-vector"))]
 pub(crate) use self::vector::*;
-#[cfg(feature = "sinks-websocket")]
+#[cfg(any(feature = "sources-websocket", feature = "sinks-websocket"))]
 pub(crate) use self::websocket::*;
 #[cfg(windows)]
 pub(crate) use self::windows::*;
+#[cfg(all(windows, feature = "sources-windows_event_log"))]
+pub(crate) use self::windows_event_log::*;
+#[cfg(all(windows, feature = "sources-windows_perf_counters"))]
+pub(crate) use self::windows_perf_counters::*;
 pub(crate) use self::{
-    adaptive_concurrency::*, batch::*, common::*, conditions::*, encoding_transcode::*,
-    heartbeat::*, open::*, process::*, socket::*, tcp::*, template::*, udp::*,
+    adaptive_concurrency::*, batch::*, circuit_breaker::*, common::*, conditions::*,
+    dead_letter::*, dropped_events::*, egress::*, encoding_transcode::*, enrichment_tables::*,
+    heartbeat::*, open::*, overflow_sink::*, process::*, sandbox::*, sequence::*, socket::*,
+    tcp::*, template::*, udp::*, unsupported_events::*,
 };
 
 // this version won't be needed once all `InternalEvent`s implement `name()`
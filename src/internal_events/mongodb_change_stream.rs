@@ -0,0 +1,52 @@
+use std::path::Path;
+
+use metrics::counter;
+use mongodb::error::Error as MongoError;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct MongoDbChangeStreamError {
+    pub error: MongoError,
+}
+
+impl InternalEvent for MongoDbChangeStreamError {
+    fn emit(self) {
+        error!(
+            message = "Failed to read the change stream.",
+            error = %self.error,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct MongoDbChangeStreamCheckpointError<'a> {
+    pub error: crate::Error,
+    pub path: &'a Path,
+}
+
+impl<'a> InternalEvent for MongoDbChangeStreamCheckpointError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to read or write the resume token checkpoint.",
+            error = %self.error,
+            path = ?self.path,
+            error_type = error_type::IO_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::IO_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
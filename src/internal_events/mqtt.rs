@@ -0,0 +1,105 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub(crate) struct MqttEventsReceived<'a> {
+    pub byte_size: usize,
+    pub count: usize,
+    pub topic: &'a str,
+}
+
+impl<'a> InternalEvent for MqttEventsReceived<'a> {
+    fn emit(self) {
+        trace!(
+            message = "Events received.",
+            count = %self.count,
+            byte_size = %self.byte_size,
+            topic = self.topic,
+        );
+        counter!(
+            "component_received_events_total", self.count as u64,
+            "topic" => self.topic.to_string(),
+        );
+        counter!(
+            "component_received_event_bytes_total", self.byte_size as u64,
+            "topic" => self.topic.to_string(),
+        );
+        // deprecated
+        counter!("events_in_total", self.count as u64);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct MqttConnectionError<E> {
+    pub error: E,
+    pub stage: &'static str,
+}
+
+impl<E: std::fmt::Display> InternalEvent for MqttConnectionError<E> {
+    fn emit(self) {
+        error!(
+            message = "Connection to the broker failed; reconnecting.",
+            error = %self.error,
+            error_code = "failed_connecting",
+            error_type = error_type::CONNECTION_FAILED,
+            stage = self.stage,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_connecting",
+            "error_type" => error_type::CONNECTION_FAILED,
+            "stage" => self.stage,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct MqttAcknowledgementError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for MqttAcknowledgementError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to acknowledge message.",
+            error = %self.error,
+            error_code = "acknowledgement_failed",
+            error_type = error_type::ACKNOWLEDGMENT_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "acknowledgement_failed",
+            "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct MqttPublishError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for MqttPublishError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to publish message.",
+            error = %self.error,
+            error_code = "publishing_message",
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::SENDING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "publishing_message",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
@@ -29,3 +29,51 @@ impl InternalEvent for NatsEventSendError {
         counter!("send_errors_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct NatsJetStreamPullError {
+    pub error: Error,
+}
+
+impl InternalEvent for NatsJetStreamPullError {
+    fn emit(self) {
+        error!(
+            message = "Failed to request messages from the consumer; retrying.",
+            error = %self.error,
+            error_type = error_type::REQUEST_FAILED,
+            error_code = io_error_code(&self.error),
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::REQUEST_FAILED,
+            "error_code" => io_error_code(&self.error),
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct NatsAcknowledgementError {
+    pub error: Error,
+}
+
+impl InternalEvent for NatsAcknowledgementError {
+    fn emit(self) {
+        error!(
+            message = "Failed to acknowledge message.",
+            error = %self.error,
+            error_type = error_type::ACKNOWLEDGMENT_FAILED,
+            error_code = io_error_code(&self.error),
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+            "error_code" => io_error_code(&self.error),
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
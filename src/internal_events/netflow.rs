@@ -0,0 +1,103 @@
+use std::{io, net::SocketAddr};
+
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+use crate::sources::netflow::ParseError;
+
+#[derive(Debug)]
+enum NetflowSocketErrorType {
+    Bind,
+    Read,
+}
+
+#[derive(Debug)]
+pub struct NetflowSocketError {
+    r#type: NetflowSocketErrorType,
+    pub error: io::Error,
+}
+
+impl NetflowSocketError {
+    pub const fn bind(error: io::Error) -> Self {
+        Self {
+            r#type: NetflowSocketErrorType::Bind,
+            error,
+        }
+    }
+
+    pub const fn read(error: io::Error) -> Self {
+        Self {
+            r#type: NetflowSocketErrorType::Read,
+            error,
+        }
+    }
+}
+
+impl InternalEvent for NetflowSocketError {
+    fn emit(self) {
+        let (message, error_code) = match self.r#type {
+            NetflowSocketErrorType::Bind => (
+                "Failed to bind to UDP listener socket.",
+                "failed_udp_binding",
+            ),
+            NetflowSocketErrorType::Read => ("Failed to read UDP datagram.", "failed_udp_datagram"),
+        };
+        error!(
+            message = %message,
+            error = %self.error,
+            error_code = %error_code,
+            error_type = error_type::CONNECTION_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => error_code,
+            "error_type" => error_type::CONNECTION_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct NetflowParseError<'a> {
+    pub error: ParseError,
+    pub exporter: &'a SocketAddr,
+}
+
+impl<'a> InternalEvent for NetflowParseError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to parse message.",
+            error = %self.error,
+            exporter = %self.exporter,
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct NetflowMissingTemplate<'a> {
+    pub template_id: u16,
+    pub exporter: &'a SocketAddr,
+}
+
+impl<'a> InternalEvent for NetflowMissingTemplate<'a> {
+    fn emit(self) {
+        warn!(
+            message = "Discarding records of a template that wasn't received yet.",
+            template_id = self.template_id,
+            exporter = %self.exporter,
+            internal_log_rate_secs = 30,
+        );
+        counter!("netflow_missing_templates_total", 1);
+    }
+}
@@ -0,0 +1,52 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct ObjectManifestWriteError<'a> {
+    pub key: &'a str,
+    pub error: crate::Error,
+}
+
+impl<'a> InternalEvent for ObjectManifestWriteError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed writing object manifest.",
+            key = %self.key,
+            error = %self.error,
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::SENDING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct ObjectManifestReadError<'a> {
+    pub key: &'a str,
+    pub error: crate::Error,
+}
+
+impl<'a> InternalEvent for ObjectManifestReadError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed reading object manifest, the objects will be added to it once it can be read.",
+            key = %self.key,
+            error = %self.error,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::SENDING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
@@ -0,0 +1,35 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+use crate::event::metric::MetricValue;
+
+#[derive(Debug)]
+pub struct OpentelemetryUnsupportedMetricError<'a> {
+    pub value: &'a MetricValue,
+}
+
+impl<'a> InternalEvent for OpentelemetryUnsupportedMetricError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Metric type has no OTLP counterpart; dropping event.",
+            error_code = "unsupported_metric",
+            error_type = error_type::ENCODER_FAILED,
+            stage = error_stage::PROCESSING,
+            value = ?self.value,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "unsupported_metric",
+            "error_type" => error_type::ENCODER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+        counter!(
+            "component_discarded_events_total", 1,
+            "error_code" => "unsupported_metric",
+            "error_type" => error_type::ENCODER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
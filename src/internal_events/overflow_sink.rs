@@ -0,0 +1,18 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct OverflowSinkEventsRouted {
+    pub count: usize,
+}
+
+impl InternalEvent for OverflowSinkEventsRouted {
+    fn emit(self) {
+        debug!(
+            message = "Routing events that don't fit in the buffer to the overflow sink.",
+            count = %self.count,
+            internal_log_rate_secs = 10,
+        );
+        counter!("overflow_sink_events_total", self.count as u64);
+    }
+}
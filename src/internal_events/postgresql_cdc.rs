@@ -0,0 +1,90 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct PostgresqlCdcConnectionError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for PostgresqlCdcConnectionError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to connect to PostgreSQL.",
+            error = %self.error,
+            error_type = error_type::CONNECTION_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::CONNECTION_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct PostgresqlCdcQueryError<'a> {
+    pub error: &'a tokio_postgres::Error,
+}
+
+impl<'a> InternalEvent for PostgresqlCdcQueryError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to read the changes of the replication slot.",
+            error = %self.error,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct PostgresqlCdcParseError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for PostgresqlCdcParseError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to parse logical replication message.",
+            error = %self.error,
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct PostgresqlCdcAdvanceError<'a> {
+    pub error: &'a tokio_postgres::Error,
+}
+
+impl<'a> InternalEvent for PostgresqlCdcAdvanceError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to advance the replication slot.",
+            error = %self.error,
+            error_type = error_type::ACKNOWLEDGMENT_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
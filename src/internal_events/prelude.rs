@@ -52,6 +52,7 @@ pub mod error_type {
     feature = "sources-aws_ecs_metrics",
     feature = "sources-aws_kinesis_firehose",
     feature = "sources-prometheus",
+    feature = "sources-utils-audit-log",
     feature = "sources-utils-http",
 ))]
 pub(crate) fn http_error_code(code: u16) -> String {
@@ -130,6 +130,33 @@ impl InternalEvent for PrometheusHttpError {
     }
 }
 
+#[cfg(feature = "sources-prometheus")]
+#[derive(Debug)]
+pub struct PrometheusDiscoveryError {
+    pub error: crate::Error,
+    pub mechanism: &'static str,
+}
+
+#[cfg(feature = "sources-prometheus")]
+impl InternalEvent for PrometheusDiscoveryError {
+    fn emit(self) {
+        error!(
+            message = "Failed to discover targets, keeping the previous ones.",
+            mechanism = %self.mechanism,
+            error = ?self.error,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "mechanism" => self.mechanism,
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
 #[derive(Debug)]
 pub struct PrometheusRemoteWriteParseError {
     pub error: prost::DecodeError,
@@ -28,3 +28,85 @@ impl<E: std::fmt::Display> InternalEvent for PulsarEncodeEventError<E> {
         counter!("encode_errors_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub(crate) struct PulsarEventsReceived<'a> {
+    pub byte_size: usize,
+    pub count: usize,
+    pub topic: &'a str,
+}
+
+impl<'a> InternalEvent for PulsarEventsReceived<'a> {
+    fn emit(self) {
+        trace!(
+            message = "Events received.",
+            count = %self.count,
+            byte_size = %self.byte_size,
+            topic = self.topic,
+        );
+        counter!(
+            "component_received_events_total", self.count as u64,
+            "topic" => self.topic.to_string(),
+        );
+        counter!(
+            "component_received_event_bytes_total", self.byte_size as u64,
+            "topic" => self.topic.to_string(),
+        );
+        // deprecated
+        counter!("events_in_total", self.count as u64);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct PulsarReadError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for PulsarReadError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to read message.",
+            error = %self.error,
+            error_code = "reading_message",
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "reading_message",
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct PulsarAcknowledgementError<E> {
+    pub error: E,
+    pub negative: bool,
+}
+
+impl<E: std::fmt::Display> InternalEvent for PulsarAcknowledgementError<E> {
+    fn emit(self) {
+        let error_code = if self.negative {
+            "negative_acknowledgement_failed"
+        } else {
+            "acknowledgement_failed"
+        };
+        error!(
+            message = "Failed to acknowledge message.",
+            error = %self.error,
+            error_code = error_code,
+            error_type = error_type::ACKNOWLEDGMENT_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => error_code,
+            "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
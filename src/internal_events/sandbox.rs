@@ -0,0 +1,33 @@
+use metrics::counter;
+use vector_core::{config::Capability, internal_event::InternalEvent};
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct SandboxViolation<'a> {
+    pub capability: &'a Capability,
+}
+
+impl<'a> InternalEvent for SandboxViolation<'a> {
+    fn emit(self) {
+        let kind = match self.capability {
+            Capability::Path(_) => "path",
+            Capability::Host { .. } => "host",
+        };
+        error!(
+            message = "Access denied by the sandbox.",
+            capability = %self.capability,
+            error_code = "sandbox_violation",
+            error_type = error_type::CONFIGURATION_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "sandbox_violation",
+            "error_type" => error_type::CONFIGURATION_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+        counter!("sandbox_violations_total", 1, "capability" => kind);
+    }
+}
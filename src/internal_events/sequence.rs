@@ -0,0 +1,37 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct SequenceGapDetected<'a> {
+    pub stream: &'a str,
+    pub missing: u64,
+}
+
+impl<'a> InternalEvent for SequenceGapDetected<'a> {
+    fn emit(self) {
+        warn!(
+            message = "Events are missing from the sequence of their source.",
+            stream = %self.stream,
+            missing = %self.missing,
+            internal_log_rate_secs = 10,
+        );
+        counter!("sequence_gaps_total", 1, "stream" => self.stream.to_owned());
+        counter!("sequence_missing_events_total", self.missing, "stream" => self.stream.to_owned());
+    }
+}
+
+#[derive(Debug)]
+pub struct SequenceReorderDetected<'a> {
+    pub stream: &'a str,
+}
+
+impl<'a> InternalEvent for SequenceReorderDetected<'a> {
+    fn emit(self) {
+        warn!(
+            message = "Event received out of the order of its source.",
+            stream = %self.stream,
+            internal_log_rate_secs = 10,
+        );
+        counter!("sequence_reordered_events_total", 1, "stream" => self.stream.to_owned());
+    }
+}
@@ -0,0 +1,162 @@
+use std::{io, net::SocketAddr};
+
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+use crate::sources::snmp::{ClientError, MessageError};
+
+#[derive(Debug)]
+pub struct SnmpEventsReceived<'a> {
+    pub count: usize,
+    pub byte_size: usize,
+    pub target: &'a str,
+}
+
+impl<'a> InternalEvent for SnmpEventsReceived<'a> {
+    fn emit(self) {
+        trace!(
+            message = "Events received.",
+            count = self.count,
+            byte_size = self.byte_size,
+            target = self.target,
+        );
+        counter!(
+            "component_received_events_total", self.count as u64,
+            "target" => self.target.to_owned(),
+        );
+        counter!(
+            "component_received_event_bytes_total", self.byte_size as u64,
+            "target" => self.target.to_owned(),
+        );
+        // deprecated
+        counter!(
+            "events_in_total", self.count as u64,
+            "target" => self.target.to_owned(),
+        );
+    }
+}
+
+#[derive(Debug)]
+enum SnmpSocketErrorType {
+    Bind,
+    Read,
+}
+
+#[derive(Debug)]
+pub struct SnmpSocketError {
+    r#type: SnmpSocketErrorType,
+    pub error: io::Error,
+}
+
+impl SnmpSocketError {
+    pub const fn bind(error: io::Error) -> Self {
+        Self {
+            r#type: SnmpSocketErrorType::Bind,
+            error,
+        }
+    }
+
+    pub const fn read(error: io::Error) -> Self {
+        Self {
+            r#type: SnmpSocketErrorType::Read,
+            error,
+        }
+    }
+}
+
+impl InternalEvent for SnmpSocketError {
+    fn emit(self) {
+        let (message, error_code) = match self.r#type {
+            SnmpSocketErrorType::Bind => (
+                "Failed to bind to UDP listener socket.",
+                "failed_udp_binding",
+            ),
+            SnmpSocketErrorType::Read => ("Failed to read UDP datagram.", "failed_udp_datagram"),
+        };
+        error!(
+            message = %message,
+            error = %self.error,
+            error_code = %error_code,
+            error_type = error_type::CONNECTION_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => error_code,
+            "error_type" => error_type::CONNECTION_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct SnmpTrapDecodeError<'a> {
+    pub error: MessageError,
+    pub peer_addr: &'a SocketAddr,
+}
+
+impl<'a> InternalEvent for SnmpTrapDecodeError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to decode trap.",
+            error = %self.error,
+            peer_addr = %self.peer_addr,
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct SnmpTrapUnauthorized<'a> {
+    pub reason: &'static str,
+    pub peer_addr: &'a SocketAddr,
+}
+
+impl<'a> InternalEvent for SnmpTrapUnauthorized<'a> {
+    fn emit(self) {
+        warn!(
+            message = "Discarding trap.",
+            reason = self.reason,
+            peer_addr = %self.peer_addr,
+            internal_log_rate_secs = 10,
+        );
+        counter!("snmp_unauthorized_traps_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct SnmpPollError<'a> {
+    pub error: ClientError,
+    pub target: &'a str,
+}
+
+impl<'a> InternalEvent for SnmpPollError<'a> {
+    fn emit(self) {
+        let error_type = match self.error {
+            ClientError::Timeout => error_type::TIMED_OUT,
+            _ => error_type::REQUEST_FAILED,
+        };
+        error!(
+            message = "Failed to poll agent.",
+            error = %self.error,
+            target = %self.target,
+            error_type = error_type,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
@@ -143,6 +143,51 @@ mod sink {
         }
     }
 
+    #[derive(Debug)]
+    pub struct SplunkHecTokenRotated<'a> {
+        pub backend: &'a str,
+        pub key: &'a str,
+    }
+
+    impl<'a> InternalEvent for SplunkHecTokenRotated<'a> {
+        fn emit(self) {
+            info!(
+                message = "Rotated the default HEC token.",
+                backend = %self.backend,
+                key = %self.key,
+            );
+            counter!("splunk_token_rotations_total", 1);
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct SplunkHecTokenRotationError<'a> {
+        pub error: crate::Error,
+        pub backend: &'a str,
+        pub key: &'a str,
+    }
+
+    impl<'a> InternalEvent for SplunkHecTokenRotationError<'a> {
+        fn emit(self) {
+            error!(
+                message = "Unable to refresh the default HEC token. Keeping the current one.",
+                error = %self.error,
+                backend = %self.backend,
+                key = %self.key,
+                error_code = "token_rotation_failed",
+                error_type = error_type::REQUEST_FAILED,
+                stage = error_stage::SENDING,
+                internal_log_rate_secs = 10,
+            );
+            counter!(
+                "component_errors_total", 1,
+                "error_code" => "token_rotation_failed",
+                "error_type" => error_type::REQUEST_FAILED,
+                "stage" => error_stage::SENDING,
+            );
+        }
+    }
+
     pub struct SplunkIndexerAcknowledgementAckAdded;
 
     impl InternalEvent for SplunkIndexerAcknowledgementAckAdded {
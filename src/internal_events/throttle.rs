@@ -1,6 +1,8 @@
 use metrics::counter;
 use vector_core::internal_event::InternalEvent;
 
+use super::prelude::{error_stage, error_type};
+
 #[derive(Debug)]
 pub(crate) struct ThrottleEventDiscarded {
     pub key: String,
@@ -15,3 +17,27 @@ impl InternalEvent for ThrottleEventDiscarded {
         );
     }
 }
+
+#[derive(Debug)]
+pub(crate) struct ThrottleRedisError {
+    pub error: redis::RedisError,
+}
+
+impl InternalEvent for ThrottleRedisError {
+    fn emit(self) {
+        error!(
+            message = "Failed to check rate limit in Redis; falling back to local rate limit.",
+            error = %self.error,
+            error_code = self.error.code().unwrap_or("UNKNOWN"),
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::PROCESSING,
+            rate_limit_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => self.error.code().unwrap_or("UNKNOWN").to_string(),
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
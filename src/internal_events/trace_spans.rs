@@ -0,0 +1,36 @@
+use std::fmt;
+
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct TraceSpansConversionError<E> {
+    pub error: E,
+}
+
+impl<E: fmt::Display> InternalEvent for TraceSpansConversionError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to convert trace into spans; dropping event.",
+            error = %self.error,
+            error_code = "invalid_span",
+            error_type = error_type::ENCODER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "invalid_span",
+            "error_type" => error_type::ENCODER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+        counter!(
+            "component_discarded_events_total", 1,
+            "error_code" => "invalid_span",
+            "error_type" => error_type::ENCODER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
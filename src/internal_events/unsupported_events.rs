@@ -0,0 +1,48 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct UnsupportedEventsRouted {
+    pub count: usize,
+}
+
+impl InternalEvent for UnsupportedEventsRouted {
+    fn emit(self) {
+        debug!(
+            message = "Routed events of a type the sink doesn't accept to its dropped output.",
+            count = %self.count,
+            internal_log_rate_secs = 10,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct UnsupportedEventsDropped {
+    pub count: usize,
+    pub reason: &'static str,
+}
+
+impl InternalEvent for UnsupportedEventsDropped {
+    fn emit(self) {
+        error!(
+            message = "Dropped events of a type the sink doesn't accept.",
+            count = %self.count,
+            reason = %self.reason,
+            error_type = error_type::CONDITION_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::CONDITION_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+        counter!(
+            "component_discarded_events_total", self.count as u64,
+            "error_type" => error_type::CONDITION_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
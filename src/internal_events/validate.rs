@@ -0,0 +1,26 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub(crate) struct ValidateEventRejected {
+    pub violations: usize,
+}
+
+impl InternalEvent for ValidateEventRejected {
+    fn emit(self) {
+        debug!(
+            message = "Event doesn't conform to the schema; rejecting event.",
+            violations = self.violations,
+            error_type = error_type::CONDITION_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::CONDITION_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
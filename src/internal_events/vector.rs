@@ -1,3 +1,5 @@
+use std::io;
+
 use metrics::counter;
 use prost::DecodeError;
 use vector_core::internal_event::InternalEvent;
@@ -28,3 +30,61 @@ impl<'a> InternalEvent for VectorProtoDecodeError<'a> {
         counter!("protobuf_decode_errors_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct VectorZstdDecompressError<'a> {
+    pub error: &'a io::Error,
+}
+
+impl<'a> InternalEvent for VectorZstdDecompressError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to decompress the events compressed with zstd.",
+            error = %self.error,
+            error_code = "zstd",
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "zstd",
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct VectorEndpointUnhealthy<'a> {
+    pub endpoint: &'a str,
+    pub reason: &'a str,
+}
+
+impl<'a> InternalEvent for VectorEndpointUnhealthy<'a> {
+    fn emit(self) {
+        warn!(
+            message = "Vector endpoint is unhealthy, sending events to the other endpoints.",
+            endpoint = %self.endpoint,
+            reason = %self.reason,
+        );
+        counter!(
+            "vector_endpoint_unhealthy_total", 1,
+            "endpoint" => self.endpoint.to_owned(),
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct VectorEndpointRecovered<'a> {
+    pub endpoint: &'a str,
+}
+
+impl<'a> InternalEvent for VectorEndpointRecovered<'a> {
+    fn emit(self) {
+        info!(
+            message = "Vector endpoint recovered.",
+            endpoint = %self.endpoint,
+        );
+    }
+}
@@ -23,6 +23,7 @@ impl InternalEvent for WsConnectionEstablished {
 #[derive(Debug)]
 pub struct WsConnectionFailedError {
     pub error: Box<dyn Error>,
+    pub stage: &'static str,
 }
 
 impl InternalEvent for WsConnectionFailedError {
@@ -32,13 +33,13 @@ impl InternalEvent for WsConnectionFailedError {
             error = %self.error,
             error_code = "ws_connection_error",
             error_type = error_type::CONNECTION_FAILED,
-            stage = error_stage::SENDING,
+            stage = self.stage,
         );
         counter!(
             "component_errors_total", 1,
             "error_code" => "ws_connection_failed",
             "error_type" => error_type::CONNECTION_FAILED,
-            "stage" => error_stage::SENDING,
+            "stage" => self.stage,
         );
     }
 
@@ -61,9 +62,62 @@ impl InternalEvent for WsConnectionShutdown {
     }
 }
 
+#[derive(Debug)]
+pub struct WsMessagesReplayed {
+    pub count: usize,
+}
+
+impl InternalEvent for WsMessagesReplayed {
+    fn emit(self) {
+        debug!(
+            message = "Sending again the messages not known to have been received.",
+            count = %self.count,
+        );
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        Some("WsMessagesReplayed")
+    }
+}
+
+#[derive(Debug)]
+pub struct WsMessagesDropped {
+    pub count: usize,
+}
+
+impl InternalEvent for WsMessagesDropped {
+    fn emit(self) {
+        error!(
+            message = "Replay buffer full, dropped the oldest message sent which the server isn't known to have received.",
+            count = %self.count,
+            error_code = "replay_buffer_full",
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::SENDING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "replay_buffer_full",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+        counter!(
+            "component_discarded_events_total", self.count as u64,
+            "error_code" => "replay_buffer_full",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        Some("WsMessagesDropped")
+    }
+}
+
 #[derive(Debug)]
 pub struct WsConnectionError {
     pub error: tokio_tungstenite::tungstenite::Error,
+    pub stage: &'static str,
 }
 
 impl InternalEvent for WsConnectionError {
@@ -72,14 +126,14 @@ impl InternalEvent for WsConnectionError {
             message = "WebSocket connection error.",
             error = %self.error,
             error_code = "ws_connection_error",
-            error_type = error_type::WRITER_FAILED,
-            stage = error_stage::SENDING,
+            error_type = error_type::CONNECTION_FAILED,
+            stage = self.stage,
         );
         counter!(
             "component_errors_total", 1,
             "error_code" => "ws_connection_error",
-            "error_type" => error_type::WRITER_FAILED,
-            "stage" => error_stage::SENDING,
+            "error_type" => error_type::CONNECTION_FAILED,
+            "stage" => self.stage,
         );
     }
 
@@ -0,0 +1,74 @@
+use std::{io, path::Path};
+
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct WindowsEventLogReadError {
+    pub error: String,
+}
+
+impl InternalEvent for WindowsEventLogReadError {
+    fn emit(self) {
+        error!(
+            message = "Failed to read events.",
+            error = %self.error,
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct WindowsEventLogParseError {
+    pub error: roxmltree::Error,
+}
+
+impl InternalEvent for WindowsEventLogParseError {
+    fn emit(self) {
+        error!(
+            message = "Failed to parse event XML.",
+            error = %self.error,
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct WindowsEventLogCheckpointError<'a> {
+    pub error: io::Error,
+    pub path: &'a Path,
+}
+
+impl<'a> InternalEvent for WindowsEventLogCheckpointError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed to read or write the bookmark checkpoint.",
+            error = %self.error,
+            path = ?self.path,
+            error_type = error_type::IO_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::IO_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
@@ -1,11 +1,14 @@
 //! Intercept [`watcher::Event`]'s.
 
-use std::{hash::Hash, time::Duration};
+use std::{collections::HashSet, hash::Hash, time::Duration};
 
 use futures::StreamExt;
 use futures_util::Stream;
 use kube::{
-    runtime::{reflector::store, watcher},
+    runtime::{
+        reflector::{store, ObjectRef},
+        watcher,
+    },
     Resource,
 };
 use tokio::pin;
@@ -18,7 +21,7 @@ pub async fn custom_reflector<K, W>(
     delay_deletion: Duration,
 ) where
     K: Resource + Clone + std::fmt::Debug,
-    K::DynamicType: Eq + Hash + Clone,
+    K::DynamicType: Eq + Hash + Clone + Default,
     W: Stream<Item = watcher::Result<watcher::Event<K>>>,
 {
     pin!(stream);
@@ -39,11 +42,33 @@ pub async fn custom_reflector<K, W>(
                                 trace!(message = "Queuing Deleted event.", ?event);
                                 delay_queue.insert(event.to_owned(), delay_deletion);
                             }
-                            // Clear all delayed events on `Restarted` events
-                            watcher::Event::Restarted(_) => {
+                            // Clear all delayed events on `Restarted` events, and
+                            // delay the deletion of the objects missing from
+                            // the new listing instead, which were deleted while
+                            // the stream was restarting or loaded from a cache.
+                            watcher::Event::Restarted(ref objects) => {
                                 trace!(message = "Processing Restarted event.", ?event);
                                 delay_queue.clear();
+                                let listed = objects
+                                    .iter()
+                                    .map(ObjectRef::from_obj)
+                                    .collect::<HashSet<_>>();
+                                let missing = store
+                                    .as_reader()
+                                    .state()
+                                    .into_iter()
+                                    .filter(|object| {
+                                        !listed.contains(&ObjectRef::from_obj(object.as_ref()))
+                                    })
+                                    .collect::<Vec<_>>();
                                 store.apply_watcher_event(&event);
+                                for object in missing {
+                                    let object = K::clone(&object);
+                                    let applied = watcher::Event::Applied(object.clone());
+                                    store.apply_watcher_event(&applied);
+                                    let deleted = watcher::Event::Deleted(object);
+                                    delay_queue.insert(deleted, delay_deletion);
+                                }
                             }
                         }
                     },
@@ -109,6 +134,42 @@ mod tests {
         assert_eq!(store.get(&ObjectRef::from_obj(&cm)).as_deref(), Some(&cm));
     }
 
+    #[tokio::test]
+    async fn restarted_should_remove_missing_object_after_delay() {
+        let store_w = store::Writer::default();
+        let store = store_w.as_reader();
+        let cm_a = ConfigMap {
+            metadata: ObjectMeta {
+                name: Some("a".to_string()),
+                ..ObjectMeta::default()
+            },
+            ..ConfigMap::default()
+        };
+        let cm_b = ConfigMap {
+            metadata: ObjectMeta {
+                name: Some("b".to_string()),
+                ..ObjectMeta::default()
+            },
+            ..ConfigMap::default()
+        };
+        let (mut tx, rx) = mpsc::channel::<_>(5);
+        tx.send(Ok(watcher::Event::Applied(cm_a.clone())))
+            .await
+            .unwrap();
+        tx.send(Ok(watcher::Event::Restarted(vec![cm_b.clone()])))
+            .await
+            .unwrap();
+        tokio::spawn(custom_reflector(store_w, rx, Duration::from_secs(2)));
+        // Ensure the missing Resource is still available after the restart
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert_eq!(store.get(&ObjectRef::from_obj(&cm_a)).as_deref(), Some(&cm_a));
+        assert_eq!(store.get(&ObjectRef::from_obj(&cm_b)).as_deref(), Some(&cm_b));
+        // Ensure the missing Resource is removed once the `delay_deletion` has elapsed
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        assert_eq!(store.get(&ObjectRef::from_obj(&cm_a)), None);
+        assert_eq!(store.get(&ObjectRef::from_obj(&cm_b)).as_deref(), Some(&cm_b));
+    }
+
     #[tokio::test]
     async fn deleted_should_remove_object_after_delay() {
         let store_w = store::Writer::default();
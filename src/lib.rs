@@ -21,23 +21,39 @@ extern crate tracing;
 #[macro_use]
 extern crate derivative;
 
-#[cfg(feature = "tikv-jemallocator")]
+#[cfg(all(feature = "tikv-jemallocator", not(feature = "api-profiling")))]
 #[global_allocator]
 static ALLOC: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
 
+// Allocations are sampled through the profiled allocator once profiling is enabled in the API.
+#[cfg(all(unix, feature = "tikv-jemallocator", feature = "api-profiling"))]
+#[global_allocator]
+static ALLOC: api::profiling::Profiled<tikv_jemallocator::Jemalloc> =
+    api::profiling::Profiled(tikv_jemallocator::Jemalloc);
+
+#[cfg(all(unix, not(feature = "tikv-jemallocator"), feature = "api-profiling"))]
+#[global_allocator]
+static ALLOC: api::profiling::Profiled<std::alloc::System> =
+    api::profiling::Profiled(std::alloc::System);
+
 #[macro_use]
 #[allow(unreachable_pub)]
 pub mod config;
 pub mod cli;
+pub mod component_scope;
 pub mod conditions;
 pub mod dns;
 #[cfg(feature = "docker")]
 pub mod docker;
+pub mod dropped_events;
+pub mod egress;
 pub mod expiring_hash_map;
 pub mod generate;
 #[macro_use]
 #[allow(unreachable_pub)]
 pub mod internal_events;
+#[cfg(any(feature = "sources-amqp", feature = "sinks-amqp"))]
+pub(crate) mod amqp;
 #[cfg(feature = "api")]
 #[allow(unreachable_pub)]
 pub mod api;
@@ -53,6 +69,8 @@ pub mod enrichment_tables;
 #[cfg(feature = "gcp")]
 pub mod gcp;
 pub(crate) mod graph;
+#[cfg(any(feature = "sources-grpc", feature = "sinks-grpc"))]
+pub(crate) mod grpc;
 pub mod heartbeat;
 pub mod http;
 #[cfg(any(feature = "sources-kafka", feature = "sinks-kafka"))]
@@ -61,11 +79,15 @@ pub(crate) mod kafka;
 pub mod kubernetes;
 pub mod line_agg;
 pub mod list;
+#[cfg(any(feature = "sources-mqtt", feature = "sinks-mqtt"))]
+pub(crate) mod mqtt;
 #[cfg(any(feature = "sources-nats", feature = "sinks-nats"))]
 pub(crate) mod nats;
 #[allow(unreachable_pub)]
 pub(crate) mod proto;
 pub mod providers;
+#[cfg(any(feature = "sources-pulsar", feature = "sinks-pulsar"))]
+pub(crate) mod pulsar;
 pub mod serde;
 #[cfg(windows)]
 pub mod service;
@@ -103,6 +125,9 @@ pub(crate) mod utilization;
 pub mod validate;
 #[cfg(windows)]
 pub mod vector_windows;
+#[cfg(any(feature = "sources-websocket", feature = "sinks-websocket"))]
+#[allow(unreachable_pub)]
+pub(crate) mod websocket;
 
 pub use source_sender::SourceSender;
 pub use vector_core::{event, metrics, schema, Error, Result};
@@ -17,6 +17,11 @@ use regex::bytes::Regex;
 use serde::{Deserialize, Serialize};
 use tokio_util::time::delay_queue::{DelayQueue, Key};
 
+use crate::{
+    conditions::Condition,
+    event::{Event, LogEvent},
+};
+
 /// The mode of operation of the line aggregator.
 #[derive(Debug, Hash, Clone, Copy, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -49,26 +54,64 @@ pub enum Mode {
     HaltWith,
 }
 
+/// Matches the lines of the line aggregator.
+#[derive(Debug, Clone)]
+pub enum Matcher {
+    /// Matches the lines with a regular expression.
+    Pattern(Regex),
+    /// Matches the lines with a condition, checked against a log event whose
+    /// message is the line, and whose `previous` field is the preceding line
+    /// of the message being aggregated, if any.
+    Condition(Condition),
+}
+
+impl Matcher {
+    fn is_match(&self, line: &Bytes, previous: Option<&Bytes>) -> bool {
+        match self {
+            Matcher::Pattern(regex) => regex.is_match(line.as_ref()),
+            Matcher::Condition(condition) => {
+                let mut log = LogEvent::from(line.clone());
+                if let Some(previous) = previous {
+                    log.insert("previous", previous.clone());
+                }
+                condition.check(&Event::from(log))
+            }
+        }
+    }
+}
+
+impl From<Regex> for Matcher {
+    fn from(regex: Regex) -> Self {
+        Matcher::Pattern(regex)
+    }
+}
+
 /// Configuration parameters of the line aggregator.
 #[derive(Debug, Clone)]
 pub struct Config {
     /// Start pattern to look for as a beginning of the message.
-    pub start_pattern: Regex,
+    pub start_pattern: Matcher,
     /// Condition pattern to look for. Exact behavior is configured via `mode`.
-    pub condition_pattern: Regex,
+    pub condition_pattern: Matcher,
     /// Mode of operation, specifies how the condition pattern is interpreted.
     pub mode: Mode,
     /// The maximum time to wait for the continuation. Once this timeout is
     /// reached, the buffered message is guaranteed to be flushed, even if
     /// incomplete.
     pub timeout: Duration,
+    /// The maximum number of lines of a message. Once reached, the buffered
+    /// message is flushed, and the following lines are handled as new ones.
+    pub max_lines: Option<usize>,
+    /// The maximum size of a message, in bytes. Once reached, the buffered
+    /// message is flushed, and the following lines are handled as new ones.
+    pub max_bytes: Option<usize>,
 }
 
 impl Config {
     /// Build `Config` from legacy `file` source line aggregator configuration
     /// params.
     pub fn for_legacy(marker: Regex, timeout_ms: u64) -> Self {
-        let start_pattern = marker;
+        let start_pattern = Matcher::Pattern(marker);
         let condition_pattern = start_pattern.clone();
         let mode = Mode::HaltBefore;
         let timeout = Duration::from_millis(timeout_ms);
@@ -78,8 +121,20 @@ impl Config {
             condition_pattern,
             mode,
             timeout,
+            max_lines: None,
+            max_bytes: None,
         }
     }
+
+    /// Whether the aggregated message reached the maximum number of lines or
+    /// bytes.
+    fn is_full<C>(&self, aggregate: &Aggregate<C>) -> bool {
+        self.max_lines
+            .map_or(false, |max_lines| aggregate.lines.len() >= max_lines)
+            || self
+                .max_bytes
+                .map_or(false, |max_bytes| aggregate.size >= max_bytes)
+    }
 }
 
 /// Line aggregator.
@@ -293,7 +348,10 @@ where
         // Check if we already have the buffered data for the source.
         match self.buffers.entry(src) {
             Entry::Occupied(mut entry) => {
-                let condition_matched = self.config.condition_pattern.is_match(line.as_ref());
+                let condition_matched = self
+                    .config
+                    .condition_pattern
+                    .is_match(&line, entry.get().1.lines.last());
                 let decision = match (self.config.mode, condition_matched) {
                     // All consecutive lines matching this pattern are included in
                     // the group.
@@ -316,8 +374,13 @@ where
                 match decision {
                     Decision::Continue => {
                         let buffered = entry.get_mut();
-                        self.timeouts.reset(&buffered.0, self.config.timeout);
                         buffered.1.add_next_line(line);
+                        if self.config.is_full(&buffered.1) {
+                            let (src, (key, buffered)) = entry.remove_entry();
+                            self.timeouts.remove(&key);
+                            return Some((src, Emit::One(buffered.merge())));
+                        }
+                        self.timeouts.reset(&buffered.0, self.config.timeout);
                         None
                     }
                     Decision::EndInclude => {
@@ -335,13 +398,18 @@ where
             }
             Entry::Vacant(entry) => {
                 // This line is a candidate for buffering, or passing through.
-                if self.config.start_pattern.is_match(line.as_ref()) {
+                if self.config.start_pattern.is_match(&line, None) {
+                    let aggregate = Aggregate::new(line, context);
+                    if self.config.is_full(&aggregate) {
+                        // The message can't hold any more lines.
+                        return Some((entry.into_key(), Emit::One(aggregate.merge())));
+                    }
                     // It was indeed a new line we need to filter.
                     // Set the timeout and buffer this line.
                     let key = self
                         .timeouts
                         .insert(entry.key().clone(), self.config.timeout);
-                    entry.insert((key, Aggregate::new(line, context)));
+                    entry.insert((key, aggregate));
                     None
                 } else {
                     // It's just a regular line we don't really care about.
@@ -354,18 +422,21 @@ where
 
 struct Aggregate<C> {
     lines: Vec<Bytes>,
+    size: usize,
     context: C,
 }
 
 impl<C> Aggregate<C> {
     fn new(first_line: Bytes, context: C) -> Self {
         Self {
+            size: first_line.len(),
             lines: vec![first_line],
             context,
         }
     }
 
     fn add_next_line(&mut self, line: Bytes) {
+        self.size += line.len() + 1;
         self.lines.push(line);
     }
 
@@ -406,10 +477,12 @@ mod tests {
             " last part of the incomplete finishing message",
         ];
         let config = Config {
-            start_pattern: Regex::new("^[^\\s]").unwrap(),
-            condition_pattern: Regex::new("^[\\s]+").unwrap(),
+            start_pattern: Regex::new("^[^\\s]").unwrap().into(),
+            condition_pattern: Regex::new("^[\\s]+").unwrap().into(),
             mode: Mode::ContinueThrough,
             timeout: Duration::from_millis(10),
+            max_lines: None,
+            max_bytes: None,
         };
         let expected = vec![
             "some usual line",
@@ -437,10 +510,12 @@ mod tests {
             "last part of the incomplete finishing message \\",
         ];
         let config = Config {
-            start_pattern: Regex::new("\\\\$").unwrap(),
-            condition_pattern: Regex::new("\\\\$").unwrap(),
+            start_pattern: Regex::new("\\\\$").unwrap().into(),
+            condition_pattern: Regex::new("\\\\$").unwrap().into(),
             mode: Mode::ContinuePast,
             timeout: Duration::from_millis(10),
+            max_lines: None,
+            max_bytes: None,
         };
         let expected = vec![
             "some usual line",
@@ -468,10 +543,12 @@ mod tests {
             "last part of the incomplete finishing message",
         ];
         let config = Config {
-            start_pattern: Regex::new("").unwrap(),
-            condition_pattern: Regex::new("^(INFO|ERROR) ").unwrap(),
+            start_pattern: Regex::new("").unwrap().into(),
+            condition_pattern: Regex::new("^(INFO|ERROR) ").unwrap().into(),
             mode: Mode::HaltBefore,
             timeout: Duration::from_millis(10),
+            max_lines: None,
+            max_bytes: None,
         };
         let expected = vec![
             "INFO some usual line",
@@ -499,10 +576,12 @@ mod tests {
             "last part of the incomplete finishing message",
         ];
         let config = Config {
-            start_pattern: Regex::new("[^;]$").unwrap(),
-            condition_pattern: Regex::new(";$").unwrap(),
+            start_pattern: Regex::new("[^;]$").unwrap().into(),
+            condition_pattern: Regex::new(";$").unwrap().into(),
             mode: Mode::HaltWith,
             timeout: Duration::from_millis(10),
+            max_lines: None,
+            max_bytes: None,
         };
         let expected = vec![
             "some usual line;",
@@ -525,10 +604,12 @@ mod tests {
             "    at com.foo.baz(baz.java:456)",
         ];
         let config = Config {
-            start_pattern: Regex::new("^[^\\s]").unwrap(),
-            condition_pattern: Regex::new("^[\\s]+at").unwrap(),
+            start_pattern: Regex::new("^[^\\s]").unwrap().into(),
+            condition_pattern: Regex::new("^[\\s]+at").unwrap().into(),
             mode: Mode::ContinueThrough,
             timeout: Duration::from_millis(10),
+            max_lines: None,
+            max_bytes: None,
         };
         let expected = vec![concat!(
             "java.lang.Exception\n",
@@ -547,10 +628,12 @@ mod tests {
             "\tfrom foobar.rb:9:in `<main>'",
         ];
         let config = Config {
-            start_pattern: Regex::new("^[^\\s]").unwrap(),
-            condition_pattern: Regex::new("^[\\s]+from").unwrap(),
+            start_pattern: Regex::new("^[^\\s]").unwrap().into(),
+            condition_pattern: Regex::new("^[\\s]+from").unwrap().into(),
             mode: Mode::ContinueThrough,
             timeout: Duration::from_millis(10),
+            max_lines: None,
+            max_bytes: None,
         };
         let expected = vec![concat!(
             "foobar.rb:6:in `/': divided by 0 (ZeroDivisionError)\n",
@@ -581,10 +664,12 @@ mod tests {
             "not merged 6", // will be stashed
         ];
         let config = Config {
-            start_pattern: Regex::new("^\\s").unwrap(),
-            condition_pattern: Regex::new("^\\s").unwrap(),
+            start_pattern: Regex::new("^\\s").unwrap().into(),
+            condition_pattern: Regex::new("^\\s").unwrap().into(),
             mode: Mode::ContinueThrough,
             timeout: Duration::from_millis(10),
+            max_lines: None,
+            max_bytes: None,
         };
         let expected = vec![
             "not merged 1",
@@ -619,10 +704,12 @@ mod tests {
             "START msg 5", // will be stashed
         ];
         let config = Config {
-            start_pattern: Regex::new("").unwrap(),
-            condition_pattern: Regex::new("^START ").unwrap(),
+            start_pattern: Regex::new("").unwrap().into(),
+            condition_pattern: Regex::new("^START ").unwrap().into(),
             mode: Mode::HaltBefore,
             timeout: Duration::from_millis(10),
+            max_lines: None,
+            max_bytes: None,
         };
         let expected = vec![
             "part 0.1\npart 0.2",
@@ -686,10 +773,12 @@ mod tests {
             lines.push(format!("line {}", i));
         }
         let config = Config {
-            start_pattern: Regex::new("").unwrap(),
-            condition_pattern: Regex::new("^START ").unwrap(),
+            start_pattern: Regex::new("").unwrap().into(),
+            condition_pattern: Regex::new("^START ").unwrap().into(),
             mode: Mode::HaltBefore,
             timeout: Duration::from_millis(10),
+            max_lines: None,
+            max_bytes: None,
         };
 
         let mut expected = "START msg 1".to_string();
@@ -717,6 +806,92 @@ mod tests {
         assert_results(results.await.unwrap(), &[expected.as_str()]);
     }
 
+    fn vrl_condition(source: &str) -> Matcher {
+        use crate::conditions::{ConditionConfig, VrlConfig};
+
+        let condition = VrlConfig {
+            source: source.to_owned(),
+            runtime: Default::default(),
+        }
+        .build(&Default::default())
+        .unwrap();
+        Matcher::Condition(condition)
+    }
+
+    #[tokio::test]
+    async fn condition_with_previous_line() {
+        let lines = vec![
+            "some usual line",
+            "first part,",
+            "second part",
+            " last part",
+            "another normal message",
+        ];
+        let config = Config {
+            start_pattern: Regex::new("").unwrap().into(),
+            condition_pattern: vrl_condition(
+                r#"starts_with(string!(.message), " ") || ends_with(string(.previous) ?? "", ",")"#,
+            ),
+            mode: Mode::ContinueThrough,
+            timeout: Duration::from_millis(10),
+            max_lines: None,
+            max_bytes: None,
+        };
+        let expected = vec![
+            "some usual line",
+            concat!("first part,\n", "second part\n", " last part"),
+            "another normal message",
+        ];
+        run_and_assert(&lines, config, &expected).await;
+    }
+
+    #[tokio::test]
+    async fn max_lines() {
+        let lines = vec![
+            "java.lang.Exception",
+            "    at com.foo.bar(bar.java:123)",
+            "    at com.foo.baz(baz.java:456)",
+            "    at com.foo.qux(qux.java:789)",
+            "another normal message",
+        ];
+        let config = Config {
+            start_pattern: Regex::new("^[^\\s]").unwrap().into(),
+            condition_pattern: Regex::new("^[\\s]+at").unwrap().into(),
+            mode: Mode::ContinueThrough,
+            timeout: Duration::from_millis(10),
+            max_lines: Some(2),
+            max_bytes: None,
+        };
+        let expected = vec![
+            concat!("java.lang.Exception\n", "    at com.foo.bar(bar.java:123)"),
+            "    at com.foo.baz(baz.java:456)",
+            "    at com.foo.qux(qux.java:789)",
+            "another normal message",
+        ];
+        run_and_assert(&lines, config, &expected).await;
+    }
+
+    #[tokio::test]
+    async fn max_bytes() {
+        let lines = vec![
+            "START msg 1",
+            "part 1.1",
+            "part 1.2",
+            "part 1.3",
+            "START msg 2",
+        ];
+        let config = Config {
+            start_pattern: Regex::new("").unwrap().into(),
+            condition_pattern: Regex::new("^START ").unwrap().into(),
+            mode: Mode::HaltBefore,
+            timeout: Duration::from_millis(10),
+            max_lines: None,
+            max_bytes: Some(20),
+        };
+        let expected = vec!["START msg 1\npart 1.1", "part 1.2\npart 1.3", "START msg 2"];
+        run_and_assert(&lines, config, &expected).await;
+    }
+
     // Test helpers.
 
     /// Private type alias to be more expressive in the internal implementation.
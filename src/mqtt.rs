@@ -0,0 +1,134 @@
+use std::time::Duration;
+
+use rand::Rng;
+use rumqttc::{Key, MqttOptions, QoS, TlsConfiguration, Transport};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+
+use crate::tls::{MaybeTlsSettings, TlsEnableableConfig, TlsError};
+
+#[derive(Debug, Snafu)]
+pub enum MqttConfigError {
+    #[snafu(display("MQTT TLS Config Error: {}", source))]
+    Tls { source: TlsError },
+    #[snafu(display("MQTT TLS Config Error: the authorities the broker is verified with must be set with `tls.ca_file`"))]
+    TlsMissingCa,
+    #[snafu(display("MQTT Auth Config Error: `password` requires `user` to be set"))]
+    MissingUser,
+}
+
+/// The broker the client connects to.
+#[derive(Clone, Debug, Derivative, Deserialize, Serialize)]
+#[derivative(Default)]
+pub(crate) struct MqttConfig {
+    pub(crate) host: String,
+    #[serde(default = "default_port")]
+    #[derivative(Default(value = "default_port()"))]
+    pub(crate) port: u16,
+    pub(crate) user: Option<String>,
+    pub(crate) password: Option<String>,
+    /// The ID of the client, which must be unique among the clients of the broker. A random one
+    /// is generated when unset, which loses the session of the client when it restarts.
+    pub(crate) client_id: Option<String>,
+    #[serde(default = "default_keep_alive_secs")]
+    #[derivative(Default(value = "default_keep_alive_secs()"))]
+    pub(crate) keep_alive_secs: u64,
+    pub(crate) tls: Option<TlsEnableableConfig>,
+}
+
+const fn default_port() -> u16 {
+    1883
+}
+
+const fn default_keep_alive_secs() -> u64 {
+    60
+}
+
+/// The quality of service messages are delivered with.
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum MqttQoS {
+    /// Messages are delivered at most once, without being acknowledged.
+    AtMostOnce,
+    /// Messages are delivered until they're acknowledged.
+    #[derivative(Default)]
+    AtLeastOnce,
+}
+
+impl From<MqttQoS> for QoS {
+    fn from(qos: MqttQoS) -> Self {
+        match qos {
+            MqttQoS::AtMostOnce => QoS::AtMostOnce,
+            MqttQoS::AtLeastOnce => QoS::AtLeastOnce,
+        }
+    }
+}
+
+impl MqttConfig {
+    pub(crate) fn options(&self) -> Result<MqttOptions, MqttConfigError> {
+        let client_id = self
+            .client_id
+            .clone()
+            .unwrap_or_else(|| format!("vector-{:016x}", rand::thread_rng().gen::<u64>()));
+        let mut options = MqttOptions::new(client_id, &self.host, self.port);
+        options.set_keep_alive(Duration::from_secs(self.keep_alive_secs));
+
+        match (&self.user, &self.password) {
+            (Some(user), password) => {
+                options.set_credentials(user, password.as_deref().unwrap_or_default());
+            }
+            (None, Some(_)) => return Err(MqttConfigError::MissingUser),
+            (None, None) => {}
+        }
+
+        let tls = MaybeTlsSettings::from_config(&self.tls, false).context(TlsSnafu)?;
+        if let Some(tls) = tls.tls() {
+            let ca = tls.authorities_pem().flatten().collect::<Vec<_>>();
+            if ca.is_empty() {
+                return Err(MqttConfigError::TlsMissingCa);
+            }
+            // The key is PKCS#8 encoded, which is what rumqttc parses `ECC` keys as, whatever
+            // their algorithm.
+            let client_auth = tls.identity_pem().map(|(cert, key)| (cert, Key::ECC(key)));
+            options.set_transport(Transport::tls_with_config(TlsConfiguration::Simple {
+                ca,
+                alpn: None,
+                client_auth,
+            }));
+        }
+
+        Ok(options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generates_unique_client_ids() {
+        let config = MqttConfig {
+            host: "localhost".into(),
+            ..Default::default()
+        };
+        let first = config.options().unwrap();
+        let second = config.options().unwrap();
+        assert!(first.client_id().starts_with("vector-"));
+        assert_ne!(first.client_id(), second.client_id());
+        assert_eq!(first.broker_address(), ("localhost".into(), 1883));
+    }
+
+    #[test]
+    fn requires_user_with_password() {
+        let config = MqttConfig {
+            host: "localhost".into(),
+            password: Some("secret".into()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            config.options(),
+            Err(MqttConfigError::MissingUser)
+        ));
+    }
+}
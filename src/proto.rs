@@ -3,3 +3,9 @@ use crate::event::proto as event;
 
 #[cfg(any(feature = "sources-vector", feature = "sinks-vector"))]
 pub mod vector;
+
+#[cfg(feature = "sinks-jaeger")]
+pub mod jaeger;
+
+#[cfg(any(feature = "sources-opentelemetry", feature = "sinks-opentelemetry"))]
+pub mod opentelemetry;
@@ -0,0 +1,4 @@
+//! The model and collector service of the Jaeger gRPC API.
+#![allow(clippy::clone_on_ref_ptr)]
+
+tonic::include_proto!("jaeger.api_v2");
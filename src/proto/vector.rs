@@ -4,3 +4,11 @@ tonic::include_proto!("vector");
 
 pub use vector_client::VectorClient as Client;
 pub use vector_server::{Vector as Service, VectorServer as Server};
+
+/// The prefix of the gRPC metadata keys carrying the metadata a client identifies itself with,
+/// such as its agent ID or site.
+pub const CLIENT_METADATA_PREFIX: &str = "vector-client-";
+
+/// The gRPC metadata key the sources advertise the compressions of the events they accept with, in
+/// the responses to the clients.
+pub const ACCEPT_COMPRESSION_KEY: &str = "vector-accept-compression";
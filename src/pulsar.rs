@@ -0,0 +1,53 @@
+use pulsar::{
+    authentication::oauth2::{OAuth2Authentication, OAuth2Params},
+    Authentication, PulsarBuilder, TokioExecutor,
+};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+
+#[derive(Debug, Snafu)]
+pub enum PulsarConfigError {
+    #[snafu(display("Authentication requires either a `name` and a `token`, or `oauth2`."))]
+    InvalidAuth,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct PulsarAuthConfig {
+    pub(crate) name: Option<String>,  // "token"
+    pub(crate) token: Option<String>, // <jwt token>
+    pub(crate) oauth2: Option<PulsarOAuth2Config>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct PulsarOAuth2Config {
+    pub(crate) issuer_url: String,
+    /// The URL of the credentials file, such as `file:///path/to/credentials.json`.
+    pub(crate) credentials_url: String,
+    pub(crate) audience: Option<String>,
+    pub(crate) scope: Option<String>,
+}
+
+impl PulsarAuthConfig {
+    pub(crate) fn apply(
+        &self,
+        builder: PulsarBuilder<TokioExecutor>,
+    ) -> Result<PulsarBuilder<TokioExecutor>, PulsarConfigError> {
+        match (&self.name, &self.token, &self.oauth2) {
+            (Some(name), Some(token), None) => Ok(builder.with_auth(Authentication {
+                name: name.clone(),
+                data: token.as_bytes().to_vec(),
+            })),
+            (None, None, Some(oauth2)) => Ok(builder.with_auth_provider(
+                OAuth2Authentication::client_credentials(OAuth2Params {
+                    issuer_url: oauth2.issuer_url.clone(),
+                    credentials_url: oauth2.credentials_url.clone(),
+                    audience: oauth2.audience.clone(),
+                    scope: oauth2.scope.clone(),
+                }),
+            )),
+            _ => Err(PulsarConfigError::InvalidAuth),
+        }
+    }
+}
@@ -22,6 +22,7 @@ use crate::{
             encoding::{
                 EncodingConfig, EncodingConfigAdapter, StandardEncodings, StandardEncodingsMigrator,
             },
+            kpl::AggregationConfig,
             retries::RetryLogic,
             BatchConfig, Compression, ServiceBuilderExt, SinkBatchSettings, TowerRequestConfig,
         },
@@ -34,6 +35,8 @@ use crate::{
 // https://docs.aws.amazon.com/firehose/latest/dev/limits.html
 pub const MAX_PAYLOAD_SIZE: usize = 1024 * 1024 * 4;
 pub const MAX_PAYLOAD_EVENTS: usize = 500;
+// and records up to 1000KiB
+pub const MAX_RECORD_SIZE: usize = 1000 * 1024;
 
 #[derive(Clone, Copy, Debug, Default)]
 pub struct KinesisFirehoseDefaultBatchSettings;
@@ -56,6 +59,8 @@ pub struct KinesisFirehoseSinkConfig {
     pub compression: Compression,
     #[serde(default)]
     pub batch: BatchConfig<KinesisFirehoseDefaultBatchSettings>,
+    /// Aggregates the events in records of the KPL format.
+    pub aggregation: Option<AggregationConfig>,
     #[serde(default)]
     pub request: TowerRequestConfig,
     pub tls: Option<TlsConfig>,
@@ -134,6 +139,12 @@ impl SinkConfig for KinesisFirehoseSinkConfig {
             .limit_max_bytes(MAX_PAYLOAD_SIZE)?
             .limit_max_events(MAX_PAYLOAD_EVENTS)?
             .into_batcher_settings()?;
+        let aggregation_settings = self
+            .aggregation
+            .map(|aggregation| {
+                aggregation.batcher_settings(batch_settings.timeout, MAX_RECORD_SIZE)
+            })
+            .transpose()?;
 
         let request_limits = self.request.unwrap_with(&TowerRequestConfig::default());
 
@@ -160,6 +171,7 @@ impl SinkConfig for KinesisFirehoseSinkConfig {
             acker: cx.acker(),
             service,
             request_builder,
+            aggregation_settings,
         };
         Ok((VectorSink::from_event_streamsink(sink), healthcheck))
     }
@@ -55,6 +55,7 @@ async fn firehose_put_records() {
         encoding: EncodingConfig::from(StandardEncodings::Json).into(), // required for ES destination w/ localstack
         compression: Compression::None,
         batch,
+        aggregation: None,
         request: TowerRequestConfig {
             timeout_secs: Some(10),
             retry_attempts: Some(0),
@@ -83,6 +84,7 @@ async fn firehose_put_records() {
         bulk: Some(BulkConfig {
             index: Some(stream.clone()),
             action: None,
+            doc_as_upsert: false,
         }),
         aws: Some(region),
         ..Default::default()
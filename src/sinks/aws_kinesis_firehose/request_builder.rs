@@ -9,7 +9,7 @@ use crate::{
     codecs::Encoder,
     event::{Event, EventFinalizers, Finalizable, LogEvent},
     sinks::util::{
-        encoding::Transformer, request_builder::EncodeResult, Compression, RequestBuilder,
+        encoding::Transformer, kpl, request_builder::EncodeResult, Compression, RequestBuilder,
     },
 };
 
@@ -28,11 +28,13 @@ pub struct KinesisRequest {
     pub record: Record,
     pub finalizers: EventFinalizers,
     pub event_byte_size: usize,
+    /// The number of events in the record, which is more than one once aggregated.
+    pub event_count: usize,
 }
 
 impl Ackable for KinesisRequest {
     fn ack_size(&self) -> usize {
-        1
+        self.event_count
     }
 }
 
@@ -53,6 +55,42 @@ impl KinesisRequest {
         // data is simply base64 encoded, quoted, and comma separated
         (data_len + 2) / 3 * 4 + 3
     }
+
+    /// Aggregates the records in a single record. Firehose records have no partition key, so the
+    /// user records are given an empty one. A single record is sent as it is.
+    pub fn aggregate(mut requests: Vec<KinesisRequest>) -> KinesisRequest {
+        if requests.len() == 1 {
+            return requests.remove(0);
+        }
+
+        let data = kpl::aggregate(requests.iter().map(|request| {
+            (
+                "",
+                request
+                    .record
+                    .data
+                    .as_ref()
+                    .map(|data| data.as_ref())
+                    .unwrap_or_default(),
+            )
+        }));
+
+        let mut finalizers = EventFinalizers::default();
+        let mut event_byte_size = 0;
+        let mut event_count = 0;
+        for mut request in requests {
+            finalizers.merge(request.take_finalizers());
+            event_byte_size += request.event_byte_size;
+            event_count += request.event_count;
+        }
+
+        KinesisRequest {
+            record: Record::builder().data(Blob::new(&data[..])).build(),
+            finalizers,
+            event_byte_size,
+            event_count,
+        }
+    }
 }
 
 impl ByteSizeOf for KinesisRequest {
@@ -102,6 +140,7 @@ impl RequestBuilder<LogEvent> for KinesisRequestBuilder {
             record: Record::builder().data(Blob::new(&payload[..])).build(),
             finalizers: metadata.finalizers,
             event_byte_size: metadata.event_byte_size,
+            event_count: 1,
         }
     }
 }
@@ -25,6 +25,8 @@ pub struct KinesisSink<S> {
     pub service: S,
     pub acker: Acker,
     pub request_builder: KinesisRequestBuilder,
+    /// The settings of the batches of records aggregated together, if they are.
+    pub aggregation_settings: Option<BatcherSettings>,
 }
 
 impl<S> KinesisSink<S>
@@ -37,7 +39,7 @@ where
     async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
         let request_builder_concurrency_limit = NonZeroUsize::new(50);
 
-        let sink = input
+        let requests = input
             .map(|event| {
                 // Panic: This sink only accepts Logs, so this should never panic
                 event.into_log()
@@ -51,7 +53,17 @@ where
                     }
                     Ok(req) => Some(req),
                 }
-            })
+            });
+
+        let requests = match self.aggregation_settings {
+            Some(settings) => requests
+                .batched(settings.into_byte_size_config())
+                .map(KinesisRequest::aggregate)
+                .boxed(),
+            None => requests.boxed(),
+        };
+
+        let sink = requests
             .batched(self.batch_settings.into_byte_size_config())
             .into_driver(self.service, self.acker);
 
@@ -1,17 +1,22 @@
 #![cfg(test)]
 
+use aws_sdk_firehose::{model::Record, types::Blob};
+use vector_core::buffers::Ackable;
+
 use super::*;
 use crate::{
     aws::RegionOrEndpoint,
     config::{SinkConfig, SinkContext},
+    event::EventFinalizers,
     sinks::{
-        aws_kinesis_firehose::config::{
-            KinesisFirehoseDefaultBatchSettings, MAX_PAYLOAD_EVENTS, MAX_PAYLOAD_SIZE,
+        aws_kinesis_firehose::{
+            config::{KinesisFirehoseDefaultBatchSettings, MAX_PAYLOAD_EVENTS, MAX_PAYLOAD_SIZE},
+            request_builder::KinesisRequest,
         },
         util::{
             batch::BatchError,
             encoding::{EncodingConfig, StandardEncodings},
-            BatchConfig, Compression,
+            kpl, BatchConfig, Compression,
         },
     },
 };
@@ -33,6 +38,7 @@ async fn check_batch_size() {
         encoding: EncodingConfig::from(StandardEncodings::Json).into(),
         compression: Compression::None,
         batch,
+        aggregation: None,
         request: Default::default(),
         tls: None,
         auth: Default::default(),
@@ -61,6 +67,7 @@ async fn check_batch_events() {
         encoding: EncodingConfig::from(StandardEncodings::Json).into(),
         compression: Compression::None,
         batch,
+        aggregation: None,
         request: Default::default(),
         tls: None,
         auth: Default::default(),
@@ -77,3 +84,26 @@ async fn check_batch_events() {
         }))
     );
 }
+
+#[test]
+fn aggregates_records() {
+    let request = |data: &str| KinesisRequest {
+        record: Record::builder().data(Blob::new(data)).build(),
+        finalizers: EventFinalizers::default(),
+        event_byte_size: data.len(),
+        event_count: 1,
+    };
+
+    let single = KinesisRequest::aggregate(vec![request("first")]);
+    assert_eq!(single.record.data.unwrap().as_ref(), b"first");
+
+    let aggregated = KinesisRequest::aggregate(vec![request("first"), request("second")]);
+    assert_eq!(aggregated.ack_size(), 2);
+    assert_eq!(aggregated.event_byte_size, 11);
+    assert!(aggregated
+        .record
+        .data
+        .unwrap()
+        .as_ref()
+        .starts_with(&kpl::MAGIC));
+}
@@ -19,14 +19,20 @@ use crate::{
             encoding::{
                 EncodingConfig, EncodingConfigAdapter, StandardEncodings, StandardEncodingsMigrator,
             },
+            kpl::AggregationConfig,
             retries::RetryLogic,
             BatchConfig, Compression, ServiceBuilderExt, SinkBatchSettings, TowerRequestConfig,
         },
         Healthcheck, VectorSink,
     },
+    template::Template,
     tls::TlsConfig,
 };
 
+// AWS Kinesis Data Streams accepts records up to 1MiB.
+// https://docs.aws.amazon.com/streams/latest/dev/service-sizes-and-limits.html
+pub const MAX_RECORD_SIZE: usize = 1024 * 1024;
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Snafu)]
 enum HealthcheckError {
@@ -73,6 +79,7 @@ impl SinkBatchSettings for KinesisDefaultBatchSettings {
 pub struct KinesisSinkConfig {
     pub stream_name: String,
     pub partition_key_field: Option<String>,
+    pub partition_key: Option<Template>,
     #[serde(flatten)]
     pub region: RegionOrEndpoint,
     pub encoding:
@@ -81,6 +88,8 @@ pub struct KinesisSinkConfig {
     pub compression: Compression,
     #[serde(default)]
     pub batch: BatchConfig<KinesisDefaultBatchSettings>,
+    /// Aggregates the events in records of the KPL format.
+    pub aggregation: Option<AggregationConfig>,
     #[serde(default)]
     pub request: TowerRequestConfig,
     pub tls: Option<TlsConfig>,
@@ -142,7 +151,17 @@ impl SinkConfig for KinesisSinkConfig {
         let client = self.create_client(&cx.proxy).await?;
         let healthcheck = self.clone().healthcheck(client.clone()).boxed();
 
+        if self.partition_key_field.is_some() && self.partition_key.is_some() {
+            return Err("`partition_key_field` and `partition_key` can't be set together".into());
+        }
+
         let batch_settings = self.batch.into_batcher_settings()?;
+        let aggregation_settings = self
+            .aggregation
+            .map(|aggregation| {
+                aggregation.batcher_settings(batch_settings.timeout, MAX_RECORD_SIZE)
+            })
+            .transpose()?;
 
         let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
 
@@ -170,6 +189,8 @@ impl SinkConfig for KinesisSinkConfig {
             service,
             request_builder,
             partition_key_field: self.partition_key_field.clone(),
+            partition_key: self.partition_key.clone(),
+            aggregation_settings,
         };
         Ok((VectorSink::from_event_streamsink(sink), healthcheck))
     }
@@ -223,4 +244,21 @@ mod tests {
     fn generate_config() {
         crate::test_util::test_generate_config::<KinesisSinkConfig>();
     }
+
+    #[test]
+    fn parses_aggregation() {
+        let config = toml::from_str::<KinesisSinkConfig>(
+            r#"
+            region = "us-east-1"
+            stream_name = "my-stream"
+            encoding.codec = "json"
+            partition_key = "{{ host }}"
+            aggregation.max_records = 100
+            "#,
+        )
+        .unwrap();
+        let aggregation = config.aggregation.unwrap();
+        assert_eq!(aggregation.max_records.get(), 100);
+        assert_eq!(aggregation.max_bytes.get(), 51_200);
+    }
 }
@@ -38,10 +38,12 @@ async fn kinesis_put_records() {
     let config = KinesisSinkConfig {
         stream_name: stream.clone(),
         partition_key_field: None,
+        partition_key: None,
         region: RegionOrEndpoint::with_both("localstack", kinesis_address().as_str()),
         encoding: EncodingConfig::from(StandardEncodings::Text).into(),
         compression: Compression::None,
         batch,
+        aggregation: None,
         request: Default::default(),
         tls: Default::default(),
         auth: Default::default(),
@@ -10,7 +10,9 @@ use crate::{
     event::{Event, EventFinalizers, Finalizable},
     sinks::{
         aws_kinesis_streams::sink::KinesisProcessedEvent,
-        util::{encoding::Transformer, request_builder::EncodeResult, Compression, RequestBuilder},
+        util::{
+            encoding::Transformer, kpl, request_builder::EncodeResult, Compression, RequestBuilder,
+        },
     },
 };
 
@@ -30,11 +32,13 @@ pub struct KinesisRequest {
     pub put_records_request: PutRecordsRequestEntry,
     pub finalizers: EventFinalizers,
     pub event_byte_size: usize,
+    /// The number of events in the record, which is more than one once aggregated.
+    pub event_count: usize,
 }
 
 impl Ackable for KinesisRequest {
     fn ack_size(&self) -> usize {
-        1
+        self.event_count
     }
 }
 
@@ -70,6 +74,48 @@ impl KinesisRequest {
 
         (data_len + 2) / 3 * 4 + hash_key_size + key_len + 10
     }
+
+    /// Aggregates the records in a single record, with the partition key of the first one. A
+    /// single record is sent as it is.
+    pub fn aggregate(mut requests: Vec<KinesisRequest>) -> KinesisRequest {
+        if requests.len() == 1 {
+            return requests.remove(0);
+        }
+
+        let data = kpl::aggregate(requests.iter().map(|request| {
+            let entry = &request.put_records_request;
+            (
+                entry.partition_key.as_deref().unwrap_or_default(),
+                entry
+                    .data
+                    .as_ref()
+                    .map(|data| data.as_ref())
+                    .unwrap_or_default(),
+            )
+        }));
+        let partition_key = requests
+            .first()
+            .and_then(|request| request.put_records_request.partition_key.clone());
+
+        let mut finalizers = EventFinalizers::default();
+        let mut event_byte_size = 0;
+        let mut event_count = 0;
+        for mut request in requests {
+            finalizers.merge(request.take_finalizers());
+            event_byte_size += request.event_byte_size;
+            event_count += request.event_count;
+        }
+
+        KinesisRequest {
+            put_records_request: PutRecordsRequestEntry::builder()
+                .data(Blob::new(&data[..]))
+                .set_partition_key(partition_key)
+                .build(),
+            finalizers,
+            event_byte_size,
+            event_count,
+        }
+    }
 }
 
 impl ByteSizeOf for KinesisRequest {
@@ -123,6 +169,7 @@ impl RequestBuilder<KinesisProcessedEvent> for KinesisRequestBuilder {
                 .build(),
             finalizers: metadata.finalizers,
             event_byte_size: metadata.event_byte_size,
+            event_count: 1,
         }
     }
 }
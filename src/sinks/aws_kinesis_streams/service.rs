@@ -48,14 +48,15 @@ impl Service<Vec<KinesisRequest>> for KinesisService {
     }
 
     fn call(&mut self, requests: Vec<KinesisRequest>) -> Self::Future {
+        let events_byte_size = requests.iter().map(|req| req.event_byte_size).sum();
+        let count = requests.iter().map(|req| req.event_count).sum();
+
         debug!(
             message = "Sending records.",
-            events = %requests.len(),
+            records = %requests.len(),
+            events = %count,
         );
 
-        let events_byte_size = requests.iter().map(|req| req.event_byte_size).sum();
-        let count = requests.len();
-
         let records = requests
             .into_iter()
             .map(|req| req.put_records_request)
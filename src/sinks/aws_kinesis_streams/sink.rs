@@ -6,15 +6,18 @@ use rand::random;
 use tower::Service;
 use vector_core::{
     buffers::Acker,
+    partition::Partitioner,
     stream::{BatcherSettings, DriverResponse},
 };
 
 use crate::{
     event::{Event, LogEvent},
+    internal_events::TemplateRenderingError,
     sinks::{
         aws_kinesis_streams::request_builder::{KinesisRequest, KinesisRequestBuilder},
         util::{processed_event::ProcessedEvent, SinkBuilderExt, StreamSink},
     },
+    template::Template,
 };
 
 pub type KinesisProcessedEvent = ProcessedEvent<LogEvent, KinesisMetadata>;
@@ -29,6 +32,29 @@ pub struct KinesisSink<S> {
     pub service: S,
     pub request_builder: KinesisRequestBuilder,
     pub partition_key_field: Option<String>,
+    pub partition_key: Option<Template>,
+    /// The settings of the batches of records aggregated together, if they are.
+    pub aggregation_settings: Option<BatcherSettings>,
+}
+
+/// Groups the records aggregated together: the records are only aggregated with the records
+/// of the same partition key, so that they're still sent to the same shard, unless their partition
+/// keys are random.
+struct AggregationPartitioner {
+    by_partition_key: bool,
+}
+
+impl Partitioner for AggregationPartitioner {
+    type Item = KinesisRequest;
+    type Key = Option<String>;
+
+    fn partition(&self, item: &Self::Item) -> Self::Key {
+        if self.by_partition_key {
+            item.put_records_request.partition_key.clone()
+        } else {
+            None
+        }
+    }
 }
 
 impl<S> KinesisSink<S>
@@ -42,12 +68,20 @@ where
         let request_builder_concurrency_limit = NonZeroUsize::new(50);
 
         let partition_key_field = self.partition_key_field.clone();
-        let sink = input
+        let partition_key = self.partition_key.clone();
+        let by_partition_key = partition_key_field.is_some() || partition_key.is_some();
+        let requests = input
             .map(|event| {
                 // Panic: This sink only accepts Logs, so this should never panic
                 event.into_log()
             })
-            .filter_map(move |log| future::ready(process_log(log, &partition_key_field)))
+            .filter_map(move |log| {
+                future::ready(process_log(
+                    log,
+                    &partition_key_field,
+                    partition_key.as_ref(),
+                ))
+            })
             .request_builder(request_builder_concurrency_limit, self.request_builder)
             .filter_map(|request| async move {
                 match request {
@@ -57,7 +91,17 @@ where
                     }
                     Ok(req) => Some(req),
                 }
-            })
+            });
+
+        let requests = match self.aggregation_settings {
+            Some(settings) => requests
+                .batched_partitioned(AggregationPartitioner { by_partition_key }, settings)
+                .map(|(_, requests)| KinesisRequest::aggregate(requests))
+                .boxed(),
+            None => requests.boxed(),
+        };
+
+        let sink = requests
             .batched(self.batch_settings.into_byte_size_config())
             .into_driver(self.service, self.acker);
 
@@ -81,8 +125,20 @@ where
 pub fn process_log(
     log: LogEvent,
     partition_key_field: &Option<String>,
+    partition_key: Option<&Template>,
 ) -> Option<KinesisProcessedEvent> {
-    let partition_key = if let Some(partition_key_field) = partition_key_field {
+    let partition_key = if let Some(partition_key) = partition_key {
+        partition_key
+            .render_string(&log)
+            .map_err(|error| {
+                emit!(TemplateRenderingError {
+                    error,
+                    field: Some("partition_key"),
+                    drop_event: true,
+                });
+            })
+            .ok()?
+    } else if let Some(partition_key_field) = partition_key_field {
         if let Some(v) = log.get(partition_key_field.as_str()) {
             v.to_string_lossy()
         } else {
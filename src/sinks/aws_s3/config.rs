@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 use tower::ServiceBuilder;
 use vector_core::sink::VectorSink;
 
-use super::sink::S3RequestOptions;
+use super::sink::{S3Encoder, S3KeyPartitioner, S3RequestOptions};
 use crate::aws::{AwsAuthentication, RegionOrEndpoint};
 use crate::sinks::util::encoding::EncodingConfigWithFramingAdapter;
 use crate::{
@@ -22,18 +22,21 @@ use crate::{
         },
         util::{
             encoding::{EncodingConfig, StandardEncodings, StandardEncodingsWithFramingMigrator},
-            partitioner::KeyPartitioner,
+            manifest::{ManifestConfig, ManifestService},
+            parquet::ParquetConfig,
             BatchConfig, BulkSizeBasedDefaultBatchSettings, Compression, ServiceBuilderExt,
             TowerRequestConfig,
         },
         Healthcheck,
     },
+    template::Template,
     tls::TlsConfig,
 };
 
 const DEFAULT_KEY_PREFIX: &str = "date=%F/";
 const DEFAULT_FILENAME_TIME_FORMAT: &str = "%s";
 const DEFAULT_FILENAME_APPEND_UUID: bool = true;
+const PARQUET_CONTENT_TYPE: &str = "application/vnd.apache.parquet";
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
@@ -43,6 +46,7 @@ pub struct S3SinkConfig {
     pub filename_time_format: Option<String>,
     pub filename_append_uuid: Option<bool>,
     pub filename_extension: Option<String>,
+    pub manifest: Option<ManifestConfig>,
     #[serde(flatten)]
     pub options: S3Options,
     #[serde(flatten)]
@@ -52,6 +56,8 @@ pub struct S3SinkConfig {
         EncodingConfig<StandardEncodings>,
         StandardEncodingsWithFramingMigrator,
     >,
+    /// Write each object as a Parquet file, instead of encoding its events with `encoding`.
+    pub parquet: Option<ParquetConfig>,
     #[serde(default = "Compression::gzip_default")]
     pub compression: Compression,
     #[serde(default)]
@@ -77,9 +83,11 @@ impl GenerateConfig for S3SinkConfig {
             filename_time_format: None,
             filename_append_uuid: None,
             filename_extension: None,
+            manifest: None,
             options: S3Options::default(),
             region: RegionOrEndpoint::default(),
             encoding: EncodingConfig::from(StandardEncodings::Text).into(),
+            parquet: None,
             compression: Compression::gzip_default(),
             batch: BatchConfig::default(),
             request: TowerRequestConfig::default(),
@@ -102,7 +110,10 @@ impl SinkConfig for S3SinkConfig {
     }
 
     fn input(&self) -> Input {
-        Input::new(self.encoding.config().1.input_type())
+        match self.parquet {
+            Some(_) => Input::log(),
+            None => Input::new(self.encoding.config().1.input_type()),
+        }
     }
 
     fn sink_type(&self) -> &'static str {
@@ -124,10 +135,14 @@ impl S3SinkConfig {
         // requests into in order to ship files to S3.  We build this here in
         // order to configure the client/service with retries, concurrency
         // limits, rate limits, and whatever else the client should have.
+        // The objects written can also be recorded in manifests, which are written with their own
+        // service.
         let request_limits = self.request.unwrap_with(&Default::default());
-        let service = ServiceBuilder::new()
-            .settings(request_limits, S3RetryLogic)
-            .service(service);
+        let service = ManifestService::new(self.manifest.clone(), service.clone(), || {
+            ServiceBuilder::new()
+                .settings(request_limits.clone(), S3RetryLogic)
+                .service(service.clone())
+        });
 
         // Configure our partitioning/batching.
         let batch_settings = self.batch.into_batcher_settings()?;
@@ -137,7 +152,21 @@ impl S3SinkConfig {
             .cloned()
             .unwrap_or_else(|| DEFAULT_KEY_PREFIX.into())
             .try_into()?;
-        let partitioner = KeyPartitioner::new(key_prefix);
+        // The KMS key can be templated, in which case the objects are partitioned by key too.
+        let ssekms_key_id = self
+            .options
+            .ssekms_key_id
+            .as_deref()
+            .map(Template::try_from)
+            .transpose()?;
+        let partitioner = S3KeyPartitioner::new(key_prefix, ssekms_key_id);
+
+        if self.options.object_lock_mode.is_some() != self.options.object_lock_retain_days.is_some()
+        {
+            return Err(
+                "`object_lock_mode` and `object_lock_retain_days` must be set together".into(),
+            );
+        }
 
         // And now collect all of the S3-specific options and configuration knobs.
         let filename_time_format = self
@@ -150,6 +179,47 @@ impl S3SinkConfig {
             .unwrap_or(DEFAULT_FILENAME_APPEND_UUID);
 
         let transformer = self.encoding.transformer();
+        let (encoder, compression, filename_extension) = match &self.parquet {
+            // Parquet files are compressed by pages, and can't be compressed as a whole.
+            Some(parquet) => (
+                S3Encoder::Parquet(parquet.build(transformer, &cx.input_definition)?),
+                Compression::None,
+                Some(
+                    self.filename_extension
+                        .clone()
+                        .unwrap_or_else(|| "parquet".to_owned()),
+                ),
+            ),
+            None => (
+                S3Encoder::Framed((transformer, self.build_encoder()?)),
+                self.compression,
+                self.filename_extension.clone(),
+            ),
+        };
+
+        let mut api_options = self.options.clone();
+        if self.parquet.is_some() {
+            api_options
+                .content_type
+                .get_or_insert_with(|| PARQUET_CONTENT_TYPE.to_owned());
+        }
+
+        let request_options = S3RequestOptions {
+            bucket: self.bucket.clone(),
+            api_options,
+            filename_extension,
+            filename_time_format,
+            filename_append_uuid,
+            encoder,
+            compression,
+        };
+
+        let sink = S3Sink::new(cx, service, request_options, partitioner, batch_settings);
+
+        Ok(VectorSink::from_event_streamsink(sink))
+    }
+
+    fn build_encoder(&self) -> crate::Result<Encoder<Framer>> {
         let (framer, serializer) = self.encoding.encoding();
         let framer = match (framer, &serializer) {
             (Some(framer), _) => framer,
@@ -166,21 +236,7 @@ impl S3SinkConfig {
                 | Serializer::Text(_),
             ) => NewlineDelimitedEncoder::new().into(),
         };
-        let encoder = Encoder::<Framer>::new(framer, serializer);
-
-        let request_options = S3RequestOptions {
-            bucket: self.bucket.clone(),
-            api_options: self.options.clone(),
-            filename_extension: self.filename_extension.clone(),
-            filename_time_format,
-            filename_append_uuid,
-            encoder: (transformer, encoder),
-            compression: self.compression,
-        };
-
-        let sink = S3Sink::new(cx, service, request_options, partitioner, batch_settings);
-
-        Ok(VectorSink::from_event_streamsink(sink))
+        Ok(Encoder::<Framer>::new(framer, serializer))
     }
 
     pub fn build_healthcheck(&self, client: S3Client) -> crate::Result<Healthcheck> {
@@ -195,9 +251,39 @@ impl S3SinkConfig {
 #[cfg(test)]
 mod tests {
     use super::S3SinkConfig;
+    use crate::sinks::s3_common::config::S3ObjectLockMode;
 
     #[test]
     fn generate_config() {
         crate::test_util::test_generate_config::<S3SinkConfig>();
     }
+
+    #[test]
+    fn parses_object_lock_settings() {
+        let config = toml::from_str::<S3SinkConfig>(
+            r#"
+            bucket = "logs"
+            region = "us-east-1"
+            encoding.codec = "text"
+            object_lock_mode = "COMPLIANCE"
+            object_lock_retain_days = 30
+            object_lock_legal_hold = true
+            storage_class = "GLACIER_IR"
+            ssekms_key_id = "alias/{{ tenant }}"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.options.object_lock_mode,
+            Some(S3ObjectLockMode::Compliance)
+        );
+        assert_eq!(
+            config
+                .options
+                .object_lock_retain_days
+                .map(|days| days.get()),
+            Some(30)
+        );
+        assert_eq!(config.options.object_lock_legal_hold, Some(true));
+    }
 }
@@ -4,20 +4,95 @@ use bytes::Bytes;
 use chrono::Utc;
 use codecs::encoding::Framer;
 use uuid::Uuid;
-use vector_core::{event::Finalizable, ByteSizeOf};
+use vector_core::{event::Finalizable, partition::Partitioner, ByteSizeOf};
 
 use crate::{
     codecs::Encoder,
     event::Event,
+    internal_events::TemplateRenderingError,
     sinks::{
         s3_common::{
             config::S3Options,
             service::{S3Metadata, S3Request},
         },
-        util::{encoding::Transformer, request_builder::EncodeResult, Compression, RequestBuilder},
+        util::{
+            encoding::{self, Transformer},
+            parquet::ParquetEncoder,
+            request_builder::EncodeResult,
+            Compression, RequestBuilder,
+        },
     },
+    template::Template,
 };
 
+/// The partition of an object: the rendered key prefix, and the KMS key it's encrypted with.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct S3PartitionKey {
+    pub key_prefix: String,
+    pub ssekms_key_id: Option<String>,
+}
+
+/// Partitions the events by key prefix and, when it's templated, by KMS key.
+pub struct S3KeyPartitioner {
+    key_prefix: Template,
+    ssekms_key_id: Option<Template>,
+}
+
+impl S3KeyPartitioner {
+    pub const fn new(key_prefix: Template, ssekms_key_id: Option<Template>) -> Self {
+        Self {
+            key_prefix,
+            ssekms_key_id,
+        }
+    }
+}
+
+impl Partitioner for S3KeyPartitioner {
+    type Item = Event;
+    type Key = Option<S3PartitionKey>;
+
+    fn partition(&self, item: &Self::Item) -> Self::Key {
+        let render = |template: &Template, field| {
+            template
+                .render_string(item)
+                .map_err(|error| {
+                    emit!(TemplateRenderingError {
+                        error,
+                        field: Some(field),
+                        drop_event: true,
+                    });
+                })
+                .ok()
+        };
+
+        let key_prefix = render(&self.key_prefix, "key_prefix")?;
+        let ssekms_key_id = match &self.ssekms_key_id {
+            Some(template) => Some(render(template, "ssekms_key_id")?),
+            None => None,
+        };
+        Some(S3PartitionKey {
+            key_prefix,
+            ssekms_key_id,
+        })
+    }
+}
+
+/// Encodes the events of each object, either one by one or as a Parquet file.
+#[derive(Clone)]
+pub enum S3Encoder {
+    Framed((Transformer, Encoder<Framer>)),
+    Parquet(ParquetEncoder),
+}
+
+impl encoding::Encoder<Vec<Event>> for S3Encoder {
+    fn encode_input(&self, events: Vec<Event>, writer: &mut dyn io::Write) -> io::Result<usize> {
+        match self {
+            Self::Framed(encoder) => encoding::Encoder::encode_input(encoder, events, writer),
+            Self::Parquet(encoder) => encoding::Encoder::encode_input(encoder, events, writer),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct S3RequestOptions {
     pub bucket: String,
@@ -25,14 +100,14 @@ pub struct S3RequestOptions {
     pub filename_append_uuid: bool,
     pub filename_extension: Option<String>,
     pub api_options: S3Options,
-    pub encoder: (Transformer, Encoder<Framer>),
+    pub encoder: S3Encoder,
     pub compression: Compression,
 }
 
-impl RequestBuilder<(String, Vec<Event>)> for S3RequestOptions {
-    type Metadata = S3Metadata;
+impl RequestBuilder<(S3PartitionKey, Vec<Event>)> for S3RequestOptions {
+    type Metadata = (S3Metadata, Option<String>);
     type Events = Vec<Event>;
-    type Encoder = (Transformer, Encoder<Framer>);
+    type Encoder = S3Encoder;
     type Payload = Bytes;
     type Request = S3Request;
     type Error = io::Error; // TODO: this is ugly.
@@ -45,22 +120,22 @@ impl RequestBuilder<(String, Vec<Event>)> for S3RequestOptions {
         &self.encoder
     }
 
-    fn split_input(&self, input: (String, Vec<Event>)) -> (Self::Metadata, Self::Events) {
+    fn split_input(&self, input: (S3PartitionKey, Vec<Event>)) -> (Self::Metadata, Self::Events) {
         let (partition_key, mut events) = input;
         let finalizers = events.take_finalizers();
         let metadata = S3Metadata {
-            partition_key,
+            partition_key: partition_key.key_prefix,
             count: events.len(),
             byte_size: events.size_of(),
             finalizers,
         };
 
-        (metadata, events)
+        ((metadata, partition_key.ssekms_key_id), events)
     }
 
     fn build_request(
         &self,
-        mut metadata: Self::Metadata,
+        (mut metadata, ssekms_key_id): Self::Metadata,
         payload: EncodeResult<Self::Payload>,
     ) -> Self::Request {
         let filename = {
@@ -78,12 +153,17 @@ impl RequestBuilder<(String, Vec<Event>)> for S3RequestOptions {
             .unwrap_or_else(|| self.compression.extension().into());
         metadata.partition_key = format!("{}{}.{}", metadata.partition_key, filename, extension);
 
+        let mut options = self.api_options.clone();
+        if ssekms_key_id.is_some() {
+            options.ssekms_key_id = ssekms_key_id;
+        }
+
         S3Request {
             body: payload.into_payload(),
             bucket: self.bucket.clone(),
             metadata,
             content_encoding: self.compression.content_encoding(),
-            options: self.api_options.clone(),
+            options,
         }
     }
 }
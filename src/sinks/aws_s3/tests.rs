@@ -350,9 +350,11 @@ mod integration_tests {
             filename_time_format: None,
             filename_append_uuid: None,
             filename_extension: None,
+            manifest: None,
             options: S3Options::default(),
             region: RegionOrEndpoint::with_both("minio", s3_address()),
             encoding: EncodingConfig::from(StandardEncodings::Text).into(),
+            parquet: None,
             compression: Compression::None,
             batch,
             request: TowerRequestConfig::default(),
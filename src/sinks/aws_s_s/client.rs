@@ -0,0 +1,25 @@
+use aws_smithy_client::SdkError;
+
+use super::request_builder::SendMessageEntry;
+
+/// A message of a batch which was rejected by the service.
+#[derive(Clone, Debug)]
+pub(super) struct FailedEntry {
+    pub code: String,
+    pub message: Option<String>,
+    /// Whether the message itself is invalid, in which case sending it again won't help.
+    pub sender_fault: bool,
+}
+
+/// The client sending the batches of messages, to SNS topics or SQS queues.
+#[async_trait::async_trait]
+pub(super) trait Client<R>: Clone + Send + Sync + 'static
+where
+    R: std::fmt::Debug + std::fmt::Display + std::error::Error + Send + Sync + 'static,
+{
+    /// Sends the messages as a single batch, returning the messages of the batch which failed.
+    async fn send_message_batch(
+        &self,
+        entries: Vec<SendMessageEntry>,
+    ) -> Result<Vec<FailedEntry>, SdkError<R>>;
+}
@@ -0,0 +1,191 @@
+use std::{collections::HashMap, convert::TryFrom};
+
+use codecs::{encoding::SerializerConfig, JsonSerializerConfig, TextSerializerConfig};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use vector_core::stream::BatcherSettings;
+
+use crate::{
+    aws::{AwsAuthentication, RegionOrEndpoint},
+    config::AcknowledgementsConfig,
+    sinks::util::{
+        encoding::{EncodingConfig, EncodingConfigAdapter, EncodingConfigMigrator},
+        BatchConfig, SinkBatchSettings, TowerRequestConfig,
+    },
+    template::{Template, TemplateParseError},
+    tls::TlsConfig,
+};
+
+// Both SNS and SQS accept batches of up to 10 messages, and 256KiB for the whole batch.
+// https://docs.aws.amazon.com/sns/latest/api/API_PublishBatch.html
+// https://docs.aws.amazon.com/AWSSimpleQueueService/latest/APIReference/API_SendMessageBatch.html
+pub(super) const MAX_PAYLOAD_SIZE: usize = 262_144;
+pub(super) const MAX_PAYLOAD_EVENTS: usize = 10;
+// and up to 10 message attributes per message.
+pub(super) const MAX_MESSAGE_ATTRIBUTES: usize = 10;
+
+#[derive(Debug, Snafu)]
+pub(super) enum BuildError {
+    #[snafu(display("`message_group_id` should be defined for FIFO queue."))]
+    MessageGroupIdMissing,
+    #[snafu(display("`message_group_id` is not allowed with non-FIFO queue."))]
+    MessageGroupIdNotAllowed,
+    #[snafu(display("invalid message_group_id template: {}", source))]
+    MessageGroupIdTemplate { source: TemplateParseError },
+    #[snafu(display("invalid message_deduplication_id template: {}", source))]
+    MessageDeduplicationIdTemplate { source: TemplateParseError },
+    #[snafu(display(
+        "Too many message attributes, at most {} can be set",
+        MAX_MESSAGE_ATTRIBUTES
+    ))]
+    TooManyMessageAttributes,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub(super) struct SSDefaultBatchSettings;
+
+impl SinkBatchSettings for SSDefaultBatchSettings {
+    const MAX_EVENTS: Option<usize> = Some(MAX_PAYLOAD_EVENTS);
+    const MAX_BYTES: Option<usize> = Some(MAX_PAYLOAD_SIZE);
+    const TIMEOUT_SECS: f64 = 1.0;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncodingMigrator;
+
+impl EncodingConfigMigrator for EncodingMigrator {
+    type Codec = Encoding;
+
+    fn migrate(codec: &Self::Codec) -> SerializerConfig {
+        match codec {
+            Encoding::Text => TextSerializerConfig::new().into(),
+            Encoding::Json => JsonSerializerConfig::new().into(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Derivative)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    Text,
+    Json,
+}
+
+/// The configuration shared by the SNS and SQS sinks, flattened in their own configuration.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub(super) struct BaseSSSinkConfig {
+    #[serde(flatten)]
+    pub(super) region: RegionOrEndpoint,
+    pub(super) encoding: EncodingConfigAdapter<EncodingConfig<Encoding>, EncodingMigrator>,
+    pub(super) message_group_id: Option<String>,
+    pub(super) message_deduplication_id: Option<String>,
+    /// The message attributes set on each message, rendered from the events.
+    #[serde(default)]
+    pub(super) message_attributes: HashMap<String, Template>,
+    #[serde(default)]
+    pub(super) batch: BatchConfig<SSDefaultBatchSettings>,
+    #[serde(default)]
+    pub(super) request: TowerRequestConfig,
+    pub(super) tls: Option<TlsConfig>,
+    #[serde(default)]
+    pub(super) auth: AwsAuthentication,
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub(super) acknowledgements: AcknowledgementsConfig,
+}
+
+impl BaseSSSinkConfig {
+    /// The message group ID template, required by the FIFO topics and queues and not allowed
+    /// by the others.
+    pub(super) fn message_group_id(&self, fifo: bool) -> crate::Result<Option<Template>> {
+        match (self.message_group_id.as_ref(), fifo) {
+            (Some(value), true) => Ok(Some(
+                Template::try_from(value.as_str()).context(MessageGroupIdTemplateSnafu)?,
+            )),
+            (Some(_), false) => Err(Box::new(BuildError::MessageGroupIdNotAllowed)),
+            (None, true) => Err(Box::new(BuildError::MessageGroupIdMissing)),
+            (None, false) => Ok(None),
+        }
+    }
+
+    pub(super) fn message_deduplication_id(&self) -> crate::Result<Option<Template>> {
+        Ok(self
+            .message_deduplication_id
+            .as_deref()
+            .map(Template::try_from)
+            .transpose()
+            .context(MessageDeduplicationIdTemplateSnafu)?)
+    }
+
+    pub(super) fn message_attributes(&self) -> crate::Result<Vec<(String, Template)>> {
+        if self.message_attributes.len() > MAX_MESSAGE_ATTRIBUTES {
+            return Err(Box::new(BuildError::TooManyMessageAttributes));
+        }
+        let mut attributes = self
+            .message_attributes
+            .iter()
+            .map(|(name, template)| (name.clone(), template.clone()))
+            .collect::<Vec<_>>();
+        attributes.sort_by(|(a, _), (b, _)| a.cmp(b));
+        Ok(attributes)
+    }
+
+    pub(super) fn batch_settings(&self) -> crate::Result<BatcherSettings> {
+        Ok(self
+            .batch
+            .validate()?
+            .limit_max_bytes(MAX_PAYLOAD_SIZE)?
+            .limit_max_events(MAX_PAYLOAD_EVENTS)?
+            .into_batcher_settings()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(config: &str) -> BaseSSSinkConfig {
+        toml::from_str(config).unwrap()
+    }
+
+    #[test]
+    fn message_group_id_requires_fifo() {
+        let config = config(
+            r#"
+            region = "us-east-1"
+            encoding.codec = "json"
+            message_group_id = "{{ host }}"
+            "#,
+        );
+        assert!(config.message_group_id(true).unwrap().is_some());
+        assert!(config.message_group_id(false).is_err());
+    }
+
+    #[test]
+    fn limits_message_attributes() {
+        let attributes = (0..=MAX_MESSAGE_ATTRIBUTES)
+            .map(|i| format!("message_attributes.attr{} = \"{{{{ field{} }}}}\"", i, i))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let config = config(&format!(
+            "region = \"us-east-1\"\nencoding.codec = \"json\"\n{}",
+            attributes
+        ));
+        assert!(config.message_attributes().is_err());
+    }
+
+    #[test]
+    fn limits_batches() {
+        let config = config(
+            r#"
+            region = "us-east-1"
+            encoding.codec = "json"
+            batch.max_events = 100
+            "#,
+        );
+        assert!(config.batch_settings().is_err());
+    }
+}
@@ -0,0 +1,17 @@
+//! The sinks publishing events to Amazon SNS topics and Amazon SQS queues.
+//!
+//! Both services take the same messages, with message attributes and, for the FIFO topics and
+//! queues, message group and deduplication IDs, in batches of up to ten messages. The sinks share
+//! their configuration, requests and batching, and only differ by the client sending the batches.
+
+mod client;
+mod config;
+mod request_builder;
+mod retry;
+mod service;
+mod sink;
+
+#[cfg(feature = "sinks-aws_sns")]
+mod sns;
+#[cfg(feature = "sinks-aws_sqs")]
+mod sqs;
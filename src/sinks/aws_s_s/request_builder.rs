@@ -0,0 +1,213 @@
+use bytes::Bytes;
+use vector_core::buffers::Ackable;
+use vector_core::ByteSizeOf;
+
+use crate::codecs::Encoder;
+use crate::event::{Event, EventFinalizers, Finalizable};
+use crate::internal_events::TemplateRenderingError;
+use crate::sinks::util::encoding::Transformer;
+use crate::sinks::util::request_builder::EncodeResult;
+use crate::sinks::util::{Compression, EncodedLength, RequestBuilder};
+use crate::template::Template;
+
+#[derive(Clone)]
+pub(super) struct Metadata {
+    pub finalizers: EventFinalizers,
+    pub event_byte_size: usize,
+    pub message_group_id: Option<String>,
+    pub message_deduplication_id: Option<String>,
+    pub message_attributes: Vec<(String, String)>,
+}
+
+#[derive(Clone)]
+pub(super) struct SSRequestBuilder {
+    encoder: (Transformer, Encoder<()>),
+    message_group_id: Option<Template>,
+    message_deduplication_id: Option<Template>,
+    message_attributes: Vec<(String, Template)>,
+}
+
+impl SSRequestBuilder {
+    pub(super) const fn new(
+        encoder: (Transformer, Encoder<()>),
+        message_group_id: Option<Template>,
+        message_deduplication_id: Option<Template>,
+        message_attributes: Vec<(String, Template)>,
+    ) -> Self {
+        Self {
+            encoder,
+            message_group_id,
+            message_deduplication_id,
+            message_attributes,
+        }
+    }
+}
+
+fn render(template: &Template, event: &Event, field: &'static str) -> Option<String> {
+    template
+        .render_string(event)
+        .map_err(|error| {
+            emit!(TemplateRenderingError {
+                error,
+                field: Some(field),
+                drop_event: false,
+            });
+        })
+        .ok()
+}
+
+impl RequestBuilder<Event> for SSRequestBuilder {
+    type Metadata = Metadata;
+    type Events = Event;
+    type Encoder = (Transformer, Encoder<()>);
+    type Payload = Bytes;
+    type Request = SendMessageEntry;
+    type Error = std::io::Error;
+
+    fn compression(&self) -> Compression {
+        Compression::None
+    }
+
+    fn encoder(&self) -> &Self::Encoder {
+        &self.encoder
+    }
+
+    fn split_input(&self, mut event: Event) -> (Self::Metadata, Self::Events) {
+        let event_byte_size = event.size_of();
+
+        let message_group_id = self
+            .message_group_id
+            .as_ref()
+            .and_then(|template| render(template, &event, "message_group_id"));
+        let message_deduplication_id = self
+            .message_deduplication_id
+            .as_ref()
+            .and_then(|template| render(template, &event, "message_deduplication_id"));
+        // The attributes which can't be rendered, or are rendered empty, are left out of the
+        // message since the services reject the attributes without a value.
+        let message_attributes = self
+            .message_attributes
+            .iter()
+            .filter_map(|(name, template)| {
+                render(template, &event, "message_attributes")
+                    .filter(|value| !value.is_empty())
+                    .map(|value| (name.clone(), value))
+            })
+            .collect();
+
+        let metadata = Metadata {
+            finalizers: event.take_finalizers(),
+            event_byte_size,
+            message_group_id,
+            message_deduplication_id,
+            message_attributes,
+        };
+        (metadata, event)
+    }
+
+    fn build_request(
+        &self,
+        metadata: Self::Metadata,
+        payload: EncodeResult<Self::Payload>,
+    ) -> Self::Request {
+        let payload = payload.into_payload();
+        let message_body = String::from_utf8_lossy(&payload).into_owned();
+        SendMessageEntry {
+            message_body,
+            message_group_id: metadata.message_group_id,
+            message_deduplication_id: metadata.message_deduplication_id,
+            message_attributes: metadata.message_attributes,
+            finalizers: metadata.finalizers,
+            event_byte_size: metadata.event_byte_size,
+        }
+    }
+}
+
+/// A message sent in a batch, to either an SNS topic or an SQS queue.
+#[derive(Debug, Clone)]
+pub(super) struct SendMessageEntry {
+    pub message_body: String,
+    pub message_group_id: Option<String>,
+    pub message_deduplication_id: Option<String>,
+    pub message_attributes: Vec<(String, String)>,
+    pub event_byte_size: usize,
+    finalizers: EventFinalizers,
+}
+
+impl ByteSizeOf for SendMessageEntry {
+    // `ByteSizeOf` is used by the batcher, to keep the batches within the limits of the services,
+    // which count the message attributes along with the message body.
+    fn size_of(&self) -> usize {
+        self.encoded_length()
+    }
+
+    fn allocated_bytes(&self) -> usize {
+        0
+    }
+}
+
+impl EncodedLength for SendMessageEntry {
+    fn encoded_length(&self) -> usize {
+        self.message_body.len()
+            + self
+                .message_attributes
+                .iter()
+                // The data type of the attributes, "String", counts too.
+                .map(|(name, value)| name.len() + value.len() + 6)
+                .sum::<usize>()
+    }
+}
+
+impl Ackable for SendMessageEntry {
+    fn ack_size(&self) -> usize {
+        1
+    }
+}
+
+impl Finalizable for SendMessageEntry {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        self.finalizers.take_finalizers()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use codecs::TextSerializer;
+
+    use super::*;
+    use crate::event::LogEvent;
+
+    #[test]
+    fn renders_message_attributes() {
+        let builder = SSRequestBuilder::new(
+            (
+                Transformer::default(),
+                Encoder::<()>::new(TextSerializer::new().into()),
+            ),
+            Some(Template::try_from("{{ group }}").unwrap()),
+            None,
+            vec![
+                ("host".to_owned(), Template::try_from("{{ host }}").unwrap()),
+                (
+                    "missing".to_owned(),
+                    Template::try_from("{{ missing }}").unwrap(),
+                ),
+                ("service".to_owned(), Template::try_from("vector").unwrap()),
+            ],
+        );
+        let mut event = LogEvent::from("message");
+        event.insert("group", "a");
+        event.insert("host", "example.com");
+
+        let (metadata, _) = builder.split_input(event.into());
+        assert_eq!(metadata.message_group_id.as_deref(), Some("a"));
+        assert_eq!(metadata.message_deduplication_id, None);
+        assert_eq!(
+            metadata.message_attributes,
+            vec![
+                ("host".to_owned(), "example.com".to_owned()),
+                ("service".to_owned(), "vector".to_owned()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use std::marker::PhantomData;
+
+use aws_smithy_client::SdkError;
+
+use super::service::SendMessageResponse;
+use crate::aws::is_retriable_error;
+use crate::sinks::util::retries::{RetryAction, RetryLogic};
+
+/// Classifies the responses of the batch APIs: the batches are only retried if none of their
+/// messages were sent, so that the messages are never sent twice.
+#[derive(Debug)]
+pub(super) struct SSRetryLogic<E> {
+    _phantom: PhantomData<fn() -> E>,
+}
+
+impl<E> SSRetryLogic<E> {
+    pub(super) const fn new() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<E> Clone for SSRetryLogic<E> {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+impl<E> RetryLogic for SSRetryLogic<E>
+where
+    E: std::fmt::Debug + std::fmt::Display + std::error::Error + Send + Sync + 'static,
+{
+    type Error = SdkError<E>;
+    type Response = SendMessageResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        is_retriable_error(error)
+    }
+
+    fn should_retry_response(&self, response: &Self::Response) -> RetryAction {
+        let failed = &response.failed;
+        match failed.first() {
+            None => RetryAction::Successful,
+            Some(entry) => {
+                let reason = format!(
+                    "{} messages failed, error code: {}, message: {}",
+                    failed.len(),
+                    entry.code,
+                    entry.message.as_deref().unwrap_or_default()
+                );
+                if response.all_failed() && failed.iter().all(|entry| !entry.sender_fault) {
+                    RetryAction::Retry(reason.into())
+                } else {
+                    RetryAction::DontRetry(reason.into())
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use std::{
+    marker::PhantomData,
+    task::{Context, Poll},
+};
+
+use aws_smithy_client::SdkError;
+use futures::future::BoxFuture;
+use tower::Service;
+use tracing::Instrument;
+use vector_core::{event::EventStatus, internal_event::EventsSent, stream::DriverResponse};
+
+use super::{
+    client::{Client, FailedEntry},
+    request_builder::SendMessageEntry,
+};
+use crate::internal_events::AwsSSMessagesRejected;
+
+pub(super) struct SSService<C, E> {
+    client: C,
+    _phantom: PhantomData<fn() -> E>,
+}
+
+impl<C: Clone, E> Clone for SSService<C, E> {
+    fn clone(&self) -> Self {
+        Self::new(self.client.clone())
+    }
+}
+
+impl<C, E> SSService<C, E> {
+    pub(super) const fn new(client: C) -> Self {
+        Self {
+            client,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<C, E> Service<Vec<SendMessageEntry>> for SSService<C, E>
+where
+    C: Client<E>,
+    E: std::fmt::Debug + std::fmt::Display + std::error::Error + Send + Sync + 'static,
+{
+    type Response = SendMessageResponse;
+    type Error = SdkError<E>;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, entries: Vec<SendMessageEntry>) -> Self::Future {
+        let byte_size = entries.iter().map(|entry| entry.event_byte_size).sum();
+        let count = entries.len();
+        let client = self.client.clone();
+
+        Box::pin(async move {
+            let failed = client
+                .send_message_batch(entries)
+                .instrument(info_span!("request").or_current())
+                .await?;
+            if !failed.is_empty() {
+                emit!(AwsSSMessagesRejected {
+                    count: failed.len(),
+                    code: &failed[0].code,
+                    message: failed[0].message.as_deref().unwrap_or_default(),
+                });
+            }
+            Ok(SendMessageResponse {
+                byte_size,
+                count,
+                failed,
+            })
+        })
+    }
+}
+
+pub(super) struct SendMessageResponse {
+    byte_size: usize,
+    count: usize,
+    pub(super) failed: Vec<FailedEntry>,
+}
+
+impl SendMessageResponse {
+    pub(super) fn all_failed(&self) -> bool {
+        self.failed.len() == self.count
+    }
+}
+
+impl DriverResponse for SendMessageResponse {
+    fn event_status(&self) -> EventStatus {
+        // The finalizers are shared by the whole batch, which is only delivered if all of its
+        // messages are.
+        if self.failed.is_empty() {
+            EventStatus::Delivered
+        } else {
+            EventStatus::Rejected
+        }
+    }
+
+    fn events_sent(&self) -> EventsSent {
+        EventsSent {
+            count: self.count - self.failed.len(),
+            byte_size: self.byte_size,
+            output: None,
+        }
+    }
+}
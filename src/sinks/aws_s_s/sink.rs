@@ -0,0 +1,73 @@
+use std::num::NonZeroUsize;
+
+use futures::stream::BoxStream;
+use futures_util::StreamExt;
+use vector_core::{buffers::Acker, sink::StreamSink, stream::BatcherSettings};
+
+use super::{
+    client::Client, request_builder::SSRequestBuilder, retry::SSRetryLogic, service::SSService,
+};
+use crate::{
+    event::Event,
+    sinks::util::{builder::SinkBuilderExt, ServiceBuilderExt, TowerRequestConfig},
+};
+
+pub(super) struct SSSink<C, E> {
+    acker: Acker,
+    batch_settings: BatcherSettings,
+    request_builder: SSRequestBuilder,
+    service: SSService<C, E>,
+    request: TowerRequestConfig,
+}
+
+impl<C, E> SSSink<C, E>
+where
+    C: Client<E>,
+    E: std::fmt::Debug + std::fmt::Display + std::error::Error + Send + Sync + 'static,
+{
+    pub(super) fn new(
+        acker: Acker,
+        batch_settings: BatcherSettings,
+        request_builder: SSRequestBuilder,
+        client: C,
+        request: TowerRequestConfig,
+    ) -> Self {
+        Self {
+            acker,
+            batch_settings,
+            request_builder,
+            service: SSService::new(client),
+            request,
+        }
+    }
+
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let request = self.request.unwrap_with(&TowerRequestConfig {
+            timeout_secs: Some(30),
+            ..Default::default()
+        });
+        let request_builder_concurrency_limit = NonZeroUsize::new(50);
+        let service = tower::ServiceBuilder::new()
+            .settings(request, SSRetryLogic::<E>::new())
+            .service(self.service);
+
+        let sink = input
+            .request_builder(request_builder_concurrency_limit, self.request_builder)
+            .filter_map(|req| async move { req.ok() })
+            .batched(self.batch_settings.into_byte_size_config())
+            .into_driver(service, self.acker);
+
+        sink.run().await
+    }
+}
+
+#[async_trait::async_trait]
+impl<C, E> StreamSink<Event> for SSSink<C, E>
+where
+    C: Client<E>,
+    E: std::fmt::Debug + std::fmt::Display + std::error::Error + Send + Sync + 'static,
+{
+    async fn run(mut self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
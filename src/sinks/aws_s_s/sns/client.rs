@@ -0,0 +1,93 @@
+use aws_sdk_sns::{
+    error::PublishBatchError,
+    model::{MessageAttributeValue, PublishBatchRequestEntry},
+    types::SdkError,
+    Client as SnsClient,
+};
+
+use crate::{
+    aws::ClientBuilder,
+    sinks::aws_s_s::{
+        client::{Client, FailedEntry},
+        request_builder::SendMessageEntry,
+    },
+};
+
+pub(super) struct SnsClientBuilder;
+
+impl ClientBuilder for SnsClientBuilder {
+    type Config = aws_sdk_sns::config::Config;
+    type Client = aws_sdk_sns::client::Client;
+    type DefaultMiddleware = aws_sdk_sns::middleware::DefaultMiddleware;
+
+    fn default_middleware() -> Self::DefaultMiddleware {
+        aws_sdk_sns::middleware::DefaultMiddleware::new()
+    }
+
+    fn build(client: aws_smithy_client::Client, config: &aws_types::SdkConfig) -> Self::Client {
+        aws_sdk_sns::client::Client::with_config(client, config.into())
+    }
+}
+
+#[derive(Clone)]
+pub(super) struct SnsMessagePublisher {
+    client: SnsClient,
+    topic_arn: String,
+}
+
+impl SnsMessagePublisher {
+    pub(super) const fn new(client: SnsClient, topic_arn: String) -> Self {
+        Self { client, topic_arn }
+    }
+}
+
+#[async_trait::async_trait]
+impl Client<PublishBatchError> for SnsMessagePublisher {
+    async fn send_message_batch(
+        &self,
+        entries: Vec<SendMessageEntry>,
+    ) -> Result<Vec<FailedEntry>, SdkError<PublishBatchError>> {
+        let entries = entries
+            .into_iter()
+            .enumerate()
+            .map(|(id, entry)| {
+                let attributes = entry
+                    .message_attributes
+                    .into_iter()
+                    .map(|(name, value)| {
+                        let value = MessageAttributeValue::builder()
+                            .data_type("String")
+                            .string_value(value)
+                            .build();
+                        (name, value)
+                    })
+                    .collect();
+                PublishBatchRequestEntry::builder()
+                    .id(id.to_string())
+                    .message(entry.message_body)
+                    .set_message_group_id(entry.message_group_id)
+                    .set_message_deduplication_id(entry.message_deduplication_id)
+                    .set_message_attributes(Some(attributes))
+                    .build()
+            })
+            .collect();
+
+        let output = self
+            .client
+            .publish_batch()
+            .topic_arn(self.topic_arn.clone())
+            .set_publish_batch_request_entries(Some(entries))
+            .send()
+            .await?;
+        Ok(output
+            .failed
+            .unwrap_or_default()
+            .into_iter()
+            .map(|entry| FailedEntry {
+                code: entry.code.unwrap_or_default(),
+                message: entry.message,
+                sender_fault: entry.sender_fault,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,121 @@
+use aws_sdk_sns::Client as SnsClient;
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+
+use super::client::{SnsClientBuilder, SnsMessagePublisher};
+use crate::{
+    aws::create_client,
+    config::{AcknowledgementsConfig, GenerateConfig, Input, ProxyConfig, SinkConfig, SinkContext},
+    sinks::{
+        aws_s_s::{config::BaseSSSinkConfig, request_builder::SSRequestBuilder, sink::SSSink},
+        Healthcheck, VectorSink,
+    },
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub(super) struct SnsSinkConfig {
+    pub(super) topic_arn: String,
+    #[serde(flatten)]
+    pub(super) base_config: BaseSSSinkConfig,
+}
+
+impl GenerateConfig for SnsSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"topic_arn = "arn:aws:sns:us-east-2:123456789012:MyTopic"
+            region = "us-east-2"
+            encoding.codec = "json""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "aws_sns")]
+impl SinkConfig for SnsSinkConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let client = self.create_client(&cx.proxy).await?;
+        let healthcheck = self.clone().healthcheck(client.clone()).boxed();
+
+        let base = &self.base_config;
+        let transformer = base.encoding.transformer();
+        let serializer = base.encoding.encoding();
+        let request_builder = SSRequestBuilder::new(
+            (transformer, crate::codecs::Encoder::<()>::new(serializer)),
+            base.message_group_id(self.topic_arn.ends_with(".fifo"))?,
+            base.message_deduplication_id()?,
+            base.message_attributes()?,
+        );
+        let sink = SSSink::new(
+            cx.acker(),
+            base.batch_settings()?,
+            request_builder,
+            SnsMessagePublisher::new(client, self.topic_arn.clone()),
+            base.request,
+        );
+        Ok((VectorSink::from_event_streamsink(sink), healthcheck))
+    }
+
+    fn input(&self) -> Input {
+        Input::new(self.base_config.encoding.config().input_type())
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "aws_sns"
+    }
+
+    fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
+        Some(&self.base_config.acknowledgements)
+    }
+}
+
+impl SnsSinkConfig {
+    pub(super) async fn healthcheck(self, client: SnsClient) -> crate::Result<()> {
+        client
+            .get_topic_attributes()
+            .topic_arn(self.topic_arn.clone())
+            .send()
+            .await
+            .map(|_| ())
+            .map_err(Into::into)
+    }
+
+    pub(super) async fn create_client(&self, proxy: &ProxyConfig) -> crate::Result<SnsClient> {
+        create_client::<SnsClientBuilder>(
+            &self.base_config.auth,
+            self.base_config.region.region(),
+            self.base_config.region.endpoint()?,
+            proxy,
+            &self.base_config.tls,
+            true,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SnsSinkConfig>();
+    }
+
+    #[test]
+    fn fifo_topics_require_message_group_id() {
+        let config = toml::from_str::<SnsSinkConfig>(
+            r#"
+            topic_arn = "arn:aws:sns:us-east-2:123456789012:MyTopic.fifo"
+            region = "us-east-2"
+            encoding.codec = "json"
+            "#,
+        )
+        .unwrap();
+        assert!(config
+            .base_config
+            .message_group_id(config.topic_arn.ends_with(".fifo"))
+            .is_err());
+    }
+}
@@ -0,0 +1,120 @@
+use std::str::FromStr;
+
+use aws_sdk_sns::Client as SnsClient;
+use aws_sdk_sqs::{model::QueueAttributeName, Client as SqsClient};
+use aws_smithy_http::endpoint::Endpoint;
+use aws_types::region::Region;
+use http::Uri;
+use tokio::time::{sleep, Duration};
+
+use super::{client::SnsClientBuilder, config::SnsSinkConfig};
+use crate::{
+    aws::{create_client, AwsAuthentication, ClientBuilder},
+    common::sqs::SqsClientBuilder,
+    config::{ProxyConfig, SinkConfig, SinkContext},
+    test_util::{
+        components::{run_and_assert_sink_compliance, AWS_SINK_TAGS},
+        random_lines_with_stream, random_string,
+    },
+};
+
+fn sns_address() -> String {
+    std::env::var("SNS_ADDRESS").unwrap_or_else(|_| "http://localhost:4566".into())
+}
+
+async fn create_test_client<T: ClientBuilder>() -> T::Client {
+    create_client::<T>(
+        &AwsAuthentication::test_auth(),
+        Some(Region::new("us-east-1")),
+        Some(Endpoint::immutable(Uri::from_str(&sns_address()).unwrap())),
+        &ProxyConfig::default(),
+        &None,
+        true,
+    )
+    .await
+    .unwrap()
+}
+
+/// Subscribes a new queue to the topic, to receive the messages published.
+async fn subscribe_queue(sns: &SnsClient, sqs: &SqsClient, topic_arn: &str) -> String {
+    let queue_url = sqs
+        .create_queue()
+        .queue_name(format!("test-{}", random_string(10).to_lowercase()))
+        .send()
+        .await
+        .unwrap()
+        .queue_url
+        .unwrap();
+    let queue_arn = sqs
+        .get_queue_attributes()
+        .queue_url(queue_url.clone())
+        .attribute_names(QueueAttributeName::QueueArn)
+        .send()
+        .await
+        .unwrap()
+        .attributes
+        .unwrap()
+        .remove(&QueueAttributeName::QueueArn)
+        .unwrap();
+    sns.subscribe()
+        .topic_arn(topic_arn)
+        .protocol("sqs")
+        .endpoint(queue_arn)
+        .attributes("RawMessageDelivery", "true")
+        .send()
+        .await
+        .unwrap();
+    queue_url
+}
+
+#[tokio::test]
+async fn sns_publish_batch() {
+    let sns = create_test_client::<SnsClientBuilder>().await;
+    let sqs = create_test_client::<SqsClientBuilder>().await;
+
+    let topic_arn = sns
+        .create_topic()
+        .name(format!("test-{}", random_string(10).to_lowercase()))
+        .send()
+        .await
+        .unwrap()
+        .topic_arn
+        .unwrap();
+    let queue_url = subscribe_queue(&sns, &sqs, &topic_arn).await;
+
+    let config = toml::from_str::<SnsSinkConfig>(&format!(
+        r#"
+        topic_arn = "{}"
+        region = "us-east-1"
+        endpoint = "{}"
+        encoding.codec = "text"
+        "#,
+        topic_arn,
+        sns_address()
+    ))
+    .unwrap();
+    let (sink, healthcheck) = config.build(SinkContext::new_test()).await.unwrap();
+    healthcheck.await.unwrap();
+
+    let (mut input_lines, events) = random_lines_with_stream(100, 10, None);
+    run_and_assert_sink_compliance(sink, events, &AWS_SINK_TAGS).await;
+
+    sleep(Duration::from_secs(1)).await;
+
+    let mut output_lines = sqs
+        .receive_message()
+        .max_number_of_messages(10)
+        .queue_url(queue_url)
+        .send()
+        .await
+        .unwrap()
+        .messages
+        .unwrap()
+        .into_iter()
+        .map(|message| message.body.unwrap())
+        .collect::<Vec<_>>();
+
+    input_lines.sort();
+    output_lines.sort();
+    assert_eq!(output_lines, input_lines);
+}
@@ -0,0 +1,12 @@
+mod client;
+mod config;
+
+#[cfg(feature = "aws-sns-integration-tests")]
+#[cfg(test)]
+mod integration_tests;
+
+use crate::config::SinkDescription;
+
+inventory::submit! {
+    SinkDescription::new::<config::SnsSinkConfig>("aws_sns")
+}
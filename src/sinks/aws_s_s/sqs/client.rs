@@ -0,0 +1,74 @@
+use aws_sdk_sqs::{
+    error::SendMessageBatchError,
+    model::{MessageAttributeValue, SendMessageBatchRequestEntry},
+    types::SdkError,
+    Client as SqsClient,
+};
+
+use crate::sinks::aws_s_s::{
+    client::{Client, FailedEntry},
+    request_builder::SendMessageEntry,
+};
+
+#[derive(Clone)]
+pub(super) struct SqsMessagePublisher {
+    client: SqsClient,
+    queue_url: String,
+}
+
+impl SqsMessagePublisher {
+    pub(super) const fn new(client: SqsClient, queue_url: String) -> Self {
+        Self { client, queue_url }
+    }
+}
+
+#[async_trait::async_trait]
+impl Client<SendMessageBatchError> for SqsMessagePublisher {
+    async fn send_message_batch(
+        &self,
+        entries: Vec<SendMessageEntry>,
+    ) -> Result<Vec<FailedEntry>, SdkError<SendMessageBatchError>> {
+        let entries = entries
+            .into_iter()
+            .enumerate()
+            .map(|(id, entry)| {
+                let attributes = entry
+                    .message_attributes
+                    .into_iter()
+                    .map(|(name, value)| {
+                        let value = MessageAttributeValue::builder()
+                            .data_type("String")
+                            .string_value(value)
+                            .build();
+                        (name, value)
+                    })
+                    .collect();
+                SendMessageBatchRequestEntry::builder()
+                    .id(id.to_string())
+                    .message_body(entry.message_body)
+                    .set_message_group_id(entry.message_group_id)
+                    .set_message_deduplication_id(entry.message_deduplication_id)
+                    .set_message_attributes(Some(attributes))
+                    .build()
+            })
+            .collect();
+
+        let output = self
+            .client
+            .send_message_batch()
+            .queue_url(self.queue_url.clone())
+            .set_entries(Some(entries))
+            .send()
+            .await?;
+        Ok(output
+            .failed
+            .unwrap_or_default()
+            .into_iter()
+            .map(|entry| FailedEntry {
+                code: entry.code.unwrap_or_default(),
+                message: entry.message,
+                sender_fault: entry.sender_fault,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,125 @@
+use aws_sdk_sqs::Client as SqsClient;
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+
+use super::client::SqsMessagePublisher;
+use crate::{
+    aws::create_client,
+    common::sqs::SqsClientBuilder,
+    config::{AcknowledgementsConfig, GenerateConfig, Input, ProxyConfig, SinkConfig, SinkContext},
+    sinks::{
+        aws_s_s::{config::BaseSSSinkConfig, request_builder::SSRequestBuilder, sink::SSSink},
+        Healthcheck, VectorSink,
+    },
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub(super) struct SqsSinkConfig {
+    pub(super) queue_url: String,
+    // Deprecated name. Moved to auth.
+    pub(super) assume_role: Option<String>,
+    #[serde(flatten)]
+    pub(super) base_config: BaseSSSinkConfig,
+}
+
+impl GenerateConfig for SqsSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"queue_url = "https://sqs.us-east-2.amazonaws.com/123456789012/MyQueue"
+            region = "us-east-2"
+            encoding.codec = "json""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "aws_sqs")]
+impl SinkConfig for SqsSinkConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let client = self.create_client(&cx.proxy).await?;
+        let healthcheck = self.clone().healthcheck(client.clone()).boxed();
+
+        let base = &self.base_config;
+        let transformer = base.encoding.transformer();
+        let serializer = base.encoding.encoding();
+        let request_builder = SSRequestBuilder::new(
+            (transformer, crate::codecs::Encoder::<()>::new(serializer)),
+            base.message_group_id(self.queue_url.ends_with(".fifo"))?,
+            base.message_deduplication_id()?,
+            base.message_attributes()?,
+        );
+        let sink = SSSink::new(
+            cx.acker(),
+            base.batch_settings()?,
+            request_builder,
+            SqsMessagePublisher::new(client, self.queue_url.clone()),
+            base.request,
+        );
+        Ok((VectorSink::from_event_streamsink(sink), healthcheck))
+    }
+
+    fn input(&self) -> Input {
+        Input::new(self.base_config.encoding.config().input_type())
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "aws_sqs"
+    }
+
+    fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
+        Some(&self.base_config.acknowledgements)
+    }
+}
+
+impl SqsSinkConfig {
+    pub(super) async fn healthcheck(self, client: SqsClient) -> crate::Result<()> {
+        client
+            .get_queue_attributes()
+            .queue_url(self.queue_url.clone())
+            .send()
+            .await
+            .map(|_| ())
+            .map_err(Into::into)
+    }
+
+    pub(super) async fn create_client(&self, proxy: &ProxyConfig) -> crate::Result<SqsClient> {
+        create_client::<SqsClientBuilder>(
+            &self.base_config.auth,
+            self.base_config.region.region(),
+            self.base_config.region.endpoint()?,
+            proxy,
+            &self.base_config.tls,
+            true,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SqsSinkConfig>();
+    }
+
+    #[test]
+    fn parses_message_attributes() {
+        let config = toml::from_str::<SqsSinkConfig>(
+            r#"
+            queue_url = "https://sqs.us-east-2.amazonaws.com/123456789012/MyQueue.fifo"
+            region = "us-east-2"
+            encoding.codec = "json"
+            message_group_id = "{{ application }}"
+            message_attributes.host = "{{ host }}"
+            "#,
+        )
+        .unwrap();
+        let attributes = config.base_config.message_attributes().unwrap();
+        assert_eq!(attributes[0].0, "host");
+        assert!(config.base_config.message_group_id(true).unwrap().is_some());
+    }
+}
@@ -7,14 +7,11 @@ use aws_sdk_sqs::{Endpoint, Region};
 use http::Uri;
 use tokio::time::{sleep, Duration};
 
-use super::config::{Encoding, SqsSinkConfig};
-use super::sink::SqsSink;
+use super::config::SqsSinkConfig;
 use crate::aws::create_client;
-use crate::aws::{AwsAuthentication, RegionOrEndpoint};
+use crate::aws::AwsAuthentication;
 use crate::common::sqs::SqsClientBuilder;
-use crate::config::{ProxyConfig, SinkContext};
-use crate::sinks::util::encoding::EncodingConfig;
-use crate::sinks::VectorSink;
+use crate::config::{ProxyConfig, SinkConfig, SinkContext};
 use crate::test_util::{
     components::{run_and_assert_sink_compliance, AWS_SINK_TAGS},
     random_lines_with_stream, random_string,
@@ -51,23 +48,21 @@ async fn sqs_send_message_batch() {
 
     let client = create_test_client().await;
 
-    let config = SqsSinkConfig {
-        queue_url: queue_url.clone(),
-        region: RegionOrEndpoint::with_both("local", sqs_address().as_str()),
-        encoding: EncodingConfig::from(Encoding::Text).into(),
-        message_group_id: None,
-        message_deduplication_id: None,
-        request: Default::default(),
-        tls: Default::default(),
-        assume_role: None,
-        auth: Default::default(),
-        acknowledgements: Default::default(),
-    };
-
-    config.clone().healthcheck(client.clone()).await.unwrap();
-
-    let sink = SqsSink::new(config, cx, client.clone()).unwrap();
-    let sink = VectorSink::from_event_streamsink(sink);
+    let config = toml::from_str::<SqsSinkConfig>(&format!(
+        r#"
+        queue_url = "{}"
+        region = "local"
+        endpoint = "{}"
+        encoding.codec = "text"
+        message_attributes.source = "vector"
+        "#,
+        queue_url,
+        sqs_address()
+    ))
+    .unwrap();
+
+    let (sink, healthcheck) = config.build(cx).await.unwrap();
+    healthcheck.await.unwrap();
 
     let (mut input_lines, events) = random_lines_with_stream(100, 10, None);
     run_and_assert_sink_compliance(sink, events, &AWS_SINK_TAGS).await;
@@ -77,6 +72,7 @@ async fn sqs_send_message_batch() {
     let response = client
         .receive_message()
         .max_number_of_messages(input_lines.len() as i32)
+        .message_attribute_names("All")
         .queue_url(queue_url)
         .send()
         .await
@@ -94,7 +90,12 @@ async fn sqs_send_message_batch() {
     output_lines.sort();
 
     assert_eq!(output_lines, input_lines);
-    assert_eq!(input_lines.len(), response.messages.unwrap().len());
+    let messages = response.messages.unwrap();
+    assert_eq!(input_lines.len(), messages.len());
+    for message in messages {
+        let attributes = message.message_attributes.unwrap();
+        assert_eq!(attributes["source"].string_value.as_deref(), Some("vector"));
+    }
 }
 
 async fn ensure_queue(queue_name: String) {
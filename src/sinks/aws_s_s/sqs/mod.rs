@@ -1,8 +1,5 @@
+mod client;
 mod config;
-mod request_builder;
-mod retry;
-mod service;
-mod sink;
 
 #[cfg(feature = "aws-sqs-integration-tests")]
 #[cfg(test)]
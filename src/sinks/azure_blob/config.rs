@@ -21,6 +21,7 @@ use crate::{
                 EncodingConfig, EncodingConfigWithFramingAdapter, StandardEncodings,
                 StandardEncodingsWithFramingMigrator,
             },
+            manifest::{ManifestConfig, ManifestService},
             partitioner::KeyPartitioner,
             BatchConfig, BulkSizeBasedDefaultBatchSettings, Compression, ServiceBuilderExt,
             TowerRequestConfig,
@@ -39,6 +40,7 @@ pub struct AzureBlobSinkConfig {
     pub blob_prefix: Option<String>,
     pub blob_time_format: Option<String>,
     pub blob_append_uuid: Option<bool>,
+    pub manifest: Option<ManifestConfig>,
     #[serde(flatten)]
     pub encoding: EncodingConfigWithFramingAdapter<
         EncodingConfig<StandardEncodings>,
@@ -67,6 +69,7 @@ impl GenerateConfig for AzureBlobSinkConfig {
             blob_prefix: Some(String::from("blob")),
             blob_time_format: Some(String::from("%s")),
             blob_append_uuid: Some(true),
+            manifest: None,
             encoding: EncodingConfig::from(StandardEncodings::Ndjson).into(),
             compression: Compression::gzip_default(),
             batch: BatchConfig::default(),
@@ -122,9 +125,12 @@ impl AzureBlobSinkConfig {
         cx: SinkContext,
     ) -> crate::Result<VectorSink> {
         let request_limits = self.request.unwrap_with(&DEFAULT_REQUEST_LIMITS);
-        let service = ServiceBuilder::new()
-            .settings(request_limits, AzureBlobRetryLogic)
-            .service(AzureBlobService::new(client));
+        let service = AzureBlobService::new(client);
+        let service = ManifestService::new(self.manifest.clone(), service.clone(), || {
+            ServiceBuilder::new()
+                .settings(request_limits.clone(), AzureBlobRetryLogic)
+                .service(service.clone())
+        });
 
         // Configure our partitioning/batching.
         let batcher_settings = self.batch.into_batcher_settings()?;
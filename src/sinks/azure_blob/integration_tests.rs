@@ -220,6 +220,7 @@ impl AzureBlobSinkConfig {
                 blob_prefix: None,
                 blob_time_format: None,
                 blob_append_uuid: None,
+                manifest: None,
                 encoding: EncodingConfig::from(StandardEncodings::Text).into(),
                 compression: Compression::None,
                 batch: Default::default(),
@@ -12,7 +12,13 @@ use vector_core::{buffers::Ackable, internal_event::EventsSent, stream::DriverRe
 
 use crate::{
     event::{EventFinalizers, EventStatus, Finalizable},
-    sinks::{util::retries::RetryLogic, Healthcheck},
+    sinks::{
+        util::{
+            manifest::{ManifestEntry, ManifestRequest},
+            retries::RetryLogic,
+        },
+        Healthcheck,
+    },
 };
 
 #[derive(Debug, Clone)]
@@ -35,6 +41,30 @@ impl Finalizable for AzureBlobRequest {
     }
 }
 
+impl ManifestRequest for AzureBlobRequest {
+    fn manifest_entry(&self) -> ManifestEntry {
+        ManifestEntry {
+            key: self.metadata.partition_key.clone(),
+            records: self.metadata.count,
+            bytes: self.blob_data.len(),
+        }
+    }
+
+    fn manifest_request(&self, key: String, body: Bytes) -> Self {
+        AzureBlobRequest {
+            blob_data: body,
+            content_encoding: None,
+            content_type: "application/json",
+            metadata: AzureBlobMetadata {
+                partition_key: key,
+                count: 0,
+                byte_size: 0,
+                finalizers: EventFinalizers::default(),
+            },
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct AzureBlobMetadata {
     pub partition_key: String,
@@ -6,13 +6,18 @@ use std::{
 
 use azure_core::HttpError;
 use azure_storage_blobs::prelude::*;
+use bytes::Bytes;
 use futures::{future::BoxFuture, TryFutureExt};
+use http::StatusCode;
 use tower::Service;
 use tracing::Instrument;
 
 use crate::{
     internal_events::azure_blob::{AzureBlobHttpError, AzureBlobResponseError},
-    sinks::azure_common::config::{AzureBlobRequest, AzureBlobResponse},
+    sinks::{
+        azure_common::config::{AzureBlobRequest, AzureBlobResponse},
+        util::manifest::ObjectReader,
+    },
 };
 use vector_common::internal_event::BytesSent;
 
@@ -79,3 +84,27 @@ impl Service<AzureBlobRequest> for AzureBlobService {
         })
     }
 }
+
+impl ObjectReader<AzureBlobRequest> for AzureBlobService {
+    fn read_object(
+        &self,
+        _request: &AzureBlobRequest,
+        key: String,
+    ) -> BoxFuture<'static, crate::Result<Option<Bytes>>> {
+        let client = Arc::clone(&self.client).as_blob_client(key.as_str());
+
+        Box::pin(async move {
+            match client.get().execute().await {
+                Ok(response) => Ok(Some(Bytes::from(response.data.to_vec()))),
+                Err(reason) => match reason.downcast_ref::<HttpError>() {
+                    Some(HttpError::StatusCode { status, .. })
+                        if *status == StatusCode::NOT_FOUND =>
+                    {
+                        Ok(None)
+                    }
+                    _ => Err(reason),
+                },
+            }
+        })
+    }
+}
@@ -0,0 +1,600 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use futures::{future, stream::BoxStream, FutureExt};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use vector_core::{
+    config::DataType,
+    event::{BatchStatus, EventArray},
+    sink::StreamSink,
+};
+
+use crate::{
+    config::{
+        AcknowledgementsConfig, Capability, GenerateConfig, Input, Resource, SinkConfig,
+        SinkContext, SinkDescription,
+    },
+    internal_events::{BalanceTargetRecovered, BalanceTargetUnhealthy},
+    sinks::{
+        util::targets::{self, Router},
+        Healthcheck, VectorSink,
+    },
+};
+
+inventory::submit! {
+    SinkDescription::new::<BalanceConfig>("balance")
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("At least one target must be configured."))]
+    NoTargets,
+    #[snafu(display("The weight of target {} must be greater than zero.", index))]
+    ZeroWeight { index: usize },
+    #[snafu(display("`failure_threshold` must be greater than zero."))]
+    ZeroFailureThreshold,
+}
+
+#[derive(Debug, Snafu)]
+enum HealthcheckError {
+    #[snafu(display("None of the targets passed their healthcheck."))]
+    AllTargetsFailed,
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Strategy {
+    #[derivative(Default)]
+    Weighted,
+    LeastOutstanding,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BalanceConfig {
+    pub targets: Vec<BalanceTarget>,
+    #[serde(default)]
+    pub strategy: Strategy,
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+    #[serde(default = "default_unhealthy_secs")]
+    pub unhealthy_secs: u64,
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub acknowledgements: AcknowledgementsConfig,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BalanceTarget {
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+    #[serde(flatten)]
+    pub sink: Box<dyn SinkConfig>,
+}
+
+const fn default_weight() -> u32 {
+    1
+}
+
+const fn default_failure_threshold() -> u32 {
+    3
+}
+
+const fn default_unhealthy_secs() -> u64 {
+    30
+}
+
+impl GenerateConfig for BalanceConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"strategy = "weighted"
+
+            [[targets]]
+            type = "blackhole"
+            weight = 2
+
+            [[targets]]
+            type = "blackhole"
+            weight = 1"#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait]
+#[typetag::serde(name = "balance")]
+impl SinkConfig for BalanceConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        if self.targets.is_empty() {
+            return Err(BuildError::NoTargets.into());
+        }
+        if let Some(index) = self.targets.iter().position(|target| target.weight == 0) {
+            return Err(BuildError::ZeroWeight { index }.into());
+        }
+        if self.failure_threshold == 0 {
+            return Err(BuildError::ZeroFailureThreshold.into());
+        }
+
+        let mut sinks = Vec::with_capacity(self.targets.len());
+        let mut healthchecks = Vec::with_capacity(self.targets.len());
+        for target in &self.targets {
+            let (sink, healthcheck) = target.sink.build(cx.clone()).await?;
+            sinks.push(sink);
+            healthchecks.push(healthcheck);
+        }
+
+        let balancer = Arc::new(Mutex::new(Balancer::new(
+            self.strategy,
+            self.targets.iter().map(|target| target.weight),
+            self.failure_threshold,
+            Duration::from_secs(self.unhealthy_secs),
+        )));
+        let healthcheck = healthcheck(healthchecks, Arc::clone(&balancer)).boxed();
+        let sink = BalanceSink { balancer, sinks };
+
+        Ok((VectorSink::Stream(Box::new(sink)), healthcheck))
+    }
+
+    fn input(&self) -> Input {
+        // Any event may be sent to any of the targets, so only the types all
+        // of them accept are allowed.
+        let ty = self.targets.iter().fold(DataType::all(), |ty, target| {
+            ty & target.sink.input().data_type()
+        });
+        Input::new(ty)
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "balance"
+    }
+
+    fn capabilities(&self) -> Vec<Capability> {
+        self.targets
+            .iter()
+            .flat_map(|target| target.sink.capabilities())
+            .collect()
+    }
+
+    fn supports_sandbox(&self) -> bool {
+        self.targets
+            .iter()
+            .all(|target| target.sink.supports_sandbox())
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        self.targets
+            .iter()
+            .flat_map(|target| target.sink.resources())
+            .collect()
+    }
+
+    fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
+        Some(&self.acknowledgements)
+    }
+}
+
+/// Runs the healthchecks of all targets, marking the ones that fail as
+/// unhealthy. The sink is healthy as long as one of its targets is.
+async fn healthcheck(
+    healthchecks: Vec<Healthcheck>,
+    balancer: Arc<Mutex<Balancer>>,
+) -> crate::Result<()> {
+    let results = future::join_all(healthchecks).await;
+
+    let mut healthy = false;
+    for (target, result) in results.into_iter().enumerate() {
+        match result {
+            Ok(()) => healthy = true,
+            Err(error) => {
+                emit!(BalanceTargetUnhealthy {
+                    target,
+                    reason: &error.to_string(),
+                });
+                balancer
+                    .lock()
+                    .expect("mutex poisoned")
+                    .mark_unhealthy(target, Instant::now());
+            }
+        }
+    }
+
+    if healthy {
+        Ok(())
+    } else {
+        Err(HealthcheckError::AllTargetsFailed.into())
+    }
+}
+
+struct BalanceSink {
+    balancer: Arc<Mutex<Balancer>>,
+    sinks: Vec<VectorSink>,
+}
+
+#[async_trait]
+impl StreamSink<EventArray> for BalanceSink {
+    async fn run(self: Box<Self>, input: BoxStream<'_, EventArray>) -> Result<(), ()> {
+        let BalanceSink { balancer, sinks } = *self;
+        targets::route(&balancer, sinks, input).await
+    }
+}
+
+#[derive(Debug)]
+struct TargetState {
+    weight: u32,
+    /// The running weight of the smooth weighted round-robin.
+    current: i64,
+    /// The number of events sent to the target whose delivery hasn't finished.
+    outstanding: usize,
+    /// The number of consecutive batches the target failed to deliver.
+    failures: u32,
+    unhealthy_until: Option<Instant>,
+}
+
+impl TargetState {
+    fn is_healthy(&self, now: Instant) -> bool {
+        self.unhealthy_until.map_or(true, |until| now >= until)
+    }
+}
+
+/// Chooses the target of each batch of events, and keeps track of the health
+/// of the targets.
+#[derive(Debug)]
+struct Balancer {
+    strategy: Strategy,
+    targets: Vec<TargetState>,
+    failure_threshold: u32,
+    unhealthy_duration: Duration,
+    /// Where the search for the least outstanding target starts, so that ties
+    /// are spread across the targets.
+    next: usize,
+}
+
+impl Balancer {
+    fn new(
+        strategy: Strategy,
+        weights: impl IntoIterator<Item = u32>,
+        failure_threshold: u32,
+        unhealthy_duration: Duration,
+    ) -> Self {
+        let targets = weights
+            .into_iter()
+            .map(|weight| TargetState {
+                weight,
+                current: 0,
+                outstanding: 0,
+                failures: 0,
+                unhealthy_until: None,
+            })
+            .collect();
+        Self {
+            strategy,
+            targets,
+            failure_threshold,
+            unhealthy_duration,
+            next: 0,
+        }
+    }
+
+    fn select_weighted(&mut self, candidates: &[usize]) -> usize {
+        let mut total = 0;
+        let mut selected = candidates[0];
+        for &index in candidates {
+            let target = &mut self.targets[index];
+            target.current += i64::from(target.weight);
+            total += i64::from(target.weight);
+            if self.targets[index].current > self.targets[selected].current {
+                selected = index;
+            }
+        }
+        self.targets[selected].current -= total;
+        selected
+    }
+
+    fn select_least_outstanding(&mut self, candidates: &[usize]) -> usize {
+        let start = candidates
+            .iter()
+            .position(|&index| index >= self.next)
+            .unwrap_or(0);
+
+        let mut selected = candidates[start];
+        for &index in candidates[start..].iter().chain(&candidates[..start]) {
+            // Compares `outstanding / weight` without dividing.
+            let target = &self.targets[index];
+            let best = &self.targets[selected];
+            if (target.outstanding as u64) * u64::from(best.weight)
+                < (best.outstanding as u64) * u64::from(target.weight)
+            {
+                selected = index;
+            }
+        }
+        self.next = selected + 1;
+        selected
+    }
+
+    fn mark_unhealthy(&mut self, index: usize, now: Instant) {
+        let target = &mut self.targets[index];
+        target.failures = 0;
+        target.unhealthy_until = Some(now + self.unhealthy_duration);
+    }
+}
+
+impl Router for Balancer {
+    /// Selects the target for the next batch among the healthy ones. When all
+    /// of them are unhealthy, they are all considered again rather than
+    /// holding up events.
+    fn select(&mut self, now: Instant) -> usize {
+        let mut candidates = (0..self.targets.len())
+            .filter(|&index| self.targets[index].is_healthy(now))
+            .collect::<Vec<_>>();
+        if candidates.is_empty() {
+            candidates = (0..self.targets.len()).collect();
+        }
+
+        match self.strategy {
+            Strategy::Weighted => self.select_weighted(&candidates),
+            Strategy::LeastOutstanding => self.select_least_outstanding(&candidates),
+        }
+    }
+
+    fn start(&mut self, index: usize, count: usize) {
+        self.targets[index].outstanding += count;
+    }
+
+    fn finish(&mut self, index: usize, count: usize, status: BatchStatus, now: Instant) {
+        let target = &mut self.targets[index];
+        target.outstanding = target.outstanding.saturating_sub(count);
+
+        match status {
+            BatchStatus::Delivered => {
+                target.failures = 0;
+                if target.unhealthy_until.take().is_some() {
+                    emit!(BalanceTargetRecovered { target: index });
+                }
+            }
+            // Rejected events are a problem with the events themselves, not
+            // with the target.
+            BatchStatus::Rejected => {}
+            BatchStatus::Errored => {
+                target.failures += 1;
+                if target.failures >= self.failure_threshold {
+                    emit!(BalanceTargetUnhealthy {
+                        target: index,
+                        reason: "too many failed deliveries",
+                    });
+                    self.mark_unhealthy(index, now);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use futures::{stream, StreamExt};
+    use tokio::sync::mpsc;
+    use tokio_stream::wrappers::ReceiverStream;
+    use vector_core::event::BatchNotifier;
+
+    use super::*;
+    use crate::{
+        event::{Event, LogEvent},
+        sinks::util::targets::test::TestTargetConfig,
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<BalanceConfig>();
+    }
+
+    fn counts(balancer: &mut Balancer, now: Instant, rounds: usize) -> HashMap<usize, usize> {
+        let mut counts = HashMap::new();
+        for _ in 0..rounds {
+            *counts.entry(balancer.select(now)).or_default() += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn weighted_follows_weights() {
+        let mut balancer = Balancer::new(Strategy::Weighted, [3, 1], 3, Duration::from_secs(30));
+        let now = Instant::now();
+
+        // The heavier target doesn't get all of its batches in a row.
+        let order = (0..4).map(|_| balancer.select(now)).collect::<Vec<_>>();
+        assert_eq!(order, vec![0, 0, 1, 0]);
+
+        let counts = counts(&mut balancer, now, 400);
+        assert_eq!(counts[&0], 300);
+        assert_eq!(counts[&1], 100);
+    }
+
+    #[test]
+    fn least_outstanding_prefers_idle_targets() {
+        let mut balancer = Balancer::new(
+            Strategy::LeastOutstanding,
+            [1, 1, 2],
+            3,
+            Duration::from_secs(30),
+        );
+        let now = Instant::now();
+
+        // Ties are spread across the targets.
+        let order = (0..3).map(|_| balancer.select(now)).collect::<Vec<_>>();
+        assert_eq!(order, vec![0, 1, 2]);
+
+        balancer.start(0, 10);
+        balancer.start(1, 4);
+        balancer.start(2, 10);
+        // Target 2 has twice the weight, so it counts as having 5 outstanding.
+        assert_eq!(balancer.select(now), 1);
+
+        balancer.finish(0, 10, BatchStatus::Delivered, now);
+        assert_eq!(balancer.select(now), 0);
+    }
+
+    #[test]
+    fn failing_targets_are_excluded() {
+        let mut balancer = Balancer::new(Strategy::Weighted, [1, 1], 2, Duration::from_secs(30));
+        let now = Instant::now();
+
+        balancer.start(1, 2);
+        balancer.finish(1, 1, BatchStatus::Errored, now);
+        // Rejections don't count against the target.
+        balancer.finish(1, 1, BatchStatus::Rejected, now);
+        assert_eq!(counts(&mut balancer, now, 10)[&1], 5);
+
+        balancer.start(1, 1);
+        balancer.finish(1, 1, BatchStatus::Errored, now);
+        assert!(!counts(&mut balancer, now, 10).contains_key(&1));
+
+        // The target is tried again once it has been excluded for long enough.
+        let later = now + Duration::from_secs(31);
+        assert_eq!(counts(&mut balancer, later, 10)[&1], 5);
+        balancer.start(1, 1);
+        balancer.finish(1, 1, BatchStatus::Delivered, later);
+        assert!(balancer.targets[1].unhealthy_until.is_none());
+    }
+
+    #[test]
+    fn all_unhealthy_targets_are_still_used() {
+        let mut balancer = Balancer::new(Strategy::Weighted, [1, 1], 1, Duration::from_secs(30));
+        let now = Instant::now();
+        balancer.mark_unhealthy(0, now);
+        balancer.mark_unhealthy(1, now);
+
+        let counts = counts(&mut balancer, now, 10);
+        assert_eq!(counts[&0], 5);
+        assert_eq!(counts[&1], 5);
+    }
+
+    fn config(targets: Vec<(u32, TestTargetConfig)>) -> BalanceConfig {
+        BalanceConfig {
+            targets: targets
+                .into_iter()
+                .map(|(weight, sink)| BalanceTarget {
+                    weight,
+                    sink: Box::new(sink),
+                })
+                .collect(),
+            strategy: Strategy::Weighted,
+            failure_threshold: default_failure_threshold(),
+            unhealthy_secs: default_unhealthy_secs(),
+            acknowledgements: Default::default(),
+        }
+    }
+
+    #[test]
+    fn input_is_shared_by_all_targets() {
+        let config: BalanceConfig = toml::from_str(
+            r#"
+            [[targets]]
+            type = "target_test"
+            fail = false
+            healthy = true
+
+            [[targets]]
+            type = "blackhole"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.input().data_type(), DataType::Log);
+    }
+
+    #[tokio::test]
+    async fn distributes_events() {
+        let first = TestTargetConfig {
+            healthy: true,
+            ..Default::default()
+        };
+        let second = TestTargetConfig {
+            healthy: true,
+            ..Default::default()
+        };
+        let (first_events, second_events) = (Arc::clone(&first.events), Arc::clone(&second.events));
+
+        let (sink, healthcheck) = config(vec![(2, first), (1, second)])
+            .build(SinkContext::new_test())
+            .await
+            .unwrap();
+        healthcheck.await.unwrap();
+
+        let (batch, receiver) = BatchNotifier::new_with_receiver();
+        let events = (0..30)
+            .map(|i| {
+                Event::from(LogEvent::from(format!("event {}", i))).with_batch_notifier(&batch)
+            })
+            .collect::<Vec<_>>();
+        drop(batch);
+        sink.run(stream::iter(events).map(EventArray::from))
+            .await
+            .unwrap();
+
+        assert_eq!(receiver.await, BatchStatus::Delivered);
+        assert_eq!(first_events.lock().unwrap().len(), 20);
+        assert_eq!(second_events.lock().unwrap().len(), 10);
+    }
+
+    #[tokio::test]
+    async fn avoids_failing_targets() {
+        let failing = TestTargetConfig {
+            fail: true,
+            healthy: true,
+            ..Default::default()
+        };
+        let working = TestTargetConfig {
+            healthy: true,
+            ..Default::default()
+        };
+        let (failing_events, working_events) =
+            (Arc::clone(&failing.events), Arc::clone(&working.events));
+
+        let mut config = config(vec![(1, failing), (1, working)]);
+        config.failure_threshold = 1;
+        let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+
+        // Each event is delivered before the next one is sent, so the failure
+        // is noticed in time.
+        let (sender, receiver) = mpsc::channel(1);
+        let run = tokio::spawn(sink.run(ReceiverStream::new(receiver)));
+        for i in 0..10 {
+            let event = Event::from(LogEvent::from(format!("event {}", i)));
+            sender.send(EventArray::from(event)).await.unwrap();
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        drop(sender);
+        run.await.unwrap().unwrap();
+
+        assert_eq!(failing_events.lock().unwrap().len(), 1);
+        assert_eq!(working_events.lock().unwrap().len(), 9);
+    }
+
+    #[tokio::test]
+    async fn healthcheck_excludes_unhealthy_targets() {
+        let unhealthy = TestTargetConfig::default();
+        let healthy = TestTargetConfig {
+            healthy: true,
+            ..Default::default()
+        };
+        let (sink, healthcheck) = config(vec![(1, unhealthy), (1, healthy)])
+            .build(SinkContext::new_test())
+            .await
+            .unwrap();
+        drop(sink);
+        healthcheck.await.unwrap();
+
+        let (_, healthcheck) = config(vec![(1, TestTargetConfig::default())])
+            .build(SinkContext::new_test())
+            .await
+            .unwrap();
+        assert!(healthcheck.await.is_err());
+    }
+}
@@ -0,0 +1,216 @@
+use std::sync::Arc;
+
+use futures::FutureExt;
+use scylla::{statement::Consistency, SessionBuilder};
+use serde::{Deserialize, Serialize};
+use tower::ServiceBuilder;
+
+use super::{
+    row::{CassandraColumn, RowEncoder},
+    service::{CassandraConnector, CassandraRetryLogic, CassandraService},
+    sink::{CassandraSink, TableStatements},
+};
+use crate::{
+    config::{
+        AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext,
+        SinkDescription,
+    },
+    sinks::{
+        util::{BatchConfig, ServiceBuilderExt, SinkBatchSettings, TowerRequestConfig},
+        Healthcheck, VectorSink,
+    },
+    template::Template,
+    tls::{tls_connector_builder, MaybeTlsSettings, TlsEnableableConfig},
+};
+
+/// The port of the native protocol, used when the endpoints don't have one.
+const DEFAULT_PORT: u16 = 9042;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CassandraDefaultBatchSettings;
+
+impl SinkBatchSettings for CassandraDefaultBatchSettings {
+    const MAX_EVENTS: Option<usize> = Some(100);
+    // Cassandra rejects batches over 50 KiB by default.
+    const MAX_BYTES: Option<usize> = Some(40_000);
+    const TIMEOUT_SECS: f64 = 1.0;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CassandraConfig {
+    /// The contact points of the cluster, as `host:port`, from which its other nodes are
+    /// discovered.
+    pub endpoints: Vec<String>,
+    pub keyspace: String,
+    /// The table the events are written to, which can be templated to route them to several
+    /// tables sharing the columns.
+    pub table: Template,
+    /// The columns the rows are written to.
+    pub columns: Vec<CassandraColumn>,
+    /// The TTL of the rows, when it isn't read from `ttl_field`.
+    pub ttl_secs: Option<u32>,
+    /// The path of the field holding the TTL of each row, in seconds.
+    pub ttl_field: Option<String>,
+    #[serde(default)]
+    pub consistency: CassandraConsistency,
+    pub auth: Option<CassandraAuth>,
+    #[serde(default)]
+    pub batch: BatchConfig<CassandraDefaultBatchSettings>,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+    pub tls: Option<TlsEnableableConfig>,
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CassandraAuth {
+    pub username: String,
+    pub password: String,
+}
+
+/// The consistency level of the writes, as in the number of replicas acknowledging them.
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, PartialEq, Eq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CassandraConsistency {
+    Any,
+    One,
+    Two,
+    Three,
+    Quorum,
+    All,
+    #[derivative(Default)]
+    LocalQuorum,
+    EachQuorum,
+    LocalOne,
+}
+
+impl From<CassandraConsistency> for Consistency {
+    fn from(consistency: CassandraConsistency) -> Self {
+        match consistency {
+            CassandraConsistency::Any => Self::Any,
+            CassandraConsistency::One => Self::One,
+            CassandraConsistency::Two => Self::Two,
+            CassandraConsistency::Three => Self::Three,
+            CassandraConsistency::Quorum => Self::Quorum,
+            CassandraConsistency::All => Self::All,
+            CassandraConsistency::LocalQuorum => Self::LocalQuorum,
+            CassandraConsistency::EachQuorum => Self::EachQuorum,
+            CassandraConsistency::LocalOne => Self::LocalOne,
+        }
+    }
+}
+
+inventory::submit! {
+    SinkDescription::new::<CassandraConfig>("cassandra")
+}
+
+impl GenerateConfig for CassandraConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"endpoints = ["localhost:9042"]
+            keyspace = "logs"
+            table = "events"
+            columns = [{ name = "timestamp" }, { name = "message" }]"#,
+        )
+        .unwrap()
+    }
+}
+
+impl CassandraConfig {
+    /// The contact points, on the default port when they don't have one.
+    fn hosts(&self) -> impl Iterator<Item = (&str, u16)> {
+        self.endpoints.iter().map(|endpoint| {
+            endpoint
+                .rsplit_once(':')
+                .and_then(|(host, port)| Some((host, port.parse().ok()?)))
+                .unwrap_or((endpoint.as_str(), DEFAULT_PORT))
+        })
+    }
+
+    fn session_builder(&self) -> crate::Result<SessionBuilder> {
+        let mut builder = SessionBuilder::new();
+        for (host, port) in self.hosts() {
+            builder = builder.known_node(format!("{}:{}", host, port));
+        }
+        if let Some(auth) = &self.auth {
+            builder = builder.user(&auth.username, &auth.password);
+        }
+        let tls = MaybeTlsSettings::from_config(&self.tls, false)?;
+        if tls.is_tls() {
+            builder =
+                builder.ssl_context(Some(tls_connector_builder(&tls)?.build().into_context()));
+        }
+        Ok(builder)
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "cassandra")]
+impl SinkConfig for CassandraConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let encoder = RowEncoder::new(self.columns.clone(), self.ttl_secs, self.ttl_field.clone())?;
+        let connector = CassandraConnector::new(self.session_builder()?, self.endpoints.join(","));
+        let statements = Arc::new(TableStatements::new(
+            connector.clone(),
+            self.keyspace.clone(),
+            encoder,
+        ));
+
+        let healthcheck = healthcheck(
+            connector.clone(),
+            Arc::clone(&statements),
+            self.table.clone(),
+        )
+        .boxed();
+
+        let batch_settings = self.batch.into_batcher_settings()?;
+        let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
+        let service = ServiceBuilder::new()
+            .settings(request_settings, CassandraRetryLogic)
+            .service(CassandraService::new(connector, self.consistency.into()));
+
+        let sink = CassandraSink {
+            service,
+            batch_settings,
+            acker: cx.acker(),
+            table: self.table.clone(),
+            statements,
+        };
+
+        Ok((VectorSink::from_event_streamsink(sink), healthcheck))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "cassandra"
+    }
+
+    fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
+        Some(&self.acknowledgements)
+    }
+}
+
+/// Checks that the cluster can be connected to, and that the rows can be inserted into the table
+/// when it isn't templated.
+async fn healthcheck(
+    connector: CassandraConnector,
+    statements: Arc<TableStatements>,
+    table: Template,
+) -> crate::Result<()> {
+    connector.session().await?;
+    if !table.is_dynamic() {
+        statements.prepare(table.get_ref()).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,97 @@
+//! The `cassandra` sink, which writes log events into an Apache Cassandra or ScyllaDB table.
+//!
+//! Events are mapped to rows with the configured columns, whose values are converted to the types
+//! of the columns as described by the prepared statement inserting into the table. The rows are
+//! batched per partition, so each batch is a single-partition `UNLOGGED BATCH` which the driver
+//! sends to a replica owning the token of the partition.
+
+use scylla::{
+    frame::value::SerializeValuesError,
+    transport::errors::{NewSessionError, QueryError},
+};
+use snafu::Snafu;
+
+mod config;
+mod row;
+mod service;
+mod sink;
+
+pub use config::CassandraConfig;
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub))]
+pub enum CassandraSinkError {
+    #[snafu(display("The sink must have at least one column."))]
+    NoColumns,
+
+    #[snafu(display("The sink has more than one `{}` column.", name))]
+    DuplicateColumn { name: String },
+
+    #[snafu(display("The `{}` column has the unsupported type {}.", name, column_type))]
+    UnsupportedColumnType { name: String, column_type: String },
+
+    #[snafu(display(
+        "The value of the `{}` column can't be converted to {}.",
+        name,
+        column_type
+    ))]
+    InvalidValue { name: String, column_type: String },
+
+    #[snafu(display("The TTL of the row isn't a number of seconds."))]
+    InvalidTtl,
+
+    #[snafu(display("Failed to connect: {}", source))]
+    Connect { source: NewSessionError },
+
+    #[snafu(display("Failed to prepare the statement: {}", source))]
+    Prepare { source: QueryError },
+
+    #[snafu(display("Failed to serialize the values of the rows: {}", source))]
+    Serialize { source: SerializeValuesError },
+
+    #[snafu(display("Failed to write the rows: {}", source))]
+    Write { source: QueryError },
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+    use crate::config::{Capability, SinkConfig, SinkContext};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<CassandraConfig>();
+    }
+
+    #[test]
+    fn declares_the_contact_points() {
+        let config: CassandraConfig = toml::from_str(indoc! {r#"
+                endpoints = ["cassandra-1.example.com:9042", "cassandra-2.example.com"]
+                keyspace = "logs"
+                table = "events"
+                columns = [{ name = "message" }]
+            "#})
+        .unwrap();
+        assert_eq!(
+            config.capabilities(),
+            vec![
+                Capability::host("cassandra-1.example.com", Some(9042)),
+                Capability::host("cassandra-2.example.com", Some(9042)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_empty_columns() {
+        let config: CassandraConfig = toml::from_str(indoc! {r#"
+                endpoints = ["localhost:9042"]
+                keyspace = "logs"
+                table = "events"
+                columns = []
+            "#})
+        .unwrap();
+        assert!(config.build(SinkContext::new_test()).await.is_err());
+    }
+}
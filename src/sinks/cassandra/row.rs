@@ -0,0 +1,601 @@
+use std::{collections::HashSet, convert::TryFrom, net::IpAddr};
+
+use bytes::Bytes;
+use chrono::{DateTime, NaiveDate, Utc};
+use scylla::{frame::response::result::ColumnType, prepared_statement::PreparedStatement};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::CassandraSinkError;
+use crate::event::{LogEvent, Value};
+
+/// The length of the values bound as `null`.
+const NULL: i32 = -1;
+
+/// The length of the values left unset, which aren't written at all.
+const UNSET: i32 = -2;
+
+/// The number of days between the epoch of the `date` type, in the middle of the range of its
+/// unsigned integers, and the Unix epoch.
+const DATE_EPOCH: i64 = 1 << 31;
+
+/// A column of the table, and the field of the events its values are read from.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct CassandraColumn {
+    /// The name of the column.
+    pub name: String,
+    /// The path of the field holding the values of the column, which defaults to its name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+}
+
+impl CassandraColumn {
+    fn path(&self) -> &str {
+        self.field.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// The statement inserting rows into a table, along with the types of its bound values.
+#[derive(Debug)]
+pub struct TableStatement {
+    pub table: String,
+    pub prepared: PreparedStatement,
+    /// The types of the columns, followed by the type of the TTL when it's bound.
+    column_types: Vec<ColumnType>,
+    /// The indexes of the columns of the partition key.
+    partition_key: Vec<usize>,
+}
+
+/// A row encoded with the types of the columns of its table, as the bound values of the
+/// statement inserting it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EncodedRow {
+    pub values: Vec<Bytes>,
+    /// The values of the columns of the partition key, which identify the partition of the row.
+    pub partition_key: Bytes,
+}
+
+/// Serializes events as rows of the tables, converting their fields to the types of the columns.
+#[derive(Debug)]
+pub struct RowEncoder {
+    columns: Vec<CassandraColumn>,
+    ttl_secs: Option<u32>,
+    ttl_field: Option<String>,
+}
+
+impl RowEncoder {
+    pub fn new(
+        columns: Vec<CassandraColumn>,
+        ttl_secs: Option<u32>,
+        ttl_field: Option<String>,
+    ) -> Result<Self, CassandraSinkError> {
+        if columns.is_empty() {
+            return Err(CassandraSinkError::NoColumns);
+        }
+        let mut names = HashSet::new();
+        if let Some(column) = columns.iter().find(|column| !names.insert(&column.name)) {
+            return Err(CassandraSinkError::DuplicateColumn {
+                name: column.name.clone(),
+            });
+        }
+        Ok(Self {
+            columns,
+            ttl_secs,
+            ttl_field,
+        })
+    }
+
+    const fn has_ttl(&self) -> bool {
+        self.ttl_secs.is_some() || self.ttl_field.is_some()
+    }
+
+    /// The statement inserting a row into the table, with the TTL of the row bound after the
+    /// values of the columns when TTLs are set.
+    pub fn insert_statement(&self, keyspace: &str, table: &str) -> String {
+        let columns = self
+            .columns
+            .iter()
+            .map(|column| quote_identifier(&column.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let markers = vec!["?"; self.columns.len()].join(", ");
+        let ttl = if self.has_ttl() { " USING TTL ?" } else { "" };
+        format!(
+            "INSERT INTO {}.{} ({}) VALUES ({}){}",
+            quote_identifier(keyspace),
+            quote_identifier(table),
+            columns,
+            markers,
+            ttl
+        )
+    }
+
+    /// Checks that the values of the columns of the prepared statement can be converted to.
+    pub fn table_statement(
+        &self,
+        table: String,
+        prepared: PreparedStatement,
+    ) -> Result<TableStatement, CassandraSinkError> {
+        let metadata = prepared.get_prepared_metadata();
+        let column_types = metadata
+            .col_specs
+            .iter()
+            .map(|spec| spec.typ.clone())
+            .collect::<Vec<_>>();
+        if let Some((column, column_type)) = self
+            .columns
+            .iter()
+            .zip(&column_types)
+            .find(|(_, column_type)| !is_supported(column_type))
+        {
+            return Err(CassandraSinkError::UnsupportedColumnType {
+                name: column.name.clone(),
+                column_type: format!("{:?}", column_type),
+            });
+        }
+        let partition_key = metadata
+            .pk_indexes
+            .iter()
+            .map(|index| index.index as usize)
+            .collect();
+
+        Ok(TableStatement {
+            table,
+            prepared,
+            column_types,
+            partition_key,
+        })
+    }
+
+    /// Serializes the event as a row of the table of the statement. Columns whose field is
+    /// missing are left unset, so that no tombstones are written for them, and the ones whose
+    /// field is null are set to null.
+    pub fn encode(
+        &self,
+        statement: &TableStatement,
+        log: &LogEvent,
+    ) -> Result<EncodedRow, CassandraSinkError> {
+        let mut values = Vec::with_capacity(statement.column_types.len());
+        for (column, column_type) in self.columns.iter().zip(&statement.column_types) {
+            let mut buffer = Vec::new();
+            if !encode_field(log.get(column.path()), column_type, &mut buffer) {
+                return Err(CassandraSinkError::InvalidValue {
+                    name: column.name.clone(),
+                    column_type: format!("{:?}", column_type),
+                });
+            }
+            values.push(Bytes::from(buffer));
+        }
+
+        if self.has_ttl() {
+            let ttl = match self.ttl_field.as_deref().and_then(|path| log.get(path)) {
+                Some(value) => Some(
+                    integer(value)
+                        .and_then(|ttl| i32::try_from(ttl).ok())
+                        .filter(|ttl| *ttl >= 0)
+                        .ok_or(CassandraSinkError::InvalidTtl)?,
+                ),
+                None => self.ttl_secs.and_then(|ttl| i32::try_from(ttl).ok()),
+            };
+            // An unset TTL falls back to the default TTL of the table.
+            let mut buffer = Vec::new();
+            match ttl {
+                Some(ttl) => {
+                    buffer.extend_from_slice(&4i32.to_be_bytes());
+                    buffer.extend_from_slice(&ttl.to_be_bytes());
+                }
+                None => buffer.extend_from_slice(&UNSET.to_be_bytes()),
+            }
+            values.push(Bytes::from(buffer));
+        }
+
+        let partition_key = statement
+            .partition_key
+            .iter()
+            .filter_map(|index| values.get(*index))
+            .flat_map(|value| value.iter().copied())
+            .collect::<Vec<_>>();
+        Ok(EncodedRow {
+            values,
+            partition_key: Bytes::from(partition_key),
+        })
+    }
+}
+
+fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+/// Whether values can be converted to the type.
+fn is_supported(column_type: &ColumnType) -> bool {
+    match column_type {
+        ColumnType::Ascii
+        | ColumnType::Text
+        | ColumnType::Blob
+        | ColumnType::Boolean
+        | ColumnType::TinyInt
+        | ColumnType::SmallInt
+        | ColumnType::Int
+        | ColumnType::BigInt
+        | ColumnType::Float
+        | ColumnType::Double
+        | ColumnType::Timestamp
+        | ColumnType::Date
+        | ColumnType::Uuid
+        | ColumnType::Timeuuid
+        | ColumnType::Inet => true,
+        ColumnType::List(element) | ColumnType::Set(element) => is_supported(element),
+        ColumnType::Map(key, value) => is_supported(key) && is_supported(value),
+        _ => false,
+    }
+}
+
+/// Serializes the field as a bound value, prefixed by its length. Returns `false` when it can't
+/// be converted to the type.
+fn encode_field(value: Option<&Value>, column_type: &ColumnType, buffer: &mut Vec<u8>) -> bool {
+    match value {
+        None => {
+            buffer.extend_from_slice(&UNSET.to_be_bytes());
+            true
+        }
+        Some(Value::Null) => {
+            buffer.extend_from_slice(&NULL.to_be_bytes());
+            true
+        }
+        Some(value) => encode_value(value, column_type, buffer),
+    }
+}
+
+/// Serializes the value, prefixed by its length, as the elements of collections are too.
+fn encode_value(value: &Value, column_type: &ColumnType, buffer: &mut Vec<u8>) -> bool {
+    let start = buffer.len();
+    buffer.extend_from_slice(&[0; 4]);
+    let length = if encode_contents(value, column_type, buffer) {
+        i32::try_from(buffer.len() - start - 4).ok()
+    } else {
+        None
+    };
+    match length {
+        Some(length) => {
+            buffer[start..start + 4].copy_from_slice(&length.to_be_bytes());
+            true
+        }
+        None => {
+            buffer.truncate(start);
+            false
+        }
+    }
+}
+
+fn encode_contents(value: &Value, column_type: &ColumnType, buffer: &mut Vec<u8>) -> bool {
+    match column_type {
+        ColumnType::Ascii => {
+            let text = value.to_string_lossy();
+            if !text.is_ascii() {
+                return false;
+            }
+            buffer.extend_from_slice(text.as_bytes());
+        }
+        ColumnType::Text => buffer.extend_from_slice(value.to_string_lossy().as_bytes()),
+        ColumnType::Blob => match value {
+            Value::Bytes(bytes) => buffer.extend_from_slice(bytes),
+            value => buffer.extend_from_slice(value.to_string_lossy().as_bytes()),
+        },
+        ColumnType::Boolean => match boolean(value) {
+            Some(boolean) => buffer.push(u8::from(boolean)),
+            None => return false,
+        },
+        ColumnType::TinyInt => {
+            match integer(value).and_then(|integer| i8::try_from(integer).ok()) {
+                Some(integer) => buffer.extend_from_slice(&integer.to_be_bytes()),
+                None => return false,
+            }
+        }
+        ColumnType::SmallInt => {
+            match integer(value).and_then(|integer| i16::try_from(integer).ok()) {
+                Some(integer) => buffer.extend_from_slice(&integer.to_be_bytes()),
+                None => return false,
+            }
+        }
+        ColumnType::Int => match integer(value).and_then(|integer| i32::try_from(integer).ok()) {
+            Some(integer) => buffer.extend_from_slice(&integer.to_be_bytes()),
+            None => return false,
+        },
+        ColumnType::BigInt => match integer(value) {
+            Some(integer) => buffer.extend_from_slice(&integer.to_be_bytes()),
+            None => return false,
+        },
+        ColumnType::Float => match float(value) {
+            Some(float) => buffer.extend_from_slice(&(float as f32).to_be_bytes()),
+            None => return false,
+        },
+        ColumnType::Double => match float(value) {
+            Some(float) => buffer.extend_from_slice(&float.to_be_bytes()),
+            None => return false,
+        },
+        // Timestamps are milliseconds since the Unix epoch.
+        ColumnType::Timestamp => match timestamp(value) {
+            Some(timestamp) => {
+                buffer.extend_from_slice(&timestamp.timestamp_millis().to_be_bytes())
+            }
+            None => return false,
+        },
+        ColumnType::Date => {
+            let days = match value {
+                Value::Bytes(bytes) => std::str::from_utf8(bytes)
+                    .ok()
+                    .and_then(|text| NaiveDate::parse_from_str(text, "%Y-%m-%d").ok())
+                    .map(|date| date.and_hms(0, 0, 0).timestamp()),
+                value => timestamp(value).map(|timestamp| timestamp.timestamp()),
+            }
+            .map(|seconds| seconds.div_euclid(86_400) + DATE_EPOCH)
+            .and_then(|days| u32::try_from(days).ok());
+            match days {
+                Some(days) => buffer.extend_from_slice(&days.to_be_bytes()),
+                None => return false,
+            }
+        }
+        ColumnType::Uuid | ColumnType::Timeuuid => {
+            match Uuid::parse_str(&value.to_string_lossy()) {
+                Ok(uuid) => buffer.extend_from_slice(uuid.as_bytes()),
+                Err(_) => return false,
+            }
+        }
+        ColumnType::Inet => match value.to_string_lossy().parse::<IpAddr>() {
+            Ok(IpAddr::V4(address)) => buffer.extend_from_slice(&address.octets()),
+            Ok(IpAddr::V6(address)) => buffer.extend_from_slice(&address.octets()),
+            Err(_) => return false,
+        },
+        ColumnType::List(element) | ColumnType::Set(element) => match value {
+            Value::Array(items) => {
+                let count = match i32::try_from(items.len()) {
+                    Ok(count) => count,
+                    Err(_) => return false,
+                };
+                buffer.extend_from_slice(&count.to_be_bytes());
+                if !items.iter().all(|item| encode_value(item, element, buffer)) {
+                    return false;
+                }
+            }
+            _ => return false,
+        },
+        ColumnType::Map(key_type, value_type) => match value {
+            Value::Object(map) => {
+                let count = match i32::try_from(map.len()) {
+                    Ok(count) => count,
+                    Err(_) => return false,
+                };
+                buffer.extend_from_slice(&count.to_be_bytes());
+                if !map.iter().all(|(key, value)| {
+                    encode_value(&Value::from(key.as_str()), key_type, buffer)
+                        && encode_value(value, value_type, buffer)
+                }) {
+                    return false;
+                }
+            }
+            _ => return false,
+        },
+        _ => return false,
+    }
+    true
+}
+
+fn boolean(value: &Value) -> Option<bool> {
+    match value {
+        Value::Boolean(boolean) => Some(*boolean),
+        Value::Bytes(bytes) => std::str::from_utf8(bytes).ok()?.parse().ok(),
+        _ => None,
+    }
+}
+
+fn integer(value: &Value) -> Option<i64> {
+    match value {
+        Value::Integer(integer) => Some(*integer),
+        Value::Bytes(bytes) => std::str::from_utf8(bytes).ok()?.parse().ok(),
+        _ => None,
+    }
+}
+
+fn float(value: &Value) -> Option<f64> {
+    match value {
+        Value::Float(float) => Some(float.into_inner()),
+        Value::Integer(integer) => Some(*integer as f64),
+        Value::Bytes(bytes) => std::str::from_utf8(bytes).ok()?.parse().ok(),
+        _ => None,
+    }
+}
+
+fn timestamp(value: &Value) -> Option<DateTime<Utc>> {
+    match value {
+        Value::Timestamp(timestamp) => Some(*timestamp),
+        Value::Bytes(bytes) => std::str::from_utf8(bytes)
+            .ok()
+            .and_then(|text| DateTime::parse_from_rfc3339(text).ok())
+            .map(|timestamp| timestamp.with_timezone(&Utc)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn encode(value: Option<Value>, column_type: ColumnType) -> Option<Vec<u8>> {
+        let mut buffer = Vec::new();
+        encode_field(value.as_ref(), &column_type, &mut buffer).then(|| buffer)
+    }
+
+    /// The value prefixed by its length.
+    fn bound(contents: &[u8]) -> Vec<u8> {
+        let mut value = (contents.len() as i32).to_be_bytes().to_vec();
+        value.extend_from_slice(contents);
+        value
+    }
+
+    #[test]
+    fn inserts_into_quoted_tables() {
+        let encoder = RowEncoder::new(
+            vec![
+                CassandraColumn {
+                    name: "id".into(),
+                    field: Some("request.id".into()),
+                },
+                CassandraColumn {
+                    name: "Message".into(),
+                    field: None,
+                },
+            ],
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            encoder.insert_statement("logs", "app\"events"),
+            r#"INSERT INTO "logs"."app""events" ("id", "Message") VALUES (?, ?)"#
+        );
+
+        let encoder = RowEncoder::new(encoder.columns, Some(3600), None).unwrap();
+        assert_eq!(
+            encoder.insert_statement("logs", "events"),
+            r#"INSERT INTO "logs"."events" ("id", "Message") VALUES (?, ?) USING TTL ?"#
+        );
+    }
+
+    #[test]
+    fn rejects_duplicate_columns() {
+        let column = CassandraColumn {
+            name: "message".into(),
+            field: None,
+        };
+        assert!(matches!(
+            RowEncoder::new(vec![column.clone(), column], None, None),
+            Err(CassandraSinkError::DuplicateColumn { .. })
+        ));
+        assert!(matches!(
+            RowEncoder::new(Vec::new(), None, None),
+            Err(CassandraSinkError::NoColumns)
+        ));
+    }
+
+    #[test]
+    fn leaves_missing_fields_unset() {
+        assert_eq!(
+            encode(None, ColumnType::Text),
+            Some(UNSET.to_be_bytes().to_vec())
+        );
+        assert_eq!(
+            encode(Some(Value::Null), ColumnType::Int),
+            Some(NULL.to_be_bytes().to_vec())
+        );
+    }
+
+    #[test]
+    fn converts_values_to_the_types_of_the_columns() {
+        assert_eq!(
+            encode(Some(Value::from(42)), ColumnType::Text),
+            Some(bound(b"42"))
+        );
+        assert_eq!(
+            encode(Some(Value::from("42")), ColumnType::Int),
+            Some(bound(&42i32.to_be_bytes()))
+        );
+        assert_eq!(
+            encode(Some(Value::from(300)), ColumnType::SmallInt),
+            Some(bound(&300i16.to_be_bytes()))
+        );
+        assert_eq!(encode(Some(Value::from(300)), ColumnType::TinyInt), None);
+        assert_eq!(
+            encode(Some(Value::from(3)), ColumnType::Double),
+            Some(bound(&3f64.to_be_bytes()))
+        );
+        assert_eq!(
+            encode(Some(Value::from(true)), ColumnType::Boolean),
+            Some(bound(&[1]))
+        );
+        assert_eq!(encode(Some(Value::from("yes")), ColumnType::Boolean), None);
+
+        let timestamp = Utc.ymd(2022, 6, 1).and_hms_milli(12, 0, 0, 250);
+        assert_eq!(
+            encode(Some(Value::from(timestamp)), ColumnType::Timestamp),
+            Some(bound(&timestamp.timestamp_millis().to_be_bytes()))
+        );
+        assert_eq!(
+            encode(
+                Some(Value::from("2022-06-01T12:00:00.250Z")),
+                ColumnType::Timestamp
+            ),
+            Some(bound(&timestamp.timestamp_millis().to_be_bytes()))
+        );
+        // 19144 days after the Unix epoch.
+        let date = ((1u32 << 31) + 19144).to_be_bytes();
+        assert_eq!(
+            encode(Some(Value::from(timestamp)), ColumnType::Date),
+            Some(bound(&date))
+        );
+        assert_eq!(
+            encode(Some(Value::from("2022-06-01")), ColumnType::Date),
+            Some(bound(&date))
+        );
+
+        let uuid = Uuid::new_v4();
+        assert_eq!(
+            encode(Some(Value::from(uuid.to_string())), ColumnType::Uuid),
+            Some(bound(uuid.as_bytes()))
+        );
+        assert_eq!(
+            encode(Some(Value::from("10.0.0.1")), ColumnType::Inet),
+            Some(bound(&[10, 0, 0, 1]))
+        );
+        assert_eq!(encode(Some(Value::from("host")), ColumnType::Inet), None);
+    }
+
+    #[test]
+    fn converts_collections() {
+        let mut list = 2i32.to_be_bytes().to_vec();
+        list.extend(bound(&1i32.to_be_bytes()));
+        list.extend(bound(&2i32.to_be_bytes()));
+        assert_eq!(
+            encode(
+                Some(Value::from(vec![1, 2])),
+                ColumnType::Set(Box::new(ColumnType::Int))
+            ),
+            Some(bound(&list))
+        );
+
+        let mut log = LogEvent::default();
+        log.insert("labels.region", "eu");
+        let mut map = 1i32.to_be_bytes().to_vec();
+        map.extend(bound(b"region"));
+        map.extend(bound(b"eu"));
+        assert_eq!(
+            encode(
+                log.get("labels").cloned(),
+                ColumnType::Map(Box::new(ColumnType::Text), Box::new(ColumnType::Text))
+            ),
+            Some(bound(&map))
+        );
+
+        assert_eq!(
+            encode(
+                Some(Value::from(vec!["a"])),
+                ColumnType::List(Box::new(ColumnType::Int))
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn supports_the_types_values_can_be_converted_to() {
+        assert!(is_supported(&ColumnType::Map(
+            Box::new(ColumnType::Text),
+            Box::new(ColumnType::List(Box::new(ColumnType::Timestamp)))
+        )));
+        assert!(!is_supported(&ColumnType::Counter));
+        assert!(!is_supported(&ColumnType::List(Box::new(
+            ColumnType::Decimal
+        ))));
+    }
+}
@@ -0,0 +1,202 @@
+use std::{
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use scylla::{
+    batch::{Batch, BatchType},
+    frame::value::{SerializedValues, Value, ValueTooBig},
+    statement::Consistency,
+    transport::errors::{DbError, QueryError},
+    Session, SessionBuilder,
+};
+use snafu::ResultExt;
+use tokio::sync::OnceCell;
+use vector_core::{buffers::Ackable, internal_event::EventsSent, stream::DriverResponse};
+
+use super::{
+    row::{EncodedRow, TableStatement},
+    CassandraSinkError, ConnectSnafu, SerializeSnafu, WriteSnafu,
+};
+use crate::{
+    event::{EventFinalizers, EventStatus, Finalizable},
+    internal_events::EndpointBytesSent,
+    sinks::util::retries::RetryLogic,
+};
+
+/// The rows of a partition of a table, written with a single batch.
+#[derive(Clone)]
+pub struct CassandraRequest {
+    pub statement: Arc<TableStatement>,
+    pub rows: Vec<EncodedRow>,
+    pub finalizers: EventFinalizers,
+    pub events_byte_size: usize,
+}
+
+impl Ackable for CassandraRequest {
+    fn ack_size(&self) -> usize {
+        self.rows.len()
+    }
+}
+
+impl Finalizable for CassandraRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        self.finalizers.take_finalizers()
+    }
+}
+
+pub struct CassandraResponse {
+    events_count: usize,
+    events_byte_size: usize,
+}
+
+impl DriverResponse for CassandraResponse {
+    fn event_status(&self) -> EventStatus {
+        EventStatus::Delivered
+    }
+
+    fn events_sent(&self) -> EventsSent {
+        EventsSent {
+            count: self.events_count,
+            byte_size: self.events_byte_size,
+            output: None,
+        }
+    }
+}
+
+/// Connects to the cluster on first use, sharing the session, which keeps connections to all its
+/// nodes, from then on.
+#[derive(Clone)]
+pub struct CassandraConnector {
+    builder: Arc<SessionBuilder>,
+    session: Arc<OnceCell<Session>>,
+    /// The contact points of the cluster.
+    pub endpoint: String,
+}
+
+impl CassandraConnector {
+    pub fn new(builder: SessionBuilder, endpoint: String) -> Self {
+        Self {
+            builder: Arc::new(builder),
+            session: Arc::default(),
+            endpoint,
+        }
+    }
+
+    pub async fn session(&self) -> Result<&Session, CassandraSinkError> {
+        self.session
+            .get_or_try_init(|| self.builder.build())
+            .await
+            .context(ConnectSnafu)
+    }
+}
+
+/// A value already serialized along with its length.
+struct BoundValue<'a>(&'a Bytes);
+
+impl<'a> Value for BoundValue<'a> {
+    fn serialize(&self, buf: &mut Vec<u8>) -> Result<(), ValueTooBig> {
+        buf.extend_from_slice(self.0);
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct CassandraService {
+    connector: CassandraConnector,
+    consistency: Consistency,
+}
+
+impl CassandraService {
+    pub const fn new(connector: CassandraConnector, consistency: Consistency) -> Self {
+        Self {
+            connector,
+            consistency,
+        }
+    }
+}
+
+impl tower::Service<CassandraRequest> for CassandraService {
+    type Response = CassandraResponse;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: CassandraRequest) -> Self::Future {
+        let service = self.clone();
+
+        Box::pin(async move {
+            // The rows all belong to the same partition, so the batch is written by its replicas
+            // without being coordinated across nodes.
+            let mut batch = Batch::new(BatchType::Unlogged);
+            batch.set_consistency(service.consistency);
+            let mut values = Vec::with_capacity(request.rows.len());
+            let mut byte_size = 0;
+            for row in &request.rows {
+                batch.append_statement(request.statement.prepared.clone());
+                let mut serialized = SerializedValues::new();
+                for value in &row.values {
+                    serialized
+                        .add_value(&BoundValue(value))
+                        .context(SerializeSnafu)?;
+                    byte_size += value.len();
+                }
+                values.push(serialized);
+            }
+
+            let session = service.connector.session().await?;
+            session.batch(&batch, values).await.context(WriteSnafu)?;
+
+            emit!(EndpointBytesSent {
+                byte_size,
+                protocol: "cassandra",
+                endpoint: &service.connector.endpoint,
+            });
+            Ok(CassandraResponse {
+                events_count: request.rows.len(),
+                events_byte_size: request.events_byte_size,
+            })
+        })
+    }
+}
+
+/// Whether the query failed because of the state of the cluster rather than the query itself,
+/// such as when too few replicas are available or they time out.
+pub fn is_retriable(error: &QueryError) -> bool {
+    match error {
+        QueryError::DbError(error, _) => matches!(
+            error,
+            DbError::Unavailable { .. }
+                | DbError::Overloaded
+                | DbError::IsBootstrapping
+                | DbError::ReadTimeout { .. }
+                | DbError::WriteTimeout { .. }
+                | DbError::ServerError
+        ),
+        QueryError::IoError(_) | QueryError::TimeoutError => true,
+        _ => false,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CassandraRetryLogic;
+
+impl RetryLogic for CassandraRetryLogic {
+    type Error = CassandraSinkError;
+    type Response = CassandraResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        match error {
+            CassandraSinkError::Connect { .. } => true,
+            CassandraSinkError::Prepare { source } | CassandraSinkError::Write { source } => {
+                is_retriable(source)
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::{stream::BoxStream, StreamExt};
+use snafu::ResultExt;
+use tokio::time::sleep;
+use tower::Service;
+use vector_core::{
+    buffers::Acker,
+    partition::Partitioner,
+    stream::{BatcherSettings, DriverResponse},
+    ByteSizeOf,
+};
+
+use super::{
+    row::{EncodedRow, RowEncoder, TableStatement},
+    service::{CassandraConnector, CassandraRequest, CassandraRetryLogic},
+    CassandraSinkError, PrepareSnafu,
+};
+use crate::{
+    event::{Event, EventFinalizers, Finalizable},
+    internal_events::{CassandraEncodingError, CassandraPrepareError, TemplateRenderingError},
+    sinks::util::{
+        retries::{ExponentialBackoff, RetryLogic},
+        SinkBuilderExt, StreamSink,
+    },
+    template::Template,
+};
+
+/// Prepares the statements inserting into the tables the events are routed to, once per table.
+pub struct TableStatements {
+    connector: CassandraConnector,
+    keyspace: String,
+    encoder: RowEncoder,
+    prepared: Mutex<HashMap<String, Arc<TableStatement>>>,
+}
+
+impl TableStatements {
+    pub fn new(connector: CassandraConnector, keyspace: String, encoder: RowEncoder) -> Self {
+        Self {
+            connector,
+            keyspace,
+            encoder,
+            prepared: Mutex::default(),
+        }
+    }
+
+    pub async fn prepare(&self, table: &str) -> Result<Arc<TableStatement>, CassandraSinkError> {
+        if let Some(statement) = self
+            .prepared
+            .lock()
+            .expect("prepared statements lock poisoned")
+            .get(table)
+        {
+            return Ok(Arc::clone(statement));
+        }
+
+        let session = self.connector.session().await?;
+        let prepared = session
+            .prepare(self.encoder.insert_statement(&self.keyspace, table))
+            .await
+            .context(PrepareSnafu)?;
+        let statement = Arc::new(self.encoder.table_statement(table.to_owned(), prepared)?);
+        self.prepared
+            .lock()
+            .expect("prepared statements lock poisoned")
+            .insert(table.to_owned(), Arc::clone(&statement));
+        Ok(statement)
+    }
+
+    /// Prepares the statement, waiting for the cluster while it can't prepare it, so that events
+    /// are only dropped when the table itself is invalid.
+    async fn prepare_backoff(
+        &self,
+        table: &str,
+    ) -> Result<Arc<TableStatement>, CassandraSinkError> {
+        let mut backoff = ExponentialBackoff::from_millis(2)
+            .factor(250)
+            .max_delay(Duration::from_secs(60));
+        loop {
+            match self.prepare(table).await {
+                Err(error) if CassandraRetryLogic.is_retriable_error(&error) => {
+                    emit!(CassandraPrepareError { error, table });
+                    sleep(backoff.next().unwrap()).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Encodes the event as a row of the table it's routed to, dropping it when it can't be.
+    async fn encode(&self, table: &Template, mut event: Event) -> Option<CassandraRow> {
+        let table = table
+            .render_string(&event)
+            .map_err(|error| {
+                emit!(TemplateRenderingError {
+                    error,
+                    field: Some("table"),
+                    drop_event: true,
+                });
+            })
+            .ok()?;
+        let result = match self.prepare_backoff(&table).await {
+            Ok(statement) => self
+                .encoder
+                .encode(&statement, event.as_log())
+                .map(|row| (statement, row)),
+            Err(error) => Err(error),
+        };
+        match result {
+            Ok((statement, row)) => Some(CassandraRow {
+                statement,
+                row,
+                events_byte_size: event.size_of(),
+                finalizers: event.take_finalizers(),
+            }),
+            Err(error) => {
+                emit!(CassandraEncodingError { error });
+                None
+            }
+        }
+    }
+}
+
+/// An event encoded as a row of the table it's routed to.
+struct CassandraRow {
+    statement: Arc<TableStatement>,
+    row: EncodedRow,
+    events_byte_size: usize,
+    finalizers: EventFinalizers,
+}
+
+impl ByteSizeOf for CassandraRow {
+    fn allocated_bytes(&self) -> usize {
+        self.row.values.iter().map(Bytes::len).sum()
+    }
+}
+
+impl Finalizable for CassandraRow {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        self.finalizers.take_finalizers()
+    }
+}
+
+/// Partitions the rows by the partition of the table they belong to, so that batches are only
+/// written by the replicas of their partition.
+struct TablePartitioner;
+
+impl Partitioner for TablePartitioner {
+    type Item = CassandraRow;
+    type Key = (String, Bytes);
+
+    fn partition(&self, item: &Self::Item) -> Self::Key {
+        (item.statement.table.clone(), item.row.partition_key.clone())
+    }
+}
+
+pub struct CassandraSink<S> {
+    pub service: S,
+    pub batch_settings: BatcherSettings,
+    pub acker: Acker,
+    pub table: Template,
+    pub statements: Arc<TableStatements>,
+}
+
+impl<S> CassandraSink<S>
+where
+    S: Service<CassandraRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: fmt::Debug + Into<crate::Error> + Send,
+{
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let table = self.table;
+        let statements = self.statements;
+
+        input
+            .filter_map(|event| {
+                let statements = Arc::clone(&statements);
+                let table = table.clone();
+                async move { statements.encode(&table, event).await }
+            })
+            .batched_partitioned(TablePartitioner, self.batch_settings)
+            .map(|(_, mut rows)| {
+                let finalizers = rows.take_finalizers();
+                let events_byte_size = rows.iter().map(|row| row.events_byte_size).sum();
+                let statement = Arc::clone(&rows[0].statement);
+                CassandraRequest {
+                    statement,
+                    rows: rows.into_iter().map(|row| row.row).collect(),
+                    finalizers,
+                    events_byte_size,
+                }
+            })
+            .into_driver(self.service, self.acker)
+            .run()
+            .await
+    }
+}
+
+#[async_trait]
+impl<S> StreamSink<Event> for CassandraSink<S>
+where
+    S: Service<CassandraRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: fmt::Debug + Into<crate::Error> + Send,
+{
+    async fn run(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
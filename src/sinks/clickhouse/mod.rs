@@ -3,12 +3,15 @@ use futures::{FutureExt, SinkExt};
 use http::{Request, StatusCode, Uri};
 use hyper::Body;
 use serde::{Deserialize, Serialize};
-use snafu::ResultExt;
+use snafu::{ResultExt, Snafu};
 
+use self::row_binary::Column;
 use crate::{
     config::{AcknowledgementsConfig, Input, SinkConfig, SinkContext, SinkDescription},
-    event::Event,
+    dropped_events,
+    event::{Event, EventStatus},
     http::{Auth, HttpClient, HttpError, MaybeAuth},
+    internal_events::ClickhouseEncodingError,
     sinks::util::{
         encoding::{EncodingConfigWithDefault, EncodingConfiguration},
         http::{BatchedHttpSink, HttpEventEncoder, HttpRetryLogic, HttpSink},
@@ -19,6 +22,8 @@ use crate::{
     tls::{TlsConfig, TlsSettings},
 };
 
+mod row_binary;
+
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct ClickhouseConfig {
@@ -28,6 +33,11 @@ pub struct ClickhouseConfig {
     pub table: String,
     pub database: Option<String>,
     #[serde(default)]
+    pub format: InsertFormat,
+    /// The columns of the table, in order, for the `row_binary` format. They're read from the
+    /// table when the sink is built if unset.
+    pub columns: Option<Vec<Column>>,
+    #[serde(default)]
     pub skip_unknown_fields: bool,
     #[serde(default = "Compression::gzip_default")]
     pub compression: Compression,
@@ -64,6 +74,55 @@ pub enum Encoding {
     Default,
 }
 
+/// The format the events are inserted in.
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Copy, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum InsertFormat {
+    /// Events are inserted as JSON objects, whose fields ClickHouse matches with the columns.
+    #[derivative(Default)]
+    JsonEachRow,
+    /// Events are encoded as rows of the table's columns, converting their fields to the types of
+    /// the columns.
+    RowBinary,
+}
+
+impl InsertFormat {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::JsonEachRow => "JSONEachRow",
+            Self::RowBinary => "RowBinary",
+        }
+    }
+
+    const fn content_type(self) -> &'static str {
+        match self {
+            Self::JsonEachRow => "application/x-ndjson",
+            Self::RowBinary => "application/octet-stream",
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum DescribeTableError {
+    #[snafu(display(
+        "Failed to read the columns of the table, which can be set with `columns` instead: {}",
+        source
+    ))]
+    Request { source: HttpError },
+    #[snafu(display(
+        "Unexpected status reading the columns of the table, which can be set with `columns` instead: {}",
+        status
+    ))]
+    UnexpectedStatus { status: StatusCode },
+    #[snafu(display("Invalid column of the table: {}", source))]
+    InvalidColumn { source: serde_json::Error },
+    #[snafu(display("Column `{}` of the table: {}", name, error))]
+    UnsupportedColumn { name: String, error: String },
+    #[snafu(display("The table has no columns events can be inserted into."))]
+    NoColumns,
+}
+
 #[async_trait::async_trait]
 #[typetag::serde(name = "clickhouse")]
 impl SinkConfig for ClickhouseConfig {
@@ -76,10 +135,13 @@ impl SinkConfig for ClickhouseConfig {
         let tls_settings = TlsSettings::from_options(&self.tls)?;
         let client = HttpClient::new(tls_settings, &cx.proxy)?;
 
-        let config = ClickhouseConfig {
+        let mut config = ClickhouseConfig {
             auth: self.auth.choose_one(&self.endpoint.auth)?,
             ..self.clone()
         };
+        if config.format == InsertFormat::RowBinary && config.columns.is_none() {
+            config.columns = Some(describe_table(&client, &config).await?);
+        }
 
         let sink = BatchedHttpSink::with_logic(
             config.clone(),
@@ -112,6 +174,8 @@ impl SinkConfig for ClickhouseConfig {
 
 pub struct ClickhouseEventEncoder {
     encoding: EncodingConfigWithDefault<Encoding>,
+    /// The columns the events are encoded as rows of, for the `row_binary` format.
+    columns: Option<Vec<Column>>,
 }
 
 impl HttpEventEncoder<BytesMut> for ClickhouseEventEncoder {
@@ -119,10 +183,27 @@ impl HttpEventEncoder<BytesMut> for ClickhouseEventEncoder {
         self.encoding.apply_rules(&mut event);
         let log = event.into_log();
 
-        let mut body = crate::serde::json::to_bytes(&log).expect("Events should be valid json!");
-        body.put_u8(b'\n');
+        match &self.columns {
+            Some(columns) => {
+                let mut row = BytesMut::new();
+                match row_binary::encode_row(&log, columns, &mut row) {
+                    Ok(()) => Some(row),
+                    Err(error) => {
+                        log.metadata().update_status(EventStatus::Rejected);
+                        dropped_events::sample(&log.into(), &error);
+                        emit!(ClickhouseEncodingError { error });
+                        None
+                    }
+                }
+            }
+            None => {
+                let mut body =
+                    crate::serde::json::to_bytes(&log).expect("Events should be valid json!");
+                body.put_u8(b'\n');
 
-        Some(body)
+                Some(body)
+            }
+        }
     }
 }
 
@@ -135,6 +216,10 @@ impl HttpSink for ClickhouseConfig {
     fn build_encoder(&self) -> Self::Encoder {
         ClickhouseEventEncoder {
             encoding: self.encoding.clone(),
+            columns: match self.format {
+                InsertFormat::JsonEachRow => None,
+                InsertFormat::RowBinary => self.columns.clone(),
+            },
         }
     }
 
@@ -145,15 +230,20 @@ impl HttpSink for ClickhouseConfig {
             "default"
         };
 
+        let columns = match self.format {
+            InsertFormat::JsonEachRow => None,
+            InsertFormat::RowBinary => self.columns.as_deref(),
+        };
         let uri = set_uri_query(
             &self.endpoint.uri,
             database,
             &self.table,
             self.skip_unknown_fields,
+            columns,
         )
         .expect("Unable to encode uri");
 
-        let mut builder = Request::post(&uri).header("Content-Type", "application/x-ndjson");
+        let mut builder = Request::post(&uri).header("Content-Type", self.format.content_type());
 
         if let Some(ce) = self.compression.content_encoding() {
             builder = builder.header("Content-Encoding", ce);
@@ -186,14 +276,102 @@ async fn healthcheck(client: HttpClient, config: ClickhouseConfig) -> crate::Res
     }
 }
 
-fn set_uri_query(uri: &Uri, database: &str, table: &str, skip_unknown: bool) -> crate::Result<Uri> {
+/// Reads the columns events can be inserted into from the table, which excludes the columns whose
+/// values are computed by ClickHouse.
+async fn describe_table(
+    client: &HttpClient,
+    config: &ClickhouseConfig,
+) -> Result<Vec<Column>, DescribeTableError> {
+    #[derive(Deserialize)]
+    struct DescribedColumn {
+        name: String,
+        #[serde(rename = "type")]
+        column_type: String,
+        default_type: String,
+    }
+
+    let query = url::form_urlencoded::Serializer::new(String::new())
+        .append_pair(
+            "query",
+            &describe_table_query(
+                config.database.as_deref().unwrap_or("default"),
+                &config.table,
+            ),
+        )
+        .finish();
+    let uri = format!("{}/?{}", config.endpoint, query);
+    let mut request = Request::get(uri).body(Body::empty()).unwrap();
+
+    if let Some(auth) = &config.auth {
+        auth.apply(&mut request);
+    }
+
+    let response = client.send(request).await.context(RequestSnafu)?;
+    let status = response.status();
+    if status != StatusCode::OK {
+        return Err(DescribeTableError::UnexpectedStatus { status });
+    }
+    let body = hyper::body::to_bytes(response.into_body())
+        .await
+        .map_err(|source| DescribeTableError::Request {
+            source: HttpError::CallRequest { source },
+        })?;
+
+    let mut columns = Vec::new();
+    for line in body
+        .split(|byte| *byte == b'\n')
+        .filter(|line| !line.is_empty())
+    {
+        let described: DescribedColumn =
+            serde_json::from_slice(line).context(InvalidColumnSnafu)?;
+        if matches!(described.default_type.as_str(), "MATERIALIZED" | "ALIAS") {
+            continue;
+        }
+        let column_type = described.column_type.parse().map_err(|error| {
+            DescribeTableError::UnsupportedColumn {
+                name: described.name.clone(),
+                error,
+            }
+        })?;
+        columns.push(Column {
+            name: described.name,
+            column_type,
+        });
+    }
+
+    if columns.is_empty() {
+        Err(DescribeTableError::NoColumns)
+    } else {
+        Ok(columns)
+    }
+}
+
+fn set_uri_query(
+    uri: &Uri,
+    database: &str,
+    table: &str,
+    skip_unknown: bool,
+    columns: Option<&[Column]>,
+) -> crate::Result<Uri> {
+    let (columns, format) = match columns {
+        Some(columns) => {
+            let names = columns
+                .iter()
+                .map(|column| quote_identifier(&column.name))
+                .collect::<Vec<_>>();
+            (format!(" ({})", names.join(", ")), InsertFormat::RowBinary)
+        }
+        None => (String::new(), InsertFormat::JsonEachRow),
+    };
     let query = url::form_urlencoded::Serializer::new(String::new())
         .append_pair(
             "query",
             format!(
-                "INSERT INTO \"{}\".\"{}\" FORMAT JSONEachRow",
+                "INSERT INTO \"{}\".{}{} FORMAT {}",
                 database,
-                table.replace('\"', "\\\"")
+                quote_identifier(table),
+                columns,
+                format.name()
             )
             .as_str(),
         )
@@ -214,6 +392,23 @@ fn set_uri_query(uri: &Uri, database: &str, table: &str, skip_unknown: bool) ->
         .map_err(Into::into)
 }
 
+fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('\"', "\\\""))
+}
+
+/// Quotes the identifier with backticks, escaping the backslashes and backticks it contains.
+fn backquote_identifier(identifier: &str) -> String {
+    format!("`{}`", identifier.replace('\\', "\\\\").replace('`', "\\`"))
+}
+
+fn describe_table_query(database: &str, table: &str) -> String {
+    format!(
+        "DESCRIBE TABLE {}.{} FORMAT JSONEachRow",
+        backquote_identifier(database),
+        backquote_identifier(table)
+    )
+}
+
 #[derive(Debug, Default, Clone)]
 struct ClickhouseRetryLogic {
     inner: HttpRetryLogic,
@@ -269,6 +464,7 @@ mod tests {
             "my_database",
             "my_table",
             false,
+            None,
         )
         .unwrap();
         assert_eq!(uri.to_string(), "http://localhost:80/?input_format_import_nested_json=1&query=INSERT+INTO+%22my_database%22.%22my_table%22+FORMAT+JSONEachRow");
@@ -278,11 +474,47 @@ mod tests {
             "my_database",
             "my_\"table\"",
             false,
+            None,
         )
         .unwrap();
         assert_eq!(uri.to_string(), "http://localhost:80/?input_format_import_nested_json=1&query=INSERT+INTO+%22my_database%22.%22my_%5C%22table%5C%22%22+FORMAT+JSONEachRow");
     }
 
+    #[test]
+    fn encode_valid_row_binary() {
+        let columns = [
+            Column {
+                name: "host".into(),
+                column_type: row_binary::ColumnType::String,
+            },
+            Column {
+                name: "timestamp".into(),
+                column_type: row_binary::ColumnType::DateTime64(3),
+            },
+        ];
+        let uri = set_uri_query(
+            &"http://localhost:80".parse().unwrap(),
+            "my_database",
+            "my_table",
+            false,
+            Some(&columns),
+        )
+        .unwrap();
+        assert_eq!(uri.to_string(), "http://localhost:80/?input_format_import_nested_json=1&query=INSERT+INTO+%22my_database%22.%22my_table%22+%28%22host%22%2C+%22timestamp%22%29+FORMAT+RowBinary");
+    }
+
+    #[test]
+    fn describe_table_quotes_identifiers() {
+        assert_eq!(
+            describe_table_query("my_database", "my_table"),
+            "DESCRIBE TABLE `my_database`.`my_table` FORMAT JSONEachRow"
+        );
+        assert_eq!(
+            describe_table_query("my`db\\", "my_\"table\""),
+            "DESCRIBE TABLE `my\\`db\\\\`.`my_\"table\"` FORMAT JSONEachRow"
+        );
+    }
+
     #[test]
     fn encode_invalid() {
         set_uri_query(
@@ -290,6 +522,7 @@ mod tests {
             "my_database",
             "my_table",
             false,
+            None,
         )
         .unwrap_err();
     }
@@ -383,6 +616,78 @@ mod integration_tests {
         assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
     }
 
+    #[tokio::test]
+    async fn insert_events_row_binary() {
+        trace_init();
+
+        let table = gen_table();
+        let host = clickhouse_address();
+
+        let mut batch = BatchConfig::default();
+        batch.max_events = Some(1);
+
+        let config = ClickhouseConfig {
+            endpoint: host.parse().unwrap(),
+            table: table.clone(),
+            format: InsertFormat::RowBinary,
+            compression: Compression::None,
+            batch,
+            request: TowerRequestConfig {
+                retry_attempts: Some(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let client = ClickhouseClient::new(host);
+        client
+            .create_table(
+                &table,
+                "host String, timestamp DateTime64(3, 'UTC'), message String, \
+                 status Nullable(UInt16), items Array(LowCardinality(String)), \
+                 day Date MATERIALIZED toDate(timestamp)",
+            )
+            .await;
+
+        // The columns are read from the table.
+        let (sink, _hc) = config.build(SinkContext::new_test()).await.unwrap();
+
+        let (mut input_event, mut receiver) = make_event();
+        input_event
+            .as_mut_log()
+            .insert("items", vec!["item1", "item2"]);
+        input_event.as_mut_log().insert("status", "200");
+
+        run_and_assert_sink_compliance(
+            sink,
+            stream::once(ready(input_event.clone())),
+            &HTTP_SINK_TAGS,
+        )
+        .await;
+
+        let output = client.select_all(&table).await;
+        assert_eq!(1, output.rows);
+
+        let timestamp = input_event
+            .as_log()
+            .get(log_schema().timestamp_key())
+            .unwrap()
+            .as_timestamp()
+            .unwrap()
+            .format("%Y-%m-%d %H:%M:%S%.3f")
+            .to_string();
+        let expected = serde_json::json!({
+            "host": "example.com",
+            "timestamp": timestamp,
+            "message": "raw log line",
+            "status": 200,
+            "items": ["item1", "item2"],
+        });
+        assert_eq!(expected, output.data[0]);
+
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+    }
+
     #[tokio::test]
     async fn skip_unknown_fields() {
         trace_init();
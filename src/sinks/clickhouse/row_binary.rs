@@ -0,0 +1,557 @@
+//! Encoding of events as rows of the ClickHouse `RowBinary` format.
+//!
+//! Each event is encoded as a row of the table's columns, in order, each value being converted to
+//! the type of its column. This spares ClickHouse from parsing JSON and guessing the types of the
+//! values, which is most of the CPU time of inserting `JSONEachRow`.
+//!
+//! Reference: https://clickhouse.com/docs/en/interfaces/formats/#rowbinary
+
+use std::{convert::TryFrom, fmt, str::FromStr};
+
+use bytes::{BufMut, BytesMut};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use vector_core::event::{LogEvent, Value};
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// A column of the table the events are inserted into.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Column {
+    /// The name of the column, which is also the path of the field of the events it's read from.
+    pub name: String,
+    /// The ClickHouse type of the column.
+    #[serde(rename = "type")]
+    pub column_type: ColumnType,
+}
+
+/// The ClickHouse types values can be encoded as.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum ColumnType {
+    String,
+    FixedString(usize),
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+    Float32,
+    Float64,
+    Bool,
+    Date,
+    DateTime,
+    DateTime64(u32),
+    Nullable(Box<ColumnType>),
+    LowCardinality(Box<ColumnType>),
+    Array(Box<ColumnType>),
+    Map(Box<ColumnType>, Box<ColumnType>),
+}
+
+#[derive(Debug, Snafu)]
+pub enum EncodeError {
+    #[snafu(display("Column `{}`: can't convert {} to {}.", column, kind, column_type))]
+    InvalidType {
+        column: String,
+        kind: String,
+        column_type: ColumnType,
+    },
+    #[snafu(display(
+        "Column `{}`: {} is out of the range of {}.",
+        column,
+        value,
+        column_type
+    ))]
+    OutOfRange {
+        column: String,
+        value: String,
+        column_type: ColumnType,
+    },
+}
+
+/// Encodes the event as a row of the columns, appending it to `buffer`.
+///
+/// Missing fields are written as the default value of the type of their column, which is null for
+/// `Nullable` columns.
+pub fn encode_row(
+    log: &LogEvent,
+    columns: &[Column],
+    buffer: &mut BytesMut,
+) -> Result<(), EncodeError> {
+    for column in columns {
+        let value = log.get(column.name.as_str()).unwrap_or(&Value::Null);
+        encode_value(value, &column.column_type, buffer)
+            .map_err(|error| error.for_column(column))?;
+    }
+    Ok(())
+}
+
+/// An error encoding a value, which is then attributed to its column.
+enum ValueError {
+    InvalidType(String),
+    OutOfRange(String),
+}
+
+impl ValueError {
+    fn for_column(self, column: &Column) -> EncodeError {
+        let name = column.name.clone();
+        let column_type = column.column_type.clone();
+        match self {
+            Self::InvalidType(kind) => EncodeError::InvalidType {
+                column: name,
+                kind,
+                column_type,
+            },
+            Self::OutOfRange(value) => EncodeError::OutOfRange {
+                column: name,
+                value,
+                column_type,
+            },
+        }
+    }
+}
+
+fn encode_value(
+    value: &Value,
+    column_type: &ColumnType,
+    buffer: &mut BytesMut,
+) -> Result<(), ValueError> {
+    match column_type {
+        ColumnType::Nullable(inner) => {
+            if let Value::Null = value {
+                buffer.put_u8(1);
+                Ok(())
+            } else {
+                buffer.put_u8(0);
+                encode_value(value, inner, buffer)
+            }
+        }
+        // `LowCardinality` columns are written as their inner type in `RowBinary`.
+        ColumnType::LowCardinality(inner) => encode_value(value, inner, buffer),
+        ColumnType::String => {
+            match value {
+                Value::Null => put_varint(0, buffer),
+                Value::Bytes(bytes) => {
+                    put_varint(bytes.len() as u64, buffer);
+                    buffer.put_slice(bytes);
+                }
+                value => {
+                    let string = value.to_string_lossy();
+                    put_varint(string.len() as u64, buffer);
+                    buffer.put_slice(string.as_bytes());
+                }
+            }
+            Ok(())
+        }
+        ColumnType::FixedString(size) => {
+            let bytes = match value {
+                Value::Null => Default::default(),
+                value => value.coerce_to_bytes(),
+            };
+            if bytes.len() > *size {
+                return Err(ValueError::OutOfRange(
+                    String::from_utf8_lossy(&bytes).into_owned(),
+                ));
+            }
+            buffer.put_slice(&bytes);
+            buffer.put_bytes(0, size - bytes.len());
+            Ok(())
+        }
+        ColumnType::UInt8 => put_integer(value, buffer, BytesMut::put_u8),
+        ColumnType::UInt16 => put_integer(value, buffer, BytesMut::put_u16_le),
+        ColumnType::UInt32 => put_integer(value, buffer, BytesMut::put_u32_le),
+        ColumnType::UInt64 => put_integer(value, buffer, BytesMut::put_u64_le),
+        ColumnType::Int8 => put_integer(value, buffer, BytesMut::put_i8),
+        ColumnType::Int16 => put_integer(value, buffer, BytesMut::put_i16_le),
+        ColumnType::Int32 => put_integer(value, buffer, BytesMut::put_i32_le),
+        ColumnType::Int64 => put_integer(value, buffer, BytesMut::put_i64_le),
+        ColumnType::Float32 => {
+            buffer.put_f32_le(to_float(value)? as f32);
+            Ok(())
+        }
+        ColumnType::Float64 => {
+            buffer.put_f64_le(to_float(value)?);
+            Ok(())
+        }
+        ColumnType::Bool => {
+            let boolean = match value {
+                Value::Null => false,
+                Value::Boolean(boolean) => *boolean,
+                Value::Integer(integer) => *integer != 0,
+                Value::Bytes(bytes) => match bytes.as_ref() {
+                    b"true" => true,
+                    b"false" => false,
+                    _ => return Err(invalid_type(value)),
+                },
+                value => return Err(invalid_type(value)),
+            };
+            buffer.put_u8(boolean as u8);
+            Ok(())
+        }
+        ColumnType::Date => {
+            let days = to_timestamp(value)?.map_or(0, |timestamp| {
+                timestamp.timestamp().div_euclid(SECONDS_PER_DAY)
+            });
+            let days = u16::try_from(days).map_err(|_| out_of_range(value))?;
+            buffer.put_u16_le(days);
+            Ok(())
+        }
+        ColumnType::DateTime => {
+            let seconds = to_timestamp(value)?.map_or(0, |timestamp| timestamp.timestamp());
+            let seconds = u32::try_from(seconds).map_err(|_| out_of_range(value))?;
+            buffer.put_u32_le(seconds);
+            Ok(())
+        }
+        ColumnType::DateTime64(precision) => {
+            let ticks = match to_timestamp(value)? {
+                Some(timestamp) => timestamp
+                    .timestamp()
+                    .checked_mul(10i64.pow(*precision))
+                    .and_then(|ticks| {
+                        let subsec = timestamp.timestamp_subsec_nanos() / 10u32.pow(9 - precision);
+                        ticks.checked_add(subsec.into())
+                    })
+                    .ok_or_else(|| out_of_range(value))?,
+                None => 0,
+            };
+            buffer.put_i64_le(ticks);
+            Ok(())
+        }
+        ColumnType::Array(inner) => match value {
+            Value::Null => {
+                put_varint(0, buffer);
+                Ok(())
+            }
+            Value::Array(values) => {
+                put_varint(values.len() as u64, buffer);
+                values
+                    .iter()
+                    .try_for_each(|value| encode_value(value, inner, buffer))
+            }
+            value => Err(invalid_type(value)),
+        },
+        ColumnType::Map(key_type, value_type) => match value {
+            Value::Null => {
+                put_varint(0, buffer);
+                Ok(())
+            }
+            Value::Object(map) => {
+                put_varint(map.len() as u64, buffer);
+                map.iter().try_for_each(|(key, value)| {
+                    encode_value(&Value::from(key.as_str()), key_type, buffer)?;
+                    encode_value(value, value_type, buffer)
+                })
+            }
+            value => Err(invalid_type(value)),
+        },
+    }
+}
+
+fn put_integer<T>(
+    value: &Value,
+    buffer: &mut BytesMut,
+    put: fn(&mut BytesMut, T),
+) -> Result<(), ValueError>
+where
+    T: TryFrom<i64> + Default,
+{
+    let integer = match value {
+        Value::Null => {
+            put(buffer, T::default());
+            return Ok(());
+        }
+        Value::Integer(integer) => *integer,
+        Value::Boolean(boolean) => *boolean as i64,
+        Value::Float(float) if float.fract() == 0.0 => float.into_inner() as i64,
+        Value::Bytes(bytes) => std::str::from_utf8(bytes)
+            .ok()
+            .and_then(|string| string.parse().ok())
+            .ok_or_else(|| invalid_type(value))?,
+        value => return Err(invalid_type(value)),
+    };
+    let integer = T::try_from(integer).map_err(|_| out_of_range(value))?;
+    put(buffer, integer);
+    Ok(())
+}
+
+fn to_float(value: &Value) -> Result<f64, ValueError> {
+    match value {
+        Value::Null => Ok(0.0),
+        Value::Float(float) => Ok(float.into_inner()),
+        Value::Integer(integer) => Ok(*integer as f64),
+        Value::Bytes(bytes) => std::str::from_utf8(bytes)
+            .ok()
+            .and_then(|string| string.parse().ok())
+            .ok_or_else(|| invalid_type(value)),
+        value => Err(invalid_type(value)),
+    }
+}
+
+/// Converts timestamps, RFC 3339 strings and Unix timestamps in seconds to timestamps.
+fn to_timestamp(value: &Value) -> Result<Option<DateTime<Utc>>, ValueError> {
+    match value {
+        Value::Null => Ok(None),
+        Value::Timestamp(timestamp) => Ok(Some(*timestamp)),
+        Value::Integer(seconds) => Ok(Some(DateTime::from_utc(
+            NaiveDateTime::from_timestamp_opt(*seconds, 0).ok_or_else(|| out_of_range(value))?,
+            Utc,
+        ))),
+        Value::Bytes(bytes) => std::str::from_utf8(bytes)
+            .ok()
+            .and_then(|string| DateTime::parse_from_rfc3339(string).ok())
+            .map(|timestamp| Some(timestamp.with_timezone(&Utc)))
+            .ok_or_else(|| invalid_type(value)),
+        value => Err(invalid_type(value)),
+    }
+}
+
+fn invalid_type(value: &Value) -> ValueError {
+    ValueError::InvalidType(value.kind_str().to_owned())
+}
+
+fn out_of_range(value: &Value) -> ValueError {
+    ValueError::OutOfRange(value.to_string_lossy())
+}
+
+/// Writes an unsigned LEB128 integer, which prefixes the lengths of strings, arrays and maps.
+fn put_varint(mut value: u64, buffer: &mut BytesMut) {
+    while value >= 0x80 {
+        buffer.put_u8((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buffer.put_u8(value as u8);
+}
+
+impl FromStr for ColumnType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (name, args) = match s.find('(') {
+            Some(index) if s.ends_with(')') => {
+                (&s[..index], split_args(&s[index + 1..s.len() - 1]))
+            }
+            _ => (s, Vec::new()),
+        };
+        let unsupported = || format!("Unsupported ClickHouse type `{}`.", s);
+        let inner = |index: usize| -> Result<Box<ColumnType>, String> {
+            args.get(index)
+                .ok_or_else(unsupported)
+                .and_then(|arg| arg.parse().map(Box::new))
+        };
+
+        Ok(match (name, args.len()) {
+            ("String", 0) => Self::String,
+            ("FixedString", 1) => Self::FixedString(args[0].parse().map_err(|_| unsupported())?),
+            ("UInt8", 0) => Self::UInt8,
+            ("UInt16", 0) => Self::UInt16,
+            ("UInt32", 0) => Self::UInt32,
+            ("UInt64", 0) => Self::UInt64,
+            ("Int8", 0) => Self::Int8,
+            ("Int16", 0) => Self::Int16,
+            ("Int32", 0) => Self::Int32,
+            ("Int64", 0) => Self::Int64,
+            ("Float32", 0) => Self::Float32,
+            ("Float64", 0) => Self::Float64,
+            ("Bool", 0) => Self::Bool,
+            ("Date", 0) => Self::Date,
+            // The time zone only affects how the values are displayed.
+            ("DateTime", 0 | 1) => Self::DateTime,
+            ("DateTime64", 1 | 2) => match args[0].parse() {
+                Ok(precision) if precision <= 9 => Self::DateTime64(precision),
+                _ => return Err(unsupported()),
+            },
+            ("Nullable", 1) => Self::Nullable(inner(0)?),
+            ("LowCardinality", 1) => Self::LowCardinality(inner(0)?),
+            ("Array", 1) => Self::Array(inner(0)?),
+            ("Map", 2) => Self::Map(inner(0)?, inner(1)?),
+            _ => return Err(unsupported()),
+        })
+    }
+}
+
+/// Splits the arguments of a type on the commas that aren't nested in other types.
+fn split_args(args: &str) -> Vec<&str> {
+    let mut depth = 0;
+    let mut start = 0;
+    let mut split = Vec::new();
+    for (index, c) in args.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                split.push(args[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    split.push(args[start..].trim());
+    split
+}
+
+impl TryFrom<String> for ColumnType {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<ColumnType> for String {
+    fn from(column_type: ColumnType) -> Self {
+        column_type.to_string()
+    }
+}
+
+impl fmt::Display for ColumnType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::String => f.write_str("String"),
+            Self::FixedString(size) => write!(f, "FixedString({})", size),
+            Self::UInt8 => f.write_str("UInt8"),
+            Self::UInt16 => f.write_str("UInt16"),
+            Self::UInt32 => f.write_str("UInt32"),
+            Self::UInt64 => f.write_str("UInt64"),
+            Self::Int8 => f.write_str("Int8"),
+            Self::Int16 => f.write_str("Int16"),
+            Self::Int32 => f.write_str("Int32"),
+            Self::Int64 => f.write_str("Int64"),
+            Self::Float32 => f.write_str("Float32"),
+            Self::Float64 => f.write_str("Float64"),
+            Self::Bool => f.write_str("Bool"),
+            Self::Date => f.write_str("Date"),
+            Self::DateTime => f.write_str("DateTime"),
+            Self::DateTime64(precision) => write!(f, "DateTime64({})", precision),
+            Self::Nullable(inner) => write!(f, "Nullable({})", inner),
+            Self::LowCardinality(inner) => write!(f, "LowCardinality({})", inner),
+            Self::Array(inner) => write!(f, "Array({})", inner),
+            Self::Map(key, value) => write!(f, "Map({}, {})", key, value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn column(name: &str, column_type: &str) -> Column {
+        Column {
+            name: name.into(),
+            column_type: column_type.parse().unwrap(),
+        }
+    }
+
+    fn encode(log: &LogEvent, columns: &[Column]) -> Result<Vec<u8>, EncodeError> {
+        let mut buffer = BytesMut::new();
+        encode_row(log, columns, &mut buffer).map(|()| buffer.to_vec())
+    }
+
+    #[test]
+    fn parses_types() {
+        for column_type in [
+            "String",
+            "FixedString(16)",
+            "UInt64",
+            "Nullable(Int32)",
+            "Array(LowCardinality(String))",
+            "Map(String, Array(Nullable(Float64)))",
+            "DateTime64(3)",
+        ] {
+            assert_eq!(
+                column_type.parse::<ColumnType>().unwrap().to_string(),
+                column_type
+            );
+        }
+        assert_eq!(
+            "DateTime('UTC')".parse::<ColumnType>().unwrap(),
+            ColumnType::DateTime
+        );
+        assert_eq!(
+            "DateTime64(6, 'Europe/Paris')"
+                .parse::<ColumnType>()
+                .unwrap(),
+            ColumnType::DateTime64(6)
+        );
+        assert!("Decimal(9, 2)".parse::<ColumnType>().is_err());
+        assert!("Array(Int128)".parse::<ColumnType>().is_err());
+        assert!("Map(String)".parse::<ColumnType>().is_err());
+        assert!("DateTime64(12)".parse::<ColumnType>().is_err());
+    }
+
+    #[test]
+    fn encodes_rows() {
+        let mut log = LogEvent::from("hello");
+        log.insert("status", 200);
+        log.insert("duration", "1.5");
+        log.insert("tags", vec!["a", "b"]);
+        log.insert("labels.env", "prod");
+        log.insert(
+            "timestamp",
+            Utc.ymd(2022, 6, 1).and_hms_milli(12, 0, 0, 250),
+        );
+
+        let columns = [
+            column("message", "String"),
+            column("status", "UInt16"),
+            column("duration", "Float32"),
+            column("tags", "Array(LowCardinality(String))"),
+            column("labels", "Map(String, String)"),
+            column("timestamp", "DateTime64(3)"),
+            column("user", "Nullable(String)"),
+            column("count", "Int32"),
+        ];
+
+        let mut expected = vec![5];
+        expected.extend(b"hello");
+        expected.extend(200u16.to_le_bytes());
+        expected.extend(1.5f32.to_le_bytes());
+        expected.extend([2, 1, b'a', 1, b'b']);
+        expected.extend([1, 3]);
+        expected.extend(b"env");
+        expected.push(4);
+        expected.extend(b"prod");
+        expected.extend(1_654_084_800_250i64.to_le_bytes());
+        expected.push(1);
+        expected.extend(0i32.to_le_bytes());
+
+        assert_eq!(encode(&log, &columns).unwrap(), expected);
+    }
+
+    #[test]
+    fn encodes_long_strings_with_varint_lengths() {
+        let mut log = LogEvent::default();
+        log.insert("message", "a".repeat(300));
+
+        let encoded = encode(&log, &[column("message", "String")]).unwrap();
+        assert_eq!(&encoded[..2], &[0xac, 0x02]);
+        assert_eq!(encoded.len(), 302);
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        let mut log = LogEvent::default();
+        log.insert("status", 70000);
+        log.insert("name", "unknown");
+
+        assert_eq!(
+            encode(&log, &[column("status", "UInt16")])
+                .unwrap_err()
+                .to_string(),
+            "Column `status`: 70000 is out of the range of UInt16."
+        );
+        assert_eq!(
+            encode(&log, &[column("name", "Int64")])
+                .unwrap_err()
+                .to_string(),
+            "Column `name`: can't convert string to Int64."
+        );
+    }
+}
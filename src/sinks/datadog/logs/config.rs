@@ -7,16 +7,22 @@ use tower::ServiceBuilder;
 use value::Kind;
 use vector_core::config::proxy::ProxyConfig;
 
-use super::{service::LogApiRetry, sink::LogSinkBuilder};
+use super::{
+    service::{LogApiRequest, LogApiRetry},
+    sink::LogSinkBuilder,
+};
 use crate::{
-    config::{AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext},
+    config::{AcknowledgementsConfig, Capability, GenerateConfig, Input, SinkConfig, SinkContext},
     http::HttpClient,
     schema,
     sinks::{
-        datadog::{get_api_validate_endpoint, healthcheck, logs::service::LogApiService, Region},
+        datadog::{
+            api_key_partition, get_api_validate_endpoint, healthcheck,
+            logs::service::LogApiService, Region,
+        },
         util::{
-            service::ServiceBuilderExt, BatchConfig, Compression, SinkBatchSettings,
-            TowerRequestConfig,
+            service::{PartitionedService, ServiceBuilderExt},
+            BatchConfig, Compression, SinkBatchSettings, TowerRequestConfig,
         },
         Healthcheck, VectorSink,
     },
@@ -35,6 +41,14 @@ pub const BATCH_GOAL_BYTES: usize = 4_250_000;
 pub const BATCH_MAX_EVENTS: usize = 1_000;
 pub const BATCH_DEFAULT_TIMEOUT_SECS: f64 = 5.0;
 
+// Requests are sent by a service per API key. This bounds the number of requests waiting to be sent
+// or in flight across all of them, matching the maximum adaptive concurrency of a single one.
+const MAX_PENDING_REQUESTS: usize = 200;
+
+// This bounds the number of requests of each API key, so that a throttled one leaves the rest of the
+// requests to the others.
+const MAX_PARTITION_PENDING_REQUESTS: usize = 50;
+
 #[derive(Clone, Copy, Debug, Default)]
 pub struct DatadogLogsDefaultBatchSettings;
 
@@ -125,9 +139,26 @@ impl DatadogLogsConfig {
             .limit_max_events(BATCH_MAX_EVENTS)?
             .into_batcher_settings()?;
 
-        let service = ServiceBuilder::new()
-            .settings(request_limits, LogApiRetry)
-            .service(LogApiService::new(client, self.get_uri(), self.enterprise));
+        // Throttled requests are only retried when the sink has no dead letter to route their
+        // events to.
+        let retry_logic = LogApiRetry {
+            retry_throttled: !cx.dead_letter,
+        };
+        // Each API key gets its own concurrency and retry state, so that one being throttled
+        // doesn't hold back the requests of the others.
+        let (uri, enterprise) = (self.get_uri(), self.enterprise);
+        let service = PartitionedService::new(
+            |request: &LogApiRequest| Arc::clone(&request.api_key),
+            move |api_key: &Arc<str>| {
+                let mut settings = request_limits.clone();
+                settings.partition = Some(api_key_partition(api_key));
+                ServiceBuilder::new()
+                    .settings(settings, retry_logic.clone())
+                    .service(LogApiService::new(client.clone(), uri.clone(), enterprise))
+            },
+            MAX_PENDING_REQUESTS,
+            MAX_PARTITION_PENDING_REQUESTS,
+        );
 
         let sink = LogSinkBuilder::new(service, cx, default_api_key, batch)
             .compression(self.compression.unwrap_or_default())
@@ -182,6 +213,14 @@ impl SinkConfig for DatadogLogsConfig {
         "datadog_logs"
     }
 
+    fn capabilities(&self) -> Vec<Capability> {
+        Capability::uri(&self.get_uri()).into_iter().collect()
+    }
+
+    fn supports_sandbox(&self) -> bool {
+        true
+    }
+
     fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
         Some(&self.acknowledgements)
     }
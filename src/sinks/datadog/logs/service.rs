@@ -23,11 +23,16 @@ use vector_core::{
 
 use crate::{
     http::HttpClient,
+    internal_events::{DatadogLogsQuotaExceeded, DatadogLogsRequestAccepted},
     sinks::util::{retries::RetryLogic, Compression},
 };
 
 #[derive(Debug, Default, Clone)]
-pub struct LogApiRetry;
+pub struct LogApiRetry {
+    /// Whether requests throttled by the API are retried, which they aren't when their events are
+    /// routed to the dead letter of the sink instead.
+    pub retry_throttled: bool,
+}
 
 impl RetryLogic for LogApiRetry {
     type Error = LogApiError;
@@ -44,6 +49,7 @@ impl RetryLogic for LogApiRetry {
             // https://github.com/vectordotdev/vector/issues/10870
             // https://github.com/vectordotdev/vector/issues/12220
             LogApiError::ServerError | LogApiError::Forbidden => true,
+            LogApiError::TooManyRequests => self.retry_throttled,
         }
     }
 }
@@ -83,6 +89,8 @@ pub enum LogApiError {
     BadRequest,
     #[snafu(display("Client request was forbidden."))]
     Forbidden,
+    #[snafu(display("Client request was throttled, the quota is exceeded."))]
+    TooManyRequests,
 }
 
 #[derive(Debug)]
@@ -190,19 +198,28 @@ impl Service<LogApiRequest> for LogApiService {
                     //      formatting)
                     // 403: Permission issue (likely using an invalid API Key)
                     // 413: Payload too large (batch is above 5MB uncompressed)
+                    // 429: Too many requests (the quota of the organization is
+                    //      exceeded)
                     // 5xx: Internal error, request should be retried after some
                     //      time
                     match status {
                         StatusCode::BAD_REQUEST => Err(LogApiError::BadRequest),
                         StatusCode::FORBIDDEN => Err(LogApiError::Forbidden),
-                        StatusCode::OK | StatusCode::ACCEPTED => Ok(LogApiResponse {
-                            event_status: EventStatus::Delivered,
-                            count,
-                            events_byte_size,
-                            raw_byte_size,
-                            protocol,
-                        }),
+                        StatusCode::OK | StatusCode::ACCEPTED => {
+                            emit!(DatadogLogsRequestAccepted);
+                            Ok(LogApiResponse {
+                                event_status: EventStatus::Delivered,
+                                count,
+                                events_byte_size,
+                                raw_byte_size,
+                                protocol,
+                            })
+                        }
                         StatusCode::PAYLOAD_TOO_LARGE => Err(LogApiError::PayloadTooLarge),
+                        StatusCode::TOO_MANY_REQUESTS => {
+                            emit!(DatadogLogsQuotaExceeded);
+                            Err(LogApiError::TooManyRequests)
+                        }
                         _ => Err(LogApiError::ServerError),
                     }
                 }
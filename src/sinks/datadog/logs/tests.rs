@@ -14,10 +14,16 @@ use indoc::indoc;
 use vector_core::event::{BatchNotifier, BatchStatus, Event};
 
 use crate::{
-    config::SinkConfig,
+    config::{SinkConfig, SinkContext},
     sinks::{
-        datadog::logs::DatadogLogsConfig,
-        util::test::{build_test_server_status, load_sink},
+        datadog::logs::{
+            service::{LogApiError, LogApiRetry},
+            DatadogLogsConfig,
+        },
+        util::{
+            retries::RetryLogic,
+            test::{build_test_server_status, load_sink},
+        },
     },
     test_util::{next_addr, random_lines_with_stream},
 };
@@ -31,6 +37,7 @@ enum ApiStatus {
     OKv2,
     BadRequestv1,
     BadRequestv2,
+    TooManyRequests,
 }
 
 fn test_server(
@@ -45,6 +52,7 @@ fn test_server(
         ApiStatus::OKv1 => StatusCode::OK,
         ApiStatus::OKv2 => StatusCode::ACCEPTED,
         ApiStatus::BadRequestv1 | ApiStatus::BadRequestv2 => StatusCode::BAD_REQUEST,
+        ApiStatus::TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
     };
 
     // NOTE: we pass `Trigger` out to the caller even though this suite never
@@ -170,6 +178,52 @@ async fn handles_failure_v2() {
     assert!(matches!(res, Err(TryRecvError { .. })));
 }
 
+#[test]
+/// Assert that throttled requests are only retried when the sink has no dead letter
+fn retries_throttled_requests_without_dead_letter() {
+    let retry = LogApiRetry {
+        retry_throttled: true,
+    };
+    assert!(retry.is_retriable_error(&LogApiError::TooManyRequests));
+
+    let retry = LogApiRetry {
+        retry_throttled: false,
+    };
+    assert!(!retry.is_retriable_error(&LogApiError::TooManyRequests));
+}
+
+#[tokio::test]
+/// Assert that throttled requests fail when the sink has a dead letter
+///
+/// The Datadog API is rigged to respond with TOO_MANY_REQUESTS, so instead of
+/// being retried every event must be rejected, for the topology to route it to
+/// the dead letter.
+async fn fails_throttled_requests_with_dead_letter() {
+    let (mut config, cx) = load_sink::<DatadogLogsConfig>(indoc! {r#"
+            default_api_key = "atoken"
+            compression = "none"
+        "#})
+    .unwrap();
+    let cx = SinkContext {
+        dead_letter: true,
+        ..cx
+    };
+
+    let addr = next_addr();
+    config.endpoint = Some(format!("http://{}", addr));
+
+    let (sink, _) = config.build(cx).await.unwrap();
+
+    let (_rx, _trigger, server) = test_server(addr, ApiStatus::TooManyRequests);
+    tokio::spawn(server);
+
+    let (batch, receiver) = BatchNotifier::new_with_receiver();
+    let (_expected, events) = random_lines_with_stream(100, 10, Some(batch));
+
+    let _ = sink.run(events).await.unwrap();
+    assert_eq!(receiver.await, BatchStatus::Rejected);
+}
+
 #[tokio::test]
 /// Assert that metadata API keys are passed correctly, v2 API
 ///
@@ -29,6 +29,18 @@ fn get_api_validate_endpoint(
     validate.parse::<Uri>().map_err(Into::into)
 }
 
+/// Names the partition of the requests sent with an API key, under which their adaptive
+/// concurrency controller is registered, without revealing the key.
+#[cfg(any(feature = "sinks-datadog_logs", feature = "sinks-datadog_traces"))]
+fn api_key_partition(api_key: &str) -> String {
+    let suffix = api_key
+        .char_indices()
+        .rev()
+        .nth(3)
+        .map_or("", |(index, _)| &api_key[index..]);
+    format!("api_key:...{}", suffix)
+}
+
 async fn healthcheck(
     client: HttpClient,
     validate_endpoint: Uri,
@@ -0,0 +1,516 @@
+use std::{
+    collections::{btree_map::Entry, BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use prost::Message;
+use vector_core::metrics::AgentDDSketch;
+
+use super::{
+    ddsketch_full::{self, index_mapping::Interpolation},
+    ClientGroupedStats, ClientStatsBucket, ClientStatsPayload, StatsPayload,
+    BUCKET_DURATION_NANOSECONDS,
+};
+use crate::{
+    event::{TraceEvent, Value},
+    sinks::datadog::traces::sink::PartitionKey,
+};
+
+/// The metric flagging the spans the trace-agent considers as top-level.
+const TOP_LEVEL_KEY: &str = "_top_level";
+/// The metric flagging the spans stats are computed for, even if they aren't top-level.
+const MEASURED_KEY: &str = "_dd.measured";
+/// The metric holding the rate the trace was sampled at by the tracer.
+const SAMPLE_RATE_KEY: &str = "_sample_rate";
+const HTTP_STATUS_CODE_KEY: &str = "http.status_code";
+const SYNTHETICS_ORIGIN_PREFIX: &str = "synthetics";
+
+/// The payload stats are sent in, along with the API key to send them with.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+struct PayloadKey {
+    api_key: Option<Arc<str>>,
+    hostname: String,
+    env: String,
+    agent_version: String,
+    version: String,
+    container_id: String,
+}
+
+/// The spans aggregated together within a bucket.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+struct AggregationKey {
+    service: String,
+    name: String,
+    resource: String,
+    r#type: String,
+    http_status_code: u32,
+    synthetics: bool,
+}
+
+#[derive(Clone, Debug)]
+struct GroupedStats {
+    hits: f64,
+    top_level_hits: f64,
+    errors: f64,
+    duration: f64,
+    ok_distribution: AgentDDSketch,
+    error_distribution: AgentDDSketch,
+}
+
+impl GroupedStats {
+    fn new() -> Self {
+        Self {
+            hits: 0.0,
+            top_level_hits: 0.0,
+            errors: 0.0,
+            duration: 0.0,
+            ok_distribution: AgentDDSketch::with_agent_defaults(),
+            error_distribution: AgentDDSketch::with_agent_defaults(),
+        }
+    }
+
+    /// Adds a span, counting for `weight` spans as the trace may have been sampled.
+    fn add(&mut self, duration: i64, error: bool, top_level: bool, weight: f64) {
+        self.hits += weight;
+        if top_level {
+            self.top_level_hits += weight;
+        }
+        self.duration += duration as f64 * weight;
+
+        // The bins of the sketches count whole samples.
+        let samples = weight.round().max(1.0) as u32;
+        if error {
+            self.errors += weight;
+            self.error_distribution.insert_n(duration as f64, samples);
+        } else {
+            self.ok_distribution.insert_n(duration as f64, samples);
+        }
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.hits += other.hits;
+        self.top_level_hits += other.top_level_hits;
+        self.errors += other.errors;
+        self.duration += other.duration;
+        // The sketches all have the configuration of the trace-agent.
+        self.ok_distribution
+            .merge(&other.ok_distribution)
+            .expect("APM stats sketches have the same configuration");
+        self.error_distribution
+            .merge(&other.error_distribution)
+            .expect("APM stats sketches have the same configuration");
+    }
+
+    fn export(self, key: AggregationKey) -> ClientGroupedStats {
+        ClientGroupedStats {
+            service: key.service,
+            name: key.name,
+            resource: key.resource,
+            http_status_code: key.http_status_code,
+            r#type: key.r#type,
+            db_type: String::new(),
+            hits: self.hits.round() as u64,
+            errors: self.errors.round() as u64,
+            duration: self.duration.round() as u64,
+            ok_summary: encode_sketch(&self.ok_distribution),
+            error_summary: encode_sketch(&self.error_distribution),
+            synthetics: key.synthetics,
+            top_level_hits: self.top_level_hits.round() as u64,
+        }
+    }
+}
+
+/// Aggregates the stats of the spans into time buckets, until they are flushed.
+#[derive(Clone, Debug, Default)]
+pub struct Aggregator {
+    /// The stats of each payload, by the start of the buckets they belong to.
+    buckets: BTreeMap<PayloadKey, BTreeMap<u64, BTreeMap<AggregationKey, GroupedStats>>>,
+}
+
+impl Aggregator {
+    /// Adds the stats of the top-level and measured spans of the trace, in the buckets they ended
+    /// in.
+    pub(crate) fn handle_trace(&mut self, partition_key: &PartitionKey, trace: &TraceEvent) {
+        let spans = match trace.get("spans") {
+            Some(Value::Array(spans)) => spans
+                .iter()
+                .filter_map(Value::as_object)
+                .collect::<Vec<_>>(),
+            _ => return,
+        };
+
+        let payload_key = PayloadKey {
+            api_key: partition_key.api_key.clone(),
+            hostname: partition_key.hostname.clone().unwrap_or_default(),
+            env: partition_key.env.clone().unwrap_or_default(),
+            agent_version: partition_key.agent_version.clone().unwrap_or_default(),
+            version: string(trace.get("app_version")),
+            container_id: string(trace.get("container_id")),
+        };
+        let synthetics = string(trace.get("origin")).starts_with(SYNTHETICS_ORIGIN_PREFIX);
+        let weight = weight(&spans);
+        let buckets = self.buckets.entry(payload_key).or_default();
+
+        for (span, top_level) in spans.iter().zip(top_level(&spans)) {
+            if !top_level && metric(span, MEASURED_KEY) != Some(1.0) {
+                continue;
+            }
+
+            let start = match span.get("start") {
+                Some(Value::Timestamp(start)) => start.timestamp_nanos(),
+                _ => 0,
+            };
+            let duration = integer(span, "duration").unwrap_or(0);
+            let end = (start + duration).max(0) as u64;
+            let bucket_start = end - end % BUCKET_DURATION_NANOSECONDS;
+
+            let key = AggregationKey {
+                service: string(span.get("service")),
+                name: string(span.get("name")),
+                resource: string(span.get("resource")),
+                r#type: string(span.get("type")),
+                http_status_code: meta(span, HTTP_STATUS_CODE_KEY)
+                    .and_then(|status| status.parse().ok())
+                    .unwrap_or(0),
+                synthetics,
+            };
+            let error = integer(span, "error").unwrap_or(0) != 0;
+
+            buckets
+                .entry(bucket_start)
+                .or_default()
+                .entry(key)
+                .or_insert_with(GroupedStats::new)
+                .add(duration, error, top_level, weight);
+        }
+    }
+
+    /// Adds the stats aggregated by another aggregator, such as the stats of the traces of a
+    /// request once it's delivered.
+    pub(crate) fn merge(&mut self, other: Aggregator) {
+        for (payload_key, other_buckets) in other.buckets {
+            let buckets = self.buckets.entry(payload_key).or_default();
+            for (bucket_start, other_stats) in other_buckets {
+                let stats = buckets.entry(bucket_start).or_default();
+                for (key, other_stats) in other_stats {
+                    match stats.entry(key) {
+                        Entry::Vacant(entry) => {
+                            entry.insert(other_stats);
+                        }
+                        Entry::Occupied(mut entry) => entry.get_mut().merge(&other_stats),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Takes the stats of the buckets that ended at least a bucket duration before `now`, leaving
+    /// time for late spans to arrive, or all of them when `force` is set. They are grouped into
+    /// payloads along with the API key to send them with.
+    pub(crate) fn flush(&mut self, now: u64, force: bool) -> Vec<(Option<Arc<str>>, StatsPayload)> {
+        let mut payloads = BTreeMap::<_, StatsPayload>::new();
+
+        for (key, buckets) in self.buckets.iter_mut() {
+            let flushed = if force {
+                std::mem::take(buckets)
+            } else {
+                let open =
+                    buckets.split_off(&now.saturating_sub(2 * BUCKET_DURATION_NANOSECONDS - 1));
+                std::mem::replace(buckets, open)
+            };
+            if flushed.is_empty() {
+                continue;
+            }
+
+            let payload = payloads
+                .entry((
+                    key.api_key.clone(),
+                    key.hostname.clone(),
+                    key.env.clone(),
+                    key.agent_version.clone(),
+                ))
+                .or_insert_with(|| StatsPayload {
+                    agent_hostname: key.hostname.clone(),
+                    agent_env: key.env.clone(),
+                    agent_version: key.agent_version.clone(),
+                    ..Default::default()
+                });
+            payload.stats.push(ClientStatsPayload {
+                hostname: key.hostname.clone(),
+                env: key.env.clone(),
+                version: key.version.clone(),
+                container_id: key.container_id.clone(),
+                stats: flushed
+                    .into_iter()
+                    .map(|(start, stats)| ClientStatsBucket {
+                        start,
+                        duration: BUCKET_DURATION_NANOSECONDS,
+                        stats: stats
+                            .into_iter()
+                            .map(|(key, stats)| stats.export(key))
+                            .collect(),
+                        agent_time_shift: 0,
+                    })
+                    .collect(),
+                ..Default::default()
+            });
+        }
+        self.buckets.retain(|_, buckets| !buckets.is_empty());
+
+        payloads
+            .into_iter()
+            .map(|((api_key, _, _, _), payload)| (api_key, payload))
+            .collect()
+    }
+}
+
+/// Flags the top-level spans of a trace, which are the entry points of their service.
+///
+/// The trace-agent already flags them when it forwards traces, otherwise a span is top-level if
+/// its parent isn't part of the trace or belongs to another service.
+fn top_level(spans: &[&BTreeMap<String, Value>]) -> Vec<bool> {
+    if spans
+        .iter()
+        .any(|span| metric(span, TOP_LEVEL_KEY).is_some())
+    {
+        return spans
+            .iter()
+            .map(|span| metric(span, TOP_LEVEL_KEY) == Some(1.0))
+            .collect();
+    }
+
+    let services = spans
+        .iter()
+        .filter_map(|span| Some((integer(span, "span_id")?, string(span.get("service")))))
+        .collect::<HashMap<_, _>>();
+    spans
+        .iter()
+        .map(|span| match integer(span, "parent_id") {
+            Some(parent_id) if parent_id != 0 => services
+                .get(&parent_id)
+                .map_or(true, |service| *service != string(span.get("service"))),
+            _ => true,
+        })
+        .collect()
+}
+
+/// The number of spans each span of the trace stands for, based on the rate the tracer sampled
+/// the trace at, as recorded on its root span.
+fn weight(spans: &[&BTreeMap<String, Value>]) -> f64 {
+    let span_ids = spans
+        .iter()
+        .filter_map(|span| integer(span, "span_id"))
+        .collect::<Vec<_>>();
+    let root = spans.iter().find(|span| match integer(span, "parent_id") {
+        Some(parent_id) => parent_id == 0 || !span_ids.contains(&parent_id),
+        None => true,
+    });
+
+    match root.and_then(|span| metric(span, SAMPLE_RATE_KEY)) {
+        Some(rate) if rate > 0.0 && rate <= 1.0 => 1.0 / rate,
+        _ => 1.0,
+    }
+}
+
+fn string(value: Option<&Value>) -> String {
+    value.map(Value::to_string_lossy).unwrap_or_default()
+}
+
+fn integer(span: &BTreeMap<String, Value>, key: &str) -> Option<i64> {
+    span.get(key).and_then(Value::as_integer)
+}
+
+fn metric(span: &BTreeMap<String, Value>, key: &str) -> Option<f64> {
+    match span.get("metrics")?.as_object()?.get(key)? {
+        Value::Float(value) => Some(value.into_inner()),
+        Value::Integer(value) => Some(*value as f64),
+        _ => None,
+    }
+}
+
+fn meta(span: &BTreeMap<String, Value>, key: &str) -> Option<String> {
+    span.get("meta")?
+        .as_object()?
+        .get(key)
+        .map(Value::to_string_lossy)
+}
+
+/// Encodes the sketch with the protobuf representation of the DDSketch library, as expected for
+/// APM stats.
+fn encode_sketch(sketch: &AgentDDSketch) -> Vec<u8> {
+    let config = sketch.config();
+    let mut positive_counts = BTreeMap::new();
+    let mut negative_counts = BTreeMap::new();
+    let mut zero_count = 0.0;
+
+    let (keys, counts) = sketch.bin_map().into_parts();
+    for (k, n) in keys.into_iter().zip(counts) {
+        let n = f64::from(n);
+        match k {
+            0 => zero_count += n,
+            k if k > 0 => *positive_counts.entry(i32::from(k)).or_insert(0.0) += n,
+            k => *negative_counts.entry(-i32::from(k)).or_insert(0.0) += n,
+        }
+    }
+
+    let store = |bin_counts: BTreeMap<i32, f64>| ddsketch_full::Store {
+        bin_counts,
+        contiguous_bin_counts: Vec::new(),
+        contiguous_bin_index_offset: 0,
+    };
+    ddsketch_full::DdSketch {
+        mapping: Some(ddsketch_full::IndexMapping {
+            gamma: config.gamma(),
+            index_offset: f64::from(config.norm_bias()),
+            interpolation: Interpolation::None as i32,
+        }),
+        positive_values: Some(store(positive_counts)),
+        negative_values: Some(store(negative_counts)),
+        zero_count,
+    }
+    .encode_to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+
+    fn span(span_id: i64, parent_id: i64, service: &str, error: i64) -> BTreeMap<String, Value> {
+        BTreeMap::from([
+            ("service".to_string(), Value::from(service)),
+            ("name".to_string(), Value::from("request")),
+            ("resource".to_string(), Value::from("GET /")),
+            ("type".to_string(), Value::from("web")),
+            ("span_id".to_string(), Value::Integer(span_id)),
+            ("parent_id".to_string(), Value::Integer(parent_id)),
+            (
+                "start".to_string(),
+                Value::from(Utc.timestamp_nanos(1_431_648_000_000_000_000)),
+            ),
+            ("duration".to_string(), Value::Integer(1_000_000)),
+            ("error".to_string(), Value::Integer(error)),
+        ])
+    }
+
+    fn trace(spans: Vec<BTreeMap<String, Value>>) -> TraceEvent {
+        let mut trace = TraceEvent::default();
+        trace.insert(
+            "spans",
+            Value::Array(spans.into_iter().map(Value::from).collect()),
+        );
+        trace
+    }
+
+    fn partition_key() -> PartitionKey {
+        PartitionKey {
+            api_key: None,
+            env: Some("prod".to_string()),
+            hostname: Some("host".to_string()),
+            agent_version: None,
+            target_tps: None,
+            error_tps: None,
+        }
+    }
+
+    #[test]
+    fn aggregates_top_level_spans() {
+        let mut aggregator = Aggregator::default();
+        let trace = trace(vec![
+            span(1, 0, "frontend", 0),
+            span(2, 1, "frontend", 0),
+            span(3, 1, "backend", 1),
+        ]);
+        aggregator.handle_trace(&partition_key(), &trace);
+        aggregator.handle_trace(&partition_key(), &trace);
+
+        let mut payloads = aggregator.flush(0, true);
+        assert_eq!(payloads.len(), 1);
+        let (api_key, mut payload) = payloads.pop().unwrap();
+        assert_eq!(api_key, None);
+        assert_eq!(payload.agent_hostname, "host");
+        assert_eq!(payload.agent_env, "prod");
+
+        let mut buckets = payload.stats.pop().unwrap().stats;
+        assert_eq!(buckets.len(), 1);
+        let bucket = buckets.pop().unwrap();
+        assert_eq!(bucket.start, 1_431_648_000_000_000_000);
+        assert_eq!(bucket.duration, BUCKET_DURATION_NANOSECONDS);
+
+        // The span with id 2 isn't the entry point of its service.
+        let stats = bucket.stats;
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].service, "backend");
+        assert_eq!(stats[0].hits, 2);
+        assert_eq!(stats[0].errors, 2);
+        assert_eq!(stats[0].top_level_hits, 2);
+        assert_eq!(stats[0].duration, 2_000_000);
+        assert_eq!(stats[1].service, "frontend");
+        assert_eq!(stats[1].hits, 2);
+        assert_eq!(stats[1].errors, 0);
+
+        assert!(aggregator.flush(0, true).is_empty());
+    }
+
+    #[test]
+    fn weights_sampled_traces() {
+        let mut aggregator = Aggregator::default();
+        let mut root = span(1, 0, "frontend", 0);
+        root.insert(
+            "metrics".to_string(),
+            Value::from(BTreeMap::from([(
+                SAMPLE_RATE_KEY.to_string(),
+                Value::from(0.25),
+            )])),
+        );
+        aggregator.handle_trace(&partition_key(), &trace(vec![root]));
+
+        let stats = aggregator.flush(0, true).pop().unwrap().1.stats[0].stats[0].stats[0].clone();
+        assert_eq!(stats.hits, 4);
+        assert_eq!(stats.top_level_hits, 4);
+        assert_eq!(stats.duration, 4_000_000);
+
+        let sketch = ddsketch_full::DdSketch::decode(&stats.ok_summary[..]).unwrap();
+        let samples = sketch.zero_count
+            + sketch
+                .positive_values
+                .unwrap()
+                .bin_counts
+                .values()
+                .sum::<f64>();
+        assert_eq!(samples, 4.0);
+    }
+
+    #[test]
+    fn merges_aggregators() {
+        let trace = trace(vec![span(1, 0, "frontend", 0)]);
+        let mut aggregator = Aggregator::default();
+        aggregator.handle_trace(&partition_key(), &trace);
+        let mut other = Aggregator::default();
+        other.handle_trace(&partition_key(), &trace);
+        other.handle_trace(&partition_key(), &trace);
+        aggregator.merge(other);
+
+        let stats = aggregator.flush(0, true).pop().unwrap().1.stats[0].stats[0].stats[0].clone();
+        assert_eq!(stats.hits, 3);
+        assert_eq!(stats.duration, 3_000_000);
+    }
+
+    #[test]
+    fn keeps_recent_buckets_until_they_are_complete() {
+        let mut aggregator = Aggregator::default();
+        aggregator.handle_trace(&partition_key(), &trace(vec![span(1, 0, "frontend", 0)]));
+
+        let bucket_start = 1_431_648_000_000_000_000;
+        assert!(aggregator
+            .flush(bucket_start + BUCKET_DURATION_NANOSECONDS, false)
+            .is_empty());
+        assert_eq!(
+            aggregator
+                .flush(bucket_start + 2 * BUCKET_DURATION_NANOSECONDS, false)
+                .len(),
+            1
+        );
+    }
+}
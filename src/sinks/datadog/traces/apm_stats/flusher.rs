@@ -0,0 +1,117 @@
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use futures::channel::oneshot;
+use http::Uri;
+use tower::{util::BoxService, Service, ServiceExt};
+
+use super::{Aggregator, StatsPayload, BUCKET_DURATION_NANOSECONDS};
+use crate::{
+    internal_events::DatadogTracesApmStatsError,
+    sinks::{
+        datadog::traces::service::{TraceApiRequest, TraceApiResponse},
+        util::{Compression, Compressor},
+    },
+};
+
+/// Sends the stats computed by the aggregator to the stats endpoint, as they are complete.
+pub struct ApmStatsFlusher {
+    /// The service sending the payloads, which retries them like trace payloads.
+    service: BoxService<TraceApiRequest, TraceApiResponse, crate::Error>,
+    endpoint: Uri,
+    default_api_key: Arc<str>,
+    aggregator: Arc<Mutex<Aggregator>>,
+}
+
+impl ApmStatsFlusher {
+    pub fn new(
+        service: BoxService<TraceApiRequest, TraceApiResponse, crate::Error>,
+        endpoint: Uri,
+        default_api_key: Arc<str>,
+        aggregator: Arc<Mutex<Aggregator>>,
+    ) -> Self {
+        Self {
+            service,
+            endpoint,
+            default_api_key,
+            aggregator,
+        }
+    }
+
+    /// Flushes the complete buckets every bucket duration, until `shutdown` resolves, at which
+    /// point all the remaining stats are flushed.
+    pub async fn run(mut self, mut shutdown: oneshot::Receiver<()>) {
+        let mut interval = tokio::time::interval(Duration::from_nanos(BUCKET_DURATION_NANOSECONDS));
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => self.flush(false).await,
+                _ = &mut shutdown => break,
+            }
+        }
+
+        self.flush(true).await;
+    }
+
+    async fn flush(&mut self, force: bool) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|now| now.as_nanos() as u64)
+            .unwrap_or_default();
+        let payloads = self
+            .aggregator
+            .lock()
+            .expect("APM stats aggregator lock poisoned")
+            .flush(now, force);
+
+        for (api_key, payload) in payloads {
+            if let Err(error) = self.send(api_key, &payload).await {
+                emit!(DatadogTracesApmStatsError { error });
+            }
+        }
+    }
+
+    async fn send(
+        &mut self,
+        api_key: Option<Arc<str>>,
+        payload: &StatsPayload,
+    ) -> crate::Result<()> {
+        // The stats endpoint expects msgpack payloads, always compressed with gzip.
+        let body = rmp_serde::to_vec_named(payload)?;
+        let uncompressed_size = body.len();
+        let mut compressor = Compressor::from(Compression::gzip_default());
+        compressor.write_all(&body)?;
+
+        let api_key = api_key.unwrap_or_else(|| Arc::clone(&self.default_api_key));
+        let headers = BTreeMap::from([
+            (
+                "Content-Type".to_string(),
+                "application/msgpack".to_string(),
+            ),
+            ("Content-Encoding".to_string(), "gzip".to_string()),
+            ("DD-API-KEY".to_string(), api_key.to_string()),
+        ]);
+        let request = TraceApiRequest {
+            api_key,
+            batch_size: 0,
+            body: compressor.into_inner().freeze(),
+            headers,
+            finalizers: Default::default(),
+            uri: self.endpoint.clone(),
+            uncompressed_size,
+            apm_stats: None,
+        };
+
+        let response = self.service.ready().await?.call(request).await?;
+        let status = response.status_code();
+        if !status.is_success() {
+            return Err(format!("response status: {}", status).into());
+        }
+
+        Ok(())
+    }
+}
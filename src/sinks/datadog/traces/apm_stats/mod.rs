@@ -0,0 +1,90 @@
+//! APM stats computed from the traces going through the sink.
+//!
+//! The Datadog trace-agent computes stats about the spans it receives (hits, errors and latency
+//! distributions), which is what the trace metrics of Datadog APM are based on. When Vector takes
+//! the place of the trace-agent when forwarding traces, it can compute those stats in the same way
+//! and submit them to the stats endpoint of the trace intake. The stats of the traces of a request
+//! are only aggregated once the request is delivered.
+
+use serde::{Deserialize, Serialize};
+
+mod aggregation;
+mod flusher;
+mod service;
+
+pub use self::{aggregation::Aggregator, flusher::ApmStatsFlusher, service::ApmStatsService};
+
+mod ddsketch_full {
+    include!(concat!(env!("OUT_DIR"), "/ddsketch_full.rs"));
+}
+
+/// The duration of the buckets that stats are aggregated into, as used by the trace-agent.
+pub(crate) const BUCKET_DURATION_NANOSECONDS: u64 = 10_000_000_000;
+
+/// The APM stats payload, as sent by the trace-agent.
+///
+/// See: <https://github.com/DataDog/datadog-agent/blob/main/pkg/trace/pb/stats.proto>
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct StatsPayload {
+    pub(crate) agent_hostname: String,
+    pub(crate) agent_env: String,
+    pub(crate) stats: Vec<ClientStatsPayload>,
+    pub(crate) agent_version: String,
+    pub(crate) client_computed: bool,
+}
+
+/// The stats of the spans sharing the same host, environment, version and container.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct ClientStatsPayload {
+    pub(crate) hostname: String,
+    pub(crate) env: String,
+    pub(crate) version: String,
+    pub(crate) stats: Vec<ClientStatsBucket>,
+    pub(crate) lang: String,
+    pub(crate) tracer_version: String,
+    #[serde(rename = "RuntimeID")]
+    pub(crate) runtime_id: String,
+    pub(crate) sequence: u64,
+    pub(crate) agent_aggregation: String,
+    pub(crate) service: String,
+    #[serde(rename = "ContainerID")]
+    pub(crate) container_id: String,
+    pub(crate) tags: Vec<String>,
+}
+
+/// The stats of the spans that ended within a time bucket.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct ClientStatsBucket {
+    pub(crate) start: u64,
+    pub(crate) duration: u64,
+    pub(crate) stats: Vec<ClientGroupedStats>,
+    pub(crate) agent_time_shift: i64,
+}
+
+/// The stats of the spans sharing the same service, operation name, resource, type and status.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct ClientGroupedStats {
+    pub(crate) service: String,
+    pub(crate) name: String,
+    pub(crate) resource: String,
+    #[serde(rename = "HTTPStatusCode")]
+    pub(crate) http_status_code: u32,
+    pub(crate) r#type: String,
+    #[serde(rename = "DBType")]
+    pub(crate) db_type: String,
+    pub(crate) hits: u64,
+    pub(crate) errors: u64,
+    pub(crate) duration: u64,
+    /// The distribution of the durations of successful spans, as an encoded DDSketch.
+    #[serde(with = "serde_bytes")]
+    pub(crate) ok_summary: Vec<u8>,
+    /// The distribution of the durations of failed spans, as an encoded DDSketch.
+    #[serde(with = "serde_bytes")]
+    pub(crate) error_summary: Vec<u8>,
+    pub(crate) synthetics: bool,
+    pub(crate) top_level_hits: u64,
+}
@@ -0,0 +1,61 @@
+use std::{
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use futures::{future::BoxFuture, FutureExt};
+use tower::Service;
+use vector_core::{event::EventStatus, stream::DriverResponse};
+
+use super::Aggregator;
+use crate::sinks::datadog::traces::service::TraceApiRequest;
+
+/// Adds the stats of the traces of each request to the aggregator once the request is delivered,
+/// so that the stats only account for the traces the intake received.
+#[derive(Clone)]
+pub struct ApmStatsService<S> {
+    inner: S,
+    aggregator: Arc<Mutex<Aggregator>>,
+}
+
+impl<S> ApmStatsService<S> {
+    pub const fn new(inner: S, aggregator: Arc<Mutex<Aggregator>>) -> Self {
+        Self { inner, aggregator }
+    }
+}
+
+impl<S> Service<TraceApiRequest> for ApmStatsService<S>
+where
+    S: Service<TraceApiRequest>,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: TraceApiRequest) -> Self::Future {
+        // The stats are taken out of the request, so that retries don't copy them.
+        let apm_stats = request.apm_stats.take();
+        let aggregator = Arc::clone(&self.aggregator);
+
+        self.inner
+            .call(request)
+            .map(move |result| {
+                if let (Ok(response), Some(apm_stats)) = (&result, apm_stats) {
+                    if response.event_status() == EventStatus::Delivered {
+                        aggregator
+                            .lock()
+                            .expect("APM stats aggregator lock poisoned")
+                            .merge(apm_stats);
+                    }
+                }
+                result
+            })
+            .boxed()
+    }
+}
@@ -1,29 +1,31 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use futures::FutureExt;
 use http::Uri;
 use indoc::indoc;
 use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
-use tower::ServiceBuilder;
+use tower::{util::BoxService, ServiceBuilder};
 use vector_core::config::{proxy::ProxyConfig, AcknowledgementsConfig};
 
-use super::service::TraceApiRetry;
+use super::service::{TraceApiRequest, TraceApiRetry};
 use crate::{
     common::datadog::get_base_domain,
     config::{GenerateConfig, Input, SinkConfig, SinkContext},
     http::HttpClient,
     sinks::{
         datadog::{
-            get_api_validate_endpoint, healthcheck,
+            api_key_partition, get_api_validate_endpoint, healthcheck,
             traces::{
-                request_builder::DatadogTracesRequestBuilder, service::TraceApiService,
+                apm_stats::{Aggregator, ApmStatsFlusher, ApmStatsService},
+                request_builder::DatadogTracesRequestBuilder,
+                service::TraceApiService,
                 sink::TracesSink,
             },
         },
         util::{
-            service::ServiceBuilderExt, BatchConfig, Compression, Concurrency, SinkBatchSettings,
-            TowerRequestConfig,
+            service::{PartitionedService, ServiceBuilderExt},
+            BatchConfig, Compression, Concurrency, SinkBatchSettings, TowerRequestConfig,
         },
         Healthcheck, UriParseSnafu, VectorSink,
     },
@@ -43,6 +45,14 @@ const DEFAULT_REQUEST_LIMITS: TowerRequestConfig = TowerRequestConfig::new(Concu
     .retry_attempts(5)
     .retry_max_duration_secs(300);
 
+// Requests are sent by a service per API key. This bounds the number of requests waiting to be sent
+// or in flight across all of them, matching the maximum adaptive concurrency of a single one.
+const MAX_PENDING_REQUESTS: usize = 200;
+
+// This bounds the number of requests of each API key, so that a throttled one leaves the rest of the
+// requests to the others.
+const MAX_PARTITION_PENDING_REQUESTS: usize = 50;
+
 #[derive(Clone, Copy, Debug, Default)]
 pub struct DatadogTracesDefaultBatchSettings;
 
@@ -70,6 +80,9 @@ pub struct DatadogTracesConfig {
     #[serde(default)]
     request: TowerRequestConfig,
 
+    #[serde(default)]
+    compute_apm_stats: bool,
+
     #[serde(
         default,
         deserialize_with = "crate::serde::bool_or_struct",
@@ -91,14 +104,12 @@ impl GenerateConfig for DatadogTracesConfig {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum DatadogTracesEndpoint {
     Traces,
-    #[allow(dead_code)] // This will be used when APM stats will be generated
     APMStats,
 }
 
 /// Store traces & APM stats endpoints actual URIs.
 pub struct DatadogTracesEndpointConfiguration {
     traces_endpoint: Uri,
-    // Unused so far
     stats_endpoint: Uri,
 }
 
@@ -143,16 +154,50 @@ impl DatadogTracesConfig {
             .limit_max_bytes(BATCH_GOAL_BYTES)?
             .limit_max_events(BATCH_MAX_EVENTS)?
             .into_batcher_settings()?;
-        let service = ServiceBuilder::new()
-            .settings(request_limits, TraceApiRetry)
-            .service(TraceApiService::new(client));
+        // The stats computed from the traces of the delivered requests are aggregated, and
+        // periodically sent to the stats endpoint by the flusher, with the same retries as the
+        // traces.
+        let aggregator = Arc::new(Mutex::new(Aggregator::default()));
+        let apm_stats_flusher = self.compute_apm_stats.then(|| {
+            ApmStatsFlusher::new(
+                BoxService::new(
+                    ServiceBuilder::new()
+                        .settings(request_limits.clone(), TraceApiRetry)
+                        .service(TraceApiService::new(client.clone())),
+                ),
+                endpoints.get_uri_for_endpoint(DatadogTracesEndpoint::APMStats),
+                Arc::clone(&default_api_key),
+                Arc::clone(&aggregator),
+            )
+        });
+        // Each API key gets its own concurrency and retry state, so that one being throttled
+        // doesn't hold back the requests of the others.
+        let service = PartitionedService::new(
+            |request: &TraceApiRequest| Arc::clone(&request.api_key),
+            move |api_key: &Arc<str>| {
+                let mut settings = request_limits.clone();
+                settings.partition = Some(api_key_partition(api_key));
+                ServiceBuilder::new()
+                    .settings(settings, TraceApiRetry)
+                    .service(TraceApiService::new(client.clone()))
+            },
+            MAX_PENDING_REQUESTS,
+            MAX_PARTITION_PENDING_REQUESTS,
+        );
         let request_builder = DatadogTracesRequestBuilder::new(
             Arc::clone(&default_api_key),
             endpoints,
             self.compression.unwrap_or_else(Compression::gzip_default),
             PAYLOAD_LIMIT,
+            self.compute_apm_stats,
         )?;
-        let sink = TracesSink::new(cx, service, request_builder, batcher_settings);
+        let sink = TracesSink::new(
+            cx,
+            ApmStatsService::new(service, aggregator),
+            request_builder,
+            batcher_settings,
+            apm_stats_flusher,
+        );
         Ok(VectorSink::from_event_streamsink(sink))
     }
 
@@ -11,6 +11,7 @@ mod integration_tests;
 #[cfg(test)]
 mod tests;
 
+mod apm_stats;
 mod config;
 mod request_builder;
 mod service;
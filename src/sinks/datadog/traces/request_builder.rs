@@ -6,6 +6,7 @@ use snafu::Snafu;
 use vector_core::event::{EventFinalizers, Finalizable};
 
 use super::{
+    apm_stats::Aggregator,
     config::{DatadogTracesEndpoint, DatadogTracesEndpointConfiguration},
     service::TraceApiRequest,
 };
@@ -55,6 +56,7 @@ pub struct DatadogTracesRequestBuilder {
     endpoint_configuration: DatadogTracesEndpointConfiguration,
     compression: Compression,
     trace_encoder: DatadogTracesEncoder,
+    compute_apm_stats: bool,
 }
 
 impl DatadogTracesRequestBuilder {
@@ -63,12 +65,14 @@ impl DatadogTracesRequestBuilder {
         endpoint_configuration: DatadogTracesEndpointConfiguration,
         compression: Compression,
         max_size: usize,
+        compute_apm_stats: bool,
     ) -> Result<Self, RequestBuilderError> {
         Ok(Self {
             api_key,
             endpoint_configuration,
             compression,
             trace_encoder: DatadogTracesEncoder { max_size },
+            compute_apm_stats,
         })
     }
 }
@@ -79,6 +83,7 @@ pub struct RequestMetadata {
     endpoint: DatadogTracesEndpoint,
     finalizers: EventFinalizers,
     uncompressed_size: usize,
+    apm_stats: Option<Aggregator>,
 }
 
 impl IncrementalRequestBuilder<(PartitionKey, Vec<Event>)> for DatadogTracesRequestBuilder {
@@ -91,7 +96,7 @@ impl IncrementalRequestBuilder<(PartitionKey, Vec<Event>)> for DatadogTracesRequ
         &mut self,
         input: (PartitionKey, Vec<Event>),
     ) -> Vec<Result<(Self::Metadata, Self::Payload), Self::Error>> {
-        let (mut key, events) = input;
+        let (key, events) = input;
         let mut results = Vec::new();
         let n = events.len();
 
@@ -105,15 +110,24 @@ impl IncrementalRequestBuilder<(PartitionKey, Vec<Event>)> for DatadogTracesRequ
             .for_each(|r| match r {
                 Ok((payload, mut processed)) => {
                     let uncompressed_size = payload.len();
+                    // The stats of the traces are only aggregated once the request is delivered.
+                    let apm_stats = self.compute_apm_stats.then(|| {
+                        let mut aggregator = Aggregator::default();
+                        for trace in &processed {
+                            aggregator.handle_trace(&key, trace);
+                        }
+                        aggregator
+                    });
                     let metadata = RequestMetadata {
                         api_key: key
                             .api_key
-                            .take()
+                            .clone()
                             .unwrap_or_else(|| Arc::clone(&self.api_key)),
                         batch_size: n,
                         endpoint: DatadogTracesEndpoint::Traces,
                         finalizers: processed.take_finalizers(),
                         uncompressed_size,
+                        apm_stats,
                     };
                     let mut compressor = Compressor::from(self.compression);
                     match compressor.write_all(&payload) {
@@ -145,6 +159,7 @@ impl IncrementalRequestBuilder<(PartitionKey, Vec<Event>)> for DatadogTracesRequ
             headers.insert("Content-Encoding".to_string(), ce.to_string());
         }
         TraceApiRequest {
+            api_key: metadata.api_key,
             batch_size: metadata.batch_size,
             body: payload,
             headers,
@@ -153,6 +168,7 @@ impl IncrementalRequestBuilder<(PartitionKey, Vec<Event>)> for DatadogTracesRequ
                 .endpoint_configuration
                 .get_uri_for_endpoint(metadata.endpoint),
             uncompressed_size: metadata.uncompressed_size,
+            apm_stats: metadata.apm_stats,
         }
     }
 }
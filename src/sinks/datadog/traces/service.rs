@@ -1,5 +1,6 @@
 use std::{
     collections::BTreeMap,
+    sync::Arc,
     task::{Context, Poll},
 };
 
@@ -17,6 +18,7 @@ use vector_core::{
     stream::DriverResponse,
 };
 
+use super::apm_stats::Aggregator;
 use crate::{
     http::{BuildRequestSnafu, CallRequestSnafu, HttpClient, HttpError},
     sinks::util::retries::{RetryAction, RetryLogic},
@@ -57,12 +59,15 @@ impl RetryLogic for TraceApiRetry {
 
 #[derive(Debug, Clone)]
 pub struct TraceApiRequest {
+    pub api_key: Arc<str>,
     pub batch_size: usize,
     pub body: Bytes,
     pub headers: BTreeMap<String, String>,
     pub finalizers: EventFinalizers,
     pub uri: Uri,
     pub uncompressed_size: usize,
+    /// The APM stats of the traces of the request, if they are computed.
+    pub apm_stats: Option<Aggregator>,
 }
 
 impl TraceApiRequest {
@@ -97,6 +102,12 @@ pub struct TraceApiResponse {
     protocol: String,
 }
 
+impl TraceApiResponse {
+    pub const fn status_code(&self) -> StatusCode {
+        self.status_code
+    }
+}
+
 impl DriverResponse for TraceApiResponse {
     fn event_status(&self) -> EventStatus {
         if self.status_code.is_success() {
@@ -1,6 +1,7 @@
 use std::{fmt::Debug, sync::Arc};
 
 use async_trait::async_trait;
+use futures::{channel::oneshot, future};
 use futures_util::{
     stream::{self, BoxStream},
     StreamExt,
@@ -15,7 +16,7 @@ use vector_core::{
     stream::{BatcherSettings, DriverResponse},
 };
 
-use super::service::TraceApiRequest;
+use super::{apm_stats::ApmStatsFlusher, service::TraceApiRequest};
 use crate::{
     config::SinkContext,
     internal_events::DatadogTracesEncodingError,
@@ -39,24 +40,20 @@ pub(crate) struct PartitionKey {
 
 impl Partitioner for EventPartitioner {
     type Item = Event;
-    type Key = PartitionKey;
+    // Events that aren't traces have no partition.
+    type Key = Option<PartitionKey>;
 
     fn partition(&self, item: &Self::Item) -> Self::Key {
         match item {
-            Event::Metric(_) => {
-                panic!("unexpected metric");
-            }
-            Event::Log(_) => {
-                panic!("unexpected log");
-            }
-            Event::Trace(t) => PartitionKey {
+            Event::Metric(_) | Event::Log(_) => None,
+            Event::Trace(t) => Some(PartitionKey {
                 api_key: item.metadata().datadog_api_key().clone(),
                 env: t.get("env").map(|s| s.to_string_lossy()),
                 hostname: t.get(log_schema().host_key()).map(|s| s.to_string_lossy()),
                 agent_version: t.get("agent_version").map(|s| s.to_string_lossy()),
                 target_tps: t.get("target_tps").and_then(|tps| tps.as_integer()),
                 error_tps: t.get("error_tps").and_then(|tps| tps.as_integer()),
-            },
+            }),
         }
     }
 }
@@ -66,6 +63,7 @@ pub struct TracesSink<S> {
     acker: Acker,
     request_builder: DatadogTracesRequestBuilder,
     batch_settings: BatcherSettings,
+    apm_stats_flusher: Option<ApmStatsFlusher>,
 }
 
 impl<S> TracesSink<S>
@@ -80,18 +78,28 @@ where
         service: S,
         request_builder: DatadogTracesRequestBuilder,
         batch_settings: BatcherSettings,
+        apm_stats_flusher: Option<ApmStatsFlusher>,
     ) -> Self {
         TracesSink {
             service,
             acker: cx.acker(),
             request_builder,
             batch_settings,
+            apm_stats_flusher,
         }
     }
 
     async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let (flusher_shutdown, flusher_shutdown_rx) = oneshot::channel();
+        let flusher = self
+            .apm_stats_flusher
+            .map(|flusher| tokio::spawn(flusher.run(flusher_shutdown_rx)));
+
         let sink = input
             .batched_partitioned(EventPartitioner, self.batch_settings)
+            // The topology sends the events that aren't traces through the dropped output of the
+            // sink, so they never get here.
+            .filter_map(|(key, events)| future::ready(key.map(|key| (key, events))))
             .incremental_request_builder(self.request_builder)
             .flat_map(stream::iter)
             .filter_map(|request| async move {
@@ -110,7 +118,15 @@ where
             })
             .into_driver(self.service, self.acker);
 
-        sink.run().await
+        let result = sink.run().await;
+
+        // Once all the traces were delivered, the stats of the buckets that are still open are sent.
+        let _ = flusher_shutdown.send(());
+        if let Some(flusher) = flusher {
+            let _ = flusher.await;
+        }
+
+        result
     }
 }
 
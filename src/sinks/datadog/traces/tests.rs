@@ -1,7 +1,8 @@
-use std::{collections::BTreeMap, sync::Arc};
+use std::{collections::BTreeMap, io::Read, sync::Arc};
 
 use bytes::Bytes;
 use chrono::{TimeZone, Utc};
+use flate2::read::GzDecoder;
 use futures::{channel::mpsc::Receiver, stream, StreamExt};
 use hyper::StatusCode;
 use indoc::indoc;
@@ -13,7 +14,7 @@ use crate::{
     config::SinkConfig,
     event::{TraceEvent, Value},
     sinks::{
-        datadog::traces::DatadogTracesConfig,
+        datadog::traces::{apm_stats::StatsPayload, DatadogTracesConfig},
         util::test::{build_test_server_status, load_sink},
     },
     test_util::{map_event_batch_stream, next_addr},
@@ -28,6 +29,7 @@ async fn start_test(
     batch_status: BatchStatus,
     http_status_code: StatusCode,
     events: Vec<Event>,
+    compute_apm_stats: bool,
 ) -> Receiver<(http::request::Parts, Bytes)> {
     let addr = next_addr();
     let config = format!(
@@ -35,8 +37,9 @@ async fn start_test(
             default_api_key = "atoken"
             compression = "none"
             endpoint = "http://{}"
+            compute_apm_stats = {}
         "#},
-        addr
+        addr, compute_apm_stats
     );
     let (config, cx) = load_sink::<DatadogTracesConfig>(&config).unwrap();
     let (sink, _) = config.build(cx).await.unwrap();
@@ -121,7 +124,7 @@ async fn smoke() {
         .set_datadog_api_key(Some(Arc::from("a_key")));
 
     let events = vec![Event::Trace(t)];
-    let rx = start_test(BatchStatus::Delivered, StatusCode::OK, events).await;
+    let rx = start_test(BatchStatus::Delivered, StatusCode::OK, events, false).await;
 
     // We only take 1 elements as the trace & the APM transaction shall be
     // encoded & emitted in the same payload
@@ -145,3 +148,66 @@ async fn smoke() {
     assert_eq!(chunk.spans.len(), 1);
     validate_simple_span(chunk.spans.pop().unwrap());
 }
+
+#[tokio::test]
+async fn apm_stats() {
+    let events = vec![Event::Trace(simple_trace_event())];
+    let rx = start_test(BatchStatus::Delivered, StatusCode::OK, events, true).await;
+
+    // The stats are sent once the sink shuts down, after the traces
+    let output = rx.take(2).collect::<Vec<_>>().await;
+    let (parts, body) = output
+        .into_iter()
+        .find(|(parts, _)| parts.uri.path() == "/api/v0.2/stats")
+        .expect("no stats payload sent");
+    assert_eq!(
+        parts.headers.get("Content-Type").unwrap(),
+        "application/msgpack"
+    );
+    assert_eq!(parts.headers.get("Content-Encoding").unwrap(), "gzip");
+    assert_eq!(parts.headers.get("DD-API-KEY").unwrap(), "atoken");
+
+    let mut decompressed = Vec::new();
+    GzDecoder::new(&body[..])
+        .read_to_end(&mut decompressed)
+        .unwrap();
+    let payload = rmp_serde::from_slice::<StatsPayload>(&decompressed).unwrap();
+    assert_eq!(payload.agent_hostname, "a_host");
+    assert_eq!(payload.agent_env, "an_env");
+    assert_eq!(payload.stats.len(), 1);
+    assert_eq!(payload.stats[0].stats.len(), 1);
+    let stats = &payload.stats[0].stats[0].stats;
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].service, "a_service");
+    assert_eq!(stats[0].resource, "a_resource");
+    assert_eq!(stats[0].hits, 1);
+    assert_eq!(stats[0].errors, 1);
+    assert_eq!(stats[0].duration, 1000);
+}
+
+fn stats_requests(requests: &[(http::request::Parts, Bytes)]) -> usize {
+    requests
+        .iter()
+        .filter(|(parts, _)| parts.uri.path() == "/api/v0.2/stats")
+        .count()
+}
+
+#[tokio::test]
+async fn apm_stats_are_opt_in() {
+    let events = vec![Event::Trace(simple_trace_event())];
+    let rx = start_test(BatchStatus::Delivered, StatusCode::OK, events, false).await;
+
+    let requests = rx.collect::<Vec<_>>().await;
+    assert_eq!(requests.len(), 1);
+    assert_eq!(stats_requests(&requests), 0);
+}
+
+#[tokio::test]
+async fn apm_stats_skip_undelivered_traces() {
+    let events = vec![Event::Trace(simple_trace_event())];
+    let rx = start_test(BatchStatus::Rejected, StatusCode::BAD_REQUEST, events, true).await;
+
+    let requests = rx.collect::<Vec<_>>().await;
+    assert_eq!(requests.len(), 1);
+    assert_eq!(stats_requests(&requests), 0);
+}
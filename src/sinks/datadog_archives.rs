@@ -552,6 +552,9 @@ impl RequestBuilder<(String, Vec<Event>)> for DatadogS3RequestBuilder {
                 server_side_encryption: s3_options.server_side_encryption,
                 ssekms_key_id: s3_options.ssekms_key_id,
                 storage_class: s3_options.storage_class,
+                object_lock_mode: None,
+                object_lock_retain_days: None,
+                object_lock_legal_hold: None,
                 tags: s3_options.tags,
                 content_encoding: None,
                 content_type: None,
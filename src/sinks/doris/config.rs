@@ -0,0 +1,320 @@
+use futures::FutureExt;
+use http::{
+    header::{HeaderName, HeaderValue},
+    Request, StatusCode,
+};
+use hyper::Body;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+
+use super::{
+    encoder::StreamLoadEncoder, retry::DorisRetryLogic, service::StreamLoadService, sink::DorisSink,
+};
+use crate::{
+    config::{AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext},
+    http::{Auth, HttpClient, MaybeAuth},
+    sinks::{
+        util::{
+            encoding::{EncodingConfigWithDefault, EncodingConfiguration},
+            BatchConfig, SinkBatchSettings, TowerRequestConfig, UriSerde,
+        },
+        Healthcheck, HealthcheckError, VectorSink,
+    },
+    tls::{TlsConfig, TlsSettings},
+};
+
+/// The longest label accepted by the stream load API.
+const MAX_LABEL_LENGTH: usize = 128;
+
+/// The length of the suffix appended to the prefix of the labels, a hyphenated UUID and the
+/// underscore separating them.
+const LABEL_SUFFIX_LENGTH: usize = 37;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DorisDefaultBatchSettings;
+
+impl SinkBatchSettings for DorisDefaultBatchSettings {
+    const MAX_EVENTS: Option<usize> = None;
+    const MAX_BYTES: Option<usize> = Some(10_000_000);
+    const TIMEOUT_SECS: f64 = 1.0;
+}
+
+#[derive(Debug, Snafu, PartialEq)]
+pub(super) enum BuildError {
+    #[snafu(display("`columns` must be set for the `csv` format"))]
+    ColumnsMissing,
+    #[snafu(display(
+        "`label_prefix` must be at most {} characters, of letters, digits, `-` and `_`",
+        max_length
+    ))]
+    InvalidLabelPrefix { max_length: usize },
+    #[snafu(display("Invalid header `{}`", name))]
+    InvalidHeader { name: String },
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum Encoding {
+    #[derivative(Default)]
+    Default,
+}
+
+/// The format of the data loaded into the table.
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Copy, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum StreamLoadFormat {
+    /// Events are loaded as a JSON array of objects, whose fields are matched with the columns.
+    #[derivative(Default)]
+    Json,
+    /// Events are loaded as rows of the `columns`, separated by `\x01`.
+    Csv,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DorisConfig {
+    /// The HTTP endpoint of a frontend, which redirects the loads to a backend.
+    pub endpoint: UriSerde,
+    pub database: String,
+    pub table: String,
+    #[serde(default)]
+    pub format: StreamLoadFormat,
+    /// The columns the values of the fields are loaded into, in order, for the `csv` format.
+    pub columns: Option<Vec<String>>,
+    #[serde(default = "default_label_prefix")]
+    pub label_prefix: String,
+    /// Additional headers sent with each load, such as `max_filter_ratio` or `timeout`.
+    #[serde(default)]
+    pub headers: IndexMap<String, String>,
+    #[serde(
+        skip_serializing_if = "crate::serde::skip_serializing_if_default",
+        default
+    )]
+    pub encoding: EncodingConfigWithDefault<Encoding>,
+    #[serde(default)]
+    pub batch: BatchConfig<DorisDefaultBatchSettings>,
+    pub auth: Option<Auth>,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+    pub tls: Option<TlsConfig>,
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+fn default_label_prefix() -> String {
+    "vector".into()
+}
+
+impl GenerateConfig for DorisConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"endpoint = "http://localhost:8030"
+            database = "logs"
+            table = "events""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "doris")]
+impl SinkConfig for DorisConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let auth = self.auth.choose_one(&self.endpoint.auth)?;
+        let endpoint = self.endpoint.with_default_parts();
+        let columns = self.columns()?;
+        let headers = self.headers(columns.as_deref())?;
+        validate_label_prefix(&self.label_prefix)?;
+
+        let batch_settings = self.batch.into_batcher_settings()?;
+        let tls_settings = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls_settings, &cx.proxy)?;
+
+        let healthcheck = healthcheck(client.clone(), endpoint.clone(), auth.clone()).boxed();
+
+        let uri = endpoint
+            .append_path(&format!(
+                "api/{}/{}/_stream_load",
+                self.database, self.table
+            ))?
+            .uri;
+        let service = StreamLoadService::new(client, uri, auth, headers);
+        let encoder = StreamLoadEncoder::new(self.format, columns, self.encoding.clone());
+
+        let sink = DorisSink::new(
+            cx.acker(),
+            batch_settings,
+            encoder,
+            self.label_prefix.clone(),
+            service,
+            self.request,
+            DorisRetryLogic,
+        );
+        Ok((VectorSink::from_event_streamsink(sink), healthcheck))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "doris"
+    }
+
+    fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
+        Some(&self.acknowledgements)
+    }
+}
+
+impl DorisConfig {
+    fn columns(&self) -> Result<Option<Vec<String>>, BuildError> {
+        match (self.format, &self.columns) {
+            (StreamLoadFormat::Csv, None) => Err(BuildError::ColumnsMissing),
+            (StreamLoadFormat::Csv, Some(columns)) if columns.is_empty() => {
+                Err(BuildError::ColumnsMissing)
+            }
+            (StreamLoadFormat::Csv, Some(columns)) => Ok(Some(columns.clone())),
+            (StreamLoadFormat::Json, _) => Ok(None),
+        }
+    }
+
+    /// The headers sent with each load, besides the label, which describe the format of the data.
+    fn headers(
+        &self,
+        columns: Option<&[String]>,
+    ) -> Result<Vec<(HeaderName, HeaderValue)>, BuildError> {
+        let mut headers = match columns {
+            Some(columns) => vec![
+                ("format".to_owned(), "csv".to_owned()),
+                ("column_separator".to_owned(), "\\x01".to_owned()),
+                ("columns".to_owned(), columns.join(",")),
+            ],
+            None => vec![
+                ("format".to_owned(), "json".to_owned()),
+                ("strip_outer_array".to_owned(), "true".to_owned()),
+            ],
+        };
+        headers.extend(
+            self.headers
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone())),
+        );
+
+        headers
+            .into_iter()
+            .map(|(name, value)| {
+                let header_name = HeaderName::from_bytes(name.as_bytes());
+                let header_value = HeaderValue::from_str(&value);
+                match (header_name, header_value) {
+                    (Ok(name), Ok(value)) => Ok((name, value)),
+                    _ => Err(BuildError::InvalidHeader { name }),
+                }
+            })
+            .collect()
+    }
+}
+
+fn validate_label_prefix(prefix: &str) -> Result<(), BuildError> {
+    let max_length = MAX_LABEL_LENGTH - LABEL_SUFFIX_LENGTH;
+    if prefix.is_empty()
+        || prefix.len() > max_length
+        || !prefix
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        Err(BuildError::InvalidLabelPrefix { max_length })
+    } else {
+        Ok(())
+    }
+}
+
+async fn healthcheck(
+    client: HttpClient,
+    endpoint: UriSerde,
+    auth: Option<Auth>,
+) -> crate::Result<()> {
+    let uri = endpoint.append_path("api/health")?.uri;
+    let mut request = Request::get(uri).body(Body::empty()).unwrap();
+
+    if let Some(auth) = &auth {
+        auth.apply(&mut request);
+    }
+
+    let response = client.send(request).await?;
+
+    match response.status() {
+        StatusCode::OK => Ok(()),
+        status => Err(HealthcheckError::UnexpectedStatus { status }.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(config: &str) -> DorisConfig {
+        toml::from_str(config).unwrap()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<DorisConfig>();
+    }
+
+    #[test]
+    fn csv_requires_columns() {
+        let config = config(
+            r#"
+            endpoint = "http://localhost:8030"
+            database = "logs"
+            table = "events"
+            format = "csv"
+            "#,
+        );
+        assert_eq!(config.columns(), Err(BuildError::ColumnsMissing));
+    }
+
+    #[test]
+    fn csv_headers() {
+        let config = config(
+            r#"
+            endpoint = "http://localhost:8030"
+            database = "logs"
+            table = "events"
+            format = "csv"
+            columns = ["timestamp", "message"]
+            headers.max_filter_ratio = "0.1"
+            "#,
+        );
+        let columns = config.columns().unwrap();
+        let headers = config.headers(columns.as_deref()).unwrap();
+        let headers = headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.to_str().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            headers,
+            vec![
+                ("format", "csv"),
+                ("column_separator", "\\x01"),
+                ("columns", "timestamp,message"),
+                ("max_filter_ratio", "0.1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn validates_label_prefix() {
+        assert!(validate_label_prefix("vector_logs-1").is_ok());
+        assert!(validate_label_prefix("").is_err());
+        assert!(validate_label_prefix("vector logs").is_err());
+        assert!(validate_label_prefix(&"a".repeat(92)).is_err());
+    }
+}
@@ -0,0 +1,170 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use snafu::Snafu;
+use vector_core::ByteSizeOf;
+
+use super::config::{Encoding, StreamLoadFormat};
+use crate::{
+    dropped_events,
+    event::{Event, EventFinalizers, EventStatus, Finalizable, LogEvent, Value},
+    internal_events::DorisEncodingError,
+    sinks::util::encoding::{EncodingConfigWithDefault, EncodingConfiguration},
+};
+
+/// The separator of the columns of the `csv` format, which is unlikely to appear in the values.
+pub(super) const COLUMN_SEPARATOR: u8 = b'\x01';
+
+/// The separator of the rows of the `csv` format.
+pub(super) const LINE_DELIMITER: u8 = b'\n';
+
+#[derive(Debug, Snafu)]
+pub(super) enum EncodingError {
+    #[snafu(display("The value of `{}` contains the column or row separator", column))]
+    Separator { column: String },
+    #[snafu(display("Failed to encode the event as JSON: {}", source))]
+    Json { source: serde_json::Error },
+}
+
+/// An event encoded as a row of the loaded data, before it's batched with the other rows.
+pub(super) struct EncodedRow {
+    pub(super) payload: Bytes,
+    pub(super) finalizers: EventFinalizers,
+    pub(super) event_byte_size: usize,
+}
+
+impl ByteSizeOf for EncodedRow {
+    // `ByteSizeOf` is used by the batcher, to limit the size of the loads.
+    fn size_of(&self) -> usize {
+        self.payload.len()
+    }
+
+    fn allocated_bytes(&self) -> usize {
+        0
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct StreamLoadEncoder {
+    format: StreamLoadFormat,
+    /// The columns the events are encoded as rows of, for the `csv` format.
+    columns: Option<Vec<String>>,
+    encoding: EncodingConfigWithDefault<Encoding>,
+}
+
+impl StreamLoadEncoder {
+    pub(super) const fn new(
+        format: StreamLoadFormat,
+        columns: Option<Vec<String>>,
+        encoding: EncodingConfigWithDefault<Encoding>,
+    ) -> Self {
+        Self {
+            format,
+            columns,
+            encoding,
+        }
+    }
+
+    pub(super) const fn format(&self) -> StreamLoadFormat {
+        self.format
+    }
+
+    /// Encodes an event as a row, rejecting it if it can't be encoded.
+    pub(super) fn encode_event(&self, mut event: Event) -> Option<EncodedRow> {
+        self.encoding.apply_rules(&mut event);
+        let event_byte_size = event.size_of();
+        let mut log = event.into_log();
+
+        let payload = match &self.columns {
+            Some(columns) => encode_csv_row(&log, columns),
+            None => crate::serde::json::to_bytes(&log)
+                .map(BytesMut::freeze)
+                .map_err(|source| EncodingError::Json { source }),
+        };
+        match payload {
+            Ok(payload) => Some(EncodedRow {
+                payload,
+                finalizers: log.take_finalizers(),
+                event_byte_size,
+            }),
+            Err(error) => {
+                log.metadata().update_status(EventStatus::Rejected);
+                dropped_events::sample(&log.into(), &error);
+                emit!(DorisEncodingError { error });
+                None
+            }
+        }
+    }
+}
+
+/// Encodes the fields of the event as the values of the columns, with `\N` for the missing ones.
+fn encode_csv_row(log: &LogEvent, columns: &[String]) -> Result<Bytes, EncodingError> {
+    let mut row = BytesMut::new();
+    for (i, column) in columns.iter().enumerate() {
+        if i > 0 {
+            row.put_u8(COLUMN_SEPARATOR);
+        }
+        match log.get(column.as_str()) {
+            None | Some(Value::Null) => row.put_slice(b"\\N"),
+            Some(Value::Bytes(bytes)) => put_value(&mut row, bytes, column)?,
+            Some(value) => put_value(&mut row, value.to_string_lossy().as_bytes(), column)?,
+        }
+    }
+    Ok(row.freeze())
+}
+
+fn put_value(row: &mut BytesMut, value: &[u8], column: &str) -> Result<(), EncodingError> {
+    if value
+        .iter()
+        .any(|byte| *byte == COLUMN_SEPARATOR || *byte == LINE_DELIMITER)
+    {
+        return Err(EncodingError::Separator {
+            column: column.to_owned(),
+        });
+    }
+    row.put_slice(value);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn csv_encoder() -> StreamLoadEncoder {
+        StreamLoadEncoder::new(
+            StreamLoadFormat::Csv,
+            Some(vec!["host".into(), "count".into(), "missing".into()]),
+            EncodingConfigWithDefault::default(),
+        )
+    }
+
+    #[test]
+    fn encodes_csv_rows() {
+        let mut log = LogEvent::from("message");
+        log.insert("host", "example.com");
+        log.insert("count", 3);
+
+        let row = csv_encoder().encode_event(log.into()).unwrap();
+        assert_eq!(&row.payload[..], b"example.com\x013\x01\\N");
+    }
+
+    #[test]
+    fn rejects_values_with_separators() {
+        let mut log = LogEvent::from("message");
+        log.insert("host", "example\ncom");
+
+        assert!(csv_encoder().encode_event(log.into()).is_none());
+    }
+
+    #[test]
+    fn encodes_json_objects() {
+        let encoder = StreamLoadEncoder::new(
+            StreamLoadFormat::Json,
+            None,
+            EncodingConfigWithDefault::default(),
+        );
+        let mut log = LogEvent::default();
+        log.insert("message", "hello");
+
+        let row = encoder.encode_event(log.into()).unwrap();
+        assert_eq!(&row.payload[..], br#"{"message":"hello"}"#);
+    }
+}
@@ -0,0 +1,21 @@
+//! The sink loading events into Apache Doris and StarRocks tables, with the stream load API.
+//!
+//! Each batch is loaded in a transaction, identified by a label which is kept across the retries
+//! of the load, so the loads whose responses were lost aren't loaded twice.
+
+use crate::config::SinkDescription;
+
+mod config;
+mod encoder;
+mod retry;
+mod service;
+mod sink;
+
+#[cfg(test)]
+mod tests;
+
+pub use self::config::DorisConfig;
+
+inventory::submit! {
+    SinkDescription::new::<DorisConfig>("doris")
+}
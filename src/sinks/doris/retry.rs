@@ -0,0 +1,181 @@
+use bytes::Bytes;
+use http::{Response, StatusCode};
+use serde::Deserialize;
+
+use super::service::StreamLoadResponse;
+use crate::{
+    http::HttpError,
+    sinks::util::retries::{RetryAction, RetryLogic},
+};
+
+/// The messages of the failed loads which are caused by the load on the backends, rather than by
+/// the data, so the loads can succeed once retried.
+const BACKPRESSURE_MESSAGES: [&str; 8] = [
+    "too many versions",
+    "too many running",
+    "too_many_tasks",
+    "mem_limit_exceeded",
+    "memory limit",
+    "load channel",
+    "timeout",
+    "busy",
+];
+
+/// The states of a previous load with the same label which mean its rows were already loaded.
+const LOADED_JOB_STATUSES: [&str; 3] = ["FINISHED", "VISIBLE", "COMMITTED"];
+
+/// The result of a load, in the body of the response.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct StreamLoadResult {
+    status: String,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    existing_job_status: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum LoadOutcome {
+    /// The rows were loaded, by this request or by a previous one with the same label.
+    Loaded,
+    /// The load can be retried, once the backends have caught up.
+    Backpressure(String),
+    /// The load can't succeed.
+    Failed(String),
+}
+
+impl LoadOutcome {
+    pub(super) fn from_response(response: &Response<Bytes>) -> Self {
+        let status = response.status();
+        let body = String::from_utf8_lossy(response.body());
+
+        if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+            return Self::Backpressure(format!("{}: {}", status, body));
+        }
+        if !status.is_success() {
+            return Self::Failed(format!("{}: {}", status, body));
+        }
+
+        let result = match serde_json::from_slice::<StreamLoadResult>(response.body()) {
+            Ok(result) => result,
+            Err(_) => return Self::Failed(format!("unexpected response: {}", body)),
+        };
+        let message = result.message.unwrap_or_default();
+        match result.status.as_str() {
+            // The transaction of a load which timed out publishing is committed, and its rows
+            // become visible once it's published.
+            "Success" | "Publish Timeout" => Self::Loaded,
+            "Label Already Exists" => match result.existing_job_status.as_deref() {
+                Some(job_status) if LOADED_JOB_STATUSES.contains(&job_status) => Self::Loaded,
+                // The previous load is still running, and its outcome is known once it's done.
+                _ => {
+                    Self::Backpressure(format!("load with the same label is running: {}", message))
+                }
+            },
+            _ => {
+                let lowercase = message.to_lowercase();
+                if BACKPRESSURE_MESSAGES
+                    .iter()
+                    .any(|pattern| lowercase.contains(pattern))
+                {
+                    Self::Backpressure(message)
+                } else {
+                    Self::Failed(format!("{}: {}", result.status, message))
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct DorisRetryLogic;
+
+impl RetryLogic for DorisRetryLogic {
+    type Error = HttpError;
+    type Response = StreamLoadResponse;
+
+    fn is_retriable_error(&self, _error: &Self::Error) -> bool {
+        true
+    }
+
+    fn should_retry_response(&self, response: &Self::Response) -> RetryAction {
+        match &response.outcome {
+            LoadOutcome::Loaded => RetryAction::Successful,
+            LoadOutcome::Backpressure(message) => RetryAction::Retry(message.clone().into()),
+            LoadOutcome::Failed(message) => RetryAction::DontRetry(message.clone().into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(status: StatusCode, body: &'static str) -> LoadOutcome {
+        let response = Response::builder()
+            .status(status)
+            .body(Bytes::from(body))
+            .unwrap();
+        LoadOutcome::from_response(&response)
+    }
+
+    #[test]
+    fn successful_loads() {
+        assert_eq!(
+            outcome(
+                StatusCode::OK,
+                r#"{"TxnId": 1, "Label": "vector_1", "Status": "Success", "Message": "OK"}"#
+            ),
+            LoadOutcome::Loaded
+        );
+        assert_eq!(
+            outcome(
+                StatusCode::OK,
+                r#"{"Status": "Label Already Exists", "ExistingJobStatus": "FINISHED"}"#
+            ),
+            LoadOutcome::Loaded
+        );
+    }
+
+    #[test]
+    fn running_loads_with_the_same_label_are_retried() {
+        assert!(matches!(
+            outcome(
+                StatusCode::OK,
+                r#"{"Status": "Label Already Exists", "ExistingJobStatus": "RUNNING"}"#
+            ),
+            LoadOutcome::Backpressure(_)
+        ));
+    }
+
+    #[test]
+    fn load_channel_errors_are_retried() {
+        assert!(matches!(
+            outcome(
+                StatusCode::OK,
+                r#"{"Status": "Fail", "Message": "[E-235]too many versions, versions=1001"}"#
+            ),
+            LoadOutcome::Backpressure(_)
+        ));
+        assert!(matches!(
+            outcome(StatusCode::SERVICE_UNAVAILABLE, "unavailable"),
+            LoadOutcome::Backpressure(_)
+        ));
+    }
+
+    #[test]
+    fn data_errors_are_not_retried() {
+        assert!(matches!(
+            outcome(
+                StatusCode::OK,
+                r#"{"Status": "Fail", "Message": "too many filtered rows"}"#
+            ),
+            LoadOutcome::Failed(_)
+        ));
+        assert!(matches!(
+            outcome(StatusCode::UNAUTHORIZED, "unauthorized"),
+            LoadOutcome::Failed(_)
+        ));
+    }
+}
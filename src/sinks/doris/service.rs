@@ -0,0 +1,177 @@
+use std::{
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use http::{
+    header::{HeaderName, HeaderValue, CONTENT_LENGTH, EXPECT, LOCATION},
+    Request, Response, StatusCode, Uri,
+};
+use hyper::Body;
+use snafu::ResultExt;
+use tower::Service;
+use vector_common::internal_event::BytesSent;
+use vector_core::{
+    buffers::Ackable,
+    event::{EventFinalizers, EventStatus, Finalizable},
+    internal_event::EventsSent,
+    stream::DriverResponse,
+};
+
+use super::retry::LoadOutcome;
+use crate::http::{
+    get_http_scheme_from_uri, Auth, BuildRequestSnafu, CallRequestSnafu, HttpClient, HttpError,
+};
+
+/// A batch of rows, loaded in a single transaction identified by its label.
+#[derive(Clone, Debug)]
+pub(super) struct StreamLoadRequest {
+    /// The label of the load, which is kept across the retries of the request so a load which
+    /// succeeded, but whose response was lost, isn't loaded twice.
+    pub(super) label: String,
+    pub(super) payload: Bytes,
+    pub(super) finalizers: EventFinalizers,
+    pub(super) event_count: usize,
+    pub(super) events_byte_size: usize,
+}
+
+impl Ackable for StreamLoadRequest {
+    fn ack_size(&self) -> usize {
+        self.event_count
+    }
+}
+
+impl Finalizable for StreamLoadRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        std::mem::take(&mut self.finalizers)
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct StreamLoadResponse {
+    pub(super) outcome: LoadOutcome,
+    pub(super) event_count: usize,
+    pub(super) events_byte_size: usize,
+    pub(super) byte_size: usize,
+    pub(super) protocol: &'static str,
+}
+
+impl DriverResponse for StreamLoadResponse {
+    fn event_status(&self) -> EventStatus {
+        match self.outcome {
+            LoadOutcome::Loaded => EventStatus::Delivered,
+            LoadOutcome::Backpressure(_) => EventStatus::Errored,
+            LoadOutcome::Failed(_) => EventStatus::Rejected,
+        }
+    }
+
+    fn events_sent(&self) -> EventsSent {
+        EventsSent {
+            count: self.event_count,
+            byte_size: self.events_byte_size,
+            output: None,
+        }
+    }
+
+    fn bytes_sent(&self) -> Option<BytesSent> {
+        Some(BytesSent {
+            byte_size: self.byte_size,
+            protocol: self.protocol,
+        })
+    }
+}
+
+#[derive(Clone)]
+pub(super) struct StreamLoadService {
+    client: HttpClient,
+    uri: Uri,
+    auth: Option<Auth>,
+    headers: Arc<Vec<(HeaderName, HeaderValue)>>,
+}
+
+impl StreamLoadService {
+    pub(super) fn new(
+        client: HttpClient,
+        uri: Uri,
+        auth: Option<Auth>,
+        headers: Vec<(HeaderName, HeaderValue)>,
+    ) -> Self {
+        Self {
+            client,
+            uri,
+            auth,
+            headers: Arc::new(headers),
+        }
+    }
+
+    fn build_request(
+        &self,
+        uri: &Uri,
+        request: &StreamLoadRequest,
+    ) -> Result<Request<Body>, HttpError> {
+        let mut builder = Request::put(uri)
+            .header(EXPECT, "100-continue")
+            .header(CONTENT_LENGTH, request.payload.len())
+            .header("label", request.label.as_str());
+        for (name, value) in self.headers.iter() {
+            builder = builder.header(name, value);
+        }
+
+        let mut http_request = builder
+            .body(Body::from(request.payload.clone()))
+            .context(BuildRequestSnafu)?;
+        if let Some(auth) = &self.auth {
+            auth.apply(&mut http_request);
+        }
+        Ok(http_request)
+    }
+}
+
+impl Service<StreamLoadRequest> for StreamLoadService {
+    type Response = StreamLoadResponse;
+    type Error = HttpError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: StreamLoadRequest) -> Self::Future {
+        let service = self.clone();
+
+        Box::pin(async move {
+            let http_request = service.build_request(&service.uri, &request)?;
+            let mut response = service.client.send(http_request).await?;
+
+            // The frontends redirect the loads to one of the backends, and the redirect has to be
+            // followed with the same headers and body.
+            if response.status() == StatusCode::TEMPORARY_REDIRECT {
+                let location = response
+                    .headers()
+                    .get(LOCATION)
+                    .and_then(|location| location.to_str().ok())
+                    .and_then(|location| location.parse::<Uri>().ok());
+                if let Some(location) = location {
+                    let http_request = service.build_request(&location, &request)?;
+                    response = service.client.send(http_request).await?;
+                }
+            }
+
+            let (parts, body) = response.into_parts();
+            let body = hyper::body::to_bytes(body)
+                .await
+                .context(CallRequestSnafu)?;
+            let http_response = Response::from_parts(parts, body);
+
+            Ok(StreamLoadResponse {
+                outcome: LoadOutcome::from_response(&http_response),
+                event_count: request.event_count,
+                events_byte_size: request.events_byte_size,
+                byte_size: request.payload.len(),
+                protocol: get_http_scheme_from_uri(&service.uri),
+            })
+        })
+    }
+}
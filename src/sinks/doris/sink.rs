@@ -0,0 +1,145 @@
+use bytes::{BufMut, BytesMut};
+use futures::{future, stream::BoxStream};
+use futures_util::StreamExt;
+use uuid::Uuid;
+use vector_core::{buffers::Acker, sink::StreamSink, stream::BatcherSettings};
+
+use super::{
+    config::StreamLoadFormat,
+    encoder::{EncodedRow, StreamLoadEncoder, LINE_DELIMITER},
+    retry::DorisRetryLogic,
+    service::{StreamLoadRequest, StreamLoadService},
+};
+use crate::{
+    event::{Event, EventFinalizers},
+    sinks::util::{builder::SinkBuilderExt, ServiceBuilderExt, TowerRequestConfig},
+};
+
+pub(super) struct DorisSink {
+    acker: Acker,
+    batch_settings: BatcherSettings,
+    encoder: StreamLoadEncoder,
+    label_prefix: String,
+    service: StreamLoadService,
+    request: TowerRequestConfig,
+    retry_logic: DorisRetryLogic,
+}
+
+impl DorisSink {
+    pub(super) const fn new(
+        acker: Acker,
+        batch_settings: BatcherSettings,
+        encoder: StreamLoadEncoder,
+        label_prefix: String,
+        service: StreamLoadService,
+        request: TowerRequestConfig,
+        retry_logic: DorisRetryLogic,
+    ) -> Self {
+        Self {
+            acker,
+            batch_settings,
+            encoder,
+            label_prefix,
+            service,
+            request,
+            retry_logic,
+        }
+    }
+
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let request = self.request.unwrap_with(&TowerRequestConfig::default());
+        let service = tower::ServiceBuilder::new()
+            .settings(request, self.retry_logic)
+            .service(self.service);
+
+        let encoder = self.encoder;
+        let format = encoder.format();
+        let label_prefix = self.label_prefix;
+
+        let sink = input
+            .filter_map(move |event| future::ready(encoder.encode_event(event)))
+            .batched(self.batch_settings.into_byte_size_config())
+            .map(move |rows| build_request(&label_prefix, format, rows))
+            .into_driver(service, self.acker);
+
+        sink.run().await
+    }
+}
+
+/// Joins the rows into the payload of a single load, with a new label.
+fn build_request(
+    label_prefix: &str,
+    format: StreamLoadFormat,
+    rows: Vec<EncodedRow>,
+) -> StreamLoadRequest {
+    let event_count = rows.len();
+    let mut finalizers = EventFinalizers::default();
+    let mut events_byte_size = 0;
+    let mut payload =
+        BytesMut::with_capacity(rows.iter().map(|row| row.payload.len() + 1).sum::<usize>() + 1);
+
+    if format == StreamLoadFormat::Json {
+        payload.put_u8(b'[');
+    }
+    for (i, row) in rows.into_iter().enumerate() {
+        if i > 0 {
+            payload.put_u8(match format {
+                StreamLoadFormat::Json => b',',
+                StreamLoadFormat::Csv => LINE_DELIMITER,
+            });
+        }
+        payload.put_slice(&row.payload);
+        finalizers.merge(row.finalizers);
+        events_byte_size += row.event_byte_size;
+    }
+    if format == StreamLoadFormat::Json {
+        payload.put_u8(b']');
+    }
+
+    StreamLoadRequest {
+        label: format!("{}_{}", label_prefix, Uuid::new_v4()),
+        payload: payload.freeze(),
+        finalizers,
+        event_count,
+        events_byte_size,
+    }
+}
+
+#[async_trait::async_trait]
+impl StreamSink<Event> for DorisSink {
+    async fn run(mut self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::*;
+
+    fn rows() -> Vec<EncodedRow> {
+        vec!["a", "b"]
+            .into_iter()
+            .map(|row| EncodedRow {
+                payload: Bytes::from(row),
+                finalizers: EventFinalizers::default(),
+                event_byte_size: 1,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn joins_json_rows() {
+        let request = build_request("vector", StreamLoadFormat::Json, rows());
+        assert_eq!(&request.payload[..], b"[a,b]");
+        assert_eq!(request.event_count, 2);
+        assert!(request.label.starts_with("vector_"));
+    }
+
+    #[test]
+    fn joins_csv_rows() {
+        let request = build_request("vector", StreamLoadFormat::Csv, rows());
+        assert_eq!(&request.payload[..], b"a\nb");
+    }
+}
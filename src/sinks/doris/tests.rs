@@ -0,0 +1,104 @@
+use futures::stream;
+use wiremock::{
+    matchers::{header, header_exists, method, path},
+    Mock, MockServer, Request, ResponseTemplate,
+};
+
+use super::DorisConfig;
+use crate::{
+    config::{SinkConfig, SinkContext},
+    event::{Event, LogEvent},
+    test_util::components::{run_and_assert_sink_compliance, HTTP_SINK_TAGS},
+};
+
+fn config(endpoint: &str) -> DorisConfig {
+    toml::from_str(&format!(
+        r#"
+        endpoint = "{}"
+        database = "logs"
+        table = "events"
+        request.retry_initial_backoff_secs = 1
+        "#,
+        endpoint
+    ))
+    .unwrap()
+}
+
+fn events() -> Vec<Event> {
+    (0..3)
+        .map(|i| LogEvent::from(format!("message {}", i)).into())
+        .collect()
+}
+
+fn label(request: &Request) -> Option<String> {
+    request
+        .headers
+        .iter()
+        .find(|(name, _)| name.as_str() == "label")
+        .map(|(_, values)| values.last().as_str().to_owned())
+}
+
+#[tokio::test]
+async fn follows_redirects_to_backends() {
+    let server = MockServer::start().await;
+    Mock::given(method("PUT"))
+        .and(path("/api/logs/events/_stream_load"))
+        .respond_with(ResponseTemplate::new(307).insert_header(
+            "Location",
+            format!("{}/backend/api/logs/events/_stream_load", server.uri()).as_str(),
+        ))
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path("/backend/api/logs/events/_stream_load"))
+        .and(header("format", "json"))
+        .and(header("strip_outer_array", "true"))
+        .and(header_exists("label"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({ "Status": "Success", "Message": "OK" })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let (sink, _) = config(&server.uri())
+        .build(SinkContext::new_test())
+        .await
+        .unwrap();
+    run_and_assert_sink_compliance(sink, stream::iter(events()), &HTTP_SINK_TAGS).await;
+
+    let requests = server.received_requests().await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&requests[1].body).unwrap();
+    assert_eq!(body.as_array().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn retries_keep_the_label() {
+    let server = MockServer::start().await;
+    Mock::given(method("PUT"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "Status": "Fail",
+            "Message": "[E-235]too many versions, versions=1001",
+        })))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "Status": "Label Already Exists",
+            "ExistingJobStatus": "FINISHED",
+        })))
+        .mount(&server)
+        .await;
+
+    let (sink, _) = config(&server.uri())
+        .build(SinkContext::new_test())
+        .await
+        .unwrap();
+    run_and_assert_sink_compliance(sink, stream::iter(events()), &HTTP_SINK_TAGS).await;
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(label(&requests[0]), label(&requests[1]));
+}
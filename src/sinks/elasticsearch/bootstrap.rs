@@ -0,0 +1,229 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use http::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+
+use super::ElasticsearchCommon;
+use crate::http::HttpClient;
+
+#[derive(Debug, Snafu)]
+pub enum BootstrapError {
+    #[snafu(display("Unable to read {:?}: {}", path, source))]
+    ReadTemplate {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("Unable to parse {:?}: {}", path, source))]
+    ParseTemplate {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[snafu(display("Elasticsearch rejected {:?} with status {}: {}", path, status, body))]
+    Rejected {
+        path: String,
+        status: StatusCode,
+        body: String,
+    },
+}
+
+/// The resources created in the cluster when the sink starts, in order: the ILM policies, the
+/// component templates, then the index templates which may be composed of them.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct BootstrapConfig {
+    #[serde(default)]
+    pub ilm_policies: BTreeMap<String, TemplateSource>,
+    #[serde(default)]
+    pub component_templates: BTreeMap<String, TemplateSource>,
+    #[serde(default)]
+    pub index_templates: BTreeMap<String, TemplateSource>,
+    /// Replaces the existing resources, which are otherwise left as they are.
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+/// The body of a resource, as sent to the Elasticsearch API.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum TemplateSource {
+    File { path: PathBuf },
+    Inline { body: serde_json::Value },
+}
+
+impl TemplateSource {
+    fn load(&self) -> Result<serde_json::Value, BootstrapError> {
+        match self {
+            Self::File { path } => {
+                let body = std::fs::read(path).context(ReadTemplateSnafu { path })?;
+                serde_json::from_slice(&body).context(ParseTemplateSnafu { path })
+            }
+            Self::Inline { body } => Ok(body.clone()),
+        }
+    }
+}
+
+impl BootstrapConfig {
+    fn resources(&self) -> impl Iterator<Item = (String, &TemplateSource)> {
+        self.ilm_policies
+            .iter()
+            .map(|(name, source)| (format!("/_ilm/policy/{}", name), source))
+            .chain(
+                self.component_templates
+                    .iter()
+                    .map(|(name, source)| (format!("/_component_template/{}", name), source)),
+            )
+            .chain(
+                self.index_templates
+                    .iter()
+                    .map(|(name, source)| (format!("/_index_template/{}", name), source)),
+            )
+    }
+
+    /// Creates the resources missing from the cluster, or all of them if they are overwritten.
+    pub async fn run(
+        &self,
+        common: &ElasticsearchCommon,
+        client: &HttpClient,
+    ) -> crate::Result<()> {
+        for (path, source) in self.resources() {
+            if !self.overwrite {
+                let (status, _) = common
+                    .send_request(client, Method::GET, &path, None)
+                    .await?;
+                if status.is_success() {
+                    debug!(message = "Elasticsearch resource already exists.", %path);
+                    continue;
+                }
+            }
+
+            let body = source.load()?;
+            let (status, body) = common
+                .send_request(client, Method::PUT, &path, Some(&body))
+                .await?;
+            if !status.is_success() {
+                return Err(BootstrapError::Rejected {
+                    path,
+                    status,
+                    body: String::from_utf8_lossy(&body).into_owned(),
+                }
+                .into());
+            }
+            info!(message = "Created Elasticsearch resource.", %path);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_core::config::proxy::ProxyConfig;
+    use wiremock::{
+        matchers::{body_json, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::sinks::elasticsearch::ElasticsearchConfig;
+
+    async fn common(endpoint: String) -> ElasticsearchCommon {
+        let config = ElasticsearchConfig {
+            endpoint,
+            ..Default::default()
+        };
+        ElasticsearchCommon::parse_config(&config).await.unwrap()
+    }
+
+    #[test]
+    fn parses_sources() {
+        let config = toml::from_str::<BootstrapConfig>(
+            r#"
+            index_templates.logs.path = "/etc/vector/logs.json"
+            ilm_policies.logs.body.policy.phases.hot.actions.rollover.max_age = "1d"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.index_templates["logs"],
+            TemplateSource::File {
+                path: "/etc/vector/logs.json".into()
+            }
+        );
+        assert_eq!(
+            config.ilm_policies["logs"],
+            TemplateSource::Inline {
+                body: serde_json::json!({
+                    "policy": { "phases": { "hot": { "actions": { "rollover": { "max_age": "1d" } } } } }
+                })
+            }
+        );
+        assert!(!config.overwrite);
+    }
+
+    #[tokio::test]
+    async fn creates_missing_resources() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/_component_template/settings"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/_index_template/logs"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/_index_template/logs"))
+            .and(body_json(
+                serde_json::json!({ "index_patterns": ["logs-*"] }),
+            ))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/_component_template/settings"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let config = toml::from_str::<BootstrapConfig>(
+            r#"
+            component_templates.settings.body.template.settings.number_of_shards = 1
+            index_templates.logs.body.index_patterns = ["logs-*"]
+            "#,
+        )
+        .unwrap();
+        let client = HttpClient::new(None, &ProxyConfig::default()).unwrap();
+        config
+            .run(&common(server.uri()).await, &client)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn reports_rejected_resources() {
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/_ilm/policy/logs"))
+            .respond_with(ResponseTemplate::new(400).set_body_string("invalid policy"))
+            .mount(&server)
+            .await;
+
+        let config = toml::from_str::<BootstrapConfig>(
+            r#"
+            overwrite = true
+            ilm_policies.logs.body.policy = {}
+            "#,
+        )
+        .unwrap();
+        let client = HttpClient::new(None, &ProxyConfig::default()).unwrap();
+        let error = config
+            .run(&common(server.uri()).await, &client)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("invalid policy"));
+    }
+}
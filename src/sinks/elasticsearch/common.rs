@@ -1,12 +1,13 @@
 use std::collections::HashMap;
 use std::time::SystemTime;
 
-use aws_sigv4::http_request::{SignableRequest, SigningSettings};
+use aws_sigv4::http_request::{PayloadChecksumKind, SignableRequest, SigningSettings};
 use aws_sigv4::SigningParams;
 use aws_types::credentials::{ProvideCredentials, SharedCredentialsProvider};
 use aws_types::region::Region;
 use bytes::Bytes;
-use http::{StatusCode, Uri};
+use http::{Method, StatusCode, Uri};
+use serde::Deserialize;
 use snafu::ResultExt;
 
 use super::{InvalidHostSnafu, Request};
@@ -14,8 +15,8 @@ use crate::{
     http::{Auth, HttpClient, MaybeAuth},
     sinks::{
         elasticsearch::{
-            encoder::ElasticsearchEncoder, ElasticsearchAuth, ElasticsearchCommonMode,
-            ElasticsearchConfig, ParseError,
+            encoder::ElasticsearchEncoder, ElasticsearchApiVersion, ElasticsearchAuth,
+            ElasticsearchCommonMode, ElasticsearchConfig, ParseError,
         },
         util::{
             encoding::EncodingConfigFixed, http::RequestConfig, Compression, TowerRequestConfig,
@@ -43,6 +44,18 @@ pub struct ElasticsearchCommon {
     pub request: RequestConfig,
     pub query_params: HashMap<String, String>,
     pub metric_to_log: MetricToLog,
+    pub api_version: ElasticsearchApiVersion,
+    pub serverless: bool,
+}
+
+#[derive(Deserialize)]
+struct ClusterInfo {
+    version: ClusterVersion,
+}
+
+#[derive(Deserialize)]
+struct ClusterVersion {
+    number: String,
 }
 
 impl ElasticsearchCommon {
@@ -138,23 +151,116 @@ impl ElasticsearchCommon {
             region,
             tls_settings,
             metric_to_log,
+            api_version: config.api_version,
+            serverless: config.serverless,
         })
     }
 
-    pub async fn healthcheck(self, client: HttpClient) -> crate::Result<()> {
-        let mut builder = Request::get(format!("{}/_cluster/health", self.base_url));
+    /// Sends a request to the cluster, returning the status and body of its response.
+    pub async fn send_request(
+        &self,
+        client: &HttpClient,
+        method: Method,
+        path: &str,
+        body: Option<&serde_json::Value>,
+    ) -> crate::Result<(StatusCode, Bytes)> {
+        let mut builder = Request::builder()
+            .method(method)
+            .uri(format!("{}{}", self.base_url, path));
 
         if let Some(authorization) = &self.http_auth {
             builder = authorization.apply_builder(builder);
         }
-        let mut request = builder.body(Bytes::new())?;
+        let body = match body {
+            Some(body) => {
+                builder = builder.header("Content-Type", "application/json");
+                Bytes::from(serde_json::to_vec(body)?)
+            }
+            None => Bytes::new(),
+        };
+        let mut request = builder.body(body)?;
 
         if let Some(credentials_provider) = &self.aws_auth {
-            sign_request(&mut request, credentials_provider, &self.region).await?;
+            sign_request(
+                &mut request,
+                credentials_provider,
+                &self.region,
+                self.serverless,
+            )
+            .await?;
         }
         let response = client.send(request.map(hyper::Body::from)).await?;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        Ok((status, body))
+    }
+
+    /// Resolves the API version, querying the cluster if it's `auto`, and adapts the requests to
+    /// it.
+    pub async fn resolve_api_version(&mut self, client: &HttpClient) -> crate::Result<()> {
+        if self.api_version == ElasticsearchApiVersion::Auto {
+            match self.cluster_api_version(client).await {
+                Ok(api_version) => {
+                    debug!(
+                        message = "Determined the Elasticsearch API version.",
+                        ?api_version
+                    );
+                    self.api_version = api_version;
+                }
+                // The requests are left as configured, as with earlier versions of Vector.
+                Err(error) => warn!(
+                    message = "Failed to determine the Elasticsearch API version. Set `api_version` to adapt the requests to it.",
+                    %error
+                ),
+            }
+        }
+
+        match self.api_version {
+            ElasticsearchApiVersion::V6 if self.mode.as_data_stream_config().is_some() => {
+                return Err(ParseError::DataStreamsUnsupported.into());
+            }
+            ElasticsearchApiVersion::V8 if !self.suppress_type_name => {
+                if self.doc_type != "_doc" {
+                    warn!(
+                        message = "Mapping types are removed in Elasticsearch 8, `doc_type` is ignored.",
+                        doc_type = %self.doc_type,
+                    );
+                }
+                self.suppress_type_name = true;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    async fn cluster_api_version(
+        &self,
+        client: &HttpClient,
+    ) -> crate::Result<ElasticsearchApiVersion> {
+        let (status, body) = self.send_request(client, Method::GET, "/", None).await?;
+        if !status.is_success() {
+            return Err(HealthcheckError::UnexpectedStatus { status }.into());
+        }
+        let info = serde_json::from_slice::<ClusterInfo>(&body)?;
+        ElasticsearchApiVersion::from_version_number(&info.version.number).ok_or_else(|| {
+            format!(
+                "Invalid Elasticsearch version number {:?}",
+                info.version.number
+            )
+            .into()
+        })
+    }
+
+    pub async fn healthcheck(self, client: HttpClient) -> crate::Result<()> {
+        // Serverless collections don't expose the cluster APIs.
+        let path = if self.serverless {
+            "/_cat/indices"
+        } else {
+            "/_cluster/health"
+        };
+        let (status, _) = self.send_request(&client, Method::GET, path, None).await?;
 
-        match response.status() {
+        match status {
             StatusCode::OK => Ok(()),
             status => Err(HealthcheckError::UnexpectedStatus { status }.into()),
         }
@@ -165,16 +271,26 @@ pub async fn sign_request(
     request: &mut http::Request<Bytes>,
     credentials_provider: &SharedCredentialsProvider,
     region: &Option<Region>,
+    serverless: bool,
 ) -> crate::Result<()> {
+    let mut settings = SigningSettings::default();
+    let service_name = if serverless {
+        // OpenSearch Serverless requires the hash of the payload in the signed headers.
+        settings.payload_checksum_kind = PayloadChecksumKind::XAmzSha256;
+        "aoss"
+    } else {
+        "es"
+    };
+
     let signable_request = SignableRequest::from(&*request);
     let credentials = credentials_provider.provide_credentials().await?;
     let mut signing_params_builder = SigningParams::builder()
         .access_key(credentials.access_key_id())
         .secret_key(credentials.secret_access_key())
         .region(region.as_ref().map(|r| r.as_ref()).unwrap_or(""))
-        .service_name("es")
+        .service_name(service_name)
         .time(SystemTime::now())
-        .settings(SigningSettings::default());
+        .settings(settings);
 
     signing_params_builder.set_security_token(credentials.session_token());
 
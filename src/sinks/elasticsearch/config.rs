@@ -12,21 +12,23 @@ use crate::aws::RegionOrEndpoint;
 use crate::{
     config::{log_schema, AcknowledgementsConfig, DataType, Input, SinkConfig, SinkContext},
     event::{EventRef, LogEvent, Value},
-    http::HttpClient,
+    http::{HttpClient, HttpError},
     internal_events::TemplateRenderingError,
     sinks::{
         elasticsearch::{
             encoder::ElasticsearchEncoder,
             request_builder::ElasticsearchRequestBuilder,
             retry::ElasticsearchRetryLogic,
-            service::{ElasticsearchService, HttpRequestBuilder},
-            sink::ElasticsearchSink,
-            BatchActionTemplateSnafu, ElasticsearchAuth, ElasticsearchCommon,
-            ElasticsearchCommonMode, ElasticsearchMode, IndexTemplateSnafu,
+            service::{ElasticsearchResponse, ElasticsearchService, HttpRequestBuilder},
+            sink::{DocumentSettings, ElasticsearchSink},
+            BatchActionTemplateSnafu, BootstrapConfig, ElasticsearchApiVersion, ElasticsearchAuth,
+            ElasticsearchCommon, ElasticsearchCommonMode, ElasticsearchMode, IdTemplateSnafu,
+            IndexTemplateSnafu, ParseError, RoutingTemplateSnafu,
         },
         util::{
-            encoding::EncodingConfigFixed, http::RequestConfig, BatchConfig, Compression,
-            RealtimeSizeBasedDefaultBatchSettings, ServiceBuilderExt, TowerRequestConfig,
+            encoding::EncodingConfigFixed, http::RequestConfig, retries::RetryLogic, BatchConfig,
+            Compression, RealtimeSizeBasedDefaultBatchSettings, ServiceBuilderExt,
+            TowerRequestConfig,
         },
         Healthcheck, VectorSink,
     },
@@ -48,9 +50,13 @@ pub struct ElasticsearchConfig {
     #[serde(default)]
     pub suppress_type_name: bool,
     pub id_key: Option<String>,
+    pub id: Option<String>,
+    pub routing: Option<String>,
     pub pipeline: Option<String>,
     #[serde(default)]
     pub mode: ElasticsearchMode,
+    #[serde(default)]
+    pub api_version: ElasticsearchApiVersion,
 
     #[serde(default)]
     pub compression: Compression,
@@ -73,6 +79,11 @@ pub struct ElasticsearchConfig {
     pub bulk: Option<BulkConfig>,
     pub data_stream: Option<DataStreamConfig>,
     pub metrics: Option<MetricToLogConfig>,
+    pub bootstrap: Option<BootstrapConfig>,
+
+    /// Whether the endpoint is an OpenSearch Serverless collection, set by the `opensearch` sink.
+    #[serde(skip)]
+    pub serverless: bool,
 
     #[serde(
         default,
@@ -110,6 +121,27 @@ impl ElasticsearchConfig {
         Ok(Template::try_from(index.as_str()).context(IndexTemplateSnafu)?)
     }
 
+    pub fn document_settings(&self) -> crate::Result<DocumentSettings> {
+        if self.id.is_some() && self.id_key.is_some() {
+            return Err(ParseError::IdAndIdKey.into());
+        }
+        let id = self
+            .id
+            .as_deref()
+            .map(|value| Template::try_from(value).context(IdTemplateSnafu))
+            .transpose()?;
+        let routing = self
+            .routing
+            .as_deref()
+            .map(|value| Template::try_from(value).context(RoutingTemplateSnafu))
+            .transpose()?;
+        Ok(DocumentSettings {
+            id_key: self.id_key.clone(),
+            id,
+            routing,
+        })
+    }
+
     pub fn common_mode(&self) -> crate::Result<ElasticsearchCommonMode> {
         match self.mode {
             ElasticsearchMode::Bulk => {
@@ -132,6 +164,9 @@ impl ElasticsearchConfig {
 pub struct BulkConfig {
     pub action: Option<String>,
     pub index: Option<String>,
+    /// Creates the documents of the `update` actions when they don't exist.
+    #[serde(default)]
+    pub doc_as_upsert: bool,
 }
 
 impl BulkConfig {
@@ -298,19 +333,30 @@ impl DataStreamConfig {
     }
 }
 
-#[async_trait::async_trait]
-#[typetag::serde(name = "elasticsearch")]
-impl SinkConfig for ElasticsearchConfig {
-    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
-        let common = ElasticsearchCommon::parse_config(self).await?;
+impl ElasticsearchConfig {
+    /// Builds the sink, classifying the responses of the cluster with the given retry logic.
+    pub(crate) async fn build_with_retry_logic<L>(
+        &self,
+        cx: SinkContext,
+        retry_logic: L,
+    ) -> crate::Result<(VectorSink, Healthcheck)>
+    where
+        L: RetryLogic<Error = HttpError, Response = ElasticsearchResponse>,
+    {
+        let mut common = ElasticsearchCommon::parse_config(self).await?;
 
         let http_client = HttpClient::new(common.tls_settings.clone(), cx.proxy())?;
+        common.resolve_api_version(&http_client).await?;
+        if let Some(bootstrap) = &self.bootstrap {
+            bootstrap.run(&common, &http_client).await?;
+        }
         let batch_settings = self.batch.into_batcher_settings()?;
 
         // This is a bit ugly, but removes a String allocation on every event
         let mut encoding = self.encoding.clone();
         encoding.codec.doc_type = common.doc_type;
         encoding.codec.suppress_type_name = common.suppress_type_name;
+        encoding.codec.doc_as_upsert = self.bulk.as_ref().map_or(false, |bulk| bulk.doc_as_upsert);
 
         let request_builder = ElasticsearchRequestBuilder {
             compression: self.compression,
@@ -330,10 +376,11 @@ impl SinkConfig for ElasticsearchConfig {
             region: common.region,
             compression: self.compression,
             credentials_provider: common.aws_auth,
+            serverless: common.serverless,
         };
 
         let service = ServiceBuilder::new()
-            .settings(request_limits, ElasticsearchRetryLogic)
+            .settings(request_limits, retry_logic)
             .service(ElasticsearchService::new(http_client, http_request_builder));
 
         let sink = ElasticsearchSink {
@@ -344,7 +391,7 @@ impl SinkConfig for ElasticsearchConfig {
             acker: cx.acker(),
             metric_to_log: common.metric_to_log,
             mode: common.mode,
-            id_key_field: self.id_key.clone(),
+            document: self.document_settings()?,
         };
 
         let common = ElasticsearchCommon::parse_config(self).await?;
@@ -353,6 +400,15 @@ impl SinkConfig for ElasticsearchConfig {
         let stream = VectorSink::from_event_streamsink(sink);
         Ok((stream, healthcheck))
     }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "elasticsearch")]
+impl SinkConfig for ElasticsearchConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        self.build_with_retry_logic(cx, ElasticsearchRetryLogic)
+            .await
+    }
 
     fn input(&self) -> Input {
         Input::new(DataType::Metric | DataType::Log)
@@ -409,4 +465,47 @@ mod tests {
         assert!(matches!(config.mode, ElasticsearchMode::DataStream));
         assert!(config.data_stream.is_some());
     }
+
+    #[test]
+    fn parse_document_settings() {
+        let config = toml::from_str::<ElasticsearchConfig>(
+            r#"
+            endpoint = ""
+            id = "{{ user_id }}"
+            routing = "{{ tenant }}"
+            bulk.action = "update"
+            bulk.doc_as_upsert = true
+        "#,
+        )
+        .unwrap();
+        let document = config.document_settings().unwrap();
+        assert!(document.id.is_some());
+        assert!(document.routing.is_some());
+        assert!(config.bulk.unwrap().doc_as_upsert);
+
+        let config = toml::from_str::<ElasticsearchConfig>(
+            r#"
+            endpoint = ""
+            id = "{{ user_id }}"
+            id_key = "user_id"
+        "#,
+        )
+        .unwrap();
+        assert!(config.document_settings().is_err());
+    }
+
+    #[test]
+    fn parse_api_version() {
+        let config = toml::from_str::<ElasticsearchConfig>(
+            r#"
+            endpoint = ""
+            api_version = "v8"
+        "#,
+        )
+        .unwrap();
+        assert_eq!(config.api_version, ElasticsearchApiVersion::V8);
+
+        let config = toml::from_str::<ElasticsearchConfig>(r#"endpoint = """#).unwrap();
+        assert_eq!(config.api_version, ElasticsearchApiVersion::Auto);
+    }
 }
@@ -15,6 +15,7 @@ pub struct ProcessedEvent {
     pub bulk_action: BulkAction,
     pub log: LogEvent,
     pub id: Option<String>,
+    pub routing: Option<String>,
 }
 
 impl Finalizable for ProcessedEvent {
@@ -25,7 +26,10 @@ impl Finalizable for ProcessedEvent {
 
 impl ByteSizeOf for ProcessedEvent {
     fn allocated_bytes(&self) -> usize {
-        self.index.allocated_bytes() + self.log.allocated_bytes() + self.id.allocated_bytes()
+        self.index.allocated_bytes()
+            + self.log.allocated_bytes()
+            + self.id.allocated_bytes()
+            + self.routing.allocated_bytes()
     }
 }
 
@@ -33,6 +37,8 @@ impl ByteSizeOf for ProcessedEvent {
 pub struct ElasticsearchEncoder {
     pub doc_type: String,
     pub suppress_type_name: bool,
+    /// Creates the documents updated by the `update` action if they don't exist.
+    pub doc_as_upsert: bool,
 }
 
 impl Encoder<Vec<ProcessedEvent>> for ElasticsearchEncoder {
@@ -50,11 +56,21 @@ impl Encoder<Vec<ProcessedEvent>> for ElasticsearchEncoder {
                 &self.doc_type,
                 self.suppress_type_name,
                 &event.id,
+                &event.routing,
             )?;
             written_bytes +=
                 as_tracked_write::<_, _, io::Error>(writer, &event.log, |mut writer, log| {
                     writer.write_all(&[b'\n'])?;
-                    serde_json::to_writer(&mut writer, log)?;
+                    if event.bulk_action == BulkAction::Update {
+                        writer.write_all(br#"{"doc":"#)?;
+                        serde_json::to_writer(&mut writer, log)?;
+                        if self.doc_as_upsert {
+                            writer.write_all(br#","doc_as_upsert":true"#)?;
+                        }
+                        writer.write_all(b"}")?;
+                    } else {
+                        serde_json::to_writer(&mut writer, log)?;
+                    }
                     writer.write_all(&[b'\n'])?;
                     Ok(())
                 })?;
@@ -70,35 +86,25 @@ fn write_bulk_action(
     doc_type: &str,
     suppress_type: bool,
     id: &Option<String>,
+    routing: &Option<String>,
 ) -> std::io::Result<usize> {
-    as_tracked_write(
+    as_tracked_write::<_, _, io::Error>(
         writer,
-        (bulk_action, index, doc_type, id, suppress_type),
-        |writer, (bulk_action, index, doc_type, id, suppress_type)| match (id, suppress_type) {
-            (Some(id), true) => {
-                write!(
-                    writer,
-                    r#"{{"{}":{{"_index":"{}","_id":"{}"}}}}"#,
-                    bulk_action, index, id
-                )
+        (bulk_action, index, doc_type, id, routing, suppress_type),
+        |mut writer, (bulk_action, index, doc_type, id, routing, suppress_type)| {
+            write!(writer, r#"{{"{}":{{"_index":"{}""#, bulk_action, index)?;
+            if !suppress_type {
+                write!(writer, r#","_type":"{}""#, doc_type)?;
             }
-            (Some(id), false) => {
-                write!(
-                    writer,
-                    r#"{{"{}":{{"_index":"{}","_type":"{}","_id":"{}"}}}}"#,
-                    bulk_action, index, doc_type, id
-                )
+            if let Some(id) = id {
+                writer.write_all(br#","_id":"#)?;
+                serde_json::to_writer(&mut writer, id)?;
             }
-            (None, true) => {
-                write!(writer, r#"{{"{}":{{"_index":"{}"}}}}"#, bulk_action, index)
-            }
-            (None, false) => {
-                write!(
-                    writer,
-                    r#"{{"{}":{{"_index":"{}","_type":"{}"}}}}"#,
-                    bulk_action, index, doc_type
-                )
+            if let Some(routing) = routing {
+                writer.write_all(br#","_routing":"#)?;
+                serde_json::to_writer(&mut writer, routing)?;
             }
+            writer.write_all(b"}}")
         },
     )
 }
@@ -127,6 +133,7 @@ mod tests {
             "TYPE",
             true,
             &Some("ID".to_string()),
+            &None,
         );
 
         let value: serde_json::Value = serde_json::from_slice(&writer).unwrap();
@@ -148,7 +155,7 @@ mod tests {
     fn suppress_type_without_id() {
         let mut writer = Vec::new();
 
-        let _ = write_bulk_action(&mut writer, "ACTION", "INDEX", "TYPE", true, &None);
+        let _ = write_bulk_action(&mut writer, "ACTION", "INDEX", "TYPE", true, &None, &None);
 
         let value: serde_json::Value = serde_json::from_slice(&writer).unwrap();
         let value = value.as_object().unwrap();
@@ -175,6 +182,7 @@ mod tests {
             "TYPE",
             false,
             &Some("ID".to_string()),
+            &None,
         );
 
         let value: serde_json::Value = serde_json::from_slice(&writer).unwrap();
@@ -197,7 +205,7 @@ mod tests {
     fn type_without_id() {
         let mut writer = Vec::new();
 
-        let _ = write_bulk_action(&mut writer, "ACTION", "INDEX", "TYPE", false, &None);
+        let _ = write_bulk_action(&mut writer, "ACTION", "INDEX", "TYPE", false, &None, &None);
 
         let value: serde_json::Value = serde_json::from_slice(&writer).unwrap();
         let value = value.as_object().unwrap();
@@ -213,4 +221,50 @@ mod tests {
         assert!(nested.contains_key("_type"));
         assert_eq!(nested.get("_type").unwrap().as_str(), Some("TYPE"));
     }
+
+    #[test]
+    fn routing() {
+        let mut writer = Vec::new();
+
+        let _ = write_bulk_action(
+            &mut writer,
+            "ACTION",
+            "INDEX",
+            "TYPE",
+            true,
+            &Some("I\"D".to_string()),
+            &Some("ROUTING".to_string()),
+        );
+
+        let value: serde_json::Value = serde_json::from_slice(&writer).unwrap();
+        let nested = value.get("ACTION").unwrap().as_object().unwrap();
+
+        assert_eq!(nested.get("_id").unwrap().as_str(), Some("I\"D"));
+        assert_eq!(nested.get("_routing").unwrap().as_str(), Some("ROUTING"));
+    }
+
+    #[test]
+    fn update_as_upsert() {
+        let encoder = ElasticsearchEncoder {
+            doc_type: "_doc".into(),
+            suppress_type_name: true,
+            doc_as_upsert: true,
+        };
+        let event = ProcessedEvent {
+            index: "entities".into(),
+            bulk_action: BulkAction::Update,
+            log: LogEvent::from("hello there"),
+            id: Some("42".into()),
+            routing: None,
+        };
+
+        let mut encoded = Vec::new();
+        let size = encoder.encode_input(vec![event], &mut encoded).unwrap();
+
+        let expected = r#"{"update":{"_index":"entities","_id":"42"}}
+{"doc":{"message":"hello there"},"doc_as_upsert":true}
+"#;
+        assert_eq!(std::str::from_utf8(&encoded).unwrap(), expected);
+        assert_eq!(encoded.len(), size);
+    }
 }
@@ -62,7 +62,13 @@ impl ElasticsearchCommon {
         let mut request = builder.body(Bytes::new())?;
 
         if let Some(credentials_provider) = &self.aws_auth {
-            sign_request(&mut request, credentials_provider, &self.region).await?;
+            sign_request(
+                &mut request,
+                credentials_provider,
+                &self.region,
+                self.serverless,
+            )
+            .await?;
         }
 
         let proxy = ProxyConfig::default();
@@ -111,6 +117,7 @@ async fn ensure_pipeline_in_params() {
         bulk: Some(BulkConfig {
             index: Some(index),
             action: None,
+            doc_as_upsert: false,
         }),
         pipeline: Some(pipeline.clone()),
         ..config()
@@ -130,6 +137,7 @@ async fn structures_events_correctly() {
         bulk: Some(BulkConfig {
             index: Some(index.clone()),
             action: None,
+            doc_as_upsert: false,
         }),
         doc_type: Some("log_lines".into()),
         id_key: Some("my_id".into()),
@@ -312,6 +320,7 @@ async fn insert_events_in_data_stream() {
         bulk: Some(BulkConfig {
             index: Some(stream_index.clone()),
             action: None,
+            doc_as_upsert: false,
         }),
         ..config()
     };
@@ -338,6 +347,7 @@ async fn run_insert_tests(
     config.bulk = Some(BulkConfig {
         index: Some(gen_index()),
         action: None,
+        doc_as_upsert: false,
     });
     run_insert_tests_with_config(&config, break_events, status).await;
 }
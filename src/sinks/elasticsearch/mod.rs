@@ -1,3 +1,4 @@
+mod bootstrap;
 mod common;
 mod config;
 mod encoder;
@@ -15,9 +16,11 @@ mod integration_tests;
 
 use std::convert::TryFrom;
 
+pub use bootstrap::{BootstrapConfig, TemplateSource};
 pub use common::*;
 pub use config::*;
 pub use encoder::ElasticsearchEncoder;
+pub use service::ElasticsearchResponse;
 use http::{uri::InvalidUri, Request};
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
@@ -51,11 +54,38 @@ impl Default for ElasticsearchMode {
     }
 }
 
+/// The version of the Elasticsearch API, which the requests are adapted to.
+#[derive(Derivative, Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum ElasticsearchApiVersion {
+    /// Determined from the cluster when the sink starts.
+    #[derivative(Default)]
+    Auto,
+    V6,
+    V7,
+    /// Mapping types are removed, so the documents are sent without a `_type`.
+    V8,
+}
+
+impl ElasticsearchApiVersion {
+    /// The API version of a cluster, from its version number.
+    fn from_version_number(number: &str) -> Option<Self> {
+        let major = number.split('.').next()?.parse::<u32>().ok()?;
+        Some(match major {
+            0..=6 => Self::V6,
+            7 => Self::V7,
+            _ => Self::V8,
+        })
+    }
+}
+
 #[derive(Derivative, Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[serde(deny_unknown_fields, rename_all = "snake_case")]
 pub enum BulkAction {
     Index,
     Create,
+    Update,
 }
 
 #[allow(clippy::trivially_copy_pass_by_ref)]
@@ -64,6 +94,7 @@ impl BulkAction {
         match self {
             BulkAction::Index => "index",
             BulkAction::Create => "create",
+            BulkAction::Update => "update",
         }
     }
 
@@ -71,6 +102,7 @@ impl BulkAction {
         match self {
             BulkAction::Index => "/index",
             BulkAction::Create => "/create",
+            BulkAction::Update => "/update",
         }
     }
 }
@@ -82,6 +114,7 @@ impl TryFrom<&str> for BulkAction {
         match input {
             "index" => Ok(BulkAction::Index),
             "create" => Ok(BulkAction::Create),
+            "update" => Ok(BulkAction::Update),
             _ => Err(format!("Invalid bulk action: {}", input)),
         }
     }
@@ -162,6 +195,14 @@ pub enum ParseError {
     IndexTemplate { source: TemplateParseError },
     #[snafu(display("Batch action template parse error: {}", source))]
     BatchActionTemplate { source: TemplateParseError },
+    #[snafu(display("Document id template parse error: {}", source))]
+    IdTemplate { source: TemplateParseError },
+    #[snafu(display("Routing template parse error: {}", source))]
+    RoutingTemplate { source: TemplateParseError },
+    #[snafu(display("`id` and `id_key` cannot be set together"))]
+    IdAndIdKey,
     #[snafu(display("aws.region required when AWS authentication is in use"))]
     RegionRequired,
+    #[snafu(display("Data streams require Elasticsearch 7.9 or later"))]
+    DataStreamsUnsupported,
 }
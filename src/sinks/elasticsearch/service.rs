@@ -90,6 +90,7 @@ pub struct HttpRequestBuilder {
     pub http_request_config: RequestConfig,
     pub http_auth: Option<Auth>,
     pub credentials_provider: Option<SharedCredentialsProvider>,
+    pub serverless: bool,
 }
 
 impl HttpRequestBuilder {
@@ -118,7 +119,13 @@ impl HttpRequestBuilder {
             .expect("Invalid http request value used");
 
         if let Some(credentials_provider) = &self.credentials_provider {
-            sign_request(&mut request, credentials_provider, &self.region).await?;
+            sign_request(
+                &mut request,
+                credentials_provider,
+                &self.region,
+                self.serverless,
+            )
+            .await?;
         }
 
         Ok(request)
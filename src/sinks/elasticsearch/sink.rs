@@ -11,6 +11,7 @@ use vector_core::{
 
 use crate::{
     event::{Event, LogEvent, Value},
+    internal_events::{ElasticsearchMissingIdError, TemplateRenderingError},
     sinks::{
         elasticsearch::{
             encoder::ProcessedEvent, request_builder::ElasticsearchRequestBuilder,
@@ -18,6 +19,7 @@ use crate::{
         },
         util::{Compression, SinkBuilderExt, StreamSink},
     },
+    template::Template,
     transforms::metric_to_log::MetricToLog,
 };
 
@@ -38,6 +40,50 @@ impl ByteSizeOf for BatchedEvents {
     }
 }
 
+/// How the documents are identified and routed in the cluster.
+#[derive(Clone, Debug, Default)]
+pub struct DocumentSettings {
+    /// The field holding the document id, removed from the document.
+    pub id_key: Option<String>,
+    /// The template rendering the document id.
+    pub id: Option<Template>,
+    /// The template rendering the shard routing value.
+    pub routing: Option<Template>,
+}
+
+impl DocumentSettings {
+    fn id(&self, log: &mut LogEvent) -> Result<Option<String>, ()> {
+        if let Some(template) = &self.id {
+            return render(template, log, "id").map(Some);
+        }
+        match self
+            .id_key
+            .as_ref()
+            .and_then(|key| log.remove(key.as_str()))
+        {
+            Some(Value::Bytes(key)) => Ok(Some(String::from_utf8_lossy(&key).into_owned())),
+            _ => Ok(None),
+        }
+    }
+
+    fn routing(&self, log: &LogEvent) -> Result<Option<String>, ()> {
+        self.routing
+            .as_ref()
+            .map(|template| render(template, log, "routing"))
+            .transpose()
+    }
+}
+
+fn render(template: &Template, log: &LogEvent, field: &'static str) -> Result<String, ()> {
+    template.render_string(log).map_err(|error| {
+        emit!(TemplateRenderingError {
+            error,
+            field: Some(field),
+            drop_event: true,
+        });
+    })
+}
+
 pub struct ElasticsearchSink<S> {
     pub batch_settings: BatcherSettings,
     pub request_builder: ElasticsearchRequestBuilder,
@@ -46,7 +92,7 @@ pub struct ElasticsearchSink<S> {
     pub acker: Acker,
     pub metric_to_log: MetricToLog,
     pub mode: ElasticsearchCommonMode,
-    pub id_key_field: Option<String>,
+    pub document: DocumentSettings,
 }
 
 impl<S> ElasticsearchSink<S>
@@ -60,7 +106,7 @@ where
         let request_builder_concurrency_limit = NonZeroUsize::new(50);
 
         let mode = self.mode;
-        let id_key_field = self.id_key_field;
+        let document = self.document;
 
         let sink = input
             .scan(self.metric_to_log, |metric_to_log, event| {
@@ -71,7 +117,7 @@ where
                 }))
             })
             .filter_map(|x| async move { x })
-            .filter_map(move |log| future::ready(process_log(log, &mode, &id_key_field)))
+            .filter_map(move |log| future::ready(process_log(log, &mode, &document)))
             .batched(self.batch_settings.into_byte_size_config())
             .request_builder(request_builder_concurrency_limit, self.request_builder)
             .filter_map(|request| async move {
@@ -92,7 +138,7 @@ where
 pub fn process_log(
     mut log: LogEvent,
     mode: &ElasticsearchCommonMode,
-    id_key_field: &Option<String>,
+    document: &DocumentSettings,
 ) -> Option<ProcessedEvent> {
     let index = mode.index(&log)?;
     let bulk_action = mode.bulk_action(&log)?;
@@ -101,19 +147,18 @@ pub fn process_log(
         cfg.sync_fields(&mut log);
         cfg.remap_timestamp(&mut log);
     };
-    let id = if let Some(Value::Bytes(key)) = id_key_field
-        .as_ref()
-        .and_then(|key| log.remove(key.as_str()))
-    {
-        Some(String::from_utf8_lossy(&key).into_owned())
-    } else {
-        None
-    };
+    let routing = document.routing(&log).ok()?;
+    let id = document.id(&mut log).ok()?;
+    if bulk_action == BulkAction::Update && id.is_none() {
+        emit!(ElasticsearchMissingIdError);
+        return None;
+    }
     Some(ProcessedEvent {
         index,
         bulk_action,
         log,
         id,
+        routing,
     })
 }
 
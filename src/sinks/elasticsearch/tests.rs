@@ -1,13 +1,20 @@
 use std::{collections::BTreeMap, convert::TryFrom};
 
+use vector_core::config::proxy::ProxyConfig;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
 use super::BulkAction;
 use crate::sinks::elasticsearch::BulkConfig;
 use crate::{
     event::{LogEvent, Metric, MetricKind, MetricValue, Value},
+    http::HttpClient,
     sinks::{
         elasticsearch::{
-            sink::process_log, DataStreamConfig, ElasticsearchCommon, ElasticsearchConfig,
-            ElasticsearchMode,
+            sink::process_log, DataStreamConfig, ElasticsearchApiVersion, ElasticsearchCommon,
+            ElasticsearchConfig, ElasticsearchMode,
         },
         util::encoding::{Encoder, EncodingConfigFixed},
     },
@@ -24,6 +31,7 @@ async fn sets_create_action_when_configured() {
         bulk: Some(BulkConfig {
             action: Some(String::from("{{ action }}te")),
             index: Some(String::from("vector")),
+            doc_as_upsert: false,
         }),
         endpoint: String::from("https://example.com"),
         ..Default::default()
@@ -41,7 +49,7 @@ async fn sets_create_action_when_configured() {
     let encoded_size = es
         .encoding
         .encode_input(
-            vec![process_log(log, &es.mode, &None).unwrap()],
+            vec![process_log(log, &es.mode, &Default::default()).unwrap()],
             &mut encoded,
         )
         .unwrap();
@@ -70,6 +78,7 @@ async fn encode_datastream_mode() {
         bulk: Some(BulkConfig {
             action: None,
             index: Some(String::from("vector")),
+            doc_as_upsert: false,
         }),
         endpoint: String::from("https://example.com"),
         mode: ElasticsearchMode::DataStream,
@@ -88,7 +97,7 @@ async fn encode_datastream_mode() {
     let encoded_size = es
         .encoding
         .encode_input(
-            vec![process_log(log, &es.mode, &None).unwrap()],
+            vec![process_log(log, &es.mode, &Default::default()).unwrap()],
             &mut encoded,
         )
         .unwrap();
@@ -110,6 +119,7 @@ async fn encode_datastream_mode_no_routing() {
         bulk: Some(BulkConfig {
             action: None,
             index: Some(String::from("vector")),
+            doc_as_upsert: false,
         }),
         endpoint: String::from("https://example.com"),
         mode: ElasticsearchMode::DataStream,
@@ -132,7 +142,7 @@ async fn encode_datastream_mode_no_routing() {
     let encoded_size = es
         .encoding
         .encode_input(
-            vec![process_log(log, &es.mode, &None).unwrap()],
+            vec![process_log(log, &es.mode, &Default::default()).unwrap()],
             &mut encoded,
         )
         .unwrap();
@@ -150,6 +160,7 @@ async fn handle_metrics() {
         bulk: Some(BulkConfig {
             action: Some(String::from("create")),
             index: Some(String::from("vector")),
+            doc_as_upsert: false,
         }),
         endpoint: String::from("https://example.com"),
         ..Default::default()
@@ -166,7 +177,7 @@ async fn handle_metrics() {
     let mut encoded = vec![];
     es.encoding
         .encode_input(
-            vec![process_log(log, &es.mode, &None).unwrap()],
+            vec![process_log(log, &es.mode, &Default::default()).unwrap()],
             &mut encoded,
         )
         .unwrap();
@@ -190,6 +201,7 @@ async fn decode_bulk_action_error() {
         bulk: Some(BulkConfig {
             action: Some(String::from("{{ action }}")),
             index: Some(String::from("vector")),
+            doc_as_upsert: false,
         }),
         endpoint: String::from("https://example.com"),
         ..Default::default()
@@ -209,6 +221,7 @@ async fn decode_bulk_action() {
         bulk: Some(BulkConfig {
             action: Some(String::from("create")),
             index: Some(String::from("vector")),
+            doc_as_upsert: false,
         }),
         endpoint: String::from("https://example.com"),
         ..Default::default()
@@ -230,6 +243,7 @@ async fn encode_datastream_mode_no_sync() {
         bulk: Some(BulkConfig {
             action: None,
             index: Some(String::from("vector")),
+            doc_as_upsert: false,
         }),
         endpoint: String::from("https://example.com"),
         mode: ElasticsearchMode::DataStream,
@@ -254,7 +268,7 @@ async fn encode_datastream_mode_no_sync() {
     let encoded_size = es
         .encoding
         .encode_input(
-            vec![process_log(log, &es.mode, &None).unwrap()],
+            vec![process_log(log, &es.mode, &Default::default()).unwrap()],
             &mut encoded,
         )
         .unwrap();
@@ -272,6 +286,7 @@ async fn allows_using_excepted_fields() {
         bulk: Some(BulkConfig {
             action: None,
             index: Some(String::from("{{ idx }}")),
+            doc_as_upsert: false,
         }),
         encoding: EncodingConfigFixed {
             except_fields: Some(vec!["idx".to_string(), "timestamp".to_string()]),
@@ -290,7 +305,7 @@ async fn allows_using_excepted_fields() {
     let encoded_size = es
         .encoding
         .encode_input(
-            vec![process_log(log, &es.mode, &None).unwrap()],
+            vec![process_log(log, &es.mode, &Default::default()).unwrap()],
             &mut encoded,
         )
         .unwrap();
@@ -301,3 +316,100 @@ async fn allows_using_excepted_fields() {
     assert_eq!(std::str::from_utf8(&encoded).unwrap(), expected);
     assert_eq!(encoded.len(), encoded_size);
 }
+
+#[tokio::test]
+async fn sets_id_and_routing_from_templates() {
+    let config = ElasticsearchConfig {
+        bulk: Some(BulkConfig {
+            action: Some(String::from("{{ action }}")),
+            index: Some(String::from("users")),
+            doc_as_upsert: false,
+        }),
+        id: Some(String::from("user-{{ user_id }}")),
+        routing: Some(String::from("{{ tenant }}")),
+        suppress_type_name: true,
+        endpoint: String::from("https://example.com"),
+        ..Default::default()
+    };
+    let es = ElasticsearchCommon::parse_config(&config).await.unwrap();
+    let document = config.document_settings().unwrap();
+
+    let mut log = LogEvent::from("hello there");
+    log.insert("action", "update");
+    log.insert("user_id", "42");
+    log.insert("tenant", "acme");
+
+    let mut encoded = vec![];
+    let encoded_size = es
+        .encoding
+        .encode_input(
+            vec![process_log(log, &es.mode, &document).unwrap()],
+            &mut encoded,
+        )
+        .unwrap();
+
+    let expected = r#"{"update":{"_index":"users","_id":"user-42","_routing":"acme"}}
+{"doc":{"action":"update","message":"hello there","tenant":"acme","user_id":"42"}}
+"#;
+    assert_eq!(std::str::from_utf8(&encoded).unwrap(), expected);
+    assert_eq!(encoded.len(), encoded_size);
+
+    let mut log = LogEvent::from("hello there");
+    log.insert("action", "update");
+    log.insert("tenant", "acme");
+    assert!(process_log(log, &es.mode, &document).is_none());
+}
+
+#[tokio::test]
+async fn drops_updates_without_id() {
+    let config = ElasticsearchConfig {
+        bulk: Some(BulkConfig {
+            action: Some(String::from("update")),
+            index: Some(String::from("users")),
+            doc_as_upsert: true,
+        }),
+        endpoint: String::from("https://example.com"),
+        ..Default::default()
+    };
+    let es = ElasticsearchCommon::parse_config(&config).await.unwrap();
+
+    let log = LogEvent::from("hello there");
+    assert!(process_log(log, &es.mode, &config.document_settings().unwrap()).is_none());
+}
+
+#[tokio::test]
+async fn resolves_api_version() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({ "version": { "number": "8.2.0" } })),
+        )
+        .mount(&server)
+        .await;
+
+    let config = ElasticsearchConfig {
+        endpoint: server.uri(),
+        ..Default::default()
+    };
+    let mut common = ElasticsearchCommon::parse_config(&config).await.unwrap();
+    let client = HttpClient::new(None, &ProxyConfig::default()).unwrap();
+    assert!(!common.suppress_type_name);
+    common.resolve_api_version(&client).await.unwrap();
+    assert_eq!(common.api_version, ElasticsearchApiVersion::V8);
+    assert!(common.suppress_type_name);
+}
+
+#[tokio::test]
+async fn rejects_data_streams_before_v7() {
+    let config = ElasticsearchConfig {
+        endpoint: String::from("https://example.com"),
+        mode: ElasticsearchMode::DataStream,
+        api_version: ElasticsearchApiVersion::V6,
+        ..Default::default()
+    };
+    let mut common = ElasticsearchCommon::parse_config(&config).await.unwrap();
+    let client = HttpClient::new(None, &ProxyConfig::default()).unwrap();
+    assert!(common.resolve_api_version(&client).await.is_err());
+}
@@ -0,0 +1,536 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use futures::{future, stream::BoxStream, FutureExt};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use vector_core::{
+    event::{BatchStatus, EventArray},
+    sink::StreamSink,
+};
+
+use crate::{
+    config::{
+        AcknowledgementsConfig, Capability, GenerateConfig, Input, Resource, SinkConfig,
+        SinkContext, SinkDescription,
+    },
+    internal_events::{FailoverActiveTarget, FailoverSwitched},
+    sinks::{
+        util::targets::{self, Router},
+        Healthcheck, VectorSink,
+    },
+};
+
+inventory::submit! {
+    SinkDescription::new::<FailoverConfig>("failover")
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("`error_rate_threshold` must be greater than 0 and at most 1."))]
+    InvalidErrorRateThreshold,
+    #[snafu(display("`error_window` must be greater than zero."))]
+    ZeroErrorWindow,
+}
+
+#[derive(Debug, Snafu)]
+enum HealthcheckError {
+    #[snafu(display("Neither the primary nor the secondary passed their healthcheck."))]
+    BothTargetsFailed,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct FailoverConfig {
+    pub primary: Box<dyn SinkConfig>,
+    pub secondary: Box<dyn SinkConfig>,
+    #[serde(default = "default_error_rate_threshold")]
+    pub error_rate_threshold: f64,
+    #[serde(default = "default_error_window")]
+    pub error_window: usize,
+    #[serde(default = "default_probation_secs")]
+    pub probation_secs: u64,
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub acknowledgements: AcknowledgementsConfig,
+}
+
+const fn default_error_rate_threshold() -> f64 {
+    0.5
+}
+
+const fn default_error_window() -> usize {
+    10
+}
+
+const fn default_probation_secs() -> u64 {
+    60
+}
+
+impl GenerateConfig for FailoverConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"[primary]
+            type = "blackhole"
+
+            [secondary]
+            type = "blackhole""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait]
+#[typetag::serde(name = "failover")]
+impl SinkConfig for FailoverConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        if !(self.error_rate_threshold > 0.0 && self.error_rate_threshold <= 1.0) {
+            return Err(BuildError::InvalidErrorRateThreshold.into());
+        }
+        if self.error_window == 0 {
+            return Err(BuildError::ZeroErrorWindow.into());
+        }
+
+        let (primary, primary_healthcheck) = self.primary.build(cx.clone()).await?;
+        let (secondary, secondary_healthcheck) = self.secondary.build(cx).await?;
+
+        let state = Arc::new(Mutex::new(FailoverState::new(
+            self.error_rate_threshold,
+            self.error_window,
+            Duration::from_secs(self.probation_secs),
+        )));
+        let healthcheck = healthcheck(
+            primary_healthcheck,
+            secondary_healthcheck,
+            Arc::clone(&state),
+        )
+        .boxed();
+        let sink = FailoverSink {
+            state,
+            primary,
+            secondary,
+        };
+
+        Ok((VectorSink::Stream(Box::new(sink)), healthcheck))
+    }
+
+    fn input(&self) -> Input {
+        // Events may end up in either target, so only the types both of them
+        // accept are allowed.
+        Input::new(self.primary.input().data_type() & self.secondary.input().data_type())
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "failover"
+    }
+
+    fn capabilities(&self) -> Vec<Capability> {
+        let mut capabilities = self.primary.capabilities();
+        capabilities.extend(self.secondary.capabilities());
+        capabilities
+    }
+
+    fn supports_sandbox(&self) -> bool {
+        self.primary.supports_sandbox() && self.secondary.supports_sandbox()
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        let mut resources = self.primary.resources();
+        resources.extend(self.secondary.resources());
+        resources
+    }
+
+    fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
+        Some(&self.acknowledgements)
+    }
+}
+
+/// Fails over to the secondary if the primary fails its healthcheck. The sink
+/// is healthy as long as one of its targets is.
+async fn healthcheck(
+    primary: Healthcheck,
+    secondary: Healthcheck,
+    state: Arc<Mutex<FailoverState>>,
+) -> crate::Result<()> {
+    let (primary, secondary) = future::join(primary, secondary).await;
+
+    if let Err(error) = &primary {
+        state
+            .lock()
+            .expect("mutex poisoned")
+            .fail_over(Instant::now(), &error.to_string());
+    }
+    if let Err(error) = &secondary {
+        warn!(message = "Secondary failed its healthcheck.", %error);
+    }
+
+    if primary.is_ok() || secondary.is_ok() {
+        Ok(())
+    } else {
+        Err(HealthcheckError::BothTargetsFailed.into())
+    }
+}
+
+struct FailoverSink {
+    state: Arc<Mutex<FailoverState>>,
+    primary: VectorSink,
+    secondary: VectorSink,
+}
+
+#[async_trait]
+impl StreamSink<EventArray> for FailoverSink {
+    async fn run(self: Box<Self>, input: BoxStream<'_, EventArray>) -> Result<(), ()> {
+        let FailoverSink {
+            state,
+            primary,
+            secondary,
+        } = *self;
+
+        emit!(FailoverActiveTarget {
+            target: state.lock().expect("mutex poisoned").active.as_str(),
+        });
+
+        // The batches the primary fails to deliver are sent to the secondary.
+        targets::route(&state, vec![primary, secondary], input).await
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Target {
+    Primary,
+    Secondary,
+}
+
+impl Target {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Target::Primary => "primary",
+            Target::Secondary => "secondary",
+        }
+    }
+
+    /// The index of the target among those the events are routed to.
+    const fn index(self) -> usize {
+        match self {
+            Target::Primary => 0,
+            Target::Secondary => 1,
+        }
+    }
+
+    const fn from_index(index: usize) -> Self {
+        if index == 0 {
+            Target::Primary
+        } else {
+            Target::Secondary
+        }
+    }
+}
+
+/// Keeps track of the error rate of the primary, and of which target is active.
+#[derive(Debug)]
+struct FailoverState {
+    active: Target,
+    error_rate_threshold: f64,
+    error_window: usize,
+    probation: Duration,
+    /// Whether each of the last batches sent to the primary failed.
+    outcomes: VecDeque<bool>,
+    failed_over_at: Option<Instant>,
+}
+
+impl FailoverState {
+    fn new(error_rate_threshold: f64, error_window: usize, probation: Duration) -> Self {
+        Self {
+            active: Target::Primary,
+            error_rate_threshold,
+            error_window,
+            probation,
+            outcomes: VecDeque::with_capacity(error_window),
+            failed_over_at: None,
+        }
+    }
+
+    /// Returns the target to send events to, switching back to the primary
+    /// once the secondary has been active for the probation period.
+    fn active(&mut self, now: Instant) -> Target {
+        if let Some(failed_over_at) = self.failed_over_at {
+            if now >= failed_over_at + self.probation {
+                self.switch(Target::Primary, None, "probation period ended");
+            }
+        }
+        self.active
+    }
+
+    fn record(&mut self, target: Target, status: BatchStatus, now: Instant) {
+        // Only the primary is judged, and only by what it was sent while it
+        // was active, so that the failures which caused a fail over don't
+        // count against it once it's back.
+        if target != Target::Primary || self.active != Target::Primary {
+            return;
+        }
+
+        let failed = match status {
+            BatchStatus::Delivered => false,
+            BatchStatus::Errored => true,
+            // Rejected events are a problem with the events themselves, not
+            // with the target.
+            BatchStatus::Rejected => return,
+        };
+        if self.outcomes.len() == self.error_window {
+            self.outcomes.pop_front();
+        }
+        self.outcomes.push_back(failed);
+
+        if self.outcomes.len() == self.error_window {
+            let failures = self.outcomes.iter().filter(|&&failed| failed).count();
+            if failures as f64 / self.error_window as f64 >= self.error_rate_threshold {
+                self.fail_over(now, "error rate exceeded the threshold");
+            }
+        }
+    }
+
+    fn fail_over(&mut self, now: Instant, reason: &str) {
+        self.switch(Target::Secondary, Some(now), reason);
+    }
+
+    fn switch(&mut self, target: Target, failed_over_at: Option<Instant>, reason: &str) {
+        self.failed_over_at = failed_over_at;
+        self.outcomes.clear();
+        if self.active != target {
+            self.active = target;
+            emit!(FailoverSwitched {
+                target: target.as_str(),
+                reason,
+            });
+        }
+    }
+}
+
+impl Router for FailoverState {
+    fn select(&mut self, now: Instant) -> usize {
+        self.active(now).index()
+    }
+
+    fn finish(&mut self, target: usize, _count: usize, status: BatchStatus, now: Instant) {
+        self.record(Target::from_index(target), status, now);
+    }
+
+    fn fallback(&self, target: usize) -> Option<usize> {
+        (Target::from_index(target) == Target::Primary).then(|| Target::Secondary.index())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::stream;
+    use tokio::sync::mpsc;
+    use tokio_stream::wrappers::ReceiverStream;
+    use vector_core::{config::DataType, event::BatchNotifier};
+
+    use super::*;
+    use crate::{
+        event::{Event, LogEvent},
+        sinks::util::targets::test::TestTargetConfig,
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<FailoverConfig>();
+    }
+
+    #[test]
+    fn fails_over_on_error_rate() {
+        let mut state = FailoverState::new(0.5, 4, Duration::from_secs(60));
+        let now = Instant::now();
+
+        for status in [
+            BatchStatus::Errored,
+            BatchStatus::Delivered,
+            BatchStatus::Rejected,
+            BatchStatus::Errored,
+        ] {
+            state.record(Target::Primary, status, now);
+            assert_eq!(state.active(now), Target::Primary);
+        }
+
+        // The window isn't full until the fourth counted batch.
+        state.record(Target::Primary, BatchStatus::Delivered, now);
+        assert_eq!(state.active(now), Target::Secondary);
+
+        // Failures of the secondary, and late ones of the primary, are ignored.
+        state.record(Target::Secondary, BatchStatus::Errored, now);
+        state.record(Target::Primary, BatchStatus::Errored, now);
+        assert!(state.outcomes.is_empty());
+    }
+
+    #[test]
+    fn recovers_after_probation() {
+        let mut state = FailoverState::new(1.0, 1, Duration::from_secs(60));
+        let now = Instant::now();
+
+        state.record(Target::Primary, BatchStatus::Errored, now);
+        assert_eq!(
+            state.active(now + Duration::from_secs(59)),
+            Target::Secondary
+        );
+        assert_eq!(state.active(now + Duration::from_secs(60)), Target::Primary);
+        assert!(state.failed_over_at.is_none());
+
+        let later = now + Duration::from_secs(61);
+        state.record(Target::Primary, BatchStatus::Errored, later);
+        assert_eq!(state.active(later), Target::Secondary);
+    }
+
+    fn config(primary: TestTargetConfig, secondary: TestTargetConfig) -> FailoverConfig {
+        FailoverConfig {
+            primary: Box::new(primary),
+            secondary: Box::new(secondary),
+            error_rate_threshold: default_error_rate_threshold(),
+            error_window: 2,
+            probation_secs: default_probation_secs(),
+            acknowledgements: Default::default(),
+        }
+    }
+
+    fn events(count: usize) -> impl Iterator<Item = EventArray> {
+        (0..count).map(|i| EventArray::from(Event::from(LogEvent::from(format!("event {}", i)))))
+    }
+
+    #[test]
+    fn input_is_shared_by_both_targets() {
+        let config: FailoverConfig = toml::from_str(
+            r#"
+            [primary]
+            type = "blackhole"
+
+            [secondary]
+            type = "target_test"
+            fail = false
+            healthy = true
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.input().data_type(), DataType::Log);
+    }
+
+    #[tokio::test]
+    async fn sends_to_primary() {
+        let primary = TestTargetConfig {
+            healthy: true,
+            ..Default::default()
+        };
+        let secondary = TestTargetConfig::default();
+        let (primary_events, secondary_events) =
+            (Arc::clone(&primary.events), Arc::clone(&secondary.events));
+
+        let (sink, healthcheck) = config(primary, secondary)
+            .build(SinkContext::new_test())
+            .await
+            .unwrap();
+        healthcheck.await.unwrap();
+        sink.run(stream::iter(events(10))).await.unwrap();
+
+        assert_eq!(primary_events.lock().unwrap().len(), 10);
+        assert!(secondary_events.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn fails_over_when_primary_fails() {
+        let primary = TestTargetConfig {
+            fail: true,
+            healthy: true,
+            ..Default::default()
+        };
+        let secondary = TestTargetConfig::default();
+        let (primary_events, secondary_events) =
+            (Arc::clone(&primary.events), Arc::clone(&secondary.events));
+
+        let (sink, _) = config(primary, secondary)
+            .build(SinkContext::new_test())
+            .await
+            .unwrap();
+
+        // Each batch is delivered before the next one is sent, so the failures
+        // are noticed in time.
+        let (sender, receiver) = mpsc::channel(1);
+        let run = tokio::spawn(sink.run(ReceiverStream::new(receiver)));
+        for array in events(10) {
+            sender.send(array).await.unwrap();
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        drop(sender);
+        run.await.unwrap().unwrap();
+
+        // The batches the primary failed to deliver were sent to the
+        // secondary too.
+        assert_eq!(primary_events.lock().unwrap().len(), 2);
+        assert_eq!(secondary_events.lock().unwrap().len(), 10);
+    }
+
+    #[tokio::test]
+    async fn resends_errored_events_to_secondary() {
+        let primary = TestTargetConfig {
+            fail: true,
+            healthy: true,
+            ..Default::default()
+        };
+        let secondary = TestTargetConfig::default();
+        let (primary_events, secondary_events) =
+            (Arc::clone(&primary.events), Arc::clone(&secondary.events));
+
+        let mut config = config(primary, secondary);
+        config.error_window = 100;
+        let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+
+        let (batch, receiver) = BatchNotifier::new_with_receiver();
+        let arrays = (0..10)
+            .map(|i| {
+                let event = Event::from(LogEvent::from(format!("event {}", i)));
+                EventArray::from(event.with_batch_notifier(&batch))
+            })
+            .collect::<Vec<_>>();
+        drop(batch);
+        sink.run(stream::iter(arrays)).await.unwrap();
+
+        // The primary is still active, but none of the events are lost.
+        assert_eq!(receiver.await, BatchStatus::Delivered);
+        assert_eq!(primary_events.lock().unwrap().len(), 10);
+        assert_eq!(secondary_events.lock().unwrap().len(), 10);
+    }
+
+    #[tokio::test]
+    async fn fails_over_when_primary_is_unhealthy() {
+        let primary = TestTargetConfig::default();
+        let secondary = TestTargetConfig {
+            healthy: true,
+            ..Default::default()
+        };
+        let (primary_events, secondary_events) =
+            (Arc::clone(&primary.events), Arc::clone(&secondary.events));
+
+        let (sink, healthcheck) = config(primary, secondary)
+            .build(SinkContext::new_test())
+            .await
+            .unwrap();
+        healthcheck.await.unwrap();
+        sink.run(stream::iter(events(5))).await.unwrap();
+
+        assert!(primary_events.lock().unwrap().is_empty());
+        assert_eq!(secondary_events.lock().unwrap().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn healthcheck_fails_when_both_targets_fail() {
+        let (_, healthcheck) = config(TestTargetConfig::default(), TestTargetConfig::default())
+            .build(SinkContext::new_test())
+            .await
+            .unwrap();
+        assert!(healthcheck.await.is_err());
+    }
+}
@@ -24,7 +24,8 @@ use vector_core::{buffers::Acker, internal_event::EventsSent, ByteSizeOf};
 use crate::{
     codecs::Encoder,
     config::{
-        AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext, SinkDescription,
+        AcknowledgementsConfig, Capability, GenerateConfig, Input, Sandbox, SinkConfig,
+        SinkContext, SinkDescription,
     },
     event::{Event, EventStatus, Finalizable},
     expiring_hash_map::ExpiringHashMap,
@@ -181,6 +182,20 @@ impl SinkConfig for FileSinkConfig {
         "file"
     }
 
+    fn capabilities(&self) -> Vec<Capability> {
+        // Files are written under the part of their path that doesn't depend on the events.
+        let path = self.path.get_ref();
+        let directory = match path.find(|c| c == '{' || c == '%') {
+            Some(index) => path[..index].rfind('/').map_or("", |slash| &path[..=slash]),
+            None => path,
+        };
+        vec![Capability::path(directory)]
+    }
+
+    fn supports_sandbox(&self) -> bool {
+        true
+    }
+
     fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
         Some(&self.acknowledgements)
     }
@@ -195,6 +210,7 @@ pub struct FileSink {
     idle_timeout: Duration,
     files: ExpiringHashMap<Bytes, OutFile>,
     compression: Compression,
+    sandbox: Sandbox,
 }
 
 impl FileSink {
@@ -212,6 +228,7 @@ impl FileSink {
             idle_timeout: Duration::from_secs(config.idle_timeout_secs.unwrap_or(30)),
             files: ExpiringHashMap::default(),
             compression: config.compression,
+            sandbox: Sandbox::current(),
         }
     }
 
@@ -320,7 +337,16 @@ impl FileSink {
             file
         } else {
             trace!(message = "Opening new file.", ?path);
-            let file = match open_file(BytesPath::new(path.clone())).await {
+            let file_path = BytesPath::new(path.clone());
+            if self
+                .sandbox
+                .enforce(&Capability::path(file_path.as_ref()))
+                .is_err()
+            {
+                event.metadata().update_status(EventStatus::Errored);
+                return;
+            }
+            let file = match open_file(file_path).await {
                 Ok(file) => file,
                 Err(error) => {
                     // We couldn't open the file for this event.
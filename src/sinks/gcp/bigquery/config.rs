@@ -0,0 +1,233 @@
+use std::convert::TryFrom;
+
+use futures::FutureExt;
+use http::{
+    uri::{Scheme, Uri},
+    Request,
+};
+use hyper::Body;
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Identity};
+use tower::ServiceBuilder;
+
+use super::{
+    proto::big_query_write_client::BigQueryWriteClient,
+    row::{BigqueryField, RowEncoder},
+    service::{BigqueryResponse, BigqueryService},
+    sink::BigquerySink,
+    BigquerySinkError, EndpointSnafu, EndpointTlsSnafu,
+};
+use crate::{
+    config::{
+        AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext, SinkDescription,
+    },
+    gcp::{GcpAuthConfig, GcpCredentials, Scope, BIGQUERY_STORAGE_URL, BIGQUERY_URL},
+    http::HttpClient,
+    sinks::{
+        gcs_common::config::healthcheck_response,
+        util::{
+            retries::RetryLogic, BatchConfig, ServiceBuilderExt, SinkBatchSettings,
+            TowerRequestConfig,
+        },
+        Healthcheck, VectorSink,
+    },
+    template::Template,
+    tls::{TlsConfig, TlsSettings},
+};
+
+// The Storage Write API rejects requests larger than 10MB, leaving room for the schema and the
+// framing of the rows.
+const MAX_BATCH_PAYLOAD_SIZE: usize = 9_000_000;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BigqueryDefaultBatchSettings;
+
+impl SinkBatchSettings for BigqueryDefaultBatchSettings {
+    const MAX_EVENTS: Option<usize> = Some(1000);
+    const MAX_BYTES: Option<usize> = Some(MAX_BATCH_PAYLOAD_SIZE);
+    const TIMEOUT_SECS: f64 = 1.0;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct BigqueryConfig {
+    pub project: String,
+    pub dataset: String,
+    /// The table the events are appended to, which can be templated to route them to several
+    /// tables sharing the schema.
+    pub table: String,
+    /// The columns of the rows, in the order of the schema of the table.
+    pub schema: Vec<BigqueryField>,
+    pub endpoint: Option<String>,
+    #[serde(default)]
+    pub skip_authentication: bool,
+    #[serde(flatten)]
+    pub auth: GcpAuthConfig,
+
+    #[serde(default)]
+    pub batch: BatchConfig<BigqueryDefaultBatchSettings>,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+
+    pub tls: Option<TlsConfig>,
+
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+inventory::submit! {
+    SinkDescription::new::<BigqueryConfig>("gcp_bigquery")
+}
+
+impl GenerateConfig for BigqueryConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"project = "my-project"
+            dataset = "my_dataset"
+            table = "my_table"
+            schema = [{ name = "message", type = "string" }]"#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "gcp_bigquery")]
+impl SinkConfig for BigqueryConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let table = Template::try_from(self.table.as_str())?;
+        let encoder = RowEncoder::new(self.schema.clone())?;
+
+        // We only need to load the credentials if we are not targeting an emulator.
+        let creds = if self.skip_authentication {
+            None
+        } else {
+            self.auth.make_credentials(Scope::BigQuery).await?
+        };
+
+        let batch_settings = self.batch.validate()?.into_batcher_settings()?;
+        let request_settings = self.request.unwrap_with(&Default::default());
+
+        let tls_settings = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls_settings.clone(), cx.proxy())?;
+        let healthcheck = healthcheck(client, self.dataset_uri()?, creds.clone()).boxed();
+
+        let endpoint = self.endpoint.as_deref().unwrap_or(BIGQUERY_STORAGE_URL);
+        let uri: Uri = endpoint.parse().context(EndpointSnafu)?;
+        let mut channel = Channel::from_shared(endpoint.to_owned()).context(EndpointSnafu)?;
+        if uri.scheme() != Some(&Scheme::HTTP) {
+            channel = channel
+                .tls_config(make_tls_config(&tls_settings, &uri))
+                .context(EndpointTlsSnafu)?;
+        }
+        let client = BigQueryWriteClient::new(channel.connect_lazy());
+
+        let service = BigqueryService::new(client, creds, encoder.writer_schema(), &uri);
+        let service = ServiceBuilder::new()
+            .settings(request_settings, BigqueryRetryLogic)
+            .service(service);
+
+        let sink = BigquerySink {
+            service,
+            batch_settings,
+            acker: cx.acker(),
+            table,
+            encoder,
+            stream_prefix: format!(
+                "projects/{}/datasets/{}/tables/",
+                self.project, self.dataset
+            ),
+        };
+
+        Ok((VectorSink::from_event_streamsink(sink), healthcheck))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "gcp_bigquery"
+    }
+
+    fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
+        Some(&self.acknowledgements)
+    }
+}
+
+impl BigqueryConfig {
+    fn dataset_uri(&self) -> crate::Result<Uri> {
+        let uri = format!(
+            "{}/bigquery/v2/projects/{}/datasets/{}",
+            BIGQUERY_URL, self.project, self.dataset
+        );
+        Ok(uri.parse()?)
+    }
+}
+
+fn make_tls_config(tls: &TlsSettings, uri: &Uri) -> ClientTlsConfig {
+    let host = uri.host().unwrap_or("bigquerystorage.googleapis.com");
+    let mut config = ClientTlsConfig::new().domain_name(host);
+    if let Some((cert, key)) = tls.identity_pem() {
+        config = config.identity(Identity::from_pem(cert, key));
+    }
+    for authority in tls.authorities_pem() {
+        config = config.ca_certificate(Certificate::from_pem(authority));
+    }
+    config
+}
+
+async fn healthcheck(
+    client: HttpClient,
+    uri: Uri,
+    creds: Option<GcpCredentials>,
+) -> crate::Result<()> {
+    let mut request = Request::get(uri).body(Body::empty()).unwrap();
+    if let Some(creds) = creds.as_ref() {
+        creds.apply(&mut request);
+    }
+
+    let response = client.send(request).await?;
+    healthcheck_response(response, creds, BigquerySinkError::DatasetNotFound.into())
+}
+
+#[derive(Debug, Clone)]
+struct BigqueryRetryLogic;
+
+impl RetryLogic for BigqueryRetryLogic {
+    type Error = BigquerySinkError;
+    type Response = BigqueryResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        match error {
+            BigquerySinkError::Request { source } => is_retriable_code(source.code()),
+            BigquerySinkError::Append { code, .. } => is_retriable_code(tonic::Code::from(*code)),
+            // The rows are malformed, so retrying won't help.
+            BigquerySinkError::RowErrors { .. } => false,
+            _ => true,
+        }
+    }
+}
+
+fn is_retriable_code(code: tonic::Code) -> bool {
+    use tonic::Code::*;
+
+    // List taken from
+    //
+    // <https://github.com/grpc/grpc/blob/ed1b20777c69bd47e730a63271eafc1b299f6ca0/doc/statuscodes.md>
+    !matches!(
+        code,
+        NotFound
+            | InvalidArgument
+            | AlreadyExists
+            | PermissionDenied
+            | OutOfRange
+            | Unimplemented
+            | Unauthenticated
+    )
+}
@@ -0,0 +1,124 @@
+//! The `gcp_bigquery` sink, which streams log events into BigQuery tables with the Storage Write
+//! API.
+//!
+//! Events are mapped to rows with the configured schema, serialized as protobuf messages described
+//! by a descriptor derived from that schema, and appended to the default stream of the table the
+//! events are routed to, where they are committed as soon as they are appended.
+
+use http::uri::InvalidUri;
+use snafu::Snafu;
+
+mod config;
+mod row;
+mod service;
+mod sink;
+
+pub use config::BigqueryConfig;
+
+// prost emits some generated code that includes clones on `Arc`
+// objects, which causes a clippy ding on this block. We don't
+// directly control the generated code, so allow this lint here.
+#[allow(clippy::clone_on_ref_ptr)]
+mod proto {
+    pub mod google {
+        pub mod rpc {
+            include!(concat!(env!("OUT_DIR"), "/google.rpc.rs"));
+        }
+
+        pub mod cloud {
+            pub mod bigquery {
+                pub mod storage {
+                    pub mod v1 {
+                        include!(concat!(
+                            env!("OUT_DIR"),
+                            "/google.cloud.bigquery.storage.v1.rs"
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    pub use google::cloud::bigquery::storage::v1::*;
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub))]
+pub enum BigquerySinkError {
+    #[snafu(display("Could not create endpoint: {}", source))]
+    Endpoint { source: InvalidUri },
+
+    #[snafu(display("Could not set up endpoint TLS settings: {}", source))]
+    EndpointTls { source: tonic::transport::Error },
+
+    #[snafu(display("The schema must have at least one field."))]
+    EmptySchema,
+
+    #[snafu(display("The schema has more than one `{}` field.", name))]
+    DuplicateField { name: String },
+
+    #[snafu(display("Invalid token text returned by GCP."))]
+    InvalidToken,
+
+    #[snafu(display("Request failed: {}", source))]
+    Request { source: tonic::Status },
+
+    #[snafu(display("Rows could not be appended, with code {}: {}", code, message))]
+    Append { code: i32, message: String },
+
+    #[snafu(display("{} rows were rejected, such as row {}: {}", count, index, message))]
+    RowErrors {
+        count: usize,
+        index: i64,
+        message: String,
+    },
+
+    #[snafu(display("The append stream closed without a response."))]
+    NoResponse,
+
+    #[snafu(display("Configured dataset not found"))]
+    DatasetNotFound,
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+    use crate::config::{SinkConfig, SinkContext};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<BigqueryConfig>();
+    }
+
+    #[tokio::test]
+    async fn builds_with_templated_table() {
+        let config: BigqueryConfig = toml::from_str(indoc! {r#"
+                project = "project"
+                dataset = "dataset"
+                table = "logs_{{ service }}"
+                endpoint = "http://localhost:9060"
+                skip_authentication = true
+                schema = [
+                    { name = "message", type = "string" },
+                    { name = "timestamp", type = "timestamp" },
+                ]
+            "#})
+        .unwrap();
+        assert!(config.build(SinkContext::new_test()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn rejects_empty_schema() {
+        let config: BigqueryConfig = toml::from_str(indoc! {r#"
+                project = "project"
+                dataset = "dataset"
+                table = "logs"
+                skip_authentication = true
+                schema = []
+            "#})
+        .unwrap();
+        assert!(config.build(SinkContext::new_test()).await.is_err());
+    }
+}
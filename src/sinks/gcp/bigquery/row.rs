@@ -0,0 +1,285 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use prost::encoding;
+use prost_types::{
+    field_descriptor_proto::{Label, Type},
+    DescriptorProto, FieldDescriptorProto,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{proto, BigquerySinkError};
+use crate::event::{LogEvent, Value};
+
+/// A column of the table, and the field of the events its values are read from.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct BigqueryField {
+    /// The name of the column.
+    pub name: String,
+    /// The path of the field holding the values of the column, which defaults to its name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    #[serde(rename = "type")]
+    pub field_type: BigqueryFieldType,
+}
+
+impl BigqueryField {
+    fn path(&self) -> &str {
+        self.field.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// The type of a column, which must match the type of the column in the table.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BigqueryFieldType {
+    String,
+    Int64,
+    Float64,
+    Bool,
+    Timestamp,
+    Json,
+}
+
+impl BigqueryFieldType {
+    /// The protobuf type the values of the column are serialized as, following the mapping of
+    /// the Storage Write API.
+    const fn proto_type(self) -> Type {
+        match self {
+            Self::String | Self::Json => Type::String,
+            // Timestamps are written as microseconds since the epoch.
+            Self::Int64 | Self::Timestamp => Type::Int64,
+            Self::Float64 => Type::Double,
+            Self::Bool => Type::Bool,
+        }
+    }
+}
+
+/// Serializes events as rows of the table, as protobuf messages with a field for each column.
+#[derive(Debug)]
+pub struct RowEncoder {
+    fields: Vec<BigqueryField>,
+}
+
+impl RowEncoder {
+    pub fn new(fields: Vec<BigqueryField>) -> Result<Self, BigquerySinkError> {
+        if fields.is_empty() {
+            return Err(BigquerySinkError::EmptySchema);
+        }
+        let mut names = HashSet::new();
+        if let Some(field) = fields.iter().find(|field| !names.insert(&field.name)) {
+            return Err(BigquerySinkError::DuplicateField {
+                name: field.name.clone(),
+            });
+        }
+        Ok(Self { fields })
+    }
+
+    /// The schema of the rows, sent along with them.
+    pub fn writer_schema(&self) -> proto::ProtoSchema {
+        let field = self
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| FieldDescriptorProto {
+                name: Some(field.name.clone()),
+                number: Some(index as i32 + 1),
+                label: Some(Label::Optional as i32),
+                r#type: Some(field.field_type.proto_type() as i32),
+                ..Default::default()
+            })
+            .collect();
+
+        proto::ProtoSchema {
+            proto_descriptor: Some(DescriptorProto {
+                name: Some("VectorRow".to_owned()),
+                field,
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Serializes the event as a row. Columns whose field is missing, null, or of a type that
+    /// can't be converted to the type of the column are left null.
+    pub fn encode(&self, log: &LogEvent) -> Vec<u8> {
+        let mut row = Vec::new();
+        for (index, field) in self.fields.iter().enumerate() {
+            let tag = index as u32 + 1;
+            let value = match log.get(field.path()) {
+                Some(Value::Null) | None => continue,
+                Some(value) => value,
+            };
+
+            match (field.field_type, value) {
+                (BigqueryFieldType::String, value) => {
+                    encoding::string::encode(tag, &value.to_string_lossy(), &mut row)
+                }
+                (BigqueryFieldType::Int64, Value::Integer(integer)) => {
+                    encoding::int64::encode(tag, integer, &mut row)
+                }
+                (BigqueryFieldType::Float64, Value::Float(float)) => {
+                    encoding::double::encode(tag, &float.into_inner(), &mut row)
+                }
+                (BigqueryFieldType::Float64, Value::Integer(integer)) => {
+                    encoding::double::encode(tag, &(*integer as f64), &mut row)
+                }
+                (BigqueryFieldType::Bool, Value::Boolean(boolean)) => {
+                    encoding::bool::encode(tag, boolean, &mut row)
+                }
+                (BigqueryFieldType::Timestamp, Value::Timestamp(timestamp)) => {
+                    encoding::int64::encode(tag, &timestamp_micros(timestamp), &mut row)
+                }
+                (BigqueryFieldType::Timestamp, Value::Bytes(bytes)) => {
+                    if let Some(timestamp) = std::str::from_utf8(bytes)
+                        .ok()
+                        .and_then(|text| DateTime::parse_from_rfc3339(text).ok())
+                    {
+                        let micros = timestamp_micros(&timestamp.with_timezone(&Utc));
+                        encoding::int64::encode(tag, &micros, &mut row)
+                    }
+                }
+                (BigqueryFieldType::Json, value) => {
+                    if let Ok(json) = serde_json::to_string(value) {
+                        encoding::string::encode(tag, &json, &mut row)
+                    }
+                }
+                _ => {}
+            }
+        }
+        row
+    }
+}
+
+fn timestamp_micros(timestamp: &DateTime<Utc>) -> i64 {
+    timestamp.timestamp() * 1_000_000 + i64::from(timestamp.timestamp_subsec_micros())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use prost::Message;
+
+    use super::*;
+
+    #[derive(Clone, PartialEq, Message)]
+    struct Row {
+        #[prost(string, optional, tag = "1")]
+        message: Option<String>,
+        #[prost(int64, optional, tag = "2")]
+        status: Option<i64>,
+        #[prost(double, optional, tag = "3")]
+        duration: Option<f64>,
+        #[prost(bool, optional, tag = "4")]
+        success: Option<bool>,
+        #[prost(int64, optional, tag = "5")]
+        timestamp: Option<i64>,
+        #[prost(string, optional, tag = "6")]
+        request: Option<String>,
+    }
+
+    #[derive(Deserialize)]
+    struct Schema {
+        schema: Vec<BigqueryField>,
+    }
+
+    fn encoder() -> RowEncoder {
+        let config: Schema = toml::from_str(
+            r#"
+            schema = [
+                { name = "message", type = "string" },
+                { name = "status", field = "response.status", type = "int64" },
+                { name = "duration", type = "float64" },
+                { name = "success", type = "bool" },
+                { name = "timestamp", type = "timestamp" },
+                { name = "request", type = "json" },
+            ]
+            "#,
+        )
+        .unwrap();
+        RowEncoder::new(config.schema).unwrap()
+    }
+
+    #[test]
+    fn encodes_rows() {
+        let mut log = LogEvent::from("hello");
+        log.insert("response.status", 200);
+        log.insert("duration", 12);
+        log.insert("success", true);
+        log.insert(
+            "timestamp",
+            Utc.ymd(2022, 6, 1).and_hms_micro(12, 0, 0, 123_456),
+        );
+        log.insert("request.path", "/index.html");
+
+        let row = Row::decode(encoder().encode(&log).as_slice()).unwrap();
+        assert_eq!(
+            row,
+            Row {
+                message: Some("hello".into()),
+                status: Some(200),
+                duration: Some(12.0),
+                success: Some(true),
+                timestamp: Some(1_654_084_800_123_456),
+                request: Some(r#"{"path":"/index.html"}"#.into()),
+            }
+        );
+    }
+
+    #[test]
+    fn leaves_missing_and_mismatched_fields_null() {
+        let mut log = LogEvent::default();
+        log.insert("response.status", "OK");
+        log.insert("success", false);
+        log.insert("timestamp", "2022-06-01T12:00:00Z");
+
+        let row = Row::decode(encoder().encode(&log).as_slice()).unwrap();
+        assert_eq!(
+            row,
+            Row {
+                success: Some(false),
+                timestamp: Some(1_654_084_800_000_000),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn describes_rows() {
+        let schema = encoder().writer_schema();
+        let descriptor = schema.proto_descriptor.unwrap();
+        let fields = descriptor
+            .field
+            .iter()
+            .map(|field| (field.name(), field.number(), field.r#type()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                ("message", 1, Type::String),
+                ("status", 2, Type::Int64),
+                ("duration", 3, Type::Double),
+                ("success", 4, Type::Bool),
+                ("timestamp", 5, Type::Int64),
+                ("request", 6, Type::String),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_schemas() {
+        assert!(matches!(
+            RowEncoder::new(Vec::new()),
+            Err(BigquerySinkError::EmptySchema)
+        ));
+        let field = BigqueryField {
+            name: "message".into(),
+            field: None,
+            field_type: BigqueryFieldType::String,
+        };
+        assert!(matches!(
+            RowEncoder::new(vec![field.clone(), field]),
+            Err(BigquerySinkError::DuplicateField { .. })
+        ));
+    }
+}
@@ -0,0 +1,182 @@
+use std::{
+    convert::TryFrom,
+    task::{Context, Poll},
+};
+
+use futures::{future::BoxFuture, stream};
+use http::uri::Uri;
+use tonic::{metadata::MetadataValue, transport::Channel};
+use vector_core::{buffers::Ackable, internal_event::EventsSent, stream::DriverResponse};
+
+use super::{
+    proto::{
+        self, append_rows_request, append_rows_response,
+        big_query_write_client::BigQueryWriteClient,
+    },
+    BigquerySinkError,
+};
+use crate::{
+    event::{EventFinalizers, EventStatus, Finalizable},
+    gcp::GcpCredentials,
+    internal_events::EndpointBytesSent,
+    sinks::util::uri,
+    Error,
+};
+
+/// Identifies the rows appended by Vector in the traces of the Storage Write API.
+const TRACE_ID: &str = "vector";
+
+#[derive(Clone)]
+pub struct BigqueryRequest {
+    /// The default stream of the table the rows are appended to.
+    pub write_stream: String,
+    pub rows: Vec<Vec<u8>>,
+    pub finalizers: EventFinalizers,
+    pub events_byte_size: usize,
+}
+
+impl Ackable for BigqueryRequest {
+    fn ack_size(&self) -> usize {
+        self.rows.len()
+    }
+}
+
+impl Finalizable for BigqueryRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        self.finalizers.take_finalizers()
+    }
+}
+
+pub struct BigqueryResponse {
+    events_count: usize,
+    events_byte_size: usize,
+}
+
+impl DriverResponse for BigqueryResponse {
+    fn event_status(&self) -> EventStatus {
+        EventStatus::Delivered
+    }
+
+    fn events_sent(&self) -> EventsSent {
+        EventsSent {
+            count: self.events_count,
+            byte_size: self.events_byte_size,
+            output: None,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct BigqueryService {
+    client: BigQueryWriteClient<Channel>,
+    credentials: Option<GcpCredentials>,
+    writer_schema: proto::ProtoSchema,
+    protocol: String,
+    endpoint: String,
+}
+
+impl BigqueryService {
+    pub fn new(
+        client: BigQueryWriteClient<Channel>,
+        credentials: Option<GcpCredentials>,
+        writer_schema: proto::ProtoSchema,
+        uri: &Uri,
+    ) -> Self {
+        let (protocol, endpoint) = uri::protocol_endpoint(uri.clone());
+        Self {
+            client,
+            credentials,
+            writer_schema,
+            protocol,
+            endpoint,
+        }
+    }
+}
+
+impl tower::Service<BigqueryRequest> for BigqueryService {
+    type Response = BigqueryResponse;
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: BigqueryRequest) -> Self::Future {
+        let service = self.clone();
+        let events_count = request.rows.len();
+        let events_byte_size = request.events_byte_size;
+        let byte_size = request.rows.iter().map(Vec::len).sum();
+
+        // Each request opens its own append stream, so it carries the schema of the rows.
+        let append = proto::AppendRowsRequest {
+            write_stream: request.write_stream,
+            offset: None,
+            trace_id: TRACE_ID.to_owned(),
+            rows: Some(append_rows_request::Rows::ProtoRows(
+                append_rows_request::ProtoData {
+                    writer_schema: Some(self.writer_schema.clone()),
+                    rows: Some(proto::ProtoRows {
+                        serialized_rows: request.rows,
+                    }),
+                },
+            )),
+        };
+
+        Box::pin(async move {
+            let BigqueryService {
+                mut client,
+                credentials,
+                protocol,
+                endpoint,
+                ..
+            } = service;
+
+            let mut request = tonic::Request::new(stream::iter(Some(append)));
+            if let Some(credentials) = &credentials {
+                let authorization = MetadataValue::try_from(&credentials.make_token())
+                    .map_err(|_| BigquerySinkError::InvalidToken)?;
+                request
+                    .metadata_mut()
+                    .insert("authorization", authorization);
+            }
+
+            let mut responses = client
+                .append_rows(request)
+                .await
+                .map_err(|source| BigquerySinkError::Request { source })?
+                .into_inner();
+            let response = responses
+                .message()
+                .await
+                .map_err(|source| BigquerySinkError::Request { source })?
+                .ok_or(BigquerySinkError::NoResponse)?;
+
+            if let Some(error) = response.row_errors.first() {
+                return Err(BigquerySinkError::RowErrors {
+                    count: response.row_errors.len(),
+                    index: error.index,
+                    message: error.message.clone(),
+                }
+                .into());
+            }
+            if let Some(append_rows_response::Response::Error(status)) = response.response {
+                return Err(BigquerySinkError::Append {
+                    code: status.code,
+                    message: status.message,
+                }
+                .into());
+            }
+
+            emit!(EndpointBytesSent {
+                byte_size,
+                protocol: &protocol,
+                endpoint: &endpoint,
+            });
+            Ok(BigqueryResponse {
+                events_count,
+                events_byte_size,
+            })
+        })
+    }
+}
@@ -0,0 +1,97 @@
+use std::fmt;
+
+use async_trait::async_trait;
+use futures::{stream::BoxStream, StreamExt};
+use tower::Service;
+use vector_core::{
+    buffers::Acker,
+    partition::Partitioner,
+    stream::{BatcherSettings, DriverResponse},
+    ByteSizeOf,
+};
+
+use super::{row::RowEncoder, service::BigqueryRequest};
+use crate::{
+    event::{Event, Finalizable},
+    internal_events::TemplateRenderingError,
+    sinks::util::{SinkBuilderExt, StreamSink},
+    template::Template,
+};
+
+/// Partitions the events by the table they're appended to.
+struct TablePartitioner(Template);
+
+impl Partitioner for TablePartitioner {
+    type Item = Event;
+    type Key = Option<String>;
+
+    fn partition(&self, item: &Self::Item) -> Self::Key {
+        self.0
+            .render_string(item)
+            .map_err(|error| {
+                emit!(TemplateRenderingError {
+                    error,
+                    field: Some("table"),
+                    drop_event: true,
+                });
+            })
+            .ok()
+    }
+}
+
+pub struct BigquerySink<S> {
+    pub service: S,
+    pub batch_settings: BatcherSettings,
+    pub acker: Acker,
+    pub table: Template,
+    pub encoder: RowEncoder,
+    /// The path of the tables of the dataset, which the name of the table is appended to.
+    pub stream_prefix: String,
+}
+
+impl<S> BigquerySink<S>
+where
+    S: Service<BigqueryRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: fmt::Debug + Into<crate::Error> + Send,
+{
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let encoder = self.encoder;
+        let stream_prefix = self.stream_prefix;
+
+        input
+            .batched_partitioned(TablePartitioner(self.table), self.batch_settings)
+            .filter_map(|(table, events)| async move { table.map(move |table| (table, events)) })
+            .map(move |(table, mut events)| {
+                let finalizers = events.take_finalizers();
+                let events_byte_size = events.size_of();
+                let rows = events
+                    .iter()
+                    .map(|event| encoder.encode(event.as_log()))
+                    .collect();
+                BigqueryRequest {
+                    write_stream: format!("{}{}/streams/_default", stream_prefix, table),
+                    rows,
+                    finalizers,
+                    events_byte_size,
+                }
+            })
+            .into_driver(self.service, self.acker)
+            .run()
+            .await
+    }
+}
+
+#[async_trait]
+impl<S> StreamSink<Event> for BigquerySink<S>
+where
+    S: Service<BigqueryRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: fmt::Debug + Into<crate::Error> + Send,
+{
+    async fn run(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
@@ -38,6 +38,7 @@ use crate::{
                 EncodingConfig, EncodingConfigWithFramingAdapter, StandardEncodings,
                 StandardEncodingsWithFramingMigrator, Transformer,
             },
+            manifest::{ManifestConfig, ManifestService},
             metadata::{RequestMetadata, RequestMetadataBuilder},
             partitioner::KeyPartitioner,
             request_builder::EncodeResult,
@@ -70,6 +71,7 @@ pub struct GcsSinkConfig {
     filename_time_format: Option<String>,
     filename_append_uuid: Option<bool>,
     filename_extension: Option<String>,
+    manifest: Option<ManifestConfig>,
     #[serde(flatten)]
     encoding: EncodingConfigWithFramingAdapter<
         EncodingConfig<StandardEncodings>,
@@ -103,6 +105,7 @@ fn default_config(e: StandardEncodings) -> GcsSinkConfig {
         filename_time_format: Default::default(),
         filename_append_uuid: Default::default(),
         filename_extension: Default::default(),
+        manifest: Default::default(),
         encoding: EncodingConfig::from(e).into(),
         compression: Compression::gzip_default(),
         batch: Default::default(),
@@ -180,9 +183,12 @@ impl GcsSinkConfig {
 
         let partitioner = self.key_partitioner()?;
 
-        let svc = ServiceBuilder::new()
-            .settings(request, GcsRetryLogic)
-            .service(GcsService::new(client, base_url, creds));
+        let svc = GcsService::new(client, base_url, creds);
+        let svc = ManifestService::new(self.manifest.clone(), svc.clone(), || {
+            ServiceBuilder::new()
+                .settings(request.clone(), GcsRetryLogic)
+                .service(svc.clone())
+        });
 
         let request_settings = RequestSettings::new(self)?;
 
@@ -4,7 +4,7 @@ use bytes::Bytes;
 use futures::future::BoxFuture;
 use http::{
     header::{HeaderName, HeaderValue},
-    Request, Uri,
+    Request, StatusCode, Uri,
 };
 use hyper::Body;
 use tower::Service;
@@ -12,10 +12,14 @@ use vector_common::internal_event::BytesSent;
 use vector_core::{buffers::Ackable, internal_event::EventsSent, stream::DriverResponse};
 
 use crate::{
-    event::{EventFinalizers, EventStatus, Finalizable},
+    event::{Event, EventFinalizers, EventStatus, Finalizable},
     gcp::GcpCredentials,
     http::{get_http_scheme_from_uri, HttpClient, HttpError},
-    sinks::util::metadata::RequestMetadata,
+    sinks::util::{
+        manifest::{ManifestEntry, ManifestRequest, ObjectReader},
+        metadata::RequestMetadata,
+        request_builder::EncodeResult,
+    },
 };
 
 #[derive(Debug, Clone)]
@@ -60,6 +64,33 @@ impl Finalizable for GcsRequest {
     }
 }
 
+impl ManifestRequest for GcsRequest {
+    fn manifest_entry(&self) -> ManifestEntry {
+        ManifestEntry {
+            key: self.key.clone(),
+            records: self.metadata.event_count(),
+            bytes: self.body.len(),
+        }
+    }
+
+    fn manifest_request(&self, key: String, body: Bytes) -> Self {
+        let metadata = RequestMetadata::builder(Vec::<Event>::new())
+            .build(&EncodeResult::uncompressed(body.clone()));
+
+        GcsRequest {
+            key,
+            body,
+            settings: GcsRequestSettings {
+                content_type: HeaderValue::from_static("application/json"),
+                content_encoding: None,
+                ..self.settings.clone()
+            },
+            finalizers: EventFinalizers::default(),
+            metadata,
+        }
+    }
+}
+
 // Settings required to produce a request that do not change per
 // request. All possible values are pre-computed for direct use in
 // producing a request.
@@ -150,3 +181,31 @@ impl Service<GcsRequest> for GcsService {
         })
     }
 }
+
+impl ObjectReader<GcsRequest> for GcsService {
+    fn read_object(
+        &self,
+        _request: &GcsRequest,
+        key: String,
+    ) -> BoxFuture<'static, crate::Result<Option<Bytes>>> {
+        let mut client = self.client.clone();
+        let uri = format!("{}{}", self.base_url, key);
+        let creds = self.creds.clone();
+
+        Box::pin(async move {
+            let mut request = Request::get(uri.parse::<Uri>()?).body(Body::empty())?;
+            if let Some(creds) = &creds {
+                creds.apply(&mut request);
+            }
+
+            let response = client.call(request).await?;
+            match response.status() {
+                StatusCode::NOT_FOUND => Ok(None),
+                status if status.is_success() => {
+                    Ok(Some(hyper::body::to_bytes(response.into_body()).await?))
+                }
+                status => Err(format!("Unexpected status reading {}: {}.", uri, status).into()),
+            }
+        })
+    }
+}
@@ -0,0 +1,162 @@
+use std::{path::PathBuf, time::Duration};
+
+use futures::future;
+use http::uri::PathAndQuery;
+use hyper::Client;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use tower::ServiceBuilder;
+
+use super::{
+    encoder::MessageEncoder,
+    service::{GrpcResponse, GrpcService},
+    sink::GrpcSink,
+    GrpcSinkError,
+};
+use crate::{
+    config::{AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext},
+    grpc,
+    http::{Auth, HttpClient, MaybeAuth},
+    sinks::{
+        util::{
+            grpc::GrpcChannel, retries::RetryLogic, BatchConfig,
+            RealtimeEventBasedDefaultBatchSettings, ServiceBuilderExt, TowerRequestConfig,
+            UriSerde,
+        },
+        Healthcheck, VectorSink,
+    },
+    template::Template,
+    tls::{MaybeTlsSettings, TlsEnableableConfig},
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct GrpcSinkConfig {
+    endpoint: UriSerde,
+    /// The path of the file descriptor set describing the service.
+    descriptor_set: PathBuf,
+    /// The method to call, as in `package.Service/Method`.
+    method: String,
+    /// The templates of the fields of the request messages. When empty, the fields of the events
+    /// are mapped onto the fields of the messages with the same names.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    fields: IndexMap<String, Template>,
+    #[serde(default)]
+    compression: bool,
+    /// The deadline of the calls, which the server is told about.
+    deadline_secs: Option<f64>,
+    auth: Option<Auth>,
+    #[serde(default)]
+    batch: BatchConfig<RealtimeEventBasedDefaultBatchSettings>,
+    #[serde(default)]
+    request: TowerRequestConfig,
+    tls: Option<TlsEnableableConfig>,
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+impl GenerateConfig for GrpcSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"endpoint = "http://localhost:50051"
+            descriptor_set = "/etc/vector/service.desc"
+            method = "package.Service/Method""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "grpc")]
+impl SinkConfig for GrpcSinkConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let method = grpc::load_method(&self.descriptor_set, &self.method).await?;
+        let encoder = MessageEncoder::new(method.input(), &self.fields)?;
+
+        let mut batch = self.batch.validate()?;
+        if !method.is_client_streaming() {
+            // Unary methods are sent a single message per call.
+            batch.max_events = Some(1);
+        }
+
+        let endpoint = self.endpoint.with_default_parts();
+        let auth = self.auth.choose_one(&endpoint.auth)?;
+        let tls = MaybeTlsSettings::from_config(&self.tls, false)?;
+        let client = HttpClient::new_with_custom_client(
+            tls,
+            cx.proxy(),
+            Client::builder().http2_only(true),
+        )?;
+        let channel = GrpcChannel::new(client, &endpoint.uri, auth)?;
+        let path = grpc::method_path(&method).parse::<PathAndQuery>()?;
+        let deadline = self.deadline_secs.map(Duration::from_secs_f64);
+        let service = GrpcService::new(
+            channel,
+            path,
+            method.is_client_streaming(),
+            deadline,
+            self.compression,
+        );
+
+        let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
+        let service = ServiceBuilder::new()
+            .settings(request_settings, GrpcRetryLogic)
+            .service(service);
+
+        let sink = GrpcSink {
+            batch_settings: batch.into_batcher_settings()?,
+            encoder,
+            service,
+            acker: cx.acker(),
+        };
+
+        // There is no way to check the health of an arbitrary service without calling it.
+        Ok((
+            VectorSink::from_event_streamsink(sink),
+            Box::pin(future::ok(())),
+        ))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "grpc"
+    }
+
+    fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
+        Some(&self.acknowledgements)
+    }
+}
+
+/// Retries the calls failing with the statuses meaning the server can't handle them for now.
+#[derive(Debug, Clone)]
+struct GrpcRetryLogic;
+
+impl RetryLogic for GrpcRetryLogic {
+    type Error = GrpcSinkError;
+    type Response = GrpcResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        use tonic::Code::*;
+
+        match error {
+            // The transport errors have the `UNKNOWN` status.
+            GrpcSinkError::Request { source } => matches!(
+                source.code(),
+                Unknown
+                    | Cancelled
+                    | DeadlineExceeded
+                    | ResourceExhausted
+                    | Aborted
+                    | Internal
+                    | Unavailable
+            ),
+        }
+    }
+}
@@ -0,0 +1,423 @@
+use std::collections::{BTreeMap, HashMap};
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use indexmap::IndexMap;
+use prost::Message;
+use prost_reflect::{
+    DynamicMessage, FieldDescriptor, Kind, MapKey, MessageDescriptor, Value as ProtobufValue,
+};
+use snafu::Snafu;
+use vector_core::ByteSizeOf;
+
+use super::BuildError;
+use crate::{
+    dropped_events,
+    event::{Event, EventFinalizers, EventStatus, Finalizable, LogEvent, Value},
+    internal_events::GrpcMessageEncodeError,
+    template::{Template, TemplateRenderingError},
+};
+
+#[derive(Debug, Snafu)]
+pub(super) enum EncodingError {
+    #[snafu(display("Failed to render the template of `{}`: {}", field, source))]
+    Template {
+        field: String,
+        source: TemplateRenderingError,
+    },
+    #[snafu(display("The value of `{}` can't be converted to {}", field, expected))]
+    InvalidValue { field: String, expected: String },
+}
+
+/// An event encoded as a request message of the method, before it's batched with the others.
+pub(super) struct EncodedMessage {
+    pub(super) message: Bytes,
+    pub(super) finalizers: EventFinalizers,
+    pub(super) event_byte_size: usize,
+}
+
+impl ByteSizeOf for EncodedMessage {
+    // `ByteSizeOf` is used by the batcher, to limit the size of the requests.
+    fn size_of(&self) -> usize {
+        self.message.len()
+    }
+
+    fn allocated_bytes(&self) -> usize {
+        0
+    }
+}
+
+/// Encodes the events as request messages, either with a template for each field of the message,
+/// or by mapping the fields of the events onto the fields of the message with the same names.
+#[derive(Clone, Debug)]
+pub(super) struct MessageEncoder {
+    descriptor: MessageDescriptor,
+    templates: Option<Vec<(FieldDescriptor, Template)>>,
+}
+
+impl MessageEncoder {
+    pub(super) fn new(
+        descriptor: MessageDescriptor,
+        fields: &IndexMap<String, Template>,
+    ) -> Result<Self, BuildError> {
+        let templates = if fields.is_empty() {
+            None
+        } else {
+            let templates = fields
+                .iter()
+                .map(|(name, template)| {
+                    let field = descriptor.get_field_by_name(name).ok_or_else(|| {
+                        BuildError::UnknownField {
+                            message: descriptor.full_name().to_owned(),
+                            field: name.clone(),
+                        }
+                    })?;
+                    // Templates render to strings, which can only be converted to scalar values.
+                    if field.is_list() || field.is_map() || matches!(field.kind(), Kind::Message(_))
+                    {
+                        return Err(BuildError::UnsupportedTemplateField {
+                            field: name.clone(),
+                        });
+                    }
+                    Ok((field, template.clone()))
+                })
+                .collect::<Result<_, _>>()?;
+            Some(templates)
+        };
+
+        Ok(Self {
+            descriptor,
+            templates,
+        })
+    }
+
+    /// Encodes an event as a message, rejecting it if it can't be encoded.
+    pub(super) fn encode_event(&self, event: Event) -> Option<EncodedMessage> {
+        let event_byte_size = event.size_of();
+        let mut log = event.into_log();
+
+        match self.encode(&log) {
+            Ok(message) => Some(EncodedMessage {
+                message: message.encode_to_vec().into(),
+                finalizers: log.take_finalizers(),
+                event_byte_size,
+            }),
+            Err(error) => {
+                log.metadata().update_status(EventStatus::Rejected);
+                dropped_events::sample(&log.into(), &error);
+                emit!(GrpcMessageEncodeError {
+                    error,
+                    message_type: self.descriptor.full_name(),
+                });
+                None
+            }
+        }
+    }
+
+    fn encode(&self, log: &LogEvent) -> Result<DynamicMessage, EncodingError> {
+        match &self.templates {
+            Some(templates) => {
+                let mut message = DynamicMessage::new(self.descriptor.clone());
+                for (field, template) in templates {
+                    let rendered =
+                        template
+                            .render_string(log)
+                            .map_err(|source| EncodingError::Template {
+                                field: field.name().to_owned(),
+                                source,
+                            })?;
+                    let value = Value::from(rendered);
+                    message.set_field(field, to_field_value(&value, field, field.name())?);
+                }
+                Ok(message)
+            }
+            None => {
+                let empty = BTreeMap::new();
+                to_message(log.as_map().unwrap_or(&empty), &self.descriptor, "")
+            }
+        }
+    }
+}
+
+/// Converts the fields of an object, at `path` in the event, to the fields of the message with the
+/// same names. Fields the message doesn't have, and null fields, are left out.
+fn to_message(
+    object: &BTreeMap<String, Value>,
+    descriptor: &MessageDescriptor,
+    path: &str,
+) -> Result<DynamicMessage, EncodingError> {
+    let mut message = DynamicMessage::new(descriptor.clone());
+    for field in descriptor.fields() {
+        match object.get(field.name()) {
+            None | Some(Value::Null) => continue,
+            Some(value) => {
+                let path = if path.is_empty() {
+                    field.name().to_owned()
+                } else {
+                    format!("{}.{}", path, field.name())
+                };
+                message.set_field(&field, to_field_value(value, &field, &path)?);
+            }
+        }
+    }
+    Ok(message)
+}
+
+fn to_field_value(
+    value: &Value,
+    field: &FieldDescriptor,
+    path: &str,
+) -> Result<ProtobufValue, EncodingError> {
+    if field.is_map() {
+        let entry = match field.kind() {
+            Kind::Message(entry) => entry,
+            _ => unreachable!("map fields are messages"),
+        };
+        let key_kind = entry.map_entry_key_field().kind();
+        let value_field = entry.map_entry_value_field();
+        match value {
+            Value::Object(object) => object
+                .iter()
+                .map(|(key, value)| {
+                    let path = format!("{}.{}", path, key);
+                    Ok((
+                        to_map_key(key, &key_kind, &path)?,
+                        to_value(value, &value_field.kind(), &path)?,
+                    ))
+                })
+                .collect::<Result<HashMap<_, _>, _>>()
+                .map(ProtobufValue::Map),
+            _ => Err(invalid(path, "a map")),
+        }
+    } else if field.is_list() {
+        match value {
+            Value::Array(values) => values
+                .iter()
+                .enumerate()
+                .map(|(index, value)| {
+                    to_value(value, &field.kind(), &format!("{}[{}]", path, index))
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(ProtobufValue::List),
+            // A single value is a list of one value.
+            value => Ok(ProtobufValue::List(vec![to_value(
+                value,
+                &field.kind(),
+                path,
+            )?])),
+        }
+    } else {
+        to_value(value, &field.kind(), path)
+    }
+}
+
+/// Converts a value to a value of the kind, parsing the strings to numbers, booleans and enum
+/// values, as in the JSON mapping of Protobuf.
+fn to_value(value: &Value, kind: &Kind, path: &str) -> Result<ProtobufValue, EncodingError> {
+    let converted = match kind {
+        Kind::String => Some(ProtobufValue::String(match value {
+            Value::Object(_) | Value::Array(_) => {
+                serde_json::to_string(value).map_err(|_| invalid(path, "a string"))?
+            }
+            value => value.to_string_lossy(),
+        })),
+        Kind::Bytes => match value {
+            Value::Bytes(bytes) => Some(ProtobufValue::Bytes(bytes.clone())),
+            _ => None,
+        },
+        Kind::Bool => match value {
+            Value::Boolean(boolean) => Some(ProtobufValue::Bool(*boolean)),
+            Value::Bytes(bytes) => match &bytes[..] {
+                b"true" => Some(ProtobufValue::Bool(true)),
+                b"false" => Some(ProtobufValue::Bool(false)),
+                _ => None,
+            },
+            _ => None,
+        },
+        Kind::Int32 | Kind::Sint32 | Kind::Sfixed32 => to_integer(value)
+            .and_then(|integer| i32::try_from(integer).ok().map(ProtobufValue::I32)),
+        Kind::Int64 | Kind::Sint64 | Kind::Sfixed64 => to_integer(value).map(ProtobufValue::I64),
+        Kind::Uint32 | Kind::Fixed32 => to_integer(value)
+            .and_then(|integer| u32::try_from(integer).ok().map(ProtobufValue::U32)),
+        Kind::Uint64 | Kind::Fixed64 => to_integer(value)
+            .and_then(|integer| u64::try_from(integer).ok().map(ProtobufValue::U64)),
+        Kind::Float => to_float(value).map(|float| ProtobufValue::F32(float as f32)),
+        Kind::Double => to_float(value).map(ProtobufValue::F64),
+        Kind::Enum(descriptor) => match value {
+            Value::Integer(number) => i32::try_from(*number).ok(),
+            Value::Bytes(bytes) => std::str::from_utf8(bytes)
+                .ok()
+                .and_then(|name| descriptor.get_value_by_name(name))
+                .map(|value| value.number()),
+            _ => None,
+        }
+        .map(ProtobufValue::EnumNumber),
+        Kind::Message(descriptor) if descriptor.full_name() == "google.protobuf.Timestamp" => {
+            to_timestamp(value).map(|timestamp| {
+                let mut message = DynamicMessage::new(descriptor.clone());
+                message.set_field_by_name("seconds", ProtobufValue::I64(timestamp.timestamp()));
+                message.set_field_by_name(
+                    "nanos",
+                    ProtobufValue::I32(timestamp.timestamp_subsec_nanos() as i32),
+                );
+                ProtobufValue::Message(message)
+            })
+        }
+        Kind::Message(descriptor) => match value {
+            Value::Object(object) => Some(ProtobufValue::Message(to_message(
+                object, descriptor, path,
+            )?)),
+            _ => None,
+        },
+    };
+    converted.ok_or_else(|| invalid(path, kind_name(kind)))
+}
+
+fn to_map_key(key: &str, kind: &Kind, path: &str) -> Result<MapKey, EncodingError> {
+    let converted = match kind {
+        Kind::String => Some(MapKey::String(key.to_owned())),
+        Kind::Bool => key.parse().ok().map(MapKey::Bool),
+        Kind::Int32 | Kind::Sint32 | Kind::Sfixed32 => key.parse().ok().map(MapKey::I32),
+        Kind::Int64 | Kind::Sint64 | Kind::Sfixed64 => key.parse().ok().map(MapKey::I64),
+        Kind::Uint32 | Kind::Fixed32 => key.parse().ok().map(MapKey::U32),
+        Kind::Uint64 | Kind::Fixed64 => key.parse().ok().map(MapKey::U64),
+        _ => None,
+    };
+    converted.ok_or_else(|| invalid(path, kind_name(kind)))
+}
+
+fn to_integer(value: &Value) -> Option<i64> {
+    match value {
+        Value::Integer(integer) => Some(*integer),
+        Value::Bytes(bytes) => std::str::from_utf8(bytes).ok()?.parse().ok(),
+        _ => None,
+    }
+}
+
+fn to_float(value: &Value) -> Option<f64> {
+    match value {
+        Value::Float(float) => Some(float.into_inner()),
+        Value::Integer(integer) => Some(*integer as f64),
+        Value::Bytes(bytes) => std::str::from_utf8(bytes).ok()?.parse().ok(),
+        _ => None,
+    }
+}
+
+fn to_timestamp(value: &Value) -> Option<DateTime<Utc>> {
+    match value {
+        Value::Timestamp(timestamp) => Some(*timestamp),
+        Value::Bytes(bytes) => DateTime::parse_from_rfc3339(std::str::from_utf8(bytes).ok()?)
+            .ok()
+            .map(|timestamp| timestamp.with_timezone(&Utc)),
+        _ => None,
+    }
+}
+
+fn kind_name(kind: &Kind) -> &'static str {
+    match kind {
+        Kind::String => "a string",
+        Kind::Bytes => "bytes",
+        Kind::Bool => "a boolean",
+        Kind::Int32 | Kind::Sint32 | Kind::Sfixed32 => "a 32-bit integer",
+        Kind::Int64 | Kind::Sint64 | Kind::Sfixed64 => "a 64-bit integer",
+        Kind::Uint32 | Kind::Fixed32 => "an unsigned 32-bit integer",
+        Kind::Uint64 | Kind::Fixed64 => "an unsigned 64-bit integer",
+        Kind::Float | Kind::Double => "a number",
+        Kind::Enum(_) => "an enum value",
+        Kind::Message(_) => "a message",
+    }
+}
+
+fn invalid(path: &str, expected: &str) -> EncodingError {
+    EncodingError::InvalidValue {
+        field: path.to_owned(),
+        expected: expected.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prost_reflect::DescriptorPool;
+
+    use super::*;
+    use crate::grpc::tests::descriptor_set;
+
+    fn descriptor() -> MessageDescriptor {
+        DescriptorPool::from_file_descriptor_set(descriptor_set())
+            .unwrap()
+            .get_message_by_name("test.v1.Log")
+            .unwrap()
+    }
+
+    fn decode(encoded: &EncodedMessage) -> DynamicMessage {
+        DynamicMessage::decode(descriptor(), encoded.message.clone()).unwrap()
+    }
+
+    #[test]
+    fn maps_fields_by_name() {
+        let encoder = MessageEncoder::new(descriptor(), &IndexMap::new()).unwrap();
+        let mut log = LogEvent::from("hello");
+        log.insert("severity", "3");
+        log.insert("host", "example.com");
+
+        let message = decode(&encoder.encode_event(log.into()).unwrap());
+        assert_eq!(
+            message.get_field_by_name("message").unwrap().as_str(),
+            Some("hello")
+        );
+        assert_eq!(
+            message.get_field_by_name("severity").unwrap().as_i32(),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn renders_templates() {
+        let mut fields = IndexMap::new();
+        fields.insert(
+            "message".to_owned(),
+            Template::try_from("{{ host }}: {{ message }}").unwrap(),
+        );
+        fields.insert(
+            "severity".to_owned(),
+            Template::try_from("{{ level }}").unwrap(),
+        );
+        let encoder = MessageEncoder::new(descriptor(), &fields).unwrap();
+        let mut log = LogEvent::from("hello");
+        log.insert("host", "example.com");
+        log.insert("level", 5);
+
+        let message = decode(&encoder.encode_event(log.into()).unwrap());
+        assert_eq!(
+            message.get_field_by_name("message").unwrap().as_str(),
+            Some("example.com: hello")
+        );
+        assert_eq!(
+            message.get_field_by_name("severity").unwrap().as_i32(),
+            Some(5)
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        let encoder = MessageEncoder::new(descriptor(), &IndexMap::new()).unwrap();
+        let mut log = LogEvent::from("hello");
+        log.insert("severity", "high");
+
+        assert!(encoder.encode_event(log.into()).is_none());
+    }
+
+    #[test]
+    fn rejects_unknown_template_fields() {
+        let mut fields = IndexMap::new();
+        fields.insert(
+            "level".to_owned(),
+            Template::try_from("{{ level }}").unwrap(),
+        );
+
+        assert!(matches!(
+            MessageEncoder::new(descriptor(), &fields),
+            Err(BuildError::UnknownField { .. })
+        ));
+    }
+}
@@ -0,0 +1,43 @@
+//! The sink calling a gRPC method described by a user-provided file descriptor set, as generated
+//! by `protoc --include_imports --descriptor_set_out`.
+//!
+//! The events are encoded as the request messages of the method, with a template for each field
+//! of the message or by mapping the fields of the events onto the fields with the same names.
+//! Methods streaming their requests are sent a batch of messages per call, and unary methods a
+//! single message.
+
+use snafu::Snafu;
+
+use crate::config::SinkDescription;
+
+mod config;
+mod encoder;
+mod service;
+mod sink;
+
+#[cfg(test)]
+mod tests;
+
+pub use self::config::GrpcSinkConfig;
+
+inventory::submit! {
+    SinkDescription::new::<GrpcSinkConfig>("grpc")
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("The message {:?} has no field {:?}", message, field))]
+    UnknownField { message: String, field: String },
+    #[snafu(display(
+        "The field {:?} is repeated, a map or a message, which can't be set with a template",
+        field
+    ))]
+    UnsupportedTemplateField { field: String },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(super)))]
+pub enum GrpcSinkError {
+    #[snafu(display("gRPC request failed: {}", source))]
+    Request { source: tonic::Status },
+}
@@ -0,0 +1,159 @@
+use std::{
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use bytes::Bytes;
+use futures::{future::BoxFuture, stream};
+use http::uri::PathAndQuery;
+use snafu::ResultExt;
+use tonic::{client::Grpc, Code, Request, Status};
+use tower::Service;
+use vector_common::internal_event::{BytesSent, EventsSent};
+use vector_core::{buffers::Ackable, stream::DriverResponse};
+
+use super::{GrpcSinkError, RequestSnafu};
+use crate::{
+    event::{EventFinalizers, EventStatus, Finalizable},
+    grpc::BytesCodec,
+    sinks::util::grpc::GrpcChannel,
+};
+
+/// The request messages of a call, a single one for unary methods.
+#[derive(Clone, Debug)]
+pub(super) struct GrpcRequest {
+    pub(super) messages: Vec<Bytes>,
+    pub(super) finalizers: EventFinalizers,
+    pub(super) events_byte_size: usize,
+}
+
+impl Ackable for GrpcRequest {
+    fn ack_size(&self) -> usize {
+        self.messages.len()
+    }
+}
+
+impl Finalizable for GrpcRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        std::mem::take(&mut self.finalizers)
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct GrpcResponse {
+    events_count: usize,
+    events_byte_size: usize,
+    byte_size: usize,
+    protocol: &'static str,
+}
+
+impl DriverResponse for GrpcResponse {
+    fn event_status(&self) -> EventStatus {
+        EventStatus::Delivered
+    }
+
+    fn events_sent(&self) -> EventsSent {
+        EventsSent {
+            count: self.events_count,
+            byte_size: self.events_byte_size,
+            output: None,
+        }
+    }
+
+    fn bytes_sent(&self) -> Option<BytesSent> {
+        Some(BytesSent {
+            byte_size: self.byte_size,
+            protocol: self.protocol,
+        })
+    }
+}
+
+/// Calls the method with the encoded messages, whose responses are ignored.
+#[derive(Clone, Debug)]
+pub(super) struct GrpcService {
+    client: Grpc<GrpcChannel>,
+    /// The path of the method, as in `/package.Service/Method`.
+    path: PathAndQuery,
+    client_streaming: bool,
+    /// The deadline of the calls, sent to the server along with them.
+    deadline: Option<Duration>,
+    protocol: &'static str,
+}
+
+impl GrpcService {
+    pub(super) fn new(
+        channel: GrpcChannel,
+        path: PathAndQuery,
+        client_streaming: bool,
+        deadline: Option<Duration>,
+        compression: bool,
+    ) -> Self {
+        let protocol = channel.protocol();
+        let mut client = Grpc::new(channel);
+        if compression {
+            client = client.send_gzip();
+        }
+        Self {
+            client,
+            path,
+            client_streaming,
+            deadline,
+            protocol,
+        }
+    }
+}
+
+impl Service<GrpcRequest> for GrpcService {
+    type Response = GrpcResponse;
+    type Error = GrpcSinkError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // Readiness of the client is checked before the call in `call()`.
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: GrpcRequest) -> Self::Future {
+        let mut service = self.clone();
+        let events_count = request.messages.len();
+        let byte_size = request.messages.iter().map(Bytes::len).sum();
+
+        Box::pin(async move {
+            service.client.ready().await.map_err(|error| {
+                let status =
+                    Status::new(Code::Unknown, format!("Service was not ready: {}", error));
+                GrpcSinkError::Request { source: status }
+            })?;
+
+            let path = service.path.clone();
+            if service.client_streaming {
+                let mut call = Request::new(stream::iter(request.messages));
+                set_deadline(&mut call, service.deadline);
+                service
+                    .client
+                    .client_streaming(call, path, BytesCodec)
+                    .await
+                    .map(drop)
+            } else {
+                let message = request.messages.into_iter().next().unwrap_or_default();
+                let mut call = Request::new(message);
+                set_deadline(&mut call, service.deadline);
+                service.client.unary(call, path, BytesCodec).await.map(drop)
+            }
+            .context(RequestSnafu)?;
+
+            Ok(GrpcResponse {
+                events_count,
+                events_byte_size: request.events_byte_size,
+                byte_size,
+                protocol: service.protocol,
+            })
+        })
+    }
+}
+
+fn set_deadline<T>(request: &mut Request<T>, deadline: Option<Duration>) {
+    if let Some(deadline) = deadline {
+        request.set_timeout(deadline);
+    }
+}
@@ -0,0 +1,77 @@
+use std::fmt;
+
+use async_trait::async_trait;
+use futures::{future, stream::BoxStream, StreamExt};
+use tower::Service;
+use vector_core::{
+    buffers::Acker,
+    stream::{BatcherSettings, DriverResponse},
+};
+
+use super::{
+    encoder::{EncodedMessage, MessageEncoder},
+    service::GrpcRequest,
+};
+use crate::{
+    event::{Event, EventFinalizers},
+    sinks::util::{SinkBuilderExt, StreamSink},
+};
+
+pub(super) struct GrpcSink<S> {
+    pub(super) batch_settings: BatcherSettings,
+    pub(super) encoder: MessageEncoder,
+    pub(super) service: S,
+    pub(super) acker: Acker,
+}
+
+impl<S> GrpcSink<S>
+where
+    S: Service<GrpcRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: fmt::Debug + Into<crate::Error> + Send,
+{
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let encoder = self.encoder;
+
+        input
+            .filter_map(move |event| future::ready(encoder.encode_event(event)))
+            .batched(self.batch_settings.into_byte_size_config())
+            .map(build_request)
+            .into_driver(self.service, self.acker)
+            .run()
+            .await
+    }
+}
+
+fn build_request(messages: Vec<EncodedMessage>) -> GrpcRequest {
+    let mut finalizers = EventFinalizers::default();
+    let mut events_byte_size = 0;
+    let messages = messages
+        .into_iter()
+        .map(|message| {
+            finalizers.merge(message.finalizers);
+            events_byte_size += message.event_byte_size;
+            message.message
+        })
+        .collect();
+
+    GrpcRequest {
+        messages,
+        finalizers,
+        events_byte_size,
+    }
+}
+
+#[async_trait]
+impl<S> StreamSink<Event> for GrpcSink<S>
+where
+    S: Service<GrpcRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: fmt::Debug + Into<crate::Error> + Send,
+{
+    async fn run(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
@@ -0,0 +1,182 @@
+use std::path::PathBuf;
+
+use bytes::{Buf, Bytes};
+use futures::StreamExt;
+use prost::Message;
+use prost_reflect::{DescriptorPool, DynamicMessage};
+use vector_core::event::{BatchNotifier, BatchStatus};
+
+use super::GrpcSinkConfig;
+use crate::{
+    config::{SinkConfig, SinkContext},
+    grpc::tests::descriptor_set,
+    sinks::util::test::build_test_server_generic,
+    test_util::{
+        components::{run_and_assert_sink_compliance, HTTP_SINK_TAGS},
+        next_addr, random_lines_with_stream,
+    },
+};
+
+#[test]
+fn generate_config() {
+    crate::test_util::test_generate_config::<GrpcSinkConfig>();
+}
+
+fn descriptor_set_path() -> PathBuf {
+    let path = tempfile::tempdir().unwrap().into_path().join("test.desc");
+    std::fs::write(&path, descriptor_set().encode_to_vec()).unwrap();
+    path
+}
+
+/// Splits the body of a call into its messages, following their length-prefixed framing.
+fn messages(mut body: Bytes) -> Vec<String> {
+    let descriptor = DescriptorPool::from_file_descriptor_set(descriptor_set())
+        .unwrap()
+        .get_message_by_name("test.v1.Log")
+        .unwrap();
+    let mut messages = Vec::new();
+    while body.has_remaining() {
+        let _compressed = body.get_u8();
+        let length = body.get_u32() as usize;
+        let message = DynamicMessage::decode(descriptor.clone(), body.split_to(length)).unwrap();
+        messages.push(
+            message
+                .get_field_by_name("message")
+                .unwrap()
+                .as_str()
+                .unwrap()
+                .to_owned(),
+        );
+    }
+    messages
+}
+
+fn ok_response() -> hyper::Response<hyper::Body> {
+    hyper::Response::builder()
+        .header("grpc-status", "0") // OK
+        .header("content-type", "application/grpc")
+        // An empty `Ack` message.
+        .body(hyper::Body::from(Bytes::from_static(&[0, 0, 0, 0, 0])))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn calls_unary_methods_with_each_message() {
+    let address = next_addr();
+    let config = toml::from_str::<GrpcSinkConfig>(&format!(
+        r#"
+            endpoint = "http://{}"
+            descriptor_set = "{}"
+            method = "test.v1.LogService/Push"
+            deadline_secs = 5
+        "#,
+        address,
+        descriptor_set_path().display()
+    ))
+    .unwrap();
+    let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+
+    let (rx, trigger, server) = build_test_server_generic(address, ok_response);
+    tokio::spawn(server);
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let (mut input_lines, events) = random_lines_with_stream(8, 3, Some(batch));
+    run_and_assert_sink_compliance(sink, events, &HTTP_SINK_TAGS).await;
+    drop(trigger);
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    let requests = rx.collect::<Vec<_>>().await;
+    assert_eq!(requests.len(), 3);
+    let mut output_lines = Vec::new();
+    for (parts, body) in requests {
+        assert_eq!(parts.uri.path(), "/test.v1.LogService/Push");
+        assert_eq!(parts.headers["content-type"], "application/grpc");
+        assert_eq!(parts.headers["grpc-timeout"], "5000000u");
+        output_lines.extend(messages(body));
+    }
+    // The calls are concurrent, so the messages may be received in any order.
+    output_lines.sort();
+    input_lines.sort();
+    assert_eq!(output_lines, input_lines);
+}
+
+#[tokio::test]
+async fn streams_batches_to_client_streaming_methods() {
+    let address = next_addr();
+    let config = toml::from_str::<GrpcSinkConfig>(&format!(
+        r#"
+            endpoint = "http://{}"
+            descriptor_set = "{}"
+            method = "test.v1.LogService/PushStream"
+            fields.message = "line: {{{{ message }}}}"
+        "#,
+        address,
+        descriptor_set_path().display()
+    ))
+    .unwrap();
+    let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+
+    let (rx, trigger, server) = build_test_server_generic(address, ok_response);
+    tokio::spawn(server);
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let (input_lines, events) = random_lines_with_stream(8, 10, Some(batch));
+    run_and_assert_sink_compliance(sink, events, &HTTP_SINK_TAGS).await;
+    drop(trigger);
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    let requests = rx.collect::<Vec<_>>().await;
+    assert_eq!(requests.len(), 1);
+    let (parts, body) = requests.into_iter().next().unwrap();
+    assert_eq!(parts.uri.path(), "/test.v1.LogService/PushStream");
+    let expected = input_lines
+        .iter()
+        .map(|line| format!("line: {}", line))
+        .collect::<Vec<_>>();
+    assert_eq!(messages(body), expected);
+}
+
+#[tokio::test]
+async fn rejects_on_permanent_errors() {
+    let address = next_addr();
+    let config = toml::from_str::<GrpcSinkConfig>(&format!(
+        r#"
+            endpoint = "http://{}"
+            descriptor_set = "{}"
+            method = "test.v1.LogService/PushStream"
+        "#,
+        address,
+        descriptor_set_path().display()
+    ))
+    .unwrap();
+    let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+
+    let (_rx, trigger, server) = build_test_server_generic(address, || {
+        hyper::Response::builder()
+            .header("grpc-status", "3") // invalid argument
+            .header("content-type", "application/grpc")
+            .body(tonic::body::empty_body())
+            .unwrap()
+    });
+    tokio::spawn(server);
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let (_, events) = random_lines_with_stream(8, 10, Some(batch));
+    sink.run(events).await.expect("Running sink failed");
+    drop(trigger);
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Rejected));
+}
+
+#[tokio::test]
+async fn rejects_server_streaming_methods() {
+    let config = toml::from_str::<GrpcSinkConfig>(&format!(
+        r#"
+            endpoint = "http://localhost:50051"
+            descriptor_set = "{}"
+            method = "test.v1.LogService/Watch"
+        "#,
+        descriptor_set_path().display()
+    ))
+    .unwrap();
+    assert!(config.build(SinkContext::new_test()).await.is_err());
+}
@@ -1,4 +1,4 @@
-use std::io::Write;
+use std::{io::Write, sync::Arc};
 
 use bytes::{BufMut, Bytes, BytesMut};
 use codecs::encoding::{
@@ -21,17 +21,18 @@ use tokio_util::codec::Encoder as _;
 use crate::{
     codecs::Encoder,
     config::{
-        AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext, SinkDescription,
+        AcknowledgementsConfig, Capability, GenerateConfig, Input, SinkConfig, SinkContext,
+        SinkDescription,
     },
     event::Event,
-    http::{Auth, HttpClient, MaybeAuth},
+    http::{Auth, HttpClient, MaybeAuth, TraceParent, TRACEPARENT},
     sinks::util::{
         self,
         encoding::{
             EncodingConfig, EncodingConfigWithFramingAdapter, EncodingConfigWithFramingMigrator,
             Transformer,
         },
-        http::{BatchedHttpSink, HttpEventEncoder, RequestConfig},
+        http::{BatchedHttpSink, HttpEventEncoder, RequestConfig, Traced, TracedBatch},
         BatchConfig, Buffer, Compression, RealtimeSizeBasedDefaultBatchSettings,
         TowerRequestConfig, UriSerde,
     },
@@ -90,6 +91,8 @@ pub struct HttpSinkConfig {
     #[serde(default)]
     pub request: RequestConfig,
     pub tls: Option<TlsConfig>,
+    #[serde(default)]
+    pub propagate_trace_context: bool,
     #[serde(
         default,
         deserialize_with = "crate::serde::bool_or_struct",
@@ -138,7 +141,7 @@ impl GenerateConfig for HttpSinkConfig {
 impl HttpSinkConfig {
     fn build_http_client(&self, cx: &SinkContext) -> crate::Result<HttpClient> {
         let tls = TlsSettings::from_options(&self.tls)?;
-        Ok(HttpClient::new(tls, cx.proxy())?)
+        Ok(HttpClient::new(tls, cx.proxy())?.with_trace_context(self.propagate_trace_context))
     }
 }
 
@@ -151,6 +154,7 @@ struct HttpSink {
     pub encoder: Encoder<Framer>,
     pub batch: BatchConfig<RealtimeSizeBasedDefaultBatchSettings>,
     pub request: RequestConfig,
+    pub propagate_trace_context: bool,
 }
 
 #[cfg(test)]
@@ -174,6 +178,7 @@ fn default_sink(encoding: Encoding) -> HttpSink {
         encoder,
         batch: Default::default(),
         request: Default::default(),
+        propagate_trace_context: false,
     }
 }
 
@@ -213,6 +218,7 @@ impl SinkConfig for HttpSinkConfig {
             encoder,
             batch: self.batch,
             request,
+            propagate_trace_context: self.propagate_trace_context,
         };
 
         let request = sink
@@ -223,7 +229,7 @@ impl SinkConfig for HttpSinkConfig {
         let batch = sink.batch.into_batch_settings()?;
         let sink = BatchedHttpSink::new(
             sink,
-            Buffer::new(batch.size, Compression::None),
+            TracedBatch::new(Buffer::new(batch.size, Compression::None)),
             request,
             batch.timeout,
             client,
@@ -244,6 +250,14 @@ impl SinkConfig for HttpSinkConfig {
         "http"
     }
 
+    fn capabilities(&self) -> Vec<Capability> {
+        Capability::uri(&self.uri.uri).into_iter().collect()
+    }
+
+    fn supports_sandbox(&self) -> bool {
+        true
+    }
+
     fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
         Some(&self.acknowledgements)
     }
@@ -252,33 +266,55 @@ impl SinkConfig for HttpSinkConfig {
 pub struct HttpSinkEventEncoder {
     encoder: Encoder<Framer>,
     transformer: Transformer,
+    propagate_trace_context: bool,
 }
 
-impl HttpEventEncoder<BytesMut> for HttpSinkEventEncoder {
-    fn encode_event(&mut self, mut event: Event) -> Option<BytesMut> {
+impl HttpEventEncoder<Traced<BytesMut>> for HttpSinkEventEncoder {
+    fn encode_event(&mut self, mut event: Event) -> Option<Traced<BytesMut>> {
+        let trace_parent = if self.propagate_trace_context {
+            event.metadata().trace_parent().clone()
+        } else {
+            None
+        };
+
         self.transformer.transform(&mut event);
 
         let mut body = BytesMut::new();
         self.encoder.encode(event, &mut body).ok()?;
 
-        Some(body)
+        // Events not received as part of a trace start one of their own.
+        let trace_parent = trace_parent.or_else(|| {
+            self.propagate_trace_context
+                .then(|| Arc::from(TraceParent::derive(&body).to_string()))
+        });
+
+        Some(Traced {
+            item: body,
+            trace_parent,
+        })
     }
 }
 
 #[async_trait::async_trait]
 impl util::http::HttpSink for HttpSink {
-    type Input = BytesMut;
-    type Output = BytesMut;
+    type Input = Traced<BytesMut>;
+    type Output = Traced<BytesMut>;
     type Encoder = HttpSinkEventEncoder;
 
     fn build_encoder(&self) -> Self::Encoder {
         HttpSinkEventEncoder {
             encoder: self.encoder.clone(),
             transformer: self.transformer.clone(),
+            propagate_trace_context: self.propagate_trace_context,
         }
     }
 
-    async fn build_request(&self, mut body: Self::Output) -> crate::Result<http::Request<Bytes>> {
+    async fn build_request(&self, output: Self::Output) -> crate::Result<http::Request<Bytes>> {
+        let Traced {
+            item: mut body,
+            trace_parent,
+        } = output;
+
         let method = match &self.method.clone().unwrap_or(HttpMethod::Post) {
             HttpMethod::Get => Method::GET,
             HttpMethod::Head => Method::HEAD,
@@ -353,6 +389,11 @@ impl util::http::HttpSink for HttpSink {
             builder = builder.header(header.as_str(), value.as_str());
         }
 
+        // The client continues the trace in a span of its own.
+        if let Some(trace_parent) = trace_parent {
+            builder = builder.header(TRACEPARENT, trace_parent.as_ref());
+        }
+
         let mut request = builder.body(body.freeze()).unwrap();
 
         if let Some(auth) = &self.auth {
@@ -415,6 +456,7 @@ mod tests {
     use crate::{
         assert_downcast_matches,
         config::SinkContext,
+        http::TraceParent,
         sinks::util::{
             http::HttpSink,
             test::{build_test_server, build_test_server_generic, build_test_server_status},
@@ -433,7 +475,7 @@ mod tests {
 
         let sink = default_sink(Encoding::Text);
         let mut encoder = sink.build_encoder();
-        let bytes = encoder.encode_event(event).unwrap();
+        let bytes = encoder.encode_event(event).unwrap().item;
 
         assert_eq!(bytes, Vec::from("hello world\n"));
     }
@@ -444,7 +486,7 @@ mod tests {
 
         let sink = default_sink(Encoding::Ndjson);
         let mut encoder = sink.build_encoder();
-        let bytes = encoder.encode_event(event).unwrap();
+        let bytes = encoder.encode_event(event).unwrap().item;
 
         #[derive(Deserialize, Debug)]
         #[serde(deny_unknown_fields)]
@@ -580,6 +622,61 @@ mod tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn http_propagates_trace_context() {
+        run_sink("propagate_trace_context = true", |parts| {
+            let trace_parent = parts.headers.get(TRACEPARENT).unwrap().to_str().unwrap();
+            assert!(TraceParent::parse(trace_parent).is_some());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn http_continues_event_trace() {
+        let value = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let mut event = Event::from("hello world");
+        event.metadata_mut().set_trace_parent(Some(value.into()));
+
+        let mut sink = default_sink(Encoding::Text);
+        let mut encoder = sink.build_encoder();
+        let request = sink
+            .build_request(encoder.encode_event(event.clone()).unwrap())
+            .await
+            .unwrap();
+        assert!(request.headers().get(TRACEPARENT).is_none());
+
+        sink.propagate_trace_context = true;
+        let mut encoder = sink.build_encoder();
+        let request = sink
+            .build_request(encoder.encode_event(event).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(request.headers().get(TRACEPARENT).unwrap(), value);
+    }
+
+    #[tokio::test]
+    async fn http_derives_trace_from_event() {
+        let mut sink = default_sink(Encoding::Text);
+        sink.propagate_trace_context = true;
+        let mut encoder = sink.build_encoder();
+
+        let mut trace_parents = Vec::new();
+        for _ in 0..2 {
+            let request = sink
+                .build_request(encoder.encode_event(Event::from("hello world")).unwrap())
+                .await
+                .unwrap();
+            let trace_parent = request
+                .headers()
+                .get(TRACEPARENT)
+                .unwrap()
+                .to_str()
+                .unwrap();
+            trace_parents.push(TraceParent::parse(trace_parent).unwrap());
+        }
+        assert_eq!(trace_parents[0], trace_parents[1]);
+    }
+
     #[tokio::test]
     async fn retries_on_no_connection() {
         let num_lines = 10;
@@ -125,6 +125,7 @@ impl HumioLogsConfig {
                 indexer_acknowledgements_enabled: false,
                 ..Default::default()
             },
+            token_rotation: None,
         }
     }
 }
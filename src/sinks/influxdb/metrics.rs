@@ -219,7 +219,7 @@ fn create_build_request(
     }
 }
 
-fn merge_tags(
+pub(in crate::sinks) fn merge_tags(
     event: &Metric,
     tags: Option<&HashMap<String, String>>,
 ) -> Option<BTreeMap<String, String>> {
@@ -291,7 +291,7 @@ fn encode_events(
     output
 }
 
-fn get_type_and_fields(
+pub(in crate::sinks) fn get_type_and_fields(
     value: &MetricValue,
     quantiles: &[f64],
 ) -> (&'static str, Option<HashMap<String, Field>>) {
@@ -0,0 +1,151 @@
+use futures::future;
+use http::StatusCode;
+use hyper::Client;
+use serde::{Deserialize, Serialize};
+use tower::ServiceBuilder;
+
+use super::{
+    service::{GrpcService, JaegerRequest, JaegerResponse, ThriftHttpService},
+    sink::JaegerSink,
+    JaegerSinkError,
+};
+use crate::{
+    config::{AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext},
+    http::{Auth, HttpClient, HttpError, MaybeAuth},
+    sinks::{
+        util::{
+            retries::RetryLogic, BatchConfig, RealtimeEventBasedDefaultBatchSettings,
+            ServiceBuilderExt, TowerRequestConfig, UriSerde,
+        },
+        Healthcheck, VectorSink,
+    },
+    tls::{MaybeTlsSettings, TlsEnableableConfig},
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct JaegerSinkConfig {
+    endpoint: UriSerde,
+    #[serde(default)]
+    protocol: Protocol,
+    auth: Option<Auth>,
+    #[serde(default)]
+    batch: BatchConfig<RealtimeEventBasedDefaultBatchSettings>,
+    #[serde(default)]
+    request: TowerRequestConfig,
+    tls: Option<TlsEnableableConfig>,
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+/// The API of the collector the spans are posted to.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Derivative, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Protocol {
+    /// The protobuf encoded `PostSpans` gRPC service.
+    #[derivative(Default)]
+    Grpc,
+    /// Thrift batches posted to the `/api/traces` HTTP endpoint.
+    ThriftHttp,
+}
+
+impl GenerateConfig for JaegerSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"endpoint = "http://localhost:14250""#).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "jaeger")]
+impl SinkConfig for JaegerSinkConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let endpoint = self.endpoint.with_default_parts();
+        let auth = self.auth.choose_one(&endpoint.auth)?;
+        let tls = MaybeTlsSettings::from_config(&self.tls, false)?;
+
+        let sink = match self.protocol {
+            Protocol::Grpc => {
+                let client = HttpClient::new_with_custom_client(
+                    tls,
+                    cx.proxy(),
+                    Client::builder().http2_only(true),
+                )?;
+                let service = GrpcService::new(client, &endpoint.uri, auth)?;
+                self.build_sink(service, &cx)?
+            }
+            Protocol::ThriftHttp => {
+                let client = HttpClient::new(tls, cx.proxy())?;
+                let service =
+                    ThriftHttpService::new(client, endpoint.append_path("api/traces")?.uri, auth);
+                self.build_sink(service, &cx)?
+            }
+        };
+
+        // The health of the collector is only served on its admin port, which isn't the one the
+        // spans are posted to.
+        Ok((sink, Box::pin(future::ok(()))))
+    }
+
+    fn input(&self) -> Input {
+        Input::trace()
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "jaeger"
+    }
+
+    fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
+        Some(&self.acknowledgements)
+    }
+}
+
+impl JaegerSinkConfig {
+    fn build_sink<S>(&self, service: S, cx: &SinkContext) -> crate::Result<VectorSink>
+    where
+        S: tower::Service<JaegerRequest, Response = JaegerResponse, Error = JaegerSinkError>
+            + Clone
+            + Send
+            + 'static,
+        S::Future: Send + 'static,
+    {
+        let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
+        let service = ServiceBuilder::new()
+            .settings(request_settings, JaegerRetryLogic)
+            .service(service);
+
+        let sink = JaegerSink {
+            batch_settings: self.batch.into_batcher_settings()?,
+            service,
+            acker: cx.acker(),
+        };
+        Ok(VectorSink::from_event_streamsink(sink))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct JaegerRetryLogic;
+
+impl RetryLogic for JaegerRetryLogic {
+    type Error = JaegerSinkError;
+    type Response = JaegerResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        use tonic::Code::*;
+
+        match error {
+            JaegerSinkError::Grpc { source } => matches!(
+                source.code(),
+                Cancelled | DeadlineExceeded | Aborted | Unavailable | ResourceExhausted
+            ),
+            JaegerSinkError::Http { source } => matches!(source, HttpError::CallRequest { .. }),
+            JaegerSinkError::HttpStatus { status } => {
+                *status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+            }
+        }
+    }
+}
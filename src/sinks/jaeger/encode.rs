@@ -0,0 +1,303 @@
+//! Encoding of spans into the Jaeger model, as Thrift batches or gRPC `PostSpans` requests.
+//!
+//! The kind and status of the spans are written as the `span.kind`, `error` and
+//! `otel.status_description` tags, and their links as `FOLLOWS_FROM` references.
+//!
+//! See: <https://opentelemetry.io/docs/reference/specification/trace/sdk_exporters/jaeger/>
+
+use std::collections::HashMap;
+
+use bytes::Bytes;
+
+use super::thrift::ThriftWriter;
+use crate::{
+    proto::jaeger as proto,
+    sinks::util::spans::{Process, Span, TagValue, Tags},
+};
+
+/// The spans are exported because they were sampled.
+const SAMPLED_FLAG: u32 = 1;
+
+/// Thrift `TagType` and `SpanRefType` values of the Jaeger model.
+const THRIFT_TAG_STRING: i32 = 0;
+const THRIFT_TAG_DOUBLE: i32 = 1;
+const THRIFT_TAG_BOOL: i32 = 2;
+const THRIFT_TAG_LONG: i32 = 3;
+const THRIFT_REF_FOLLOWS_FROM: i32 = 1;
+
+/// The tags of the span, with the ones standing for its kind and status.
+fn span_tags(span: &Span) -> Tags {
+    let mut tags = span.tags.clone();
+    if let Some(kind) = span.kind.tag() {
+        tags.push(("span.kind".to_owned(), TagValue::String(kind.to_owned())));
+    }
+    if span.error {
+        tags.push(("error".to_owned(), TagValue::Bool(true)));
+    }
+    if let Some(message) = &span.status_message {
+        tags.push((
+            "otel.status_description".to_owned(),
+            TagValue::String(message.clone()),
+        ));
+    }
+    tags
+}
+
+const fn micros(nanos: i64) -> i64 {
+    nanos / 1_000
+}
+
+/// Encodes the spans as Thrift `Batch`es, one per process as the spans of a batch share theirs.
+pub(super) fn thrift_batches(spans: &[Span]) -> Vec<Bytes> {
+    let mut batches = HashMap::<&Process, Vec<&Span>>::new();
+    for span in spans {
+        batches.entry(&span.process).or_default().push(span);
+    }
+
+    batches
+        .into_iter()
+        .map(|(process, spans)| {
+            let mut writer = ThriftWriter::default();
+            writer.write_struct(|writer| {
+                writer.struct_field(1, |writer| {
+                    writer.string_field(1, &process.service);
+                    writer.struct_list_field(2, &process.tags, thrift_tag);
+                });
+                writer.struct_list_field(2, &spans, |writer, span| thrift_span(writer, span));
+            });
+            writer.into_bytes()
+        })
+        .collect()
+}
+
+fn thrift_span(writer: &mut ThriftWriter, span: &Span) {
+    writer.i64_field(1, span.trace_id as i64);
+    writer.i64_field(2, (span.trace_id >> 64) as i64);
+    writer.i64_field(3, span.span_id as i64);
+    // Root spans have a parent span ID of zero.
+    writer.i64_field(4, span.parent_span_id.unwrap_or_default() as i64);
+    writer.string_field(5, &span.name);
+    writer.struct_list_field(6, &span.links, |writer, link| {
+        writer.i32_field(1, THRIFT_REF_FOLLOWS_FROM);
+        writer.i64_field(2, link.trace_id as i64);
+        writer.i64_field(3, (link.trace_id >> 64) as i64);
+        writer.i64_field(4, link.span_id as i64);
+    });
+    writer.i32_field(7, SAMPLED_FLAG as i32);
+    writer.i64_field(8, micros(span.start_unix_nano));
+    writer.i64_field(9, micros(span.duration_nano));
+    writer.struct_list_field(10, &span_tags(span), thrift_tag);
+    writer.struct_list_field(11, &span.events, |writer, event| {
+        writer.i64_field(1, micros(event.time_unix_nano));
+        let mut fields = vec![("event".to_owned(), TagValue::String(event.name.clone()))];
+        fields.extend(event.attributes.iter().cloned());
+        writer.struct_list_field(2, &fields, thrift_tag);
+    });
+}
+
+fn thrift_tag(writer: &mut ThriftWriter, (key, value): &(String, TagValue)) {
+    writer.string_field(1, key);
+    match value {
+        TagValue::String(string) => {
+            writer.i32_field(2, THRIFT_TAG_STRING);
+            writer.string_field(3, string);
+        }
+        TagValue::Double(double) => {
+            writer.i32_field(2, THRIFT_TAG_DOUBLE);
+            writer.double_field(4, double.into_inner());
+        }
+        TagValue::Bool(boolean) => {
+            writer.i32_field(2, THRIFT_TAG_BOOL);
+            writer.bool_field(5, *boolean);
+        }
+        TagValue::Long(integer) => {
+            writer.i32_field(2, THRIFT_TAG_LONG);
+            writer.i64_field(6, *integer);
+        }
+    }
+}
+
+/// Encodes the spans as a `PostSpans` request, in which each span has its own process.
+pub(super) fn post_spans_request(spans: &[Span]) -> proto::PostSpansRequest {
+    proto::PostSpansRequest {
+        batch: Some(proto::Batch {
+            spans: spans.iter().map(proto_span).collect(),
+            process: None,
+        }),
+    }
+}
+
+fn proto_span(span: &Span) -> proto::Span {
+    let mut references = Vec::new();
+    if let Some(parent_span_id) = span.parent_span_id {
+        references.push(proto::SpanRef {
+            trace_id: span.trace_id.to_be_bytes().to_vec(),
+            span_id: parent_span_id.to_be_bytes().to_vec(),
+            ref_type: proto::SpanRefType::ChildOf as i32,
+        });
+    }
+    references.extend(span.links.iter().map(|link| proto::SpanRef {
+        trace_id: link.trace_id.to_be_bytes().to_vec(),
+        span_id: link.span_id.to_be_bytes().to_vec(),
+        ref_type: proto::SpanRefType::FollowsFrom as i32,
+    }));
+
+    proto::Span {
+        trace_id: span.trace_id.to_be_bytes().to_vec(),
+        span_id: span.span_id.to_be_bytes().to_vec(),
+        operation_name: span.name.clone(),
+        references,
+        flags: SAMPLED_FLAG,
+        start_time: Some(prost_types::Timestamp {
+            seconds: span.start_unix_nano.div_euclid(1_000_000_000),
+            nanos: span.start_unix_nano.rem_euclid(1_000_000_000) as i32,
+        }),
+        duration: Some(prost_types::Duration {
+            seconds: span.duration_nano / 1_000_000_000,
+            nanos: (span.duration_nano % 1_000_000_000) as i32,
+        }),
+        tags: proto_tags(&span_tags(span)),
+        logs: span
+            .events
+            .iter()
+            .map(|event| {
+                let mut fields = vec![proto_tag("event", &TagValue::String(event.name.clone()))];
+                fields.extend(proto_tags(&event.attributes));
+                proto::Log {
+                    timestamp: Some(prost_types::Timestamp {
+                        seconds: event.time_unix_nano.div_euclid(1_000_000_000),
+                        nanos: event.time_unix_nano.rem_euclid(1_000_000_000) as i32,
+                    }),
+                    fields,
+                }
+            })
+            .collect(),
+        process: Some(proto::Process {
+            service_name: span.process.service.clone(),
+            tags: proto_tags(&span.process.tags),
+        }),
+        process_id: String::new(),
+        warnings: Vec::new(),
+    }
+}
+
+fn proto_tags(tags: &Tags) -> Vec<proto::KeyValue> {
+    tags.iter()
+        .map(|(key, value)| proto_tag(key, value))
+        .collect()
+}
+
+fn proto_tag(key: &str, value: &TagValue) -> proto::KeyValue {
+    let mut tag = proto::KeyValue {
+        key: key.to_owned(),
+        ..Default::default()
+    };
+    match value {
+        TagValue::String(string) => {
+            tag.v_type = proto::ValueType::String as i32;
+            tag.v_str = string.clone();
+        }
+        TagValue::Bool(boolean) => {
+            tag.v_type = proto::ValueType::Bool as i32;
+            tag.v_bool = *boolean;
+        }
+        TagValue::Long(integer) => {
+            tag.v_type = proto::ValueType::Int64 as i32;
+            tag.v_int64 = *integer;
+        }
+        TagValue::Double(double) => {
+            tag.v_type = proto::ValueType::Float64 as i32;
+            tag.v_float64 = double.into_inner();
+        }
+    }
+    tag
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sinks::util::spans::{SpanKind, SpanLink};
+
+    fn span(service: &str) -> Span {
+        Span {
+            process: Process {
+                service: service.to_owned(),
+                tags: Vec::new(),
+            },
+            trace_id: 0x4bf92f3577b34da6_a3ce929d0e0e4736,
+            span_id: 0x00f067aa0ba902b7,
+            parent_span_id: Some(0x53995c3f42cd8ad8),
+            name: "GET /orders".to_owned(),
+            kind: SpanKind::Server,
+            start_unix_nano: 1_654_000_000_500_000_000,
+            duration_nano: 250_000_000,
+            tags: Vec::new(),
+            events: Vec::new(),
+            links: vec![SpanLink {
+                trace_id: 1,
+                span_id: 2,
+            }],
+            error: true,
+            status_message: None,
+        }
+    }
+
+    #[test]
+    fn encodes_post_spans_requests() {
+        let request = post_spans_request(&[span("orders")]);
+        let span = &request.batch.unwrap().spans[0];
+
+        assert_eq!(
+            span.trace_id,
+            vec![
+                0x4b, 0xf9, 0x2f, 0x35, 0x77, 0xb3, 0x4d, 0xa6, 0xa3, 0xce, 0x92, 0x9d, 0x0e, 0x0e,
+                0x47, 0x36
+            ]
+        );
+        assert_eq!(span.span_id, 0x00f067aa0ba902b7_u64.to_be_bytes());
+        assert_eq!(
+            span.references
+                .iter()
+                .map(|reference| reference.ref_type)
+                .collect::<Vec<_>>(),
+            vec![
+                proto::SpanRefType::ChildOf as i32,
+                proto::SpanRefType::FollowsFrom as i32
+            ]
+        );
+        assert_eq!(
+            span.start_time,
+            Some(prost_types::Timestamp {
+                seconds: 1_654_000_000,
+                nanos: 500_000_000,
+            })
+        );
+        assert_eq!(
+            span.tags,
+            vec![
+                proto_tag("span.kind", &TagValue::String("server".to_owned())),
+                proto_tag("error", &TagValue::Bool(true)),
+            ]
+        );
+        assert_eq!(span.process.as_ref().unwrap().service_name, "orders");
+    }
+
+    #[test]
+    fn encodes_a_thrift_batch_per_process() {
+        let mut batches = thrift_batches(&[span("orders"), span("payments"), span("orders")]);
+        batches.sort();
+
+        assert_eq!(batches.len(), 2);
+        let mut expected = vec![
+            12, 0, 1, // process struct field 1
+            11, 0, 1, 0, 0, 0, 6, // service name string field 1
+        ];
+        expected.extend_from_slice(b"orders");
+        expected.extend_from_slice(&[
+            15, 0, 2, 12, 0, 0, 0, 0, // empty tags list field 2
+            0, // end of the process
+            15, 0, 2, 12, 0, 0, 0, 2, // spans list field 2 of two structs
+        ]);
+        assert!(batches[0].starts_with(&expected));
+    }
+}
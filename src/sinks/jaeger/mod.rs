@@ -0,0 +1,38 @@
+//! The Jaeger [`VectorSink`](crate::sinks::VectorSink).
+//!
+//! This module contains the sink exporting trace events to the Jaeger collector, either with its
+//! gRPC `PostSpans` service or as Thrift batches posted to its `/api/traces` endpoint. The events
+//! are converted into spans by [`spans`](crate::sinks::util::spans), whichever source they were
+//! received by.
+
+#[cfg(test)]
+mod tests;
+
+mod config;
+mod encode;
+mod service;
+mod sink;
+mod thrift;
+
+use http::StatusCode;
+use snafu::Snafu;
+
+pub use self::config::JaegerSinkConfig;
+use crate::{config::SinkDescription, http::HttpError};
+
+inventory::submit! {
+    SinkDescription::new::<JaegerSinkConfig>("jaeger")
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(super)))]
+pub enum JaegerSinkError {
+    #[snafu(display("gRPC request failed: {}", source))]
+    Grpc { source: tonic::Status },
+
+    #[snafu(display("HTTP request failed: {}", source))]
+    Http { source: HttpError },
+
+    #[snafu(display("HTTP request failed with status {}", status))]
+    HttpStatus { status: StatusCode },
+}
@@ -0,0 +1,187 @@
+use std::task::{Context, Poll};
+
+use futures::future::BoxFuture;
+use http::{header::CONTENT_TYPE, uri::Scheme, Request, Uri};
+use hyper::Body;
+use prost::Message;
+use snafu::ResultExt;
+use tonic::body::BoxBody;
+use tower::Service;
+use vector_common::internal_event::{BytesSent, EventsSent};
+use vector_core::{buffers::Ackable, stream::DriverResponse};
+
+use super::{encode, GrpcSnafu, HttpSnafu, JaegerSinkError};
+use crate::{
+    event::{EventFinalizers, EventStatus, Finalizable},
+    http::{Auth, BuildRequestSnafu, HttpClient},
+    proto::jaeger::collector_service_client::CollectorServiceClient,
+    sinks::util::{grpc::GrpcChannel, spans::Span},
+};
+
+const THRIFT_CONTENT_TYPE: &str = "application/x-thrift";
+
+/// The spans of a batch of trace events, encoded by the service for its protocol.
+#[derive(Clone, Debug)]
+pub struct JaegerRequest {
+    pub spans: Vec<Span>,
+    pub finalizers: EventFinalizers,
+    pub events_count: usize,
+    pub events_byte_size: usize,
+}
+
+impl Ackable for JaegerRequest {
+    fn ack_size(&self) -> usize {
+        self.events_count
+    }
+}
+
+impl Finalizable for JaegerRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        std::mem::take(&mut self.finalizers)
+    }
+}
+
+#[derive(Debug)]
+pub struct JaegerResponse {
+    events_count: usize,
+    events_byte_size: usize,
+    byte_size: usize,
+    protocol: &'static str,
+}
+
+impl DriverResponse for JaegerResponse {
+    fn event_status(&self) -> EventStatus {
+        EventStatus::Delivered
+    }
+
+    fn events_sent(&self) -> EventsSent {
+        EventsSent {
+            count: self.events_count,
+            byte_size: self.events_byte_size,
+            output: None,
+        }
+    }
+
+    fn bytes_sent(&self) -> Option<BytesSent> {
+        Some(BytesSent {
+            byte_size: self.byte_size,
+            protocol: self.protocol,
+        })
+    }
+}
+
+/// Posts the spans with the gRPC collector service.
+#[derive(Clone, Debug)]
+pub struct GrpcService {
+    client: CollectorServiceClient<GrpcChannel>,
+    protocol: &'static str,
+}
+
+impl GrpcService {
+    pub fn new(client: HttpClient<BoxBody>, uri: &Uri, auth: Option<Auth>) -> crate::Result<Self> {
+        let channel = GrpcChannel::new(client, uri, auth)?;
+        let protocol = channel.protocol();
+        Ok(Self {
+            client: CollectorServiceClient::new(channel),
+            protocol,
+        })
+    }
+}
+
+impl Service<JaegerRequest> for GrpcService {
+    type Response = JaegerResponse;
+    type Error = JaegerSinkError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // Readiness of the client is checked when it sends the request in `call()`.
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: JaegerRequest) -> Self::Future {
+        let mut service = self.clone();
+
+        Box::pin(async move {
+            let post_spans = encode::post_spans_request(&request.spans);
+            let byte_size = post_spans.encoded_len();
+            service
+                .client
+                .post_spans(post_spans)
+                .await
+                .context(GrpcSnafu)?;
+
+            Ok(JaegerResponse {
+                events_count: request.events_count,
+                events_byte_size: request.events_byte_size,
+                byte_size,
+                protocol: service.protocol,
+            })
+        })
+    }
+}
+
+/// Posts the spans as Thrift batches to the `/api/traces` endpoint of the collector, one per
+/// process of the spans.
+#[derive(Clone, Debug)]
+pub struct ThriftHttpService {
+    client: HttpClient,
+    uri: Uri,
+    auth: Option<Auth>,
+}
+
+impl ThriftHttpService {
+    pub const fn new(client: HttpClient, uri: Uri, auth: Option<Auth>) -> Self {
+        Self { client, uri, auth }
+    }
+}
+
+impl Service<JaegerRequest> for ThriftHttpService {
+    type Response = JaegerResponse;
+    type Error = JaegerSinkError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.client
+            .poll_ready(cx)
+            .map_err(|source| JaegerSinkError::Http { source })
+    }
+
+    fn call(&mut self, request: JaegerRequest) -> Self::Future {
+        let service = self.clone();
+        let protocol = if service.uri.scheme() == Some(&Scheme::HTTPS) {
+            "https"
+        } else {
+            "http"
+        };
+
+        Box::pin(async move {
+            let mut byte_size = 0;
+            for batch in encode::thrift_batches(&request.spans) {
+                byte_size += batch.len();
+
+                let mut builder =
+                    Request::post(service.uri.clone()).header(CONTENT_TYPE, THRIFT_CONTENT_TYPE);
+                if let Some(auth) = &service.auth {
+                    builder = auth.apply_builder(builder);
+                }
+                let http_request = builder
+                    .body(Body::from(batch))
+                    .context(BuildRequestSnafu)
+                    .context(HttpSnafu)?;
+
+                let response = service.client.send(http_request).await.context(HttpSnafu)?;
+                let status = response.status();
+                if !status.is_success() {
+                    return Err(JaegerSinkError::HttpStatus { status });
+                }
+            }
+
+            Ok(JaegerResponse {
+                events_count: request.events_count,
+                events_byte_size: request.events_byte_size,
+                byte_size,
+                protocol,
+            })
+        })
+    }
+}
@@ -0,0 +1,66 @@
+use std::fmt;
+
+use async_trait::async_trait;
+use futures::{future, stream::BoxStream, StreamExt};
+use tower::Service;
+use vector_core::{
+    buffers::Acker,
+    stream::{BatcherSettings, DriverResponse},
+};
+
+use super::service::JaegerRequest;
+use crate::{
+    event::{Event, Finalizable},
+    sinks::util::{spans::TraceSpans, SinkBuilderExt, StreamSink},
+};
+
+pub struct JaegerSink<S> {
+    pub batch_settings: BatcherSettings,
+    pub service: S,
+    pub acker: Acker,
+}
+
+impl<S> JaegerSink<S>
+where
+    S: Service<JaegerRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: fmt::Debug + Into<crate::Error> + Send,
+{
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        input
+            .filter_map(|event| future::ready(TraceSpans::from_event(event)))
+            .batched(self.batch_settings.into_byte_size_config())
+            .map(build_request)
+            .into_driver(self.service, self.acker)
+            .run()
+            .await
+    }
+}
+
+fn build_request(mut traces: Vec<TraceSpans>) -> JaegerRequest {
+    let finalizers = traces.take_finalizers();
+    let events_count = traces.len();
+    let events_byte_size = traces.iter().map(|trace| trace.events_byte_size).sum();
+    let spans = traces.into_iter().flat_map(|trace| trace.spans).collect();
+
+    JaegerRequest {
+        spans,
+        finalizers,
+        events_count,
+        events_byte_size,
+    }
+}
+
+#[async_trait]
+impl<S> StreamSink<Event> for JaegerSink<S>
+where
+    S: Service<JaegerRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: fmt::Debug + Into<crate::Error> + Send,
+{
+    async fn run(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::BTreeMap;
+
+use bytes::{BufMut, Bytes, BytesMut};
+use chrono::{TimeZone, Utc};
+use futures::{stream, StreamExt};
+use prost::Message;
+use vector_core::event::{BatchNotifier, BatchStatus};
+
+use super::JaegerSinkConfig;
+use crate::{
+    config::{SinkConfig, SinkContext},
+    event::{Event, TraceEvent},
+    proto::jaeger::{PostSpansRequest, PostSpansResponse},
+    sinks::util::test::build_test_server_generic,
+    test_util::{
+        components::{run_and_assert_sink_compliance, HTTP_SINK_TAGS},
+        next_addr,
+    },
+};
+
+// one byte for the compression flag plus four bytes for the length
+const GRPC_HEADER_SIZE: usize = 5;
+
+#[test]
+fn generate_config() {
+    crate::test_util::test_generate_config::<JaegerSinkConfig>();
+}
+
+fn grpc_body(message: impl Message) -> Bytes {
+    let message = message.encode_to_vec();
+    let mut body = BytesMut::with_capacity(GRPC_HEADER_SIZE + message.len());
+    body.put_u8(0);
+    body.put_u32(message.len() as u32);
+    body.put_slice(&message);
+    body.freeze()
+}
+
+fn traces(batch: &std::sync::Arc<BatchNotifier>) -> Vec<Event> {
+    ["orders", "payments"]
+        .iter()
+        .map(|service| {
+            let mut trace = TraceEvent::from(BTreeMap::new());
+            trace.insert("trace_id", "4bf92f3577b34da6a3ce929d0e0e4736");
+            trace.insert("span_id", "00f067aa0ba902b7");
+            trace.insert("name", "GET /orders");
+            trace.insert("resources.\"service.name\"", *service);
+            trace.insert("start_timestamp", Utc.timestamp(1_654_000_000, 0));
+            trace.insert("end_timestamp", Utc.timestamp(1_654_000_001, 0));
+            Event::from(trace.with_batch_notifier(batch))
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn exports_spans_over_grpc() {
+    let address = next_addr();
+    let config =
+        toml::from_str::<JaegerSinkConfig>(&format!(r#"endpoint = "http://{}""#, address)).unwrap();
+    let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+
+    let (rx, trigger, server) = build_test_server_generic(address, || {
+        hyper::Response::builder()
+            .header("grpc-status", "0") // OK
+            .header("content-type", "application/grpc")
+            .body(hyper::Body::from(grpc_body(PostSpansResponse::default())))
+            .unwrap()
+    });
+    tokio::spawn(server);
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let events = traces(&batch);
+    drop(batch);
+    run_and_assert_sink_compliance(sink, stream::iter(events), &HTTP_SINK_TAGS).await;
+    drop(trigger);
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    let requests = rx.collect::<Vec<_>>().await;
+    assert_eq!(requests.len(), 1);
+    let (parts, body) = &requests[0];
+    assert_eq!(
+        parts.uri.path(),
+        "/jaeger.api_v2.CollectorService/PostSpans"
+    );
+    let request = PostSpansRequest::decode(body.slice(GRPC_HEADER_SIZE..)).unwrap();
+    let services = request
+        .batch
+        .unwrap()
+        .spans
+        .into_iter()
+        .map(|span| span.process.unwrap().service_name)
+        .collect::<Vec<_>>();
+    assert_eq!(services, ["orders", "payments"]);
+}
+
+#[tokio::test]
+async fn exports_a_thrift_batch_per_service() {
+    let address = next_addr();
+    let config = toml::from_str::<JaegerSinkConfig>(&format!(
+        r#"
+            endpoint = "http://{}"
+            protocol = "thrift_http"
+        "#,
+        address
+    ))
+    .unwrap();
+    let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+
+    let (rx, trigger, server) =
+        build_test_server_generic(address, || hyper::Response::new(hyper::Body::empty()));
+    tokio::spawn(server);
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let events = traces(&batch);
+    drop(batch);
+    run_and_assert_sink_compliance(sink, stream::iter(events), &HTTP_SINK_TAGS).await;
+    drop(trigger);
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    let requests = rx.collect::<Vec<_>>().await;
+    assert_eq!(requests.len(), 2);
+    for (parts, _) in &requests {
+        assert_eq!(parts.uri.path(), "/api/traces");
+        assert_eq!(parts.headers["content-type"], "application/x-thrift");
+    }
+}
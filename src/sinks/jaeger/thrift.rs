@@ -0,0 +1,124 @@
+//! A writer of the Thrift binary protocol, as accepted by the `/api/traces` endpoint of the Jaeger
+//! collector.
+//!
+//! Structs are written as a sequence of fields, each with a header made of the type of its value
+//! and its ID, followed by a stop byte. All integers are big-endian.
+//!
+//! See: <https://github.com/apache/thrift/blob/master/doc/specs/thrift-binary-protocol.md>
+
+use bytes::{BufMut, Bytes, BytesMut};
+
+#[derive(Clone, Copy, Debug)]
+#[repr(u8)]
+pub enum FieldType {
+    Bool = 2,
+    Double = 4,
+    I32 = 8,
+    I64 = 10,
+    String = 11,
+    Struct = 12,
+    List = 15,
+}
+
+const STOP: u8 = 0;
+
+#[derive(Default)]
+pub struct ThriftWriter(BytesMut);
+
+impl ThriftWriter {
+    pub fn into_bytes(self) -> Bytes {
+        self.0.freeze()
+    }
+
+    fn field_header(&mut self, field_type: FieldType, id: i16) {
+        self.0.put_u8(field_type as u8);
+        self.0.put_i16(id);
+    }
+
+    pub fn bool_field(&mut self, id: i16, value: bool) {
+        self.field_header(FieldType::Bool, id);
+        self.0.put_u8(u8::from(value));
+    }
+
+    pub fn double_field(&mut self, id: i16, value: f64) {
+        self.field_header(FieldType::Double, id);
+        self.0.put_f64(value);
+    }
+
+    pub fn i32_field(&mut self, id: i16, value: i32) {
+        self.field_header(FieldType::I32, id);
+        self.0.put_i32(value);
+    }
+
+    pub fn i64_field(&mut self, id: i16, value: i64) {
+        self.field_header(FieldType::I64, id);
+        self.0.put_i64(value);
+    }
+
+    pub fn binary_field(&mut self, id: i16, value: &[u8]) {
+        self.field_header(FieldType::String, id);
+        self.binary(value);
+    }
+
+    pub fn string_field(&mut self, id: i16, value: &str) {
+        self.binary_field(id, value.as_bytes());
+    }
+
+    /// Writes a struct field, whose fields are written by `write`.
+    pub fn struct_field(&mut self, id: i16, write: impl FnOnce(&mut Self)) {
+        self.field_header(FieldType::Struct, id);
+        self.write_struct(write);
+    }
+
+    /// Writes a list field of structs, with the fields of each item written by `write`.
+    pub fn struct_list_field<T>(
+        &mut self,
+        id: i16,
+        items: &[T],
+        mut write: impl FnMut(&mut Self, &T),
+    ) {
+        self.field_header(FieldType::List, id);
+        self.0.put_u8(FieldType::Struct as u8);
+        self.0.put_i32(items.len() as i32);
+        for item in items {
+            self.write_struct(|writer| write(writer, item));
+        }
+    }
+
+    /// Writes the fields of a struct, followed by the stop byte ending it.
+    pub fn write_struct(&mut self, write: impl FnOnce(&mut Self)) {
+        write(self);
+        self.0.put_u8(STOP);
+    }
+
+    fn binary(&mut self, value: &[u8]) {
+        self.0.put_i32(value.len() as i32);
+        self.0.put_slice(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_structs() {
+        let mut writer = ThriftWriter::default();
+        writer.write_struct(|writer| {
+            writer.string_field(1, "id");
+            writer.struct_list_field(2, &[7_i64], |writer, value| writer.i64_field(1, *value));
+            writer.bool_field(3, true);
+        });
+
+        assert_eq!(
+            &writer.into_bytes()[..],
+            &[
+                11, 0, 1, 0, 0, 0, 2, b'i', b'd', // string field 1
+                15, 0, 2, 12, 0, 0, 0, 1, // list field 2 of one struct
+                10, 0, 1, 0, 0, 0, 0, 0, 0, 0, 7, 0, // struct with i64 field 1
+                2, 0, 3, 1, // bool field 3
+                0, // stop
+            ][..]
+        );
+    }
+}
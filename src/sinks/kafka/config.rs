@@ -14,7 +14,7 @@ use crate::{
             encoding::{
                 EncodingConfig, EncodingConfigAdapter, StandardEncodings, StandardEncodingsMigrator,
             },
-            BatchConfig, NoDefaultsBatchSettings,
+            BatchConfig, NoDefaultsBatchSettings, RealtimeEventBasedDefaultBatchSettings,
         },
         Healthcheck, VectorSink,
     },
@@ -45,6 +45,10 @@ pub(crate) struct KafkaSinkConfig {
     pub librdkafka_options: HashMap<String, String>,
     #[serde(alias = "headers_field")] // accidentally released as `headers_field` in 0.18
     pub headers_key: Option<String>,
+    /// Keeps the retries of the producer from duplicating or reordering messages.
+    #[serde(default)]
+    pub idempotence: bool,
+    pub transaction: Option<KafkaTransactionConfig>,
     #[serde(
         default,
         deserialize_with = "crate::serde::bool_or_struct",
@@ -53,6 +57,25 @@ pub(crate) struct KafkaSinkConfig {
     pub acknowledgements: AcknowledgementsConfig,
 }
 
+/// Produces the events of each batch in a transaction, which is committed once they're all
+/// produced, so that consumers reading committed messages only never see the ones of the
+/// transactions aborted and retried.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct KafkaTransactionConfig {
+    /// Identifies the producer across restarts, so that the transactions it left open are aborted
+    /// once it's back. It has to be unique to each sink.
+    pub transactional_id: String,
+    #[serde(default = "default_transaction_timeout_ms")]
+    pub timeout_ms: u64,
+    #[serde(default)]
+    pub batch: BatchConfig<RealtimeEventBasedDefaultBatchSettings>,
+}
+
+const fn default_transaction_timeout_ms() -> u64 {
+    60000 // default in librdkafka
+}
+
 const fn default_socket_timeout_ms() -> u64 {
     60000 // default in librdkafka
 }
@@ -86,6 +109,22 @@ impl KafkaSinkConfig {
                     .set("compression.codec", &to_string(self.compression))
                     .set("message.timeout.ms", &self.message_timeout_ms.to_string());
 
+                // Transactional producers are idempotent too.
+                if self.idempotence || self.transaction.is_some() {
+                    client_config.set("enable.idempotence", "true");
+                }
+                if let Some(transaction) = &self.transaction {
+                    if transaction.transactional_id.is_empty() {
+                        return Err("`transaction.transactional_id` can't be empty.".into());
+                    }
+                    client_config
+                        .set("transactional.id", &transaction.transactional_id)
+                        .set(
+                            "transaction.timeout.ms",
+                            &transaction.timeout_ms.to_string(),
+                        );
+                }
+
                 if let Some(value) = self.batch.timeout_secs {
                     // Delay in milliseconds to wait for messages in the producer queue to accumulate before
                     // constructing message batches (MessageSets) to transmit to brokers. A higher value
@@ -175,6 +214,8 @@ impl GenerateConfig for KafkaSinkConfig {
             message_timeout_ms: default_message_timeout_ms(),
             librdkafka_options: Default::default(),
             headers_key: None,
+            idempotence: false,
+            transaction: None,
             acknowledgements: Default::default(),
         })
         .unwrap()
@@ -211,4 +252,24 @@ mod tests {
     fn generate_config() {
         KafkaSinkConfig::generate_config();
     }
+
+    #[test]
+    fn transactional_producers_are_idempotent() {
+        let config: KafkaSinkConfig = toml::from_str(
+            r#"
+            bootstrap_servers = "localhost:9092"
+            topic = "topic"
+            encoding.codec = "json"
+            transaction.transactional_id = "vector-kafka"
+            "#,
+        )
+        .unwrap();
+        let client_config = config.to_rdkafka(KafkaRole::Producer).unwrap();
+        assert_eq!(client_config.get("enable.idempotence"), Some("true"));
+        assert_eq!(client_config.get("transactional.id"), Some("vector-kafka"));
+        assert_eq!(client_config.get("transaction.timeout.ms"), Some("60000"));
+
+        let client_config = config.to_rdkafka(KafkaRole::Consumer).unwrap();
+        assert_eq!(client_config.get("transactional.id"), None);
+    }
 }
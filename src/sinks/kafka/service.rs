@@ -1,13 +1,17 @@
-use std::task::{Context, Poll};
+use std::{
+    task::{Context, Poll},
+    time::Duration,
+};
 
 use bytes::Bytes;
-use futures::future::BoxFuture;
+use futures::future::{self, BoxFuture};
 use rdkafka::{
-    error::KafkaError,
+    error::{KafkaError, RDKafkaErrorCode},
     message::OwnedHeaders,
-    producer::{FutureProducer, FutureRecord},
+    producer::{FutureProducer, FutureRecord, Producer},
     util::Timeout,
 };
+use tokio::time::sleep;
 use tower::Service;
 use vector_core::{
     buffers::Ackable,
@@ -17,7 +21,9 @@ use vector_core::{
 
 use crate::{
     event::{EventFinalizers, EventStatus, Finalizable},
+    internal_events::KafkaTransactionAborted,
     kafka::KafkaStatisticsContext,
+    sinks::util::retries::ExponentialBackoff,
 };
 
 pub struct KafkaRequest {
@@ -52,6 +58,22 @@ impl DriverResponse for KafkaResponse {
     }
 }
 
+impl KafkaRequest {
+    fn to_record(&self) -> FutureRecord<'_, [u8], [u8]> {
+        let mut record = FutureRecord::to(&self.metadata.topic).payload(self.body.as_ref());
+        if let Some(key) = &self.metadata.key {
+            record = record.key(&key[..]);
+        }
+        if let Some(timestamp) = self.metadata.timestamp_millis {
+            record = record.timestamp(timestamp);
+        }
+        if let Some(headers) = &self.metadata.headers {
+            record = record.headers(headers.clone());
+        }
+        record
+    }
+}
+
 impl Ackable for KafkaRequest {
     fn ack_size(&self) -> usize {
         // rdkafka takes care of batching internally, so a request here is always 1 event
@@ -90,20 +112,11 @@ impl Service<KafkaRequest> for KafkaService {
         let kafka_producer = self.kafka_producer.clone();
 
         Box::pin(async move {
-            let mut record =
-                FutureRecord::to(&request.metadata.topic).payload(request.body.as_ref());
-            if let Some(key) = &request.metadata.key {
-                record = record.key(&key[..]);
-            }
-            if let Some(timestamp) = request.metadata.timestamp_millis {
-                record = record.timestamp(timestamp);
-            }
-            if let Some(headers) = request.metadata.headers {
-                record = record.headers(headers);
-            }
-
             //rdkafka will internally retry forever if the queue is full
-            let result = match kafka_producer.send(record, Timeout::Never).await {
+            let result = match kafka_producer
+                .send(request.to_record(), Timeout::Never)
+                .await
+            {
                 Ok((_partition, _offset)) => {
                     emit!(BytesSent {
                         byte_size: request.body.len()
@@ -120,3 +133,249 @@ impl Service<KafkaRequest> for KafkaService {
         })
     }
 }
+
+/// The requests of a batch, produced in a single transaction.
+pub struct KafkaTransactionRequest {
+    pub requests: Vec<KafkaRequest>,
+    pub finalizers: EventFinalizers,
+}
+
+impl From<Vec<KafkaRequest>> for KafkaTransactionRequest {
+    fn from(mut requests: Vec<KafkaRequest>) -> Self {
+        let mut finalizers = EventFinalizers::default();
+        for request in &mut requests {
+            finalizers.merge(request.take_finalizers());
+        }
+        Self {
+            requests,
+            finalizers,
+        }
+    }
+}
+
+impl Ackable for KafkaTransactionRequest {
+    fn ack_size(&self) -> usize {
+        self.requests.len()
+    }
+}
+
+impl Finalizable for KafkaTransactionRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        std::mem::take(&mut self.finalizers)
+    }
+}
+
+pub struct KafkaTransactionResponse {
+    count: usize,
+    event_byte_size: usize,
+}
+
+impl DriverResponse for KafkaTransactionResponse {
+    fn event_status(&self) -> EventStatus {
+        EventStatus::Delivered
+    }
+
+    fn events_sent(&self) -> EventsSent {
+        EventsSent {
+            count: self.count,
+            byte_size: self.event_byte_size,
+            output: None,
+        }
+    }
+}
+
+/// Produces each request in a transaction, which is aborted when producing any of its messages
+/// fails. It's then produced again after a backoff when the error is retriable, until it's
+/// committed, while the request fails on a permanent error or when the producer fails fatally. The
+/// transactions of a producer can't overlap, so the calls have to be made one at a time.
+#[derive(Clone)]
+pub struct KafkaTransactionService {
+    kafka_producer: FutureProducer<KafkaStatisticsContext>,
+    timeout: Duration,
+}
+
+impl KafkaTransactionService {
+    pub(crate) const fn new(
+        kafka_producer: FutureProducer<KafkaStatisticsContext>,
+        timeout: Duration,
+    ) -> KafkaTransactionService {
+        KafkaTransactionService {
+            kafka_producer,
+            timeout,
+        }
+    }
+
+    /// Registers the producer with the transaction coordinator, which aborts the transactions
+    /// its previous incarnation left open.
+    pub(crate) async fn init(&self) -> Result<(), KafkaError> {
+        let kafka_producer = self.kafka_producer.clone();
+        let timeout = self.timeout;
+        tokio::task::spawn_blocking(move || kafka_producer.init_transactions(timeout))
+            .await
+            .expect("Initializing transactions panicked.")
+    }
+
+    async fn produce(&self, request: &KafkaTransactionRequest) -> Result<(), KafkaError> {
+        self.kafka_producer.begin_transaction()?;
+
+        future::try_join_all(request.requests.iter().map(|request| async move {
+            self.kafka_producer
+                .send(request.to_record(), Timeout::Never)
+                .await
+                .map_err(|(error, _record)| error)
+        }))
+        .await?;
+
+        loop {
+            let kafka_producer = self.kafka_producer.clone();
+            let timeout = self.timeout;
+            match tokio::task::spawn_blocking(move || kafka_producer.commit_transaction(timeout))
+                .await
+                .expect("Committing the transaction panicked.")
+            {
+                Err(KafkaError::Transaction(error)) if error.is_retriable() => continue,
+                result => return result,
+            }
+        }
+    }
+
+    async fn abort(&self) -> Result<(), KafkaError> {
+        let kafka_producer = self.kafka_producer.clone();
+        let timeout = self.timeout;
+        tokio::task::spawn_blocking(move || kafka_producer.abort_transaction(timeout))
+            .await
+            .expect("Aborting the transaction panicked.")
+    }
+}
+
+/// Whether the transaction can be aborted, rather than the producer having failed fatally.
+fn is_abortable(error: &KafkaError) -> bool {
+    match error {
+        KafkaError::Transaction(error) => error.txn_requires_abort(),
+        // The transaction of the messages failing to be produced is left to be aborted.
+        _ => true,
+    }
+}
+
+/// Whether producing the transaction again, once it's aborted, could succeed, rather than failing
+/// the same way.
+fn is_retriable(error: &KafkaError) -> bool {
+    if let KafkaError::Transaction(error) = error {
+        return error.txn_requires_abort();
+    }
+    matches!(
+        error.rdkafka_error_code(),
+        Some(
+            RDKafkaErrorCode::BrokerTransportFailure
+                | RDKafkaErrorCode::Resolve
+                | RDKafkaErrorCode::MessageTimedOut
+                | RDKafkaErrorCode::AllBrokersDown
+                | RDKafkaErrorCode::OperationTimedOut
+                | RDKafkaErrorCode::QueueFull
+                | RDKafkaErrorCode::TimedOutQueue
+                | RDKafkaErrorCode::Retry
+                | RDKafkaErrorCode::PurgeQueue
+                | RDKafkaErrorCode::PurgeInflight
+                | RDKafkaErrorCode::WaitingForCoordinator
+                | RDKafkaErrorCode::LeaderNotAvailable
+                | RDKafkaErrorCode::NotLeaderForPartition
+                | RDKafkaErrorCode::RequestTimedOut
+                | RDKafkaErrorCode::BrokerNotAvailable
+                | RDKafkaErrorCode::ReplicaNotAvailable
+                | RDKafkaErrorCode::NetworkException
+                | RDKafkaErrorCode::CoordinatorLoadInProgress
+                | RDKafkaErrorCode::CoordinatorNotAvailable
+                | RDKafkaErrorCode::NotCoordinator
+                | RDKafkaErrorCode::NotEnoughReplicas
+                | RDKafkaErrorCode::NotEnoughReplicasAfterAppend
+                | RDKafkaErrorCode::KafkaStorageError
+                | RDKafkaErrorCode::ConcurrentTransactions
+                | RDKafkaErrorCode::ThrottlingQuotaExceeded
+        )
+    )
+}
+
+impl Service<KafkaTransactionRequest> for KafkaTransactionService {
+    type Response = KafkaTransactionResponse;
+    type Error = KafkaError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: KafkaTransactionRequest) -> Self::Future {
+        let service = self.clone();
+
+        Box::pin(async move {
+            let mut backoff = ExponentialBackoff::from_millis(2)
+                .factor(250)
+                .max_delay(Duration::from_secs(60));
+            loop {
+                let error = match service.produce(&request).await {
+                    Ok(()) => break,
+                    Err(error) => error,
+                };
+                if !is_abortable(&error) {
+                    return Err(error);
+                }
+                let retry = is_retriable(&error);
+                emit!(KafkaTransactionAborted {
+                    error: &error,
+                    retry
+                });
+                service.abort().await?;
+                // The request fails on a permanent error, which rejects its events.
+                if !retry {
+                    return Err(error);
+                }
+                sleep(backoff.next().unwrap()).await;
+            }
+
+            emit!(BytesSent {
+                byte_size: request
+                    .requests
+                    .iter()
+                    .map(|request| {
+                        request.body.len()
+                            + request.metadata.key.as_ref().map(|x| x.len()).unwrap_or(0)
+                    })
+                    .sum(),
+                protocol: "kafka"
+            });
+            Ok(KafkaTransactionResponse {
+                count: request.requests.len(),
+                event_byte_size: request
+                    .requests
+                    .iter()
+                    .map(|request| request.event_byte_size)
+                    .sum(),
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_production_errors() {
+        for code in [
+            RDKafkaErrorCode::MessageTimedOut,
+            RDKafkaErrorCode::QueueFull,
+            RDKafkaErrorCode::NotLeaderForPartition,
+        ] {
+            assert!(is_retriable(&KafkaError::MessageProduction(code)));
+        }
+        for code in [
+            RDKafkaErrorCode::MessageSizeTooLarge,
+            RDKafkaErrorCode::TopicAuthorizationFailed,
+            RDKafkaErrorCode::InvalidRecord,
+        ] {
+            let error = KafkaError::MessageProduction(code);
+            assert!(is_abortable(&error));
+            assert!(!is_retriable(&error));
+        }
+    }
+}
@@ -11,7 +11,7 @@ use rdkafka::{
 use snafu::{ResultExt, Snafu};
 use tokio::time::Duration;
 use tower::limit::ConcurrencyLimit;
-use vector_core::{buffers::Acker, config::log_schema};
+use vector_core::{buffers::Acker, config::log_schema, stream::BatcherSettings};
 
 use super::config::{KafkaRole, KafkaSinkConfig};
 use crate::{
@@ -20,10 +20,13 @@ use crate::{
     kafka::KafkaStatisticsContext,
     sinks::{
         kafka::{
-            config::QUEUED_MIN_MESSAGES, request_builder::KafkaRequestBuilder,
-            service::KafkaService,
+            config::QUEUED_MIN_MESSAGES,
+            request_builder::KafkaRequestBuilder,
+            service::{
+                KafkaRequest, KafkaService, KafkaTransactionRequest, KafkaTransactionService,
+            },
         },
-        util::{builder::SinkBuilderExt, encoding::Transformer, StreamSink},
+        util::{batch::BatchError, builder::SinkBuilderExt, encoding::Transformer, StreamSink},
     },
     template::{Template, TemplateParseError},
 };
@@ -34,6 +37,8 @@ pub(super) enum BuildError {
     KafkaCreateFailed { source: KafkaError },
     #[snafu(display("invalid topic template: {}", source))]
     TopicTemplate { source: TemplateParseError },
+    #[snafu(display("invalid transaction batch settings: {}", source))]
+    TransactionBatch { source: BatchError },
 }
 
 pub struct KafkaSink {
@@ -41,6 +46,7 @@ pub struct KafkaSink {
     encoder: Encoder<()>,
     acker: Acker,
     service: KafkaService,
+    transaction: Option<(KafkaTransactionService, BatcherSettings)>,
     topic: Template,
     key_field: Option<String>,
     headers_key: Option<String>,
@@ -59,6 +65,19 @@ impl KafkaSink {
     pub(crate) fn new(config: KafkaSinkConfig, acker: Acker) -> crate::Result<Self> {
         let producer_config = config.to_rdkafka(KafkaRole::Producer)?;
         let producer = create_producer(producer_config)?;
+        let transaction = match &config.transaction {
+            Some(transaction) => Some((
+                KafkaTransactionService::new(
+                    producer.clone(),
+                    Duration::from_millis(transaction.timeout_ms),
+                ),
+                transaction
+                    .batch
+                    .into_batcher_settings()
+                    .context(TransactionBatchSnafu)?,
+            )),
+            None => None,
+        };
         let transformer = config.encoding.transformer();
         let serializer = config.encoding.encoding();
         let encoder = Encoder::<()>::new(serializer);
@@ -69,14 +88,13 @@ impl KafkaSink {
             encoder,
             acker,
             service: KafkaService::new(producer),
+            transaction,
             topic: Template::try_from(config.topic).context(TopicTemplateSnafu)?,
             key_field: config.key_field,
         })
     }
 
     async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
-        // rdkafka will internally retry forever, so we need some limit to prevent this from overflowing
-        let service = ConcurrencyLimit::new(self.service, QUEUED_MIN_MESSAGES as usize);
         let mut request_builder = KafkaRequestBuilder {
             key_field: self.key_field,
             headers_key: self.headers_key,
@@ -85,10 +103,33 @@ impl KafkaSink {
             encoder: self.encoder,
             log_schema: log_schema(),
         };
-        let sink = input
-            .filter_map(|event| future::ready(request_builder.build_request(event)))
-            .into_driver(service, self.acker);
-        sink.run().await
+        let requests =
+            input.filter_map(move |event| future::ready(request_builder.build_request(event)));
+
+        match self.transaction {
+            Some((service, batch_settings)) => {
+                if let Err(error) = service.init().await {
+                    error!(message = "Failed to initialize the transactional producer.", %error);
+                    return Err(());
+                }
+                // The transactions of a producer can't overlap.
+                let service = ConcurrencyLimit::new(service, 1);
+                let sink = requests
+                    .batched(
+                        batch_settings
+                            .into_item_size_config(|request: &KafkaRequest| request.body.len()),
+                    )
+                    .map(KafkaTransactionRequest::from)
+                    .into_driver(service, self.acker);
+                sink.run().await
+            }
+            None => {
+                // rdkafka will internally retry forever, so we need some limit to prevent this from overflowing
+                let service = ConcurrencyLimit::new(self.service, QUEUED_MIN_MESSAGES as usize);
+                let sink = requests.into_driver(service, self.acker);
+                sink.run().await
+            }
+        }
     }
 }
 
@@ -45,12 +45,18 @@ pub struct LokiConfig {
     pub labels: HashMap<Template, Template>,
     #[serde(default = "crate::serde::default_false")]
     pub remove_label_fields: bool,
+    #[serde(default)]
+    pub structured_metadata: HashMap<Template, Template>,
+    #[serde(default = "crate::serde::default_false")]
+    pub remove_structured_metadata_fields: bool,
     #[serde(default = "crate::serde::default_true")]
     pub remove_timestamp: bool,
     #[serde(default)]
     pub compression: Compression,
     #[serde(default)]
     pub out_of_order_action: OutOfOrderAction,
+    #[serde(default = "default_restream_label")]
+    pub restream_label: String,
     pub auth: Option<Auth>,
     #[serde(default)]
     pub request: TowerRequestConfig,
@@ -74,16 +80,25 @@ impl SinkBatchSettings for LokiDefaultBatchSettings {
     const TIMEOUT_SECS: f64 = 1.0;
 }
 
-#[derive(Copy, Clone, Debug, Derivative, Deserialize, Serialize)]
+/// What to do with the events older than the latest event pushed to their stream.
+#[derive(Copy, Clone, Debug, Derivative, Deserialize, Serialize, PartialEq, Eq)]
 #[derivative(Default)]
 #[serde(rename_all = "snake_case")]
 pub enum OutOfOrderAction {
     #[derivative(Default)]
     Drop,
+    /// Clamps the timestamp of the event to the latest timestamp of its stream.
+    #[serde(alias = "clamp_to_latest")]
     RewriteTimestamp,
+    /// Sends the event to a sibling stream, labelled with `restream_label`.
+    Restream,
     Accept,
 }
 
+fn default_restream_label() -> String {
+    "out_of_order".into()
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum Encoding {
@@ -128,6 +143,16 @@ impl SinkConfig for LokiConfig {
             }
         }
 
+        for key in self.structured_metadata.keys() {
+            if !valid_label_name(key) {
+                return Err(format!("Invalid structured metadata name {:?}", key.get_ref()).into());
+            }
+        }
+
+        if !valid_label_name(&Template::try_from(self.restream_label.as_str())?) {
+            return Err(format!("Invalid restream label {:?}", self.restream_label).into());
+        }
+
         let client = self.build_client(cx.clone())?;
 
         let config = LokiConfig {
@@ -184,7 +209,7 @@ pub fn valid_label_name(label: &Template) -> bool {
 mod tests {
     use std::convert::TryInto;
 
-    use super::valid_label_name;
+    use super::{valid_label_name, OutOfOrderAction};
 
     #[test]
     fn valid_label_names() {
@@ -202,4 +227,26 @@ mod tests {
 
         assert!(valid_label_name(&"{{field}}".try_into().unwrap()));
     }
+
+    #[test]
+    fn parses_out_of_order_actions() {
+        let action = |action: &str| {
+            toml::from_str::<super::LokiConfig>(&format!(
+                r#"
+                endpoint = "http://localhost:3100"
+                encoding.codec = "json"
+                labels.source = "vector"
+                out_of_order_action = "{}"
+                "#,
+                action
+            ))
+            .unwrap()
+            .out_of_order_action
+        };
+        assert_eq!(
+            action("clamp_to_latest"),
+            OutOfOrderAction::RewriteTimestamp
+        );
+        assert_eq!(action("restream"), OutOfOrderAction::Restream);
+    }
 }
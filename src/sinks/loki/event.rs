@@ -1,4 +1,7 @@
-use std::{collections::HashMap, io};
+use std::{
+    collections::{BTreeMap, HashMap},
+    io,
+};
 
 use bytes::Bytes;
 use serde::{ser::SerializeSeq, Serialize};
@@ -55,11 +58,17 @@ impl From<Vec<LokiRecord>> for LokiBatch {
 pub struct LokiEvent {
     pub timestamp: i64,
     pub event: Bytes,
+    /// The structured metadata attached to the log line, which isn't indexed as the labels are.
+    pub structured_metadata: Labels,
 }
 
 impl ByteSizeOf for LokiEvent {
     fn allocated_bytes(&self) -> usize {
-        self.timestamp.allocated_bytes() + self.event.allocated_bytes()
+        self.timestamp.allocated_bytes()
+            + self.event.allocated_bytes()
+            + self.structured_metadata.iter().fold(0, |res, item| {
+                res + item.0.allocated_bytes() + item.1.allocated_bytes()
+            })
     }
 }
 
@@ -68,10 +77,23 @@ impl Serialize for LokiEvent {
     where
         S: serde::Serializer,
     {
-        let mut seq = serializer.serialize_seq(Some(2))?;
+        let len = if self.structured_metadata.is_empty() {
+            2
+        } else {
+            3
+        };
+        let mut seq = serializer.serialize_seq(Some(len))?;
         seq.serialize_element(&self.timestamp.to_string())?;
         let event = String::from_utf8_lossy(&self.event);
         seq.serialize_element(&event)?;
+        if !self.structured_metadata.is_empty() {
+            let structured_metadata = self
+                .structured_metadata
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str()))
+                .collect::<BTreeMap<_, _>>();
+            seq.serialize_element(&structured_metadata)?;
+        }
         seq.end()
     }
 }
@@ -137,3 +159,30 @@ impl PartitionKey {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_structured_metadata() {
+        let event = LokiEvent {
+            timestamp: 1,
+            event: Bytes::from("hello"),
+            structured_metadata: vec![],
+        };
+        assert_eq!(serde_json::to_string(&event).unwrap(), r#"["1","hello"]"#);
+
+        let event = LokiEvent {
+            structured_metadata: vec![
+                ("trace_id".into(), "abc".into()),
+                ("pod".into(), "web-0".into()),
+            ],
+            ..event
+        };
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"["1","hello",{"pod":"web-0","trace_id":"abc"}]"#
+        );
+    }
+}
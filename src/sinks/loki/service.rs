@@ -1,10 +1,13 @@
-use std::task::{Context, Poll};
+use std::{
+    collections::HashMap,
+    task::{Context, Poll},
+};
 
 use bytes::Bytes;
-use futures::future::BoxFuture;
+use futures::{future::BoxFuture, FutureExt, TryFutureExt};
 use http::StatusCode;
 use snafu::Snafu;
-use tower::Service;
+use tower::{buffer::Buffer, Service, ServiceBuilder, ServiceExt};
 use tracing::Instrument;
 use vector_common::internal_event::BytesSent;
 use vector_core::{
@@ -16,7 +19,12 @@ use vector_core::{
 
 use crate::{
     http::{get_http_scheme_from_uri, Auth, HttpClient},
-    sinks::util::{metadata::RequestMetadata, retries::RetryLogic, Compression, UriSerde},
+    sinks::util::{
+        metadata::RequestMetadata,
+        retries::RetryLogic,
+        service::{ServiceBuilderExt, Svc, TowerRequestSettings},
+        Compression, UriSerde,
+    },
 };
 
 #[derive(Clone)]
@@ -157,3 +165,52 @@ impl Service<LokiRequest> for LokiService {
         })
     }
 }
+
+/// Sends the requests of each tenant with its own service, so that the retries and the
+/// concurrency limit of a tenant don't hold back the requests of the other tenants.
+pub struct LokiTenantSvc {
+    service: LokiService,
+    request_settings: TowerRequestSettings,
+    tenants: HashMap<Option<String>, Buffer<Svc<LokiService, LokiRetryLogic>, LokiRequest>>,
+}
+
+impl LokiTenantSvc {
+    pub fn new(service: LokiService, request_settings: TowerRequestSettings) -> Self {
+        Self {
+            service,
+            request_settings,
+            tenants: HashMap::new(),
+        }
+    }
+}
+
+impl Service<LokiRequest> for LokiTenantSvc {
+    type Response = LokiResponse;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: LokiRequest) -> Self::Future {
+        let Self {
+            service,
+            request_settings,
+            tenants,
+        } = self;
+        let svc = tenants
+            .entry(request.tenant_id.clone())
+            .or_insert_with_key(|tenant_id| {
+                let mut settings = request_settings.clone();
+                settings.partition = tenant_id.clone();
+                let svc = ServiceBuilder::new()
+                    .settings(settings, LokiRetryLogic)
+                    .service(service.clone());
+                Buffer::new(svc, 1)
+            })
+            .clone();
+
+        svc.oneshot(request).map_err(Into::into).boxed()
+    }
+}
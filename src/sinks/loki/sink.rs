@@ -17,23 +17,22 @@ use vector_core::{
 
 use super::{
     config::{LokiConfig, OutOfOrderAction},
-    event::{LokiBatchEncoder, LokiEvent, LokiRecord, PartitionKey},
-    service::{LokiRequest, LokiRetryLogic, LokiService},
+    event::{Labels, LokiBatchEncoder, LokiEvent, LokiRecord, PartitionKey},
+    service::{LokiRequest, LokiService, LokiTenantSvc},
 };
 use crate::{
     codecs::Encoder,
     config::{log_schema, SinkContext},
     http::HttpClient,
     internal_events::{
-        LokiEventUnlabeled, LokiOutOfOrderEventDropped, LokiOutOfOrderEventRewritten,
-        TemplateRenderingError,
+        LokiEventUnlabeled, LokiOutOfOrderEventAccepted, LokiOutOfOrderEventDropped,
+        LokiOutOfOrderEventRestreamed, LokiOutOfOrderEventRewritten, TemplateRenderingError,
     },
     sinks::util::{
         builder::SinkBuilderExt,
         encoding::Transformer,
         metadata::{RequestMetadata, RequestMetadataBuilder},
         request_builder::EncodeResult,
-        service::{ServiceBuilderExt, Svc},
         Compression, RequestBuilder,
     },
     template::Template,
@@ -152,56 +151,65 @@ pub(super) struct EventEncoder {
     encoder: Encoder<()>,
     labels: HashMap<Template, Template>,
     remove_label_fields: bool,
+    structured_metadata: HashMap<Template, Template>,
+    remove_structured_metadata_fields: bool,
     remove_timestamp: bool,
 }
 
-impl EventEncoder {
-    fn build_labels(&self, event: &Event) -> Vec<(String, String)> {
-        let mut vec: Vec<(String, String)> = Vec::new();
-
-        for (key_template, value_template) in self.labels.iter() {
-            if let (Ok(key), Ok(value)) = (
-                key_template.render_string(event),
-                value_template.render_string(event),
-            ) {
-                if let Some(opening_prefix) = key.strip_suffix('*') {
-                    let output: Result<serde_json::map::Map<String, serde_json::Value>, _> =
-                        serde_json::from_str(value.as_str());
-
-                    if let Ok(output) = output {
-                        // key_* -> key_one, key_two, key_three
-                        for (k, v) in output {
-                            vec.push((
-                                slugify_text(format!("{}{}", opening_prefix, k)),
-                                Value::from(v).to_string_lossy(),
-                            ))
-                        }
+/// Renders the pairs of templates of the labels or the structured metadata. The names ending with
+/// `*` are prefixes, for the keys of the objects rendered by their values.
+fn render_pairs(templates: &HashMap<Template, Template>, event: &Event) -> Labels {
+    let mut vec: Vec<(String, String)> = Vec::new();
+
+    for (key_template, value_template) in templates.iter() {
+        if let (Ok(key), Ok(value)) = (
+            key_template.render_string(event),
+            value_template.render_string(event),
+        ) {
+            if let Some(opening_prefix) = key.strip_suffix('*') {
+                let output: Result<serde_json::map::Map<String, serde_json::Value>, _> =
+                    serde_json::from_str(value.as_str());
+
+                if let Ok(output) = output {
+                    // key_* -> key_one, key_two, key_three
+                    for (k, v) in output {
+                        vec.push((
+                            slugify_text(format!("{}{}", opening_prefix, k)),
+                            Value::from(v).to_string_lossy(),
+                        ))
                     }
-                } else {
-                    vec.push((key, value));
                 }
+            } else {
+                vec.push((key, value));
             }
         }
-        vec
     }
+    vec
+}
 
-    fn remove_label_fields(&self, event: &mut Event) {
-        if self.remove_label_fields {
-            for template in self.labels.values() {
-                if let Some(fields) = template.get_fields() {
-                    for field in fields {
-                        event.as_mut_log().remove(field.as_str());
-                    }
-                }
+/// Removes the fields used by the values of the pairs of templates.
+fn remove_template_fields(templates: &HashMap<Template, Template>, event: &mut Event) {
+    for template in templates.values() {
+        if let Some(fields) = template.get_fields() {
+            for field in fields {
+                event.as_mut_log().remove(field.as_str());
             }
         }
     }
+}
 
+impl EventEncoder {
     pub(super) fn encode_event(&mut self, mut event: Event) -> Option<LokiRecord> {
         let tenant_id = self.key_partitioner.partition(&event);
         let finalizers = event.take_finalizers();
-        let mut labels = self.build_labels(&event);
-        self.remove_label_fields(&mut event);
+        let mut labels = render_pairs(&self.labels, &event);
+        let structured_metadata = render_pairs(&self.structured_metadata, &event);
+        if self.remove_label_fields {
+            remove_template_fields(&self.labels, &mut event);
+        }
+        if self.remove_structured_metadata_fields {
+            remove_template_fields(&self.structured_metadata, &mut event);
+        }
 
         let schema = log_schema();
         let timestamp_key = schema.timestamp_key();
@@ -233,6 +241,7 @@ impl EventEncoder {
             event: LokiEvent {
                 timestamp,
                 event: bytes.freeze(),
+                structured_metadata,
             },
             partition,
             finalizers,
@@ -241,21 +250,22 @@ impl EventEncoder {
 }
 
 struct FilteredRecord {
-    pub rewritten: bool,
+    /// The action taken on the record if it was out of order.
+    pub out_of_order: Option<OutOfOrderAction>,
     pub inner: LokiRecord,
 }
 
 impl FilteredRecord {
-    pub const fn rewritten(inner: LokiRecord) -> Self {
+    pub const fn out_of_order(inner: LokiRecord, action: OutOfOrderAction) -> Self {
         Self {
-            rewritten: true,
+            out_of_order: Some(action),
             inner,
         }
     }
 
     pub const fn valid(inner: LokiRecord) -> Self {
         Self {
-            rewritten: false,
+            out_of_order: None,
             inner,
         }
     }
@@ -275,16 +285,21 @@ impl ByteSizeOf for FilteredRecord {
     }
 }
 
+/// The number of sibling streams an out-of-order event can be restreamed to.
+const MAX_RESTREAMS: usize = 10;
+
 struct RecordFilter {
     timestamps: HashMap<PartitionKey, i64>,
     out_of_order_action: OutOfOrderAction,
+    restream_label: String,
 }
 
 impl RecordFilter {
-    fn new(out_of_order_action: OutOfOrderAction) -> Self {
+    fn new(out_of_order_action: OutOfOrderAction, restream_label: String) -> Self {
         Self {
             timestamps: HashMap::new(),
             out_of_order_action,
+            restream_label,
         }
     }
 }
@@ -297,9 +312,16 @@ impl RecordFilter {
                     OutOfOrderAction::Drop => None,
                     OutOfOrderAction::RewriteTimestamp => {
                         record.event.timestamp = *latest;
-                        Some(FilteredRecord::rewritten(record))
+                        Some(FilteredRecord::out_of_order(
+                            record,
+                            OutOfOrderAction::RewriteTimestamp,
+                        ))
                     }
-                    OutOfOrderAction::Accept => Some(FilteredRecord::valid(record)),
+                    OutOfOrderAction::Restream => self.restream(record),
+                    OutOfOrderAction::Accept => Some(FilteredRecord::out_of_order(
+                        record,
+                        OutOfOrderAction::Accept,
+                    )),
                 }
             } else {
                 *latest = record.event.timestamp;
@@ -311,6 +333,38 @@ impl RecordFilter {
             Some(FilteredRecord::valid(record))
         }
     }
+
+    /// Moves the record to the first sibling stream it is in order for, the siblings being
+    /// labelled with `restream_label` set to 1, 2 and so on. The record is dropped if it is out of
+    /// order for all of them.
+    fn restream(&mut self, mut record: LokiRecord) -> Option<FilteredRecord> {
+        let timestamp = record.event.timestamp;
+        for sibling in 1..=MAX_RESTREAMS {
+            let mut labels = record
+                .labels
+                .iter()
+                .filter(|(name, _)| *name != self.restream_label)
+                .cloned()
+                .collect::<Labels>();
+            labels.push((self.restream_label.clone(), sibling.to_string()));
+            let partition = PartitionKey::new(record.partition.tenant_id.clone(), &mut labels);
+
+            let latest = self
+                .timestamps
+                .entry(partition.clone())
+                .or_insert(timestamp);
+            if timestamp >= *latest {
+                *latest = timestamp;
+                record.labels = labels;
+                record.partition = partition;
+                return Some(FilteredRecord::out_of_order(
+                    record,
+                    OutOfOrderAction::Restream,
+                ));
+            }
+        }
+        None
+    }
 }
 
 pub struct LokiSink {
@@ -319,7 +373,8 @@ pub struct LokiSink {
     pub(super) encoder: EventEncoder,
     batch_settings: BatcherSettings,
     out_of_order_action: OutOfOrderAction,
-    service: Svc<LokiService, LokiRetryLogic>,
+    restream_label: String,
+    service: LokiTenantSvc,
 }
 
 impl LokiSink {
@@ -337,16 +392,19 @@ impl LokiSink {
         // of 1 for now.
         let request_limits = match config.out_of_order_action {
             OutOfOrderAction::Accept => config.request.unwrap_with(&Default::default()),
-            OutOfOrderAction::Drop | OutOfOrderAction::RewriteTimestamp => {
+            OutOfOrderAction::Drop
+            | OutOfOrderAction::RewriteTimestamp
+            | OutOfOrderAction::Restream => {
                 let mut settings = config.request.unwrap_with(&Default::default());
                 settings.concurrency = Some(1);
                 settings
             }
         };
 
-        let service = tower::ServiceBuilder::new()
-            .settings(request_limits, LokiRetryLogic)
-            .service(LokiService::new(client, config.endpoint, config.auth)?);
+        let service = LokiTenantSvc::new(
+            LokiService::new(client, config.endpoint, config.auth)?,
+            request_limits,
+        );
 
         let transformer = config.encoding.transformer();
         let serializer = config.encoding.encoding();
@@ -364,25 +422,28 @@ impl LokiSink {
                 encoder,
                 labels: config.labels,
                 remove_label_fields: config.remove_label_fields,
+                structured_metadata: config.structured_metadata,
+                remove_structured_metadata_fields: config.remove_structured_metadata_fields,
                 remove_timestamp: config.remove_timestamp,
             },
             batch_settings: config.batch.into_batcher_settings()?,
             out_of_order_action: config.out_of_order_action,
+            restream_label: config.restream_label,
             service,
         })
     }
 
     async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
         let mut encoder = self.encoder.clone();
-        let mut filter = RecordFilter::new(self.out_of_order_action);
+        let mut filter = RecordFilter::new(self.out_of_order_action, self.restream_label.clone());
 
         // out_of_order_action's that require a complete ordering are limited to building 1 request
         // at a time
         let request_builder_concurrency = match self.out_of_order_action {
             OutOfOrderAction::Accept => NonZeroUsize::new(50).expect("static"),
-            OutOfOrderAction::Drop | OutOfOrderAction::RewriteTimestamp => {
-                NonZeroUsize::new(1).expect("static")
-            }
+            OutOfOrderAction::Drop
+            | OutOfOrderAction::RewriteTimestamp
+            | OutOfOrderAction::Restream => NonZeroUsize::new(1).expect("static"),
         };
 
         let sink = input
@@ -392,19 +453,28 @@ impl LokiSink {
             .batched_partitioned(RecordPartitioner::default(), self.batch_settings)
             .filter_map(|(partition, batch)| async {
                 if let Some(partition) = partition {
-                    let mut count: usize = 0;
+                    let (mut rewritten, mut restreamed, mut accepted) = (0, 0, 0);
                     let result = batch
                         .into_iter()
                         .flatten()
                         .map(|event| {
-                            if event.rewritten {
-                                count += 1;
+                            match event.out_of_order {
+                                Some(OutOfOrderAction::RewriteTimestamp) => rewritten += 1,
+                                Some(OutOfOrderAction::Restream) => restreamed += 1,
+                                Some(OutOfOrderAction::Accept) => accepted += 1,
+                                Some(OutOfOrderAction::Drop) | None => {}
                             }
                             event.inner
                         })
                         .collect::<Vec<_>>();
-                    if count > 0 {
-                        emit!(LokiOutOfOrderEventRewritten { count });
+                    if rewritten > 0 {
+                        emit!(LokiOutOfOrderEventRewritten { count: rewritten });
+                    }
+                    if restreamed > 0 {
+                        emit!(LokiOutOfOrderEventRestreamed { count: restreamed });
+                    }
+                    if accepted > 0 {
+                        emit!(LokiOutOfOrderEventAccepted { count: accepted });
                     }
                     Some((partition, result))
                 } else {
@@ -467,6 +537,8 @@ mod tests {
             encoder: Encoder::<()>::new(JsonSerializer::new().into()),
             labels: HashMap::default(),
             remove_label_fields: false,
+            structured_metadata: HashMap::default(),
+            remove_structured_metadata_fields: false,
             remove_timestamp: false,
         };
         let mut event = Event::from("hello world");
@@ -506,6 +578,8 @@ mod tests {
             encoder: Encoder::<()>::new(JsonSerializer::new().into()),
             labels,
             remove_label_fields: false,
+            structured_metadata: HashMap::default(),
+            remove_structured_metadata_fields: false,
             remove_timestamp: false,
         };
         let mut event = Event::from("hello world");
@@ -530,6 +604,42 @@ mod tests {
         assert_eq!(labels["test_key_two"], "baz".to_string());
     }
 
+    #[test]
+    fn encoder_with_structured_metadata() {
+        let mut labels = HashMap::default();
+        labels.insert(
+            Template::try_from("app").unwrap(),
+            Template::try_from("{{ app }}").unwrap(),
+        );
+        let mut structured_metadata = HashMap::default();
+        structured_metadata.insert(
+            Template::try_from("trace_id").unwrap(),
+            Template::try_from("{{ trace_id }}").unwrap(),
+        );
+        let mut encoder = EventEncoder {
+            key_partitioner: KeyPartitioner::new(None),
+            transformer: Default::default(),
+            encoder: Encoder::<()>::new(JsonSerializer::new().into()),
+            labels,
+            remove_label_fields: false,
+            structured_metadata,
+            remove_structured_metadata_fields: true,
+            remove_timestamp: false,
+        };
+        let mut event = Event::from("hello world");
+        let log = event.as_mut_log();
+        log.insert("app", "web");
+        log.insert("trace_id", "abc");
+
+        let record = encoder.encode_event(event).unwrap();
+        assert_eq!(record.labels, vec![("app".to_string(), "web".to_string())]);
+        assert_eq!(
+            record.event.structured_metadata,
+            vec![("trace_id".to_string(), "abc".to_string())]
+        );
+        assert!(!String::from_utf8_lossy(&record.event.event).contains("trace_id"));
+    }
+
     #[test]
     fn encoder_no_ts() {
         let mut encoder = EventEncoder {
@@ -538,6 +648,8 @@ mod tests {
             encoder: Encoder::<()>::new(JsonSerializer::new().into()),
             labels: HashMap::default(),
             remove_label_fields: false,
+            structured_metadata: HashMap::default(),
+            remove_structured_metadata_fields: false,
             remove_timestamp: true,
         };
         let mut event = Event::from("hello world");
@@ -566,6 +678,8 @@ mod tests {
             encoder: Encoder::<()>::new(JsonSerializer::new().into()),
             labels,
             remove_label_fields: true,
+            structured_metadata: HashMap::default(),
+            remove_structured_metadata_fields: false,
             remove_timestamp: false,
         };
         let mut event = Event::from("hello world");
@@ -585,6 +699,8 @@ mod tests {
             encoder: Encoder::<()>::new(JsonSerializer::new().into()),
             labels: HashMap::default(),
             remove_label_fields: false,
+            structured_metadata: HashMap::default(),
+            remove_structured_metadata_fields: false,
             remove_timestamp: false,
         };
         let base = chrono::Utc::now();
@@ -603,7 +719,7 @@ mod tests {
                 event
             })
             .collect::<Vec<_>>();
-        let mut filter = RecordFilter::new(OutOfOrderAction::Drop);
+        let mut filter = RecordFilter::new(OutOfOrderAction::Drop, "out_of_order".into());
         let stream = futures::stream::iter(events)
             .map(|event| encoder.encode_event(event))
             .filter_map(|event| async { event })
@@ -618,4 +734,44 @@ mod tests {
         }
         assert_eq!(result.len(), 17);
     }
+
+    #[test]
+    fn filter_restreams_out_of_order_events() {
+        let mut encoder = EventEncoder {
+            key_partitioner: KeyPartitioner::new(None),
+            transformer: Default::default(),
+            encoder: Encoder::<()>::new(JsonSerializer::new().into()),
+            labels: HashMap::default(),
+            remove_label_fields: false,
+            structured_metadata: HashMap::default(),
+            remove_structured_metadata_fields: false,
+            remove_timestamp: false,
+        };
+        let mut filter = RecordFilter::new(OutOfOrderAction::Restream, "out_of_order".into());
+        let base = chrono::Utc::now();
+        let siblings = [10, 5, 3, 7]
+            .iter()
+            .map(|seconds| {
+                let mut event = Event::from("hello world");
+                let log = event.as_mut_log();
+                log.insert(
+                    log_schema().timestamp_key(),
+                    base + chrono::Duration::seconds(*seconds),
+                );
+                let record = filter
+                    .filter_record(encoder.encode_event(event).unwrap())
+                    .unwrap();
+                record
+                    .inner
+                    .labels
+                    .iter()
+                    .find(|(name, _)| name == "out_of_order")
+                    .map(|(_, sibling)| sibling.clone())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            siblings,
+            vec![None, Some("1".into()), Some("2".into()), Some("1".into())]
+        );
+    }
 }
@@ -0,0 +1,441 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use bytes::BytesMut;
+use codecs::{encoding::SerializerConfig, JsonSerializerConfig, TextSerializerConfig};
+use futures::{stream::BoxStream, FutureExt, StreamExt};
+use rumqttc::{AsyncClient, ConnectionError, Event as MqttEvent, EventLoop, Outgoing, Packet, QoS};
+use serde::{Deserialize, Serialize};
+use tokio::time::sleep;
+use tokio_util::codec::Encoder as _;
+use vector_buffers::Acker;
+use vector_common::internal_event::{BytesSent, EventsSent};
+use vector_core::ByteSizeOf;
+
+use crate::{
+    codecs::Encoder,
+    config::{
+        AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext, SinkDescription,
+    },
+    event::{Event, EventFinalizers, EventStatus, Finalizable},
+    internal_events::{
+        prelude::error_stage, MqttConnectionError, MqttPublishError, TemplateRenderingError,
+    },
+    mqtt::{MqttConfig, MqttQoS},
+    sinks::util::{
+        encoding::{EncodingConfig, EncodingConfigAdapter, EncodingConfigMigrator, Transformer},
+        retries::ExponentialBackoff,
+        StreamSink,
+    },
+    template::Template,
+};
+
+/// The number of messages published but not yet acknowledged by the broker, past which no more
+/// are published until some are.
+const MAX_IN_FLIGHT: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncodingMigrator;
+
+impl EncodingConfigMigrator for EncodingMigrator {
+    type Codec = Encoding;
+
+    fn migrate(codec: &Self::Codec) -> SerializerConfig {
+        match codec {
+            Encoding::Text => TextSerializerConfig::new().into(),
+            Encoding::Json => JsonSerializerConfig::new().into(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct MqttSinkConfig {
+    #[serde(flatten)]
+    connection: MqttConfig,
+    topic: Template,
+    #[serde(default)]
+    qos: MqttQoS,
+    /// Whether the broker keeps the last message of each topic, to send it to the clients
+    /// subscribing to it later.
+    #[serde(default)]
+    retain: bool,
+    encoding: EncodingConfigAdapter<EncodingConfig<Encoding>, EncodingMigrator>,
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+inventory::submit! {
+    SinkDescription::new::<MqttSinkConfig>("mqtt")
+}
+
+impl GenerateConfig for MqttSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"
+            host = "127.0.0.1"
+            topic = "vector/{{ host }}"
+            encoding.codec = "json"
+            "#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "mqtt")]
+impl SinkConfig for MqttSinkConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        let options = self.connection.options()?;
+        let (client, eventloop) = AsyncClient::new(options, MAX_IN_FLIGHT);
+        let healthcheck = healthcheck(self.connection.clone()).boxed();
+
+        let sink = MqttSink {
+            client,
+            eventloop,
+            topic: self.topic.clone(),
+            qos: self.qos.into(),
+            retain: self.retain,
+            transformer: self.encoding.transformer(),
+            encoder: Encoder::<()>::new(self.encoding.encoding()),
+            acker: cx.acker(),
+        };
+
+        Ok((super::VectorSink::from_event_streamsink(sink), healthcheck))
+    }
+
+    fn input(&self) -> Input {
+        Input::new(self.encoding.config().input_type())
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "mqtt"
+    }
+
+    fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
+        Some(&self.acknowledgements)
+    }
+}
+
+/// Connects to the broker with a client of its own, which disconnects once it's accepted.
+async fn healthcheck(config: MqttConfig) -> crate::Result<()> {
+    let (client, mut eventloop) = AsyncClient::new(config.options()?, 1);
+    loop {
+        match eventloop.poll().await? {
+            MqttEvent::Incoming(Packet::ConnAck(_)) => {
+                client.try_disconnect()?;
+                return Ok(());
+            }
+            _ => continue,
+        }
+    }
+}
+
+const fn fresh_backoff() -> ExponentialBackoff {
+    ExponentialBackoff::from_millis(2)
+        .factor(250)
+        .max_delay(Duration::from_secs(60))
+}
+
+struct MqttSink {
+    client: AsyncClient,
+    eventloop: EventLoop,
+    topic: Template,
+    qos: QoS,
+    retain: bool,
+    transformer: Transformer,
+    encoder: Encoder<()>,
+    acker: Acker,
+}
+
+/// A message published by the sink, until the broker acknowledges it.
+struct PendingMessage {
+    seqno: usize,
+    finalizers: EventFinalizers,
+    event_byte_size: usize,
+    byte_size: usize,
+}
+
+/// Tracks the messages from the moment they're published to the client until they're
+/// acknowledged, to acknowledge their events in the order they came in.
+#[derive(Default)]
+struct InFlight {
+    /// The messages the event loop of the client has yet to send, in the order they were
+    /// published, which is the order it sends them in.
+    unsent: VecDeque<PendingMessage>,
+    /// The messages sent with a QoS of 1, by packet identifier, until the broker acknowledges
+    /// them.
+    unacknowledged: HashMap<u16, PendingMessage>,
+    seq_head: usize,
+    seq_tail: usize,
+    pending_acks: HashSet<usize>,
+}
+
+impl InFlight {
+    fn len(&self) -> usize {
+        self.unsent.len() + self.unacknowledged.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn next_seqno(&mut self) -> usize {
+        let seqno = self.seq_head;
+        self.seq_head += 1;
+        seqno
+    }
+
+    /// Marks the message as completed, and acknowledges the events that came in before the ones
+    /// that are still in flight.
+    fn complete(&mut self, seqno: usize, acker: &Acker) {
+        self.pending_acks.insert(seqno);
+        let mut num_to_ack = 0;
+        while self.pending_acks.remove(&self.seq_tail) {
+            num_to_ack += 1;
+            self.seq_tail += 1;
+        }
+        acker.ack(num_to_ack);
+    }
+
+    fn delivered(&mut self, message: PendingMessage, acker: &Acker) {
+        emit!(EventsSent {
+            count: 1,
+            byte_size: message.event_byte_size,
+            output: None,
+        });
+        emit!(BytesSent {
+            byte_size: message.byte_size,
+            protocol: "mqtt",
+        });
+        message.finalizers.update_status(EventStatus::Delivered);
+        self.complete(message.seqno, acker);
+    }
+}
+
+impl MqttSink {
+    /// Publishes the event to the client, whose event loop sends it to the broker once polled.
+    fn publish(&mut self, mut event: Event, in_flight: &mut InFlight) {
+        let seqno = in_flight.next_seqno();
+        let finalizers = event.take_finalizers();
+
+        let topic = match self.topic.render_string(&event) {
+            Ok(topic) => topic,
+            Err(error) => {
+                emit!(TemplateRenderingError {
+                    error,
+                    field: Some("topic"),
+                    drop_event: true,
+                });
+                finalizers.update_status(EventStatus::Rejected);
+                in_flight.complete(seqno, &self.acker);
+                return;
+            }
+        };
+
+        self.transformer.transform(&mut event);
+        let event_byte_size = event.size_of();
+        let mut bytes = BytesMut::new();
+        if self.encoder.encode(event, &mut bytes).is_err() {
+            // Error is logged by `Encoder`.
+            finalizers.update_status(EventStatus::Rejected);
+            in_flight.complete(seqno, &self.acker);
+            return;
+        }
+
+        let byte_size = bytes.len();
+        match self
+            .client
+            .try_publish(topic, self.qos, self.retain, bytes.to_vec())
+        {
+            Ok(()) => in_flight.unsent.push_back(PendingMessage {
+                seqno,
+                finalizers,
+                event_byte_size,
+                byte_size,
+            }),
+            Err(error) => {
+                emit!(MqttPublishError { error });
+                finalizers.update_status(EventStatus::Errored);
+                in_flight.complete(seqno, &self.acker);
+            }
+        }
+    }
+
+    /// Handles the notifications of the event loop, which sends the messages and receives their
+    /// acknowledgements. Reconnecting is left to the next poll once the connection fails, the
+    /// messages that weren't acknowledged yet being sent again then.
+    async fn handle_notification(
+        &mut self,
+        notification: Result<MqttEvent, ConnectionError>,
+        in_flight: &mut InFlight,
+        backoff: &mut ExponentialBackoff,
+    ) {
+        match notification {
+            Ok(MqttEvent::Incoming(Packet::ConnAck(_))) => *backoff = fresh_backoff(),
+            // Messages sent with a QoS of 0 aren't acknowledged, and have no packet identifier.
+            Ok(MqttEvent::Outgoing(Outgoing::Publish(0))) => {
+                if let Some(message) = in_flight.unsent.pop_front() {
+                    in_flight.delivered(message, &self.acker);
+                }
+            }
+            Ok(MqttEvent::Outgoing(Outgoing::Publish(pkid))) => {
+                // Messages sent again after reconnecting are already tracked.
+                if !in_flight.unacknowledged.contains_key(&pkid) {
+                    if let Some(message) = in_flight.unsent.pop_front() {
+                        in_flight.unacknowledged.insert(pkid, message);
+                    }
+                }
+            }
+            Ok(MqttEvent::Incoming(Packet::PubAck(ack))) => {
+                if let Some(message) = in_flight.unacknowledged.remove(&ack.pkid) {
+                    in_flight.delivered(message, &self.acker);
+                }
+            }
+            Ok(_) => {}
+            Err(error) => {
+                emit!(MqttConnectionError {
+                    error,
+                    stage: error_stage::SENDING,
+                });
+                sleep(backoff.next().unwrap()).await;
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl StreamSink<Event> for MqttSink {
+    async fn run(mut self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let mut input = input.fuse();
+        let mut in_flight = InFlight::default();
+        let mut backoff = fresh_backoff();
+
+        loop {
+            tokio::select! {
+                event = input.next(), if in_flight.len() < MAX_IN_FLIGHT => match event {
+                    Some(event) => self.publish(event, &mut in_flight),
+                    None => break,
+                },
+                notification = self.eventloop.poll() => {
+                    self.handle_notification(notification, &mut in_flight, &mut backoff).await;
+                }
+            }
+        }
+
+        while !in_flight.is_empty() {
+            let notification = self.eventloop.poll().await;
+            self.handle_notification(notification, &mut in_flight, &mut backoff)
+                .await;
+        }
+        let _ = self.client.try_disconnect();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<MqttSinkConfig>();
+    }
+
+    #[test]
+    fn acknowledges_events_in_order() {
+        let (acker, counter) = Acker::basic();
+        let mut in_flight = InFlight::default();
+        let first = in_flight.next_seqno();
+        let second = in_flight.next_seqno();
+
+        in_flight.complete(second, &acker);
+        assert_eq!(counter.load(std::sync::atomic::Ordering::Relaxed), 0);
+        in_flight.complete(first, &acker);
+        assert_eq!(counter.load(std::sync::atomic::Ordering::Relaxed), 2);
+    }
+}
+
+#[cfg(feature = "mqtt-integration-tests")]
+#[cfg(test)]
+mod integration_tests {
+    use futures::future;
+    use rumqttc::{MqttOptions, SubscribeFilter};
+
+    use super::*;
+    use crate::{
+        event::{BatchNotifier, BatchStatus, LogEvent},
+        test_util::{
+            components::{run_and_assert_sink_compliance, SINK_TAGS},
+            random_string,
+        },
+    };
+
+    fn mqtt_host() -> String {
+        std::env::var("MQTT_HOST").unwrap_or_else(|_| "127.0.0.1".into())
+    }
+
+    #[tokio::test]
+    async fn publishes_retained_messages() {
+        let prefix = format!("test-{}", random_string(10));
+        let config: MqttSinkConfig = toml::from_str(&format!(
+            r#"
+            host = "{}"
+            topic = "{}/{{{{ sensor }}}}"
+            retain = true
+            encoding.codec = "text"
+            "#,
+            mqtt_host(),
+            prefix
+        ))
+        .unwrap();
+
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let mut log = LogEvent::from("hello").with_batch_notifier(&batch);
+        log.insert("sensor", "1");
+        drop(batch);
+
+        let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+        let events = futures::stream::once(future::ready(Event::from(log)));
+        run_and_assert_sink_compliance(sink, events, &SINK_TAGS).await;
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+        // The message is retained, so it's received by clients subscribing after it was
+        // published.
+        let options = MqttOptions::new(random_string(10), mqtt_host(), 1883);
+        let (client, mut eventloop) = AsyncClient::new(options, 10);
+        client
+            .subscribe_many(vec![SubscribeFilter::new(
+                format!("{}/+", prefix),
+                QoS::AtLeastOnce,
+            )])
+            .await
+            .unwrap();
+        loop {
+            if let MqttEvent::Incoming(Packet::Publish(publish)) = eventloop.poll().await.unwrap() {
+                assert_eq!(publish.topic, format!("{}/1", prefix));
+                assert_eq!(&publish.payload[..], b"hello");
+                assert!(publish.retain);
+                break;
+            }
+        }
+    }
+}
@@ -170,6 +170,7 @@ impl NewRelicLogsConfig {
             batch: batch_settings.into(),
             request,
             tls: None,
+            propagate_trace_context: false,
             acknowledgements: self.acknowledgements,
         })
     }
@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+
+use super::retry::OpensearchRetryLogic;
+use crate::{
+    aws::RegionOrEndpoint,
+    config::{AcknowledgementsConfig, DataType, GenerateConfig, Input, SinkConfig, SinkContext},
+    sinks::{
+        elasticsearch::{
+            BulkConfig, DataStreamConfig, ElasticsearchApiVersion, ElasticsearchAuth,
+            ElasticsearchConfig, ElasticsearchEncoder, ElasticsearchMode,
+        },
+        util::{
+            encoding::EncodingConfigFixed, http::RequestConfig, BatchConfig, Compression,
+            RealtimeSizeBasedDefaultBatchSettings,
+        },
+        Healthcheck, VectorSink,
+    },
+    tls::TlsConfig,
+    transforms::metric_to_log::MetricToLogConfig,
+};
+
+#[derive(Debug, Snafu)]
+enum OpensearchConfigError {
+    #[snafu(display("Serverless collections require the `aws` authentication strategy"))]
+    ServerlessRequiresAws,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct OpensearchConfig {
+    pub endpoint: String,
+    /// Whether the endpoint is an Amazon OpenSearch Serverless collection.
+    #[serde(default)]
+    pub serverless: bool,
+
+    pub id_key: Option<String>,
+    pub id: Option<String>,
+    pub routing: Option<String>,
+    pub pipeline: Option<String>,
+    #[serde(default)]
+    pub mode: ElasticsearchMode,
+
+    #[serde(default)]
+    pub compression: Compression,
+    #[serde(
+        skip_serializing_if = "crate::serde::skip_serializing_if_default",
+        default
+    )]
+    pub encoding: EncodingConfigFixed<ElasticsearchEncoder>,
+
+    #[serde(default)]
+    pub batch: BatchConfig<RealtimeSizeBasedDefaultBatchSettings>,
+    #[serde(default)]
+    pub request: RequestConfig,
+    pub auth: Option<ElasticsearchAuth>,
+    pub query: Option<HashMap<String, String>>,
+    pub aws: Option<RegionOrEndpoint>,
+    pub tls: Option<TlsConfig>,
+
+    pub bulk: Option<BulkConfig>,
+    pub data_stream: Option<DataStreamConfig>,
+    pub metrics: Option<MetricToLogConfig>,
+
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub acknowledgements: AcknowledgementsConfig,
+}
+
+impl GenerateConfig for OpensearchConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"endpoint = "http://localhost:9200""#).unwrap()
+    }
+}
+
+impl OpensearchConfig {
+    /// The configuration of the `elasticsearch` sink the requests are built with.
+    fn elasticsearch_config(&self) -> crate::Result<ElasticsearchConfig> {
+        if self.serverless && !matches!(self.auth, Some(ElasticsearchAuth::Aws(_))) {
+            return Err(OpensearchConfigError::ServerlessRequiresAws.into());
+        }
+
+        Ok(ElasticsearchConfig {
+            endpoint: self.endpoint.clone(),
+            // Mapping types are removed in OpenSearch 2, and the requests without a type are
+            // accepted by all the versions of OpenSearch.
+            suppress_type_name: true,
+            id_key: self.id_key.clone(),
+            id: self.id.clone(),
+            routing: self.routing.clone(),
+            pipeline: self.pipeline.clone(),
+            mode: self.mode.clone(),
+            // OpenSearch forked from Elasticsearch 7.10, its version isn't queried.
+            api_version: ElasticsearchApiVersion::V7,
+            compression: self.compression,
+            encoding: self.encoding.clone(),
+            batch: self.batch,
+            request: self.request.clone(),
+            auth: self.auth.clone(),
+            query: self.query.clone(),
+            aws: self.aws.clone(),
+            tls: self.tls.clone(),
+            bulk: self.bulk.clone(),
+            data_stream: self.data_stream.clone(),
+            metrics: self.metrics.clone(),
+            serverless: self.serverless,
+            acknowledgements: self.acknowledgements,
+            ..Default::default()
+        })
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "opensearch")]
+impl SinkConfig for OpensearchConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        self.elasticsearch_config()?
+            .build_with_retry_logic(cx, OpensearchRetryLogic)
+            .await
+    }
+
+    fn input(&self) -> Input {
+        Input::new(DataType::Metric | DataType::Log)
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "opensearch"
+    }
+
+    fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
+        Some(&self.acknowledgements)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<OpensearchConfig>();
+    }
+
+    #[test]
+    fn adapts_the_elasticsearch_config() {
+        let config = toml::from_str::<OpensearchConfig>(
+            r#"
+            endpoint = "https://example.us-east-1.aoss.amazonaws.com"
+            serverless = true
+            auth.strategy = "aws"
+            aws.region = "us-east-1"
+            bulk.index = "logs"
+        "#,
+        )
+        .unwrap();
+        let config = config.elasticsearch_config().unwrap();
+        assert!(config.serverless);
+        assert!(config.suppress_type_name);
+        assert_eq!(config.api_version, ElasticsearchApiVersion::V7);
+        assert_eq!(config.bulk.unwrap().index.as_deref(), Some("logs"));
+    }
+
+    #[test]
+    fn serverless_requires_aws_auth() {
+        let config = toml::from_str::<OpensearchConfig>(
+            r#"
+            endpoint = "https://example.us-east-1.aoss.amazonaws.com"
+            serverless = true
+            auth.strategy = "basic"
+            auth.user = "user"
+            auth.password = "password"
+        "#,
+        )
+        .unwrap();
+        assert!(config.elasticsearch_config().is_err());
+    }
+}
@@ -0,0 +1,16 @@
+//! The OpenSearch [`VectorSink`](crate::sinks::VectorSink).
+//!
+//! This module contains the sink sending logs and metrics to OpenSearch clusters, including the
+//! Amazon OpenSearch Service domains and the Amazon OpenSearch Serverless collections. It builds on
+//! the `elasticsearch` sink, with the requests adapted to OpenSearch and its own classification of
+//! the bulk responses.
+
+mod config;
+mod retry;
+
+pub use self::config::OpensearchConfig;
+use crate::config::SinkDescription;
+
+inventory::submit! {
+    SinkDescription::new::<OpensearchConfig>("opensearch")
+}
@@ -0,0 +1,188 @@
+use std::collections::BTreeMap;
+
+use http::StatusCode;
+use serde::Deserialize;
+
+use crate::{
+    http::HttpError,
+    sinks::{
+        elasticsearch::ElasticsearchResponse,
+        util::retries::{RetryAction, RetryLogic},
+    },
+};
+
+#[derive(Deserialize, Debug)]
+struct BulkResponse {
+    items: Vec<BTreeMap<String, BulkItem>>,
+}
+
+#[derive(Deserialize, Debug)]
+struct BulkItem {
+    status: u16,
+    error: Option<BulkItemError>,
+}
+
+#[derive(Deserialize, Debug)]
+struct BulkItemError {
+    #[serde(rename = "type")]
+    err_type: String,
+    reason: Option<String>,
+}
+
+impl BulkItem {
+    /// The documents rejected because the cluster is overloaded, or failing on its side, can be
+    /// sent again as they are.
+    fn is_retriable(&self) -> bool {
+        self.status == StatusCode::TOO_MANY_REQUESTS.as_u16() || self.status >= 500
+    }
+}
+
+/// Classifies the responses of OpenSearch: the bulk requests are only retried if all their
+/// documents can be retried, so that the documents written are never sent twice.
+#[derive(Clone)]
+pub struct OpensearchRetryLogic;
+
+impl RetryLogic for OpensearchRetryLogic {
+    type Error = HttpError;
+    type Response = ElasticsearchResponse;
+
+    fn is_retriable_error(&self, _error: &Self::Error) -> bool {
+        true
+    }
+
+    fn should_retry_response(&self, response: &ElasticsearchResponse) -> RetryAction {
+        let status = response.http_response.status();
+        let body = String::from_utf8_lossy(response.http_response.body());
+
+        match status {
+            StatusCode::TOO_MANY_REQUESTS => RetryAction::Retry("too many requests".into()),
+            StatusCode::PAYLOAD_TOO_LARGE => {
+                RetryAction::DontRetry("request too large, the batches should be smaller".into())
+            }
+            StatusCode::NOT_IMPLEMENTED => {
+                RetryAction::DontRetry("endpoint not implemented".into())
+            }
+            _ if status.is_server_error() => {
+                RetryAction::Retry(format!("{}: {}", status, body).into())
+            }
+            _ if status.is_client_error() => {
+                RetryAction::DontRetry(format!("client-side error, {}: {}", status, body).into())
+            }
+            _ if status.is_success() => {
+                if body.contains("\"errors\":true") {
+                    classify_bulk_errors(&body)
+                } else {
+                    RetryAction::Successful
+                }
+            }
+            _ => RetryAction::DontRetry(format!("response status: {}", status).into()),
+        }
+    }
+}
+
+fn classify_bulk_errors(body: &str) -> RetryAction {
+    let response = match serde_json::from_str::<BulkResponse>(body) {
+        Ok(response) => response,
+        Err(error) => {
+            return RetryAction::DontRetry(
+                format!(
+                    "some documents failed, could not parse response, error: {}",
+                    error
+                )
+                .into(),
+            )
+        }
+    };
+
+    let items = response
+        .items
+        .into_iter()
+        .flat_map(BTreeMap::into_values)
+        .collect::<Vec<_>>();
+    let failed = items
+        .iter()
+        .filter(|item| item.error.is_some())
+        .collect::<Vec<_>>();
+    // The reported error is the one preventing the retries, if any.
+    let reason = failed
+        .iter()
+        .find(|item| !item.is_retriable())
+        .or_else(|| failed.first())
+        .and_then(|item| item.error.as_ref())
+        .map(|error| {
+            format!(
+                "{} of {} documents failed, error type: {}, reason: {}",
+                failed.len(),
+                items.len(),
+                error.err_type,
+                error.reason.as_deref().unwrap_or_default()
+            )
+        })
+        .unwrap_or_else(|| format!("error response: {}", body));
+
+    if items.iter().all(BulkItem::is_retriable) {
+        RetryAction::Retry(reason.into())
+    } else {
+        RetryAction::DontRetry(reason.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use http::Response;
+
+    use super::*;
+    use crate::event::EventStatus;
+
+    fn should_retry(status: StatusCode, body: &'static str) -> RetryAction {
+        let response = Response::builder()
+            .status(status)
+            .body(Bytes::from(body))
+            .unwrap();
+        OpensearchRetryLogic.should_retry_response(&ElasticsearchResponse {
+            http_response: response,
+            event_status: EventStatus::Rejected,
+            batch_size: 1,
+            events_byte_size: 1,
+        })
+    }
+
+    #[test]
+    fn retries_rejected_executions() {
+        let body = r#"{"took":3,"errors":true,"items":[{"index":{"_index":"logs","_id":"1","status":429,"error":{"type":"rejected_execution_exception","reason":"rejected execution of coordinating operation"}}},{"create":{"_index":"logs","_id":"2","status":503,"error":{"type":"unavailable_shards_exception","reason":"primary shard is not active"}}}]}"#;
+        assert!(matches!(
+            should_retry(StatusCode::OK, body),
+            RetryAction::Retry(_)
+        ));
+    }
+
+    #[test]
+    fn does_not_retry_partial_failures() {
+        let body = r#"{"took":3,"errors":true,"items":[{"index":{"_index":"logs","_id":"1","status":201,"result":"created"}},{"index":{"_index":"logs","_id":"2","status":429,"error":{"type":"rejected_execution_exception","reason":"rejected execution of coordinating operation"}}}]}"#;
+        assert!(matches!(
+            should_retry(StatusCode::OK, body),
+            RetryAction::DontRetry(_)
+        ));
+    }
+
+    #[test]
+    fn reports_mapping_errors() {
+        let body = r#"{"took":3,"errors":true,"items":[{"index":{"_index":"logs","_id":"1","status":400,"error":{"type":"mapper_parsing_exception","reason":"failed to parse field [host] of type [keyword]"}}}]}"#;
+        match should_retry(StatusCode::OK, body) {
+            RetryAction::DontRetry(reason) => assert_eq!(
+                reason,
+                "1 of 1 documents failed, error type: mapper_parsing_exception, reason: failed to parse field [host] of type [keyword]"
+            ),
+            _ => panic!("mapping errors must not be retried"),
+        }
+    }
+
+    #[test]
+    fn does_not_retry_large_requests() {
+        assert!(matches!(
+            should_retry(StatusCode::PAYLOAD_TOO_LARGE, ""),
+            RetryAction::DontRetry(_)
+        ));
+    }
+}
@@ -0,0 +1,168 @@
+use futures::future;
+use http::StatusCode;
+use hyper::Client;
+use serde::{Deserialize, Serialize};
+use tower::ServiceBuilder;
+
+use super::{
+    service::{GrpcService, HttpService, OpentelemetryRequest, OpentelemetryResponse},
+    sink::OpentelemetrySink,
+    OpentelemetrySinkError,
+};
+use crate::{
+    config::{AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext},
+    http::{Auth, HttpClient, HttpError, MaybeAuth},
+    sinks::{
+        util::{
+            retries::RetryLogic, BatchConfig, RealtimeEventBasedDefaultBatchSettings,
+            ServiceBuilderExt, TowerRequestConfig, UriSerde,
+        },
+        Healthcheck, VectorSink,
+    },
+    tls::{MaybeTlsSettings, TlsEnableableConfig},
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct OpentelemetrySinkConfig {
+    endpoint: UriSerde,
+    #[serde(default)]
+    protocol: Protocol,
+    #[serde(default)]
+    compression: bool,
+    auth: Option<Auth>,
+    #[serde(default)]
+    batch: BatchConfig<RealtimeEventBasedDefaultBatchSettings>,
+    #[serde(default)]
+    request: TowerRequestConfig,
+    tls: Option<TlsEnableableConfig>,
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+/// The transport of the OTLP export requests.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Derivative, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Protocol {
+    #[derivative(Default)]
+    Grpc,
+    Http,
+}
+
+impl GenerateConfig for OpentelemetrySinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"endpoint = "http://localhost:4317""#).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "opentelemetry")]
+impl SinkConfig for OpentelemetrySinkConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let endpoint = self.endpoint.with_default_parts();
+        let auth = self.auth.choose_one(&endpoint.auth)?;
+        let tls = MaybeTlsSettings::from_config(&self.tls, false)?;
+
+        let sink = match self.protocol {
+            Protocol::Grpc => {
+                let client = HttpClient::new_with_custom_client(
+                    tls,
+                    cx.proxy(),
+                    Client::builder().http2_only(true),
+                )?;
+                let service = GrpcService::new(client, &endpoint.uri, auth, self.compression)?;
+                self.build_sink(service, &cx)?
+            }
+            Protocol::Http => {
+                let client = HttpClient::new(tls, cx.proxy())?;
+                let service = HttpService::new(
+                    client,
+                    endpoint.append_path("v1/logs")?.uri,
+                    endpoint.append_path("v1/metrics")?.uri,
+                    endpoint.append_path("v1/traces")?.uri,
+                    auth,
+                    self.compression,
+                );
+                self.build_sink(service, &cx)?
+            }
+        };
+
+        // OTLP has no way to check the health of the endpoint without exporting data.
+        Ok((sink, Box::pin(future::ok(()))))
+    }
+
+    fn input(&self) -> Input {
+        Input::all()
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "opentelemetry"
+    }
+
+    fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
+        Some(&self.acknowledgements)
+    }
+}
+
+impl OpentelemetrySinkConfig {
+    fn build_sink<S>(&self, service: S, cx: &SinkContext) -> crate::Result<VectorSink>
+    where
+        S: tower::Service<
+                OpentelemetryRequest,
+                Response = OpentelemetryResponse,
+                Error = OpentelemetrySinkError,
+            > + Clone
+            + Send
+            + 'static,
+        S::Future: Send + 'static,
+    {
+        let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
+        let service = ServiceBuilder::new()
+            .settings(request_settings, OpentelemetryRetryLogic)
+            .service(service);
+
+        let sink = OpentelemetrySink {
+            batch_settings: self.batch.into_batcher_settings()?,
+            service,
+            acker: cx.acker(),
+        };
+        Ok(VectorSink::from_event_streamsink(sink))
+    }
+}
+
+/// Retries the failures the OTLP specification considers transient.
+///
+/// See: <https://github.com/open-telemetry/opentelemetry-specification/blob/main/specification/protocol/otlp.md#failures>
+#[derive(Debug, Clone)]
+struct OpentelemetryRetryLogic;
+
+impl RetryLogic for OpentelemetryRetryLogic {
+    type Error = OpentelemetrySinkError;
+    type Response = OpentelemetryResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        use tonic::Code::*;
+
+        match error {
+            OpentelemetrySinkError::Grpc { source } => matches!(
+                source.code(),
+                Cancelled | DeadlineExceeded | Aborted | OutOfRange | Unavailable | DataLoss
+            ),
+            OpentelemetrySinkError::Http { source } => {
+                matches!(source, HttpError::CallRequest { .. })
+            }
+            OpentelemetrySinkError::HttpStatus { status } => matches!(
+                *status,
+                StatusCode::TOO_MANY_REQUESTS
+                    | StatusCode::BAD_GATEWAY
+                    | StatusCode::SERVICE_UNAVAILABLE
+                    | StatusCode::GATEWAY_TIMEOUT
+            ),
+        }
+    }
+}
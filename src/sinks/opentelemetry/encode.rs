@@ -0,0 +1,745 @@
+//! Conversions of Vector events into OTLP export requests.
+//!
+//! These are the reverse of the conversions of the `opentelemetry` source: the metadata of logs
+//! and traces, and the tags prefixed with `resource.` and `scope.` of metrics, describe the
+//! resource and instrumentation scope that produced the data. Events sharing them are grouped
+//! together in the export request.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    config::log_schema,
+    event::{
+        metric::{Bucket, MetricTags},
+        Event, LogEvent, Metric, MetricKind, MetricValue, OpenTelemetryScope, TraceEvent, Value,
+    },
+    proto::opentelemetry::{
+        any_value,
+        proto::{
+            common::v1::{ArrayValue, KeyValueList},
+            logs::v1::{LogRecord, ResourceLogs, ScopeLogs},
+            metrics::v1::{
+                metric::Data, number_data_point, summary_data_point::ValueAtQuantile,
+                AggregationTemporality, Gauge, Histogram, HistogramDataPoint, Metric as OtlpMetric,
+                NumberDataPoint, ResourceMetrics, ScopeMetrics, Sum, Summary, SummaryDataPoint,
+            },
+            trace::v1::{span, status, ResourceSpans, ScopeSpans, Span, Status},
+        },
+        AnyValue, ExportLogsServiceRequest, ExportMetricsServiceRequest, ExportTraceServiceRequest,
+        InstrumentationScope, KeyValue, Resource,
+    },
+    sinks::util::encode_namespace,
+};
+
+/// Whether a metric has a counterpart in the OTLP data model.
+///
+/// Distributions and sketches hold samples that OTLP has no data point for.
+pub(super) fn is_supported(metric: &Metric) -> bool {
+    !matches!(
+        metric.value(),
+        MetricValue::Distribution { .. } | MetricValue::Sketch { .. }
+    )
+}
+
+pub(super) fn logs(events: Vec<Event>) -> ExportLogsServiceRequest {
+    let mut groups = Groups::default();
+    for event in events {
+        let (scope, record) = log_record(event.into_log());
+        groups.push(scope, record);
+    }
+
+    ExportLogsServiceRequest {
+        resource_logs: groups
+            .0
+            .into_iter()
+            .map(|(scope, log_records)| ResourceLogs {
+                resource: resource(&scope),
+                scope_logs: vec![ScopeLogs {
+                    scope: instrumentation_scope(&scope),
+                    log_records,
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            })
+            .collect(),
+    }
+}
+
+/// Fields of the log that aren't part of the OTLP log data model are sent as attributes.
+fn log_record(mut log: LogEvent) -> (Option<Arc<OpenTelemetryScope>>, LogRecord) {
+    let body = log.remove(log_schema().message_key());
+    let time = log.remove(log_schema().timestamp_key());
+    log.remove(log_schema().source_type_key());
+
+    let (fields, metadata) = log.into_parts();
+    let mut fields = fields.into_object().unwrap_or_default();
+    let scope = metadata.opentelemetry_scope().clone();
+
+    let record = LogRecord {
+        time_unix_nano: time_unix_nano(time.as_ref()),
+        observed_time_unix_nano: time_unix_nano(fields.remove("observed_timestamp").as_ref()),
+        severity_number: integer(fields.remove("severity_number")),
+        severity_text: string(fields.remove("severity_text")),
+        body: body.map(any),
+        flags: integer(fields.remove("flags")),
+        dropped_attributes_count: integer(fields.remove("dropped_attributes_count")),
+        trace_id: id(fields.remove("trace_id")),
+        span_id: id(fields.remove("span_id")),
+        attributes: attributes(&mut fields),
+    };
+    (scope, record)
+}
+
+pub(super) fn traces(events: Vec<Event>) -> ExportTraceServiceRequest {
+    let mut groups = Groups::default();
+    for event in events {
+        let (scope, span) = span(event.into_trace());
+        groups.push(scope, span);
+    }
+
+    ExportTraceServiceRequest {
+        resource_spans: groups
+            .0
+            .into_iter()
+            .map(|(scope, spans)| ResourceSpans {
+                resource: resource(&scope),
+                scope_spans: vec![ScopeSpans {
+                    scope: instrumentation_scope(&scope),
+                    spans,
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            })
+            .collect(),
+    }
+}
+
+/// Fields of the trace that aren't part of the OTLP span data model are sent as attributes.
+fn span(trace: TraceEvent) -> (Option<Arc<OpenTelemetryScope>>, Span) {
+    let (mut fields, metadata) = trace.into_parts();
+    fields.remove(log_schema().source_type_key());
+    let scope = metadata.opentelemetry_scope().clone();
+
+    let status = fields
+        .remove("status")
+        .and_then(Value::into_object)
+        .map(|mut status| Status {
+            message: string(status.remove("message")),
+            code: status_code(status.get("code")) as i32,
+        });
+    let span = Span {
+        trace_id: id(fields.remove("trace_id")),
+        span_id: id(fields.remove("span_id")),
+        trace_state: string(fields.remove("trace_state")),
+        parent_span_id: id(fields.remove("parent_span_id")),
+        name: string(fields.remove("name")),
+        kind: span_kind(fields.remove("kind").as_ref()) as i32,
+        start_time_unix_nano: time_unix_nano(fields.remove("start_timestamp").as_ref()),
+        end_time_unix_nano: time_unix_nano(fields.remove("end_timestamp").as_ref()),
+        dropped_attributes_count: integer(fields.remove("dropped_attributes_count")),
+        events: objects(fields.remove("events"))
+            .map(|mut event| span::Event {
+                time_unix_nano: time_unix_nano(event.remove("timestamp").as_ref()),
+                name: string(event.remove("name")),
+                dropped_attributes_count: integer(event.remove("dropped_attributes_count")),
+                attributes: attributes(&mut event),
+            })
+            .collect(),
+        dropped_events_count: integer(fields.remove("dropped_events_count")),
+        links: objects(fields.remove("links"))
+            .map(|mut link| span::Link {
+                trace_id: id(link.remove("trace_id")),
+                span_id: id(link.remove("span_id")),
+                trace_state: string(link.remove("trace_state")),
+                dropped_attributes_count: integer(link.remove("dropped_attributes_count")),
+                attributes: attributes(&mut link),
+            })
+            .collect(),
+        dropped_links_count: integer(fields.remove("dropped_links_count")),
+        status,
+        attributes: attributes(&mut fields),
+    };
+    (scope, span)
+}
+
+fn span_kind(kind: Option<&Value>) -> span::SpanKind {
+    match kind.and_then(Value::as_bytes).map(|kind| &kind[..]) {
+        Some(b"internal") => span::SpanKind::Internal,
+        Some(b"server") => span::SpanKind::Server,
+        Some(b"client") => span::SpanKind::Client,
+        Some(b"producer") => span::SpanKind::Producer,
+        Some(b"consumer") => span::SpanKind::Consumer,
+        _ => span::SpanKind::Unspecified,
+    }
+}
+
+fn status_code(code: Option<&Value>) -> status::StatusCode {
+    match code.and_then(Value::as_bytes).map(|code| &code[..]) {
+        Some(b"ok") => status::StatusCode::Ok,
+        Some(b"error") => status::StatusCode::Error,
+        _ => status::StatusCode::Unset,
+    }
+}
+
+/// Converts the metrics, which must all be supported, into an export request with a data point
+/// for each of them.
+pub(super) fn metrics(events: Vec<Event>) -> ExportMetricsServiceRequest {
+    let mut groups = Groups::default();
+    for event in events {
+        let metric = event.into_metric();
+        let name = encode_namespace(metric.namespace(), '.', metric.name());
+        let timestamp = metric.timestamp();
+        let (series, data, _) = metric.into_parts();
+        let (scope, tags) = split_tags(series.tags.unwrap_or_default());
+        if let Some(data) = metric_data(data.kind, data.value, tags, timestamp) {
+            groups.push(
+                Some(Arc::new(scope)),
+                OtlpMetric {
+                    name,
+                    data: Some(data),
+                    ..Default::default()
+                },
+            );
+        }
+    }
+
+    ExportMetricsServiceRequest {
+        resource_metrics: groups
+            .0
+            .into_iter()
+            .map(|(scope, metrics)| ResourceMetrics {
+                resource: resource(&scope),
+                scope_metrics: vec![ScopeMetrics {
+                    scope: instrumentation_scope(&scope),
+                    metrics,
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            })
+            .collect(),
+    }
+}
+
+/// Splits the tags of a metric into the attributes of its resource, its instrumentation scope
+/// and its data point.
+fn split_tags(tags: MetricTags) -> (OpenTelemetryScope, Vec<KeyValue>) {
+    let mut resources = BTreeMap::new();
+    let mut scope = BTreeMap::new();
+    let mut scope_attributes = BTreeMap::new();
+    let mut attributes = Vec::new();
+    for (key, value) in tags {
+        if let Some(key) = key.strip_prefix("resource.") {
+            resources.insert(key.to_owned(), value.into());
+        } else if let Some(key) = key.strip_prefix("scope.") {
+            if key == "name" || key == "version" {
+                scope.insert(key.to_owned(), value.into());
+            } else {
+                scope_attributes.insert(key.to_owned(), value.into());
+            }
+        } else {
+            attributes.push(key_value(key, value.into()));
+        }
+    }
+    if !scope_attributes.is_empty() {
+        scope.insert("attributes".to_owned(), Value::Object(scope_attributes));
+    }
+    let scope = OpenTelemetryScope {
+        resource: Value::Object(resources),
+        scope: Value::Object(scope),
+    };
+    (scope, attributes)
+}
+
+fn metric_data(
+    kind: MetricKind,
+    value: MetricValue,
+    attributes: Vec<KeyValue>,
+    timestamp: Option<DateTime<Utc>>,
+) -> Option<Data> {
+    let time_unix_nano = timestamp.as_ref().map(nanos).unwrap_or_default();
+    let number_point = |value: f64| NumberDataPoint {
+        attributes: attributes.clone(),
+        time_unix_nano,
+        value: Some(number_data_point::Value::AsDouble(value)),
+        ..Default::default()
+    };
+    let aggregation_temporality = match kind {
+        MetricKind::Incremental => AggregationTemporality::Delta,
+        MetricKind::Absolute => AggregationTemporality::Cumulative,
+    } as i32;
+
+    let data = match value {
+        MetricValue::Counter { value } => Data::Sum(Sum {
+            data_points: vec![number_point(value)],
+            aggregation_temporality,
+            is_monotonic: true,
+        }),
+        // Incremental gauges are changes of a value that can decrease.
+        MetricValue::Gauge { value } if kind == MetricKind::Incremental => Data::Sum(Sum {
+            data_points: vec![number_point(value)],
+            aggregation_temporality,
+            is_monotonic: false,
+        }),
+        MetricValue::Gauge { value } => Data::Gauge(Gauge {
+            data_points: vec![number_point(value)],
+        }),
+        MetricValue::Set { values } => Data::Gauge(Gauge {
+            data_points: vec![number_point(values.len() as f64)],
+        }),
+        MetricValue::AggregatedHistogram {
+            buckets,
+            count,
+            sum,
+        } => {
+            let (bucket_counts, explicit_bounds) = histogram_buckets(&buckets, count);
+            Data::Histogram(Histogram {
+                data_points: vec![HistogramDataPoint {
+                    attributes,
+                    time_unix_nano,
+                    count: u64::from(count),
+                    sum,
+                    bucket_counts,
+                    explicit_bounds,
+                    ..Default::default()
+                }],
+                aggregation_temporality,
+            })
+        }
+        MetricValue::AggregatedSummary {
+            quantiles,
+            count,
+            sum,
+        } => Data::Summary(Summary {
+            data_points: vec![SummaryDataPoint {
+                attributes,
+                time_unix_nano,
+                count: u64::from(count),
+                sum,
+                quantile_values: quantiles
+                    .iter()
+                    .map(|quantile| ValueAtQuantile {
+                        quantile: quantile.quantile,
+                        value: quantile.value,
+                    })
+                    .collect(),
+                ..Default::default()
+            }],
+        }),
+        MetricValue::Distribution { .. } | MetricValue::Sketch { .. } => return None,
+    };
+    Some(data)
+}
+
+/// OTLP histograms always end with an overflow bucket, without an upper limit. When the buckets
+/// of the metric don't end with an infinite upper limit, the overflow bucket holds the values not
+/// counted by any other bucket.
+fn histogram_buckets(buckets: &[Bucket], count: u32) -> (Vec<u64>, Vec<f64>) {
+    let mut bucket_counts = buckets
+        .iter()
+        .map(|bucket| u64::from(bucket.count))
+        .collect::<Vec<_>>();
+    let mut explicit_bounds = buckets
+        .iter()
+        .map(|bucket| bucket.upper_limit)
+        .collect::<Vec<_>>();
+    if explicit_bounds.last() == Some(&f64::INFINITY) {
+        explicit_bounds.pop();
+    } else {
+        let counted = bucket_counts.iter().sum::<u64>();
+        bucket_counts.push(u64::from(count).saturating_sub(counted));
+    }
+    (bucket_counts, explicit_bounds)
+}
+
+/// Items grouped by the resource and instrumentation scope that produced them, in the order
+/// they were first seen.
+struct Groups<T>(Vec<(Option<Arc<OpenTelemetryScope>>, Vec<T>)>);
+
+impl<T> Default for Groups<T> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<T> Groups<T> {
+    fn push(&mut self, scope: Option<Arc<OpenTelemetryScope>>, item: T) {
+        match self
+            .0
+            .iter_mut()
+            .find(|(group_scope, _)| *group_scope == scope)
+        {
+            Some((_, items)) => items.push(item),
+            None => self.0.push((scope, vec![item])),
+        }
+    }
+}
+
+fn resource(scope: &Option<Arc<OpenTelemetryScope>>) -> Option<Resource> {
+    let mut resources = scope
+        .as_ref()?
+        .resource
+        .as_object()
+        .filter(|fields| !fields.is_empty())?
+        .clone();
+    Some(Resource {
+        attributes: attributes(&mut resources),
+        dropped_attributes_count: 0,
+    })
+}
+
+fn instrumentation_scope(scope: &Option<Arc<OpenTelemetryScope>>) -> Option<InstrumentationScope> {
+    let mut scope = scope
+        .as_ref()?
+        .scope
+        .as_object()
+        .filter(|fields| !fields.is_empty())?
+        .clone();
+    Some(InstrumentationScope {
+        name: string(scope.remove("name")),
+        version: string(scope.remove("version")),
+        attributes: scope
+            .remove("attributes")
+            .and_then(Value::into_object)
+            .map(|mut attributes| self::attributes(&mut attributes))
+            .unwrap_or_default(),
+        dropped_attributes_count: 0,
+    })
+}
+
+/// Turns the remaining fields into attributes. The fields of an `attributes` object are
+/// flattened into them.
+fn attributes(fields: &mut BTreeMap<String, Value>) -> Vec<KeyValue> {
+    let mut attributes = match fields.remove("attributes") {
+        Some(Value::Object(attributes)) => attributes,
+        Some(value) => {
+            fields.insert("attributes".to_owned(), value);
+            BTreeMap::new()
+        }
+        None => BTreeMap::new(),
+    };
+    for (key, value) in std::mem::take(fields) {
+        attributes.entry(key).or_insert(value);
+    }
+    attributes
+        .into_iter()
+        .map(|(key, value)| key_value(key, value))
+        .collect()
+}
+
+fn key_value(key: String, value: Value) -> KeyValue {
+    KeyValue {
+        key,
+        value: Some(any(value)),
+    }
+}
+
+fn any(value: Value) -> AnyValue {
+    let value = match value {
+        Value::Bytes(bytes) => Some(match String::from_utf8(bytes.to_vec()) {
+            Ok(string) => any_value::Value::StringValue(string),
+            Err(error) => any_value::Value::BytesValue(error.into_bytes()),
+        }),
+        Value::Regex(regex) => Some(any_value::Value::StringValue(
+            String::from_utf8_lossy(regex.as_bytes_slice()).into_owned(),
+        )),
+        Value::Integer(integer) => Some(any_value::Value::IntValue(integer)),
+        Value::Float(float) => Some(any_value::Value::DoubleValue(float.into_inner())),
+        Value::Boolean(boolean) => Some(any_value::Value::BoolValue(boolean)),
+        Value::Timestamp(timestamp) => Some(any_value::Value::StringValue(
+            timestamp.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true),
+        )),
+        Value::Object(fields) => Some(any_value::Value::KvlistValue(KeyValueList {
+            values: fields
+                .into_iter()
+                .map(|(key, value)| key_value(key, value))
+                .collect(),
+        })),
+        Value::Array(values) => Some(any_value::Value::ArrayValue(ArrayValue {
+            values: values.into_iter().map(any).collect(),
+        })),
+        Value::Null => None,
+    };
+    AnyValue { value }
+}
+
+fn string(value: Option<Value>) -> String {
+    value
+        .map(|value| value.to_string_lossy())
+        .unwrap_or_default()
+}
+
+/// Zero stands for an absent or invalid value in OTLP.
+fn integer<T: TryFrom<i64> + Default>(value: Option<Value>) -> T {
+    value
+        .and_then(|value| value.as_integer())
+        .and_then(|integer| T::try_from(integer).ok())
+        .unwrap_or_default()
+}
+
+/// Zero stands for an unknown time in OTLP.
+fn time_unix_nano(value: Option<&Value>) -> u64 {
+    value
+        .and_then(Value::as_timestamp)
+        .map(nanos)
+        .unwrap_or_default()
+}
+
+fn nanos(timestamp: &DateTime<Utc>) -> u64 {
+    u64::try_from(timestamp.timestamp_nanos()).unwrap_or_default()
+}
+
+/// Trace and span IDs are read from their hexadecimal representation, and left empty if they
+/// aren't valid.
+fn id(value: Option<Value>) -> Vec<u8> {
+    let hex = match value.as_ref().and_then(Value::as_bytes) {
+        Some(hex) if hex.len() % 2 == 0 => hex,
+        _ => return Vec::new(),
+    };
+    hex.chunks(2)
+        .map(|digits| {
+            std::str::from_utf8(digits)
+                .ok()
+                .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+        })
+        .collect::<Option<Vec<_>>>()
+        .unwrap_or_default()
+}
+
+fn objects(value: Option<Value>) -> impl Iterator<Item = BTreeMap<String, Value>> {
+    let values = match value {
+        Some(Value::Array(values)) => values,
+        _ => Vec::new(),
+    };
+    values.into_iter().filter_map(Value::into_object)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::event::metric::Quantile;
+
+    #[test]
+    fn encodes_logs() {
+        let mut log = LogEvent::from("Order placed.");
+        log.insert(
+            log_schema().timestamp_key(),
+            Utc.timestamp_nanos(1_654_000_000_123_000_000),
+        );
+        log.insert(log_schema().source_type_key(), "file");
+        log.insert("severity_text", "INFO");
+        log.insert("severity_number", 9);
+        log.insert("trace_id", "4b".repeat(16));
+        log.insert("span_id", "not an id");
+        log.insert("attributes.\"order.id\"", 42);
+        log.insert("host", "web-1");
+        let scope = OpenTelemetryScope {
+            resource: Value::from(BTreeMap::from([(
+                "service.name".to_owned(),
+                Value::from("checkout"),
+            )])),
+            scope: Value::from(BTreeMap::from([(
+                "name".to_owned(),
+                Value::from("io.opentelemetry.http"),
+            )])),
+        };
+        log.metadata_mut()
+            .set_opentelemetry_scope(Some(Arc::new(scope.clone())));
+        let mut other = LogEvent::from("Order shipped.");
+        // Equal scopes are grouped, even if they aren't shared.
+        other
+            .metadata_mut()
+            .set_opentelemetry_scope(Some(Arc::new(scope)));
+
+        let request = logs(vec![log.into(), other.into()]);
+        assert_eq!(request.resource_logs.len(), 1);
+        let resource_logs = &request.resource_logs[0];
+        assert_eq!(
+            resource_logs.resource.as_ref().unwrap().attributes,
+            vec![key_value("service.name".to_owned(), "checkout".into())]
+        );
+        let scope_logs = &resource_logs.scope_logs[0];
+        assert_eq!(
+            scope_logs.scope.as_ref().unwrap().name,
+            "io.opentelemetry.http"
+        );
+        assert_eq!(scope_logs.log_records.len(), 2);
+
+        let record = &scope_logs.log_records[0];
+        assert_eq!(record.body, Some(any("Order placed.".into())));
+        assert_eq!(record.time_unix_nano, 1_654_000_000_123_000_000);
+        assert_eq!(record.severity_text, "INFO");
+        assert_eq!(record.severity_number, 9);
+        assert_eq!(record.trace_id, vec![0x4b; 16]);
+        assert!(record.span_id.is_empty());
+        assert_eq!(
+            record.attributes,
+            vec![
+                key_value("host".to_owned(), "web-1".into()),
+                key_value("order.id".to_owned(), 42.into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn encodes_traces() {
+        let mut trace = TraceEvent::from(BTreeMap::new());
+        trace.insert("trace_id", "01".repeat(16));
+        trace.insert("span_id", "00f067aa0ba902b7");
+        trace.insert("name", "GET /orders");
+        trace.insert("kind", "server");
+        trace.insert("start_timestamp", Utc.timestamp(1_654_000_000, 0));
+        trace.insert("status.code", "error");
+        trace.insert("status.message", "timed out");
+        trace.insert("attributes.\"http.method\"", "GET");
+        trace.insert(
+            "events",
+            Value::Array(vec![Value::Object(
+                vec![("name".to_owned(), "retry".into())]
+                    .into_iter()
+                    .collect(),
+            )]),
+        );
+
+        let request = traces(vec![trace.into()]);
+        let resource_spans = &request.resource_spans[0];
+        assert!(resource_spans.resource.is_none());
+        let span = &resource_spans.scope_spans[0].spans[0];
+        assert_eq!(span.trace_id, vec![1; 16]);
+        assert_eq!(
+            span.span_id,
+            vec![0, 0xf0, 0x67, 0xaa, 0x0b, 0xa9, 0x02, 0xb7]
+        );
+        assert_eq!(span.name, "GET /orders");
+        assert_eq!(span.kind, span::SpanKind::Server as i32);
+        assert_eq!(span.start_time_unix_nano, 1_654_000_000_000_000_000);
+        assert_eq!(span.end_time_unix_nano, 0);
+        assert_eq!(
+            span.status,
+            Some(Status {
+                message: "timed out".to_owned(),
+                code: status::StatusCode::Error as i32,
+            })
+        );
+        assert_eq!(
+            span.attributes,
+            vec![key_value("http.method".to_owned(), "GET".into())]
+        );
+        assert_eq!(span.events.len(), 1);
+        assert_eq!(span.events[0].name, "retry");
+    }
+
+    fn tags() -> MetricTags {
+        vec![
+            ("host".to_owned(), "a".to_owned()),
+            ("resource.service.name".to_owned(), "checkout".to_owned()),
+            ("scope.name".to_owned(), "io.opentelemetry.http".to_owned()),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn encodes_metrics() {
+        let timestamp = Utc.timestamp(1_654_000_000, 0);
+        let metric = |name: &str, kind: MetricKind, value: MetricValue| {
+            Event::from(
+                Metric::new(name, kind, value)
+                    .with_tags(Some(tags()))
+                    .with_timestamp(Some(timestamp)),
+            )
+        };
+
+        let request = metrics(vec![
+            metric(
+                "requests",
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 3.0 },
+            ),
+            metric(
+                "temperature",
+                MetricKind::Absolute,
+                MetricValue::Gauge { value: 21.5 },
+            ),
+            metric(
+                "latency",
+                MetricKind::Absolute,
+                MetricValue::AggregatedSummary {
+                    quantiles: vec![Quantile {
+                        quantile: 0.5,
+                        value: 0.2,
+                    }],
+                    count: 6,
+                    sum: 2.5,
+                },
+            ),
+        ]);
+        assert_eq!(request.resource_metrics.len(), 1);
+        let resource_metrics = &request.resource_metrics[0];
+        assert_eq!(
+            resource_metrics.resource.as_ref().unwrap().attributes,
+            vec![key_value("service.name".to_owned(), "checkout".into())]
+        );
+        let scope_metrics = &resource_metrics.scope_metrics[0];
+        assert_eq!(
+            scope_metrics.scope.as_ref().unwrap().name,
+            "io.opentelemetry.http"
+        );
+        let metrics = &scope_metrics.metrics;
+        assert_eq!(metrics.len(), 3);
+
+        let attributes = vec![key_value("host".to_owned(), "a".into())];
+        assert_eq!(metrics[0].name, "requests");
+        assert_eq!(
+            metrics[0].data,
+            Some(Data::Sum(Sum {
+                data_points: vec![NumberDataPoint {
+                    attributes: attributes.clone(),
+                    time_unix_nano: 1_654_000_000_000_000_000,
+                    value: Some(number_data_point::Value::AsDouble(3.0)),
+                    ..Default::default()
+                }],
+                aggregation_temporality: AggregationTemporality::Delta as i32,
+                is_monotonic: true,
+            }))
+        );
+        assert!(matches!(metrics[1].data, Some(Data::Gauge(_))));
+        match &metrics[2].data {
+            Some(Data::Summary(summary)) => {
+                let data_point = &summary.data_points[0];
+                assert_eq!(data_point.attributes, attributes);
+                assert_eq!(data_point.count, 6);
+                assert_eq!(data_point.quantile_values[0].value, 0.2);
+            }
+            data => panic!("unexpected data: {:?}", data),
+        }
+    }
+
+    #[test]
+    fn encodes_histogram_buckets() {
+        let bucket = |upper_limit, count| Bucket { upper_limit, count };
+
+        assert_eq!(
+            histogram_buckets(&[bucket(0.1, 1), bucket(1.0, 2)], 6),
+            (vec![1, 2, 3], vec![0.1, 1.0])
+        );
+        assert_eq!(
+            histogram_buckets(&[bucket(0.1, 1), bucket(f64::INFINITY, 5)], 6),
+            (vec![1, 5], vec![0.1])
+        );
+    }
+
+    #[test]
+    fn metric_support() {
+        let metric = |value| Metric::new("metric", MetricKind::Absolute, value);
+
+        assert!(is_supported(&metric(MetricValue::Counter { value: 1.0 })));
+        assert!(!is_supported(&metric(MetricValue::Distribution {
+            samples: Vec::new(),
+            statistic: crate::event::metric::StatisticKind::Histogram,
+        })));
+    }
+}
@@ -0,0 +1,36 @@
+//! The OpenTelemetry [`VectorSink`](crate::sinks::VectorSink).
+//!
+//! This module contains the sink exporting logs, metrics and traces with the OpenTelemetry
+//! protocol (OTLP), over gRPC or HTTP, to any OTLP compatible backend such as the OpenTelemetry
+//! Collector.
+
+#[cfg(test)]
+mod tests;
+
+mod config;
+mod encode;
+mod service;
+mod sink;
+
+use http::StatusCode;
+use snafu::Snafu;
+
+pub use self::config::OpentelemetrySinkConfig;
+use crate::{config::SinkDescription, http::HttpError};
+
+inventory::submit! {
+    SinkDescription::new::<OpentelemetrySinkConfig>("opentelemetry")
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(super)))]
+pub enum OpentelemetrySinkError {
+    #[snafu(display("gRPC request failed: {}", source))]
+    Grpc { source: tonic::Status },
+
+    #[snafu(display("HTTP request failed: {}", source))]
+    Http { source: HttpError },
+
+    #[snafu(display("HTTP request failed with status {}", status))]
+    HttpStatus { status: StatusCode },
+}
@@ -0,0 +1,280 @@
+use std::{
+    io::Write,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use http::{
+    header::{CONTENT_ENCODING, CONTENT_TYPE},
+    uri::Scheme,
+    Request, Uri,
+};
+use hyper::Body;
+use prost::Message;
+use snafu::ResultExt;
+use tonic::body::BoxBody;
+use tower::Service;
+use vector_common::internal_event::{BytesSent, EventsSent};
+use vector_core::{buffers::Ackable, stream::DriverResponse};
+
+use super::{GrpcSnafu, HttpSnafu, OpentelemetrySinkError};
+use crate::{
+    event::{EventFinalizers, EventStatus, Finalizable},
+    http::{Auth, BuildRequestSnafu, HttpClient},
+    proto::opentelemetry::{
+        proto::collector::{
+            logs::v1::logs_service_client::LogsServiceClient,
+            metrics::v1::metrics_service_client::MetricsServiceClient,
+            trace::v1::trace_service_client::TraceServiceClient,
+        },
+        ExportLogsServiceRequest, ExportMetricsServiceRequest, ExportTraceServiceRequest,
+    },
+    sinks::util::{grpc::GrpcChannel, Compression, Compressor},
+};
+
+const PROTOBUF_CONTENT_TYPE: &str = "application/x-protobuf";
+
+#[derive(Clone, Debug)]
+pub enum ExportRequest {
+    Logs(ExportLogsServiceRequest),
+    Metrics(ExportMetricsServiceRequest),
+    Traces(ExportTraceServiceRequest),
+}
+
+impl ExportRequest {
+    fn encoded_len(&self) -> usize {
+        match self {
+            Self::Logs(request) => request.encoded_len(),
+            Self::Metrics(request) => request.encoded_len(),
+            Self::Traces(request) => request.encoded_len(),
+        }
+    }
+
+    fn encode_to_vec(&self) -> Vec<u8> {
+        match self {
+            Self::Logs(request) => request.encode_to_vec(),
+            Self::Metrics(request) => request.encode_to_vec(),
+            Self::Traces(request) => request.encode_to_vec(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct OpentelemetryRequest {
+    pub export: ExportRequest,
+    pub finalizers: EventFinalizers,
+    pub events_count: usize,
+    pub events_byte_size: usize,
+}
+
+impl Ackable for OpentelemetryRequest {
+    fn ack_size(&self) -> usize {
+        self.events_count
+    }
+}
+
+impl Finalizable for OpentelemetryRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        std::mem::take(&mut self.finalizers)
+    }
+}
+
+#[derive(Debug)]
+pub struct OpentelemetryResponse {
+    events_count: usize,
+    events_byte_size: usize,
+    byte_size: usize,
+    protocol: &'static str,
+}
+
+impl DriverResponse for OpentelemetryResponse {
+    fn event_status(&self) -> EventStatus {
+        EventStatus::Delivered
+    }
+
+    fn events_sent(&self) -> EventsSent {
+        EventsSent {
+            count: self.events_count,
+            byte_size: self.events_byte_size,
+            output: None,
+        }
+    }
+
+    fn bytes_sent(&self) -> Option<BytesSent> {
+        Some(BytesSent {
+            byte_size: self.byte_size,
+            protocol: self.protocol,
+        })
+    }
+}
+
+fn protocol(uri: &Uri) -> &'static str {
+    if uri.scheme() == Some(&Scheme::HTTPS) {
+        "https"
+    } else {
+        "http"
+    }
+}
+
+/// Exports requests with the OTLP/gRPC services.
+#[derive(Clone, Debug)]
+pub struct GrpcService {
+    logs: LogsServiceClient<GrpcChannel>,
+    metrics: MetricsServiceClient<GrpcChannel>,
+    traces: TraceServiceClient<GrpcChannel>,
+    protocol: &'static str,
+}
+
+impl GrpcService {
+    pub fn new(
+        client: HttpClient<BoxBody>,
+        uri: &Uri,
+        auth: Option<Auth>,
+        compression: bool,
+    ) -> crate::Result<Self> {
+        let channel = GrpcChannel::new(client, uri, auth)?;
+        let protocol = channel.protocol();
+
+        let mut logs = LogsServiceClient::new(channel.clone());
+        let mut metrics = MetricsServiceClient::new(channel.clone());
+        let mut traces = TraceServiceClient::new(channel);
+        if compression {
+            logs = logs.send_gzip();
+            metrics = metrics.send_gzip();
+            traces = traces.send_gzip();
+        }
+
+        Ok(Self {
+            logs,
+            metrics,
+            traces,
+            protocol,
+        })
+    }
+}
+
+impl Service<OpentelemetryRequest> for GrpcService {
+    type Response = OpentelemetryResponse;
+    type Error = OpentelemetrySinkError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // Readiness of the clients is checked when they send the request in `call()`.
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: OpentelemetryRequest) -> Self::Future {
+        let mut service = self.clone();
+        let byte_size = request.export.encoded_len();
+
+        Box::pin(async move {
+            match request.export {
+                ExportRequest::Logs(export) => service.logs.export(export).await.map(drop),
+                ExportRequest::Metrics(export) => service.metrics.export(export).await.map(drop),
+                ExportRequest::Traces(export) => service.traces.export(export).await.map(drop),
+            }
+            .context(GrpcSnafu)?;
+
+            Ok(OpentelemetryResponse {
+                events_count: request.events_count,
+                events_byte_size: request.events_byte_size,
+                byte_size,
+                protocol: service.protocol,
+            })
+        })
+    }
+}
+
+/// Exports requests with protobuf encoded OTLP/HTTP requests.
+#[derive(Clone, Debug)]
+pub struct HttpService {
+    client: HttpClient,
+    logs_uri: Uri,
+    metrics_uri: Uri,
+    traces_uri: Uri,
+    auth: Option<Auth>,
+    compression: bool,
+}
+
+impl HttpService {
+    pub const fn new(
+        client: HttpClient,
+        logs_uri: Uri,
+        metrics_uri: Uri,
+        traces_uri: Uri,
+        auth: Option<Auth>,
+        compression: bool,
+    ) -> Self {
+        Self {
+            client,
+            logs_uri,
+            metrics_uri,
+            traces_uri,
+            auth,
+            compression,
+        }
+    }
+}
+
+impl Service<OpentelemetryRequest> for HttpService {
+    type Response = OpentelemetryResponse;
+    type Error = OpentelemetrySinkError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.client
+            .poll_ready(cx)
+            .map_err(|source| OpentelemetrySinkError::Http { source })
+    }
+
+    fn call(&mut self, request: OpentelemetryRequest) -> Self::Future {
+        let client = self.client.clone();
+        let uri = match request.export {
+            ExportRequest::Logs(_) => &self.logs_uri,
+            ExportRequest::Metrics(_) => &self.metrics_uri,
+            ExportRequest::Traces(_) => &self.traces_uri,
+        }
+        .clone();
+        let protocol = protocol(&uri);
+        let auth = self.auth.clone();
+        let compression = self.compression;
+
+        Box::pin(async move {
+            let body = request.export.encode_to_vec();
+            let byte_size = body.len();
+
+            let mut builder = Request::post(uri).header(CONTENT_TYPE, PROTOBUF_CONTENT_TYPE);
+            let body = if compression {
+                builder = builder.header(CONTENT_ENCODING, "gzip");
+                let mut compressor = Compressor::from(Compression::gzip_default());
+                compressor
+                    .write_all(&body)
+                    .expect("writing to the compressor buffer should not fail");
+                compressor.into_inner().freeze()
+            } else {
+                Bytes::from(body)
+            };
+            if let Some(auth) = auth {
+                builder = auth.apply_builder(builder);
+            }
+            let http_request = builder
+                .body(Body::from(body))
+                .context(BuildRequestSnafu)
+                .context(HttpSnafu)?;
+
+            let response = client.send(http_request).await.context(HttpSnafu)?;
+            let status = response.status();
+            if !status.is_success() {
+                return Err(OpentelemetrySinkError::HttpStatus { status });
+            }
+
+            Ok(OpentelemetryResponse {
+                events_count: request.events_count,
+                events_byte_size: request.events_byte_size,
+                byte_size,
+                protocol,
+            })
+        })
+    }
+}
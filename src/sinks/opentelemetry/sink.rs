@@ -0,0 +1,110 @@
+use std::fmt;
+
+use async_trait::async_trait;
+use futures::{future, stream::BoxStream, StreamExt};
+use tower::Service;
+use vector_core::{
+    buffers::Acker,
+    partition::Partitioner,
+    stream::{BatcherSettings, DriverResponse},
+    ByteSizeOf,
+};
+
+use super::{
+    encode,
+    service::{ExportRequest, OpentelemetryRequest},
+};
+use crate::{
+    event::{Event, EventStatus, Finalizable},
+    internal_events::OpentelemetryUnsupportedMetricError,
+    sinks::util::{SinkBuilderExt, StreamSink},
+};
+
+/// The OTLP signal an event belongs to, as each has its own export request.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum Signal {
+    Logs,
+    Metrics,
+    Traces,
+}
+
+struct SignalPartitioner;
+
+impl Partitioner for SignalPartitioner {
+    type Item = Event;
+    type Key = Signal;
+
+    fn partition(&self, item: &Self::Item) -> Self::Key {
+        match item {
+            Event::Log(_) => Signal::Logs,
+            Event::Metric(_) => Signal::Metrics,
+            Event::Trace(_) => Signal::Traces,
+        }
+    }
+}
+
+pub struct OpentelemetrySink<S> {
+    pub batch_settings: BatcherSettings,
+    pub service: S,
+    pub acker: Acker,
+}
+
+impl<S> OpentelemetrySink<S>
+where
+    S: Service<OpentelemetryRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: fmt::Debug + Into<crate::Error> + Send,
+{
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        input
+            .filter(|event| {
+                future::ready(match event {
+                    Event::Metric(metric) if !encode::is_supported(metric) => {
+                        metric.metadata().update_status(EventStatus::Rejected);
+                        emit!(OpentelemetryUnsupportedMetricError {
+                            value: metric.value()
+                        });
+                        false
+                    }
+                    _ => true,
+                })
+            })
+            .batched_partitioned(SignalPartitioner, self.batch_settings)
+            .map(|(signal, events)| build_request(signal, events))
+            .into_driver(self.service, self.acker)
+            .run()
+            .await
+    }
+}
+
+fn build_request(signal: Signal, mut events: Vec<Event>) -> OpentelemetryRequest {
+    let finalizers = events.take_finalizers();
+    let events_count = events.len();
+    let events_byte_size = events.size_of();
+    let export = match signal {
+        Signal::Logs => ExportRequest::Logs(encode::logs(events)),
+        Signal::Metrics => ExportRequest::Metrics(encode::metrics(events)),
+        Signal::Traces => ExportRequest::Traces(encode::traces(events)),
+    };
+
+    OpentelemetryRequest {
+        export,
+        finalizers,
+        events_count,
+        events_byte_size,
+    }
+}
+
+#[async_trait]
+impl<S> StreamSink<Event> for OpentelemetrySink<S>
+where
+    S: Service<OpentelemetryRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: fmt::Debug + Into<crate::Error> + Send,
+{
+    async fn run(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
@@ -0,0 +1,196 @@
+use std::io::Read;
+
+use bytes::{BufMut, Bytes, BytesMut};
+use flate2::read::GzDecoder;
+use futures::{future, stream, StreamExt};
+use prost::Message;
+use vector_core::event::{BatchNotifier, BatchStatus};
+
+use super::OpentelemetrySinkConfig;
+use crate::{
+    config::{SinkConfig, SinkContext},
+    event::{
+        metric::{Metric, MetricKind, MetricValue, StatisticKind},
+        Event,
+    },
+    proto::opentelemetry::{any_value, ExportLogsServiceRequest, ExportLogsServiceResponse},
+    sinks::util::test::build_test_server_generic,
+    test_util::{
+        components::{run_and_assert_sink_compliance, HTTP_SINK_TAGS},
+        next_addr, random_lines_with_stream,
+    },
+};
+
+// one byte for the compression flag plus four bytes for the length
+const GRPC_HEADER_SIZE: usize = 5;
+
+#[test]
+fn generate_config() {
+    crate::test_util::test_generate_config::<OpentelemetrySinkConfig>();
+}
+
+fn grpc_body(message: impl Message) -> Bytes {
+    let message = message.encode_to_vec();
+    let mut body = BytesMut::with_capacity(GRPC_HEADER_SIZE + message.len());
+    body.put_u8(0);
+    body.put_u32(message.len() as u32);
+    body.put_slice(&message);
+    body.freeze()
+}
+
+fn log_bodies(request: &ExportLogsServiceRequest) -> Vec<String> {
+    request
+        .resource_logs
+        .iter()
+        .flat_map(|resource_logs| &resource_logs.scope_logs)
+        .flat_map(|scope_logs| &scope_logs.log_records)
+        .map(
+            |record| match record.body.as_ref().and_then(|body| body.value.as_ref()) {
+                Some(any_value::Value::StringValue(line)) => line.clone(),
+                body => panic!("unexpected body: {:?}", body),
+            },
+        )
+        .collect()
+}
+
+#[tokio::test]
+async fn exports_logs_over_grpc() {
+    let address = next_addr();
+    let config = toml::from_str::<OpentelemetrySinkConfig>(&format!(
+        r#"
+            endpoint = "http://{}"
+            auth.strategy = "bearer"
+            auth.token = "secret"
+        "#,
+        address
+    ))
+    .unwrap();
+    let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+
+    let (rx, trigger, server) = build_test_server_generic(address, || {
+        hyper::Response::builder()
+            .header("grpc-status", "0") // OK
+            .header("content-type", "application/grpc")
+            .body(hyper::Body::from(grpc_body(
+                ExportLogsServiceResponse::default(),
+            )))
+            .unwrap()
+    });
+    tokio::spawn(server);
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let (input_lines, events) = random_lines_with_stream(8, 10, Some(batch));
+    run_and_assert_sink_compliance(sink, events, &HTTP_SINK_TAGS).await;
+    drop(trigger);
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    let requests = rx.collect::<Vec<_>>().await;
+    assert_eq!(requests.len(), 1);
+    let (parts, body) = &requests[0];
+    assert_eq!(
+        parts.uri.path(),
+        "/opentelemetry.proto.collector.logs.v1.LogsService/Export"
+    );
+    assert_eq!(parts.headers["content-type"], "application/grpc");
+    assert_eq!(parts.headers["authorization"], "Bearer secret");
+    let request = ExportLogsServiceRequest::decode(body.slice(GRPC_HEADER_SIZE..)).unwrap();
+    assert_eq!(log_bodies(&request), input_lines);
+}
+
+#[tokio::test]
+async fn grpc_rejects_on_permanent_errors() {
+    let address = next_addr();
+    let config =
+        toml::from_str::<OpentelemetrySinkConfig>(&format!(r#"endpoint = "http://{}""#, address))
+            .unwrap();
+    let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+
+    let (_rx, trigger, server) = build_test_server_generic(address, || {
+        hyper::Response::builder()
+            .header("grpc-status", "7") // permission denied
+            .header("content-type", "application/grpc")
+            .body(tonic::body::empty_body())
+            .unwrap()
+    });
+    tokio::spawn(server);
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let (_, events) = random_lines_with_stream(8, 10, Some(batch));
+    sink.run(events).await.expect("Running sink failed");
+    drop(trigger);
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Rejected));
+}
+
+#[tokio::test]
+async fn rejects_unsupported_metrics() {
+    let address = next_addr();
+    let config =
+        toml::from_str::<OpentelemetrySinkConfig>(&format!(r#"endpoint = "http://{}""#, address))
+            .unwrap();
+    let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+
+    let (rx, trigger, server) = build_test_server_generic(address, || {
+        hyper::Response::builder()
+            .header("grpc-status", "0") // OK
+            .header("content-type", "application/grpc")
+            .body(tonic::body::empty_body())
+            .unwrap()
+    });
+    tokio::spawn(server);
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let metric = Metric::new(
+        "latency",
+        MetricKind::Incremental,
+        MetricValue::Distribution {
+            samples: vector_core::samples![1.0 => 1],
+            statistic: StatisticKind::Histogram,
+        },
+    )
+    .with_batch_notifier(&batch);
+    drop(batch);
+    sink.run(stream::once(future::ready(Event::from(metric))).boxed())
+        .await
+        .expect("Running sink failed");
+    drop(trigger);
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Rejected));
+    assert!(rx.collect::<Vec<_>>().await.is_empty());
+}
+
+#[tokio::test]
+async fn exports_compressed_logs_over_http() {
+    let address = next_addr();
+    let config = toml::from_str::<OpentelemetrySinkConfig>(&format!(
+        r#"
+            endpoint = "http://{}/otlp/"
+            protocol = "http"
+            compression = true
+        "#,
+        address
+    ))
+    .unwrap();
+    let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+
+    let (rx, trigger, server) =
+        build_test_server_generic(address, || hyper::Response::new(hyper::Body::empty()));
+    tokio::spawn(server);
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let (input_lines, events) = random_lines_with_stream(8, 10, Some(batch));
+    run_and_assert_sink_compliance(sink, events, &HTTP_SINK_TAGS).await;
+    drop(trigger);
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    let requests = rx.collect::<Vec<_>>().await;
+    assert_eq!(requests.len(), 1);
+    let (parts, body) = &requests[0];
+    assert_eq!(parts.uri.path(), "/otlp/v1/logs");
+    assert_eq!(parts.headers["content-type"], "application/x-protobuf");
+    assert_eq!(parts.headers["content-encoding"], "gzip");
+    let mut decompressed = Vec::new();
+    GzDecoder::new(&body[..])
+        .read_to_end(&mut decompressed)
+        .unwrap();
+    let request = ExportLogsServiceRequest::decode(&decompressed[..]).unwrap();
+    assert_eq!(log_bodies(&request), input_lines);
+}
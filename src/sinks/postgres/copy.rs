@@ -0,0 +1,316 @@
+use std::collections::HashSet;
+
+use bytes::{BufMut, BytesMut};
+use serde::{Deserialize, Serialize};
+
+use super::PostgresSinkError;
+use crate::event::{LogEvent, Value};
+
+/// The temporary table batches are copied into before being upserted.
+const STAGING_TABLE: &str = "vector_staging";
+
+/// A column of the table, and the field of the events its values are read from.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct PostgresColumn {
+    /// The name of the column.
+    pub name: String,
+    /// The path of the field holding the values of the column, which defaults to its name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+}
+
+impl PostgresColumn {
+    fn path(&self) -> &str {
+        self.field.as_deref().unwrap_or(&self.name)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct UpsertConfig {
+    /// The columns of the unique index or constraint the rows conflict on.
+    pub conflict_columns: Vec<String>,
+    #[serde(default)]
+    pub on_conflict: ConflictAction,
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, PartialEq, Eq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictAction {
+    /// The conflicting rows are updated with the values of the new ones.
+    #[derivative(Default)]
+    Update,
+    /// The conflicting rows are kept as is, and the new ones discarded.
+    Nothing,
+}
+
+/// The statements writing a batch of rows.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CopyStatements {
+    /// Selects no rows from the columns of the table, to check that they can be written.
+    pub check: String,
+    /// Copies the rows into the table, or into the staging table when upserting.
+    pub copy: String,
+    /// Creates the staging table, and then inserts its rows into the table, when upserting.
+    pub upsert: Option<(String, String)>,
+}
+
+/// Serializes events as rows in the text format of `COPY`.
+#[derive(Debug)]
+pub struct CopyEncoder {
+    columns: Vec<PostgresColumn>,
+}
+
+impl CopyEncoder {
+    pub fn new(columns: Vec<PostgresColumn>) -> Result<Self, PostgresSinkError> {
+        if columns.is_empty() {
+            return Err(PostgresSinkError::NoColumns);
+        }
+        let mut names = HashSet::new();
+        if let Some(column) = columns.iter().find(|column| !names.insert(&column.name)) {
+            return Err(PostgresSinkError::DuplicateColumn {
+                name: column.name.clone(),
+            });
+        }
+        Ok(Self { columns })
+    }
+
+    pub fn statements(
+        &self,
+        schema: Option<&str>,
+        table: &str,
+        upsert: Option<&UpsertConfig>,
+    ) -> Result<CopyStatements, PostgresSinkError> {
+        let table = match schema {
+            Some(schema) => format!("{}.{}", quote_identifier(schema), quote_identifier(table)),
+            None => quote_identifier(table),
+        };
+        let columns = self
+            .columns
+            .iter()
+            .map(|column| quote_identifier(&column.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let check = format!("SELECT {} FROM {} LIMIT 0", columns, table);
+
+        let upsert = match upsert {
+            Some(upsert) => upsert,
+            None => {
+                return Ok(CopyStatements {
+                    check,
+                    copy: format!("COPY {} ({}) FROM STDIN", table, columns),
+                    upsert: None,
+                })
+            }
+        };
+
+        if upsert.conflict_columns.is_empty() {
+            return Err(PostgresSinkError::NoConflictColumns);
+        }
+        if let Some(name) = upsert
+            .conflict_columns
+            .iter()
+            .find(|name| !self.columns.iter().any(|column| &column.name == *name))
+        {
+            return Err(PostgresSinkError::UnknownConflictColumn { name: name.clone() });
+        }
+        let conflict_columns = upsert
+            .conflict_columns
+            .iter()
+            .map(|name| quote_identifier(name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let updates = self
+            .columns
+            .iter()
+            .filter(|column| !upsert.conflict_columns.contains(&column.name))
+            .map(|column| {
+                let name = quote_identifier(&column.name);
+                format!("{} = EXCLUDED.{}", name, name)
+            })
+            .collect::<Vec<_>>();
+        let action = if upsert.on_conflict == ConflictAction::Nothing || updates.is_empty() {
+            "NOTHING".to_owned()
+        } else {
+            format!("UPDATE SET {}", updates.join(", "))
+        };
+
+        let create = format!(
+            "CREATE TEMPORARY TABLE {} (LIKE {} INCLUDING DEFAULTS) ON COMMIT DROP",
+            STAGING_TABLE, table
+        );
+        // A row can only be upserted once by a statement, so only the last of the rows of the
+        // batch sharing a key is kept.
+        let insert = format!(
+            "INSERT INTO {table} ({columns}) \
+             SELECT DISTINCT ON ({conflict}) {columns} FROM {staging} \
+             ORDER BY {conflict}, ctid DESC \
+             ON CONFLICT ({conflict}) DO {action}",
+            table = table,
+            columns = columns,
+            conflict = conflict_columns,
+            staging = STAGING_TABLE,
+            action = action,
+        );
+
+        Ok(CopyStatements {
+            check,
+            copy: format!("COPY {} ({}) FROM STDIN", STAGING_TABLE, columns),
+            upsert: Some((create, insert)),
+        })
+    }
+
+    /// Serializes the event as a row, leaving the columns whose field is missing null.
+    pub fn encode(&self, log: &LogEvent, buffer: &mut BytesMut) {
+        for (index, column) in self.columns.iter().enumerate() {
+            if index > 0 {
+                buffer.put_u8(b'\t');
+            }
+            match log.get(column.path()) {
+                Some(Value::Null) | None => buffer.put_slice(b"\\N"),
+                Some(value) => escape(&value.to_string_lossy(), buffer),
+            }
+        }
+        buffer.put_u8(b'\n');
+    }
+}
+
+fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+fn escape(text: &str, buffer: &mut BytesMut) {
+    for byte in text.bytes() {
+        match byte {
+            b'\\' => buffer.put_slice(b"\\\\"),
+            b'\t' => buffer.put_slice(b"\\t"),
+            b'\n' => buffer.put_slice(b"\\n"),
+            b'\r' => buffer.put_slice(b"\\r"),
+            byte => buffer.put_u8(byte),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+
+    fn encoder() -> CopyEncoder {
+        CopyEncoder::new(vec![
+            PostgresColumn {
+                name: "id".into(),
+                field: Some("request.id".into()),
+            },
+            PostgresColumn {
+                name: "message".into(),
+                field: None,
+            },
+            PostgresColumn {
+                name: "timestamp".into(),
+                field: None,
+            },
+            PostgresColumn {
+                name: "tags".into(),
+                field: None,
+            },
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn encodes_rows() {
+        let mut log = LogEvent::from("tab\there\nback\\slash");
+        log.insert("request.id", 42);
+        log.insert("timestamp", Utc.ymd(2022, 6, 1).and_hms(12, 0, 0));
+        log.insert("tags", vec!["a", "b"]);
+
+        let mut buffer = BytesMut::new();
+        encoder().encode(&log, &mut buffer);
+        encoder().encode(&LogEvent::default(), &mut buffer);
+        assert_eq!(
+            buffer,
+            "42\ttab\\there\\nback\\\\slash\t2022-06-01T12:00:00Z\t[\"a\",\"b\"]\n\
+             \\N\t\\N\t\\N\t\\N\n"
+        );
+    }
+
+    #[test]
+    fn copies_into_the_table() {
+        let statements = encoder()
+            .statements(Some("public"), "lo\"gs", None)
+            .unwrap();
+        assert_eq!(
+            statements,
+            CopyStatements {
+                check:
+                    r#"SELECT "id", "message", "timestamp", "tags" FROM "public"."lo""gs" LIMIT 0"#
+                        .into(),
+                copy: r#"COPY "public"."lo""gs" ("id", "message", "timestamp", "tags") FROM STDIN"#
+                    .into(),
+                upsert: None,
+            }
+        );
+    }
+
+    #[test]
+    fn upserts_through_a_staging_table() {
+        let upsert = UpsertConfig {
+            conflict_columns: vec!["id".into()],
+            on_conflict: ConflictAction::Update,
+        };
+        let statements = encoder().statements(None, "logs", Some(&upsert)).unwrap();
+        let (create, insert) = statements.upsert.unwrap();
+        assert_eq!(
+            statements.copy,
+            r#"COPY vector_staging ("id", "message", "timestamp", "tags") FROM STDIN"#
+        );
+        assert_eq!(
+            create,
+            r#"CREATE TEMPORARY TABLE vector_staging (LIKE "logs" INCLUDING DEFAULTS) ON COMMIT DROP"#
+        );
+        assert_eq!(
+            insert,
+            r#"INSERT INTO "logs" ("id", "message", "timestamp", "tags") SELECT DISTINCT ON ("id") "id", "message", "timestamp", "tags" FROM vector_staging ORDER BY "id", ctid DESC ON CONFLICT ("id") DO UPDATE SET "message" = EXCLUDED."message", "timestamp" = EXCLUDED."timestamp", "tags" = EXCLUDED."tags""#
+        );
+
+        let upsert = UpsertConfig {
+            on_conflict: ConflictAction::Nothing,
+            ..upsert
+        };
+        let (_, insert) = encoder()
+            .statements(None, "logs", Some(&upsert))
+            .unwrap()
+            .upsert
+            .unwrap();
+        assert!(insert.ends_with(r#"ON CONFLICT ("id") DO NOTHING"#));
+    }
+
+    #[test]
+    fn rejects_invalid_columns() {
+        assert!(matches!(
+            CopyEncoder::new(Vec::new()),
+            Err(PostgresSinkError::NoColumns)
+        ));
+        let column = PostgresColumn {
+            name: "message".into(),
+            field: None,
+        };
+        assert!(matches!(
+            CopyEncoder::new(vec![column.clone(), column]),
+            Err(PostgresSinkError::DuplicateColumn { .. })
+        ));
+
+        let upsert = UpsertConfig {
+            conflict_columns: vec!["host".into()],
+            on_conflict: ConflictAction::Update,
+        };
+        assert!(matches!(
+            encoder().statements(None, "logs", Some(&upsert)),
+            Err(PostgresSinkError::UnknownConflictColumn { .. })
+        ));
+    }
+}
@@ -0,0 +1,220 @@
+use std::{
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use futures::{future::BoxFuture, pin_mut, SinkExt};
+use postgres_openssl::MakeTlsConnector;
+use snafu::ResultExt;
+use tokio_postgres::{config::Host, Client, Config, Error as PgError, NoTls};
+use vector_core::{buffers::Ackable, internal_event::EventsSent, stream::DriverResponse};
+
+use super::{copy::CopyStatements, ConnectSnafu, PostgresSinkError, WriteSnafu};
+use crate::{
+    event::{EventFinalizers, EventStatus, Finalizable},
+    internal_events::EndpointBytesSent,
+    sinks::util::retries::RetryLogic,
+};
+
+#[derive(Clone)]
+pub struct PostgresRequest {
+    /// The rows, in the text format of `COPY`.
+    pub rows: Bytes,
+    pub events_count: usize,
+    pub finalizers: EventFinalizers,
+    pub events_byte_size: usize,
+}
+
+impl Ackable for PostgresRequest {
+    fn ack_size(&self) -> usize {
+        self.events_count
+    }
+}
+
+impl Finalizable for PostgresRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        self.finalizers.take_finalizers()
+    }
+}
+
+pub struct PostgresResponse {
+    events_count: usize,
+    events_byte_size: usize,
+}
+
+impl DriverResponse for PostgresResponse {
+    fn event_status(&self) -> EventStatus {
+        EventStatus::Delivered
+    }
+
+    fn events_sent(&self) -> EventsSent {
+        EventsSent {
+            count: self.events_count,
+            byte_size: self.events_byte_size,
+            output: None,
+        }
+    }
+}
+
+/// Connects to the server, with TLS when it's configured.
+#[derive(Clone)]
+pub struct PostgresConnector {
+    config: Config,
+    tls: Option<MakeTlsConnector>,
+    /// The host and port of the server, without the credentials of the connection string.
+    endpoint: String,
+}
+
+impl PostgresConnector {
+    pub fn new(config: Config, tls: Option<MakeTlsConnector>) -> Self {
+        let host = match config.get_hosts().first() {
+            Some(Host::Tcp(host)) => host.clone(),
+            #[cfg(unix)]
+            Some(Host::Unix(path)) => path.to_string_lossy().into_owned(),
+            None => "localhost".to_owned(),
+        };
+        let port = config.get_ports().first().copied().unwrap_or(5432);
+        Self {
+            config,
+            tls,
+            endpoint: format!("{}:{}", host, port),
+        }
+    }
+
+    pub async fn connect(&self) -> Result<Client, PgError> {
+        let client = match &self.tls {
+            Some(tls) => {
+                let (client, connection) = self.config.connect(tls.clone()).await?;
+                tokio::spawn(connection);
+                client
+            }
+            None => {
+                let (client, connection) = self.config.connect(NoTls).await?;
+                tokio::spawn(connection);
+                client
+            }
+        };
+        Ok(client)
+    }
+}
+
+/// Writes the rows, over connections reused across requests.
+#[derive(Clone)]
+pub struct PostgresService {
+    connector: PostgresConnector,
+    statements: Arc<CopyStatements>,
+    idle: Arc<Mutex<Vec<Client>>>,
+}
+
+impl PostgresService {
+    pub fn new(connector: PostgresConnector, statements: CopyStatements) -> Self {
+        Self {
+            connector,
+            statements: Arc::new(statements),
+            idle: Arc::default(),
+        }
+    }
+
+    async fn take_client(&self) -> Result<Client, PostgresSinkError> {
+        let idle = {
+            let mut idle = self.idle.lock().expect("idle clients lock poisoned");
+            idle.retain(|client| !client.is_closed());
+            idle.pop()
+        };
+        match idle {
+            Some(client) => Ok(client),
+            None => self.connector.connect().await.context(ConnectSnafu),
+        }
+    }
+
+    fn release_client(&self, client: Client) {
+        if !client.is_closed() {
+            self.idle
+                .lock()
+                .expect("idle clients lock poisoned")
+                .push(client);
+        }
+    }
+
+    async fn write(&self, client: &mut Client, rows: Bytes) -> Result<(), PgError> {
+        let statements = &*self.statements;
+        match &statements.upsert {
+            None => {
+                let sink = client.copy_in(statements.copy.as_str()).await?;
+                pin_mut!(sink);
+                sink.as_mut().send(rows).await?;
+                sink.finish().await?;
+            }
+            // The staging table is dropped when the transaction ends, whether it's committed or
+            // rolled back.
+            Some((create, insert)) => {
+                let transaction = client.transaction().await?;
+                transaction.batch_execute(create).await?;
+                {
+                    let sink = transaction.copy_in(statements.copy.as_str()).await?;
+                    pin_mut!(sink);
+                    sink.as_mut().send(rows).await?;
+                    sink.finish().await?;
+                }
+                transaction.batch_execute(insert).await?;
+                transaction.commit().await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl tower::Service<PostgresRequest> for PostgresService {
+    type Response = PostgresResponse;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: PostgresRequest) -> Self::Future {
+        let service = self.clone();
+
+        Box::pin(async move {
+            let byte_size = request.rows.len();
+            let mut client = service.take_client().await?;
+            let result = service.write(&mut client, request.rows).await;
+            service.release_client(client);
+            result.context(WriteSnafu)?;
+
+            emit!(EndpointBytesSent {
+                byte_size,
+                protocol: "postgres",
+                endpoint: &service.connector.endpoint,
+            });
+            Ok(PostgresResponse {
+                events_count: request.events_count,
+                events_byte_size: request.events_byte_size,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PostgresRetryLogic;
+
+impl RetryLogic for PostgresRetryLogic {
+    type Error = PostgresSinkError;
+    type Response = PostgresResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        match error {
+            PostgresSinkError::Connect { .. } => true,
+            // Errors without a code didn't come from the server, such as I/O errors, and the
+            // classes retried are connection exceptions, transaction rollbacks such as deadlocks,
+            // insufficient resources, operator interventions and system errors. Other errors,
+            // such as invalid values or constraint violations, would fail again.
+            PostgresSinkError::Write { source } => source.code().map_or(true, |code| {
+                matches!(&code.code()[..2], "08" | "40" | "53" | "57" | "58")
+            }),
+            _ => false,
+        }
+    }
+}
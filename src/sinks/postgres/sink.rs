@@ -0,0 +1,69 @@
+use std::fmt;
+
+use async_trait::async_trait;
+use bytes::BytesMut;
+use futures::{stream::BoxStream, StreamExt};
+use tower::Service;
+use vector_core::{
+    buffers::Acker,
+    stream::{BatcherSettings, DriverResponse},
+    ByteSizeOf,
+};
+
+use super::{copy::CopyEncoder, service::PostgresRequest};
+use crate::{
+    event::{Event, Finalizable},
+    sinks::util::{SinkBuilderExt, StreamSink},
+};
+
+pub struct PostgresSink<S> {
+    pub service: S,
+    pub batch_settings: BatcherSettings,
+    pub acker: Acker,
+    pub encoder: CopyEncoder,
+}
+
+impl<S> PostgresSink<S>
+where
+    S: Service<PostgresRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: fmt::Debug + Into<crate::Error> + Send,
+{
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let encoder = self.encoder;
+
+        input
+            .batched(self.batch_settings.into_byte_size_config())
+            .map(move |mut events| {
+                let finalizers = events.take_finalizers();
+                let events_byte_size = events.size_of();
+                let mut rows = BytesMut::new();
+                for event in &events {
+                    encoder.encode(event.as_log(), &mut rows);
+                }
+                PostgresRequest {
+                    rows: rows.freeze(),
+                    events_count: events.len(),
+                    finalizers,
+                    events_byte_size,
+                }
+            })
+            .into_driver(self.service, self.acker)
+            .run()
+            .await
+    }
+}
+
+#[async_trait]
+impl<S> StreamSink<Event> for PostgresSink<S>
+where
+    S: Service<PostgresRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: fmt::Debug + Into<crate::Error> + Send,
+{
+    async fn run(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
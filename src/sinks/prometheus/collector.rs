@@ -1,8 +1,11 @@
-use std::{collections::BTreeMap, fmt::Write as _};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write as _,
+};
 
 use chrono::Utc;
 use indexmap::map::IndexMap;
-use prometheus_parser::{proto, METRIC_NAME_LABEL};
+use prometheus_parser::{proto, EXEMPLAR_SUFFIX, METRIC_NAME_LABEL};
 use vector_core::event::metric::{samples_to_buckets, MetricSketch, Quantile};
 
 use crate::{
@@ -320,8 +323,33 @@ impl StringCollector {
 
 type Labels = Vec<proto::Label>;
 
+/// The zero threshold of the native histograms, as in the Prometheus clients.
+const NATIVE_HISTOGRAM_ZERO_THRESHOLD: f64 = 2.938735877055719e-39;
+
+/// The samples, native histograms and exemplars of a series, along with the name of its family.
+#[derive(Default)]
+struct Series {
+    family: String,
+    samples: Vec<proto::Sample>,
+    histograms: Vec<proto::Histogram>,
+    exemplars: Vec<proto::Exemplar>,
+}
+
+impl Series {
+    fn len(&self) -> usize {
+        self.samples.len() + self.histograms.len() + self.exemplars.len()
+    }
+
+    /// Sorts the samples by timestamp, as the metrics of a batch may be out of order.
+    fn sort(&mut self) {
+        self.samples.sort_by_key(|sample| sample.timestamp);
+        self.histograms.sort_by_key(|histogram| histogram.timestamp);
+        self.exemplars.sort_by_key(|exemplar| exemplar.timestamp);
+    }
+}
+
 pub(super) struct TimeSeries {
-    buffer: IndexMap<Labels, Vec<proto::Sample>>,
+    buffer: IndexMap<Labels, Series>,
     metadata: IndexMap<String, proto::MetricMetadata>,
     timestamp: Option<i64>,
 }
@@ -358,6 +386,267 @@ impl TimeSeries {
             .timestamp
             .get_or_insert_with(|| Utc::now().timestamp_millis())
     }
+
+    fn series(&mut self, labels: Labels, family: &str) -> &mut Series {
+        self.buffer.entry(labels).or_insert_with(|| Series {
+            family: family.into(),
+            ..Default::default()
+        })
+    }
+
+    /// Encodes a histogram distribution as a native histogram of the given schema, whose buckets
+    /// are bounded by the powers of `2^(2^-schema)`.
+    pub(super) fn encode_native_histogram(
+        &mut self,
+        default_namespace: Option<&str>,
+        schema: i32,
+        metric: &Metric,
+    ) {
+        let samples = match metric.value() {
+            MetricValue::Distribution {
+                samples,
+                statistic: StatisticKind::Histogram,
+            } if metric.kind() == MetricKind::Absolute => samples,
+            _ => return,
+        };
+        let name = encode_namespace(metric.namespace().or(default_namespace), '_', metric.name());
+        let timestamp = metric
+            .timestamp()
+            .map(|t| t.timestamp_millis())
+            .unwrap_or_else(|| self.default_timestamp());
+        self.emit_metadata(metric.name(), &name, metric.value());
+
+        let mut positive = BTreeMap::new();
+        let mut negative = BTreeMap::new();
+        let mut zero_count = 0;
+        let mut count = 0;
+        let mut sum = 0.0;
+        for sample in samples {
+            let rate = u64::from(sample.rate);
+            count += rate;
+            sum += sample.value * f64::from(sample.rate);
+            if sample.value.abs() <= NATIVE_HISTOGRAM_ZERO_THRESHOLD {
+                zero_count += rate;
+                continue;
+            }
+            // The bucket of index `i` holds the values up to `2^(i * 2^-schema)`.
+            let index = (sample.value.abs().log2() * 2f64.powi(schema)).ceil() as i32;
+            let buckets = if sample.value > 0.0 {
+                &mut positive
+            } else {
+                &mut negative
+            };
+            *buckets.entry(index).or_insert(0) += rate;
+        }
+
+        let (negative_spans, negative_deltas) = native_spans(&negative);
+        let (positive_spans, positive_deltas) = native_spans(&positive);
+        let histogram = proto::Histogram {
+            count: Some(proto::histogram::Count::CountInt(count)),
+            sum,
+            schema,
+            zero_threshold: NATIVE_HISTOGRAM_ZERO_THRESHOLD,
+            zero_count: Some(proto::histogram::ZeroCount::ZeroCountInt(zero_count)),
+            negative_spans,
+            negative_deltas,
+            positive_spans,
+            positive_deltas,
+            timestamp,
+            ..Default::default()
+        };
+        let labels = Self::make_labels(metric.tags(), &name, "", None);
+        self.series(labels, &name).histograms.push(histogram);
+    }
+
+    /// Encodes a gauge holding an exemplar, named after its series with the `_exemplar` suffix, as
+    /// an exemplar of this series, whose labels are the given tags of the gauge.
+    pub(super) fn encode_exemplar(
+        &mut self,
+        default_namespace: Option<&str>,
+        exemplar_labels: &[String],
+        metric: &Metric,
+    ) {
+        let value = match metric.value() {
+            MetricValue::Gauge { value } => *value,
+            _ => return,
+        };
+        let name = encode_namespace(metric.namespace().or(default_namespace), '_', metric.name());
+        let name = name.strip_suffix(EXEMPLAR_SUFFIX).unwrap_or(&name);
+        let timestamp = metric
+            .timestamp()
+            .map(|t| t.timestamp_millis())
+            .unwrap_or_else(|| self.default_timestamp());
+
+        let (labels, tags): (BTreeMap<_, _>, BTreeMap<_, _>) = metric
+            .tags()
+            .cloned()
+            .unwrap_or_default()
+            .into_iter()
+            .partition(|(name, _)| exemplar_labels.contains(name));
+        let exemplar = proto::Exemplar {
+            labels: labels
+                .into_iter()
+                .map(|(name, value)| proto::Label { name, value })
+                .collect(),
+            value,
+            timestamp,
+        };
+        let labels = Self::make_labels(Some(&tags), name, "", None);
+        self.series(labels, name).exemplars.push(exemplar);
+    }
+
+    /// Splits the series into groups of at most `max_samples` samples, native histograms and
+    /// exemplars, except for the series exceeding it on their own.
+    fn split(self, max_samples: Option<usize>) -> Vec<Vec<(Labels, Series)>> {
+        let mut groups = vec![Vec::new()];
+        let mut len = 0;
+        for (labels, mut series) in self.buffer {
+            series.sort();
+            if let Some(max_samples) = max_samples {
+                if len > 0 && len + series.len() > max_samples {
+                    groups.push(Vec::new());
+                    len = 0;
+                }
+            }
+            len += series.len();
+            groups.last_mut().unwrap().push((labels, series));
+        }
+        groups
+    }
+
+    /// Builds the requests of the 1.0 version of the remote write protocol, with the metadata in
+    /// the first one.
+    pub(super) fn finish_v1(self, max_samples: Option<usize>) -> Vec<proto::WriteRequest> {
+        let mut metadata = self.metadata.values().cloned().collect::<Vec<_>>();
+        self.split(max_samples)
+            .into_iter()
+            .map(|group| proto::WriteRequest {
+                timeseries: group
+                    .into_iter()
+                    .map(|(labels, series)| proto::TimeSeries {
+                        labels,
+                        samples: series.samples,
+                        exemplars: series.exemplars,
+                        histograms: series.histograms,
+                    })
+                    .collect(),
+                metadata: std::mem::take(&mut metadata),
+            })
+            .collect()
+    }
+
+    /// Builds the requests of the 2.0 version of the remote write protocol, whose series hold
+    /// the metadata of their family, and whose strings are interned in the symbols of each
+    /// request.
+    pub(super) fn finish_v2(self, max_samples: Option<usize>) -> Vec<proto::v2::Request> {
+        let metadata = self
+            .metadata
+            .values()
+            .map(|metadata| (metadata.metric_family_name.clone(), metadata.clone()))
+            .collect::<HashMap<_, _>>();
+        self.split(max_samples)
+            .into_iter()
+            .map(|group| {
+                let mut symbols = Symbols::default();
+                let timeseries = group
+                    .into_iter()
+                    .map(|(labels, series)| {
+                        let metadata = metadata.get(&series.family).map(|metadata| {
+                            proto::v2::Metadata {
+                                // The metric types of both versions share the same values.
+                                r#type: metadata.r#type,
+                                help_ref: symbols.intern(&metadata.help),
+                                unit_ref: symbols.intern(&metadata.unit),
+                            }
+                        });
+                        proto::v2::TimeSeries {
+                            labels_refs: symbols.intern_labels(&labels),
+                            samples: series.samples,
+                            histograms: series.histograms,
+                            exemplars: series
+                                .exemplars
+                                .into_iter()
+                                .map(|exemplar| proto::v2::Exemplar {
+                                    labels_refs: symbols.intern_labels(&exemplar.labels),
+                                    value: exemplar.value,
+                                    timestamp: exemplar.timestamp,
+                                })
+                                .collect(),
+                            metadata,
+                            created_timestamp: 0,
+                        }
+                    })
+                    .collect();
+                proto::v2::Request {
+                    symbols: symbols.symbols,
+                    timeseries,
+                }
+            })
+            .collect()
+    }
+}
+
+/// The spans and count deltas of the buckets of a native histogram.
+fn native_spans(buckets: &BTreeMap<i32, u64>) -> (Vec<proto::BucketSpan>, Vec<i64>) {
+    let mut spans: Vec<proto::BucketSpan> = Vec::new();
+    let mut deltas = Vec::new();
+    let mut previous: Option<(i32, i64)> = None;
+    for (&index, &count) in buckets {
+        let count = count as i64;
+        match previous {
+            Some((previous_index, _)) if index == previous_index + 1 => {
+                spans.last_mut().expect("a span").length += 1;
+            }
+            // The offset of a span is the gap to the previous one, or the index of the first one.
+            Some((previous_index, _)) => spans.push(proto::BucketSpan {
+                offset: index - previous_index - 1,
+                length: 1,
+            }),
+            None => spans.push(proto::BucketSpan {
+                offset: index,
+                length: 1,
+            }),
+        }
+        deltas.push(count - previous.map_or(0, |(_, count)| count));
+        previous = Some((index, count));
+    }
+    (spans, deltas)
+}
+
+/// The strings of a request of the 2.0 version of the remote write protocol, referred to by
+/// their index.
+struct Symbols {
+    symbols: Vec<String>,
+    refs: HashMap<String, u32>,
+}
+
+impl Default for Symbols {
+    fn default() -> Self {
+        // The symbols start with the empty string, referred to by the optional fields.
+        Self {
+            symbols: vec![String::new()],
+            refs: HashMap::from([(String::new(), 0)]),
+        }
+    }
+}
+
+impl Symbols {
+    fn intern(&mut self, symbol: &str) -> u32 {
+        if let Some(index) = self.refs.get(symbol) {
+            return *index;
+        }
+        let index = self.symbols.len() as u32;
+        self.symbols.push(symbol.into());
+        self.refs.insert(symbol.into(), index);
+        index
+    }
+
+    fn intern_labels(&mut self, labels: &[proto::Label]) -> Vec<u32> {
+        labels
+            .iter()
+            .flat_map(|label| [self.intern(&label.name), self.intern(&label.value)])
+            .collect()
+    }
 }
 
 impl MetricCollector for TimeSeries {
@@ -394,27 +683,16 @@ impl MetricCollector for TimeSeries {
         extra: Option<(&str, String)>,
     ) {
         let timestamp = timestamp_millis.unwrap_or_else(|| self.default_timestamp());
-        self.buffer
-            .entry(Self::make_labels(tags, name, suffix, extra))
-            .or_default()
+        let labels = Self::make_labels(tags, name, suffix, extra);
+        self.series(labels, name)
+            .samples
             .push(proto::Sample { value, timestamp });
     }
 
     fn finish(self) -> proto::WriteRequest {
-        let timeseries = self
-            .buffer
-            .into_iter()
-            .map(|(labels, samples)| proto::TimeSeries { labels, samples })
-            .collect::<Vec<_>>();
-        let metadata = self
-            .metadata
-            .into_iter()
-            .map(|(_, metadata)| metadata)
-            .collect();
-        proto::WriteRequest {
-            timeseries,
-            metadata,
-        }
+        self.finish_v1(None)
+            .pop()
+            .expect("a request without a maximum number of samples")
     }
 }
 
@@ -495,6 +773,7 @@ mod tests {
                                 value: $svalue,
                                 timestamp: $timestamp,
                             }],
+                            ..Default::default()
                         },
                     )*
                 ],
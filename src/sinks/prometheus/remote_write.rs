@@ -1,8 +1,9 @@
-use std::task;
+use std::{collections::BTreeMap, task};
 
 use bytes::{Bytes, BytesMut};
 use futures::{future::BoxFuture, stream, FutureExt, SinkExt};
 use http::Uri;
+use prometheus_parser::EXEMPLAR_SUFFIX;
 use prost::Message;
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
@@ -11,7 +12,10 @@ use vector_core::ByteSizeOf;
 use super::collector::{self, MetricCollector as _};
 use crate::{
     config::{self, AcknowledgementsConfig, Input, SinkConfig, SinkDescription},
-    event::{Event, Metric},
+    event::{
+        metric::{MetricValue, StatisticKind},
+        Event, Metric,
+    },
     http::{Auth, HttpClient},
     internal_events::TemplateRenderingError,
     sinks::{
@@ -41,6 +45,35 @@ impl SinkBatchSettings for PrometheusRemoteWriteDefaultBatchSettings {
 enum Errors {
     #[snafu(display(r#"Prometheus remote_write sink cannot accept "set" metrics"#))]
     SetMetricInvalid,
+    #[snafu(display("The native histogram schema must be between -4 and 8, got {}", schema))]
+    InvalidNativeHistogramSchema { schema: i32 },
+}
+
+/// The version of the remote write protocol.
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, PartialEq, Eq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RemoteWriteProtocol {
+    #[derivative(Default)]
+    V1,
+    /// Interns the strings of the requests, and sends the metadata of the series along with them.
+    V2,
+}
+
+impl RemoteWriteProtocol {
+    const fn version(self) -> &'static str {
+        match self {
+            Self::V1 => "0.1.0",
+            Self::V2 => "2.0.0",
+        }
+    }
+
+    const fn content_type(self) -> &'static str {
+        match self {
+            Self::V1 => "application/x-protobuf",
+            Self::V2 => "application/x-protobuf;proto=io.prometheus.write.v2.Request",
+        }
+    }
 }
 
 #[derive(Debug, Default, Deserialize, Serialize)]
@@ -63,6 +96,25 @@ pub struct RemoteWriteConfig {
     #[serde(default)]
     pub tenant_id: Option<Template>,
 
+    /// The additional headers of the requests of each tenant, keyed by tenant ID.
+    #[serde(default)]
+    pub tenant_headers: BTreeMap<String, BTreeMap<String, String>>,
+
+    #[serde(default)]
+    pub protocol: RemoteWriteProtocol,
+
+    /// The maximum number of samples of a request, beyond which a batch is sent with several
+    /// requests.
+    pub max_samples_per_request: Option<usize>,
+
+    /// The schema of the native histograms to encode the histogram distributions as, instead of
+    /// the buckets of `buckets`.
+    pub native_histogram_schema: Option<i32>,
+
+    /// The tags sent as the labels of the exemplars, which are the gauges named after their
+    /// series with the `_exemplar` suffix. They are sent as gauges if unset.
+    pub exemplar_labels: Option<Vec<String>>,
+
     pub tls: Option<TlsConfig>,
 
     pub auth: Option<Auth>,
@@ -87,6 +139,15 @@ impl SinkConfig for RemoteWriteConfig {
         let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
         let buckets = self.buckets.clone();
         let quantiles = self.quantiles.clone();
+        if let Some(schema) = self.native_histogram_schema {
+            if !(-4..=8).contains(&schema) {
+                return Err(Errors::InvalidNativeHistogramSchema { schema }.into());
+            }
+        }
+        for (name, value) in self.tenant_headers.values().flatten() {
+            http::header::HeaderName::from_bytes(name.as_bytes())?;
+            http::HeaderValue::from_str(value)?;
+        }
 
         let client = HttpClient::new(tls_settings, cx.proxy())?;
         let tenant_id = self.tenant_id.clone();
@@ -100,6 +161,11 @@ impl SinkConfig for RemoteWriteConfig {
             buckets,
             quantiles,
             auth,
+            tenant_headers: self.tenant_headers.clone(),
+            protocol: self.protocol,
+            max_samples_per_request: self.max_samples_per_request,
+            native_histogram_schema: self.native_histogram_schema,
+            exemplar_labels: self.exemplar_labels.clone(),
         };
 
         let sink = {
@@ -186,27 +252,87 @@ struct RemoteWriteService {
     buckets: Vec<f64>,
     quantiles: Vec<f64>,
     auth: Option<Auth>,
+    tenant_headers: BTreeMap<String, BTreeMap<String, String>>,
+    protocol: RemoteWriteProtocol,
+    max_samples_per_request: Option<usize>,
+    native_histogram_schema: Option<i32>,
+    exemplar_labels: Option<Vec<String>>,
 }
 
 impl RemoteWriteService {
-    fn encode_events(&self, metrics: Vec<Metric>) -> Bytes {
+    /// Encodes the metrics of a batch, in as many requests as needed to respect the maximum
+    /// number of samples of a request.
+    fn encode_events(&self, metrics: Vec<Metric>) -> Vec<Bytes> {
+        let default_namespace = self.default_namespace.as_deref();
         let mut time_series = collector::TimeSeries::new();
         for metric in metrics {
-            time_series.encode_metric(
-                self.default_namespace.as_deref(),
-                &self.buckets,
-                &self.quantiles,
-                &metric,
-            );
+            match (
+                metric.value(),
+                &self.exemplar_labels,
+                self.native_histogram_schema,
+            ) {
+                (MetricValue::Gauge { .. }, Some(labels), _)
+                    if metric.name().ends_with(EXEMPLAR_SUFFIX) =>
+                {
+                    time_series.encode_exemplar(default_namespace, labels, &metric);
+                }
+                (
+                    MetricValue::Distribution {
+                        statistic: StatisticKind::Histogram,
+                        ..
+                    },
+                    _,
+                    Some(schema),
+                ) => time_series.encode_native_histogram(default_namespace, schema, &metric),
+                _ => time_series.encode_metric(
+                    default_namespace,
+                    &self.buckets,
+                    &self.quantiles,
+                    &metric,
+                ),
+            }
+        }
+
+        match self.protocol {
+            RemoteWriteProtocol::V1 => time_series
+                .finish_v1(self.max_samples_per_request)
+                .iter()
+                .map(encode_message)
+                .collect(),
+            RemoteWriteProtocol::V2 => time_series
+                .finish_v2(self.max_samples_per_request)
+                .iter()
+                .map(encode_message)
+                .collect(),
+        }
+    }
+
+    fn build_request(&self, body: Bytes, tenant_id: Option<&str>) -> http::Request<hyper::Body> {
+        let mut builder = http::Request::post(self.endpoint.clone())
+            .header("X-Prometheus-Remote-Write-Version", self.protocol.version())
+            .header("Content-Encoding", "snappy")
+            .header("Content-Type", self.protocol.content_type());
+        if let Some(tenant_id) = tenant_id {
+            builder = builder.header("X-Scope-OrgID", tenant_id);
+            for (name, value) in self.tenant_headers.get(tenant_id).into_iter().flatten() {
+                builder = builder.header(name.as_str(), value.as_str());
+            }
         }
-        let request = time_series.finish();
 
-        let mut out = BytesMut::with_capacity(request.encoded_len());
-        request.encode(&mut out).expect("Out of memory");
-        out.freeze()
+        let mut request = builder.body(snap_block(body).into()).unwrap();
+        if let Some(auth) = &self.auth {
+            auth.apply(&mut request);
+        }
+        request
     }
 }
 
+fn encode_message(message: &impl Message) -> Bytes {
+    let mut out = BytesMut::with_capacity(message.encoded_len());
+    message.encode(&mut out).expect("Out of memory");
+    out.freeze()
+}
+
 impl tower::Service<PartitionInnerBuffer<Vec<Metric>, PartitionKey>> for RemoteWriteService {
     type Response = http::Response<Bytes>;
     type Error = crate::Error;
@@ -218,28 +344,26 @@ impl tower::Service<PartitionInnerBuffer<Vec<Metric>, PartitionKey>> for RemoteW
 
     fn call(&mut self, buffer: PartitionInnerBuffer<Vec<Metric>, PartitionKey>) -> Self::Future {
         let (events, key) = buffer.into_parts();
-        let body = self.encode_events(events);
-        let body = snap_block(body);
-
-        let mut builder = http::Request::post(self.endpoint.clone())
-            .header("X-Prometheus-Remote-Write-Version", "0.1.0")
-            .header("Content-Encoding", "snappy")
-            .header("Content-Type", "application/x-protobuf");
-        if let Some(tenant_id) = key.tenant_id {
-            builder = builder.header("X-Scope-OrgID", tenant_id);
-        }
-
-        let mut request = builder.body(body.into()).unwrap();
-        if let Some(auth) = &self.auth {
-            auth.apply(&mut request);
-        }
+        let requests = self
+            .encode_events(events)
+            .into_iter()
+            .map(|body| self.build_request(body, key.tenant_id.as_deref()))
+            .collect::<Vec<_>>();
         let client = self.client.clone();
 
         Box::pin(async move {
-            let response = client.send(request).await?;
-            let (parts, body) = response.into_parts();
-            let body = hyper::body::to_bytes(body).await?;
-            Ok(hyper::Response::from_parts(parts, body))
+            // The requests are sent in order, stopping at the first failed one.
+            let mut response = None;
+            for request in requests {
+                let (parts, body) = client.send(request).await?.into_parts();
+                let body = hyper::body::to_bytes(body).await?;
+                let success = parts.status.is_success();
+                response = Some(hyper::Response::from_parts(parts, body));
+                if !success {
+                    break;
+                }
+            }
+            Ok(response.expect("at least one request"))
         })
     }
 }
@@ -386,10 +510,205 @@ mod tests {
         check_output(2, "counter-1", 26.0);
     }
 
+    #[tokio::test]
+    async fn sends_tenant_headers() {
+        let outputs = send_request(
+            indoc! {r#"
+                tenant_id = "{{ tags.tenant }}"
+                [tenant_headers.tenant-a]
+                X-Tenant-Region = "us-west-1"
+            "#},
+            vec![
+                create_tenant_event("gauge-1".into(), "tenant-a"),
+                create_tenant_event("gauge-2".into(), "tenant-b"),
+            ],
+        )
+        .await;
+
+        assert_eq!(outputs.len(), 2);
+        for (headers, req) in &outputs {
+            let tenant = headers["x-scope-orgid"].to_str().unwrap();
+            assert_eq!(req.timeseries.len(), 1);
+            if tenant == "tenant-a" {
+                assert_eq!(headers["x-tenant-region"], "us-west-1");
+            } else {
+                assert!(!headers.contains_key("x-tenant-region"));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn splits_requests_by_samples() {
+        let outputs = send_request(
+            r#"max_samples_per_request = 2"#,
+            vec![
+                create_event("gauge-1".into(), 1.0),
+                create_event("gauge-2".into(), 2.0),
+                create_event("gauge-3".into(), 3.0),
+            ],
+        )
+        .await;
+
+        assert_eq!(outputs.len(), 2);
+        let (_, first) = &outputs[0];
+        assert_eq!(first.timeseries.len(), 2);
+        assert_eq!(first.metadata.len(), 3);
+        let (_, second) = &outputs[1];
+        assert_eq!(second.timeseries.len(), 1);
+        assert_eq!(second.timeseries[0].samples[0].value, 3.0);
+        assert!(second.metadata.is_empty());
+    }
+
+    #[tokio::test]
+    async fn sends_native_histograms() {
+        let event = Metric::new(
+            "latency",
+            MetricKind::Absolute,
+            MetricValue::Distribution {
+                samples: vector_core::samples![1.0 => 2, 3.0 => 1],
+                statistic: StatisticKind::Histogram,
+            },
+        )
+        .with_timestamp(Some(chrono::Utc::now()))
+        .into();
+        let outputs = send_request(r#"native_histogram_schema = 0"#, vec![event]).await;
+
+        assert_eq!(outputs.len(), 1);
+        let (_, req) = &outputs[0];
+        assert_eq!(req.timeseries.len(), 1);
+        assert_eq!(req.timeseries[0].labels, labels!("__name__" => "latency"));
+        assert!(req.timeseries[0].samples.is_empty());
+        let histogram = &req.timeseries[0].histograms[0];
+        assert_eq!(histogram.count, Some(proto::histogram::Count::CountInt(3)));
+        assert_eq!(histogram.sum, 5.0);
+        assert_eq!(
+            histogram.positive_spans,
+            vec![
+                proto::BucketSpan {
+                    offset: 0,
+                    length: 1
+                },
+                proto::BucketSpan {
+                    offset: 1,
+                    length: 1
+                },
+            ]
+        );
+        assert_eq!(histogram.positive_deltas, vec![2, -1]);
+        assert_eq!(req.metadata[0].r#type, proto::MetricType::Histogram as i32);
+    }
+
+    #[tokio::test]
+    async fn sends_exemplars() {
+        let event = Metric::new(
+            "latency_exemplar",
+            MetricKind::Absolute,
+            MetricValue::Gauge { value: 0.5 },
+        )
+        .with_tags(Some(
+            vec![
+                ("code".to_owned(), "200".to_owned()),
+                ("trace_id".to_owned(), "abc".to_owned()),
+            ]
+            .into_iter()
+            .collect(),
+        ))
+        .with_timestamp(Some(chrono::Utc::now()))
+        .into();
+        let outputs = send_request(r#"exemplar_labels = ["trace_id"]"#, vec![event]).await;
+
+        assert_eq!(outputs.len(), 1);
+        let (_, req) = &outputs[0];
+        assert_eq!(req.timeseries.len(), 1);
+        assert_eq!(
+            req.timeseries[0].labels,
+            labels!("__name__" => "latency", "code" => "200")
+        );
+        assert_eq!(req.timeseries[0].exemplars.len(), 1);
+        assert_eq!(
+            req.timeseries[0].exemplars[0].labels,
+            labels!("trace_id" => "abc")
+        );
+        assert_eq!(req.timeseries[0].exemplars[0].value, 0.5);
+    }
+
+    #[tokio::test]
+    async fn sends_v2_request() {
+        let outputs = send_encoded_request(
+            r#"protocol = "v2""#,
+            vec![
+                create_event("gauge-1".into(), 12.0),
+                create_event("gauge-2".into(), 13.0),
+            ],
+        )
+        .await;
+
+        assert_eq!(outputs.len(), 1);
+        let (headers, body) = &outputs[0];
+        assert_eq!(headers["x-prometheus-remote-write-version"], "2.0.0");
+        assert_eq!(
+            headers["content-type"],
+            "application/x-protobuf;proto=io.prometheus.write.v2.Request"
+        );
+
+        let req = proto::v2::Request::decode(body.clone()).expect("Invalid protobuf");
+        assert_eq!(req.symbols[0], "");
+        let symbol = |index: u32| req.symbols[index as usize].as_str();
+        // The tags shared by both series are interned once.
+        assert_eq!(req.symbols.len(), 8);
+
+        assert_eq!(req.timeseries.len(), 2);
+        let series = &req.timeseries[1];
+        let labels = series
+            .labels_refs
+            .chunks(2)
+            .map(|pair| (symbol(pair[0]), symbol(pair[1])))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            labels,
+            vec![
+                ("__name__", "gauge-2"),
+                ("production", "true"),
+                ("region", "us-west-1")
+            ]
+        );
+        assert_eq!(series.samples[0].value, 13.0);
+        let metadata = series.metadata.as_ref().unwrap();
+        assert_eq!(
+            metadata.r#type,
+            proto::v2::metadata::MetricType::Gauge as i32
+        );
+        assert_eq!(symbol(metadata.help_ref), "gauge-2");
+        assert_eq!(symbol(metadata.unit_ref), "");
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_native_histogram_schema() {
+        let config = format!(
+            "endpoint = \"http://{}/write\"\nnative_histogram_schema = 9",
+            test_util::next_addr()
+        );
+        let config: RemoteWriteConfig = toml::from_str(&config).unwrap();
+        assert!(config.build(SinkContext::new_test()).await.is_err());
+    }
+
     async fn send_request(
         config: &str,
         events: Vec<Event>,
     ) -> Vec<(HeaderMap, proto::WriteRequest)> {
+        send_encoded_request(config, events)
+            .await
+            .into_iter()
+            .map(|(headers, body)| {
+                assert_eq!(headers["x-prometheus-remote-write-version"], "0.1.0");
+                assert_eq!(headers["content-type"], "application/x-protobuf");
+                let request = proto::WriteRequest::decode(body).expect("Invalid protobuf");
+                (headers, request)
+            })
+            .collect()
+    }
+
+    async fn send_encoded_request(config: &str, events: Vec<Event>) -> Vec<(HeaderMap, Bytes)> {
         let addr = test_util::next_addr();
         let (rx, trigger, server) = build_test_server(addr);
         tokio::spawn(server);
@@ -407,9 +726,7 @@ mod tests {
             assert_eq!(parts.method, "POST");
             assert_eq!(parts.uri.path(), "/write");
             let headers = parts.headers;
-            assert_eq!(headers["x-prometheus-remote-write-version"], "0.1.0");
             assert_eq!(headers["content-encoding"], "snappy");
-            assert_eq!(headers["content-type"], "application/x-protobuf");
 
             if config.auth.is_some() {
                 assert!(headers.contains_key("authorization"));
@@ -418,9 +735,7 @@ mod tests {
             let decoded = snap::raw::Decoder::new()
                 .decompress_vec(&body)
                 .expect("Invalid snappy compressed data");
-            let request =
-                proto::WriteRequest::decode(Bytes::from(decoded)).expect("Invalid protobuf");
-            (headers, request)
+            (headers, Bytes::from(decoded))
         })
         .collect::<Vec<_>>()
         .await
@@ -440,6 +755,21 @@ mod tests {
             .into()
     }
 
+    fn create_tenant_event(name: String, tenant: &str) -> Event {
+        Metric::new(
+            name,
+            MetricKind::Absolute,
+            MetricValue::Gauge { value: 1.0 },
+        )
+        .with_tags(Some(
+            vec![("tenant".to_owned(), tenant.to_owned())]
+                .into_iter()
+                .collect(),
+        ))
+        .with_timestamp(Some(chrono::Utc::now()))
+        .into()
+    }
+
     fn create_inc_event(name: String, value: f64) -> Event {
         Metric::new(
             name,
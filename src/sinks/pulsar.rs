@@ -7,7 +7,9 @@ use std::{
 
 use futures::{future::BoxFuture, ready, stream::FuturesUnordered, FutureExt, Sink, Stream};
 use pulsar::{
-    message::proto, producer::SendFuture, proto::CommandSendReceipt, Authentication,
+    message::proto,
+    producer::{Message, ProducerOptions, SendFuture},
+    proto::CommandSendReceipt,
     Error as PulsarError, Producer, Pulsar, TokioExecutor,
 };
 use serde::{Deserialize, Serialize};
@@ -21,17 +23,21 @@ use crate::{
         SinkDescription,
     },
     event::Event,
-    internal_events::PulsarEncodeEventError,
+    internal_events::{PulsarEncodeEventError, TemplateRenderingError},
+    pulsar::PulsarAuthConfig,
     sinks::util::{
         encoding::{EncodingConfig, EncodingConfiguration},
         metadata::RequestMetadata,
     },
+    template::Template,
 };
 
 #[derive(Debug, Snafu)]
 enum BuildError {
     #[snafu(display("creating pulsar producer failed: {}", source))]
     CreatePulsarSink { source: PulsarError },
+    #[snafu(display("Only the `avro` and `json` encodings can register a schema."))]
+    SchemaNotSupported,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -40,14 +46,36 @@ pub struct PulsarSinkConfig {
     #[serde(alias = "address")]
     endpoint: String,
     topic: String,
+    /// The key messages are routed and compacted by, rendered from each event.
+    partition_key: Option<Template>,
+    #[serde(default)]
+    compression: PulsarCompression,
     encoding: EncodingConfig<Encoding>,
-    auth: Option<AuthConfig>,
+    auth: Option<PulsarAuthConfig>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-struct AuthConfig {
-    name: String,  // "token"
-    token: String, // <jwt token>
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub(self) enum PulsarCompression {
+    #[derivative(Default)]
+    None,
+    Lz4,
+    Zlib,
+    Zstd,
+    Snappy,
+}
+
+impl PulsarCompression {
+    const fn compression_type(self) -> Option<proto::CompressionType> {
+        match self {
+            Self::None => None,
+            Self::Lz4 => Some(proto::CompressionType::Lz4),
+            Self::Zlib => Some(proto::CompressionType::Zlib),
+            Self::Zstd => Some(proto::CompressionType::Zstd),
+            Self::Snappy => Some(proto::CompressionType::Snappy),
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
@@ -78,6 +106,7 @@ enum PulsarSinkState {
 
 struct PulsarSink {
     encoding: EncodingConfig<Encoding>,
+    partition_key: Option<Template>,
     avro_schema: Option<avro_rs::Schema>,
     state: PulsarSinkState,
     in_flight: FuturesUnordered<
@@ -106,6 +135,8 @@ impl GenerateConfig for PulsarSinkConfig {
         toml::Value::try_from(Self {
             endpoint: "pulsar://127.0.0.1:6650".to_string(),
             topic: "topic-1234".to_string(),
+            partition_key: None,
+            compression: PulsarCompression::None,
             encoding: Encoding::Text.into(),
             auth: None,
         })
@@ -120,16 +151,15 @@ impl SinkConfig for PulsarSinkConfig {
         &self,
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
-        let producer = self
-            .create_pulsar_producer()
-            .await
-            .context(CreatePulsarSinkSnafu)?;
-        let sink = PulsarSink::new(producer, self.encoding.clone(), cx.acker())?;
-
-        let producer = self
-            .create_pulsar_producer()
-            .await
-            .context(CreatePulsarSinkSnafu)?;
+        let producer = self.create_pulsar_producer().await?;
+        let sink = PulsarSink::new(
+            producer,
+            self.encoding.clone(),
+            self.partition_key.clone(),
+            cx.acker(),
+        )?;
+
+        let producer = self.create_pulsar_producer().await?;
         let healthcheck = healthcheck(producer).boxed();
 
         Ok((super::VectorSink::from_event_sink(sink), healthcheck))
@@ -149,34 +179,45 @@ impl SinkConfig for PulsarSinkConfig {
 }
 
 impl PulsarSinkConfig {
-    async fn create_pulsar_producer(&self) -> Result<PulsarProducer, PulsarError> {
+    async fn create_pulsar_producer(&self) -> crate::Result<PulsarProducer> {
         let mut builder = Pulsar::builder(&self.endpoint, TokioExecutor);
         if let Some(auth) = &self.auth {
-            builder = builder.with_auth(Authentication {
-                name: auth.name.clone(),
-                data: auth.token.as_bytes().to_vec(),
-            });
+            builder = auth.apply(builder)?;
         }
 
-        if let Some(avro_schema) = &self.encoding.schema() {
-            let pulsar = builder.build().await?;
-            pulsar
-                .producer()
-                .with_options(pulsar::producer::ProducerOptions {
-                    schema: Some(proto::Schema {
-                        schema_data: avro_schema.to_string().into_bytes(),
-                        r#type: proto::schema::Type::Avro as i32,
-                        ..Default::default()
-                    }),
-                    ..Default::default()
-                })
-                .with_topic(&self.topic)
-                .build()
-                .await
-        } else {
-            let pulsar = builder.build().await?;
-            pulsar.producer().with_topic(&self.topic).build().await
-        }
+        let options = ProducerOptions {
+            schema: self.schema()?,
+            compression: self.compression.compression_type(),
+            ..Default::default()
+        };
+        let pulsar = builder.build().await.context(CreatePulsarSinkSnafu)?;
+        let producer = pulsar
+            .producer()
+            .with_options(options)
+            .with_topic(&self.topic)
+            .build()
+            .await
+            .context(CreatePulsarSinkSnafu)?;
+        Ok(producer)
+    }
+
+    /// The schema registered on the topic, which Pulsar checks the schema of its producers and
+    /// consumers against.
+    fn schema(&self) -> Result<Option<proto::Schema>, BuildError> {
+        let schema = match self.encoding.schema() {
+            Some(schema) => schema,
+            None => return Ok(None),
+        };
+        let schema_type = match self.encoding.codec() {
+            Encoding::Avro => proto::schema::Type::Avro,
+            Encoding::Json => proto::schema::Type::Json,
+            Encoding::Text => return Err(BuildError::SchemaNotSupported),
+        };
+        Ok(Some(proto::Schema {
+            schema_data: schema.clone().into_bytes(),
+            r#type: schema_type as i32,
+            ..Default::default()
+        }))
     }
 }
 
@@ -188,6 +229,7 @@ impl PulsarSink {
     fn new(
         producer: PulsarProducer,
         encoding: EncodingConfig<Encoding>,
+        partition_key: Option<Template>,
         acker: Acker,
     ) -> crate::Result<Self> {
         let schema = match &encoding.codec() {
@@ -206,6 +248,7 @@ impl PulsarSink {
 
         Ok(Self {
             encoding,
+            partition_key,
             avro_schema: schema,
             state: PulsarSinkState::Ready(Box::new(producer)),
             in_flight: FuturesUnordered::new(),
@@ -252,12 +295,29 @@ impl Sink<Event> for PulsarSink {
         );
 
         let metadata_builder = RequestMetadata::builder(&item);
-        let message = encode_event(item, &self.encoding, &self.avro_schema)
+        let partition_key = self.partition_key.as_ref().and_then(|template| {
+            template
+                .render_string(&item)
+                .map_err(|error| {
+                    emit!(TemplateRenderingError {
+                        error,
+                        field: Some("partition_key"),
+                        drop_event: false,
+                    });
+                })
+                .ok()
+        });
+        let payload = encode_event(item, &self.encoding, &self.avro_schema)
             .map_err(|error| emit!(PulsarEncodeEventError { error }))?;
 
         let message_len =
-            NonZeroUsize::new(message.len()).expect("payload should never be zero length");
+            NonZeroUsize::new(payload.len()).expect("payload should never be zero length");
         let metadata = metadata_builder.with_request_size(message_len);
+        let message = Message {
+            payload,
+            partition_key,
+            ..Default::default()
+        };
 
         let mut producer = match std::mem::replace(&mut self.state, PulsarSinkState::None) {
             PulsarSinkState::Ready(producer) => producer,
@@ -433,6 +493,47 @@ mod tests {
         let map: HashMap<String, String> = serde_json::from_slice(&event[..]).unwrap();
         assert!(!map.contains_key("key"));
     }
+
+    #[test]
+    fn pulsar_registers_schema_of_codec() {
+        let mut config: PulsarSinkConfig = toml::from_str(
+            r#"
+            endpoint = "pulsar://127.0.0.1:6650"
+            topic = "logs"
+            encoding.codec = "json"
+            encoding.schema = '{"type": "record", "name": "Log", "fields": []}'
+            "#,
+        )
+        .unwrap();
+        let schema = config.schema().unwrap().unwrap();
+        assert_eq!(schema.r#type, proto::schema::Type::Json as i32);
+
+        config.encoding.codec = Encoding::Avro;
+        let schema = config.schema().unwrap().unwrap();
+        assert_eq!(schema.r#type, proto::schema::Type::Avro as i32);
+
+        config.encoding.codec = Encoding::Text;
+        assert!(matches!(
+            config.schema(),
+            Err(BuildError::SchemaNotSupported)
+        ));
+    }
+
+    #[tokio::test]
+    async fn pulsar_rejects_mixed_auth() {
+        let config: PulsarSinkConfig = toml::from_str(
+            r#"
+            endpoint = "pulsar://127.0.0.1:6650"
+            topic = "logs"
+            encoding.codec = "text"
+            auth.token = "secret"
+            auth.oauth2.issuer_url = "https://auth.example.com"
+            auth.oauth2.credentials_url = "file:///etc/pulsar/credentials.json"
+            "#,
+        )
+        .unwrap();
+        assert!(config.create_pulsar_producer().await.is_err());
+    }
 }
 
 #[cfg(feature = "pulsar-integration-tests")]
@@ -463,6 +564,8 @@ mod integration_tests {
         let cnf = PulsarSinkConfig {
             endpoint: pulsar_address(),
             topic: topic.clone(),
+            partition_key: None,
+            compression: PulsarCompression::None,
             encoding: Encoding::Text.into(),
             auth: None,
         };
@@ -487,7 +590,7 @@ mod integration_tests {
 
         let (acker, ack_counter) = Acker::basic();
         let producer = cnf.create_pulsar_producer().await.unwrap();
-        let sink = PulsarSink::new(producer, cnf.encoding, acker).unwrap();
+        let sink = PulsarSink::new(producer, cnf.encoding, None, acker).unwrap();
         let sink = VectorSink::from_event_sink(sink);
         run_and_assert_sink_compliance(sink, events, &SINK_TAGS).await;
 
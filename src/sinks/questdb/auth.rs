@@ -0,0 +1,156 @@
+use openssl::{
+    bn::{BigNum, BigNumContext},
+    ec::{EcGroup, EcKey, EcPoint},
+    hash::MessageDigest,
+    nid::Nid,
+    pkey::{PKey, Private},
+    sign::Signer,
+};
+use snafu::ResultExt;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use super::{AuthenticateSnafu, QuestdbAuth, QuestdbSinkError, SignSnafu};
+
+/// The longest challenge accepted from the server, which sends 512 bytes.
+const MAX_CHALLENGE_LENGTH: usize = 4096;
+
+/// Authenticates connections by signing the challenge of the server with an ECDSA P-256 key.
+#[derive(Clone)]
+pub struct Authenticator {
+    key_id: String,
+    key: PKey<Private>,
+}
+
+impl std::fmt::Debug for Authenticator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Authenticator")
+            .field("key_id", &self.key_id)
+            .finish()
+    }
+}
+
+impl Authenticator {
+    pub fn new(auth: &QuestdbAuth) -> Result<Self, QuestdbSinkError> {
+        let invalid = |reason: String| QuestdbSinkError::InvalidKey { reason };
+
+        let d = base64::decode_config(
+            auth.private_key.trim_end_matches('='),
+            base64::URL_SAFE_NO_PAD,
+        )
+        .map_err(|error| invalid(error.to_string()))?;
+        let key = private_key(&d).map_err(|error| invalid(error.to_string()))?;
+        Ok(Self {
+            key_id: auth.key_id.clone(),
+            key,
+        })
+    }
+
+    /// Sends the ID of the key, and then the signature of the challenge the server replies with.
+    pub async fn authenticate<S>(&self, stream: &mut S) -> Result<(), QuestdbSinkError>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        stream
+            .write_all(format!("{}\n", self.key_id).as_bytes())
+            .await
+            .context(AuthenticateSnafu)?;
+        stream.flush().await.context(AuthenticateSnafu)?;
+
+        let mut challenge = Vec::new();
+        loop {
+            let byte = stream.read_u8().await.context(AuthenticateSnafu)?;
+            if byte == b'\n' {
+                break;
+            }
+            if challenge.len() == MAX_CHALLENGE_LENGTH {
+                return Err(QuestdbSinkError::Authenticate {
+                    source: std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        "the challenge of the server is too long",
+                    ),
+                });
+            }
+            challenge.push(byte);
+        }
+
+        let mut response = base64::encode(self.sign(&challenge).context(SignSnafu)?);
+        response.push('\n');
+        stream
+            .write_all(response.as_bytes())
+            .await
+            .context(AuthenticateSnafu)?;
+        stream.flush().await.context(AuthenticateSnafu)
+    }
+
+    /// Signs the challenge with SHA-256, in the DER encoding the server expects.
+    fn sign(&self, challenge: &[u8]) -> Result<Vec<u8>, openssl::error::ErrorStack> {
+        let mut signer = Signer::new(MessageDigest::sha256(), &self.key)?;
+        signer.update(challenge)?;
+        signer.sign_to_vec()
+    }
+}
+
+/// Builds the key from its private scalar, deriving the public point from it.
+fn private_key(d: &[u8]) -> Result<PKey<Private>, openssl::error::ErrorStack> {
+    let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)?;
+    let d = BigNum::from_slice(d)?;
+    let context = BigNumContext::new()?;
+    let mut public = EcPoint::new(&group)?;
+    public.mul_generator(&group, &d, &context)?;
+    let key = EcKey::from_private_components(&group, &d, &public)?;
+    key.check_key()?;
+    PKey::from_ec_key(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use openssl::sign::Verifier;
+    use tokio::io::{duplex, AsyncBufReadExt, BufReader};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn signs_the_challenge() {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let key = EcKey::generate(&group).unwrap();
+        let auth = QuestdbAuth {
+            key_id: "vector".into(),
+            private_key: base64::encode_config(key.private_key().to_vec(), base64::URL_SAFE_NO_PAD),
+        };
+        let authenticator = Authenticator::new(&auth).unwrap();
+
+        let (mut client, server) = duplex(1024);
+        let server = tokio::spawn(async move {
+            let mut server = BufReader::new(server);
+            let mut line = String::new();
+            server.read_line(&mut line).await.unwrap();
+            assert_eq!(line, "vector\n");
+
+            server.get_mut().write_all(b"challenge\n").await.unwrap();
+            line.clear();
+            server.read_line(&mut line).await.unwrap();
+            base64::decode(line.trim_end()).unwrap()
+        });
+
+        authenticator.authenticate(&mut client).await.unwrap();
+        let signature = server.await.unwrap();
+
+        let public = EcKey::from_public_key(&group, key.public_key()).unwrap();
+        let public = PKey::from_ec_key(public).unwrap();
+        let mut verifier = Verifier::new(MessageDigest::sha256(), &public).unwrap();
+        verifier.update(b"challenge").unwrap();
+        assert!(verifier.verify(&signature).unwrap());
+    }
+
+    #[test]
+    fn rejects_invalid_keys() {
+        let auth = QuestdbAuth {
+            key_id: "vector".into(),
+            private_key: "AAAA".into(),
+        };
+        assert!(matches!(
+            Authenticator::new(&auth),
+            Err(QuestdbSinkError::InvalidKey { .. })
+        ));
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    auth::Authenticator,
+    sink::{LineEncoder, QuestdbConnector, QuestdbSink},
+};
+use crate::{
+    config::{
+        AcknowledgementsConfig, Capability, GenerateConfig, Input, Sandbox, SinkConfig,
+        SinkContext, SinkDescription,
+    },
+    sinks::{
+        influxdb::metrics::default_summary_quantiles,
+        util::{BatchConfig, SinkBatchSettings, SinkBuildError},
+        Healthcheck, VectorSink,
+    },
+    tcp::TcpKeepaliveConfig,
+    template::Template,
+    tls::{MaybeTlsSettings, TlsEnableableConfig},
+};
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct QuestdbDefaultBatchSettings;
+
+impl SinkBatchSettings for QuestdbDefaultBatchSettings {
+    const MAX_EVENTS: Option<usize> = Some(1000);
+    const MAX_BYTES: Option<usize> = Some(1_000_000);
+    const TIMEOUT_SECS: f64 = 1.0;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct QuestdbConfig {
+    /// The address of the InfluxDB line protocol listener of the server, such as
+    /// `localhost:9009`.
+    pub address: String,
+    /// The table each metric is written to, which defaults to its namespaced name.
+    pub table: Option<Template>,
+    #[serde(alias = "namespace")]
+    pub default_namespace: Option<String>,
+    pub tags: Option<HashMap<String, String>>,
+    #[serde(default = "default_summary_quantiles")]
+    pub quantiles: Vec<f64>,
+    pub auth: Option<QuestdbAuth>,
+    #[serde(default)]
+    pub batch: BatchConfig<QuestdbDefaultBatchSettings>,
+    pub keepalive: Option<TcpKeepaliveConfig>,
+    pub tls: Option<TlsEnableableConfig>,
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+/// The key connections are authenticated with.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct QuestdbAuth {
+    /// The ID of the key, which is the name of the user it's configured for on the server.
+    pub key_id: String,
+    /// The private ECDSA P-256 key, as the base64url encoded `d` parameter of its JSON Web Key.
+    pub private_key: String,
+}
+
+inventory::submit! {
+    SinkDescription::new::<QuestdbConfig>("questdb")
+}
+
+impl GenerateConfig for QuestdbConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"address = "localhost:9009""#).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "questdb")]
+impl SinkConfig for QuestdbConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let uri = self.address.parse::<http::Uri>()?;
+        let host = uri.host().ok_or(SinkBuildError::MissingHost)?.to_string();
+        let port = uri.port_u16().ok_or(SinkBuildError::MissingPort)?;
+        Sandbox::current().enforce(&Capability::host(&host, Some(port)))?;
+
+        let authenticator = self.auth.as_ref().map(Authenticator::new).transpose()?;
+        let connector = QuestdbConnector {
+            host,
+            port,
+            keepalive: self.keepalive,
+            tls: MaybeTlsSettings::from_config(&self.tls, false)?,
+            authenticator,
+        };
+        let healthcheck = connector.clone().healthcheck().boxed();
+
+        let sink = QuestdbSink {
+            connector,
+            batch_settings: self.batch.into_batcher_settings()?,
+            acker: cx.acker(),
+            encoder: LineEncoder {
+                table: self.table.clone(),
+                default_namespace: self.default_namespace.clone(),
+                tags: self.tags.clone(),
+                quantiles: self.quantiles.clone(),
+            },
+        };
+
+        Ok((VectorSink::from_event_streamsink(sink), healthcheck))
+    }
+
+    fn input(&self) -> Input {
+        Input::metric()
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "questdb"
+    }
+
+    /// The host the sink connects to.
+    fn capabilities(&self) -> Vec<Capability> {
+        self.address
+            .parse::<http::Uri>()
+            .ok()
+            .and_then(|uri| Some(Capability::host(uri.host()?, uri.port_u16())))
+            .into_iter()
+            .collect()
+    }
+
+    fn supports_sandbox(&self) -> bool {
+        true
+    }
+
+    fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
+        Some(&self.acknowledgements)
+    }
+}
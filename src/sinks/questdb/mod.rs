@@ -0,0 +1,71 @@
+//! The `questdb` sink, which writes metrics into QuestDB tables with the InfluxDB line protocol.
+//!
+//! Each metric is written as a row of the table its name, or the configured template, renders to,
+//! which QuestDB creates on the fly. Rows are sent over a persistent TCP connection that's
+//! authenticated, when a key is configured, by signing the challenge of the server.
+
+use snafu::Snafu;
+
+mod auth;
+mod config;
+mod sink;
+
+pub use config::{QuestdbAuth, QuestdbConfig};
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub))]
+pub enum QuestdbSinkError {
+    #[snafu(display("Invalid private key: {}", reason))]
+    InvalidKey { reason: String },
+
+    #[snafu(display("Unable to resolve DNS: {}", source))]
+    Dns { source: crate::dns::DnsError },
+
+    #[snafu(display("No addresses returned."))]
+    NoAddresses,
+
+    #[snafu(display("Connect error: {}", source))]
+    Connect { source: crate::tls::TlsError },
+
+    #[snafu(display("Authentication failed: {}", source))]
+    Authenticate { source: std::io::Error },
+
+    #[snafu(display("Failed to sign the challenge of the server: {}", source))]
+    Sign { source: openssl::error::ErrorStack },
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+    use crate::config::{Capability, SinkConfig, SinkContext};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<QuestdbConfig>();
+    }
+
+    #[test]
+    fn declares_the_server_host() {
+        let config: QuestdbConfig = toml::from_str(indoc! {r#"
+                address = "questdb.example.com:9009"
+            "#})
+        .unwrap();
+        assert_eq!(
+            config.capabilities(),
+            vec![Capability::host("questdb.example.com", Some(9009))]
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_keys() {
+        let config: QuestdbConfig = toml::from_str(indoc! {r#"
+                address = "localhost:9009"
+                auth.key_id = "vector"
+                auth.private_key = "not a key"
+            "#})
+        .unwrap();
+        assert!(config.build(SinkContext::new_test()).await.is_err());
+    }
+}
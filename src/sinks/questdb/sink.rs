@@ -0,0 +1,297 @@
+use std::{collections::HashMap, net::SocketAddr, time::Duration};
+
+use async_trait::async_trait;
+use bytes::BytesMut;
+use futures::{stream::BoxStream, StreamExt};
+use snafu::ResultExt;
+use tokio::{io::AsyncWriteExt, net::TcpStream, time::sleep};
+use vector_common::internal_event::{BytesSent, EventsSent};
+use vector_core::{buffers::Acker, stream::BatcherSettings, ByteSizeOf};
+
+use super::{auth::Authenticator, ConnectSnafu, DnsSnafu, QuestdbSinkError};
+use crate::{
+    dns,
+    event::{Event, EventStatus, Finalizable, Metric},
+    internal_events::{
+        TcpSocketConnectionError, TcpSocketConnectionEstablished, TcpSocketError,
+        TemplateRenderingError,
+    },
+    sinks::{
+        influxdb::{
+            encode_timestamp, influx_line_protocol,
+            metrics::{get_type_and_fields, merge_tags, InfluxMetricNormalize},
+            Field, ProtocolVersion,
+        },
+        util::{encode_namespace, retries::ExponentialBackoff, SinkBuilderExt, StreamSink},
+    },
+    tcp::TcpKeepaliveConfig,
+    template::Template,
+    tls::{MaybeTlsSettings, MaybeTlsStream},
+};
+
+/// Connects to the server, and authenticates the connection when a key is configured.
+#[derive(Clone)]
+pub struct QuestdbConnector {
+    pub host: String,
+    pub port: u16,
+    pub keepalive: Option<TcpKeepaliveConfig>,
+    pub tls: MaybeTlsSettings,
+    pub authenticator: Option<Authenticator>,
+}
+
+impl QuestdbConnector {
+    const fn fresh_backoff() -> ExponentialBackoff {
+        ExponentialBackoff::from_millis(2)
+            .factor(250)
+            .max_delay(Duration::from_secs(60))
+    }
+
+    async fn connect(&self) -> Result<MaybeTlsStream<TcpStream>, QuestdbSinkError> {
+        let ip = dns::Resolver
+            .lookup_ip(self.host.clone())
+            .await
+            .context(DnsSnafu)?
+            .next()
+            .ok_or(QuestdbSinkError::NoAddresses)?;
+
+        let addr = SocketAddr::new(ip, self.port);
+        let mut stream = self
+            .tls
+            .connect(&self.host, &addr)
+            .await
+            .context(ConnectSnafu)?;
+        if let Some(keepalive) = self.keepalive {
+            if let Err(error) = stream.set_keepalive(keepalive) {
+                warn!(message = "Failed configuring TCP keepalive.", %error);
+            }
+        }
+        if let Some(authenticator) = &self.authenticator {
+            authenticator.authenticate(&mut stream).await?;
+        }
+        Ok(stream)
+    }
+
+    async fn connect_backoff(&self) -> MaybeTlsStream<TcpStream> {
+        let mut backoff = Self::fresh_backoff();
+        loop {
+            match self.connect().await {
+                Ok(stream) => {
+                    emit!(TcpSocketConnectionEstablished {
+                        peer_addr: stream.peer_addr().ok(),
+                    });
+                    return stream;
+                }
+                Err(error) => {
+                    emit!(TcpSocketConnectionError { error });
+                    sleep(backoff.next().unwrap()).await;
+                }
+            }
+        }
+    }
+
+    pub async fn healthcheck(self) -> crate::Result<()> {
+        self.connect().await.map(|_| ()).map_err(Into::into)
+    }
+}
+
+/// Serializes metrics as rows of the InfluxDB line protocol.
+pub struct LineEncoder {
+    pub table: Option<Template>,
+    pub default_namespace: Option<String>,
+    pub tags: Option<HashMap<String, String>>,
+    pub quantiles: Vec<f64>,
+}
+
+impl LineEncoder {
+    /// The table the metric is written to, or `None` when the template fails to render.
+    fn table(&self, metric: &Metric) -> Option<String> {
+        match &self.table {
+            Some(table) => table
+                .render_string(metric)
+                .map_err(|error| {
+                    emit!(TemplateRenderingError {
+                        error,
+                        field: Some("table"),
+                        drop_event: true,
+                    });
+                })
+                .ok(),
+            None => Some(encode_namespace(
+                metric.namespace().or(self.default_namespace.as_deref()),
+                '_',
+                metric.name(),
+            )),
+        }
+    }
+
+    pub fn encode(&self, metric: &Metric, output: &mut BytesMut) {
+        let table = match self.table(metric) {
+            Some(table) => table,
+            None => return,
+        };
+        let mut tags = merge_tags(metric, self.tags.as_ref()).unwrap_or_default();
+        let (metric_type, fields) = get_type_and_fields(metric.value(), &self.quantiles);
+        tags.insert("metric_type".to_owned(), metric_type.to_owned());
+
+        let tags = tags
+            .into_iter()
+            .map(|(key, value)| (column_name(&key), value))
+            .collect();
+        let fields = fields.map(|fields| {
+            fields
+                .into_iter()
+                .map(|(key, value)| (column_name(&key), value))
+                .collect::<HashMap<String, Field>>()
+        });
+        if let Err(error) = influx_line_protocol(
+            ProtocolVersion::V1,
+            &table,
+            Some(tags),
+            fields,
+            encode_timestamp(metric.timestamp()),
+            output,
+        ) {
+            warn!(message = "Failed to encode event; dropping event.", %error, internal_log_rate_secs = 30);
+        }
+    }
+}
+
+/// Replaces the characters QuestDB doesn't allow in the names of columns, such as the dots of the
+/// `bucket_0.5` fields of histograms.
+fn column_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '.' | '-' | '?' | ',' | '\'' | '"' | '\\' | '/' | ':' | '(' | ')' | '+' | '*' | '%'
+            | '~' | '\r' | '\n' | '\0' => '_',
+            c => c,
+        })
+        .collect()
+}
+
+pub struct QuestdbSink {
+    pub connector: QuestdbConnector,
+    pub batch_settings: BatcherSettings,
+    pub acker: Acker,
+    pub encoder: LineEncoder,
+}
+
+#[async_trait]
+impl StreamSink<Event> for QuestdbSink {
+    async fn run(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let mut batches = input
+            .map(Event::into_metric)
+            .normalized_with_default::<InfluxMetricNormalize>()
+            .batched(self.batch_settings.into_byte_size_config());
+
+        // The connection is kept open across batches, and reopened when writing to it fails.
+        let mut connection = None;
+        while let Some(mut metrics) = batches.next().await {
+            let finalizers = metrics.take_finalizers();
+            let events_count = metrics.len();
+            let events_byte_size = metrics.size_of();
+
+            let mut lines = BytesMut::new();
+            for metric in &metrics {
+                self.encoder.encode(metric, &mut lines);
+            }
+
+            loop {
+                if connection.is_none() {
+                    connection = Some(self.connector.connect_backoff().await);
+                }
+                let stream = connection.as_mut().expect("connected above");
+                let result = match stream.write_all(&lines).await {
+                    Ok(()) => stream.flush().await,
+                    Err(error) => Err(error),
+                };
+                match result {
+                    Ok(()) => break,
+                    Err(error) => {
+                        emit!(TcpSocketError { error });
+                        connection = None;
+                    }
+                }
+            }
+
+            finalizers.update_status(EventStatus::Delivered);
+            self.acker.ack(events_count);
+            emit!(EventsSent {
+                count: events_count,
+                byte_size: events_byte_size,
+                output: None,
+            });
+            emit!(BytesSent {
+                byte_size: lines.len(),
+                protocol: "tcp",
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use chrono::{TimeZone, Utc};
+    use vector_core::event::{MetricKind, MetricValue, StatisticKind};
+
+    use super::*;
+
+    fn encoder(table: Option<&str>) -> LineEncoder {
+        LineEncoder {
+            table: table.map(|table| Template::try_from(table).unwrap()),
+            default_namespace: Some("vector".into()),
+            tags: None,
+            quantiles: vec![0.5],
+        }
+    }
+
+    fn encode(encoder: &LineEncoder, metric: &Metric) -> String {
+        let mut output = BytesMut::new();
+        encoder.encode(metric, &mut output);
+        String::from_utf8(output.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn encodes_rows_into_the_table_of_the_metric() {
+        let metric = Metric::new(
+            "requests",
+            MetricKind::Absolute,
+            MetricValue::Gauge { value: 42.0 },
+        )
+        .with_tags(Some(BTreeMap::from([(
+            "host.name".to_owned(),
+            "a".to_owned(),
+        )])))
+        .with_timestamp(Some(Utc.ymd(2022, 6, 1).and_hms(12, 0, 0)));
+
+        assert_eq!(
+            encode(&encoder(None), &metric),
+            "vector_requests,host_name=a,metric_type=gauge value=42 1654084800000000000\n"
+        );
+        assert_eq!(
+            encode(&encoder(Some("metrics_{{ tags.host.name }}")), &metric),
+            "metrics_a,host_name=a,metric_type=gauge value=42 1654084800000000000\n"
+        );
+        assert_eq!(encode(&encoder(Some("metrics_{{ missing }}")), &metric), "");
+    }
+
+    #[test]
+    fn renames_the_fields_of_distributions() {
+        let metric = Metric::new(
+            "latency",
+            MetricKind::Absolute,
+            MetricValue::Distribution {
+                samples: vector_core::samples![1.0 => 1],
+                statistic: StatisticKind::Summary,
+            },
+        )
+        .with_timestamp(Some(Utc.ymd(2022, 6, 1).and_hms(12, 0, 0)));
+
+        let line = encode(&encoder(None), &metric);
+        assert!(line.starts_with("vector_latency,metric_type=distribution "));
+        assert!(line.contains("quantile_0_50=1"));
+    }
+}
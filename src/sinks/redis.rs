@@ -6,7 +6,7 @@ use std::{
 use bytes::{Bytes, BytesMut};
 use codecs::{encoding::SerializerConfig, JsonSerializerConfig, TextSerializerConfig};
 use futures::{future::BoxFuture, stream, FutureExt, SinkExt, StreamExt};
-use redis::{aio::ConnectionManager, RedisError, RedisResult};
+use redis::{aio::ConnectionManager, Cmd, RedisError, RedisResult, Value};
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 use tokio_util::codec::Encoder as _;
@@ -32,6 +32,10 @@ use crate::{
     template::{Template, TemplateParseError},
 };
 
+mod cluster;
+
+pub use cluster::ClusterConnection;
+
 inventory::submit! {
     SinkDescription::new::<RedisSinkConfig>("redis")
 }
@@ -51,6 +55,7 @@ pub enum DataTypeConfig {
     #[derivative(Default)]
     List,
     Channel,
+    Stream,
 }
 
 #[derive(Copy, Clone, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
@@ -59,13 +64,35 @@ pub struct ListOption {
     method: Method,
 }
 
-#[derive(Copy, Clone, Debug, Derivative, Deserialize, Serialize)]
+#[derive(Clone, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct StreamOption {
+    /// The field of the stream entries the encoded events are set to.
+    #[serde(default = "default_stream_field")]
+    #[derivative(Default(value = "default_stream_field()"))]
+    field: String,
+    /// The length the streams are trimmed to when entries are added, if any.
+    maxlen: Option<usize>,
+    /// Whether the streams are trimmed to about `maxlen` entries, which Redis does more
+    /// efficiently, rather than exactly.
+    #[serde(default = "crate::serde::default_true")]
+    #[derivative(Default(value = "true"))]
+    approximate: bool,
+}
+
+fn default_stream_field() -> String {
+    "message".into()
+}
+
+#[derive(Clone, Debug, Derivative, Deserialize, Serialize)]
 #[derivative(Default)]
 #[serde(rename_all = "lowercase")]
 pub enum DataType {
     #[derivative(Default)]
     List(Method),
     Channel,
+    Stream(StreamOption),
 }
 
 #[derive(Copy, Clone, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
@@ -115,7 +142,13 @@ pub struct RedisSinkConfig {
     data_type: DataTypeConfig,
     #[serde(alias = "list")]
     list_option: Option<ListOption>,
+    #[serde(alias = "stream")]
+    stream_option: Option<StreamOption>,
     url: String,
+    /// Whether `url` is a node of a Redis Cluster, the commands being routed to the nodes owning
+    /// the slots of their keys.
+    #[serde(default)]
+    cluster: bool,
     key: String,
     #[serde(default)]
     batch: BatchConfig<RedisDefaultBatchSettings>,
@@ -155,7 +188,10 @@ impl SinkConfig for RedisSinkConfig {
         if self.key.is_empty() {
             return Err("`key` cannot be empty.".into());
         }
-        let conn = self.build_client().await.context(RedisCreateFailedSnafu)?;
+        let conn = self
+            .build_connection()
+            .await
+            .context(RedisCreateFailedSnafu)?;
         let healthcheck = RedisSinkConfig::healthcheck(conn.clone()).boxed();
         let sink = self.new(conn, cx)?;
         Ok((sink, healthcheck))
@@ -175,11 +211,7 @@ impl SinkConfig for RedisSinkConfig {
 }
 
 impl RedisSinkConfig {
-    pub fn new(
-        &self,
-        conn: ConnectionManager,
-        cx: SinkContext,
-    ) -> crate::Result<super::VectorSink> {
+    pub fn new(&self, conn: RedisConnection, cx: SinkContext) -> crate::Result<super::VectorSink> {
         let request = self.request.unwrap_with(&TowerRequestConfig {
             concurrency: Concurrency::Fixed(1),
             ..Default::default()
@@ -196,6 +228,9 @@ impl RedisSinkConfig {
         let data_type = match self.data_type {
             DataTypeConfig::Channel => DataType::Channel,
             DataTypeConfig::List => DataType::List(method.unwrap_or_default()),
+            DataTypeConfig::Stream => {
+                DataType::Stream(self.stream_option.clone().unwrap_or_default())
+            }
         };
 
         let batch = self.batch.into_batch_settings()?;
@@ -227,14 +262,33 @@ impl RedisSinkConfig {
         conn
     }
 
-    async fn healthcheck(mut conn: ConnectionManager) -> crate::Result<()> {
-        redis::cmd("PING")
-            .query_async(&mut conn)
-            .await
-            .map_err(Into::into)
+    async fn build_connection(&self) -> RedisResult<RedisConnection> {
+        if self.cluster {
+            let client = redis::Client::open(self.url.as_str())?;
+            let seed = client.get_connection_info().clone();
+            Ok(RedisConnection::Cluster(ClusterConnection::new(seed)))
+        } else {
+            self.build_client().await.map(RedisConnection::Single)
+        }
+    }
+
+    async fn healthcheck(conn: RedisConnection) -> crate::Result<()> {
+        match conn {
+            RedisConnection::Single(mut conn) => redis::cmd("PING")
+                .query_async(&mut conn)
+                .await
+                .map_err(Into::into),
+            RedisConnection::Cluster(conn) => conn.healthcheck().await.map_err(Into::into),
+        }
     }
 }
 
+#[derive(Clone)]
+pub enum RedisConnection {
+    Single(ConnectionManager),
+    Cluster(ClusterConnection),
+}
+
 #[derive(Debug, Clone)]
 struct RedisKvEntry {
     key: String,
@@ -311,10 +365,41 @@ impl RetryLogic for RedisRetryLogic {
 
 #[derive(Clone)]
 pub struct RedisSink {
-    conn: ConnectionManager,
+    conn: RedisConnection,
     data_type: DataType,
 }
 
+fn command(data_type: &DataType, kv: &RedisKvEntry) -> Cmd {
+    match data_type {
+        DataType::List(Method::LPush) => Cmd::lpush(&kv.key, kv.value.as_ref()),
+        DataType::List(Method::RPush) => Cmd::rpush(&kv.key, kv.value.as_ref()),
+        DataType::Channel => Cmd::publish(&kv.key, kv.value.as_ref()),
+        DataType::Stream(option) => {
+            let mut cmd = redis::cmd("XADD");
+            cmd.arg(&kv.key);
+            if let Some(maxlen) = option.maxlen {
+                cmd.arg("MAXLEN");
+                if option.approximate {
+                    cmd.arg("~");
+                }
+                cmd.arg(maxlen);
+            }
+            cmd.arg("*").arg(&option.field).arg(kv.value.as_ref());
+            cmd
+        }
+    }
+}
+
+/// Whether the command was successful, from its reply: the length of the list for `LPUSH` and
+/// `RPUSH`, the number of receivers for `PUBLISH`, and the ID of the entry for `XADD`.
+const fn is_successful_reply(reply: &Value) -> bool {
+    match reply {
+        Value::Int(value) => *value != 0,
+        Value::Data(_) | Value::Status(_) | Value::Okay => true,
+        Value::Nil | Value::Bulk(_) => false,
+    }
+}
+
 impl Service<Vec<RedisKvEntry>> for RedisSink {
     type Response = Vec<bool>;
     type Error = RedisError;
@@ -326,42 +411,34 @@ impl Service<Vec<RedisKvEntry>> for RedisSink {
 
     fn call(&mut self, kvs: Vec<RedisKvEntry>) -> Self::Future {
         let count = kvs.len();
-        let mut byte_size = 0;
-
-        let mut conn = self.conn.clone();
-        let mut pipe = redis::pipe();
-
-        for kv in kvs {
-            byte_size += kv.encoded_length();
-            match self.data_type {
-                DataType::List(method) => match method {
-                    Method::LPush => {
-                        if count > 1 {
-                            pipe.atomic().lpush(kv.key, kv.value.as_ref());
-                        } else {
-                            pipe.lpush(kv.key, kv.value.as_ref());
-                        }
-                    }
-                    Method::RPush => {
-                        if count > 1 {
-                            pipe.atomic().rpush(kv.key, kv.value.as_ref());
-                        } else {
-                            pipe.rpush(kv.key, kv.value.as_ref());
-                        }
-                    }
-                },
-                DataType::Channel => {
-                    if count > 1 {
-                        pipe.atomic().publish(kv.key, kv.value.as_ref());
-                    } else {
-                        pipe.publish(kv.key, kv.value.as_ref());
-                    }
+        let byte_size = kvs.iter().map(EncodedLength::encoded_length).sum();
+        let conn = self.conn.clone();
+
+        let query = match conn {
+            RedisConnection::Single(mut conn) => {
+                let mut pipe = redis::pipe();
+                if count > 1 {
+                    pipe.atomic();
+                }
+                for kv in &kvs {
+                    pipe.add_command(command(&self.data_type, kv));
                 }
+                async move { pipe.query_async::<_, Vec<Value>>(&mut conn).await }.boxed()
             }
-        }
+            // The keys of a transaction must share a slot, so the commands are only pipelined.
+            RedisConnection::Cluster(conn) => {
+                let commands = kvs
+                    .iter()
+                    .map(|kv| (kv.key.clone().into_bytes(), command(&self.data_type, kv)))
+                    .collect();
+                async move { conn.query(commands).await }.boxed()
+            }
+        };
 
         Box::pin(async move {
-            let result: RedisPipeResult = pipe.query_async(&mut conn).await;
+            let result: RedisPipeResult = query
+                .await
+                .map(|replies| replies.iter().map(is_successful_reply).collect());
             match &result {
                 Ok(res) => {
                     if res.is_successful() {
@@ -447,6 +524,58 @@ mod tests {
         let map: HashMap<String, String> = serde_json::from_slice(&result[..]).unwrap();
         assert!(!map.contains_key("key"));
     }
+
+    #[test]
+    fn redis_stream_command() {
+        let kv = RedisKvEntry {
+            key: "events".into(),
+            value: Bytes::from("hello"),
+        };
+
+        let option = StreamOption::default();
+        assert_eq!(
+            command(&DataType::Stream(option.clone()), &kv).get_packed_command(),
+            redis::cmd("XADD")
+                .arg("events")
+                .arg("*")
+                .arg("message")
+                .arg("hello")
+                .get_packed_command()
+        );
+
+        let option = StreamOption {
+            maxlen: Some(100),
+            ..option
+        };
+        assert_eq!(
+            command(&DataType::Stream(option.clone()), &kv).get_packed_command(),
+            redis::cmd("XADD")
+                .arg("events")
+                .arg("MAXLEN")
+                .arg("~")
+                .arg(100)
+                .arg("*")
+                .arg("message")
+                .arg("hello")
+                .get_packed_command()
+        );
+
+        let option = StreamOption {
+            approximate: false,
+            ..option
+        };
+        assert_eq!(
+            command(&DataType::Stream(option), &kv).get_packed_command(),
+            redis::cmd("XADD")
+                .arg("events")
+                .arg("MAXLEN")
+                .arg(100)
+                .arg("*")
+                .arg("message")
+                .arg("hello")
+                .get_packed_command()
+        );
+    }
 }
 
 #[cfg(feature = "redis-integration-tests")]
@@ -478,12 +607,14 @@ mod integration_tests {
 
         let cnf = RedisSinkConfig {
             url: redis_server(),
+            cluster: false,
             key: key.clone(),
             encoding: EncodingConfig::from(Encoding::Json).into(),
             data_type: DataTypeConfig::List,
             list_option: Some(ListOption {
                 method: Method::LPush,
             }),
+            stream_option: None,
             batch: BatchConfig::default(),
             request: TowerRequestConfig {
                 rate_limit_num: Option::from(u64::MAX),
@@ -496,7 +627,7 @@ mod integration_tests {
         let conn = cnf.build_client().await.unwrap();
         let cx = SinkContext::new_test();
 
-        let sink = cnf.new(conn, cx).unwrap();
+        let sink = cnf.new(RedisConnection::Single(conn), cx).unwrap();
 
         let mut events: Vec<Event> = Vec::new();
         for i in 0..num_events {
@@ -537,12 +668,14 @@ mod integration_tests {
 
         let cnf = RedisSinkConfig {
             url: redis_server(),
+            cluster: false,
             key: key.clone(),
             encoding: EncodingConfig::from(Encoding::Json).into(),
             data_type: DataTypeConfig::List,
             list_option: Some(ListOption {
                 method: Method::RPush,
             }),
+            stream_option: None,
             batch: BatchConfig::default(),
             request: TowerRequestConfig {
                 rate_limit_num: Option::from(u64::MAX),
@@ -555,7 +688,7 @@ mod integration_tests {
         let conn = cnf.build_client().await.unwrap();
         let cx = SinkContext::new_test();
 
-        let sink = cnf.new(conn, cx).unwrap();
+        let sink = cnf.new(RedisConnection::Single(conn), cx).unwrap();
         let mut events: Vec<Event> = Vec::new();
         for i in 0..num_events {
             let s: String = i.to_string();
@@ -583,6 +716,67 @@ mod integration_tests {
         }
     }
 
+    #[tokio::test]
+    async fn redis_sink_stream() {
+        trace_init();
+
+        let key = format!("test-{}", random_string(10));
+        debug!("Test key name: {}.", key);
+        let num_events = 1000;
+
+        let cnf = RedisSinkConfig {
+            url: redis_server(),
+            cluster: false,
+            key: key.clone(),
+            encoding: EncodingConfig::from(Encoding::Text).into(),
+            data_type: DataTypeConfig::Stream,
+            list_option: None,
+            stream_option: Some(StreamOption {
+                maxlen: Some(100),
+                approximate: false,
+                ..Default::default()
+            }),
+            batch: BatchConfig::default(),
+            request: TowerRequestConfig {
+                rate_limit_num: Option::from(u64::MAX),
+                ..Default::default()
+            },
+            acknowledgements: Default::default(),
+        };
+
+        let (input, events) = random_lines_with_stream(100, num_events, None);
+        let cx = SinkContext::new_test();
+        let (sink, _healthcheck) = cnf.build(cx).await.unwrap();
+        sink.run(events).await.unwrap();
+
+        let client = redis::Client::open(redis_server()).unwrap();
+        let mut conn = client.get_async_connection().await.unwrap();
+
+        let len: usize = redis::cmd("XLEN")
+            .arg(&key)
+            .query_async(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(len, 100);
+
+        // The entries are an ID and the `message` field with the encoded event.
+        let entries: Vec<(String, (String, String))> = redis::cmd("XRANGE")
+            .arg(&key)
+            .arg("-")
+            .arg("+")
+            .query_async(&mut conn)
+            .await
+            .unwrap();
+        let messages = entries
+            .into_iter()
+            .map(|(_id, (field, message))| {
+                assert_eq!(field, "message");
+                message
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(messages, input[num_events - 100..]);
+    }
+
     #[tokio::test]
     async fn redis_sink_channel() {
         trace_init();
@@ -611,10 +805,12 @@ mod integration_tests {
 
         let cnf = RedisSinkConfig {
             url: redis_server(),
+            cluster: false,
             key: key.clone(),
             encoding: EncodingConfig::from(Encoding::Json).into(),
             data_type: DataTypeConfig::Channel,
             list_option: None,
+            stream_option: None,
             batch: BatchConfig::default(),
             request: TowerRequestConfig {
                 rate_limit_num: Option::from(u64::MAX),
@@ -627,7 +823,7 @@ mod integration_tests {
         let conn = cnf.build_client().await.unwrap();
         let cx = SinkContext::new_test();
 
-        let sink = cnf.new(conn, cx).unwrap();
+        let sink = cnf.new(RedisConnection::Single(conn), cx).unwrap();
         let (_input, events) = random_lines_with_stream(100, num_events, None);
         sink.run(events).await.unwrap();
 
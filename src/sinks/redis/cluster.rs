@@ -0,0 +1,227 @@
+//! Routes the commands to the nodes of a Redis Cluster, each of them owning a range of the slots
+//! the keys are hashed to.
+
+use std::{collections::HashMap, sync::Arc};
+
+use futures::future;
+use redis::{
+    aio::ConnectionManager, Cmd, ConnectionAddr, ConnectionInfo, ErrorKind, Pipeline, RedisError,
+    RedisResult, Value,
+};
+use tokio::sync::Mutex;
+
+const SLOT_COUNT: u16 = 16384;
+
+/// The slot of the key, hashing only its hash tag, the part between its first `{` and the
+/// following `}`, when there is one and it isn't empty, so that related keys share a slot.
+pub(super) fn key_slot(key: &[u8]) -> u16 {
+    let hashed = key
+        .iter()
+        .position(|byte| *byte == b'{')
+        .and_then(|open| {
+            let tag = &key[open + 1..];
+            tag.iter()
+                .position(|byte| *byte == b'}')
+                .filter(|close| *close > 0)
+                .map(|close| &tag[..close])
+        })
+        .unwrap_or(key);
+    crc16(hashed) % SLOT_COUNT
+}
+
+/// The CRC16 variant used by Redis Cluster, CCITT with a zero initial value (XMODEM).
+fn crc16(bytes: &[u8]) -> u16 {
+    bytes.iter().fold(0, |crc, byte| {
+        (0..8).fold(crc ^ (u16::from(*byte) << 8), |crc, _| {
+            if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            }
+        })
+    })
+}
+
+#[derive(Default)]
+struct ClusterState {
+    /// The last slot of each range, sorted, with the address of the primary node owning it.
+    slots: Vec<(u16, (String, u16))>,
+    connections: HashMap<(String, u16), ConnectionManager>,
+}
+
+impl ClusterState {
+    fn node(&self, slot: u16) -> Option<&(String, u16)> {
+        let index = self.slots.partition_point(|(last, _)| *last < slot);
+        self.slots.get(index).map(|(_, node)| node)
+    }
+}
+
+/// The connections to the primary nodes of a cluster. The slots are fetched from the seed node on
+/// the first request, and fetched again after a request fails, such as when a slot was migrated.
+#[derive(Clone)]
+pub struct ClusterConnection {
+    seed: ConnectionInfo,
+    state: Arc<Mutex<ClusterState>>,
+}
+
+impl ClusterConnection {
+    pub fn new(seed: ConnectionInfo) -> Self {
+        Self {
+            seed,
+            state: Arc::default(),
+        }
+    }
+
+    async fn connect(&self, host: &str, port: u16) -> RedisResult<ConnectionManager> {
+        let mut info = self.seed.clone();
+        info.addr = match info.addr {
+            ConnectionAddr::TcpTls { insecure, .. } => ConnectionAddr::TcpTls {
+                host: host.to_owned(),
+                port,
+                insecure,
+            },
+            _ => ConnectionAddr::Tcp(host.to_owned(), port),
+        };
+        redis::Client::open(info)?
+            .get_tokio_connection_manager()
+            .await
+    }
+
+    /// Fetches the slots owned by the primary nodes from the seed node.
+    async fn refresh(&self, state: &mut ClusterState) -> RedisResult<()> {
+        let mut seed = redis::Client::open(self.seed.clone())?
+            .get_multiplexed_tokio_connection()
+            .await?;
+        let reply: Value = redis::cmd("CLUSTER")
+            .arg("SLOTS")
+            .query_async(&mut seed)
+            .await?;
+
+        let mut slots = parse_slots(reply)?;
+        slots.sort_unstable_by_key(|(last, _)| *last);
+        state.slots = slots;
+        Ok(())
+    }
+
+    pub async fn healthcheck(&self) -> RedisResult<()> {
+        let mut state = self.state.lock().await;
+        self.refresh(&mut state).await
+    }
+
+    /// Sends the commands, each along with its key, in a pipeline to each of the nodes owning
+    /// their slots, and returns all their replies.
+    pub async fn query(&self, commands: Vec<(Vec<u8>, Cmd)>) -> RedisResult<Vec<Value>> {
+        let mut state = self.state.lock().await;
+        if state.slots.is_empty() {
+            self.refresh(&mut state).await?;
+        }
+
+        let mut pipelines = HashMap::<(String, u16), Pipeline>::new();
+        for (key, cmd) in commands {
+            let node = state.node(key_slot(&key)).cloned().ok_or_else(|| {
+                RedisError::from((ErrorKind::ClusterDown, "No node owns the slot of the key"))
+            })?;
+            pipelines
+                .entry(node)
+                .or_insert_with(redis::pipe)
+                .add_command(cmd);
+        }
+
+        let mut requests = Vec::with_capacity(pipelines.len());
+        for (node, pipeline) in pipelines {
+            let connection = match state.connections.get(&node) {
+                Some(connection) => connection.clone(),
+                None => {
+                    let connection = self.connect(&node.0, node.1).await?;
+                    state.connections.insert(node, connection.clone());
+                    connection
+                }
+            };
+            requests.push(async move {
+                let mut connection = connection;
+                pipeline.query_async::<_, Vec<Value>>(&mut connection).await
+            });
+        }
+
+        let results = future::join_all(requests).await;
+        let mut replies = Vec::new();
+        for result in results {
+            match result {
+                Ok(values) => replies.extend(values),
+                Err(error) => {
+                    // The slots may have moved, they're fetched again before the request is retried.
+                    state.slots.clear();
+                    return Err(error);
+                }
+            }
+        }
+        Ok(replies)
+    }
+}
+
+/// Parses the reply to `CLUSTER SLOTS`, an array of slot ranges with their first and last slots
+/// followed by the address of the primary node, and then of the replicas.
+fn parse_slots(reply: Value) -> RedisResult<Vec<(u16, (String, u16))>> {
+    let invalid = || RedisError::from((ErrorKind::TypeError, "Invalid reply to CLUSTER SLOTS"));
+
+    let ranges = match reply {
+        Value::Bulk(ranges) => ranges,
+        _ => return Err(invalid()),
+    };
+    ranges
+        .into_iter()
+        .map(|range| {
+            let range = match range {
+                Value::Bulk(range) if range.len() >= 3 => range,
+                _ => return Err(invalid()),
+            };
+            let last = redis::from_redis_value::<u16>(&range[1])?;
+            let node = match &range[2] {
+                Value::Bulk(node) if node.len() >= 2 => node,
+                _ => return Err(invalid()),
+            };
+            let host = redis::from_redis_value::<String>(&node[0])?;
+            let port = redis::from_redis_value::<u16>(&node[1])?;
+            Ok((last, (host, port)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_keys_to_slots() {
+        assert_eq!(crc16(b"123456789"), 0x31c3);
+        assert_eq!(key_slot(b"foo"), 12182);
+        assert_eq!(key_slot(b"{user1000}.following"), key_slot(b"user1000"));
+        // Empty hash tags are not hash tags.
+        assert_eq!(key_slot(b"foo{}{bar}"), crc16(b"foo{}{bar}") % SLOT_COUNT);
+    }
+
+    #[test]
+    fn routes_slots_to_nodes() {
+        let node = |port| Value::Bulk(vec![Value::Data(b"10.0.0.1".to_vec()), Value::Int(port)]);
+        let reply = Value::Bulk(vec![
+            Value::Bulk(vec![Value::Int(5461), Value::Int(16383), node(7001)]),
+            Value::Bulk(vec![
+                Value::Int(0),
+                Value::Int(5460),
+                node(7000),
+                node(7002),
+            ]),
+        ]);
+        let mut slots = parse_slots(reply).unwrap();
+        slots.sort_unstable_by_key(|(last, _)| *last);
+        let state = ClusterState {
+            slots,
+            connections: HashMap::new(),
+        };
+
+        assert_eq!(state.node(0).unwrap().1, 7000);
+        assert_eq!(state.node(5460).unwrap().1, 7000);
+        assert_eq!(state.node(5461).unwrap().1, 7001);
+        assert_eq!(state.node(16383).unwrap().1, 7001);
+    }
+}
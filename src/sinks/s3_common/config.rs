@@ -1,7 +1,7 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, num::NonZeroU32};
 
 use aws_sdk_s3::error::PutObjectError;
-use aws_sdk_s3::model::{ObjectCannedAcl, ServerSideEncryption, StorageClass};
+use aws_sdk_s3::model::{ObjectCannedAcl, ObjectLockMode, ServerSideEncryption, StorageClass};
 use aws_sdk_s3::Client as S3Client;
 use aws_smithy_client::SdkError;
 use futures::FutureExt;
@@ -29,6 +29,11 @@ pub struct S3Options {
     pub server_side_encryption: Option<S3ServerSideEncryption>,
     pub ssekms_key_id: Option<String>,
     pub storage_class: Option<S3StorageClass>,
+    pub object_lock_mode: Option<S3ObjectLockMode>,
+    /// The number of days the objects are retained for, from the time they're written, with
+    /// `object_lock_mode`.
+    pub object_lock_retain_days: Option<NonZeroU32>,
+    pub object_lock_legal_hold: Option<bool>,
     pub tags: Option<BTreeMap<String, String>>,
     pub content_encoding: Option<String>, // inherit from compression value
     pub content_type: Option<String>,     // default `text/x-log`
@@ -45,6 +50,7 @@ pub enum S3StorageClass {
     StandardIa,
     OnezoneIa,
     Glacier,
+    GlacierIr,
     DeepArchive,
 }
 
@@ -57,11 +63,28 @@ impl From<S3StorageClass> for StorageClass {
             S3StorageClass::StandardIa => Self::StandardIa,
             S3StorageClass::OnezoneIa => Self::OnezoneIa,
             S3StorageClass::Glacier => Self::Glacier,
+            S3StorageClass::GlacierIr => Self::GlacierIr,
             S3StorageClass::DeepArchive => Self::DeepArchive,
         }
     }
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum S3ObjectLockMode {
+    Governance,
+    Compliance,
+}
+
+impl From<S3ObjectLockMode> for ObjectLockMode {
+    fn from(x: S3ObjectLockMode) -> Self {
+        match x {
+            S3ObjectLockMode::Governance => Self::Governance,
+            S3ObjectLockMode::Compliance => Self::Compliance,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Serialize)]
 pub enum S3ServerSideEncryption {
     #[serde(rename = "AES256")]
@@ -182,6 +205,7 @@ mod tests {
         for &(name, storage_class) in &[
             ("DEEP_ARCHIVE", S3StorageClass::DeepArchive),
             ("GLACIER", S3StorageClass::Glacier),
+            ("GLACIER_IR", S3StorageClass::GlacierIr),
             ("INTELLIGENT_TIERING", S3StorageClass::IntelligentTiering),
             ("ONEZONE_IA", S3StorageClass::OnezoneIa),
             ("REDUCED_REDUNDANCY", S3StorageClass::ReducedRedundancy),
@@ -1,10 +1,13 @@
 use std::task::{Context, Poll};
 
 use aws_sdk_s3::error::PutObjectError;
-use aws_sdk_s3::types::{ByteStream, SdkError};
+use aws_sdk_s3::model::ObjectLockLegalHoldStatus;
+use aws_sdk_s3::types::{ByteStream, DateTime, SdkError};
 use aws_sdk_s3::Client as S3Client;
 use bytes::Bytes;
+use chrono::Utc;
 use futures::future::BoxFuture;
+use http::StatusCode;
 use md5::Digest;
 use tower::Service;
 use tracing::Instrument;
@@ -16,6 +19,7 @@ use vector_core::{
 };
 
 use super::config::S3Options;
+use crate::sinks::util::manifest::{ManifestEntry, ManifestRequest, ObjectReader};
 
 #[derive(Debug, Clone)]
 pub struct S3Request {
@@ -38,6 +42,37 @@ impl Finalizable for S3Request {
     }
 }
 
+impl ManifestRequest for S3Request {
+    fn manifest_entry(&self) -> ManifestEntry {
+        ManifestEntry {
+            key: self.metadata.partition_key.clone(),
+            records: self.metadata.count,
+            bytes: self.body.len(),
+        }
+    }
+
+    fn manifest_request(&self, key: String, body: Bytes) -> Self {
+        S3Request {
+            body,
+            bucket: self.bucket.clone(),
+            metadata: S3Metadata {
+                partition_key: key,
+                count: 0,
+                byte_size: 0,
+                finalizers: EventFinalizers::default(),
+            },
+            content_encoding: None,
+            options: S3Options {
+                content_encoding: None,
+                content_type: Some("application/json".to_owned()),
+                // The manifests are read as soon as they're written, they can't be archived.
+                storage_class: None,
+                ..self.options.clone()
+            },
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct S3Metadata {
     pub partition_key: String,
@@ -116,6 +151,18 @@ impl Service<S3Request> for S3Service {
             }
             tagging.finish()
         });
+        let object_lock_retain_until_date = options.object_lock_retain_days.map(|days| {
+            DateTime::from_secs(
+                (Utc::now() + chrono::Duration::days(days.get().into())).timestamp(),
+            )
+        });
+        let object_lock_legal_hold_status = options.object_lock_legal_hold.map(|hold| {
+            if hold {
+                ObjectLockLegalHoldStatus::On
+            } else {
+                ObjectLockLegalHoldStatus::Off
+            }
+        });
         let count = request.metadata.count;
         let events_byte_size = request.metadata.byte_size;
 
@@ -137,6 +184,9 @@ impl Service<S3Request> for S3Service {
                 .set_server_side_encryption(options.server_side_encryption.map(Into::into))
                 .set_ssekms_key_id(options.ssekms_key_id)
                 .set_storage_class(options.storage_class.map(Into::into))
+                .set_object_lock_mode(options.object_lock_mode.map(Into::into))
+                .set_object_lock_retain_until_date(object_lock_retain_until_date)
+                .set_object_lock_legal_hold_status(object_lock_legal_hold_status)
                 .set_tagging(tagging)
                 .content_md5(content_md5);
 
@@ -150,6 +200,28 @@ impl Service<S3Request> for S3Service {
     }
 }
 
+impl ObjectReader<S3Request> for S3Service {
+    fn read_object(
+        &self,
+        request: &S3Request,
+        key: String,
+    ) -> BoxFuture<'static, crate::Result<Option<Bytes>>> {
+        let request = self.client.get_object().bucket(&request.bucket).key(key);
+
+        Box::pin(async move {
+            match request.send().in_current_span().await {
+                Ok(output) => Ok(Some(output.body.collect().await?.into_bytes())),
+                Err(SdkError::ServiceError { raw, .. })
+                    if raw.http().status() == StatusCode::NOT_FOUND =>
+                {
+                    Ok(None)
+                }
+                Err(error) => Err(error.into()),
+            }
+        })
+    }
+}
+
 fn bytes_to_bytestream(buf: Bytes) -> ByteStream {
     ByteStream::from(buf)
 }
@@ -1,4 +1,4 @@
-use std::{fmt, num::NonZeroUsize};
+use std::{fmt, hash::Hash, num::NonZeroUsize};
 
 use async_trait::async_trait;
 use futures::stream::BoxStream;
@@ -7,6 +7,7 @@ use tower::Service;
 use vector_core::{
     buffers::{Ackable, Acker},
     event::Finalizable,
+    partition::Partitioner,
     sink::StreamSink,
     stream::{BatcherSettings, DriverResponse},
 };
@@ -17,20 +18,20 @@ use crate::{
     sinks::util::{partitioner::KeyPartitioner, RequestBuilder, SinkBuilderExt},
 };
 
-pub struct S3Sink<Svc, RB> {
+pub struct S3Sink<Svc, RB, P = KeyPartitioner> {
     acker: Acker,
     service: Svc,
     request_builder: RB,
-    partitioner: KeyPartitioner,
+    partitioner: P,
     batcher_settings: BatcherSettings,
 }
 
-impl<Svc, RB> S3Sink<Svc, RB> {
+impl<Svc, RB, P> S3Sink<Svc, RB, P> {
     pub fn new(
         cx: SinkContext,
         service: Svc,
         request_builder: RB,
-        partitioner: KeyPartitioner,
+        partitioner: P,
         batcher_settings: BatcherSettings,
     ) -> Self {
         Self {
@@ -43,15 +44,17 @@ impl<Svc, RB> S3Sink<Svc, RB> {
     }
 }
 
-impl<Svc, RB> S3Sink<Svc, RB>
+impl<Svc, RB, P, K> S3Sink<Svc, RB, P>
 where
     Svc: Service<RB::Request> + Send + 'static,
     Svc::Future: Send + 'static,
     Svc::Response: DriverResponse + Send + 'static,
     Svc::Error: fmt::Debug + Into<crate::Error> + Send,
-    RB: RequestBuilder<(String, Vec<Event>)> + Send + Sync + 'static,
+    RB: RequestBuilder<(K, Vec<Event>)> + Send + Sync + 'static,
     RB::Error: fmt::Debug + Send,
     RB::Request: Ackable + Finalizable + Send,
+    P: Partitioner<Item = Event, Key = Option<K>> + Unpin + Send,
+    K: Hash + Eq + Clone + Send + 'static,
 {
     async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
         let partitioner = self.partitioner;
@@ -80,15 +83,17 @@ where
 }
 
 #[async_trait]
-impl<Svc, RB> StreamSink<Event> for S3Sink<Svc, RB>
+impl<Svc, RB, P, K> StreamSink<Event> for S3Sink<Svc, RB, P>
 where
     Svc: Service<RB::Request> + Send + 'static,
     Svc::Future: Send + 'static,
     Svc::Response: DriverResponse + Send + 'static,
     Svc::Error: fmt::Debug + Into<crate::Error> + Send,
-    RB: RequestBuilder<(String, Vec<Event>)> + Send + Sync + 'static,
+    RB: RequestBuilder<(K, Vec<Event>)> + Send + Sync + 'static,
     RB::Error: fmt::Debug + Send,
     RB::Request: Ackable + Finalizable + Send,
+    P: Partitioner<Item = Event, Key = Option<K>> + Unpin + Send,
+    K: Hash + Eq + Clone + Send + 'static,
 {
     async fn run(mut self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
         self.run_inner(input).await
@@ -11,7 +11,7 @@ use crate::{
     },
     event::EventArray,
     sinks::{
-        elasticsearch::{ElasticsearchConfig, ElasticsearchEncoder},
+        elasticsearch::{ElasticsearchApiVersion, ElasticsearchConfig, ElasticsearchEncoder},
         util::{
             encoding::EncodingConfigFixed, http::RequestConfig, BatchConfig, Compression,
             RealtimeSizeBasedDefaultBatchSettings, StreamSink, TowerRequestConfig,
@@ -87,6 +87,7 @@ impl SinkConfig for SematextLogsConfig {
             bulk: Some(BulkConfig {
                 action: None,
                 index: Some(self.token.clone()),
+                doc_as_upsert: false,
             }),
             batch: self.batch,
             request: RequestConfig {
@@ -94,6 +95,8 @@ impl SinkConfig for SematextLogsConfig {
                 ..Default::default()
             },
             encoding: self.encoding.clone(),
+            // Sematext doesn't follow the Elasticsearch versions, its requests are left as they are.
+            api_version: ElasticsearchApiVersion::V6,
             ..Default::default()
         }
         .build(cx)
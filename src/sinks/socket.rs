@@ -9,7 +9,8 @@ use crate::sinks::util::unix::UnixSinkConfig;
 use crate::{
     codecs::Encoder,
     config::{
-        AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext, SinkDescription,
+        AcknowledgementsConfig, Capability, GenerateConfig, Input, SinkConfig, SinkContext,
+        SinkDescription,
     },
     sinks::util::{
         encoding::{
@@ -119,6 +120,19 @@ impl SinkConfig for SocketSinkConfig {
         "socket"
     }
 
+    fn capabilities(&self) -> Vec<Capability> {
+        match &self.mode {
+            Mode::Tcp(config) => config.capabilities(),
+            Mode::Udp(config) => config.capabilities(),
+            #[cfg(unix)]
+            Mode::Unix(config) => config.capabilities(),
+        }
+    }
+
+    fn supports_sandbox(&self) -> bool {
+        true
+    }
+
     fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
         None
     }
@@ -5,6 +5,7 @@ use std::{
     time::Duration,
 };
 
+use futures_util::{future, stream, StreamExt};
 use hyper::Body;
 use serde::{Deserialize, Serialize};
 use tokio::sync::{mpsc::Receiver, oneshot::Sender};
@@ -27,6 +28,13 @@ pub struct HecClientAcknowledgementsConfig {
     pub query_interval: NonZeroU8,
     pub retry_limit: NonZeroU8,
     pub max_pending_acks: NonZeroU64,
+    /// The timeout of the ack queries, in seconds.
+    pub query_timeout: NonZeroU8,
+    /// The maximum number of channels queried at once.
+    pub query_concurrency: NonZeroU8,
+    /// The number of channels the requests are spread over. The acks of a request are queried on
+    /// its channel.
+    pub channels: NonZeroU8,
     #[serde(
         default,
         deserialize_with = "crate::serde::bool_or_struct",
@@ -43,6 +51,9 @@ impl Default for HecClientAcknowledgementsConfig {
             query_interval: NonZeroU8::new(10).unwrap(),
             retry_limit: NonZeroU8::new(30).unwrap(),
             max_pending_acks: NonZeroU64::new(1_000_000).unwrap(),
+            query_timeout: NonZeroU8::new(10).unwrap(),
+            query_concurrency: NonZeroU8::new(1).unwrap(),
+            channels: NonZeroU8::new(1).unwrap(),
             inner: Default::default(),
         }
     }
@@ -64,11 +75,15 @@ pub enum HecAckApiError {
     ClientParseResponse,
     ClientSendQuery,
     ServerSendQuery,
+    ServerTimeout,
 }
 
+/// Queries the acks of a channel.
 struct HecAckClient {
     acks: HashMap<u64, (u8, Sender<EventStatus>)>,
     retry_limit: u8,
+    query_timeout: Duration,
+    channel: usize,
     client: HttpClient,
     http_request_builder: Arc<HttpRequestBuilder>,
 }
@@ -76,12 +91,16 @@ struct HecAckClient {
 impl HecAckClient {
     fn new(
         retry_limit: u8,
+        query_timeout: Duration,
+        channel: usize,
         client: HttpClient,
         http_request_builder: Arc<HttpRequestBuilder>,
     ) -> Self {
         Self {
             acks: HashMap::new(),
             retry_limit,
+            query_timeout,
+            channel,
             client,
             http_request_builder,
         }
@@ -201,14 +220,21 @@ impl HecAckClient {
             .freeze();
         let request = self
             .http_request_builder
-            .build_request(request_body_bytes, "/services/collector/ack", None)
+            .build_request(
+                request_body_bytes,
+                "/services/collector/ack",
+                None,
+                self.channel,
+            )
             .map_err(|_| HecAckApiError::ClientBuildRequest)?;
 
-        let response = self
-            .client
-            .send(request.map(Body::from))
-            .await
-            .map_err(|_| HecAckApiError::ServerSendQuery)?;
+        let response = tokio::time::timeout(
+            self.query_timeout,
+            self.client.send(request.map(Body::from)),
+        )
+        .await
+        .map_err(|_| HecAckApiError::ServerTimeout)?
+        .map_err(|_| HecAckApiError::ServerSendQuery)?;
 
         let status = response.status();
         if status.is_success() {
@@ -226,7 +252,7 @@ impl HecAckClient {
 }
 
 pub async fn run_acknowledgements(
-    mut receiver: Receiver<(u64, Sender<EventStatus>)>,
+    mut receiver: Receiver<(usize, u64, Sender<EventStatus>)>,
     client: HttpClient,
     http_request_builder: Arc<HttpRequestBuilder>,
     indexer_acknowledgements: HecClientAcknowledgementsConfig,
@@ -234,22 +260,33 @@ pub async fn run_acknowledgements(
     let mut interval = tokio::time::interval(Duration::from_secs(
         indexer_acknowledgements.query_interval.get() as u64,
     ));
-    let mut ack_client = HecAckClient::new(
-        indexer_acknowledgements.retry_limit.get(),
-        client,
-        http_request_builder,
-    );
+    let query_timeout = Duration::from_secs(indexer_acknowledgements.query_timeout.get() as u64);
+    let query_concurrency = indexer_acknowledgements.query_concurrency.get() as usize;
+    let mut ack_clients = (0..http_request_builder.channels())
+        .map(|channel| {
+            HecAckClient::new(
+                indexer_acknowledgements.retry_limit.get(),
+                query_timeout,
+                channel,
+                client.clone(),
+                Arc::clone(&http_request_builder),
+            )
+        })
+        .collect::<Vec<_>>();
 
     loop {
         tokio::select! {
             _ = interval.tick() => {
-                ack_client.run().await;
+                stream::iter(ack_clients.iter_mut().map(|ack_client| ack_client.run()))
+                    .buffer_unordered(query_concurrency)
+                    .for_each(|_| future::ready(()))
+                    .await;
             },
             ack_info = receiver.recv() => {
                 match ack_info {
-                    Some((ack_id, tx)) => {
-                        ack_client.add(ack_id, tx);
-                        debug!(message = "Stored ack id.", ?ack_id);
+                    Some((channel, ack_id, tx)) => {
+                        ack_clients[channel % ack_clients.len()].add(ack_id, tx);
+                        debug!(message = "Stored ack id.", ?ack_id, ?channel);
                     },
                     None => break,
                 }
@@ -260,7 +297,7 @@ pub async fn run_acknowledgements(
 
 #[cfg(test)]
 mod tests {
-    use std::sync::Arc;
+    use std::{sync::Arc, time::Duration};
 
     use futures_util::{stream::FuturesUnordered, StreamExt};
     use tokio::sync::oneshot::{self, Receiver};
@@ -281,7 +318,13 @@ mod tests {
         let client = HttpClient::new(None, &ProxyConfig::default()).unwrap();
         let http_request_builder =
             HttpRequestBuilder::new(String::from(""), String::from(""), Compression::default());
-        HecAckClient::new(retry_limit, client, Arc::new(http_request_builder))
+        HecAckClient::new(
+            retry_limit,
+            Duration::from_secs(10),
+            0,
+            client,
+            Arc::new(http_request_builder),
+        )
     }
 
     fn populate_ack_client(
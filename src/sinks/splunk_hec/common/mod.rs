@@ -2,6 +2,7 @@ pub mod acknowledgements;
 pub mod request;
 pub mod response;
 pub mod service;
+pub mod token;
 pub mod util;
 
 pub use util::*;
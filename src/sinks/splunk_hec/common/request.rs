@@ -16,6 +16,8 @@ pub struct HecRequest {
     pub events_byte_size: usize,
     pub finalizers: EventFinalizers,
     pub passthrough_token: Option<Arc<str>>,
+    /// The index of the HEC channel the request is sent on, assigned by the service.
+    pub channel: usize,
 }
 
 impl ByteSizeOf for HecRequest {
@@ -1,6 +1,7 @@
 use std::{
     fmt,
-    sync::Arc,
+    num::NonZeroU8,
+    sync::{Arc, RwLock},
     task::{Context, Poll},
 };
 
@@ -31,9 +32,11 @@ use crate::{
 
 pub struct HecService<S> {
     pub inner: S,
-    ack_finalizer_tx: Option<mpsc::Sender<(u64, oneshot::Sender<EventStatus>)>>,
+    ack_finalizer_tx: Option<mpsc::Sender<(usize, u64, oneshot::Sender<EventStatus>)>>,
     ack_slots: PollSemaphore,
     current_ack_slot: Option<OwnedSemaphorePermit>,
+    channels: usize,
+    next_channel: usize,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -56,6 +59,7 @@ where
         indexer_acknowledgements: HecClientAcknowledgementsConfig,
     ) -> Self {
         let max_pending_acks = indexer_acknowledgements.max_pending_acks.get();
+        let channels = http_request_builder.channels();
         let tx = if let Some(ack_client) = ack_client {
             let (tx, rx) = mpsc::channel(128);
             tokio::spawn(run_acknowledgements(
@@ -75,6 +79,8 @@ where
             ack_finalizer_tx: tx,
             ack_slots,
             current_ack_slot: None,
+            channels,
+            next_channel: 0,
         }
     }
 }
@@ -108,10 +114,16 @@ where
         }
     }
 
-    fn call(&mut self, req: HecRequest) -> Self::Future {
+    fn call(&mut self, mut req: HecRequest) -> Self::Future {
         let ack_finalizer_tx = self.ack_finalizer_tx.clone();
         let ack_slot = self.current_ack_slot.take();
 
+        // The acks are queried on the channel of the request, as their ids are only unique within
+        // a channel.
+        let channel = self.next_channel;
+        self.next_channel = (channel + 1) % self.channels;
+        req.channel = channel;
+
         let events_count = req.events_count;
         let events_byte_size = req.events_byte_size;
         let response = self.inner.call(req);
@@ -126,7 +138,7 @@ where
                         Ok(body) => {
                             if let Some(ack_id) = body.ack_id {
                                 let (tx, rx) = oneshot::channel();
-                                match ack_finalizer_tx.send((ack_id, tx)).await {
+                                match ack_finalizer_tx.send((channel, ack_id, tx)).await {
                                     Ok(_) => rx.await.unwrap_or(EventStatus::Rejected),
                                     // If we cannot send ack ids to the ack client, fall back to default behavior
                                     Err(error) => {
@@ -178,21 +190,45 @@ impl ResponseExt for http::Response<Bytes> {
 
 pub struct HttpRequestBuilder {
     pub endpoint: String,
-    pub default_token: String,
+    default_token: RwLock<String>,
     pub compression: Compression,
     // A Splunk channel must be a GUID/UUID formatted value
     // https://docs.splunk.com/Documentation/Splunk/8.2.3/Data/AboutHECIDXAck#About_channels_and_sending_data
-    pub channel: String,
+    channels: Vec<String>,
 }
 
 impl HttpRequestBuilder {
     pub fn new(endpoint: String, default_token: String, compression: Compression) -> Self {
-        let channel = Uuid::new_v4().hyphenated().to_string();
         Self {
             endpoint,
-            default_token,
+            default_token: RwLock::new(default_token),
             compression,
-            channel,
+            channels: vec![new_channel()],
+        }
+    }
+
+    /// Spreads the requests over the given number of channels.
+    pub fn with_channels(mut self, channels: NonZeroU8) -> Self {
+        self.channels = (0..channels.get()).map(|_| new_channel()).collect();
+        self
+    }
+
+    pub fn channels(&self) -> usize {
+        self.channels.len()
+    }
+
+    pub fn default_token(&self) -> String {
+        self.default_token.read().unwrap().clone()
+    }
+
+    /// Replaces the default token, returning whether it changed.
+    pub fn set_default_token(&self, token: String) -> bool {
+        let mut default_token = self.default_token.write().unwrap();
+        if *default_token == token {
+            false
+        } else {
+            *default_token = token;
+            true
         }
     }
 
@@ -201,19 +237,18 @@ impl HttpRequestBuilder {
         body: Bytes,
         path: &str,
         passthrough_token: Option<Arc<str>>,
+        channel: usize,
     ) -> Result<Request<Bytes>, crate::Error> {
         let uri = build_uri(self.endpoint.as_str(), path).context(UriParseSnafu)?;
+        let token = passthrough_token.unwrap_or_else(|| self.default_token().into());
 
         let mut builder = Request::post(uri)
             .header("Content-Type", "application/json")
+            .header("Authorization", format!("Splunk {}", token))
             .header(
-                "Authorization",
-                format!(
-                    "Splunk {}",
-                    passthrough_token.unwrap_or_else(|| self.default_token.as_str().into())
-                ),
-            )
-            .header("X-Splunk-Request-Channel", self.channel.as_str());
+                "X-Splunk-Request-Channel",
+                self.channels[channel % self.channels.len()].as_str(),
+            );
 
         if let Some(ce) = self.compression.content_encoding() {
             builder = builder.header("Content-Encoding", ce);
@@ -223,6 +258,10 @@ impl HttpRequestBuilder {
     }
 }
 
+fn new_channel() -> String {
+    Uuid::new_v4().hyphenated().to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use std::{
@@ -233,6 +272,7 @@ mod tests {
             Arc,
         },
         task::Poll,
+        time::Duration,
     };
 
     use bytes::Bytes;
@@ -270,11 +310,10 @@ mod tests {
         acknowledgements_config: HecClientAcknowledgementsConfig,
     ) -> HecService<BoxService<HecRequest, http::Response<Bytes>, crate::Error>> {
         let client = HttpClient::new(None, &ProxyConfig::default()).unwrap();
-        let http_request_builder = Arc::new(HttpRequestBuilder::new(
-            endpoint,
-            String::from(TOKEN),
-            Compression::default(),
-        ));
+        let http_request_builder = Arc::new(
+            HttpRequestBuilder::new(endpoint, String::from(TOKEN), Compression::default())
+                .with_channels(acknowledgements_config.channels),
+        );
         let http_service =
             build_http_batch_service(client.clone(), Arc::clone(&http_request_builder));
         HecService::new(
@@ -294,6 +333,7 @@ mod tests {
             events_byte_size,
             finalizers: EventFinalizers::default(),
             passthrough_token: None,
+            channel: 0,
         }
     }
 
@@ -458,6 +498,58 @@ mod tests {
         assert_eq!(EventStatus::Errored, response.event_status)
     }
 
+    #[tokio::test]
+    async fn acknowledgements_query_timeout() {
+        let ack_response =
+            |req: &Request| ack_response_always_succeed(req).set_delay(Duration::from_secs(5));
+        let mock_server = get_hec_mock_server(true, ack_response).await;
+
+        let acknowledgements_config = HecClientAcknowledgementsConfig {
+            query_interval: NonZeroU8::new(1).unwrap(),
+            query_timeout: NonZeroU8::new(1).unwrap(),
+            retry_limit: NonZeroU8::new(1).unwrap(),
+            ..Default::default()
+        };
+        let mut service = get_hec_service(mock_server.uri(), acknowledgements_config);
+
+        let request = get_hec_request();
+        let response = service.ready().await.unwrap().call(request).await.unwrap();
+        assert_eq!(EventStatus::Errored, response.event_status)
+    }
+
+    #[tokio::test]
+    async fn acknowledgements_queried_on_request_channel() {
+        let mock_server = MockServer::start().await;
+        // The ack ids are only unique within a channel.
+        Mock::given(method("POST"))
+            .and(path("/services/collector/event"))
+            .respond_with(|_: &Request| {
+                ResponseTemplate::new(200).set_body_json(HecAckResponseBody { ack_id: Some(0) })
+            })
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/services/collector/ack"))
+            .respond_with(ack_response_always_succeed)
+            .mount(&mock_server)
+            .await;
+
+        let acknowledgements_config = HecClientAcknowledgementsConfig {
+            query_interval: NonZeroU8::new(1).unwrap(),
+            query_concurrency: NonZeroU8::new(2).unwrap(),
+            channels: NonZeroU8::new(2).unwrap(),
+            ..Default::default()
+        };
+        let mut service = get_hec_service(mock_server.uri(), acknowledgements_config);
+
+        let mut responses = FuturesUnordered::new();
+        responses.push(service.ready().await.unwrap().call(get_hec_request()));
+        responses.push(service.ready().await.unwrap().call(get_hec_request()));
+        while let Some(response) = responses.next().await {
+            assert_eq!(EventStatus::Delivered, response.unwrap().event_status)
+        }
+    }
+
     #[tokio::test]
     async fn acknowledgements_server_changed_event_response_format() {
         let mock_server = get_hec_mock_server(true, ack_response_always_succeed).await;
@@ -0,0 +1,211 @@
+use std::{
+    num::NonZeroU64,
+    sync::{Arc, Weak},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use tokio::{
+    sync::broadcast,
+    time::{interval_at, Instant},
+};
+
+use super::service::HttpRequestBuilder;
+use crate::{
+    config::{SecretBackend, SinkContext},
+    internal_events::{SplunkHecTokenRotated, SplunkHecTokenRotationError},
+};
+
+#[derive(Debug, Snafu)]
+pub enum TokenRotationError {
+    #[snafu(display(
+        "Secret backend {:?} is required for token rotation but was not found in config",
+        backend
+    ))]
+    MissingBackend { backend: String },
+}
+
+/// Refreshes the default token from a secret backend, so that it can be rotated without
+/// restarting Vector.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct HecTokenRotationConfig {
+    /// The name of the secret backend, as used in `SECRET[<backend>.<key>]`.
+    pub backend: String,
+    /// The key of the token in the secret backend.
+    pub key: String,
+    #[serde(default = "default_refresh_interval_secs")]
+    pub refresh_interval_secs: NonZeroU64,
+}
+
+fn default_refresh_interval_secs() -> NonZeroU64 {
+    NonZeroU64::new(300).unwrap()
+}
+
+impl HecTokenRotationConfig {
+    /// Spawns the task refreshing the default token of the request builder, which ends once the
+    /// request builder is dropped.
+    pub fn spawn(
+        &self,
+        cx: &SinkContext,
+        http_request_builder: &Arc<HttpRequestBuilder>,
+    ) -> crate::Result<()> {
+        let backend =
+            cx.secret_backend(&self.backend)
+                .ok_or_else(|| TokenRotationError::MissingBackend {
+                    backend: self.backend.clone(),
+                })?;
+        tokio::spawn(run_token_rotation(
+            backend,
+            self.clone(),
+            Arc::downgrade(http_request_builder),
+        ));
+        Ok(())
+    }
+}
+
+async fn retrieve_token(backend: &dyn SecretBackend, key: &str) -> crate::Result<Option<String>> {
+    let mut backend = dyn_clone::clone_box(backend);
+    let key = key.to_owned();
+    // Secret backends block while retrieving secrets, as they are used when loading the config.
+    tokio::task::spawn_blocking(move || {
+        let (_signal_tx, mut signal_rx) = broadcast::channel(1);
+        let mut secrets = backend.retrieve(vec![key.clone()], &mut signal_rx)?;
+        Ok(secrets.remove(&key))
+    })
+    .await?
+}
+
+async fn run_token_rotation(
+    backend: Box<dyn SecretBackend>,
+    config: HecTokenRotationConfig,
+    http_request_builder: Weak<HttpRequestBuilder>,
+) {
+    let period = Duration::from_secs(config.refresh_interval_secs.get());
+    let mut interval = interval_at(Instant::now() + period, period);
+
+    loop {
+        interval.tick().await;
+        let token = retrieve_token(backend.as_ref(), &config.key).await;
+
+        let http_request_builder = match http_request_builder.upgrade() {
+            Some(http_request_builder) => http_request_builder,
+            None => break,
+        };
+        match token {
+            Ok(Some(token)) if !token.is_empty() => {
+                if http_request_builder.set_default_token(token) {
+                    emit!(SplunkHecTokenRotated {
+                        backend: &config.backend,
+                        key: &config.key,
+                    });
+                }
+            }
+            Ok(_) => emit!(SplunkHecTokenRotationError {
+                error: "The secret backend returned no token.".into(),
+                backend: &config.backend,
+                key: &config.key,
+            }),
+            Err(error) => emit!(SplunkHecTokenRotationError {
+                error,
+                backend: &config.backend,
+                key: &config.key,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use indexmap::IndexMap;
+
+    use super::*;
+    use crate::{config::ComponentKey, signal, sinks::util::Compression};
+
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    struct RotatingBackend {
+        tokens: Vec<String>,
+    }
+
+    #[typetag::serde(name = "rotating_test")]
+    impl SecretBackend for RotatingBackend {
+        fn retrieve(
+            &mut self,
+            secret_keys: Vec<String>,
+            _: &mut signal::SignalRx,
+        ) -> crate::Result<HashMap<String, String>> {
+            let token = self.tokens.remove(0);
+            Ok(secret_keys
+                .into_iter()
+                .map(|key| (key, token.clone()))
+                .collect())
+        }
+    }
+
+    fn context(backend: RotatingBackend) -> SinkContext {
+        let mut secret_backends = IndexMap::new();
+        secret_backends.insert(
+            ComponentKey::from("vault"),
+            Box::new(backend) as Box<dyn SecretBackend>,
+        );
+        SinkContext {
+            secret_backends,
+            ..SinkContext::new_test()
+        }
+    }
+
+    fn config(backend: &str) -> HecTokenRotationConfig {
+        HecTokenRotationConfig {
+            backend: backend.into(),
+            key: "hec_token".into(),
+            refresh_interval_secs: NonZeroU64::new(1).unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn retrieves_tokens_from_the_backend() {
+        let backend = RotatingBackend {
+            tokens: vec!["first".into(), "second".into()],
+        };
+        assert_eq!(
+            retrieve_token(&backend, "hec_token").await.unwrap(),
+            Some("first".into())
+        );
+    }
+
+    #[tokio::test]
+    async fn rotates_the_default_token() {
+        let cx = context(RotatingBackend {
+            tokens: vec!["rotated".into()],
+        });
+        let http_request_builder = Arc::new(HttpRequestBuilder::new(
+            String::from("http://localhost:8088"),
+            String::from("initial"),
+            Compression::default(),
+        ));
+        config("vault").spawn(&cx, &http_request_builder).unwrap();
+        assert_eq!(http_request_builder.default_token(), "initial");
+
+        for _ in 0..30 {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            if http_request_builder.default_token() == "rotated" {
+                break;
+            }
+        }
+        assert_eq!(http_request_builder.default_token(), "rotated");
+    }
+
+    #[test]
+    fn requires_the_backend() {
+        let cx = SinkContext::new_test();
+        let http_request_builder = Arc::new(HttpRequestBuilder::new(
+            String::new(),
+            String::new(),
+            Compression::default(),
+        ));
+        assert!(config("vault").spawn(&cx, &http_request_builder).is_err());
+    }
+}
@@ -57,6 +57,7 @@ pub fn build_http_batch_service(
                     req.body,
                     "/services/collector/event",
                     req.passthrough_token,
+                    req.channel,
                 )
             });
         future
@@ -212,7 +213,7 @@ mod tests {
             HttpRequestBuilder::new(String::from(endpoint), String::from(token), compression);
 
         let request = http_request_builder
-            .build_request(events.clone(), "/services/collector/event", None)
+            .build_request(events.clone(), "/services/collector/event", None, 0)
             .unwrap();
 
         assert_eq!(
@@ -245,7 +246,7 @@ mod tests {
             HttpRequestBuilder::new(String::from(endpoint), String::from(token), compression);
 
         let request = http_request_builder
-            .build_request(events.clone(), "/services/collector/event", None)
+            .build_request(events.clone(), "/services/collector/event", None, 0)
             .unwrap();
 
         assert_eq!(
@@ -281,7 +282,7 @@ mod tests {
             HttpRequestBuilder::new(String::from(endpoint), String::from(token), compression);
 
         let err = http_request_builder
-            .build_request(events, "/services/collector/event", None)
+            .build_request(events, "/services/collector/event", None, 0)
             .unwrap_err();
         assert_eq!(err.to_string(), "URI parse error: invalid format")
     }
@@ -14,6 +14,7 @@ use crate::{
             acknowledgements::HecClientAcknowledgementsConfig,
             build_healthcheck, build_http_batch_service, create_client, host_key,
             service::{HecService, HttpRequestBuilder},
+            token::HecTokenRotationConfig,
             SplunkHecDefaultBatchSettings,
         },
         util::{
@@ -50,6 +51,7 @@ pub struct HecLogsSinkConfig {
     pub tls: Option<TlsConfig>,
     #[serde(default)]
     pub acknowledgements: HecClientAcknowledgementsConfig,
+    pub token_rotation: Option<HecTokenRotationConfig>,
     // This settings is relevant only for the `humio_logs` sink and should be left to None everywhere else
     pub timestamp_nanos_key: Option<String>,
 }
@@ -70,6 +72,7 @@ impl GenerateConfig for HecLogsSinkConfig {
             request: TowerRequestConfig::default(),
             tls: None,
             acknowledgements: Default::default(),
+            token_rotation: None,
             timestamp_nanos_key: None,
         })
         .unwrap()
@@ -123,11 +126,17 @@ impl HecLogsSinkConfig {
         };
 
         let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
-        let http_request_builder = Arc::new(HttpRequestBuilder::new(
-            self.endpoint.clone(),
-            self.default_token.clone(),
-            self.compression,
-        ));
+        let http_request_builder = Arc::new(
+            HttpRequestBuilder::new(
+                self.endpoint.clone(),
+                self.default_token.clone(),
+                self.compression,
+            )
+            .with_channels(self.acknowledgements.channels),
+        );
+        if let Some(token_rotation) = &self.token_rotation {
+            token_rotation.spawn(&cx, &http_request_builder)?;
+        }
         let http_service = ServiceBuilder::new()
             .settings(request_settings, HttpRetryLogic)
             .service(build_http_batch_service(
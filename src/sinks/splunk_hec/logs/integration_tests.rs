@@ -116,6 +116,7 @@ async fn config(
         request: TowerRequestConfig::default(),
         tls: None,
         acknowledgements: Default::default(),
+        token_rotation: None,
         timestamp_nanos_key: None,
     }
 }
@@ -61,6 +61,7 @@ impl RequestBuilder<(Option<Arc<str>>, Vec<HecProcessedEvent>)> for HecLogsReque
             events_count,
             events_byte_size,
             passthrough_token,
+            channel: 0,
         }
     }
 }
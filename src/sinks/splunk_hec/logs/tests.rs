@@ -164,6 +164,7 @@ async fn splunk_passthrough_token() {
         request: Default::default(),
         tls: None,
         acknowledgements: Default::default(),
+        token_rotation: None,
         timestamp_nanos_key: None,
     };
     let cx = SinkContext::new_test();
@@ -14,6 +14,7 @@ use crate::{
             acknowledgements::HecClientAcknowledgementsConfig,
             build_healthcheck, build_http_batch_service, create_client, host_key,
             service::{HecService, HttpRequestBuilder},
+            token::HecTokenRotationConfig,
             SplunkHecDefaultBatchSettings,
         },
         util::{
@@ -47,6 +48,7 @@ pub struct HecMetricsSinkConfig {
     pub tls: Option<TlsConfig>,
     #[serde(default)]
     pub acknowledgements: HecClientAcknowledgementsConfig,
+    pub token_rotation: Option<HecTokenRotationConfig>,
 }
 
 impl GenerateConfig for HecMetricsSinkConfig {
@@ -64,6 +66,7 @@ impl GenerateConfig for HecMetricsSinkConfig {
             request: TowerRequestConfig::default(),
             tls: None,
             acknowledgements: Default::default(),
+            token_rotation: None,
         })
         .unwrap()
     }
@@ -114,11 +117,17 @@ impl HecMetricsSinkConfig {
         };
 
         let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
-        let http_request_builder = Arc::new(HttpRequestBuilder::new(
-            self.endpoint.clone(),
-            self.default_token.clone(),
-            self.compression,
-        ));
+        let http_request_builder = Arc::new(
+            HttpRequestBuilder::new(
+                self.endpoint.clone(),
+                self.default_token.clone(),
+                self.compression,
+            )
+            .with_channels(self.acknowledgements.channels),
+        );
+        if let Some(token_rotation) = &self.token_rotation {
+            token_rotation.spawn(&cx, &http_request_builder)?;
+        }
         let http_service = ServiceBuilder::new()
             .settings(request_settings, HttpRetryLogic)
             .service(build_http_batch_service(
@@ -39,6 +39,7 @@ async fn config() -> HecMetricsSinkConfig {
         request: TowerRequestConfig::default(),
         tls: None,
         acknowledgements: Default::default(),
+        token_rotation: None,
     }
 }
 
@@ -60,6 +60,7 @@ impl RequestBuilder<(Option<Arc<str>>, Vec<HecProcessedEvent>)> for HecMetricsRe
             events_count,
             events_byte_size,
             passthrough_token,
+            channel: 0,
         }
     }
 }
@@ -331,6 +331,7 @@ async fn splunk_passthrough_token() {
         request: Default::default(),
         tls: None,
         acknowledgements: Default::default(),
+        token_rotation: None,
         default_namespace: None,
     };
     let cx = SinkContext::new_test();
@@ -42,6 +42,8 @@ pub(super) struct Inner {
     current_rtt: Mean,
     had_back_pressure: bool,
     reached_limit: bool,
+    /// Whether the limit was overridden through the API, which stops it from being adjusted.
+    overridden: bool,
 }
 
 #[cfg(test)]
@@ -77,6 +79,7 @@ impl<L> Controller<L> {
                 current_rtt: Default::default(),
                 had_back_pressure: false,
                 reached_limit: false,
+                overridden: false,
             })),
             #[cfg(test)]
             stats: Arc::new(Mutex::new(ControllerStatistics::default())),
@@ -179,8 +182,9 @@ impl<L> Controller<L> {
                         });
                     }
 
-                    // Only manage the concurrency if `concurrency` was set to "adaptive"
-                    if self.concurrency.is_none() {
+                    // Only manage the concurrency if `concurrency` was set to "adaptive", and the
+                    // limit isn't overridden
+                    if self.concurrency.is_none() && !inner.overridden {
                         self.manage_limit(&mut inner, past_rtt, current_rtt);
                     }
 
@@ -197,6 +201,29 @@ impl<L> Controller<L> {
         }
     }
 
+    /// Returns the current limit, the number of requests in flight, and whether the limit is
+    /// overridden.
+    pub(super) fn status(&self) -> (usize, usize, bool) {
+        let inner = self.inner.lock().expect("Controller mutex is poisoned");
+        (inner.current_limit, inner.in_flight, inner.overridden)
+    }
+
+    /// Overrides the limit, or lifts the override with `None`. Once lifted, an adaptive limit is
+    /// adjusted again starting from the overriding one, while a configured one is restored.
+    pub(super) fn override_limit(&self, limit: Option<usize>) {
+        let mut inner = self.inner.lock().expect("Controller mutex is poisoned");
+        inner.overridden = limit.is_some();
+        if let Some(limit) = limit.or(self.concurrency) {
+            let limit = limit.max(1);
+            if limit > inner.current_limit {
+                self.semaphore.add_permits(limit - inner.current_limit);
+            } else {
+                self.semaphore.forget_permits(inner.current_limit - limit);
+            }
+            inner.current_limit = limit;
+        }
+    }
+
     fn manage_limit(
         &self,
         inner: &mut MutexGuard<Inner>,
@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 mod controller;
 mod future;
 mod layer;
+mod registry;
 mod semaphore;
 mod service;
 mod tests;
@@ -12,6 +13,7 @@ mod tests;
 pub(super) const MAX_CONCURRENCY: usize = 200;
 
 pub(crate) use layer::AdaptiveConcurrencyLimitLayer;
+pub use registry::{limits, override_limit, ConcurrencyLimitStatus};
 pub(crate) use service::AdaptiveConcurrencyLimit;
 
 pub(self) fn instant_now() -> std::time::Instant {
@@ -0,0 +1,199 @@
+//! The adaptive concurrency controllers of the running sinks.
+//!
+//! Each controller is registered under the sink whose task built it, along with the partition it
+//! limits the requests of, for sinks sending the requests of each partition, such as an API key
+//! or a tenant, with their own service. This lets the limits be inspected, and overridden for live
+//! tuning, through the API. Controllers are unregistered once their service is dropped.
+
+use std::sync::{Arc, Mutex, Weak};
+
+use once_cell::sync::Lazy;
+
+use super::controller::Controller;
+use crate::{
+    component_scope, config::ComponentKey, internal_events::AdaptiveConcurrencyOverridden,
+};
+
+static CONTROLLERS: Lazy<Mutex<Vec<Registration>>> = Lazy::new(Default::default);
+
+/// The part of a controller the registry needs, independent of its retry logic.
+pub(super) trait LimitHandle: Send + Sync {
+    fn status(&self) -> (usize, usize, bool);
+
+    fn override_limit(&self, limit: Option<usize>);
+}
+
+impl<L: Send + Sync> LimitHandle for Controller<L> {
+    fn status(&self) -> (usize, usize, bool) {
+        Controller::status(self)
+    }
+
+    fn override_limit(&self, limit: Option<usize>) {
+        Controller::override_limit(self, limit)
+    }
+}
+
+struct Registration {
+    component: ComponentKey,
+    partition: Option<String>,
+    controller: Weak<dyn LimitHandle>,
+}
+
+/// The state of the concurrency limit of a sink, or of one of its partitions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConcurrencyLimitStatus {
+    pub component: ComponentKey,
+    pub partition: Option<String>,
+    pub limit: usize,
+    pub in_flight: usize,
+    /// Whether the limit was overridden through the API.
+    pub overridden: bool,
+}
+
+/// Registers the controller under the component whose task is running. Controllers built outside
+/// of components, such as in tests, aren't registered.
+pub(super) fn register(partition: Option<String>, controller: Arc<dyn LimitHandle>) {
+    if let Some(component) = component_scope::current() {
+        let mut controllers = CONTROLLERS.lock().expect("controllers lock poisoned");
+        controllers.retain(|registration| registration.controller.strong_count() > 0);
+        controllers.push(Registration {
+            component,
+            partition,
+            controller: Arc::downgrade(&controller),
+        });
+    }
+}
+
+/// Returns the state of the limits of the running controllers.
+pub fn limits() -> Vec<ConcurrencyLimitStatus> {
+    let controllers = CONTROLLERS.lock().expect("controllers lock poisoned");
+    controllers
+        .iter()
+        .filter_map(|registration| {
+            let (limit, in_flight, overridden) = registration.controller.upgrade()?.status();
+            Some(ConcurrencyLimitStatus {
+                component: registration.component.clone(),
+                partition: registration.partition.clone(),
+                limit,
+                in_flight,
+                overridden,
+            })
+        })
+        .collect()
+}
+
+/// Overrides the limit of the controllers of the component, or lifts their override with `None`,
+/// and returns the number of controllers it applied to. Without a `partition`, it applies to the
+/// controllers of all the partitions of the component.
+///
+/// Overrides only apply to the running controllers, so they don't outlive a reload of the sink,
+/// and the controllers of the partitions the sink starts sending requests to afterwards start out
+/// adaptive.
+pub fn override_limit(
+    component: &ComponentKey,
+    partition: Option<&str>,
+    limit: Option<usize>,
+) -> usize {
+    let controllers = CONTROLLERS.lock().expect("controllers lock poisoned");
+    let mut applied = 0;
+    for registration in controllers.iter().filter(|registration| {
+        registration.component == *component
+            && partition.map_or(true, |partition| {
+                registration.partition.as_deref() == Some(partition)
+            })
+    }) {
+        if let Some(controller) = registration.controller.upgrade() {
+            controller.override_limit(limit);
+            emit!(AdaptiveConcurrencyOverridden {
+                component,
+                partition: registration.partition.as_deref(),
+                limit,
+            });
+            applied += 1;
+        }
+    }
+    applied
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sinks::util::adaptive_concurrency::AdaptiveConcurrencySettings;
+
+    fn controller(concurrency: Option<usize>) -> Arc<Controller<()>> {
+        Arc::new(Controller::new(
+            concurrency,
+            AdaptiveConcurrencySettings::default(),
+            (),
+        ))
+    }
+
+    fn status(component: &ComponentKey) -> Vec<(Option<String>, usize, bool)> {
+        limits()
+            .into_iter()
+            .filter(|status| status.component == *component)
+            .map(|status| (status.partition, status.limit, status.overridden))
+            .collect()
+    }
+
+    // The registry is global, so each test registers the controllers of its own component.
+    #[tokio::test]
+    async fn overrides_partition_limits() {
+        let component = ComponentKey::from("registry_overrides");
+        let first = controller(None);
+        let second = controller(Some(4));
+        component_scope::scope(component.clone(), async {
+            register(
+                Some("first".into()),
+                Arc::clone(&first) as Arc<dyn LimitHandle>,
+            );
+            register(
+                Some("second".into()),
+                Arc::clone(&second) as Arc<dyn LimitHandle>,
+            );
+        })
+        .await;
+        // Controllers built outside of components aren't registered.
+        register(None, controller(None));
+
+        assert_eq!(
+            status(&component),
+            vec![
+                (Some("first".into()), 1, false),
+                (Some("second".into()), 4, false)
+            ]
+        );
+
+        assert_eq!(override_limit(&component, Some("first"), Some(10)), 1);
+        assert_eq!(
+            status(&component),
+            vec![
+                (Some("first".into()), 10, true),
+                (Some("second".into()), 4, false)
+            ]
+        );
+
+        assert_eq!(override_limit(&component, None, Some(2)), 2);
+        assert_eq!(
+            status(&component),
+            vec![
+                (Some("first".into()), 2, true),
+                (Some("second".into()), 2, true)
+            ]
+        );
+
+        // Lifting the override restores the configured limit, and keeps the adaptive one.
+        assert_eq!(override_limit(&component, None, None), 2);
+        assert_eq!(
+            status(&component),
+            vec![
+                (Some("first".into()), 2, false),
+                (Some("second".into()), 4, false)
+            ]
+        );
+
+        drop(first);
+        assert_eq!(status(&component), vec![(Some("second".into()), 4, false)]);
+        assert_eq!(override_limit(&component, Some("first"), Some(10)), 0);
+    }
+}
@@ -10,7 +10,9 @@ use futures::{future::BoxFuture, ready};
 use tokio::sync::OwnedSemaphorePermit;
 use tower::Service;
 
-use super::{controller::Controller, future::ResponseFuture, AdaptiveConcurrencySettings};
+use super::{
+    controller::Controller, future::ResponseFuture, registry, AdaptiveConcurrencySettings,
+};
 use crate::sinks::util::retries::RetryLogic;
 
 /// Enforces a limit on the concurrent number of requests the underlying
@@ -29,17 +31,21 @@ enum State {
     Empty,
 }
 
-impl<S, L> AdaptiveConcurrencyLimit<S, L> {
-    /// Create a new automated concurrency limiter.
+impl<S, L: RetryLogic> AdaptiveConcurrencyLimit<S, L> {
+    /// Create a new automated concurrency limiter, whose controller is registered under the
+    /// partition, if any, of the requests it limits.
     pub(crate) fn new(
         inner: S,
         logic: L,
         concurrency: Option<usize>,
         options: AdaptiveConcurrencySettings,
+        partition: Option<String>,
     ) -> Self {
+        let controller = Arc::new(Controller::new(concurrency, options, logic));
+        registry::register(partition, Arc::clone(&controller) as _);
         AdaptiveConcurrencyLimit {
             inner,
-            controller: Arc::new(Controller::new(concurrency, options, logic)),
+            controller,
             state: State::Empty,
         }
     }
@@ -339,4 +345,37 @@ mod tests {
         })
         .await;
     }
+
+    #[tokio::test]
+    async fn keeps_overridden_limit() {
+        TestService::run(|mut svc| async move {
+            svc.service.get_ref().controller.override_limit(Some(3));
+            assert_eq!(svc.inner().current_limit, 3);
+
+            let mut reqs = Vec::new();
+            for i in 0..3 {
+                reqs.push(svc.send(i < 2).await);
+            }
+            advance(Duration::from_secs(1)).await;
+            for req in reqs {
+                req.respond().await;
+            }
+
+            // Back pressure doesn't decrease an overridden limit.
+            let req = svc.send(true).await;
+            advance(Duration::from_secs(1)).await;
+            req.defer().await;
+            assert_eq!(svc.inner().current_limit, 3);
+
+            // Once lifted, the limit is adjusted again starting from the overriding one.
+            svc.service.get_ref().controller.override_limit(None);
+            assert_eq!(svc.inner().current_limit, 3);
+
+            let req = svc.send(true).await;
+            advance(Duration::from_secs(1)).await;
+            req.defer().await;
+            assert_eq!(svc.inner().current_limit, 1);
+        })
+        .await;
+    }
 }
@@ -0,0 +1,63 @@
+use std::task::{Context, Poll};
+
+use futures::future::BoxFuture;
+use http::{
+    uri::{Authority, Scheme},
+    Request, Uri,
+};
+use hyper::Body;
+use tonic::body::BoxBody;
+use tower::Service;
+
+use crate::http::{Auth, HttpClient, HttpError};
+
+/// Sends the requests of the gRPC clients to the configured endpoint, with its credentials.
+#[derive(Clone, Debug)]
+pub struct GrpcChannel {
+    client: HttpClient<BoxBody>,
+    scheme: Scheme,
+    authority: Authority,
+    auth: Option<Auth>,
+}
+
+impl GrpcChannel {
+    pub fn new(client: HttpClient<BoxBody>, uri: &Uri, auth: Option<Auth>) -> crate::Result<Self> {
+        Ok(Self {
+            client,
+            scheme: uri.scheme().cloned().unwrap_or(Scheme::HTTP),
+            authority: uri.authority().cloned().ok_or("Endpoint has no host.")?,
+            auth,
+        })
+    }
+
+    /// The protocol of the requests, for the `BytesSent` internal events.
+    pub fn protocol(&self) -> &'static str {
+        if self.scheme == Scheme::HTTPS {
+            "https"
+        } else {
+            "http"
+        }
+    }
+}
+
+impl Service<Request<BoxBody>> for GrpcChannel {
+    type Response = http::Response<Body>;
+    type Error = HttpError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut request: Request<BoxBody>) -> Self::Future {
+        let mut parts = request.uri().clone().into_parts();
+        parts.scheme = Some(self.scheme.clone());
+        parts.authority = Some(self.authority.clone());
+        *request.uri_mut() = Uri::from_parts(parts).expect("URI parts should be valid");
+        if let Some(auth) = &self.auth {
+            auth.apply(&mut request);
+        }
+
+        self.client.send(request)
+    }
+}
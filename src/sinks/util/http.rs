@@ -20,9 +20,10 @@ use tower::Service;
 use vector_core::{buffers::Acker, ByteSizeOf};
 
 use super::{
+    batch::BatchError,
     retries::{RetryAction, RetryLogic},
-    sink, uri, Batch, EncodedEvent, Partition, TowerBatchedSink, TowerPartitionSink,
-    TowerRequestConfig, TowerRequestSettings,
+    sink, uri, Batch, BatchConfig, EncodedEvent, Merged, Partition, PushResult, SinkBatchSettings,
+    TowerBatchedSink, TowerPartitionSink, TowerRequestConfig, TowerRequestSettings,
 };
 use crate::{
     event::Event,
@@ -518,6 +519,83 @@ where
     }
 }
 
+/// An encoded event, or a batch of them, along with the W3C `traceparent` of
+/// the request the events were received in.
+#[derive(Clone, Debug)]
+pub struct Traced<T> {
+    pub item: T,
+    pub trace_parent: Option<Arc<str>>,
+}
+
+impl<T: ByteSizeOf> ByteSizeOf for Traced<T> {
+    fn allocated_bytes(&self) -> usize {
+        self.item.allocated_bytes()
+    }
+}
+
+/// Wraps a batch to keep track of the `traceparent` of its events, so that the
+/// request built from it can continue their trace.
+///
+/// A request can only be part of a single trace, so the first `traceparent` in
+/// the batch is kept.
+#[derive(Debug)]
+pub struct TracedBatch<B> {
+    inner: B,
+    trace_parent: Option<Arc<str>>,
+}
+
+impl<B> TracedBatch<B> {
+    pub const fn new(inner: B) -> Self {
+        Self {
+            inner,
+            trace_parent: None,
+        }
+    }
+}
+
+impl<B: Batch> Batch for TracedBatch<B> {
+    type Input = Traced<B::Input>;
+    type Output = Traced<B::Output>;
+
+    fn get_settings_defaults<D: SinkBatchSettings>(
+        config: BatchConfig<D, Merged>,
+    ) -> Result<BatchConfig<D, Merged>, BatchError> {
+        B::get_settings_defaults(config)
+    }
+
+    fn push(&mut self, item: Self::Input) -> PushResult<Self::Input> {
+        let Traced { item, trace_parent } = item;
+        match self.inner.push(item) {
+            PushResult::Ok(full) => {
+                if self.trace_parent.is_none() {
+                    self.trace_parent = trace_parent;
+                }
+                PushResult::Ok(full)
+            }
+            PushResult::Overflow(item) => PushResult::Overflow(Traced { item, trace_parent }),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    fn fresh(&self) -> Self {
+        Self::new(self.inner.fresh())
+    }
+
+    fn finish(self) -> Self::Output {
+        Traced {
+            item: self.inner.finish(),
+            trace_parent: self.trace_parent,
+        }
+    }
+
+    fn num_items(&self) -> usize {
+        self.inner.num_items()
+    }
+}
+
 /// A helper config struct
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
@@ -0,0 +1,195 @@
+//! Aggregation of records in the format of the Kinesis Producer Library (KPL).
+//!
+//! An aggregated record packs many user records in a single Kinesis record: the magic number of
+//! the format, followed by the user records as an `AggregatedRecord` Protobuf message, and the MD5
+//! digest of that message. The consumers built with the Kinesis Client Library, and the
+//! de-aggregation modules of the KPL, unpack the aggregated records transparently, and pass the
+//! other records through as they are.
+
+use std::{collections::HashMap, num::NonZeroUsize, time::Duration};
+
+use bytes::{BufMut, Bytes, BytesMut};
+use md5::Digest;
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use vector_core::stream::BatcherSettings;
+
+/// The magic number starting the aggregated records.
+pub const MAGIC: [u8; 4] = [0xf3, 0x89, 0x9a, 0xc2];
+
+const DIGEST_SIZE: usize = 16;
+
+/// The maximum size of the framing of each user record in an aggregated record, i.e. its index in
+/// the partition keys table and the length prefixes of its message and data.
+const RECORD_FRAMING: usize = 16;
+
+#[derive(Debug, PartialEq, Snafu)]
+pub enum AggregationError {
+    #[snafu(display(
+        "Aggregation max size is too high. The value must be {} bytes or less",
+        max_bytes
+    ))]
+    MaxBytes { max_bytes: usize },
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct AggregationConfig {
+    /// The maximum number of user records in an aggregated record.
+    #[serde(default = "default_max_records")]
+    pub max_records: NonZeroUsize,
+    /// The maximum size of the user records of an aggregated record, in bytes.
+    #[serde(default = "default_max_bytes")]
+    pub max_bytes: NonZeroUsize,
+}
+
+fn default_max_records() -> NonZeroUsize {
+    NonZeroUsize::new(1000).unwrap()
+}
+
+// The default of the KPL, which keeps the records small enough to be sent quickly.
+fn default_max_bytes() -> NonZeroUsize {
+    NonZeroUsize::new(51_200).unwrap()
+}
+
+impl Default for AggregationConfig {
+    fn default() -> Self {
+        Self {
+            max_records: default_max_records(),
+            max_bytes: default_max_bytes(),
+        }
+    }
+}
+
+impl AggregationConfig {
+    /// The settings of the batches aggregated in a single record, which must fit in
+    /// `max_record_bytes` once aggregated.
+    pub fn batcher_settings(
+        &self,
+        timeout: Duration,
+        max_record_bytes: usize,
+    ) -> Result<BatcherSettings, AggregationError> {
+        // The sinks measure the user records as they're sent, base64 encoded, which is more than
+        // they take once aggregated, except for their framing.
+        let max_bytes = max_record_bytes
+            .saturating_sub(MAGIC.len() + DIGEST_SIZE + RECORD_FRAMING * self.max_records.get());
+        if self.max_bytes.get() > max_bytes {
+            return Err(AggregationError::MaxBytes { max_bytes });
+        }
+        Ok(BatcherSettings::new(
+            timeout,
+            self.max_bytes,
+            self.max_records,
+        ))
+    }
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct AggregatedRecord {
+    #[prost(string, repeated, tag = "1")]
+    partition_key_table: Vec<String>,
+    #[prost(string, repeated, tag = "2")]
+    explicit_hash_key_table: Vec<String>,
+    #[prost(message, repeated, tag = "3")]
+    records: Vec<Record>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct Record {
+    #[prost(uint64, required, tag = "1")]
+    partition_key_index: u64,
+    #[prost(uint64, optional, tag = "2")]
+    explicit_hash_key_index: Option<u64>,
+    #[prost(bytes = "vec", required, tag = "3")]
+    data: Vec<u8>,
+}
+
+/// Aggregates the user records, given as their partition key and data, in a single record.
+pub fn aggregate<'a>(records: impl IntoIterator<Item = (&'a str, &'a [u8])>) -> Bytes {
+    let mut partition_keys = HashMap::new();
+    let mut aggregated = AggregatedRecord::default();
+    for (partition_key, data) in records {
+        let partition_key_index = *partition_keys.entry(partition_key).or_insert_with(|| {
+            aggregated
+                .partition_key_table
+                .push(partition_key.to_owned());
+            aggregated.partition_key_table.len() as u64 - 1
+        });
+        aggregated.records.push(Record {
+            partition_key_index,
+            explicit_hash_key_index: None,
+            data: data.to_vec(),
+        });
+    }
+
+    let message = aggregated.encode_to_vec();
+    let mut body = BytesMut::with_capacity(MAGIC.len() + message.len() + DIGEST_SIZE);
+    body.put_slice(&MAGIC);
+    body.put_slice(&message);
+    body.put_slice(&md5::Md5::digest(&message));
+    body.freeze()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Unpacks the user records of an aggregated record, as the consumers do.
+    fn deaggregate(record: &[u8]) -> Vec<(String, Vec<u8>)> {
+        assert_eq!(record[..MAGIC.len()], MAGIC);
+        let (message, digest) =
+            record[MAGIC.len()..].split_at(record.len() - MAGIC.len() - DIGEST_SIZE);
+        assert_eq!(md5::Md5::digest(message).as_slice(), digest);
+
+        let aggregated = AggregatedRecord::decode(message).unwrap();
+        aggregated
+            .records
+            .into_iter()
+            .map(|record| {
+                let partition_key =
+                    aggregated.partition_key_table[record.partition_key_index as usize].clone();
+                (partition_key, record.data)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn aggregates_records() {
+        let record = aggregate(vec![
+            ("a", &b"first"[..]),
+            ("b", &b"second"[..]),
+            ("a", &b"third"[..]),
+        ]);
+        assert_eq!(
+            deaggregate(&record),
+            vec![
+                ("a".to_owned(), b"first".to_vec()),
+                ("b".to_owned(), b"second".to_vec()),
+                ("a".to_owned(), b"third".to_vec()),
+            ]
+        );
+        let aggregated =
+            AggregatedRecord::decode(&record[MAGIC.len()..record.len() - DIGEST_SIZE]).unwrap();
+        assert_eq!(aggregated.partition_key_table, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn limits_aggregated_size() {
+        let config = toml::from_str::<AggregationConfig>("max_bytes = 1048576").unwrap();
+        assert_eq!(
+            config
+                .batcher_settings(Duration::from_secs(1), 1_048_576)
+                .unwrap_err(),
+            AggregationError::MaxBytes {
+                max_bytes: 1_048_576 - 20 - 16_000
+            }
+        );
+
+        let settings = AggregationConfig::default()
+            .batcher_settings(Duration::from_secs(1), 1_048_576)
+            .unwrap();
+        assert_eq!(settings.size_limit, 51_200);
+        assert_eq!(settings.item_limit, 1000);
+    }
+}
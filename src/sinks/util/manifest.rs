@@ -0,0 +1,764 @@
+//! Manifests of the objects written by the object store sinks.
+//!
+//! Once an object is written, the object store sinks can record it in a manifest, so that batch
+//! loaders can discover the complete objects of a partition without having to list them, and
+//! without picking up objects that are still being written. Two formats are supported:
+//!
+//! - `json`: a manifest object per partition, listing the objects written to it along with their
+//!   record counts and sizes, which is rewritten each time an object is added to the partition.
+//!   The manifest already written for a partition is read before the partition's first write, so
+//!   that it's extended rather than overwritten, for instance after a restart.
+//! - `marker`: a marker object written next to each object once it's complete, describing it.
+//! - `success`: a `_SUCCESS` marker object per partition, written once no object has been added to
+//!   the partition for a while, for the batch loaders waiting for complete partitions.
+//!
+//! The partition of an object is the "directory" it was written to, i.e. its key up to the last
+//! `/`, which is the rendered key prefix when it ends with a `/`.
+
+use std::{
+    collections::HashMap,
+    num::NonZeroU64,
+    sync::{Arc, Mutex, Weak},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+use tower::{buffer::Buffer, Service, ServiceExt};
+use vector_core::{event::EventStatus, stream::DriverResponse};
+
+use crate::internal_events::{ObjectManifestReadError, ObjectManifestWriteError};
+
+/// The number of manifest writes that can be queued in front of the service writing them.
+const MANIFEST_BUFFER_SIZE: usize = 64;
+
+/// How long the manifest of a partition no object has been added to is kept in memory, for the
+/// `json` format. It's read again from the object store when an object is added to the partition
+/// afterwards.
+const MANIFEST_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// How often the idle partitions are looked for, to write their success markers.
+const SUCCESS_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, PartialEq, Eq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ManifestFormat {
+    /// A manifest object per partition, listing all of its objects.
+    #[derivative(Default)]
+    Json,
+    /// A marker object per object.
+    Marker,
+    /// A success marker per partition, once it's idle.
+    Success,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestConfig {
+    #[serde(default)]
+    pub format: ManifestFormat,
+    /// The name of the manifest object of each partition, for the `json` format.
+    #[serde(default = "default_name")]
+    pub name: String,
+    /// The suffix appended to the key of each object to get the key of its marker, for the
+    /// `marker` format.
+    #[serde(default = "default_marker_suffix")]
+    pub marker_suffix: String,
+    /// The name of the success marker of each partition, for the `success` format.
+    #[serde(default = "default_success_name")]
+    pub success_name: String,
+    /// The time after which a partition no object has been added to is complete, for the
+    /// `success` format.
+    #[serde(default = "default_success_after_secs")]
+    pub success_after_secs: NonZeroU64,
+}
+
+fn default_name() -> String {
+    "manifest.json".to_owned()
+}
+
+fn default_marker_suffix() -> String {
+    ".manifest.json".to_owned()
+}
+
+fn default_success_name() -> String {
+    "_SUCCESS".to_owned()
+}
+
+fn default_success_after_secs() -> NonZeroU64 {
+    NonZeroU64::new(300).unwrap()
+}
+
+/// An object recorded in a manifest.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct ManifestEntry {
+    /// The key of the object.
+    pub key: String,
+    /// The number of records in the object.
+    pub records: usize,
+    /// The size of the object, in bytes.
+    pub bytes: usize,
+}
+
+/// The manifest of a partition, listing the objects written to it.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub struct Manifest {
+    pub partition: String,
+    pub records: usize,
+    pub bytes: usize,
+    pub objects: Vec<ManifestEntry>,
+}
+
+impl Manifest {
+    /// Adds an object to the manifest, replacing the object with the same key if there is one.
+    fn add(&mut self, entry: ManifestEntry) {
+        if let Some(index) = self
+            .objects
+            .iter()
+            .position(|object| object.key == entry.key)
+        {
+            let replaced = self.objects.remove(index);
+            self.records -= replaced.records;
+            self.bytes -= replaced.bytes;
+        }
+        self.records += entry.records;
+        self.bytes += entry.bytes;
+        self.objects.push(entry);
+    }
+}
+
+/// A request writing an object, that can be recorded in a manifest.
+pub trait ManifestRequest: Sized {
+    /// Describes the object written by this request.
+    fn manifest_entry(&self) -> ManifestEntry;
+
+    /// Builds a request writing a JSON object at `key`, to the same destination and with the same
+    /// settings as this request.
+    fn manifest_request(&self, key: String, body: Bytes) -> Self;
+}
+
+/// Reads the objects written by the object store sinks, for the manifests to extend the ones
+/// already written.
+pub trait ObjectReader<Request>: Send + Sync {
+    /// Reads the object at `key`, in the same destination as `request`, returning `None` if it
+    /// doesn't exist.
+    fn read_object(
+        &self,
+        request: &Request,
+        key: String,
+    ) -> BoxFuture<'static, crate::Result<Option<Bytes>>>;
+}
+
+/// Records the objects written by the inner service in manifests, when configured.
+///
+/// The manifests are only written once the objects they describe are, and with a separate
+/// service, so that they go through their own retries.
+pub struct ManifestService<S, Request>
+where
+    S: Service<Request>,
+{
+    inner: S,
+    writer: Option<Arc<ManifestWriter<S, Request>>>,
+}
+
+impl<S, Request> ManifestService<S, Request>
+where
+    S: Service<Request> + Send + 'static,
+    S::Error: Into<crate::Error> + Send + Sync,
+    S::Future: Send,
+    Request: Send + 'static,
+{
+    /// Creates the service, with `make_service` building both the service writing the objects
+    /// and, when manifests are enabled, the one writing the manifests. `reader` reads the manifests
+    /// already written.
+    pub fn new(
+        config: Option<ManifestConfig>,
+        reader: impl ObjectReader<Request> + 'static,
+        make_service: impl Fn() -> S,
+    ) -> Self
+    where
+        S::Response: DriverResponse,
+        Request: ManifestRequest,
+    {
+        let writer = config.map(|config| {
+            let format = config.format;
+            let writer = Arc::new(ManifestWriter {
+                config,
+                service: Buffer::new(make_service(), MANIFEST_BUFFER_SIZE),
+                reader: Box::new(reader),
+                manifests: Mutex::new(Manifests {
+                    partitions: HashMap::new(),
+                    last_eviction: Instant::now(),
+                }),
+                pending: Mutex::default(),
+            });
+            if format == ManifestFormat::Success {
+                tokio::spawn(write_success_markers(Arc::downgrade(&writer)));
+            }
+            writer
+        });
+
+        Self {
+            inner: make_service(),
+            writer,
+        }
+    }
+}
+
+impl<S, Request> Service<Request> for ManifestService<S, Request>
+where
+    S: Service<Request> + Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: Into<crate::Error> + Send + Sync,
+    S::Future: Send + 'static,
+    Request: ManifestRequest + Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let manifest = self.writer.as_ref().map(|writer| {
+            (
+                Arc::clone(writer),
+                request.manifest_entry(),
+                request.manifest_request(String::new(), Bytes::new()),
+            )
+        });
+        let future = self.inner.call(request);
+
+        Box::pin(async move {
+            let response = future.await?;
+            if let Some((writer, entry, template)) = manifest {
+                if response.event_status() == EventStatus::Delivered {
+                    writer.record(entry, template).await;
+                }
+            }
+            Ok(response)
+        })
+    }
+}
+
+struct ManifestWriter<S, Request>
+where
+    S: Service<Request>,
+{
+    config: ManifestConfig,
+    service: Buffer<S, Request>,
+    reader: Box<dyn ObjectReader<Request>>,
+    manifests: Mutex<Manifests>,
+    /// The partitions waiting for their success marker, with the time their last object was
+    /// written at.
+    pending: Mutex<HashMap<String, PendingPartition<Request>>>,
+}
+
+/// The manifests of the partitions objects were recently added to, for the `json` format.
+struct Manifests {
+    partitions: HashMap<String, Arc<tokio::sync::Mutex<PartitionManifest>>>,
+    last_eviction: Instant,
+}
+
+struct PartitionManifest {
+    manifest: Manifest,
+    /// Whether the manifest already written for the partition was read.
+    loaded: bool,
+    updated_at: Instant,
+}
+
+struct PendingPartition<Request> {
+    manifest: Manifest,
+    updated_at: Instant,
+    template: Request,
+}
+
+impl<S, Request> ManifestWriter<S, Request>
+where
+    S: Service<Request> + Send + 'static,
+    S::Response: DriverResponse,
+    S::Error: Into<crate::Error> + Send + Sync,
+    S::Future: Send,
+    Request: ManifestRequest + Send + 'static,
+{
+    async fn record(&self, entry: ManifestEntry, template: Request) {
+        match self.config.format {
+            ManifestFormat::Json => {
+                let partition = partition(&entry.key).to_owned();
+                let manifest = self.partition_manifest(&partition);
+                let key = format!("{}{}", partition, self.config.name);
+
+                // The manifest stays locked while it's read and written, so that the last manifest
+                // written for a partition is always the most complete one.
+                let mut manifest = manifest.lock().await;
+                manifest.updated_at = Instant::now();
+                if !manifest.loaded {
+                    match self.read_manifest(&key, &template).await {
+                        Ok(written) => {
+                            // The objects recorded while the manifest couldn't be read come last.
+                            let recorded = std::mem::replace(
+                                &mut manifest.manifest,
+                                written.unwrap_or_else(|| Manifest {
+                                    partition: partition.clone(),
+                                    ..Default::default()
+                                }),
+                            );
+                            for object in recorded.objects {
+                                manifest.manifest.add(object);
+                            }
+                            manifest.loaded = true;
+                        }
+                        Err(error) => {
+                            // Writing the manifest now would overwrite the objects it lists, so
+                            // the object is only recorded, until the manifest can be read.
+                            emit!(ObjectManifestReadError { key: &key, error });
+                            manifest.manifest.add(entry);
+                            return;
+                        }
+                    }
+                }
+
+                manifest.manifest.add(entry);
+                let body =
+                    serde_json::to_vec(&manifest.manifest).expect("manifests are serializable");
+                self.write(key, body.into(), &template).await;
+            }
+            ManifestFormat::Marker => {
+                let key = format!("{}{}", entry.key, self.config.marker_suffix);
+                let body = serde_json::to_vec(&entry).expect("manifests are serializable");
+                self.write(key, body.into(), &template).await;
+            }
+            ManifestFormat::Success => {
+                let partition = partition(&entry.key).to_owned();
+                let mut pending = self.pending.lock().expect("pending lock poisoned");
+                let pending =
+                    pending
+                        .entry(partition.clone())
+                        .or_insert_with(|| PendingPartition {
+                            manifest: Manifest {
+                                partition,
+                                ..Default::default()
+                            },
+                            updated_at: Instant::now(),
+                            template,
+                        });
+                pending.manifest.add(entry);
+                pending.updated_at = Instant::now();
+            }
+        }
+    }
+
+    /// Returns the manifest of the partition, after dropping the manifests of the partitions no
+    /// object has been added to for a while.
+    fn partition_manifest(&self, partition: &str) -> Arc<tokio::sync::Mutex<PartitionManifest>> {
+        let mut manifests = self.manifests.lock().expect("manifests lock poisoned");
+
+        if manifests.last_eviction.elapsed() >= MANIFEST_IDLE_TIMEOUT {
+            // The manifests being written are still referenced by the objects they record.
+            manifests.partitions.retain(|_, manifest| {
+                Arc::strong_count(manifest) > 1
+                    || manifest.try_lock().map_or(true, |manifest| {
+                        manifest.updated_at.elapsed() < MANIFEST_IDLE_TIMEOUT
+                    })
+            });
+            manifests.last_eviction = Instant::now();
+        }
+
+        Arc::clone(
+            manifests
+                .partitions
+                .entry(partition.to_owned())
+                .or_insert_with(|| {
+                    Arc::new(tokio::sync::Mutex::new(PartitionManifest {
+                        manifest: Manifest {
+                            partition: partition.to_owned(),
+                            ..Default::default()
+                        },
+                        loaded: false,
+                        updated_at: Instant::now(),
+                    }))
+                }),
+        )
+    }
+
+    async fn read_manifest(
+        &self,
+        key: &str,
+        template: &Request,
+    ) -> crate::Result<Option<Manifest>> {
+        match self.reader.read_object(template, key.to_owned()).await? {
+            Some(body) => Ok(Some(serde_json::from_slice(&body)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Writes the success markers of the partitions no object has been added to for
+    /// `success_after_secs`, which are then forgotten.
+    async fn write_idle_success_markers(&self) {
+        let success_after = Duration::from_secs(self.config.success_after_secs.get());
+        let idle = {
+            let mut pending = self.pending.lock().expect("pending lock poisoned");
+            let partitions = pending
+                .iter()
+                .filter(|(_, pending)| pending.updated_at.elapsed() >= success_after)
+                .map(|(partition, _)| partition.clone())
+                .collect::<Vec<_>>();
+            partitions
+                .into_iter()
+                .filter_map(|partition| pending.remove(&partition))
+                .collect::<Vec<_>>()
+        };
+
+        for pending in idle {
+            let key = format!("{}{}", pending.manifest.partition, self.config.success_name);
+            let body = serde_json::to_vec(&pending.manifest).expect("manifests are serializable");
+            self.write(key, body.into(), &pending.template).await;
+        }
+    }
+
+    async fn write(&self, key: String, body: Bytes, template: &Request) {
+        let request = template.manifest_request(key.clone(), body);
+        let mut service = self.service.clone();
+        let result = match service.ready().await {
+            Ok(service) => service.call(request).await,
+            Err(error) => Err(error),
+        };
+
+        let error: crate::Error = match result {
+            Ok(response) => match response.event_status() {
+                EventStatus::Delivered => return,
+                status => format!("Request failed with status {:?}.", status).into(),
+            },
+            Err(error) => error,
+        };
+        emit!(ObjectManifestWriteError { key: &key, error });
+    }
+}
+
+/// Writes the success markers of the idle partitions, until the writer is dropped along with the
+/// sink.
+async fn write_success_markers<S, Request>(writer: Weak<ManifestWriter<S, Request>>)
+where
+    S: Service<Request> + Send + 'static,
+    S::Response: DriverResponse,
+    S::Error: Into<crate::Error> + Send + Sync,
+    S::Future: Send,
+    Request: ManifestRequest + Send + 'static,
+{
+    let mut interval = tokio::time::interval(SUCCESS_CHECK_INTERVAL);
+    loop {
+        interval.tick().await;
+        match writer.upgrade() {
+            Some(writer) => writer.write_idle_success_markers().await,
+            None => break,
+        }
+    }
+}
+
+/// Returns the partition of the object with the given key, i.e. its key up to the last `/`.
+fn partition(key: &str) -> &str {
+    key.rfind('/').map_or("", |index| &key[..=index])
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_core::internal_event::EventsSent;
+
+    use super::*;
+
+    #[derive(Clone, Debug)]
+    struct TestRequest {
+        key: String,
+        body: Bytes,
+        records: usize,
+    }
+
+    impl ManifestRequest for TestRequest {
+        fn manifest_entry(&self) -> ManifestEntry {
+            ManifestEntry {
+                key: self.key.clone(),
+                records: self.records,
+                bytes: self.body.len(),
+            }
+        }
+
+        fn manifest_request(&self, key: String, body: Bytes) -> Self {
+            Self {
+                key,
+                body,
+                records: 0,
+            }
+        }
+    }
+
+    struct TestResponse;
+
+    impl DriverResponse for TestResponse {
+        fn event_status(&self) -> EventStatus {
+            EventStatus::Delivered
+        }
+
+        fn events_sent(&self) -> EventsSent {
+            EventsSent {
+                count: 0,
+                byte_size: 0,
+                output: None,
+            }
+        }
+    }
+
+    type Objects = Arc<Mutex<HashMap<String, Bytes>>>;
+
+    /// Reads the objects written to `objects`.
+    struct TestReader(Objects);
+
+    impl ObjectReader<TestRequest> for TestReader {
+        fn read_object(
+            &self,
+            _request: &TestRequest,
+            key: String,
+        ) -> BoxFuture<'static, crate::Result<Option<Bytes>>> {
+            let object = self.0.lock().unwrap().get(&key).cloned();
+            Box::pin(async move { Ok(object) })
+        }
+    }
+
+    /// Writes the objects to `objects`, keeping the last body written for each key.
+    async fn write_objects(
+        config: ManifestConfig,
+        requests: Vec<(&str, &str, usize)>,
+    ) -> HashMap<String, Bytes> {
+        write_objects_to(Objects::default(), config, requests).await
+    }
+
+    async fn write_objects_to(
+        objects: Objects,
+        config: ManifestConfig,
+        requests: Vec<(&str, &str, usize)>,
+    ) -> HashMap<String, Bytes> {
+        let reader = TestReader(Arc::clone(&objects));
+        let mut service = ManifestService::new(Some(config), reader, || {
+            let objects = Arc::clone(&objects);
+            tower::service_fn(move |request: TestRequest| {
+                objects.lock().unwrap().insert(request.key, request.body);
+                futures::future::ok::<_, crate::Error>(TestResponse)
+            })
+        });
+
+        for (key, body, records) in requests {
+            let request = TestRequest {
+                key: key.to_owned(),
+                body: Bytes::from(body.to_owned()),
+                records,
+            };
+            service.ready().await.unwrap().call(request).await.unwrap();
+        }
+
+        let objects = objects.lock().unwrap().clone();
+        objects
+    }
+
+    #[tokio::test]
+    async fn writes_json_manifest_per_partition() {
+        let config = toml::from_str::<ManifestConfig>(r#"format = "json""#).unwrap();
+        let objects = write_objects(
+            config,
+            vec![
+                ("date=2022-06-01/a.log", "abc", 3),
+                ("date=2022-06-01/b.log", "de", 2),
+                ("date=2022-06-02/c.log", "f", 1),
+            ],
+        )
+        .await;
+
+        let manifest: Manifest =
+            serde_json::from_slice(&objects["date=2022-06-01/manifest.json"]).unwrap();
+        assert_eq!(
+            manifest,
+            Manifest {
+                partition: "date=2022-06-01/".into(),
+                records: 5,
+                bytes: 5,
+                objects: vec![
+                    ManifestEntry {
+                        key: "date=2022-06-01/a.log".into(),
+                        records: 3,
+                        bytes: 3,
+                    },
+                    ManifestEntry {
+                        key: "date=2022-06-01/b.log".into(),
+                        records: 2,
+                        bytes: 2,
+                    },
+                ],
+            }
+        );
+
+        let manifest: Manifest =
+            serde_json::from_slice(&objects["date=2022-06-02/manifest.json"]).unwrap();
+        assert_eq!(manifest.objects.len(), 1);
+        assert_eq!(manifest.records, 1);
+    }
+
+    #[tokio::test]
+    async fn extends_existing_json_manifest() {
+        let config = toml::from_str::<ManifestConfig>(r#"format = "json""#).unwrap();
+        let objects = Objects::default();
+        write_objects_to(
+            Arc::clone(&objects),
+            config.clone(),
+            vec![("date=2022-06-01/a.log", "abc", 3)],
+        )
+        .await;
+
+        // A new service, like after a restart, adds to the manifest written by the previous one.
+        let objects = write_objects_to(
+            objects,
+            config,
+            vec![
+                ("date=2022-06-01/b.log", "de", 2),
+                ("date=2022-06-01/a.log", "abcd", 4),
+            ],
+        )
+        .await;
+
+        let manifest: Manifest =
+            serde_json::from_slice(&objects["date=2022-06-01/manifest.json"]).unwrap();
+        assert_eq!(manifest.records, 6);
+        assert_eq!(manifest.bytes, 6);
+        let keys = manifest
+            .objects
+            .iter()
+            .map(|object| object.key.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec!["date=2022-06-01/b.log", "date=2022-06-01/a.log"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn drops_idle_json_manifests() {
+        let config = toml::from_str::<ManifestConfig>(r#"format = "json""#).unwrap();
+        let objects = Objects::default();
+        let mut service =
+            ManifestService::new(Some(config), TestReader(Arc::clone(&objects)), || {
+                let objects = Arc::clone(&objects);
+                tower::service_fn(move |request: TestRequest| {
+                    objects.lock().unwrap().insert(request.key, request.body);
+                    futures::future::ok::<_, crate::Error>(TestResponse)
+                })
+            });
+
+        for key in ["date=2022-06-01/a.log", "date=2022-06-02/b.log"] {
+            let request = TestRequest {
+                key: key.to_owned(),
+                body: Bytes::from_static(b"abc"),
+                records: 3,
+            };
+            service.ready().await.unwrap().call(request).await.unwrap();
+            tokio::time::sleep(MANIFEST_IDLE_TIMEOUT).await;
+        }
+
+        let partitions = service
+            .writer
+            .as_ref()
+            .unwrap()
+            .manifests
+            .lock()
+            .unwrap()
+            .partitions
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(partitions, vec!["date=2022-06-02/".to_owned()]);
+    }
+
+    #[tokio::test]
+    async fn writes_marker_per_object() {
+        let config = toml::from_str::<ManifestConfig>(
+            r#"
+                format = "marker"
+                marker_suffix = ".done"
+            "#,
+        )
+        .unwrap();
+        let objects = write_objects(
+            config,
+            vec![
+                ("date=2022-06-01/a.log", "abc", 3),
+                ("date=2022-06-01/b.log", "de", 2),
+            ],
+        )
+        .await;
+
+        assert_eq!(objects.len(), 4);
+        let entry: ManifestEntry =
+            serde_json::from_slice(&objects["date=2022-06-01/b.log.done"]).unwrap();
+        assert_eq!(
+            entry,
+            ManifestEntry {
+                key: "date=2022-06-01/b.log".into(),
+                records: 2,
+                bytes: 2,
+            }
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn writes_success_marker_per_idle_partition() {
+        let config = toml::from_str::<ManifestConfig>(
+            r#"
+                format = "success"
+                success_after_secs = 10
+            "#,
+        )
+        .unwrap();
+        let objects = Objects::default();
+        let reader = TestReader(Arc::clone(&objects));
+        let mut service = ManifestService::new(Some(config), reader, || {
+            let objects = Arc::clone(&objects);
+            tower::service_fn(move |request: TestRequest| {
+                objects.lock().unwrap().insert(request.key, request.body);
+                futures::future::ok::<_, crate::Error>(TestResponse)
+            })
+        });
+
+        for (key, body) in [
+            ("date=2022-06-01/a.log", "abc"),
+            ("date=2022-06-01/b.log", "de"),
+        ] {
+            let request = TestRequest {
+                key: key.to_owned(),
+                body: Bytes::from(body.to_owned()),
+                records: 1,
+            };
+            service.ready().await.unwrap().call(request).await.unwrap();
+        }
+
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        assert!(!objects
+            .lock()
+            .unwrap()
+            .contains_key("date=2022-06-01/_SUCCESS"));
+
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        let marker = objects.lock().unwrap()["date=2022-06-01/_SUCCESS"].clone();
+        let manifest: Manifest = serde_json::from_slice(&marker).unwrap();
+        assert_eq!(manifest.partition, "date=2022-06-01/");
+        assert_eq!(manifest.records, 2);
+        assert_eq!(manifest.objects.len(), 2);
+    }
+
+    #[test]
+    fn partition_is_the_object_directory() {
+        assert_eq!(
+            partition("logs/date=2022-06-01/a.log"),
+            "logs/date=2022-06-01/"
+        );
+        assert_eq!(partition("a.log"), "");
+    }
+}
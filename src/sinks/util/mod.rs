@@ -4,9 +4,21 @@ pub mod buffer;
 pub mod builder;
 pub mod compressor;
 pub mod encoding;
+#[cfg(any(
+    feature = "sinks-grpc",
+    feature = "sinks-jaeger",
+    feature = "sinks-opentelemetry"
+))]
+pub mod grpc;
 pub mod http;
+#[cfg(any(feature = "sinks-aws_kinesis_firehose", feature = "sinks-aws_kinesis_streams"))]
+pub mod kpl;
+#[cfg(any(feature = "sinks-aws_s3", feature = "sinks-azure_blob", feature = "sinks-gcp"))]
+pub mod manifest;
 pub mod metadata;
 pub mod normalizer;
+#[cfg(feature = "sinks-aws_s3")]
+pub mod parquet;
 pub mod partitioner;
 pub mod processed_event;
 pub mod request_builder;
@@ -14,7 +26,11 @@ pub mod retries;
 pub mod service;
 pub mod sink;
 pub mod socket_bytes_sink;
+#[cfg(any(feature = "sinks-jaeger", feature = "sinks-zipkin"))]
+pub mod spans;
 pub mod statistic;
+#[cfg(any(feature = "sinks-balance", feature = "sinks-failover"))]
+pub mod targets;
 pub mod tcp;
 #[cfg(test)]
 pub mod test;
@@ -54,7 +70,7 @@ pub use uri::UriSerde;
 use crate::event::{Event, EventFinalizers};
 
 #[derive(Debug, Snafu)]
-enum SinkBuildError {
+pub(in crate::sinks) enum SinkBuildError {
     #[snafu(display("Missing host in address field"))]
     MissingHost,
     #[snafu(display("Missing port in address field"))]
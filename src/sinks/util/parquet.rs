@@ -0,0 +1,466 @@
+//! Encoding of batches of events as [Parquet] files, for sinks writing objects to data lakes.
+//!
+//! Each batch is written as a file whose columns are either configured, or derived from the
+//! schema definition of the events the sink receives. Fields that aren't columns of the file are
+//! dropped.
+//!
+//! [Parquet]: https://parquet.apache.org/
+
+use std::{collections::HashSet, io, sync::Arc};
+
+use parquet::{
+    basic::{Compression, ConvertedType, Repetition, Type as PhysicalType},
+    column::writer::{ColumnWriter, ColumnWriterImpl},
+    data_type::{ByteArray, DataType},
+    errors::ParquetError,
+    file::{
+        properties::{WriterProperties, WriterPropertiesPtr},
+        writer::{FileWriter, InMemoryWriteableCursor, SerializedFileWriter},
+    },
+    schema::types::{Type, TypePtr},
+};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use value::Kind;
+
+use crate::{
+    event::{Event, LogEvent, Value},
+    schema,
+    sinks::util::encoding::{Encoder, Transformer},
+};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ParquetConfig {
+    /// The columns of the files, derived from the schema definition of the events when unset.
+    pub schema: Option<Vec<ParquetColumn>>,
+    /// The maximum number of events in each row group of the files.
+    #[serde(default = "default_row_group_size")]
+    pub row_group_size: usize,
+    #[serde(default)]
+    pub compression: ParquetCompression,
+}
+
+const fn default_row_group_size() -> usize {
+    10_000
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ParquetColumn {
+    /// The path of the field the column holds.
+    pub name: String,
+    #[serde(rename = "type")]
+    pub column_type: ParquetColumnType,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ParquetColumnType {
+    String,
+    Int64,
+    Double,
+    Boolean,
+    /// Microseconds since the Unix epoch.
+    Timestamp,
+    /// Any value, encoded as JSON.
+    Json,
+}
+
+impl ParquetColumnType {
+    /// The type of the column holding values of the kind, which is JSON unless the kind is a single
+    /// primitive type.
+    fn from_kind(kind: &Kind) -> Self {
+        let mut types = [
+            (kind.contains_bytes(), Self::String),
+            (kind.contains_integer(), Self::Int64),
+            (kind.contains_float(), Self::Double),
+            (kind.contains_boolean(), Self::Boolean),
+            (kind.contains_timestamp(), Self::Timestamp),
+        ]
+        .into_iter()
+        .filter_map(|(contained, column_type)| contained.then(|| column_type));
+        let collection = kind.contains_array() || kind.contains_object() || kind.contains_regex();
+
+        match (types.next(), types.next(), collection) {
+            (Some(column_type), None, false) => column_type,
+            _ => Self::Json,
+        }
+    }
+
+    const fn physical_type(self) -> (PhysicalType, ConvertedType) {
+        match self {
+            Self::String => (PhysicalType::BYTE_ARRAY, ConvertedType::UTF8),
+            Self::Int64 => (PhysicalType::INT64, ConvertedType::NONE),
+            Self::Double => (PhysicalType::DOUBLE, ConvertedType::NONE),
+            Self::Boolean => (PhysicalType::BOOLEAN, ConvertedType::NONE),
+            Self::Timestamp => (PhysicalType::INT64, ConvertedType::TIMESTAMP_MICROS),
+            Self::Json => (PhysicalType::BYTE_ARRAY, ConvertedType::JSON),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, PartialEq, Eq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ParquetCompression {
+    None,
+    #[derivative(Default)]
+    Snappy,
+    Gzip,
+    Zstd,
+}
+
+impl From<ParquetCompression> for Compression {
+    fn from(compression: ParquetCompression) -> Self {
+        match compression {
+            ParquetCompression::None => Self::UNCOMPRESSED,
+            ParquetCompression::Snappy => Self::SNAPPY,
+            ParquetCompression::Gzip => Self::GZIP,
+            ParquetCompression::Zstd => Self::ZSTD,
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum ParquetBuildError {
+    #[snafu(display(
+        "The Parquet schema can't be derived from the schema of the events, it must be set with `parquet.schema`."
+    ))]
+    UnknownSchema,
+    #[snafu(display("Duplicate Parquet column {:?}.", name))]
+    DuplicateColumn { name: String },
+    #[snafu(display("`parquet.row_group_size` must be greater than zero."))]
+    EmptyRowGroups,
+    #[snafu(display("Invalid Parquet schema: {}", source))]
+    InvalidSchema { source: ParquetError },
+}
+
+impl ParquetConfig {
+    /// Builds the encoder, deriving the columns from the definition of the events unless they're
+    /// configured.
+    pub fn build(
+        &self,
+        transformer: Transformer,
+        definition: &schema::Definition,
+    ) -> Result<ParquetEncoder, ParquetBuildError> {
+        let columns = match &self.schema {
+            Some(columns) => columns.clone(),
+            None => definition
+                .collection()
+                .known()
+                .iter()
+                .map(|(field, kind)| ParquetColumn {
+                    name: field.as_str().to_owned(),
+                    column_type: ParquetColumnType::from_kind(kind),
+                })
+                .collect(),
+        };
+        if columns.is_empty() {
+            return Err(ParquetBuildError::UnknownSchema);
+        }
+        if self.row_group_size == 0 {
+            return Err(ParquetBuildError::EmptyRowGroups);
+        }
+
+        let mut names = HashSet::new();
+        if let Some(column) = columns.iter().find(|column| !names.insert(&column.name)) {
+            return Err(ParquetBuildError::DuplicateColumn {
+                name: column.name.clone(),
+            });
+        }
+
+        let schema =
+            build_schema(&columns).map_err(|source| ParquetBuildError::InvalidSchema { source })?;
+        let properties = WriterProperties::builder()
+            .set_compression(self.compression.into())
+            .set_max_row_group_size(self.row_group_size)
+            .set_created_by(format!("vector {}", crate::get_version()))
+            .build();
+
+        Ok(ParquetEncoder {
+            transformer,
+            columns,
+            schema,
+            properties: Arc::new(properties),
+            row_group_size: self.row_group_size,
+        })
+    }
+}
+
+fn build_schema(columns: &[ParquetColumn]) -> Result<TypePtr, ParquetError> {
+    let mut fields = columns
+        .iter()
+        .map(|column| {
+            let (physical_type, converted_type) = column.column_type.physical_type();
+            Type::primitive_type_builder(&column.name, physical_type)
+                .with_converted_type(converted_type)
+                .with_repetition(Repetition::OPTIONAL)
+                .build()
+                .map(Arc::new)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Type::group_type_builder("vector")
+        .with_fields(&mut fields)
+        .build()
+        .map(Arc::new)
+}
+
+/// Encodes batches of log events as Parquet files.
+///
+/// Missing fields, and values that can't be converted to the type of their column, are written as
+/// nulls.
+#[derive(Clone, Debug)]
+pub struct ParquetEncoder {
+    transformer: Transformer,
+    columns: Vec<ParquetColumn>,
+    schema: TypePtr,
+    properties: WriterPropertiesPtr,
+    row_group_size: usize,
+}
+
+impl ParquetEncoder {
+    fn write(&self, logs: &[LogEvent]) -> Result<Vec<u8>, ParquetError> {
+        let cursor = InMemoryWriteableCursor::default();
+        let mut writer = SerializedFileWriter::new(
+            cursor.clone(),
+            Arc::clone(&self.schema),
+            Arc::clone(&self.properties),
+        )?;
+
+        for rows in logs.chunks(self.row_group_size) {
+            let mut row_group = writer.next_row_group()?;
+            for column in &self.columns {
+                let mut column_writer = row_group
+                    .next_column()?
+                    .expect("The schema has a column for each configured column.");
+                write_column(&mut column_writer, column, rows)?;
+                row_group.close_column(column_writer)?;
+            }
+            writer.close_row_group(row_group)?;
+        }
+
+        writer.close()?;
+        drop(writer);
+        Ok(cursor
+            .into_inner()
+            .expect("The writer shares the cursor until it's dropped."))
+    }
+}
+
+impl Encoder<Vec<Event>> for ParquetEncoder {
+    fn encode_input(&self, events: Vec<Event>, writer: &mut dyn io::Write) -> io::Result<usize> {
+        let logs = events
+            .into_iter()
+            .filter_map(|mut event| {
+                self.transformer.transform(&mut event);
+                event.try_into_log()
+            })
+            .collect::<Vec<_>>();
+
+        let buffer = self
+            .write(&logs)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        writer.write_all(&buffer)?;
+        Ok(buffer.len())
+    }
+}
+
+fn write_column(
+    writer: &mut ColumnWriter,
+    column: &ParquetColumn,
+    rows: &[LogEvent],
+) -> Result<(), ParquetError> {
+    let values = rows.iter().map(|log| {
+        log.get(column.name.as_str())
+            .filter(|value| !value.is_null())
+    });
+
+    match (writer, column.column_type) {
+        (ColumnWriter::ByteArrayColumnWriter(writer), ParquetColumnType::String) => {
+            let values = values.map(|value| {
+                value.map(|value| match value {
+                    Value::Bytes(bytes) => ByteArray::from(bytes.to_vec()),
+                    value => ByteArray::from(value.to_string_lossy().into_bytes()),
+                })
+            });
+            write_values(writer, values)
+        }
+        (ColumnWriter::ByteArrayColumnWriter(writer), ParquetColumnType::Json) => {
+            let values = values.map(|value| {
+                value
+                    .and_then(|value| serde_json::to_vec(value).ok())
+                    .map(ByteArray::from)
+            });
+            write_values(writer, values)
+        }
+        (ColumnWriter::Int64ColumnWriter(writer), ParquetColumnType::Int64) => {
+            let values = values.map(|value| match value {
+                Some(Value::Integer(integer)) => Some(*integer),
+                _ => None,
+            });
+            write_values(writer, values)
+        }
+        (ColumnWriter::Int64ColumnWriter(writer), ParquetColumnType::Timestamp) => {
+            let values = values.map(|value| match value {
+                Some(Value::Timestamp(timestamp)) => Some(
+                    timestamp.timestamp() * 1_000_000
+                        + i64::from(timestamp.timestamp_subsec_micros()),
+                ),
+                _ => None,
+            });
+            write_values(writer, values)
+        }
+        (ColumnWriter::DoubleColumnWriter(writer), ParquetColumnType::Double) => {
+            let values = values.map(|value| match value {
+                Some(Value::Float(float)) => Some(float.into_inner()),
+                Some(Value::Integer(integer)) => Some(*integer as f64),
+                _ => None,
+            });
+            write_values(writer, values)
+        }
+        (ColumnWriter::BoolColumnWriter(writer), ParquetColumnType::Boolean) => {
+            let values = values.map(|value| match value {
+                Some(Value::Boolean(boolean)) => Some(*boolean),
+                _ => None,
+            });
+            write_values(writer, values)
+        }
+        _ => unreachable!("The column writers match the types of the columns."),
+    }
+}
+
+/// Writes the values of an optional column, nulls being only recorded in its definition levels.
+fn write_values<T: DataType>(
+    writer: &mut ColumnWriterImpl<T>,
+    values: impl Iterator<Item = Option<T::T>>,
+) -> Result<(), ParquetError> {
+    let mut def_levels = Vec::new();
+    let values = values
+        .filter_map(|value| {
+            def_levels.push(i16::from(value.is_some()));
+            value
+        })
+        .collect::<Vec<_>>();
+
+    writer.write_batch(&values, Some(&def_levels), None)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use parquet::{
+        file::{
+            reader::{FileReader, SerializedFileReader},
+            serialized_reader::SliceableCursor,
+        },
+        record::RowAccessor,
+    };
+
+    use value::kind::Collection;
+
+    use super::*;
+
+    fn encoder(config: &str, definition: &schema::Definition) -> ParquetEncoder {
+        toml::from_str::<ParquetConfig>(config)
+            .unwrap()
+            .build(Transformer::default(), definition)
+            .unwrap()
+    }
+
+    #[test]
+    fn derives_columns_from_definition() {
+        let definition = schema::Definition::empty()
+            .required_field("message", Kind::bytes(), None)
+            .required_field("count", Kind::integer(), None)
+            .optional_field("ratio", Kind::float().or_integer(), None)
+            .required_field("labels", Kind::object(Collection::empty()), None);
+        let encoder = encoder("", &definition);
+
+        assert_eq!(
+            encoder.columns,
+            vec![
+                ParquetColumn {
+                    name: "count".into(),
+                    column_type: ParquetColumnType::Int64,
+                },
+                ParquetColumn {
+                    name: "labels".into(),
+                    column_type: ParquetColumnType::Json,
+                },
+                ParquetColumn {
+                    name: "message".into(),
+                    column_type: ParquetColumnType::String,
+                },
+                ParquetColumn {
+                    name: "ratio".into(),
+                    column_type: ParquetColumnType::Json,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn requires_schema() {
+        let config = toml::from_str::<ParquetConfig>("").unwrap();
+        assert!(matches!(
+            config.build(Transformer::default(), &schema::Definition::empty()),
+            Err(ParquetBuildError::UnknownSchema)
+        ));
+    }
+
+    #[test]
+    fn encodes_events() {
+        let encoder = encoder(
+            r#"
+            row_group_size = 2
+            schema = [
+                { name = "message", type = "string" },
+                { name = "status", type = "int64" },
+                { name = "timestamp", type = "timestamp" },
+                { name = "http.path", type = "string" },
+            ]
+            "#,
+            &schema::Definition::empty(),
+        );
+
+        let timestamp = Utc.ymd(2022, 5, 4).and_hms_micro(10, 30, 0, 123_456);
+        let events = (0..3)
+            .map(|index| {
+                let mut log = LogEvent::from(format!("event {}", index));
+                log.insert("timestamp", timestamp);
+                log.insert("http.path", "/");
+                if index != 1 {
+                    log.insert("status", 200);
+                }
+                Event::from(log)
+            })
+            .chain(std::iter::once(Event::from(crate::event::Metric::new(
+                "dropped",
+                crate::event::MetricKind::Absolute,
+                crate::event::MetricValue::Counter { value: 1.0 },
+            ))))
+            .collect::<Vec<_>>();
+
+        let mut buffer = Vec::new();
+        encoder.encode_input(events, &mut buffer).unwrap();
+
+        let reader = SerializedFileReader::new(SliceableCursor::new(buffer)).unwrap();
+        let metadata = reader.metadata();
+        assert_eq!(metadata.num_row_groups(), 2);
+        assert_eq!(metadata.file_metadata().num_rows(), 3);
+
+        let rows = reader.get_row_iter(None).unwrap().collect::<Vec<_>>();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].get_string(0).unwrap(), "event 0");
+        assert_eq!(rows[0].get_long(1).unwrap(), 200);
+        assert!(rows[1].get_long(1).is_err());
+        assert_eq!(
+            rows[2].get_timestamp_micros(2).unwrap(),
+            timestamp.timestamp() as u64 * 1_000_000 + 123_456
+        );
+        assert_eq!(rows[2].get_string(3).unwrap(), "/");
+    }
+}
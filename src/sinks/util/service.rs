@@ -11,23 +11,34 @@ use tower::{
 use vector_buffers::Acker;
 
 pub use crate::sinks::util::service::{
+    circuit_breaker::{CircuitBreaker, CircuitBreakerLayer, CircuitBreakerSettings},
     concurrency::{concurrency_is_none, Concurrency},
     map::Map,
+    partition::PartitionedService,
 };
-use crate::sinks::util::{
-    adaptive_concurrency::{
-        AdaptiveConcurrencyLimit, AdaptiveConcurrencyLimitLayer, AdaptiveConcurrencySettings,
+use crate::{
+    egress::{Egress, EgressLimit},
+    sinks::util::{
+        adaptive_concurrency::{
+            AdaptiveConcurrencyLimit, AdaptiveConcurrencyLimitLayer, AdaptiveConcurrencySettings,
+        },
+        retries::{FixedRetryPolicy, RetryLogic},
+        service::map::MapLayer,
+        sink::Response,
+        Batch, BatchSink, Partition, PartitionBatchSink,
     },
-    retries::{FixedRetryPolicy, RetryLogic},
-    service::map::MapLayer,
-    sink::Response,
-    Batch, BatchSink, Partition, PartitionBatchSink,
 };
 
+mod circuit_breaker;
 mod concurrency;
 mod map;
+mod partition;
 
-pub type Svc<S, L> = RateLimit<AdaptiveConcurrencyLimit<Retry<FixedRetryPolicy<L>, Timeout<S>>, L>>;
+pub type Svc<S, L> = Egress<
+    RateLimit<
+        AdaptiveConcurrencyLimit<Retry<FixedRetryPolicy<L>, CircuitBreaker<Timeout<S>, L>>, L>,
+    >,
+>;
 pub type TowerBatchedSink<S, B, RL> = BatchSink<Svc<S, RL>, B>;
 pub type TowerPartitionSink<S, B, RL, K> = PartitionBatchSink<Svc<S, RL>, B, K>;
 
@@ -78,6 +89,8 @@ pub struct TowerRequestConfig {
     pub retry_initial_backoff_secs: Option<u64>, // 1
     #[serde(default)]
     pub adaptive_concurrency: AdaptiveConcurrencySettings,
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerSettings,
 }
 
 pub const CONCURRENCY_DEFAULT: Concurrency = Concurrency::None;
@@ -105,6 +118,7 @@ impl TowerRequestConfig {
             retry_max_duration_secs: Some(RETRY_MAX_DURATION_SECONDS_DEFAULT),
             retry_initial_backoff_secs: Some(RETRY_INITIAL_BACKOFF_SECONDS_DEFAULT),
             adaptive_concurrency: AdaptiveConcurrencySettings::const_default(),
+            circuit_breaker: CircuitBreakerSettings::const_default(),
         }
     }
 
@@ -174,6 +188,8 @@ impl TowerRequestConfig {
                     .unwrap_or(RETRY_INITIAL_BACKOFF_SECONDS_DEFAULT),
             ),
             adaptive_concurrency: self.adaptive_concurrency,
+            circuit_breaker: self.circuit_breaker,
+            partition: None,
         }
     }
 }
@@ -188,6 +204,10 @@ pub struct TowerRequestSettings {
     pub retry_max_duration_secs: Duration,
     pub retry_initial_backoff_secs: Duration,
     pub adaptive_concurrency: AdaptiveConcurrencySettings,
+    pub circuit_breaker: CircuitBreakerSettings,
+    /// The partition of the requests, for the sinks sending the requests of each partition with
+    /// their own service, under which their adaptive concurrency controller is registered.
+    pub partition: Option<String>,
 }
 
 impl TowerRequestSettings {
@@ -270,16 +290,24 @@ where
     fn layer(&self, inner: S) -> Self::Service {
         let policy = self.settings.retry_policy(self.retry_logic.clone());
         ServiceBuilder::new()
+            .layer(EgressLimit::layer())
             .rate_limit(
                 self.settings.rate_limit_num,
                 self.settings.rate_limit_duration,
             )
-            .layer(AdaptiveConcurrencyLimitLayer::new(
-                self.settings.concurrency,
-                self.settings.adaptive_concurrency,
+            .layer(
+                AdaptiveConcurrencyLimitLayer::new(
+                    self.settings.concurrency,
+                    self.settings.adaptive_concurrency,
+                    self.retry_logic.clone(),
+                )
+                .partition(self.settings.partition.clone()),
+            )
+            .retry(policy)
+            .layer(CircuitBreakerLayer::new(
+                self.settings.circuit_breaker,
                 self.retry_logic.clone(),
             ))
-            .retry(policy)
             .timeout(self.settings.timeout)
             .service(inner)
     }
@@ -0,0 +1,526 @@
+//! Hold back the requests to a service that keeps failing.
+//!
+//! The circuit opens after a number of consecutive requests failed, or took longer than the
+//! latency threshold, and stays open for a while, during which no requests are sent and the
+//! service isn't ready, which propagates the backpressure to the sink. The circuit is then half
+//! opened lazily, by the next poll of the service, letting a few probe requests through which close
+//! it again if they all succeed, or open it for another while as soon as one of them fails.
+
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use futures::ready;
+use pin_project::{pin_project, pinned_drop};
+use serde::{Deserialize, Serialize};
+use tokio::time::{sleep_until, Instant, Sleep};
+use tower::{timeout::error::Elapsed, Layer, Service};
+
+use crate::{
+    internal_events::{CircuitBreakerClosed, CircuitBreakerHalfOpened, CircuitBreakerOpened},
+    sinks::util::retries::{RetryAction, RetryLogic},
+};
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CircuitBreakerSettings {
+    /// Whether the requests are held back while the service keeps failing.
+    pub(super) enabled: bool,
+
+    /// The number of consecutive failed requests opening the circuit.
+    pub(super) failure_threshold: usize,
+
+    /// Requests taking longer than this are counted as failed, even when they succeed.
+    pub(super) latency_threshold_secs: Option<f64>,
+
+    /// How long the circuit stays open before probing the service again.
+    pub(super) open_secs: u64,
+
+    /// The number of probe requests sent when the circuit is half open.
+    pub(super) half_open_requests: usize,
+}
+
+impl CircuitBreakerSettings {
+    pub const fn const_default() -> Self {
+        Self {
+            enabled: false,
+            failure_threshold: 5,
+            latency_threshold_secs: None,
+            open_secs: 30,
+            half_open_requests: 1,
+        }
+    }
+}
+
+impl Default for CircuitBreakerSettings {
+    fn default() -> Self {
+        Self::const_default()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CircuitBreakerLayer<L> {
+    settings: CircuitBreakerSettings,
+    logic: L,
+}
+
+impl<L> CircuitBreakerLayer<L> {
+    pub const fn new(settings: CircuitBreakerSettings, logic: L) -> Self {
+        Self { settings, logic }
+    }
+}
+
+impl<S, L: Clone> Layer<S> for CircuitBreakerLayer<L> {
+    type Service = CircuitBreaker<S, L>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        let breaker = self
+            .settings
+            .enabled
+            .then(|| Arc::new(Breaker::new(self.settings, self.logic.clone())));
+        CircuitBreaker {
+            inner,
+            breaker,
+            sleep: None,
+            probe: None,
+        }
+    }
+}
+
+/// Holds back the requests to the inner service while the circuit is open. The state of the
+/// circuit is shared by the clones of the service.
+pub struct CircuitBreaker<S, L> {
+    inner: S,
+    breaker: Option<Arc<Breaker<L>>>,
+    sleep: Option<Pin<Box<Sleep>>>,
+    /// The probe slot of the half open circuit reserved by `poll_ready` for the next request.
+    probe: Option<u64>,
+}
+
+impl<S, L, Request> Service<Request> for CircuitBreaker<S, L>
+where
+    S: Service<Request>,
+    S::Error: Into<crate::Error>,
+    L: RetryLogic<Response = S::Response>,
+{
+    type Response = S::Response;
+    type Error = crate::Error;
+    type Future = ResponseFuture<S::Future, L>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if let Some(breaker) = &self.breaker {
+            ready!(breaker.poll_admit(cx, &mut self.sleep, &mut self.probe));
+        }
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        ResponseFuture {
+            inner: self.inner.call(request),
+            breaker: self.breaker.clone(),
+            probe: self.probe.take(),
+            start: Instant::now(),
+        }
+    }
+}
+
+impl<S: Clone, L> Clone for CircuitBreaker<S, L> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            breaker: self.breaker.clone(),
+            sleep: None,
+            probe: None,
+        }
+    }
+}
+
+impl<S, L> Drop for CircuitBreaker<S, L> {
+    fn drop(&mut self) {
+        if let (Some(breaker), Some(probe)) = (&self.breaker, self.probe) {
+            breaker.release_probe(probe);
+        }
+    }
+}
+
+impl<S: fmt::Debug, L> fmt::Debug for CircuitBreaker<S, L> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CircuitBreaker")
+            .field("inner", &self.inner)
+            .field("enabled", &self.breaker.is_some())
+            .finish()
+    }
+}
+
+/// Future for the `CircuitBreaker` service, recording the outcome of the request in the circuit.
+/// A probe dropped before it completes gives its slot back to the half open circuit.
+#[pin_project(PinnedDrop)]
+pub struct ResponseFuture<F, L> {
+    #[pin]
+    inner: F,
+    breaker: Option<Arc<Breaker<L>>>,
+    /// The probe slot of the half open circuit taken by the request, if it's one of its probes.
+    probe: Option<u64>,
+    start: Instant,
+}
+
+impl<F, L, E> Future for ResponseFuture<F, L>
+where
+    F: Future<Output = Result<L::Response, E>>,
+    L: RetryLogic,
+    E: Into<crate::Error>,
+{
+    type Output = Result<L::Response, crate::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let output = ready!(this.inner.poll(cx)).map_err(Into::into);
+        if let Some(breaker) = this.breaker.take() {
+            let failed = breaker.is_failure(&output)
+                || breaker
+                    .latency_threshold
+                    .map_or(false, |threshold| this.start.elapsed() > threshold);
+            breaker.end_request(this.probe.take(), failed);
+        }
+        Poll::Ready(output)
+    }
+}
+
+#[pinned_drop]
+impl<F, L> PinnedDrop for ResponseFuture<F, L> {
+    fn drop(self: Pin<&mut Self>) {
+        let this = self.project();
+        if let (Some(breaker), Some(probe)) = (this.breaker.take(), this.probe.take()) {
+            breaker.release_probe(probe);
+        }
+    }
+}
+
+struct Breaker<L> {
+    settings: CircuitBreakerSettings,
+    latency_threshold: Option<Duration>,
+    logic: L,
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    circuit: Circuit,
+    /// The number of consecutive failed requests while the circuit is closed, or of the
+    /// successful probes while it is half open.
+    count: usize,
+    /// The number of probe slots taken while the circuit is half open.
+    probes: usize,
+    /// Incremented by each transition of the circuit, telling the probe slots of the current half
+    /// open circuit apart from the ones of the previous ones.
+    epoch: u64,
+    /// The services waiting for the probes to complete.
+    waiters: Vec<Waker>,
+}
+
+#[derive(Clone, Copy, Derivative)]
+#[derivative(Default)]
+enum Circuit {
+    #[derivative(Default)]
+    Closed,
+    Open {
+        until: Instant,
+    },
+    HalfOpen,
+}
+
+impl<L: RetryLogic> Breaker<L> {
+    fn new(mut settings: CircuitBreakerSettings, logic: L) -> Self {
+        // The circuit would never close again without any probes.
+        settings.half_open_requests = settings.half_open_requests.max(1);
+        Self {
+            settings,
+            latency_threshold: settings.latency_threshold_secs.map(Duration::from_secs_f64),
+            logic,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Waits for the circuit to let a request through, reserving one of its probe slots in `probe`
+    /// when it is half open.
+    fn poll_admit(
+        &self,
+        cx: &mut Context<'_>,
+        sleep: &mut Option<Pin<Box<Sleep>>>,
+        probe: &mut Option<u64>,
+    ) -> Poll<()> {
+        let mut state = self.state.lock().expect("circuit breaker mutex poisoned");
+        loop {
+            let circuit = state.circuit;
+            match circuit {
+                Circuit::Closed => return Poll::Ready(()),
+                Circuit::Open { until } if until <= Instant::now() => {
+                    state.transition(Circuit::HalfOpen);
+                    emit!(CircuitBreakerHalfOpened);
+                }
+                Circuit::Open { until } => {
+                    let sleep = sleep.get_or_insert_with(|| Box::pin(sleep_until(until)));
+                    if sleep.deadline() != until {
+                        sleep.as_mut().reset(until);
+                    }
+                    ready!(sleep.as_mut().poll(cx));
+                }
+                Circuit::HalfOpen if *probe == Some(state.epoch) => return Poll::Ready(()),
+                Circuit::HalfOpen if state.probes < self.settings.half_open_requests => {
+                    state.probes += 1;
+                    *probe = Some(state.epoch);
+                    return Poll::Ready(());
+                }
+                Circuit::HalfOpen => {
+                    if !state
+                        .waiters
+                        .iter()
+                        .any(|waker| waker.will_wake(cx.waker()))
+                    {
+                        state.waiters.push(cx.waker().clone());
+                    }
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+
+    fn end_request(&self, probe: Option<u64>, failed: bool) {
+        let mut state = self.state.lock().expect("circuit breaker mutex poisoned");
+        // The slots taken before the last transition aren't probes of the current circuit.
+        let probe = probe == Some(state.epoch);
+        let circuit = state.circuit;
+        match circuit {
+            Circuit::Closed if failed => {
+                state.count += 1;
+                let failures = state.count;
+                if failures >= self.settings.failure_threshold {
+                    self.open(&mut state, failures);
+                }
+            }
+            Circuit::Closed => state.count = 0,
+            // Requests sent before the circuit was half opened don't tell whether the service
+            // recovered.
+            Circuit::HalfOpen if probe && failed => self.open(&mut state, 1),
+            Circuit::HalfOpen if probe => {
+                state.count += 1;
+                if state.count >= self.settings.half_open_requests {
+                    state.transition(Circuit::Closed);
+                    emit!(CircuitBreakerClosed);
+                }
+            }
+            Circuit::HalfOpen | Circuit::Open { .. } => {}
+        }
+    }
+
+    fn open(&self, state: &mut State, failures: usize) {
+        let open = Duration::from_secs(self.settings.open_secs);
+        state.transition(Circuit::Open {
+            until: Instant::now() + open,
+        });
+        emit!(CircuitBreakerOpened { failures, open });
+    }
+
+    fn is_failure(&self, output: &Result<L::Response, crate::Error>) -> bool {
+        match output {
+            Ok(response) => matches!(
+                self.logic.should_retry_response(response),
+                RetryAction::Retry(_)
+            ),
+            Err(error) => {
+                if let Some(error) = error.downcast_ref::<L::Error>() {
+                    self.logic.is_retriable_error(error)
+                } else {
+                    error.downcast_ref::<Elapsed>().is_some()
+                }
+            }
+        }
+    }
+}
+
+impl<L> Breaker<L> {
+    /// Gives back a probe slot that wasn't used, or whose request was dropped before completing.
+    fn release_probe(&self, probe: u64) {
+        let mut state = self.state.lock().expect("circuit breaker mutex poisoned");
+        if matches!(state.circuit, Circuit::HalfOpen) && probe == state.epoch {
+            state.probes -= 1;
+            state.wake_waiters();
+        }
+    }
+}
+
+impl State {
+    fn transition(&mut self, circuit: Circuit) {
+        self.circuit = circuit;
+        self.count = 0;
+        self.probes = 0;
+        self.epoch = self.epoch.wrapping_add(1);
+        self.wake_waiters();
+    }
+
+    fn wake_waiters(&mut self) {
+        for waker in self.waiters.drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::future;
+    use tokio::time::{advance, pause};
+    use tokio_test::{assert_pending, assert_ready_ok, task};
+
+    use super::*;
+
+    #[derive(Clone, Debug)]
+    struct TestRetryLogic;
+
+    impl RetryLogic for TestRetryLogic {
+        type Error = std::io::Error;
+        type Response = ();
+
+        fn is_retriable_error(&self, _error: &Self::Error) -> bool {
+            true
+        }
+    }
+
+    /// Fails the requests asking for it.
+    #[derive(Clone, Debug)]
+    struct Endpoint;
+
+    impl Service<bool> for Endpoint {
+        type Response = ();
+        type Error = std::io::Error;
+        type Future = future::Ready<Result<(), std::io::Error>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, fail: bool) -> Self::Future {
+            future::ready(if fail {
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    "unavailable",
+                ))
+            } else {
+                Ok(())
+            })
+        }
+    }
+
+    fn service(settings: CircuitBreakerSettings) -> CircuitBreaker<Endpoint, TestRetryLogic> {
+        CircuitBreakerLayer::new(settings, TestRetryLogic).layer(Endpoint)
+    }
+
+    /// Returns whether the request succeeded.
+    async fn send(service: &mut CircuitBreaker<Endpoint, TestRetryLogic>, fail: bool) -> bool {
+        future::poll_fn(|cx| service.poll_ready(cx)).await.unwrap();
+        service.call(fail).await.is_ok()
+    }
+
+    const SETTINGS: CircuitBreakerSettings = CircuitBreakerSettings {
+        enabled: true,
+        failure_threshold: 2,
+        latency_threshold_secs: None,
+        open_secs: 10,
+        half_open_requests: 1,
+    };
+
+    #[tokio::test]
+    async fn opens_after_consecutive_failures() {
+        pause();
+        let mut service = service(SETTINGS);
+
+        assert!(!send(&mut service, true).await);
+        assert!(send(&mut service, false).await);
+        assert!(!send(&mut service, true).await);
+        assert!(!send(&mut service, true).await);
+
+        let mut clone = service.clone();
+        let mut ready = task::spawn(future::poll_fn(|cx| clone.poll_ready(cx)));
+        assert_pending!(ready.poll());
+
+        advance(Duration::from_secs(10)).await;
+        assert!(ready.is_woken());
+        assert_ready_ok!(ready.poll());
+    }
+
+    #[tokio::test]
+    async fn closes_after_successful_probes() {
+        pause();
+        let mut service = service(SETTINGS);
+        assert!(!send(&mut service, true).await);
+        assert!(!send(&mut service, true).await);
+        advance(Duration::from_secs(10)).await;
+
+        // Only one probe is let through while the circuit is half open.
+        future::poll_fn(|cx| service.poll_ready(cx)).await.unwrap();
+        let probe = service.call(false);
+        let mut other = service.clone();
+        let mut ready = task::spawn(future::poll_fn(|cx| other.poll_ready(cx)));
+        assert_pending!(ready.poll());
+
+        probe.await.unwrap();
+        assert!(ready.is_woken());
+        assert_ready_ok!(ready.poll());
+    }
+
+    #[tokio::test]
+    async fn reserves_probes_when_ready() {
+        pause();
+        let mut service = service(SETTINGS);
+        assert!(!send(&mut service, true).await);
+        assert!(!send(&mut service, true).await);
+        advance(Duration::from_secs(10)).await;
+
+        // The clones share the probe slots before any of them sends its request.
+        future::poll_fn(|cx| service.poll_ready(cx)).await.unwrap();
+        let mut other = service.clone();
+        let mut ready = task::spawn(future::poll_fn(|cx| other.poll_ready(cx)));
+        assert_pending!(ready.poll());
+        assert_pending!(ready.poll());
+
+        // A probe dropped before it completes gives its slot back.
+        drop(service.call(false));
+        assert!(ready.is_woken());
+        assert_ready_ok!(ready.poll());
+        drop(ready);
+
+        // And so does a service dropped with a reserved slot.
+        let mut third = service.clone();
+        let mut ready = task::spawn(future::poll_fn(|cx| third.poll_ready(cx)));
+        assert_pending!(ready.poll());
+        drop(other);
+        assert!(ready.is_woken());
+        assert_ready_ok!(ready.poll());
+    }
+
+    #[tokio::test]
+    async fn reopens_after_failed_probe() {
+        pause();
+        let mut service = service(SETTINGS);
+        assert!(!send(&mut service, true).await);
+        assert!(!send(&mut service, true).await);
+        advance(Duration::from_secs(10)).await;
+
+        assert!(!send(&mut service, true).await);
+        let mut ready = task::spawn(future::poll_fn(|cx| service.poll_ready(cx)));
+        assert_pending!(ready.poll());
+    }
+
+    #[tokio::test]
+    async fn passes_through_when_disabled() {
+        let mut service = service(CircuitBreakerSettings::default());
+        for _ in 0..10 {
+            assert!(!send(&mut service, true).await);
+        }
+        assert!(send(&mut service, false).await);
+    }
+}
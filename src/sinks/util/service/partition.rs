@@ -0,0 +1,294 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    hash::Hash,
+    mem,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures::{future::BoxFuture, ready, FutureExt};
+use tokio::{
+    sync::{OwnedSemaphorePermit, Semaphore},
+    time::Instant,
+};
+use tower::{buffer::Buffer, Service, ServiceExt};
+
+/// How long the service of a partition without requests is kept, along with its concurrency and
+/// retry state.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Dispatches each request to a service dedicated to its partition, built on first use.
+///
+/// The service of each partition is driven by its own buffer, so a partition whose service isn't
+/// ready, for instance because it is being throttled or is retrying requests, doesn't prevent the
+/// requests of the other partitions from being sent. This way, each partition also gets its own
+/// concurrency and retry state when `make_service` builds the services with
+/// `ServiceBuilderExt::settings`.
+///
+/// The number of requests waiting to be sent or in flight is bounded by `limit` across all the
+/// partitions, and by `partition_limit` for each of them. The requests of a partition beyond its
+/// share give back their part of `limit` while they wait for the partition, and are counted in a
+/// queue of `limit` requests of its own instead, so that a partition whose requests don't
+/// complete can only take up `partition_limit` of it. The requests wait for their partition while
+/// holding their part of `limit` only once that queue is full. The services of the partitions
+/// which had no requests for a while are dropped, so that partitions that are only seen once, such
+/// as the API keys of short-lived clients, don't accumulate.
+pub struct PartitionedService<K, S, Request>
+where
+    S: Service<Request>,
+{
+    services: HashMap<K, Partition<S, Request>>,
+    partition: Arc<dyn Fn(&Request) -> K + Send + Sync>,
+    make_service: Arc<dyn Fn(&K) -> S + Send + Sync>,
+    limit: usize,
+    partition_limit: usize,
+    semaphore: Arc<Semaphore>,
+    /// Held by the requests waiting for their partition to be below its share.
+    queue: Arc<Semaphore>,
+    state: State,
+    last_eviction: Instant,
+}
+
+struct Partition<S, Request>
+where
+    S: Service<Request>,
+{
+    service: Buffer<S, Request>,
+    last_used: Instant,
+    /// Held by the requests of the partition until they complete.
+    semaphore: Arc<Semaphore>,
+}
+
+impl<S, Request> Partition<S, Request>
+where
+    S: Service<Request>,
+{
+    fn is_idle(&self, now: Instant) -> bool {
+        Arc::strong_count(&self.semaphore) == 1
+            && now.duration_since(self.last_used) >= IDLE_TIMEOUT
+    }
+}
+
+enum State {
+    Waiting(BoxFuture<'static, OwnedSemaphorePermit>),
+    Ready(OwnedSemaphorePermit),
+    Empty,
+}
+
+impl<K, S, Request> PartitionedService<K, S, Request>
+where
+    S: Service<Request>,
+{
+    pub fn new(
+        partition: impl Fn(&Request) -> K + Send + Sync + 'static,
+        make_service: impl Fn(&K) -> S + Send + Sync + 'static,
+        limit: usize,
+        partition_limit: usize,
+    ) -> Self {
+        Self {
+            services: HashMap::new(),
+            partition: Arc::new(partition),
+            make_service: Arc::new(make_service),
+            limit,
+            partition_limit,
+            semaphore: Arc::new(Semaphore::new(limit)),
+            queue: Arc::new(Semaphore::new(limit)),
+            state: State::Empty,
+            last_eviction: Instant::now(),
+        }
+    }
+}
+
+impl<K, S, Request> Service<Request> for PartitionedService<K, S, Request>
+where
+    K: Hash + Eq,
+    S: Service<Request> + Send + 'static,
+    S::Response: Send + 'static,
+    S::Error: Into<crate::Error> + Send + Sync,
+    S::Future: Send,
+    Request: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        loop {
+            self.state = match self.state {
+                State::Ready(_) => return Poll::Ready(Ok(())),
+                State::Waiting(ref mut fut) => State::Ready(ready!(fut.poll_unpin(cx))),
+                State::Empty => {
+                    let semaphore = Arc::clone(&self.semaphore);
+                    State::Waiting(Box::pin(async move {
+                        semaphore
+                            .acquire_owned()
+                            .await
+                            .expect("semaphore is never closed")
+                    }))
+                }
+            };
+        }
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        // Make sure a permit has been acquired
+        let mut permit = match mem::replace(&mut self.state, State::Empty) {
+            State::Ready(permit) => permit,
+            _ => panic!("Maximum requests pending; poll_ready must be called first"),
+        };
+
+        let now = Instant::now();
+        if now.duration_since(self.last_eviction) >= IDLE_TIMEOUT {
+            self.services.retain(|_, partition| !partition.is_idle(now));
+            self.last_eviction = now;
+        }
+
+        let make_service = &self.make_service;
+        let partition_limit = self.partition_limit;
+        let partition = self
+            .services
+            .entry((self.partition)(&request))
+            .or_insert_with_key(|key| Partition {
+                service: Buffer::new(make_service(key), partition_limit),
+                last_used: now,
+                semaphore: Arc::new(Semaphore::new(partition_limit)),
+            });
+        partition.last_used = now;
+        let mut service = partition.service.clone();
+        let semaphore = Arc::clone(&partition.semaphore);
+
+        // A request beyond the share of its partition waits in the queue, when it has room.
+        let admitted = Arc::clone(&semaphore).try_acquire_owned().ok();
+        if admitted.is_none() {
+            if let Ok(queued) = Arc::clone(&self.queue).try_acquire_owned() {
+                permit = queued;
+            }
+        }
+
+        Box::pin(async move {
+            let admitted = match admitted {
+                Some(admitted) => admitted,
+                None => semaphore
+                    .acquire_owned()
+                    .await
+                    .expect("semaphore is never closed"),
+            };
+            let response = service.ready().await?.call(request).await;
+            drop(admitted);
+            drop(permit);
+            response
+        })
+    }
+}
+
+impl<K, S, Request> fmt::Debug for PartitionedService<K, S, Request>
+where
+    S: Service<Request>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PartitionedService")
+            .field("partitions", &self.services.len())
+            .field("limit", &self.limit)
+            .field("partition_limit", &self.partition_limit)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::future;
+    use tower::util::BoxService;
+
+    use super::*;
+
+    type Request = (&'static str, usize);
+
+    /// Builds a service responding with the second element of the requests, except for the
+    /// "stalled" partition, whose requests never complete.
+    fn service(
+        limit: usize,
+        partition_limit: usize,
+    ) -> PartitionedService<&'static str, BoxService<Request, usize, crate::Error>, Request> {
+        PartitionedService::new(
+            |request: &Request| request.0,
+            |partition: &&'static str| {
+                let stalled = *partition == "stalled";
+                BoxService::new(tower::service_fn(move |request: Request| async move {
+                    if stalled {
+                        future::pending::<()>().await;
+                    }
+                    Ok(request.1)
+                }))
+            },
+            limit,
+            partition_limit,
+        )
+    }
+
+    #[tokio::test]
+    async fn stalled_partition_does_not_block_others() {
+        let mut service = service(10, 5);
+
+        let stalled = service.ready().await.unwrap().call(("stalled", 1));
+        tokio::spawn(stalled);
+
+        let response = service.ready().await.unwrap().call(("other", 2)).await;
+        assert_eq!(response.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn stalled_partition_fills_only_its_share() {
+        let mut service = service(4, 2);
+
+        // The stalled partition fills its share, and then the queue of the requests beyond it.
+        for index in 0..6 {
+            let stalled = service.ready().await.unwrap().call(("stalled", index));
+            tokio::spawn(stalled);
+        }
+
+        for index in 0..8 {
+            let response = service.ready().await.unwrap().call(("other", index)).await;
+            assert_eq!(response.unwrap(), index);
+        }
+
+        // Its next requests wait for it while holding the rest of the limit.
+        for index in 0..2 {
+            let stalled = service.ready().await.unwrap().call(("stalled", index));
+            tokio::spawn(stalled);
+        }
+        assert!(service.ready().now_or_never().is_none());
+    }
+
+    #[tokio::test]
+    async fn limits_pending_requests() {
+        let mut service = service(1, 1);
+
+        let stalled = service.ready().await.unwrap().call(("stalled", 1));
+        tokio::spawn(stalled);
+
+        assert!(service.ready().now_or_never().is_none());
+    }
+
+    #[tokio::test]
+    async fn evicts_idle_partitions() {
+        tokio::time::pause();
+        let mut service = service(10, 5);
+
+        let stalled = service.ready().await.unwrap().call(("stalled", 1));
+        tokio::spawn(stalled);
+        let response = service.ready().await.unwrap().call(("idle", 2)).await;
+        assert_eq!(response.unwrap(), 2);
+        assert_eq!(service.services.len(), 2);
+
+        tokio::time::advance(IDLE_TIMEOUT).await;
+        let response = service.ready().await.unwrap().call(("other", 3)).await;
+        assert_eq!(response.unwrap(), 3);
+
+        // The stalled partition still has a pending request.
+        let mut partitions = service.services.keys().copied().collect::<Vec<_>>();
+        partitions.sort_unstable();
+        assert_eq!(partitions, vec!["other", "stalled"]);
+    }
+}
@@ -0,0 +1,544 @@
+//! Conversion of trace events into the spans exported to tracing backends.
+//!
+//! Trace events come in two shapes. The `opentelemetry` source emits one event per span, with
+//! hexadecimal IDs, `start_timestamp` and `end_timestamp`, and the service in its `resources`. The
+//! `datadog_agent` source emits one event per trace, holding its spans in a `spans` array with
+//! integer IDs, a `start` timestamp and a `duration` in nanoseconds. Both are converted into the
+//! same `Span`s, whose tags follow the conventions of the OpenTelemetry exporters to Jaeger and
+//! Zipkin.
+
+use std::{collections::BTreeMap, fmt};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use ordered_float::NotNan;
+use snafu::Snafu;
+use vector_core::{config::log_schema, ByteSizeOf};
+
+use crate::{
+    dropped_events,
+    event::{Event, EventFinalizers, EventStatus, Finalizable, TraceEvent, Value},
+    internal_events::TraceSpansConversionError,
+};
+
+/// The service of the spans whose events don't name one.
+const UNKNOWN_SERVICE: &str = "unknown_service";
+
+#[derive(Debug, Snafu)]
+pub enum SpanError {
+    #[snafu(display("The span has no valid `{}`.", field))]
+    InvalidId { field: &'static str },
+
+    #[snafu(display("The span has no `{}` timestamp.", field))]
+    MissingTimestamp { field: &'static str },
+}
+
+/// The value of a tag, as the tracing backends only support scalar ones.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum TagValue {
+    String(String),
+    Bool(bool),
+    Long(i64),
+    Double(NotNan<f64>),
+}
+
+impl TagValue {
+    /// Objects and arrays are written as JSON, and null values have no tag.
+    fn from_value(value: &Value) -> Option<Self> {
+        Some(match value {
+            Value::Bytes(bytes) => Self::String(String::from_utf8_lossy(bytes).into_owned()),
+            Value::Regex(regex) => {
+                Self::String(String::from_utf8_lossy(regex.as_bytes_slice()).into_owned())
+            }
+            Value::Integer(integer) => Self::Long(*integer),
+            Value::Float(float) => Self::Double(*float),
+            Value::Boolean(boolean) => Self::Bool(*boolean),
+            Value::Timestamp(timestamp) => Self::String(timestamp_string(timestamp)),
+            Value::Object(_) | Value::Array(_) => Self::String(serde_json::to_string(value).ok()?),
+            Value::Null => return None,
+        })
+    }
+}
+
+impl fmt::Display for TagValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::String(string) => string.fmt(f),
+            Self::Bool(boolean) => boolean.fmt(f),
+            Self::Long(integer) => integer.fmt(f),
+            Self::Double(double) => double.fmt(f),
+        }
+    }
+}
+
+pub type Tags = Vec<(String, TagValue)>;
+
+/// The service emitting spans, with the tags describing it.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Process {
+    pub service: String,
+    pub tags: Tags,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SpanKind {
+    Unspecified,
+    Internal,
+    Server,
+    Client,
+    Producer,
+    Consumer,
+}
+
+impl SpanKind {
+    fn from_value(value: Option<&Value>) -> Self {
+        match value.and_then(Value::as_bytes).map(|kind| &kind[..]) {
+            Some(b"internal") => Self::Internal,
+            Some(b"server") => Self::Server,
+            Some(b"client") => Self::Client,
+            Some(b"producer") => Self::Producer,
+            Some(b"consumer") => Self::Consumer,
+            _ => Self::Unspecified,
+        }
+    }
+
+    /// The value of the `span.kind` tag, as used by OpenTracing.
+    pub const fn tag(self) -> Option<&'static str> {
+        match self {
+            Self::Server => Some("server"),
+            Self::Client => Some("client"),
+            Self::Producer => Some("producer"),
+            Self::Consumer => Some("consumer"),
+            Self::Unspecified | Self::Internal => None,
+        }
+    }
+}
+
+/// An event of a span, which the tracing backends record as a log or an annotation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpanEvent {
+    pub time_unix_nano: i64,
+    pub name: String,
+    pub attributes: Tags,
+}
+
+/// A span the span is linked to, other than its parent.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpanLink {
+    pub trace_id: u128,
+    pub span_id: u64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Span {
+    pub process: Process,
+    pub trace_id: u128,
+    pub span_id: u64,
+    pub parent_span_id: Option<u64>,
+    pub name: String,
+    pub kind: SpanKind,
+    pub start_unix_nano: i64,
+    pub duration_nano: i64,
+    pub tags: Tags,
+    pub events: Vec<SpanEvent>,
+    pub links: Vec<SpanLink>,
+    pub error: bool,
+    pub status_message: Option<String>,
+}
+
+/// The spans of a trace event, with what the sinks need to acknowledge it.
+pub struct TraceSpans {
+    pub spans: Vec<Span>,
+    pub events_byte_size: usize,
+    pub finalizers: EventFinalizers,
+}
+
+impl TraceSpans {
+    /// Converts the trace event into its spans, rejecting it when any of them can't be exported.
+    pub fn from_event(event: Event) -> Option<Self> {
+        let mut trace = event.into_trace();
+        match spans(&trace) {
+            Ok(spans) => Some(Self {
+                spans,
+                events_byte_size: trace.size_of(),
+                finalizers: trace.take_finalizers(),
+            }),
+            Err(error) => {
+                trace.metadata().update_status(EventStatus::Rejected);
+                dropped_events::sample(&trace.into(), &error);
+                emit!(TraceSpansConversionError { error });
+                None
+            }
+        }
+    }
+}
+
+impl ByteSizeOf for TraceSpans {
+    fn allocated_bytes(&self) -> usize {
+        self.events_byte_size
+    }
+}
+
+impl Finalizable for TraceSpans {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        std::mem::take(&mut self.finalizers)
+    }
+}
+
+/// Converts the trace event into its spans, failing when any of them can't be exported.
+pub fn spans(trace: &TraceEvent) -> Result<Vec<Span>, SpanError> {
+    let fields = trace.as_map();
+    match fields.get("spans") {
+        Some(Value::Array(spans)) => spans
+            .iter()
+            .filter_map(Value::as_object)
+            .map(|span| datadog_span(fields, span))
+            .collect(),
+        _ => opentelemetry_span(fields).map(|span| vec![span]),
+    }
+}
+
+fn opentelemetry_span(fields: &BTreeMap<String, Value>) -> Result<Span, SpanError> {
+    let mut process = Process {
+        service: UNKNOWN_SERVICE.to_owned(),
+        tags: Vec::new(),
+    };
+    if let Some(Value::Object(resources)) = fields.get("resources") {
+        for (key, value) in resources {
+            match (key.as_str(), value) {
+                ("service.name", Value::Bytes(service)) => {
+                    process.service = String::from_utf8_lossy(service).into_owned();
+                }
+                _ => push_tag(&mut process.tags, key, value),
+            }
+        }
+    }
+
+    let start = timestamp(fields, "start_timestamp")?;
+    let end = timestamp(fields, "end_timestamp")?;
+
+    let mut tags = attributes(fields.get("attributes"));
+    if let Some(Value::Object(scope)) = fields.get("scope") {
+        if let Some(name) = scope.get("name") {
+            push_tag(&mut tags, "otel.scope.name", name);
+        }
+        if let Some(version) = scope.get("version") {
+            push_tag(&mut tags, "otel.scope.version", version);
+        }
+    }
+    let status = fields.get("status").and_then(Value::as_object);
+    let status_code = status
+        .and_then(|status| status.get("code"))
+        .and_then(Value::as_bytes)
+        .map(|code| &code[..]);
+    match status_code {
+        Some(b"ok") => tags.push(("otel.status_code".to_owned(), string("OK"))),
+        Some(b"error") => tags.push(("otel.status_code".to_owned(), string("ERROR"))),
+        _ => (),
+    }
+
+    Ok(Span {
+        process,
+        trace_id: hex_id(fields.get("trace_id"))
+            .ok_or(SpanError::InvalidId { field: "trace_id" })?,
+        span_id: hex_id(fields.get("span_id")).ok_or(SpanError::InvalidId { field: "span_id" })?,
+        parent_span_id: hex_id(fields.get("parent_span_id")),
+        name: fields
+            .get("name")
+            .map(Value::to_string_lossy)
+            .unwrap_or_default(),
+        kind: SpanKind::from_value(fields.get("kind")),
+        start_unix_nano: start,
+        duration_nano: end.saturating_sub(start).max(0),
+        tags,
+        events: objects(fields.get("events"))
+            .map(|event| SpanEvent {
+                time_unix_nano: event
+                    .get("timestamp")
+                    .and_then(Value::as_timestamp)
+                    .map_or(start, DateTime::timestamp_nanos),
+                name: event
+                    .get("name")
+                    .map(Value::to_string_lossy)
+                    .unwrap_or_default(),
+                attributes: attributes(event.get("attributes")),
+            })
+            .collect(),
+        links: objects(fields.get("links"))
+            .filter_map(|link| {
+                Some(SpanLink {
+                    trace_id: hex_id(link.get("trace_id"))?,
+                    span_id: hex_id(link.get("span_id"))?,
+                })
+            })
+            .collect(),
+        error: matches!(status_code, Some(b"error")),
+        status_message: status
+            .and_then(|status| status.get("message"))
+            .map(Value::to_string_lossy)
+            .filter(|message| !message.is_empty()),
+    })
+}
+
+/// The spans of Datadog traces keep their tags in `meta` and `metrics`, and their resource in a
+/// field of its own.
+fn datadog_span(
+    trace: &BTreeMap<String, Value>,
+    span: &BTreeMap<String, Value>,
+) -> Result<Span, SpanError> {
+    let mut process = Process {
+        service: span
+            .get("service")
+            .map(Value::to_string_lossy)
+            .filter(|service| !service.is_empty())
+            .unwrap_or_else(|| UNKNOWN_SERVICE.to_owned()),
+        tags: Vec::new(),
+    };
+    if let Some(env) = trace.get("env") {
+        push_tag(&mut process.tags, "deployment.environment", env);
+    }
+    if let Some(host) = trace.get(log_schema().host_key()) {
+        push_tag(&mut process.tags, "host.name", host);
+    }
+
+    let mut tags = Vec::new();
+    if let Some(resource) = span.get("resource") {
+        push_tag(&mut tags, "resource.name", resource);
+    }
+    if let Some(span_type) = span.get("type").filter(|span_type| !is_empty(span_type)) {
+        push_tag(&mut tags, "span.type", span_type);
+    }
+    let meta = span.get("meta").and_then(Value::as_object);
+    for field in ["meta", "metrics"] {
+        if let Some(Value::Object(values)) = span.get(field) {
+            for (key, value) in values {
+                push_tag(&mut tags, key, value);
+            }
+        }
+    }
+
+    Ok(Span {
+        process,
+        trace_id: u128::from(
+            integer_id(span.get("trace_id"))
+                .or_else(|| integer_id(trace.get("trace_id")))
+                .ok_or(SpanError::InvalidId { field: "trace_id" })?,
+        ),
+        span_id: integer_id(span.get("span_id"))
+            .ok_or(SpanError::InvalidId { field: "span_id" })?,
+        parent_span_id: integer_id(span.get("parent_id")),
+        name: span
+            .get("name")
+            .map(Value::to_string_lossy)
+            .unwrap_or_default(),
+        kind: SpanKind::from_value(meta.and_then(|meta| meta.get("span.kind"))),
+        start_unix_nano: timestamp(span, "start")?,
+        duration_nano: span
+            .get("duration")
+            .and_then(Value::as_integer)
+            .unwrap_or_default()
+            .max(0),
+        tags,
+        events: Vec::new(),
+        links: Vec::new(),
+        error: span
+            .get("error")
+            .and_then(Value::as_integer)
+            .map_or(false, |error| error != 0),
+        status_message: meta
+            .and_then(|meta| meta.get("error.msg"))
+            .map(Value::to_string_lossy),
+    })
+}
+
+fn push_tag(tags: &mut Tags, key: &str, value: &Value) {
+    if let Some(value) = TagValue::from_value(value) {
+        tags.push((key.to_owned(), value));
+    }
+}
+
+fn attributes(value: Option<&Value>) -> Tags {
+    let mut tags = Vec::new();
+    if let Some(Value::Object(attributes)) = value {
+        for (key, value) in attributes {
+            push_tag(&mut tags, key, value);
+        }
+    }
+    tags
+}
+
+fn string(value: &str) -> TagValue {
+    TagValue::String(value.to_owned())
+}
+
+fn is_empty(value: &Value) -> bool {
+    value.as_bytes().map_or(false, |bytes| bytes.is_empty())
+}
+
+fn timestamp(fields: &BTreeMap<String, Value>, field: &'static str) -> Result<i64, SpanError> {
+    fields
+        .get(field)
+        .and_then(Value::as_timestamp)
+        .map(DateTime::timestamp_nanos)
+        .ok_or(SpanError::MissingTimestamp { field })
+}
+
+fn timestamp_string(timestamp: &DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+/// IDs are written in hexadecimal by the `opentelemetry` source. Zero isn't a valid ID.
+fn hex_id<T: TryFrom<u128>>(value: Option<&Value>) -> Option<T> {
+    let hex = std::str::from_utf8(value?.as_bytes()?).ok()?;
+    u128::from_str_radix(hex, 16)
+        .ok()
+        .filter(|&id| id != 0)
+        .and_then(|id| T::try_from(id).ok())
+}
+
+/// IDs are written as signed integers by the `datadog_agent` source. Zero isn't a valid ID.
+fn integer_id(value: Option<&Value>) -> Option<u64> {
+    value?
+        .as_integer()
+        .map(|id| id as u64)
+        .filter(|&id| id != 0)
+}
+
+fn objects(value: Option<&Value>) -> impl Iterator<Item = &BTreeMap<String, Value>> {
+    value
+        .and_then(Value::as_array)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(Value::as_object)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn converts_opentelemetry_spans() {
+        let mut trace = TraceEvent::from(BTreeMap::new());
+        trace.insert("trace_id", "4bf92f3577b34da6a3ce929d0e0e4736");
+        trace.insert("span_id", "00f067aa0ba902b7");
+        trace.insert("parent_span_id", "53995c3f42cd8ad8");
+        trace.insert("name", "GET /orders");
+        trace.insert("kind", "server");
+        trace.insert("start_timestamp", Utc.timestamp(1_654_000_000, 0));
+        trace.insert("end_timestamp", Utc.timestamp(1_654_000_000, 250_000_000));
+        trace.insert("resources.\"service.name\"", "orders");
+        trace.insert("resources.\"host.name\"", "web-1");
+        trace.insert("attributes.\"http.status_code\"", 500);
+        trace.insert("status.code", "error");
+        trace.insert("status.message", "timed out");
+        trace.insert(
+            "links",
+            vec![Value::from(BTreeMap::from([
+                ("trace_id".to_owned(), Value::from("01".repeat(16))),
+                ("span_id".to_owned(), Value::from("02".repeat(8))),
+            ]))],
+        );
+
+        let spans = spans(&trace).unwrap();
+        assert_eq!(spans.len(), 1);
+        let span = &spans[0];
+        assert_eq!(
+            span.process,
+            Process {
+                service: "orders".to_owned(),
+                tags: vec![("host.name".to_owned(), string("web-1"))],
+            }
+        );
+        assert_eq!(span.trace_id, 0x4bf92f3577b34da6a3ce929d0e0e4736);
+        assert_eq!(span.span_id, 0x00f067aa0ba902b7);
+        assert_eq!(span.parent_span_id, Some(0x53995c3f42cd8ad8));
+        assert_eq!(span.kind, SpanKind::Server);
+        assert_eq!(span.start_unix_nano, 1_654_000_000_000_000_000);
+        assert_eq!(span.duration_nano, 250_000_000);
+        assert_eq!(
+            span.tags,
+            vec![
+                ("http.status_code".to_owned(), TagValue::Long(500)),
+                ("otel.status_code".to_owned(), string("ERROR")),
+            ]
+        );
+        assert_eq!(
+            span.links,
+            vec![SpanLink {
+                trace_id: 0x0101_0101_0101_0101_0101_0101_0101_0101,
+                span_id: 0x0202_0202_0202_0202,
+            }]
+        );
+        assert!(span.error);
+        assert_eq!(span.status_message.as_deref(), Some("timed out"));
+    }
+
+    #[test]
+    fn converts_datadog_spans() {
+        let mut trace = TraceEvent::from(BTreeMap::new());
+        trace.insert("trace_id", 123);
+        trace.insert("env", "prod");
+        trace.insert(
+            "spans",
+            vec![Value::from(BTreeMap::from([
+                ("service".to_owned(), Value::from("orders")),
+                ("name".to_owned(), Value::from("http.request")),
+                ("resource".to_owned(), Value::from("GET /orders")),
+                ("trace_id".to_owned(), Value::from(123)),
+                ("span_id".to_owned(), Value::from(456)),
+                ("parent_id".to_owned(), Value::from(0)),
+                (
+                    "start".to_owned(),
+                    Value::from(Utc.timestamp(1_654_000_000, 0)),
+                ),
+                ("duration".to_owned(), Value::from(1_000)),
+                ("error".to_owned(), Value::from(1)),
+                (
+                    "meta".to_owned(),
+                    Value::from(BTreeMap::from([(
+                        "span.kind".to_owned(),
+                        Value::from("client"),
+                    )])),
+                ),
+                ("type".to_owned(), Value::from("")),
+            ]))],
+        );
+
+        let spans = spans(&trace).unwrap();
+        assert_eq!(spans.len(), 1);
+        let span = &spans[0];
+        assert_eq!(span.process.service, "orders");
+        assert_eq!(
+            span.process.tags,
+            vec![("deployment.environment".to_owned(), string("prod"))]
+        );
+        assert_eq!(span.trace_id, 123);
+        assert_eq!(span.span_id, 456);
+        assert_eq!(span.parent_span_id, None);
+        assert_eq!(span.name, "http.request");
+        assert_eq!(span.kind, SpanKind::Client);
+        assert_eq!(span.duration_nano, 1_000);
+        assert_eq!(
+            span.tags,
+            vec![
+                ("resource.name".to_owned(), string("GET /orders")),
+                ("span.kind".to_owned(), string("client")),
+            ]
+        );
+        assert!(span.error);
+    }
+
+    #[test]
+    fn rejects_spans_without_ids() {
+        let mut trace = TraceEvent::from(BTreeMap::new());
+        trace.insert("span_id", "00f067aa0ba902b7");
+        trace.insert("start_timestamp", Utc.timestamp(1_654_000_000, 0));
+        trace.insert("end_timestamp", Utc.timestamp(1_654_000_001, 0));
+
+        assert!(matches!(
+            spans(&trace),
+            Err(SpanError::InvalidId { field: "trace_id" })
+        ));
+    }
+}
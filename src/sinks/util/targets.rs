@@ -0,0 +1,316 @@
+//! Runs the targets of the sinks which route each batch of events to one of
+//! several other sinks, like `balance` and `failover`.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use futures::{
+    future::BoxFuture,
+    stream::{BoxStream, FuturesUnordered},
+    FutureExt, StreamExt,
+};
+use tokio::{sync::mpsc, task::JoinHandle};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::Instrument;
+use vector_core::event::{
+    BatchNotifier, BatchStatus, EventArray, EventContainer, EventFinalizer, EventFinalizers,
+    EventStatus,
+};
+
+use crate::{config::Sandbox, sinks::VectorSink};
+
+/// The number of event batches queued for each target before the sink waits
+/// for it to catch up.
+const TARGET_BUFFER_SIZE: usize = 16;
+
+/// Chooses the target of each batch of events, based on how the targets
+/// delivered the previous ones.
+pub trait Router: Send {
+    /// Selects the target of the next batch of events.
+    fn select(&mut self, now: Instant) -> usize;
+
+    /// Records that a batch of `count` events is sent to the target.
+    fn start(&mut self, _target: usize, _count: usize) {}
+
+    /// Records how the target delivered a batch of `count` events.
+    fn finish(&mut self, target: usize, count: usize, status: BatchStatus, now: Instant);
+
+    /// The target to send the batches the target failed to deliver to, if any.
+    fn fallback(&self, _target: usize) -> Option<usize> {
+        None
+    }
+}
+
+/// A batch of events sent to a target. The finalizers the events came with
+/// are only updated once the targets are done with them, so that a batch can
+/// be sent to its fallback target when the first target fails to deliver it.
+struct Delivery {
+    target: usize,
+    count: usize,
+    finalizers: EventFinalizers,
+    /// A copy of the events, kept only when the target has a fallback.
+    retry: Option<EventArray>,
+}
+
+/// Sends each batch of events of the input to the target selected by the
+/// router, until the input ends and the targets have flushed what they were
+/// sent.
+pub async fn route<R: Router>(
+    router: &Mutex<R>,
+    sinks: Vec<VectorSink>,
+    mut input: BoxStream<'_, EventArray>,
+) -> Result<(), ()> {
+    let mut targets = Targets::spawn(sinks);
+
+    loop {
+        tokio::select! {
+            Some((delivery, status)) = targets.pending.next(), if !targets.pending.is_empty() => {
+                targets.finish(router, delivery, status).await?;
+            }
+            array = input.next() => match array {
+                Some(array) => {
+                    let (target, fallback) = {
+                        let mut router = router.lock().expect("mutex poisoned");
+                        let target = router.select(Instant::now());
+                        router.start(target, array.len());
+                        (target, router.fallback(target))
+                    };
+                    targets.send(target, array, fallback.is_some()).await?;
+                }
+                None => break,
+            },
+        }
+    }
+
+    // The batches which may still be sent to their fallback target are waited
+    // for before the targets are closed.
+    while targets.retries > 0 {
+        match targets.pending.next().await {
+            Some((delivery, status)) => targets.finish(router, delivery, status).await?,
+            None => break,
+        }
+    }
+    targets.close(router).await
+}
+
+struct Targets {
+    senders: Vec<mpsc::Sender<EventArray>>,
+    tasks: Vec<JoinHandle<Result<(), ()>>>,
+    pending: FuturesUnordered<BoxFuture<'static, (Delivery, BatchStatus)>>,
+    /// The number of pending deliveries which keep a copy of their events.
+    retries: usize,
+}
+
+impl Targets {
+    fn spawn(sinks: Vec<VectorSink>) -> Self {
+        let mut senders = Vec::with_capacity(sinks.len());
+        let mut tasks = Vec::with_capacity(sinks.len());
+        for sink in sinks {
+            let (sender, receiver) = mpsc::channel(TARGET_BUFFER_SIZE);
+            senders.push(sender);
+            tasks.push(tokio::spawn(
+                Sandbox::in_current_scope(sink.run(ReceiverStream::new(receiver)))
+                    .in_current_span(),
+            ));
+        }
+        Self {
+            senders,
+            tasks,
+            pending: FuturesUnordered::new(),
+            retries: 0,
+        }
+    }
+
+    /// Sends the events to the target, with a notifier of their own which
+    /// tells whether the target delivered them.
+    async fn send(&mut self, target: usize, mut array: EventArray, keep: bool) -> Result<(), ()> {
+        let mut finalizers = EventFinalizers::default();
+        array.for_each_event(|mut event| {
+            finalizers.merge(event.metadata_mut().take_finalizers());
+        });
+        self.send_delivery(target, array, finalizers, keep).await
+    }
+
+    async fn send_delivery(
+        &mut self,
+        target: usize,
+        mut array: EventArray,
+        finalizers: EventFinalizers,
+        keep: bool,
+    ) -> Result<(), ()> {
+        let retry = keep.then(|| array.clone());
+        if retry.is_some() {
+            self.retries += 1;
+        }
+
+        let (batch, receiver) = BatchNotifier::new_with_receiver();
+        array.for_each_event(|mut event| {
+            event
+                .metadata_mut()
+                .add_finalizer(EventFinalizer::new(Arc::clone(&batch)));
+        });
+        drop(batch);
+        let delivery = Delivery {
+            target,
+            count: array.len(),
+            finalizers,
+            retry,
+        };
+        self.pending
+            .push(receiver.map(move |status| (delivery, status)).boxed());
+
+        if self.senders[target].send(array).await.is_err() {
+            error!(message = "Target stopped unexpectedly.", target);
+            return Err(());
+        }
+        Ok(())
+    }
+
+    /// Records how the target delivered the events, and either sends them to
+    /// the fallback target or finalizes them.
+    async fn finish<R: Router>(
+        &mut self,
+        router: &Mutex<R>,
+        delivery: Delivery,
+        status: BatchStatus,
+    ) -> Result<(), ()> {
+        let Delivery {
+            target,
+            count,
+            finalizers,
+            retry,
+        } = delivery;
+        if retry.is_some() {
+            self.retries -= 1;
+        }
+
+        let fallback = {
+            let mut router = router.lock().expect("mutex poisoned");
+            router.finish(target, count, status, Instant::now());
+            match router.fallback(target) {
+                Some(fallback)
+                    if status == BatchStatus::Errored
+                        && retry.is_some()
+                        && !self.senders.is_empty() =>
+                {
+                    router.start(fallback, count);
+                    Some((fallback, router.fallback(fallback).is_some()))
+                }
+                _ => None,
+            }
+        };
+
+        match (fallback, retry) {
+            (Some((fallback, keep)), Some(array)) => {
+                self.send_delivery(fallback, array, finalizers, keep).await
+            }
+            _ => {
+                finalizers.update_status(event_status(status));
+                Ok(())
+            }
+        }
+    }
+
+    /// Lets the targets flush what they were sent, then finalizes the events
+    /// they were still delivering.
+    async fn close<R: Router>(mut self, router: &Mutex<R>) -> Result<(), ()> {
+        self.senders.clear();
+        let mut result = Ok(());
+        for task in self.tasks.drain(..) {
+            if !matches!(task.await, Ok(Ok(()))) {
+                result = Err(());
+            }
+        }
+        while let Some((delivery, status)) = self.pending.next().await {
+            // Without senders, nothing can be sent to the fallback targets.
+            let _ = self.finish(router, delivery, status).await;
+        }
+        result
+    }
+}
+
+const fn event_status(status: BatchStatus) -> EventStatus {
+    match status {
+        BatchStatus::Delivered => EventStatus::Delivered,
+        BatchStatus::Errored => EventStatus::Errored,
+        BatchStatus::Rejected => EventStatus::Rejected,
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use std::sync::{Arc, Mutex};
+
+    use async_trait::async_trait;
+    use futures::{future, stream::BoxStream, FutureExt, StreamExt};
+    use serde::{Deserialize, Serialize};
+    use vector_core::{event::EventStatus, sink::StreamSink};
+
+    use crate::{
+        config::{AcknowledgementsConfig, Input, SinkConfig, SinkContext},
+        event::Event,
+        sinks::{Healthcheck, VectorSink},
+    };
+
+    /// A target which records the events it's sent, and either delivers or
+    /// fails to deliver all of them.
+    #[derive(Debug, Default, Deserialize, Serialize)]
+    pub struct TestTargetConfig {
+        pub fail: bool,
+        pub healthy: bool,
+        #[serde(skip)]
+        pub events: Arc<Mutex<Vec<Event>>>,
+    }
+
+    struct TestTarget {
+        fail: bool,
+        events: Arc<Mutex<Vec<Event>>>,
+    }
+
+    #[async_trait]
+    #[typetag::serde(name = "target_test")]
+    impl SinkConfig for TestTargetConfig {
+        async fn build(&self, _cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+            let sink = TestTarget {
+                fail: self.fail,
+                events: Arc::clone(&self.events),
+            };
+            let healthcheck = if self.healthy {
+                future::ok(()).boxed()
+            } else {
+                future::err("unreachable".into()).boxed()
+            };
+            Ok((VectorSink::from_event_streamsink(sink), healthcheck))
+        }
+
+        fn input(&self) -> Input {
+            Input::log()
+        }
+
+        fn sink_type(&self) -> &'static str {
+            "target_test"
+        }
+
+        fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
+            None
+        }
+    }
+
+    #[async_trait]
+    impl StreamSink<Event> for TestTarget {
+        async fn run(self: Box<Self>, mut input: BoxStream<'_, Event>) -> Result<(), ()> {
+            while let Some(mut event) = input.next().await {
+                let status = if self.fail {
+                    EventStatus::Errored
+                } else {
+                    EventStatus::Delivered
+                };
+                event.metadata_mut().take_finalizers().update_status(status);
+                self.events.lock().unwrap().push(event);
+            }
+            Ok(())
+        }
+    }
+}
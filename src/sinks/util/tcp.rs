@@ -22,7 +22,7 @@ use vector_common::internal_event::{BytesSent, EventsSent};
 use vector_core::{buffers::Acker, ByteSizeOf};
 
 use crate::{
-    config::SinkContext,
+    config::{Capability, Sandbox, SinkContext},
     dns,
     event::Event,
     internal_events::{
@@ -86,6 +86,16 @@ impl TcpSinkConfig {
         }
     }
 
+    /// The host the sink connects to.
+    pub fn capabilities(&self) -> Vec<Capability> {
+        self.address
+            .parse::<http::Uri>()
+            .ok()
+            .and_then(|uri| Some(Capability::host(uri.host()?, uri.port_u16())))
+            .into_iter()
+            .collect()
+    }
+
     pub fn build(
         &self,
         cx: SinkContext,
@@ -95,6 +105,7 @@ impl TcpSinkConfig {
         let uri = self.address.parse::<http::Uri>()?;
         let host = uri.host().ok_or(SinkBuildError::MissingHost)?.to_string();
         let port = uri.port_u16().ok_or(SinkBuildError::MissingPort)?;
+        Sandbox::current().enforce(&Capability::host(&host, Some(port)))?;
         let tls = MaybeTlsSettings::from_config(&self.tls, false)?;
         let connector = TcpConnector::new(host, port, self.keepalive, tls, self.send_buffer_bytes);
         let sink = TcpSink::new(connector.clone(), cx.acker(), transformer, encoder);
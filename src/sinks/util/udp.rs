@@ -18,7 +18,7 @@ use vector_core::ByteSizeOf;
 
 use super::SinkBuildError;
 use crate::{
-    config::SinkContext,
+    config::{Capability, Sandbox, SinkContext},
     dns,
     event::Event,
     internal_events::{
@@ -62,10 +62,21 @@ impl UdpSinkConfig {
         }
     }
 
+    /// The host the sink sends to.
+    pub fn capabilities(&self) -> Vec<Capability> {
+        self.address
+            .parse::<http::Uri>()
+            .ok()
+            .and_then(|uri| Some(Capability::host(uri.host()?, uri.port_u16())))
+            .into_iter()
+            .collect()
+    }
+
     fn build_connector(&self, _cx: SinkContext) -> crate::Result<UdpConnector> {
         let uri = self.address.parse::<http::Uri>()?;
         let host = uri.host().ok_or(SinkBuildError::MissingHost)?.to_string();
         let port = uri.port_u16().ok_or(SinkBuildError::MissingPort)?;
+        Sandbox::current().enforce(&Capability::host(&host, Some(port)))?;
         Ok(UdpConnector::new(host, port, self.send_buffer_bytes))
     }
 
@@ -10,7 +10,7 @@ use tokio_util::codec::Encoder;
 use vector_core::{buffers::Acker, ByteSizeOf};
 
 use crate::{
-    config::SinkContext,
+    config::{Capability, Sandbox, SinkContext},
     event::Event,
     internal_events::{
         ConnectionOpen, OpenGauge, SocketMode, UnixSocketConnectionError,
@@ -44,12 +44,18 @@ impl UnixSinkConfig {
         Self { path }
     }
 
+    /// The socket the sink connects to.
+    pub fn capabilities(&self) -> Vec<Capability> {
+        vec![Capability::path(&self.path)]
+    }
+
     pub fn build(
         &self,
         cx: SinkContext,
         transformer: Transformer,
         encoder: impl Encoder<Event, Error = codecs::encoding::Error> + Clone + Send + Sync + 'static,
     ) -> crate::Result<(VectorSink, Healthcheck)> {
+        Sandbox::current().enforce(&Capability::path(&self.path))?;
         let connector = UnixConnector::new(self.path.clone());
         let sink = UnixSink::new(connector.clone(), cx.acker(), transformer, encoder);
         Ok((
@@ -0,0 +1,150 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use futures::future::{self, BoxFuture};
+
+use crate::{
+    internal_events::{VectorEndpointRecovered, VectorEndpointUnhealthy},
+    sinks::vector::v2::{
+        service::{is_unavailable, VectorRequest, VectorResponse, VectorService},
+        VectorSinkError,
+    },
+    Error,
+};
+
+#[derive(Debug)]
+struct Endpoint {
+    service: VectorService,
+    unhealthy_until: Mutex<Option<Instant>>,
+}
+
+impl Endpoint {
+    fn is_healthy(&self, now: Instant) -> bool {
+        self.unhealthy_until
+            .lock()
+            .expect("mutex poisoned")
+            .map_or(true, |until| now >= until)
+    }
+
+    fn mark_unhealthy(&self, until: Instant, reason: &str) {
+        let mut unhealthy_until = self.unhealthy_until.lock().expect("mutex poisoned");
+        if unhealthy_until.is_none() {
+            emit!(VectorEndpointUnhealthy {
+                endpoint: &self.service.endpoint,
+                reason,
+            });
+        }
+        *unhealthy_until = Some(until);
+    }
+
+    fn mark_healthy(&self) {
+        let mut unhealthy_until = self.unhealthy_until.lock().expect("mutex poisoned");
+        if unhealthy_until.take().is_some() {
+            emit!(VectorEndpointRecovered {
+                endpoint: &self.service.endpoint,
+            });
+        }
+    }
+}
+
+/// Balances the requests across the downstream Vector instances, in turn, skipping the ones which
+/// recently failed until `unhealthy_duration` has elapsed. Since the requests are retried, a
+/// failed request is sent to the next healthy instance.
+#[derive(Clone, Debug)]
+pub struct BalancedService {
+    endpoints: Arc<Vec<Endpoint>>,
+    next: Arc<AtomicUsize>,
+    unhealthy_duration: Duration,
+}
+
+impl BalancedService {
+    pub fn new(services: Vec<VectorService>, unhealthy_duration: Duration) -> Self {
+        let endpoints = services
+            .into_iter()
+            .map(|service| Endpoint {
+                service,
+                unhealthy_until: Mutex::new(None),
+            })
+            .collect();
+        Self {
+            endpoints: Arc::new(endpoints),
+            next: Arc::new(AtomicUsize::new(0)),
+            unhealthy_duration,
+        }
+    }
+
+    /// Selects the next healthy endpoint. When all of them are unhealthy, they are all considered
+    /// again rather than holding up events.
+    fn select(&self, now: Instant) -> usize {
+        let count = self.endpoints.len();
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        (0..count)
+            .map(|offset| (start + offset) % count)
+            .find(|&index| self.endpoints[index].is_healthy(now))
+            .unwrap_or(start % count)
+    }
+
+    /// Runs the health checks of all the endpoints, marking the ones that fail as unhealthy. The
+    /// sink is healthy as long as one of them is.
+    pub async fn health_check(&self) -> bool {
+        let results = future::join_all(
+            self.endpoints
+                .iter()
+                .map(|endpoint| async move { endpoint.service.clone().health_check().await }),
+        )
+        .await;
+
+        let until = Instant::now() + self.unhealthy_duration;
+        let mut healthy = false;
+        for (endpoint, result) in self.endpoints.iter().zip(results) {
+            if result {
+                healthy = true;
+            } else {
+                endpoint.mark_unhealthy(until, "health check failed");
+            }
+        }
+        healthy
+    }
+}
+
+impl tower::Service<VectorRequest> for BalancedService {
+    type Response = VectorResponse;
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: VectorRequest) -> Self::Future {
+        let index = self.select(Instant::now());
+        let mut service = self.endpoints[index].service.clone();
+        let endpoints = Arc::clone(&self.endpoints);
+        let unhealthy_duration = self.unhealthy_duration;
+
+        Box::pin(async move {
+            let result = tower::Service::call(&mut service, request).await;
+            let endpoint = &endpoints[index];
+            match &result {
+                Ok(_) => endpoint.mark_healthy(),
+                Err(error) => {
+                    if let Some(VectorSinkError::Request { source }) = error.downcast_ref() {
+                        if is_unavailable(source) {
+                            endpoint.mark_unhealthy(
+                                Instant::now() + unhealthy_duration,
+                                source.message(),
+                            );
+                        }
+                    }
+                }
+            }
+            result
+        })
+    }
+}
@@ -1,9 +1,14 @@
+use std::{collections::BTreeMap, str::FromStr, time::Duration};
+
 use http::Uri;
 use hyper::client::HttpConnector;
 use hyper_openssl::HttpsConnector;
 use hyper_proxy::ProxyConnector;
-use serde::{Deserialize, Serialize};
-use tonic::body::BoxBody;
+use serde::{Deserialize, Deserializer, Serialize};
+use tonic::{
+    body::BoxBody,
+    metadata::{AsciiMetadataKey, AsciiMetadataValue, MetadataMap},
+};
 use tower::ServiceBuilder;
 
 use crate::{
@@ -17,6 +22,7 @@ use crate::{
             ServiceBuilderExt, TowerRequestConfig,
         },
         vector::v2::{
+            balancer::BalancedService,
             service::{VectorResponse, VectorService},
             sink::VectorSink,
             VectorSinkError,
@@ -26,18 +32,42 @@ use crate::{
     tls::{tls_connector_builder, MaybeTlsSettings, TlsEnableableConfig},
 };
 
+/// The compression of the events sent to the `vector` source.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum VectorCompression {
+    #[derivative(Default)]
+    None,
+    Gzip,
+    /// The events are compressed with gzip until the source advertises it accepts zstd, which
+    /// older sources don't.
+    Zstd,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct VectorConfig {
-    address: String,
-    #[serde(default)]
-    compression: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    address: Option<String>,
+    /// The addresses of several `vector` sources, which the events are balanced across.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    addresses: Vec<String>,
+    /// How long an address which failed isn't sent events, when balancing across `addresses`.
+    #[serde(default = "default_unhealthy_secs")]
+    unhealthy_secs: u64,
+    #[serde(default, deserialize_with = "bool_or_compression")]
+    pub(super) compression: VectorCompression,
     #[serde(default)]
     pub batch: BatchConfig<RealtimeEventBasedDefaultBatchSettings>,
     #[serde(default)]
     pub request: TowerRequestConfig,
     #[serde(default)]
     tls: Option<TlsEnableableConfig>,
+    /// Metadata identifying this client, such as its agent ID or site, which is sent with every
+    /// request so that the `vector` source can attach it to the events.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    client_metadata: BTreeMap<String, String>,
     #[serde(
         default,
         deserialize_with = "crate::serde::bool_or_struct",
@@ -52,13 +82,39 @@ impl GenerateConfig for VectorConfig {
     }
 }
 
+const fn default_unhealthy_secs() -> u64 {
+    30
+}
+
+/// Deserializes the compression, which used to be a boolean enabling gzip.
+fn bool_or_compression<'de, D>(deserializer: D) -> Result<VectorCompression, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum BoolOrCompression {
+        Bool(bool),
+        Compression(VectorCompression),
+    }
+
+    Ok(match BoolOrCompression::deserialize(deserializer)? {
+        BoolOrCompression::Bool(true) => VectorCompression::Gzip,
+        BoolOrCompression::Bool(false) => VectorCompression::None,
+        BoolOrCompression::Compression(compression) => compression,
+    })
+}
+
 fn default_config(address: &str) -> VectorConfig {
     VectorConfig {
-        address: address.to_owned(),
-        compression: false,
+        address: Some(address.to_owned()),
+        addresses: Vec::new(),
+        unhealthy_secs: default_unhealthy_secs(),
+        compression: VectorCompression::None,
         batch: BatchConfig::default(),
         request: TowerRequestConfig::default(),
         tls: None,
+        client_metadata: BTreeMap::new(),
         acknowledgements: Default::default(),
     }
 }
@@ -69,19 +125,29 @@ impl VectorConfig {
         cx: SinkContext,
     ) -> crate::Result<(VectorSinkType, Healthcheck)> {
         let tls = MaybeTlsSettings::from_config(&self.tls, false)?;
-        let uri = with_default_scheme(&self.address, tls.is_tls())?;
-
         let client = new_client(&tls, cx.proxy())?;
+        let client_metadata = client_metadata(&self.client_metadata)?;
+
+        let services = self
+            .addresses()?
+            .into_iter()
+            .map(|address| {
+                let uri = with_default_scheme(address, tls.is_tls())?;
+                Ok(VectorService::new(client.clone(), uri, self.compression)
+                    .with_metadata(client_metadata.clone()))
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+        let service = BalancedService::new(services, Duration::from_secs(self.unhealthy_secs));
 
-        let healthcheck_uri = cx
-            .healthcheck
-            .uri
-            .clone()
-            .map(|uri| uri.uri)
-            .unwrap_or_else(|| uri.clone());
-        let healthcheck_client = VectorService::new(client.clone(), healthcheck_uri, false);
+        let healthcheck_client = match cx.healthcheck.uri.clone() {
+            Some(uri) => HealthcheckClient::Single(VectorService::new(
+                client,
+                uri.uri,
+                VectorCompression::None,
+            )),
+            None => HealthcheckClient::Balanced(service.clone()),
+        };
         let healthcheck = healthcheck(healthcheck_client, cx.healthcheck.clone());
-        let service = VectorService::new(client, uri, self.compression);
         let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
         let batch_settings = self.batch.into_batcher_settings()?;
 
@@ -100,28 +166,42 @@ impl VectorConfig {
             Box::pin(healthcheck),
         ))
     }
+
+    /// The addresses the events are sent to, exactly one of `address` and `addresses` being set.
+    fn addresses(&self) -> Result<Vec<&str>, VectorSinkError> {
+        match (&self.address, self.addresses.is_empty()) {
+            (Some(address), true) => Ok(vec![address.as_str()]),
+            (None, false) => Ok(self.addresses.iter().map(String::as_str).collect()),
+            _ => Err(VectorSinkError::InvalidAddresses),
+        }
+    }
+}
+
+enum HealthcheckClient {
+    /// The address of the health check was overridden.
+    Single(VectorService),
+    Balanced(BalancedService),
 }
 
 /// Check to see if the remote service accepts new events.
 async fn healthcheck(
-    mut service: VectorService,
+    client: HealthcheckClient,
     options: SinkHealthcheckOptions,
 ) -> crate::Result<()> {
     if !options.enabled {
         return Ok(());
     }
 
-    let request = service.client.health_check(proto::HealthCheckRequest {});
-
-    if let Ok(response) = request.await {
-        let status = proto::ServingStatus::from_i32(response.into_inner().status);
+    let healthy = match client {
+        HealthcheckClient::Single(mut service) => service.health_check().await,
+        HealthcheckClient::Balanced(service) => service.health_check().await,
+    };
 
-        if let Some(proto::ServingStatus::Serving) = status {
-            return Ok(());
-        }
+    if healthy {
+        Ok(())
+    } else {
+        Err(Box::new(VectorSinkError::Health))
     }
-
-    Err(Box::new(VectorSinkError::Health))
 }
 
 /// grpc doesn't like an address without a scheme, so we default to http or https if one isn't
@@ -158,6 +238,30 @@ pub fn with_default_scheme(address: &str, tls: bool) -> crate::Result<Uri> {
     }
 }
 
+/// Builds the gRPC metadata carrying the client metadata, whose names are restricted to lowercase
+/// letters, digits and underscores so that they can be used as field names and tags as-is.
+fn client_metadata(metadata: &BTreeMap<String, String>) -> Result<MetadataMap, VectorSinkError> {
+    let mut map = MetadataMap::new();
+    for (name, value) in metadata {
+        let valid_name = !name.is_empty()
+            && name
+                .bytes()
+                .all(|byte| matches!(byte, b'a'..=b'z' | b'0'..=b'9' | b'_'));
+        let key = format!("{}{}", proto::CLIENT_METADATA_PREFIX, name);
+        match (
+            valid_name,
+            AsciiMetadataKey::from_str(&key),
+            AsciiMetadataValue::from_str(value),
+        ) {
+            (true, Ok(key), Ok(value)) => {
+                map.insert(key, value);
+            }
+            _ => return Err(VectorSinkError::InvalidClientMetadata { name: name.clone() }),
+        }
+    }
+    Ok(map)
+}
+
 fn new_client(
     tls_settings: &MaybeTlsSettings,
     proxy_config: &ProxyConfig,
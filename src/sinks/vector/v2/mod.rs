@@ -1,5 +1,6 @@
 use snafu::Snafu;
 
+mod balancer;
 mod config;
 mod service;
 mod sink;
@@ -17,12 +18,21 @@ pub enum VectorSinkError {
 
     #[snafu(display("URL has no host."))]
     NoHost,
+
+    #[snafu(display("Exactly one of `address` and `addresses` must be set."))]
+    InvalidAddresses,
+
+    #[snafu(display(
+        "Invalid client metadata `{}`: names can only contain lowercase letters, digits and underscores, and values printable ASCII characters.",
+        name
+    ))]
+    InvalidClientMetadata { name: String },
 }
 
 #[cfg(test)]
 mod tests {
     use bytes::{BufMut, Bytes, BytesMut};
-    use futures::{channel::mpsc, StreamExt};
+    use futures::{stream, Stream, StreamExt};
     use http::request::Parts;
     use hyper::Method;
     use prost::Message;
@@ -33,7 +43,10 @@ mod tests {
         config::SinkContext,
         event::Event,
         proto::vector as proto,
-        sinks::{util::test::build_test_server_generic, vector::v2::config::with_default_scheme},
+        sinks::{
+            util::test::build_test_server_generic,
+            vector::v2::config::{with_default_scheme, VectorCompression},
+        },
         test_util::{
             components::{run_and_assert_sink_compliance, HTTP_SINK_TAGS},
             next_addr, random_lines_with_stream,
@@ -48,6 +61,23 @@ mod tests {
         crate::test_util::test_generate_config::<VectorConfig>();
     }
 
+    #[tokio::test]
+    async fn rejects_invalid_client_metadata() {
+        let config: VectorConfig = toml::from_str(
+            r#"address = "127.0.0.1:6000"
+            client_metadata."Agent-ID" = "agent-1""#,
+        )
+        .unwrap();
+
+        let error = match config.build(SinkContext::new_test()).await {
+            Ok(_) => panic!("invalid client metadata should be rejected"),
+            Err(error) => error,
+        };
+        assert!(error
+            .to_string()
+            .starts_with("Invalid client metadata `Agent-ID`"));
+    }
+
     #[tokio::test]
     async fn deliver_message() {
         let num_lines = 10;
@@ -123,6 +153,118 @@ mod tests {
         assert_eq!(receiver.try_recv(), Ok(BatchStatus::Rejected));
     }
 
+    #[test]
+    fn compression_accepts_booleans() {
+        let config: VectorConfig = toml::from_str(
+            r#"address = "127.0.0.1:6000"
+            compression = true"#,
+        )
+        .unwrap();
+        assert_eq!(config.compression, VectorCompression::Gzip);
+
+        let config: VectorConfig = toml::from_str(
+            r#"address = "127.0.0.1:6000"
+            compression = "zstd""#,
+        )
+        .unwrap();
+        assert_eq!(config.compression, VectorCompression::Zstd);
+    }
+
+    #[tokio::test]
+    async fn requires_exactly_one_of_address_and_addresses() {
+        for config in [
+            "",
+            r#"address = "127.0.0.1:6000"
+            addresses = ["127.0.0.1:6001"]"#,
+        ] {
+            let config: VectorConfig = toml::from_str(config).unwrap();
+            assert!(config.build(SinkContext::new_test()).await.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn fails_over_to_healthy_addresses() {
+        let num_lines = 10;
+
+        let down_addr = next_addr();
+        let in_addr = next_addr();
+
+        let config = format!(
+            r#"addresses = ["http://{}/", "http://{}/"]
+            request.retry_initial_backoff_secs = 1"#,
+            down_addr, in_addr
+        );
+        let config: VectorConfig = toml::from_str(&config).unwrap();
+
+        let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+        let (rx, trigger, server) = build_test_server_generic(in_addr, move || {
+            hyper::Response::builder()
+                .header("grpc-status", "0") // OK
+                .header("content-type", "application/grpc")
+                .body(hyper::Body::from(encode_body(proto::PushEventsResponse {})))
+                .unwrap()
+        });
+
+        tokio::spawn(server);
+
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let (input_lines, events) = random_lines_with_stream(8, num_lines, Some(batch));
+
+        sink.run(events).await.expect("Running sink failed");
+        drop(trigger);
+
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+        let output_lines = get_received(rx, |_| {}).await;
+        assert_eq!(input_lines, output_lines);
+    }
+
+    #[tokio::test]
+    async fn compresses_with_zstd_once_accepted() {
+        let num_lines = 10;
+
+        let in_addr = next_addr();
+
+        let config = format!(
+            r#"address = "http://{}/"
+            compression = "zstd"
+            batch.max_events = 1"#,
+            in_addr
+        );
+        let config: VectorConfig = toml::from_str(&config).unwrap();
+
+        let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+        let (rx, trigger, server) = build_test_server_generic(in_addr, move || {
+            hyper::Response::builder()
+                .header("grpc-status", "0") // OK
+                .header("content-type", "application/grpc")
+                .header(proto::ACCEPT_COMPRESSION_KEY, "zstd")
+                .body(hyper::Body::from(encode_body(proto::PushEventsResponse {})))
+                .unwrap()
+        });
+
+        tokio::spawn(server);
+
+        let (input_lines, events) = random_lines_with_stream(8, num_lines, None);
+
+        sink.run(events).await.expect("Running sink failed");
+        drop(trigger);
+
+        let requests = rx.collect::<Vec<_>>().await;
+        let zstd_requests = requests
+            .iter()
+            .filter(|(_, body)| {
+                let request =
+                    proto::PushEventsRequest::decode(body.slice(GRPC_HEADER_SIZE..)).unwrap();
+                !request.zstd_events.is_empty()
+            })
+            .count();
+        assert!(zstd_requests > 0);
+
+        let output_lines = get_received(stream::iter(requests), |_| {}).await;
+        assert_eq!(input_lines, output_lines);
+    }
+
     #[test]
     fn test_with_default_scheme() {
         assert_eq!(
@@ -136,7 +278,7 @@ mod tests {
     }
 
     async fn get_received(
-        rx: mpsc::Receiver<(Parts, Bytes)>,
+        rx: impl Stream<Item = (Parts, Bytes)>,
         assert_parts: impl Fn(Parts),
     ) -> Vec<String> {
         rx.map(|(parts, body)| {
@@ -144,7 +286,11 @@ mod tests {
 
             let proto_body = body.slice(GRPC_HEADER_SIZE..);
 
-            let req = proto::PushEventsRequest::decode(proto_body).unwrap();
+            let mut req = proto::PushEventsRequest::decode(proto_body).unwrap();
+            if !req.zstd_events.is_empty() {
+                let decompressed = zstd::stream::decode_all(req.zstd_events.as_slice()).unwrap();
+                req = proto::PushEventsRequest::decode(decompressed.as_slice()).unwrap();
+            }
 
             let mut events = Vec::with_capacity(req.events.len());
             for event in req.events {
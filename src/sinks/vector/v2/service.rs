@@ -1,4 +1,10 @@
-use std::task::{Context, Poll};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+};
 
 use futures::{future::BoxFuture, TryFutureExt};
 use http::Uri;
@@ -7,7 +13,7 @@ use hyper_openssl::HttpsConnector;
 use hyper_proxy::ProxyConnector;
 use prost::Message;
 use proto_event::EventWrapper;
-use tonic::{body::BoxBody, IntoRequest};
+use tonic::{body::BoxBody, metadata::MetadataMap, IntoRequest, Status};
 use vector_core::{
     buffers::Ackable, event::proto as proto_event, internal_event::EventsSent,
     stream::DriverResponse,
@@ -17,15 +23,27 @@ use crate::{
     event::{EventFinalizers, EventStatus, Finalizable},
     internal_events::EndpointBytesSent,
     proto::vector as proto_vector,
-    sinks::{util::uri, vector::v2::VectorSinkError},
+    sinks::{
+        util::uri,
+        vector::v2::{config::VectorCompression, VectorSinkError},
+    },
     Error,
 };
 
 #[derive(Clone, Debug)]
 pub struct VectorService {
-    pub client: proto_vector::Client<HyperSvc>,
+    client: proto_vector::Client<HyperSvc>,
+    /// The client compressing the requests with gzip, which all the sources accept, used until the
+    /// source advertises it accepts events compressed with zstd.
+    gzip_client: Option<proto_vector::Client<HyperSvc>>,
     pub protocol: String,
     pub endpoint: String,
+    compression: VectorCompression,
+    /// Whether the source advertised it accepts events compressed with zstd, in the metadata of
+    /// its responses.
+    accepts_zstd: Arc<AtomicBool>,
+    /// The metadata sent with every request.
+    metadata: MetadataMap,
 }
 
 pub struct VectorResponse {
@@ -70,23 +88,98 @@ impl VectorService {
     pub fn new(
         hyper_client: hyper::Client<ProxyConnector<HttpsConnector<HttpConnector>>, BoxBody>,
         uri: Uri,
-        compression: bool,
+        compression: VectorCompression,
     ) -> Self {
         let (protocol, endpoint) = uri::protocol_endpoint(uri.clone());
-        let mut proto_client = proto_vector::Client::new(HyperSvc {
+        let client = proto_vector::Client::new(HyperSvc {
             uri,
             client: hyper_client,
         });
+        let gzip_client =
+            (compression != VectorCompression::None).then(|| client.clone().send_gzip());
 
-        if compression {
-            proto_client = proto_client.send_gzip();
-        }
         Self {
-            client: proto_client,
+            client,
+            gzip_client,
             protocol,
             endpoint,
+            compression,
+            accepts_zstd: Arc::new(AtomicBool::new(false)),
+            metadata: MetadataMap::new(),
+        }
+    }
+
+    pub fn with_metadata(mut self, metadata: MetadataMap) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Checks to see if the source accepts new events.
+    pub async fn health_check(&mut self) -> bool {
+        match self
+            .client
+            .health_check(proto_vector::HealthCheckRequest {})
+            .await
+        {
+            Ok(response) => {
+                self.read_accepted_compressions(response.metadata());
+                let status = proto_vector::ServingStatus::from_i32(response.into_inner().status);
+                status == Some(proto_vector::ServingStatus::Serving)
+            }
+            Err(_) => false,
         }
     }
+
+    fn read_accepted_compressions(&self, metadata: &MetadataMap) {
+        let accepts_zstd = metadata
+            .get(proto_vector::ACCEPT_COMPRESSION_KEY)
+            .and_then(|value| value.to_str().ok())
+            .map_or(false, |value| {
+                value
+                    .split(',')
+                    .any(|compression| compression.trim() == "zstd")
+            });
+        self.accepts_zstd.store(accepts_zstd, Ordering::Relaxed);
+    }
+
+    /// Builds the request pushing the events, compressing them with zstd once the source
+    /// advertised it accepts them, and returns the client to send it with.
+    fn push_request(
+        &self,
+        events: Vec<EventWrapper>,
+    ) -> (
+        proto_vector::PushEventsRequest,
+        proto_vector::Client<HyperSvc>,
+    ) {
+        let request = proto_vector::PushEventsRequest {
+            events,
+            zstd_events: Vec::new(),
+        };
+        if self.compression == VectorCompression::Zstd && self.accepts_zstd.load(Ordering::Relaxed)
+        {
+            // The events are sent uncompressed in the unlikely case they can't be compressed.
+            if let Ok(zstd_events) = zstd::bulk::compress(&request.encode_to_vec(), 0) {
+                let request = proto_vector::PushEventsRequest {
+                    events: Vec::new(),
+                    zstd_events,
+                };
+                return (request, self.client.clone());
+            }
+        }
+        let client = self.gzip_client.as_ref().unwrap_or(&self.client).clone();
+        (request, client)
+    }
+}
+
+/// Whether the error means the source can't be reached or can't take the events, rather than
+/// that the request is invalid, so another source should be tried.
+pub fn is_unavailable(status: &Status) -> bool {
+    use tonic::Code::*;
+
+    matches!(
+        status.code(),
+        Unavailable | Unknown | DeadlineExceeded | Internal | Cancelled
+    )
 }
 
 impl tower::Service<VectorRequest> for VectorService {
@@ -104,19 +197,19 @@ impl tower::Service<VectorRequest> for VectorService {
     }
 
     fn call(&mut self, list: VectorRequest) -> Self::Future {
-        let mut service = self.clone();
+        let service = self.clone();
         let events_count = list.events.len();
         let events_byte_size = list.events_byte_size;
 
-        let request = proto_vector::PushEventsRequest {
-            events: list.events,
-        };
+        let (request, mut client) = self.push_request(list.events);
         let byte_size = request.encoded_len();
+        let mut request = request.into_request();
+        *request.metadata_mut() = self.metadata.clone();
         let future = async move {
-            service
-                .client
-                .push_events(request.into_request())
-                .map_ok(|_response| {
+            client
+                .push_events(request)
+                .map_ok(|response| {
+                    service.read_accepted_compressions(response.metadata());
                     emit!(EndpointBytesSent {
                         byte_size,
                         protocol: &service.protocol,
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
 
@@ -5,10 +7,11 @@ use crate::{
     config::{AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext},
     sinks::{
         util::encoding::{EncodingConfig, StandardEncodings},
-        websocket::sink::{ConnectSnafu, WebSocketConnector, WebSocketError, WebSocketSink},
+        websocket::sink::WebSocketSink,
         Healthcheck, VectorSink,
     },
     tls::{MaybeTlsSettings, TlsEnableableConfig},
+    websocket::{ConnectSnafu, WebSocketConnector, WebSocketError},
 };
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -18,6 +21,24 @@ pub struct WebSocketSinkConfig {
     pub encoding: EncodingConfig<StandardEncodings>,
     pub ping_interval: Option<u64>,
     pub ping_timeout: Option<u64>,
+    #[serde(default = "default_reconnect_initial_backoff_ms")]
+    pub reconnect_initial_backoff_ms: u64,
+    #[serde(default = "default_reconnect_max_backoff_secs")]
+    pub reconnect_max_backoff_secs: u64,
+    #[serde(default = "default_replay_buffer_max_events")]
+    pub replay_buffer_max_events: usize,
+}
+
+const fn default_reconnect_initial_backoff_ms() -> u64 {
+    500
+}
+
+const fn default_reconnect_max_backoff_secs() -> u64 {
+    60
+}
+
+const fn default_replay_buffer_max_events() -> usize {
+    1000
 }
 
 impl GenerateConfig for WebSocketSinkConfig {
@@ -28,6 +49,9 @@ impl GenerateConfig for WebSocketSinkConfig {
             encoding: StandardEncodings::Json.into(),
             ping_interval: None,
             ping_timeout: None,
+            reconnect_initial_backoff_ms: default_reconnect_initial_backoff_ms(),
+            reconnect_max_backoff_secs: default_reconnect_max_backoff_secs(),
+            replay_buffer_max_events: default_replay_buffer_max_events(),
         })
         .unwrap()
     }
@@ -62,7 +86,12 @@ impl SinkConfig for WebSocketSinkConfig {
 impl WebSocketSinkConfig {
     fn build_connector(&self) -> Result<WebSocketConnector, WebSocketError> {
         let tls = MaybeTlsSettings::from_config(&self.tls, false).context(ConnectSnafu)?;
-        WebSocketConnector::new(self.uri.clone(), tls)
+        WebSocketConnector::new(
+            self.uri.clone(),
+            tls,
+            Duration::from_millis(self.reconnect_initial_backoff_ms),
+            Duration::from_secs(self.reconnect_max_backoff_secs),
+        )
     }
 }
 
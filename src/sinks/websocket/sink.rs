@@ -1,30 +1,15 @@
-use std::{
-    fmt::Debug,
-    io,
-    net::SocketAddr,
-    task::{Context, Poll},
-    time::{Duration, Instant},
-};
+use std::{collections::VecDeque, time::Duration};
 
 use async_trait::async_trait;
 use futures::{
-    future::{self},
     pin_mut,
     sink::SinkExt,
-    stream::BoxStream,
+    stream::{BoxStream, FusedStream},
     Sink, Stream, StreamExt,
 };
-use snafu::{ResultExt, Snafu};
 use tokio::{net::TcpStream, time};
 use tokio_tungstenite::{
-    client_async_with_config,
-    tungstenite::{
-        client::{uri_mode, IntoClientRequest},
-        error::{Error as WsError, ProtocolError, UrlError},
-        handshake::client::Request as WsRequest,
-        protocol::{Message, WebSocketConfig},
-        stream::Mode as UriMode,
-    },
+    tungstenite::{error::Error as WsError, protocol::Message},
     WebSocketStream as WsStream,
 };
 use vector_core::{
@@ -33,152 +18,113 @@ use vector_core::{
 };
 
 use crate::{
-    dns, emit,
+    emit,
     event::Event,
     internal_events::{
-        ConnectionOpen, OpenGauge, WsConnectionError, WsConnectionEstablished,
-        WsConnectionFailedError, WsConnectionShutdown,
+        prelude::error_stage, ConnectionOpen, OpenGauge, WsConnectionError, WsConnectionShutdown,
+        WsMessagesDropped, WsMessagesReplayed,
     },
     sinks::util::{
         encoding::{Encoder, EncodingConfig, StandardEncodings},
-        retries::ExponentialBackoff,
         StreamSink,
     },
     sinks::websocket::config::WebSocketSinkConfig,
-    tls::{MaybeTlsSettings, MaybeTlsStream, TlsError},
+    tls::MaybeTlsStream,
+    websocket::{is_closed, pong_timeout, PingInterval, WebSocketConnector},
 };
 
-#[derive(Debug, Snafu)]
-#[snafu(visibility(pub))]
-pub enum WebSocketError {
-    #[snafu(display("Creating WebSocket client failed: {}", source))]
-    CreateFailed { source: WsError },
-    #[snafu(display("Connect error: {}", source))]
-    ConnectError { source: TlsError },
-    #[snafu(display("Unable to resolve DNS: {}", source))]
-    DnsError { source: dns::DnsError },
-    #[snafu(display("No addresses returned."))]
-    NoAddresses,
-}
-
-#[derive(Clone)]
-pub struct WebSocketConnector {
-    uri: String,
-    host: String,
-    port: u16,
-    tls: MaybeTlsSettings,
+/// The messages of the accepted events which aren't known to have been received by the server, in
+/// order: the ones sent on the current connection, followed by the ones waiting to be sent. They are
+/// all sent again after reconnecting.
+///
+/// Each message has a position, counting all the accepted messages, which is sent as the payload
+/// of the pings. Since the server answers the pings in order, a pong tells that the messages before
+/// its position were received.
+///
+/// Each message also holds the number of events acknowledged once it leaves the buffer: its own,
+/// and those accepted after it which couldn't be encoded, since the events are acknowledged in
+/// order.
+struct ReplayBuffer {
+    messages: VecDeque<(Message, usize)>,
+    /// The position of the first message.
+    first_position: u64,
+    /// The number of messages, at the front, sent on the current connection.
+    sent: usize,
+    max_events: usize,
 }
 
-impl WebSocketConnector {
-    pub fn new(uri: String, tls: MaybeTlsSettings) -> Result<Self, WebSocketError> {
-        let request = (&uri).into_client_request().context(CreateFailedSnafu)?;
-        let (host, port) = Self::extract_host_and_port(&request).context(CreateFailedSnafu)?;
-
-        Ok(Self {
-            uri,
-            host,
-            port,
-            tls,
-        })
+impl ReplayBuffer {
+    fn new(max_events: usize) -> Self {
+        Self {
+            messages: VecDeque::new(),
+            first_position: 0,
+            sent: 0,
+            max_events: max_events.max(1),
+        }
     }
 
-    fn extract_host_and_port(request: &WsRequest) -> Result<(String, u16), WsError> {
-        let host = request
-            .uri()
-            .host()
-            .ok_or(WsError::Url(UrlError::NoHostName))?
-            .to_string();
-        let mode = uri_mode(request.uri())?;
-        let port = request.uri().port_u16().unwrap_or_else(|| match mode {
-            UriMode::Tls => 443,
-            UriMode::Plain => 80,
-        });
-
-        Ok((host, port))
+    fn is_empty(&self) -> bool {
+        self.messages.is_empty()
     }
 
-    const fn fresh_backoff() -> ExponentialBackoff {
-        ExponentialBackoff::from_millis(2)
-            .factor(250)
-            .max_delay(Duration::from_secs(60))
+    /// Whether another message can be accepted. When the buffer is full, its oldest sent message
+    /// is dropped to make room, but the messages that were never sent are kept, holding up the
+    /// input.
+    fn has_room(&self) -> bool {
+        self.messages.len() < self.max_events || self.sent > 0
     }
 
-    async fn tls_connect(&self) -> Result<MaybeTlsStream<TcpStream>, WebSocketError> {
-        let ip = dns::Resolver
-            .lookup_ip(self.host.clone())
-            .await
-            .context(DnsSnafu)?
-            .next()
-            .ok_or(WebSocketError::NoAddresses)?;
-
-        let addr = SocketAddr::new(ip, self.port);
-        self.tls
-            .connect(&self.host, &addr)
-            .await
-            .context(ConnectSnafu)
-    }
-
-    async fn connect(&self) -> Result<WsStream<MaybeTlsStream<TcpStream>>, WebSocketError> {
-        let request = (&self.uri)
-            .into_client_request()
-            .context(CreateFailedSnafu)?;
-        let maybe_tls = self.tls_connect().await?;
-
-        let ws_config = WebSocketConfig {
-            max_send_queue: None, // don't buffer messages
-            ..Default::default()
+    /// Adds a message, returning the number of events to acknowledge for the oldest sent message
+    /// if it had to be dropped to make room.
+    fn push(&mut self, message: Message) -> Option<usize> {
+        let dropped = if self.messages.len() >= self.max_events && self.sent > 0 {
+            self.first_position += 1;
+            self.sent -= 1;
+            self.messages.pop_front().map(|(_, events)| events)
+        } else {
+            None
         };
-
-        let (ws_stream, _response) = client_async_with_config(request, maybe_tls, Some(ws_config))
-            .await
-            .context(CreateFailedSnafu)?;
-
-        Ok(ws_stream)
+        self.messages.push_back((message, 1));
+        dropped
     }
 
-    async fn connect_backoff(&self) -> WsStream<MaybeTlsStream<TcpStream>> {
-        let mut backoff = Self::fresh_backoff();
-        loop {
-            match self.connect().await {
-                Ok(ws_stream) => {
-                    emit!(WsConnectionEstablished {});
-                    return ws_stream;
-                }
-                Err(error) => {
-                    emit!(WsConnectionFailedError {
-                        error: Box::new(error)
-                    });
-                    time::sleep(backoff.next().unwrap()).await;
-                }
+    /// Accounts for an event which couldn't be encoded, returning the number of events to
+    /// acknowledge right away.
+    fn skip(&mut self) -> usize {
+        match self.messages.back_mut() {
+            Some((_, events)) => {
+                *events += 1;
+                0
             }
+            None => 1,
         }
     }
 
-    pub async fn healthcheck(&self) -> crate::Result<()> {
-        self.connect().await.map(|_| ()).map_err(Into::into)
+    fn next_unsent(&self) -> Option<&Message> {
+        self.messages.get(self.sent).map(|(message, _)| message)
     }
-}
 
-struct PingInterval {
-    interval: Option<time::Interval>,
-}
+    fn mark_sent(&mut self) {
+        self.sent += 1;
+    }
 
-impl PingInterval {
-    fn new(period: Option<u64>) -> Self {
-        Self {
-            interval: period.map(|period| time::interval(Duration::from_secs(period))),
-        }
+    /// The position following the last sent message.
+    const fn sent_position(&self) -> u64 {
+        self.first_position + self.sent as u64
     }
 
-    fn poll_tick(&mut self, cx: &mut Context<'_>) -> Poll<time::Instant> {
-        match self.interval.as_mut() {
-            Some(interval) => interval.poll_tick(cx),
-            None => Poll::Pending,
-        }
+    /// Drops the sent messages before `position`, which the server is known to have received,
+    /// returning the number of events to acknowledge.
+    fn confirm(&mut self, position: u64) -> usize {
+        let count = (position.saturating_sub(self.first_position) as usize).min(self.sent);
+        self.first_position += count as u64;
+        self.sent -= count;
+        self.messages.drain(..count).map(|(_, events)| events).sum()
     }
 
-    async fn tick(&mut self) -> time::Instant {
-        future::poll_fn(|cx| self.poll_tick(cx)).await
+    /// Marks the sent messages to be sent again, returning their number.
+    fn rewind(&mut self) -> usize {
+        std::mem::take(&mut self.sent)
     }
 }
 
@@ -188,6 +134,7 @@ pub struct WebSocketSink {
     acker: Acker,
     ping_interval: Option<u64>,
     ping_timeout: Option<u64>,
+    replay_buffer_max_events: usize,
 }
 
 impl WebSocketSink {
@@ -198,137 +145,191 @@ impl WebSocketSink {
             acker,
             ping_interval: config.ping_interval.filter(|v| *v > 0),
             ping_timeout: config.ping_timeout.filter(|v| *v > 0),
+            replay_buffer_max_events: config.replay_buffer_max_events,
         }
     }
 
-    async fn create_sink_and_stream(
-        &self,
-    ) -> (
-        impl Sink<Message, Error = WsError>,
-        impl Stream<Item = Result<Message, WsError>>,
-    ) {
-        let ws_stream = self.connector.connect_backoff().await;
-        ws_stream.split()
+    /// Adds the event to the buffer. It is acknowledged once the server is known to have received
+    /// it, or when it's dropped.
+    fn accept(&self, event: Event, buffer: &mut ReplayBuffer) {
+        match encode_event(event, &self.encoding) {
+            Some(msg) => {
+                if let Some(events) = buffer.push(msg) {
+                    emit!(WsMessagesDropped { count: 1 });
+                    self.acker.ack(events);
+                }
+            }
+            None => self.acker.ack(buffer.skip()),
+        }
     }
 
-    fn check_received_pong_time(&self, last_pong: Instant) -> Result<(), WsError> {
-        if let Some(ping_timeout) = self.ping_timeout {
-            if last_pong.elapsed() > Duration::from_secs(ping_timeout) {
-                return Err(WsError::Io(io::Error::new(
-                    io::ErrorKind::TimedOut,
-                    "Pong not received in time",
-                )));
+    fn confirm(&self, buffer: &mut ReplayBuffer, position: u64) {
+        self.acker.ack(buffer.confirm(position));
+    }
+
+    /// Connects to the server, accepting events while the buffer has room in the meantime.
+    /// Returns `None` once the input has ended and all the accepted events were sent.
+    async fn connect<I>(
+        &self,
+        input: &mut I,
+        buffer: &mut ReplayBuffer,
+    ) -> Option<WsStream<MaybeTlsStream<TcpStream>>>
+    where
+        I: FusedStream<Item = Event> + Unpin,
+    {
+        if buffer.is_empty() {
+            // Don't connect until there is something to send.
+            let event = input.next().await?;
+            self.accept(event, buffer);
+        }
+
+        let connect = self.connector.connect_backoff(error_stage::SENDING);
+        pin_mut!(connect);
+        loop {
+            tokio::select! {
+                ws_stream = &mut connect => return Some(ws_stream),
+                Some(event) = input.next(), if buffer.has_room() => self.accept(event, buffer),
             }
         }
+    }
 
+    async fn send<O>(&self, ws_sink: &mut O, msg: Message) -> Result<(), WsError>
+    where
+        O: Sink<Message, Error = WsError> + Unpin,
+    {
+        let msg_len = msg.len();
+        ws_sink.send(msg).await?;
+        emit!(EventsSent {
+            count: 1,
+            byte_size: msg_len,
+            output: None
+        });
+        emit!(BytesSent {
+            byte_size: msg_len,
+            protocol: "websocket"
+        });
+        Ok(())
+    }
+
+    /// Sends the next message waiting in the buffer, if any. Without pings, nothing tells whether
+    /// the server received it, so it is dropped from the buffer once sent.
+    async fn send_next<O>(&self, ws_sink: &mut O, buffer: &mut ReplayBuffer) -> Result<(), WsError>
+    where
+        O: Sink<Message, Error = WsError> + Unpin,
+    {
+        if let Some(msg) = buffer.next_unsent().cloned() {
+            self.send(ws_sink, msg).await?;
+            buffer.mark_sent();
+            if self.ping_interval.is_none() {
+                self.confirm(buffer, buffer.sent_position());
+            }
+        }
         Ok(())
     }
 
     async fn handle_events<I, WS, O>(
         &self,
         input: &mut I,
+        buffer: &mut ReplayBuffer,
         ws_stream: &mut WS,
         ws_sink: &mut O,
     ) -> Result<(), ()>
     where
-        I: Stream<Item = Event> + Unpin,
+        I: FusedStream<Item = Event> + Unpin,
         WS: Stream<Item = Result<Message, WsError>> + Unpin,
         O: Sink<Message, Error = WsError> + Unpin,
     {
-        const PING: &[u8] = b"PING";
-
         let mut ping_interval = PingInterval::new(self.ping_interval);
-
-        if let Err(error) = ws_sink.send(Message::Ping(PING.to_vec())).await {
-            emit!(WsConnectionError { error });
-            return Err(());
+        // When the oldest ping which wasn't answered yet was sent.
+        let mut unanswered_ping = None;
+
+        let mut result = async {
+            // Send the events accepted while disconnected, and those which weren't known to have
+            // been received on the previous connection, before the first ping.
+            while buffer.next_unsent().is_some() {
+                self.send_next(ws_sink, buffer).await?;
+            }
+            send_ping(ws_sink, buffer, &mut unanswered_ping).await
         }
-        let mut last_pong = Instant::now();
+        .await;
 
-        loop {
-            let result = tokio::select! {
-                _ = ping_interval.tick() => {
-                    match self.check_received_pong_time(last_pong) {
-                        Ok(()) => ws_sink.send(Message::Ping(PING.to_vec())).await.map(|_| ()),
-                        Err(e) => Err(e)
-                    }
-                },
+        while result.is_ok() {
+            let pong_deadline = unanswered_ping
+                .zip(self.ping_timeout)
+                .map(|(sent_at, timeout)| sent_at + Duration::from_secs(timeout));
+
+            result = tokio::select! {
+                _ = ping_interval.tick() => send_ping(ws_sink, buffer, &mut unanswered_ping).await,
 
-                Some(msg) = ws_stream.next() => {
+                error = pong_timeout(pong_deadline) => Err(error),
+
+                msg = ws_stream.next() => {
                     // Pongs are sent automatically by tungstenite during reading from the stream.
                     match msg {
-                        Ok(Message::Pong(_)) => {
-                            last_pong = Instant::now();
+                        Some(Ok(Message::Pong(payload))) => {
+                            unanswered_ping = None;
+                            if let Ok(position) = payload[..].try_into() {
+                                self.confirm(buffer, u64::from_be_bytes(position));
+                            }
                             Ok(())
                         },
-                        Ok(_) => Ok(()),
-                        Err(e) => Err(e)
+                        Some(Ok(_)) => Ok(()),
+                        Some(Err(e)) => Err(e),
+                        None => Err(WsError::ConnectionClosed),
                     }
                 },
 
-                event = input.next() => {
-                    if event.is_none() {
-                        break;
-                    }
-                    let log = encode_event(event.unwrap(), &self.encoding);
-                    let res = match log {
-                        Some(msg) => {
-                            let msg_len = msg.len();
-                            ws_sink.send(msg).await.map(|_| {
-                                emit!(EventsSent {
-                                    count: 1,
-                                    byte_size: msg_len,
-                                    output: None
-                                });
-                                emit!(BytesSent {
-                                    byte_size: msg_len,
-                                    protocol: "websocket"
-                                });
-                            })
+                event = input.next(), if buffer.has_room() => {
+                    match event {
+                        Some(event) => {
+                            self.accept(event, buffer);
+                            self.send_next(ws_sink, buffer).await
                         },
-                        None => {
-                            Ok(())
-                        }
-                    };
-                    self.acker.ack(1);
-                    res
+                        None => return Ok(()),
+                    }
                 },
-                else => break,
             };
+        }
 
-            if let Err(error) = result {
-                if is_closed(&error) {
-                    emit!(WsConnectionShutdown);
-                } else {
-                    emit!(WsConnectionError { error });
-                }
-                return Err(());
+        if let Err(error) = result {
+            if is_closed(&error) {
+                emit!(WsConnectionShutdown);
+            } else {
+                emit!(WsConnectionError {
+                    error,
+                    stage: error_stage::SENDING,
+                });
             }
         }
-
-        Ok(())
+        Err(())
     }
 }
 
 #[async_trait]
 impl StreamSink<Event> for WebSocketSink {
     async fn run(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
-        let input = input.fuse().peekable();
-        pin_mut!(input);
+        let mut input = input.fuse();
+        let mut buffer = ReplayBuffer::new(self.replay_buffer_max_events);
 
-        while input.as_mut().peek().await.is_some() {
-            let (ws_sink, ws_stream) = self.create_sink_and_stream().await;
+        while let Some(ws_stream) = self.connect(&mut input, &mut buffer).await {
+            let (ws_sink, ws_stream) = ws_stream.split();
             pin_mut!(ws_sink);
             pin_mut!(ws_stream);
 
             let _open_token = OpenGauge::new().open(|count| emit!(ConnectionOpen { count }));
 
             if self
-                .handle_events(&mut input, &mut ws_stream, &mut ws_sink)
+                .handle_events(&mut input, &mut buffer, &mut ws_stream, &mut ws_sink)
                 .await
                 .is_ok()
             {
                 let _ = ws_sink.close().await;
+                break;
+            }
+
+            let count = buffer.rewind();
+            if count > 0 {
+                emit!(WsMessagesReplayed { count });
             }
         }
 
@@ -336,13 +337,19 @@ impl StreamSink<Event> for WebSocketSink {
     }
 }
 
-const fn is_closed(error: &WsError) -> bool {
-    matches!(
-        error,
-        WsError::ConnectionClosed
-            | WsError::AlreadyClosed
-            | WsError::Protocol(ProtocolError::ResetWithoutClosingHandshake)
-    )
+/// Sends a ping with the position following the sent messages as its payload.
+async fn send_ping<O>(
+    ws_sink: &mut O,
+    buffer: &ReplayBuffer,
+    unanswered_ping: &mut Option<time::Instant>,
+) -> Result<(), WsError>
+where
+    O: Sink<Message, Error = WsError> + Unpin,
+{
+    let payload = buffer.sent_position().to_be_bytes().to_vec();
+    ws_sink.send(Message::Ping(payload)).await?;
+    unanswered_ping.get_or_insert_with(time::Instant::now);
+    Ok(())
 }
 
 fn encode_event(event: Event, encoding: &EncodingConfig<StandardEncodings>) -> Option<Message> {
@@ -374,7 +381,7 @@ mod tests {
             components::{run_and_assert_sink_compliance, SINK_TAGS},
             next_addr, random_lines_with_stream, trace_init, CountReceiver,
         },
-        tls::{self, TlsConfig, TlsEnableableConfig},
+        tls::{self, MaybeTlsSettings, TlsConfig, TlsEnableableConfig},
     };
 
     #[test]
@@ -410,6 +417,9 @@ mod tests {
             encoding: StandardEncodings::Json.into(),
             ping_interval: None,
             ping_timeout: None,
+            reconnect_initial_backoff_ms: 500,
+            reconnect_max_backoff_secs: 60,
+            replay_buffer_max_events: 1000,
         };
         let tls = MaybeTlsSettings::Raw(());
 
@@ -438,6 +448,9 @@ mod tests {
             encoding: StandardEncodings::Json.into(),
             ping_timeout: None,
             ping_interval: None,
+            reconnect_initial_backoff_ms: 500,
+            reconnect_max_backoff_secs: 60,
+            replay_buffer_max_events: 1000,
         };
 
         send_events_and_assert(addr, config, tls).await;
@@ -454,6 +467,9 @@ mod tests {
             encoding: StandardEncodings::Json.into(),
             ping_interval: None,
             ping_timeout: None,
+            reconnect_initial_backoff_ms: 500,
+            reconnect_max_backoff_secs: 60,
+            replay_buffer_max_events: 1000,
         };
         let tls = MaybeTlsSettings::Raw(());
 
@@ -479,6 +495,86 @@ mod tests {
             .is_ok());
     }
 
+    #[tokio::test]
+    async fn sends_events_accepted_while_disconnected() {
+        trace_init();
+
+        let addr = next_addr();
+        let config = WebSocketSinkConfig {
+            uri: format!("ws://{}", addr),
+            tls: None,
+            encoding: StandardEncodings::Text.into(),
+            ping_interval: Some(1),
+            ping_timeout: None,
+            reconnect_initial_backoff_ms: 10,
+            reconnect_max_backoff_secs: 1,
+            replay_buffer_max_events: 1000,
+        };
+
+        let context = SinkContext::new_test();
+        let (sink, _healthcheck) = config.build(context).await.unwrap();
+
+        let (lines, events) = random_lines_with_stream(10, 100, None);
+        let sink = tokio::spawn(sink.run(events));
+
+        // The server is only started once the sink has failed to connect.
+        time::sleep(Duration::from_millis(200)).await;
+        let mut receiver = create_count_receiver(addr, MaybeTlsSettings::Raw(()), false);
+        receiver.connected().await;
+        sink.await.unwrap().unwrap();
+
+        assert_eq!(receiver.await, lines);
+    }
+
+    #[test]
+    fn replay_buffer_confirms_and_rewinds_messages() {
+        let mut buffer = ReplayBuffer::new(3);
+        for message in ["0", "1", "2"] {
+            buffer.push(Message::text(message));
+        }
+        buffer.mark_sent();
+        buffer.mark_sent();
+        assert_eq!(buffer.sent_position(), 2);
+
+        assert_eq!(buffer.confirm(1), 1);
+        assert_eq!(buffer.sent_position(), 2);
+        assert_eq!(buffer.next_unsent(), Some(&Message::text("2")));
+
+        assert_eq!(buffer.rewind(), 1);
+        assert_eq!(buffer.next_unsent(), Some(&Message::text("1")));
+    }
+
+    #[test]
+    fn replay_buffer_drops_oldest_sent_messages_when_full() {
+        let mut buffer = ReplayBuffer::new(2);
+        buffer.push(Message::text("0"));
+        buffer.push(Message::text("1"));
+        assert!(!buffer.has_room());
+
+        buffer.mark_sent();
+        assert!(buffer.has_room());
+        assert_eq!(buffer.push(Message::text("2")), Some(1));
+        assert_eq!(buffer.sent_position(), 1);
+        assert_eq!(buffer.next_unsent(), Some(&Message::text("1")));
+        assert!(!buffer.has_room());
+    }
+
+    #[test]
+    fn replay_buffer_acknowledges_skipped_events_in_order() {
+        let mut buffer = ReplayBuffer::new(3);
+        assert_eq!(buffer.skip(), 1);
+
+        assert_eq!(buffer.push(Message::text("0")), None);
+        assert_eq!(buffer.skip(), 0);
+        assert_eq!(buffer.push(Message::text("1")), None);
+        buffer.mark_sent();
+        buffer.mark_sent();
+
+        assert_eq!(buffer.confirm(1), 2);
+        assert_eq!(buffer.confirm(2), 1);
+        assert!(buffer.is_empty());
+    }
+
     async fn send_events_and_assert(
         addr: SocketAddr,
         config: WebSocketSinkConfig,
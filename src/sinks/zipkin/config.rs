@@ -0,0 +1,131 @@
+use futures::FutureExt;
+use http::{Request, StatusCode, Uri};
+use hyper::Body;
+use serde::{Deserialize, Serialize};
+use tower::ServiceBuilder;
+
+use super::{
+    service::{ZipkinResponse, ZipkinService},
+    sink::ZipkinSink,
+    ZipkinSinkError,
+};
+use crate::{
+    config::{AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext},
+    http::{Auth, HttpClient, HttpError, MaybeAuth},
+    sinks::{
+        util::{
+            retries::RetryLogic, BatchConfig, RealtimeEventBasedDefaultBatchSettings,
+            ServiceBuilderExt, TowerRequestConfig, UriSerde,
+        },
+        Healthcheck, HealthcheckError, VectorSink,
+    },
+    tls::{MaybeTlsSettings, TlsEnableableConfig},
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ZipkinSinkConfig {
+    /// The base URL of the Zipkin API, to which `/api/v2/spans` is appended.
+    endpoint: UriSerde,
+    #[serde(default)]
+    compression: bool,
+    auth: Option<Auth>,
+    #[serde(default)]
+    batch: BatchConfig<RealtimeEventBasedDefaultBatchSettings>,
+    #[serde(default)]
+    request: TowerRequestConfig,
+    tls: Option<TlsEnableableConfig>,
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+impl GenerateConfig for ZipkinSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"endpoint = "http://localhost:9411""#).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "zipkin")]
+impl SinkConfig for ZipkinSinkConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let endpoint = self.endpoint.with_default_parts();
+        let auth = self.auth.choose_one(&endpoint.auth)?;
+        let tls = MaybeTlsSettings::from_config(&self.tls, false)?;
+        let client = HttpClient::new(tls, cx.proxy())?;
+
+        let healthcheck = healthcheck(
+            client.clone(),
+            endpoint.append_path("health")?.uri,
+            auth.clone(),
+        )
+        .boxed();
+
+        let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
+        let service = ServiceBuilder::new()
+            .settings(request_settings, ZipkinRetryLogic)
+            .service(ZipkinService::new(
+                client,
+                endpoint.append_path("api/v2/spans")?.uri,
+                auth,
+                self.compression,
+            ));
+
+        let sink = ZipkinSink {
+            batch_settings: self.batch.into_batcher_settings()?,
+            service,
+            acker: cx.acker(),
+        };
+
+        Ok((VectorSink::from_event_streamsink(sink), healthcheck))
+    }
+
+    fn input(&self) -> Input {
+        Input::trace()
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "zipkin"
+    }
+
+    fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
+        Some(&self.acknowledgements)
+    }
+}
+
+/// Checks the `/health` endpoint of the Zipkin server.
+async fn healthcheck(client: HttpClient, uri: Uri, auth: Option<Auth>) -> crate::Result<()> {
+    let mut request = Request::get(uri)
+        .body(Body::empty())
+        .expect("Building request never fails.");
+    if let Some(auth) = auth {
+        auth.apply(&mut request);
+    }
+
+    let response = client.send(request).await?;
+    match response.status() {
+        StatusCode::OK => Ok(()),
+        status => Err(HealthcheckError::UnexpectedStatus { status }.into()),
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ZipkinRetryLogic;
+
+impl RetryLogic for ZipkinRetryLogic {
+    type Error = ZipkinSinkError;
+    type Response = ZipkinResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        match error {
+            ZipkinSinkError::Http { source } => matches!(source, HttpError::CallRequest { .. }),
+            ZipkinSinkError::HttpStatus { status } => {
+                *status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+            }
+        }
+    }
+}
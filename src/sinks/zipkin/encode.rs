@@ -0,0 +1,193 @@
+//! Encoding of spans into the Zipkin v2 JSON model.
+//!
+//! The tags of the process are merged into the tags of the spans, as Zipkin only records the name
+//! of the service. Links have no Zipkin counterpart, and aren't sent.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::sinks::util::spans::{Span, SpanKind, TagValue, Tags};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ZipkinSpan<'a> {
+    trace_id: String,
+    id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent_id: Option<String>,
+    name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kind: Option<&'static str>,
+    timestamp: i64,
+    duration: i64,
+    local_endpoint: Endpoint<'a>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    annotations: Vec<Annotation>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    tags: BTreeMap<&'a str, String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Endpoint<'a> {
+    service_name: &'a str,
+}
+
+#[derive(Serialize)]
+struct Annotation {
+    timestamp: i64,
+    value: String,
+}
+
+/// Encodes the spans as the JSON array posted to the collector.
+pub(super) fn spans(spans: &[Span]) -> Vec<u8> {
+    let spans = spans.iter().map(zipkin_span).collect::<Vec<_>>();
+    serde_json::to_vec(&spans).expect("serializing the spans should not fail")
+}
+
+fn zipkin_span(span: &Span) -> ZipkinSpan<'_> {
+    let mut tags = BTreeMap::new();
+    for (key, value) in span.process.tags.iter().chain(&span.tags) {
+        tags.insert(key.as_str(), value.to_string());
+    }
+    if span.error {
+        tags.insert(
+            "error",
+            span.status_message
+                .clone()
+                .unwrap_or_else(|| "true".to_owned()),
+        );
+    }
+
+    ZipkinSpan {
+        trace_id: trace_id(span.trace_id),
+        id: format!("{:016x}", span.span_id),
+        parent_id: span.parent_span_id.map(|id| format!("{:016x}", id)),
+        name: &span.name,
+        kind: kind(span.kind),
+        timestamp: micros(span.start_unix_nano),
+        // Zipkin considers a duration of zero to be unknown.
+        duration: micros(span.duration_nano).max(1),
+        local_endpoint: Endpoint {
+            service_name: &span.process.service,
+        },
+        annotations: span
+            .events
+            .iter()
+            .map(|event| Annotation {
+                timestamp: micros(event.time_unix_nano),
+                value: annotation_value(&event.name, &event.attributes),
+            })
+            .collect(),
+        tags,
+    }
+}
+
+/// Trace IDs are 64 bits long when their high bits aren't set, as with Datadog traces.
+fn trace_id(id: u128) -> String {
+    if id >> 64 == 0 {
+        format!("{:016x}", id)
+    } else {
+        format!("{:032x}", id)
+    }
+}
+
+const fn kind(kind: SpanKind) -> Option<&'static str> {
+    match kind {
+        SpanKind::Server => Some("SERVER"),
+        SpanKind::Client => Some("CLIENT"),
+        SpanKind::Producer => Some("PRODUCER"),
+        SpanKind::Consumer => Some("CONSUMER"),
+        SpanKind::Unspecified | SpanKind::Internal => None,
+    }
+}
+
+const fn micros(nanos: i64) -> i64 {
+    nanos / 1_000
+}
+
+/// Events with attributes are written as a JSON object of the attributes keyed by the name of the
+/// event, as done by the OpenTelemetry exporters.
+fn annotation_value(name: &str, attributes: &Tags) -> String {
+    if attributes.is_empty() {
+        return name.to_owned();
+    }
+    let attributes = attributes
+        .iter()
+        .map(|(key, value)| (key.clone(), json(value)))
+        .collect::<serde_json::Map<_, _>>();
+    serde_json::json!({ name: attributes }).to_string()
+}
+
+fn json(value: &TagValue) -> serde_json::Value {
+    match value {
+        TagValue::String(string) => string.clone().into(),
+        TagValue::Bool(boolean) => (*boolean).into(),
+        TagValue::Long(integer) => (*integer).into(),
+        TagValue::Double(double) => double.into_inner().into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::sinks::util::spans::{Process, SpanEvent};
+
+    #[test]
+    fn encodes_spans() {
+        let span = Span {
+            process: Process {
+                service: "orders".to_owned(),
+                tags: vec![("host.name".to_owned(), TagValue::String("web-1".to_owned()))],
+            },
+            trace_id: 0x4bf92f3577b34da6a3ce929d0e0e4736,
+            span_id: 0x00f067aa0ba902b7,
+            parent_span_id: Some(0x53995c3f42cd8ad8),
+            name: "GET /orders".to_owned(),
+            kind: SpanKind::Server,
+            start_unix_nano: 1_654_000_000_000_000_000,
+            duration_nano: 250_000_000,
+            tags: vec![("http.status_code".to_owned(), TagValue::Long(500))],
+            events: vec![SpanEvent {
+                time_unix_nano: 1_654_000_000_100_000_000,
+                name: "retry".to_owned(),
+                attributes: vec![("attempt".to_owned(), TagValue::Long(2))],
+            }],
+            links: Vec::new(),
+            error: true,
+            status_message: Some("timed out".to_owned()),
+        };
+
+        let encoded: serde_json::Value = serde_json::from_slice(&spans(&[span])).unwrap();
+        assert_eq!(
+            encoded,
+            json!([{
+                "traceId": "4bf92f3577b34da6a3ce929d0e0e4736",
+                "id": "00f067aa0ba902b7",
+                "parentId": "53995c3f42cd8ad8",
+                "name": "GET /orders",
+                "kind": "SERVER",
+                "timestamp": 1_654_000_000_000_000_i64,
+                "duration": 250_000,
+                "localEndpoint": { "serviceName": "orders" },
+                "annotations": [{
+                    "timestamp": 1_654_000_000_100_000_i64,
+                    "value": r#"{"retry":{"attempt":2}}"#,
+                }],
+                "tags": {
+                    "error": "timed out",
+                    "host.name": "web-1",
+                    "http.status_code": "500",
+                },
+            }])
+        );
+    }
+
+    #[test]
+    fn encodes_short_trace_ids() {
+        assert_eq!(trace_id(123), "000000000000007b");
+    }
+}
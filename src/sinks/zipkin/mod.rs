@@ -0,0 +1,34 @@
+//! The Zipkin [`VectorSink`](crate::sinks::VectorSink).
+//!
+//! This module contains the sink exporting trace events to Zipkin, or to any backend accepting the
+//! Zipkin v2 JSON API. The events are converted into spans by
+//! [`spans`](crate::sinks::util::spans), whichever source they were received by, and posted to
+//! `/api/v2/spans`.
+
+#[cfg(test)]
+mod tests;
+
+mod config;
+mod encode;
+mod service;
+mod sink;
+
+use http::StatusCode;
+use snafu::Snafu;
+
+pub use self::config::ZipkinSinkConfig;
+use crate::{config::SinkDescription, http::HttpError};
+
+inventory::submit! {
+    SinkDescription::new::<ZipkinSinkConfig>("zipkin")
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(super)))]
+pub enum ZipkinSinkError {
+    #[snafu(display("HTTP request failed: {}", source))]
+    Http { source: HttpError },
+
+    #[snafu(display("HTTP request failed with status {}", status))]
+    HttpStatus { status: StatusCode },
+}
@@ -0,0 +1,153 @@
+use std::{
+    io::Write,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use http::{
+    header::{CONTENT_ENCODING, CONTENT_TYPE},
+    uri::Scheme,
+    Request, Uri,
+};
+use hyper::Body;
+use snafu::ResultExt;
+use tower::Service;
+use vector_common::internal_event::{BytesSent, EventsSent};
+use vector_core::{buffers::Ackable, stream::DriverResponse};
+
+use super::{HttpSnafu, ZipkinSinkError};
+use crate::{
+    event::{EventFinalizers, EventStatus, Finalizable},
+    http::{Auth, BuildRequestSnafu, HttpClient},
+    sinks::util::{Compression, Compressor},
+};
+
+#[derive(Clone, Debug)]
+pub struct ZipkinRequest {
+    pub body: Bytes,
+    pub finalizers: EventFinalizers,
+    pub events_count: usize,
+    pub events_byte_size: usize,
+}
+
+impl Ackable for ZipkinRequest {
+    fn ack_size(&self) -> usize {
+        self.events_count
+    }
+}
+
+impl Finalizable for ZipkinRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        std::mem::take(&mut self.finalizers)
+    }
+}
+
+#[derive(Debug)]
+pub struct ZipkinResponse {
+    events_count: usize,
+    events_byte_size: usize,
+    byte_size: usize,
+    protocol: &'static str,
+}
+
+impl DriverResponse for ZipkinResponse {
+    fn event_status(&self) -> EventStatus {
+        EventStatus::Delivered
+    }
+
+    fn events_sent(&self) -> EventsSent {
+        EventsSent {
+            count: self.events_count,
+            byte_size: self.events_byte_size,
+            output: None,
+        }
+    }
+
+    fn bytes_sent(&self) -> Option<BytesSent> {
+        Some(BytesSent {
+            byte_size: self.byte_size,
+            protocol: self.protocol,
+        })
+    }
+}
+
+/// Posts the spans to the Zipkin collector.
+#[derive(Clone, Debug)]
+pub struct ZipkinService {
+    client: HttpClient,
+    uri: Uri,
+    auth: Option<Auth>,
+    compression: bool,
+}
+
+impl ZipkinService {
+    pub const fn new(client: HttpClient, uri: Uri, auth: Option<Auth>, compression: bool) -> Self {
+        Self {
+            client,
+            uri,
+            auth,
+            compression,
+        }
+    }
+}
+
+impl Service<ZipkinRequest> for ZipkinService {
+    type Response = ZipkinResponse;
+    type Error = ZipkinSinkError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.client
+            .poll_ready(cx)
+            .map_err(|source| ZipkinSinkError::Http { source })
+    }
+
+    fn call(&mut self, request: ZipkinRequest) -> Self::Future {
+        let client = self.client.clone();
+        let uri = self.uri.clone();
+        let protocol = if uri.scheme() == Some(&Scheme::HTTPS) {
+            "https"
+        } else {
+            "http"
+        };
+        let auth = self.auth.clone();
+        let compression = self.compression;
+
+        Box::pin(async move {
+            let byte_size = request.body.len();
+
+            let mut builder = Request::post(uri).header(CONTENT_TYPE, "application/json");
+            let body = if compression {
+                builder = builder.header(CONTENT_ENCODING, "gzip");
+                let mut compressor = Compressor::from(Compression::gzip_default());
+                compressor
+                    .write_all(&request.body)
+                    .expect("writing to the compressor buffer should not fail");
+                compressor.into_inner().freeze()
+            } else {
+                request.body
+            };
+            if let Some(auth) = auth {
+                builder = auth.apply_builder(builder);
+            }
+            let http_request = builder
+                .body(Body::from(body))
+                .context(BuildRequestSnafu)
+                .context(HttpSnafu)?;
+
+            let response = client.send(http_request).await.context(HttpSnafu)?;
+            let status = response.status();
+            if !status.is_success() {
+                return Err(ZipkinSinkError::HttpStatus { status });
+            }
+
+            Ok(ZipkinResponse {
+                events_count: request.events_count,
+                events_byte_size: request.events_byte_size,
+                byte_size,
+                protocol,
+            })
+        })
+    }
+}
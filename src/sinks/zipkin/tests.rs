@@ -0,0 +1,83 @@
+use std::collections::BTreeMap;
+
+use chrono::{TimeZone, Utc};
+use futures::{stream, StreamExt};
+use vector_core::event::{BatchNotifier, BatchStatus};
+
+use super::ZipkinSinkConfig;
+use crate::{
+    config::{SinkConfig, SinkContext},
+    event::{Event, TraceEvent},
+    sinks::util::test::build_test_server_generic,
+    test_util::{
+        components::{run_and_assert_sink_compliance, HTTP_SINK_TAGS},
+        next_addr,
+    },
+};
+
+#[test]
+fn generate_config() {
+    crate::test_util::test_generate_config::<ZipkinSinkConfig>();
+}
+
+fn trace(span_id: &str) -> TraceEvent {
+    let mut trace = TraceEvent::from(BTreeMap::new());
+    trace.insert("trace_id", "4bf92f3577b34da6a3ce929d0e0e4736");
+    trace.insert("span_id", span_id);
+    trace.insert("name", "GET /orders");
+    trace.insert("resources.\"service.name\"", "orders");
+    trace.insert("start_timestamp", Utc.timestamp(1_654_000_000, 0));
+    trace.insert("end_timestamp", Utc.timestamp(1_654_000_001, 0));
+    trace
+}
+
+#[tokio::test]
+async fn exports_spans() {
+    let address = next_addr();
+    let config =
+        toml::from_str::<ZipkinSinkConfig>(&format!(r#"endpoint = "http://{}""#, address)).unwrap();
+    let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+
+    let (rx, trigger, server) =
+        build_test_server_generic(address, || hyper::Response::new(hyper::Body::empty()));
+    tokio::spawn(server);
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let events = ["00f067aa0ba902b7", "53995c3f42cd8ad8"]
+        .iter()
+        .map(|span_id| Event::from(trace(span_id).with_batch_notifier(&batch)))
+        .collect::<Vec<_>>();
+    drop(batch);
+    run_and_assert_sink_compliance(sink, stream::iter(events), &HTTP_SINK_TAGS).await;
+    drop(trigger);
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    let requests = rx.collect::<Vec<_>>().await;
+    assert_eq!(requests.len(), 1);
+    let (parts, body) = &requests[0];
+    assert_eq!(parts.uri.path(), "/api/v2/spans");
+    assert_eq!(parts.headers["content-type"], "application/json");
+    let spans: Vec<serde_json::Value> = serde_json::from_slice(body).unwrap();
+    let ids = spans.iter().map(|span| &span["id"]).collect::<Vec<_>>();
+    assert_eq!(ids, ["00f067aa0ba902b7", "53995c3f42cd8ad8"]);
+    assert_eq!(spans[0]["localEndpoint"]["serviceName"], "orders");
+    assert_eq!(spans[0]["duration"], 1_000_000);
+}
+
+#[tokio::test]
+async fn rejects_spans_without_ids() {
+    let address = next_addr();
+    let config =
+        toml::from_str::<ZipkinSinkConfig>(&format!(r#"endpoint = "http://{}""#, address)).unwrap();
+    let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let mut trace = trace("00f067aa0ba902b7");
+    trace.insert("trace_id", "not hexadecimal");
+    let events = vec![Event::from(trace.with_batch_notifier(&batch))];
+    drop(batch);
+    sink.run(stream::iter(events).map(Into::into))
+        .await
+        .expect("Running sink failed");
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Rejected));
+}
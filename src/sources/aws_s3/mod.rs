@@ -3,6 +3,7 @@ use std::io::ErrorKind;
 
 use async_compression::tokio::bufread;
 use aws_sdk_s3::types::ByteStream;
+use codecs::decoding::DeserializerConfig;
 use futures::stream;
 use futures::{stream::StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
@@ -18,11 +19,10 @@ use crate::tls::TlsConfig;
 use crate::{
     aws::auth::AwsAuthentication,
     config::{
-        AcknowledgementsConfig, DataType, Output, ProxyConfig, SourceConfig, SourceContext,
-        SourceDescription,
+        AcknowledgementsConfig, Output, ProxyConfig, SourceConfig, SourceContext, SourceDescription,
     },
     line_agg,
-    serde::bool_or_struct,
+    serde::{bool_or_struct, default_decoding},
 };
 
 pub mod sqs;
@@ -46,7 +46,8 @@ enum Strategy {
     Sqs,
 }
 
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Derivative, Clone, Debug, Deserialize, Serialize)]
+#[derivative(Default)]
 #[serde(default, deny_unknown_fields)]
 struct AwsS3Config {
     #[serde(flatten)]
@@ -65,6 +66,10 @@ struct AwsS3Config {
 
     multiline: Option<MultilineConfig>,
 
+    #[serde(default = "default_decoding")]
+    #[derivative(Default(value = "default_decoding()"))]
+    decoding: DeserializerConfig,
+
     #[serde(default, deserialize_with = "bool_or_struct")]
     acknowledgements: AcknowledgementsConfig,
 
@@ -97,7 +102,7 @@ impl SourceConfig for AwsS3Config {
     }
 
     fn outputs(&self) -> Vec<Output> {
-        vec![Output::default(DataType::Log)]
+        vec![Output::default(self.decoding.output_type())]
     }
 
     fn source_type(&self) -> &'static str {
@@ -154,6 +159,7 @@ impl AwsS3Config {
                     sqs.clone(),
                     self.compression,
                     multiline,
+                    &self.decoding,
                 )
                 .await?;
 
@@ -490,10 +496,14 @@ mod integration_tests {
             None,
             None,
             Some(MultilineConfig {
-                start_pattern: "abc".to_owned(),
+                start_pattern: Some("abc".to_owned()),
+                start_condition: None,
                 mode: line_agg::Mode::HaltWith,
-                condition_pattern: "geh".to_owned(),
+                condition_pattern: Some("geh".to_owned()),
+                condition: None,
                 timeout_ms: 1000,
+                max_lines: None,
+                max_bytes: None,
             }),
             logs.join("\n").into_bytes(),
             vec!["abc\ndef\ngeh".to_owned()],
@@ -10,10 +10,11 @@ use aws_smithy_client::SdkError;
 use aws_types::region::Region;
 use bytes::Bytes;
 use chrono::{TimeZone, Utc};
-use codecs::{decoding::FramingError, CharacterDelimitedDecoder};
+use codecs::decoding::{self, format::Deserializer as _, DeserializerConfig, Framer, FramingError};
 use futures::{FutureExt, Stream, StreamExt, TryFutureExt};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use smallvec::SmallVec;
 use snafu::{ResultExt, Snafu};
 use tokio::{pin, select};
 use tokio_util::codec::FramedRead;
@@ -23,12 +24,12 @@ use vector_core::ByteSizeOf;
 use crate::tls::TlsConfig;
 use crate::{
     config::{log_schema, AcknowledgementsConfig, SourceContext},
-    event::{BatchNotifier, BatchStatus, LogEvent},
+    event::{BatchNotifier, BatchStatus, Event},
     internal_events::{
-        BytesReceived, OldEventsReceived, SqsMessageDeleteBatchError, SqsMessageDeletePartialError,
-        SqsMessageDeleteSucceeded, SqsMessageProcessingError, SqsMessageProcessingSucceeded,
-        SqsMessageReceiveError, SqsMessageReceiveSucceeded, SqsS3EventRecordInvalidEventIgnored,
-        StreamClosedError,
+        BytesReceived, DecoderDeserializeFailed, OldEventsReceived, SqsMessageDeleteBatchError,
+        SqsMessageDeletePartialError, SqsMessageDeleteSucceeded, SqsMessageProcessingError,
+        SqsMessageProcessingSucceeded, SqsMessageReceiveError, SqsMessageReceiveSucceeded,
+        SqsS3EventRecordInvalidEventIgnored, StreamClosedError,
     },
     line_agg::{self, LineAgg},
     shutdown::ShutdownSignal,
@@ -149,6 +150,8 @@ pub struct State {
 
     multiline: Option<line_agg::Config>,
     compression: super::Compression,
+    framer: Framer,
+    deserializer: decoding::Deserializer,
 
     queue_url: String,
     poll_secs: i32,
@@ -169,6 +172,7 @@ impl Ingestor {
         config: Config,
         compression: super::Compression,
         multiline: Option<line_agg::Config>,
+        decoding: &DeserializerConfig,
     ) -> Result<Ingestor, IngestorNewError> {
         let state = Arc::new(State {
             region,
@@ -178,6 +182,8 @@ impl Ingestor {
 
             compression,
             multiline,
+            framer: decoding.default_stream_framing().build(),
+            deserializer: decoding.build(),
 
             queue_url: config.queue_url,
             poll_secs: config.poll_secs as i32,
@@ -437,7 +443,7 @@ impl IngestorProcess {
         // the case that the same vector instance processes the same message.
         let mut read_error = None;
         let lines: Box<dyn Stream<Item = Bytes> + Send + Unpin> = Box::new(
-            FramedRead::new(object_reader, CharacterDelimitedDecoder::new(b'\n'))
+            FramedRead::new(object_reader, self.state.framer.clone())
                 .map(|res| {
                     res.map(|bytes| {
                         emit!(BytesReceived {
@@ -470,27 +476,50 @@ impl IngestorProcess {
         let object_key = Bytes::from(s3_event.s3.object.key.as_str().as_bytes().to_vec());
         let aws_region = Bytes::from(s3_event.aws_region.as_str().as_bytes().to_vec());
 
-        let mut stream = lines.filter_map(move |line| {
-            let mut log = LogEvent::from(line).with_batch_notifier_option(&batch);
+        let deserializer = self.state.deserializer.clone();
+        let mut stream = lines.flat_map(move |line| {
+            let events = match deserializer.parse(line) {
+                Ok(events) => events,
+                Err(error) => {
+                    emit!(DecoderDeserializeFailed { error: &error });
+                    SmallVec::new()
+                }
+            };
 
-            log.insert(path!("bucket"), bucket_name.clone());
-            log.insert(path!("object"), object_key.clone());
-            log.insert(path!("region"), aws_region.clone());
-            log.insert(log_schema().source_type_key(), Bytes::from("aws_s3"));
-            log.insert(log_schema().timestamp_key(), timestamp);
+            let events = events.into_iter().map(|event| {
+                // Only logs have room for the location of the object. Metrics and traces, as
+                // decoded by the `native` codecs, are forwarded as is.
+                let event = match event {
+                    Event::Log(log) => {
+                        let mut log = log.with_batch_notifier_option(&batch);
+
+                        log.insert(path!("bucket"), bucket_name.clone());
+                        log.insert(path!("object"), object_key.clone());
+                        log.insert(path!("region"), aws_region.clone());
+                        log.insert(log_schema().source_type_key(), Bytes::from("aws_s3"));
+                        // Events decoded with their own time, such as CloudTrail records, keep it.
+                        log.try_insert(log_schema().timestamp_key(), timestamp);
+
+                        if let Some(metadata) = &metadata {
+                            for (key, value) in metadata {
+                                log.insert(key.as_str(), value.clone());
+                            }
+                        }
 
-            if let Some(metadata) = &metadata {
-                for (key, value) in metadata {
-                    log.insert(key.as_str(), value.clone());
-                }
-            }
+                        Event::Log(log)
+                    }
+                    event => event.with_batch_notifier_option(&batch),
+                };
+
+                emit!(OldEventsReceived {
+                    count: 1,
+                    byte_size: event.size_of()
+                });
 
-            emit!(OldEventsReceived {
-                count: 1,
-                byte_size: log.size_of()
+                event
             });
 
-            ready(Some(log))
+            futures::stream::iter(events.collect::<Vec<_>>())
         });
 
         let send_error = match self.out.send_event_stream(&mut stream).await {
@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::aws::create_client;
 use crate::codecs::DecodingConfig;
-use crate::common::sqs::SqsClientBuilder;
+use crate::common::{s3::S3ClientBuilder, sqs::SqsClientBuilder};
 use crate::tls::TlsConfig;
 use crate::{
     aws::{auth::AwsAuthentication, region::RegionOrEndpoint},
@@ -43,6 +43,9 @@ pub struct AwsSqsConfig {
     #[derivative(Default(value = "default_client_concurrency()"))]
     pub client_concurrency: u32,
 
+    /// Fetches the payloads stored in S3 by the SQS Extended Client Library.
+    pub(super) extended_payloads: Option<ExtendedPayloadsConfig>,
+
     #[serde(default = "default_framing_message_based")]
     #[derivative(Default(value = "default_framing_message_based()"))]
     pub framing: FramingConfig,
@@ -54,6 +57,14 @@ pub struct AwsSqsConfig {
     pub tls: Option<TlsConfig>,
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub(super) struct ExtendedPayloadsConfig {
+    /// Deletes the payloads from S3 along with their messages.
+    #[serde(default)]
+    pub(super) delete_payloads: bool,
+}
+
 #[async_trait::async_trait]
 #[typetag::serde(name = "aws_sqs")]
 impl SourceConfig for AwsSqsConfig {
@@ -61,6 +72,10 @@ impl SourceConfig for AwsSqsConfig {
         let client = self.build_client(&cx).await?;
         let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build();
         let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
+        let s3_client = match self.extended_payloads {
+            Some(_) => Some(self.build_s3_client(&cx).await?),
+            None => None,
+        };
 
         Ok(Box::pin(
             SqsSource {
@@ -72,6 +87,12 @@ impl SourceConfig for AwsSqsConfig {
                 visibility_timeout_secs: self.visibility_timeout_secs,
                 delete_message: self.delete_message,
                 acknowledgements,
+                fifo: self.queue_url.ends_with(".fifo"),
+                s3_client,
+                delete_payloads: self
+                    .extended_payloads
+                    .as_ref()
+                    .map_or(false, |config| config.delete_payloads),
             }
             .run(cx.out, cx.shutdown),
         ))
@@ -102,6 +123,18 @@ impl AwsSqsConfig {
         )
         .await
     }
+
+    async fn build_s3_client(&self, cx: &SourceContext) -> crate::Result<aws_sdk_s3::Client> {
+        create_client::<S3ClientBuilder>(
+            &self.auth,
+            self.region.region(),
+            self.region.endpoint()?,
+            &cx.proxy,
+            &self.tls,
+            false,
+        )
+        .await
+    }
 }
 
 const fn default_poll_secs() -> u32 {
@@ -1,17 +1,25 @@
 use std::{collections::HashMap, panic, str::FromStr, sync::Arc};
 
+use aws_sdk_s3::Client as S3Client;
 use aws_sdk_sqs::{
-    model::{DeleteMessageBatchRequestEntry, MessageSystemAttributeName, QueueAttributeName},
+    model::{
+        DeleteMessageBatchRequestEntry, Message, MessageSystemAttributeName, QueueAttributeName,
+    },
     Client as SqsClient,
 };
+use bytes::Bytes;
 use chrono::{DateTime, TimeZone, Utc};
-use futures::{FutureExt, StreamExt};
+use futures::{future::join_all, FutureExt, StreamExt};
+use serde::Deserialize;
 use tokio::{pin, select, time::Duration};
 
 use crate::{
     codecs::Decoder,
     event::{BatchNotifier, BatchStatus},
-    internal_events::{EndpointBytesReceived, SqsMessageDeleteError, StreamClosedError},
+    internal_events::{
+        EndpointBytesReceived, SqsMessageDeleteError, SqsMessagePayloadFetchError,
+        StreamClosedError,
+    },
     shutdown::ShutdownSignal,
     sources::util::{self, finalizer::UnorderedFinalizer},
     SourceSender,
@@ -20,7 +28,43 @@ use crate::{
 // This is the maximum SQS supports in a single batch request
 const MAX_BATCH_SIZE: i32 = 10;
 
-type Finalizer = UnorderedFinalizer<Vec<String>>;
+// The message attributes set by the SQS Extended Client Library on the messages whose payload is
+// stored in S3, by its current and legacy versions.
+const EXTENDED_PAYLOAD_ATTRIBUTES: [&str; 2] = ["ExtendedPayloadSize", "SQSLargePayloadSize"];
+
+type Finalizer = UnorderedFinalizer<Vec<Receipt>>;
+
+/// The handle to delete a message once processed, along with its payload stored in S3, if any.
+#[derive(Debug)]
+struct Receipt {
+    handle: String,
+    payload: Option<S3Pointer>,
+}
+
+/// The location of a payload stored in S3 by the SQS Extended Client Library.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct S3Pointer {
+    s3_bucket_name: String,
+    s3_key: String,
+}
+
+/// The body of the messages whose payload is stored in S3: the pointer along with the name of its
+/// Java class, or the pointer alone for the legacy versions of the library.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum S3PointerBody {
+    Typed(String, S3Pointer),
+    Plain(S3Pointer),
+}
+
+impl S3Pointer {
+    fn parse(body: &str) -> Option<Self> {
+        match serde_json::from_str(body).ok()? {
+            S3PointerBody::Typed(_, pointer) | S3PointerBody::Plain(pointer) => Some(pointer),
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct SqsSource {
@@ -32,6 +76,11 @@ pub struct SqsSource {
     pub delete_message: bool,
     pub concurrency: u32,
     pub(super) acknowledgements: bool,
+    /// Whether the queue is a FIFO queue, whose message groups are processed in order.
+    pub(super) fifo: bool,
+    /// The client fetching the payloads stored in S3, if they are.
+    pub(super) s3_client: Option<S3Client>,
+    pub(super) delete_payloads: bool,
 }
 
 impl SqsSource {
@@ -40,11 +89,18 @@ impl SqsSource {
         let finalizer = self.acknowledgements.then(|| {
             let (finalizer, mut ack_stream) = Finalizer::new(shutdown.clone());
             let client = self.client.clone();
+            let s3_client = self.payload_cleanup_client();
             let queue_url = self.queue_url.clone();
             tokio::spawn(async move {
                 while let Some((status, receipts)) = ack_stream.next().await {
                     if status == BatchStatus::Delivered {
-                        delete_messages(client.clone(), receipts, queue_url.clone()).await;
+                        delete_messages(
+                            client.clone(),
+                            s3_client.clone(),
+                            receipts,
+                            queue_url.clone(),
+                        )
+                        .await;
                     }
                 }
             });
@@ -80,8 +136,13 @@ impl SqsSource {
         Ok(())
     }
 
+    /// The client deleting the payloads stored in S3 along with their messages, if they are.
+    fn payload_cleanup_client(&self) -> Option<S3Client> {
+        self.s3_client.clone().filter(|_| self.delete_payloads)
+    }
+
     async fn run_once(&self, out: &mut SourceSender, finalizer: Option<&Arc<Finalizer>>) {
-        let result = self
+        let mut request = self
             .client
             .receive_message()
             .queue_url(&self.queue_url)
@@ -90,11 +151,18 @@ impl SqsSource {
             .visibility_timeout(self.visibility_timeout_secs as i32)
             // I think this should be a known attribute
             // https://github.com/awslabs/aws-sdk-rust/issues/411
-            .attribute_names(QueueAttributeName::Unknown(String::from("SentTimestamp")))
-            .send()
-            .await;
+            .attribute_names(QueueAttributeName::Unknown(String::from("SentTimestamp")));
+        if self.fifo {
+            request = request
+                .attribute_names(QueueAttributeName::Unknown(String::from("MessageGroupId")));
+        }
+        if self.s3_client.is_some() {
+            for name in EXTENDED_PAYLOAD_ATTRIBUTES {
+                request = request.message_attribute_names(name);
+            }
+        }
 
-        let receive_message_output = match result {
+        let receive_message_output = match request.send().await {
             Ok(output) => output,
             Err(err) => {
                 error!("SQS receive message error: {:?}.", err);
@@ -115,53 +183,138 @@ impl SqsSource {
                 endpoint: &self.queue_url
             });
 
-            let mut receipts_to_ack = Vec::with_capacity(messages.len());
-            let mut events = Vec::with_capacity(messages.len());
+            join_all(
+                group_messages(messages, self.fifo)
+                    .into_iter()
+                    .map(|messages| {
+                        let mut out = out.clone();
+                        async move { self.process_messages(&mut out, finalizer, messages).await }
+                    }),
+            )
+            .await;
+        }
+    }
 
-            let (batch, batch_receiver) =
-                BatchNotifier::maybe_new_with_receiver(finalizer.is_some());
-            for message in messages {
-                if let Some(body) = message.body {
-                    // a receipt handle should always exist
-                    if let Some(receipt_handle) = message.receipt_handle {
-                        receipts_to_ack.push(receipt_handle);
-                    }
-                    let timestamp = get_timestamp(&message.attributes);
-                    let decoded = util::decode_message(
-                        self.decoder.clone(),
-                        "aws_sqs",
-                        body.as_bytes(),
-                        timestamp,
-                        &batch,
-                    );
-                    events.extend(decoded);
+    /// Processes the messages in order, as a single batch acknowledged as a whole.
+    async fn process_messages(
+        &self,
+        out: &mut SourceSender,
+        finalizer: Option<&Arc<Finalizer>>,
+        messages: Vec<Message>,
+    ) {
+        let mut receipts_to_ack = Vec::with_capacity(messages.len());
+        let mut events = Vec::with_capacity(messages.len());
+
+        let (batch, batch_receiver) = BatchNotifier::maybe_new_with_receiver(finalizer.is_some());
+        for message in messages {
+            let extended = has_extended_payload(&message);
+            if let Some(body) = message.body {
+                let payload = extended
+                    .then(|| S3Pointer::parse(&body))
+                    .flatten()
+                    .filter(|_| self.s3_client.is_some());
+                let body = match &payload {
+                    Some(pointer) => match self.fetch_payload(pointer).await {
+                        Some(body) => body,
+                        // The message is left in the queue to be received again, along with the
+                        // following messages of its group, so that they're still processed in
+                        // order.
+                        None if self.fifo => break,
+                        None => continue,
+                    },
+                    None => Bytes::from(body),
+                };
+                // a receipt handle should always exist
+                if let Some(handle) = message.receipt_handle {
+                    receipts_to_ack.push(Receipt { handle, payload });
                 }
+                let timestamp = get_timestamp(&message.attributes);
+                let decoded =
+                    util::decode_message(self.decoder.clone(), "aws_sqs", &body, timestamp, &batch);
+                events.extend(decoded);
             }
-            drop(batch); // Drop last reference to batch acknowledgement finalizer
-            let count = events.len();
-
-            match out.send_batch(events).await {
-                Ok(()) => {
-                    if self.delete_message {
-                        match batch_receiver {
-                            Some(receiver) => finalizer
-                                .expect("Finalizer must exist for the batch receiver to be created")
-                                .add(receipts_to_ack, receiver),
-                            None => {
-                                delete_messages(
-                                    self.client.clone(),
-                                    receipts_to_ack,
-                                    self.queue_url.clone(),
-                                )
-                                .await
-                            }
+        }
+        drop(batch); // Drop last reference to batch acknowledgement finalizer
+        let count = events.len();
+
+        match out.send_batch(events).await {
+            Ok(()) => {
+                if self.delete_message {
+                    match batch_receiver {
+                        Some(receiver) => finalizer
+                            .expect("Finalizer must exist for the batch receiver to be created")
+                            .add(receipts_to_ack, receiver),
+                        None => {
+                            delete_messages(
+                                self.client.clone(),
+                                self.payload_cleanup_client(),
+                                receipts_to_ack,
+                                self.queue_url.clone(),
+                            )
+                            .await
                         }
                     }
                 }
-                Err(error) => emit!(StreamClosedError { error, count }),
             }
+            Err(error) => emit!(StreamClosedError { error, count }),
+        }
+    }
+
+    async fn fetch_payload(&self, pointer: &S3Pointer) -> Option<Bytes> {
+        let s3_client = self.s3_client.as_ref()?;
+        let result: crate::Result<Bytes> = async {
+            let object = s3_client
+                .get_object()
+                .bucket(&pointer.s3_bucket_name)
+                .key(&pointer.s3_key)
+                .send()
+                .await?;
+            Ok(object.body.collect().await?.into_bytes())
+        }
+        .await;
+        result
+            .map_err(|error| {
+                emit!(SqsMessagePayloadFetchError {
+                    bucket: &pointer.s3_bucket_name,
+                    key: &pointer.s3_key,
+                    error: &error,
+                })
+            })
+            .ok()
+    }
+}
+
+/// Splits the messages received from FIFO queues by message group, keeping the order of the
+/// messages of each group, so that the groups are processed in parallel and the messages of each
+/// group serially. The other queues don't order their messages, which are processed together.
+fn group_messages(messages: Vec<Message>, fifo: bool) -> Vec<Vec<Message>> {
+    if !fifo {
+        return vec![messages];
+    }
+    let mut groups: Vec<(Option<String>, Vec<Message>)> = Vec::new();
+    for message in messages {
+        let group_id = message
+            .attributes
+            .as_ref()
+            .and_then(|attributes| attributes.get(&MessageSystemAttributeName::MessageGroupId))
+            .cloned();
+        match groups.iter_mut().find(|(id, _)| *id == group_id) {
+            Some((_, group)) => group.push(message),
+            None => groups.push((group_id, vec![message])),
         }
     }
+    groups.into_iter().map(|(_, group)| group).collect()
+}
+
+fn has_extended_payload(message: &Message) -> bool {
+    message
+        .message_attributes
+        .as_ref()
+        .map_or(false, |attributes| {
+            EXTENDED_PAYLOAD_ATTRIBUTES
+                .iter()
+                .any(|name| attributes.contains_key(*name))
+        })
 }
 
 fn get_timestamp(
@@ -173,20 +326,44 @@ fn get_timestamp(
     })
 }
 
-async fn delete_messages(client: SqsClient, receipts: Vec<String>, queue_url: String) {
+async fn delete_messages(
+    client: SqsClient,
+    s3_client: Option<S3Client>,
+    receipts: Vec<Receipt>,
+    queue_url: String,
+) {
     if !receipts.is_empty() {
         let mut batch = client.delete_message_batch().queue_url(queue_url);
 
+        let mut payloads = Vec::new();
         for (id, receipt) in receipts.into_iter().enumerate() {
             batch = batch.entries(
                 DeleteMessageBatchRequestEntry::builder()
                     .id(id.to_string())
-                    .receipt_handle(receipt)
+                    .receipt_handle(receipt.handle)
                     .build(),
             );
+            payloads.extend(receipt.payload);
         }
         if let Err(err) = batch.send().await {
             emit!(SqsMessageDeleteError { error: &err });
+            return;
+        }
+
+        // The payloads are only deleted once their messages are, so that the messages are never
+        // left in the queue without their payload.
+        if let Some(s3_client) = s3_client {
+            for payload in payloads {
+                let result = s3_client
+                    .delete_object()
+                    .bucket(payload.s3_bucket_name)
+                    .key(payload.s3_key)
+                    .send()
+                    .await;
+                if let Err(err) = result {
+                    emit!(SqsMessageDeleteError { error: &err });
+                }
+            }
         }
     }
 }
@@ -226,6 +403,60 @@ mod tests {
         );
     }
 
+    fn message(group_id: &str, body: &str) -> Message {
+        Message::builder()
+            .body(body)
+            .attributes(MessageSystemAttributeName::MessageGroupId, group_id)
+            .build()
+    }
+
+    fn bodies(messages: &[Message]) -> Vec<&str> {
+        messages
+            .iter()
+            .map(|message| message.body().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn groups_fifo_messages() {
+        let messages = vec![
+            message("a", "1"),
+            message("b", "2"),
+            message("a", "3"),
+            message("c", "4"),
+            message("b", "5"),
+        ];
+
+        let groups = group_messages(messages.clone(), true);
+        let groups = groups.iter().map(|group| bodies(group)).collect::<Vec<_>>();
+        assert_eq!(groups, vec![vec!["1", "3"], vec!["2", "5"], vec!["4"]]);
+
+        let groups = group_messages(messages, false);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(bodies(&groups[0]), vec!["1", "2", "3", "4", "5"]);
+    }
+
+    #[test]
+    fn parses_s3_pointers() {
+        let pointer = S3Pointer {
+            s3_bucket_name: "bucket".into(),
+            s3_key: "8a9b2cd1-d5e1-4f6b-9b0c-2d6c5e1f4a3b".into(),
+        };
+        assert_eq!(
+            S3Pointer::parse(
+                r#"["software.amazon.payloadoffloading.PayloadS3Pointer",{"s3BucketName":"bucket","s3Key":"8a9b2cd1-d5e1-4f6b-9b0c-2d6c5e1f4a3b"}]"#
+            ),
+            Some(pointer.clone())
+        );
+        assert_eq!(
+            S3Pointer::parse(
+                r#"{"s3BucketName":"bucket","s3Key":"8a9b2cd1-d5e1-4f6b-9b0c-2d6c5e1f4a3b"}"#
+            ),
+            Some(pointer)
+        );
+        assert_eq!(S3Pointer::parse("message"), None);
+    }
+
     #[test]
     fn test_get_timestamp() {
         let attributes = HashMap::from([(
@@ -0,0 +1,358 @@
+//! The checkpoints and the ownership of the partitions are stored as blobs, whose metadata follows
+//! the layout of the checkpoint stores of the Azure SDKs, so that Vector can take over consuming
+//! from the applications built with them.
+
+use std::{collections::HashMap, sync::Arc};
+
+use azure_core::{new_http_client, prelude::*};
+use azure_identity::{AutoRefreshingTokenCredential, DefaultAzureCredential};
+use azure_storage::prelude::*;
+use azure_storage_blobs::prelude::*;
+use bytes::Bytes;
+use chrono::{DateTime, Duration, Utc};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+/// The container the checkpoints are stored in.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct CheckpointStoreConfig {
+    pub(super) connection_string: Option<String>,
+    pub(super) storage_account: Option<String>,
+    pub(super) container_name: String,
+}
+
+impl CheckpointStoreConfig {
+    fn build_client(&self) -> crate::Result<Arc<ContainerClient>> {
+        let client = match (&self.connection_string, &self.storage_account) {
+            (Some(connection_string), None) => {
+                StorageAccountClient::new_connection_string(new_http_client(), connection_string)?
+                    .as_storage_client()
+                    .as_container_client(self.container_name.clone())
+            }
+            (None, Some(storage_account)) => {
+                let creds = Arc::new(DefaultAzureCredential::default());
+                let auto_creds = Box::new(AutoRefreshingTokenCredential::new(creds));
+
+                StorageAccountClient::new_token_credential(
+                    new_http_client(),
+                    storage_account.clone(),
+                    auto_creds,
+                )
+                .as_storage_client()
+                .as_container_client(self.container_name.clone())
+            }
+            (None, None) => {
+                return Err("Either `checkpoint_store.connection_string` or `checkpoint_store.storage_account` has to be provided".into())
+            }
+            (Some(_), Some(_)) => {
+                return Err("`checkpoint_store.connection_string` and `checkpoint_store.storage_account` can't be provided at the same time".into())
+            }
+        };
+        Ok(client)
+    }
+}
+
+/// The position of a consumer group in a partition.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct Checkpoint {
+    pub(super) offset: String,
+    pub(super) sequence_number: i64,
+}
+
+/// The claim of an instance on a partition, which expires unless it's renewed.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct Ownership {
+    pub(super) partition_id: String,
+    pub(super) owner_id: String,
+    pub(super) last_modified: DateTime<Utc>,
+    /// The version of the blob storing the ownership, which is unset when it doesn't exist yet.
+    pub(super) etag: Option<String>,
+}
+
+pub(super) struct CheckpointStore {
+    client: Arc<ContainerClient>,
+    prefix: String,
+}
+
+impl CheckpointStore {
+    pub(super) fn new(
+        config: &CheckpointStoreConfig,
+        namespace: &str,
+        event_hub: &str,
+        consumer_group: &str,
+    ) -> crate::Result<Self> {
+        Ok(Self {
+            client: config.build_client()?,
+            prefix: format!("{}/{}/{}", namespace, event_hub, consumer_group).to_lowercase(),
+        })
+    }
+
+    async fn list(&self, kind: &str) -> crate::Result<Vec<(String, Blob)>> {
+        let prefix = format!("{}/{}/", self.prefix, kind);
+        let response = self
+            .client
+            .list_blobs()
+            .prefix(prefix.clone())
+            .include_metadata(true)
+            .execute()
+            .await?;
+        Ok(response
+            .blobs
+            .blobs
+            .into_iter()
+            .filter_map(|blob| {
+                let partition_id = blob.name.strip_prefix(&prefix)?.to_string();
+                Some((partition_id, blob))
+            })
+            .collect())
+    }
+
+    pub(super) async fn list_checkpoints(&self) -> crate::Result<HashMap<String, Checkpoint>> {
+        Ok(self
+            .list("checkpoint")
+            .await?
+            .into_iter()
+            .filter_map(|(partition_id, blob)| {
+                let metadata = blob.metadata?;
+                let checkpoint = Checkpoint {
+                    offset: metadata.get("offset")?.clone(),
+                    sequence_number: metadata.get("sequencenumber")?.parse().ok()?,
+                };
+                Some((partition_id, checkpoint))
+            })
+            .collect())
+    }
+
+    pub(super) async fn list_ownership(&self) -> crate::Result<Vec<Ownership>> {
+        Ok(self
+            .list("ownership")
+            .await?
+            .into_iter()
+            .map(|(partition_id, blob)| Ownership {
+                partition_id,
+                owner_id: blob
+                    .metadata
+                    .and_then(|metadata| metadata.get("ownerid").cloned())
+                    .unwrap_or_default(),
+                last_modified: blob.properties.last_modified,
+                etag: Some(blob.properties.etag.to_string()),
+            })
+            .collect())
+    }
+
+    /// Claims, or renews, the ownership of the partition, which fails when another instance
+    /// modified it since it was listed.
+    pub(super) async fn claim_ownership(
+        &self,
+        ownership: &Ownership,
+        owner_id: &str,
+    ) -> crate::Result<Ownership> {
+        let mut metadata = Metadata::new();
+        metadata.insert("ownerid", owner_id);
+        let condition = match &ownership.etag {
+            Some(etag) => IfMatchCondition::Match(etag.clone()),
+            None => IfMatchCondition::NotMatch("*".into()),
+        };
+
+        let response = self
+            .client
+            .as_blob_client(format!(
+                "{}/ownership/{}",
+                self.prefix, ownership.partition_id
+            ))
+            .put_block_blob(Bytes::new())
+            .metadata(&metadata)
+            .if_match_condition(condition)
+            .execute()
+            .await?;
+
+        Ok(Ownership {
+            partition_id: ownership.partition_id.clone(),
+            owner_id: owner_id.into(),
+            last_modified: response.last_modified,
+            etag: Some(response.etag.to_string()),
+        })
+    }
+
+    pub(super) async fn update_checkpoint(
+        &self,
+        partition_id: &str,
+        checkpoint: &Checkpoint,
+    ) -> crate::Result<()> {
+        let mut metadata = Metadata::new();
+        metadata.insert("offset", checkpoint.offset.as_str());
+        metadata.insert(
+            "sequencenumber",
+            checkpoint.sequence_number.to_string().as_str(),
+        );
+
+        self.client
+            .as_blob_client(format!("{}/checkpoint/{}", self.prefix, partition_id))
+            .put_block_blob(Bytes::new())
+            .metadata(&metadata)
+            .execute()
+            .await?;
+        Ok(())
+    }
+}
+
+/// Chooses the ownerships the instance claims during a load balancing cycle: the ones it holds
+/// already, which are renewed, and, while it holds less than its share of the partitions, one more
+/// partition, which is either unowned, or taken over from the instance holding the most of them.
+/// Claiming one partition per cycle lets the instances converge on an even distribution without
+/// taking over each other's partitions back and forth.
+pub(super) fn claims(
+    partition_ids: &[String],
+    ownerships: &[Ownership],
+    owner_id: &str,
+    now: DateTime<Utc>,
+    expiration: Duration,
+) -> Vec<Ownership> {
+    let active = ownerships
+        .iter()
+        .filter(|ownership| {
+            !ownership.owner_id.is_empty()
+                && now - ownership.last_modified < expiration
+                && partition_ids.contains(&ownership.partition_id)
+        })
+        .collect::<Vec<_>>();
+
+    let mut owners = HashMap::<&str, Vec<&Ownership>>::new();
+    owners.entry(owner_id).or_default();
+    for ownership in &active {
+        owners
+            .entry(ownership.owner_id.as_str())
+            .or_default()
+            .push(ownership);
+    }
+
+    let minimum = partition_ids.len() / owners.len();
+    let remainder = partition_ids.len() % owners.len();
+    let owned = owners[owner_id].len();
+    let owners_above_minimum = owners
+        .values()
+        .filter(|owned| owned.len() > minimum)
+        .count();
+
+    let mut claims = owners[owner_id]
+        .iter()
+        .map(|&ownership| ownership.clone())
+        .collect::<Vec<_>>();
+
+    let balanced = owned > minimum || (owned == minimum && owners_above_minimum >= remainder);
+    if balanced {
+        return claims;
+    }
+
+    let unowned = partition_ids
+        .iter()
+        .filter(|partition_id| {
+            !active
+                .iter()
+                .any(|ownership| &&ownership.partition_id == partition_id)
+        })
+        .collect::<Vec<_>>();
+    let mut rng = rand::thread_rng();
+    if let Some(partition_id) = unowned.choose(&mut rng) {
+        // The expired ownership is claimed through its blob, which otherwise doesn't exist yet.
+        let etag = ownerships
+            .iter()
+            .find(|ownership| &&ownership.partition_id == partition_id)
+            .and_then(|ownership| ownership.etag.clone());
+        claims.push(Ownership {
+            partition_id: partition_id.to_string(),
+            owner_id: owner_id.into(),
+            last_modified: now,
+            etag,
+        });
+        return claims;
+    }
+
+    let busiest = owners
+        .iter()
+        .filter(|(owner, _)| **owner != owner_id)
+        .max_by_key(|(owner, owned)| (owned.len(), **owner))
+        .map(|(_, owned)| owned);
+    if let Some(busiest) = busiest {
+        if busiest.len() > minimum + 1 || (busiest.len() == minimum + 1 && owned < minimum) {
+            if let Some(ownership) = busiest.choose(&mut rng) {
+                claims.push((*ownership).clone());
+            }
+        }
+    }
+    claims
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn partition_ids(count: usize) -> Vec<String> {
+        (0..count).map(|id| id.to_string()).collect()
+    }
+
+    fn ownership(partition_id: usize, owner_id: &str, age_secs: i64) -> Ownership {
+        Ownership {
+            partition_id: partition_id.to_string(),
+            owner_id: owner_id.into(),
+            last_modified: Utc::now() - Duration::seconds(age_secs),
+            etag: Some(format!("etag-{}", partition_id)),
+        }
+    }
+
+    fn claim(partition_ids: &[String], ownerships: &[Ownership]) -> Vec<Ownership> {
+        claims(
+            partition_ids,
+            ownerships,
+            "self",
+            Utc::now(),
+            Duration::seconds(60),
+        )
+    }
+
+    #[test]
+    fn claims_one_unowned_partition_at_a_time() {
+        let claims = claim(&partition_ids(4), &[]);
+        assert_eq!(claims.len(), 1);
+        assert_eq!(claims[0].owner_id, "self");
+        assert_eq!(claims[0].etag, None);
+    }
+
+    #[test]
+    fn renews_owned_partitions() {
+        let ownerships = vec![ownership(0, "self", 10), ownership(1, "other", 10)];
+        let claims = claim(&partition_ids(2), &ownerships);
+        assert_eq!(claims, vec![ownerships[0].clone()]);
+    }
+
+    #[test]
+    fn claims_expired_partitions_through_their_blob() {
+        let ownerships = vec![ownership(0, "self", 10), ownership(1, "other", 120)];
+        let claims = claim(&partition_ids(2), &ownerships);
+        assert_eq!(claims.len(), 2);
+        assert_eq!(claims[1].partition_id, "1");
+        assert_eq!(claims[1].etag, Some("etag-1".into()));
+    }
+
+    #[test]
+    fn takes_over_partitions_from_the_busiest_owner() {
+        let ownerships = (0..4)
+            .map(|partition_id| ownership(partition_id, "other", 10))
+            .collect::<Vec<_>>();
+        let claims = claim(&partition_ids(4), &ownerships);
+        assert_eq!(claims.len(), 1);
+        assert_eq!(claims[0].owner_id, "other");
+    }
+
+    #[test]
+    fn leaves_balanced_partitions() {
+        let ownerships = vec![
+            ownership(0, "self", 10),
+            ownership(1, "other", 10),
+            ownership(2, "other", 10),
+        ];
+        let claims = claim(&partition_ids(3), &ownerships);
+        assert_eq!(claims, vec![ownerships[0].clone()]);
+    }
+}
@@ -0,0 +1,415 @@
+use std::sync::Arc;
+
+use azure_core::auth::TokenCredential;
+use azure_identity::DefaultAzureCredential;
+use bytes::{Bytes, BytesMut};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use fe2o3_amqp::{
+    connection::ConnectionHandle,
+    sasl_profile::SaslProfile,
+    session::SessionHandle,
+    types::{
+        definitions::SenderSettleMode,
+        messaging::{
+            annotations::OwnedKey, ApplicationProperties, Body, Message, Properties, Source,
+        },
+        primitives::{OrderedMap, SimpleValue, Symbol, Timestamp, Value},
+    },
+    Connection, Delivery, Receiver, Sender, Session,
+};
+use serde_amqp::{described::Described, descriptor::Descriptor};
+
+/// The audience of the Azure AD tokens authenticating with Event Hubs.
+const EVENT_HUBS_RESOURCE: &str = "https://eventhubs.azure.net/";
+const SELECTOR_FILTER: &str = "apache.org:selector-filter:string";
+/// The address the responses of the management and claims-based security nodes are sent to.
+const REPLY_TO: &str = "vector-reply";
+
+/// How Vector authenticates with the namespace.
+pub(super) enum Credentials {
+    SharedAccessKey {
+        key_name: String,
+        key: String,
+    },
+    /// Authenticates with an Azure AD token, obtained from a managed identity, the environment, or
+    /// the Azure CLI.
+    AzureIdentity(Arc<DefaultAzureCredential>),
+}
+
+/// The location of the event hub, along with the credentials authenticating with it.
+pub(super) struct EventHubsEndpoint {
+    /// The fully qualified name of the namespace, such as `example.servicebus.windows.net`.
+    pub(super) namespace: String,
+    pub(super) event_hub: String,
+    pub(super) credentials: Credentials,
+}
+
+/// The fields of a connection string, such as the ones listed in the Azure portal.
+#[derive(Debug, Default, PartialEq)]
+pub(super) struct ConnectionString {
+    pub(super) namespace: String,
+    pub(super) key_name: String,
+    pub(super) key: String,
+    pub(super) entity_path: Option<String>,
+}
+
+impl std::str::FromStr for ConnectionString {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut connection_string = ConnectionString::default();
+        for pair in s.split(';').filter(|pair| !pair.trim().is_empty()) {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or("Connection string pairs must be separated by `=`.")?;
+            match key.trim() {
+                "Endpoint" => {
+                    connection_string.namespace = value
+                        .trim()
+                        .trim_start_matches("sb://")
+                        .trim_end_matches('/')
+                        .into();
+                }
+                "SharedAccessKeyName" => connection_string.key_name = value.trim().into(),
+                "SharedAccessKey" => connection_string.key = value.trim().into(),
+                "EntityPath" => connection_string.entity_path = Some(value.trim().into()),
+                _ => {}
+            }
+        }
+
+        if connection_string.namespace.is_empty() {
+            return Err("Connection string is missing `Endpoint`.".into());
+        }
+        if connection_string.key_name.is_empty() || connection_string.key.is_empty() {
+            return Err(
+                "Connection string is missing `SharedAccessKeyName` or `SharedAccessKey`.".into(),
+            );
+        }
+        Ok(connection_string)
+    }
+}
+
+/// Where a partition is consumed from.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum EventPosition {
+    Earliest,
+    Latest,
+    /// After the event with the offset, such as the one of a checkpoint.
+    Offset(String),
+}
+
+impl EventPosition {
+    fn selector(&self) -> String {
+        let offset = match self {
+            EventPosition::Earliest => "-1",
+            EventPosition::Latest => "@latest",
+            EventPosition::Offset(offset) => offset,
+        };
+        format!("amqp.annotation.x-opt-offset > '{}'", offset)
+    }
+}
+
+/// An event of a partition.
+#[derive(Debug, Default)]
+pub(super) struct EventData {
+    pub(super) body: Bytes,
+    pub(super) offset: Option<String>,
+    pub(super) sequence_number: Option<i64>,
+    pub(super) enqueued_time: Option<DateTime<Utc>>,
+    pub(super) partition_key: Option<String>,
+}
+
+impl From<&Message<Body<Value>>> for EventData {
+    fn from(message: &Message<Body<Value>>) -> Self {
+        let body = match &message.body {
+            Body::Data(batch) => {
+                let mut body = BytesMut::new();
+                for data in batch.iter() {
+                    body.extend_from_slice(&data.0);
+                }
+                body.freeze()
+            }
+            Body::Value(value) => match &value.0 {
+                Value::String(string) => Bytes::from(string.clone()),
+                Value::Binary(binary) => Bytes::from(binary.to_vec()),
+                _ => Bytes::new(),
+            },
+            _ => Bytes::new(),
+        };
+
+        let annotation = |name: &str| {
+            message
+                .message_annotations
+                .as_ref()?
+                .0
+                .iter()
+                .find_map(|(key, value)| match key {
+                    OwnedKey::Symbol(symbol) if symbol.as_str() == name => Some(value),
+                    _ => None,
+                })
+        };
+
+        EventData {
+            body,
+            offset: match annotation("x-opt-offset") {
+                Some(Value::String(offset)) => Some(offset.clone()),
+                _ => None,
+            },
+            sequence_number: match annotation("x-opt-sequence-number") {
+                Some(Value::Long(sequence_number)) => Some(*sequence_number),
+                _ => None,
+            },
+            enqueued_time: match annotation("x-opt-enqueued-time") {
+                Some(Value::Timestamp(timestamp)) => {
+                    Some(Utc.timestamp_millis(timestamp.milliseconds()))
+                }
+                _ => None,
+            },
+            partition_key: match annotation("x-opt-partition-key") {
+                Some(Value::String(partition_key)) => Some(partition_key.clone()),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// A connection to the namespace, whose session the receivers of the partitions are attached to.
+pub(super) struct EventHubsClient {
+    connection: ConnectionHandle<()>,
+    session: SessionHandle<()>,
+    endpoint: Arc<EventHubsEndpoint>,
+    /// When the Azure AD token authenticating the connection expires.
+    token_expires_on: Option<DateTime<Utc>>,
+}
+
+impl EventHubsClient {
+    pub(super) async fn connect(endpoint: Arc<EventHubsEndpoint>) -> crate::Result<Self> {
+        let url = format!("amqps://{}:5671", endpoint.namespace);
+        // The connection is authenticated with a token sent to the claims-based security node
+        // once it's opened, rather than with SASL, when authenticating with Azure AD.
+        let sasl_profile = match &endpoint.credentials {
+            Credentials::SharedAccessKey { key_name, key } => SaslProfile::Plain {
+                username: key_name.clone(),
+                password: key.clone(),
+            },
+            Credentials::AzureIdentity(_) => SaslProfile::Anonymous,
+        };
+        let mut connection = Connection::builder()
+            .container_id(format!("vector-{}", uuid::Uuid::new_v4()))
+            .hostname(endpoint.namespace.as_str())
+            .sasl_profile(sasl_profile)
+            .open(url.as_str())
+            .await?;
+        let session = Session::begin(&mut connection).await?;
+
+        let mut client = Self {
+            connection,
+            session,
+            endpoint,
+            token_expires_on: None,
+        };
+        client.refresh_token().await?;
+        Ok(client)
+    }
+
+    /// Sends a new Azure AD token to the claims-based security node, when authenticating with
+    /// Azure AD, once the one authenticating the connection is about to expire.
+    pub(super) async fn refresh_token(&mut self) -> crate::Result<()> {
+        let credential = match &self.endpoint.credentials {
+            Credentials::AzureIdentity(credential) => Arc::clone(credential),
+            Credentials::SharedAccessKey { .. } => return Ok(()),
+        };
+        if matches!(self.token_expires_on, Some(expires_on) if expires_on - Utc::now() > Duration::minutes(10))
+        {
+            return Ok(());
+        }
+
+        let token = credential.get_token(EVENT_HUBS_RESOURCE).await?;
+        let audience = format!(
+            "amqp://{}/{}",
+            self.endpoint.namespace, self.endpoint.event_hub
+        );
+        self.request(
+            "$cbs",
+            ApplicationProperties::builder()
+                .insert("operation", "put-token")
+                .insert("type", "jwt")
+                .insert("name", audience)
+                .insert(
+                    "expiration",
+                    Timestamp::from_milliseconds(token.expires_on.timestamp_millis()),
+                )
+                .build(),
+            Value::String(token.token.secret().into()),
+        )
+        .await?;
+
+        self.token_expires_on = Some(token.expires_on);
+        Ok(())
+    }
+
+    /// Reads the identifiers of the partitions of the event hub from its management node.
+    pub(super) async fn partition_ids(&mut self) -> crate::Result<Vec<String>> {
+        let properties = ApplicationProperties::builder()
+            .insert("operation", "READ")
+            .insert("type", "com.microsoft:eventhub")
+            .insert("name", self.endpoint.event_hub.as_str())
+            .build();
+        let response = self.request("$management", properties, Value::Null).await?;
+        partition_ids(&response)
+    }
+
+    /// Sends a request to the node, and returns the body of its response once the node
+    /// acknowledged it with a successful status code.
+    async fn request(
+        &mut self,
+        node: &str,
+        properties: ApplicationProperties,
+        body: Value,
+    ) -> crate::Result<Value> {
+        let mut sender = Sender::attach(
+            &mut self.session,
+            format!("vector-request-{}", uuid::Uuid::new_v4()),
+            node,
+        )
+        .await?;
+        let mut receiver = Receiver::builder()
+            .name(format!("vector-reply-{}", uuid::Uuid::new_v4()))
+            .source(node)
+            .target(REPLY_TO)
+            .attach(&mut self.session)
+            .await?;
+
+        let request = Message::builder()
+            .properties(
+                Properties::builder()
+                    .message_id(uuid::Uuid::new_v4().to_string())
+                    .reply_to(REPLY_TO)
+                    .build(),
+            )
+            .application_properties(properties)
+            .value(body)
+            .build();
+        sender.send(request).await?;
+        let response: Delivery<Value> = receiver.recv().await?;
+        receiver.accept(&response).await?;
+        let _ = sender.close().await;
+        let _ = receiver.close().await;
+
+        let properties = response.message().application_properties.as_ref();
+        let property = |name: &str| properties.and_then(|properties| properties.0.get(name));
+        let status = property("status-code");
+        if !matches!(
+            status,
+            Some(SimpleValue::Int(200..=299) | SimpleValue::UInt(200..=299))
+        ) {
+            let description = match property("status-description") {
+                Some(SimpleValue::String(description)) => description.as_str(),
+                _ => "no description",
+            };
+            return Err(format!(
+                "The {} node rejected the request with status {:?}: {}",
+                node, status, description
+            )
+            .into());
+        }
+        Ok(response.into_body())
+    }
+
+    /// Attaches a receiver consuming the partition from the position.
+    pub(super) async fn open_receiver(
+        &mut self,
+        consumer_group: &str,
+        partition_id: &str,
+        position: &EventPosition,
+    ) -> crate::Result<Receiver> {
+        let address = format!(
+            "{}/ConsumerGroups/{}/Partitions/{}",
+            self.endpoint.event_hub, consumer_group, partition_id
+        );
+        let source = Source::builder()
+            .address(address)
+            .add_to_filter(
+                Symbol::from(SELECTOR_FILTER),
+                Described {
+                    descriptor: Descriptor::Name(Symbol::from(SELECTOR_FILTER)),
+                    value: Value::String(position.selector()),
+                },
+            )
+            .build();
+
+        let receiver = Receiver::builder()
+            .name(format!("vector-{}-{}", partition_id, uuid::Uuid::new_v4()))
+            .source(source)
+            .target("vector")
+            // The events are settled as they're sent, as the checkpoints keep track of the consumed
+            // ones instead.
+            .sender_settle_mode(SenderSettleMode::Settled)
+            .attach(&mut self.session)
+            .await?;
+        Ok(receiver)
+    }
+
+    pub(super) async fn close(mut self) {
+        let _ = self.session.end().await;
+        let _ = self.connection.close().await;
+    }
+}
+
+fn partition_ids(body: &Value) -> crate::Result<Vec<String>> {
+    let map: &OrderedMap<Value, Value> = match body {
+        Value::Map(map) => map,
+        _ => return Err("The event hub description isn't a map.".into()),
+    };
+    match map.get(&Value::String("partition_ids".into())) {
+        Some(Value::Array(ids)) => Ok(ids
+            .iter()
+            .filter_map(|id| match id {
+                Value::String(id) => Some(id.clone()),
+                _ => None,
+            })
+            .collect()),
+        _ => Err("The event hub description is missing `partition_ids`.".into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_connection_strings() {
+        let connection_string: ConnectionString = "Endpoint=sb://example.servicebus.windows.net/;SharedAccessKeyName=vector;SharedAccessKey=c2VjcmV0;EntityPath=logs"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            connection_string,
+            ConnectionString {
+                namespace: "example.servicebus.windows.net".into(),
+                key_name: "vector".into(),
+                key: "c2VjcmV0".into(),
+                entity_path: Some("logs".into()),
+            }
+        );
+
+        assert!("Endpoint=sb://example.servicebus.windows.net/"
+            .parse::<ConnectionString>()
+            .is_err());
+    }
+
+    #[test]
+    fn filters_partitions_from_positions() {
+        assert_eq!(
+            EventPosition::Earliest.selector(),
+            "amqp.annotation.x-opt-offset > '-1'"
+        );
+        assert_eq!(
+            EventPosition::Latest.selector(),
+            "amqp.annotation.x-opt-offset > '@latest'"
+        );
+        assert_eq!(
+            EventPosition::Offset("4096".into()).selector(),
+            "amqp.annotation.x-opt-offset > '4096'"
+        );
+    }
+}
@@ -0,0 +1,593 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+
+use azure_identity::DefaultAzureCredential;
+use bytes::Bytes;
+use chrono::Utc;
+use codecs::decoding::{DeserializerConfig, FramingConfig, StreamDecodingError};
+use fe2o3_amqp::{
+    types::{messaging::Body, primitives::Value},
+    Delivery, Receiver,
+};
+use futures::{stream::BoxStream, StreamExt};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use tokio::time::sleep;
+use tokio_stream::StreamMap;
+use tokio_util::codec::FramedRead;
+use vector_core::ByteSizeOf;
+
+use self::{
+    checkpoint::{Checkpoint, CheckpointStore, CheckpointStoreConfig, Ownership},
+    client::{
+        ConnectionString, Credentials, EventData, EventHubsClient, EventHubsEndpoint, EventPosition,
+    },
+};
+use super::util::finalizer::OrderedFinalizer;
+use crate::{
+    codecs::{Decoder, DecodingConfig},
+    config::{
+        log_schema, AcknowledgementsConfig, GenerateConfig, Output, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::{BatchNotifier, BatchStatus, Event},
+    internal_events::{
+        AzureEventHubsCheckpointError, AzureEventHubsConnectionError, AzureEventHubsEventsReceived,
+        AzureEventHubsLoadBalancingError, BytesReceived, StreamClosedError,
+    },
+    serde::{bool_or_struct, default_decoding, default_framing_message_based},
+    shutdown::ShutdownSignal,
+    sinks::util::retries::ExponentialBackoff,
+    SourceSender,
+};
+
+mod checkpoint;
+mod client;
+
+#[derive(Clone, Debug, Derivative, Deserialize, Serialize)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct AzureEventHubsSourceConfig {
+    /// A connection string of the namespace, or of the event hub, authenticating with a shared
+    /// access key.
+    connection_string: Option<String>,
+    /// The fully qualified name of the namespace, which is authenticated with through Azure AD,
+    /// such as with a managed identity, when there is no connection string.
+    namespace: Option<String>,
+    /// The name of the event hub, which is read from the connection string when unset.
+    event_hub_name: Option<String>,
+    #[serde(default = "default_consumer_group")]
+    #[derivative(Default(value = "default_consumer_group()"))]
+    consumer_group: String,
+    /// Where the partitions without a checkpoint are consumed from.
+    #[serde(default)]
+    start_position: StartPosition,
+    checkpoint_store: CheckpointStoreConfig,
+    /// How often the checkpoints are written, and the ownership of the partitions renewed and
+    /// balanced among the instances of Vector consuming the event hub.
+    #[serde(default = "default_load_balancing_interval_secs")]
+    #[derivative(Default(value = "default_load_balancing_interval_secs()"))]
+    load_balancing_interval_secs: u64,
+    /// How long the ownership of a partition lasts without being renewed, after which the other
+    /// instances take the partition over.
+    #[serde(default = "default_ownership_expiration_secs")]
+    #[derivative(Default(value = "default_ownership_expiration_secs()"))]
+    ownership_expiration_secs: u64,
+    #[serde(default = "default_framing_message_based")]
+    #[derivative(Default(value = "default_framing_message_based()"))]
+    framing: FramingConfig,
+    #[serde(default = "default_decoding")]
+    #[derivative(Default(value = "default_decoding()"))]
+    decoding: DeserializerConfig,
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+enum StartPosition {
+    /// The oldest events retained by the event hub.
+    Earliest,
+    /// The events enqueued once the partition is consumed.
+    #[derivative(Default)]
+    Latest,
+}
+
+impl From<StartPosition> for EventPosition {
+    fn from(position: StartPosition) -> Self {
+        match position {
+            StartPosition::Earliest => EventPosition::Earliest,
+            StartPosition::Latest => EventPosition::Latest,
+        }
+    }
+}
+
+fn default_consumer_group() -> String {
+    "$Default".into()
+}
+
+const fn default_load_balancing_interval_secs() -> u64 {
+    10
+}
+
+const fn default_ownership_expiration_secs() -> u64 {
+    60
+}
+
+inventory::submit! {
+    SourceDescription::new::<AzureEventHubsSourceConfig>("azure_event_hubs")
+}
+
+impl GenerateConfig for AzureEventHubsSourceConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"
+            connection_string = "Endpoint=sb://example.servicebus.windows.net/;SharedAccessKeyName=vector;SharedAccessKey=c2VjcmV0;EntityPath=logs"
+            checkpoint_store.connection_string = "DefaultEndpointsProtocol=https;AccountName=vector;AccountKey=c2VjcmV0;EndpointSuffix=core.windows.net"
+            checkpoint_store.container_name = "checkpoints"
+            "#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "azure_event_hubs")]
+impl SourceConfig for AzureEventHubsSourceConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let endpoint = Arc::new(self.endpoint()?);
+        let checkpoints = CheckpointStore::new(
+            &self.checkpoint_store,
+            &endpoint.namespace,
+            &endpoint.event_hub,
+            &self.consumer_group,
+        )?;
+        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build();
+        let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
+
+        Ok(Box::pin(event_hubs_source(
+            self.clone(),
+            endpoint,
+            checkpoints,
+            decoder,
+            cx.shutdown,
+            cx.out,
+            acknowledgements,
+        )))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(self.decoding.output_type())]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "azure_event_hubs"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+impl AzureEventHubsSourceConfig {
+    fn endpoint(&self) -> crate::Result<EventHubsEndpoint> {
+        let (namespace, entity_path, credentials) = match (&self.connection_string, &self.namespace)
+        {
+            (Some(connection_string), None) => {
+                let connection_string: ConnectionString = connection_string.parse()?;
+                (
+                    connection_string.namespace,
+                    connection_string.entity_path,
+                    Credentials::SharedAccessKey {
+                        key_name: connection_string.key_name,
+                        key: connection_string.key,
+                    },
+                )
+            }
+            (None, Some(namespace)) => (
+                namespace.clone(),
+                None,
+                Credentials::AzureIdentity(Arc::new(DefaultAzureCredential::default())),
+            ),
+            (None, None) => {
+                return Err("Either `connection_string` or `namespace` has to be provided".into())
+            }
+            (Some(_), Some(_)) => {
+                return Err(
+                    "`connection_string` and `namespace` can't be provided at the same time".into(),
+                )
+            }
+        };
+
+        let event_hub = match (&self.event_hub_name, entity_path) {
+            (Some(name), Some(path)) if name != &path => {
+                return Err(format!(
+                    "`event_hub_name` doesn't match the event hub of the connection string: {}",
+                    path
+                )
+                .into())
+            }
+            (Some(name), _) => name.clone(),
+            (None, Some(path)) => path,
+            (None, None) => return Err("`event_hub_name` has to be provided".into()),
+        };
+
+        Ok(EventHubsEndpoint {
+            namespace,
+            event_hub,
+            credentials,
+        })
+    }
+}
+
+const fn fresh_backoff() -> ExponentialBackoff {
+    ExponentialBackoff::from_millis(2)
+        .factor(250)
+        .max_delay(Duration::from_secs(60))
+}
+
+#[derive(Debug)]
+struct FinalizerEntry {
+    partition_id: String,
+    checkpoint: Checkpoint,
+}
+
+type Finalizer = OrderedFinalizer<FinalizerEntry>;
+
+type PartitionStream = BoxStream<'static, crate::Result<Delivery<Body<Value>>>>;
+
+#[derive(Debug, Snafu)]
+enum BalanceError {
+    #[snafu(display("Checkpoint store error: {}", source))]
+    Store { source: crate::Error },
+    #[snafu(display("Event hub error: {}", source))]
+    Client { source: crate::Error },
+}
+
+async fn event_hubs_source(
+    config: AzureEventHubsSourceConfig,
+    endpoint: Arc<EventHubsEndpoint>,
+    checkpoints: CheckpointStore,
+    decoder: Decoder,
+    mut shutdown: ShutdownSignal,
+    mut out: SourceSender,
+    acknowledgements: bool,
+) -> Result<(), ()> {
+    let (finalizer, mut ack_stream) = Finalizer::maybe_new(acknowledgements, shutdown.clone());
+    // Identifies the instance among the ones sharing the partitions.
+    let owner_id = uuid::Uuid::new_v4().to_string();
+    // The latest checkpoint of each partition, which are written on every load balancing cycle.
+    let mut pending_checkpoints = HashMap::new();
+    let mut backoff = fresh_backoff();
+
+    loop {
+        let connected = async {
+            let mut client = EventHubsClient::connect(Arc::clone(&endpoint)).await?;
+            let partition_ids = client.partition_ids().await?;
+            Ok::<_, crate::Error>((client, partition_ids))
+        };
+        let (mut client, partition_ids) = match connected.await {
+            Ok(connected) => connected,
+            Err(error) => {
+                emit!(AzureEventHubsConnectionError { error });
+                tokio::select! {
+                    _ = &mut shutdown => break,
+                    _ = sleep(backoff.next().unwrap()) => continue,
+                }
+            }
+        };
+        backoff = fresh_backoff();
+
+        let mut partitions = StreamMap::<String, PartitionStream>::new();
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(config.load_balancing_interval_secs));
+
+        let reconnect = loop {
+            tokio::select! {
+                _ = &mut shutdown => break false,
+                _ = interval.tick() => {
+                    write_checkpoints(&checkpoints, &mut pending_checkpoints, &partitions).await;
+                    if let Err(error) = client.refresh_token().await {
+                        emit!(AzureEventHubsConnectionError { error });
+                        break true;
+                    }
+                    match balance(&config, &mut client, &checkpoints, &partition_ids, &owner_id, &mut partitions).await {
+                        Ok(()) => {}
+                        Err(error @ BalanceError::Store { .. }) => {
+                            emit!(AzureEventHubsLoadBalancingError { error });
+                        }
+                        Err(BalanceError::Client { source: error }) => {
+                            emit!(AzureEventHubsConnectionError { error });
+                            break true;
+                        }
+                    }
+                },
+                entry = ack_stream.next() => if let Some((status, entry)) = entry {
+                    if status == BatchStatus::Delivered {
+                        pending_checkpoints.insert(entry.partition_id, entry.checkpoint);
+                    }
+                },
+                delivery = partitions.next(), if !partitions.is_empty() => match delivery {
+                    None => {}
+                    Some((_, Err(error))) => {
+                        emit!(AzureEventHubsConnectionError { error });
+                        break true;
+                    }
+                    Some((partition_id, Ok(delivery))) => {
+                        let data = EventData::from(delivery.message());
+                        emit!(BytesReceived {
+                            byte_size: data.body.len(),
+                            protocol: "amqp",
+                        });
+
+                        let events = parse_event(&data, &partition_id, decoder.clone()).await;
+                        let count = events.len();
+                        let checkpoint = data.offset.zip(data.sequence_number).map(
+                            |(offset, sequence_number)| Checkpoint {
+                                offset,
+                                sequence_number,
+                            },
+                        );
+
+                        match (&finalizer, checkpoint) {
+                            (Some(finalizer), Some(checkpoint)) => {
+                                let (batch, receiver) = BatchNotifier::new_with_receiver();
+                                let events = events
+                                    .into_iter()
+                                    .map(|event| event.with_batch_notifier(&batch));
+                                if let Err(error) = out.send_batch(events).await {
+                                    emit!(StreamClosedError { error, count });
+                                    return Err(());
+                                }
+                                finalizer.add(FinalizerEntry { partition_id, checkpoint }, receiver);
+                            }
+                            (_, checkpoint) => {
+                                if let Err(error) = out.send_batch(events).await {
+                                    emit!(StreamClosedError { error, count });
+                                    return Err(());
+                                }
+                                if let Some(checkpoint) = checkpoint {
+                                    pending_checkpoints.insert(partition_id, checkpoint);
+                                }
+                            }
+                        }
+                    }
+                },
+            }
+        };
+
+        write_checkpoints(&checkpoints, &mut pending_checkpoints, &partitions).await;
+        client.close().await;
+        if !reconnect {
+            break;
+        }
+        tokio::select! {
+            _ = &mut shutdown => break,
+            _ = sleep(backoff.next().unwrap()) => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the checkpoints of the partitions still owned by the instance, as the ones of the
+/// partitions taken over by other instances could overwrite more recent checkpoints of theirs.
+async fn write_checkpoints(
+    checkpoints: &CheckpointStore,
+    pending_checkpoints: &mut HashMap<String, Checkpoint>,
+    partitions: &StreamMap<String, PartitionStream>,
+) {
+    for (partition_id, checkpoint) in pending_checkpoints.drain() {
+        if !partitions.contains_key(&partition_id) {
+            continue;
+        }
+        if let Err(error) = checkpoints
+            .update_checkpoint(&partition_id, &checkpoint)
+            .await
+        {
+            emit!(AzureEventHubsCheckpointError {
+                error,
+                partition_id: &partition_id,
+            });
+        }
+    }
+}
+
+/// Renews and claims the ownership of the partitions, then starts consuming the partitions the
+/// instance gained, from their checkpoint, and stops consuming the ones it lost.
+async fn balance(
+    config: &AzureEventHubsSourceConfig,
+    client: &mut EventHubsClient,
+    checkpoints: &CheckpointStore,
+    partition_ids: &[String],
+    owner_id: &str,
+    partitions: &mut StreamMap<String, PartitionStream>,
+) -> Result<(), BalanceError> {
+    let ownerships = checkpoints
+        .list_ownership()
+        .await
+        .map_err(|source| BalanceError::Store { source })?;
+    let claims = checkpoint::claims(
+        partition_ids,
+        &ownerships,
+        owner_id,
+        Utc::now(),
+        chrono::Duration::seconds(config.ownership_expiration_secs as i64),
+    );
+
+    let mut owned = HashSet::new();
+    for claim in claims {
+        // Claiming fails when another instance claimed the partition first.
+        match checkpoints.claim_ownership(&claim, owner_id).await {
+            Ok(Ownership { partition_id, .. }) => {
+                owned.insert(partition_id);
+            }
+            Err(error) => {
+                debug!(message = "Failed to claim partition.", partition_id = %claim.partition_id, %error);
+            }
+        }
+    }
+
+    let lost = partitions
+        .keys()
+        .filter(|partition_id| !owned.contains(*partition_id))
+        .cloned()
+        .collect::<Vec<_>>();
+    for partition_id in lost {
+        debug!(message = "Stopped consuming partition.", %partition_id);
+        partitions.remove(&partition_id);
+    }
+
+    let gained = owned
+        .into_iter()
+        .filter(|partition_id| !partitions.contains_key(partition_id))
+        .collect::<Vec<_>>();
+    if gained.is_empty() {
+        return Ok(());
+    }
+
+    let stored = checkpoints
+        .list_checkpoints()
+        .await
+        .map_err(|source| BalanceError::Store { source })?;
+    for partition_id in gained {
+        let position = stored
+            .get(&partition_id)
+            .map(|checkpoint| EventPosition::Offset(checkpoint.offset.clone()))
+            .unwrap_or_else(|| config.start_position.into());
+        let receiver = client
+            .open_receiver(&config.consumer_group, &partition_id, &position)
+            .await
+            .map_err(|source| BalanceError::Client { source })?;
+        debug!(message = "Started consuming partition.", %partition_id, ?position);
+        partitions.insert(partition_id, receiver_stream(receiver));
+    }
+    Ok(())
+}
+
+fn receiver_stream(receiver: Receiver) -> PartitionStream {
+    futures::stream::unfold(receiver, |mut receiver| async move {
+        let delivery = receiver.recv::<Body<Value>>().await.map_err(Into::into);
+        Some((delivery, receiver))
+    })
+    .boxed()
+}
+
+async fn parse_event(data: &EventData, partition_id: &str, decoder: Decoder) -> Vec<Event> {
+    let timestamp = data.enqueued_time.unwrap_or_else(Utc::now);
+    let mut stream = FramedRead::new(data.body.as_ref(), decoder);
+    let mut output = Vec::new();
+    while let Some(result) = stream.next().await {
+        match result {
+            Ok((events, _byte_size)) => {
+                emit!(AzureEventHubsEventsReceived {
+                    count: events.len(),
+                    byte_size: events.size_of(),
+                    partition_id,
+                });
+                output.extend(events.into_iter().map(|mut event| {
+                    if let Event::Log(ref mut log) = event {
+                        log.insert(
+                            log_schema().source_type_key(),
+                            Bytes::from("azure_event_hubs"),
+                        );
+                        log.insert(log_schema().timestamp_key(), timestamp);
+                        log.insert("partition_id", partition_id.to_string());
+                        if let Some(offset) = &data.offset {
+                            log.insert("offset", offset.clone());
+                        }
+                        if let Some(sequence_number) = data.sequence_number {
+                            log.insert("sequence_number", sequence_number);
+                        }
+                        if let Some(partition_key) = &data.partition_key {
+                            log.insert("partition_key", partition_key.clone());
+                        }
+                    }
+                    event
+                }));
+            }
+            Err(error) => {
+                // Error is logged by `crate::codecs`, no further handling is needed here.
+                if !error.can_continue() {
+                    break;
+                }
+            }
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<AzureEventHubsSourceConfig>();
+    }
+
+    #[test]
+    fn reads_event_hub_from_connection_string() {
+        let config: AzureEventHubsSourceConfig = toml::from_str(
+            r#"
+            connection_string = "Endpoint=sb://example.servicebus.windows.net/;SharedAccessKeyName=vector;SharedAccessKey=c2VjcmV0;EntityPath=logs"
+            checkpoint_store.storage_account = "vector"
+            checkpoint_store.container_name = "checkpoints"
+            "#,
+        )
+        .unwrap();
+        let endpoint = config.endpoint().unwrap();
+        assert_eq!(endpoint.namespace, "example.servicebus.windows.net");
+        assert_eq!(endpoint.event_hub, "logs");
+        assert!(matches!(
+            endpoint.credentials,
+            Credentials::SharedAccessKey { .. }
+        ));
+        assert_eq!(config.consumer_group, "$Default");
+        assert_eq!(config.start_position, StartPosition::Latest);
+    }
+
+    #[test]
+    fn requires_event_hub_name_with_namespace() {
+        let mut config = AzureEventHubsSourceConfig {
+            namespace: Some("example.servicebus.windows.net".into()),
+            ..Default::default()
+        };
+        assert!(config.endpoint().is_err());
+
+        config.event_hub_name = Some("logs".into());
+        let endpoint = config.endpoint().unwrap();
+        assert!(matches!(
+            endpoint.credentials,
+            Credentials::AzureIdentity(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn inserts_event_metadata() {
+        let config = AzureEventHubsSourceConfig::default();
+        let decoder = DecodingConfig::new(config.framing, config.decoding).build();
+        let data = EventData {
+            body: Bytes::from("hello"),
+            offset: Some("4096".into()),
+            sequence_number: Some(42),
+            enqueued_time: Some(Utc::now()),
+            partition_key: None,
+        };
+
+        let events = parse_event(&data, "3", decoder).await;
+        let log = events[0].as_log();
+        assert_eq!(log[log_schema().message_key()], "hello".into());
+        assert_eq!(log["partition_id"], "3".into());
+        assert_eq!(log["offset"], "4096".into());
+        assert_eq!(log["sequence_number"], 42.into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            data.enqueued_time.unwrap().into()
+        );
+    }
+}
@@ -167,6 +167,8 @@ impl SourceConfig for DatadogAgentConfig {
 
             DeserializerConfig::Native => self.decoding.schema_definition(),
             DeserializerConfig::NativeJson => self.decoding.schema_definition(),
+            DeserializerConfig::AwsCloudtrail => self.decoding.schema_definition(),
+            DeserializerConfig::AwsS3AccessLog => self.decoding.schema_definition(),
         };
 
         if self.multiple_outputs {
@@ -1657,10 +1657,14 @@ mod integration_tests {
             include_containers: Some(vec![name.to_owned()]),
             include_images: Some(vec!["busybox".to_owned()]),
             multiline: Some(MultilineConfig {
-                start_pattern: "^[^\\s]".to_owned(),
-                condition_pattern: "^[\\s]+at".to_owned(),
+                start_pattern: Some("^[^\\s]".to_owned()),
+                start_condition: None,
+                condition_pattern: Some("^[\\s]+at".to_owned()),
+                condition: None,
                 mode: line_agg::Mode::ContinueThrough,
                 timeout_ms: 10,
+                max_lines: None,
+                max_bytes: None,
             }),
             ..DockerLogsConfig::default()
         };
@@ -0,0 +1,174 @@
+// SPDX-License-Identifier: GPL-2.0
+/*
+ * Captures process exec/exit and TCP connect/accept events, and sends them to
+ * the `ebpf` source through a ring buffer.
+ *
+ * The layout of `struct event` must be kept in sync with `src/sources/ebpf/event.rs`.
+ */
+#include "kernel_types.h"
+
+#include <bpf/bpf_core_read.h>
+#include <bpf/bpf_endian.h>
+#include <bpf/bpf_helpers.h>
+#include <bpf/bpf_tracing.h>
+
+#define FILENAME_LEN 128
+
+enum event_kind {
+	EVENT_PROCESS_EXEC = 1,
+	EVENT_PROCESS_EXIT = 2,
+	EVENT_TCP_CONNECT = 3,
+	EVENT_TCP_ACCEPT = 4,
+};
+
+struct event {
+	__u8 kind;
+	__u8 family;
+	__u16 source_port;
+	__u16 destination_port;
+	__u16 _padding;
+	__u32 pid;
+	__u32 ppid;
+	__u32 uid;
+	__s32 exit_code;
+	__u8 source_address[16];
+	__u8 destination_address[16];
+	char comm[TASK_COMM_LEN];
+	char filename[FILENAME_LEN];
+};
+
+struct {
+	__uint(type, BPF_MAP_TYPE_RINGBUF);
+	__uint(max_entries, 256 * 1024);
+} events SEC(".maps");
+
+/* The sockets of the `tcp_v*_connect` calls in flight, by thread. */
+struct {
+	__uint(type, BPF_MAP_TYPE_HASH);
+	__uint(max_entries, 8192);
+	__type(key, __u32);
+	__type(value, struct sock *);
+} connecting SEC(".maps");
+
+static __always_inline struct event *reserve_event(enum event_kind kind)
+{
+	struct event *e = bpf_ringbuf_reserve(&events, sizeof(*e), 0);
+	if (!e)
+		return NULL;
+	__builtin_memset(e, 0, sizeof(*e));
+
+	struct task_struct *task = (struct task_struct *)bpf_get_current_task();
+	e->kind = kind;
+	e->pid = bpf_get_current_pid_tgid() >> 32;
+	e->ppid = BPF_CORE_READ(task, real_parent, tgid);
+	e->uid = (__u32)bpf_get_current_uid_gid();
+	bpf_get_current_comm(&e->comm, sizeof(e->comm));
+	return e;
+}
+
+SEC("tp/sched/sched_process_exec")
+int handle_process_exec(struct trace_event_raw_sched_process_exec *ctx)
+{
+	struct event *e = reserve_event(EVENT_PROCESS_EXEC);
+	if (!e)
+		return 0;
+
+	unsigned int filename_offset = ctx->__data_loc_filename & 0xFFFF;
+	bpf_probe_read_str(&e->filename, sizeof(e->filename), (void *)ctx + filename_offset);
+	bpf_ringbuf_submit(e, 0);
+	return 0;
+}
+
+SEC("tp/sched/sched_process_exit")
+int handle_process_exit(struct trace_event_raw_sched_process_template *ctx)
+{
+	__u64 pid_tgid = bpf_get_current_pid_tgid();
+	/* Ignore the threads other than the main one of the process. */
+	if ((__u32)pid_tgid != pid_tgid >> 32)
+		return 0;
+
+	struct event *e = reserve_event(EVENT_PROCESS_EXIT);
+	if (!e)
+		return 0;
+
+	struct task_struct *task = (struct task_struct *)bpf_get_current_task();
+	e->exit_code = (BPF_CORE_READ(task, exit_code) >> 8) & 0xFF;
+	bpf_ringbuf_submit(e, 0);
+	return 0;
+}
+
+static __always_inline void submit_sock(enum event_kind kind, struct sock *sk)
+{
+	struct event *e = reserve_event(kind);
+	if (!e)
+		return;
+
+	e->family = BPF_CORE_READ(sk, __sk_common.skc_family);
+	e->source_port = BPF_CORE_READ(sk, __sk_common.skc_num);
+	e->destination_port = bpf_ntohs(BPF_CORE_READ(sk, __sk_common.skc_dport));
+	if (e->family == AF_INET) {
+		bpf_core_read(&e->source_address, 4, &sk->__sk_common.skc_rcv_saddr);
+		bpf_core_read(&e->destination_address, 4, &sk->__sk_common.skc_daddr);
+	} else if (e->family == AF_INET6) {
+		bpf_core_read(&e->source_address, 16, &sk->__sk_common.skc_v6_rcv_saddr);
+		bpf_core_read(&e->destination_address, 16, &sk->__sk_common.skc_v6_daddr);
+	} else {
+		bpf_ringbuf_discard(e, 0);
+		return;
+	}
+	bpf_ringbuf_submit(e, 0);
+}
+
+static __always_inline int enter_connect(struct sock *sk)
+{
+	__u32 tid = (__u32)bpf_get_current_pid_tgid();
+	bpf_map_update_elem(&connecting, &tid, &sk, BPF_ANY);
+	return 0;
+}
+
+static __always_inline int exit_connect(int ret)
+{
+	__u32 tid = (__u32)bpf_get_current_pid_tgid();
+	struct sock **skp = bpf_map_lookup_elem(&connecting, &tid);
+	if (!skp)
+		return 0;
+
+	if (ret == 0)
+		submit_sock(EVENT_TCP_CONNECT, *skp);
+	bpf_map_delete_elem(&connecting, &tid);
+	return 0;
+}
+
+SEC("kprobe/tcp_v4_connect")
+int BPF_KPROBE(handle_tcp_v4_connect, struct sock *sk)
+{
+	return enter_connect(sk);
+}
+
+SEC("kretprobe/tcp_v4_connect")
+int BPF_KRETPROBE(handle_tcp_v4_connect_ret, int ret)
+{
+	return exit_connect(ret);
+}
+
+SEC("kprobe/tcp_v6_connect")
+int BPF_KPROBE(handle_tcp_v6_connect, struct sock *sk)
+{
+	return enter_connect(sk);
+}
+
+SEC("kretprobe/tcp_v6_connect")
+int BPF_KRETPROBE(handle_tcp_v6_connect_ret, int ret)
+{
+	return exit_connect(ret);
+}
+
+SEC("kretprobe/inet_csk_accept")
+int BPF_KRETPROBE(handle_tcp_accept, struct sock *sk)
+{
+	if (sk)
+		submit_sock(EVENT_TCP_ACCEPT, sk);
+	return 0;
+}
+
+char LICENSE[] SEC("license") = "GPL";
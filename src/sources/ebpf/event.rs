@@ -0,0 +1,235 @@
+//! The events sent by the eBPF programs through the ring buffer.
+//!
+//! The layout of [`RawEvent`] must be kept in sync with `struct event` in
+//! `bpf/telemetry.bpf.c`.
+
+use std::{
+    convert::TryInto,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+
+use snafu::Snafu;
+
+const EVENT_SIZE: usize = 200;
+const COMM_OFFSET: usize = 56;
+const COMM_LEN: usize = 16;
+const FILENAME_OFFSET: usize = 72;
+const FILENAME_LEN: usize = 128;
+
+const AF_INET: u8 = 2;
+const AF_INET6: u8 = 10;
+
+#[derive(Debug, Snafu, PartialEq)]
+pub enum ParseError {
+    #[snafu(display("Event of {} bytes is shorter than {} bytes", length, EVENT_SIZE))]
+    Truncated { length: usize },
+    #[snafu(display("Unknown event kind {}", kind))]
+    UnknownKind { kind: u8 },
+    #[snafu(display("Unknown address family {}", family))]
+    UnknownFamily { family: u8 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Kind {
+    ProcessExec,
+    ProcessExit,
+    TcpConnect,
+    TcpAccept,
+}
+
+impl Kind {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::ProcessExec => "process_exec",
+            Self::ProcessExit => "process_exit",
+            Self::TcpConnect => "tcp_connect",
+            Self::TcpAccept => "tcp_accept",
+        }
+    }
+}
+
+/// The endpoints of a TCP connection.
+#[derive(Debug, PartialEq)]
+pub struct Connection {
+    pub source_address: IpAddr,
+    pub source_port: u16,
+    pub destination_address: IpAddr,
+    pub destination_port: u16,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RawEvent {
+    pub kind: Kind,
+    pub pid: u32,
+    pub ppid: u32,
+    pub uid: u32,
+    pub comm: String,
+    /// The executed file, for the `process_exec` events.
+    pub filename: Option<String>,
+    /// The exit code, for the `process_exit` events.
+    pub exit_code: Option<i32>,
+    /// The connection, for the TCP events.
+    pub connection: Option<Connection>,
+}
+
+impl RawEvent {
+    /// Parse an event from the bytes of a ring buffer entry, which are in the
+    /// native byte order.
+    pub fn parse(bytes: &[u8]) -> Result<Self, ParseError> {
+        if bytes.len() < EVENT_SIZE {
+            return Err(ParseError::Truncated {
+                length: bytes.len(),
+            });
+        }
+
+        let u16_at =
+            |offset: usize| u16::from_ne_bytes(bytes[offset..offset + 2].try_into().unwrap());
+        let u32_at =
+            |offset: usize| u32::from_ne_bytes(bytes[offset..offset + 4].try_into().unwrap());
+
+        let kind = match bytes[0] {
+            1 => Kind::ProcessExec,
+            2 => Kind::ProcessExit,
+            3 => Kind::TcpConnect,
+            4 => Kind::TcpAccept,
+            kind => return Err(ParseError::UnknownKind { kind }),
+        };
+
+        let connection = match kind {
+            Kind::TcpConnect | Kind::TcpAccept => {
+                let (source_address, destination_address) = match bytes[1] {
+                    AF_INET => (ipv4_at(bytes, 24), ipv4_at(bytes, 40)),
+                    AF_INET6 => (ipv6_at(bytes, 24), ipv6_at(bytes, 40)),
+                    family => return Err(ParseError::UnknownFamily { family }),
+                };
+                Some(Connection {
+                    source_address,
+                    source_port: u16_at(2),
+                    destination_address,
+                    destination_port: u16_at(4),
+                })
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            kind,
+            pid: u32_at(8),
+            ppid: u32_at(12),
+            uid: u32_at(16),
+            comm: c_string(&bytes[COMM_OFFSET..COMM_OFFSET + COMM_LEN]),
+            filename: (kind == Kind::ProcessExec)
+                .then(|| c_string(&bytes[FILENAME_OFFSET..FILENAME_OFFSET + FILENAME_LEN])),
+            exit_code: (kind == Kind::ProcessExit).then(|| u32_at(20) as i32),
+            connection,
+        })
+    }
+}
+
+fn ipv4_at(bytes: &[u8], offset: usize) -> IpAddr {
+    let octets: [u8; 4] = bytes[offset..offset + 4].try_into().unwrap();
+    Ipv4Addr::from(octets).into()
+}
+
+fn ipv6_at(bytes: &[u8], offset: usize) -> IpAddr {
+    let octets: [u8; 16] = bytes[offset..offset + 16].try_into().unwrap();
+    Ipv6Addr::from(octets).into()
+}
+
+/// Decode a NUL-terminated string, which fills the buffer if it's truncated.
+fn c_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw(kind: u8, family: u8) -> Vec<u8> {
+        let mut bytes = vec![0; EVENT_SIZE];
+        bytes[0] = kind;
+        bytes[1] = family;
+        bytes[2..4].copy_from_slice(&54321u16.to_ne_bytes());
+        bytes[4..6].copy_from_slice(&443u16.to_ne_bytes());
+        bytes[8..12].copy_from_slice(&1234u32.to_ne_bytes());
+        bytes[12..16].copy_from_slice(&1u32.to_ne_bytes());
+        bytes[16..20].copy_from_slice(&1000u32.to_ne_bytes());
+        bytes[20..24].copy_from_slice(&2i32.to_ne_bytes());
+        bytes[COMM_OFFSET..COMM_OFFSET + 4].copy_from_slice(b"curl");
+        bytes[FILENAME_OFFSET..FILENAME_OFFSET + 13].copy_from_slice(b"/usr/bin/curl");
+        bytes
+    }
+
+    #[test]
+    fn parses_process_exec() {
+        let event = RawEvent::parse(&raw(1, 0)).unwrap();
+        assert_eq!(
+            event,
+            RawEvent {
+                kind: Kind::ProcessExec,
+                pid: 1234,
+                ppid: 1,
+                uid: 1000,
+                comm: "curl".into(),
+                filename: Some("/usr/bin/curl".into()),
+                exit_code: None,
+                connection: None,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_process_exit() {
+        let event = RawEvent::parse(&raw(2, 0)).unwrap();
+        assert_eq!(event.kind, Kind::ProcessExit);
+        assert_eq!(event.exit_code, Some(2));
+        assert_eq!(event.filename, None);
+    }
+
+    #[test]
+    fn parses_tcp_connect_ipv4() {
+        let mut bytes = raw(3, AF_INET);
+        bytes[24..28].copy_from_slice(&[10, 0, 0, 1]);
+        bytes[40..44].copy_from_slice(&[93, 184, 216, 34]);
+        let event = RawEvent::parse(&bytes).unwrap();
+        assert_eq!(
+            event.connection,
+            Some(Connection {
+                source_address: "10.0.0.1".parse().unwrap(),
+                source_port: 54321,
+                destination_address: "93.184.216.34".parse().unwrap(),
+                destination_port: 443,
+            })
+        );
+    }
+
+    #[test]
+    fn parses_tcp_accept_ipv6() {
+        let mut bytes = raw(4, AF_INET6);
+        let localhost: Ipv6Addr = "::1".parse().unwrap();
+        bytes[24..40].copy_from_slice(&localhost.octets());
+        bytes[40..56].copy_from_slice(&localhost.octets());
+        let event = RawEvent::parse(&bytes).unwrap();
+        assert_eq!(event.kind, Kind::TcpAccept);
+        let connection = event.connection.unwrap();
+        assert_eq!(connection.source_address, IpAddr::from(localhost));
+        assert_eq!(connection.destination_address, IpAddr::from(localhost));
+    }
+
+    #[test]
+    fn rejects_invalid_events() {
+        assert_eq!(
+            RawEvent::parse(&[1; 10]),
+            Err(ParseError::Truncated { length: 10 })
+        );
+        assert_eq!(
+            RawEvent::parse(&raw(9, 0)),
+            Err(ParseError::UnknownKind { kind: 9 })
+        );
+        assert_eq!(
+            RawEvent::parse(&raw(3, 1)),
+            Err(ParseError::UnknownFamily { family: 1 })
+        );
+    }
+}
@@ -0,0 +1,82 @@
+//! Checks that the running kernel supports the eBPF programs.
+
+use std::{fs, io, path::Path};
+
+use snafu::{ResultExt, Snafu};
+
+/// The ring buffer used to send the events requires Linux 5.8.
+pub const MIN_KERNEL_VERSION: (u32, u32) = (5, 8);
+
+const OS_RELEASE_PATH: &str = "/proc/sys/kernel/osrelease";
+
+/// The BTF of the kernel, required to relocate the programs (CO-RE).
+const BTF_PATH: &str = "/sys/kernel/btf/vmlinux";
+
+#[derive(Debug, Snafu)]
+pub enum KernelError {
+    #[snafu(display("Unable to read the kernel release: {}", source))]
+    ReadRelease { source: io::Error },
+    #[snafu(display("Unable to parse the kernel release {:?}", release))]
+    ParseRelease { release: String },
+    #[snafu(display(
+        "Kernel {} is not supported, the ebpf source requires Linux {}.{} or later",
+        release,
+        MIN_KERNEL_VERSION.0,
+        MIN_KERNEL_VERSION.1
+    ))]
+    Unsupported { release: String },
+    #[snafu(display(
+        "The kernel BTF is not available at {}, the kernel must be built with CONFIG_DEBUG_INFO_BTF",
+        BTF_PATH
+    ))]
+    MissingBtf,
+}
+
+/// Check the version of the running kernel, and that its BTF is available.
+pub fn check() -> Result<(), KernelError> {
+    let release = fs::read_to_string(OS_RELEASE_PATH).context(ReadReleaseSnafu)?;
+    let release = release.trim();
+    let version = parse_release(release).ok_or_else(|| KernelError::ParseRelease {
+        release: release.into(),
+    })?;
+    if version < MIN_KERNEL_VERSION {
+        return Err(KernelError::Unsupported {
+            release: release.into(),
+        });
+    }
+    if !Path::new(BTF_PATH).exists() {
+        return Err(KernelError::MissingBtf);
+    }
+    Ok(())
+}
+
+/// Parse the major and minor versions of a kernel release, such as `5.15.0-56-generic`.
+fn parse_release(release: &str) -> Option<(u32, u32)> {
+    let mut parts = release.split(|c: char| !c.is_ascii_digit());
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_releases() {
+        assert_eq!(parse_release("5.15.0-56-generic"), Some((5, 15)));
+        assert_eq!(parse_release("6.1.0"), Some((6, 1)));
+        assert_eq!(parse_release("4.18.0-372.9.1.el8.x86_64"), Some((4, 18)));
+        assert_eq!(parse_release("5.10"), Some((5, 10)));
+        assert_eq!(parse_release("linux"), None);
+        assert_eq!(parse_release("5"), None);
+    }
+
+    #[test]
+    fn compares_versions() {
+        assert!(parse_release("5.8.0").unwrap() >= MIN_KERNEL_VERSION);
+        assert!(parse_release("5.10.0").unwrap() >= MIN_KERNEL_VERSION);
+        assert!(parse_release("4.19.0").unwrap() < MIN_KERNEL_VERSION);
+        assert!(parse_release("5.4.0").unwrap() < MIN_KERNEL_VERSION);
+    }
+}
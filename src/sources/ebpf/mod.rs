@@ -0,0 +1,315 @@
+//! An experimental source capturing process and network telemetry with eBPF
+//! programs, which are compiled once and relocated against the BTF of the
+//! running kernel when loaded (CO-RE).
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use bytes::Bytes;
+use chrono::Utc;
+use libbpf_rs::{
+    skel::{OpenSkel, Skel, SkelBuilder},
+    RingBufferBuilder,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, Output, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::{Event, LogEvent},
+    internal_events::{
+        BytesReceived, EbpfParseError, EbpfProbeError, OldEventsReceived, StreamClosedError,
+    },
+    shutdown::ShutdownSignal,
+    SourceSender,
+};
+
+mod event;
+mod kernel;
+
+pub use event::ParseError;
+use event::RawEvent;
+pub use kernel::KernelError;
+
+#[allow(clippy::all, dead_code)]
+mod skel {
+    include!(concat!(env!("OUT_DIR"), "/telemetry.skel.rs"));
+}
+
+/// How long to wait for events in the ring buffer before checking for shutdown.
+const POLL_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// The number of events buffered between the ring buffer and the source.
+const CHANNEL_CAPACITY: usize = 1024;
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Probe {
+    ProcessExec,
+    ProcessExit,
+    TcpConnect,
+    TcpAccept,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct EbpfConfig {
+    /// The probes to attach, all of them by default.
+    #[serde(default = "default_probes")]
+    probes: Vec<Probe>,
+}
+
+fn default_probes() -> Vec<Probe> {
+    vec![
+        Probe::ProcessExec,
+        Probe::ProcessExit,
+        Probe::TcpConnect,
+        Probe::TcpAccept,
+    ]
+}
+
+inventory::submit! {
+    SourceDescription::new::<EbpfConfig>("ebpf")
+}
+
+impl GenerateConfig for EbpfConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            probes: default_probes(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "ebpf")]
+impl SourceConfig for EbpfConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        if self.probes.is_empty() {
+            return Err("At least one probe must be enabled.".into());
+        }
+        kernel::check()?;
+
+        Ok(Box::pin(ebpf(self.probes.clone(), cx.shutdown, cx.out)))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "ebpf"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+async fn ebpf(
+    probes: Vec<Probe>,
+    mut shutdown: ShutdownSignal,
+    mut out: SourceSender,
+) -> Result<(), ()> {
+    let (tx, mut rx) = mpsc::channel(CHANNEL_CAPACITY);
+    let stop = Arc::new(AtomicBool::new(false));
+
+    // The skeleton and the ring buffer aren't `Send`, so they live in their
+    // own thread.
+    let poller = {
+        let stop = Arc::clone(&stop);
+        tokio::task::spawn_blocking(move || run_probes(&probes, tx, &stop))
+    };
+
+    let host = crate::get_hostname().ok();
+    loop {
+        let bytes: Bytes = tokio::select! {
+            bytes = rx.recv() => match bytes {
+                Some(bytes) => bytes,
+                None => break,
+            },
+            _ = &mut shutdown => break,
+        };
+
+        emit!(BytesReceived {
+            byte_size: bytes.len(),
+            protocol: "ebpf",
+        });
+
+        let raw = match RawEvent::parse(&bytes) {
+            Ok(raw) => raw,
+            Err(error) => {
+                emit!(EbpfParseError { error });
+                continue;
+            }
+        };
+
+        let event = create_event(raw, host.as_deref());
+        emit!(OldEventsReceived {
+            count: 1,
+            byte_size: event.size_of(),
+        });
+        if let Err(error) = out.send_event(event).await {
+            emit!(StreamClosedError { error, count: 1 });
+            stop.store(true, Ordering::Relaxed);
+            return Err(());
+        }
+    }
+
+    // Unblock the poller if it waits for room in the channel.
+    drop(rx);
+    stop.store(true, Ordering::Relaxed);
+    match poller.await {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(error)) => {
+            emit!(EbpfProbeError { error });
+            Err(())
+        }
+        Err(error) => {
+            error!(message = "The eBPF poller panicked.", %error);
+            Err(())
+        }
+    }
+}
+
+/// Load and attach the programs of the probes, and forward the events of the
+/// ring buffer until stopped.
+fn run_probes(
+    probes: &[Probe],
+    tx: mpsc::Sender<Bytes>,
+    stop: &AtomicBool,
+) -> Result<(), libbpf_rs::Error> {
+    let mut open_skel = skel::TelemetrySkelBuilder::default().open()?;
+    {
+        let enabled = |probe| probes.contains(&probe);
+        let mut progs = open_skel.progs_mut();
+        progs
+            .handle_process_exec()
+            .set_autoload(enabled(Probe::ProcessExec))?;
+        progs
+            .handle_process_exit()
+            .set_autoload(enabled(Probe::ProcessExit))?;
+        let tcp_connect = enabled(Probe::TcpConnect);
+        progs.handle_tcp_v4_connect().set_autoload(tcp_connect)?;
+        progs
+            .handle_tcp_v4_connect_ret()
+            .set_autoload(tcp_connect)?;
+        progs.handle_tcp_v6_connect().set_autoload(tcp_connect)?;
+        progs
+            .handle_tcp_v6_connect_ret()
+            .set_autoload(tcp_connect)?;
+        progs
+            .handle_tcp_accept()
+            .set_autoload(enabled(Probe::TcpAccept))?;
+    }
+
+    let mut skel = open_skel.load()?;
+    skel.attach()?;
+    info!(message = "Attached eBPF probes.", ?probes);
+
+    let mut builder = RingBufferBuilder::new();
+    let maps = skel.maps();
+    builder.add(maps.events(), move |data: &[u8]| {
+        // The receiver is only dropped on shutdown, which stops the polling.
+        let _ = tx.blocking_send(Bytes::copy_from_slice(data));
+        0
+    })?;
+    let ring_buffer = builder.build()?;
+
+    while !stop.load(Ordering::Relaxed) {
+        ring_buffer.poll(POLL_TIMEOUT)?;
+    }
+    Ok(())
+}
+
+fn create_event(raw: RawEvent, host: Option<&str>) -> Event {
+    let mut log = LogEvent::default();
+
+    log.insert("event", raw.kind.as_str());
+    log.insert("pid", raw.pid);
+    log.insert("ppid", raw.ppid);
+    log.insert("uid", raw.uid);
+    log.insert("comm", raw.comm);
+    if let Some(filename) = raw.filename {
+        log.insert("filename", filename);
+    }
+    if let Some(exit_code) = raw.exit_code {
+        log.insert("exit_code", exit_code);
+    }
+    if let Some(connection) = raw.connection {
+        log.insert("source_address", connection.source_address.to_string());
+        log.insert("source_port", connection.source_port);
+        log.insert(
+            "destination_address",
+            connection.destination_address.to_string(),
+        );
+        log.insert("destination_port", connection.destination_port);
+    }
+    if let Some(host) = host {
+        log.insert(log_schema().host_key(), host.to_owned());
+    }
+    log.insert(log_schema().timestamp_key(), Utc::now());
+    log.insert(log_schema().source_type_key(), Bytes::from("ebpf"));
+
+    log.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::Value;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<EbpfConfig>();
+    }
+
+    #[test]
+    fn parses_probes() {
+        let config: EbpfConfig =
+            toml::from_str(r#"probes = ["process_exec", "tcp_accept"]"#).unwrap();
+        assert_eq!(config.probes, vec![Probe::ProcessExec, Probe::TcpAccept]);
+
+        let config: EbpfConfig = toml::from_str("").unwrap();
+        assert_eq!(config.probes, default_probes());
+    }
+
+    #[test]
+    fn creates_connection_events() {
+        let raw = RawEvent {
+            kind: event::Kind::TcpConnect,
+            pid: 1234,
+            ppid: 1,
+            uid: 0,
+            comm: "curl".into(),
+            filename: None,
+            exit_code: None,
+            connection: Some(event::Connection {
+                source_address: "10.0.0.1".parse().unwrap(),
+                source_port: 54321,
+                destination_address: "93.184.216.34".parse().unwrap(),
+                destination_port: 443,
+            }),
+        };
+        let event = create_event(raw, Some("node-1"));
+        let log = event.as_log();
+
+        assert_eq!(log["event"], "tcp_connect".into());
+        assert_eq!(log["pid"], Value::Integer(1234));
+        assert_eq!(log["comm"], "curl".into());
+        assert_eq!(log["destination_address"], "93.184.216.34".into());
+        assert_eq!(log["destination_port"], Value::Integer(443));
+        assert_eq!(log[log_schema().host_key()], "node-1".into());
+        assert_eq!(log[log_schema().source_type_key()], "ebpf".into());
+        assert!(log.get("filename").is_none());
+    }
+}
@@ -27,14 +27,17 @@ use vector_core::ByteSizeOf;
 use crate::{
     async_read::VecAsyncReadExt,
     codecs::{Decoder, DecodingConfig},
-    config::{log_schema, Output, SourceConfig, SourceContext, SourceDescription},
+    config::{
+        log_schema, AcknowledgementsConfig, Output, SourceConfig, SourceContext, SourceDescription,
+    },
     event::Event,
     internal_events::{
         BytesReceived, ExecCommandExecuted, ExecEventsReceived, ExecFailedError, ExecTimeoutError,
         StreamClosedError,
     },
-    serde::default_decoding,
+    serde::{bool_or_struct, default_decoding},
     shutdown::ShutdownSignal,
+    sources::util::DeliveryTracker,
     SourceSender,
 };
 use lookup::path;
@@ -56,6 +59,8 @@ pub struct ExecConfig {
     framing: Option<FramingConfig>,
     #[serde(default = "default_decoding")]
     decoding: DeserializerConfig,
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
 }
 
 // TODO: Would be nice to combine the scheduled and streaming config with the mode enum once
@@ -105,6 +110,7 @@ impl Default for ExecConfig {
             maximum_buffer_size_bytes: default_maximum_buffer_size(),
             framing: None,
             decoding: default_decoding(),
+            acknowledgements: Default::default(),
         }
     }
 }
@@ -196,6 +202,7 @@ impl SourceConfig for ExecConfig {
             .clone()
             .unwrap_or_else(|| self.decoding.default_stream_framing());
         let decoder = DecodingConfig::new(framing, self.decoding.clone()).build();
+        let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
 
         match &self.mode {
             Mode::Scheduled => {
@@ -208,6 +215,7 @@ impl SourceConfig for ExecConfig {
                     decoder,
                     cx.shutdown,
                     cx.out,
+                    acknowledgements,
                 )))
             }
             Mode::Streaming => {
@@ -222,6 +230,7 @@ impl SourceConfig for ExecConfig {
                     decoder,
                     cx.shutdown,
                     cx.out,
+                    acknowledgements,
                 )))
             }
         }
@@ -236,7 +245,7 @@ impl SourceConfig for ExecConfig {
     }
 
     fn can_acknowledge(&self) -> bool {
-        false
+        true
     }
 }
 
@@ -247,6 +256,7 @@ async fn run_scheduled(
     decoder: Decoder,
     shutdown: ShutdownSignal,
     out: SourceSender,
+    acknowledgements: bool,
 ) -> Result<(), ()> {
     debug!("Starting scheduled exec runs.");
     let schedule = Duration::from_secs(exec_interval_secs);
@@ -263,6 +273,7 @@ async fn run_scheduled(
                 decoder.clone(),
                 shutdown.clone(),
                 out.clone(),
+                acknowledgements,
             ),
         )
         .await;
@@ -298,6 +309,7 @@ async fn run_streaming(
     decoder: Decoder,
     shutdown: ShutdownSignal,
     out: SourceSender,
+    acknowledgements: bool,
 ) -> Result<(), ()> {
     if respawn_on_exit {
         let duration = Duration::from_secs(respawn_interval_secs);
@@ -311,7 +323,8 @@ async fn run_streaming(
                     hostname.clone(),
                     decoder.clone(),
                     shutdown.clone(),
-                    out.clone()
+                    out.clone(),
+                    acknowledgements,
                 ) => {
                     // handle command finished
                     if let Err(command_error) = output {
@@ -334,7 +347,15 @@ async fn run_streaming(
             }
         }
     } else {
-        let output = run_command(config.clone(), hostname, decoder, shutdown, out).await;
+        let output = run_command(
+            config.clone(),
+            hostname,
+            decoder,
+            shutdown,
+            out,
+            acknowledgements,
+        )
+        .await;
 
         if let Err(command_error) = output {
             emit!(ExecFailedError {
@@ -353,8 +374,12 @@ async fn run_command(
     decoder: Decoder,
     shutdown: ShutdownSignal,
     mut out: SourceSender,
+    acknowledgements: bool,
 ) -> Result<Option<ExitStatus>, Error> {
     debug!("Starting command run.");
+    // With acknowledgements, the run only finishes once the events of the command are delivered,
+    // holding off the next run.
+    let tracker = DeliveryTracker::new(acknowledgements, shutdown.clone());
     let mut command = build_command(&config);
 
     // Mark the start time just before spawning the process as
@@ -408,6 +433,7 @@ async fn run_command(
         for event in &mut events {
             handle_event(&config, &hostname, &Some(stream.to_string()), pid, event);
         }
+        tracker.track(&mut events);
         if let Err(error) = out.send_batch(events).await {
             emit!(StreamClosedError { count, error });
             break;
@@ -433,6 +459,15 @@ async fn run_command(
         }
     };
 
+    let undelivered = tracker.finish().await;
+    if undelivered > 0 {
+        error!(
+            message = "Failed to deliver events of command.",
+            command = %config.command_line(),
+            batches = %undelivered,
+        );
+    }
+
     debug!("Finished command run.");
 
     result
@@ -555,6 +590,8 @@ mod tests {
     use futures::task::Poll;
 
     use super::*;
+    #[cfg(not(target_os = "windows"))]
+    use crate::event::EventStatus;
     use crate::test_util::trace_init;
 
     #[test]
@@ -684,7 +721,7 @@ mod tests {
         // Wait for our task to finish, wrapping it in a timeout
         let timeout = tokio::time::timeout(
             time::Duration::from_secs(5),
-            run_command(config.clone(), hostname, decoder, shutdown, tx),
+            run_command(config.clone(), hostname, decoder, shutdown, tx, false),
         );
 
         let timeout_result =
@@ -711,6 +748,26 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    #[cfg(not(target_os = "windows"))]
+    async fn test_run_command_waits_for_delivery() {
+        let config = standard_scheduled_test_config();
+        let (tx, rx) = SourceSender::new_test_finalize(EventStatus::Delivered);
+
+        let run = tokio::spawn(run_command(
+            config,
+            None,
+            Default::default(),
+            ShutdownSignal::noop(),
+            tx,
+            true,
+        ));
+
+        assert_eq!(rx.collect::<Vec<_>>().await.len(), 1);
+        let exit_status = run.await.unwrap().expect("command error");
+        assert_eq!(0_i32, exit_status.unwrap().code().unwrap());
+    }
+
     fn standard_scheduled_test_config() -> ExecConfig {
         Default::default()
     }
@@ -16,8 +16,8 @@ use tracing::{Instrument, Span};
 use super::util::{finalizer::OrderedFinalizer, EncodingConfig, MultilineConfig};
 use crate::{
     config::{
-        log_schema, AcknowledgementsConfig, DataType, Output, SourceConfig, SourceContext,
-        SourceDescription,
+        log_schema, AcknowledgementsConfig, Capability, DataType, Output, Sandbox, SourceConfig,
+        SourceContext, SourceDescription,
     },
     encoding_transcode::{Decoder, Encoder},
     event::{BatchNotifier, BatchStatus, LogEvent},
@@ -88,6 +88,7 @@ pub struct FileConfig {
     pub oldest_first: bool,
     #[serde(alias = "remove_after")]
     pub remove_after_secs: Option<u64>,
+    pub max_checkpoints: Option<usize>,
     pub line_delimiter: String,
     pub encoding: Option<EncodingConfig>,
     #[serde(default, deserialize_with = "bool_or_struct")]
@@ -191,6 +192,7 @@ impl Default for FileConfig {
             max_read_bytes: 2048,
             oldest_first: false,
             remove_after_secs: None,
+            max_checkpoints: None,
             line_delimiter: "\n".to_string(),
             encoding: None,
             acknowledgements: Default::default(),
@@ -230,6 +232,11 @@ impl SourceConfig for FileConfig {
             }
         }
 
+        let sandbox = Sandbox::current();
+        for capability in self.capabilities() {
+            sandbox.enforce(&capability)?;
+        }
+
         let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
 
         Ok(file_source(
@@ -249,6 +256,17 @@ impl SourceConfig for FileConfig {
         "file"
     }
 
+    fn capabilities(&self) -> Vec<Capability> {
+        self.include
+            .iter()
+            .map(|pattern| Capability::glob(&pattern.to_string_lossy()))
+            .collect()
+    }
+
+    fn supports_sandbox(&self) -> bool {
+        true
+    }
+
     fn can_acknowledge(&self) -> bool {
         true
     }
@@ -306,6 +324,7 @@ pub fn file_source(
         },
         oldest_first: config.oldest_first,
         remove_after: config.remove_after_secs.map(Duration::from_secs),
+        max_checkpoints: config.max_checkpoints,
         emitter: FileSourceInternalEventsEmitter,
         handle: tokio::runtime::Handle::current(),
     };
@@ -1332,10 +1351,14 @@ mod tests {
         let config = file::FileConfig {
             include: vec![dir.path().join("*")],
             multiline: Some(MultilineConfig {
-                start_pattern: "INFO".to_owned(),
-                condition_pattern: "INFO".to_owned(),
+                start_pattern: Some("INFO".to_owned()),
+                start_condition: None,
+                condition_pattern: Some("INFO".to_owned()),
+                condition: None,
                 mode: line_agg::Mode::HaltBefore,
                 timeout_ms: 25, // less than 50 in sleep()
+                max_lines: None,
+                max_bytes: None,
             }),
             ..test_default_file_config(&dir)
         };
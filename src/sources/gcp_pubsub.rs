@@ -1,14 +1,18 @@
-use std::{error::Error as _, pin::Pin, sync::Arc, time::Duration};
+use std::{collections::HashMap, error::Error as _, pin::Pin, sync::Arc, time::Duration};
 
 use chrono::{DateTime, NaiveDateTime, Utc};
 use codecs::decoding::{DeserializerConfig, FramingConfig};
 use derivative::Derivative;
-use futures::{stream, Stream, StreamExt, TryFutureExt};
+use futures::{future, stream, Stream, StreamExt, TryFutureExt};
 use http::uri::{InvalidUri, Scheme, Uri};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
-use tokio::sync::Mutex;
+use tokio::{
+    sync::mpsc,
+    time::{interval_at, Instant},
+};
+use tokio_stream::wrappers::UnboundedReceiverStream;
 use tonic::{
     metadata::{errors::InvalidMetadataValue, MetadataValue},
     transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Identity},
@@ -22,8 +26,8 @@ use crate::{
     event::{BatchNotifier, BatchStatus, Event, MaybeAsLogMut, Value},
     gcp::{GcpAuthConfig, GcpCredentials, Scope, PUBSUB_URL},
     internal_events::{
-        BytesReceived, GcpPubsubConnectError, GcpPubsubReceiveError, GcpPubsubStreamingPullError,
-        StreamClosedError,
+        BytesReceived, GcpPubsubAcknowledgementError, GcpPubsubConnectError, GcpPubsubReceiveError,
+        GcpPubsubStreamingPullError, StreamClosedError,
     },
     serde::{bool_or_struct, default_decoding, default_framing_message_based},
     shutdown::ShutdownSignal,
@@ -35,6 +39,9 @@ use crate::{
 
 const MIN_ACK_DEADLINE_SECONDS: i32 = 10;
 const MAX_ACK_DEADLINE_SECONDS: i32 = 600;
+// The maximum number of acknowledgement IDs sent at once, which keeps
+// requests under the size limit of the server.
+const MAX_IDS_PER_REQUEST: usize = 2500;
 
 type Finalizer = UnorderedFinalizer<Vec<String>>;
 
@@ -89,6 +96,8 @@ pub(crate) enum PubsubError {
         MAX_ACK_DEADLINE_SECONDS
     ))]
     InvalidAckDeadline,
+    #[snafu(display("`keepalive_secs` must be positive"))]
+    InvalidKeepalive,
 }
 
 static CLIENT_ID: Lazy<String> = Lazy::new(|| uuid::Uuid::new_v4().to_string());
@@ -114,6 +123,31 @@ pub struct PubsubConfig {
     #[serde(default = "default_retry_delay")]
     pub retry_delay_seconds: f64,
 
+    /// The maximum number of messages delivered to Vector while their
+    /// events wait to be acknowledged.
+    #[serde(default = "default_max_outstanding_messages")]
+    #[derivative(Default(value = "default_max_outstanding_messages()"))]
+    pub max_outstanding_messages: i64,
+
+    /// The maximum size of the messages delivered to Vector while their
+    /// events wait to be acknowledged.
+    #[serde(default = "default_max_outstanding_bytes")]
+    #[derivative(Default(value = "default_max_outstanding_bytes()"))]
+    pub max_outstanding_bytes: i64,
+
+    /// How often an empty request is sent on the stream, which keeps it
+    /// from being closed while there is nothing to acknowledge.
+    #[serde(default = "default_keepalive")]
+    #[derivative(Default(value = "default_keepalive()"))]
+    pub keepalive_secs: f64,
+
+    /// How long the deadline of the messages waiting for their events
+    /// to be acknowledged is extended for, after which the server
+    /// redelivers them.
+    #[serde(default = "default_max_lease_duration")]
+    #[derivative(Default(value = "default_max_lease_duration()"))]
+    pub max_lease_duration_secs: u64,
+
     #[serde(default = "default_framing_message_based")]
     #[derivative(Default(value = "default_framing_message_based()"))]
     pub framing: FramingConfig,
@@ -133,6 +167,22 @@ const fn default_retry_delay() -> f64 {
     1.0
 }
 
+const fn default_max_outstanding_messages() -> i64 {
+    1000
+}
+
+const fn default_max_outstanding_bytes() -> i64 {
+    100 * 1024 * 1024
+}
+
+const fn default_keepalive() -> f64 {
+    60.0
+}
+
+const fn default_max_lease_duration() -> u64 {
+    3600
+}
+
 #[async_trait::async_trait]
 #[typetag::serde(name = "gcp_pubsub")]
 impl SourceConfig for PubsubConfig {
@@ -142,6 +192,9 @@ impl SourceConfig for PubsubConfig {
         {
             return Err(PubsubError::InvalidAckDeadline.into());
         }
+        if self.keepalive_secs <= 0.0 {
+            return Err(PubsubError::InvalidKeepalive.into());
+        }
 
         let credentials = if self.skip_authentication {
             None
@@ -165,7 +218,15 @@ impl SourceConfig for PubsubConfig {
             shutdown: cx.shutdown,
             out: cx.out,
             ack_deadline_seconds: self.ack_deadline_seconds,
-            ack_ids: Default::default(),
+            max_outstanding_messages: self.max_outstanding_messages,
+            max_outstanding_bytes: self.max_outstanding_bytes,
+            keepalive: Duration::from_secs_f64(self.keepalive_secs),
+            max_lease_duration: Duration::from_secs(self.max_lease_duration_secs),
+            ack_ids: Vec::new(),
+            modify_deadlines: Vec::new(),
+            leases: HashMap::new(),
+            exactly_once: false,
+            unconfirmed_ack_ids: HashMap::new(),
             retry_delay: Duration::from_secs_f64(self.retry_delay_seconds),
         }
         .run()
@@ -197,13 +258,25 @@ struct PubsubSource {
     acknowledgements: bool,
     tls: TlsSettings,
     ack_deadline_seconds: i32,
+    max_outstanding_messages: i64,
+    max_outstanding_bytes: i64,
+    keepalive: Duration,
+    max_lease_duration: Duration,
     shutdown: ShutdownSignal,
     out: SourceSender,
-    // The acknowledgement IDs are pulled out of the response message
-    // and then inserted into the request. However, the request is
-    // generated in a separate async task from the response handling,
-    // so the data needs to be shared this way.
-    ack_ids: Arc<Mutex<Vec<String>>>,
+    // The acknowledgements and the deadline modifications waiting to be
+    // sent on the stream, which are kept across streams.
+    ack_ids: Vec<String>,
+    modify_deadlines: Vec<(String, i32)>,
+    // The messages whose events are waiting to be delivered, along with
+    // when they were received, whose deadline is extended until then.
+    leases: HashMap<String, Instant>,
+    // Whether the subscription has exactly-once delivery enabled, in
+    // which case acknowledgements are confirmed by the server.
+    exactly_once: bool,
+    // The acknowledgements sent but not confirmed yet, along with when
+    // they were sent, which are sent again unless they're confirmed.
+    unconfirmed_ack_ids: HashMap<String, Instant>,
     retry_delay: Duration,
 }
 
@@ -274,9 +347,14 @@ impl PubsubSource {
             },
         );
 
+        // The acknowledgements of the events sent on the previous
+        // stream are lost with its finalizer, so their messages are
+        // left for the server to redeliver.
+        self.leases.clear();
+
         // Handle shutdown during startup, the streaming pull doesn't
         // start if there is no data in the subscription.
-        let request_stream = self.request_stream();
+        let (request_sender, request_stream) = self.request_stream();
         debug!("Starting streaming pull.");
         let stream = tokio::select! {
             _ = &mut self.shutdown => return State::Shutdown,
@@ -292,8 +370,13 @@ impl PubsubSource {
 
         let (finalizer, mut ack_stream) =
             Finalizer::maybe_new(self.acknowledgements, self.shutdown.clone());
+        // Leases are extended halfway through their deadline.
+        let lease_period = Duration::from_secs(self.ack_deadline_seconds as u64 / 2);
+        let mut lease_interval = interval_at(Instant::now() + lease_period, lease_period);
+        let mut keepalive_interval = interval_at(Instant::now() + self.keepalive, self.keepalive);
 
         loop {
+            let mut keepalive = false;
             tokio::select! {
                 _ = &mut self.shutdown => return State::Shutdown,
                 _ = &mut token_generator.next() => {
@@ -301,16 +384,20 @@ impl PubsubSource {
                     break State::RetryNow;
                 },
                 receipts = ack_stream.next() => if let Some((status, receipts)) = receipts {
-                    if status == BatchStatus::Delivered {
-                        self.ack_ids.lock().await.extend(receipts);
-                    }
+                    self.handle_ack(status, receipts);
                 },
+                _ = lease_interval.tick() => self.extend_leases(),
+                _ = keepalive_interval.tick() => keepalive = true,
                 response = stream.next() => match response {
                     Some(Ok(response)) => self.handle_response(response, &finalizer).await,
                     Some(Err(error)) => break translate_error(error),
                     None => break State::RetryNow,
                 },
             }
+
+            if !self.send_requests(&request_sender, keepalive) {
+                break State::RetryNow;
+            }
         }
     }
 
@@ -326,28 +413,112 @@ impl PubsubSource {
         config
     }
 
-    fn request_stream(&self) -> impl Stream<Item = proto::StreamingPullRequest> + 'static {
+    /// Creates the stream of requests of a streaming pull, which starts
+    /// with the request opening it, followed by the requests sent
+    /// through the returned sender.
+    fn request_stream(
+        &self,
+    ) -> (
+        mpsc::UnboundedSender<proto::StreamingPullRequest>,
+        impl Stream<Item = proto::StreamingPullRequest> + 'static,
+    ) {
         // This data is only allowed in the first request
-        let mut subscription = Some(self.subscription.clone());
-        let mut client_id = Some(CLIENT_ID.clone());
-
-        let ack_ids = Arc::clone(&self.ack_ids);
-        let stream_ack_deadline_seconds = self.ack_deadline_seconds;
-        stream::repeat(()).then(move |()| {
-            let ack_ids = Arc::clone(&ack_ids);
-            let subscription = subscription.take().unwrap_or_default();
-            let client_id = client_id.take().unwrap_or_default();
-            async move {
-                let mut ack_ids = ack_ids.lock().await;
-                proto::StreamingPullRequest {
-                    subscription,
-                    client_id,
-                    ack_ids: std::mem::take(ack_ids.as_mut()),
-                    stream_ack_deadline_seconds,
-                    ..Default::default()
-                }
+        let initial = proto::StreamingPullRequest {
+            subscription: self.subscription.clone(),
+            client_id: CLIENT_ID.clone(),
+            stream_ack_deadline_seconds: self.ack_deadline_seconds,
+            max_outstanding_messages: self.max_outstanding_messages,
+            max_outstanding_bytes: self.max_outstanding_bytes,
+            ..Default::default()
+        };
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let stream =
+            stream::once(future::ready(initial)).chain(UnboundedReceiverStream::new(receiver));
+        (sender, stream)
+    }
+
+    /// Sends the pending acknowledgements and deadline modifications,
+    /// or an empty request keeping the stream alive, returning whether
+    /// the stream is still open.
+    fn send_requests(
+        &mut self,
+        sender: &mpsc::UnboundedSender<proto::StreamingPullRequest>,
+        keepalive: bool,
+    ) -> bool {
+        if self.ack_ids.is_empty() && self.modify_deadlines.is_empty() && !keepalive {
+            return true;
+        }
+
+        let now = Instant::now();
+        if self.exactly_once {
+            for ack_id in &self.ack_ids {
+                self.unconfirmed_ack_ids.insert(ack_id.clone(), now);
             }
-        })
+        }
+
+        let mut ack_ids = std::mem::take(&mut self.ack_ids);
+        let mut modify_deadlines = std::mem::take(&mut self.modify_deadlines);
+        loop {
+            let (modify_deadline_ack_ids, modify_deadline_seconds) =
+                drain_front(&mut modify_deadlines, MAX_IDS_PER_REQUEST)
+                    .into_iter()
+                    .unzip();
+            let request = proto::StreamingPullRequest {
+                ack_ids: drain_front(&mut ack_ids, MAX_IDS_PER_REQUEST),
+                modify_deadline_ack_ids,
+                modify_deadline_seconds,
+                stream_ack_deadline_seconds: self.ack_deadline_seconds,
+                ..Default::default()
+            };
+            if sender.send(request).is_err() {
+                return false;
+            }
+            if ack_ids.is_empty() && modify_deadlines.is_empty() {
+                return true;
+            }
+        }
+    }
+
+    fn handle_ack(&mut self, status: BatchStatus, receipts: Vec<String>) {
+        for ack_id in &receipts {
+            self.leases.remove(ack_id);
+        }
+        match status {
+            BatchStatus::Delivered => self.ack_ids.extend(receipts),
+            // Setting the deadline of the messages to zero has the
+            // server redeliver them right away.
+            BatchStatus::Errored => self
+                .modify_deadlines
+                .extend(receipts.into_iter().map(|ack_id| (ack_id, 0))),
+            // Rejected events would likely be rejected again, so their
+            // messages are left to expire, which has the server
+            // redeliver them after their deadline, or with the backoff
+            // of the retry policy of the subscription, rather than in
+            // a loop.
+            BatchStatus::Rejected => {}
+        }
+    }
+
+    /// Extends the deadline of the messages waiting for their events
+    /// to be delivered, until the maximum lease duration, and sends
+    /// again the acknowledgements that weren't confirmed in time.
+    fn extend_leases(&mut self) {
+        let now = Instant::now();
+        let max_lease_duration = self.max_lease_duration;
+        self.leases
+            .retain(|_, received| now.duration_since(*received) < max_lease_duration);
+        let deadline = self.ack_deadline_seconds;
+        self.modify_deadlines
+            .extend(self.leases.keys().map(|ack_id| (ack_id.clone(), deadline)));
+
+        let retry_after = Duration::from_secs(deadline as u64 / 2);
+        let unconfirmed = self
+            .unconfirmed_ack_ids
+            .iter()
+            .filter(|(_, sent)| now.duration_since(**sent) >= retry_after)
+            .map(|(ack_id, _)| ack_id.clone())
+            .collect::<Vec<_>>();
+        self.ack_ids.extend(unconfirmed);
     }
 
     async fn handle_response(
@@ -360,6 +531,26 @@ impl PubsubSource {
             protocol: self.uri.scheme().map(Scheme::as_str).unwrap_or("http"),
         });
 
+        if let Some(properties) = &response.subscription_properties {
+            self.exactly_once = properties.exactly_once_delivery_enabled;
+            if !self.exactly_once {
+                self.unconfirmed_ack_ids.clear();
+            }
+        }
+        if let Some(confirmation) = response.acknowledge_confirmation {
+            self.handle_acknowledge_confirmation(confirmation);
+        }
+        if let Some(confirmation) = response.modify_ack_deadline_confirmation {
+            // The messages whose lease couldn't be extended are
+            // redelivered, whatever happens to their events.
+            for ack_id in &confirmation.invalid_ack_ids {
+                self.leases.remove(ack_id);
+            }
+        }
+        if response.received_messages.is_empty() {
+            return;
+        }
+
         let (batch, notifier) = BatchNotifier::maybe_new_with_receiver(self.acknowledgements);
         let (events, ids) = self.parse_messages(response.received_messages, batch).await;
 
@@ -367,15 +558,39 @@ impl PubsubSource {
         match self.out.send_batch(events).await {
             Err(error) => emit!(StreamClosedError { error, count }),
             Ok(()) => match notifier {
-                None => self.ack_ids.lock().await.extend(ids),
-                Some(notifier) => finalizer
-                    .as_ref()
-                    .expect("Finalizer must have been set up for acknowledgements")
-                    .add(ids, notifier),
+                None => self.ack_ids.extend(ids),
+                Some(notifier) => {
+                    let now = Instant::now();
+                    for ack_id in &ids {
+                        self.leases.insert(ack_id.clone(), now);
+                    }
+                    finalizer
+                        .as_ref()
+                        .expect("Finalizer must have been set up for acknowledgements")
+                        .add(ids, notifier)
+                }
             },
         }
     }
 
+    fn handle_acknowledge_confirmation(
+        &mut self,
+        confirmation: proto::streaming_pull_response::AcknowledgeConfirmation,
+    ) {
+        for ack_id in &confirmation.ack_ids {
+            self.unconfirmed_ack_ids.remove(ack_id);
+        }
+        let failed = confirmation
+            .invalid_ack_ids
+            .iter()
+            .chain(confirmation.unordered_ack_ids.iter())
+            .filter(|ack_id| self.unconfirmed_ack_ids.remove(*ack_id).is_some())
+            .count();
+        if failed > 0 {
+            emit!(GcpPubsubAcknowledgementError { count: failed });
+        }
+    }
+
     async fn parse_messages(
         &self,
         response: Vec<proto::ReceivedMessage>,
@@ -429,6 +644,11 @@ impl PubsubSource {
     }
 }
 
+fn drain_front<T>(items: &mut Vec<T>, count: usize) -> Vec<T> {
+    let count = count.min(items.len());
+    items.drain(..count).collect()
+}
+
 fn translate_error(error: tonic::Status) -> State {
     // GCP occasionally issues a connection reset
     // in the middle of the streaming pull. This
@@ -462,6 +682,137 @@ mod tests {
     fn generate_config() {
         crate::test_util::test_generate_config::<PubsubConfig>();
     }
+
+    fn source() -> (
+        PubsubSource,
+        mpsc::UnboundedSender<proto::StreamingPullRequest>,
+        mpsc::UnboundedReceiver<proto::StreamingPullRequest>,
+    ) {
+        let config = PubsubConfig {
+            ack_deadline_seconds: 60,
+            ..Default::default()
+        };
+        let source = PubsubSource {
+            endpoint: PUBSUB_URL.into(),
+            uri: Uri::from_static(PUBSUB_URL),
+            credentials: None,
+            subscription: "projects/vector/subscriptions/logs".into(),
+            decoder: DecodingConfig::new(config.framing, config.decoding).build(),
+            acknowledgements: true,
+            tls: TlsSettings::default(),
+            ack_deadline_seconds: config.ack_deadline_seconds,
+            max_outstanding_messages: config.max_outstanding_messages,
+            max_outstanding_bytes: config.max_outstanding_bytes,
+            keepalive: Duration::from_secs_f64(config.keepalive_secs),
+            max_lease_duration: Duration::from_secs(config.max_lease_duration_secs),
+            shutdown: ShutdownSignal::noop(),
+            out: SourceSender::new_test().0,
+            ack_ids: Vec::new(),
+            modify_deadlines: Vec::new(),
+            leases: HashMap::new(),
+            exactly_once: false,
+            unconfirmed_ack_ids: HashMap::new(),
+            retry_delay: Duration::from_secs(1),
+        };
+        let (sender, receiver) = mpsc::unbounded_channel();
+        (source, sender, receiver)
+    }
+
+    fn ids(prefix: &str, count: usize) -> Vec<String> {
+        (0..count).map(|i| format!("{}-{}", prefix, i)).collect()
+    }
+
+    #[tokio::test]
+    async fn splits_acknowledgements_across_requests() {
+        let (mut source, sender, mut receiver) = source();
+        source.handle_ack(BatchStatus::Delivered, ids("ack", MAX_IDS_PER_REQUEST + 1));
+
+        assert!(source.send_requests(&sender, false));
+        assert_eq!(
+            receiver.recv().await.unwrap().ack_ids.len(),
+            MAX_IDS_PER_REQUEST
+        );
+        assert_eq!(receiver.recv().await.unwrap().ack_ids.len(), 1);
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn redelivers_failed_messages_right_away() {
+        let (mut source, sender, mut receiver) = source();
+        source.leases.insert("ack-0".into(), Instant::now());
+        source.handle_ack(BatchStatus::Errored, ids("ack", 1));
+        assert!(source.leases.is_empty());
+
+        assert!(source.send_requests(&sender, false));
+        let request = receiver.recv().await.unwrap();
+        assert!(request.ack_ids.is_empty());
+        assert_eq!(request.modify_deadline_ack_ids, ids("ack", 1));
+        assert_eq!(request.modify_deadline_seconds, vec![0]);
+    }
+
+    #[tokio::test]
+    async fn lets_rejected_messages_expire() {
+        let (mut source, sender, mut receiver) = source();
+        source.leases.insert("ack-0".into(), Instant::now());
+        source.handle_ack(BatchStatus::Rejected, ids("ack", 1));
+        assert!(source.leases.is_empty());
+
+        source.extend_leases();
+        assert!(source.send_requests(&sender, false));
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn extends_leases_of_outstanding_messages() {
+        let (mut source, sender, mut receiver) = source();
+        source.leases.insert("ack-0".into(), Instant::now());
+        source
+            .leases
+            .insert("ack-1".into(), Instant::now() - Duration::from_secs(7200));
+        source.extend_leases();
+        assert_eq!(source.leases.len(), 1);
+
+        assert!(source.send_requests(&sender, false));
+        let request = receiver.recv().await.unwrap();
+        assert_eq!(request.modify_deadline_ack_ids, ids("ack", 1));
+        assert_eq!(request.modify_deadline_seconds, vec![60]);
+    }
+
+    #[tokio::test]
+    async fn resends_unconfirmed_acknowledgements() {
+        let (mut source, sender, mut receiver) = source();
+        source.exactly_once = true;
+        source.handle_ack(BatchStatus::Delivered, ids("ack", 3));
+        assert!(source.send_requests(&sender, false));
+        receiver.recv().await.unwrap();
+        assert_eq!(source.unconfirmed_ack_ids.len(), 3);
+
+        source.handle_acknowledge_confirmation(
+            proto::streaming_pull_response::AcknowledgeConfirmation {
+                ack_ids: vec!["ack-0".into()],
+                invalid_ack_ids: vec!["ack-1".into()],
+                unordered_ack_ids: Vec::new(),
+            },
+        );
+        assert_eq!(
+            source.unconfirmed_ack_ids.keys().collect::<Vec<_>>(),
+            vec!["ack-2"]
+        );
+
+        *source.unconfirmed_ack_ids.get_mut("ack-2").unwrap() -= Duration::from_secs(60);
+        source.extend_leases();
+        assert_eq!(source.ack_ids, vec!["ack-2".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn sends_keepalives_without_pending_requests() {
+        let (mut source, sender, mut receiver) = source();
+        assert!(source.send_requests(&sender, false));
+        assert!(receiver.try_recv().is_err());
+
+        assert!(source.send_requests(&sender, true));
+        assert!(receiver.try_recv().is_ok());
+    }
 }
 
 #[cfg(all(test, feature = "gcp-pubsub-integration-tests"))]
@@ -0,0 +1,286 @@
+use std::path::PathBuf;
+
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use http::Request;
+use hyper::Body;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{
+    config::{DataType, GenerateConfig, Output, SourceConfig, SourceContext, SourceDescription},
+    gcp::{GcpAuthConfig, GcpCredentials, Scope},
+    http::HttpClient,
+    sources::util::audit_log::{
+        default_interval_secs, default_lookback_secs, parse_timestamp, ApiClient, AuditLogApi,
+        Checkpointer, Page, Poller,
+    },
+    tls::{TlsConfig, TlsSettings},
+};
+
+const REPORTS_URL: &str = "https://admin.googleapis.com/admin/reports/v1/activity/users/all/";
+
+/// The largest page the Reports API returns.
+const PAGE_SIZE: &str = "1000";
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct GoogleWorkspaceConfig {
+    /// The path to the key of a service account granted domain-wide delegation.
+    credentials_path: Option<String>,
+    /// The email address of the administrator the service account acts on behalf of.
+    subject: String,
+    /// The application whose activities are collected, such as `login`, `admin` or `drive`.
+    #[serde(default = "default_application")]
+    application: String,
+    #[serde(default = "default_interval_secs")]
+    interval_secs: u64,
+    #[serde(default = "default_lookback_secs")]
+    lookback_secs: u64,
+    #[serde(default = "default_delay_secs")]
+    delay_secs: u64,
+    data_dir: Option<PathBuf>,
+    tls: Option<TlsConfig>,
+}
+
+fn default_application() -> String {
+    "login".to_owned()
+}
+
+const fn default_delay_secs() -> u64 {
+    180
+}
+
+inventory::submit! {
+    SourceDescription::new::<GoogleWorkspaceConfig>("google_workspace")
+}
+
+impl GenerateConfig for GoogleWorkspaceConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"credentials_path = "/path/to/credentials.json"
+            subject = "admin@example.com"
+            application = "login""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "google_workspace")]
+impl SourceConfig for GoogleWorkspaceConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let data_dir = cx
+            .globals
+            .resolve_and_make_data_subdir(self.data_dir.as_ref(), cx.key.id())?;
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls, &cx.proxy)?;
+
+        let auth = GcpAuthConfig {
+            api_key: None,
+            credentials_path: self.credentials_path.clone(),
+        };
+        let credentials = auth
+            .make_delegated_credentials(Scope::AdminReportsAuditReadOnly, self.subject.clone())
+            .await?;
+        credentials.spawn_regenerate_token();
+
+        let api = Reports {
+            url: Url::parse(REPORTS_URL)?.join(&format!("applications/{}", self.application))?,
+            credentials,
+            lookback: Duration::seconds(self.lookback_secs as i64),
+            delay: Duration::seconds(self.delay_secs as i64),
+            window: None,
+        };
+        let poller = Poller {
+            client: ApiClient::new(client),
+            checkpointer: Checkpointer::new(data_dir),
+            interval: std::time::Duration::from_secs(self.interval_secs),
+            source_type: "google_workspace",
+            timestamp_field: "id.time",
+        };
+        Ok(Box::pin(poller.run(api, cx.out, cx.shutdown)))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "google_workspace"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+/// The activities of the [Reports API]. They're listed newest first, so each poll collects
+/// every page of the activities that happened in a window of time, and the cursor is the end
+/// of the last window collected.
+///
+/// [Reports API]: https://developers.google.com/admin-sdk/reports/reference/rest/v1/activities/list
+struct Reports {
+    url: Url,
+    credentials: GcpCredentials,
+    lookback: Duration,
+    /// Activities can take a while to show up, so windows end this far in the past.
+    delay: Duration,
+    /// The window being collected, when its pages haven't all been fetched yet.
+    window: Option<Window>,
+}
+
+struct Window {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    page_token: Option<String>,
+}
+
+impl Window {
+    /// The window following the cursor, up to `delay` ago, unless it's empty.
+    fn next(
+        cursor: Option<&str>,
+        now: DateTime<Utc>,
+        lookback: Duration,
+        delay: Duration,
+    ) -> Option<Self> {
+        let end = now - delay;
+        let start = cursor
+            .and_then(parse_timestamp)
+            .unwrap_or_else(|| end - lookback);
+        (start < end).then(|| Self {
+            start,
+            end,
+            page_token: None,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Activities {
+    #[serde(default)]
+    items: Vec<serde_json::Value>,
+    next_page_token: Option<String>,
+}
+
+impl Reports {
+    fn page_url(&self, window: &Window) -> Url {
+        let mut url = self.url.clone();
+        {
+            let mut query = url.query_pairs_mut();
+            query
+                .append_pair("startTime", &format_time(window.start))
+                .append_pair("endTime", &format_time(window.end))
+                .append_pair("maxResults", PAGE_SIZE);
+            if let Some(page_token) = &window.page_token {
+                query.append_pair("pageToken", page_token);
+            }
+        }
+        url
+    }
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+#[async_trait::async_trait]
+impl AuditLogApi for Reports {
+    async fn fetch(&mut self, client: &mut ApiClient, cursor: Option<&str>) -> crate::Result<Page> {
+        // A window whose collection failed is started over.
+        let mut window = match self.window.take() {
+            Some(window) => window,
+            None => match Window::next(cursor, Utc::now(), self.lookback, self.delay) {
+                Some(window) => window,
+                None => {
+                    return Ok(Page {
+                        records: Vec::new(),
+                        cursor: None,
+                        more: false,
+                    })
+                }
+            },
+        };
+
+        let url = self.page_url(&window);
+        let credentials = &self.credentials;
+        let response = client
+            .send(|| {
+                let mut request = Request::get(url.as_str())
+                    .body(Body::empty())
+                    .expect("valid request");
+                credentials.apply(&mut request);
+                request
+            })
+            .await?;
+        let activities: Activities = response.json()?;
+
+        Ok(match activities.next_page_token {
+            Some(page_token) => {
+                window.page_token = Some(page_token);
+                self.window = Some(window);
+                Page {
+                    records: activities.items,
+                    cursor: None,
+                    more: true,
+                }
+            }
+            None => Page {
+                records: activities.items,
+                cursor: Some(format_time(window.end)),
+                more: false,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<GoogleWorkspaceConfig>();
+    }
+
+    #[test]
+    fn parses_activities() {
+        let activities: Activities = serde_json::from_str(
+            r#"{
+                "kind": "admin#reports#activities",
+                "items": [{ "id": { "time": "2022-06-01T12:30:15.000Z" } }],
+                "nextPageToken": "A:1654086615000000:-123:login:-456"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(activities.items.len(), 1);
+        assert!(activities.next_page_token.is_some());
+
+        let activities: Activities =
+            serde_json::from_str(r#"{ "kind": "admin#reports#activities" }"#).unwrap();
+        assert!(activities.items.is_empty());
+        assert!(activities.next_page_token.is_none());
+    }
+
+    #[test]
+    fn windows_follow_the_cursor() {
+        let now = Utc.ymd(2022, 6, 1).and_hms(13, 0, 0);
+        let next = |cursor| Window::next(cursor, now, Duration::hours(1), Duration::minutes(3));
+
+        let window = next(None).unwrap();
+        assert_eq!(window.start, Utc.ymd(2022, 6, 1).and_hms(11, 57, 0));
+        assert_eq!(window.end, Utc.ymd(2022, 6, 1).and_hms(12, 57, 0));
+
+        let window = next(Some("2022-06-01T12:30:00.000Z")).unwrap();
+        assert_eq!(window.start, Utc.ymd(2022, 6, 1).and_hms(12, 30, 0));
+        assert_eq!(
+            format_time(window.end),
+            "2022-06-01T12:57:00.000Z",
+            "the end of a window is the cursor of the next one"
+        );
+
+        assert!(next(Some("2022-06-01T12:58:00.000Z")).is_none());
+    }
+}
@@ -0,0 +1,253 @@
+//! A gRPC server exposing a single method, whose types are described by a user-provided file
+//! descriptor set, as generated by `protoc --include_imports --descriptor_set_out`.
+
+use std::{convert::Infallible, net::SocketAddr, path::PathBuf};
+
+use futures::{FutureExt, StreamExt};
+use hyper::{server::accept, service::make_service_fn, Server};
+use prost::Message;
+use prost_reflect::DynamicMessage;
+use serde::{Deserialize, Serialize};
+use tracing::{Instrument, Span};
+
+use crate::{
+    config::{
+        AcknowledgementsConfig, DataType, GenerateConfig, Output, Resource, SourceConfig,
+        SourceContext, SourceDescription,
+    },
+    grpc,
+    internal_events::TcpBytesReceived,
+    serde::bool_or_struct,
+    shutdown::ShutdownSignalToken,
+    sources::{util::AfterReadExt as _, Source},
+    tls::{MaybeTlsSettings, TlsEnableableConfig},
+};
+
+mod service;
+
+use service::MethodService;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct GrpcConfig {
+    address: SocketAddr,
+    /// The path of the file descriptor set describing the service.
+    descriptor_set: PathBuf,
+    /// The method to expose, as in `package.Service/Method`.
+    method: String,
+    #[serde(default)]
+    tls: Option<TlsEnableableConfig>,
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+inventory::submit! {
+    SourceDescription::new::<GrpcConfig>("grpc")
+}
+
+impl GenerateConfig for GrpcConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            address: "0.0.0.0:50051".parse().unwrap(),
+            descriptor_set: PathBuf::from("/etc/vector/service.desc"),
+            method: "package.Service/Method".to_owned(),
+            tls: None,
+            acknowledgements: Default::default(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "grpc")]
+impl SourceConfig for GrpcConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<Source> {
+        let method = grpc::load_method(&self.descriptor_set, &self.method).await?;
+
+        let service = MethodService {
+            path: grpc::method_path(&method).into(),
+            client_streaming: method.is_client_streaming(),
+            input: method.input(),
+            response: DynamicMessage::new(method.output()).encode_to_vec().into(),
+            out: cx.out,
+            acknowledgements: cx.do_acknowledgements(&self.acknowledgements),
+        };
+        let tls_settings = MaybeTlsSettings::from_config(&self.tls, true)?;
+        let address = self.address;
+        let shutdown = cx.shutdown;
+
+        Ok(Box::pin(async move {
+            let span = Span::current();
+            let (tx, rx) = tokio::sync::oneshot::channel::<ShutdownSignalToken>();
+            let listener = tls_settings.bind(&address).await.map_err(|error| {
+                error!(message = "Failed to bind the gRPC server.", %error);
+            })?;
+            let stream = listener.accept_stream().map(|result| {
+                result.map(|socket| {
+                    let peer_addr = socket.peer_addr();
+                    socket.after_read(move |byte_size| {
+                        emit!(TcpBytesReceived {
+                            byte_size,
+                            peer_addr,
+                        })
+                    })
+                })
+            });
+            let make_service = make_service_fn(move |_| {
+                let service = service.clone();
+                async move { Ok::<_, Infallible>(service) }
+            });
+
+            info!(message = "Building gRPC server.", %address);
+            Server::builder(accept::from_stream(stream))
+                .http2_only(true)
+                .serve(make_service)
+                .with_graceful_shutdown(shutdown.map(|token| tx.send(token).unwrap()))
+                .instrument(span)
+                .await
+                .map_err(|error| {
+                    error!(message = "gRPC server failed.", %error);
+                })?;
+
+            drop(rx.await);
+            Ok(())
+        }))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "grpc"
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![Resource::tcp(self.address)]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use futures::Stream;
+    use http::uri::PathAndQuery;
+    use tonic::{transport::Endpoint, Code, Request};
+
+    use super::*;
+    use crate::{
+        config::log_schema,
+        event::{Event, EventStatus},
+        grpc::{tests::descriptor_set, BytesCodec},
+        test_util::{
+            collect_ready,
+            components::{assert_source_compliance, init_test, SOURCE_TAGS},
+            next_addr, wait_for_tcp,
+        },
+        SourceSender,
+    };
+
+    fn log_message(message: &str) -> Bytes {
+        let mut bytes = vec![0x0a, message.len() as u8];
+        bytes.extend_from_slice(message.as_bytes());
+        bytes.into()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<GrpcConfig>();
+    }
+
+    async fn source(status: EventStatus) -> (impl Stream<Item = Event> + Unpin, SocketAddr) {
+        init_test();
+        let path = tempfile::tempdir().unwrap().into_path().join("test.desc");
+        std::fs::write(&path, descriptor_set().encode_to_vec()).unwrap();
+
+        let address = next_addr();
+        let config = GrpcConfig {
+            address,
+            descriptor_set: path,
+            method: "test.v1.LogService/Push".to_owned(),
+            tls: None,
+            acknowledgements: true.into(),
+        };
+        let (sender, recv) = SourceSender::new_test_finalize(status);
+        let source = config
+            .build(SourceContext::new_test(sender, None))
+            .await
+            .unwrap();
+        tokio::spawn(source);
+        wait_for_tcp(address).await;
+        (recv, address)
+    }
+
+    async fn push(address: SocketAddr, path: &'static str, message: Bytes) -> Result<(), Code> {
+        let channel = Endpoint::from_shared(format!("http://{}", address))
+            .unwrap()
+            .connect()
+            .await
+            .unwrap();
+        let mut client = tonic::client::Grpc::new(channel);
+        client.ready().await.unwrap();
+        client
+            .unary(
+                Request::new(message),
+                PathAndQuery::from_static(path),
+                BytesCodec,
+            )
+            .await
+            .map(|_| ())
+            .map_err(|status| status.code())
+    }
+
+    #[tokio::test]
+    async fn receives_messages() {
+        assert_source_compliance(&SOURCE_TAGS, async {
+            let (mut rx, address) = source(EventStatus::Delivered).await;
+
+            push(address, "/test.v1.LogService/Push", log_message("hello"))
+                .await
+                .unwrap();
+
+            let events = collect_ready(&mut rx).await;
+            assert_eq!(events.len(), 1);
+            let log = events[0].as_log();
+            assert_eq!(log["message"], "hello".into());
+            assert_eq!(log[log_schema().source_type_key()], "grpc".into());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn reports_delivery_failures() {
+        let (_rx, address) = source(EventStatus::Rejected).await;
+
+        assert_eq!(
+            push(address, "/test.v1.LogService/Push", log_message("hello")).await,
+            Err(Code::DataLoss)
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_requests() {
+        let (_rx, address) = source(EventStatus::Delivered).await;
+
+        assert_eq!(
+            push(address, "/test.v1.LogService/Other", log_message("hello")).await,
+            Err(Code::Unimplemented)
+        );
+        assert_eq!(
+            push(
+                address,
+                "/test.v1.LogService/Push",
+                Bytes::from_static(b"\xff")
+            )
+            .await,
+            Err(Code::InvalidArgument)
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use std::{
+    convert::Infallible,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use chrono::Utc;
+use futures::{future::BoxFuture, FutureExt};
+use hyper::Body;
+use prost_reflect::{DynamicMessage, MessageDescriptor};
+use tonic::{
+    body::BoxBody,
+    server::{ClientStreamingService, Grpc, UnaryService},
+    Request, Response, Status, Streaming,
+};
+use vector_core::{
+    event::{BatchNotifier, BatchStatus, BatchStatusReceiver},
+    ByteSizeOf,
+};
+
+use crate::{
+    config::log_schema,
+    event::{Event, LogEvent},
+    grpc::BytesCodec,
+    internal_events::{EventsReceived, GrpcMessageDecodeError, StreamClosedError},
+    sources::util::message_to_value,
+    SourceSender,
+};
+
+/// Serves a single gRPC method, whose request messages are decoded with the descriptor of its
+/// input type, and whose responses are the empty messages of its output type.
+#[derive(Clone)]
+pub(super) struct MethodService {
+    /// The path of the method, as in `/package.Service/Method`.
+    pub(super) path: Arc<str>,
+    pub(super) client_streaming: bool,
+    pub(super) input: MessageDescriptor,
+    pub(super) response: Bytes,
+    pub(super) out: SourceSender,
+    pub(super) acknowledgements: bool,
+}
+
+impl tower::Service<http::Request<Body>> for MethodService {
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<Body>) -> Self::Future {
+        if request.uri().path() != &*self.path {
+            let status = Status::unimplemented(format!("Unknown method {}", request.uri().path()));
+            return futures::future::ok(status.to_http()).boxed();
+        }
+
+        let handler = Handler(self.clone());
+        let mut grpc = Grpc::new(BytesCodec);
+        if self.client_streaming {
+            async move { Ok(grpc.client_streaming(handler, request).await) }.boxed()
+        } else {
+            async move { Ok(grpc.unary(handler, request).await) }.boxed()
+        }
+    }
+}
+
+impl MethodService {
+    /// Decodes a request message and sends its event, returning the receiver of its status
+    /// when acknowledgements are enabled.
+    async fn send(&self, message: Bytes) -> Result<Option<BatchStatusReceiver>, Status> {
+        let message = DynamicMessage::decode(self.input.clone(), message).map_err(|error| {
+            let status = Status::invalid_argument(format!("Invalid message: {}", error));
+            emit!(GrpcMessageDecodeError {
+                error,
+                message_type: self.input.full_name(),
+            });
+            status
+        })?;
+
+        let mut events = vec![create_event(&message)];
+        emit!(EventsReceived {
+            count: 1,
+            byte_size: events.size_of(),
+        });
+
+        let receiver = BatchNotifier::maybe_apply_to_events(self.acknowledgements, &mut events);
+        self.out.clone().send_batch(events).await.map_err(|error| {
+            emit!(StreamClosedError { error, count: 1 });
+            Status::unavailable("Source is shutting down")
+        })?;
+        Ok(receiver)
+    }
+
+    /// Waits for the events of a request to be delivered, before responding.
+    async fn respond(
+        &self,
+        receivers: Vec<BatchStatusReceiver>,
+    ) -> Result<Response<Bytes>, Status> {
+        for receiver in receivers {
+            match receiver.await {
+                BatchStatus::Delivered => (),
+                BatchStatus::Errored => return Err(Status::internal("Delivery error")),
+                BatchStatus::Rejected => return Err(Status::data_loss("Delivery failed")),
+            }
+        }
+        Ok(Response::new(self.response.clone()))
+    }
+}
+
+fn create_event(message: &DynamicMessage) -> Event {
+    let mut log = LogEvent::from(message_to_value(message));
+    log.try_insert(log_schema().source_type_key(), Bytes::from("grpc"));
+    log.try_insert(log_schema().timestamp_key(), Utc::now());
+    log.into()
+}
+
+struct Handler(MethodService);
+
+impl UnaryService<Bytes> for Handler {
+    type Response = Bytes;
+    type Future = BoxFuture<'static, Result<Response<Bytes>, Status>>;
+
+    fn call(&mut self, request: Request<Bytes>) -> Self::Future {
+        let service = self.0.clone();
+        async move {
+            let receiver = service.send(request.into_inner()).await?;
+            service.respond(receiver.into_iter().collect()).await
+        }
+        .boxed()
+    }
+}
+
+impl ClientStreamingService<Bytes> for Handler {
+    type Response = Bytes;
+    type Future = BoxFuture<'static, Result<Response<Bytes>, Status>>;
+
+    fn call(&mut self, request: Request<Streaming<Bytes>>) -> Self::Future {
+        let service = self.0.clone();
+        async move {
+            let mut messages = request.into_inner();
+            let mut receivers = Vec::new();
+            while let Some(message) = messages.message().await? {
+                receivers.extend(service.send(message).await?);
+            }
+            service.respond(receivers).await
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prost_reflect::DescriptorPool;
+
+    use super::*;
+    use crate::{event::Value, grpc::tests::descriptor_set};
+
+    #[test]
+    fn creates_events() {
+        let pool = DescriptorPool::from_file_descriptor_set(descriptor_set()).unwrap();
+        let descriptor = pool.get_message_by_name("test.v1.Log").unwrap();
+        // The fields `message` and `severity`.
+        let mut bytes = vec![0x0a, 0x05];
+        bytes.extend_from_slice(b"hello");
+        bytes.extend_from_slice(&[0x10, 0x03]);
+
+        let message = DynamicMessage::decode(descriptor, bytes.as_slice()).unwrap();
+        let event = create_event(&message);
+        let log = event.as_log();
+
+        assert_eq!(log["message"], "hello".into());
+        assert_eq!(log["severity"], Value::Integer(3));
+        assert_eq!(log[log_schema().source_type_key()], "grpc".into());
+        assert!(log.get(log_schema().timestamp_key()).is_some());
+    }
+}
@@ -1,4 +1,8 @@
-use std::{collections::HashMap, net::SocketAddr};
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::SocketAddr,
+    sync::Arc,
+};
 
 use bytes::{Bytes, BytesMut};
 use chrono::Utc;
@@ -7,7 +11,7 @@ use codecs::{
     BytesDecoderConfig, BytesDeserializerConfig, JsonDeserializerConfig,
     NewlineDelimitedDecoderConfig,
 };
-use http::StatusCode;
+use http::{header::CONTENT_TYPE, StatusCode};
 use lookup::path;
 use serde::{Deserialize, Serialize};
 use tokio_util::codec::Decoder as _;
@@ -20,6 +24,7 @@ use crate::{
         SourceConfig, SourceContext, SourceDescription,
     },
     event::{Event, Value},
+    http::{TraceParent, TRACEPARENT},
     serde::{bool_or_struct, default_decoding},
     sources::util::{
         add_query_parameters, Encoding, ErrorMessage, HttpSource, HttpSourceAuthConfig,
@@ -63,6 +68,65 @@ pub(super) struct SimpleHttpConfig {
     decoding: Option<DeserializerConfig>,
     #[serde(default, deserialize_with = "bool_or_struct")]
     acknowledgements: AcknowledgementsConfig,
+    /// The request paths, under `path`, whose events are sent to the named
+    /// outputs instead of the default one.
+    #[serde(default)]
+    routes: BTreeMap<String, String>,
+    #[serde(default)]
+    response: ResponseConfig,
+    /// The request headers to keep in the metadata of the events.
+    #[serde(default)]
+    metadata_headers: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub(super) struct ResponseConfig {
+    #[serde(default = "default_response_code")]
+    code: u16,
+    body: Option<String>,
+    content_type: Option<String>,
+}
+
+impl Default for ResponseConfig {
+    fn default() -> Self {
+        Self {
+            code: default_response_code(),
+            body: None,
+            content_type: None,
+        }
+    }
+}
+
+const fn default_response_code() -> u16 {
+    200
+}
+
+impl ResponseConfig {
+    fn build(&self) -> crate::Result<Response> {
+        let code = StatusCode::from_u16(self.code)?;
+        if !code.is_success() {
+            return Err(format!("The response code {} is not a 2xx status code.", code).into());
+        }
+        let content_type = self
+            .content_type
+            .as_deref()
+            .map(HeaderValue::from_str)
+            .transpose()?;
+        Ok(Response {
+            code,
+            body: self.body.clone().map(Bytes::from).unwrap_or_default(),
+            content_type,
+        })
+    }
+}
+
+/// The response to the accepted requests.
+#[derive(Clone, Debug)]
+struct Response {
+    code: StatusCode,
+    body: Bytes,
+    content_type: Option<HeaderValue>,
 }
 
 inventory::submit! {
@@ -85,6 +149,9 @@ impl GenerateConfig for SimpleHttpConfig {
             framing: None,
             decoding: Some(default_decoding()),
             acknowledgements: AcknowledgementsConfig::default(),
+            routes: BTreeMap::new(),
+            response: ResponseConfig::default(),
+            metadata_headers: Vec::new(),
         })
         .unwrap()
     }
@@ -104,6 +171,17 @@ struct SimpleHttpSource {
     query_parameters: Vec<String>,
     path_key: String,
     decoder: Decoder,
+    strict_path: bool,
+    path: String,
+    /// The output name by request path.
+    routes: HashMap<String, String>,
+    response: Response,
+    metadata_headers: Vec<String>,
+}
+
+/// Drops the trailing slashes, so that `/logs/` and `/logs` are the same route.
+fn normalize_path(path: &str) -> &str {
+    path.trim_end_matches('/')
 }
 
 impl HttpSource for SimpleHttpSource {
@@ -114,6 +192,19 @@ impl HttpSource for SimpleHttpSource {
         query_parameters: HashMap<String, String>,
         request_path: &str,
     ) -> Result<Vec<Event>, ErrorMessage> {
+        // With routes, the server accepts any path under `path`, so the
+        // strictness is checked here.
+        if self.strict_path
+            && !self.routes.is_empty()
+            && normalize_path(request_path) != normalize_path(&self.path)
+            && self.output(request_path).is_none()
+        {
+            return Err(ErrorMessage::new(
+                StatusCode::NOT_FOUND,
+                "Not found".to_string(),
+            ));
+        }
+
         let mut decoder = self.decoder.clone();
         let mut events = Vec::new();
         let mut bytes = BytesMut::new();
@@ -134,6 +225,8 @@ impl HttpSource for SimpleHttpSource {
             }
         }
 
+        add_trace_parent(&mut events, &header_map);
+        add_metadata_headers(&mut events, &self.metadata_headers, &header_map);
         add_headers(&mut events, &self.headers, header_map);
         add_query_parameters(&mut events, &self.query_parameters, query_parameters);
         add_path(&mut events, self.path_key.as_str(), request_path);
@@ -148,6 +241,21 @@ impl HttpSource for SimpleHttpSource {
 
         Ok(events)
     }
+
+    fn output(&self, path: &str) -> Option<String> {
+        self.routes.get(normalize_path(path)).cloned()
+    }
+
+    fn response(&self) -> warp::reply::Response {
+        let mut response = warp::reply::Response::new(self.response.body.clone().into());
+        *response.status_mut() = self.response.code;
+        if let Some(content_type) = &self.response.content_type {
+            response
+                .headers_mut()
+                .insert(CONTENT_TYPE, content_type.clone());
+        }
+        response
+    }
 }
 
 #[async_trait::async_trait]
@@ -187,17 +295,37 @@ impl SourceConfig for SimpleHttpConfig {
         };
 
         let decoder = DecodingConfig::new(framing, decoding).build();
+        let routes = self
+            .routes
+            .iter()
+            .map(|(output, path)| (normalize_path(path).to_owned(), output.clone()))
+            .collect::<HashMap<_, _>>();
+        if routes.len() < self.routes.len() {
+            return Err("The `routes` must have distinct paths.".into());
+        }
+        if let Some(path) = routes
+            .keys()
+            .find(|path| !path.starts_with(normalize_path(&self.path)))
+        {
+            return Err(format!("The route path {:?} is not under `path`.", path).into());
+        }
         let source = SimpleHttpSource {
             headers: self.headers.clone(),
             query_parameters: self.query_parameters.clone(),
             path_key: self.path_key.clone(),
             decoder,
+            strict_path: self.strict_path,
+            path: self.path.clone(),
+            routes,
+            response: self.response.build()?,
+            metadata_headers: self.metadata_headers.clone(),
         };
         source.run(
             self.address,
             self.path.as_str(),
             self.method,
-            self.strict_path,
+            // The routes are matched by the source.
+            self.strict_path && self.routes.is_empty(),
             &self.tls,
             &self.auth,
             cx,
@@ -206,12 +334,18 @@ impl SourceConfig for SimpleHttpConfig {
     }
 
     fn outputs(&self) -> Vec<Output> {
-        vec![Output::default(
-            self.decoding
-                .as_ref()
-                .map(|d| d.output_type())
-                .unwrap_or(DataType::Log),
-        )]
+        let ty = self
+            .decoding
+            .as_ref()
+            .map(|d| d.output_type())
+            .unwrap_or(DataType::Log);
+        let mut outputs = vec![Output::default(ty)];
+        outputs.extend(
+            self.routes
+                .keys()
+                .map(|output| Output::default(ty).with_port(output.clone())),
+        );
+        outputs
     }
 
     fn source_type(&self) -> &'static str {
@@ -248,6 +382,42 @@ fn add_headers(events: &mut [Event], headers_config: &[String], headers: HeaderM
     }
 }
 
+fn add_metadata_headers(events: &mut [Event], header_names: &[String], headers: &HeaderMap) {
+    if header_names.is_empty() {
+        return;
+    }
+
+    let http_headers = header_names
+        .iter()
+        .filter_map(|name| {
+            let value = headers.get(name)?.to_str().ok()?;
+            Some((name.to_lowercase(), value.to_owned()))
+        })
+        .collect::<BTreeMap<_, _>>();
+    let http_headers = Arc::new(http_headers);
+    for event in events.iter_mut() {
+        event
+            .metadata_mut()
+            .set_http_headers(Some(Arc::clone(&http_headers)));
+    }
+}
+
+/// Keeps a valid W3C `traceparent` of the request on the events, so that sinks
+/// propagating trace context continue the sender's trace.
+fn add_trace_parent(events: &mut [Event], headers: &HeaderMap) {
+    let trace_parent = headers
+        .get(TRACEPARENT)
+        .and_then(|value| value.to_str().ok())
+        .and_then(TraceParent::parse)
+        .map(|trace_parent| Arc::<str>::from(trace_parent.to_string()));
+
+    if trace_parent.is_some() {
+        for event in events.iter_mut() {
+            event.metadata_mut().set_trace_parent(trace_parent.clone());
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use lookup::path;
@@ -262,15 +432,15 @@ mod tests {
         write::{GzEncoder, ZlibEncoder},
         Compression,
     };
-    use futures::Stream;
-    use http::{HeaderMap, Method};
+    use futures::{Stream, StreamExt};
+    use http::{HeaderMap, HeaderValue, Method};
     use pretty_assertions::assert_eq;
 
     use super::SimpleHttpConfig;
     use crate::sources::http::HttpMethod;
     use crate::{
         config::{log_schema, SourceConfig, SourceContext},
-        event::{Event, EventStatus, Value},
+        event::{Event, EventArray, EventContainer, EventStatus, Value},
         test_util::{
             components::{self, assert_source_compliance, HTTP_PUSH_SOURCE_TAGS},
             next_addr, spawn_collect_n, trace_init, wait_for_tcp,
@@ -323,6 +493,9 @@ mod tests {
                 framing,
                 decoding,
                 acknowledgements: acknowledgements.into(),
+                routes: BTreeMap::new(),
+                response: Default::default(),
+                metadata_headers: vec![],
             }
             .build(context)
             .await
@@ -780,6 +953,43 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn http_trace_parent() {
+        let trace_parent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let mut events = assert_source_compliance(&HTTP_PUSH_SOURCE_TAGS, async {
+            let mut headers = HeaderMap::new();
+            headers.insert("traceparent", trace_parent.parse().unwrap());
+
+            let (rx, addr) = source(
+                vec![],
+                vec![],
+                "http_path",
+                "/",
+                "POST",
+                true,
+                EventStatus::Delivered,
+                true,
+                None,
+                Some(JsonDeserializerConfig::new().into()),
+            )
+            .await;
+
+            spawn_ok_collect_n(
+                send_with_headers(addr, "{\"key1\":\"value1\"}", headers),
+                rx,
+                1,
+            )
+            .await
+        })
+        .await;
+
+        let event = events.remove(0);
+        assert_eq!(
+            event.metadata().trace_parent().as_deref(),
+            Some(trace_parent)
+        );
+    }
+
     #[tokio::test]
     async fn http_query() {
         let mut events = assert_source_compliance(&HTTP_PUSH_SOURCE_TAGS, async {
@@ -1056,4 +1266,131 @@ mod tests {
 
         assert_eq!(200, send_request(addr, "GET", "", "/").await);
     }
+
+    async fn configured_source(
+        config: &str,
+    ) -> (
+        impl Stream<Item = Event> + Unpin,
+        impl Stream<Item = EventArray> + Unpin,
+        SocketAddr,
+    ) {
+        components::init_test();
+        let (mut sender, recv) = SourceSender::new_test_finalize(EventStatus::Delivered);
+        let errors = sender.add_outputs(EventStatus::Delivered, "errors".to_owned());
+        let address = next_addr();
+        let config = format!("address = \"{}\"\n{}", address, config);
+        let config: SimpleHttpConfig = toml::from_str(&config).unwrap();
+        let source = config
+            .build(SourceContext::new_test(sender, None))
+            .await
+            .unwrap();
+        tokio::spawn(source);
+        wait_for_tcp(address).await;
+        (recv, errors, address)
+    }
+
+    #[tokio::test]
+    async fn http_routes() {
+        let (mut rx, mut errors, addr) = configured_source(
+            r#"
+            routes.errors = "/errors"
+            "#,
+        )
+        .await;
+
+        assert_eq!(200, send_with_path(addr, "error", "/errors").await);
+        let events = errors.next().await.unwrap();
+        assert_eq!(events.len(), 1);
+
+        assert_eq!(200, send_with_path(addr, "default", "/").await);
+        let event = rx.next().await.unwrap();
+        assert_eq!(event.as_log()[log_schema().message_key()], "default".into());
+
+        assert_eq!(404, send_with_path(addr, "unrouted", "/other").await);
+    }
+
+    #[tokio::test]
+    async fn http_custom_response() {
+        let (_rx, _errors, addr) = configured_source(
+            r#"
+            response.code = 202
+            response.body = '{"accepted":true}'
+            response.content_type = "application/json"
+            "#,
+        )
+        .await;
+
+        let response = reqwest::Client::new()
+            .post(&format!("http://{}/", addr))
+            .body("test body")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 202);
+        assert_eq!(
+            response.headers()["content-type"],
+            HeaderValue::from_static("application/json")
+        );
+        assert_eq!(response.text().await.unwrap(), r#"{"accepted":true}"#);
+    }
+
+    #[tokio::test]
+    async fn rejects_non_success_response_code() {
+        let config: SimpleHttpConfig = toml::from_str(
+            r#"
+            address = "127.0.0.1:0"
+            response.code = 500
+            "#,
+        )
+        .unwrap();
+        let (sender, _recv) = SourceSender::new_test();
+        assert!(config
+            .build(SourceContext::new_test(sender, None))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn http_bearer_auth() {
+        let (mut rx, _errors, addr) = configured_source(
+            r#"
+            auth.strategy = "bearer"
+            auth.token = "secret-token"
+            "#,
+        )
+        .await;
+
+        assert_eq!(401, send(addr, "test body").await);
+
+        let mut headers = HeaderMap::new();
+        headers.insert("Authorization", "Bearer secret-token".parse().unwrap());
+        assert_eq!(200, send_with_headers(addr, "test body", headers).await);
+        let event = rx.next().await.unwrap();
+        assert_eq!(
+            event.as_log()[log_schema().message_key()],
+            "test body".into()
+        );
+    }
+
+    #[tokio::test]
+    async fn http_metadata_headers() {
+        let (mut rx, _errors, addr) = configured_source(
+            r#"
+            metadata_headers = ["X-Request-Id", "X-Absent"]
+            "#,
+        )
+        .await;
+
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Request-Id", "1234".parse().unwrap());
+        assert_eq!(200, send_with_headers(addr, "test body", headers).await);
+
+        let event = rx.next().await.unwrap();
+        let http_headers = event.metadata().http_headers().as_ref().unwrap();
+        assert_eq!(
+            **http_headers,
+            BTreeMap::from([("x-request-id".to_owned(), "1234".to_owned())])
+        );
+        assert!(event.as_log().get("X-Request-Id").is_none());
+    }
 }
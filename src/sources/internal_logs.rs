@@ -128,7 +128,7 @@ mod tests {
         // `start_source` helper) panics when called more than once.
         let test_id: u8 = rand::random();
         let start = chrono::Utc::now();
-        trace::init(false, false, "debug");
+        trace::init(false, false, "debug", None);
         trace::reset_early_buffer();
 
         error!(message = "Before source started without span.", %test_id);
@@ -10,7 +10,7 @@ use std::{
 };
 
 use bytes::Bytes;
-use chrono::TimeZone;
+use chrono::{DateTime, TimeZone, Utc};
 use codecs::{decoding::BoxedFramingError, CharacterDelimitedDecoder};
 use futures::{future, stream::BoxStream, StreamExt};
 use nix::{
@@ -76,6 +76,29 @@ enum BuildError {
         value,
     ))]
     DuplicatedMatches { field: String, value: String },
+    #[snafu(display("Cannot use both `since_now` and `seek`"))]
+    BothSinceNowAndSeek,
+    #[snafu(display("Cannot use both `journal_directory` and `journal_files`"))]
+    BothJournalDirectoryAndFiles,
+    #[snafu(display(
+        "The Journal field {:?} is duplicated in both include_fields and exclude_fields",
+        field
+    ))]
+    DuplicatedField { field: String },
+}
+
+/// Where to start reading the journal from when there is no checkpoint.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Seek {
+    /// The oldest entry.
+    Head,
+    /// The entries written after the source started.
+    Tail,
+    /// The entry of the cursor.
+    Cursor(String),
+    /// The entries written at, or after, the timestamp.
+    Timestamp(DateTime<Utc>),
 }
 
 #[derive(Deserialize, Serialize, Debug, Default)]
@@ -92,6 +115,10 @@ pub struct JournaldConfig {
     pub batch_size: Option<usize>,
     pub journalctl_path: Option<PathBuf>,
     pub journal_directory: Option<PathBuf>,
+    pub journal_files: Vec<PathBuf>,
+    pub seek: Option<Seek>,
+    pub include_fields: HashSet<String>,
+    pub exclude_fields: HashSet<String>,
     #[serde(default, deserialize_with = "bool_or_struct")]
     acknowledgements: AcknowledgementsConfig,
     /// Deprecated
@@ -165,6 +192,26 @@ impl SourceConfig for JournaldConfig {
             return Err(BuildError::DuplicatedMatches { field, value }.into());
         }
 
+        if let Some(field) = self
+            .include_fields
+            .intersection(&self.exclude_fields)
+            .next()
+        {
+            let field = field.into();
+            return Err(BuildError::DuplicatedField { field }.into());
+        }
+
+        let seek = match (self.since_now, &self.seek) {
+            (Some(_), Some(_)) => return Err(BuildError::BothSinceNowAndSeek.into()),
+            (Some(true), None) => Seek::Tail,
+            (_, Some(seek)) => seek.clone(),
+            (_, None) => Seek::Head,
+        };
+
+        if self.journal_directory.is_some() && !self.journal_files.is_empty() {
+            return Err(BuildError::BothJournalDirectoryAndFiles.into());
+        }
+
         let mut checkpoint_path = data_dir;
         checkpoint_path.push(CHECKPOINT_FILENAME);
 
@@ -176,8 +223,9 @@ impl SourceConfig for JournaldConfig {
         let start = StartJournalctl::new(
             journalctl_path,
             self.journal_directory.clone(),
+            self.journal_files.clone(),
             self.current_boot_only.unwrap_or(true),
-            self.since_now.unwrap_or(false),
+            seek,
         );
 
         let batch_size = self.batch_size.unwrap_or(DEFAULT_BATCH_SIZE);
@@ -187,6 +235,8 @@ impl SourceConfig for JournaldConfig {
             JournaldSource {
                 include_matches,
                 exclude_matches,
+                include_fields: self.include_fields.clone(),
+                exclude_fields: self.exclude_fields.clone(),
                 checkpoint_path,
                 batch_size,
                 remap_priority: self.remap_priority,
@@ -213,6 +263,8 @@ impl SourceConfig for JournaldConfig {
 struct JournaldSource {
     include_matches: Matches,
     exclude_matches: Matches,
+    include_fields: HashSet<String>,
+    exclude_fields: HashSet<String>,
     checkpoint_path: PathBuf,
     batch_size: usize,
     remap_priority: bool,
@@ -364,6 +416,11 @@ impl<'a> Batch<'a> {
                             &self.source.include_matches,
                             &self.source.exclude_matches,
                         ) {
+                            filter_fields(
+                                &mut record,
+                                &self.source.include_fields,
+                                &self.source.exclude_fields,
+                            );
                             self.record_size += bytes.len();
                             let event = create_event(record, &self.batch);
                             self.events.push(event);
@@ -427,22 +484,25 @@ type JournalStream = BoxStream<'static, Result<Bytes, BoxedFramingError>>;
 struct StartJournalctl {
     path: PathBuf,
     journal_dir: Option<PathBuf>,
+    journal_files: Vec<PathBuf>,
     current_boot_only: bool,
-    since_now: bool,
+    seek: Seek,
 }
 
 impl StartJournalctl {
     const fn new(
         path: PathBuf,
         journal_dir: Option<PathBuf>,
+        journal_files: Vec<PathBuf>,
         current_boot_only: bool,
-        since_now: bool,
+        seek: Seek,
     ) -> Self {
         Self {
             path,
             journal_dir,
+            journal_files,
             current_boot_only,
-            since_now,
+            seek,
         }
     }
 
@@ -458,17 +518,26 @@ impl StartJournalctl {
             command.arg(format!("--directory={}", dir.display()));
         }
 
+        for file in &self.journal_files {
+            command.arg(format!("--file={}", file.display()));
+        }
+
         if self.current_boot_only {
             command.arg("--boot");
         }
 
         if let Some(cursor) = checkpoint {
             command.arg(format!("--after-cursor={}", cursor));
-        } else if self.since_now {
-            command.arg("--since=now");
         } else {
-            // journalctl --follow only outputs a few lines without a starting point
-            command.arg("--since=2000-01-01");
+            match &self.seek {
+                // journalctl --follow only outputs a few lines without a starting point
+                Seek::Head => command.arg("--since=2000-01-01"),
+                Seek::Tail => command.arg("--since=now"),
+                Seek::Cursor(cursor) => command.arg(format!("--cursor={}", cursor)),
+                Seek::Timestamp(timestamp) => {
+                    command.arg(format!("--since=@{}", timestamp.timestamp()))
+                }
+            };
         }
 
         command
@@ -596,6 +665,18 @@ fn remap_priority(priority: &mut JsonValue) {
     }
 }
 
+/// Keep only the included fields, if any, and remove the excluded ones. The
+/// message and the timestamps are always kept unless explicitly excluded.
+fn filter_fields(record: &mut Record, includes: &HashSet<String>, excludes: &HashSet<String>) {
+    if !includes.is_empty() {
+        record.retain(|field, _| {
+            includes.contains(field)
+                || [MESSAGE, SOURCE_TIMESTAMP, RECEIVED_TIMESTAMP].contains(&field.as_str())
+        });
+    }
+    record.retain(|field, _| !excludes.contains(field));
+}
+
 fn filter_matches(record: &Record, includes: &Matches, excludes: &Matches) -> bool {
     match (includes.is_empty(), excludes.is_empty()) {
         (true, true) => false,
@@ -895,6 +976,8 @@ mod tests {
             let source = JournaldSource {
                 include_matches,
                 exclude_matches,
+                include_fields: HashSet::new(),
+                exclude_fields: HashSet::new(),
                 checkpoint_path,
                 batch_size: DEFAULT_BATCH_SIZE,
                 remap_priority: true,
@@ -1199,40 +1282,109 @@ mod tests {
         let journal_dir = None;
         let current_boot_only = false;
         let cursor = None;
-        let since_now = false;
+        let seek = Seek::Head;
 
-        let command = create_command(&path, journal_dir, current_boot_only, since_now, cursor);
+        let command = create_command(&path, journal_dir, current_boot_only, seek, cursor);
         let cmd_line = format!("{:?}", command);
         assert!(!cmd_line.contains("--directory="));
+        assert!(!cmd_line.contains("--file="));
         assert!(!cmd_line.contains("--boot"));
         assert!(cmd_line.contains("--since=2000-01-01"));
 
-        let since_now = true;
+        let seek = Seek::Tail;
         let journal_dir = None;
 
-        let command = create_command(&path, journal_dir, current_boot_only, since_now, cursor);
+        let command = create_command(&path, journal_dir, current_boot_only, seek, cursor);
         let cmd_line = format!("{:?}", command);
         assert!(cmd_line.contains("--since=now"));
 
+        let seek = Seek::Cursor("s=abc".into());
+
+        let command = create_command(&path, None, current_boot_only, seek, cursor);
+        let cmd_line = format!("{:?}", command);
+        assert!(cmd_line.contains("--cursor=s=abc"));
+        assert!(!cmd_line.contains("--since="));
+
+        let seek = Seek::Timestamp(chrono::Utc.timestamp(1578529839, 0));
+
+        let command = create_command(&path, None, current_boot_only, seek.clone(), cursor);
+        let cmd_line = format!("{:?}", command);
+        assert!(cmd_line.contains("--since=@1578529839"));
+
         let journal_dir = Some(PathBuf::from("/tmp/journal-dir"));
         let current_boot_only = true;
         let cursor = Some("2021-01-01");
 
-        let command = create_command(&path, journal_dir, current_boot_only, since_now, cursor);
+        let command = create_command(&path, journal_dir, current_boot_only, seek, cursor);
         let cmd_line = format!("{:?}", command);
         assert!(cmd_line.contains("--directory=/tmp/journal-dir"));
         assert!(cmd_line.contains("--boot"));
         assert!(cmd_line.contains("--after-cursor="));
+        assert!(!cmd_line.contains("--since="));
+
+        let command = StartJournalctl::new(
+            path,
+            None,
+            vec![PathBuf::from("/var/log/journal/remote/*.journal")],
+            false,
+            Seek::Head,
+        )
+        .make_command(None);
+        let cmd_line = format!("{:?}", command);
+        assert!(cmd_line.contains("--file=/var/log/journal/remote/*.journal"));
+    }
+
+    #[test]
+    fn filter_fields_works_correctly() {
+        let record: Record = vec![
+            (MESSAGE, "message"),
+            (RECEIVED_TIMESTAMP, "1578529839140001"),
+            (HOSTNAME, "host"),
+            (SYSTEMD_UNIT, "unit.service"),
+            ("PRIORITY", "6"),
+        ]
+        .into_iter()
+        .map(|(field, value)| (field.to_owned(), value.to_owned()))
+        .collect();
+        let fields =
+            |v: &[&str]| -> HashSet<String> { v.iter().copied().map(String::from).collect() };
+        let keys = |record: &Record| -> HashSet<String> { record.keys().cloned().collect() };
+
+        let mut filtered = record.clone();
+        filter_fields(&mut filtered, &HashSet::new(), &HashSet::new());
+        assert_eq!(filtered, record);
+
+        let mut filtered = record.clone();
+        filter_fields(&mut filtered, &fields(&[SYSTEMD_UNIT]), &HashSet::new());
+        assert_eq!(
+            keys(&filtered),
+            fields(&[MESSAGE, RECEIVED_TIMESTAMP, SYSTEMD_UNIT])
+        );
+
+        let mut filtered = record.clone();
+        filter_fields(
+            &mut filtered,
+            &HashSet::new(),
+            &fields(&[HOSTNAME, "PRIORITY"]),
+        );
+        assert_eq!(
+            keys(&filtered),
+            fields(&[MESSAGE, RECEIVED_TIMESTAMP, SYSTEMD_UNIT])
+        );
+
+        let mut filtered = record;
+        filter_fields(&mut filtered, &fields(&["PRIORITY"]), &fields(&[MESSAGE]));
+        assert_eq!(keys(&filtered), fields(&[RECEIVED_TIMESTAMP, "PRIORITY"]));
     }
 
     fn create_command(
         path: &Path,
         journal_dir: Option<PathBuf>,
         current_boot_only: bool,
-        since_now: bool,
+        seek: Seek,
         cursor: Option<&str>,
     ) -> Command {
-        StartJournalctl::new(path.into(), journal_dir, current_boot_only, since_now)
+        StartJournalctl::new(path.into(), journal_dir, vec![], current_boot_only, seek)
             .make_command(cursor)
     }
 
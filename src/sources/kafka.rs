@@ -1,27 +1,31 @@
 use std::{
     collections::{BTreeMap, HashMap},
     io::Cursor,
-    sync::Arc,
+    sync::{mpsc as std_mpsc, Arc, Mutex},
+    time::Duration,
 };
 
 use async_stream::stream;
 use bytes::Bytes;
 use chrono::{DateTime, TimeZone, Utc};
 use codecs::{
-    decoding::{DeserializerConfig, FramingConfig},
-    StreamDecodingError,
+    decoding::{Deserializer, DeserializerConfig, Framer, FramingConfig},
+    BytesDecoder, StreamDecodingError,
 };
 use futures::{Stream, StreamExt};
 use rdkafka::{
     config::ClientConfig,
-    consumer::{Consumer, StreamConsumer},
+    consumer::{Consumer, ConsumerContext, Rebalance, StreamConsumer},
     message::{BorrowedMessage, Headers, Message},
+    ClientContext, Statistics,
 };
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
 use tokio_util::codec::FramedRead;
 use vector_core::ByteSizeOf;
 
+use self::schema_registry::{SchemaRegistry, SchemaRegistryConfig};
 use super::util::finalizer::OrderedFinalizer;
 use crate::{
     codecs::{Decoder, DecodingConfig},
@@ -32,14 +36,17 @@ use crate::{
     event::{BatchNotifier, BatchStatus, Event, Value},
     internal_events::{
         KafkaBytesReceived, KafkaEventsReceived, KafkaOffsetUpdateError, KafkaReadError,
+        KafkaRevokedPartitionsDrainTimeout, KafkaSchemaRegistryError, KafkaStatisticsReceived,
         StreamClosedError,
     },
-    kafka::{KafkaAuthConfig, KafkaStatisticsContext},
+    kafka::KafkaAuthConfig,
     serde::{bool_or_struct, default_decoding, default_framing_message_based},
     shutdown::ShutdownSignal,
     SourceSender,
 };
 
+mod schema_registry;
+
 #[derive(Debug, Snafu)]
 enum BuildError {
     #[snafu(display("Could not create Kafka consumer: {}", source))]
@@ -55,6 +62,13 @@ pub struct KafkaSourceConfig {
     bootstrap_servers: String,
     topics: Vec<String>,
     group_id: String,
+    group_instance_id: Option<String>,
+    #[serde(default = "default_partition_assignment_strategy")]
+    #[derivative(Default(value = "default_partition_assignment_strategy()"))]
+    partition_assignment_strategy: String,
+    #[serde(default = "default_drain_timeout_ms")]
+    #[derivative(Default(value = "default_drain_timeout_ms()"))]
+    drain_timeout_ms: u64,
     #[serde(default = "default_auto_offset_reset")]
     auto_offset_reset: String,
     #[serde(default = "default_session_timeout_ms")]
@@ -84,6 +98,7 @@ pub struct KafkaSourceConfig {
     #[serde(default = "default_decoding")]
     #[derivative(Default(value = "default_decoding()"))]
     decoding: DeserializerConfig,
+    schema_registry: Option<SchemaRegistryConfig>,
     #[serde(default, deserialize_with = "bool_or_struct")]
     acknowledgements: AcknowledgementsConfig,
 }
@@ -104,6 +119,14 @@ const fn default_commit_interval_ms() -> u64 {
     5000 // default in librdkafka
 }
 
+const fn default_drain_timeout_ms() -> u64 {
+    2500
+}
+
+fn default_partition_assignment_strategy() -> String {
+    "range,roundrobin".into() // default in librdkafka
+}
+
 fn default_auto_offset_reset() -> String {
     "largest".into() // default in librdkafka
 }
@@ -138,14 +161,31 @@ impl_generate_config_from_default!(KafkaSourceConfig);
 #[typetag::serde(name = "kafka")]
 impl SourceConfig for KafkaSourceConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
-        let consumer = create_consumer(self)?;
-        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build();
+        let (context, drains) = KafkaSourceContext::new(self.drain_timeout_ms);
+        let consumer = create_consumer(self, context)?;
+        let (decoder, schema_registry) = match &self.schema_registry {
+            Some(config) => {
+                let schema_registry = SchemaRegistry::new(config, &cx.proxy)?;
+                // Each message holds a single record, prefixed by the identifier of its schema.
+                let decoder = Decoder::new(
+                    Framer::Bytes(BytesDecoder::new()),
+                    Deserializer::Boxed(Box::new(schema_registry.deserializer())),
+                );
+                (decoder, Some(schema_registry))
+            }
+            None => (
+                DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build(),
+                None,
+            ),
+        };
         let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
 
         Ok(Box::pin(kafka_source(
             self.clone(),
             consumer,
+            drains,
             decoder,
+            schema_registry,
             cx.shutdown,
             cx.out,
             acknowledgements,
@@ -167,30 +207,33 @@ impl SourceConfig for KafkaSourceConfig {
 
 async fn kafka_source(
     config: KafkaSourceConfig,
-    consumer: StreamConsumer<KafkaStatisticsContext>,
+    consumer: StreamConsumer<KafkaSourceContext>,
+    drains: UnboundedReceiver<DrainRequest>,
     decoder: Decoder,
+    schema_registry: Option<SchemaRegistry>,
     mut shutdown: ShutdownSignal,
     mut out: SourceSender,
     acknowledgements: bool,
 ) -> Result<(), ()> {
     let consumer = Arc::new(consumer);
-    let (finalizer, mut ack_stream) =
+    let (finalizer, ack_stream) =
         OrderedFinalizer::<FinalizerEntry>::maybe_new(acknowledgements, shutdown.clone());
+    let in_flight = InFlight::default();
+    // The acknowledgements are handled by their own task, as the revocation of partitions blocks
+    // the task polling the consumer until the events read from them are acknowledged.
+    tokio::spawn(handle_acks(
+        Arc::clone(&consumer),
+        ack_stream,
+        drains,
+        in_flight.clone(),
+        shutdown.clone(),
+    ));
     let mut stream = consumer.stream();
     let keys = Keys::from(log_schema(), &config);
 
     loop {
         tokio::select! {
             _ = &mut shutdown => break,
-            entry = ack_stream.next() => if let Some((status, entry)) = entry {
-                if status == BatchStatus::Delivered {
-                    if let Err(error) =
-                        consumer.store_offset(&entry.topic, entry.partition, entry.offset)
-                    {
-                        emit!(KafkaOffsetUpdateError { error });
-                    }
-                }
-            },
             message = stream.next() => match message {
                 None => break,  // WHY?
                 Some(Err(error)) => emit!(KafkaReadError { error }),
@@ -202,7 +245,15 @@ async fn kafka_source(
                         partition: msg.partition(),
                     });
 
-                    parse_message(msg, decoder.clone(), keys, &finalizer, &mut out, &consumer).await;
+                    // Decoding can't wait on the Schema Registry, so the schema of the payload is
+                    // resolved beforehand.
+                    if let (Some(schema_registry), Some(payload)) = (&schema_registry, msg.payload()) {
+                        if let Err(error) = schema_registry.resolve(payload).await {
+                            emit!(KafkaSchemaRegistryError { error });
+                        }
+                    }
+
+                    parse_message(msg, decoder.clone(), keys, &finalizer, &in_flight, &mut out, &consumer).await;
                 }
             },
         }
@@ -211,13 +262,54 @@ async fn kafka_source(
     Ok(())
 }
 
+async fn handle_acks(
+    consumer: Arc<StreamConsumer<KafkaSourceContext>>,
+    mut ack_stream: impl Stream<Item = (BatchStatus, FinalizerEntry)> + Unpin,
+    mut drains: UnboundedReceiver<DrainRequest>,
+    in_flight: InFlight,
+    mut shutdown: ShutdownSignal,
+) {
+    let mut pending_drains = Vec::<DrainRequest>::new();
+
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            entry = ack_stream.next() => match entry {
+                None => break,
+                Some((status, entry)) => {
+                    if status == BatchStatus::Delivered {
+                        if let Err(error) =
+                            consumer.store_offset(&entry.topic, entry.partition, entry.offset)
+                        {
+                            emit!(KafkaOffsetUpdateError { error });
+                        }
+                    }
+                    in_flight.remove(&entry.topic, entry.partition);
+                }
+            },
+            Some(drain) = drains.recv() => pending_drains.push(drain),
+        }
+
+        // The offsets stored for the revoked partitions are committed by librdkafka once they're
+        // unassigned, which happens after the drain completes.
+        pending_drains.retain(|drain| {
+            let drained = in_flight.is_drained(&drain.partitions);
+            if drained {
+                let _ = drain.done.send(());
+            }
+            !drained
+        });
+    }
+}
+
 async fn parse_message(
     msg: BorrowedMessage<'_>,
     decoder: Decoder,
     keys: Keys<'_>,
     finalizer: &Option<OrderedFinalizer<FinalizerEntry>>,
+    in_flight: &InFlight,
     out: &mut SourceSender,
-    consumer: &Arc<StreamConsumer<KafkaStatisticsContext>>,
+    consumer: &Arc<StreamConsumer<KafkaSourceContext>>,
 ) {
     if let Some((count, mut stream)) = parse_stream(&msg, decoder, keys) {
         match finalizer {
@@ -232,6 +324,7 @@ async fn parse_message(
                         // Drop stream to avoid borrowing `msg`: "[...] borrow might be used
                         // here, when `stream` is dropped and runs the destructor [...]".
                         drop(stream);
+                        in_flight.add(msg.topic(), msg.partition());
                         finalizer.add(msg.into(), receiver);
                     }
                 }
@@ -397,9 +490,106 @@ impl<'a> From<BorrowedMessage<'a>> for FinalizerEntry {
     }
 }
 
+/// The number of messages read from each partition whose events haven't been acknowledged yet.
+#[derive(Clone, Default)]
+struct InFlight(Arc<Mutex<HashMap<(String, i32), usize>>>);
+
+impl InFlight {
+    fn add(&self, topic: &str, partition: i32) {
+        *self
+            .0
+            .lock()
+            .expect("mutex poisoned")
+            .entry((topic.into(), partition))
+            .or_default() += 1;
+    }
+
+    fn remove(&self, topic: &str, partition: i32) {
+        let mut in_flight = self.0.lock().expect("mutex poisoned");
+        let key = (topic.to_string(), partition);
+        if let Some(count) = in_flight.get_mut(&key) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                in_flight.remove(&key);
+            }
+        }
+    }
+
+    fn is_drained(&self, partitions: &[(String, i32)]) -> bool {
+        let in_flight = self.0.lock().expect("mutex poisoned");
+        partitions
+            .iter()
+            .all(|partition| !in_flight.contains_key(partition))
+    }
+}
+
+/// A request to wait for the events read from the partitions being revoked to be acknowledged.
+struct DrainRequest {
+    partitions: Vec<(String, i32)>,
+    done: std_mpsc::SyncSender<()>,
+}
+
+/// Drains the partitions revoked during a rebalance before they're handed over to other members
+/// of the group, so that they resume from the offsets of the events that were acknowledged
+/// rather than from the last committed ones.
+struct KafkaSourceContext {
+    drain_timeout: Duration,
+    drains: UnboundedSender<DrainRequest>,
+}
+
+impl KafkaSourceContext {
+    fn new(drain_timeout_ms: u64) -> (Self, UnboundedReceiver<DrainRequest>) {
+        let (drains, receiver) = mpsc::unbounded_channel();
+        let context = Self {
+            drain_timeout: Duration::from_millis(drain_timeout_ms),
+            drains,
+        };
+        (context, receiver)
+    }
+}
+
+impl ClientContext for KafkaSourceContext {
+    fn stats(&self, statistics: Statistics) {
+        emit!(KafkaStatisticsReceived {
+            statistics: &statistics
+        });
+    }
+}
+
+impl ConsumerContext for KafkaSourceContext {
+    fn pre_rebalance<'a>(&self, rebalance: &Rebalance<'a>) {
+        if let Rebalance::Revoke(partitions) = rebalance {
+            let partitions = partitions
+                .elements()
+                .iter()
+                .map(|element| (element.topic().to_string(), element.partition()))
+                .collect::<Vec<_>>();
+            if partitions.is_empty() {
+                return;
+            }
+
+            let count = partitions.len();
+            let (done, drained) = std_mpsc::sync_channel(1);
+            if self.drains.send(DrainRequest { partitions, done }).is_err() {
+                // The source is shutting down.
+                return;
+            }
+            // This is called from the task polling the consumer, which is blocked until the
+            // acknowledgements, handled by another task, have caught up.
+            if drained.recv_timeout(self.drain_timeout).is_err() {
+                emit!(KafkaRevokedPartitionsDrainTimeout {
+                    partitions: count,
+                    timeout: self.drain_timeout,
+                });
+            }
+        }
+    }
+}
+
 fn create_consumer(
     config: &KafkaSourceConfig,
-) -> crate::Result<StreamConsumer<KafkaStatisticsContext>> {
+    context: KafkaSourceContext,
+) -> crate::Result<StreamConsumer<KafkaSourceContext>> {
     let mut client_config = ClientConfig::new();
     client_config
         .set("group.id", &config.group_id)
@@ -416,7 +606,17 @@ fn create_consumer(
         )
         .set("enable.auto.offset.store", "false")
         .set("statistics.interval.ms", "1000")
-        .set("client.id", "vector");
+        .set("client.id", "vector")
+        .set(
+            "partition.assignment.strategy",
+            &config.partition_assignment_strategy,
+        );
+
+    // Static members keep their partitions while they restart, as long as they rejoin before
+    // the session times out.
+    if let Some(group_instance_id) = &config.group_instance_id {
+        client_config.set("group.instance.id", group_instance_id);
+    }
 
     config.auth.apply(&mut client_config)?;
 
@@ -427,7 +627,7 @@ fn create_consumer(
     }
 
     let consumer = client_config
-        .create_with_context::<_, StreamConsumer<_>>(KafkaStatisticsContext)
+        .create_with_context::<_, StreamConsumer<_>>(context)
         .context(KafkaCreateSnafu)?;
     let topics: Vec<&str> = config.topics.iter().map(|s| s.as_str()).collect();
     consumer.subscribe(&topics).context(KafkaSubscribeSnafu)?;
@@ -471,10 +671,20 @@ mod test {
         }
     }
 
+    pub(super) fn make_consumer(
+        config: &KafkaSourceConfig,
+    ) -> crate::Result<(
+        StreamConsumer<KafkaSourceContext>,
+        UnboundedReceiver<DrainRequest>,
+    )> {
+        let (context, drains) = KafkaSourceContext::new(config.drain_timeout_ms);
+        Ok((create_consumer(config, context)?, drains))
+    }
+
     #[tokio::test]
     async fn consumer_create_ok() {
         let config = make_config("topic", "group");
-        assert!(create_consumer(&config).is_ok());
+        assert!(make_consumer(&config).is_ok());
     }
 
     #[tokio::test]
@@ -483,7 +693,44 @@ mod test {
             auto_offset_reset: "incorrect-auto-offset-reset".to_string(),
             ..make_config("topic", "group")
         };
-        assert!(create_consumer(&config).is_err());
+        assert!(make_consumer(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn consumer_create_static_cooperative_member() {
+        let config = KafkaSourceConfig {
+            group_instance_id: Some("vector-0".into()),
+            partition_assignment_strategy: "cooperative-sticky".into(),
+            ..make_config("topic", "group")
+        };
+        assert!(make_consumer(&config).is_ok());
+    }
+
+    #[tokio::test]
+    async fn consumer_create_incorrect_partition_assignment_strategy() {
+        let config = KafkaSourceConfig {
+            partition_assignment_strategy: "incorrect-strategy".into(),
+            ..make_config("topic", "group")
+        };
+        assert!(make_consumer(&config).is_err());
+    }
+
+    #[test]
+    fn drains_partitions_once_acknowledged() {
+        let in_flight = InFlight::default();
+        let revoked = vec![("topic".to_string(), 0)];
+        assert!(in_flight.is_drained(&revoked));
+
+        in_flight.add("topic", 0);
+        in_flight.add("topic", 0);
+        in_flight.add("topic", 1);
+        assert!(!in_flight.is_drained(&revoked));
+
+        in_flight.remove("topic", 0);
+        assert!(!in_flight.is_drained(&revoked));
+        in_flight.remove("topic", 0);
+        assert!(in_flight.is_drained(&revoked));
+        assert!(!in_flight.is_drained(&[("topic".into(), 1)]));
     }
 }
 
@@ -578,11 +825,13 @@ mod integration_test {
         let events = assert_source_compliance(&["protocol", "topic", "partition"], async move {
             let (trigger_shutdown, shutdown, shutdown_done) = ShutdownSignal::new_wired();
             let (tx, rx) = SourceSender::new_test_finalize(EventStatus::Delivered);
-            let consumer = create_consumer(&config).unwrap();
+            let (consumer, drains) = make_consumer(&config).unwrap();
             tokio::spawn(kafka_source(
                 config,
                 consumer,
+                drains,
                 crate::codecs::Decoder::default(),
+                None,
                 shutdown,
                 tx,
                 acknowledgements,
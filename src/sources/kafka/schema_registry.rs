@@ -0,0 +1,501 @@
+//! Decodes the payloads written in the wire format of the Confluent Schema Registry: a zero magic
+//! byte and the big endian identifier of the schema they were written with, followed, for
+//! Protobuf, by the indexes of their message type within the schema, and by the encoded record.
+
+use std::{
+    collections::{HashMap, HashSet},
+    convert::TryFrom,
+    io::Cursor,
+    sync::{Arc, RwLock},
+};
+
+use bytes::Bytes;
+use chrono::{TimeZone, Utc};
+use codecs::decoding::format::Deserializer;
+use http::{header, Request, StatusCode};
+use hyper::Body;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use prost::Message as _;
+use prost_reflect::{DescriptorPool, DynamicMessage};
+use prost_types::{DescriptorProto, FileDescriptorProto, FileDescriptorSet};
+use serde::{Deserialize, Serialize};
+use smallvec::{smallvec, SmallVec};
+use snafu::Snafu;
+
+use crate::{
+    config::{log_schema, ProxyConfig},
+    event::{Event, LogEvent, Value},
+    http::{Auth, HttpClient},
+    sources::util::message_to_value,
+    tls::{TlsConfig, TlsSettings},
+};
+
+const MAGIC_BYTE: u8 = 0;
+const HEADER_LEN: usize = 5;
+const CONTENT_TYPE: &str = "application/vnd.schemaregistry.v1+json";
+
+/// The Schema Registry the schemas of the payloads are resolved against.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct SchemaRegistryConfig {
+    url: String,
+    auth: Option<Auth>,
+    tls: Option<TlsConfig>,
+}
+
+#[derive(Debug, Snafu)]
+pub(super) enum SchemaRegistryError {
+    #[snafu(display("Payload isn't in the Schema Registry wire format."))]
+    WireFormat,
+    #[snafu(display("Schema {} hasn't been resolved.", id))]
+    UnknownSchema { id: u32 },
+    #[snafu(display("Resolving schema {} failed: {}", id, source))]
+    Resolve { id: u32, source: crate::Error },
+    #[snafu(display("Decoding payload with schema {} failed: {}", id, message))]
+    Decode { id: u32, message: String },
+}
+
+/// The schemas resolved so far. The identifier of a schema always refers to the same one, so they
+/// never expire.
+type Schemas = Arc<RwLock<HashMap<u32, Arc<Schema>>>>;
+
+#[derive(Debug)]
+enum Schema {
+    Avro(avro_rs::Schema),
+    Json,
+    Protobuf {
+        pool: DescriptorPool,
+        file: FileDescriptorProto,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SchemaResponse {
+    schema: String,
+    #[serde(default)]
+    schema_type: Option<String>,
+    #[serde(default)]
+    references: Vec<SchemaReference>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SchemaReference {
+    name: String,
+    subject: String,
+    version: i32,
+}
+
+#[derive(Clone)]
+pub(super) struct SchemaRegistry {
+    client: HttpClient,
+    url: String,
+    auth: Option<Auth>,
+    schemas: Schemas,
+}
+
+impl SchemaRegistry {
+    pub(super) fn new(config: &SchemaRegistryConfig, proxy: &ProxyConfig) -> crate::Result<Self> {
+        let tls = TlsSettings::from_options(&config.tls)?;
+        Ok(Self {
+            client: HttpClient::new(tls, proxy)?,
+            url: config.url.trim_end_matches('/').into(),
+            auth: config.auth.clone(),
+            schemas: Schemas::default(),
+        })
+    }
+
+    /// The deserializer decoding the payloads whose schema has been resolved.
+    pub(super) fn deserializer(&self) -> SchemaRegistryDeserializer {
+        SchemaRegistryDeserializer {
+            schemas: Arc::clone(&self.schemas),
+        }
+    }
+
+    /// Fetches the schema the payload was written with, unless it's cached already.
+    pub(super) async fn resolve(&self, payload: &[u8]) -> Result<(), SchemaRegistryError> {
+        let id = schema_id(payload)?;
+        if self
+            .schemas
+            .read()
+            .expect("lock poisoned")
+            .contains_key(&id)
+        {
+            return Ok(());
+        }
+
+        let schema = self
+            .fetch(id)
+            .await
+            .map_err(|source| SchemaRegistryError::Resolve { id, source })?;
+        self.schemas
+            .write()
+            .expect("lock poisoned")
+            .insert(id, Arc::new(schema));
+        Ok(())
+    }
+
+    async fn fetch(&self, id: u32) -> crate::Result<Schema> {
+        let response = self
+            .get(&format!("/schemas/ids/{}?format=serialized", id))
+            .await?;
+
+        match response.schema_type.as_deref().unwrap_or("AVRO") {
+            "AVRO" => Ok(Schema::Avro(avro_rs::Schema::parse_str(&response.schema)?)),
+            "JSON" => Ok(Schema::Json),
+            "PROTOBUF" => {
+                let mut files = self.fetch_references(response.references).await?;
+                let mut file = decode_file_descriptor(&response.schema)?;
+                if file.name.as_deref().unwrap_or_default().is_empty() {
+                    file.name = Some(format!("schema-{}.proto", id));
+                }
+                files.push(file.clone());
+                let pool =
+                    DescriptorPool::from_file_descriptor_set(FileDescriptorSet { file: files })?;
+                Ok(Schema::Protobuf { pool, file })
+            }
+            schema_type => Err(format!("Unsupported schema type `{}`.", schema_type).into()),
+        }
+    }
+
+    /// Fetches the files a Protobuf schema imports, recursively, ordered so that each one follows
+    /// the ones it imports.
+    async fn fetch_references(
+        &self,
+        references: Vec<SchemaReference>,
+    ) -> crate::Result<Vec<FileDescriptorProto>> {
+        let mut files = Vec::new();
+        let mut seen = HashSet::new();
+        // A reference is pushed back along with its file once the references of the file are
+        // pushed, so that it's popped after them.
+        let mut stack = references
+            .into_iter()
+            .rev()
+            .map(|reference| (reference, None))
+            .collect::<Vec<_>>();
+
+        while let Some((reference, file)) = stack.pop() {
+            if let Some(file) = file {
+                files.push(file);
+                continue;
+            }
+            if !seen.insert(reference.name.clone()) {
+                continue;
+            }
+
+            let response = self
+                .get(&format!(
+                    "/subjects/{}/versions/{}?format=serialized",
+                    utf8_percent_encode(&reference.subject, NON_ALPHANUMERIC),
+                    reference.version
+                ))
+                .await?;
+            let mut file = decode_file_descriptor(&response.schema)?;
+            // The importing files refer to it by the name of the reference.
+            file.name = Some(reference.name.clone());
+            let references = response.references;
+            stack.push((reference, Some(file)));
+            stack.extend(
+                references
+                    .into_iter()
+                    .rev()
+                    .map(|reference| (reference, None)),
+            );
+        }
+
+        Ok(files)
+    }
+
+    async fn get(&self, path: &str) -> crate::Result<SchemaResponse> {
+        let mut request = Request::get(format!("{}{}", self.url, path))
+            .header(header::ACCEPT, CONTENT_TYPE)
+            .body(Body::empty())?;
+        if let Some(auth) = &self.auth {
+            auth.apply(&mut request);
+        }
+
+        let response = self.client.send(request).await?;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        if status != StatusCode::OK {
+            return Err(format!(
+                "Schema Registry responded with {}: {}",
+                status,
+                String::from_utf8_lossy(&body)
+            )
+            .into());
+        }
+        Ok(serde_json::from_slice(&body)?)
+    }
+}
+
+fn schema_id(payload: &[u8]) -> Result<u32, SchemaRegistryError> {
+    match payload {
+        [MAGIC_BYTE, id @ ..] if id.len() >= HEADER_LEN - 1 => {
+            Ok(u32::from_be_bytes([id[0], id[1], id[2], id[3]]))
+        }
+        _ => Err(SchemaRegistryError::WireFormat),
+    }
+}
+
+fn decode_file_descriptor(schema: &str) -> crate::Result<FileDescriptorProto> {
+    let bytes = base64::decode(schema)?;
+    Ok(FileDescriptorProto::decode(bytes.as_slice())?)
+}
+
+/// Builds events from the payloads whose schema was resolved by the `SchemaRegistry` beforehand.
+#[derive(Clone, Debug)]
+pub(super) struct SchemaRegistryDeserializer {
+    schemas: Schemas,
+}
+
+impl Deserializer for SchemaRegistryDeserializer {
+    fn parse(&self, bytes: Bytes) -> vector_core::Result<SmallVec<[Event; 1]>> {
+        let id = schema_id(&bytes)?;
+        let schema = self
+            .schemas
+            .read()
+            .expect("lock poisoned")
+            .get(&id)
+            .cloned()
+            .ok_or(SchemaRegistryError::UnknownSchema { id })?;
+
+        let value = schema
+            .decode(bytes.slice(HEADER_LEN..))
+            .map_err(|message| SchemaRegistryError::Decode { id, message })?;
+        let log = match value {
+            Value::Object(fields) => LogEvent::from(fields),
+            value => {
+                let mut log = LogEvent::default();
+                log.insert(log_schema().message_key(), value);
+                log
+            }
+        };
+        Ok(smallvec![Event::from(log)])
+    }
+}
+
+impl Schema {
+    fn decode(&self, mut bytes: Bytes) -> Result<Value, String> {
+        match self {
+            Schema::Avro(schema) => {
+                let value = avro_rs::from_avro_datum(schema, &mut Cursor::new(bytes), None)
+                    .map_err(|error| error.to_string())?;
+                Ok(avro_to_value(value))
+            }
+            Schema::Json => serde_json::from_slice::<serde_json::Value>(&bytes)
+                .map(Value::from)
+                .map_err(|error| error.to_string()),
+            Schema::Protobuf { pool, file } => {
+                let indexes = message_indexes(&mut bytes)?;
+                let name = message_name(file, &indexes)
+                    .ok_or_else(|| format!("No message type at indexes {:?}.", indexes))?;
+                let descriptor = pool
+                    .get_message_by_name(&name)
+                    .ok_or_else(|| format!("Unknown message type `{}`.", name))?;
+                let message =
+                    DynamicMessage::decode(descriptor, bytes).map_err(|error| error.to_string())?;
+                Ok(message_to_value(&message))
+            }
+        }
+    }
+}
+
+/// Reads the indexes of the message type of a Protobuf payload, which are encoded as an array of
+/// zigzag varints, shortened to a single zero for the first message type of the schema.
+fn message_indexes(bytes: &mut Bytes) -> Result<Vec<i32>, String> {
+    let mut read = || {
+        prost::encoding::decode_varint(bytes)
+            .map(|value| ((value >> 1) as i64 ^ -((value & 1) as i64)) as i32)
+            .map_err(|error| error.to_string())
+    };
+
+    let count = read()?;
+    if count == 0 {
+        return Ok(vec![0]);
+    }
+    (0..count).map(|_| read()).collect()
+}
+
+/// The fully qualified name of the message type at the indexes, each of which is the index of a
+/// message type within the file, and then within the previous one.
+fn message_name(file: &FileDescriptorProto, indexes: &[i32]) -> Option<String> {
+    let (first, rest) = indexes.split_first()?;
+    let mut message: &DescriptorProto = file.message_type.get(usize::try_from(*first).ok()?)?;
+    let mut name = match file.package.as_deref() {
+        Some(package) if !package.is_empty() => format!("{}.{}", package, message.name()),
+        _ => message.name().to_string(),
+    };
+    for index in rest {
+        message = message.nested_type.get(usize::try_from(*index).ok()?)?;
+        name = format!("{}.{}", name, message.name());
+    }
+    Some(name)
+}
+
+fn avro_to_value(value: avro_rs::types::Value) -> Value {
+    use avro_rs::types::Value as AvroValue;
+
+    match value {
+        AvroValue::Null => Value::Null,
+        AvroValue::Boolean(value) => Value::from(value),
+        AvroValue::Int(value) | AvroValue::Date(value) | AvroValue::TimeMillis(value) => {
+            Value::from(value)
+        }
+        AvroValue::Long(value) | AvroValue::TimeMicros(value) => Value::from(value),
+        AvroValue::Float(value) => Value::from(f64::from(value)),
+        AvroValue::Double(value) => Value::from(value),
+        AvroValue::Bytes(value) | AvroValue::Fixed(_, value) => Value::from(Bytes::from(value)),
+        AvroValue::String(value) | AvroValue::Enum(_, value) => Value::from(value),
+        AvroValue::Union(value) => avro_to_value(*value),
+        AvroValue::Array(values) => Value::Array(values.into_iter().map(avro_to_value).collect()),
+        AvroValue::Map(values) => Value::Object(
+            values
+                .into_iter()
+                .map(|(key, value)| (key, avro_to_value(value)))
+                .collect(),
+        ),
+        AvroValue::Record(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| (key, avro_to_value(value)))
+                .collect(),
+        ),
+        AvroValue::TimestampMillis(millis) => Value::from(Utc.timestamp_millis(millis)),
+        AvroValue::TimestampMicros(micros) => Value::from(Utc.timestamp_nanos(micros * 1000)),
+        AvroValue::Decimal(decimal) => Vec::<u8>::try_from(&decimal)
+            .map(|bytes| Value::from(Bytes::from(bytes)))
+            .unwrap_or(Value::Null),
+        AvroValue::Uuid(uuid) => Value::from(uuid.to_string()),
+        // Durations don't have an equivalent type.
+        AvroValue::Duration(_) => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prost_types::FieldDescriptorProto;
+
+    use super::*;
+
+    fn payload(id: u32, record: &[u8]) -> Bytes {
+        let mut payload = vec![MAGIC_BYTE];
+        payload.extend_from_slice(&id.to_be_bytes());
+        payload.extend_from_slice(record);
+        payload.into()
+    }
+
+    fn deserializer(id: u32, schema: Schema) -> SchemaRegistryDeserializer {
+        let schemas = Schemas::default();
+        schemas.write().unwrap().insert(id, Arc::new(schema));
+        SchemaRegistryDeserializer { schemas }
+    }
+
+    #[test]
+    fn reads_schema_ids() {
+        assert_eq!(schema_id(&payload(42, b"record")).unwrap(), 42);
+        assert!(schema_id(b"{\"json\":true}").is_err());
+        assert!(schema_id(&[MAGIC_BYTE, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn rejects_unresolved_schemas() {
+        let deserializer = deserializer(1, Schema::Json);
+        assert!(deserializer.parse(payload(2, b"{}")).is_err());
+    }
+
+    #[test]
+    fn decodes_avro() {
+        let schema = avro_rs::Schema::parse_str(
+            r#"{
+                "type": "record",
+                "name": "user",
+                "fields": [
+                    {"name": "name", "type": "string"},
+                    {"name": "age", "type": ["null", "long"]}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let mut record = avro_rs::types::Record::new(&schema).unwrap();
+        record.put("name", "ferris");
+        record.put(
+            "age",
+            avro_rs::types::Value::Union(Box::new(avro_rs::types::Value::Long(12))),
+        );
+        let record = avro_rs::to_avro_datum(&schema, record).unwrap();
+
+        let events = deserializer(1, Schema::Avro(schema))
+            .parse(payload(1, &record))
+            .unwrap();
+        let log = events[0].as_log();
+        assert_eq!(log["name"], "ferris".into());
+        assert_eq!(log["age"], 12.into());
+    }
+
+    #[test]
+    fn decodes_json() {
+        let events = deserializer(1, Schema::Json)
+            .parse(payload(1, br#"{"name":"ferris"}"#))
+            .unwrap();
+        assert_eq!(events[0].as_log()["name"], "ferris".into());
+    }
+
+    #[test]
+    fn decodes_protobuf() {
+        let field = |name: &str, number, r#type: i32| FieldDescriptorProto {
+            name: Some(name.into()),
+            number: Some(number),
+            r#type: Some(r#type),
+            label: Some(1),
+            ..Default::default()
+        };
+        let file = FileDescriptorProto {
+            name: Some("user.proto".into()),
+            package: Some("example".into()),
+            syntax: Some("proto3".into()),
+            message_type: vec![
+                DescriptorProto {
+                    name: Some("Other".into()),
+                    ..Default::default()
+                },
+                DescriptorProto {
+                    name: Some("User".into()),
+                    // string and int64.
+                    field: vec![field("name", 1, 9), field("age", 2, 3)],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let pool = DescriptorPool::from_file_descriptor_set(FileDescriptorSet {
+            file: vec![file.clone()],
+        })
+        .unwrap();
+
+        // The indexes [1], followed by the fields `name` and `age`.
+        let mut record = vec![0x02, 0x02];
+        record.extend_from_slice(&[0x0a, 0x06]);
+        record.extend_from_slice(b"ferris");
+        record.extend_from_slice(&[0x10, 0x0c]);
+
+        let events = deserializer(1, Schema::Protobuf { pool, file })
+            .parse(payload(1, &record))
+            .unwrap();
+        let log = events[0].as_log();
+        assert_eq!(log["name"], "ferris".into());
+        assert_eq!(log["age"], 12.into());
+    }
+
+    #[test]
+    fn reads_message_indexes() {
+        assert_eq!(
+            message_indexes(&mut Bytes::from_static(&[0x00])).unwrap(),
+            vec![0]
+        );
+        assert_eq!(
+            message_indexes(&mut Bytes::from_static(&[0x04, 0x02, 0x06])).unwrap(),
+            vec![1, 3]
+        );
+    }
+}
@@ -2,13 +2,13 @@
 
 #![deny(missing_docs)]
 
-use std::path::PathBuf;
+use std::{collections::BTreeMap, path::PathBuf};
 
 use file_source::paths_provider::PathsProvider;
 use k8s_openapi::api::core::v1::{Namespace, Pod};
 use kube::runtime::reflector::{store::Store, ObjectRef};
 
-use super::path_helpers::build_pod_logs_directory;
+use super::path_helpers::{build_legacy_pod_logs_directory, build_pod_logs_directory};
 use crate::kubernetes::pod_manager_logic::extract_static_pod_config_hashsum;
 
 /// A paths provider implementation that uses the state obtained from the
@@ -17,6 +17,8 @@ pub struct K8sPathsProvider {
     pod_state: Store<Pod>,
     namespace_state: Store<Namespace>,
     exclude_paths: Vec<glob::Pattern>,
+    annotation_filter: AnnotationFilter,
+    legacy_pod_log_directories: bool,
 }
 
 impl K8sPathsProvider {
@@ -25,15 +27,42 @@ impl K8sPathsProvider {
         pod_state: Store<Pod>,
         namespace_state: Store<Namespace>,
         exclude_paths: Vec<glob::Pattern>,
+        annotation_filter: AnnotationFilter,
+        legacy_pod_log_directories: bool,
     ) -> Self {
         Self {
             pod_state,
             namespace_state,
             exclude_paths,
+            annotation_filter,
+            legacy_pod_log_directories,
         }
     }
 }
 
+/// Filters the `Pod`s by their annotations, which, unlike their labels, can't
+/// be selected by the k8s API.
+#[derive(Debug, Default)]
+pub struct AnnotationFilter {
+    /// The annotations the `Pod`s must all have.
+    pub include: BTreeMap<String, String>,
+    /// The annotations the `Pod`s must not have any of.
+    pub exclude: BTreeMap<String, String>,
+}
+
+impl AnnotationFilter {
+    fn matches(&self, pod: &Pod) -> bool {
+        let has_annotation = |(key, value): (&String, &String)| {
+            pod.metadata
+                .annotations
+                .as_ref()
+                .and_then(|annotations| annotations.get(key))
+                == Some(value)
+        };
+        self.include.iter().all(has_annotation) && !self.exclude.iter().any(has_annotation)
+    }
+}
+
 impl PathsProvider for K8sPathsProvider {
     type IntoIter = Vec<PathBuf>;
 
@@ -54,9 +83,11 @@ impl PathsProvider for K8sPathsProvider {
                     false
                 }
             })
+            .filter(|pod| self.annotation_filter.matches(pod))
             .flat_map(|pod| {
                 trace!(message = "Providing log paths for pod.", pod = ?pod.metadata.name);
-                let paths_iter = list_pod_log_paths(real_glob, pod.as_ref());
+                let paths_iter =
+                    list_pod_log_paths(real_glob, pod.as_ref(), self.legacy_pod_log_directories);
                 exclude_paths(paths_iter, &self.exclude_paths).collect::<Vec<_>>()
             })
             .collect()
@@ -86,16 +117,27 @@ fn extract_pod_logs_directory(pod: &Pod) -> Option<PathBuf> {
     let metadata = &pod.metadata;
     let namespace = metadata.namespace.as_ref()?;
     let name = metadata.name.as_ref()?;
+    let uid = extract_pod_logs_uid(pod)?;
 
-    let uid = if let Some(static_pod_config_hashsum) = extract_static_pod_config_hashsum(metadata) {
+    Some(build_pod_logs_directory(namespace, name, uid))
+}
+
+/// This function takes a `Pod` resource and returns the path to where the logs
+/// for the said `Pod` are expected to be found by `kubelet`s prior to 1.14,
+/// which only use its uid.
+fn extract_legacy_pod_logs_directory(pod: &Pod) -> Option<PathBuf> {
+    extract_pod_logs_uid(pod).map(build_legacy_pod_logs_directory)
+}
+
+fn extract_pod_logs_uid(pod: &Pod) -> Option<&str> {
+    let metadata = &pod.metadata;
+    if let Some(static_pod_config_hashsum) = extract_static_pod_config_hashsum(metadata) {
         // If there's a static pod config hashsum - use it instead of uid.
-        static_pod_config_hashsum
+        Some(static_pod_config_hashsum)
     } else {
         // In the common case - just fallback to the real pod uid.
-        metadata.uid.as_ref()?
-    };
-
-    Some(build_pod_logs_directory(namespace, name, uid))
+        metadata.uid.as_deref()
+    }
 }
 
 const CONTAINER_EXCLUSION_ANNOTATION_KEY: &str = "vector.dev/exclude-containers";
@@ -129,13 +171,21 @@ fn build_container_exclusion_patterns<'a>(
 fn list_pod_log_paths<'a, G, GI>(
     mut glob_impl: G,
     pod: &'a Pod,
+    legacy_pod_log_directories: bool,
 ) -> impl Iterator<Item = PathBuf> + 'a
 where
     G: FnMut(&str) -> GI + 'a,
     GI: Iterator<Item = PathBuf> + 'a,
 {
+    let legacy_dir = if legacy_pod_log_directories {
+        extract_legacy_pod_logs_directory(pod)
+    } else {
+        None
+    };
+
     extract_pod_logs_directory(pod)
         .into_iter()
+        .chain(legacy_dir)
         .flat_map(move |dir| {
             let dir = dir
                 .to_str()
@@ -200,7 +250,7 @@ mod tests {
 
     use super::{
         build_container_exclusion_patterns, exclude_paths, extract_excluded_containers_for_pod,
-        extract_pod_logs_directory, list_pod_log_paths,
+        extract_pod_logs_directory, list_pod_log_paths, AnnotationFilter,
     };
 
     #[test]
@@ -439,12 +489,85 @@ mod tests {
                 paths_to_return.into_iter().map(PathBuf::from)
             };
 
-            let actual_paths: Vec<_> = list_pod_log_paths(mock_glob, &pod).collect();
+            let actual_paths: Vec<_> = list_pod_log_paths(mock_glob, &pod, false).collect();
             let expected_paths: Vec<_> = expected_paths.into_iter().map(PathBuf::from).collect();
             assert_eq!(actual_paths, expected_paths)
         }
     }
 
+    #[test]
+    fn test_list_legacy_pod_log_paths() {
+        let pod = Pod {
+            metadata: ObjectMeta {
+                namespace: Some("sandbox0-ns".to_owned()),
+                name: Some("sandbox0-name".to_owned()),
+                uid: Some("sandbox0-uid".to_owned()),
+                ..ObjectMeta::default()
+            },
+            ..Pod::default()
+        };
+        let mut expected_calls = vec![
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/*/*.log*",
+                vec![],
+            ),
+            (
+                "/var/log/pods/sandbox0-uid/*/*.log*",
+                vec!["/var/log/pods/sandbox0-uid/container1/0.log"],
+            ),
+        ]
+        .into_iter();
+        let mock_glob = move |pattern: &str| {
+            let (expected_pattern, paths_to_return) = expected_calls
+                .next()
+                .expect("implementation did a call that wasn't expected");
+
+            assert_eq!(pattern, expected_pattern);
+            paths_to_return.into_iter().map(PathBuf::from)
+        };
+
+        let actual_paths: Vec<_> = list_pod_log_paths(mock_glob, &pod, true).collect();
+        assert_eq!(
+            actual_paths,
+            vec![PathBuf::from("/var/log/pods/sandbox0-uid/container1/0.log")]
+        );
+    }
+
+    #[test]
+    fn test_annotation_filter() {
+        let pod = Pod {
+            metadata: ObjectMeta {
+                annotations: Some(
+                    vec![
+                        ("team".to_owned(), "payments".to_owned()),
+                        ("logs".to_owned(), "verbose".to_owned()),
+                    ]
+                    .into_iter()
+                    .collect(),
+                ),
+                ..ObjectMeta::default()
+            },
+            ..Pod::default()
+        };
+        let filter = |include: Vec<(&str, &str)>, exclude: Vec<(&str, &str)>| AnnotationFilter {
+            include: include
+                .into_iter()
+                .map(|(key, value)| (key.to_owned(), value.to_owned()))
+                .collect(),
+            exclude: exclude
+                .into_iter()
+                .map(|(key, value)| (key.to_owned(), value.to_owned()))
+                .collect(),
+        };
+
+        assert!(filter(vec![], vec![]).matches(&pod));
+        assert!(filter(vec![("team", "payments")], vec![]).matches(&pod));
+        assert!(!filter(vec![("team", "payments"), ("tier", "web")], vec![]).matches(&pod));
+        assert!(!filter(vec![("team", "search")], vec![]).matches(&pod));
+        assert!(filter(vec![], vec![("logs", "none")]).matches(&pod));
+        assert!(!filter(vec![], vec![("logs", "none"), ("team", "payments")]).matches(&pod));
+    }
+
     #[test]
     fn test_exclude_paths() {
         let cases = vec![
@@ -0,0 +1,130 @@
+//! Persists the k8s metadata across restarts.
+
+#![deny(missing_docs)]
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use k8s_openapi::api::core::v1::{Namespace, Node, Pod};
+use kube::runtime::reflector::store::Store;
+use serde::{Deserialize, Serialize};
+
+const TMP_FILE_NAME: &str = "metadata.new.json";
+const STABLE_FILE_NAME: &str = "metadata.json";
+
+/// The k8s metadata, as of the last time it was cached.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Snapshot {
+    /// The `Pod`s.
+    pub pods: Vec<Pod>,
+    /// The `Namespace`s.
+    pub namespaces: Vec<Namespace>,
+    /// The `Node`s.
+    pub nodes: Vec<Node>,
+}
+
+/// A cache of the k8s metadata in the data directory, which annotates the
+/// events before the k8s API is reached, and the events of the `Pod`s deleted
+/// while Vector wasn't running.
+#[derive(Clone, Debug)]
+pub struct MetadataCache {
+    tmp_file_path: PathBuf,
+    stable_file_path: PathBuf,
+}
+
+impl MetadataCache {
+    /// Create a new [`MetadataCache`] in the `data_dir`.
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            tmp_file_path: data_dir.join(TMP_FILE_NAME),
+            stable_file_path: data_dir.join(STABLE_FILE_NAME),
+        }
+    }
+
+    /// Read the cached metadata, which is empty if it couldn't be read.
+    pub fn read(&self) -> Snapshot {
+        let result = fs::File::open(&self.stable_file_path).and_then(|file| {
+            serde_json::from_reader(io::BufReader::new(file))
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+        });
+        match result {
+            Ok(snapshot) => {
+                info!(message = "Loaded cached Kubernetes metadata.");
+                snapshot
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Snapshot::default(),
+            Err(error) => {
+                warn!(message = "Unable to load cached Kubernetes metadata.", %error);
+                Snapshot::default()
+            }
+        }
+    }
+
+    /// Write the metadata of the stores, replacing the cached one atomically.
+    pub fn write(
+        &self,
+        pods: &Store<Pod>,
+        namespaces: &Store<Namespace>,
+        nodes: &Store<Node>,
+    ) -> Result<(), io::Error> {
+        let snapshot = Snapshot {
+            pods: pods.state().iter().map(|pod| Pod::clone(pod)).collect(),
+            namespaces: namespaces
+                .state()
+                .iter()
+                .map(|namespace| Namespace::clone(namespace))
+                .collect(),
+            nodes: nodes.state().iter().map(|node| Node::clone(node)).collect(),
+        };
+
+        let mut file = io::BufWriter::new(fs::File::create(&self.tmp_file_path)?);
+        serde_json::to_writer(&mut file, &snapshot)?;
+        file.into_inner()?.sync_all()?;
+        fs::rename(&self.tmp_file_path, &self.stable_file_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+    use kube::runtime::{reflector::store, watcher};
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn write_and_read() {
+        let data_dir = tempdir().unwrap();
+        let cache = MetadataCache::new(data_dir.path());
+        assert!(cache.read().pods.is_empty());
+
+        let pod = Pod {
+            metadata: ObjectMeta {
+                namespace: Some("sandbox0-ns".to_owned()),
+                name: Some("sandbox0-name".to_owned()),
+                uid: Some("sandbox0-uid".to_owned()),
+                ..ObjectMeta::default()
+            },
+            ..Pod::default()
+        };
+        let mut pods = store::Writer::default();
+        pods.apply_watcher_event(&watcher::Event::Applied(pod.clone()));
+        let namespaces = store::Writer::<Namespace>::default();
+        let nodes = store::Writer::<Node>::default();
+
+        cache
+            .write(
+                &pods.as_reader(),
+                &namespaces.as_reader(),
+                &nodes.as_reader(),
+            )
+            .unwrap();
+
+        let snapshot = MetadataCache::new(data_dir.path()).read();
+        assert_eq!(snapshot.pods, vec![pod]);
+        assert!(snapshot.namespaces.is_empty());
+        assert!(snapshot.nodes.is_empty());
+    }
+}
@@ -5,7 +5,7 @@
 
 #![deny(missing_docs)]
 
-use std::{convert::TryInto, path::PathBuf, time::Duration};
+use std::{collections::BTreeMap, convert::TryInto, path::PathBuf, time::Duration};
 
 use bytes::Bytes;
 use chrono::Utc;
@@ -38,7 +38,7 @@ use crate::{
         BytesReceived, FileSourceInternalEventsEmitter, KubernetesLifecycleError,
         KubernetesLogsEventAnnotationError, KubernetesLogsEventNamespaceAnnotationError,
         KubernetesLogsEventNodeAnnotationError, KubernetesLogsEventsReceived,
-        KubernetesLogsPodInfo, StreamClosedError,
+        KubernetesLogsPodInfo, KubernetesMetadataCacheError, StreamClosedError,
     },
     kubernetes::custom_reflector,
     shutdown::ShutdownSignal,
@@ -49,6 +49,7 @@ use crate::{
 
 mod k8s_paths_provider;
 mod lifecycle;
+mod metadata_cache;
 mod namespace_metadata_annotator;
 mod node_metadata_annotator;
 mod parser;
@@ -62,8 +63,9 @@ use self::namespace_metadata_annotator::NamespaceMetadataAnnotator;
 use self::node_metadata_annotator::NodeMetadataAnnotator;
 use self::pod_metadata_annotator::PodMetadataAnnotator;
 use futures::{future::FutureExt, stream::StreamExt};
-use k8s_paths_provider::K8sPathsProvider;
+use k8s_paths_provider::{AnnotationFilter, K8sPathsProvider};
 use lifecycle::Lifecycle;
+use metadata_cache::MetadataCache;
 
 /// The key we use for `file` field.
 const FILE_KEY: &str = "file";
@@ -71,6 +73,9 @@ const FILE_KEY: &str = "file";
 /// The `self_node_name` value env var key.
 const SELF_NODE_NAME_ENV_KEY: &str = "VECTOR_SELF_NODE_NAME";
 
+/// The interval between the writes of the metadata cache.
+const METADATA_CACHE_INTERVAL: Duration = Duration::from_secs(30);
+
 /// Configuration for the `kubernetes_logs` source.
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields, default)]
@@ -83,6 +88,30 @@ pub struct Config {
     /// addition to the built-in `vector.dev/exclude` filter.
     extra_namespace_label_selector: String,
 
+    /// Specifies the labels the `Pod`s must have to be included, folded into
+    /// the label selector.
+    include_pod_labels: BTreeMap<String, String>,
+
+    /// Specifies the labels that exclude the `Pod`s having any of them,
+    /// folded into the label selector.
+    exclude_pod_labels: BTreeMap<String, String>,
+
+    /// Specifies the labels the `Namespace`s must have to be included, folded
+    /// into the namespace label selector.
+    include_namespace_labels: BTreeMap<String, String>,
+
+    /// Specifies the labels that exclude the `Namespace`s having any of them,
+    /// folded into the namespace label selector.
+    exclude_namespace_labels: BTreeMap<String, String>,
+
+    /// Specifies the annotations the `Pod`s must have for their log files to
+    /// be read.
+    include_pod_annotations: BTreeMap<String, String>,
+
+    /// Specifies the annotations that prevent the log files of the `Pod`s
+    /// having any of them from being read.
+    exclude_pod_annotations: BTreeMap<String, String>,
+
     /// The `name` of the Kubernetes `Node` that Vector runs at.
     /// Required to filter the `Pod`s to only include the ones with the log
     /// files accessible locally.
@@ -148,6 +177,16 @@ pub struct Config {
     /// How long to delay removing entries from our map when we receive a deletion
     /// event from the watched stream.
     delay_deletion_ms: usize,
+
+    /// Also read the log files in the legacy `/var/log/pods/<pod_uid>`
+    /// layout, used by older `kubelet`s, such as the ones of OpenShift 3
+    /// running CRI-O.
+    legacy_pod_log_directories: bool,
+
+    /// Cache the k8s metadata in the data directory, to annotate the events
+    /// before the k8s API is reached after a restart, including the ones of
+    /// the `Pod`s deleted in the meantime.
+    metadata_cache: bool,
 }
 
 inventory::submit! {
@@ -170,6 +209,12 @@ impl Default for Config {
         Self {
             extra_label_selector: "".to_string(),
             extra_namespace_label_selector: "".to_string(),
+            include_pod_labels: BTreeMap::new(),
+            exclude_pod_labels: BTreeMap::new(),
+            include_namespace_labels: BTreeMap::new(),
+            exclude_namespace_labels: BTreeMap::new(),
+            include_pod_annotations: BTreeMap::new(),
+            exclude_pod_annotations: BTreeMap::new(),
             self_node_name: default_self_node_name_env_template(),
             extra_field_selector: "".to_string(),
             auto_partial_merge: true,
@@ -186,6 +231,8 @@ impl Default for Config {
             timezone: None,
             kube_config_file: None,
             delay_deletion_ms: default_delay_deletion_ms(),
+            legacy_pod_log_directories: false,
+            metadata_cache: false,
         }
     }
 }
@@ -231,6 +278,9 @@ struct Source {
     node_selector: String,
     self_node_name: String,
     exclude_paths: Vec<glob::Pattern>,
+    annotation_filter: AnnotationFilter,
+    legacy_pod_log_directories: bool,
+    metadata_cache: bool,
     max_read_bytes: usize,
     max_line_bytes: usize,
     fingerprint_lines: usize,
@@ -260,9 +310,16 @@ impl Source {
         };
 
         let field_selector = prepare_field_selector(config, self_node_name.as_str())?;
-        let label_selector = prepare_label_selector(config.extra_label_selector.as_ref());
-        let namespace_label_selector =
-            prepare_label_selector(config.extra_namespace_label_selector.as_ref());
+        let label_selector = prepare_label_selector(
+            config.extra_label_selector.as_ref(),
+            &config.include_pod_labels,
+            &config.exclude_pod_labels,
+        );
+        let namespace_label_selector = prepare_label_selector(
+            config.extra_namespace_label_selector.as_ref(),
+            &config.include_namespace_labels,
+            &config.exclude_namespace_labels,
+        );
         let node_selector = prepare_node_selector(self_node_name.as_str())?;
 
         // If the user passed a custom Kubeconfig use it, otherwise
@@ -310,6 +367,12 @@ impl Source {
             node_selector,
             self_node_name,
             exclude_paths,
+            annotation_filter: AnnotationFilter {
+                include: config.include_pod_annotations.clone(),
+                exclude: config.exclude_pod_annotations.clone(),
+            },
+            legacy_pod_log_directories: config.legacy_pod_log_directories,
+            metadata_cache: config.metadata_cache,
             max_read_bytes: config.max_read_bytes,
             max_line_bytes: config.max_line_bytes,
             fingerprint_lines: config.fingerprint_lines,
@@ -338,6 +401,9 @@ impl Source {
             node_selector,
             self_node_name,
             exclude_paths,
+            annotation_filter,
+            legacy_pod_log_directories,
+            metadata_cache,
             max_read_bytes,
             max_line_bytes,
             fingerprint_lines,
@@ -349,6 +415,12 @@ impl Source {
 
         let mut reflectors = Vec::new();
 
+        let metadata_cache = metadata_cache.then(|| MetadataCache::new(&data_dir));
+        let cached = metadata_cache
+            .as_ref()
+            .map(MetadataCache::read)
+            .unwrap_or_default();
+
         let pods = Api::<Pod>::all(client.clone());
         let pod_watcher = watcher(
             pods,
@@ -363,7 +435,7 @@ impl Source {
 
         reflectors.push(tokio::spawn(custom_reflector(
             pod_store_w,
-            pod_watcher,
+            with_cached(cached.pods, pod_watcher),
             delay_deletion,
         )));
 
@@ -382,7 +454,7 @@ impl Source {
 
         reflectors.push(tokio::spawn(custom_reflector(
             ns_store_w,
-            ns_watcher,
+            with_cached(cached.namespaces, ns_watcher),
             delay_deletion,
         )));
 
@@ -401,15 +473,38 @@ impl Source {
 
         reflectors.push(tokio::spawn(custom_reflector(
             node_store_w,
-            node_watcher,
+            with_cached(cached.nodes, node_watcher),
             delay_deletion,
         )));
 
-        let paths_provider =
-            K8sPathsProvider::new(pod_state.clone(), ns_state.clone(), exclude_paths);
-        let annotator = PodMetadataAnnotator::new(pod_state, pod_fields_spec);
-        let ns_annotator = NamespaceMetadataAnnotator::new(ns_state, namespace_fields_spec);
-        let node_annotator = NodeMetadataAnnotator::new(node_state, node_field_spec);
+        if let Some(metadata_cache) = &metadata_cache {
+            let metadata_cache = metadata_cache.clone();
+            let (pod_state, ns_state, node_state) =
+                (pod_state.clone(), ns_state.clone(), node_state.clone());
+            reflectors.push(tokio::spawn(async move {
+                // Skip the immediate tick to not overwrite the cache before
+                // the cached metadata is applied.
+                let start = tokio::time::Instant::now() + METADATA_CACHE_INTERVAL;
+                let mut interval = tokio::time::interval_at(start, METADATA_CACHE_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    if let Err(error) = metadata_cache.write(&pod_state, &ns_state, &node_state) {
+                        emit!(KubernetesMetadataCacheError { error });
+                    }
+                }
+            }));
+        }
+
+        let paths_provider = K8sPathsProvider::new(
+            pod_state.clone(),
+            ns_state.clone(),
+            exclude_paths,
+            annotation_filter,
+            legacy_pod_log_directories,
+        );
+        let annotator = PodMetadataAnnotator::new(pod_state.clone(), pod_fields_spec);
+        let ns_annotator = NamespaceMetadataAnnotator::new(ns_state.clone(), namespace_fields_spec);
+        let node_annotator = NodeMetadataAnnotator::new(node_state.clone(), node_field_spec);
 
         // TODO: maybe more of the parameters have to be configurable.
 
@@ -439,7 +534,7 @@ impl Source {
             // Delimiter bytes that is used to read the file line-by-line
             line_delimiter: Bytes::from("\n"),
             // The directory where to keep the checkpoints.
-            data_dir,
+            data_dir: data_dir.clone(),
             // This value specifies not exactly the globbing, but interval
             // between the polling the files to watch from the `paths_provider`.
             glob_minimum_cooldown,
@@ -461,6 +556,9 @@ impl Source {
             oldest_first: true,
             // We do not remove the log files, `kubelet` is responsible for it.
             remove_after: None,
+            // The checkpoints of removed log files expire on their own, so we
+            // don't limit them.
+            max_checkpoints: None,
             // The standard emitter.
             emitter: FileSourceInternalEventsEmitter,
             // A handle to the current tokio runtime
@@ -493,15 +591,15 @@ impl Source {
                 file: &line.filename,
                 byte_size: event.size_of(),
                 pod_info: file_info.as_ref().map(|info| KubernetesLogsPodInfo {
-                    name: info.pod_name.to_owned(),
-                    namespace: info.pod_namespace.to_owned(),
+                    name: info.pod_name.to_string(),
+                    namespace: info.pod_namespace.to_string(),
                 }),
             });
 
             if file_info.is_none() {
                 emit!(KubernetesLogsEventAnnotationError { event: &event });
             } else {
-                let namespace = file_info.as_ref().map(|info| info.pod_namespace);
+                let namespace = file_info.as_ref().map(|info| info.pod_namespace.as_ref());
 
                 if let Some(name) = namespace {
                     let ns_info = ns_annotator.annotate(&mut event, name);
@@ -572,11 +670,30 @@ impl Source {
         for reflector in reflectors {
             reflector.abort();
         }
+        if let Some(metadata_cache) = metadata_cache {
+            if let Err(error) = metadata_cache.write(&pod_state, &ns_state, &node_state) {
+                emit!(KubernetesMetadataCacheError { error });
+            }
+        }
         info!(message = "Done.");
         Ok(())
     }
 }
 
+/// Prepends the cached objects to the watcher stream, as if they were applied
+/// before the first listing.
+fn with_cached<K>(
+    cached: Vec<K>,
+    stream: impl Stream<Item = watcher::Result<watcher::Event<K>>>,
+) -> impl Stream<Item = watcher::Result<watcher::Event<K>>> {
+    futures::stream::iter(
+        cached
+            .into_iter()
+            .map(|object| Ok(watcher::Event::Applied(object))),
+    )
+    .chain(stream)
+}
+
 fn create_event(line: Bytes, file: &str, ingestion_timestamp_field: Option<&str>) -> Event {
     let mut event = LogEvent::from(line);
 
@@ -687,18 +804,32 @@ fn prepare_node_selector(self_node_name: &str) -> crate::Result<String> {
 
 // This function constructs the effective label selector to use, based on
 // the specified configuration.
-fn prepare_label_selector(selector: &str) -> String {
+fn prepare_label_selector(
+    selector: &str,
+    include: &BTreeMap<String, String>,
+    exclude: &BTreeMap<String, String>,
+) -> String {
     const BUILT_IN: &str = "vector.dev/exclude!=true";
 
-    if selector.is_empty() {
-        return BUILT_IN.to_string();
+    let mut label_selector = BUILT_IN.to_string();
+    for (key, value) in include {
+        label_selector.push_str(&format!(",{}={}", key, value));
+    }
+    for (key, value) in exclude {
+        label_selector.push_str(&format!(",{}!={}", key, value));
+    }
+    if !selector.is_empty() {
+        label_selector.push(',');
+        label_selector.push_str(selector);
     }
 
-    format!("{},{}", BUILT_IN, selector)
+    label_selector
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
+
     use super::Config;
 
     #[test]
@@ -830,8 +961,30 @@ mod tests {
         ];
 
         for (input, expected) in cases {
-            let output = super::prepare_label_selector(&input);
+            let output = super::prepare_label_selector(&input, &BTreeMap::new(), &BTreeMap::new());
             assert_eq!(expected, output, "expected left, actual right");
         }
     }
+
+    #[test]
+    fn prepare_label_selector_with_labels() {
+        let include = vec![("app".to_owned(), "web".to_owned())]
+            .into_iter()
+            .collect();
+        let exclude = vec![
+            ("tier".to_owned(), "debug".to_owned()),
+            ("team".to_owned(), "qa".to_owned()),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            super::prepare_label_selector("", &include, &exclude),
+            "vector.dev/exclude!=true,app=web,team!=qa,tier!=debug"
+        );
+        assert_eq!(
+            super::prepare_label_selector("qwe", &include, &BTreeMap::new()),
+            "vector.dev/exclude!=true,app=web,qwe"
+        );
+    }
 }
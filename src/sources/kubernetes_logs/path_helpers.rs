@@ -4,7 +4,7 @@
 
 #![deny(missing_docs)]
 
-use std::path::PathBuf;
+use std::{borrow::Cow, path::PathBuf};
 
 /// The root directory for pod logs.
 const K8S_LOGS_DIR: &str = "/var/log/pods";
@@ -28,9 +28,18 @@ pub(super) fn build_pod_logs_directory(
     .into()
 }
 
+/// Builds absolute log directory path for a pod sandbox in the legacy layout,
+/// used by `kubelet`s prior to 1.14, such as the ones of OpenShift 3 running
+/// CRI-O.
+pub(super) fn build_legacy_pod_logs_directory(pod_uid: &str) -> PathBuf {
+    [K8S_LOGS_DIR, pod_uid].join("/").into()
+}
+
 /// Parses pod log file path and returns the log file info.
 ///
-/// Assumes the input is a valid pod log file name.
+/// Assumes the input is a valid pod log file name. The namespace and the name
+/// of the pod are empty for the paths of the legacy layout, which only
+/// contain its uid.
 ///
 /// Inspired by https://github.com/kubernetes/kubernetes/blob/31305966789525fca49ec26c289e565467d1f1c4/pkg/kubelet/kuberuntime/helpers.go#L186
 pub(super) fn parse_log_file_path(path: &str) -> Option<LogFileInfo<'_>> {
@@ -40,6 +49,20 @@ pub(super) fn parse_log_file_path(path: &str) -> Option<LogFileInfo<'_>> {
     let container_name = components.next()?;
     let pod_dir = components.next()?;
 
+    if !pod_dir.contains(LOG_PATH_DELIMITER) {
+        // The legacy layout, `<pod_uid>/<container_name>/<n>.log`, under the
+        // root directory for pod logs.
+        if components.next()? != "pods" || pod_dir.is_empty() {
+            return None;
+        }
+        return Some(LogFileInfo {
+            pod_namespace: Cow::Borrowed(""),
+            pod_name: Cow::Borrowed(""),
+            pod_uid: pod_dir,
+            container_name,
+        });
+    }
+
     let mut pod_dir_components = pod_dir.rsplit(LOG_PATH_DELIMITER);
 
     let pod_uid = pod_dir_components.next()?;
@@ -47,8 +70,8 @@ pub(super) fn parse_log_file_path(path: &str) -> Option<LogFileInfo<'_>> {
     let pod_namespace = pod_dir_components.next()?;
 
     Some(LogFileInfo {
-        pod_namespace,
-        pod_name,
+        pod_namespace: Cow::Borrowed(pod_namespace),
+        pod_name: Cow::Borrowed(pod_name),
         pod_uid,
         container_name,
     })
@@ -57,12 +80,20 @@ pub(super) fn parse_log_file_path(path: &str) -> Option<LogFileInfo<'_>> {
 /// Contains the information extracted from the pod log file path.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct LogFileInfo<'a> {
-    pub pod_namespace: &'a str,
-    pub pod_name: &'a str,
+    pub pod_namespace: Cow<'a, str>,
+    pub pod_name: Cow<'a, str>,
     pub pod_uid: &'a str,
     pub container_name: &'a str,
 }
 
+impl<'a> LogFileInfo<'a> {
+    /// Whether the path is in the legacy layout, which doesn't contain the
+    /// namespace and the name of the pod.
+    pub fn is_legacy(&self) -> bool {
+        self.pod_name.is_empty()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -94,8 +125,17 @@ mod tests {
             (
                 "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/1.log",
                 Some(LogFileInfo {
-                    pod_namespace: "sandbox0-ns",
-                    pod_name: "sandbox0-name",
+                    pod_namespace: "sandbox0-ns".into(),
+                    pod_name: "sandbox0-name".into(),
+                    pod_uid: "sandbox0-uid",
+                    container_name: "sandbox0-container0-name",
+                }),
+            ),
+            (
+                "/var/log/pods/sandbox0-uid/sandbox0-container0-name/1.log",
+                Some(LogFileInfo {
+                    pod_namespace: "".into(),
+                    pod_name: "".into(),
                     pod_uid: "sandbox0-uid",
                     container_name: "sandbox0-container0-name",
                 }),
@@ -71,9 +71,21 @@ impl PodMetadataAnnotator {
     /// [`FILE_KEY`] field set with a file that the line came from.
     pub fn annotate<'a>(&self, event: &mut Event, file: &'a str) -> Option<LogFileInfo<'a>> {
         let log = event.as_mut_log();
-        let file_info = parse_log_file_path(file)?;
-        let obj = ObjectRef::<Pod>::new(file_info.pod_name).within(file_info.pod_namespace);
-        let resource = self.pods_state_reader.get(&obj)?;
+        let mut file_info = parse_log_file_path(file)?;
+        let resource = if file_info.is_legacy() {
+            // The paths of the legacy layout only contain the uid of the pod.
+            let resource = self
+                .pods_state_reader
+                .state()
+                .into_iter()
+                .find(|pod| pod.metadata.uid.as_deref() == Some(file_info.pod_uid))?;
+            file_info.pod_namespace = resource.metadata.namespace.clone()?.into();
+            file_info.pod_name = resource.metadata.name.clone()?.into();
+            resource
+        } else {
+            let obj = ObjectRef::<Pod>::new(&file_info.pod_name).within(&file_info.pod_namespace);
+            self.pods_state_reader.get(&obj)?
+        };
         let pod: &Pod = resource.as_ref();
 
         annotate_from_file_info(log, &self.fields_spec, &file_info);
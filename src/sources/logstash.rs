@@ -23,7 +23,7 @@ use crate::{
     event::{Event, Value},
     serde::bool_or_struct,
     tcp::TcpKeepaliveConfig,
-    tls::{MaybeTlsSettings, TlsEnableableConfig},
+    tls::{CertificateMetadata, MaybeTlsSettings, TlsEnableableConfig},
     types,
 };
 
@@ -109,7 +109,12 @@ impl TcpSource for LogstashSource {
         LogstashDecoder::new()
     }
 
-    fn handle_events(&self, events: &mut [Event], host: SocketAddr) {
+    fn handle_events(
+        &self,
+        events: &mut [Event],
+        host: SocketAddr,
+        _certificate: Option<&CertificateMetadata>,
+    ) {
         let now = Value::from(chrono::Utc::now());
         for event in events {
             let log = event.as_mut_log();
@@ -0,0 +1,417 @@
+use std::{collections::VecDeque, path::PathBuf};
+
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use http::{header::CONTENT_TYPE, Request};
+use hyper::Body;
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+use url::Url;
+
+use crate::{
+    config::{DataType, GenerateConfig, Output, SourceConfig, SourceContext, SourceDescription},
+    http::HttpClient,
+    sources::util::audit_log::{
+        default_interval_secs, default_lookback_secs, parse_timestamp, ApiClient, ApiError,
+        AuditLogApi, Checkpointer, Page, Poller, Response,
+    },
+    tls::{TlsConfig, TlsSettings},
+};
+
+const LOGIN_URL: &str = "https://login.microsoftonline.com/";
+const MANAGEMENT_URL: &str = "https://manage.office.com/";
+
+/// The listings of available content span at most a day, and can't start more than a week ago.
+const MAX_WINDOW_HOURS: i64 = 24;
+const RETENTION_DAYS: i64 = 7;
+
+/// The error returned when starting a subscription which has already been started.
+const SUBSCRIPTION_ENABLED: &str = "AF20024";
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Microsoft365Config {
+    /// The ID of the Azure AD tenant of the organization.
+    tenant_id: String,
+    /// The ID of an application registered in the tenant, granted the `ActivityFeed.Read`
+    /// permission of the Office 365 Management APIs.
+    client_id: String,
+    client_secret: String,
+    /// The types of content to collect, such as `Audit.Exchange` or `DLP.All`.
+    #[serde(default = "default_content_types")]
+    content_types: Vec<String>,
+    #[serde(default = "default_interval_secs")]
+    interval_secs: u64,
+    #[serde(default = "default_lookback_secs")]
+    lookback_secs: u64,
+    data_dir: Option<PathBuf>,
+    tls: Option<TlsConfig>,
+}
+
+fn default_content_types() -> Vec<String> {
+    [
+        "Audit.AzureActiveDirectory",
+        "Audit.Exchange",
+        "Audit.SharePoint",
+        "Audit.General",
+    ]
+    .iter()
+    .map(|content_type| content_type.to_string())
+    .collect()
+}
+
+inventory::submit! {
+    SourceDescription::new::<Microsoft365Config>("microsoft_365")
+}
+
+impl GenerateConfig for Microsoft365Config {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"tenant_id = "00000000-0000-0000-0000-000000000000"
+            client_id = "00000000-0000-0000-0000-000000000000"
+            client_secret = "${MICROSOFT_365_CLIENT_SECRET}""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "microsoft_365")]
+impl SourceConfig for Microsoft365Config {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        if self.content_types.is_empty() {
+            return Err("At least one content type must be configured".into());
+        }
+        let data_dir = cx
+            .globals
+            .resolve_and_make_data_subdir(self.data_dir.as_ref(), cx.key.id())?;
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls, &cx.proxy)?;
+
+        let api = ManagementActivity {
+            token_url: Url::parse(LOGIN_URL)?
+                .join(&format!("{}/oauth2/v2.0/token", self.tenant_id))?,
+            feed_url: Url::parse(MANAGEMENT_URL)?
+                .join(&format!("api/v1.0/{}/activity/feed/", self.tenant_id))?,
+            tenant_id: self.tenant_id.clone(),
+            client_id: self.client_id.clone(),
+            client_secret: self.client_secret.clone(),
+            content_types: self.content_types.clone(),
+            lookback: Duration::seconds(self.lookback_secs as i64),
+            token: None,
+            subscribed: false,
+            window: None,
+        };
+        let poller = Poller {
+            client: ApiClient::new(client),
+            checkpointer: Checkpointer::new(data_dir),
+            interval: std::time::Duration::from_secs(self.interval_secs),
+            source_type: "microsoft_365",
+            timestamp_field: "CreationTime",
+        };
+        Ok(Box::pin(poller.run(api, cx.out, cx.shutdown)))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "microsoft_365"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+/// The [Office 365 Management Activity API]. Records are published in blobs of content, which
+/// are listed by the time they became available. Each poll collects the blobs of every content
+/// type made available in a window of time, and the cursor is the end of the last window
+/// collected.
+///
+/// [Office 365 Management Activity API]: https://docs.microsoft.com/en-us/office/office-365-management-api/office-365-management-activity-api-reference
+struct ManagementActivity {
+    token_url: Url,
+    feed_url: Url,
+    tenant_id: String,
+    client_id: String,
+    client_secret: String,
+    content_types: Vec<String>,
+    lookback: Duration,
+    token: Option<AccessToken>,
+    /// Content is only made available once the subscriptions to its types are started.
+    subscribed: bool,
+    /// The window being collected, when its content hasn't all been fetched yet.
+    window: Option<Window>,
+}
+
+struct AccessToken {
+    value: String,
+    expires_at: Instant,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Blob {
+    content_uri: String,
+}
+
+struct Window {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    /// Whether the window ends now, rather than being one of several to catch up with.
+    last: bool,
+    /// The content types left to collect, the first one being collected.
+    pending: VecDeque<String>,
+    /// The next page of the listing of the content being collected.
+    next_page: Option<String>,
+}
+
+impl Window {
+    /// The window following the cursor, bounded by the span and retention of the listings,
+    /// unless it's empty.
+    fn next(
+        cursor: Option<&str>,
+        now: DateTime<Utc>,
+        lookback: Duration,
+        content_types: &[String],
+    ) -> Option<Self> {
+        let start = cursor
+            .and_then(parse_timestamp)
+            .unwrap_or_else(|| now - lookback)
+            // Leave some room for the time the requests take.
+            .max(now - Duration::days(RETENTION_DAYS) + Duration::minutes(5));
+        let end = now.min(start + Duration::hours(MAX_WINDOW_HOURS));
+        (start < end).then(|| Self {
+            start,
+            end,
+            last: end == now,
+            pending: content_types.iter().cloned().collect(),
+            next_page: None,
+        })
+    }
+}
+
+/// The listings take times in UTC without a time zone.
+fn format_time(time: DateTime<Utc>) -> String {
+    time.format("%Y-%m-%dT%H:%M:%S").to_string()
+}
+
+impl ManagementActivity {
+    async fn token(&mut self, client: &mut ApiClient) -> crate::Result<String> {
+        if let Some(token) = &self.token {
+            // Tokens are renewed a bit before they expire, so they don't expire in flight.
+            if token.expires_at > Instant::now() + std::time::Duration::from_secs(60) {
+                return Ok(token.value.clone());
+            }
+        }
+
+        let body = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair("grant_type", "client_credentials")
+            .append_pair("client_id", &self.client_id)
+            .append_pair("client_secret", &self.client_secret)
+            .append_pair("scope", &format!("{}.default", MANAGEMENT_URL))
+            .finish();
+        let token_url = &self.token_url;
+        let response: TokenResponse = client
+            .send(|| {
+                Request::post(token_url.as_str())
+                    .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                    .body(Body::from(body.clone()))
+                    .expect("valid request")
+            })
+            .await?
+            .json()?;
+
+        let value = format!("Bearer {}", response.access_token);
+        self.token = Some(AccessToken {
+            value: value.clone(),
+            expires_at: Instant::now() + std::time::Duration::from_secs(response.expires_in),
+        });
+        Ok(value)
+    }
+
+    /// Builds the URL of an operation of the API, identifying the tenant as the publisher of
+    /// the requests so they're counted against its own quota.
+    fn url(&self, operation: &str, content_type: &str) -> Url {
+        let mut url = self.feed_url.join(operation).expect("valid path");
+        url.query_pairs_mut()
+            .append_pair("contentType", content_type)
+            .append_pair("PublisherIdentifier", &self.tenant_id);
+        url
+    }
+
+    async fn subscribe(&mut self, client: &mut ApiClient) -> crate::Result<()> {
+        for content_type in self.content_types.clone() {
+            let token = self.token(client).await?;
+            let url = self.url("subscriptions/start", &content_type);
+            let result = client
+                .send(|| {
+                    Request::post(url.as_str())
+                        .header("Authorization", token.as_str())
+                        .header(CONTENT_TYPE, "application/json")
+                        .body(Body::empty())
+                        .expect("valid request")
+                })
+                .await;
+            match result {
+                Ok(_) => debug!(message = "Started subscription.", %content_type),
+                Err(ApiError::Status { body, .. }) if body.contains(SUBSCRIPTION_ENABLED) => {}
+                Err(error) => return Err(error.into()),
+            }
+        }
+        self.subscribed = true;
+        Ok(())
+    }
+
+    async fn get(&mut self, client: &mut ApiClient, url: &str) -> crate::Result<Response> {
+        let token = self.token(client).await?;
+        client
+            .send(|| {
+                Request::get(url)
+                    .header("Authorization", token.as_str())
+                    .body(Body::empty())
+                    .expect("valid request")
+            })
+            .await
+            .map_err(Into::into)
+    }
+}
+
+#[async_trait::async_trait]
+impl AuditLogApi for ManagementActivity {
+    async fn fetch(&mut self, client: &mut ApiClient, cursor: Option<&str>) -> crate::Result<Page> {
+        if !self.subscribed {
+            self.subscribe(client).await?;
+        }
+
+        // A window whose collection failed is started over.
+        let mut window = match self.window.take() {
+            Some(window) => window,
+            None => match Window::next(cursor, Utc::now(), self.lookback, &self.content_types) {
+                Some(window) => window,
+                None => {
+                    return Ok(Page {
+                        records: Vec::new(),
+                        cursor: None,
+                        more: false,
+                    })
+                }
+            },
+        };
+
+        let content_type = match window.pending.front() {
+            Some(content_type) => content_type.clone(),
+            None => {
+                return Ok(Page {
+                    records: Vec::new(),
+                    cursor: Some(window.end.to_rfc3339_opts(SecondsFormat::Secs, true)),
+                    more: !window.last,
+                })
+            }
+        };
+        let listing = match window.next_page.take() {
+            Some(next_page) => next_page,
+            None => {
+                let mut url = self.url("subscriptions/content", &content_type);
+                url.query_pairs_mut()
+                    .append_pair("startTime", &format_time(window.start))
+                    .append_pair("endTime", &format_time(window.end));
+                url.into()
+            }
+        };
+
+        let response = self.get(client, &listing).await?;
+        let blobs: Vec<Blob> = response.json()?;
+        let mut records = Vec::new();
+        for blob in blobs {
+            let content = self.get(client, &blob.content_uri).await?;
+            records.extend(content.json::<Vec<serde_json::Value>>()?);
+        }
+
+        window.next_page = response
+            .headers
+            .get("NextPageUri")
+            .and_then(|value| value.to_str().ok())
+            .map(Into::into);
+        if window.next_page.is_none() {
+            window.pending.pop_front();
+        }
+
+        if window.pending.is_empty() {
+            Ok(Page {
+                records,
+                cursor: Some(window.end.to_rfc3339_opts(SecondsFormat::Secs, true)),
+                more: !window.last,
+            })
+        } else {
+            self.window = Some(window);
+            Ok(Page {
+                records,
+                cursor: None,
+                more: true,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<Microsoft365Config>();
+    }
+
+    #[test]
+    fn windows_are_bounded() {
+        let now = Utc.ymd(2022, 6, 10).and_hms(12, 0, 0);
+        let content_types = default_content_types();
+        let next = |cursor| Window::next(cursor, now, Duration::hours(1), &content_types);
+
+        let window = next(None).unwrap();
+        assert_eq!(window.start, Utc.ymd(2022, 6, 10).and_hms(11, 0, 0));
+        assert_eq!(window.end, now);
+        assert!(window.last);
+        assert_eq!(window.pending.len(), 4);
+
+        // Catching up is done a day at a time.
+        let window = next(Some("2022-06-08T06:00:00Z")).unwrap();
+        assert_eq!(window.end, Utc.ymd(2022, 6, 9).and_hms(6, 0, 0));
+        assert!(!window.last);
+
+        // Content older than the retention period can't be listed anymore.
+        let window = next(Some("2022-05-01T00:00:00Z")).unwrap();
+        assert_eq!(window.start, Utc.ymd(2022, 6, 3).and_hms(12, 5, 0));
+        assert_eq!(format_time(window.start), "2022-06-03T12:05:00");
+
+        assert!(next(Some("2022-06-10T12:00:00Z")).is_none());
+    }
+
+    #[test]
+    fn parses_listings() {
+        let blobs: Vec<Blob> = serde_json::from_str(
+            r#"[{
+                "contentUri": "https://manage.office.com/api/v1.0/tenant/activity/feed/audit/1$2",
+                "contentId": "1$2",
+                "contentType": "Audit.Exchange",
+                "contentCreated": "2022-06-10T11:30:00.000Z",
+                "contentExpiration": "2022-06-17T11:30:00.000Z"
+            }]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            blobs[0].content_uri,
+            "https://manage.office.com/api/v1.0/tenant/activity/feed/audit/1$2"
+        );
+    }
+}
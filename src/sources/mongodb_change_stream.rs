@@ -0,0 +1,635 @@
+use std::{convert::TryFrom, io, path::PathBuf, time::Duration};
+
+use bytes::Bytes;
+use chrono::{TimeZone, Utc};
+use futures::StreamExt;
+use mongodb::{
+    bson::{self, Bson, Document},
+    change_stream::{event::ResumeToken, ChangeStream},
+    error::Error as MongoError,
+    options::{ChangeStreamOptions, ClientOptions, FullDocumentType},
+    Client,
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use tokio::time::sleep;
+use vector_core::ByteSizeOf;
+
+use super::util::finalizer::OrderedFinalizer;
+use crate::{
+    config::{
+        log_schema, AcknowledgementsConfig, DataType, GenerateConfig, Output, SourceConfig,
+        SourceContext, SourceDescription,
+    },
+    event::{BatchNotifier, BatchStatus, Event, LogEvent, Value},
+    internal_events::{
+        EventsReceived, MongoDbChangeStreamCheckpointError, MongoDbChangeStreamError,
+        StreamClosedError,
+    },
+    serde::bool_or_struct,
+    shutdown::ShutdownSignal,
+    sinks::util::retries::ExponentialBackoff,
+    SourceSender,
+};
+
+const CHECKPOINT_FILENAME: &str = "checkpoint.json";
+
+/// How often the resume token of the last delivered change is written to the checkpoint.
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("invalid endpoint: {}", source))]
+    InvalidEndpoint { source: MongoError },
+    #[snafu(display("invalid client options: {}", source))]
+    InvalidClientOptions { source: MongoError },
+    #[snafu(display("`collection` requires `database` to be set."))]
+    CollectionWithoutDatabase,
+}
+
+/// What the events of updates hold.
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+enum FullDocument {
+    /// The changed and removed fields only.
+    #[derivative(Default)]
+    Delta,
+    /// The current version of the updated document as well, which is looked up when the change is
+    /// read, so it may hold later changes.
+    UpdateLookup,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct MongoDbChangeStreamConfig {
+    /// The connection string of the replica set or sharded cluster.
+    endpoint: String,
+    /// The database whose changes are consumed, or all of the deployment's when unset.
+    database: Option<String>,
+    /// The collection of the database whose changes are consumed, or all of the database's when
+    /// unset.
+    collection: Option<String>,
+    #[serde(default)]
+    full_document: FullDocument,
+    data_dir: Option<PathBuf>,
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+inventory::submit! {
+    SourceDescription::new::<MongoDbChangeStreamConfig>("mongodb_change_stream")
+}
+
+impl GenerateConfig for MongoDbChangeStreamConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"endpoint = "mongodb://localhost:27017/?replicaSet=rs0"
+            database = "app"
+            collection = "users""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "mongodb_change_stream")]
+impl SourceConfig for MongoDbChangeStreamConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        if self.collection.is_some() && self.database.is_none() {
+            return Err(BuildError::CollectionWithoutDatabase.into());
+        }
+
+        let options = ClientOptions::parse(&self.endpoint)
+            .await
+            .context(InvalidEndpointSnafu)?;
+        let client = Client::with_options(options).context(InvalidClientOptionsSnafu)?;
+
+        let data_dir = cx
+            .globals
+            .resolve_and_make_data_subdir(self.data_dir.as_ref(), cx.key.id())?;
+
+        let source = MongoDbChangeStream {
+            client,
+            database: self.database.clone(),
+            collection: self.collection.clone(),
+            full_document: self.full_document,
+            checkpointer: Checkpointer::new(data_dir),
+        };
+        let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
+        Ok(Box::pin(source.run(cx.out, cx.shutdown, acknowledgements)))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "mongodb_change_stream"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+const fn fresh_backoff() -> ExponentialBackoff {
+    ExponentialBackoff::from_millis(2)
+        .factor(250)
+        .max_delay(Duration::from_secs(60))
+}
+
+struct MongoDbChangeStream {
+    client: Client,
+    database: Option<String>,
+    collection: Option<String>,
+    full_document: FullDocument,
+    checkpointer: Checkpointer,
+}
+
+impl MongoDbChangeStream {
+    async fn run(
+        self,
+        mut out: SourceSender,
+        mut shutdown: ShutdownSignal,
+        acknowledgements: bool,
+    ) -> Result<(), ()> {
+        let (finalizer, mut ack_stream) =
+            OrderedFinalizer::<ResumeToken>::maybe_new(acknowledgements, shutdown.clone());
+        // The token the stream is resumed after when it's reopened, which is the one of the last
+        // change sent, while the checkpoint holds the one of the last change delivered.
+        let mut resume_token = self.checkpointer.get().await;
+        let mut pending_checkpoint = None;
+        let mut checkpoint_interval = tokio::time::interval(CHECKPOINT_INTERVAL);
+        let mut backoff = fresh_backoff();
+
+        'watch: loop {
+            let mut stream = match self.watch(resume_token.clone()).await {
+                Ok(stream) => stream,
+                Err(error) => {
+                    emit!(MongoDbChangeStreamError { error });
+                    tokio::select! {
+                        _ = &mut shutdown => break,
+                        _ = sleep(backoff.next().unwrap()) => continue,
+                    }
+                }
+            };
+
+            loop {
+                tokio::select! {
+                    _ = &mut shutdown => break 'watch,
+                    _ = checkpoint_interval.tick() => {
+                        if let Some(token) = pending_checkpoint.take() {
+                            self.checkpointer.set(&token).await;
+                        }
+                    },
+                    entry = ack_stream.next() => {
+                        if let Some((BatchStatus::Delivered, token)) = entry {
+                            pending_checkpoint = Some(token);
+                        }
+                    },
+                    change = stream.next() => match change {
+                        Some(Ok(change)) => {
+                            backoff = fresh_backoff();
+                            let token = match stream.resume_token() {
+                                Some(token) => token,
+                                None => continue,
+                            };
+                            let event = Event::from(change_to_log(change));
+                            emit!(EventsReceived {
+                                count: 1,
+                                byte_size: event.size_of(),
+                            });
+
+                            let event = match &finalizer {
+                                Some(finalizer) => {
+                                    let (batch, receiver) = BatchNotifier::new_with_receiver();
+                                    finalizer.add(token.clone(), receiver);
+                                    event.with_batch_notifier(&batch)
+                                }
+                                None => {
+                                    pending_checkpoint = Some(token.clone());
+                                    event
+                                }
+                            };
+                            if let Err(error) = out.send_event(event).await {
+                                emit!(StreamClosedError { error, count: 1 });
+                                break 'watch;
+                            }
+                            resume_token = Some(token);
+                        }
+                        Some(Err(error)) => {
+                            emit!(MongoDbChangeStreamError { error });
+                            tokio::select! {
+                                _ = &mut shutdown => break 'watch,
+                                _ = sleep(backoff.next().unwrap()) => continue 'watch,
+                            }
+                        }
+                        // The stream ends once it's invalidated, such as when its collection is
+                        // dropped, after which it's reopened to consume the changes following
+                        // the invalidation.
+                        None => continue 'watch,
+                    },
+                }
+            }
+        }
+
+        if let Some(token) = pending_checkpoint {
+            self.checkpointer.set(&token).await;
+        }
+        Ok(())
+    }
+
+    /// Opens the change stream, after the change of the resume token when there's one.
+    async fn watch(
+        &self,
+        resume_token: Option<ResumeToken>,
+    ) -> Result<ChangeStream<Document>, MongoError> {
+        // The stream is resumed with `startAfter` rather than `resumeAfter`, which rejects the
+        // tokens of invalidations.
+        let options = ChangeStreamOptions::builder()
+            .full_document(match self.full_document {
+                FullDocument::Delta => None,
+                FullDocument::UpdateLookup => Some(FullDocumentType::UpdateLookup),
+            })
+            .start_after(resume_token)
+            .build();
+
+        let stream = match (&self.database, &self.collection) {
+            (Some(database), Some(collection)) => self
+                .client
+                .database(database)
+                .collection::<Document>(collection)
+                .watch(None, options)
+                .await?
+                .with_type::<Document>(),
+            (Some(database), None) => self
+                .client
+                .database(database)
+                .watch(None, options)
+                .await?
+                .with_type::<Document>(),
+            _ => self
+                .client
+                .watch(None, options)
+                .await?
+                .with_type::<Document>(),
+        };
+        Ok(stream)
+    }
+}
+
+/// Builds the event of a change from its raw document, rather than from the driver's change
+/// events, so that the fields added by newer servers are kept.
+fn change_to_log(mut change: Document) -> LogEvent {
+    let mut log = LogEvent::default();
+    if let Ok(operation) = change.get_str("operationType") {
+        log.insert("operation", operation.to_owned());
+    }
+    if let Ok(namespace) = change.get_document("ns") {
+        if let Ok(database) = namespace.get_str("db") {
+            log.insert("database", database.to_owned());
+        }
+        if let Ok(collection) = namespace.get_str("coll") {
+            log.insert("collection", collection.to_owned());
+        }
+    }
+    if let Some(to) = change.remove("to") {
+        log.insert("to", bson_to_value(to));
+    }
+    if let Some(document_key) = change.remove("documentKey") {
+        log.insert("document_key", bson_to_value(document_key));
+    }
+    match change.remove("fullDocument") {
+        None | Some(Bson::Null) => {}
+        Some(full_document) => {
+            log.insert("full_document", bson_to_value(full_document));
+        }
+    }
+    if let Ok(mut update) = change
+        .get_document_mut("updateDescription")
+        .map(std::mem::take)
+    {
+        if let Some(updated_fields) = update.remove("updatedFields") {
+            log.insert("updated_fields", bson_to_value(updated_fields));
+        }
+        if let Some(removed_fields) = update.remove("removedFields") {
+            log.insert("removed_fields", bson_to_value(removed_fields));
+        }
+    }
+
+    // The wall time of the change is only reported by MongoDB 6.0 and later, and the cluster time
+    // is only precise to the second.
+    let timestamp = match (
+        change.get_datetime("wallTime"),
+        change.get_timestamp("clusterTime"),
+    ) {
+        (Ok(wall_time), _) => wall_time.to_chrono(),
+        (_, Ok(cluster_time)) => Utc.timestamp(cluster_time.time as i64, 0),
+        _ => Utc::now(),
+    };
+    log.insert(log_schema().timestamp_key(), timestamp);
+    log.insert(
+        log_schema().source_type_key(),
+        Bytes::from("mongodb_change_stream"),
+    );
+    log
+}
+
+/// Converts the BSON value to the matching type, or to its relaxed extended JSON representation
+/// when there's none.
+fn bson_to_value(bson: Bson) -> Value {
+    match bson {
+        Bson::Null | Bson::Undefined => Value::Null,
+        Bson::Boolean(boolean) => Value::from(boolean),
+        Bson::Int32(int) => Value::from(int as i64),
+        Bson::Int64(int) => Value::from(int),
+        Bson::Double(double) => Value::from(double),
+        Bson::String(string) | Bson::Symbol(string) => Value::from(string),
+        Bson::DateTime(datetime) => Value::from(datetime.to_chrono()),
+        Bson::ObjectId(id) => Value::from(id.to_hex()),
+        Bson::Decimal128(decimal) => Value::from(decimal.to_string()),
+        Bson::Binary(binary) => Value::from(Bytes::from(binary.bytes)),
+        Bson::Array(array) => Value::Array(array.into_iter().map(bson_to_value).collect()),
+        Bson::Document(document) => Value::Object(
+            document
+                .into_iter()
+                .map(|(key, value)| (key, bson_to_value(value)))
+                .collect(),
+        ),
+        bson => Value::from(bson.into_relaxed_extjson()),
+    }
+}
+
+/// Keeps the resume token of the last delivered change in the data directory, in its extended
+/// JSON representation.
+struct Checkpointer {
+    path: PathBuf,
+}
+
+impl Checkpointer {
+    fn new(data_dir: PathBuf) -> Self {
+        Self {
+            path: data_dir.join(CHECKPOINT_FILENAME),
+        }
+    }
+
+    async fn get(&self) -> Option<ResumeToken> {
+        let token = match tokio::fs::read(&self.path).await {
+            Ok(token) => token,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return None,
+            Err(error) => {
+                emit!(MongoDbChangeStreamCheckpointError {
+                    error: error.into(),
+                    path: &self.path,
+                });
+                return None;
+            }
+        };
+
+        let token = serde_json::from_slice::<serde_json::Value>(&token)
+            .map_err(crate::Error::from)
+            .and_then(|token| Bson::try_from(token).map_err(Into::into))
+            .and_then(|token| bson::from_bson::<ResumeToken>(token).map_err(Into::into));
+        match token {
+            Ok(token) => Some(token),
+            Err(error) => {
+                emit!(MongoDbChangeStreamCheckpointError {
+                    error,
+                    path: &self.path,
+                });
+                None
+            }
+        }
+    }
+
+    /// Writes the token to a temporary file first, so a crash can't leave a partial token behind.
+    async fn set(&self, token: &ResumeToken) {
+        let temp = self.path.with_extension("tmp");
+        let result = async {
+            let token = bson::to_bson(token)?.into_relaxed_extjson();
+            tokio::fs::write(&temp, serde_json::to_vec(&token)?).await?;
+            tokio::fs::rename(&temp, &self.path).await?;
+            Ok::<_, crate::Error>(())
+        }
+        .await;
+        if let Err(error) = result {
+            emit!(MongoDbChangeStreamCheckpointError {
+                error,
+                path: &self.path,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::{doc, oid::ObjectId, DateTime, Timestamp};
+
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<MongoDbChangeStreamConfig>();
+    }
+
+    #[test]
+    fn builds_insert_events() {
+        let id = ObjectId::new();
+        let log = change_to_log(doc! {
+            "_id": { "_data": "8262" },
+            "operationType": "insert",
+            "clusterTime": Timestamp { time: 1_650_000_000, increment: 1 },
+            "ns": { "db": "app", "coll": "users" },
+            "documentKey": { "_id": id },
+            "fullDocument": { "_id": id, "name": "ferris", "age": 7 },
+        });
+
+        assert_eq!(log["operation"], "insert".into());
+        assert_eq!(log["database"], "app".into());
+        assert_eq!(log["collection"], "users".into());
+        assert_eq!(log["document_key._id"], id.to_hex().into());
+        assert_eq!(log["full_document.name"], "ferris".into());
+        assert_eq!(log["full_document.age"], 7.into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.timestamp(1_650_000_000, 0).into()
+        );
+        assert_eq!(
+            log[log_schema().source_type_key()],
+            "mongodb_change_stream".into()
+        );
+        assert!(log.get("updated_fields").is_none());
+    }
+
+    #[test]
+    fn builds_update_events() {
+        let wall_time = DateTime::from_millis(1_650_000_000_123);
+        let log = change_to_log(doc! {
+            "_id": { "_data": "8262" },
+            "operationType": "update",
+            "clusterTime": Timestamp { time: 1_650_000_000, increment: 1 },
+            "wallTime": wall_time,
+            "ns": { "db": "app", "coll": "users" },
+            "documentKey": { "_id": 1 },
+            "updateDescription": {
+                "updatedFields": { "name": "crab" },
+                "removedFields": ["age"],
+            },
+            "fullDocument": Bson::Null,
+        });
+
+        assert_eq!(log["operation"], "update".into());
+        assert_eq!(log["updated_fields.name"], "crab".into());
+        assert_eq!(log["removed_fields[0]"], "age".into());
+        assert!(log.get("full_document").is_none());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            wall_time.to_chrono().into()
+        );
+    }
+
+    #[test]
+    fn converts_bson_values() {
+        assert_eq!(bson_to_value(Bson::Int32(1)), 1.into());
+        assert_eq!(bson_to_value(Bson::Double(1.5)), 1.5.into());
+        assert_eq!(
+            bson_to_value(Bson::Array(vec![Bson::Boolean(true), Bson::Null])),
+            Value::Array(vec![true.into(), Value::Null])
+        );
+        assert_eq!(
+            bson_to_value(Bson::Timestamp(Timestamp {
+                time: 1,
+                increment: 2
+            })),
+            Value::from(serde_json::json!({"$timestamp": {"t": 1, "i": 2}}))
+        );
+    }
+
+    #[tokio::test]
+    async fn checkpoints_resume_tokens() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let checkpointer = Checkpointer::new(data_dir.path().to_path_buf());
+        assert!(checkpointer.get().await.is_none());
+
+        let token: ResumeToken = bson::from_bson(Bson::Document(doc! { "_data": "8262" })).unwrap();
+        checkpointer.set(&token).await;
+        assert_eq!(checkpointer.get().await, Some(token));
+    }
+}
+
+#[cfg(all(test, feature = "mongodb_change_stream-integration-tests"))]
+mod integration_tests {
+    use mongodb::bson::doc;
+
+    use super::*;
+    use crate::{
+        config::GlobalOptions,
+        event::EventStatus,
+        test_util::{
+            collect_n,
+            components::{assert_source_compliance, SOURCE_TAGS},
+            random_string,
+        },
+    };
+
+    fn endpoint() -> String {
+        std::env::var("PRIMARY_MONGODB_ADDRESS")
+            .unwrap_or_else(|_| "mongodb://localhost:27017".into())
+    }
+
+    async fn run_source(
+        config: &MongoDbChangeStreamConfig,
+        data_dir: PathBuf,
+        changes: impl std::future::Future<Output = ()>,
+        count: usize,
+    ) -> Vec<Event> {
+        assert_source_compliance(&SOURCE_TAGS, async {
+            let (tx, rx) = SourceSender::new_test_finalize(EventStatus::Delivered);
+            let (trigger, shutdown, shutdown_done) = ShutdownSignal::new_wired();
+            let mut cx = SourceContext::new_test(tx, None);
+            cx.shutdown = shutdown;
+            cx.globals = GlobalOptions {
+                data_dir: Some(data_dir),
+                ..Default::default()
+            };
+            let source = tokio::spawn(config.build(cx).await.unwrap());
+
+            // Wait for the change stream to be opened.
+            sleep(Duration::from_secs(1)).await;
+            changes.await;
+            let events = collect_n(rx, count).await;
+
+            // Wait for the checkpoint to be written.
+            sleep(CHECKPOINT_INTERVAL * 2).await;
+            drop(trigger);
+            shutdown_done.await;
+            source.await.unwrap().unwrap();
+            events
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn consumes_and_resumes_changes() {
+        let database = format!("vector_{}", random_string(10).to_lowercase());
+        let client = Client::with_uri_str(endpoint()).await.unwrap();
+        let collection = client.database(&database).collection::<Document>("users");
+        let config: MongoDbChangeStreamConfig = toml::from_str(&format!(
+            r#"
+            endpoint = "{}"
+            database = "{}"
+            collection = "users"
+            full_document = "update_lookup"
+            "#,
+            endpoint(),
+            database
+        ))
+        .unwrap();
+        let data_dir = tempfile::tempdir().unwrap();
+
+        let events = run_source(
+            &config,
+            data_dir.path().to_path_buf(),
+            async {
+                collection
+                    .insert_one(doc! { "_id": 1, "name": "ferris" }, None)
+                    .await
+                    .unwrap();
+                collection
+                    .update_one(doc! { "_id": 1 }, doc! { "$set": { "name": "crab" } }, None)
+                    .await
+                    .unwrap();
+                collection
+                    .delete_one(doc! { "_id": 1 }, None)
+                    .await
+                    .unwrap();
+            },
+            3,
+        )
+        .await;
+
+        let operations = events
+            .iter()
+            .map(|event| event.as_log()["operation"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            operations,
+            vec!["insert".into(), "update".into(), "delete".into()]
+        );
+        assert_eq!(events[0].as_log()["full_document.name"], "ferris".into());
+        assert_eq!(events[1].as_log()["updated_fields.name"], "crab".into());
+        assert_eq!(events[2].as_log()["document_key._id"], 1.into());
+
+        // The changes made while the source is stopped are consumed once it's restarted.
+        collection
+            .insert_one(doc! { "_id": 2, "name": "ferris" }, None)
+            .await
+            .unwrap();
+        let events = run_source(&config, data_dir.path().to_path_buf(), async {}, 1).await;
+        assert_eq!(events[0].as_log()["operation"], "insert".into());
+        assert_eq!(events[0].as_log()["document_key._id"], 2.into());
+
+        client.database(&database).drop(None).await.unwrap();
+    }
+}
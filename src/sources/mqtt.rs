@@ -0,0 +1,376 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+use chrono::Utc;
+use codecs::decoding::{DeserializerConfig, FramingConfig, StreamDecodingError};
+use futures::StreamExt;
+use rumqttc::{AsyncClient, Event as MqttEvent, EventLoop, Packet, Publish, SubscribeFilter};
+use serde::{Deserialize, Serialize};
+use tokio::time::sleep;
+use tokio_util::codec::FramedRead;
+use vector_core::ByteSizeOf;
+
+use super::util::finalizer::UnorderedFinalizer;
+use crate::{
+    codecs::{Decoder, DecodingConfig},
+    config::{
+        log_schema, AcknowledgementsConfig, GenerateConfig, Output, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::{BatchNotifier, BatchStatus, Event},
+    internal_events::{
+        prelude::error_stage, BytesReceived, MqttAcknowledgementError, MqttConnectionError,
+        MqttEventsReceived, StreamClosedError,
+    },
+    mqtt::{MqttConfig, MqttQoS},
+    serde::{bool_or_struct, default_decoding, default_framing_message_based},
+    shutdown::ShutdownSignal,
+    sinks::util::retries::ExponentialBackoff,
+    SourceSender,
+};
+
+/// The number of requests, such as acknowledgements, queued for the event loop of the client.
+const CLIENT_CAPACITY: usize = 1024;
+
+#[derive(Clone, Debug, Derivative, Deserialize, Serialize)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct MqttSourceConfig {
+    #[serde(flatten)]
+    connection: MqttConfig,
+    /// The topic filters subscribed to, which can contain the `+` and `#` wildcards.
+    topics: Vec<String>,
+    #[serde(default)]
+    qos: MqttQoS,
+    /// The field the topic of each message is inserted into.
+    #[serde(default = "default_topic_key")]
+    #[derivative(Default(value = "default_topic_key()"))]
+    topic_key: String,
+    /// Whether the broker discards the session of the client when it disconnects, rather than
+    /// keeping its subscriptions, and queuing the messages it misses, until it reconnects.
+    #[serde(default = "crate::serde::default_true")]
+    #[derivative(Default(value = "true"))]
+    clean_session: bool,
+    #[serde(default = "default_framing_message_based")]
+    #[derivative(Default(value = "default_framing_message_based()"))]
+    framing: FramingConfig,
+    #[serde(default = "default_decoding")]
+    #[derivative(Default(value = "default_decoding()"))]
+    decoding: DeserializerConfig,
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+fn default_topic_key() -> String {
+    "topic".into()
+}
+
+inventory::submit! {
+    SourceDescription::new::<MqttSourceConfig>("mqtt")
+}
+
+impl GenerateConfig for MqttSourceConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"
+            host = "127.0.0.1"
+            topics = ["sensors/+/logs"]
+            "#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "mqtt")]
+impl SourceConfig for MqttSourceConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let mut options = self.connection.options()?;
+        options.set_clean_session(self.clean_session);
+        options.set_manual_acks(true);
+        let (client, eventloop) = AsyncClient::new(options, CLIENT_CAPACITY);
+
+        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build();
+        let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
+
+        Ok(Box::pin(mqtt_source(
+            self.clone(),
+            client,
+            eventloop,
+            decoder,
+            cx.shutdown,
+            cx.out,
+            acknowledgements,
+        )))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(self.decoding.output_type())]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "mqtt"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+const fn fresh_backoff() -> ExponentialBackoff {
+    ExponentialBackoff::from_millis(2)
+        .factor(250)
+        .max_delay(Duration::from_secs(60))
+}
+
+type Finalizer = UnorderedFinalizer<Publish>;
+
+async fn mqtt_source(
+    config: MqttSourceConfig,
+    client: AsyncClient,
+    mut eventloop: EventLoop,
+    decoder: Decoder,
+    mut shutdown: ShutdownSignal,
+    mut out: SourceSender,
+    acknowledgements: bool,
+) -> Result<(), ()> {
+    let (finalizer, mut ack_stream) = Finalizer::maybe_new(acknowledgements, shutdown.clone());
+    if finalizer.is_some() {
+        // Acknowledging waits for the event loop to take the request, so it can't be done by the
+        // task polling it.
+        let client = client.clone();
+        tokio::spawn(async move {
+            while let Some((status, publish)) = ack_stream.next().await {
+                handle_ack(&client, status, publish).await;
+            }
+        });
+    }
+
+    let filters = config
+        .topics
+        .iter()
+        .map(|topic| SubscribeFilter::new(topic.clone(), config.qos.into()))
+        .collect::<Vec<_>>();
+    let mut backoff = fresh_backoff();
+
+    loop {
+        let notification = tokio::select! {
+            _ = &mut shutdown => break,
+            notification = eventloop.poll() => notification,
+        };
+
+        match notification {
+            // The subscriptions are renewed on every connection, as they're discarded with the
+            // session when it's clean.
+            Ok(MqttEvent::Incoming(Packet::ConnAck(_))) => {
+                debug!(message = "Connected.", topics = ?config.topics);
+                backoff = fresh_backoff();
+                if let Err(error) = client.try_subscribe_many(filters.clone()) {
+                    emit!(MqttConnectionError {
+                        error,
+                        stage: error_stage::RECEIVING,
+                    });
+                }
+            }
+            Ok(MqttEvent::Incoming(Packet::Publish(publish))) => {
+                emit!(BytesReceived {
+                    byte_size: publish.payload.len(),
+                    protocol: "mqtt",
+                });
+
+                let events = parse_publish(&publish, &config.topic_key, decoder.clone()).await;
+                let count = events.len();
+                // Only the packet identifier is needed to acknowledge the message.
+                let mut entry = Publish::new("", publish.qos, Vec::new());
+                entry.pkid = publish.pkid;
+
+                match &finalizer {
+                    Some(finalizer) => {
+                        let (batch, receiver) = BatchNotifier::new_with_receiver();
+                        let events = events
+                            .into_iter()
+                            .map(|event| event.with_batch_notifier(&batch));
+                        if let Err(error) = out.send_batch(events).await {
+                            emit!(StreamClosedError { error, count });
+                            return Err(());
+                        }
+                        finalizer.add(entry, receiver);
+                    }
+                    None => {
+                        if let Err(error) = out.send_batch(events).await {
+                            emit!(StreamClosedError { error, count });
+                            return Err(());
+                        }
+                        if let Err(error) = client.try_ack(&entry) {
+                            emit!(MqttAcknowledgementError { error });
+                        }
+                    }
+                }
+            }
+            Ok(_) => {}
+            // Polling the event loop again reconnects to the broker.
+            Err(error) => {
+                emit!(MqttConnectionError {
+                    error,
+                    stage: error_stage::RECEIVING,
+                });
+                sleep(backoff.next().unwrap()).await;
+            }
+        }
+    }
+
+    let _ = client.try_disconnect();
+    Ok(())
+}
+
+/// Acknowledges the message once its events are delivered or rejected. MQTT has no negative
+/// acknowledgements, so the messages whose events failed to be delivered are left unacknowledged,
+/// for the broker to redeliver them once the client reconnects to its persistent session.
+async fn handle_ack(client: &AsyncClient, status: BatchStatus, publish: Publish) {
+    match status {
+        BatchStatus::Delivered | BatchStatus::Rejected => {
+            if let Err(error) = client.ack(&publish).await {
+                emit!(MqttAcknowledgementError { error });
+            }
+        }
+        BatchStatus::Errored => {}
+    }
+}
+
+async fn parse_publish(publish: &Publish, topic_key: &str, decoder: Decoder) -> Vec<Event> {
+    let timestamp = Utc::now();
+    let payload = Bytes::clone(&publish.payload);
+    let mut stream = FramedRead::new(payload.as_ref(), decoder);
+    let mut output = Vec::new();
+    while let Some(result) = stream.next().await {
+        match result {
+            Ok((events, _byte_size)) => {
+                emit!(MqttEventsReceived {
+                    count: events.len(),
+                    byte_size: events.size_of(),
+                    topic: &publish.topic,
+                });
+                output.extend(events.into_iter().map(|mut event| {
+                    if let Event::Log(ref mut log) = event {
+                        log.insert(log_schema().source_type_key(), Bytes::from("mqtt"));
+                        log.insert(log_schema().timestamp_key(), timestamp);
+                        log.insert(topic_key, publish.topic.clone());
+                    }
+                    event
+                }));
+            }
+            Err(error) => {
+                // Error is logged by `crate::codecs`, no further handling is needed here.
+                if !error.can_continue() {
+                    break;
+                }
+            }
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<MqttSourceConfig>();
+    }
+
+    #[test]
+    fn parses_flattened_connection() {
+        let config: MqttSourceConfig = toml::from_str(
+            r#"
+            host = "broker.example.com"
+            port = 8883
+            user = "vector"
+            password = "secret"
+            client_id = "vector-1"
+            topics = ["sensors/#"]
+            qos = "at_most_once"
+            clean_session = false
+            tls.enabled = true
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.connection.host, "broker.example.com");
+        assert_eq!(config.connection.port, 8883);
+        assert_eq!(config.qos, MqttQoS::AtMostOnce);
+        assert_eq!(config.topic_key, "topic");
+        assert!(!config.clean_session);
+    }
+
+    #[tokio::test]
+    async fn inserts_topic_into_field() {
+        let config = MqttSourceConfig::default();
+        let decoder = DecodingConfig::new(config.framing, config.decoding).build();
+        let publish = Publish::new("sensors/1/logs", rumqttc::QoS::AtLeastOnce, "hello");
+
+        let events = parse_publish(&publish, "topic", decoder).await;
+        let log = events[0].as_log();
+        assert_eq!(log[log_schema().message_key()], "hello".into());
+        assert_eq!(log["topic"], "sensors/1/logs".into());
+        assert_eq!(log[log_schema().source_type_key()], "mqtt".into());
+    }
+}
+
+#[cfg(feature = "mqtt-integration-tests")]
+#[cfg(test)]
+mod integration_tests {
+    use rumqttc::{MqttOptions, QoS};
+
+    use super::*;
+    use crate::test_util::{
+        collect_n,
+        components::{assert_source_compliance, SOURCE_TAGS},
+        random_string,
+    };
+
+    fn mqtt_host() -> String {
+        std::env::var("MQTT_HOST").unwrap_or_else(|_| "127.0.0.1".into())
+    }
+
+    #[tokio::test]
+    async fn consumes_messages_of_wildcard_topics() {
+        let prefix = format!("test-{}", random_string(10));
+        let config: MqttSourceConfig = toml::from_str(&format!(
+            r#"
+            host = "{}"
+            topics = ["{}/+/logs"]
+            "#,
+            mqtt_host(),
+            prefix
+        ))
+        .unwrap();
+
+        let events = assert_source_compliance(&SOURCE_TAGS, async {
+            let (tx, rx) = SourceSender::new_test();
+            let cx = SourceContext::new_test(tx, None);
+            tokio::spawn(config.build(cx).await.unwrap());
+
+            // Gives the source the time to subscribe.
+            sleep(Duration::from_secs(1)).await;
+            let options = MqttOptions::new(random_string(10), mqtt_host(), 1883);
+            let (client, mut eventloop) = AsyncClient::new(options, 10);
+            client
+                .publish(
+                    format!("{}/1/logs", prefix),
+                    QoS::AtLeastOnce,
+                    false,
+                    "hello",
+                )
+                .await
+                .unwrap();
+            tokio::spawn(async move { while eventloop.poll().await.is_ok() {} });
+
+            collect_n(rx, 1).await
+        })
+        .await;
+
+        let log = events[0].as_log();
+        assert_eq!(log[log_schema().message_key()], "hello".into());
+        assert_eq!(log["topic"], format!("{}/1/logs", prefix).into());
+    }
+}
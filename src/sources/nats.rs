@@ -1,24 +1,38 @@
+use std::{io, time::Duration};
+
 use bytes::Bytes;
 use chrono::Utc;
 use codecs::decoding::{DeserializerConfig, FramingConfig, StreamDecodingError};
 use futures::{pin_mut, stream, Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
+use tokio::time::{sleep, sleep_until, Instant};
 use tokio_util::codec::FramedRead;
 use vector_core::ByteSizeOf;
 
+use super::util::finalizer::UnorderedFinalizer;
 use crate::{
     codecs::{Decoder, DecodingConfig},
-    config::{log_schema, GenerateConfig, Output, SourceConfig, SourceContext, SourceDescription},
-    event::Event,
-    internal_events::{BytesReceived, OldEventsReceived, StreamClosedError},
+    config::{
+        log_schema, AcknowledgementsConfig, GenerateConfig, Output, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::{BatchNotifier, BatchStatus, Event},
+    internal_events::{
+        BytesReceived, NatsAcknowledgementError, NatsJetStreamPullError, OldEventsReceived,
+        StreamClosedError,
+    },
     nats::{from_tls_auth_config, NatsAuthConfig, NatsConfigError},
-    serde::{default_decoding, default_framing_message_based},
+    serde::{bool_or_struct, default_decoding, default_framing_message_based},
     shutdown::ShutdownSignal,
+    sinks::util::retries::ExponentialBackoff,
     tls::TlsEnableableConfig,
     SourceSender,
 };
 
+/// How long a pull request waits for messages before the server expires it and it's renewed.
+const PULL_EXPIRES: Duration = Duration::from_secs(5);
+
 #[derive(Debug, Snafu)]
 enum BuildError {
     #[snafu(display("NATS Config Error: {}", source))]
@@ -27,6 +41,12 @@ enum BuildError {
     Connect { source: std::io::Error },
     #[snafu(display("NATS Subscribe Error: {}", source))]
     Subscribe { source: std::io::Error },
+    #[snafu(display("NATS JetStream Consumer Error: {}", source))]
+    JetStreamConsumer { source: std::io::Error },
+    #[snafu(display(
+        "NATS Config Error: `queue` can't be set with `jetstream`, whose consumer is shared instead"
+    ))]
+    QueueWithJetStream,
 }
 
 #[derive(Clone, Debug, Derivative, Deserialize, Serialize)]
@@ -38,6 +58,8 @@ struct NatsSourceConfig {
     connection_name: String,
     subject: String,
     queue: Option<String>,
+    /// Consumes the subject from a JetStream stream, rather than subscribing to it.
+    jetstream: Option<NatsJetStreamConfig>,
     tls: Option<TlsEnableableConfig>,
     auth: Option<NatsAuthConfig>,
     #[serde(default = "default_framing_message_based")]
@@ -46,6 +68,37 @@ struct NatsSourceConfig {
     #[serde(default = "default_decoding")]
     #[derivative(Default(value = "default_decoding()"))]
     decoding: DeserializerConfig,
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+/// The durable pull consumer messages are consumed with. The server keeps track of the messages
+/// acknowledged by the consumer, so that consuming resumes where it stopped across restarts, and
+/// the instances sharing the consumer share its messages.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct NatsJetStreamConfig {
+    stream: String,
+    #[serde(default = "default_durable_name")]
+    durable_name: String,
+    /// The maximum number of messages requested at once.
+    #[serde(default = "default_batch_size")]
+    batch_size: usize,
+    /// How long the server waits for a message to be acknowledged before redelivering it.
+    #[serde(default = "default_ack_wait_secs")]
+    ack_wait_secs: u64,
+}
+
+fn default_durable_name() -> String {
+    "vector".into()
+}
+
+const fn default_batch_size() -> usize {
+    100
+}
+
+const fn default_ack_wait_secs() -> u64 {
+    30
 }
 
 inventory::submit! {
@@ -68,16 +121,41 @@ impl GenerateConfig for NatsSourceConfig {
 #[typetag::serde(name = "nats")]
 impl SourceConfig for NatsSourceConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
-        let (connection, subscription) = create_subscription(self).await?;
         let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build();
 
-        Ok(Box::pin(nats_source(
-            connection,
-            subscription,
-            decoder,
-            cx.shutdown,
-            cx.out,
-        )))
+        match &self.jetstream {
+            None => {
+                let (connection, subscription) = create_subscription(self).await?;
+
+                Ok(Box::pin(nats_source(
+                    connection,
+                    subscription,
+                    decoder,
+                    cx.shutdown,
+                    cx.out,
+                )))
+            }
+            Some(jetstream) => {
+                if self.queue.is_some() {
+                    return Err(BuildError::QueueWithJetStream.into());
+                }
+                let connection = self.connect().await?;
+                let (consumer, subscription) =
+                    JetStreamConsumer::new(connection, jetstream, &self.subject)
+                        .await
+                        .context(JetStreamConsumerSnafu)?;
+                let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
+
+                Ok(Box::pin(jetstream_source(
+                    consumer,
+                    subscription,
+                    decoder,
+                    cx.shutdown,
+                    cx.out,
+                    acknowledgements,
+                )))
+            }
+        }
     }
 
     fn outputs(&self) -> Vec<Output> {
@@ -89,7 +167,7 @@ impl SourceConfig for NatsSourceConfig {
     }
 
     fn can_acknowledge(&self) -> bool {
-        false
+        self.jetstream.is_some()
     }
 }
 
@@ -131,41 +209,148 @@ async fn nats_source(
             byte_size: msg.data.len(),
             protocol: "tcp",
         });
-        let mut stream = FramedRead::new(msg.data.as_ref(), decoder.clone());
-        while let Some(next) = stream.next().await {
-            match next {
-                Ok((events, _byte_size)) => {
-                    let count = events.len();
-                    emit!(OldEventsReceived {
-                        byte_size: events.size_of(),
-                        count
-                    });
+        let events = decode_message(&msg.data, decoder.clone()).await;
+        let count = events.len();
+        out.send_batch(events).await.map_err(|error| {
+            emit!(StreamClosedError { error, count });
+        })?;
+    }
+    Ok(())
+}
 
-                    let now = Utc::now();
+const fn fresh_backoff() -> ExponentialBackoff {
+    ExponentialBackoff::from_millis(2)
+        .factor(250)
+        .max_delay(Duration::from_secs(60))
+}
 
-                    let events = events.into_iter().map(|mut event| {
-                        if let Event::Log(ref mut log) = event {
-                            log.try_insert(log_schema().source_type_key(), Bytes::from("nats"));
-                            log.try_insert(log_schema().timestamp_key(), now);
-                        }
-                        event
+/// Messages are acknowledged by publishing to their reply subject.
+type Finalizer = UnorderedFinalizer<String>;
+
+async fn jetstream_source(
+    consumer: JetStreamConsumer,
+    subscription: nats::asynk::Subscription,
+    decoder: Decoder,
+    mut shutdown: ShutdownSignal,
+    mut out: SourceSender,
+    acknowledgements: bool,
+) -> Result<(), ()> {
+    let (finalizer, mut ack_stream) = Finalizer::maybe_new(acknowledgements, shutdown.clone());
+    let messages = get_subscription_stream(subscription);
+    pin_mut!(messages);
+
+    // The number of messages the outstanding pull request can still be answered with.
+    let mut pending = 0;
+    let mut pull_deadline = Instant::now();
+    let mut backoff = fresh_backoff();
+
+    loop {
+        if pending == 0 {
+            if let Err(error) = consumer.pull().await {
+                emit!(NatsJetStreamPullError { error });
+                tokio::select! {
+                    _ = &mut shutdown => break,
+                    _ = sleep(backoff.next().unwrap()) => continue,
+                }
+            }
+            backoff = fresh_backoff();
+            pending = consumer.batch_size;
+            // The request is renewed once the server had the time to expire it, in case the
+            // status message expiring it was lost with the connection.
+            pull_deadline = Instant::now() + PULL_EXPIRES * 2;
+        }
+
+        tokio::select! {
+            _ = &mut shutdown => break,
+            entry = ack_stream.next() => if let Some((status, reply)) = entry {
+                handle_ack(&consumer, status, &reply).await;
+            },
+            _ = sleep_until(pull_deadline) => pending = 0,
+            message = messages.next() => match message {
+                None => break,
+                // The status messages ending pull requests, such as when they expire, are the
+                // only ones without a reply subject.
+                Some(nats::asynk::Message { reply: None, .. }) => pending = 0,
+                Some(nats::asynk::Message { reply: Some(reply), data, .. }) => {
+                    pending = pending.saturating_sub(1);
+                    emit!(BytesReceived {
+                        byte_size: data.len(),
+                        protocol: "tcp",
                     });
+                    let events = decode_message(&data, decoder.clone()).await;
+                    let count = events.len();
 
-                    out.send_batch(events).await.map_err(|error| {
-                        emit!(StreamClosedError { error, count });
-                    })?;
+                    match &finalizer {
+                        Some(finalizer) => {
+                            let (batch, receiver) = BatchNotifier::new_with_receiver();
+                            let events = events
+                                .into_iter()
+                                .map(|event| event.with_batch_notifier(&batch));
+                            if let Err(error) = out.send_batch(events).await {
+                                emit!(StreamClosedError { error, count });
+                                return Err(());
+                            }
+                            finalizer.add(reply, receiver);
+                        }
+                        None => {
+                            if let Err(error) = out.send_batch(events).await {
+                                emit!(StreamClosedError { error, count });
+                                return Err(());
+                            }
+                            handle_ack(&consumer, BatchStatus::Delivered, &reply).await;
+                        }
+                    }
                 }
-                Err(error) => {
-                    // Error is logged by `crate::codecs`, no further
-                    // handling is needed here.
-                    if !error.can_continue() {
-                        break;
+            },
+        }
+    }
+
+    Ok(())
+}
+
+/// Acknowledges the message once its events are delivered, has the server redeliver it when they
+/// failed to be, and terminates it when they're rejected, as redelivering it would fail again.
+async fn handle_ack(consumer: &JetStreamConsumer, status: BatchStatus, reply: &str) {
+    let body: &[u8] = match status {
+        BatchStatus::Delivered => b"+ACK",
+        BatchStatus::Errored => b"-NAK",
+        BatchStatus::Rejected => b"+TERM",
+    };
+    if let Err(error) = consumer.connection.publish(reply, body).await {
+        emit!(NatsAcknowledgementError { error });
+    }
+}
+
+async fn decode_message(data: &[u8], decoder: Decoder) -> Vec<Event> {
+    let now = Utc::now();
+    let mut stream = FramedRead::new(data, decoder);
+    let mut output = Vec::new();
+    while let Some(next) = stream.next().await {
+        match next {
+            Ok((events, _byte_size)) => {
+                emit!(OldEventsReceived {
+                    byte_size: events.size_of(),
+                    count: events.len(),
+                });
+
+                output.extend(events.into_iter().map(|mut event| {
+                    if let Event::Log(ref mut log) = event {
+                        log.try_insert(log_schema().source_type_key(), Bytes::from("nats"));
+                        log.try_insert(log_schema().timestamp_key(), now);
                     }
+                    event
+                }));
+            }
+            Err(error) => {
+                // Error is logged by `crate::codecs`, no further
+                // handling is needed here.
+                if !error.can_continue() {
+                    break;
                 }
             }
         }
     }
-    Ok(())
+    output
 }
 
 async fn create_subscription(
@@ -183,6 +368,98 @@ async fn create_subscription(
     Ok((nc, subscription))
 }
 
+/// A durable pull consumer of a JetStream stream, driven through the JetStream API subjects.
+struct JetStreamConsumer {
+    connection: nats::asynk::Connection,
+    /// The subject messages are requested from.
+    next_subject: String,
+    /// The subject messages are delivered to.
+    inbox: String,
+    batch_size: usize,
+}
+
+#[derive(Deserialize)]
+struct JetStreamApiResponse {
+    error: Option<JetStreamApiError>,
+}
+
+#[derive(Deserialize)]
+struct JetStreamApiError {
+    code: u16,
+    description: String,
+}
+
+impl JetStreamConsumer {
+    /// Creates the durable consumer, or resumes it when it exists already, and subscribes to the
+    /// inbox its messages are delivered to.
+    async fn new(
+        connection: nats::asynk::Connection,
+        config: &NatsJetStreamConfig,
+        subject: &str,
+    ) -> io::Result<(Self, nats::asynk::Subscription)> {
+        let request = serde_json::json!({
+            "stream_name": config.stream,
+            "config": {
+                "durable_name": config.durable_name,
+                "deliver_policy": "all",
+                "ack_policy": "explicit",
+                "ack_wait": Duration::from_secs(config.ack_wait_secs).as_nanos() as u64,
+                "filter_subject": subject,
+            },
+        });
+        let response = connection
+            .request(
+                &format!(
+                    "$JS.API.CONSUMER.DURABLE.CREATE.{}.{}",
+                    config.stream, config.durable_name
+                ),
+                serde_json::to_vec(&request)?,
+            )
+            .await?;
+        check_api_response(&response.data)?;
+
+        let inbox = connection.new_inbox();
+        let subscription = connection.subscribe(&inbox).await?;
+        let consumer = Self {
+            connection,
+            next_subject: format!(
+                "$JS.API.CONSUMER.MSG.NEXT.{}.{}",
+                config.stream, config.durable_name
+            ),
+            inbox,
+            batch_size: config.batch_size,
+        };
+        Ok((consumer, subscription))
+    }
+
+    /// Requests the next batch of messages, which the server answers as they become available,
+    /// until the request expires.
+    async fn pull(&self) -> io::Result<()> {
+        let request = serde_json::json!({
+            "batch": self.batch_size,
+            "expires": PULL_EXPIRES.as_nanos() as u64,
+        });
+        self.connection
+            .publish_request(
+                &self.next_subject,
+                &self.inbox,
+                serde_json::to_vec(&request)?,
+            )
+            .await
+    }
+}
+
+fn check_api_response(data: &[u8]) -> io::Result<()> {
+    let response: JetStreamApiResponse = serde_json::from_slice(data)?;
+    match response.error {
+        None => Ok(()),
+        Some(error) => Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!("{} (code {})", error.description, error.code),
+        )),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(clippy::print_stdout)] //tests
@@ -193,6 +470,39 @@ mod tests {
     fn generate_config() {
         crate::test_util::test_generate_config::<NatsSourceConfig>();
     }
+
+    #[test]
+    fn parses_jetstream_config() {
+        let config: NatsSourceConfig = toml::from_str(
+            r#"
+            connection_name = "vector"
+            subject = "logs.>"
+            url = "nats://127.0.0.1:4222"
+            jetstream.stream = "LOGS"
+            acknowledgements = true
+            "#,
+        )
+        .unwrap();
+        let jetstream = config.jetstream.as_ref().unwrap();
+        assert_eq!(jetstream.stream, "LOGS");
+        assert_eq!(jetstream.durable_name, "vector");
+        assert_eq!(jetstream.batch_size, 100);
+        assert!(config.can_acknowledge());
+    }
+
+    #[test]
+    fn checks_jetstream_api_errors() {
+        assert!(check_api_response(
+            br#"{"type":"io.nats.jetstream.api.v1.consumer_create_response","name":"vector"}"#
+        )
+        .is_ok());
+
+        let error = check_api_response(
+            br#"{"error":{"code":404,"err_code":10059,"description":"stream not found"}}"#,
+        )
+        .unwrap_err();
+        assert_eq!(error.to_string(), "stream not found (code 404)");
+    }
 }
 
 #[cfg(feature = "nats-integration-tests")]
@@ -241,6 +551,8 @@ mod integration_tests {
             subject: subject.clone(),
             url,
             queue: None,
+            jetstream: None,
+            acknowledgements: Default::default(),
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             tls: None,
@@ -266,6 +578,8 @@ mod integration_tests {
             subject: subject.clone(),
             url,
             queue: None,
+            jetstream: None,
+            acknowledgements: Default::default(),
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             tls: None,
@@ -296,6 +610,8 @@ mod integration_tests {
             subject: subject.clone(),
             url,
             queue: None,
+            jetstream: None,
+            acknowledgements: Default::default(),
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             tls: None,
@@ -326,6 +642,8 @@ mod integration_tests {
             subject: subject.clone(),
             url,
             queue: None,
+            jetstream: None,
+            acknowledgements: Default::default(),
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             tls: None,
@@ -355,6 +673,8 @@ mod integration_tests {
             subject: subject.clone(),
             url,
             queue: None,
+            jetstream: None,
+            acknowledgements: Default::default(),
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             tls: None,
@@ -384,6 +704,8 @@ mod integration_tests {
             subject: subject.clone(),
             url,
             queue: None,
+            jetstream: None,
+            acknowledgements: Default::default(),
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             tls: None,
@@ -414,6 +736,8 @@ mod integration_tests {
             subject: subject.clone(),
             url,
             queue: None,
+            jetstream: None,
+            acknowledgements: Default::default(),
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             tls: None,
@@ -444,6 +768,8 @@ mod integration_tests {
             subject: subject.clone(),
             url,
             queue: None,
+            jetstream: None,
+            acknowledgements: Default::default(),
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             tls: Some(TlsEnableableConfig {
@@ -475,6 +801,8 @@ mod integration_tests {
             subject: subject.clone(),
             url,
             queue: None,
+            jetstream: None,
+            acknowledgements: Default::default(),
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             tls: None,
@@ -500,6 +828,8 @@ mod integration_tests {
             subject: subject.clone(),
             url,
             queue: None,
+            jetstream: None,
+            acknowledgements: Default::default(),
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             tls: Some(TlsEnableableConfig {
@@ -533,6 +863,8 @@ mod integration_tests {
             subject: subject.clone(),
             url,
             queue: None,
+            jetstream: None,
+            acknowledgements: Default::default(),
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             tls: Some(TlsEnableableConfig {
@@ -564,6 +896,8 @@ mod integration_tests {
             subject: subject.clone(),
             url,
             queue: None,
+            jetstream: None,
+            acknowledgements: Default::default(),
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             tls: Some(TlsEnableableConfig {
@@ -599,6 +933,8 @@ mod integration_tests {
             subject: subject.clone(),
             url,
             queue: None,
+            jetstream: None,
+            acknowledgements: Default::default(),
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             tls: Some(TlsEnableableConfig {
@@ -622,4 +958,58 @@ mod integration_tests {
             r
         );
     }
+
+    #[tokio::test]
+    async fn nats_jetstream_acknowledges_consumed_messages() {
+        let subject = format!("test-{}", random_string(10));
+        let stream = format!("TEST-{}", random_string(10));
+        let url = std::env::var("NATS_JETSTREAM_ADDRESS")
+            .unwrap_or_else(|_| String::from("nats://localhost:4222"));
+
+        let conf: NatsSourceConfig = toml::from_str(&format!(
+            r#"
+            connection_name = "vector"
+            subject = "{}"
+            url = "{}"
+            jetstream.stream = "{}"
+            "#,
+            subject, url, stream
+        ))
+        .unwrap();
+
+        let nc = conf.connect().await.unwrap();
+        let request = serde_json::json!({ "name": stream, "subjects": [subject] });
+        let response = nc
+            .request(
+                &format!("$JS.API.STREAM.CREATE.{}", stream),
+                serde_json::to_vec(&request).unwrap(),
+            )
+            .await
+            .unwrap();
+        check_api_response(&response.data).unwrap();
+        nc.request(&subject, "my message").await.unwrap();
+
+        let events = assert_source_compliance(&SOURCE_TAGS, async {
+            let (tx, rx) = SourceSender::new_test_finalize(crate::event::EventStatus::Delivered);
+            let cx = SourceContext::new_test(tx, None);
+            tokio::spawn(conf.build(cx).await.unwrap());
+
+            collect_n(rx, 1).await
+        })
+        .await;
+        assert_eq!(
+            events[0].as_log()[log_schema().message_key()],
+            "my message".into()
+        );
+
+        // The acknowledged message is no longer pending for the durable consumer.
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        let info = nc
+            .request(&format!("$JS.API.CONSUMER.INFO.{}.vector", stream), "")
+            .await
+            .unwrap();
+        let info: serde_json::Value = serde_json::from_slice(&info.data).unwrap();
+        assert_eq!(info["num_ack_pending"], 0);
+        assert_eq!(info["num_pending"], 0);
+    }
 }
@@ -0,0 +1,234 @@
+use std::net::SocketAddr;
+
+use futures::TryFutureExt;
+use serde::{Deserialize, Serialize};
+use tokio::net::UdpSocket;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, Output, Resource, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::{Event, LogEvent, Value},
+    internal_events::{
+        BytesReceived, NetflowMissingTemplate, NetflowParseError, NetflowSocketError,
+        SocketEventsReceived, SocketMode, StreamClosedError,
+    },
+    shutdown::ShutdownSignal,
+    udp, SourceSender,
+};
+
+mod fields;
+mod parser;
+
+pub use parser::ParseError;
+use parser::{Message, Parser};
+
+/// The largest UDP payload, which bounds the size of the messages.
+const MAX_DATAGRAM_LENGTH: usize = 65535;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct NetflowConfig {
+    /// The address to listen on for the messages of the exporters.
+    address: SocketAddr,
+    receive_buffer_bytes: Option<usize>,
+}
+
+inventory::submit! {
+    SourceDescription::new::<NetflowConfig>("netflow")
+}
+
+impl GenerateConfig for NetflowConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"address = "0.0.0.0:2055""#).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "netflow")]
+impl SourceConfig for NetflowConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        Ok(Box::pin(netflow(self.clone(), cx.shutdown, cx.out)))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "netflow"
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![Resource::udp(self.address)]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+async fn netflow(
+    config: NetflowConfig,
+    mut shutdown: ShutdownSignal,
+    mut out: SourceSender,
+) -> Result<(), ()> {
+    let socket = UdpSocket::bind(&config.address)
+        .map_err(|error| emit!(NetflowSocketError::bind(error)))
+        .await?;
+
+    if let Some(receive_buffer_bytes) = config.receive_buffer_bytes {
+        if let Err(error) = udp::set_receive_buffer_size(&socket, receive_buffer_bytes) {
+            warn!(message = "Failed configuring receive buffer size on UDP socket.", %error);
+        }
+    }
+
+    info!(message = "Listening.", address = %config.address);
+
+    let mut parser = Parser::default();
+    let mut buf = vec![0; MAX_DATAGRAM_LENGTH];
+    loop {
+        let (byte_size, exporter) = tokio::select! {
+            recv = socket.recv_from(&mut buf) => match recv {
+                Ok(recv) => recv,
+                Err(error) => {
+                    emit!(NetflowSocketError::read(error));
+                    continue;
+                }
+            },
+            _ = &mut shutdown => break,
+        };
+
+        emit!(BytesReceived {
+            byte_size,
+            protocol: "udp",
+        });
+
+        let message = match parser.parse(exporter, &buf[..byte_size]) {
+            Ok(message) => message,
+            Err(error) => {
+                emit!(NetflowParseError {
+                    error,
+                    exporter: &exporter,
+                });
+                continue;
+            }
+        };
+        for template_id in &message.missing_templates {
+            emit!(NetflowMissingTemplate {
+                template_id: *template_id,
+                exporter: &exporter,
+            });
+        }
+
+        let events = message_to_events(message, &exporter);
+        if events.is_empty() {
+            continue;
+        }
+
+        let count = events.len();
+        emit!(SocketEventsReceived {
+            mode: SocketMode::Udp,
+            byte_size: events.size_of(),
+            count,
+        });
+        if let Err(error) = out.send_batch(events).await {
+            emit!(StreamClosedError { error, count });
+            return Err(());
+        }
+    }
+
+    Ok(())
+}
+
+/// Converts the records of a message to events, along with the fields of its header.
+fn message_to_events(message: Message, exporter: &SocketAddr) -> Vec<Event> {
+    message
+        .records
+        .into_iter()
+        .map(|record| {
+            let mut log = LogEvent::default();
+            for (name, value) in record.fields {
+                log.insert(name.as_str(), value);
+            }
+            for (name, value) in &message.header {
+                log.insert(*name, value.clone());
+            }
+            log.insert("version", Value::from(message.version));
+            if let Some(template_id) = record.template_id {
+                log.insert("template_id", Value::from(template_id));
+            }
+            log.insert(
+                "record_type",
+                if record.options { "options" } else { "flow" },
+            );
+            log.insert(log_schema().host_key(), exporter.ip().to_string());
+            log.insert(log_schema().source_type_key(), "netflow");
+            log.insert(log_schema().timestamp_key(), message.export_time);
+            log.into()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::UdpSocket;
+
+    use super::*;
+    use crate::test_util::{
+        collect_n,
+        components::{assert_source_compliance, SOURCE_TAGS},
+        next_addr,
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<NetflowConfig>();
+    }
+
+    fn v5_message() -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&5u16.to_be_bytes());
+        data.extend_from_slice(&1u16.to_be_bytes());
+        data.extend_from_slice(&1000u32.to_be_bytes());
+        data.extend_from_slice(&1_600_000_000u32.to_be_bytes());
+        data.extend_from_slice(&[0; 12]);
+
+        data.extend_from_slice(&[10, 0, 0, 1, 10, 0, 0, 2]);
+        data.extend_from_slice(&[0; 40]);
+        data
+    }
+
+    #[tokio::test]
+    async fn receives_flows() {
+        assert_source_compliance(&SOURCE_TAGS, async {
+            let address = next_addr();
+            let (tx, rx) = SourceSender::new_test();
+            let config = NetflowConfig {
+                address,
+                receive_buffer_bytes: None,
+            };
+            let source = config
+                .build(SourceContext::new_test(tx, None))
+                .await
+                .unwrap();
+            tokio::spawn(source);
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+            let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+            socket.send_to(&v5_message(), address).unwrap();
+
+            let events = collect_n(rx, 1).await;
+            let log = events[0].as_log();
+            assert_eq!(log["version"], 5.into());
+            assert_eq!(log["source_ipv4_address"], "10.0.0.1".into());
+            assert_eq!(log["destination_ipv4_address"], "10.0.0.2".into());
+            assert_eq!(log["record_type"], "flow".into());
+            assert_eq!(log[log_schema().host_key()], "127.0.0.1".into());
+            assert_eq!(log[log_schema().source_type_key()], "netflow".into());
+        })
+        .await;
+    }
+}
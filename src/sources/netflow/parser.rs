@@ -0,0 +1,937 @@
+//! Parsing of NetFlow v5, NetFlow v9 and IPFIX messages.
+//!
+//! NetFlow v5 records have a fixed layout, while NetFlow v9 and IPFIX records are described by
+//! templates that exporters send periodically, cached per exporter and observation domain.
+
+use std::{
+    collections::HashMap,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+};
+
+use chrono::{DateTime, TimeZone, Utc};
+use snafu::Snafu;
+
+use super::fields::{information_element, v9_scope, FieldType};
+use crate::event::Value;
+
+/// The information element of padding octets, which are skipped.
+const PADDING: u16 = 210;
+
+/// The length of IPFIX fields whose length is encoded in each record.
+const VARIABLE_LENGTH: u16 = 65535;
+
+/// The seconds between the NTP epoch (1900) and the Unix epoch.
+const NTP_UNIX_OFFSET: i64 = 2_208_988_800;
+
+/// The layout of NetFlow v5 records, as the information elements equivalent to their fields.
+const V5_RECORD: [(u16, u16); 20] = [
+    (8, 4),
+    (12, 4),
+    (15, 4),
+    (10, 2),
+    (14, 2),
+    (2, 4),
+    (1, 4),
+    (22, 4),
+    (21, 4),
+    (7, 2),
+    (11, 2),
+    (PADDING, 1),
+    (6, 1),
+    (4, 1),
+    (5, 1),
+    (16, 2),
+    (17, 2),
+    (9, 1),
+    (13, 1),
+    (PADDING, 2),
+];
+
+const V5_RECORD_LENGTH: usize = 48;
+
+#[derive(Debug, PartialEq, Snafu)]
+pub enum ParseError {
+    #[snafu(display("Message is truncated"))]
+    Truncated,
+    #[snafu(display("Unsupported version {}", version))]
+    UnsupportedVersion { version: u16 },
+    #[snafu(display("Invalid length {} of set {}", length, set_id))]
+    InvalidSetLength { set_id: u16, length: u16 },
+    #[snafu(display("Invalid options template {}", template_id))]
+    InvalidTemplate { template_id: u16 },
+}
+
+/// A message parsed from a datagram.
+#[derive(Debug)]
+pub(super) struct Message {
+    pub(super) version: u16,
+    pub(super) export_time: DateTime<Utc>,
+    /// The fields of the header identifying the exporter and the message.
+    pub(super) header: Vec<(&'static str, Value)>,
+    pub(super) records: Vec<Record>,
+    /// The templates of the data sets that were skipped as they haven't been received yet.
+    pub(super) missing_templates: Vec<u16>,
+}
+
+/// A flow record, or the record of an options template describing the exporter.
+#[derive(Debug)]
+pub(super) struct Record {
+    pub(super) template_id: Option<u16>,
+    pub(super) options: bool,
+    pub(super) fields: Vec<(String, Value)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Field {
+    id: u16,
+    length: u16,
+    enterprise: Option<u32>,
+    scope: bool,
+}
+
+#[derive(Clone, Debug)]
+struct Template {
+    fields: Vec<Field>,
+    options: bool,
+}
+
+impl Template {
+    /// The length of the shortest record, variable length fields taking at least one byte.
+    fn min_length(&self) -> usize {
+        self.fields
+            .iter()
+            .map(|field| match field.length {
+                VARIABLE_LENGTH => 1,
+                length => length as usize,
+            })
+            .sum()
+    }
+}
+
+/// Templates are scoped to the transport session, identified here by the address of the
+/// exporter, and to its observation domain.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+struct TemplateKey {
+    exporter: SocketAddr,
+    version: u16,
+    domain: u32,
+    template_id: u16,
+}
+
+/// Parses the messages received from any exporter, caching their templates.
+#[derive(Debug, Default)]
+pub(super) struct Parser {
+    templates: HashMap<TemplateKey, Template>,
+}
+
+impl Parser {
+    pub(super) fn parse(
+        &mut self,
+        exporter: SocketAddr,
+        data: &[u8],
+    ) -> Result<Message, ParseError> {
+        let version = Reader::new(data).u16()?;
+        match version {
+            5 => parse_v5(data),
+            9 => self.parse_v9(exporter, data),
+            10 => self.parse_ipfix(exporter, data),
+            version => Err(ParseError::UnsupportedVersion { version }),
+        }
+    }
+
+    fn parse_v9(&mut self, exporter: SocketAddr, data: &[u8]) -> Result<Message, ParseError> {
+        let mut reader = Reader::new(data);
+        let version = reader.u16()?;
+        let _count = reader.u16()?;
+        let sys_uptime = reader.u32()?;
+        let unix_secs = reader.u32()?;
+        let sequence = reader.u32()?;
+        let source_id = reader.u32()?;
+
+        let mut message = Message {
+            version,
+            export_time: Utc.timestamp(unix_secs as i64, 0),
+            header: vec![
+                ("sequence_number", Value::from(sequence as i64)),
+                ("source_id", Value::from(source_id as i64)),
+                ("sys_uptime", Value::from(sys_uptime as i64)),
+            ],
+            records: Vec::new(),
+            missing_templates: Vec::new(),
+        };
+        let key = |template_id| TemplateKey {
+            exporter,
+            version,
+            domain: source_id,
+            template_id,
+        };
+
+        while !reader.is_empty() {
+            let (set_id, mut set) = reader.set()?;
+            match set_id {
+                0 => {
+                    while set.remaining() >= 4 {
+                        let template_id = set.u16()?;
+                        let count = set.u16()?;
+                        let fields = (0..count)
+                            .map(|_| set.v9_field(false))
+                            .collect::<Result<_, _>>()?;
+                        self.insert(key(template_id), fields, false);
+                    }
+                }
+                1 => {
+                    // Options templates are followed by padding up to a four byte boundary.
+                    while set.remaining() >= 6 {
+                        let template_id = set.u16()?;
+                        let scope_length = set.u16()?;
+                        let options_length = set.u16()?;
+                        if scope_length % 4 != 0 || options_length % 4 != 0 {
+                            return Err(ParseError::InvalidTemplate { template_id });
+                        }
+                        let scope_count = scope_length / 4;
+                        let fields = (0..scope_count + options_length / 4)
+                            .map(|index| set.v9_field(index < scope_count))
+                            .collect::<Result<_, _>>()?;
+                        self.insert(key(template_id), fields, true);
+                    }
+                }
+                2..=255 => {}
+                template_id => self.parse_data(key(template_id), set, &mut message)?,
+            }
+        }
+
+        Ok(message)
+    }
+
+    fn parse_ipfix(&mut self, exporter: SocketAddr, data: &[u8]) -> Result<Message, ParseError> {
+        let mut reader = Reader::new(data);
+        let version = reader.u16()?;
+        let length = reader.u16()? as usize;
+        let export_time = reader.u32()?;
+        let sequence = reader.u32()?;
+        let domain = reader.u32()?;
+
+        // The message may be followed by other ones in the same datagram, which isn't expected
+        // over UDP, so only the first one is read.
+        if length < 16 || length > data.len() {
+            return Err(ParseError::Truncated);
+        }
+        let mut reader = Reader::new(&data[16..length]);
+
+        let mut message = Message {
+            version,
+            export_time: Utc.timestamp(export_time as i64, 0),
+            header: vec![
+                ("observation_domain_id", Value::from(domain as i64)),
+                ("sequence_number", Value::from(sequence as i64)),
+            ],
+            records: Vec::new(),
+            missing_templates: Vec::new(),
+        };
+        let key = |template_id| TemplateKey {
+            exporter,
+            version,
+            domain,
+            template_id,
+        };
+
+        while !reader.is_empty() {
+            let (set_id, mut set) = reader.set()?;
+            match set_id {
+                2 | 3 => {
+                    while set.remaining() >= 4 {
+                        let template_id = set.u16()?;
+                        let count = set.u16()?;
+                        if count == 0 {
+                            self.withdraw(key(template_id), set_id);
+                            continue;
+                        }
+                        let scope_count = if set_id == 3 { set.u16()? } else { 0 };
+                        if scope_count > count {
+                            return Err(ParseError::InvalidTemplate { template_id });
+                        }
+                        let fields = (0..count)
+                            .map(|index| set.ipfix_field(index < scope_count))
+                            .collect::<Result<_, _>>()?;
+                        self.insert(key(template_id), fields, set_id == 3);
+                    }
+                }
+                4..=255 => {}
+                template_id => self.parse_data(key(template_id), set, &mut message)?,
+            }
+        }
+
+        Ok(message)
+    }
+
+    fn parse_data(
+        &self,
+        key: TemplateKey,
+        mut set: Reader<'_>,
+        message: &mut Message,
+    ) -> Result<(), ParseError> {
+        let template = match self.templates.get(&key) {
+            Some(template) => template,
+            None => {
+                message.missing_templates.push(key.template_id);
+                return Ok(());
+            }
+        };
+
+        // The records are followed by padding, shorter than any of them.
+        let min_length = template.min_length();
+        if min_length == 0 {
+            return Ok(());
+        }
+        while set.remaining() >= min_length {
+            let mut fields = Vec::with_capacity(template.fields.len());
+            for field in &template.fields {
+                let length = match field.length {
+                    VARIABLE_LENGTH if key.version == 10 => set.variable_length()?,
+                    length => length as usize,
+                };
+                let bytes = set.bytes(length)?;
+                if field.id != PADDING || field.enterprise.is_some() {
+                    fields.push(decode_field(field, key.version, bytes));
+                }
+            }
+            message.records.push(Record {
+                template_id: Some(key.template_id),
+                options: template.options,
+                fields,
+            });
+        }
+        Ok(())
+    }
+
+    fn insert(&mut self, key: TemplateKey, fields: Vec<Field>, options: bool) {
+        self.templates.insert(key, Template { fields, options });
+    }
+
+    /// Withdraws a template, or all the templates of the kind of the set when its ID is the
+    /// one of the set.
+    fn withdraw(&mut self, key: TemplateKey, set_id: u16) {
+        if key.template_id == set_id {
+            self.templates.retain(|other, template| {
+                (other.exporter, other.version, other.domain)
+                    != (key.exporter, key.version, key.domain)
+                    || template.options != (set_id == 3)
+            });
+        } else {
+            self.templates.remove(&key);
+        }
+    }
+
+    #[cfg(test)]
+    pub(super) fn template_count(&self) -> usize {
+        self.templates.len()
+    }
+}
+
+fn parse_v5(data: &[u8]) -> Result<Message, ParseError> {
+    let mut reader = Reader::new(data);
+    let version = reader.u16()?;
+    let count = reader.u16()?;
+    let sys_uptime = reader.u32()?;
+    let unix_secs = reader.u32()?;
+    let unix_nsecs = reader.u32()?;
+    let sequence = reader.u32()?;
+    let engine_type = reader.u8()?;
+    let engine_id = reader.u8()?;
+    // The two most significant bits hold the sampling mode.
+    let sampling_interval = reader.u16()? & 0x3fff;
+
+    let records = (0..count)
+        .map(|_| {
+            let mut record = Reader::new(reader.bytes(V5_RECORD_LENGTH)?);
+            let mut fields = Vec::with_capacity(V5_RECORD.len());
+            for (id, length) in V5_RECORD {
+                let field = Field {
+                    id,
+                    length,
+                    enterprise: None,
+                    scope: false,
+                };
+                let bytes = record.bytes(length as usize)?;
+                if id != PADDING {
+                    fields.push(decode_field(&field, version, bytes));
+                }
+            }
+            Ok(Record {
+                template_id: None,
+                options: false,
+                fields,
+            })
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Message {
+        version,
+        export_time: Utc.timestamp(unix_secs as i64, unix_nsecs.min(999_999_999)),
+        header: vec![
+            ("engine_id", Value::from(engine_id as i64)),
+            ("engine_type", Value::from(engine_type as i64)),
+            ("sampling_interval", Value::from(sampling_interval as i64)),
+            ("sequence_number", Value::from(sequence as i64)),
+            ("sys_uptime", Value::from(sys_uptime as i64)),
+        ],
+        records,
+        missing_templates: Vec::new(),
+    })
+}
+
+/// Names and decodes a field. Fields of unknown information elements are named after their ID
+/// and decoded as integers when they have the length of one, or as hexadecimal otherwise.
+fn decode_field(field: &Field, version: u16, bytes: &[u8]) -> (String, Value) {
+    let known = match field.enterprise {
+        Some(_) => None,
+        None if field.scope && version == 9 => {
+            v9_scope(field.id).map(|name| (name, FieldType::Unsigned))
+        }
+        None => information_element(field.id),
+    };
+
+    match known {
+        Some((name, field_type)) => (name.to_owned(), decode(field_type, bytes)),
+        None => {
+            let name = match field.enterprise {
+                Some(enterprise) => format!("ie_{}_{}", enterprise, field.id),
+                None if field.scope && version == 9 => format!("scope_{}", field.id),
+                None => format!("ie_{}", field.id),
+            };
+            let value = match bytes.len() {
+                1 | 2 | 4 | 8 => decode(FieldType::Unsigned, bytes),
+                _ => decode(FieldType::Octets, bytes),
+            };
+            (name, value)
+        }
+    }
+}
+
+/// Decodes a value, falling back to hexadecimal when its length doesn't match its type.
+fn decode(field_type: FieldType, bytes: &[u8]) -> Value {
+    let value = match (field_type, bytes.len()) {
+        (FieldType::Unsigned, 1..=8) => Some(unsigned(bytes)),
+        (FieldType::Ipv4, 4) => {
+            let address = Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]);
+            Some(Value::from(address.to_string()))
+        }
+        (FieldType::Ipv6, 16) => {
+            let mut octets = [0; 16];
+            octets.copy_from_slice(bytes);
+            Some(Value::from(Ipv6Addr::from(octets).to_string()))
+        }
+        (FieldType::Mac, 6) => {
+            let address = bytes
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<Vec<_>>()
+                .join(":");
+            Some(Value::from(address))
+        }
+        (FieldType::String, _) => {
+            let end = bytes
+                .iter()
+                .position(|byte| *byte == 0)
+                .unwrap_or(bytes.len());
+            Some(Value::from(
+                String::from_utf8_lossy(&bytes[..end]).into_owned(),
+            ))
+        }
+        (FieldType::Seconds, 4) => {
+            let seconds = be_u64(bytes) as i64;
+            Some(Value::from(Utc.timestamp(seconds, 0)))
+        }
+        (FieldType::Milliseconds, 8) => {
+            let millis = be_u64(bytes) as i64;
+            Utc.timestamp_millis_opt(millis).single().map(Value::from)
+        }
+        (FieldType::Microseconds, 8) | (FieldType::Nanoseconds, 8) => {
+            let seconds = be_u64(&bytes[..4]) as i64 - NTP_UNIX_OFFSET;
+            let fraction = be_u64(&bytes[4..]);
+            let mut nanos = (fraction * 1_000_000_000) >> 32;
+            if field_type == FieldType::Microseconds {
+                // Only the 21 most significant bits of the fraction are significant.
+                nanos = nanos / 1_000 * 1_000;
+            }
+            Utc.timestamp_opt(seconds, nanos as u32)
+                .single()
+                .map(Value::from)
+        }
+        _ => None,
+    };
+    value.unwrap_or_else(|| Value::from(hex::encode(bytes)))
+}
+
+fn unsigned(bytes: &[u8]) -> Value {
+    let value = be_u64(bytes);
+    // Counters beyond the range of signed integers are only representable as floats.
+    i64::try_from(value).map_or_else(|_| Value::from(value as f64), Value::from)
+}
+
+fn be_u64(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0, |value, byte| (value << 8) | u64::from(*byte))
+}
+
+/// Reads big-endian values from a message, failing when it's truncated.
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    const fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    const fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    const fn remaining(&self) -> usize {
+        self.data.len()
+    }
+
+    fn bytes(&mut self, length: usize) -> Result<&'a [u8], ParseError> {
+        if self.data.len() < length {
+            return Err(ParseError::Truncated);
+        }
+        let (bytes, rest) = self.data.split_at(length);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, ParseError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, ParseError> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, ParseError> {
+        Ok(be_u64(self.bytes(4)?) as u32)
+    }
+
+    /// Reads the header of a set, returning its ID and a reader over its content.
+    fn set(&mut self) -> Result<(u16, Reader<'a>), ParseError> {
+        let set_id = self.u16()?;
+        let length = self.u16()?;
+        if length < 4 {
+            return Err(ParseError::InvalidSetLength { set_id, length });
+        }
+        Ok((set_id, Reader::new(self.bytes(length as usize - 4)?)))
+    }
+
+    fn v9_field(&mut self, scope: bool) -> Result<Field, ParseError> {
+        Ok(Field {
+            id: self.u16()?,
+            length: self.u16()?,
+            enterprise: None,
+            scope,
+        })
+    }
+
+    fn ipfix_field(&mut self, scope: bool) -> Result<Field, ParseError> {
+        let id = self.u16()?;
+        let length = self.u16()?;
+        let enterprise = if id & 0x8000 != 0 {
+            Some(self.u32()?)
+        } else {
+            None
+        };
+        Ok(Field {
+            id: id & 0x7fff,
+            length,
+            enterprise,
+            scope,
+        })
+    }
+
+    /// Reads the length of a variable length field, encoded in one byte, or in the two bytes
+    /// following 255 for the longer ones.
+    fn variable_length(&mut self) -> Result<usize, ParseError> {
+        match self.u8()? {
+            255 => Ok(self.u16()? as usize),
+            length => Ok(length as usize),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exporter(port: u16) -> SocketAddr {
+        SocketAddr::from(([192, 0, 2, 1], port))
+    }
+
+    fn field<'a>(record: &'a Record, name: &str) -> Option<&'a Value> {
+        record
+            .fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value)
+    }
+
+    fn v9_header(count: u16) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&9u16.to_be_bytes());
+        data.extend_from_slice(&count.to_be_bytes());
+        data.extend_from_slice(&1000u32.to_be_bytes());
+        data.extend_from_slice(&1_600_000_000u32.to_be_bytes());
+        data.extend_from_slice(&7u32.to_be_bytes());
+        data.extend_from_slice(&42u32.to_be_bytes());
+        data
+    }
+
+    fn set(data: &mut Vec<u8>, set_id: u16, content: &[u8]) {
+        data.extend_from_slice(&set_id.to_be_bytes());
+        data.extend_from_slice(&(content.len() as u16 + 4).to_be_bytes());
+        data.extend_from_slice(content);
+    }
+
+    fn ipfix(sets: &[(u16, Vec<u8>)]) -> Vec<u8> {
+        let mut body = Vec::new();
+        for (set_id, content) in sets {
+            set(&mut body, *set_id, content);
+        }
+        let mut data = Vec::new();
+        data.extend_from_slice(&10u16.to_be_bytes());
+        data.extend_from_slice(&(body.len() as u16 + 16).to_be_bytes());
+        data.extend_from_slice(&1_600_000_000u32.to_be_bytes());
+        data.extend_from_slice(&3u32.to_be_bytes());
+        data.extend_from_slice(&5u32.to_be_bytes());
+        data.extend(body);
+        data
+    }
+
+    #[test]
+    fn parses_v5() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&5u16.to_be_bytes());
+        data.extend_from_slice(&1u16.to_be_bytes());
+        data.extend_from_slice(&1000u32.to_be_bytes());
+        data.extend_from_slice(&1_600_000_000u32.to_be_bytes());
+        data.extend_from_slice(&500u32.to_be_bytes());
+        data.extend_from_slice(&9u32.to_be_bytes());
+        data.extend_from_slice(&[1, 2]);
+        data.extend_from_slice(&(0x4000u16 | 100).to_be_bytes());
+
+        data.extend_from_slice(&[10, 0, 0, 1, 10, 0, 0, 2, 10, 0, 0, 254]);
+        data.extend_from_slice(&3u16.to_be_bytes());
+        data.extend_from_slice(&4u16.to_be_bytes());
+        data.extend_from_slice(&12u32.to_be_bytes());
+        data.extend_from_slice(&3400u32.to_be_bytes());
+        data.extend_from_slice(&100u32.to_be_bytes());
+        data.extend_from_slice(&900u32.to_be_bytes());
+        data.extend_from_slice(&51000u16.to_be_bytes());
+        data.extend_from_slice(&443u16.to_be_bytes());
+        data.extend_from_slice(&[0, 0x12, 6, 0]);
+        data.extend_from_slice(&64512u16.to_be_bytes());
+        data.extend_from_slice(&64513u16.to_be_bytes());
+        data.extend_from_slice(&[24, 16, 0, 0]);
+
+        let message = Parser::default().parse(exporter(2055), &data).unwrap();
+        assert_eq!(message.version, 5);
+        assert_eq!(message.export_time, Utc.timestamp(1_600_000_000, 500));
+        assert!(message
+            .header
+            .contains(&("sampling_interval", Value::from(100))));
+        assert!(message.header.contains(&("engine_id", Value::from(2))));
+        assert_eq!(message.records.len(), 1);
+
+        let record = &message.records[0];
+        assert_eq!(record.fields.len(), 18);
+        assert_eq!(
+            field(record, "source_ipv4_address"),
+            Some(&Value::from("10.0.0.1"))
+        );
+        assert_eq!(
+            field(record, "ip_next_hop_ipv4_address"),
+            Some(&Value::from("10.0.0.254"))
+        );
+        assert_eq!(field(record, "octet_delta_count"), Some(&Value::from(3400)));
+        assert_eq!(
+            field(record, "destination_transport_port"),
+            Some(&Value::from(443))
+        );
+        assert_eq!(field(record, "tcp_control_bits"), Some(&Value::from(0x12)));
+        assert_eq!(
+            field(record, "bgp_destination_as_number"),
+            Some(&Value::from(64513))
+        );
+        assert_eq!(
+            field(record, "destination_ipv4_prefix_length"),
+            Some(&Value::from(16))
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_v5() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&5u16.to_be_bytes());
+        data.extend_from_slice(&2u16.to_be_bytes());
+        data.extend_from_slice(&[0; 20]);
+        data.extend_from_slice(&[0; 48]);
+
+        let error = Parser::default().parse(exporter(2055), &data).unwrap_err();
+        assert_eq!(error, ParseError::Truncated);
+    }
+
+    #[test]
+    fn rejects_unsupported_version() {
+        let error = Parser::default()
+            .parse(exporter(2055), &[0, 7, 0, 0])
+            .unwrap_err();
+        assert_eq!(error, ParseError::UnsupportedVersion { version: 7 });
+    }
+
+    #[test]
+    fn parses_v9_templates_and_data() {
+        let mut parser = Parser::default();
+
+        let mut template = Vec::new();
+        template.extend_from_slice(&256u16.to_be_bytes());
+        template.extend_from_slice(&3u16.to_be_bytes());
+        for (id, length) in [(8u16, 4u16), (7, 2), (2, 4)] {
+            template.extend_from_slice(&id.to_be_bytes());
+            template.extend_from_slice(&length.to_be_bytes());
+        }
+        let mut data = v9_header(1);
+        set(&mut data, 0, &template);
+
+        let message = parser.parse(exporter(2055), &data).unwrap();
+        assert!(message.records.is_empty());
+        assert_eq!(parser.template_count(), 1);
+
+        let mut records = Vec::new();
+        records.extend_from_slice(&[192, 168, 0, 1, 0x1f, 0x90, 0, 0, 0, 5]);
+        records.extend_from_slice(&[192, 168, 0, 2, 0, 53, 0, 0, 0, 1]);
+        // Padding up to a four byte boundary.
+        records.extend_from_slice(&[0, 0]);
+        let mut data = v9_header(2);
+        set(&mut data, 256, &records);
+
+        let message = parser.parse(exporter(2055), &data).unwrap();
+        assert_eq!(message.version, 9);
+        assert!(message.header.contains(&("source_id", Value::from(42))));
+        assert_eq!(message.records.len(), 2);
+        assert_eq!(message.records[0].template_id, Some(256));
+        assert!(!message.records[0].options);
+        assert_eq!(
+            field(&message.records[0], "source_ipv4_address"),
+            Some(&Value::from("192.168.0.1"))
+        );
+        assert_eq!(
+            field(&message.records[0], "source_transport_port"),
+            Some(&Value::from(8080))
+        );
+        assert_eq!(
+            field(&message.records[1], "packet_delta_count"),
+            Some(&Value::from(1))
+        );
+    }
+
+    #[test]
+    fn parses_v9_options() {
+        let mut parser = Parser::default();
+
+        let mut template = Vec::new();
+        template.extend_from_slice(&257u16.to_be_bytes());
+        template.extend_from_slice(&4u16.to_be_bytes());
+        template.extend_from_slice(&4u16.to_be_bytes());
+        for (id, length) in [(1u16, 4u16), (34, 4)] {
+            template.extend_from_slice(&id.to_be_bytes());
+            template.extend_from_slice(&length.to_be_bytes());
+        }
+        template.extend_from_slice(&[0, 0]);
+        let mut data = v9_header(2);
+        set(&mut data, 1, &template);
+        set(&mut data, 257, &[10, 0, 0, 1, 0, 0, 0, 100]);
+
+        let message = parser.parse(exporter(2055), &data).unwrap();
+        assert_eq!(message.records.len(), 1);
+        assert!(message.records[0].options);
+        assert_eq!(
+            field(&message.records[0], "scope_system"),
+            Some(&Value::from(0x0a00_0001))
+        );
+        assert_eq!(
+            field(&message.records[0], "sampling_interval"),
+            Some(&Value::from(100))
+        );
+    }
+
+    #[test]
+    fn skips_data_without_template() {
+        let mut parser = Parser::default();
+        let mut data = v9_header(1);
+        set(&mut data, 300, &[0; 8]);
+
+        let message = parser.parse(exporter(2055), &data).unwrap();
+        assert!(message.records.is_empty());
+        assert_eq!(message.missing_templates, vec![300]);
+    }
+
+    #[test]
+    fn scopes_templates_to_exporters() {
+        let mut parser = Parser::default();
+
+        let mut template = Vec::new();
+        template.extend_from_slice(&256u16.to_be_bytes());
+        template.extend_from_slice(&1u16.to_be_bytes());
+        template.extend_from_slice(&4u16.to_be_bytes());
+        template.extend_from_slice(&1u16.to_be_bytes());
+        let mut data = v9_header(1);
+        set(&mut data, 0, &template);
+        parser.parse(exporter(2055), &data).unwrap();
+
+        let mut data = v9_header(1);
+        set(&mut data, 256, &[6]);
+
+        let message = parser.parse(exporter(2056), &data).unwrap();
+        assert_eq!(message.missing_templates, vec![256]);
+
+        let message = parser.parse(exporter(2055), &data).unwrap();
+        assert_eq!(
+            field(&message.records[0], "protocol_identifier"),
+            Some(&Value::from(6))
+        );
+    }
+
+    #[test]
+    fn parses_ipfix() {
+        let mut parser = Parser::default();
+
+        let mut template = Vec::new();
+        template.extend_from_slice(&256u16.to_be_bytes());
+        template.extend_from_slice(&5u16.to_be_bytes());
+        for (id, length) in [(27u16, 16u16), (152, 8), (82, VARIABLE_LENGTH), (56, 6)] {
+            template.extend_from_slice(&id.to_be_bytes());
+            template.extend_from_slice(&length.to_be_bytes());
+        }
+        template.extend_from_slice(&(0x8000u16 | 12).to_be_bytes());
+        template.extend_from_slice(&2u16.to_be_bytes());
+        template.extend_from_slice(&9u32.to_be_bytes());
+
+        let mut record = Vec::new();
+        record.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+        record.extend_from_slice(&1_600_000_000_123u64.to_be_bytes());
+        record.push(4);
+        record.extend_from_slice(b"eth0");
+        record.extend_from_slice(&[0, 0x1b, 0x21, 0x3c, 0x4d, 0x5e]);
+        record.extend_from_slice(&[0xab, 0xcd]);
+
+        let data = ipfix(&[(2, template), (256, record)]);
+        let message = parser.parse(exporter(4739), &data).unwrap();
+        assert_eq!(message.version, 10);
+        assert_eq!(message.export_time, Utc.timestamp(1_600_000_000, 0));
+        assert!(message
+            .header
+            .contains(&("observation_domain_id", Value::from(5))));
+        assert_eq!(message.records.len(), 1);
+
+        let record = &message.records[0];
+        assert_eq!(
+            field(record, "source_ipv6_address"),
+            Some(&Value::from("::1"))
+        );
+        assert_eq!(
+            field(record, "flow_start_milliseconds"),
+            Some(&Value::from(Utc.timestamp_millis(1_600_000_000_123)))
+        );
+        assert_eq!(field(record, "interface_name"), Some(&Value::from("eth0")));
+        assert_eq!(
+            field(record, "source_mac_address"),
+            Some(&Value::from("00:1b:21:3c:4d:5e"))
+        );
+        assert_eq!(field(record, "ie_9_12"), Some(&Value::from(0xabcd)));
+    }
+
+    #[test]
+    fn parses_ipfix_long_variable_length() {
+        let mut parser = Parser::default();
+
+        let mut template = Vec::new();
+        template.extend_from_slice(&256u16.to_be_bytes());
+        template.extend_from_slice(&1u16.to_be_bytes());
+        template.extend_from_slice(&96u16.to_be_bytes());
+        template.extend_from_slice(&VARIABLE_LENGTH.to_be_bytes());
+
+        let name = "a".repeat(300);
+        let mut record = vec![255];
+        record.extend_from_slice(&300u16.to_be_bytes());
+        record.extend_from_slice(name.as_bytes());
+
+        let data = ipfix(&[(2, template), (256, record)]);
+        let message = parser.parse(exporter(4739), &data).unwrap();
+        assert_eq!(
+            field(&message.records[0], "application_name"),
+            Some(&Value::from(name))
+        );
+    }
+
+    #[test]
+    fn parses_ipfix_timestamps() {
+        let ntp = |seconds: u64, fraction: u64| ((seconds << 32) | fraction).to_be_bytes();
+
+        let value = decode(
+            FieldType::Nanoseconds,
+            &ntp(NTP_UNIX_OFFSET as u64 + 10, 1 << 31),
+        );
+        assert_eq!(value, Value::from(Utc.timestamp(10, 500_000_000)));
+
+        let value = decode(FieldType::Microseconds, &ntp(NTP_UNIX_OFFSET as u64, 1));
+        assert_eq!(value, Value::from(Utc.timestamp(0, 0)));
+
+        let value = decode(FieldType::Seconds, &[0, 0, 0, 10]);
+        assert_eq!(value, Value::from(Utc.timestamp(10, 0)));
+    }
+
+    #[test]
+    fn withdraws_ipfix_templates() {
+        let mut parser = Parser::default();
+
+        let mut template = Vec::new();
+        for template_id in [256u16, 257] {
+            template.extend_from_slice(&template_id.to_be_bytes());
+            template.extend_from_slice(&1u16.to_be_bytes());
+            template.extend_from_slice(&4u16.to_be_bytes());
+            template.extend_from_slice(&1u16.to_be_bytes());
+        }
+        parser
+            .parse(exporter(4739), &ipfix(&[(2, template)]))
+            .unwrap();
+        assert_eq!(parser.template_count(), 2);
+
+        let withdrawal = [1u8, 0, 0, 0].to_vec();
+        parser
+            .parse(exporter(4739), &ipfix(&[(2, withdrawal)]))
+            .unwrap();
+        assert_eq!(parser.template_count(), 1);
+
+        let withdrawal = [0u8, 2, 0, 0].to_vec();
+        parser
+            .parse(exporter(4739), &ipfix(&[(2, withdrawal)]))
+            .unwrap();
+        assert_eq!(parser.template_count(), 0);
+    }
+
+    #[test]
+    fn rejects_invalid_set_length() {
+        let mut data = v9_header(1);
+        data.extend_from_slice(&[0, 0, 0, 2]);
+
+        let error = Parser::default().parse(exporter(2055), &data).unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::InvalidSetLength {
+                set_id: 0,
+                length: 2
+            }
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use std::path::PathBuf;
+
+use chrono::{Duration, SecondsFormat, Utc};
+use http::Request;
+use hyper::Body;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{
+    config::{DataType, GenerateConfig, Output, SourceConfig, SourceContext, SourceDescription},
+    http::HttpClient,
+    sources::util::audit_log::{
+        default_interval_secs, default_lookback_secs, next_link, ApiClient, AuditLogApi,
+        Checkpointer, Page, Poller,
+    },
+    tls::{TlsConfig, TlsSettings},
+};
+
+/// The largest page the System Log API returns.
+const PAGE_SIZE: &str = "1000";
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct OktaConfig {
+    /// The domain of the Okta organization, such as `example.okta.com`.
+    domain: String,
+    /// An API token, of an administrator allowed to read the System Log.
+    token: String,
+    /// An expression filtering the collected events, in the filter syntax of the System Log API.
+    filter: Option<String>,
+    #[serde(default = "default_interval_secs")]
+    interval_secs: u64,
+    #[serde(default = "default_lookback_secs")]
+    lookback_secs: u64,
+    data_dir: Option<PathBuf>,
+    tls: Option<TlsConfig>,
+}
+
+inventory::submit! {
+    SourceDescription::new::<OktaConfig>("okta")
+}
+
+impl GenerateConfig for OktaConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"domain = "example.okta.com"
+            token = "${OKTA_API_TOKEN}""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "okta")]
+impl SourceConfig for OktaConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let data_dir = cx
+            .globals
+            .resolve_and_make_data_subdir(self.data_dir.as_ref(), cx.key.id())?;
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls, &cx.proxy)?;
+
+        let api = SystemLog {
+            base_url: self.base_url()?,
+            token: self.token.clone(),
+            filter: self.filter.clone(),
+            lookback: Duration::seconds(self.lookback_secs as i64),
+        };
+        let poller = Poller {
+            client: ApiClient::new(client),
+            checkpointer: Checkpointer::new(data_dir),
+            interval: std::time::Duration::from_secs(self.interval_secs),
+            source_type: "okta",
+            timestamp_field: "published",
+        };
+        Ok(Box::pin(poller.run(api, cx.out, cx.shutdown)))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "okta"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+impl OktaConfig {
+    /// The domain can be given with a scheme, to reach an API served over plain HTTP.
+    fn base_url(&self) -> crate::Result<Url> {
+        let domain = self.domain.trim_end_matches('/');
+        let url = if domain.contains("://") {
+            domain.to_owned()
+        } else {
+            format!("https://{}", domain)
+        };
+        Ok(Url::parse(&url)?)
+    }
+}
+
+/// The [System Log API], polled by following the `next` links of its pages, which are always
+/// present when the events are sorted in ascending order. The cursor is the link to the next
+/// page, pointing past the events collected so far.
+///
+/// [System Log API]: https://developer.okta.com/docs/reference/api/system-log/
+struct SystemLog {
+    base_url: Url,
+    token: String,
+    filter: Option<String>,
+    lookback: Duration,
+}
+
+impl SystemLog {
+    fn first_page(&self) -> String {
+        let since = Utc::now() - self.lookback;
+        let mut url = self.base_url.join("/api/v1/logs").expect("valid path");
+        {
+            let mut query = url.query_pairs_mut();
+            query
+                .append_pair("since", &since.to_rfc3339_opts(SecondsFormat::Millis, true))
+                .append_pair("sortOrder", "ASCENDING")
+                .append_pair("limit", PAGE_SIZE);
+            if let Some(filter) = &self.filter {
+                query.append_pair("filter", filter);
+            }
+        }
+        url.into()
+    }
+
+    /// Checkpointed links are only followed if they're for the configured organization.
+    fn resume(&self, cursor: Option<&str>) -> Option<String> {
+        let cursor = Url::parse(cursor?).ok()?;
+        (cursor.origin() == self.base_url.origin()).then(|| cursor.into())
+    }
+}
+
+#[async_trait::async_trait]
+impl AuditLogApi for SystemLog {
+    async fn fetch(&mut self, client: &mut ApiClient, cursor: Option<&str>) -> crate::Result<Page> {
+        let url = self.resume(cursor).unwrap_or_else(|| self.first_page());
+        let authorization = format!("SSWS {}", self.token);
+        let response = client
+            .send(|| {
+                Request::get(url.as_str())
+                    .header("Accept", "application/json")
+                    .header("Authorization", authorization.as_str())
+                    .body(Body::empty())
+                    .expect("valid request")
+            })
+            .await?;
+
+        let records: Vec<serde_json::Value> = response.json()?;
+        let next = next_link(&response.headers);
+        Ok(Page {
+            more: !records.is_empty() && next.is_some(),
+            cursor: next,
+            records,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<OktaConfig>();
+    }
+
+    fn system_log(domain: &str) -> SystemLog {
+        let config: OktaConfig = toml::from_str(&format!(
+            r#"domain = "{}"
+            token = "secret"
+            filter = 'eventType eq "user.session.start"'"#,
+            domain
+        ))
+        .unwrap();
+        SystemLog {
+            base_url: config.base_url().unwrap(),
+            token: config.token,
+            filter: config.filter,
+            lookback: Duration::seconds(config.lookback_secs as i64),
+        }
+    }
+
+    #[test]
+    fn builds_first_page() {
+        let url = Url::parse(&system_log("example.okta.com").first_page()).unwrap();
+        assert_eq!(url.host_str(), Some("example.okta.com"));
+        assert_eq!(url.path(), "/api/v1/logs");
+        let query = url.query_pairs().into_owned().collect::<Vec<_>>();
+        assert_eq!(query[0].0, "since");
+        assert!(query.contains(&("sortOrder".into(), "ASCENDING".into())));
+        assert!(query.contains(&(
+            "filter".into(),
+            r#"eventType eq "user.session.start""#.into()
+        )));
+    }
+
+    #[test]
+    fn resumes_from_links_of_the_organization() {
+        let api = system_log("http://localhost:8080/");
+        let link = "http://localhost:8080/api/v1/logs?after=1650000000000_1";
+        assert_eq!(api.resume(Some(link)).as_deref(), Some(link));
+        assert_eq!(
+            api.resume(Some("https://other.okta.com/api/v1/logs?after=1")),
+            None
+        );
+        assert_eq!(api.resume(None), None);
+    }
+}
@@ -0,0 +1,822 @@
+//! Conversions of the OTLP export requests into Vector events.
+//!
+//! The attributes of the resource and instrumentation scope that produced the data are kept
+//! with each event: in the metadata of logs and traces, shared by the events of the same scope,
+//! and as tags prefixed with `resource.` and `scope.` for metrics, since they tell apart the
+//! series of different resources.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use bytes::Bytes;
+use chrono::{DateTime, TimeZone, Utc};
+use ordered_float::NotNan;
+
+use super::SOURCE_TYPE;
+use crate::{
+    config::log_schema,
+    event::{
+        metric::{Bucket, MetricTags, Quantile},
+        Event, LogEvent, Metric, MetricKind, MetricValue, OpenTelemetryScope, TraceEvent, Value,
+    },
+    proto::opentelemetry::{
+        any_value,
+        proto::{
+            logs::v1::LogRecord,
+            metrics::v1::{
+                exponential_histogram_data_point::Buckets, metric::Data, number_data_point,
+                AggregationTemporality, NumberDataPoint,
+            },
+            trace::v1::{span, status, Span},
+        },
+        AnyValue, ExportLogsServiceRequest, ExportMetricsServiceRequest, ExportTraceServiceRequest,
+        InstrumentationScope, KeyValue, Resource,
+    },
+};
+
+pub(super) fn logs(request: ExportLogsServiceRequest) -> Vec<Event> {
+    let now = Utc::now();
+    let mut events = Vec::new();
+    for resource_logs in request.resource_logs {
+        let resource = resource_value(resource_logs.resource);
+        for scope_logs in resource_logs.scope_logs {
+            let scope = opentelemetry_scope(&resource, scope_logs.scope);
+            events.extend(
+                scope_logs
+                    .log_records
+                    .into_iter()
+                    .map(|record| log_event(record, &scope, now)),
+            );
+        }
+    }
+    events
+}
+
+fn log_event(record: LogRecord, scope: &Arc<OpenTelemetryScope>, now: DateTime<Utc>) -> Event {
+    let observed_timestamp = timestamp(record.observed_time_unix_nano);
+
+    let mut fields = BTreeMap::new();
+    fields.insert("attributes".to_owned(), attributes_value(record.attributes));
+    insert_id(&mut fields, "trace_id", &record.trace_id);
+    insert_id(&mut fields, "span_id", &record.span_id);
+    if !record.severity_text.is_empty() {
+        fields.insert("severity_text".to_owned(), record.severity_text.into());
+    }
+    if record.severity_number != 0 {
+        fields.insert(
+            "severity_number".to_owned(),
+            i64::from(record.severity_number).into(),
+        );
+    }
+    if record.flags != 0 {
+        fields.insert("flags".to_owned(), i64::from(record.flags).into());
+    }
+    if record.dropped_attributes_count != 0 {
+        fields.insert(
+            "dropped_attributes_count".to_owned(),
+            i64::from(record.dropped_attributes_count).into(),
+        );
+    }
+    if let Some(observed_timestamp) = observed_timestamp {
+        fields.insert("observed_timestamp".to_owned(), observed_timestamp.into());
+    }
+
+    let mut log = LogEvent::from(fields);
+    log.insert(
+        log_schema().message_key(),
+        record.body.map(to_value).unwrap_or(Value::Null),
+    );
+    log.insert(
+        log_schema().timestamp_key(),
+        timestamp(record.time_unix_nano)
+            .or(observed_timestamp)
+            .unwrap_or(now),
+    );
+    log.insert(log_schema().source_type_key(), Bytes::from(SOURCE_TYPE));
+    log.metadata_mut()
+        .set_opentelemetry_scope(Some(Arc::clone(scope)));
+    log.into()
+}
+
+pub(super) fn traces(request: ExportTraceServiceRequest) -> Vec<Event> {
+    let mut events = Vec::new();
+    for resource_spans in request.resource_spans {
+        let resource = resource_value(resource_spans.resource);
+        for scope_spans in resource_spans.scope_spans {
+            let scope = opentelemetry_scope(&resource, scope_spans.scope);
+            events.extend(
+                scope_spans
+                    .spans
+                    .into_iter()
+                    .map(|span| trace_event(span, &scope)),
+            );
+        }
+    }
+    events
+}
+
+fn trace_event(span: Span, scope: &Arc<OpenTelemetryScope>) -> Event {
+    let mut fields = BTreeMap::new();
+    insert_id(&mut fields, "trace_id", &span.trace_id);
+    insert_id(&mut fields, "span_id", &span.span_id);
+    insert_id(&mut fields, "parent_span_id", &span.parent_span_id);
+    if !span.trace_state.is_empty() {
+        fields.insert("trace_state".to_owned(), span.trace_state.into());
+    }
+    fields.insert("name".to_owned(), span.name.into());
+    fields.insert("kind".to_owned(), span_kind(span.kind).into());
+    if let Some(start) = timestamp(span.start_time_unix_nano) {
+        fields.insert("start_timestamp".to_owned(), start.into());
+    }
+    if let Some(end) = timestamp(span.end_time_unix_nano) {
+        fields.insert("end_timestamp".to_owned(), end.into());
+    }
+    fields.insert("attributes".to_owned(), attributes_value(span.attributes));
+    fields.insert(
+        "events".to_owned(),
+        Value::Array(
+            span.events
+                .into_iter()
+                .map(|event| {
+                    let mut fields = BTreeMap::new();
+                    fields.insert("name".to_owned(), event.name.into());
+                    if let Some(time) = timestamp(event.time_unix_nano) {
+                        fields.insert("timestamp".to_owned(), time.into());
+                    }
+                    fields.insert("attributes".to_owned(), attributes_value(event.attributes));
+                    Value::Object(fields)
+                })
+                .collect(),
+        ),
+    );
+    fields.insert(
+        "links".to_owned(),
+        Value::Array(
+            span.links
+                .into_iter()
+                .map(|link| {
+                    let mut fields = BTreeMap::new();
+                    insert_id(&mut fields, "trace_id", &link.trace_id);
+                    insert_id(&mut fields, "span_id", &link.span_id);
+                    if !link.trace_state.is_empty() {
+                        fields.insert("trace_state".to_owned(), link.trace_state.into());
+                    }
+                    fields.insert("attributes".to_owned(), attributes_value(link.attributes));
+                    Value::Object(fields)
+                })
+                .collect(),
+        ),
+    );
+    let status = span.status.unwrap_or_default();
+    let mut status_fields = BTreeMap::new();
+    status_fields.insert("code".to_owned(), status_code(status.code).into());
+    if !status.message.is_empty() {
+        status_fields.insert("message".to_owned(), status.message.into());
+    }
+    fields.insert("status".to_owned(), Value::Object(status_fields));
+    for (key, count) in [
+        ("dropped_attributes_count", span.dropped_attributes_count),
+        ("dropped_events_count", span.dropped_events_count),
+        ("dropped_links_count", span.dropped_links_count),
+    ] {
+        if count != 0 {
+            fields.insert(key.to_owned(), i64::from(count).into());
+        }
+    }
+    fields.insert(
+        log_schema().source_type_key().to_owned(),
+        Bytes::from(SOURCE_TYPE).into(),
+    );
+
+    let mut trace = TraceEvent::from(fields);
+    trace
+        .metadata_mut()
+        .set_opentelemetry_scope(Some(Arc::clone(scope)));
+    trace.into()
+}
+
+fn span_kind(kind: i32) -> &'static str {
+    match span::SpanKind::from_i32(kind) {
+        Some(span::SpanKind::Internal) => "internal",
+        Some(span::SpanKind::Server) => "server",
+        Some(span::SpanKind::Client) => "client",
+        Some(span::SpanKind::Producer) => "producer",
+        Some(span::SpanKind::Consumer) => "consumer",
+        Some(span::SpanKind::Unspecified) | None => "unspecified",
+    }
+}
+
+fn status_code(code: i32) -> &'static str {
+    match status::StatusCode::from_i32(code) {
+        Some(status::StatusCode::Ok) => "ok",
+        Some(status::StatusCode::Error) => "error",
+        Some(status::StatusCode::Unset) | None => "unset",
+    }
+}
+
+pub(super) fn metrics(request: ExportMetricsServiceRequest) -> Vec<Event> {
+    let mut events = Vec::new();
+    for resource_metrics in request.resource_metrics {
+        let mut resource_tags = MetricTags::new();
+        if let Some(resource) = resource_metrics.resource {
+            add_tags(&mut resource_tags, "resource.", resource.attributes);
+        }
+        for scope_metrics in resource_metrics.scope_metrics {
+            let mut scope_tags = resource_tags.clone();
+            if let Some(scope) = scope_metrics.scope {
+                if !scope.name.is_empty() {
+                    scope_tags.insert("scope.name".to_owned(), scope.name);
+                }
+                if !scope.version.is_empty() {
+                    scope_tags.insert("scope.version".to_owned(), scope.version);
+                }
+                add_tags(&mut scope_tags, "scope.", scope.attributes);
+            }
+            for metric in scope_metrics.metrics {
+                if let Some(data) = metric.data {
+                    events.extend(
+                        metric_events(&metric.name, data, &scope_tags)
+                            .into_iter()
+                            .map(Event::from),
+                    );
+                }
+            }
+        }
+    }
+    events
+}
+
+/// Converts each data point of an OTLP metric into a metric.
+fn metric_events(name: &str, data: Data, scope_tags: &MetricTags) -> Vec<Metric> {
+    let point = |attributes: Vec<KeyValue>, time: u64, kind: MetricKind, value: MetricValue| {
+        let mut tags = scope_tags.clone();
+        add_tags(&mut tags, "", attributes);
+        Metric::new(name, kind, value)
+            .with_tags(Some(tags))
+            .with_timestamp(timestamp(time))
+    };
+
+    match data {
+        Data::Gauge(gauge) => gauge
+            .data_points
+            .into_iter()
+            .filter_map(|data_point| {
+                let value = number_value(&data_point)?;
+                Some(point(
+                    data_point.attributes,
+                    data_point.time_unix_nano,
+                    MetricKind::Absolute,
+                    MetricValue::Gauge { value },
+                ))
+            })
+            .collect(),
+        Data::Sum(sum) => {
+            let kind = temporality_kind(sum.aggregation_temporality);
+            sum.data_points
+                .into_iter()
+                .filter_map(|data_point| {
+                    let value = number_value(&data_point)?;
+                    // Sums that can decrease are reported as gauges.
+                    let value = if sum.is_monotonic {
+                        MetricValue::Counter { value }
+                    } else {
+                        MetricValue::Gauge { value }
+                    };
+                    Some(point(
+                        data_point.attributes,
+                        data_point.time_unix_nano,
+                        kind,
+                        value,
+                    ))
+                })
+                .collect()
+        }
+        Data::Histogram(histogram) => {
+            let kind = temporality_kind(histogram.aggregation_temporality);
+            histogram
+                .data_points
+                .into_iter()
+                .map(|data_point| {
+                    let buckets = data_point
+                        .bucket_counts
+                        .iter()
+                        .enumerate()
+                        .map(|(index, &count)| Bucket {
+                            upper_limit: data_point
+                                .explicit_bounds
+                                .get(index)
+                                .copied()
+                                .unwrap_or(f64::INFINITY),
+                            count: saturating_count(count),
+                        })
+                        .collect();
+                    point(
+                        data_point.attributes,
+                        data_point.time_unix_nano,
+                        kind,
+                        MetricValue::AggregatedHistogram {
+                            buckets,
+                            count: saturating_count(data_point.count),
+                            sum: data_point.sum,
+                        },
+                    )
+                })
+                .collect()
+        }
+        Data::ExponentialHistogram(histogram) => {
+            let kind = temporality_kind(histogram.aggregation_temporality);
+            histogram
+                .data_points
+                .into_iter()
+                .map(|data_point| {
+                    let buckets = exponential_buckets(
+                        data_point.scale,
+                        data_point.zero_count,
+                        data_point.negative,
+                        data_point.positive,
+                    );
+                    point(
+                        data_point.attributes,
+                        data_point.time_unix_nano,
+                        kind,
+                        MetricValue::AggregatedHistogram {
+                            buckets,
+                            count: saturating_count(data_point.count),
+                            sum: data_point.sum,
+                        },
+                    )
+                })
+                .collect()
+        }
+        Data::Summary(summary) => summary
+            .data_points
+            .into_iter()
+            .map(|data_point| {
+                let quantiles = data_point
+                    .quantile_values
+                    .iter()
+                    .map(|quantile| Quantile {
+                        quantile: quantile.quantile,
+                        value: quantile.value,
+                    })
+                    .collect();
+                point(
+                    data_point.attributes,
+                    data_point.time_unix_nano,
+                    MetricKind::Absolute,
+                    MetricValue::AggregatedSummary {
+                        quantiles,
+                        count: saturating_count(data_point.count),
+                        sum: data_point.sum,
+                    },
+                )
+            })
+            .collect(),
+    }
+}
+
+fn number_value(data_point: &NumberDataPoint) -> Option<f64> {
+    match data_point.value? {
+        number_data_point::Value::AsDouble(value) => Some(value),
+        number_data_point::Value::AsInt(value) => Some(value as f64),
+    }
+}
+
+/// Delta data points are increments, while cumulative ones are absolute values since their
+/// start time.
+fn temporality_kind(temporality: i32) -> MetricKind {
+    match AggregationTemporality::from_i32(temporality) {
+        Some(AggregationTemporality::Delta) => MetricKind::Incremental,
+        _ => MetricKind::Absolute,
+    }
+}
+
+/// Lists the buckets of an exponential histogram by their upper limit, from the negative
+/// buckets to the positive ones.
+///
+/// The bucket at index `i` holds the values in `(base^i, base^(i+1)]`, where
+/// `base = 2^(2^-scale)`, and its negative counterpart holds the opposite values.
+fn exponential_buckets(
+    scale: i32,
+    zero_count: u64,
+    negative: Option<Buckets>,
+    positive: Option<Buckets>,
+) -> Vec<Bucket> {
+    let bound = |index: i32| (f64::from(index) * f64::from(-scale).exp2()).exp2();
+
+    let mut buckets = Vec::new();
+    if let Some(negative) = negative {
+        for (position, &count) in negative.bucket_counts.iter().enumerate().rev() {
+            buckets.push(Bucket {
+                upper_limit: -bound(negative.offset + position as i32),
+                count: saturating_count(count),
+            });
+        }
+    }
+    buckets.push(Bucket {
+        upper_limit: 0.0,
+        count: saturating_count(zero_count),
+    });
+    if let Some(positive) = positive {
+        for (position, &count) in positive.bucket_counts.iter().enumerate() {
+            buckets.push(Bucket {
+                upper_limit: bound(positive.offset + position as i32 + 1),
+                count: saturating_count(count),
+            });
+        }
+    }
+    buckets
+}
+
+fn saturating_count(count: u64) -> u32 {
+    u32::try_from(count).unwrap_or(u32::MAX)
+}
+
+fn add_tags(tags: &mut MetricTags, prefix: &str, attributes: Vec<KeyValue>) {
+    for attribute in attributes {
+        let value = attribute.value.map(to_value).unwrap_or(Value::Null);
+        tags.insert(
+            format!("{}{}", prefix, attribute.key),
+            value.to_string_lossy(),
+        );
+    }
+}
+
+fn resource_value(resource: Option<Resource>) -> Value {
+    attributes_value(
+        resource
+            .map(|resource| resource.attributes)
+            .unwrap_or_default(),
+    )
+}
+
+fn opentelemetry_scope(
+    resource: &Value,
+    scope: Option<InstrumentationScope>,
+) -> Arc<OpenTelemetryScope> {
+    Arc::new(OpenTelemetryScope {
+        resource: resource.clone(),
+        scope: scope_value(scope),
+    })
+}
+
+fn scope_value(scope: Option<InstrumentationScope>) -> Value {
+    let scope = scope.unwrap_or_default();
+    let mut fields = BTreeMap::new();
+    if !scope.name.is_empty() {
+        fields.insert("name".to_owned(), scope.name.into());
+    }
+    if !scope.version.is_empty() {
+        fields.insert("version".to_owned(), scope.version.into());
+    }
+    if !scope.attributes.is_empty() {
+        fields.insert("attributes".to_owned(), attributes_value(scope.attributes));
+    }
+    Value::Object(fields)
+}
+
+fn attributes_value(attributes: Vec<KeyValue>) -> Value {
+    Value::Object(
+        attributes
+            .into_iter()
+            .map(|attribute| {
+                (
+                    attribute.key,
+                    attribute.value.map(to_value).unwrap_or(Value::Null),
+                )
+            })
+            .collect(),
+    )
+}
+
+fn to_value(value: AnyValue) -> Value {
+    match value.value {
+        Some(any_value::Value::StringValue(string)) => string.into(),
+        Some(any_value::Value::BoolValue(boolean)) => boolean.into(),
+        Some(any_value::Value::IntValue(integer)) => integer.into(),
+        Some(any_value::Value::DoubleValue(double)) => {
+            NotNan::new(double).map(Value::Float).unwrap_or(Value::Null)
+        }
+        Some(any_value::Value::BytesValue(bytes)) => Bytes::from(bytes).into(),
+        Some(any_value::Value::ArrayValue(array)) => {
+            Value::Array(array.values.into_iter().map(to_value).collect())
+        }
+        Some(any_value::Value::KvlistValue(list)) => attributes_value(list.values),
+        None => Value::Null,
+    }
+}
+
+/// Trace and span IDs are written in lowercase hexadecimal, as in W3C trace context headers.
+fn insert_id(fields: &mut BTreeMap<String, Value>, key: &str, id: &[u8]) {
+    if !id.is_empty() {
+        let id = id
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>();
+        fields.insert(key.to_owned(), id.into());
+    }
+}
+
+/// Zero stands for an unknown time in OTLP.
+fn timestamp(nanos: u64) -> Option<DateTime<Utc>> {
+    i64::try_from(nanos)
+        .ok()
+        .filter(|&nanos| nanos != 0)
+        .map(|nanos| Utc.timestamp_nanos(nanos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::opentelemetry::proto::{
+        common::v1::ArrayValue,
+        logs::v1::{ResourceLogs, ScopeLogs},
+        metrics::v1::{
+            metric, Gauge, Histogram, HistogramDataPoint, Metric as OtlpMetric, ResourceMetrics,
+            ScopeMetrics, Sum,
+        },
+        trace::v1::{ResourceSpans, ScopeSpans, Status},
+    };
+
+    fn string(value: &str) -> Option<AnyValue> {
+        Some(AnyValue {
+            value: Some(any_value::Value::StringValue(value.to_owned())),
+        })
+    }
+
+    fn attribute(key: &str, value: Option<AnyValue>) -> KeyValue {
+        KeyValue {
+            key: key.to_owned(),
+            value,
+        }
+    }
+
+    fn resource() -> Option<Resource> {
+        Some(Resource {
+            attributes: vec![attribute("service.name", string("checkout"))],
+            dropped_attributes_count: 0,
+        })
+    }
+
+    fn scope() -> Option<InstrumentationScope> {
+        Some(InstrumentationScope {
+            name: "io.opentelemetry.http".to_owned(),
+            version: "1.0.0".to_owned(),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn converts_logs() {
+        let request = ExportLogsServiceRequest {
+            resource_logs: vec![ResourceLogs {
+                resource: resource(),
+                scope_logs: vec![ScopeLogs {
+                    scope: scope(),
+                    log_records: vec![LogRecord {
+                        time_unix_nano: 1_654_000_000_123_000_000,
+                        severity_number: 9,
+                        severity_text: "INFO".to_owned(),
+                        body: string("Order placed."),
+                        attributes: vec![
+                            attribute(
+                                "order.items",
+                                Some(AnyValue {
+                                    value: Some(any_value::Value::ArrayValue(ArrayValue {
+                                        values: vec![string("book").unwrap()],
+                                    })),
+                                }),
+                            ),
+                            attribute("empty", None),
+                        ],
+                        trace_id: vec![0x4b; 16],
+                        span_id: vec![0, 0xf0, 0x67, 0xaa, 0x0b, 0xa9, 0x02, 0xb7],
+                        ..Default::default()
+                    }],
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            }],
+        };
+
+        let events = logs(request);
+        assert_eq!(events.len(), 1);
+        let log = events[0].as_log();
+        assert_eq!(log[log_schema().message_key()], "Order placed.".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.timestamp_nanos(1_654_000_000_123_000_000).into()
+        );
+        assert_eq!(log[log_schema().source_type_key()], SOURCE_TYPE.into());
+        assert_eq!(log["severity_text"], "INFO".into());
+        assert_eq!(log["severity_number"], 9.into());
+        assert_eq!(
+            log["attributes.\"order.items\""],
+            Value::Array(vec!["book".into()])
+        );
+        assert_eq!(log["attributes.empty"], Value::Null);
+        assert!(log.get("resources").is_none());
+        assert!(log.get("scope").is_none());
+        let scope = log.metadata().opentelemetry_scope().clone().unwrap();
+        assert_eq!(
+            scope.resource,
+            Value::from(BTreeMap::from([(
+                "service.name".to_owned(),
+                Value::from("checkout")
+            )]))
+        );
+        assert_eq!(
+            scope.scope,
+            Value::from(BTreeMap::from([
+                ("name".to_owned(), Value::from("io.opentelemetry.http")),
+                ("version".to_owned(), Value::from("1.0.0")),
+            ]))
+        );
+        assert_eq!(log["trace_id"], "4b".repeat(16).into());
+        assert_eq!(log["span_id"], "00f067aa0ba902b7".into());
+        assert!(log.get("flags").is_none());
+    }
+
+    #[test]
+    fn converts_traces() {
+        let request = ExportTraceServiceRequest {
+            resource_spans: vec![ResourceSpans {
+                resource: resource(),
+                scope_spans: vec![ScopeSpans {
+                    scope: scope(),
+                    spans: vec![Span {
+                        trace_id: vec![1; 16],
+                        span_id: vec![2; 8],
+                        name: "GET /orders".to_owned(),
+                        kind: span::SpanKind::Server as i32,
+                        start_time_unix_nano: 1_654_000_000_000_000_000,
+                        end_time_unix_nano: 1_654_000_001_000_000_000,
+                        attributes: vec![attribute("http.method", string("GET"))],
+                        status: Some(Status {
+                            message: "timed out".to_owned(),
+                            code: status::StatusCode::Error as i32,
+                        }),
+                        ..Default::default()
+                    }],
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            }],
+        };
+
+        let events = traces(request);
+        assert_eq!(events.len(), 1);
+        let trace = events[0].as_trace();
+        assert_eq!(trace.get("name"), Some(&"GET /orders".into()));
+        assert_eq!(trace.get("kind"), Some(&"server".into()));
+        assert_eq!(trace.get("trace_id"), Some(&"01".repeat(16).into()));
+        assert_eq!(trace.get("parent_span_id"), None);
+        assert_eq!(
+            trace.get("end_timestamp"),
+            Some(&Utc.timestamp(1_654_000_001, 0).into())
+        );
+        assert_eq!(trace.get("status.code"), Some(&"error".into()));
+        assert_eq!(trace.get("status.message"), Some(&"timed out".into()));
+        assert_eq!(trace.get("attributes.\"http.method\""), Some(&"GET".into()));
+        assert_eq!(trace.get("resources"), None);
+        let scope = trace.metadata().opentelemetry_scope().clone().unwrap();
+        assert_eq!(
+            scope.resource,
+            Value::from(BTreeMap::from([(
+                "service.name".to_owned(),
+                Value::from("checkout")
+            )]))
+        );
+    }
+
+    fn metrics_request(metrics: Vec<OtlpMetric>) -> ExportMetricsServiceRequest {
+        ExportMetricsServiceRequest {
+            resource_metrics: vec![ResourceMetrics {
+                resource: resource(),
+                scope_metrics: vec![ScopeMetrics {
+                    scope: scope(),
+                    metrics,
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            }],
+        }
+    }
+
+    fn number_point(value: number_data_point::Value) -> NumberDataPoint {
+        NumberDataPoint {
+            attributes: vec![attribute("host", string("a"))],
+            time_unix_nano: 1_654_000_000_000_000_000,
+            value: Some(value),
+            ..Default::default()
+        }
+    }
+
+    fn otlp_metric(name: &str, data: metric::Data) -> OtlpMetric {
+        OtlpMetric {
+            name: name.to_owned(),
+            data: Some(data),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn converts_metrics() {
+        let events = metrics(metrics_request(vec![
+            otlp_metric(
+                "temperature",
+                metric::Data::Gauge(Gauge {
+                    data_points: vec![number_point(number_data_point::Value::AsDouble(21.5))],
+                }),
+            ),
+            otlp_metric(
+                "requests",
+                metric::Data::Sum(Sum {
+                    data_points: vec![number_point(number_data_point::Value::AsInt(3))],
+                    aggregation_temporality: AggregationTemporality::Delta as i32,
+                    is_monotonic: true,
+                }),
+            ),
+            otlp_metric(
+                "latency",
+                metric::Data::Histogram(Histogram {
+                    data_points: vec![HistogramDataPoint {
+                        count: 6,
+                        sum: 2.5,
+                        bucket_counts: vec![1, 2, 3],
+                        explicit_bounds: vec![0.1, 1.0],
+                        ..Default::default()
+                    }],
+                    aggregation_temporality: AggregationTemporality::Cumulative as i32,
+                }),
+            ),
+        ]));
+        let metrics = events
+            .into_iter()
+            .map(Event::into_metric)
+            .collect::<Vec<_>>();
+        assert_eq!(metrics.len(), 3);
+
+        assert_eq!(metrics[0].name(), "temperature");
+        assert_eq!(metrics[0].kind(), MetricKind::Absolute);
+        assert_eq!(metrics[0].value(), &MetricValue::Gauge { value: 21.5 });
+        assert_eq!(
+            metrics[0].timestamp(),
+            Some(Utc.timestamp(1_654_000_000, 0))
+        );
+        let tags = metrics[0].tags().unwrap();
+        assert_eq!(tags["host"], "a");
+        assert_eq!(tags["resource.service.name"], "checkout");
+        assert_eq!(tags["scope.name"], "io.opentelemetry.http");
+
+        assert_eq!(metrics[1].kind(), MetricKind::Incremental);
+        assert_eq!(metrics[1].value(), &MetricValue::Counter { value: 3.0 });
+
+        assert_eq!(metrics[2].kind(), MetricKind::Absolute);
+        assert_eq!(
+            metrics[2].value(),
+            &MetricValue::AggregatedHistogram {
+                buckets: vec![
+                    Bucket {
+                        upper_limit: 0.1,
+                        count: 1
+                    },
+                    Bucket {
+                        upper_limit: 1.0,
+                        count: 2
+                    },
+                    Bucket {
+                        upper_limit: f64::INFINITY,
+                        count: 3
+                    },
+                ],
+                count: 6,
+                sum: 2.5,
+            }
+        );
+    }
+
+    #[test]
+    fn converts_exponential_buckets() {
+        let buckets = exponential_buckets(
+            1,
+            4,
+            Some(Buckets {
+                offset: 0,
+                bucket_counts: vec![1, 2],
+            }),
+            Some(Buckets {
+                offset: -1,
+                bucket_counts: vec![5, 6],
+            }),
+        );
+        let limits = buckets
+            .iter()
+            .map(|bucket| (bucket.upper_limit, bucket.count))
+            .collect::<Vec<_>>();
+        let root = 2f64.sqrt();
+        assert_eq!(
+            limits,
+            vec![(-root, 2), (-1.0, 1), (0.0, 4), (1.0, 5), (root, 6)]
+        );
+    }
+}
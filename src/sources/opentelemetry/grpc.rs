@@ -0,0 +1,89 @@
+use std::net::SocketAddr;
+
+use tonic::{Request, Response, Status};
+
+use super::{convert, send_events, LOGS, METRICS, TRACES};
+use crate::{
+    proto::opentelemetry::{
+        ExportLogsServiceRequest, ExportLogsServiceResponse, ExportMetricsServiceRequest,
+        ExportMetricsServiceResponse, ExportTraceServiceRequest, ExportTraceServiceResponse,
+        LogsService, LogsServiceServer, MetricsService, MetricsServiceServer, TraceService,
+        TraceServiceServer,
+    },
+    shutdown::ShutdownSignal,
+    sources::util::grpc::run_grpc_server_with_routes,
+    tls::MaybeTlsSettings,
+    SourceSender,
+};
+
+#[derive(Debug, Clone)]
+struct Service {
+    pipeline: SourceSender,
+    acknowledgements: bool,
+}
+
+#[tonic::async_trait]
+impl LogsService for Service {
+    async fn export(
+        &self,
+        request: Request<ExportLogsServiceRequest>,
+    ) -> Result<Response<ExportLogsServiceResponse>, Status> {
+        let events = convert::logs(request.into_inner());
+        send_events(self.pipeline.clone(), LOGS, events, self.acknowledgements).await?;
+
+        Ok(Response::new(ExportLogsServiceResponse::default()))
+    }
+}
+
+#[tonic::async_trait]
+impl MetricsService for Service {
+    async fn export(
+        &self,
+        request: Request<ExportMetricsServiceRequest>,
+    ) -> Result<Response<ExportMetricsServiceResponse>, Status> {
+        let events = convert::metrics(request.into_inner());
+        send_events(
+            self.pipeline.clone(),
+            METRICS,
+            events,
+            self.acknowledgements,
+        )
+        .await?;
+
+        Ok(Response::new(ExportMetricsServiceResponse::default()))
+    }
+}
+
+#[tonic::async_trait]
+impl TraceService for Service {
+    async fn export(
+        &self,
+        request: Request<ExportTraceServiceRequest>,
+    ) -> Result<Response<ExportTraceServiceResponse>, Status> {
+        let events = convert::traces(request.into_inner());
+        send_events(self.pipeline.clone(), TRACES, events, self.acknowledgements).await?;
+
+        Ok(Response::new(ExportTraceServiceResponse::default()))
+    }
+}
+
+pub(super) async fn run(
+    address: SocketAddr,
+    tls_settings: MaybeTlsSettings,
+    pipeline: SourceSender,
+    acknowledgements: bool,
+    shutdown: ShutdownSignal,
+) -> crate::Result<()> {
+    let service = Service {
+        pipeline,
+        acknowledgements,
+    };
+
+    run_grpc_server_with_routes(address, tls_settings, shutdown, |server| {
+        server
+            .add_service(LogsServiceServer::new(service.clone()).accept_gzip())
+            .add_service(MetricsServiceServer::new(service.clone()).accept_gzip())
+            .add_service(TraceServiceServer::new(service).accept_gzip())
+    })
+    .await
+}
@@ -0,0 +1,192 @@
+use std::net::SocketAddr;
+
+use bytes::Bytes;
+use futures::FutureExt;
+use http::StatusCode;
+use prost::Message;
+use tracing::Span;
+use warp::{
+    filters::BoxedFilter, path, path::FullPath, reject::Rejection, reply::Response, Filter, Reply,
+};
+
+use super::{convert, send_events, SendError, LOGS, METRICS, TRACES};
+use crate::{
+    event::Event,
+    internal_events::HttpBytesReceived,
+    proto::opentelemetry::{
+        ExportLogsServiceRequest, ExportLogsServiceResponse, ExportMetricsServiceRequest,
+        ExportMetricsServiceResponse, ExportTraceServiceRequest, ExportTraceServiceResponse,
+    },
+    shutdown::ShutdownSignal,
+    sources::util::{decode, ErrorMessage},
+    tls::MaybeTlsSettings,
+    SourceSender,
+};
+
+const PROTOBUF_CONTENT_TYPE: &str = "application/x-protobuf";
+
+pub(super) async fn run(
+    address: SocketAddr,
+    tls_settings: MaybeTlsSettings,
+    out: SourceSender,
+    acknowledgements: bool,
+    shutdown: ShutdownSignal,
+) -> crate::Result<()> {
+    let protocol = tls_settings.http_protocol_name();
+    let listener = tls_settings.bind(&address).await?;
+
+    let logs = build_filter::<ExportLogsServiceRequest, ExportLogsServiceResponse>(
+        path!("v1" / "logs"),
+        LOGS,
+        convert::logs,
+        out.clone(),
+        acknowledgements,
+        protocol,
+    );
+    let metrics = build_filter::<ExportMetricsServiceRequest, ExportMetricsServiceResponse>(
+        path!("v1" / "metrics"),
+        METRICS,
+        convert::metrics,
+        out.clone(),
+        acknowledgements,
+        protocol,
+    );
+    let traces = build_filter::<ExportTraceServiceRequest, ExportTraceServiceResponse>(
+        path!("v1" / "traces"),
+        TRACES,
+        convert::traces,
+        out,
+        acknowledgements,
+        protocol,
+    );
+
+    let span = Span::current();
+    let routes = logs
+        .or(metrics)
+        .unify()
+        .or(traces)
+        .unify()
+        .with(warp::trace(move |_info| span.clone()))
+        .recover(|r: Rejection| async move {
+            if let Some(e_msg) = r.find::<ErrorMessage>() {
+                let json = warp::reply::json(e_msg);
+                Ok(warp::reply::with_status(json, e_msg.status_code()))
+            } else {
+                // other internal error - will return 500 internal server error
+                Err(r)
+            }
+        });
+    warp::serve(routes)
+        .serve_incoming_with_graceful_shutdown(listener.accept_stream(), shutdown.map(|_| ()))
+        .await;
+
+    Ok(())
+}
+
+/// Builds the route accepting the protobuf encoded export requests of one signal, as
+/// specified for OTLP/HTTP.
+fn build_filter<Req, Resp>(
+    path: impl Filter<Extract = (), Error = Rejection> + Clone + Send + Sync + 'static,
+    output: &'static str,
+    convert: fn(Req) -> Vec<Event>,
+    out: SourceSender,
+    acknowledgements: bool,
+    protocol: &'static str,
+) -> BoxedFilter<(Response,)>
+where
+    Req: Message + Default + 'static,
+    Resp: Message + Default + 'static,
+{
+    warp::post()
+        .and(path)
+        .and(warp::path::full())
+        .and(warp::header::optional::<String>("content-type"))
+        .and(warp::header::optional::<String>("content-encoding"))
+        .and(warp::body::bytes())
+        .and_then(
+            move |path: FullPath,
+                  content_type: Option<String>,
+                  encoding_header: Option<String>,
+                  body: Bytes| {
+                let events = decode_request(
+                    path.as_str(),
+                    content_type.as_deref(),
+                    &encoding_header,
+                    body,
+                    protocol,
+                )
+                .map(convert);
+                let out = out.clone();
+                async move {
+                    let events = events.map_err(warp::reject::custom)?;
+                    send_events(out, output, events, acknowledgements)
+                        .await
+                        .map_err(|error| warp::reject::custom(error_message(error)))?;
+                    Ok::<_, Rejection>(protobuf_reply(&Resp::default()))
+                }
+            },
+        )
+        .boxed()
+}
+
+fn decode_request<Req: Message + Default>(
+    path: &str,
+    content_type: Option<&str>,
+    encoding_header: &Option<String>,
+    body: Bytes,
+    protocol: &'static str,
+) -> Result<Req, ErrorMessage> {
+    // Parameters such as a charset don't matter for protobuf.
+    let media_type = content_type
+        .and_then(|content_type| content_type.split(';').next())
+        .map(str::trim);
+    if media_type != Some(PROTOBUF_CONTENT_TYPE) {
+        return Err(ErrorMessage::new(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            format!(
+                "Unsupported content type, expected {}",
+                PROTOBUF_CONTENT_TYPE
+            ),
+        ));
+    }
+
+    let body = decode(encoding_header, body)?;
+    emit!(HttpBytesReceived {
+        byte_size: body.len(),
+        http_path: path,
+        protocol,
+    });
+
+    Req::decode(body).map_err(|error| {
+        ErrorMessage::new(
+            StatusCode::BAD_REQUEST,
+            format!("Error decoding request: {}", error),
+        )
+    })
+}
+
+fn error_message(error: SendError) -> ErrorMessage {
+    match error {
+        SendError::Closed => ErrorMessage::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Source is shutting down".into(),
+        ),
+        SendError::Errored => ErrorMessage::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Error delivering contents to sink".into(),
+        ),
+        SendError::Rejected => ErrorMessage::new(
+            StatusCode::BAD_REQUEST,
+            "Contents failed to deliver to sink".into(),
+        ),
+    }
+}
+
+fn protobuf_reply(message: &impl Message) -> Response {
+    warp::reply::with_header(
+        message.encode_to_vec(),
+        http::header::CONTENT_TYPE,
+        PROTOBUF_CONTENT_TYPE,
+    )
+    .into_response()
+}
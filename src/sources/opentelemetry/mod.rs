@@ -0,0 +1,200 @@
+mod convert;
+mod grpc;
+mod http;
+#[cfg(test)]
+mod tests;
+
+use std::net::SocketAddr;
+
+use futures::{future::try_join, FutureExt, TryFutureExt};
+use serde::{Deserialize, Serialize};
+use tonic::Status;
+use vector_core::{
+    event::{BatchNotifier, BatchStatus, Event},
+    ByteSizeOf,
+};
+
+use crate::{
+    config::{
+        AcknowledgementsConfig, DataType, GenerateConfig, Output, Resource, SourceConfig,
+        SourceContext, SourceDescription,
+    },
+    internal_events::{EventsReceived, StreamClosedError},
+    serde::bool_or_struct,
+    sources::Source,
+    tls::{MaybeTlsSettings, TlsEnableableConfig},
+    SourceSender,
+};
+
+pub const LOGS: &str = "logs";
+pub const METRICS: &str = "metrics";
+pub const TRACES: &str = "traces";
+
+const SOURCE_TYPE: &str = "opentelemetry";
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct OpentelemetryConfig {
+    /// Where to accept OTLP over gRPC, usually on port 4317.
+    #[serde(default)]
+    grpc: Option<ListenerConfig>,
+    /// Where to accept OTLP over HTTP, usually on port 4318.
+    #[serde(default)]
+    http: Option<ListenerConfig>,
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+struct ListenerConfig {
+    address: SocketAddr,
+    #[serde(default)]
+    tls: Option<TlsEnableableConfig>,
+}
+
+impl GenerateConfig for OpentelemetryConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            grpc: Some(ListenerConfig {
+                address: "0.0.0.0:4317".parse().unwrap(),
+                tls: None,
+            }),
+            http: Some(ListenerConfig {
+                address: "0.0.0.0:4318".parse().unwrap(),
+                tls: None,
+            }),
+            acknowledgements: Default::default(),
+        })
+        .unwrap()
+    }
+}
+
+inventory::submit! {
+    SourceDescription::new::<OpentelemetryConfig>(SOURCE_TYPE)
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "opentelemetry")]
+impl SourceConfig for OpentelemetryConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<Source> {
+        if self.grpc.is_none() && self.http.is_none() {
+            return Err("At least one of `grpc` or `http` must be configured.".into());
+        }
+
+        let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
+
+        let grpc = match &self.grpc {
+            Some(listener) => {
+                let tls_settings = MaybeTlsSettings::from_config(&listener.tls, true)?;
+                grpc::run(
+                    listener.address,
+                    tls_settings,
+                    cx.out.clone(),
+                    acknowledgements,
+                    cx.shutdown.clone(),
+                )
+                .boxed()
+            }
+            None => futures::future::ok(()).boxed(),
+        };
+
+        let http = match &self.http {
+            Some(listener) => {
+                let tls_settings = MaybeTlsSettings::from_config(&listener.tls, true)?;
+                http::run(
+                    listener.address,
+                    tls_settings,
+                    cx.out,
+                    acknowledgements,
+                    cx.shutdown,
+                )
+                .boxed()
+            }
+            None => futures::future::ok(()).boxed(),
+        };
+
+        Ok(Box::pin(try_join(grpc, http).map_ok(|_| ()).map_err(
+            |error| {
+                error!(message = "Source future failed.", %error);
+            },
+        )))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![
+            Output::default(DataType::Log).with_port(LOGS),
+            Output::default(DataType::Metric).with_port(METRICS),
+            Output::default(DataType::Trace).with_port(TRACES),
+        ]
+    }
+
+    fn source_type(&self) -> &'static str {
+        SOURCE_TYPE
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        self.grpc
+            .iter()
+            .chain(self.http.iter())
+            .map(|listener| Resource::tcp(listener.address))
+            .collect()
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+/// Why a batch of events couldn't be handed off to the topology or delivered by its sinks.
+#[derive(Debug)]
+enum SendError {
+    Closed,
+    Errored,
+    Rejected,
+}
+
+impl From<SendError> for Status {
+    fn from(error: SendError) -> Self {
+        match error {
+            SendError::Closed => Status::unavailable("Source is shutting down"),
+            SendError::Errored => Status::internal("Delivery error"),
+            SendError::Rejected => Status::data_loss("Delivery failed"),
+        }
+    }
+}
+
+/// Sends the events decoded from a single export request to the given output, waiting for them
+/// to be delivered when acknowledgements are enabled.
+async fn send_events(
+    mut out: SourceSender,
+    output: &str,
+    mut events: Vec<Event>,
+    acknowledgements: bool,
+) -> Result<(), SendError> {
+    let count = events.len();
+    if count == 0 {
+        return Ok(());
+    }
+    emit!(EventsReceived {
+        count,
+        byte_size: events.size_of(),
+    });
+
+    let receiver = BatchNotifier::maybe_apply_to_events(acknowledgements, &mut events);
+    out.send_batch_named(output, events)
+        .await
+        .map_err(|error| {
+            emit!(StreamClosedError { error, count });
+            SendError::Closed
+        })?;
+
+    match receiver {
+        None => Ok(()),
+        Some(receiver) => match receiver.await {
+            BatchStatus::Delivered => Ok(()),
+            BatchStatus::Errored => Err(SendError::Errored),
+            BatchStatus::Rejected => Err(SendError::Rejected),
+        },
+    }
+}
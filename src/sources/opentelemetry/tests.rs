@@ -0,0 +1,196 @@
+use std::net::SocketAddr;
+
+use futures::{Stream, StreamExt};
+use prost::Message;
+use tonic::Code;
+
+use super::{OpentelemetryConfig, LOGS, METRICS, TRACES};
+use crate::{
+    config::{log_schema, SourceConfig, SourceContext},
+    event::{into_event_stream, Event, EventStatus},
+    proto::opentelemetry::{
+        any_value,
+        proto::{
+            collector::logs::v1::logs_service_client::LogsServiceClient,
+            logs::v1::{LogRecord, ResourceLogs, ScopeLogs},
+            trace::v1::{ResourceSpans, ScopeSpans, Span},
+        },
+        AnyValue, ExportLogsServiceRequest, ExportTraceServiceRequest, ExportTraceServiceResponse,
+    },
+    test_util::{
+        collect_ready,
+        components::{assert_source_compliance, HTTP_PUSH_SOURCE_TAGS, SOCKET_PUSH_SOURCE_TAGS},
+        next_addr, wait_for_tcp,
+    },
+    SourceSender,
+};
+
+#[test]
+fn generate_config() {
+    crate::test_util::test_generate_config::<OpentelemetryConfig>();
+}
+
+struct Outputs {
+    logs: Box<dyn Stream<Item = Event> + Unpin + Send>,
+    traces: Box<dyn Stream<Item = Event> + Unpin + Send>,
+}
+
+async fn source(status: EventStatus, acknowledgements: bool) -> (Outputs, SocketAddr, SocketAddr) {
+    let (mut sender, _) = SourceSender::new_test_finalize(status);
+    let logs = sender
+        .add_outputs(status, LOGS.to_owned())
+        .flat_map(into_event_stream);
+    let _metrics = sender.add_outputs(status, METRICS.to_owned());
+    let traces = sender
+        .add_outputs(status, TRACES.to_owned())
+        .flat_map(into_event_stream);
+
+    let grpc_address = next_addr();
+    let http_address = next_addr();
+    let config = toml::from_str::<OpentelemetryConfig>(&format!(
+        r#"
+            acknowledgements = {}
+            grpc.address = "{}"
+            http.address = "{}"
+        "#,
+        acknowledgements, grpc_address, http_address
+    ))
+    .unwrap();
+    let source = config
+        .build(SourceContext::new_test(sender, None))
+        .await
+        .unwrap();
+    tokio::spawn(source);
+    wait_for_tcp(grpc_address).await;
+    wait_for_tcp(http_address).await;
+
+    let outputs = Outputs {
+        logs: Box::new(logs),
+        traces: Box::new(traces),
+    };
+    (outputs, grpc_address, http_address)
+}
+
+fn logs_request() -> ExportLogsServiceRequest {
+    ExportLogsServiceRequest {
+        resource_logs: vec![ResourceLogs {
+            resource: None,
+            scope_logs: vec![ScopeLogs {
+                scope: None,
+                log_records: vec![LogRecord {
+                    time_unix_nano: 1_654_000_000_000_000_000,
+                    body: Some(AnyValue {
+                        value: Some(any_value::Value::StringValue("Order placed.".to_owned())),
+                    }),
+                    ..Default::default()
+                }],
+                schema_url: String::new(),
+            }],
+            schema_url: String::new(),
+        }],
+    }
+}
+
+fn traces_request() -> ExportTraceServiceRequest {
+    ExportTraceServiceRequest {
+        resource_spans: vec![ResourceSpans {
+            resource: None,
+            scope_spans: vec![ScopeSpans {
+                scope: None,
+                spans: vec![Span {
+                    trace_id: vec![1; 16],
+                    span_id: vec![2; 8],
+                    name: "GET /orders".to_owned(),
+                    ..Default::default()
+                }],
+                schema_url: String::new(),
+            }],
+            schema_url: String::new(),
+        }],
+    }
+}
+
+#[tokio::test]
+async fn build_requires_a_listener() {
+    let config = toml::from_str::<OpentelemetryConfig>("").unwrap();
+    let (sender, _) = SourceSender::new_test();
+    assert!(config
+        .build(SourceContext::new_test(sender, None))
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn receives_grpc_logs() {
+    assert_source_compliance(&SOCKET_PUSH_SOURCE_TAGS, async {
+        let (outputs, grpc_address, _) = source(EventStatus::Delivered, true).await;
+
+        let mut client = LogsServiceClient::connect(format!("http://{}", grpc_address))
+            .await
+            .unwrap();
+        client.export(logs_request()).await.unwrap();
+
+        let events = collect_ready(outputs.logs).await;
+        assert_eq!(events.len(), 1);
+        let log = events[0].as_log();
+        assert_eq!(log[log_schema().message_key()], "Order placed.".into());
+        assert_eq!(log[log_schema().source_type_key()], "opentelemetry".into());
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn grpc_reports_rejected_logs() {
+    let (_outputs, grpc_address, _) = source(EventStatus::Rejected, true).await;
+
+    let mut client = LogsServiceClient::connect(format!("http://{}", grpc_address))
+        .await
+        .unwrap();
+    let status = client.export(logs_request()).await.unwrap_err();
+    assert_eq!(status.code(), Code::DataLoss);
+}
+
+#[tokio::test]
+async fn receives_http_traces() {
+    assert_source_compliance(&HTTP_PUSH_SOURCE_TAGS, async {
+        let (outputs, _, http_address) = source(EventStatus::Delivered, true).await;
+
+        let response = reqwest::Client::new()
+            .post(&format!("http://{}/v1/traces", http_address))
+            .header("content-type", "application/x-protobuf")
+            .body(traces_request().encode_to_vec())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.headers()["content-type"], "application/x-protobuf");
+        let body = response.bytes().await.unwrap();
+        assert_eq!(
+            ExportTraceServiceResponse::decode(body).unwrap(),
+            ExportTraceServiceResponse::default()
+        );
+
+        let events = collect_ready(outputs.traces).await;
+        assert_eq!(events.len(), 1);
+        let trace = events[0].as_trace();
+        assert_eq!(trace.get("name"), Some(&"GET /orders".into()));
+        assert_eq!(trace.get("trace_id"), Some(&"01".repeat(16).into()));
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn http_rejects_other_content_types() {
+    let (outputs, _, http_address) = source(EventStatus::Delivered, true).await;
+
+    let status = reqwest::Client::new()
+        .post(&format!("http://{}/v1/logs", http_address))
+        .header("content-type", "application/json")
+        .body("{}")
+        .send()
+        .await
+        .unwrap()
+        .status();
+    assert_eq!(status, 415);
+    assert!(collect_ready(outputs.logs).await.is_empty());
+}
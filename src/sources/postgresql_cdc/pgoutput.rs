@@ -0,0 +1,328 @@
+//! Parses the messages of the `pgoutput` logical decoding plugin, in version 1 of its protocol.
+
+use bytes::{Buf, Bytes};
+use snafu::Snafu;
+
+#[derive(Debug, PartialEq, Snafu)]
+pub(super) enum PgOutputError {
+    #[snafu(display("Message is truncated."))]
+    Truncated,
+    #[snafu(display("Unknown tuple data kind `{}`.", kind as char))]
+    UnknownTupleKind { kind: u8 },
+    #[snafu(display("Unknown update tuple kind `{}`.", kind as char))]
+    UnknownUpdateKind { kind: u8 },
+}
+
+/// A column of a relation, as described by a `Relation` message.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct Column {
+    pub(super) name: String,
+    pub(super) type_oid: u32,
+}
+
+/// A table, described before the first change to it in each session, or after its schema changes.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct Relation {
+    pub(super) id: u32,
+    pub(super) schema: String,
+    pub(super) table: String,
+    pub(super) columns: Vec<Column>,
+}
+
+/// The value of a column in a tuple, which is sent in its text representation.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum TupleValue {
+    Null,
+    /// The value of a TOASTed column that the update didn't change, which isn't sent.
+    UnchangedToast,
+    Text(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum LogicalMessage {
+    Begin {
+        final_lsn: u64,
+        /// Microseconds since 2000-01-01.
+        timestamp: i64,
+        xid: u32,
+    },
+    Commit {
+        end_lsn: u64,
+        timestamp: i64,
+    },
+    Relation(Relation),
+    Insert {
+        relation_id: u32,
+        new: Vec<TupleValue>,
+    },
+    Update {
+        relation_id: u32,
+        /// The old values of the replica identity columns, or of all the columns with the
+        /// `FULL` replica identity, when the update changed them.
+        old: Option<Vec<TupleValue>>,
+        new: Vec<TupleValue>,
+    },
+    Delete {
+        relation_id: u32,
+        old: Vec<TupleValue>,
+    },
+    Truncate {
+        relation_ids: Vec<u32>,
+    },
+    /// The origin, type and logical decoding messages, which aren't emitted.
+    Other,
+}
+
+impl LogicalMessage {
+    pub(super) fn parse(mut buf: Bytes) -> Result<Self, PgOutputError> {
+        let buf = &mut buf;
+        let message = match read_u8(buf)? {
+            b'B' => LogicalMessage::Begin {
+                final_lsn: read_u64(buf)?,
+                timestamp: read_i64(buf)?,
+                xid: read_u32(buf)?,
+            },
+            b'C' => {
+                let _flags = read_u8(buf)?;
+                let _commit_lsn = read_u64(buf)?;
+                LogicalMessage::Commit {
+                    end_lsn: read_u64(buf)?,
+                    timestamp: read_i64(buf)?,
+                }
+            }
+            b'R' => {
+                let id = read_u32(buf)?;
+                let schema = read_string(buf)?;
+                let table = read_string(buf)?;
+                let _replica_identity = read_u8(buf)?;
+                let count = read_i16(buf)?;
+                let mut columns = Vec::with_capacity(count.max(0) as usize);
+                for _ in 0..count {
+                    let _flags = read_u8(buf)?;
+                    let name = read_string(buf)?;
+                    let type_oid = read_u32(buf)?;
+                    let _type_modifier = read_i32(buf)?;
+                    columns.push(Column { name, type_oid });
+                }
+                LogicalMessage::Relation(Relation {
+                    id,
+                    schema,
+                    table,
+                    columns,
+                })
+            }
+            b'I' => {
+                let relation_id = read_u32(buf)?;
+                let _new = read_u8(buf)?;
+                LogicalMessage::Insert {
+                    relation_id,
+                    new: read_tuple(buf)?,
+                }
+            }
+            b'U' => {
+                let relation_id = read_u32(buf)?;
+                let (old, new) = match read_u8(buf)? {
+                    b'K' | b'O' => {
+                        let old = read_tuple(buf)?;
+                        let _new = read_u8(buf)?;
+                        (Some(old), read_tuple(buf)?)
+                    }
+                    b'N' => (None, read_tuple(buf)?),
+                    kind => return Err(PgOutputError::UnknownUpdateKind { kind }),
+                };
+                LogicalMessage::Update {
+                    relation_id,
+                    old,
+                    new,
+                }
+            }
+            b'D' => {
+                let relation_id = read_u32(buf)?;
+                let _old = read_u8(buf)?;
+                LogicalMessage::Delete {
+                    relation_id,
+                    old: read_tuple(buf)?,
+                }
+            }
+            b'T' => {
+                let count = read_u32(buf)?;
+                let _options = read_u8(buf)?;
+                let relation_ids = (0..count)
+                    .map(|_| read_u32(buf))
+                    .collect::<Result<_, _>>()?;
+                LogicalMessage::Truncate { relation_ids }
+            }
+            _ => LogicalMessage::Other,
+        };
+        Ok(message)
+    }
+}
+
+fn read_tuple(buf: &mut Bytes) -> Result<Vec<TupleValue>, PgOutputError> {
+    let count = read_i16(buf)?;
+    (0..count)
+        .map(|_| match read_u8(buf)? {
+            b'n' => Ok(TupleValue::Null),
+            b'u' => Ok(TupleValue::UnchangedToast),
+            b't' => {
+                let len = read_i32(buf)?.max(0) as usize;
+                if buf.remaining() < len {
+                    return Err(PgOutputError::Truncated);
+                }
+                let value = buf.split_to(len);
+                Ok(TupleValue::Text(
+                    String::from_utf8_lossy(&value).into_owned(),
+                ))
+            }
+            kind => Err(PgOutputError::UnknownTupleKind { kind }),
+        })
+        .collect()
+}
+
+/// Reads a null terminated string.
+fn read_string(buf: &mut Bytes) -> Result<String, PgOutputError> {
+    let end = buf
+        .iter()
+        .position(|byte| *byte == 0)
+        .ok_or(PgOutputError::Truncated)?;
+    let string = String::from_utf8_lossy(&buf.split_to(end)).into_owned();
+    buf.advance(1);
+    Ok(string)
+}
+
+macro_rules! read_int {
+    ($name:ident, $type:ty, $get:ident) => {
+        fn $name(buf: &mut Bytes) -> Result<$type, PgOutputError> {
+            if buf.remaining() < std::mem::size_of::<$type>() {
+                return Err(PgOutputError::Truncated);
+            }
+            Ok(buf.$get())
+        }
+    };
+}
+
+read_int!(read_u8, u8, get_u8);
+read_int!(read_i16, i16, get_i16);
+read_int!(read_i32, i32, get_i32);
+read_int!(read_u32, u32, get_u32);
+read_int!(read_i64, i64, get_i64);
+read_int!(read_u64, u64, get_u64);
+
+#[cfg(test)]
+pub(super) mod tests {
+    use bytes::{BufMut, BytesMut};
+
+    use super::*;
+
+    pub(in crate::sources::postgresql_cdc) fn tuple(buf: &mut BytesMut, values: &[Option<&str>]) {
+        buf.put_i16(values.len() as i16);
+        for value in values {
+            match value {
+                Some(value) => {
+                    buf.put_u8(b't');
+                    buf.put_i32(value.len() as i32);
+                    buf.put_slice(value.as_bytes());
+                }
+                None => buf.put_u8(b'n'),
+            }
+        }
+    }
+
+    pub(in crate::sources::postgresql_cdc) fn relation() -> Bytes {
+        let mut buf = BytesMut::new();
+        buf.put_u8(b'R');
+        buf.put_u32(16384);
+        buf.put_slice(b"public\0users\0");
+        buf.put_u8(b'd');
+        buf.put_i16(2);
+        buf.put_u8(1);
+        buf.put_slice(b"id\0");
+        buf.put_u32(23);
+        buf.put_i32(-1);
+        buf.put_u8(0);
+        buf.put_slice(b"name\0");
+        buf.put_u32(25);
+        buf.put_i32(-1);
+        buf.freeze()
+    }
+
+    #[test]
+    fn parses_relations() {
+        assert_eq!(
+            LogicalMessage::parse(relation()).unwrap(),
+            LogicalMessage::Relation(Relation {
+                id: 16384,
+                schema: "public".into(),
+                table: "users".into(),
+                columns: vec![
+                    Column {
+                        name: "id".into(),
+                        type_oid: 23
+                    },
+                    Column {
+                        name: "name".into(),
+                        type_oid: 25
+                    },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn parses_updates() {
+        let mut buf = BytesMut::new();
+        buf.put_u8(b'U');
+        buf.put_u32(16384);
+        buf.put_u8(b'K');
+        tuple(&mut buf, &[Some("1"), None]);
+        buf.put_u8(b'N');
+        buf.put_i16(2);
+        buf.put_u8(b't');
+        buf.put_i32(1);
+        buf.put_slice(b"2");
+        buf.put_u8(b'u');
+
+        assert_eq!(
+            LogicalMessage::parse(buf.freeze()).unwrap(),
+            LogicalMessage::Update {
+                relation_id: 16384,
+                old: Some(vec![TupleValue::Text("1".into()), TupleValue::Null]),
+                new: vec![TupleValue::Text("2".into()), TupleValue::UnchangedToast],
+            }
+        );
+    }
+
+    #[test]
+    fn parses_truncates() {
+        let mut buf = BytesMut::new();
+        buf.put_u8(b'T');
+        buf.put_u32(2);
+        buf.put_u8(0);
+        buf.put_u32(1);
+        buf.put_u32(2);
+
+        assert_eq!(
+            LogicalMessage::parse(buf.freeze()).unwrap(),
+            LogicalMessage::Truncate {
+                relation_ids: vec![1, 2]
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_messages() {
+        let mut buf = BytesMut::new();
+        buf.put_u8(b'I');
+        buf.put_u32(16384);
+        buf.put_u8(b'N');
+        buf.put_i16(1);
+        buf.put_u8(b't');
+        buf.put_i32(10);
+        buf.put_slice(b"short");
+
+        assert_eq!(
+            LogicalMessage::parse(buf.freeze()),
+            Err(PgOutputError::Truncated)
+        );
+    }
+}
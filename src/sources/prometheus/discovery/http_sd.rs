@@ -0,0 +1,90 @@
+//! The discovery of the targets listed by an HTTP endpoint, in the format of the HTTP service
+//! discovery of Prometheus.
+
+use hyper::{Body, Request};
+use serde::{Deserialize, Serialize};
+
+use super::Labels;
+use crate::http::{Auth, HttpClient};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct HttpSdConfig {
+    /// The endpoint listing the target groups.
+    pub url: String,
+    #[serde(default = "default_refresh_interval_secs")]
+    pub refresh_interval_secs: u64,
+    pub auth: Option<Auth>,
+}
+
+const fn default_refresh_interval_secs() -> u64 {
+    60
+}
+
+/// A group of targets sharing the same labels.
+#[derive(Debug, Deserialize)]
+struct TargetGroup {
+    targets: Vec<String>,
+    #[serde(default)]
+    labels: Labels,
+}
+
+pub(super) async fn discover(
+    client: &HttpClient,
+    config: &HttpSdConfig,
+) -> crate::Result<Vec<Labels>> {
+    let mut request = Request::get(&config.url)
+        .header(http::header::ACCEPT, "application/json")
+        .body(Body::empty())?;
+    if let Some(auth) = &config.auth {
+        auth.apply(&mut request);
+    }
+
+    let response = client.send(request).await?;
+    let (parts, body) = response.into_parts();
+    if !parts.status.is_success() {
+        return Err(format!("Unexpected status code {}", parts.status).into());
+    }
+    let body = hyper::body::to_bytes(body).await?;
+    let groups: Vec<TargetGroup> = serde_json::from_slice(&body)?;
+    Ok(targets(groups, &config.url))
+}
+
+fn targets(groups: Vec<TargetGroup>, url: &str) -> Vec<Labels> {
+    groups
+        .into_iter()
+        .flat_map(|group| {
+            let labels = group.labels;
+            group.targets.into_iter().map(move |address| {
+                let mut labels = labels.clone();
+                labels.insert("__address__".into(), address);
+                labels.insert("__meta_url".into(), url.into());
+                labels
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_target_groups() {
+        let groups: Vec<TargetGroup> = serde_json::from_str(
+            r#"[
+                {"targets": ["10.0.0.1:9100", "10.0.0.2:9100"], "labels": {"job": "node"}},
+                {"targets": ["10.0.0.3:8080"]}
+            ]"#,
+        )
+        .unwrap();
+
+        let targets = targets(groups, "http://sd.local/targets");
+        assert_eq!(targets.len(), 3);
+        assert_eq!(targets[0]["__address__"], "10.0.0.1:9100");
+        assert_eq!(targets[1]["job"], "node");
+        assert_eq!(targets[2]["__address__"], "10.0.0.3:8080");
+        assert!(!targets[2].contains_key("job"));
+        assert_eq!(targets[2]["__meta_url"], "http://sd.local/targets");
+    }
+}
@@ -0,0 +1,255 @@
+//! The discovery of the ports of the pods of a Kubernetes cluster, with the meta labels of the
+//! `pod` role of the Kubernetes service discovery of Prometheus.
+
+use std::path::PathBuf;
+
+use k8s_openapi::api::core::v1::Pod;
+use kube::{
+    api::{Api, ListParams},
+    config::{self, KubeConfigOptions},
+    Client, Config as ClientConfig,
+};
+use serde::{Deserialize, Serialize};
+
+use super::Labels;
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct KubernetesSdConfig {
+    /// The namespaces of the pods, all of them if empty.
+    #[serde(default)]
+    pub namespaces: Vec<String>,
+    pub label_selector: Option<String>,
+    pub field_selector: Option<String>,
+    pub kube_config_file: Option<PathBuf>,
+    #[serde(default = "default_refresh_interval_secs")]
+    pub refresh_interval_secs: u64,
+}
+
+const fn default_refresh_interval_secs() -> u64 {
+    30
+}
+
+/// Builds the client as the `kubernetes_logs` source does: from the given Kubeconfig, or from
+/// the local one, followed by the in-cluster environment.
+pub(super) async fn client(config: &KubernetesSdConfig) -> crate::Result<Client> {
+    let client_config = match &config.kube_config_file {
+        Some(kc) => {
+            ClientConfig::from_custom_kubeconfig(
+                config::Kubeconfig::read_from(kc)?,
+                &KubeConfigOptions::default(),
+            )
+            .await?
+        }
+        None => ClientConfig::infer().await?,
+    };
+    Ok(Client::try_from(client_config)?)
+}
+
+pub(super) async fn discover(
+    client: &Client,
+    config: &KubernetesSdConfig,
+) -> crate::Result<Vec<Labels>> {
+    let params = ListParams {
+        label_selector: config.label_selector.clone(),
+        field_selector: config.field_selector.clone(),
+        ..Default::default()
+    };
+
+    let mut pods = Vec::new();
+    if config.namespaces.is_empty() {
+        pods.extend(Api::<Pod>::all(client.clone()).list(&params).await?.items);
+    } else {
+        for namespace in &config.namespaces {
+            let api = Api::<Pod>::namespaced(client.clone(), namespace);
+            pods.extend(api.list(&params).await?.items);
+        }
+    }
+
+    Ok(pods.iter().flat_map(pod_targets).collect())
+}
+
+/// The targets of the declared TCP ports of a running pod, or of the pod itself if it declares
+/// no ports.
+fn pod_targets(pod: &Pod) -> Vec<Labels> {
+    let status = match &pod.status {
+        Some(status) => status,
+        None => return Vec::new(),
+    };
+    let ip = match &status.pod_ip {
+        Some(ip) => ip,
+        None => return Vec::new(),
+    };
+    if matches!(status.phase.as_deref(), Some("Succeeded") | Some("Failed")) {
+        return Vec::new();
+    }
+
+    let mut labels = Labels::new();
+    let metadata = &pod.metadata;
+    let mut insert = |name: &str, value: Option<&String>| {
+        if let Some(value) = value {
+            labels.insert(format!("__meta_kubernetes_{}", name), value.clone());
+        }
+    };
+    insert("namespace", metadata.namespace.as_ref());
+    insert("pod_name", metadata.name.as_ref());
+    insert("pod_ip", Some(ip));
+    insert("pod_phase", status.phase.as_ref());
+    insert(
+        "pod_node_name",
+        pod.spec.as_ref().and_then(|spec| spec.node_name.as_ref()),
+    );
+    let ready = status
+        .conditions
+        .iter()
+        .flatten()
+        .any(|condition| condition.type_ == "Ready" && condition.status == "True");
+    labels.insert("__meta_kubernetes_pod_ready".into(), ready.to_string());
+    for (name, value) in metadata.labels.iter().flatten() {
+        labels.insert(
+            format!("__meta_kubernetes_pod_label_{}", sanitize(name)),
+            value.clone(),
+        );
+    }
+    for (name, value) in metadata.annotations.iter().flatten() {
+        labels.insert(
+            format!("__meta_kubernetes_pod_annotation_{}", sanitize(name)),
+            value.clone(),
+        );
+    }
+
+    let mut targets = Vec::new();
+    for container in pod.spec.iter().flat_map(|spec| &spec.containers) {
+        for port in container.ports.iter().flatten() {
+            if !matches!(port.protocol.as_deref(), None | Some("TCP")) {
+                continue;
+            }
+            let mut labels = labels.clone();
+            labels.insert(
+                "__address__".into(),
+                format!("{}:{}", ip, port.container_port),
+            );
+            labels.insert(
+                "__meta_kubernetes_pod_container_name".into(),
+                container.name.clone(),
+            );
+            labels.insert(
+                "__meta_kubernetes_pod_container_port_number".into(),
+                port.container_port.to_string(),
+            );
+            if let Some(name) = &port.name {
+                labels.insert(
+                    "__meta_kubernetes_pod_container_port_name".into(),
+                    name.clone(),
+                );
+            }
+            targets.push(labels);
+        }
+    }
+    if targets.is_empty() {
+        labels.insert("__address__".into(), ip.clone());
+        targets.push(labels);
+    }
+    targets
+}
+
+/// Replaces the characters of label and annotation names that are invalid in label names.
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use k8s_openapi::{
+        api::core::v1::{Container, ContainerPort, PodCondition, PodSpec, PodStatus},
+        apimachinery::pkg::apis::meta::v1::ObjectMeta,
+    };
+
+    use super::*;
+
+    fn pod(ports: Vec<ContainerPort>, phase: &str) -> Pod {
+        Pod {
+            metadata: ObjectMeta {
+                name: Some("web-0".into()),
+                namespace: Some("default".into()),
+                labels: Some([("app.kubernetes.io/name".into(), "web".into())].into()),
+                annotations: Some([("prometheus.io/scrape".into(), "true".into())].into()),
+                ..Default::default()
+            },
+            spec: Some(PodSpec {
+                node_name: Some("node-1".into()),
+                containers: vec![Container {
+                    name: "web".into(),
+                    ports: Some(ports),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }),
+            status: Some(PodStatus {
+                pod_ip: Some("10.0.0.1".into()),
+                phase: Some(phase.into()),
+                conditions: Some(vec![PodCondition {
+                    type_: "Ready".into(),
+                    status: "True".into(),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn port(number: i32, name: Option<&str>, protocol: Option<&str>) -> ContainerPort {
+        ContainerPort {
+            container_port: number,
+            name: name.map(Into::into),
+            protocol: protocol.map(Into::into),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn targets_pod_ports() {
+        let targets = pod_targets(&pod(
+            vec![
+                port(8080, Some("http"), Some("TCP")),
+                port(9100, None, None),
+                port(53, Some("dns"), Some("UDP")),
+            ],
+            "Running",
+        ));
+        assert_eq!(targets.len(), 2);
+
+        let target = &targets[0];
+        assert_eq!(target["__address__"], "10.0.0.1:8080");
+        assert_eq!(target["__meta_kubernetes_namespace"], "default");
+        assert_eq!(target["__meta_kubernetes_pod_name"], "web-0");
+        assert_eq!(target["__meta_kubernetes_pod_node_name"], "node-1");
+        assert_eq!(target["__meta_kubernetes_pod_ready"], "true");
+        assert_eq!(target["__meta_kubernetes_pod_container_name"], "web");
+        assert_eq!(target["__meta_kubernetes_pod_container_port_name"], "http");
+        assert_eq!(
+            target["__meta_kubernetes_pod_label_app_kubernetes_io_name"],
+            "web"
+        );
+        assert_eq!(
+            target["__meta_kubernetes_pod_annotation_prometheus_io_scrape"],
+            "true"
+        );
+        assert_eq!(targets[1]["__address__"], "10.0.0.1:9100");
+        assert!(!targets[1].contains_key("__meta_kubernetes_pod_container_port_name"));
+    }
+
+    #[test]
+    fn targets_pods_without_ports() {
+        let targets = pod_targets(&pod(vec![], "Running"));
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0]["__address__"], "10.0.0.1");
+    }
+
+    #[test]
+    fn ignores_terminated_pods() {
+        assert!(pod_targets(&pod(vec![port(8080, None, None)], "Succeeded")).is_empty());
+    }
+}
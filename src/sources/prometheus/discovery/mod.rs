@@ -0,0 +1,249 @@
+//! The discovery of the targets of the `prometheus_scrape` source, in addition to its static
+//! endpoints.
+//!
+//! The discovered targets are described by labels, as in Prometheus: the labels prefixed with
+//! `__` describe the target itself, such as its `__address__`, and are removed once its URL is
+//! built, while the others are added as tags to its metrics.
+
+mod http_sd;
+mod kubernetes_sd;
+mod relabel;
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use tokio::sync::Mutex;
+
+use self::relabel::Relabeler;
+pub use self::{
+    http_sd::HttpSdConfig,
+    kubernetes_sd::KubernetesSdConfig,
+    relabel::{Labels, RelabelConfig},
+};
+use crate::{
+    config::ProxyConfig, http::HttpClient, internal_events::PrometheusDiscoveryError,
+    tls::TlsSettings,
+};
+
+/// A target to scrape, with the labels to add to its metrics.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Target {
+    pub url: http::Uri,
+    pub labels: Labels,
+}
+
+impl Target {
+    /// A target built from the labels of a discovered target, once relabeled.
+    fn from_labels(labels: Labels) -> crate::Result<Self> {
+        let address = labels
+            .get("__address__")
+            .ok_or("The target has no `__address__` label")?;
+        let scheme = labels
+            .get("__scheme__")
+            .map(String::as_str)
+            .unwrap_or("http");
+        let path = labels
+            .get("__metrics_path__")
+            .map(String::as_str)
+            .unwrap_or("/metrics");
+
+        let mut url = format!("{}://{}{}", scheme, address, path);
+        let mut params = url::form_urlencoded::Serializer::new(String::new());
+        for (name, value) in &labels {
+            if let Some(name) = name.strip_prefix("__param_") {
+                params.append_pair(name, value);
+            }
+        }
+        let params = params.finish();
+        if !params.is_empty() {
+            url.push('?');
+            url.push_str(&params);
+        }
+
+        Ok(Self {
+            url: url.parse()?,
+            labels: labels
+                .into_iter()
+                .filter(|(name, _)| !name.starts_with("__"))
+                .collect(),
+        })
+    }
+}
+
+enum Mechanism {
+    Http {
+        client: HttpClient,
+        config: HttpSdConfig,
+    },
+    Kubernetes {
+        client: kube::Client,
+        config: KubernetesSdConfig,
+    },
+}
+
+impl Mechanism {
+    const fn name(&self) -> &'static str {
+        match self {
+            Self::Http { .. } => "http_sd",
+            Self::Kubernetes { .. } => "kubernetes_sd",
+        }
+    }
+
+    async fn discover(&self) -> crate::Result<Vec<Labels>> {
+        match self {
+            Self::Http { client, config } => http_sd::discover(client, config).await,
+            Self::Kubernetes { client, config } => kubernetes_sd::discover(client, config).await,
+        }
+    }
+}
+
+/// A discovery mechanism, with its last discovered targets.
+struct Discoverer {
+    mechanism: Mechanism,
+    refresh_interval: Duration,
+    refreshed: Option<Instant>,
+    targets: Vec<Labels>,
+}
+
+impl Discoverer {
+    fn new(mechanism: Mechanism, refresh_interval_secs: u64) -> Self {
+        Self {
+            mechanism,
+            refresh_interval: Duration::from_secs(refresh_interval_secs),
+            refreshed: None,
+            targets: Vec::new(),
+        }
+    }
+
+    /// The discovered targets, refreshed once the refresh interval has elapsed. The previous
+    /// targets are kept if they cannot be refreshed.
+    async fn targets(&mut self) -> &[Labels] {
+        if self.refreshed.map_or(true, |refreshed| {
+            refreshed.elapsed() >= self.refresh_interval
+        }) {
+            match self.mechanism.discover().await {
+                Ok(targets) => self.targets = targets,
+                Err(error) => emit!(PrometheusDiscoveryError {
+                    error,
+                    mechanism: self.mechanism.name(),
+                }),
+            }
+            self.refreshed = Some(Instant::now());
+        }
+        &self.targets
+    }
+}
+
+/// The static and discovered targets of a source.
+#[derive(Clone)]
+pub struct Discovery {
+    static_targets: Vec<Target>,
+    discoverers: Vec<Arc<Mutex<Discoverer>>>,
+    relabeler: Relabeler,
+}
+
+impl Discovery {
+    pub async fn new(
+        static_targets: Vec<http::Uri>,
+        kubernetes_sd: Option<&KubernetesSdConfig>,
+        http_sd: Option<&HttpSdConfig>,
+        relabel_configs: &[RelabelConfig],
+        tls: &TlsSettings,
+        proxy: &ProxyConfig,
+    ) -> crate::Result<Self> {
+        let mut discoverers = Vec::new();
+        if let Some(config) = kubernetes_sd {
+            let client = kubernetes_sd::client(config).await?;
+            let mechanism = Mechanism::Kubernetes {
+                client,
+                config: config.clone(),
+            };
+            discoverers.push(Discoverer::new(mechanism, config.refresh_interval_secs));
+        }
+        if let Some(config) = http_sd {
+            let client = HttpClient::new(tls.clone(), proxy)?;
+            let mechanism = Mechanism::Http {
+                client,
+                config: config.clone(),
+            };
+            discoverers.push(Discoverer::new(mechanism, config.refresh_interval_secs));
+        }
+
+        Ok(Self {
+            static_targets: static_targets
+                .into_iter()
+                .map(|url| Target {
+                    url,
+                    labels: Labels::new(),
+                })
+                .collect(),
+            discoverers: discoverers
+                .into_iter()
+                .map(|discoverer| Arc::new(Mutex::new(discoverer)))
+                .collect(),
+            relabeler: Relabeler::new(relabel_configs)?,
+        })
+    }
+
+    /// The targets to scrape: the static ones, followed by the discovered ones kept by the
+    /// relabeling rules.
+    pub async fn targets(&self) -> Vec<Target> {
+        let mut targets = self.static_targets.clone();
+        for discoverer in &self.discoverers {
+            let mut discoverer = discoverer.lock().await;
+            let mechanism = discoverer.mechanism.name();
+            for labels in discoverer.targets().await {
+                let target = match self.relabeler.apply(labels.clone()) {
+                    Some(labels) => Target::from_labels(labels),
+                    None => continue,
+                };
+                match target {
+                    Ok(target) => targets.push(target),
+                    Err(error) => emit!(PrometheusDiscoveryError { error, mechanism }),
+                }
+            }
+        }
+        targets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(pairs: &[(&str, &str)]) -> Labels {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn builds_targets_from_labels() {
+        let target = Target::from_labels(labels(&[("__address__", "10.0.0.1:9100")])).unwrap();
+        assert_eq!(target.url, "http://10.0.0.1:9100/metrics");
+        assert!(target.labels.is_empty());
+
+        let target = Target::from_labels(labels(&[
+            ("__address__", "10.0.0.1:8443"),
+            ("__scheme__", "https"),
+            ("__metrics_path__", "/federate"),
+            ("__param_match[]", "{job=\"node\"}"),
+            ("__meta_kubernetes_pod_name", "web-0"),
+            ("pod", "web-0"),
+        ]))
+        .unwrap();
+        assert_eq!(
+            target.url,
+            "https://10.0.0.1:8443/federate?match%5B%5D=%7Bjob%3D%22node%22%7D"
+        );
+        assert_eq!(target.labels, labels(&[("pod", "web-0")]));
+    }
+
+    #[test]
+    fn rejects_targets_without_address() {
+        assert!(Target::from_labels(labels(&[("pod", "web-0")])).is_err());
+    }
+}
@@ -0,0 +1,300 @@
+//! The relabeling of the discovered targets, following the `relabel_configs` of Prometheus.
+
+use std::collections::BTreeMap;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+
+pub type Labels = BTreeMap<String, String>;
+
+#[derive(Debug, Snafu)]
+pub enum RelabelError {
+    #[snafu(display("Invalid relabeling regex {:?}: {}", regex, source))]
+    InvalidRegex { regex: String, source: regex::Error },
+    #[snafu(display("The `{:?}` relabeling action requires a `target_label`", action))]
+    MissingTargetLabel { action: RelabelAction },
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, PartialEq, Eq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RelabelAction {
+    /// Sets `target_label` to the `replacement`, if `regex` matches the source labels.
+    #[derivative(Default)]
+    Replace,
+    /// Drops the targets whose source labels don't match `regex`.
+    Keep,
+    /// Drops the targets whose source labels match `regex`.
+    Drop,
+    /// Copies the labels whose names match `regex` to the labels named by the `replacement`.
+    Labelmap,
+    /// Removes the labels whose names match `regex`.
+    Labeldrop,
+    /// Removes the labels whose names don't match `regex`.
+    Labelkeep,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RelabelConfig {
+    #[serde(default)]
+    source_labels: Vec<String>,
+    #[serde(default = "default_separator")]
+    separator: String,
+    #[serde(default = "default_regex")]
+    regex: String,
+    #[serde(default)]
+    target_label: Option<String>,
+    #[serde(default = "default_replacement")]
+    replacement: String,
+    #[serde(default)]
+    action: RelabelAction,
+}
+
+fn default_separator() -> String {
+    ";".into()
+}
+
+fn default_regex() -> String {
+    "(.*)".into()
+}
+
+fn default_replacement() -> String {
+    "$1".into()
+}
+
+#[derive(Clone, Debug)]
+struct Rule {
+    config: RelabelConfig,
+    regex: Regex,
+}
+
+/// The compiled relabeling rules, applied in order.
+#[derive(Clone, Debug, Default)]
+pub struct Relabeler {
+    rules: Vec<Rule>,
+}
+
+impl Relabeler {
+    pub fn new(configs: &[RelabelConfig]) -> Result<Self, RelabelError> {
+        let rules = configs
+            .iter()
+            .map(|config| {
+                if config.action == RelabelAction::Replace && config.target_label.is_none() {
+                    return Err(RelabelError::MissingTargetLabel {
+                        action: config.action,
+                    });
+                }
+                // The regexes match the whole values, as in Prometheus.
+                let regex =
+                    Regex::new(&format!("^(?:{})$", config.regex)).context(InvalidRegexSnafu {
+                        regex: config.regex.clone(),
+                    })?;
+                Ok(Rule {
+                    config: config.clone(),
+                    regex,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { rules })
+    }
+
+    /// Applies the rules to the labels of a target, returning `None` if the target is dropped.
+    pub fn apply(&self, mut labels: Labels) -> Option<Labels> {
+        for Rule { config, regex } in &self.rules {
+            let value = config
+                .source_labels
+                .iter()
+                .map(|name| labels.get(name).map(String::as_str).unwrap_or_default())
+                .collect::<Vec<_>>()
+                .join(&config.separator);
+
+            match config.action {
+                RelabelAction::Replace => {
+                    if let Some(captures) = regex.captures(&value) {
+                        let mut target = String::new();
+                        captures.expand(
+                            config.target_label.as_deref().unwrap_or_default(),
+                            &mut target,
+                        );
+                        let mut replacement = String::new();
+                        captures.expand(&config.replacement, &mut replacement);
+                        if target.is_empty() {
+                            continue;
+                        }
+                        if replacement.is_empty() {
+                            labels.remove(&target);
+                        } else {
+                            labels.insert(target, replacement);
+                        }
+                    }
+                }
+                RelabelAction::Keep => {
+                    if !regex.is_match(&value) {
+                        return None;
+                    }
+                }
+                RelabelAction::Drop => {
+                    if regex.is_match(&value) {
+                        return None;
+                    }
+                }
+                RelabelAction::Labelmap => {
+                    let mapped = labels
+                        .iter()
+                        .filter_map(|(name, value)| {
+                            let captures = regex.captures(name)?;
+                            let mut target = String::new();
+                            captures.expand(&config.replacement, &mut target);
+                            Some((target, value.clone()))
+                        })
+                        .collect::<Vec<_>>();
+                    labels.extend(mapped);
+                }
+                RelabelAction::Labeldrop => labels.retain(|name, _| !regex.is_match(name)),
+                RelabelAction::Labelkeep => labels.retain(|name, _| regex.is_match(name)),
+            }
+        }
+        Some(labels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn configs(config: &str) -> Vec<RelabelConfig> {
+        #[derive(Deserialize)]
+        struct Configs {
+            relabel_configs: Vec<RelabelConfig>,
+        }
+        let configs: Configs = toml::from_str(config).unwrap();
+        configs.relabel_configs
+    }
+
+    fn relabeler(config: &str) -> Relabeler {
+        Relabeler::new(&configs(config)).unwrap()
+    }
+
+    fn labels(pairs: &[(&str, &str)]) -> Labels {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn keeps_and_drops_targets() {
+        let relabeler = relabeler(
+            r#"
+            [[relabel_configs]]
+            source_labels = ["__meta_kubernetes_pod_annotation_prometheus_io_scrape"]
+            action = "keep"
+            regex = "true"
+
+            [[relabel_configs]]
+            source_labels = ["__meta_kubernetes_namespace"]
+            action = "drop"
+            regex = "kube-.*"
+            "#,
+        );
+
+        let annotated = |namespace| {
+            labels(&[
+                (
+                    "__meta_kubernetes_pod_annotation_prometheus_io_scrape",
+                    "true",
+                ),
+                ("__meta_kubernetes_namespace", namespace),
+            ])
+        };
+        assert!(relabeler.apply(annotated("default")).is_some());
+        assert!(relabeler.apply(annotated("kube-system")).is_none());
+        assert!(relabeler
+            .apply(labels(&[("__meta_kubernetes_namespace", "default")]))
+            .is_none());
+    }
+
+    #[test]
+    fn replaces_labels() {
+        let relabeler = relabeler(
+            r#"
+            [[relabel_configs]]
+            source_labels = ["__address__", "__meta_kubernetes_pod_annotation_prometheus_io_port"]
+            regex = "([^:]+)(?::\\d+)?;(\\d+)"
+            replacement = "$1:$2"
+            target_label = "__address__"
+
+            [[relabel_configs]]
+            source_labels = ["__meta_kubernetes_pod_name"]
+            target_label = "pod"
+
+            [[relabel_configs]]
+            source_labels = ["missing"]
+            target_label = "removed"
+            "#,
+        );
+
+        let relabeled = relabeler
+            .apply(labels(&[
+                ("__address__", "10.0.0.1:8080"),
+                (
+                    "__meta_kubernetes_pod_annotation_prometheus_io_port",
+                    "9100",
+                ),
+                ("__meta_kubernetes_pod_name", "web-0"),
+                ("removed", "value"),
+            ]))
+            .unwrap();
+        assert_eq!(relabeled["__address__"], "10.0.0.1:9100");
+        assert_eq!(relabeled["pod"], "web-0");
+        assert!(!relabeled.contains_key("removed"));
+    }
+
+    #[test]
+    fn maps_and_drops_label_names() {
+        let relabeler = relabeler(
+            r#"
+            [[relabel_configs]]
+            action = "labelmap"
+            regex = "__meta_kubernetes_pod_label_(.+)"
+
+            [[relabel_configs]]
+            action = "labeldrop"
+            regex = "pod_template_hash"
+            "#,
+        );
+
+        let relabeled = relabeler
+            .apply(labels(&[
+                ("__meta_kubernetes_pod_label_app", "web"),
+                ("__meta_kubernetes_pod_label_pod_template_hash", "abc"),
+            ]))
+            .unwrap();
+        assert_eq!(relabeled["app"], "web");
+        assert!(!relabeled.contains_key("pod_template_hash"));
+        assert_eq!(relabeled["__meta_kubernetes_pod_label_app"], "web");
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        let config = |config: &str| Relabeler::new(&configs(config));
+
+        assert!(config(
+            r#"
+            [[relabel_configs]]
+            source_labels = ["a"]
+            "#
+        )
+        .is_err());
+        assert!(config(
+            r#"
+            [[relabel_configs]]
+            action = "keep"
+            regex = "("
+            "#
+        )
+        .is_err());
+    }
+}
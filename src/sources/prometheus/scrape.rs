@@ -11,11 +11,15 @@ use snafu::{ResultExt, Snafu};
 use tokio_stream::wrappers::IntervalStream;
 use vector_core::ByteSizeOf;
 
-use super::parser;
+use super::{
+    discovery::{Discovery, HttpSdConfig, KubernetesSdConfig, RelabelConfig, Target},
+    parser,
+};
 use crate::{
     config::{
         self, GenerateConfig, Output, ProxyConfig, SourceConfig, SourceContext, SourceDescription,
     },
+    event::Metric,
     http::{Auth, HttpClient},
     internal_events::{
         EndpointBytesReceived, PrometheusEventsReceived, PrometheusHttpError,
@@ -39,13 +43,19 @@ static NOT_FOUND_NO_PATH: &str = "No path is set on the endpoint and we got a 40
 enum ConfigError {
     #[snafu(display("Cannot set both `endpoints` and `hosts`"))]
     BothEndpointsAndHosts,
+    #[snafu(display("One of `endpoints`, `kubernetes_sd` or `http_sd` must be set"))]
+    NoTargets,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
 struct PrometheusScrapeConfig {
     // Deprecated name
-    #[serde(alias = "hosts")]
+    #[serde(alias = "hosts", default)]
     endpoints: Vec<String>,
+    kubernetes_sd: Option<KubernetesSdConfig>,
+    http_sd: Option<HttpSdConfig>,
+    #[serde(default)]
+    relabel_configs: Vec<RelabelConfig>,
     #[serde(default = "default_scrape_interval_secs")]
     scrape_interval_secs: u64,
     instance_tag: Option<String>,
@@ -77,6 +87,9 @@ impl GenerateConfig for PrometheusScrapeConfig {
             instance_tag: Some("instance".to_string()),
             endpoint_tag: Some("endpoint".to_string()),
             honor_labels: false,
+            kubernetes_sd: None,
+            http_sd: None,
+            relabel_configs: Vec::new(),
             query: None,
             tls: None,
             auth: None,
@@ -89,42 +102,27 @@ impl GenerateConfig for PrometheusScrapeConfig {
 #[typetag::serde(name = "prometheus_scrape")]
 impl SourceConfig for PrometheusScrapeConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<sources::Source> {
+        if self.endpoints.is_empty() && self.kubernetes_sd.is_none() && self.http_sd.is_none() {
+            return Err(ConfigError::NoTargets.into());
+        }
         let urls = self
             .endpoints
             .iter()
             .map(|s| s.parse::<http::Uri>().context(sources::UriParseSnafu))
-            .map(|r| {
-                r.map(|uri| {
-                    let mut serializer = url::form_urlencoded::Serializer::new(String::new());
-                    if let Some(query) = uri.query() {
-                        serializer.extend_pairs(url::form_urlencoded::parse(query.as_bytes()));
-                    };
-                    if let Some(query) = &self.query {
-                        for (k, l) in query {
-                            for v in l {
-                                serializer.append_pair(k, v);
-                            }
-                        }
-                    };
-                    let mut builder = http::Uri::builder();
-                    if let Some(scheme) = uri.scheme() {
-                        builder = builder.scheme(scheme.clone());
-                    };
-                    if let Some(authority) = uri.authority() {
-                        builder = builder.authority(authority.clone());
-                    };
-                    builder = builder.path_and_query(match serializer.finish() {
-                        query if !query.is_empty() => format!("{}?{}", uri.path(), query),
-                        _ => uri.path().to_string(),
-                    });
-                    builder.build().expect("error building URI")
-                })
-            })
             .collect::<Result<Vec<http::Uri>, sources::BuildError>>()?;
         let tls = TlsSettings::from_options(&self.tls)?;
+        let discovery = Discovery::new(
+            urls,
+            self.kubernetes_sd.as_ref(),
+            self.http_sd.as_ref(),
+            &self.relabel_configs,
+            &tls,
+            &cx.proxy,
+        )
+        .await?;
         Ok(prometheus(
             self.clone(),
-            urls,
+            discovery,
             tls,
             cx.proxy.clone(),
             cx.shutdown,
@@ -152,8 +150,12 @@ impl SourceConfig for PrometheusScrapeConfig {
 struct PrometheusCompatConfig {
     // Clone of PrometheusScrapeConfig to work around serde bug
     // https://github.com/serde-rs/serde/issues/1504
-    #[serde(alias = "hosts")]
+    #[serde(alias = "hosts", default)]
     endpoints: Vec<String>,
+    kubernetes_sd: Option<KubernetesSdConfig>,
+    http_sd: Option<HttpSdConfig>,
+    #[serde(default)]
+    relabel_configs: Vec<RelabelConfig>,
     instance_tag: Option<String>,
     endpoint_tag: Option<String>,
     #[serde(default = "crate::serde::default_false")]
@@ -176,6 +178,9 @@ impl SourceConfig for PrometheusCompatConfig {
             instance_tag: self.instance_tag.clone(),
             endpoint_tag: self.endpoint_tag.clone(),
             honor_labels: self.honor_labels,
+            kubernetes_sd: self.kubernetes_sd.clone(),
+            http_sd: self.http_sd.clone(),
+            relabel_configs: self.relabel_configs.clone(),
             query: self.query.clone(),
             scrape_interval_secs: self.scrape_interval_secs,
             tls: self.tls.clone(),
@@ -217,9 +222,51 @@ struct EndpointInfo {
     honor_label: bool,
 }
 
+/// Appends the configured query parameters to the query of the URL of a target.
+fn with_query(uri: &http::Uri, query: &Option<HashMap<String, Vec<String>>>) -> http::Uri {
+    let mut serializer = url::form_urlencoded::Serializer::new(String::new());
+    if let Some(query) = uri.query() {
+        serializer.extend_pairs(url::form_urlencoded::parse(query.as_bytes()));
+    };
+    if let Some(query) = query {
+        for (k, l) in query {
+            for v in l {
+                serializer.append_pair(k, v);
+            }
+        }
+    };
+    let mut builder = http::Uri::builder();
+    if let Some(scheme) = uri.scheme() {
+        builder = builder.scheme(scheme.clone());
+    };
+    if let Some(authority) = uri.authority() {
+        builder = builder.authority(authority.clone());
+    };
+    builder = builder.path_and_query(match serializer.finish() {
+        query if !query.is_empty() => format!("{}?{}", uri.path(), query),
+        _ => uri.path().to_string(),
+    });
+    builder.build().expect("error building URI")
+}
+
+/// Sets a tag of a scraped metric, keeping its scraped value as `exported_<tag>` unless the
+/// scraped labels are honored.
+fn insert_tag(metric: &mut Metric, tag: &str, value: &str, honor_label: bool) {
+    match (honor_label, metric.tag_value(tag)) {
+        (false, Some(old_value)) => {
+            metric.insert_tag(format!("exported_{}", tag), old_value);
+            metric.insert_tag(tag.to_string(), value.to_string());
+        }
+        (true, Some(_)) => {}
+        (_, None) => {
+            metric.insert_tag(tag.to_string(), value.to_string());
+        }
+    }
+}
+
 async fn prometheus(
     config: PrometheusScrapeConfig,
-    urls: Vec<http::Uri>,
+    discovery: Discovery,
     tls: TlsSettings,
     proxy: ProxyConfig,
     shutdown: ShutdownSignal,
@@ -229,9 +276,13 @@ async fn prometheus(
         config.scrape_interval_secs,
     )))
     .take_until(shutdown)
-    .map(move |_| stream::iter(urls.clone()))
+    .then(move |_| {
+        let discovery = discovery.clone();
+        async move { stream::iter(discovery.targets().await) }
+    })
     .flatten()
-    .map(move |url| {
+    .map(move |Target { url, labels }| {
+        let url = with_query(&url, &config.query);
         let client = HttpClient::new(tls.clone(), &proxy).expect("Building HTTP client failed");
         let endpoint = url.to_string();
 
@@ -259,6 +310,7 @@ async fn prometheus(
                 honor_label: config.honor_labels,
             }
         });
+        let honor_labels = config.honor_labels;
         let endpoint_info = config.endpoint_tag.as_ref().map(|tag| EndpointInfo {
             tag: tag.to_string(),
             endpoint: url.to_string(),
@@ -283,6 +335,7 @@ async fn prometheus(
             .filter_map(move |response| {
                 let instance_info = instance_info.clone();
                 let endpoint_info = endpoint_info.clone();
+                let labels = labels.clone();
 
                 ready(match response {
                     Ok((header, body)) if header.status == hyper::StatusCode::OK => {
@@ -308,19 +361,7 @@ async fn prometheus(
                                         honor_label,
                                     }) = &instance_info
                                     {
-                                        match (honor_label, metric.tag_value(tag)) {
-                                            (false, Some(old_instance)) => {
-                                                metric.insert_tag(
-                                                    format!("exported_{}", tag),
-                                                    old_instance,
-                                                );
-                                                metric.insert_tag(tag.clone(), instance.clone());
-                                            }
-                                            (true, Some(_)) => {}
-                                            (_, None) => {
-                                                metric.insert_tag(tag.clone(), instance.clone());
-                                            }
-                                        }
+                                        insert_tag(metric, tag, instance, *honor_label);
                                     }
                                     if let Some(EndpointInfo {
                                         tag,
@@ -328,19 +369,10 @@ async fn prometheus(
                                         honor_label,
                                     }) = &endpoint_info
                                     {
-                                        match (honor_label, metric.tag_value(tag)) {
-                                            (false, Some(old_endpoint)) => {
-                                                metric.insert_tag(
-                                                    format!("exported_{}", tag),
-                                                    old_endpoint,
-                                                );
-                                                metric.insert_tag(tag.clone(), endpoint.clone());
-                                            }
-                                            (true, Some(_)) => {}
-                                            (_, None) => {
-                                                metric.insert_tag(tag.clone(), endpoint.clone());
-                                            }
-                                        }
+                                        insert_tag(metric, tag, endpoint, *honor_label);
+                                    }
+                                    for (name, value) in &labels {
+                                        insert_tag(metric, name, value, honor_labels);
                                     }
                                     event
                                 }))
@@ -449,6 +481,9 @@ mod test {
             instance_tag: Some("instance".to_string()),
             endpoint_tag: Some("endpoint".to_string()),
             honor_labels: true,
+            kubernetes_sd: None,
+            http_sd: None,
+            relabel_configs: Vec::new(),
             query: None,
             auth: None,
             tls: None,
@@ -481,6 +516,9 @@ mod test {
             instance_tag: Some("instance".to_string()),
             endpoint_tag: Some("endpoint".to_string()),
             honor_labels: true,
+            kubernetes_sd: None,
+            http_sd: None,
+            relabel_configs: Vec::new(),
             query: None,
             auth: None,
             tls: None,
@@ -531,6 +569,9 @@ mod test {
             instance_tag: Some("instance".to_string()),
             endpoint_tag: Some("endpoint".to_string()),
             honor_labels: false,
+            kubernetes_sd: None,
+            http_sd: None,
+            relabel_configs: Vec::new(),
             query: None,
             auth: None,
             tls: None,
@@ -594,6 +635,9 @@ mod test {
             instance_tag: Some("instance".to_string()),
             endpoint_tag: Some("endpoint".to_string()),
             honor_labels: false,
+            kubernetes_sd: None,
+            http_sd: None,
+            relabel_configs: Vec::new(),
             query: Some(HashMap::from([
                 ("key1".to_string(), vec!["val2".to_string()]),
                 (
@@ -644,6 +688,78 @@ mod test {
         }
     }
 
+    #[tokio::test]
+    async fn test_prometheus_http_sd() {
+        let in_addr = next_addr();
+        let sd_addr = next_addr();
+
+        let dummy_endpoint = warp::path!("metrics").map(|| {
+            r#"
+                promhttp_metric_handler_requests_total{code="200"} 100 1612411516789
+            "#
+        });
+        tokio::spawn(warp::serve(dummy_endpoint).run(in_addr));
+
+        let targets = format!(
+            r#"[
+                {{"targets": ["{}"], "labels": {{"job": "node", "__meta_team": "web"}}}},
+                {{"targets": ["{}"], "labels": {{"job": "dropped"}}}}
+            ]"#,
+            in_addr, sd_addr
+        );
+        let sd_endpoint = warp::path!("targets").map(move || targets.clone());
+        tokio::spawn(warp::serve(sd_endpoint).run(sd_addr));
+
+        let config: PrometheusScrapeConfig = toml::from_str(&format!(
+            r#"
+            scrape_interval_secs = 1
+            instance_tag = "instance"
+
+            [http_sd]
+            url = "http://{}/targets"
+
+            [[relabel_configs]]
+            source_labels = ["job"]
+            action = "keep"
+            regex = "node"
+
+            [[relabel_configs]]
+            source_labels = ["__meta_team"]
+            target_label = "team"
+            "#,
+            sd_addr
+        ))
+        .unwrap();
+
+        let events = run_and_assert_source_compliance(
+            config,
+            Duration::from_secs(1),
+            &HTTP_PULL_SOURCE_TAGS,
+        )
+        .await;
+        assert!(!events.is_empty());
+
+        for event in events {
+            let metric = event.into_metric();
+            assert_eq!(metric.tag_value("job"), Some(String::from("node")));
+            assert_eq!(metric.tag_value("team"), Some(String::from("web")));
+            assert_eq!(metric.tag_value("__meta_team"), None);
+            assert_eq!(
+                metric.tag_value("instance"),
+                Some(format!("{}:{}", in_addr.ip(), in_addr.port()))
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prometheus_requires_targets() {
+        let config: PrometheusScrapeConfig = toml::from_str("scrape_interval_secs = 1").unwrap();
+        assert!(config
+            .build(SourceContext::new_test(SourceSender::new_test().0, None))
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_prometheus_routing() {
         let in_addr = next_addr();
@@ -698,6 +814,9 @@ mod test {
                 instance_tag: None,
                 endpoint_tag: None,
                 honor_labels: false,
+                kubernetes_sd: None,
+                http_sd: None,
+                relabel_configs: Vec::new(),
                 query: None,
                 scrape_interval_secs: 1,
                 tls: None,
@@ -787,6 +906,9 @@ mod integration_tests {
             instance_tag: Some("instance".to_string()),
             endpoint_tag: Some("endpoint".to_string()),
             honor_labels: false,
+            kubernetes_sd: None,
+            http_sd: None,
+            relabel_configs: Vec::new(),
             query: None,
             auth: None,
             tls: None,
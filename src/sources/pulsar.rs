@@ -0,0 +1,415 @@
+use std::collections::BTreeMap;
+
+use bytes::Bytes;
+use chrono::{TimeZone, Utc};
+use codecs::decoding::{DeserializerConfig, FramingConfig, StreamDecodingError};
+use futures::StreamExt;
+use pulsar::{
+    consumer::{DeadLetterPolicy, Message},
+    message::proto::MessageIdData,
+    Consumer, Error as PulsarError, Pulsar, SubType, TokioExecutor,
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use tokio_util::codec::FramedRead;
+use vector_core::ByteSizeOf;
+
+use super::util::finalizer::UnorderedFinalizer;
+use crate::{
+    codecs::{Decoder, DecodingConfig},
+    config::{
+        log_schema, AcknowledgementsConfig, GenerateConfig, Output, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::{BatchNotifier, BatchStatus, Event, Value},
+    internal_events::{
+        BytesReceived, PulsarAcknowledgementError, PulsarEventsReceived, PulsarReadError,
+        StreamClosedError,
+    },
+    pulsar::PulsarAuthConfig,
+    serde::{bool_or_struct, default_decoding, default_framing_message_based},
+    shutdown::ShutdownSignal,
+    SourceSender,
+};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Could not create Pulsar consumer: {}", source))]
+    CreateConsumer { source: PulsarError },
+}
+
+#[derive(Clone, Debug, Derivative, Deserialize, Serialize)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct PulsarSourceConfig {
+    endpoint: String,
+    topics: Vec<String>,
+    #[serde(default = "default_subscription_name")]
+    #[derivative(Default(value = "default_subscription_name()"))]
+    subscription_name: String,
+    #[serde(default)]
+    subscription_type: SubscriptionType,
+    consumer_name: Option<String>,
+    /// The number of messages the consumer asks the broker for at a time.
+    batch_size: Option<u32>,
+    dead_letter_queue_policy: Option<DeadLetterQueuePolicy>,
+    auth: Option<PulsarAuthConfig>,
+    #[serde(default = "default_framing_message_based")]
+    #[derivative(Default(value = "default_framing_message_based()"))]
+    framing: FramingConfig,
+    #[serde(default = "default_decoding")]
+    #[derivative(Default(value = "default_decoding()"))]
+    decoding: DeserializerConfig,
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+enum SubscriptionType {
+    Exclusive,
+    #[derivative(Default)]
+    Shared,
+    Failover,
+    KeyShared,
+}
+
+impl From<SubscriptionType> for SubType {
+    fn from(subscription_type: SubscriptionType) -> Self {
+        match subscription_type {
+            SubscriptionType::Exclusive => SubType::Exclusive,
+            SubscriptionType::Shared => SubType::Shared,
+            SubscriptionType::Failover => SubType::Failover,
+            SubscriptionType::KeyShared => SubType::KeyShared,
+        }
+    }
+}
+
+/// Sends the messages redelivered too many times to another topic, rather than redelivering them
+/// again.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct DeadLetterQueuePolicy {
+    max_redeliver_count: usize,
+    dead_letter_topic: String,
+}
+
+fn default_subscription_name() -> String {
+    "vector".into()
+}
+
+inventory::submit! {
+    SourceDescription::new::<PulsarSourceConfig>("pulsar")
+}
+
+impl GenerateConfig for PulsarSourceConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"
+            endpoint = "pulsar://127.0.0.1:6650"
+            topics = ["topic-1234"]
+            "#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "pulsar")]
+impl SourceConfig for PulsarSourceConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let consumer = self.create_consumer().await?;
+        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build();
+        let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
+
+        Ok(Box::pin(pulsar_source(
+            consumer,
+            decoder,
+            cx.shutdown,
+            cx.out,
+            acknowledgements,
+        )))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(self.decoding.output_type())]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "pulsar"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+impl PulsarSourceConfig {
+    async fn create_consumer(&self) -> crate::Result<Consumer<String, TokioExecutor>> {
+        let mut builder = Pulsar::builder(&self.endpoint, TokioExecutor);
+        if let Some(auth) = &self.auth {
+            builder = auth.apply(builder)?;
+        }
+        let pulsar = builder.build().await.context(CreateConsumerSnafu)?;
+
+        let mut builder = pulsar
+            .consumer()
+            .with_topics(&self.topics)
+            .with_subscription(&self.subscription_name)
+            .with_subscription_type(self.subscription_type.into());
+        if let Some(consumer_name) = &self.consumer_name {
+            builder = builder.with_consumer_name(consumer_name);
+        }
+        if let Some(batch_size) = self.batch_size {
+            builder = builder.with_batch_size(batch_size);
+        }
+        if let Some(policy) = &self.dead_letter_queue_policy {
+            builder = builder.with_dead_letter_policy(DeadLetterPolicy {
+                max_redeliver_count: policy.max_redeliver_count,
+                dead_letter_topic: policy.dead_letter_topic.clone(),
+            });
+        }
+
+        let consumer = builder.build().await.context(CreateConsumerSnafu)?;
+        Ok(consumer)
+    }
+}
+
+type Finalizer = UnorderedFinalizer<FinalizerEntry>;
+
+/// Identifies the message the events were decoded from, to acknowledge it once they're delivered.
+#[derive(Debug)]
+struct FinalizerEntry {
+    topic: String,
+    message_id: MessageIdData,
+}
+
+async fn pulsar_source(
+    mut consumer: Consumer<String, TokioExecutor>,
+    decoder: Decoder,
+    mut shutdown: ShutdownSignal,
+    mut out: SourceSender,
+    acknowledgements: bool,
+) -> Result<(), ()> {
+    let (finalizer, mut ack_stream) = Finalizer::maybe_new(acknowledgements, shutdown.clone());
+
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            entry = ack_stream.next() => if let Some((status, entry)) = entry {
+                handle_ack(&mut consumer, status, entry).await;
+            },
+            message = consumer.next() => match message {
+                None => break,
+                Some(Err(error)) => emit!(PulsarReadError { error }),
+                Some(Ok(message)) => {
+                    emit!(BytesReceived {
+                        byte_size: message.payload.data.len(),
+                        protocol: "tcp",
+                    });
+
+                    let events = parse_message(&message, decoder.clone()).await;
+                    let count = events.len();
+                    let entry = FinalizerEntry {
+                        topic: message.topic.clone(),
+                        message_id: message.message_id().clone(),
+                    };
+
+                    match &finalizer {
+                        Some(finalizer) => {
+                            let (batch, receiver) = BatchNotifier::new_with_receiver();
+                            let events = events
+                                .into_iter()
+                                .map(|event| event.with_batch_notifier(&batch));
+                            if let Err(error) = out.send_batch(events).await {
+                                emit!(StreamClosedError { error, count });
+                                return Err(());
+                            }
+                            finalizer.add(entry, receiver);
+                        }
+                        None => {
+                            if let Err(error) = out.send_batch(events).await {
+                                emit!(StreamClosedError { error, count });
+                                return Err(());
+                            }
+                            handle_ack(&mut consumer, BatchStatus::Delivered, entry).await;
+                        }
+                    }
+                }
+            },
+        }
+    }
+
+    Ok(())
+}
+
+/// Acknowledges the message once its events are delivered, or asks for it to be redelivered
+/// otherwise, which sends it to the dead letter topic once it's been redelivered too many times.
+async fn handle_ack(
+    consumer: &mut Consumer<String, TokioExecutor>,
+    status: BatchStatus,
+    entry: FinalizerEntry,
+) {
+    match status {
+        BatchStatus::Delivered => {
+            if let Err(error) = consumer.ack_with_id(&entry.topic, entry.message_id).await {
+                emit!(PulsarAcknowledgementError {
+                    error,
+                    negative: false,
+                });
+            }
+        }
+        BatchStatus::Errored | BatchStatus::Rejected => {
+            if let Err(error) = consumer.nack_with_id(&entry.topic, entry.message_id).await {
+                emit!(PulsarAcknowledgementError {
+                    error,
+                    negative: true,
+                });
+            }
+        }
+    }
+}
+
+async fn parse_message(message: &Message<String>, decoder: Decoder) -> Vec<Event> {
+    let metadata = message.metadata();
+    let timestamp = Utc
+        .timestamp_millis_opt(metadata.publish_time as i64)
+        .latest()
+        .unwrap_or_else(Utc::now);
+    let key = message.key().map(Value::from).unwrap_or(Value::Null);
+    let properties = metadata
+        .properties
+        .iter()
+        .map(|property| (property.key.clone(), Value::from(property.value.clone())))
+        .collect::<BTreeMap<_, _>>();
+
+    let payload = Bytes::copy_from_slice(&message.payload.data);
+    let mut stream = FramedRead::new(payload.as_ref(), decoder);
+    let mut output = Vec::new();
+    while let Some(result) = stream.next().await {
+        match result {
+            Ok((events, _byte_size)) => {
+                emit!(PulsarEventsReceived {
+                    count: events.len(),
+                    byte_size: events.size_of(),
+                    topic: &message.topic,
+                });
+                output.extend(events.into_iter().map(|mut event| {
+                    if let Event::Log(ref mut log) = event {
+                        log.insert(log_schema().source_type_key(), Bytes::from("pulsar"));
+                        log.insert(log_schema().timestamp_key(), timestamp);
+                        log.insert("topic", message.topic.clone());
+                        log.insert("message_key", key.clone());
+                        log.insert("properties", properties.clone());
+                        log.insert("producer_name", metadata.producer_name.clone());
+                    }
+                    event
+                }));
+            }
+            Err(error) => {
+                // Error is logged by `crate::codecs`, no further handling is needed here.
+                if !error.can_continue() {
+                    break;
+                }
+            }
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<PulsarSourceConfig>();
+    }
+
+    #[test]
+    fn parses_subscription_types() {
+        let config: PulsarSourceConfig = toml::from_str(
+            r#"
+            endpoint = "pulsar://127.0.0.1:6650"
+            topics = ["logs"]
+            subscription_type = "key_shared"
+            dead_letter_queue_policy.max_redeliver_count = 3
+            dead_letter_queue_policy.dead_letter_topic = "logs-dlq"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.subscription_type, SubscriptionType::KeyShared);
+        assert_eq!(config.subscription_name, "vector");
+        assert!(matches!(
+            SubType::from(config.subscription_type),
+            SubType::KeyShared
+        ));
+    }
+}
+
+#[cfg(feature = "pulsar-integration-tests")]
+#[cfg(test)]
+mod integration_tests {
+    use pulsar::producer;
+
+    use super::*;
+    use crate::test_util::{
+        collect_n,
+        components::{assert_source_compliance, SOURCE_TAGS},
+        random_string,
+    };
+
+    fn pulsar_address() -> String {
+        std::env::var("PULSAR_ADDRESS").unwrap_or_else(|_| "pulsar://127.0.0.1:6650".into())
+    }
+
+    #[tokio::test]
+    async fn consumes_messages_with_acknowledgements() {
+        let topic = format!("test-{}", random_string(10));
+        let config = PulsarSourceConfig {
+            endpoint: pulsar_address(),
+            topics: vec![topic.clone()],
+            ..Default::default()
+        };
+
+        let pulsar = Pulsar::<TokioExecutor>::builder(&config.endpoint, TokioExecutor)
+            .build()
+            .await
+            .unwrap();
+        let consumer = config.create_consumer().await.unwrap();
+        let mut producer = pulsar.producer().with_topic(&topic).build().await.unwrap();
+        producer
+            .send(producer::Message {
+                payload: b"hello".to_vec(),
+                partition_key: Some("key".into()),
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+            .await
+            .unwrap();
+
+        let events = assert_source_compliance(&SOURCE_TAGS, async move {
+            let (tx, rx) = SourceSender::new_test();
+            let decoder = DecodingConfig::new(config.framing, config.decoding).build();
+            tokio::spawn(pulsar_source(
+                consumer,
+                decoder,
+                ShutdownSignal::noop(),
+                tx,
+                true,
+            ));
+            collect_n(rx, 1).await
+        })
+        .await;
+
+        let log = events[0].as_log();
+        assert_eq!(log[log_schema().message_key()], "hello".into());
+        assert_eq!(
+            log["topic"],
+            format!("persistent://public/default/{}", topic).into()
+        );
+        assert_eq!(log["message_key"], "key".into());
+    }
+}
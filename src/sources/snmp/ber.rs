@@ -0,0 +1,539 @@
+//! The subset of the Basic Encoding Rules of ASN.1 used by SNMP messages, along with the types
+//! of their variables and PDUs.
+
+use std::{fmt, str::FromStr};
+
+use snafu::Snafu;
+
+pub(super) const TAG_INTEGER: u8 = 0x02;
+pub(super) const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_NULL: u8 = 0x05;
+const TAG_OBJECT_IDENTIFIER: u8 = 0x06;
+pub(super) const TAG_SEQUENCE: u8 = 0x30;
+const TAG_IP_ADDRESS: u8 = 0x40;
+const TAG_COUNTER32: u8 = 0x41;
+const TAG_GAUGE32: u8 = 0x42;
+const TAG_TIMETICKS: u8 = 0x43;
+const TAG_OPAQUE: u8 = 0x44;
+const TAG_COUNTER64: u8 = 0x46;
+const TAG_NO_SUCH_OBJECT: u8 = 0x80;
+const TAG_NO_SUCH_INSTANCE: u8 = 0x81;
+const TAG_END_OF_MIB_VIEW: u8 = 0x82;
+
+#[derive(Debug, PartialEq, Snafu)]
+pub enum BerError {
+    #[snafu(display("Message is truncated"))]
+    Truncated,
+    #[snafu(display("Expected tag {:#04x}, found {:#04x}", expected, found))]
+    UnexpectedTag { expected: u8, found: u8 },
+    #[snafu(display("Unsupported tag {:#04x}", tag))]
+    UnsupportedTag { tag: u8 },
+    #[snafu(display("Invalid length"))]
+    InvalidLength,
+    #[snafu(display("Integer overflows 64 bits"))]
+    IntegerOverflow,
+    #[snafu(display("Invalid object identifier"))]
+    InvalidOid,
+}
+
+/// An object identifier.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Oid(pub(super) Vec<u32>);
+
+impl Oid {
+    pub(super) fn starts_with(&self, prefix: &Oid) -> bool {
+        self.0.starts_with(&prefix.0)
+    }
+}
+
+impl fmt::Display for Oid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, id) in self.0.iter().enumerate() {
+            if index > 0 {
+                f.write_str(".")?;
+            }
+            write!(f, "{}", id)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Oid {
+    type Err = BerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let ids = s
+            .trim_start_matches('.')
+            .split('.')
+            .map(|id| id.parse().map_err(|_| BerError::InvalidOid))
+            .collect::<Result<Vec<_>, _>>()?;
+        if ids.len() < 2 {
+            return Err(BerError::InvalidOid);
+        }
+        Ok(Oid(ids))
+    }
+}
+
+/// The value of a variable.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum SnmpValue {
+    Integer(i64),
+    OctetString(Vec<u8>),
+    Null,
+    ObjectIdentifier(Oid),
+    IpAddress([u8; 4]),
+    Counter32(u32),
+    Gauge32(u32),
+    TimeTicks(u32),
+    Opaque(Vec<u8>),
+    Counter64(u64),
+    NoSuchObject,
+    NoSuchInstance,
+    EndOfMibView,
+}
+
+impl SnmpValue {
+    /// Whether the value is one of the exceptions returned in place of missing variables.
+    pub(super) const fn is_exception(&self) -> bool {
+        matches!(
+            self,
+            SnmpValue::NoSuchObject | SnmpValue::NoSuchInstance | SnmpValue::EndOfMibView
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct VarBind {
+    pub(super) oid: Oid,
+    pub(super) value: SnmpValue,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum PduType {
+    GetRequest = 0xa0,
+    GetNextRequest = 0xa1,
+    Response = 0xa2,
+    SetRequest = 0xa3,
+    GetBulkRequest = 0xa5,
+    InformRequest = 0xa6,
+    Trap = 0xa7,
+    Report = 0xa8,
+}
+
+impl PduType {
+    const fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            0xa0 => Some(PduType::GetRequest),
+            0xa1 => Some(PduType::GetNextRequest),
+            0xa2 => Some(PduType::Response),
+            0xa3 => Some(PduType::SetRequest),
+            0xa5 => Some(PduType::GetBulkRequest),
+            0xa6 => Some(PduType::InformRequest),
+            0xa7 => Some(PduType::Trap),
+            0xa8 => Some(PduType::Report),
+            _ => None,
+        }
+    }
+}
+
+/// A PDU. The error status and index hold the non-repeaters and max-repetitions of bulk
+/// requests.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct Pdu {
+    pub(super) pdu_type: PduType,
+    pub(super) request_id: i32,
+    pub(super) error_status: i64,
+    pub(super) error_index: i64,
+    pub(super) varbinds: Vec<VarBind>,
+}
+
+impl Pdu {
+    pub(super) fn decode(decoder: &mut Decoder<'_>) -> Result<Self, BerError> {
+        let (tag, content) = decoder.tlv()?;
+        let pdu_type = PduType::from_tag(tag).ok_or(BerError::UnsupportedTag { tag })?;
+        let mut content = Decoder::new(content);
+        let request_id = content.integer()? as i32;
+        let error_status = content.integer()?;
+        let error_index = content.integer()?;
+
+        let mut list = content.sequence()?;
+        let mut varbinds = Vec::new();
+        while !list.is_empty() {
+            let mut varbind = list.sequence()?;
+            let oid = varbind.oid()?;
+            let value = varbind.value()?;
+            varbinds.push(VarBind { oid, value });
+        }
+
+        Ok(Pdu {
+            pdu_type,
+            request_id,
+            error_status,
+            error_index,
+            varbinds,
+        })
+    }
+
+    pub(super) fn encode(&self, out: &mut Vec<u8>) {
+        let mut list = Vec::new();
+        for varbind in &self.varbinds {
+            let mut content = Vec::new();
+            encode_oid(&varbind.oid, &mut content);
+            encode_value(&varbind.value, &mut content);
+            encode_tlv(TAG_SEQUENCE, &content, &mut list);
+        }
+
+        let mut content = Vec::new();
+        encode_integer(self.request_id.into(), &mut content);
+        encode_integer(self.error_status, &mut content);
+        encode_integer(self.error_index, &mut content);
+        encode_tlv(TAG_SEQUENCE, &list, &mut content);
+        encode_tlv(self.pdu_type as u8, &content, out);
+    }
+}
+
+/// Reads the elements of an encoding.
+pub(super) struct Decoder<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    pub(super) const fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub(super) const fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// The elements that weren't read yet.
+    pub(super) const fn remaining(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Reads an element, returning its tag and content.
+    pub(super) fn tlv(&mut self) -> Result<(u8, &'a [u8]), BerError> {
+        let (&tag, rest) = self.data.split_first().ok_or(BerError::Truncated)?;
+        let (&first, mut rest) = rest.split_first().ok_or(BerError::Truncated)?;
+        let length = if first & 0x80 == 0 {
+            first as usize
+        } else {
+            let count = (first & 0x7f) as usize;
+            if count == 0 || count > 4 || rest.len() < count {
+                return Err(BerError::InvalidLength);
+            }
+            let (bytes, remaining) = rest.split_at(count);
+            rest = remaining;
+            bytes
+                .iter()
+                .fold(0, |length, byte| (length << 8) | *byte as usize)
+        };
+        if rest.len() < length {
+            return Err(BerError::Truncated);
+        }
+        let (content, rest) = rest.split_at(length);
+        self.data = rest;
+        Ok((tag, content))
+    }
+
+    pub(super) fn expect(&mut self, expected: u8) -> Result<&'a [u8], BerError> {
+        match self.tlv()? {
+            (tag, content) if tag == expected => Ok(content),
+            (found, _) => Err(BerError::UnexpectedTag { expected, found }),
+        }
+    }
+
+    pub(super) fn sequence(&mut self) -> Result<Decoder<'a>, BerError> {
+        self.expect(TAG_SEQUENCE).map(Decoder::new)
+    }
+
+    pub(super) fn integer(&mut self) -> Result<i64, BerError> {
+        decode_integer(self.expect(TAG_INTEGER)?)
+    }
+
+    pub(super) fn octet_string(&mut self) -> Result<&'a [u8], BerError> {
+        self.expect(TAG_OCTET_STRING)
+    }
+
+    fn oid(&mut self) -> Result<Oid, BerError> {
+        decode_oid(self.expect(TAG_OBJECT_IDENTIFIER)?)
+    }
+
+    fn value(&mut self) -> Result<SnmpValue, BerError> {
+        let (tag, content) = self.tlv()?;
+        let value = match tag {
+            TAG_INTEGER => SnmpValue::Integer(decode_integer(content)?),
+            TAG_OCTET_STRING => SnmpValue::OctetString(content.to_vec()),
+            TAG_NULL => SnmpValue::Null,
+            TAG_OBJECT_IDENTIFIER => SnmpValue::ObjectIdentifier(decode_oid(content)?),
+            TAG_IP_ADDRESS => {
+                let address = <[u8; 4]>::try_from(content).map_err(|_| BerError::InvalidLength)?;
+                SnmpValue::IpAddress(address)
+            }
+            TAG_COUNTER32 => SnmpValue::Counter32(decode_unsigned(content)? as u32),
+            TAG_GAUGE32 => SnmpValue::Gauge32(decode_unsigned(content)? as u32),
+            TAG_TIMETICKS => SnmpValue::TimeTicks(decode_unsigned(content)? as u32),
+            TAG_OPAQUE => SnmpValue::Opaque(content.to_vec()),
+            TAG_COUNTER64 => SnmpValue::Counter64(decode_unsigned(content)?),
+            TAG_NO_SUCH_OBJECT => SnmpValue::NoSuchObject,
+            TAG_NO_SUCH_INSTANCE => SnmpValue::NoSuchInstance,
+            TAG_END_OF_MIB_VIEW => SnmpValue::EndOfMibView,
+            tag => return Err(BerError::UnsupportedTag { tag }),
+        };
+        Ok(value)
+    }
+}
+
+fn decode_integer(content: &[u8]) -> Result<i64, BerError> {
+    if content.is_empty() {
+        return Err(BerError::InvalidLength);
+    }
+    if content.len() > 8 {
+        return Err(BerError::IntegerOverflow);
+    }
+    // The value is sign extended from its first byte.
+    let initial = if content[0] & 0x80 != 0 { -1 } else { 0 };
+    Ok(content
+        .iter()
+        .fold(initial, |value, byte| (value << 8) | *byte as i64))
+}
+
+fn decode_unsigned(content: &[u8]) -> Result<u64, BerError> {
+    // Values with their most significant bit set are prefixed by a null byte.
+    let content = match content {
+        [0, rest @ ..] => rest,
+        content => content,
+    };
+    if content.len() > 8 {
+        return Err(BerError::IntegerOverflow);
+    }
+    Ok(content
+        .iter()
+        .fold(0, |value, byte| (value << 8) | *byte as u64))
+}
+
+fn decode_oid(content: &[u8]) -> Result<Oid, BerError> {
+    let mut ids = Vec::new();
+    let mut id: u32 = 0;
+    for (index, byte) in content.iter().enumerate() {
+        id = id
+            .checked_mul(128)
+            .ok_or(BerError::InvalidOid)?
+            .checked_add((byte & 0x7f) as u32)
+            .ok_or(BerError::InvalidOid)?;
+        if byte & 0x80 == 0 {
+            if ids.is_empty() {
+                // The first two arcs are encoded together.
+                let first = (id / 40).min(2);
+                ids.push(first);
+                ids.push(id - first * 40);
+            } else {
+                ids.push(id);
+            }
+            id = 0;
+        } else if index == content.len() - 1 {
+            return Err(BerError::InvalidOid);
+        }
+    }
+    if ids.is_empty() {
+        return Err(BerError::InvalidOid);
+    }
+    Ok(Oid(ids))
+}
+
+pub(super) fn encode_tlv(tag: u8, content: &[u8], out: &mut Vec<u8>) {
+    out.push(tag);
+    let length = content.len();
+    if length < 0x80 {
+        out.push(length as u8);
+    } else {
+        let bytes = (length as u32).to_be_bytes();
+        let skip = bytes.iter().take_while(|byte| **byte == 0).count();
+        out.push(0x80 | (4 - skip) as u8);
+        out.extend_from_slice(&bytes[skip..]);
+    }
+    out.extend_from_slice(content);
+}
+
+pub(super) fn encode_integer(value: i64, out: &mut Vec<u8>) {
+    let bytes = value.to_be_bytes();
+    // The redundant leading bytes are skipped, as long as the sign is preserved.
+    let mut skip = 0;
+    while skip < 7 {
+        let (byte, next) = (bytes[skip], bytes[skip + 1]);
+        if (byte == 0 && next & 0x80 == 0) || (byte == 0xff && next & 0x80 != 0) {
+            skip += 1;
+        } else {
+            break;
+        }
+    }
+    encode_tlv(TAG_INTEGER, &bytes[skip..], out);
+}
+
+pub(super) fn encode_octet_string(value: &[u8], out: &mut Vec<u8>) {
+    encode_tlv(TAG_OCTET_STRING, value, out);
+}
+
+fn encode_unsigned(tag: u8, value: u64, out: &mut Vec<u8>) {
+    let bytes = value.to_be_bytes();
+    let skip = bytes[..7].iter().take_while(|byte| **byte == 0).count();
+    let mut content = Vec::with_capacity(9);
+    if bytes[skip] & 0x80 != 0 {
+        content.push(0);
+    }
+    content.extend_from_slice(&bytes[skip..]);
+    encode_tlv(tag, &content, out);
+}
+
+fn encode_oid(oid: &Oid, out: &mut Vec<u8>) {
+    let mut content = Vec::new();
+    let (first, rest) = match oid.0.as_slice() {
+        [first, second, rest @ ..] => (first * 40 + second, rest),
+        [first] => (first * 40, &[][..]),
+        [] => (0, &[][..]),
+    };
+    for id in std::iter::once(&first).chain(rest) {
+        let mut id = *id;
+        let mut bytes = vec![(id & 0x7f) as u8];
+        id >>= 7;
+        while id > 0 {
+            bytes.push(0x80 | (id & 0x7f) as u8);
+            id >>= 7;
+        }
+        content.extend(bytes.iter().rev());
+    }
+    encode_tlv(TAG_OBJECT_IDENTIFIER, &content, out);
+}
+
+fn encode_value(value: &SnmpValue, out: &mut Vec<u8>) {
+    match value {
+        SnmpValue::Integer(value) => encode_integer(*value, out),
+        SnmpValue::OctetString(value) => encode_octet_string(value, out),
+        SnmpValue::Null => encode_tlv(TAG_NULL, &[], out),
+        SnmpValue::ObjectIdentifier(oid) => encode_oid(oid, out),
+        SnmpValue::IpAddress(address) => encode_tlv(TAG_IP_ADDRESS, address, out),
+        SnmpValue::Counter32(value) => encode_unsigned(TAG_COUNTER32, (*value).into(), out),
+        SnmpValue::Gauge32(value) => encode_unsigned(TAG_GAUGE32, (*value).into(), out),
+        SnmpValue::TimeTicks(value) => encode_unsigned(TAG_TIMETICKS, (*value).into(), out),
+        SnmpValue::Opaque(value) => encode_tlv(TAG_OPAQUE, value, out),
+        SnmpValue::Counter64(value) => encode_unsigned(TAG_COUNTER64, *value, out),
+        SnmpValue::NoSuchObject => encode_tlv(TAG_NO_SUCH_OBJECT, &[], out),
+        SnmpValue::NoSuchInstance => encode_tlv(TAG_NO_SUCH_INSTANCE, &[], out),
+        SnmpValue::EndOfMibView => encode_tlv(TAG_END_OF_MIB_VIEW, &[], out),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn oid(s: &str) -> Oid {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_oids() {
+        assert_eq!(
+            oid("1.3.6.1.2.1.1.3.0"),
+            Oid(vec![1, 3, 6, 1, 2, 1, 1, 3, 0])
+        );
+        assert_eq!(oid(".1.3.6"), Oid(vec![1, 3, 6]));
+        assert_eq!(oid("1.3.6.1").to_string(), "1.3.6.1");
+        assert!("1".parse::<Oid>().is_err());
+        assert!("1.3.x".parse::<Oid>().is_err());
+    }
+
+    #[test]
+    fn encodes_integers() {
+        for (value, expected) in [
+            (0, vec![0x02, 0x01, 0x00]),
+            (127, vec![0x02, 0x01, 0x7f]),
+            (128, vec![0x02, 0x02, 0x00, 0x80]),
+            (256, vec![0x02, 0x02, 0x01, 0x00]),
+            (-1, vec![0x02, 0x01, 0xff]),
+            (-129, vec![0x02, 0x02, 0xff, 0x7f]),
+        ] {
+            let mut out = Vec::new();
+            encode_integer(value, &mut out);
+            assert_eq!(out, expected, "{}", value);
+            assert_eq!(Decoder::new(&out).integer().unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn encodes_long_lengths() {
+        let content = vec![0; 300];
+        let mut out = Vec::new();
+        encode_tlv(TAG_OCTET_STRING, &content, &mut out);
+        assert_eq!(&out[..4], &[0x04, 0x82, 0x01, 0x2c]);
+        assert_eq!(Decoder::new(&out).octet_string().unwrap(), &content[..]);
+    }
+
+    #[test]
+    fn round_trips_pdus() {
+        let pdu = Pdu {
+            pdu_type: PduType::Trap,
+            request_id: 1234,
+            error_status: 0,
+            error_index: 0,
+            varbinds: vec![
+                VarBind {
+                    oid: oid("1.3.6.1.2.1.1.3.0"),
+                    value: SnmpValue::TimeTicks(4_000_000_000),
+                },
+                VarBind {
+                    oid: oid("1.3.6.1.6.3.1.1.4.1.0"),
+                    value: SnmpValue::ObjectIdentifier(oid("1.3.6.1.6.3.1.1.5.3")),
+                },
+                VarBind {
+                    oid: oid("1.3.6.1.2.1.2.2.1.10.2"),
+                    value: SnmpValue::Counter64(u64::MAX),
+                },
+                VarBind {
+                    oid: oid("1.3.6.1.2.1.2.2.1.2.2"),
+                    value: SnmpValue::OctetString(b"eth0".to_vec()),
+                },
+                VarBind {
+                    oid: oid("1.3.6.1.2.1.4.20.1.1.10.0.0.1"),
+                    value: SnmpValue::IpAddress([10, 0, 0, 1]),
+                },
+                VarBind {
+                    oid: oid("1.3.6.1.2.1.1.7.0"),
+                    value: SnmpValue::NoSuchInstance,
+                },
+            ],
+        };
+
+        let mut out = Vec::new();
+        pdu.encode(&mut out);
+        assert_eq!(Pdu::decode(&mut Decoder::new(&out)).unwrap(), pdu);
+    }
+
+    #[test]
+    fn decodes_large_oid_arcs() {
+        let mut out = Vec::new();
+        encode_oid(&oid("1.3.6.1.4.1.2636.3.1.13.1.8"), &mut out);
+        assert_eq!(&out[2..9], &[0x2b, 0x06, 0x01, 0x04, 0x01, 0x94, 0x4c]);
+        assert_eq!(
+            decode_oid(&out[2..]).unwrap(),
+            oid("1.3.6.1.4.1.2636.3.1.13.1.8")
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_encodings() {
+        assert_eq!(
+            Decoder::new(&[0x04, 0x05, 0x01]).tlv().unwrap_err(),
+            BerError::Truncated
+        );
+        assert_eq!(
+            Decoder::new(&[0x02, 0x01, 0x01])
+                .octet_string()
+                .unwrap_err(),
+            BerError::UnexpectedTag {
+                expected: 0x04,
+                found: 0x02
+            }
+        );
+    }
+}
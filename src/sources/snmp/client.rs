@@ -0,0 +1,353 @@
+//! A client polling the objects of an agent, discovering its engine for SNMPv3.
+
+use std::{sync::Arc, time::Duration};
+
+use snafu::{ResultExt, Snafu};
+use tokio::{net::UdpSocket, time::Instant};
+
+use super::{
+    ber::{Oid, Pdu, PduType, SnmpValue, VarBind},
+    message::{Message, MessageError, ScopedPdu, V3Message, FLAG_AUTH, FLAG_PRIVACY},
+    usm::{LocalizedKeys, User, UsmError},
+};
+
+/// The report of the agents receiving messages outside of their time window.
+const NOT_IN_TIME_WINDOWS: &[u32] = &[1, 3, 6, 1, 6, 3, 15, 1, 1, 2, 0];
+
+/// The report of the agents receiving messages of engines they don't know.
+const UNKNOWN_ENGINE_IDS: &[u32] = &[1, 3, 6, 1, 6, 3, 15, 1, 1, 4, 0];
+
+const MAX_MESSAGE_LENGTH: usize = 65535;
+
+#[derive(Debug, Snafu)]
+pub enum ClientError {
+    #[snafu(display("Failed to resolve {}: {}", target, source))]
+    Resolve {
+        target: String,
+        source: std::io::Error,
+    },
+    #[snafu(display("No address found for {}", target))]
+    NoAddress { target: String },
+    #[snafu(display("I/O error: {}", source))]
+    Io { source: std::io::Error },
+    #[snafu(display("Request timed out"))]
+    Timeout,
+    #[snafu(display("Invalid response: {}", source))]
+    InvalidResponse { source: MessageError },
+    #[snafu(display("Failed to localize keys: {}", source))]
+    Keys { source: UsmError },
+    #[snafu(display("The agent reported {}", oid))]
+    Report { oid: Oid },
+    #[snafu(display("The agent responded with error {} at index {}", status, index))]
+    ErrorStatus { status: i64, index: i64 },
+    #[snafu(display("The agent returned {} after {} while walking", next, previous))]
+    OidNotIncreasing { previous: Oid, next: Oid },
+}
+
+pub(super) enum Security {
+    V2c { community: Vec<u8> },
+    V3 { user: Arc<User> },
+}
+
+/// The authoritative engine of the agent, whose time is estimated from the time it was
+/// synchronized at.
+struct Engine {
+    id: Vec<u8>,
+    boots: u32,
+    time: u32,
+    synchronized_at: Instant,
+    keys: LocalizedKeys,
+}
+
+impl Engine {
+    fn time(&self) -> u32 {
+        self.time
+            .saturating_add(self.synchronized_at.elapsed().as_secs() as u32)
+    }
+}
+
+pub(super) struct Client {
+    socket: UdpSocket,
+    security: Security,
+    engine: Option<Engine>,
+    timeout: Duration,
+    retries: usize,
+    request_id: i32,
+    salt: u64,
+}
+
+impl Client {
+    pub(super) async fn connect(
+        target: &str,
+        security: Security,
+        timeout: Duration,
+        retries: usize,
+    ) -> Result<Self, ClientError> {
+        let address = tokio::net::lookup_host(target)
+            .await
+            .context(ResolveSnafu { target })?
+            .next()
+            .ok_or_else(|| ClientError::NoAddress {
+                target: target.to_owned(),
+            })?;
+        let local = if address.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        };
+        let socket = UdpSocket::bind(local).await.context(IoSnafu)?;
+        socket.connect(address).await.context(IoSnafu)?;
+
+        Ok(Self {
+            socket,
+            security,
+            engine: None,
+            timeout,
+            retries,
+            request_id: rand::random::<i32>() & i32::MAX,
+            salt: rand::random(),
+        })
+    }
+
+    /// Gets the values of the variables.
+    pub(super) async fn get(&mut self, oids: &[Oid]) -> Result<Vec<VarBind>, ClientError> {
+        let varbinds = oids
+            .iter()
+            .map(|oid| VarBind {
+                oid: oid.clone(),
+                value: SnmpValue::Null,
+            })
+            .collect();
+        let pdu = self.request(PduType::GetRequest, varbinds, 0, 0).await?;
+        Ok(pdu.varbinds)
+    }
+
+    /// Gets the values of the variables of a subtree, such as the columns of a table.
+    pub(super) async fn walk(
+        &mut self,
+        root: &Oid,
+        max_repetitions: u32,
+    ) -> Result<Vec<VarBind>, ClientError> {
+        let mut varbinds = Vec::new();
+        let mut current = root.clone();
+        loop {
+            let request = vec![VarBind {
+                oid: current.clone(),
+                value: SnmpValue::Null,
+            }];
+            let pdu = self
+                .request(PduType::GetBulkRequest, request, 0, max_repetitions.into())
+                .await?;
+            if pdu.varbinds.is_empty() {
+                return Ok(varbinds);
+            }
+            for varbind in pdu.varbinds {
+                if !varbind.oid.starts_with(root) || varbind.value == SnmpValue::EndOfMibView {
+                    return Ok(varbinds);
+                }
+                if varbind.oid <= current {
+                    return Err(ClientError::OidNotIncreasing {
+                        previous: current,
+                        next: varbind.oid,
+                    });
+                }
+                current = varbind.oid.clone();
+                varbinds.push(varbind);
+            }
+        }
+    }
+
+    async fn request(
+        &mut self,
+        pdu_type: PduType,
+        varbinds: Vec<VarBind>,
+        error_status: i64,
+        error_index: i64,
+    ) -> Result<Pdu, ClientError> {
+        self.request_id = self.request_id.wrapping_add(1) & i32::MAX;
+        let pdu = Pdu {
+            pdu_type,
+            request_id: self.request_id,
+            error_status,
+            error_index,
+            varbinds,
+        };
+
+        // Agents whose engine restarted, or whose clock drifted, report it, after which the
+        // request is sent again once.
+        let mut resynchronized = false;
+        loop {
+            let response = match self.exchange(&pdu).await {
+                Err(ClientError::Report { oid }) if !resynchronized => {
+                    if oid.0 == UNKNOWN_ENGINE_IDS {
+                        self.engine = None;
+                    } else if oid.0 != NOT_IN_TIME_WINDOWS {
+                        return Err(ClientError::Report { oid });
+                    }
+                    resynchronized = true;
+                    continue;
+                }
+                response => response?,
+            };
+            if response.error_status != 0 {
+                return Err(ClientError::ErrorStatus {
+                    status: response.error_status,
+                    index: response.error_index,
+                });
+            }
+            return Ok(response);
+        }
+    }
+
+    /// Sends a request until its response is received, or the retries are exhausted.
+    async fn exchange(&mut self, pdu: &Pdu) -> Result<Pdu, ClientError> {
+        if matches!(self.security, Security::V3 { .. }) && self.engine.is_none() {
+            self.engine = Some(self.discover().await?);
+        }
+        let message = self.encode(pdu)?;
+
+        for _ in 0..=self.retries {
+            self.socket.send(&message).await.context(IoSnafu)?;
+            let deadline = Instant::now() + self.timeout;
+            let mut buf = vec![0; MAX_MESSAGE_LENGTH];
+            loop {
+                let length =
+                    match tokio::time::timeout_at(deadline, self.socket.recv(&mut buf)).await {
+                        Ok(length) => length.context(IoSnafu)?,
+                        Err(_) => break,
+                    };
+                // Responses to previous requests that timed out are skipped.
+                if let Some(response) = self.decode(&buf[..length], pdu.request_id)? {
+                    return Ok(response);
+                }
+            }
+        }
+        Err(ClientError::Timeout)
+    }
+
+    fn encode(&mut self, pdu: &Pdu) -> Result<Vec<u8>, ClientError> {
+        match (&self.security, &self.engine) {
+            (Security::V2c { community }, _) => Ok(Message::encode_v2c(community, pdu)),
+            (Security::V3 { user }, Some(engine)) => {
+                self.salt = self.salt.wrapping_add(1);
+                let scoped_pdu = ScopedPdu {
+                    context_engine_id: engine.id.clone(),
+                    context_name: Vec::new(),
+                    pdu: pdu.clone(),
+                };
+                V3Message::encode(
+                    pdu.request_id,
+                    true,
+                    &engine.id,
+                    engine.boots,
+                    engine.time(),
+                    user.name.as_bytes(),
+                    Some(&engine.keys),
+                    &scoped_pdu,
+                    self.salt,
+                )
+                .context(InvalidResponseSnafu)
+            }
+            (Security::V3 { .. }, None) => unreachable!("engines are discovered before encoding"),
+        }
+    }
+
+    /// Decodes the response to a request, or `None` for the messages of other requests.
+    fn decode(&mut self, data: &[u8], request_id: i32) -> Result<Option<Pdu>, ClientError> {
+        let message = Message::decode(data).context(InvalidResponseSnafu)?;
+        match message {
+            Message::V2c { pdu, .. } => Ok((pdu.request_id == request_id).then(|| pdu)),
+            Message::V3(message) => {
+                if message.message_id != request_id {
+                    return Ok(None);
+                }
+                let engine = self
+                    .engine
+                    .as_mut()
+                    .expect("engines are discovered before requests");
+                let scoped_pdu = if message.flags & FLAG_PRIVACY == 0 {
+                    message.open_report(&engine.keys)
+                } else {
+                    message.open(&engine.keys)
+                }
+                .context(InvalidResponseSnafu)?;
+
+                if scoped_pdu.pdu.pdu_type == PduType::Report {
+                    // Authenticated reports carry the current time of the engine.
+                    if message.flags & FLAG_AUTH != 0 || !engine.keys.has_auth() {
+                        engine.boots = message.usm.engine_boots;
+                        engine.time = message.usm.engine_time;
+                        engine.synchronized_at = Instant::now();
+                    }
+                    let oid = scoped_pdu
+                        .pdu
+                        .varbinds
+                        .into_iter()
+                        .next()
+                        .map(|varbind| varbind.oid)
+                        .unwrap_or_default();
+                    return Err(ClientError::Report { oid });
+                }
+                let auth = message.flags & FLAG_AUTH != 0;
+                let privacy = message.flags & FLAG_PRIVACY != 0;
+                if auth != engine.keys.has_auth() || privacy != engine.keys.has_privacy() {
+                    return Err(ClientError::InvalidResponse {
+                        source: MessageError::SecurityLevelMismatch,
+                    });
+                }
+                Ok(Some(scoped_pdu.pdu))
+            }
+        }
+    }
+
+    /// Discovers the authoritative engine of the agent, with an empty request it reports its
+    /// identifier and time in response to (RFC 3414, 4).
+    async fn discover(&mut self) -> Result<Engine, ClientError> {
+        let user = match &self.security {
+            Security::V3 { user } => Arc::clone(user),
+            Security::V2c { .. } => unreachable!("engines are only discovered for SNMPv3"),
+        };
+        self.request_id = self.request_id.wrapping_add(1) & i32::MAX;
+        let pdu = Pdu {
+            pdu_type: PduType::GetRequest,
+            request_id: self.request_id,
+            error_status: 0,
+            error_index: 0,
+            varbinds: Vec::new(),
+        };
+        let scoped_pdu = ScopedPdu {
+            context_engine_id: Vec::new(),
+            context_name: Vec::new(),
+            pdu,
+        };
+        let request =
+            V3Message::encode(self.request_id, true, &[], 0, 0, &[], None, &scoped_pdu, 0)
+                .context(InvalidResponseSnafu)?;
+
+        for _ in 0..=self.retries {
+            self.socket.send(&request).await.context(IoSnafu)?;
+            let deadline = Instant::now() + self.timeout;
+            let mut buf = vec![0; MAX_MESSAGE_LENGTH];
+            loop {
+                let length =
+                    match tokio::time::timeout_at(deadline, self.socket.recv(&mut buf)).await {
+                        Ok(length) => length.context(IoSnafu)?,
+                        Err(_) => break,
+                    };
+                let message = match Message::decode(&buf[..length]).context(InvalidResponseSnafu)? {
+                    Message::V3(message) if message.message_id == self.request_id => message,
+                    _ => continue,
+                };
+                let keys = user.localize(message.usm.engine_id).context(KeysSnafu)?;
+                return Ok(Engine {
+                    id: message.usm.engine_id.to_vec(),
+                    boots: message.usm.engine_boots,
+                    time: message.usm.engine_time,
+                    synchronized_at: Instant::now(),
+                    keys,
+                });
+            }
+        }
+        Err(ClientError::Timeout)
+    }
+}
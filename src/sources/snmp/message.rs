@@ -0,0 +1,425 @@
+//! SNMPv2c and SNMPv3 messages.
+
+use snafu::{ResultExt, Snafu};
+
+use super::{
+    ber::{
+        encode_integer, encode_octet_string, encode_tlv, BerError, Decoder, Pdu, TAG_OCTET_STRING,
+        TAG_SEQUENCE,
+    },
+    usm::{LocalizedKeys, UsmError},
+};
+
+const VERSION_2C: i64 = 1;
+const VERSION_3: i64 = 3;
+
+/// The security model of the USM.
+const SECURITY_MODEL_USM: i64 = 3;
+
+/// The largest message accepted in return, the largest UDP payload over IPv4.
+const MAX_MESSAGE_SIZE: i64 = 65507;
+
+pub(super) const FLAG_AUTH: u8 = 0x01;
+pub(super) const FLAG_PRIVACY: u8 = 0x02;
+pub(super) const FLAG_REPORTABLE: u8 = 0x04;
+
+#[derive(Debug, Snafu)]
+pub enum MessageError {
+    #[snafu(display("Invalid encoding: {}", source))]
+    Encoding { source: BerError },
+    #[snafu(display("Unsupported version {}", version))]
+    UnsupportedVersion { version: i64 },
+    #[snafu(display("Unsupported security model {}", model))]
+    UnsupportedSecurityModel { model: i64 },
+    #[snafu(display("Security failed: {}", source))]
+    Security { source: UsmError },
+    #[snafu(display("The security level of the message doesn't match the one of the user"))]
+    SecurityLevelMismatch,
+}
+
+impl From<BerError> for MessageError {
+    fn from(source: BerError) -> Self {
+        MessageError::Encoding { source }
+    }
+}
+
+/// A decoded message, whose scoped PDU is only decoded once authenticated and decrypted for
+/// SNMPv3 messages.
+#[derive(Debug)]
+pub(super) enum Message<'a> {
+    V2c { community: &'a [u8], pdu: Pdu },
+    V3(V3Message<'a>),
+}
+
+impl<'a> Message<'a> {
+    pub(super) fn decode(data: &'a [u8]) -> Result<Self, MessageError> {
+        let mut message = Decoder::new(data).sequence()?;
+        match message.integer()? {
+            VERSION_2C => {
+                let community = message.octet_string()?;
+                let pdu = Pdu::decode(&mut message)?;
+                Ok(Message::V2c { community, pdu })
+            }
+            VERSION_3 => V3Message::decode(data, message).map(Message::V3),
+            version => Err(MessageError::UnsupportedVersion { version }),
+        }
+    }
+
+    pub(super) fn encode_v2c(community: &[u8], pdu: &Pdu) -> Vec<u8> {
+        let mut content = Vec::new();
+        encode_integer(VERSION_2C, &mut content);
+        encode_octet_string(community, &mut content);
+        pdu.encode(&mut content);
+
+        let mut out = Vec::new();
+        encode_tlv(TAG_SEQUENCE, &content, &mut out);
+        out
+    }
+}
+
+/// The security parameters of the USM.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(super) struct UsmParameters<'a> {
+    pub(super) engine_id: &'a [u8],
+    pub(super) engine_boots: u32,
+    pub(super) engine_time: u32,
+    pub(super) user_name: &'a [u8],
+    pub(super) auth_parameters: &'a [u8],
+    pub(super) privacy_parameters: &'a [u8],
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct ScopedPdu {
+    pub(super) context_engine_id: Vec<u8>,
+    pub(super) context_name: Vec<u8>,
+    pub(super) pdu: Pdu,
+}
+
+impl ScopedPdu {
+    fn encode(&self, out: &mut Vec<u8>) {
+        let mut content = Vec::new();
+        encode_octet_string(&self.context_engine_id, &mut content);
+        encode_octet_string(&self.context_name, &mut content);
+        self.pdu.encode(&mut content);
+        encode_tlv(TAG_SEQUENCE, &content, out);
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct V3Message<'a> {
+    data: &'a [u8],
+    pub(super) message_id: i32,
+    pub(super) flags: u8,
+    pub(super) usm: UsmParameters<'a>,
+    /// The encoded scoped PDU, or its ciphertext when the message is encrypted.
+    scoped_pdu: &'a [u8],
+}
+
+impl<'a> V3Message<'a> {
+    fn decode(data: &'a [u8], mut message: Decoder<'a>) -> Result<Self, MessageError> {
+        let mut header = message.sequence()?;
+        let message_id = header.integer()? as i32;
+        let _max_size = header.integer()?;
+        let flags = header.octet_string()?.first().copied().unwrap_or_default();
+        let model = header.integer()?;
+        if model != SECURITY_MODEL_USM {
+            return Err(MessageError::UnsupportedSecurityModel { model });
+        }
+
+        let mut parameters = Decoder::new(message.octet_string()?).sequence()?;
+        let usm = UsmParameters {
+            engine_id: parameters.octet_string()?,
+            engine_boots: parameters.integer()? as u32,
+            engine_time: parameters.integer()? as u32,
+            user_name: parameters.octet_string()?,
+            auth_parameters: parameters.octet_string()?,
+            privacy_parameters: parameters.octet_string()?,
+        };
+
+        let scoped_pdu = if flags & FLAG_PRIVACY != 0 {
+            message.octet_string()?
+        } else {
+            let remaining = message.remaining();
+            message.sequence()?;
+            &remaining[..remaining.len() - message.remaining().len()]
+        };
+
+        Ok(Self {
+            data,
+            message_id,
+            flags,
+            usm,
+            scoped_pdu,
+        })
+    }
+
+    /// Authenticates and decrypts the scoped PDU of the message with the keys of its user,
+    /// localized to its authoritative engine. Messages whose security level doesn't match the
+    /// one of the keys are rejected.
+    pub(super) fn open(&self, keys: &LocalizedKeys) -> Result<ScopedPdu, MessageError> {
+        let auth = self.flags & FLAG_AUTH != 0;
+        let privacy = self.flags & FLAG_PRIVACY != 0;
+        if auth != keys.has_auth() || privacy != keys.has_privacy() {
+            return Err(MessageError::SecurityLevelMismatch);
+        }
+
+        if auth {
+            let offset = self.usm.auth_parameters.as_ptr() as usize - self.data.as_ptr() as usize;
+            keys.verify(self.data, offset).context(SecuritySnafu)?;
+        }
+
+        if privacy {
+            let plaintext = keys
+                .decrypt(
+                    self.scoped_pdu,
+                    self.usm.engine_boots,
+                    self.usm.engine_time,
+                    self.usm.privacy_parameters,
+                )
+                .context(SecuritySnafu)?;
+            decode_scoped_pdu(&plaintext)
+        } else {
+            decode_scoped_pdu(self.scoped_pdu)
+        }
+    }
+
+    /// Authenticates the scoped PDU of a report, which agents never encrypt (RFC 3414, 3.1.3).
+    pub(super) fn open_report(&self, keys: &LocalizedKeys) -> Result<ScopedPdu, MessageError> {
+        if self.flags & FLAG_PRIVACY != 0 {
+            return Err(MessageError::SecurityLevelMismatch);
+        }
+        if self.flags & FLAG_AUTH != 0 {
+            let offset = self.usm.auth_parameters.as_ptr() as usize - self.data.as_ptr() as usize;
+            keys.verify(self.data, offset).context(SecuritySnafu)?;
+        }
+        decode_scoped_pdu(self.scoped_pdu)
+    }
+
+    /// Encodes a message, authenticated and encrypted with the keys when there are some.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn encode(
+        message_id: i32,
+        reportable: bool,
+        engine_id: &[u8],
+        engine_boots: u32,
+        engine_time: u32,
+        user_name: &[u8],
+        keys: Option<&LocalizedKeys>,
+        scoped_pdu: &ScopedPdu,
+        salt: u64,
+    ) -> Result<Vec<u8>, MessageError> {
+        let mut plaintext = Vec::new();
+        scoped_pdu.encode(&mut plaintext);
+
+        let mut flags = if reportable { FLAG_REPORTABLE } else { 0 };
+        let (data, privacy_parameters) = match keys {
+            Some(keys) if keys.has_privacy() => {
+                flags |= FLAG_PRIVACY;
+                let (ciphertext, parameters) = keys
+                    .encrypt(&plaintext, engine_boots, engine_time, salt)
+                    .context(SecuritySnafu)?;
+                let mut data = Vec::new();
+                encode_octet_string(&ciphertext, &mut data);
+                (data, parameters)
+            }
+            _ => (plaintext, Vec::new()),
+        };
+        let auth_length = keys.map_or(0, LocalizedKeys::auth_parameters_length);
+        if auth_length > 0 {
+            flags |= FLAG_AUTH;
+        }
+
+        // The offset of the authentication parameters is tracked through the encoding, as
+        // they're computed over the whole message.
+        let mut usm = Vec::new();
+        encode_octet_string(engine_id, &mut usm);
+        encode_integer(engine_boots.into(), &mut usm);
+        encode_integer(engine_time.into(), &mut usm);
+        encode_octet_string(user_name, &mut usm);
+        let mut offset = usm.len() + 2;
+        encode_octet_string(&vec![0; auth_length], &mut usm);
+        encode_octet_string(&privacy_parameters, &mut usm);
+        let usm = wrap(TAG_SEQUENCE, &usm, &mut offset);
+        let usm = wrap(TAG_OCTET_STRING, &usm, &mut offset);
+
+        let mut header = Vec::new();
+        encode_integer(message_id.into(), &mut header);
+        encode_integer(MAX_MESSAGE_SIZE, &mut header);
+        encode_octet_string(&[flags], &mut header);
+        encode_integer(SECURITY_MODEL_USM, &mut header);
+
+        let mut content = Vec::new();
+        encode_integer(VERSION_3, &mut content);
+        encode_tlv(TAG_SEQUENCE, &header, &mut content);
+        offset += content.len();
+        content.extend(usm);
+        content.extend(data);
+        let mut message = wrap(TAG_SEQUENCE, &content, &mut offset);
+
+        if let Some(keys) = keys.filter(|_| auth_length > 0) {
+            let digest = keys.sign(&message).context(SecuritySnafu)?;
+            message[offset..offset + auth_length].copy_from_slice(&digest);
+        }
+        Ok(message)
+    }
+}
+
+/// Wraps content in an element, moving an offset into the content by the length of its header.
+fn wrap(tag: u8, content: &[u8], offset: &mut usize) -> Vec<u8> {
+    let mut out = Vec::new();
+    encode_tlv(tag, content, &mut out);
+    *offset += out.len() - content.len();
+    out
+}
+
+fn decode_scoped_pdu(data: &[u8]) -> Result<ScopedPdu, MessageError> {
+    // Encrypted scoped PDUs may be followed by padding.
+    let mut scoped_pdu = Decoder::new(data).sequence()?;
+    Ok(ScopedPdu {
+        context_engine_id: scoped_pdu.octet_string()?.to_vec(),
+        context_name: scoped_pdu.octet_string()?.to_vec(),
+        pdu: Pdu::decode(&mut scoped_pdu)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{
+            ber::{PduType, SnmpValue, VarBind},
+            usm::{AuthProtocol, PrivacyProtocol, User},
+        },
+        *,
+    };
+
+    const ENGINE_ID: &[u8] = b"\x80\x00\x1f\x88\x04vector";
+
+    fn pdu() -> Pdu {
+        Pdu {
+            pdu_type: PduType::Trap,
+            request_id: 7,
+            error_status: 0,
+            error_index: 0,
+            varbinds: vec![VarBind {
+                oid: "1.3.6.1.2.1.1.3.0".parse().unwrap(),
+                value: SnmpValue::TimeTicks(100),
+            }],
+        }
+    }
+
+    #[test]
+    fn round_trips_v2c() {
+        let data = Message::encode_v2c(b"public", &pdu());
+        match Message::decode(&data).unwrap() {
+            Message::V2c {
+                community,
+                pdu: decoded,
+            } => {
+                assert_eq!(community, b"public");
+                assert_eq!(decoded, pdu());
+            }
+            message => panic!("unexpected message {:?}", message),
+        }
+    }
+
+    fn round_trip_v3(user: &User) -> (Vec<u8>, ScopedPdu) {
+        let keys = user.localize(ENGINE_ID).unwrap();
+        let scoped_pdu = ScopedPdu {
+            context_engine_id: ENGINE_ID.to_vec(),
+            context_name: Vec::new(),
+            pdu: pdu(),
+        };
+        let data = V3Message::encode(
+            42,
+            false,
+            ENGINE_ID,
+            2,
+            300,
+            user.name.as_bytes(),
+            Some(&keys),
+            &scoped_pdu,
+            9,
+        )
+        .unwrap();
+        (data, scoped_pdu)
+    }
+
+    #[test]
+    fn round_trips_v3() {
+        for (auth, privacy) in [
+            (None, None),
+            (Some((AuthProtocol::Sha, "authpassword")), None),
+            (
+                Some((AuthProtocol::Md5, "authpassword")),
+                Some((PrivacyProtocol::Des, "privpassword")),
+            ),
+            (
+                Some((AuthProtocol::Sha256, "authpassword")),
+                Some((PrivacyProtocol::Aes, "privpassword")),
+            ),
+        ] {
+            let user = User::new("user".to_owned(), auth, privacy).unwrap();
+            let (data, scoped_pdu) = round_trip_v3(&user);
+
+            let message = match Message::decode(&data).unwrap() {
+                Message::V3(message) => message,
+                message => panic!("unexpected message {:?}", message),
+            };
+            assert_eq!(message.message_id, 42);
+            assert_eq!(message.usm.engine_id, ENGINE_ID);
+            assert_eq!(message.usm.engine_boots, 2);
+            assert_eq!(message.usm.engine_time, 300);
+            assert_eq!(message.usm.user_name, b"user");
+
+            let keys = user.localize(message.usm.engine_id).unwrap();
+            assert_eq!(message.open(&keys).unwrap(), scoped_pdu);
+        }
+    }
+
+    #[test]
+    fn rejects_tampered_v3() {
+        let user = User::new(
+            "user".to_owned(),
+            Some((AuthProtocol::Sha, "authpassword")),
+            None,
+        )
+        .unwrap();
+        let (mut data, _) = round_trip_v3(&user);
+        let last = data.len() - 1;
+        data[last] ^= 0x01;
+
+        let message = match Message::decode(&data).unwrap() {
+            Message::V3(message) => message,
+            message => panic!("unexpected message {:?}", message),
+        };
+        let keys = user.localize(ENGINE_ID).unwrap();
+        assert!(matches!(
+            message.open(&keys),
+            Err(MessageError::Security {
+                source: UsmError::WrongDigest
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_mismatched_security_level() {
+        let user = User::new("user".to_owned(), None, None).unwrap();
+        let (data, _) = round_trip_v3(&user);
+
+        let message = match Message::decode(&data).unwrap() {
+            Message::V3(message) => message,
+            message => panic!("unexpected message {:?}", message),
+        };
+        let user = User::new(
+            "user".to_owned(),
+            Some((AuthProtocol::Sha, "authpassword")),
+            None,
+        )
+        .unwrap();
+        let keys = user.localize(ENGINE_ID).unwrap();
+        assert!(matches!(
+            message.open(&keys),
+            Err(MessageError::SecurityLevelMismatch)
+        ));
+    }
+}
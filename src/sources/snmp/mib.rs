@@ -0,0 +1,536 @@
+//! Resolution of the names of objects, from the objects of the common MIB modules and of the
+//! MIB modules configured.
+//!
+//! MIB modules are only parsed as far as the assignments of the object identifiers of their
+//! definitions, `name OBJECT-TYPE ... ::= { parent 1 }`, are concerned.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use snafu::{ResultExt, Snafu};
+
+use super::ber::Oid;
+
+/// The objects of the common MIB modules, along with the nodes they're defined under.
+const BUILTIN_OBJECTS: &[(&str, &str)] = &[
+    ("iso", "1"),
+    ("org", "1.3"),
+    ("dod", "1.3.6"),
+    ("internet", "1.3.6.1"),
+    ("mgmt", "1.3.6.1.2"),
+    ("mib-2", "1.3.6.1.2.1"),
+    ("private", "1.3.6.1.4"),
+    ("enterprises", "1.3.6.1.4.1"),
+    ("snmpV2", "1.3.6.1.6"),
+    ("snmpModules", "1.3.6.1.6.3"),
+    // SNMPv2-MIB
+    ("system", "1.3.6.1.2.1.1"),
+    ("sysDescr", "1.3.6.1.2.1.1.1"),
+    ("sysObjectID", "1.3.6.1.2.1.1.2"),
+    ("sysUpTime", "1.3.6.1.2.1.1.3"),
+    ("sysContact", "1.3.6.1.2.1.1.4"),
+    ("sysName", "1.3.6.1.2.1.1.5"),
+    ("sysLocation", "1.3.6.1.2.1.1.6"),
+    ("sysServices", "1.3.6.1.2.1.1.7"),
+    ("snmpMIB", "1.3.6.1.6.3.1"),
+    ("snmpMIBObjects", "1.3.6.1.6.3.1.1"),
+    ("snmpTrap", "1.3.6.1.6.3.1.1.4"),
+    ("snmpTrapOID", "1.3.6.1.6.3.1.1.4.1"),
+    ("snmpTrapEnterprise", "1.3.6.1.6.3.1.1.4.3"),
+    ("snmpTraps", "1.3.6.1.6.3.1.1.5"),
+    ("coldStart", "1.3.6.1.6.3.1.1.5.1"),
+    ("warmStart", "1.3.6.1.6.3.1.1.5.2"),
+    ("linkDown", "1.3.6.1.6.3.1.1.5.3"),
+    ("linkUp", "1.3.6.1.6.3.1.1.5.4"),
+    ("authenticationFailure", "1.3.6.1.6.3.1.1.5.5"),
+    // IF-MIB
+    ("interfaces", "1.3.6.1.2.1.2"),
+    ("ifNumber", "1.3.6.1.2.1.2.1"),
+    ("ifTable", "1.3.6.1.2.1.2.2"),
+    ("ifEntry", "1.3.6.1.2.1.2.2.1"),
+    ("ifIndex", "1.3.6.1.2.1.2.2.1.1"),
+    ("ifDescr", "1.3.6.1.2.1.2.2.1.2"),
+    ("ifType", "1.3.6.1.2.1.2.2.1.3"),
+    ("ifMtu", "1.3.6.1.2.1.2.2.1.4"),
+    ("ifSpeed", "1.3.6.1.2.1.2.2.1.5"),
+    ("ifPhysAddress", "1.3.6.1.2.1.2.2.1.6"),
+    ("ifAdminStatus", "1.3.6.1.2.1.2.2.1.7"),
+    ("ifOperStatus", "1.3.6.1.2.1.2.2.1.8"),
+    ("ifLastChange", "1.3.6.1.2.1.2.2.1.9"),
+    ("ifInOctets", "1.3.6.1.2.1.2.2.1.10"),
+    ("ifInUcastPkts", "1.3.6.1.2.1.2.2.1.11"),
+    ("ifInNUcastPkts", "1.3.6.1.2.1.2.2.1.12"),
+    ("ifInDiscards", "1.3.6.1.2.1.2.2.1.13"),
+    ("ifInErrors", "1.3.6.1.2.1.2.2.1.14"),
+    ("ifInUnknownProtos", "1.3.6.1.2.1.2.2.1.15"),
+    ("ifOutOctets", "1.3.6.1.2.1.2.2.1.16"),
+    ("ifOutUcastPkts", "1.3.6.1.2.1.2.2.1.17"),
+    ("ifOutNUcastPkts", "1.3.6.1.2.1.2.2.1.18"),
+    ("ifOutDiscards", "1.3.6.1.2.1.2.2.1.19"),
+    ("ifOutErrors", "1.3.6.1.2.1.2.2.1.20"),
+    ("ifOutQLen", "1.3.6.1.2.1.2.2.1.21"),
+    ("ifMIB", "1.3.6.1.2.1.31"),
+    ("ifXTable", "1.3.6.1.2.1.31.1.1"),
+    ("ifXEntry", "1.3.6.1.2.1.31.1.1.1"),
+    ("ifName", "1.3.6.1.2.1.31.1.1.1.1"),
+    ("ifInMulticastPkts", "1.3.6.1.2.1.31.1.1.1.2"),
+    ("ifInBroadcastPkts", "1.3.6.1.2.1.31.1.1.1.3"),
+    ("ifOutMulticastPkts", "1.3.6.1.2.1.31.1.1.1.4"),
+    ("ifOutBroadcastPkts", "1.3.6.1.2.1.31.1.1.1.5"),
+    ("ifHCInOctets", "1.3.6.1.2.1.31.1.1.1.6"),
+    ("ifHCInUcastPkts", "1.3.6.1.2.1.31.1.1.1.7"),
+    ("ifHCInMulticastPkts", "1.3.6.1.2.1.31.1.1.1.8"),
+    ("ifHCInBroadcastPkts", "1.3.6.1.2.1.31.1.1.1.9"),
+    ("ifHCOutOctets", "1.3.6.1.2.1.31.1.1.1.10"),
+    ("ifHCOutUcastPkts", "1.3.6.1.2.1.31.1.1.1.11"),
+    ("ifHCOutMulticastPkts", "1.3.6.1.2.1.31.1.1.1.12"),
+    ("ifHCOutBroadcastPkts", "1.3.6.1.2.1.31.1.1.1.13"),
+    ("ifHighSpeed", "1.3.6.1.2.1.31.1.1.1.15"),
+    ("ifAlias", "1.3.6.1.2.1.31.1.1.1.18"),
+    // IP-MIB, TCP-MIB and UDP-MIB
+    ("ip", "1.3.6.1.2.1.4"),
+    ("ipForwarding", "1.3.6.1.2.1.4.1"),
+    ("ipInReceives", "1.3.6.1.2.1.4.3"),
+    ("ipInDelivers", "1.3.6.1.2.1.4.9"),
+    ("ipOutRequests", "1.3.6.1.2.1.4.10"),
+    ("tcp", "1.3.6.1.2.1.6"),
+    ("tcpActiveOpens", "1.3.6.1.2.1.6.5"),
+    ("tcpPassiveOpens", "1.3.6.1.2.1.6.6"),
+    ("tcpCurrEstab", "1.3.6.1.2.1.6.9"),
+    ("tcpInSegs", "1.3.6.1.2.1.6.10"),
+    ("tcpOutSegs", "1.3.6.1.2.1.6.11"),
+    ("tcpRetransSegs", "1.3.6.1.2.1.6.12"),
+    ("udp", "1.3.6.1.2.1.7"),
+    ("udpInDatagrams", "1.3.6.1.2.1.7.1"),
+    ("udpNoPorts", "1.3.6.1.2.1.7.2"),
+    ("udpInErrors", "1.3.6.1.2.1.7.3"),
+    ("udpOutDatagrams", "1.3.6.1.2.1.7.4"),
+    // HOST-RESOURCES-MIB
+    ("host", "1.3.6.1.2.1.25"),
+    ("hrSystemUptime", "1.3.6.1.2.1.25.1.1"),
+    ("hrSystemProcesses", "1.3.6.1.2.1.25.1.6"),
+    ("hrMemorySize", "1.3.6.1.2.1.25.2.2"),
+    ("hrStorageTable", "1.3.6.1.2.1.25.2.3"),
+    ("hrStorageEntry", "1.3.6.1.2.1.25.2.3.1"),
+    ("hrStorageIndex", "1.3.6.1.2.1.25.2.3.1.1"),
+    ("hrStorageType", "1.3.6.1.2.1.25.2.3.1.2"),
+    ("hrStorageDescr", "1.3.6.1.2.1.25.2.3.1.3"),
+    ("hrStorageAllocationUnits", "1.3.6.1.2.1.25.2.3.1.4"),
+    ("hrStorageSize", "1.3.6.1.2.1.25.2.3.1.5"),
+    ("hrStorageUsed", "1.3.6.1.2.1.25.2.3.1.6"),
+    ("hrProcessorLoad", "1.3.6.1.2.1.25.3.3.1.2"),
+    // SNMP-FRAMEWORK-MIB and SNMP-USER-BASED-SM-MIB
+    ("snmpEngineID", "1.3.6.1.6.3.10.2.1.1"),
+    ("snmpEngineBoots", "1.3.6.1.6.3.10.2.1.2"),
+    ("snmpEngineTime", "1.3.6.1.6.3.10.2.1.3"),
+    ("usmStatsUnsupportedSecLevels", "1.3.6.1.6.3.15.1.1.1"),
+    ("usmStatsNotInTimeWindows", "1.3.6.1.6.3.15.1.1.2"),
+    ("usmStatsUnknownUserNames", "1.3.6.1.6.3.15.1.1.3"),
+    ("usmStatsUnknownEngineIDs", "1.3.6.1.6.3.15.1.1.4"),
+    ("usmStatsWrongDigests", "1.3.6.1.6.3.15.1.1.5"),
+    ("usmStatsDecryptionErrors", "1.3.6.1.6.3.15.1.1.6"),
+];
+
+/// The nodes above `mgmt` and `private` are too broad to name the objects beneath them, which
+/// are left numeric.
+const MIN_NAMED_LENGTH: usize = 5;
+
+#[derive(Debug, Snafu)]
+pub enum MibError {
+    #[snafu(display("Failed to read MIB module {:?}: {}", path, source))]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("Unknown object {:?}", name))]
+    UnknownObject { name: String },
+}
+
+/// The names of the objects, which are resolved in both directions.
+#[derive(Debug)]
+pub(super) struct Mib {
+    oids: HashMap<String, Oid>,
+    names: HashMap<Vec<u32>, String>,
+}
+
+impl Mib {
+    /// Loads the common objects along with the ones of the MIB modules at the paths, which are
+    /// either files or directories of files.
+    pub(super) fn new(paths: &[PathBuf]) -> Result<Self, MibError> {
+        let mut mib = Mib {
+            oids: HashMap::new(),
+            names: HashMap::new(),
+        };
+        for (name, oid) in BUILTIN_OBJECTS {
+            mib.insert(
+                name,
+                oid.parse().expect("builtin object identifiers are valid"),
+            );
+        }
+
+        let mut assignments = Vec::new();
+        for path in paths {
+            let metadata = fs::metadata(path).context(ReadSnafu { path })?;
+            if metadata.is_dir() {
+                let mut entries = fs::read_dir(path)
+                    .context(ReadSnafu { path })?
+                    .map(|entry| entry.map(|entry| entry.path()))
+                    .collect::<Result<Vec<_>, _>>()
+                    .context(ReadSnafu { path })?;
+                entries.sort();
+                for entry in entries.iter().filter(|entry| entry.is_file()) {
+                    assignments.extend(read_module(entry)?);
+                }
+            } else {
+                assignments.extend(read_module(path)?);
+            }
+        }
+        mib.resolve_assignments(assignments);
+
+        Ok(mib)
+    }
+
+    fn insert(&mut self, name: &str, oid: Oid) {
+        self.names.insert(oid.0.clone(), name.to_owned());
+        self.oids.insert(name.to_owned(), oid);
+    }
+
+    /// Resolves the assignments whose parent is known, until none of the remaining ones can
+    /// be, as modules may refer to objects of modules read after them.
+    fn resolve_assignments(&mut self, mut assignments: Vec<Assignment>) {
+        loop {
+            let count = assignments.len();
+            assignments.retain(|assignment| {
+                let parent = match self.oids.get(&assignment.parent) {
+                    Some(parent) => parent.clone(),
+                    None => return true,
+                };
+                let mut oid = parent;
+                for (name, id) in &assignment.path {
+                    oid.0.push(*id);
+                    if let Some(name) = name {
+                        if !self.oids.contains_key(name) {
+                            self.insert(name, oid.clone());
+                        }
+                    }
+                }
+                self.insert(&assignment.name, oid);
+                false
+            });
+            if assignments.is_empty() || assignments.len() == count {
+                break;
+            }
+        }
+        for assignment in assignments {
+            debug!(
+                message = "Skipping MIB object with an unknown parent.",
+                name = %assignment.name,
+                parent = %assignment.parent,
+            );
+        }
+    }
+
+    /// Resolves the name of an object, optionally qualified by its module and followed by the
+    /// index of an instance, such as `IF-MIB::ifInOctets.1`, or a numeric object identifier.
+    pub(super) fn resolve(&self, name: &str) -> Result<Oid, MibError> {
+        if let Ok(oid) = name.parse::<Oid>() {
+            return Ok(oid);
+        }
+
+        let unqualified = name.rsplit("::").next().unwrap_or(name);
+        let (object, index) = match unqualified.split_once('.') {
+            Some((object, index)) => (object, Some(index)),
+            None => (unqualified, None),
+        };
+        let mut oid = self
+            .oids
+            .get(object)
+            .cloned()
+            .ok_or_else(|| MibError::UnknownObject {
+                name: name.to_owned(),
+            })?;
+        if let Some(index) = index {
+            for id in index.split('.') {
+                oid.0.push(id.parse().map_err(|_| MibError::UnknownObject {
+                    name: name.to_owned(),
+                })?);
+            }
+        }
+        Ok(oid)
+    }
+
+    /// Finds the object of an identifier, returning its name and the index of the instance.
+    pub(super) fn lookup<'a>(&self, oid: &'a Oid) -> Option<(&str, &'a [u32])> {
+        (MIN_NAMED_LENGTH..=oid.0.len()).rev().find_map(|length| {
+            self.names
+                .get(&oid.0[..length])
+                .map(|name| (name.as_str(), &oid.0[length..]))
+        })
+    }
+
+    /// Formats an identifier as the name of its object followed by the index of the instance,
+    /// such as `ifInOctets.1`, or numerically when its object isn't known.
+    pub(super) fn format(&self, oid: &Oid) -> String {
+        match self.lookup(oid) {
+            Some((name, index)) => {
+                let mut formatted = name.to_owned();
+                for id in index {
+                    formatted.push('.');
+                    formatted.push_str(&id.to_string());
+                }
+                formatted
+            }
+            None => oid.to_string(),
+        }
+    }
+}
+
+/// The assignment of an object identifier to a name, relative to a parent, through named
+/// intermediate nodes, such as `{ iso org(3) dod(6) 1 }`.
+#[derive(Debug, PartialEq)]
+struct Assignment {
+    name: String,
+    parent: String,
+    path: Vec<(Option<String>, u32)>,
+}
+
+fn read_module(path: &Path) -> Result<Vec<Assignment>, MibError> {
+    let content = fs::read(path).context(ReadSnafu { path })?;
+    Ok(parse_module(&String::from_utf8_lossy(&content)))
+}
+
+fn parse_module(content: &str) -> Vec<Assignment> {
+    let tokens = tokenize(content);
+    let mut assignments = Vec::new();
+    // The name of a definition precedes its macro, or `OBJECT IDENTIFIER`.
+    let mut name: Option<&str> = None;
+    let mut index = 0;
+    while index < tokens.len() {
+        let token = tokens[index];
+        if token == "::=" {
+            if let (Some(defined), Some("{")) = (name.take(), tokens.get(index + 1).copied()) {
+                let end = tokens[index..]
+                    .iter()
+                    .position(|token| *token == "}")
+                    .map_or(tokens.len(), |end| index + end);
+                if let Some(assignment) = parse_assignment(defined, &tokens[index + 2..end]) {
+                    assignments.push(assignment);
+                }
+                index = end;
+            }
+        } else if is_definition_keyword(token, tokens.get(index + 1).copied()) && index > 0 {
+            let previous = tokens[index - 1];
+            if previous.starts_with(|c: char| c.is_ascii_lowercase()) {
+                name = Some(previous);
+            }
+        }
+        index += 1;
+    }
+    assignments
+}
+
+fn is_definition_keyword(token: &str, next: Option<&str>) -> bool {
+    matches!(
+        token,
+        "OBJECT-TYPE"
+            | "OBJECT-IDENTITY"
+            | "MODULE-IDENTITY"
+            | "NOTIFICATION-TYPE"
+            | "OBJECT-GROUP"
+            | "NOTIFICATION-GROUP"
+            | "MODULE-COMPLIANCE"
+            | "AGENT-CAPABILITIES"
+    ) || (token == "OBJECT" && next == Some("IDENTIFIER"))
+}
+
+fn parse_assignment(name: &str, tokens: &[&str]) -> Option<Assignment> {
+    let (parent, rest) = tokens.split_first()?;
+    let mut path = Vec::new();
+    let mut index = 0;
+    while index < rest.len() {
+        match rest.get(index..index + 4) {
+            Some([label, "(", id, ")"]) => {
+                path.push((Some((*label).to_owned()), id.parse().ok()?));
+                index += 4;
+            }
+            _ => {
+                path.push((None, rest[index].parse().ok()?));
+                index += 1;
+            }
+        }
+    }
+    if path.is_empty() {
+        return None;
+    }
+    Some(Assignment {
+        name: name.to_owned(),
+        parent: (*parent).to_owned(),
+        path,
+    })
+}
+
+/// Splits a module into tokens, skipping comments and quoted strings.
+fn tokenize(content: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let bytes = content.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'-' if bytes.get(index + 1) == Some(&b'-') => {
+                // Comments end at the end of the line, or at the next `--`.
+                index += 2;
+                while index < bytes.len() && bytes[index] != b'\n' {
+                    if bytes[index] == b'-' && bytes.get(index + 1) == Some(&b'-') {
+                        index += 1;
+                        break;
+                    }
+                    index += 1;
+                }
+                index += 1;
+            }
+            b'"' => {
+                index += 1;
+                while index < bytes.len() && bytes[index] != b'"' {
+                    index += 1;
+                }
+                index += 1;
+            }
+            b'{' | b'}' | b'(' | b')' | b',' | b';' => {
+                tokens.push(&content[index..index + 1]);
+                index += 1;
+            }
+            byte if byte.is_ascii_whitespace() => index += 1,
+            _ => {
+                let start = index;
+                while index < bytes.len()
+                    && !bytes[index].is_ascii_whitespace()
+                    && !matches!(bytes[index], b'{' | b'}' | b'(' | b')' | b',' | b';' | b'"')
+                    && !(bytes[index] == b'-' && bytes.get(index + 1) == Some(&b'-'))
+                {
+                    index += 1;
+                }
+                tokens.push(&content[start..index]);
+            }
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    const MODULE: &str = r#"
+EXAMPLE-MIB DEFINITIONS ::= BEGIN
+
+IMPORTS
+    MODULE-IDENTITY, OBJECT-TYPE, Integer32, enterprises
+        FROM SNMPv2-SMI;
+
+example MODULE-IDENTITY
+    LAST-UPDATED "202201010000Z"
+    ORGANIZATION "Example"
+    CONTACT-INFO "ops@example.com"
+    DESCRIPTION  "An example module, whose description contains ::= { ignored 1 }."
+    ::= { enterprises 99999 }
+
+exampleObjects OBJECT IDENTIFIER ::= { example 1 }
+
+-- A comment mentioning exampleIgnored OBJECT IDENTIFIER ::= { example 9 }
+exampleTemperature OBJECT-TYPE
+    SYNTAX      Integer32 (-50..150) -- inline comment
+    MAX-ACCESS  read-only
+    STATUS      current
+    DESCRIPTION "The temperature."
+    ::= { exampleObjects 1 }
+
+exampleTable OBJECT-TYPE
+    SYNTAX      SEQUENCE OF ExampleEntry
+    MAX-ACCESS  not-accessible
+    STATUS      current
+    DESCRIPTION "A table."
+    ::= { exampleObjects 2 }
+
+exampleEntry OBJECT-TYPE
+    SYNTAX      ExampleEntry
+    MAX-ACCESS  not-accessible
+    STATUS      current
+    DESCRIPTION "A row."
+    INDEX       { exampleIndex }
+    ::= { exampleTable 1 }
+
+exampleRoot OBJECT IDENTIFIER ::= { iso org(3) dod(6) internet(1) private(4) 98 }
+
+END
+"#;
+
+    fn mib() -> Mib {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(MODULE.as_bytes()).unwrap();
+        Mib::new(&[file.path().to_path_buf()]).unwrap()
+    }
+
+    #[test]
+    fn resolves_builtin_names() {
+        let mib = Mib::new(&[]).unwrap();
+        assert_eq!(
+            mib.resolve("sysUpTime.0").unwrap().to_string(),
+            "1.3.6.1.2.1.1.3.0"
+        );
+        assert_eq!(
+            mib.resolve("IF-MIB::ifHCInOctets").unwrap().to_string(),
+            "1.3.6.1.2.1.31.1.1.1.6"
+        );
+        assert_eq!(
+            mib.resolve(".1.3.6.1.2.1.1.5.0").unwrap().to_string(),
+            "1.3.6.1.2.1.1.5.0"
+        );
+        assert!(mib.resolve("ifUnknown").is_err());
+        assert!(mib.resolve("ifDescr.x").is_err());
+    }
+
+    #[test]
+    fn formats_identifiers() {
+        let mib = Mib::new(&[]).unwrap();
+        let format = |oid: &str| mib.format(&oid.parse().unwrap());
+        assert_eq!(format("1.3.6.1.2.1.2.2.1.10.3"), "ifInOctets.3");
+        assert_eq!(format("1.3.6.1.6.3.1.1.5.3"), "linkDown");
+        assert_eq!(format("1.3.6.1.4.1.9.9.13"), "enterprises.9.9.13");
+        assert_eq!(format("1.3.6.1.3.1"), "1.3.6.1.3.1");
+    }
+
+    #[test]
+    fn loads_modules() {
+        let mib = mib();
+        assert_eq!(
+            mib.resolve("EXAMPLE-MIB::exampleTemperature.0")
+                .unwrap()
+                .to_string(),
+            "1.3.6.1.4.1.99999.1.1.0"
+        );
+        assert_eq!(
+            mib.format(&"1.3.6.1.4.1.99999.1.2.1.4.7".parse().unwrap()),
+            "exampleEntry.4.7"
+        );
+        assert_eq!(
+            mib.resolve("exampleRoot").unwrap().to_string(),
+            "1.3.6.1.4.98"
+        );
+        assert!(mib.resolve("exampleIgnored").is_err());
+    }
+
+    #[test]
+    fn resolves_modules_out_of_order() {
+        let assignments = parse_module(
+            "child OBJECT IDENTIFIER ::= { parent 2 }\nparent OBJECT IDENTIFIER ::= { enterprises 5 }",
+        );
+        let mut mib = Mib::new(&[]).unwrap();
+        mib.resolve_assignments(assignments);
+        assert_eq!(mib.resolve("child").unwrap().to_string(), "1.3.6.1.4.1.5.2");
+    }
+}
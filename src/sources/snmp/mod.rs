@@ -0,0 +1,1001 @@
+use std::{
+    collections::{hash_map::Entry, BTreeMap, HashMap, HashSet},
+    net::{IpAddr, SocketAddr},
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use derivative::Derivative;
+use futures::{future::join_all, FutureExt, StreamExt, TryFutureExt};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use tokio::{net::UdpSocket, time};
+use tokio_stream::wrappers::IntervalStream;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, Output, Resource, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::{
+        metric::{Metric, MetricKind, MetricValue},
+        Event, LogEvent, Value,
+    },
+    internal_events::{
+        BytesReceived, CollectionCompleted, SnmpEventsReceived, SnmpPollError, SnmpSocketError,
+        SnmpTrapDecodeError, SnmpTrapUnauthorized, SocketEventsReceived, SocketMode,
+        StreamClosedError,
+    },
+    shutdown::ShutdownSignal,
+    udp, SourceSender,
+};
+
+mod ber;
+mod client;
+mod message;
+mod mib;
+mod usm;
+
+use ber::{Oid, Pdu, PduType, SnmpValue, VarBind};
+pub use client::ClientError;
+use client::{Client, Security};
+use message::Message;
+pub use message::MessageError;
+use mib::{Mib, MibError};
+use usm::{AuthProtocol, LocalizedKeys, PrivacyProtocol, User, UsmError};
+
+/// The largest UDP payload, which bounds the size of the messages.
+const MAX_DATAGRAM_LENGTH: usize = 65535;
+
+/// The shortest password accepted by the USM (RFC 3414, 11.2).
+const MIN_PASSWORD_LENGTH: usize = 8;
+
+/// The number of engines the keys of the users are localized to for SNMPv3 traps, beyond
+/// which they're localized again.
+const MAX_LOCALIZED_KEYS: usize = 1024;
+
+const DEFAULT_AGENT_PORT: u16 = 161;
+
+/// `sysUpTime.0`, the first variable of the notifications.
+const SYS_UP_TIME: &[u32] = &[1, 3, 6, 1, 2, 1, 1, 3, 0];
+
+/// `snmpTrapOID.0`, the second variable of the notifications.
+const SNMP_TRAP_OID: &[u32] = &[1, 3, 6, 1, 6, 3, 1, 1, 4, 1, 0];
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("At least one of `traps` or `poll` must be configured"))]
+    NothingToDo,
+    #[snafu(display("`poll.targets` must not be empty"))]
+    MissingTargets,
+    #[snafu(display("At least one of `poll.get` or `poll.walk` must be configured"))]
+    MissingObjects,
+    #[snafu(display("`poll.user` is required for SNMPv3"))]
+    MissingUser,
+    #[snafu(display("The {} password of user {:?} is missing", kind, user))]
+    MissingPassword { kind: &'static str, user: String },
+    #[snafu(display(
+        "The {} password of user {:?} must be at least {} characters long",
+        kind,
+        user,
+        MIN_PASSWORD_LENGTH
+    ))]
+    PasswordTooShort { kind: &'static str, user: String },
+    #[snafu(display("Invalid user {:?}: {}", user, source))]
+    InvalidUser { user: String, source: UsmError },
+    #[snafu(display("Failed to load MIB modules: {}", source))]
+    LoadMib { source: MibError },
+    #[snafu(display("Invalid object to poll: {}", source))]
+    InvalidObject { source: MibError },
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct SnmpConfig {
+    /// Receives the traps and informs of agents.
+    traps: Option<TrapsConfig>,
+    /// Polls the objects of agents periodically.
+    poll: Option<PollConfig>,
+    /// The MIB modules, or the directories of MIB modules, resolving the names of objects in
+    /// addition to the common ones.
+    #[serde(default)]
+    mib_paths: Vec<PathBuf>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TrapsConfig {
+    #[serde(default = "default_traps_address")]
+    address: SocketAddr,
+    receive_buffer_bytes: Option<usize>,
+    /// The communities of the SNMPv2c traps accepted, or any when empty.
+    #[serde(default)]
+    communities: Vec<String>,
+    /// The users of the SNMPv3 traps accepted.
+    #[serde(default)]
+    users: Vec<UserConfig>,
+}
+
+fn default_traps_address() -> SocketAddr {
+    SocketAddr::new([0, 0, 0, 0].into(), 162)
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct UserConfig {
+    name: String,
+    auth_protocol: Option<AuthProtocol>,
+    auth_password: Option<String>,
+    privacy_protocol: Option<PrivacyProtocol>,
+    privacy_password: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, PartialEq)]
+#[derivative(Default)]
+pub enum SnmpVersion {
+    #[derivative(Default)]
+    #[serde(rename = "2c")]
+    V2c,
+    #[serde(rename = "3")]
+    V3,
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PollEmit {
+    #[derivative(Default)]
+    Metrics,
+    Logs,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PollConfig {
+    /// The agents to poll, on port 161 unless specified.
+    targets: Vec<String>,
+    #[serde(default)]
+    version: SnmpVersion,
+    #[serde(default = "default_community")]
+    community: String,
+    user: Option<UserConfig>,
+    /// The variables to get, by name or object identifier.
+    #[serde(default)]
+    get: Vec<String>,
+    /// The subtrees to walk, by name or object identifier.
+    #[serde(default)]
+    walk: Vec<String>,
+    #[serde(default = "default_interval_secs")]
+    interval_secs: u64,
+    #[serde(default = "default_timeout_secs")]
+    timeout_secs: f64,
+    #[serde(default = "default_retries")]
+    retries: usize,
+    #[serde(default = "default_max_repetitions")]
+    max_repetitions: u32,
+    #[serde(default)]
+    emit: PollEmit,
+    #[serde(default = "default_namespace")]
+    namespace: String,
+}
+
+fn default_community() -> String {
+    "public".to_owned()
+}
+
+const fn default_interval_secs() -> u64 {
+    60
+}
+
+const fn default_timeout_secs() -> f64 {
+    5.0
+}
+
+const fn default_retries() -> usize {
+    1
+}
+
+const fn default_max_repetitions() -> u32 {
+    10
+}
+
+fn default_namespace() -> String {
+    "snmp".to_owned()
+}
+
+inventory::submit! {
+    SourceDescription::new::<SnmpConfig>("snmp")
+}
+
+impl GenerateConfig for SnmpConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"[traps]
+            address = "0.0.0.0:162""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "snmp")]
+impl SourceConfig for SnmpConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        if self.traps.is_none() && self.poll.is_none() {
+            return Err(BuildError::NothingToDo.into());
+        }
+        let mib = Arc::new(Mib::new(&self.mib_paths).context(LoadMibSnafu)?);
+
+        let mut tasks = Vec::new();
+        if let Some(traps) = &self.traps {
+            let receiver = TrapReceiver::new(traps, Arc::clone(&mib))?;
+            tasks.push(
+                receive_traps(traps.clone(), receiver, cx.shutdown.clone(), cx.out.clone()).boxed(),
+            );
+        }
+        if let Some(poll) = &self.poll {
+            let pollers = Poller::build_all(poll, &mib)?;
+            let interval = Duration::from_secs(poll.interval_secs);
+            tasks.push(poll_agents(pollers, interval, cx.shutdown.clone(), cx.out.clone()).boxed());
+        }
+
+        Ok(Box::pin(
+            futures::future::try_join_all(tasks).map_ok(|_| ()),
+        ))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::all())]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "snmp"
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        self.traps
+            .iter()
+            .map(|traps| Resource::udp(traps.address))
+            .collect()
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+impl UserConfig {
+    fn build(&self) -> crate::Result<User> {
+        let auth = password(self.auth_protocol, &self.auth_password, "auth", &self.name)?;
+        let privacy = password(
+            self.privacy_protocol,
+            &self.privacy_password,
+            "privacy",
+            &self.name,
+        )?;
+        Ok(User::new(self.name.clone(), auth, privacy)
+            .context(InvalidUserSnafu { user: &self.name })?)
+    }
+}
+
+fn password<'a, P>(
+    protocol: Option<P>,
+    password: &'a Option<String>,
+    kind: &'static str,
+    user: &str,
+) -> Result<Option<(P, &'a str)>, BuildError> {
+    match (protocol, password) {
+        (Some(_), None) => Err(BuildError::MissingPassword {
+            kind,
+            user: user.to_owned(),
+        }),
+        (Some(_), Some(password)) if password.chars().count() < MIN_PASSWORD_LENGTH => {
+            Err(BuildError::PasswordTooShort {
+                kind,
+                user: user.to_owned(),
+            })
+        }
+        (Some(protocol), Some(password)) => Ok(Some((protocol, password.as_str()))),
+        (None, _) => Ok(None),
+    }
+}
+
+/// Authenticates the traps of agents and converts them to events.
+struct TrapReceiver {
+    communities: HashSet<Vec<u8>>,
+    users: HashMap<String, User>,
+    /// The keys of the users localized to the engines of the agents, which are the
+    /// authoritative engines of the traps they send.
+    keys: HashMap<(String, Vec<u8>), LocalizedKeys>,
+    mib: Arc<Mib>,
+}
+
+impl TrapReceiver {
+    fn new(config: &TrapsConfig, mib: Arc<Mib>) -> crate::Result<Self> {
+        let users = config
+            .users
+            .iter()
+            .map(|user| Ok((user.name.clone(), user.build()?)))
+            .collect::<crate::Result<_>>()?;
+        Ok(Self {
+            communities: config
+                .communities
+                .iter()
+                .map(|community| community.as_bytes().to_vec())
+                .collect(),
+            users,
+            keys: HashMap::new(),
+            mib,
+        })
+    }
+
+    /// Converts a trap to an event, along with the response to send when it's an SNMPv2c
+    /// inform.
+    fn receive(
+        &mut self,
+        data: &[u8],
+        peer_addr: &SocketAddr,
+    ) -> Option<(LogEvent, Option<Vec<u8>>)> {
+        let message = match Message::decode(data) {
+            Ok(message) => message,
+            Err(error) => {
+                emit!(SnmpTrapDecodeError { error, peer_addr });
+                return None;
+            }
+        };
+
+        match message {
+            Message::V2c { community, pdu } => {
+                if !self.communities.is_empty() && !self.communities.contains(community) {
+                    emit!(SnmpTrapUnauthorized {
+                        reason: "unknown_community",
+                        peer_addr,
+                    });
+                    return None;
+                }
+                let response = (pdu.pdu_type == PduType::InformRequest).then(|| {
+                    let response = Pdu {
+                        pdu_type: PduType::Response,
+                        request_id: pdu.request_id,
+                        error_status: 0,
+                        error_index: 0,
+                        varbinds: pdu.varbinds.clone(),
+                    };
+                    Message::encode_v2c(community, &response)
+                });
+                let mut log = self.notification_to_log(pdu, peer_addr)?;
+                log.insert("version", "2c");
+                log.insert("community", String::from_utf8_lossy(community).into_owned());
+                Some((log, response))
+            }
+            Message::V3(message) => {
+                let user_name = String::from_utf8_lossy(message.usm.user_name).into_owned();
+                let user = match self.users.get(&user_name) {
+                    Some(user) => user,
+                    None => {
+                        emit!(SnmpTrapUnauthorized {
+                            reason: "unknown_user",
+                            peer_addr,
+                        });
+                        return None;
+                    }
+                };
+
+                if self.keys.len() >= MAX_LOCALIZED_KEYS {
+                    self.keys.clear();
+                }
+                let keys = match self
+                    .keys
+                    .entry((user_name.clone(), message.usm.engine_id.to_vec()))
+                {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => match user.localize(message.usm.engine_id) {
+                        Ok(keys) => entry.insert(keys),
+                        Err(source) => {
+                            emit!(SnmpTrapDecodeError {
+                                error: MessageError::Security { source },
+                                peer_addr,
+                            });
+                            return None;
+                        }
+                    },
+                };
+                let scoped_pdu = match message.open(keys) {
+                    Ok(scoped_pdu) => scoped_pdu,
+                    Err(error) => {
+                        emit!(SnmpTrapDecodeError { error, peer_addr });
+                        return None;
+                    }
+                };
+
+                // Acknowledging SNMPv3 informs requires the receiver to be their authoritative
+                // engine, which isn't supported.
+                let mut log = self.notification_to_log(scoped_pdu.pdu, peer_addr)?;
+                log.insert("version", "3");
+                log.insert("user", user_name);
+                Some((log, None))
+            }
+        }
+    }
+
+    fn notification_to_log(&self, pdu: Pdu, peer_addr: &SocketAddr) -> Option<LogEvent> {
+        let pdu_type = match pdu.pdu_type {
+            PduType::Trap => "trap",
+            PduType::InformRequest => "inform",
+            pdu_type => {
+                debug!(
+                    message = "Ignoring PDU that isn't a notification.",
+                    ?pdu_type,
+                    %peer_addr,
+                );
+                return None;
+            }
+        };
+
+        let mut log = LogEvent::default();
+        let mut variables = BTreeMap::new();
+        for VarBind { oid, value } in pdu.varbinds {
+            if oid.0 == SYS_UP_TIME {
+                log.insert("uptime", to_value(value, &self.mib));
+            } else if oid.0 == SNMP_TRAP_OID {
+                log.insert("trap_oid", to_value(value, &self.mib));
+            } else {
+                variables.insert(self.mib.format(&oid), to_value(value, &self.mib));
+            }
+        }
+        log.insert("variables", variables);
+        log.insert("pdu_type", pdu_type);
+        log.insert("request_id", pdu.request_id);
+        log.insert(log_schema().host_key(), peer_addr.ip().to_string());
+        log.insert(log_schema().source_type_key(), "snmp");
+        log.insert(log_schema().timestamp_key(), Utc::now());
+        Some(log)
+    }
+}
+
+async fn receive_traps(
+    config: TrapsConfig,
+    mut receiver: TrapReceiver,
+    mut shutdown: ShutdownSignal,
+    mut out: SourceSender,
+) -> Result<(), ()> {
+    let socket = UdpSocket::bind(&config.address)
+        .map_err(|error| emit!(SnmpSocketError::bind(error)))
+        .await?;
+
+    if let Some(receive_buffer_bytes) = config.receive_buffer_bytes {
+        if let Err(error) = udp::set_receive_buffer_size(&socket, receive_buffer_bytes) {
+            warn!(message = "Failed configuring receive buffer size on UDP socket.", %error);
+        }
+    }
+
+    info!(message = "Listening.", address = %config.address);
+
+    let mut buf = vec![0; MAX_DATAGRAM_LENGTH];
+    loop {
+        let (byte_size, peer_addr) = tokio::select! {
+            recv = socket.recv_from(&mut buf) => match recv {
+                Ok(recv) => recv,
+                Err(error) => {
+                    emit!(SnmpSocketError::read(error));
+                    continue;
+                }
+            },
+            _ = &mut shutdown => break,
+        };
+
+        emit!(BytesReceived {
+            byte_size,
+            protocol: "udp",
+        });
+
+        let (log, response) = match receiver.receive(&buf[..byte_size], &peer_addr) {
+            Some(received) => received,
+            None => continue,
+        };
+        if let Some(response) = response {
+            if let Err(error) = socket.send_to(&response, peer_addr).await {
+                warn!(message = "Failed to acknowledge inform.", %error, %peer_addr);
+            }
+        }
+
+        let event = Event::from(log);
+        emit!(SocketEventsReceived {
+            mode: SocketMode::Udp,
+            byte_size: event.size_of(),
+            count: 1,
+        });
+        if let Err(error) = out.send_event(event).await {
+            emit!(StreamClosedError { error, count: 1 });
+            return Err(());
+        }
+    }
+
+    Ok(())
+}
+
+/// Polls the objects of an agent, through a client kept across the polls.
+struct Poller {
+    target: String,
+    address: String,
+    version: SnmpVersion,
+    community: String,
+    user: Option<Arc<User>>,
+    get: Vec<Oid>,
+    walk: Vec<Oid>,
+    timeout: Duration,
+    retries: usize,
+    max_repetitions: u32,
+    emit: PollEmit,
+    namespace: Option<String>,
+    mib: Arc<Mib>,
+    client: Option<Client>,
+}
+
+impl Poller {
+    fn build_all(config: &PollConfig, mib: &Arc<Mib>) -> crate::Result<Vec<Self>> {
+        if config.targets.is_empty() {
+            return Err(BuildError::MissingTargets.into());
+        }
+        if config.get.is_empty() && config.walk.is_empty() {
+            return Err(BuildError::MissingObjects.into());
+        }
+        let user = match (config.version, &config.user) {
+            (SnmpVersion::V2c, _) => None,
+            (SnmpVersion::V3, Some(user)) => Some(Arc::new(user.build()?)),
+            (SnmpVersion::V3, None) => return Err(BuildError::MissingUser.into()),
+        };
+        let resolve = |names: &[String]| {
+            names
+                .iter()
+                .map(|name| mib.resolve(name))
+                .collect::<Result<Vec<_>, _>>()
+                .context(InvalidObjectSnafu)
+        };
+        let get = resolve(&config.get)?;
+        let walk = resolve(&config.walk)?;
+        let namespace = Some(config.namespace.clone()).filter(|namespace| !namespace.is_empty());
+
+        Ok(config
+            .targets
+            .iter()
+            .map(|target| Self {
+                target: target.clone(),
+                address: with_default_port(target),
+                version: config.version,
+                community: config.community.clone(),
+                user: user.clone(),
+                get: get.clone(),
+                walk: walk.clone(),
+                timeout: Duration::from_secs_f64(config.timeout_secs),
+                retries: config.retries,
+                max_repetitions: config.max_repetitions,
+                emit: config.emit,
+                namespace: namespace.clone(),
+                mib: Arc::clone(mib),
+                client: None,
+            })
+            .collect())
+    }
+
+    async fn collect(&mut self) -> Vec<Event> {
+        let varbinds = match self.poll().await {
+            Ok(varbinds) => varbinds,
+            Err(error) => {
+                emit!(SnmpPollError {
+                    error,
+                    target: &self.target,
+                });
+                // The target is resolved and its engine discovered again on the next poll.
+                self.client = None;
+                return Vec::new();
+            }
+        };
+
+        let timestamp = Utc::now();
+        let events: Vec<Event> = match self.emit {
+            PollEmit::Metrics => varbinds
+                .into_iter()
+                .filter_map(|varbind| self.to_metric(varbind, timestamp))
+                .map(Event::from)
+                .collect(),
+            PollEmit::Logs => vec![self.to_log(varbinds, timestamp).into()],
+        };
+        emit!(SnmpEventsReceived {
+            count: events.len(),
+            byte_size: events.size_of(),
+            target: &self.target,
+        });
+        events
+    }
+
+    async fn poll(&mut self) -> Result<Vec<VarBind>, ClientError> {
+        if self.client.is_none() {
+            let security = match &self.user {
+                Some(user) => Security::V3 {
+                    user: Arc::clone(user),
+                },
+                None => Security::V2c {
+                    community: self.community.as_bytes().to_vec(),
+                },
+            };
+            let client =
+                Client::connect(&self.address, security, self.timeout, self.retries).await?;
+            self.client = Some(client);
+        }
+        let client = self.client.as_mut().expect("connected above");
+
+        let mut varbinds = if self.get.is_empty() {
+            Vec::new()
+        } else {
+            client.get(&self.get).await?
+        };
+        for root in &self.walk {
+            varbinds.extend(client.walk(root, self.max_repetitions).await?);
+        }
+        Ok(varbinds)
+    }
+
+    /// Converts a variable to a metric named after its object, with the index of the instance
+    /// as a tag. Counters are converted to counters and other numbers to gauges.
+    fn to_metric(&self, varbind: VarBind, timestamp: DateTime<Utc>) -> Option<Metric> {
+        let value = match varbind.value {
+            SnmpValue::Counter32(value) => MetricValue::Counter {
+                value: value as f64,
+            },
+            SnmpValue::Counter64(value) => MetricValue::Counter {
+                value: value as f64,
+            },
+            SnmpValue::Integer(value) => MetricValue::Gauge {
+                value: value as f64,
+            },
+            SnmpValue::Gauge32(value) | SnmpValue::TimeTicks(value) => MetricValue::Gauge {
+                value: value as f64,
+            },
+            _ => return None,
+        };
+
+        let mut tags = BTreeMap::new();
+        tags.insert("host".to_owned(), self.target.clone());
+        tags.insert("oid".to_owned(), varbind.oid.to_string());
+        let name = match self.mib.lookup(&varbind.oid) {
+            Some((name, index)) => {
+                if !index.is_empty() {
+                    let index = index
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(".");
+                    tags.insert("index".to_owned(), index);
+                }
+                name.to_owned()
+            }
+            None => varbind.oid.to_string(),
+        };
+
+        Some(
+            Metric::new(name, MetricKind::Absolute, value)
+                .with_namespace(self.namespace.clone())
+                .with_tags(Some(tags))
+                .with_timestamp(Some(timestamp)),
+        )
+    }
+
+    /// Converts the variables of a poll to an event, keyed by the names of their instances.
+    fn to_log(&self, varbinds: Vec<VarBind>, timestamp: DateTime<Utc>) -> LogEvent {
+        let variables = varbinds
+            .into_iter()
+            .filter(|varbind| !varbind.value.is_exception())
+            .map(|varbind| {
+                (
+                    self.mib.format(&varbind.oid),
+                    to_value(varbind.value, &self.mib),
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let mut log = LogEvent::default();
+        log.insert("variables", variables);
+        log.insert(
+            "version",
+            match self.version {
+                SnmpVersion::V2c => "2c",
+                SnmpVersion::V3 => "3",
+            },
+        );
+        log.insert(log_schema().host_key(), self.target.clone());
+        log.insert(log_schema().source_type_key(), "snmp");
+        log.insert(log_schema().timestamp_key(), timestamp);
+        log
+    }
+}
+
+async fn poll_agents(
+    mut pollers: Vec<Poller>,
+    interval: Duration,
+    shutdown: ShutdownSignal,
+    mut out: SourceSender,
+) -> Result<(), ()> {
+    let mut interval = IntervalStream::new(time::interval(interval)).take_until(shutdown);
+    while interval.next().await.is_some() {
+        let start = Instant::now();
+        let events = join_all(pollers.iter_mut().map(|poller| poller.collect())).await;
+        emit!(CollectionCompleted {
+            start,
+            end: Instant::now()
+        });
+
+        let events = events.into_iter().flatten().collect::<Vec<_>>();
+        let count = events.len();
+        if count == 0 {
+            continue;
+        }
+        if let Err(error) = out.send_batch(events).await {
+            emit!(StreamClosedError { error, count });
+            return Err(());
+        }
+    }
+
+    Ok(())
+}
+
+/// Appends the default port of the agents to the targets lacking one.
+fn with_default_port(target: &str) -> String {
+    if target.parse::<SocketAddr>().is_ok() {
+        target.to_owned()
+    } else if let Ok(ip) = target.parse::<IpAddr>() {
+        SocketAddr::new(ip, DEFAULT_AGENT_PORT).to_string()
+    } else if target.contains(':') {
+        target.to_owned()
+    } else {
+        format!("{}:{}", target, DEFAULT_AGENT_PORT)
+    }
+}
+
+/// Converts the value of a variable to the value of an event. Octet strings are converted to
+/// strings when they're printable, and hex encoded otherwise.
+fn to_value(value: SnmpValue, mib: &Mib) -> Value {
+    match value {
+        SnmpValue::Integer(value) => value.into(),
+        SnmpValue::OctetString(bytes) => match String::from_utf8(bytes) {
+            Ok(string)
+                if string
+                    .chars()
+                    .all(|c| !c.is_control() || c.is_ascii_whitespace()) =>
+            {
+                string.into()
+            }
+            Ok(string) => hex::encode(string).into(),
+            Err(error) => hex::encode(error.into_bytes()).into(),
+        },
+        SnmpValue::ObjectIdentifier(oid) => mib.format(&oid).into(),
+        SnmpValue::IpAddress(address) => IpAddr::from(address).to_string().into(),
+        SnmpValue::Counter32(value) | SnmpValue::Gauge32(value) | SnmpValue::TimeTicks(value) => {
+            i64::from(value).into()
+        }
+        SnmpValue::Counter64(value) => {
+            i64::try_from(value).map_or_else(|_| Value::from(value as f64), Value::from)
+        }
+        SnmpValue::Opaque(bytes) => hex::encode(bytes).into(),
+        SnmpValue::Null
+        | SnmpValue::NoSuchObject
+        | SnmpValue::NoSuchInstance
+        | SnmpValue::EndOfMibView => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{
+        collect_n,
+        components::{assert_source_compliance, SOURCE_TAGS},
+        next_addr,
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SnmpConfig>();
+    }
+
+    fn varbind(oid: &str, value: SnmpValue) -> VarBind {
+        VarBind {
+            oid: oid.parse().unwrap(),
+            value,
+        }
+    }
+
+    fn link_down(pdu_type: PduType) -> Pdu {
+        Pdu {
+            pdu_type,
+            request_id: 42,
+            error_status: 0,
+            error_index: 0,
+            varbinds: vec![
+                varbind("1.3.6.1.2.1.1.3.0", SnmpValue::TimeTicks(12345)),
+                varbind(
+                    "1.3.6.1.6.3.1.1.4.1.0",
+                    SnmpValue::ObjectIdentifier("1.3.6.1.6.3.1.1.5.3".parse().unwrap()),
+                ),
+                varbind("1.3.6.1.2.1.2.2.1.1.2", SnmpValue::Integer(2)),
+                varbind(
+                    "1.3.6.1.2.1.2.2.1.2.2",
+                    SnmpValue::OctetString(b"eth0".to_vec()),
+                ),
+            ],
+        }
+    }
+
+    fn traps_config(address: SocketAddr) -> TrapsConfig {
+        TrapsConfig {
+            address,
+            receive_buffer_bytes: None,
+            communities: vec!["public".to_owned()],
+            users: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn receives_traps() {
+        assert_source_compliance(&SOURCE_TAGS, async {
+            let address = next_addr();
+            let (tx, rx) = SourceSender::new_test();
+            let config = SnmpConfig {
+                traps: Some(traps_config(address)),
+                ..SnmpConfig::default()
+            };
+            let source = config
+                .build(SourceContext::new_test(tx, None))
+                .await
+                .unwrap();
+            tokio::spawn(source);
+            tokio::time::sleep(Duration::from_millis(100)).await;
+
+            let socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+            let trap = Message::encode_v2c(b"public", &link_down(PduType::Trap));
+            socket.send_to(&trap, address).unwrap();
+
+            let events = collect_n(rx, 1).await;
+            let log = events[0].as_log();
+            assert_eq!(log["version"], "2c".into());
+            assert_eq!(log["community"], "public".into());
+            assert_eq!(log["pdu_type"], "trap".into());
+            assert_eq!(log["trap_oid"], "linkDown".into());
+            assert_eq!(log["uptime"], 12345.into());
+            assert_eq!(log["variables.\"ifIndex.2\""], 2.into());
+            assert_eq!(log["variables.\"ifDescr.2\""], "eth0".into());
+            assert_eq!(log[log_schema().host_key()], "127.0.0.1".into());
+            assert_eq!(log[log_schema().source_type_key()], "snmp".into());
+        })
+        .await;
+    }
+
+    #[test]
+    fn acknowledges_informs() {
+        let mib = Arc::new(Mib::new(&[]).unwrap());
+        let mut receiver = TrapReceiver::new(&traps_config(next_addr()), mib).unwrap();
+        let peer_addr = "127.0.0.1:10162".parse().unwrap();
+
+        let inform = Message::encode_v2c(b"public", &link_down(PduType::InformRequest));
+        let (log, response) = receiver.receive(&inform, &peer_addr).unwrap();
+        assert_eq!(log["pdu_type"], "inform".into());
+
+        let response = response.unwrap();
+        match Message::decode(&response).unwrap() {
+            Message::V2c { community, pdu } => {
+                assert_eq!(community, b"public");
+                assert_eq!(pdu.pdu_type, PduType::Response);
+                assert_eq!(pdu.request_id, 42);
+                assert_eq!(pdu.varbinds, link_down(PduType::InformRequest).varbinds);
+            }
+            message => panic!("unexpected message {:?}", message),
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_communities() {
+        let mib = Arc::new(Mib::new(&[]).unwrap());
+        let mut receiver = TrapReceiver::new(&traps_config(next_addr()), mib).unwrap();
+        let peer_addr = "127.0.0.1:10162".parse().unwrap();
+
+        let trap = Message::encode_v2c(b"private", &link_down(PduType::Trap));
+        assert!(receiver.receive(&trap, &peer_addr).is_none());
+    }
+
+    #[test]
+    fn converts_variables_to_metrics() {
+        let config: PollConfig = toml::from_str(
+            r#"
+            targets = ["192.0.2.1"]
+            walk = ["IF-MIB::ifTable"]
+            "#,
+        )
+        .unwrap();
+        let mib = Arc::new(Mib::new(&[]).unwrap());
+        let pollers = Poller::build_all(&config, &mib).unwrap();
+        assert_eq!(pollers[0].address, "192.0.2.1:161");
+
+        let timestamp = Utc::now();
+        let metric = pollers[0]
+            .to_metric(
+                varbind("1.3.6.1.2.1.2.2.1.10.3", SnmpValue::Counter32(1000)),
+                timestamp,
+            )
+            .unwrap();
+        assert_eq!(metric.name(), "ifInOctets");
+        assert_eq!(metric.namespace(), Some("snmp"));
+        assert_eq!(metric.value(), &MetricValue::Counter { value: 1000.0 });
+        let tags = metric.tags().unwrap();
+        assert_eq!(tags["host"], "192.0.2.1");
+        assert_eq!(tags["index"], "3");
+        assert_eq!(tags["oid"], "1.3.6.1.2.1.2.2.1.10.3");
+
+        let metric = pollers[0]
+            .to_metric(
+                varbind("1.3.6.1.2.1.2.2.1.5.3", SnmpValue::Gauge32(100_000_000)),
+                timestamp,
+            )
+            .unwrap();
+        assert_eq!(metric.value(), &MetricValue::Gauge { value: 1e8 });
+
+        assert!(pollers[0]
+            .to_metric(
+                varbind(
+                    "1.3.6.1.2.1.2.2.1.2.3",
+                    SnmpValue::OctetString(b"eth2".to_vec())
+                ),
+                timestamp,
+            )
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn polls_agents() {
+        let agent = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let address = agent.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buf = vec![0; MAX_DATAGRAM_LENGTH];
+            loop {
+                let (length, peer_addr) = agent.recv_from(&mut buf).await.unwrap();
+                let mut pdu = match Message::decode(&buf[..length]).unwrap() {
+                    Message::V2c { pdu, .. } => pdu,
+                    Message::V3(_) => unreachable!(),
+                };
+                pdu.pdu_type = PduType::Response;
+                pdu.varbinds[0].value = SnmpValue::TimeTicks(500);
+                let response = Message::encode_v2c(b"public", &pdu);
+                agent.send_to(&response, peer_addr).await.unwrap();
+            }
+        });
+
+        let config: PollConfig = toml::from_str(&format!(
+            r#"
+            targets = ["{}"]
+            get = ["sysUpTime.0"]
+            "#,
+            address
+        ))
+        .unwrap();
+        let mib = Arc::new(Mib::new(&[]).unwrap());
+        let mut pollers = Poller::build_all(&config, &mib).unwrap();
+        let events = pollers[0].collect().await;
+        assert_eq!(events.len(), 1);
+
+        let metric = events[0].as_metric();
+        assert_eq!(metric.name(), "sysUpTime");
+        assert_eq!(metric.value(), &MetricValue::Gauge { value: 500.0 });
+        assert_eq!(metric.tags().unwrap()["index"], "0");
+    }
+
+    #[tokio::test]
+    async fn requires_traps_or_poll() {
+        let (tx, _rx) = SourceSender::new_test();
+        assert!(SnmpConfig::default()
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,385 @@
+//! The User-based Security Model of SNMPv3 (RFC 3414), with the HMAC-SHA-2 authentication
+//! protocols of RFC 7860 and the AES privacy protocol of RFC 3826.
+
+use openssl::{
+    error::ErrorStack,
+    hash::{Hasher, MessageDigest},
+    pkey::PKey,
+    sign::Signer,
+    symm::{Cipher, Crypter, Mode},
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+
+/// The length of the passwords expanded into keys.
+const PASSWORD_EXPANSION_LENGTH: usize = 1_048_576;
+
+#[derive(Debug, Snafu)]
+pub enum UsmError {
+    #[snafu(display("Cryptographic operation failed: {}", source))]
+    Crypto { source: ErrorStack },
+    #[snafu(display("The digest of the message doesn't match"))]
+    WrongDigest,
+    #[snafu(display("Invalid privacy parameters"))]
+    InvalidPrivacyParameters,
+    #[snafu(display("Privacy requires authentication"))]
+    PrivacyWithoutAuth,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthProtocol {
+    Md5,
+    Sha,
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl AuthProtocol {
+    fn digest(self) -> MessageDigest {
+        match self {
+            AuthProtocol::Md5 => MessageDigest::md5(),
+            AuthProtocol::Sha => MessageDigest::sha1(),
+            AuthProtocol::Sha224 => MessageDigest::sha224(),
+            AuthProtocol::Sha256 => MessageDigest::sha256(),
+            AuthProtocol::Sha384 => MessageDigest::sha384(),
+            AuthProtocol::Sha512 => MessageDigest::sha512(),
+        }
+    }
+
+    /// The length of the truncated digests of the messages.
+    pub(super) const fn parameters_length(self) -> usize {
+        match self {
+            AuthProtocol::Md5 | AuthProtocol::Sha => 12,
+            AuthProtocol::Sha224 => 16,
+            AuthProtocol::Sha256 => 24,
+            AuthProtocol::Sha384 => 32,
+            AuthProtocol::Sha512 => 48,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PrivacyProtocol {
+    Des,
+    Aes,
+}
+
+/// The keys of a user, derived from its passwords, which are localized to each engine.
+#[derive(Clone, Debug)]
+pub(super) struct User {
+    pub(super) name: String,
+    auth: Option<(AuthProtocol, Vec<u8>)>,
+    privacy: Option<(PrivacyProtocol, Vec<u8>)>,
+}
+
+impl User {
+    /// Derives the keys of a user. The privacy key is derived with the hash function of the
+    /// authentication protocol, so privacy requires authentication.
+    pub(super) fn new(
+        name: String,
+        auth: Option<(AuthProtocol, &str)>,
+        privacy: Option<(PrivacyProtocol, &str)>,
+    ) -> Result<Self, UsmError> {
+        let auth_protocol = auth.map(|(protocol, _)| protocol);
+        let auth = auth
+            .map(|(protocol, password)| {
+                password_to_key(protocol, password).map(|key| (protocol, key))
+            })
+            .transpose()?;
+        let privacy = match (privacy, auth_protocol) {
+            (Some((protocol, password)), Some(auth_protocol)) => {
+                Some((protocol, password_to_key(auth_protocol, password)?))
+            }
+            (Some(_), None) => return Err(UsmError::PrivacyWithoutAuth),
+            (None, _) => None,
+        };
+        Ok(Self {
+            name,
+            auth,
+            privacy,
+        })
+    }
+
+    /// Localizes the keys of the user to an engine.
+    pub(super) fn localize(&self, engine_id: &[u8]) -> Result<LocalizedKeys, UsmError> {
+        let auth = self
+            .auth
+            .as_ref()
+            .map(|(protocol, key)| localize(*protocol, key, engine_id).map(|key| (*protocol, key)))
+            .transpose()?;
+        let privacy = match (&self.privacy, &auth) {
+            (Some((protocol, key)), Some((auth_protocol, _))) => {
+                Some((*protocol, localize(*auth_protocol, key, engine_id)?))
+            }
+            _ => None,
+        };
+        Ok(LocalizedKeys { auth, privacy })
+    }
+}
+
+/// The keys of a user localized to an engine.
+#[derive(Clone, Debug)]
+pub(super) struct LocalizedKeys {
+    auth: Option<(AuthProtocol, Vec<u8>)>,
+    privacy: Option<(PrivacyProtocol, Vec<u8>)>,
+}
+
+impl LocalizedKeys {
+    /// The length of the authentication parameters of the messages, which are empty without
+    /// authentication.
+    pub(super) fn auth_parameters_length(&self) -> usize {
+        self.auth
+            .as_ref()
+            .map_or(0, |(protocol, _)| protocol.parameters_length())
+    }
+
+    pub(super) const fn has_auth(&self) -> bool {
+        self.auth.is_some()
+    }
+
+    pub(super) const fn has_privacy(&self) -> bool {
+        self.privacy.is_some()
+    }
+
+    /// Computes the authentication parameters of a message, whose own parameters are zeroed.
+    pub(super) fn sign(&self, message: &[u8]) -> Result<Vec<u8>, UsmError> {
+        match &self.auth {
+            Some((protocol, key)) => {
+                let key = PKey::hmac(key).context(CryptoSnafu)?;
+                let mut signer = Signer::new(protocol.digest(), &key).context(CryptoSnafu)?;
+                signer.update(message).context(CryptoSnafu)?;
+                let mut digest = signer.sign_to_vec().context(CryptoSnafu)?;
+                digest.truncate(protocol.parameters_length());
+                Ok(digest)
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Verifies the authentication parameters of a message, at `offset`.
+    pub(super) fn verify(&self, message: &[u8], offset: usize) -> Result<(), UsmError> {
+        let length = self.auth_parameters_length();
+        let expected = message
+            .get(offset..offset + length)
+            .ok_or(UsmError::WrongDigest)?;
+
+        let mut zeroed = message.to_vec();
+        zeroed[offset..offset + length].fill(0);
+        let digest = self.sign(&zeroed)?;
+        if digest.len() == expected.len() && openssl::memcmp::eq(&digest, expected) {
+            Ok(())
+        } else {
+            Err(UsmError::WrongDigest)
+        }
+    }
+
+    /// Encrypts a scoped PDU, returning it along with the privacy parameters.
+    pub(super) fn encrypt(
+        &self,
+        plaintext: &[u8],
+        engine_boots: u32,
+        engine_time: u32,
+        salt: u64,
+    ) -> Result<(Vec<u8>, Vec<u8>), UsmError> {
+        match &self.privacy {
+            Some((PrivacyProtocol::Des, key)) => {
+                let salt = ((engine_boots as u64) << 32 | (salt & 0xffff_ffff)).to_be_bytes();
+                let iv = des_iv(key, &salt);
+                // The plaintext is padded up to the block size, with arbitrary bytes.
+                let mut plaintext = plaintext.to_vec();
+                plaintext.resize((plaintext.len() + 7) / 8 * 8, 0);
+                let ciphertext =
+                    crypt(Cipher::des_cbc(), Mode::Encrypt, &key[..8], &iv, &plaintext)?;
+                Ok((ciphertext, salt.to_vec()))
+            }
+            Some((PrivacyProtocol::Aes, key)) => {
+                let salt = salt.to_be_bytes();
+                let iv = aes_iv(engine_boots, engine_time, &salt);
+                let ciphertext = crypt(
+                    Cipher::aes_128_cfb128(),
+                    Mode::Encrypt,
+                    &key[..16],
+                    &iv,
+                    plaintext,
+                )?;
+                Ok((ciphertext, salt.to_vec()))
+            }
+            None => Ok((plaintext.to_vec(), Vec::new())),
+        }
+    }
+
+    /// Decrypts a scoped PDU. Any padding is left after its encoding.
+    pub(super) fn decrypt(
+        &self,
+        ciphertext: &[u8],
+        engine_boots: u32,
+        engine_time: u32,
+        parameters: &[u8],
+    ) -> Result<Vec<u8>, UsmError> {
+        let salt =
+            <[u8; 8]>::try_from(parameters).map_err(|_| UsmError::InvalidPrivacyParameters)?;
+        match &self.privacy {
+            Some((PrivacyProtocol::Des, key)) => {
+                if ciphertext.len() % 8 != 0 {
+                    return Err(UsmError::InvalidPrivacyParameters);
+                }
+                let iv = des_iv(key, &salt);
+                crypt(Cipher::des_cbc(), Mode::Decrypt, &key[..8], &iv, ciphertext)
+            }
+            Some((PrivacyProtocol::Aes, key)) => {
+                let iv = aes_iv(engine_boots, engine_time, &salt);
+                crypt(
+                    Cipher::aes_128_cfb128(),
+                    Mode::Decrypt,
+                    &key[..16],
+                    &iv,
+                    ciphertext,
+                )
+            }
+            None => Ok(ciphertext.to_vec()),
+        }
+    }
+}
+
+/// Expands a password into a key (RFC 3414, A.2).
+fn password_to_key(protocol: AuthProtocol, password: &str) -> Result<Vec<u8>, UsmError> {
+    let password = password.as_bytes();
+    let mut hasher = Hasher::new(protocol.digest()).context(CryptoSnafu)?;
+    if !password.is_empty() {
+        let mut block = [0; 64];
+        let mut index = 0;
+        for _ in 0..PASSWORD_EXPANSION_LENGTH / block.len() {
+            for byte in block.iter_mut() {
+                *byte = password[index % password.len()];
+                index += 1;
+            }
+            hasher.update(&block).context(CryptoSnafu)?;
+        }
+    }
+    Ok(hasher.finish().context(CryptoSnafu)?.to_vec())
+}
+
+fn localize(protocol: AuthProtocol, key: &[u8], engine_id: &[u8]) -> Result<Vec<u8>, UsmError> {
+    let mut hasher = Hasher::new(protocol.digest()).context(CryptoSnafu)?;
+    hasher.update(key).context(CryptoSnafu)?;
+    hasher.update(engine_id).context(CryptoSnafu)?;
+    hasher.update(key).context(CryptoSnafu)?;
+    Ok(hasher.finish().context(CryptoSnafu)?.to_vec())
+}
+
+/// The initialization vector of DES is the pre-IV, the second half of the key, XORed with the
+/// salt.
+fn des_iv(key: &[u8], salt: &[u8; 8]) -> [u8; 8] {
+    let mut iv = [0; 8];
+    for (index, byte) in iv.iter_mut().enumerate() {
+        *byte = key[8 + index] ^ salt[index];
+    }
+    iv
+}
+
+fn aes_iv(engine_boots: u32, engine_time: u32, salt: &[u8; 8]) -> [u8; 16] {
+    let mut iv = [0; 16];
+    iv[..4].copy_from_slice(&engine_boots.to_be_bytes());
+    iv[4..8].copy_from_slice(&engine_time.to_be_bytes());
+    iv[8..].copy_from_slice(salt);
+    iv
+}
+
+fn crypt(
+    cipher: Cipher,
+    mode: Mode,
+    key: &[u8],
+    iv: &[u8],
+    data: &[u8],
+) -> Result<Vec<u8>, UsmError> {
+    let mut crypter = Crypter::new(cipher, mode, key, Some(iv)).context(CryptoSnafu)?;
+    crypter.pad(false);
+    let mut out = vec![0; data.len() + cipher.block_size()];
+    let count = crypter.update(data, &mut out).context(CryptoSnafu)?;
+    let rest = crypter.finalize(&mut out[count..]).context(CryptoSnafu)?;
+    out.truncate(count + rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENGINE_ID: [u8; 12] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2];
+
+    // The keys of the password "maplesyrup" localized to the engine of RFC 3414, A.3.
+    #[test]
+    fn localizes_md5_keys() {
+        let key = password_to_key(AuthProtocol::Md5, "maplesyrup").unwrap();
+        let key = localize(AuthProtocol::Md5, &key, &ENGINE_ID).unwrap();
+        assert_eq!(hex::encode(key), "526f5eed9fcce26f8964c2930787d82b");
+    }
+
+    #[test]
+    fn localizes_sha_keys() {
+        let key = password_to_key(AuthProtocol::Sha, "maplesyrup").unwrap();
+        let key = localize(AuthProtocol::Sha, &key, &ENGINE_ID).unwrap();
+        assert_eq!(hex::encode(key), "6695febc9288e36282235fc7151f128497b38f3f");
+    }
+
+    #[test]
+    fn signs_and_verifies() {
+        let user = User::new(
+            "user".to_owned(),
+            Some((AuthProtocol::Sha256, "authpassword")),
+            None,
+        )
+        .unwrap();
+        let keys = user.localize(&ENGINE_ID).unwrap();
+
+        let mut message = b"header".to_vec();
+        let offset = message.len();
+        message.extend_from_slice(&[0; 24]);
+        message.extend_from_slice(b"trailer");
+
+        let digest = keys.sign(&message).unwrap();
+        assert_eq!(digest.len(), 24);
+        message[offset..offset + 24].copy_from_slice(&digest);
+        keys.verify(&message, offset).unwrap();
+
+        message[0] = b'H';
+        assert!(matches!(
+            keys.verify(&message, offset),
+            Err(UsmError::WrongDigest)
+        ));
+    }
+
+    #[test]
+    fn encrypts_and_decrypts() {
+        for protocol in [PrivacyProtocol::Des, PrivacyProtocol::Aes] {
+            let user = User::new(
+                "user".to_owned(),
+                Some((AuthProtocol::Md5, "authpassword")),
+                Some((protocol, "privpassword")),
+            )
+            .unwrap();
+            let keys = user.localize(&ENGINE_ID).unwrap();
+
+            let plaintext = b"a scoped pdu of some length".to_vec();
+            let (ciphertext, parameters) = keys.encrypt(&plaintext, 3, 1000, 42).unwrap();
+            assert_ne!(ciphertext[..plaintext.len()], plaintext[..]);
+            let decrypted = keys.decrypt(&ciphertext, 3, 1000, &parameters).unwrap();
+            assert_eq!(&decrypted[..plaintext.len()], &plaintext[..]);
+        }
+    }
+
+    #[test]
+    fn requires_auth_for_privacy() {
+        let error = User::new(
+            "user".to_owned(),
+            None,
+            Some((PrivacyProtocol::Aes, "privpassword")),
+        )
+        .unwrap_err();
+        assert!(matches!(error, UsmError::PrivacyWithoutAuth));
+    }
+}
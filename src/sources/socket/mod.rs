@@ -11,8 +11,8 @@ use crate::serde::default_framing_message_based;
 use crate::{
     codecs::DecodingConfig,
     config::{
-        log_schema, DataType, GenerateConfig, Output, Resource, SourceConfig, SourceContext,
-        SourceDescription,
+        log_schema, Capability, DataType, GenerateConfig, Output, Resource, SourceConfig,
+        SourceContext, SourceDescription,
     },
     sources::util::TcpSource,
     tls::MaybeTlsSettings,
@@ -128,7 +128,7 @@ impl SourceConfig for SocketConfig {
                     tls,
                     config.receive_buffer_bytes(),
                     cx,
-                    false.into(),
+                    config.acknowledgements(),
                     config.connection_limit,
                 )
             }
@@ -223,8 +223,22 @@ impl SourceConfig for SocketConfig {
         }
     }
 
+    fn capabilities(&self) -> Vec<Capability> {
+        match &self.mode {
+            #[cfg(unix)]
+            Mode::UnixDatagram(config) | Mode::UnixStream(config) => {
+                vec![Capability::path(&config.path)]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn supports_sandbox(&self) -> bool {
+        true
+    }
+
     fn can_acknowledge(&self) -> bool {
-        false
+        matches!(self.mode, Mode::Tcp(_))
     }
 }
 
@@ -246,6 +260,8 @@ mod test {
     use codecs::{decoding::CharacterDelimitedDecoderOptions, CharacterDelimitedDecoderConfig};
     use futures::{stream, StreamExt};
     use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpStream,
         task::JoinHandle,
         time::{timeout, Duration, Instant},
     };
@@ -257,7 +273,6 @@ mod test {
         std::os::unix::fs::PermissionsExt,
         std::path::PathBuf,
         tokio::{
-            io::AsyncWriteExt,
             net::{UnixDatagram, UnixStream},
             task::yield_now,
         },
@@ -269,7 +284,7 @@ mod test {
         config::{
             log_schema, ComponentKey, GlobalOptions, SinkContext, SourceConfig, SourceContext,
         },
-        event::Event,
+        event::{Event, EventStatus},
         shutdown::{ShutdownSignal, SourceShutdownCoordinator},
         sinks::util::tcp::TcpSinkConfig,
         test_util::{
@@ -337,6 +352,34 @@ mod test {
         assert_eq!(events[1].as_log()[log_schema().message_key()], "bar".into());
     }
 
+    #[tokio::test]
+    async fn tcp_closes_connection_on_rejected_events() {
+        let (tx, mut rx) = SourceSender::new_test_finalize(EventStatus::Rejected);
+        let addr = next_addr();
+
+        let mut config = TcpConfig::from_address(addr.into());
+        config.set_acknowledgements(true.into());
+        let server = SocketConfig::from(config)
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .unwrap();
+        tokio::spawn(server);
+
+        wait_for_tcp(addr).await;
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(b"foo\nbar\n").await.unwrap();
+
+        let event = rx.next().await.unwrap();
+        assert_eq!(event.as_log()[log_schema().message_key()], "foo".into());
+
+        // The source closes the connection instead of reading lines it can't deliver.
+        let mut buf = [0u8; 1];
+        let read = timeout(Duration::from_secs(5), stream.read(&mut buf))
+            .await
+            .expect("connection not closed");
+        assert!(matches!(read, Ok(0) | Err(_)));
+    }
+
     #[tokio::test]
     async fn tcp_it_includes_source_type() {
         assert_source_compliance(&SOCKET_HIGH_CARDINALITY_PUSH_SOURCE_TAGS, async {
@@ -6,12 +6,12 @@ use smallvec::SmallVec;
 
 use crate::{
     codecs::Decoder,
-    config::log_schema,
+    config::{log_schema, AcknowledgementsConfig},
     event::Event,
-    serde::default_decoding,
+    serde::{bool_or_struct, default_decoding},
     sources::util::{SocketListenAddr, TcpNullAcker, TcpSource},
     tcp::TcpKeepaliveConfig,
-    tls::TlsEnableableConfig,
+    tls::{CertificateMetadata, TlsEnableableConfig},
 };
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -29,6 +29,8 @@ pub struct TcpConfig {
     #[serde(default = "default_decoding")]
     decoding: DeserializerConfig,
     pub connection_limit: Option<u32>,
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
 }
 
 const fn default_shutdown_timeout_secs() -> u64 {
@@ -49,6 +51,7 @@ impl TcpConfig {
             framing: None,
             decoding: default_decoding(),
             connection_limit: None,
+            acknowledgements: Default::default(),
         }
     }
 
@@ -88,6 +91,10 @@ impl TcpConfig {
         self.receive_buffer_bytes
     }
 
+    pub const fn acknowledgements(&self) -> AcknowledgementsConfig {
+        self.acknowledgements
+    }
+
     pub fn set_max_length(&mut self, val: Option<usize>) -> &mut Self {
         self.max_length = val;
         self
@@ -112,6 +119,11 @@ impl TcpConfig {
         self.decoding = val;
         self
     }
+
+    pub fn set_acknowledgements(&mut self, val: AcknowledgementsConfig) -> &mut Self {
+        self.acknowledgements = val;
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -136,7 +148,12 @@ impl TcpSource for RawTcpSource {
         self.decoder.clone()
     }
 
-    fn handle_events(&self, events: &mut [Event], host: std::net::SocketAddr) {
+    fn handle_events(
+        &self,
+        events: &mut [Event],
+        host: std::net::SocketAddr,
+        _certificate: Option<&CertificateMetadata>,
+    ) {
         let now = Utc::now();
 
         for event in events {
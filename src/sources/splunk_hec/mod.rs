@@ -1065,6 +1065,7 @@ mod tests {
             request: TowerRequestConfig::default(),
             tls: None,
             acknowledgements: Default::default(),
+            token_rotation: None,
             timestamp_nanos_key: None,
         }
         .build(SinkContext::new_test())
@@ -14,10 +14,14 @@ use vector_core::ByteSizeOf;
 
 use crate::{
     codecs::DecodingConfig,
-    config::{log_schema, Output, Resource, SourceConfig, SourceContext, SourceDescription},
+    config::{
+        log_schema, AcknowledgementsConfig, Output, Resource, SourceConfig, SourceContext,
+        SourceDescription,
+    },
     internal_events::{BytesReceived, OldEventsReceived, StreamClosedError},
-    serde::default_decoding,
+    serde::{bool_or_struct, default_decoding},
     shutdown::ShutdownSignal,
+    sources::util::DeliveryTracker,
     SourceSender,
 };
 
@@ -30,6 +34,8 @@ pub struct StdinConfig {
     pub framing: Option<FramingConfig>,
     #[serde(default = "default_decoding")]
     pub decoding: DeserializerConfig,
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    pub acknowledgements: AcknowledgementsConfig,
 }
 
 impl Default for StdinConfig {
@@ -39,6 +45,7 @@ impl Default for StdinConfig {
             host_key: Default::default(),
             framing: None,
             decoding: default_decoding(),
+            acknowledgements: Default::default(),
         }
     }
 }
@@ -53,11 +60,13 @@ impl_generate_config_from_default!(StdinConfig);
 #[typetag::serde(name = "stdin")]
 impl SourceConfig for StdinConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
         stdin_source(
             io::BufReader::new(io::stdin()),
             self.clone(),
             cx.shutdown,
             cx.out,
+            acknowledgements,
         )
     }
 
@@ -74,7 +83,7 @@ impl SourceConfig for StdinConfig {
     }
 
     fn can_acknowledge(&self) -> bool {
-        false
+        true
     }
 }
 
@@ -83,6 +92,7 @@ pub fn stdin_source<R>(
     config: StdinConfig,
     shutdown: ShutdownSignal,
     mut out: SourceSender,
+    acknowledgements: bool,
 ) -> crate::Result<super::Source>
 where
     R: Send + io::BufRead + 'static,
@@ -125,12 +135,17 @@ where
     });
 
     Ok(Box::pin(async move {
+        // With acknowledgements, the source only finishes once the events read are delivered, so
+        // that the process doesn't exit with events in flight.
+        let tracker = DeliveryTracker::new(acknowledgements, shutdown.clone());
+        let tracker_ref = &tracker;
+
         let stream = StreamReader::new(receiver);
         let mut stream = FramedRead::new(stream, decoder).take_until(shutdown);
         let mut stream = stream! {
             while let Some(result) = stream.next().await {
                 match result {
-                    Ok((events, byte_size)) => {
+                    Ok((mut events, byte_size)) => {
                         emit!(BytesReceived { byte_size, protocol: "none" });
 
                         emit!(OldEventsReceived {
@@ -138,6 +153,7 @@ where
                             count: events.len()
                         });
 
+                        tracker_ref.track(&mut events);
                         let now = Utc::now();
 
                         for mut event in events {
@@ -167,8 +183,15 @@ where
 
         match out.send_event_stream(&mut stream).await {
             Ok(()) => {
+                drop(stream);
                 info!("Finished sending.");
-                Ok(())
+                match tracker.finish().await {
+                    0 => Ok(()),
+                    undelivered => {
+                        error!(message = "Failed to deliver events read.", batches = %undelivered);
+                        Err(())
+                    }
+                }
             }
             Err(error) => {
                 let (count, _) = stream.size_hint();
@@ -184,7 +207,9 @@ mod tests {
     use std::io::Cursor;
 
     use super::*;
-    use crate::{test_util::components::assert_source_compliance, SourceSender};
+    use crate::{
+        event::EventStatus, test_util::components::assert_source_compliance, SourceSender,
+    };
 
     #[test]
     fn generate_config() {
@@ -198,7 +223,7 @@ mod tests {
             let config = StdinConfig::default();
             let buf = Cursor::new("hello world\nhello world again");
 
-            stdin_source(buf, config, ShutdownSignal::noop(), tx)
+            stdin_source(buf, config, ShutdownSignal::noop(), tx, false)
                 .unwrap()
                 .await
                 .unwrap();
@@ -222,4 +247,22 @@ mod tests {
         })
         .await;
     }
+
+    #[tokio::test]
+    async fn stdin_waits_for_delivery() {
+        for (status, delivered) in [
+            (EventStatus::Delivered, true),
+            (EventStatus::Rejected, false),
+        ] {
+            let (tx, rx) = SourceSender::new_test_finalize(status);
+            let config = StdinConfig::default();
+            let buf = Cursor::new("hello world\nhello world again");
+
+            let source =
+                tokio::spawn(stdin_source(buf, config, ShutdownSignal::noop(), tx, true).unwrap());
+
+            assert_eq!(rx.collect::<Vec<_>>().await.len(), 2);
+            assert_eq!(source.await.unwrap().is_ok(), delivered);
+        }
+    }
 }
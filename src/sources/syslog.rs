@@ -6,8 +6,9 @@ use bytes::Bytes;
 use chrono::Utc;
 use codecs::{
     decoding::{Deserializer, Framer},
-    BytesDecoder, OctetCountingDecoder, SyslogDeserializer,
+    BytesDecoder, NewlineDelimitedDecoder, OctetCountingDecoder, SyslogDeserializer,
 };
+use derivative::Derivative;
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
@@ -27,7 +28,7 @@ use crate::{
     shutdown::ShutdownSignal,
     sources::util::{SocketListenAddr, TcpNullAcker, TcpSource},
     tcp::TcpKeepaliveConfig,
-    tls::{MaybeTlsSettings, TlsEnableableConfig},
+    tls::{CertificateMetadata, MaybeTlsSettings, TlsEnableableConfig},
     udp, SourceSender,
 };
 
@@ -50,8 +51,12 @@ pub enum Mode {
         address: SocketListenAddr,
         keepalive: Option<TcpKeepaliveConfig>,
         tls: Option<TlsEnableableConfig>,
+        /// The key of the subject of the certificate clients authenticated with.
+        tls_client_subject_key: Option<String>,
         receive_buffer_bytes: Option<usize>,
         connection_limit: Option<u32>,
+        #[serde(default)]
+        framing: Framing,
     },
     Udp {
         address: SocketAddr,
@@ -61,9 +66,40 @@ pub enum Mode {
     Unix {
         path: PathBuf,
         socket_file_mode: Option<u32>,
+        #[serde(default)]
+        framing: Framing,
     },
 }
 
+/// How the messages of the stream modes are framed (RFC 6587, 3.4).
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Framing {
+    /// Detected from the first message of each connection.
+    #[derivative(Default)]
+    Auto,
+    OctetCounting,
+    NewlineDelimited,
+}
+
+impl Framing {
+    fn decoder(self, max_length: usize) -> Decoder {
+        let framer = match self {
+            Framing::Auto => Framer::OctetCounting(
+                OctetCountingDecoder::new_with_max_length(max_length).detect_per_stream(),
+            ),
+            Framing::OctetCounting => Framer::OctetCounting(
+                OctetCountingDecoder::new_with_max_length(max_length).octet_counted_only(),
+            ),
+            Framing::NewlineDelimited => {
+                Framer::NewlineDelimited(NewlineDelimitedDecoder::new_with_max_length(max_length))
+            }
+        };
+        Decoder::new(framer, Deserializer::Syslog(SyslogDeserializer))
+    }
+}
+
 impl SyslogConfig {
     pub fn from_mode(mode: Mode) -> Self {
         Self {
@@ -85,8 +121,10 @@ impl GenerateConfig for SyslogConfig {
                 address: SocketListenAddr::SocketAddr("0.0.0.0:514".parse().unwrap()),
                 keepalive: None,
                 tls: None,
+                tls_client_subject_key: None,
                 receive_buffer_bytes: None,
                 connection_limit: None,
+                framing: Framing::default(),
             },
             host_key: None,
             max_length: crate::serde::default_max_length(),
@@ -109,12 +147,16 @@ impl SourceConfig for SyslogConfig {
                 address,
                 keepalive,
                 tls,
+                tls_client_subject_key,
                 receive_buffer_bytes,
                 connection_limit,
+                framing,
             } => {
                 let source = SyslogTcpSource {
                     max_length: self.max_length,
                     host_key,
+                    tls_client_subject_key,
+                    framing,
                 };
                 let shutdown_secs = 30;
                 let tls = MaybeTlsSettings::from_config(&tls, true)?;
@@ -144,23 +186,15 @@ impl SourceConfig for SyslogConfig {
             Mode::Unix {
                 path,
                 socket_file_mode,
-            } => {
-                let decoder = Decoder::new(
-                    Framer::OctetCounting(OctetCountingDecoder::new_with_max_length(
-                        self.max_length,
-                    )),
-                    Deserializer::Syslog(SyslogDeserializer),
-                );
-
-                build_unix_stream_source(
-                    path,
-                    socket_file_mode,
-                    decoder,
-                    move |events, host| handle_events(events, &host_key, host),
-                    cx.shutdown,
-                    cx.out,
-                )
-            }
+                framing,
+            } => build_unix_stream_source(
+                path,
+                socket_file_mode,
+                framing.decoder(self.max_length),
+                move |events, host| handle_events(events, &host_key, host),
+                cx.shutdown,
+                cx.out,
+            ),
         }
     }
 
@@ -190,6 +224,8 @@ impl SourceConfig for SyslogConfig {
 struct SyslogTcpSource {
     max_length: usize,
     host_key: String,
+    tls_client_subject_key: Option<String>,
+    framing: Framing,
 }
 
 impl TcpSource for SyslogTcpSource {
@@ -199,14 +235,24 @@ impl TcpSource for SyslogTcpSource {
     type Acker = TcpNullAcker;
 
     fn decoder(&self) -> Self::Decoder {
-        Decoder::new(
-            Framer::OctetCounting(OctetCountingDecoder::new_with_max_length(self.max_length)),
-            Deserializer::Syslog(SyslogDeserializer),
-        )
+        self.framing.decoder(self.max_length)
     }
 
-    fn handle_events(&self, events: &mut [Event], host: SocketAddr) {
+    fn handle_events(
+        &self,
+        events: &mut [Event],
+        host: SocketAddr,
+        certificate: Option<&CertificateMetadata>,
+    ) {
         handle_events(events, &self.host_key, Some(host.ip().to_string().into()));
+
+        if let (Some(key), Some(certificate)) = (&self.tls_client_subject_key, certificate) {
+            for event in events {
+                event
+                    .as_mut_log()
+                    .insert(key.as_str(), certificate.subject.clone());
+            }
+        }
     }
 
     fn build_acker(&self, _: &[Self::Item]) -> Self::Acker {
@@ -429,6 +475,52 @@ mod test {
         assert_eq!(keepalive.time_secs, Some(7200));
     }
 
+    #[test]
+    fn config_tcp_framing() {
+        let config: SyslogConfig = toml::from_str(
+            r#"
+            mode = "tcp"
+            address = "127.0.0.1:6514"
+            framing = "octet_counting"
+          "#,
+        )
+        .unwrap();
+
+        let framing = match config.mode {
+            Mode::Tcp { framing, .. } => framing,
+            _ => panic!("expected Mode::Tcp"),
+        };
+
+        assert_eq!(framing, Framing::OctetCounting);
+    }
+
+    #[test]
+    fn inserts_tls_client_subject() {
+        let source = SyslogTcpSource {
+            max_length: crate::serde::default_max_length(),
+            host_key: "host".to_owned(),
+            tls_client_subject_key: Some("tls_client_subject".to_owned()),
+            framing: Framing::Auto,
+        };
+        let certificate = CertificateMetadata {
+            subject: "C=US,O=Example,CN=client".to_owned(),
+        };
+
+        let mut events = vec![Event::from(
+            "<13>Feb 13 20:07:26 74794bfb6795 root[8539]: hello",
+        )];
+        source.handle_events(
+            &mut events,
+            "127.0.0.1:6514".parse().unwrap(),
+            Some(&certificate),
+        );
+
+        assert_eq!(
+            events[0].as_log()["tls_client_subject"],
+            "C=US,O=Example,CN=client".into()
+        );
+    }
+
     #[test]
     fn config_udp() {
         let config: SyslogConfig = toml::from_str(
@@ -491,8 +583,7 @@ mod test {
         .unwrap();
         let socket_file_mode = match config.mode {
             Mode::Unix {
-                path: _,
-                socket_file_mode,
+                socket_file_mode, ..
             } => socket_file_mode,
             _ => panic!("expected Mode::Unix"),
         };
@@ -743,8 +834,10 @@ mod test {
                 address: in_addr.into(),
                 keepalive: None,
                 tls: None,
+                tls_client_subject_key: None,
                 receive_buffer_bytes: None,
                 connection_limit: None,
+                framing: Framing::default(),
             });
 
             let key = ComponentKey::from("in");
@@ -816,6 +909,7 @@ mod test {
             let config = SyslogConfig::from_mode(Mode::Unix {
                 path: in_path.clone(),
                 socket_file_mode: None,
+                framing: Framing::default(),
             });
 
             let key = ComponentKey::from("in");
@@ -886,8 +980,10 @@ mod test {
                 address: in_addr.into(),
                 keepalive: None,
                 tls: None,
+                tls_client_subject_key: None,
                 receive_buffer_bytes: None,
                 connection_limit: None,
+                framing: Framing::default(),
             });
 
             let key = ComponentKey::from("in");
@@ -0,0 +1,494 @@
+//! The machinery shared by the sources pulling audit logs out of the APIs of SaaS products:
+//! polling the API for the records published since the last poll, checkpointing the cursor
+//! telling where to resume from, and backing off while the API is rate limiting requests.
+
+use std::{io, path::PathBuf, time::Duration};
+
+use bytes::Bytes;
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use futures::StreamExt;
+use http::{
+    header::{LINK, RETRY_AFTER},
+    HeaderMap, Request, StatusCode,
+};
+use hyper::Body;
+use snafu::{ResultExt, Snafu};
+use tokio::time::{self, Instant};
+use tokio_stream::wrappers::IntervalStream;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    config::log_schema,
+    event::{Event, LogEvent, Value},
+    http::{HttpClient, HttpError},
+    internal_events::{
+        AuditLogCheckpointError, AuditLogRateLimited, AuditLogRequestError, EventsReceived,
+        StreamClosedError,
+    },
+    shutdown::ShutdownSignal,
+    SourceSender,
+};
+
+const CHECKPOINT_FILENAME: &str = "checkpoint.txt";
+
+/// Requests which are rate limited, or fail on the side of the API, are retried this many times.
+const MAX_RETRIES: usize = 5;
+
+/// The longest the API can have requests wait for, which protects against bogus rate limits.
+const MAX_DELAY: Duration = Duration::from_secs(300);
+
+/// The rate limit headers of Okta.
+const RATE_LIMIT_REMAINING: &str = "x-rate-limit-remaining";
+const RATE_LIMIT_RESET: &str = "x-rate-limit-reset";
+
+#[derive(Debug, Snafu)]
+pub enum ApiError {
+    #[snafu(display("Request failed: {}", source))]
+    Request { source: HttpError },
+    #[snafu(display("Failed to read the response: {}", source))]
+    ReadBody { source: hyper::Error },
+    #[snafu(display("Unexpected response status {}: {}", status, body))]
+    Status { status: StatusCode, body: String },
+    #[snafu(display("Invalid response: {}", source))]
+    Parse { source: serde_json::Error },
+}
+
+/// A response whose body has been read.
+pub struct Response {
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+impl Response {
+    pub fn json<T: serde::de::DeserializeOwned>(&self) -> Result<T, ApiError> {
+        serde_json::from_slice(&self.body).context(ParseSnafu)
+    }
+}
+
+/// An HTTP client honoring the rate limits of an API.
+pub struct ApiClient {
+    client: HttpClient,
+    /// Set when the API announced that no requests are left until its rate limit resets.
+    resume_at: Option<Instant>,
+}
+
+impl ApiClient {
+    pub const fn new(client: HttpClient) -> Self {
+        Self {
+            client,
+            resume_at: None,
+        }
+    }
+
+    /// Sends the request built by `request`, building it anew to retry it when the API is rate
+    /// limiting requests or failing.
+    pub async fn send(
+        &mut self,
+        request: impl Fn() -> Request<Body>,
+    ) -> Result<Response, ApiError> {
+        let mut attempt = 0;
+        loop {
+            if let Some(resume_at) = self.resume_at.take() {
+                time::sleep_until(resume_at).await;
+            }
+
+            let response = self.client.send(request()).await.context(RequestSnafu)?;
+            let (parts, body) = response.into_parts();
+            let body = hyper::body::to_bytes(body).await.context(ReadBodySnafu)?;
+
+            if let Some(delay) = exhausted_delay(&parts.headers, Utc::now()) {
+                self.resume_at = Some(Instant::now() + delay);
+            }
+
+            let status = parts.status;
+            if status.is_success() {
+                return Ok(Response {
+                    headers: parts.headers,
+                    body,
+                });
+            }
+            if attempt < MAX_RETRIES
+                && (status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error())
+            {
+                let delay = retry_delay(&parts.headers, Utc::now())
+                    .unwrap_or_else(|| backoff(attempt))
+                    .min(MAX_DELAY);
+                emit!(AuditLogRateLimited { status, delay });
+                time::sleep(delay).await;
+                attempt += 1;
+                continue;
+            }
+            return Err(ApiError::Status {
+                status,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            });
+        }
+    }
+}
+
+const fn backoff(attempt: usize) -> Duration {
+    Duration::from_secs(1 << attempt)
+}
+
+/// How long to wait before retrying a rate limited request, as told by the standard
+/// `Retry-After` header, or the rate limit headers of Okta.
+fn retry_delay(headers: &HeaderMap, now: DateTime<Utc>) -> Option<Duration> {
+    let retry_after = headers
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok());
+    match retry_after {
+        Some(value) => match value.trim().parse::<u64>() {
+            Ok(seconds) => Some(Duration::from_secs(seconds)),
+            Err(_) => DateTime::parse_from_rfc2822(value)
+                .ok()
+                .map(|at| until(at.with_timezone(&Utc), now)),
+        },
+        None => rate_limit_reset(headers, now),
+    }
+}
+
+/// How long to wait before sending any further request, when the response tells that the rate
+/// limit has been used up.
+fn exhausted_delay(headers: &HeaderMap, now: DateTime<Utc>) -> Option<Duration> {
+    let remaining = headers
+        .get(RATE_LIMIT_REMAINING)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    if remaining > 0 {
+        return None;
+    }
+    rate_limit_reset(headers, now).map(|delay| delay.min(MAX_DELAY))
+}
+
+fn rate_limit_reset(headers: &HeaderMap, now: DateTime<Utc>) -> Option<Duration> {
+    let reset = headers
+        .get(RATE_LIMIT_RESET)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<i64>()
+        .ok()?;
+    Utc.timestamp_opt(reset, 0)
+        .single()
+        .map(|reset| until(reset, now))
+}
+
+fn until(at: DateTime<Utc>, now: DateTime<Utc>) -> Duration {
+    (at - now).to_std().unwrap_or_default()
+}
+
+/// The URL of the next page of results, from the `Link` headers of a response.
+pub fn next_link(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all(LINK)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .find_map(|link| {
+            let mut parts = link.split(';').map(str::trim);
+            let url = parts.next()?.strip_prefix('<')?.strip_suffix('>')?;
+            parts
+                .any(|param| param == r#"rel="next""# || param == "rel=next")
+                .then(|| url.to_owned())
+        })
+}
+
+/// Persists the cursor of a source in its data directory.
+pub struct Checkpointer {
+    path: PathBuf,
+}
+
+impl Checkpointer {
+    pub fn new(data_dir: PathBuf) -> Self {
+        Self {
+            path: data_dir.join(CHECKPOINT_FILENAME),
+        }
+    }
+
+    pub async fn get(&self) -> Option<String> {
+        match tokio::fs::read_to_string(&self.path).await {
+            Ok(cursor) => Some(cursor.trim().to_owned()).filter(|cursor| !cursor.is_empty()),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+            Err(error) => {
+                emit!(AuditLogCheckpointError {
+                    error,
+                    path: &self.path
+                });
+                None
+            }
+        }
+    }
+
+    /// Writes the cursor to a temporary file first, so a crash can't leave a partial cursor
+    /// behind.
+    pub async fn set(&self, cursor: &str) {
+        let temp = self.path.with_extension("tmp");
+        let result = async {
+            tokio::fs::write(&temp, format!("{}\n", cursor)).await?;
+            tokio::fs::rename(&temp, &self.path).await
+        }
+        .await;
+        if let Err(error) = result {
+            emit!(AuditLogCheckpointError {
+                error,
+                path: &self.path
+            });
+        }
+    }
+}
+
+pub const fn default_interval_secs() -> u64 {
+    60
+}
+
+/// How far back collecting starts from, when there's no checkpoint yet.
+pub const fn default_lookback_secs() -> u64 {
+    3600
+}
+
+/// A page of records.
+pub struct Page {
+    pub records: Vec<serde_json::Value>,
+    /// The cursor to resume from once the records have been sent, when it's safe to checkpoint.
+    pub cursor: Option<String>,
+    /// Whether more records can be fetched right away, rather than at the next poll.
+    pub more: bool,
+}
+
+/// An audit log API, fetching the records published after a cursor.
+#[async_trait::async_trait]
+pub trait AuditLogApi: Send {
+    /// Fetches the next page of records, resuming from `cursor`, or from the start of the logs
+    /// to collect when there's none yet.
+    async fn fetch(&mut self, client: &mut ApiClient, cursor: Option<&str>) -> crate::Result<Page>;
+}
+
+/// The settings shared by the audit log sources.
+pub struct Poller {
+    pub client: ApiClient,
+    pub checkpointer: Checkpointer,
+    pub interval: Duration,
+    pub source_type: &'static str,
+    /// The field of the records holding the time of the audited activity.
+    pub timestamp_field: &'static str,
+}
+
+impl Poller {
+    /// Polls the API until shut down, sending the records as they are fetched, and checkpointing
+    /// the cursor once they've been sent. A poll stops at the first error, the next one resuming
+    /// from the last checkpoint.
+    pub async fn run(
+        mut self,
+        mut api: impl AuditLogApi,
+        mut out: SourceSender,
+        shutdown: ShutdownSignal,
+    ) -> Result<(), ()> {
+        let mut cursor = self.checkpointer.get().await;
+        let mut interval =
+            IntervalStream::new(time::interval(self.interval)).take_until(shutdown.clone());
+
+        while interval.next().await.is_some() {
+            loop {
+                let page = tokio::select! {
+                    page = api.fetch(&mut self.client, cursor.as_deref()) => page,
+                    _ = shutdown.clone() => return Ok(()),
+                };
+                let page = match page {
+                    Ok(page) => page,
+                    Err(error) => {
+                        emit!(AuditLogRequestError { error });
+                        break;
+                    }
+                };
+
+                let events = page
+                    .records
+                    .into_iter()
+                    .filter_map(|record| {
+                        to_event(record, self.source_type, self.timestamp_field, Utc::now())
+                    })
+                    .collect::<Vec<_>>();
+                let count = events.len();
+                if count > 0 {
+                    emit!(EventsReceived {
+                        count,
+                        byte_size: events.size_of(),
+                    });
+                    if let Err(error) = out.send_batch(events).await {
+                        emit!(StreamClosedError { error, count });
+                        return Err(());
+                    }
+                }
+
+                if let Some(next) = page.cursor {
+                    if cursor.as_ref() != Some(&next) {
+                        self.checkpointer.set(&next).await;
+                        cursor = Some(next);
+                    }
+                }
+                if !page.more {
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Turns a record into a log event holding its fields. Records which aren't objects are
+/// dropped, as audit logs are made of objects.
+fn to_event(
+    record: serde_json::Value,
+    source_type: &'static str,
+    timestamp_field: &str,
+    now: DateTime<Utc>,
+) -> Option<Event> {
+    let fields = match Value::from(record) {
+        Value::Object(fields) => fields,
+        _ => return None,
+    };
+    let mut log = LogEvent::from(fields);
+
+    let timestamp = log
+        .get(timestamp_field)
+        .and_then(|value| match value {
+            Value::Bytes(bytes) => parse_timestamp(&String::from_utf8_lossy(bytes)),
+            Value::Timestamp(timestamp) => Some(*timestamp),
+            _ => None,
+        })
+        .unwrap_or(now);
+    log.try_insert(log_schema().timestamp_key(), timestamp);
+    log.try_insert(log_schema().source_type_key(), Bytes::from(source_type));
+    Some(log.into())
+}
+
+/// Parses a timestamp in the RFC 3339 format, or without a time zone, as the records of Office
+/// 365 are, in which case it's in UTC.
+pub fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+                .map(|timestamp| Utc.from_utc_datetime(&timestamp))
+        })
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderValue;
+
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    #[test]
+    fn retry_delays() {
+        let now = Utc.ymd(2022, 6, 1).and_hms(12, 0, 0);
+        assert_eq!(
+            retry_delay(&headers(&[("retry-after", "30")]), now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            retry_delay(
+                &headers(&[("retry-after", "Wed, 01 Jun 2022 12:01:00 GMT")]),
+                now
+            ),
+            Some(Duration::from_secs(60))
+        );
+        // Okta tells when the rate limit resets instead.
+        let reset = (now.timestamp() + 15).to_string();
+        let mut okta = headers(&[("x-rate-limit-remaining", "0")]);
+        okta.insert("x-rate-limit-reset", reset.parse().unwrap());
+        assert_eq!(retry_delay(&okta, now), Some(Duration::from_secs(15)));
+        assert_eq!(exhausted_delay(&okta, now), Some(Duration::from_secs(15)));
+        okta.insert("x-rate-limit-remaining", HeaderValue::from_static("10"));
+        assert_eq!(exhausted_delay(&okta, now), None);
+
+        assert_eq!(retry_delay(&HeaderMap::new(), now), None);
+    }
+
+    #[test]
+    fn finds_next_link() {
+        let links = headers(&[
+            (
+                "link",
+                r#"<https://example.okta.com/api/v1/logs?limit=100>; rel="self""#,
+            ),
+            (
+                "link",
+                r#"<https://example.okta.com/api/v1/logs?limit=100&after=abc>; rel="next""#,
+            ),
+        ]);
+        assert_eq!(
+            next_link(&links).as_deref(),
+            Some("https://example.okta.com/api/v1/logs?limit=100&after=abc")
+        );
+        assert_eq!(
+            next_link(&headers(&[("link", r#"<https://a/1>; rel="self""#)])),
+            None
+        );
+    }
+
+    #[test]
+    fn parses_timestamps() {
+        let expected = Utc.ymd(2022, 6, 1).and_hms_milli(12, 30, 15, 250);
+        assert_eq!(parse_timestamp("2022-06-01T12:30:15.250Z"), Some(expected));
+        assert_eq!(
+            parse_timestamp("2022-06-01T14:30:15.250+02:00"),
+            Some(expected)
+        );
+        assert_eq!(parse_timestamp("2022-06-01T12:30:15.25"), Some(expected));
+        assert_eq!(parse_timestamp("yesterday"), None);
+    }
+
+    #[test]
+    fn converts_records() {
+        let now = Utc::now();
+        let record = serde_json::json!({
+            "id": { "time": "2022-06-01T12:30:15Z", "uniqueQualifier": "42" },
+            "actor": { "email": "admin@example.com" },
+        });
+        let event = to_event(record, "google_workspace", "id.time", now).unwrap();
+        let log = event.as_log();
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Value::Timestamp(Utc.ymd(2022, 6, 1).and_hms(12, 30, 15))
+        );
+        assert_eq!(
+            log[log_schema().source_type_key()],
+            "google_workspace".into()
+        );
+        assert_eq!(log["actor.email"], "admin@example.com".into());
+
+        let event = to_event(serde_json::json!({}), "okta", "published", now).unwrap();
+        assert_eq!(event.as_log()[log_schema().timestamp_key()], now.into());
+        assert!(to_event(serde_json::json!("text"), "okta", "published", now).is_none());
+    }
+
+    #[tokio::test]
+    async fn checkpoints_cursors() {
+        let dir = tempfile::tempdir().unwrap();
+        let checkpointer = Checkpointer::new(dir.path().to_owned());
+        assert_eq!(checkpointer.get().await, None);
+        checkpointer.set("2022-06-01T12:30:15Z").await;
+        checkpointer.set("2022-06-01T12:45:00Z").await;
+        assert_eq!(
+            Checkpointer::new(dir.path().to_owned())
+                .get()
+                .await
+                .as_deref(),
+            Some("2022-06-01T12:45:00Z")
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use futures::StreamExt;
+use tokio::task::JoinHandle;
+
+use super::finalizer::UnorderedFinalizer;
+use crate::{
+    event::{BatchNotifier, BatchStatus, Event},
+    shutdown::ShutdownSignal,
+};
+
+/// Tracks the delivery of the events of sources that have no way of acknowledging them upstream,
+/// such as `exec` and `stdin`, so they can hold off on finishing their work, and with it the
+/// process or the command they read from, until the events they read are delivered.
+pub(crate) struct DeliveryTracker {
+    finalizer: Option<UnorderedFinalizer<()>>,
+    undelivered: Option<JoinHandle<usize>>,
+}
+
+impl DeliveryTracker {
+    /// Tracks nothing unless `acknowledgements` are enabled.
+    pub(crate) fn new(acknowledgements: bool, shutdown: ShutdownSignal) -> Self {
+        if !acknowledgements {
+            return Self {
+                finalizer: None,
+                undelivered: None,
+            };
+        }
+
+        let (finalizer, statuses) = UnorderedFinalizer::new(shutdown);
+        let undelivered = tokio::spawn(statuses.fold(0, |undelivered, (status, ())| async move {
+            match status {
+                BatchStatus::Delivered => undelivered,
+                BatchStatus::Errored | BatchStatus::Rejected => {
+                    warn!(
+                        message = "Failed to deliver events to sink.",
+                        internal_log_rate_secs = 5
+                    );
+                    undelivered + 1
+                }
+            }
+        }));
+        Self {
+            finalizer: Some(finalizer),
+            undelivered: Some(undelivered),
+        }
+    }
+
+    /// Tracks the delivery of a batch of events.
+    pub(crate) fn track(&self, events: &mut [Event]) {
+        if let Some(finalizer) = &self.finalizer {
+            let (batch, receiver) = BatchNotifier::new_with_receiver();
+            for event in events {
+                event.add_batch_notifier(Arc::clone(&batch));
+            }
+            finalizer.add((), receiver);
+        }
+    }
+
+    /// Waits for the events tracked to be delivered, or to fail to be, and returns the number of
+    /// batches that failed to be delivered.
+    pub(crate) async fn finish(self) -> usize {
+        drop(self.finalizer);
+        match self.undelivered {
+            Some(undelivered) => undelivered.await.expect("delivery tracking task panicked"),
+            None => 0,
+        }
+    }
+}
@@ -13,9 +13,13 @@ use crate::shutdown::ShutdownSignal;
 /// *in the order they are received from the source*, using
 /// `FinalizerSet`.
 #[cfg(any(
+    feature = "sources-azure_event_hubs",
     feature = "sources-file",
     feature = "sources-journald",
     feature = "sources-kafka",
+    feature = "sources-mongodb_change_stream",
+    feature = "sources-postgresql_cdc",
+    all(windows, feature = "sources-windows_event_log"),
 ))]
 pub(crate) type OrderedFinalizer<T> = FinalizerSet<T, FuturesOrdered<FinalizerFuture<T>>>;
 
@@ -24,9 +28,15 @@ pub(crate) type OrderedFinalizer<T> = FinalizerSet<T, FuturesOrdered<FinalizerFu
 /// background task *in the order that finalization happens on the
 /// event batches*, using `FinalizerSet`.
 #[cfg(any(
+    feature = "sources-amqp",
     feature = "sources-aws_sqs",
+    feature = "sources-exec",
     feature = "sources-splunk_hec",
-    feature = "sources-gcp_pubsub"
+    feature = "sources-gcp_pubsub",
+    feature = "sources-mqtt",
+    feature = "sources-nats",
+    feature = "sources-pulsar",
+    feature = "sources-stdin"
 ))]
 pub(crate) type UnorderedFinalizer<T> = FinalizerSet<T, FuturesUnordered<FinalizerFuture<T>>>;
 
@@ -70,7 +80,18 @@ where
     /// stream of acknowledged identifiers. In the case the finalizer
     /// is not to be used, a special empty stream is returned that is
     /// always pending and so never wakes.
-    #[cfg(any(feature = "sources-gcp_pubsub", feature = "sources-kafka"))]
+    #[cfg(any(
+        feature = "sources-amqp",
+        feature = "sources-azure_event_hubs",
+        feature = "sources-gcp_pubsub",
+        feature = "sources-kafka",
+        feature = "sources-mongodb_change_stream",
+        feature = "sources-mqtt",
+        feature = "sources-nats",
+        feature = "sources-postgresql_cdc",
+        feature = "sources-pulsar",
+        all(windows, feature = "sources-windows_event_log")
+    ))]
     pub(crate) fn maybe_new(
         maybe: bool,
         shutdown: ShutdownSignal,
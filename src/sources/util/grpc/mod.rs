@@ -2,15 +2,19 @@ use crate::{
     internal_events::TcpBytesReceived,
     shutdown::{ShutdownSignal, ShutdownSignalToken},
     sources::util::AfterReadExt as _,
-    tls::MaybeTlsSettings,
+    tls::{MaybeTlsIncomingStream, MaybeTlsSettings},
 };
 use futures::{FutureExt, StreamExt};
 use http::{Request, Response};
 use hyper::Body;
 use std::{convert::Infallible, net::SocketAddr};
+use tokio::net::TcpStream;
 use tonic::{
     body::BoxBody,
-    transport::server::{Connected, NamedService, Server},
+    transport::{
+        server::{Connected, NamedService, Router, Server},
+        Certificate,
+    },
 };
 use tower::Service;
 use tracing::{Instrument, Span};
@@ -29,6 +33,19 @@ where
         + 'static,
     S::Future: Send + 'static,
 {
+    run_grpc_server_with_routes(address, tls_settings, shutdown, |server| {
+        server.add_service(service)
+    })
+    .await
+}
+
+/// Runs a gRPC server serving the services added to it by `routes`.
+pub async fn run_grpc_server_with_routes(
+    address: SocketAddr,
+    tls_settings: MaybeTlsSettings,
+    shutdown: ShutdownSignal,
+    routes: impl FnOnce(&mut Server) -> Router,
+) -> crate::Result<()> {
     let span = Span::current();
     let (tx, rx) = tokio::sync::oneshot::channel::<ShutdownSignalToken>();
     let listener = tls_settings.bind(&address).await?;
@@ -63,9 +80,8 @@ where
         })
     });
 
-    Server::builder()
-        .trace_fn(move |_| span.clone())
-        .add_service(service)
+    let mut server = Server::builder().trace_fn(move |_| span.clone());
+    routes(&mut server)
         .serve_with_incoming_shutdown(stream, shutdown.map(|token| tx.send(token).unwrap()))
         .in_current_span()
         .await?;
@@ -74,3 +90,28 @@ where
 
     Ok(())
 }
+
+#[derive(Clone)]
+pub struct MaybeTlsConnectInfo {
+    pub remote_addr: SocketAddr,
+    pub peer_certs: Option<Vec<Certificate>>,
+}
+
+impl Connected for MaybeTlsIncomingStream<TcpStream> {
+    type ConnectInfo = MaybeTlsConnectInfo;
+
+    fn connect_info(&self) -> Self::ConnectInfo {
+        MaybeTlsConnectInfo {
+            remote_addr: self.peer_addr(),
+            peer_certs: self
+                .ssl_stream()
+                .and_then(|s| s.ssl().peer_cert_chain())
+                .map(|s| {
+                    s.into_iter()
+                        .filter_map(|c| c.to_pem().ok())
+                        .map(Certificate::from_pem)
+                        .collect()
+                }),
+        }
+    }
+}
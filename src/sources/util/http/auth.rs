@@ -1,7 +1,9 @@
 use std::convert::TryFrom;
 
 use headers::{Authorization, HeaderMapExt};
+use hmac::{Hmac, Mac};
 use serde::{Deserialize, Serialize};
+use sha2::Sha256;
 use warp::http::HeaderMap;
 
 #[cfg(any(
@@ -11,62 +13,227 @@ use warp::http::HeaderMap;
 use super::error::ErrorMessage;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
-pub struct HttpSourceAuthConfig {
-    pub username: String,
-    pub password: String,
+#[serde(untagged)]
+pub enum HttpSourceAuthConfig {
+    Strategy(HttpSourceAuthStrategy),
+    /// The basic authentication, configured without a `strategy`.
+    Basic {
+        username: String,
+        password: String,
+    },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields, rename_all = "snake_case", tag = "strategy")]
+pub enum HttpSourceAuthStrategy {
+    Basic {
+        username: String,
+        password: String,
+    },
+    Bearer {
+        token: String,
+    },
+    /// The HMAC-SHA256 signature of the body, hex encoded, in a header.
+    Hmac {
+        secret: String,
+        #[serde(default = "default_signature_header")]
+        header: String,
+        #[serde(default)]
+        prefix: String,
+    },
+}
+
+fn default_signature_header() -> String {
+    "X-Signature".to_owned()
 }
 
 impl TryFrom<Option<&HttpSourceAuthConfig>> for HttpSourceAuth {
     type Error = String;
 
     fn try_from(auth: Option<&HttpSourceAuthConfig>) -> Result<Self, Self::Error> {
-        match auth {
-            Some(auth) => {
+        let validator = match auth {
+            Some(HttpSourceAuthConfig::Basic { username, password })
+            | Some(HttpSourceAuthConfig::Strategy(HttpSourceAuthStrategy::Basic {
+                username,
+                password,
+            })) => {
                 let mut headers = HeaderMap::new();
-                headers.typed_insert(Authorization::basic(&auth.username, &auth.password));
+                headers.typed_insert(Authorization::basic(username, password));
                 match headers.get("authorization") {
                     Some(value) => {
                         let token = value
                             .to_str()
                             .map_err(|error| format!("Failed stringify HeaderValue: {:?}", error))?
                             .to_owned();
-                        Ok(HttpSourceAuth { token: Some(token) })
+                        Some(Validator::Authorization(token))
                     }
-                    None => Err("Authorization headers wasn't generated".to_owned()),
+                    None => return Err("Authorization headers wasn't generated".to_owned()),
                 }
             }
-            None => Ok(HttpSourceAuth { token: None }),
-        }
+            Some(HttpSourceAuthConfig::Strategy(HttpSourceAuthStrategy::Bearer { token })) => {
+                Some(Validator::Authorization(format!("Bearer {}", token)))
+            }
+            Some(HttpSourceAuthConfig::Strategy(HttpSourceAuthStrategy::Hmac {
+                secret,
+                header,
+                prefix,
+            })) => Some(Validator::Hmac {
+                secret: secret.as_bytes().to_vec(),
+                header: header.clone(),
+                prefix: prefix.clone(),
+            }),
+            None => None,
+        };
+        Ok(HttpSourceAuth { validator })
     }
 }
 
+#[derive(Debug, Clone)]
+enum Validator {
+    /// The expected value of the `Authorization` header.
+    Authorization(String),
+    Hmac {
+        secret: Vec<u8>,
+        header: String,
+        prefix: String,
+    },
+}
+
 #[derive(Debug, Clone)]
 pub struct HttpSourceAuth {
     #[allow(unused)] // triggered by cargo-hack
-    pub(self) token: Option<String>,
+    validator: Option<Validator>,
 }
 
 impl HttpSourceAuth {
+    /// Validates the request, with its body as received, before decompressing it.
     #[allow(unused)] // triggered by cargo-hack
-    pub fn is_valid(&self, header: &Option<String>) -> Result<(), ErrorMessage> {
+    pub fn is_valid(&self, headers: &HeaderMap, body: &[u8]) -> Result<(), ErrorMessage> {
         use warp::http::StatusCode;
 
-        match (&self.token, header) {
-            (Some(token1), Some(token2)) => {
-                if token1 == token2 {
-                    Ok(())
-                } else {
-                    Err(ErrorMessage::new(
-                        StatusCode::UNAUTHORIZED,
-                        "Invalid username/password".to_owned(),
-                    ))
+        let unauthorized = |message: &str| {
+            Err(ErrorMessage::new(
+                StatusCode::UNAUTHORIZED,
+                message.to_owned(),
+            ))
+        };
+
+        match &self.validator {
+            Some(Validator::Authorization(expected)) => {
+                match headers.get("authorization").map(|value| value.to_str()) {
+                    Some(Ok(value)) if value == expected => Ok(()),
+                    Some(_) => unauthorized("Invalid credentials"),
+                    None => unauthorized("No authorization header"),
                 }
             }
-            (Some(_), None) => Err(ErrorMessage::new(
-                StatusCode::UNAUTHORIZED,
-                "No authorization header".to_owned(),
-            )),
-            (None, _) => Ok(()),
+            Some(Validator::Hmac {
+                secret,
+                header,
+                prefix,
+            }) => {
+                let signature = match headers.get(header.as_str()).map(|value| value.to_str()) {
+                    Some(Ok(value)) => value,
+                    Some(Err(_)) => return unauthorized("Invalid signature"),
+                    None => return unauthorized("No signature header"),
+                };
+                let signature = match signature
+                    .strip_prefix(prefix.as_str())
+                    .and_then(|signature| hex::decode(signature).ok())
+                {
+                    Some(signature) => signature,
+                    None => return unauthorized("Invalid signature"),
+                };
+                let mut mac = Hmac::<Sha256>::new_from_slice(secret)
+                    .expect("HMAC can take a key of any size");
+                mac.update(body);
+                // Compared in constant time.
+                mac.verify_slice(&signature)
+                    .or_else(|_| unauthorized("Invalid signature"))
+            }
+            None => Ok(()),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use warp::http::HeaderValue;
+
+    use super::*;
+
+    fn auth(config: &str) -> HttpSourceAuth {
+        let config: HttpSourceAuthConfig = toml::from_str(config).unwrap();
+        HttpSourceAuth::try_from(Some(&config)).unwrap()
+    }
+
+    fn headers(name: &'static str, value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn validates_basic() {
+        for config in [
+            r#"username = "user"
+               password = "pass""#,
+            r#"strategy = "basic"
+               username = "user"
+               password = "pass""#,
+        ] {
+            let auth = auth(config);
+            assert!(auth
+                .is_valid(&headers("authorization", "Basic dXNlcjpwYXNz"), b"")
+                .is_ok());
+            assert!(auth
+                .is_valid(&headers("authorization", "Basic dXNlcjpvdGhlcg=="), b"")
+                .is_err());
+            assert!(auth.is_valid(&HeaderMap::new(), b"").is_err());
+        }
+    }
+
+    #[test]
+    fn validates_bearer() {
+        let auth = auth(
+            r#"strategy = "bearer"
+               token = "secret-token""#,
+        );
+        assert!(auth
+            .is_valid(&headers("authorization", "Bearer secret-token"), b"")
+            .is_ok());
+        assert!(auth
+            .is_valid(&headers("authorization", "Bearer other-token"), b"")
+            .is_err());
+        assert!(auth.is_valid(&HeaderMap::new(), b"").is_err());
+    }
+
+    #[test]
+    fn validates_hmac() {
+        let auth = auth(
+            r#"strategy = "hmac"
+               secret = "key"
+               header = "X-Hub-Signature-256"
+               prefix = "sha256=""#,
+        );
+        // HMAC-SHA256("key", "The quick brown fox jumps over the lazy dog")
+        let signature = "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8";
+        let body = b"The quick brown fox jumps over the lazy dog";
+
+        assert!(auth
+            .is_valid(&headers("x-hub-signature-256", signature), body)
+            .is_ok());
+        assert!(auth
+            .is_valid(&headers("x-hub-signature-256", signature), b"other body")
+            .is_err());
+        assert!(auth
+            .is_valid(&headers("x-hub-signature-256", &signature[7..]), body)
+            .is_err());
+        assert!(auth.is_valid(&HeaderMap::new(), body).is_err());
+    }
+
+    #[test]
+    fn accepts_without_auth() {
+        let auth = HttpSourceAuth::try_from(None).unwrap();
+        assert!(auth.is_valid(&HeaderMap::new(), b"").is_ok());
+    }
+}
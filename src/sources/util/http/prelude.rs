@@ -2,7 +2,7 @@ use std::{collections::HashMap, convert::TryFrom, fmt, net::SocketAddr};
 
 use async_trait::async_trait;
 use bytes::Bytes;
-use futures::{FutureExt, TryFutureExt};
+use futures::FutureExt;
 use tracing::Span;
 use warp::{
     filters::{
@@ -11,7 +11,7 @@ use warp::{
     },
     http::{HeaderMap, StatusCode},
     reject::Rejection,
-    Filter,
+    Filter, Reply,
 };
 
 use vector_core::{
@@ -43,6 +43,17 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
         path: &str,
     ) -> Result<Vec<Event>, ErrorMessage>;
 
+    /// The named output to send the events of a request on `path` to, or the
+    /// default output if `None`.
+    fn output(&self, _path: &str) -> Option<String> {
+        None
+    }
+
+    /// The response to the requests whose events were accepted.
+    fn response(&self) -> warp::reply::Response {
+        warp::reply().into_response()
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn run(
         self,
@@ -89,14 +100,12 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
                 })
                 .untuple_one()
                 .and(warp::path::full())
-                .and(warp::header::optional::<String>("authorization"))
                 .and(warp::header::optional::<String>("content-encoding"))
                 .and(warp::header::headers_cloned())
                 .and(warp::body::bytes())
                 .and(warp::query::<HashMap<String, String>>())
                 .and_then(
                     move |path: FullPath,
-                          auth_header,
                           encoding_header,
                           headers: HeaderMap,
                           body: Bytes,
@@ -110,7 +119,7 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
                         });
 
                         let events = auth
-                            .is_valid(&auth_header)
+                            .is_valid(&headers, &body)
                             .and_then(|()| decode(&encoding_header, body))
                             .and_then(|body| {
                                 self.build_events(body, headers, query_parameters, path.as_str())
@@ -125,7 +134,9 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
                                 events
                             });
 
-                        handle_request(events, acknowledgements, cx.out.clone())
+                        let output = self.output(http_path);
+                        let response = self.response();
+                        handle_request(events, output, response, acknowledgements, cx.out.clone())
                     },
                 )
                 .with(warp::trace(move |_info| span.clone()));
@@ -167,6 +178,8 @@ impl warp::reject::Reject for RejectShuttingDown {}
 
 async fn handle_request(
     events: Result<Vec<Event>, ErrorMessage>,
+    output: Option<String>,
+    response: warp::reply::Response,
     acknowledgements: bool,
     mut out: SourceSender,
 ) -> Result<impl warp::Reply, Rejection> {
@@ -174,16 +187,18 @@ async fn handle_request(
         Ok(mut events) => {
             let receiver = BatchNotifier::maybe_apply_to_events(acknowledgements, &mut events);
 
-            out.send_batch(events)
-                .map_err(move |error: crate::source_sender::ClosedError| {
-                    // can only fail if receiving end disconnected, so we are shutting down,
-                    // probably not gracefully.
-                    error!(message = "Failed to forward events, downstream is closed.");
-                    error!(message = "Tried to send the following event.", %error);
-                    warp::reject::custom(RejectShuttingDown)
-                })
-                .and_then(|_| handle_batch_status(receiver))
-                .await
+            let sent = match output {
+                Some(name) => out.send_batch_named(&name, events).await,
+                None => out.send_batch(events).await,
+            };
+            if let Err(error) = sent {
+                // can only fail if receiving end disconnected, so we are shutting down,
+                // probably not gracefully.
+                error!(message = "Failed to forward events, downstream is closed.");
+                error!(message = "Tried to send the following event.", %error);
+                return Err(warp::reject::custom(RejectShuttingDown));
+            }
+            handle_batch_status(receiver, response).await
         }
         Err(error) => {
             emit!(HttpBadRequest::new(error.code(), error.message()));
@@ -194,11 +209,12 @@ async fn handle_request(
 
 async fn handle_batch_status(
     receiver: Option<BatchStatusReceiver>,
+    response: warp::reply::Response,
 ) -> Result<impl warp::Reply, Rejection> {
     match receiver {
-        None => Ok(warp::reply()),
+        None => Ok(response),
         Some(receiver) => match receiver.await {
-            BatchStatus::Delivered => Ok(warp::reply()),
+            BatchStatus::Delivered => Ok(response),
             BatchStatus::Errored => Err(warp::reject::custom(ErrorMessage::new(
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "Error delivering contents to sink".into(),
@@ -1,18 +1,32 @@
+#[cfg(feature = "sources-utils-audit-log")]
+pub mod audit_log;
 #[cfg(any(feature = "sources-http"))]
 mod body_decoding;
+#[cfg(any(feature = "sources-exec", feature = "sources-stdin"))]
+mod delivery;
 mod encoding_config;
 #[cfg(any(
+    feature = "sources-amqp",
+    feature = "sources-azure_event_hubs",
     feature = "sources-aws_sqs",
+    feature = "sources-exec",
     feature = "sources-file",
     feature = "sources-gcp_pubsub",
     feature = "sources-journald",
     feature = "sources-kafka",
-    feature = "sources-splunk_hec"
+    feature = "sources-mongodb_change_stream",
+    feature = "sources-mqtt",
+    feature = "sources-nats",
+    feature = "sources-postgresql_cdc",
+    feature = "sources-pulsar",
+    feature = "sources-splunk_hec",
+    feature = "sources-stdin",
+    all(windows, feature = "sources-windows_event_log")
 ))]
 pub mod finalizer;
 #[cfg(all(unix, feature = "sources-dnstap"))]
 pub mod framestream;
-#[cfg(feature = "sources-vector")]
+#[cfg(any(feature = "sources-opentelemetry", feature = "sources-vector"))]
 pub mod grpc;
 #[cfg(any(
     feature = "sources-utils-http-auth",
@@ -25,6 +39,8 @@ mod http;
 #[cfg(any(feature = "sources-aws_sqs", feature = "sources-gcp_pubsub"))]
 mod message_decoding;
 pub mod multiline_config;
+#[cfg(any(feature = "sources-grpc", feature = "sources-kafka"))]
+mod protobuf;
 #[cfg(all(feature = "sources-utils-tls", feature = "listenfd"))]
 mod tcp;
 #[cfg(all(unix, any(feature = "sources-socket", feature = "sources-utils-unix",)))]
@@ -40,6 +56,8 @@ mod unix_stream;
 ))]
 mod wrappers;
 
+#[cfg(any(feature = "sources-exec", feature = "sources-stdin"))]
+pub(crate) use delivery::DeliveryTracker;
 #[cfg(feature = "sources-file")]
 pub use encoding_config::EncodingConfig;
 pub use multiline_config::MultilineConfig;
@@ -75,3 +93,5 @@ pub use self::http::HttpSource;
 pub use self::http::HttpSourceAuthConfig;
 #[cfg(any(feature = "sources-aws_sqs", feature = "sources-gcp_pubsub"))]
 pub use self::message_decoding::decode_message;
+#[cfg(any(feature = "sources-grpc", feature = "sources-kafka"))]
+pub use self::protobuf::message_to_value;
@@ -4,15 +4,28 @@ use regex::bytes::Regex;
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 
-use crate::line_agg;
+use crate::{
+    conditions::{Condition, ConditionConfig, VrlConfig},
+    line_agg,
+};
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct MultilineConfig {
-    pub start_pattern: String,
-    pub condition_pattern: String,
+    #[serde(default)]
+    pub start_pattern: Option<String>,
+    #[serde(default)]
+    pub start_condition: Option<String>,
+    #[serde(default)]
+    pub condition_pattern: Option<String>,
+    #[serde(default)]
+    pub condition: Option<String>,
     pub mode: line_agg::Mode,
     pub timeout_ms: u64,
+    #[serde(default)]
+    pub max_lines: Option<usize>,
+    #[serde(default)]
+    pub max_bytes: Option<usize>,
 }
 
 impl TryFrom<&MultilineConfig> for line_agg::Config {
@@ -21,15 +34,42 @@ impl TryFrom<&MultilineConfig> for line_agg::Config {
     fn try_from(config: &MultilineConfig) -> Result<Self, Self::Error> {
         let MultilineConfig {
             start_pattern,
+            start_condition,
             condition_pattern,
+            condition,
             mode,
             timeout_ms,
+            max_lines,
+            max_bytes,
         } = config;
 
-        let start_pattern = Regex::new(start_pattern)
-            .with_context(|_| InvalidMultilineStartPatternSnafu { start_pattern })?;
-        let condition_pattern = Regex::new(condition_pattern)
-            .with_context(|_| InvalidMultilineConditionPatternSnafu { condition_pattern })?;
+        let start_pattern = match (start_pattern, start_condition) {
+            (Some(start_pattern), None) => Regex::new(start_pattern)
+                .with_context(|_| InvalidMultilineStartPatternSnafu { start_pattern })?
+                .into(),
+            (None, Some(start_condition)) => line_agg::Matcher::Condition(
+                build_condition(start_condition)
+                    .map_err(|message| Error::InvalidMultilineStartCondition { message })?,
+            ),
+            _ => {
+                return Err(Error::MultilineStartMatcher);
+            }
+        };
+        let condition_pattern = match (condition_pattern, condition) {
+            (Some(condition_pattern), None) => Regex::new(condition_pattern)
+                .with_context(|_| InvalidMultilineConditionPatternSnafu { condition_pattern })?
+                .into(),
+            (None, Some(condition)) => line_agg::Matcher::Condition(
+                build_condition(condition)
+                    .map_err(|message| Error::InvalidMultilineCondition { message })?,
+            ),
+            _ => {
+                return Err(Error::MultilineConditionMatcher);
+            }
+        };
+        if *max_lines == Some(0) || *max_bytes == Some(0) {
+            return Err(Error::MultilineZeroLimit);
+        }
         let timeout = Duration::from_millis(*timeout_ms);
 
         Ok(Self {
@@ -37,10 +77,21 @@ impl TryFrom<&MultilineConfig> for line_agg::Config {
             condition_pattern,
             mode: *mode,
             timeout,
+            max_lines: *max_lines,
+            max_bytes: *max_bytes,
         })
     }
 }
 
+fn build_condition(source: &str) -> Result<Condition, String> {
+    VrlConfig {
+        source: source.to_owned(),
+        runtime: Default::default(),
+    }
+    .build(&Default::default())
+    .map_err(|error| error.to_string())
+}
+
 #[derive(Debug, Snafu)]
 pub enum Error {
     #[snafu(display(
@@ -61,4 +112,14 @@ pub enum Error {
         condition_pattern: String,
         source: regex::Error,
     },
+    #[snafu(display("unable to compile multiline start condition: {}", message))]
+    InvalidMultilineStartCondition { message: String },
+    #[snafu(display("unable to compile multiline condition: {}", message))]
+    InvalidMultilineCondition { message: String },
+    #[snafu(display("exactly one of multiline start_pattern or start_condition must be set"))]
+    MultilineStartMatcher,
+    #[snafu(display("exactly one of multiline condition_pattern or condition must be set"))]
+    MultilineConditionMatcher,
+    #[snafu(display("multiline max_lines and max_bytes must be greater than zero"))]
+    MultilineZeroLimit,
 }
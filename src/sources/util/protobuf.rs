@@ -0,0 +1,75 @@
+//! Conversions of the dynamic Protobuf messages, whose types are only known at runtime.
+
+use std::collections::BTreeMap;
+
+use prost_reflect::{DynamicMessage, Kind, MapKey};
+
+use crate::event::Value;
+
+/// Converts the fields set in the message, following the JSON mapping of Protobuf, which omits
+/// the fields set to their default value.
+pub fn message_to_value(message: &DynamicMessage) -> Value {
+    Value::Object(
+        message
+            .descriptor()
+            .fields()
+            .filter(|field| message.has_field(field))
+            .map(|field| {
+                let value = protobuf_to_value(&message.get_field(&field), &field.kind());
+                (field.name().to_string(), value)
+            })
+            .collect::<BTreeMap<_, _>>(),
+    )
+}
+
+fn protobuf_to_value(value: &prost_reflect::Value, kind: &Kind) -> Value {
+    use prost_reflect::Value as ProtobufValue;
+
+    match value {
+        ProtobufValue::Bool(value) => Value::from(*value),
+        ProtobufValue::I32(value) => Value::from(*value),
+        ProtobufValue::I64(value) => Value::from(*value),
+        ProtobufValue::U32(value) => Value::from(*value),
+        ProtobufValue::U64(value) => Value::from(*value),
+        ProtobufValue::F32(value) => Value::from(f64::from(*value)),
+        ProtobufValue::F64(value) => Value::from(*value),
+        ProtobufValue::String(value) => Value::from(value.as_str()),
+        ProtobufValue::Bytes(value) => Value::from(value.clone()),
+        ProtobufValue::EnumNumber(number) => match kind {
+            Kind::Enum(descriptor) => descriptor
+                .get_value(*number)
+                .map(|value| Value::from(value.name()))
+                .unwrap_or_else(|| Value::from(*number)),
+            _ => Value::from(*number),
+        },
+        ProtobufValue::Message(message) => message_to_value(message),
+        ProtobufValue::List(values) => Value::Array(
+            values
+                .iter()
+                .map(|value| protobuf_to_value(value, kind))
+                .collect(),
+        ),
+        ProtobufValue::Map(values) => {
+            let value_kind = match kind {
+                Kind::Message(entry) => entry.map_entry_value_field().kind(),
+                kind => kind.clone(),
+            };
+            Value::Object(
+                values
+                    .iter()
+                    .map(|(key, value)| {
+                        let key = match key {
+                            MapKey::Bool(key) => key.to_string(),
+                            MapKey::I32(key) => key.to_string(),
+                            MapKey::I64(key) => key.to_string(),
+                            MapKey::U32(key) => key.to_string(),
+                            MapKey::U64(key) => key.to_string(),
+                            MapKey::String(key) => key.clone(),
+                        };
+                        (key, protobuf_to_value(value, &value_kind))
+                    })
+                    .collect(),
+            )
+        }
+    }
+}
@@ -31,7 +31,7 @@ use crate::{
     },
     shutdown::ShutdownSignal,
     tcp::TcpKeepaliveConfig,
-    tls::{MaybeTlsIncomingStream, MaybeTlsListener, MaybeTlsSettings},
+    tls::{CertificateMetadata, MaybeTlsIncomingStream, MaybeTlsListener, MaybeTlsSettings},
     SourceSender,
 };
 
@@ -109,7 +109,13 @@ where
 
     fn decoder(&self) -> Self::Decoder;
 
-    fn handle_events(&self, _events: &mut [Event], _host: std::net::SocketAddr) {}
+    fn handle_events(
+        &self,
+        _events: &mut [Event],
+        _host: std::net::SocketAddr,
+        _certificate: Option<&CertificateMetadata>,
+    ) {
+    }
 
     fn build_acker(&self, item: &[Self::Item]) -> Self::Acker;
 
@@ -253,6 +259,8 @@ async fn handle_stream<T>(
         }
     };
 
+    let certificate = socket.peer_certificate_metadata();
+
     if let Some(keepalive) = keepalive {
         if let Err(error) = socket.set_keepalive(keepalive) {
             warn!(message = "Failed configuring TCP keepalive.", %error);
@@ -334,7 +342,7 @@ async fn handle_stream<T>(
                             }
                         }
 
-                        source.handle_events(&mut events, peer_addr);
+                        source.handle_events(&mut events, peer_addr, certificate.as_ref());
                         match out.send_batch(events).await {
                             Ok(_) => {
                                 let ack = match receiver {
@@ -1,23 +1,30 @@
+use std::{
+    collections::BTreeMap,
+    io::{self, Read},
+    net::SocketAddr,
+};
+
 use futures::TryFutureExt;
+use prost::Message;
 use serde::{Deserialize, Serialize};
-use std::net::SocketAddr;
-use tokio::net::TcpStream;
 use tonic::{
-    transport::{server::Connected, Certificate},
+    metadata::{AsciiMetadataValue, MetadataMap},
     Request, Response, Status,
 };
 use vector_core::{
-    event::{BatchNotifier, BatchStatus, BatchStatusReceiver, Event},
+    event::{BatchNotifier, BatchStatus, BatchStatusReceiver, Event, Value},
     ByteSizeOf,
 };
 
 use crate::{
     config::{AcknowledgementsConfig, DataType, GenerateConfig, Output, Resource, SourceContext},
-    internal_events::{EventsReceived, StreamClosedError},
+    internal_events::{
+        EventsReceived, StreamClosedError, VectorProtoDecodeError, VectorZstdDecompressError,
+    },
     proto::vector as proto,
     serde::bool_or_struct,
     sources::{util::grpc::run_grpc_server, Source},
-    tls::{MaybeTlsIncomingStream, MaybeTlsSettings, TlsEnableableConfig},
+    tls::{MaybeTlsSettings, TlsEnableableConfig},
     SourceSender,
 };
 
@@ -25,6 +32,7 @@ use crate::{
 pub struct Service {
     pipeline: SourceSender,
     acknowledgements: bool,
+    client_metadata_key: Option<String>,
 }
 
 #[tonic::async_trait]
@@ -33,12 +41,25 @@ impl proto::Service for Service {
         &self,
         request: Request<proto::PushEventsRequest>,
     ) -> Result<Response<proto::PushEventsResponse>, Status> {
-        let mut events: Vec<Event> = request
-            .into_inner()
-            .events
-            .into_iter()
-            .map(Event::from)
-            .collect();
+        let client_metadata = self
+            .client_metadata_key
+            .as_ref()
+            .map(|key| (key, client_metadata(request.metadata())))
+            .filter(|(_, metadata)| !metadata.is_empty());
+
+        let mut request = request.into_inner();
+        if !request.zstd_events.is_empty() {
+            let decompressed = decompress_zstd_events(&request.zstd_events)?;
+            request.events.extend(decompressed.events);
+        }
+
+        let mut events: Vec<Event> = request.events.into_iter().map(Event::from).collect();
+
+        if let Some((key, metadata)) = client_metadata {
+            events
+                .iter_mut()
+                .for_each(|event| insert_client_metadata(event, key, &metadata));
+        }
 
         let count = events.len();
         let byte_size = events.size_of();
@@ -58,7 +79,9 @@ impl proto::Service for Service {
             .and_then(|_| handle_batch_status(receiver))
             .await?;
 
-        Ok(Response::new(proto::PushEventsResponse {}))
+        Ok(with_accepted_compressions(Response::new(
+            proto::PushEventsResponse {},
+        )))
     }
 
     // TODO: figure out a way to determine if the current Vector instance is "healthy".
@@ -70,7 +93,90 @@ impl proto::Service for Service {
             status: proto::ServingStatus::Serving.into(),
         };
 
-        Ok(Response::new(message))
+        Ok(with_accepted_compressions(Response::new(message)))
+    }
+}
+
+/// The largest size of the events compressed with zstd once decompressed, so that a small request
+/// can't exhaust the memory.
+const MAX_DECOMPRESSED_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Advertises the compressions accepted besides the ones of gRPC, so that the sinks only send
+/// events compressed with them to the sources which accept them.
+fn with_accepted_compressions<T>(mut response: Response<T>) -> Response<T> {
+    response.metadata_mut().insert(
+        proto::ACCEPT_COMPRESSION_KEY,
+        AsciiMetadataValue::from_static("zstd"),
+    );
+    response
+}
+
+/// Decompresses the events compressed with zstd, which are an encoded request holding them.
+fn decompress_zstd_events(compressed: &[u8]) -> Result<proto::PushEventsRequest, Status> {
+    let mut decompressed = Vec::new();
+    zstd::stream::read::Decoder::new(compressed)
+        .and_then(|decoder| {
+            decoder
+                .take(MAX_DECOMPRESSED_SIZE + 1)
+                .read_to_end(&mut decompressed)
+        })
+        .and_then(|size| {
+            if size as u64 > MAX_DECOMPRESSED_SIZE {
+                Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("decompressed events exceed {} bytes", MAX_DECOMPRESSED_SIZE),
+                ))
+            } else {
+                Ok(())
+            }
+        })
+        .map_err(|error| {
+            emit!(VectorZstdDecompressError { error: &error });
+            Status::invalid_argument(error.to_string())
+        })?;
+
+    proto::PushEventsRequest::decode(decompressed.as_slice()).map_err(|error| {
+        emit!(VectorProtoDecodeError { error: &error });
+        Status::invalid_argument(error.to_string())
+    })
+}
+
+/// Reads the metadata the client identifies itself with from the request metadata.
+fn client_metadata(metadata: &MetadataMap) -> BTreeMap<String, String> {
+    metadata
+        .iter()
+        .filter_map(|entry| match entry {
+            tonic::metadata::KeyAndValueRef::Ascii(key, value) => {
+                let name = key.as_str().strip_prefix(proto::CLIENT_METADATA_PREFIX)?;
+                let value = value.to_str().ok()?;
+                Some((name.to_owned(), value.to_owned()))
+            }
+            tonic::metadata::KeyAndValueRef::Binary(..) => None,
+        })
+        .collect()
+}
+
+/// Attaches the client metadata to the event, as an object at `key` for logs and traces and as
+/// tags prefixed with `key` for metrics.
+fn insert_client_metadata(event: &mut Event, key: &str, metadata: &BTreeMap<String, String>) {
+    let object = || {
+        metadata
+            .iter()
+            .map(|(name, value)| (name.clone(), Value::from(value.as_str())))
+            .collect::<BTreeMap<_, _>>()
+    };
+    match event {
+        Event::Log(log) => {
+            log.insert(key, object());
+        }
+        Event::Trace(trace) => {
+            trace.insert(key, object());
+        }
+        Event::Metric(metric) => {
+            for (name, value) in metadata {
+                metric.insert_tag(format!("{}_{}", key, name), value.clone());
+            }
+        }
     }
 }
 
@@ -94,6 +200,10 @@ pub struct VectorConfig {
     pub shutdown_timeout_secs: u64,
     #[serde(default)]
     tls: Option<TlsEnableableConfig>,
+    /// The field the metadata clients identify themselves with, such as their agent ID or site, is
+    /// attached to their events at. It isn't attached when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    client_metadata_key: Option<String>,
     #[serde(default, deserialize_with = "bool_or_struct")]
     acknowledgements: AcknowledgementsConfig,
 }
@@ -108,6 +218,7 @@ impl GenerateConfig for VectorConfig {
             address: "0.0.0.0:6000".parse().unwrap(),
             shutdown_timeout_secs: default_shutdown_timeout_secs(),
             tls: None,
+            client_metadata_key: None,
             acknowledgements: Default::default(),
         })
         .unwrap()
@@ -121,6 +232,7 @@ impl VectorConfig {
         let service = proto::Server::new(Service {
             pipeline: cx.out,
             acknowledgements,
+            client_metadata_key: self.client_metadata_key.clone(),
         })
         .accept_gzip();
         let source =
@@ -144,31 +256,6 @@ impl VectorConfig {
     }
 }
 
-#[derive(Clone)]
-pub struct MaybeTlsConnectInfo {
-    pub remote_addr: SocketAddr,
-    pub peer_certs: Option<Vec<Certificate>>,
-}
-
-impl Connected for MaybeTlsIncomingStream<TcpStream> {
-    type ConnectInfo = MaybeTlsConnectInfo;
-
-    fn connect_info(&self) -> Self::ConnectInfo {
-        MaybeTlsConnectInfo {
-            remote_addr: self.peer_addr(),
-            peer_certs: self
-                .ssl_stream()
-                .and_then(|s| s.ssl().peer_cert_chain())
-                .map(|s| {
-                    s.into_iter()
-                        .filter_map(|c| c.to_pem().ok())
-                        .map(Certificate::from_pem)
-                        .collect()
-                }),
-        }
-    }
-}
-
 #[cfg(feature = "sinks-vector")]
 #[cfg(test)]
 mod tests {
@@ -252,4 +339,94 @@ mod tests {
         })
         .await;
     }
+
+    #[tokio::test]
+    async fn receive_zstd_message() {
+        assert_source_compliance(&SOCKET_PUSH_SOURCE_TAGS, async {
+            let addr = test_util::next_addr();
+            let config = format!(r#"address = "{}""#, addr);
+            let source: VectorConfig = toml::from_str(&config).unwrap();
+
+            let (tx, rx) = SourceSender::new_test();
+            let server = source
+                .build(SourceContext::new_test(tx, None))
+                .await
+                .unwrap();
+            tokio::spawn(server);
+            test_util::wait_for_tcp(addr).await;
+
+            // The sink's health check learns that the source accepts zstd.
+            let config = format!(
+                r#"address = "{}"
+            compression = "zstd""#,
+                addr
+            );
+            let sink: SinkConfig = toml::from_str(&config).unwrap();
+            let cx = SinkContext::new_test();
+            let (sink, healthcheck) = sink.build(cx).await.unwrap();
+            healthcheck.await.unwrap();
+
+            let (events, stream) = test_util::random_events_with_stream(100, 100, None);
+            sink.run(stream).await.unwrap();
+
+            let output = test_util::collect_ready(rx).await;
+            assert_event_data_eq!(events, output);
+        })
+        .await;
+    }
+
+    #[test]
+    fn rejects_oversized_zstd_events() {
+        let request = proto::PushEventsRequest {
+            events: Vec::new(),
+            zstd_events: vec![0; MAX_DECOMPRESSED_SIZE as usize + 1],
+        };
+        let compressed = zstd::bulk::compress(&request.encode_to_vec(), 0).unwrap();
+
+        let status = decompress_zstd_events(&compressed).unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn attaches_client_metadata() {
+        assert_source_compliance(&SOCKET_PUSH_SOURCE_TAGS, async {
+            let addr = test_util::next_addr();
+            let config = format!(
+                r#"address = "{}"
+            client_metadata_key = "client""#,
+                addr
+            );
+            let source: VectorConfig = toml::from_str(&config).unwrap();
+
+            let (tx, rx) = SourceSender::new_test();
+            let server = source
+                .build(SourceContext::new_test(tx, None))
+                .await
+                .unwrap();
+            tokio::spawn(server);
+            test_util::wait_for_tcp(addr).await;
+
+            let config = format!(
+                r#"address = "{}"
+            client_metadata.agent_id = "agent-1"
+            client_metadata.site = "paris""#,
+                addr
+            );
+            let sink: SinkConfig = toml::from_str(&config).unwrap();
+            let cx = SinkContext::new_test();
+            let (sink, _) = sink.build(cx).await.unwrap();
+
+            let (events, stream) = test_util::random_events_with_stream(100, 10, None);
+            sink.run(stream).await.unwrap();
+
+            let output = test_util::collect_ready(rx).await;
+            assert_eq!(output.len(), events.len());
+            for event in output {
+                let log = event.as_log();
+                assert_eq!(log["client.agent_id"], "agent-1".into());
+                assert_eq!(log["client.site"], "paris".into());
+            }
+        })
+        .await;
+    }
 }
@@ -0,0 +1,366 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+use chrono::Utc;
+use codecs::decoding::{DeserializerConfig, FramingConfig, StreamDecodingError};
+use futures::{SinkExt, StreamExt};
+use http::header::{HeaderMap, HeaderName, HeaderValue, SEC_WEBSOCKET_PROTOCOL};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use tokio::time;
+use tokio_tungstenite::tungstenite::{error::Error as WsError, protocol::Message};
+use tokio_util::codec::FramedRead;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    codecs::{Decoder, DecodingConfig},
+    config::{log_schema, GenerateConfig, Output, SourceConfig, SourceContext, SourceDescription},
+    event::Event,
+    http::Auth,
+    internal_events::{
+        prelude::error_stage, BytesReceived, ConnectionOpen, EventsReceived, OpenGauge,
+        StreamClosedError, WsConnectionError, WsConnectionShutdown,
+    },
+    serde::{default_decoding, default_framing_message_based},
+    shutdown::ShutdownSignal,
+    tls::{MaybeTlsSettings, TlsEnableableConfig},
+    websocket::{is_closed, pong_timeout, PingInterval, WebSocketConnector},
+    SourceSender,
+};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebSocketSourceConfig {
+    /// The WebSocket URI to connect to, as in `wss://example.com/stream`.
+    uri: String,
+    /// Additional headers sent with the handshake requests.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    headers: IndexMap<String, String>,
+    auth: Option<Auth>,
+    /// The subprotocols offered to the server, in order of preference.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    subprotocols: Vec<String>,
+    /// The text messages sent after each connection, such as the subscription requests of the
+    /// streaming APIs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    subscribe_messages: Vec<String>,
+    ping_interval: Option<u64>,
+    ping_timeout: Option<u64>,
+    #[serde(default = "default_reconnect_initial_backoff_ms")]
+    reconnect_initial_backoff_ms: u64,
+    #[serde(default = "default_reconnect_max_backoff_secs")]
+    reconnect_max_backoff_secs: u64,
+    tls: Option<TlsEnableableConfig>,
+    #[serde(default = "default_framing_message_based")]
+    framing: FramingConfig,
+    #[serde(default = "default_decoding")]
+    decoding: DeserializerConfig,
+}
+
+const fn default_reconnect_initial_backoff_ms() -> u64 {
+    500
+}
+
+const fn default_reconnect_max_backoff_secs() -> u64 {
+    60
+}
+
+inventory::submit! {
+    SourceDescription::new::<WebSocketSourceConfig>("websocket")
+}
+
+impl GenerateConfig for WebSocketSourceConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"
+            uri = "wss://stream.example.com/ws"
+            subscribe_messages = ['{"op": "subscribe", "channel": "trades"}']
+            "#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "websocket")]
+impl SourceConfig for WebSocketSourceConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let tls = MaybeTlsSettings::from_config(&self.tls, false)?;
+        let connector = WebSocketConnector::new(
+            self.uri.clone(),
+            tls,
+            Duration::from_millis(self.reconnect_initial_backoff_ms),
+            Duration::from_secs(self.reconnect_max_backoff_secs),
+        )?
+        .with_headers(self.build_headers()?);
+        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build();
+
+        Ok(Box::pin(websocket_source(
+            self.clone(),
+            connector,
+            decoder,
+            cx.shutdown,
+            cx.out,
+        )))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(self.decoding.output_type())]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "websocket"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+impl WebSocketSourceConfig {
+    fn build_headers(&self) -> crate::Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            headers.insert(
+                HeaderName::from_bytes(name.as_bytes())?,
+                HeaderValue::from_str(value)?,
+            );
+        }
+        if !self.subprotocols.is_empty() {
+            headers.insert(
+                SEC_WEBSOCKET_PROTOCOL,
+                HeaderValue::from_str(&self.subprotocols.join(", "))?,
+            );
+        }
+        if let Some(auth) = &self.auth {
+            auth.apply_headers_map(&mut headers);
+        }
+        Ok(headers)
+    }
+}
+
+async fn websocket_source(
+    config: WebSocketSourceConfig,
+    connector: WebSocketConnector,
+    decoder: Decoder,
+    mut shutdown: ShutdownSignal,
+    mut out: SourceSender,
+) -> Result<(), ()> {
+    let ping_timeout = config.ping_timeout.filter(|timeout| *timeout > 0);
+
+    'connection: loop {
+        let ws_stream = tokio::select! {
+            _ = &mut shutdown => break,
+            ws_stream = connector.connect_backoff(error_stage::RECEIVING) => ws_stream,
+        };
+        let _open_token = OpenGauge::new().open(|count| emit!(ConnectionOpen { count }));
+        let (mut ws_sink, mut ws_stream) = ws_stream.split();
+
+        // The subscriptions don't outlive the connections, so they're renewed on every one.
+        for message in &config.subscribe_messages {
+            if let Err(error) = ws_sink.send(Message::text(message.clone())).await {
+                emit!(WsConnectionError {
+                    error,
+                    stage: error_stage::RECEIVING,
+                });
+                continue 'connection;
+            }
+        }
+
+        let mut ping_interval = PingInterval::new(config.ping_interval.filter(|v| *v > 0));
+        // When the oldest ping which wasn't answered yet was sent.
+        let mut unanswered_ping = None;
+
+        let error = loop {
+            let pong_deadline = unanswered_ping
+                .zip(ping_timeout)
+                .map(|(sent_at, timeout)| sent_at + Duration::from_secs(timeout));
+
+            tokio::select! {
+                _ = &mut shutdown => {
+                    let _ = ws_sink.close().await;
+                    break 'connection;
+                },
+
+                _ = ping_interval.tick() => {
+                    if let Err(error) = ws_sink.send(Message::Ping(Vec::new())).await {
+                        break error;
+                    }
+                    unanswered_ping.get_or_insert_with(time::Instant::now);
+                },
+
+                error = pong_timeout(pong_deadline) => break error,
+
+                message = ws_stream.next() => {
+                    // Pings are answered automatically by tungstenite during reading from the stream.
+                    match message {
+                        Some(Ok(Message::Text(text))) => {
+                            handle_message(Bytes::from(text), &decoder, &mut out).await?
+                        },
+                        Some(Ok(Message::Binary(data))) => {
+                            handle_message(Bytes::from(data), &decoder, &mut out).await?
+                        },
+                        Some(Ok(Message::Pong(_))) => unanswered_ping = None,
+                        Some(Ok(_)) => {},
+                        Some(Err(error)) => break error,
+                        None => break WsError::ConnectionClosed,
+                    }
+                },
+            }
+        };
+
+        // Reconnect after the connection fails.
+        if is_closed(&error) {
+            emit!(WsConnectionShutdown);
+        } else {
+            emit!(WsConnectionError {
+                error,
+                stage: error_stage::RECEIVING,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_message(
+    payload: Bytes,
+    decoder: &Decoder,
+    out: &mut SourceSender,
+) -> Result<(), ()> {
+    emit!(BytesReceived {
+        byte_size: payload.len(),
+        protocol: "websocket",
+    });
+
+    let now = Utc::now();
+    let mut stream = FramedRead::new(payload.as_ref(), decoder.clone());
+    while let Some(result) = stream.next().await {
+        match result {
+            Ok((events, _byte_size)) => {
+                let count = events.len();
+                emit!(EventsReceived {
+                    byte_size: events.size_of(),
+                    count,
+                });
+
+                let events = events.into_iter().map(|mut event| {
+                    if let Event::Log(ref mut log) = event {
+                        log.try_insert(log_schema().source_type_key(), Bytes::from("websocket"));
+                        log.try_insert(log_schema().timestamp_key(), now);
+                    }
+                    event
+                });
+
+                if let Err(error) = out.send_batch(events).await {
+                    emit!(StreamClosedError { error, count });
+                    return Err(());
+                }
+            }
+            Err(error) => {
+                // Error is logged by `crate::codecs`, no further handling is needed here.
+                if !error.can_continue() {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use http::header::AUTHORIZATION;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::{
+        accept_hdr_async,
+        tungstenite::handshake::server::{Request, Response},
+    };
+
+    use super::*;
+    use crate::test_util::{
+        collect_n,
+        components::{assert_source_compliance, SOURCE_TAGS},
+        next_addr, trace_init,
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<WebSocketSourceConfig>();
+    }
+
+    #[tokio::test]
+    async fn resubscribes_after_reconnecting() {
+        trace_init();
+
+        let addr = next_addr();
+        let listener = TcpListener::bind(addr).await.unwrap();
+        let handshakes = Arc::new(Mutex::new(Vec::new()));
+        let server_handshakes = Arc::clone(&handshakes);
+        tokio::spawn(async move {
+            for message in ["first", "second"] {
+                let (stream, _) = listener.accept().await.unwrap();
+                let handshakes = Arc::clone(&server_handshakes);
+                let mut ws_stream =
+                    accept_hdr_async(stream, move |request: &Request, mut response: Response| {
+                        handshakes.lock().unwrap().push(request.headers().clone());
+                        response
+                            .headers_mut()
+                            .insert(SEC_WEBSOCKET_PROTOCOL, HeaderValue::from_static("v1"));
+                        Ok(response)
+                    })
+                    .await
+                    .unwrap();
+
+                let subscribe = ws_stream.next().await.unwrap().unwrap();
+                assert_eq!(subscribe, Message::text("subscribe"));
+                ws_stream.send(Message::text(message)).await.unwrap();
+                // Dropping the connection makes the source reconnect.
+            }
+        });
+
+        let config = toml::from_str::<WebSocketSourceConfig>(&format!(
+            r#"
+            uri = "ws://{}"
+            headers.X-Api-Key = "secret"
+            auth.strategy = "bearer"
+            auth.token = "token"
+            subprotocols = ["v1", "v2"]
+            subscribe_messages = ["subscribe"]
+            reconnect_initial_backoff_ms = 10
+            "#,
+            addr
+        ))
+        .unwrap();
+
+        let events = assert_source_compliance(&SOURCE_TAGS, async {
+            let (tx, rx) = SourceSender::new_test();
+            let cx = SourceContext::new_test(tx, None);
+            tokio::spawn(config.build(cx).await.unwrap());
+            collect_n(rx, 2).await
+        })
+        .await;
+
+        let messages = events
+            .iter()
+            .map(|event| event.as_log()[log_schema().message_key()].to_string_lossy())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, ["first", "second"]);
+        for event in &events {
+            assert_eq!(
+                event.as_log()[log_schema().source_type_key()],
+                "websocket".into()
+            );
+        }
+
+        let handshakes = handshakes.lock().unwrap();
+        assert_eq!(handshakes.len(), 2);
+        for headers in handshakes.iter() {
+            assert_eq!(headers[SEC_WEBSOCKET_PROTOCOL], "v1, v2");
+            assert_eq!(headers["x-api-key"], "secret");
+            assert_eq!(headers[AUTHORIZATION], "Bearer token");
+        }
+    }
+}
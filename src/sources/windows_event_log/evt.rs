@@ -0,0 +1,296 @@
+//! A thin wrapper over the Windows Event Log API, subscribing to channels and rendering events.
+
+use std::{collections::HashMap, ffi::OsStr, fmt, os::windows::ffi::OsStrExt, ptr};
+
+use winapi::{
+    shared::{
+        minwindef::{DWORD, FALSE, TRUE},
+        winerror::{ERROR_INSUFFICIENT_BUFFER, ERROR_NO_MORE_ITEMS},
+    },
+    um::{
+        errhandlingapi::GetLastError,
+        handleapi::CloseHandle,
+        synchapi::CreateEventW,
+        winevt::{
+            EvtClose, EvtCreateBookmark, EvtFormatMessage, EvtFormatMessageEvent, EvtNext,
+            EvtOpenPublisherMetadata, EvtRender, EvtRenderBookmark, EvtRenderEventXml,
+            EvtSubscribe, EvtSubscribeStartAfterBookmark, EvtSubscribeStartAtOldestRecord,
+            EvtSubscribeToFutureEvents, EvtUpdateBookmark, EVT_HANDLE,
+        },
+        winnt::HANDLE,
+    },
+};
+
+#[derive(Debug)]
+pub(super) struct EvtError {
+    call: &'static str,
+    code: DWORD,
+}
+
+impl fmt::Display for EvtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} failed with error {}", self.call, self.code)
+    }
+}
+
+impl std::error::Error for EvtError {}
+
+fn last_error(call: &'static str) -> EvtError {
+    EvtError {
+        call,
+        code: unsafe { GetLastError() },
+    }
+}
+
+/// An event log handle, closed once dropped.
+struct Handle(EVT_HANDLE);
+
+impl Drop for Handle {
+    fn drop(&mut self) {
+        unsafe {
+            EvtClose(self.0);
+        }
+    }
+}
+
+/// The event signaled when new events are available, which subscriptions require even when
+/// they're polled, closed once dropped.
+struct Signal(HANDLE);
+
+impl Drop for Signal {
+    fn drop(&mut self) {
+        unsafe {
+            CloseHandle(self.0);
+        }
+    }
+}
+
+/// An event read from a subscription.
+pub(super) struct Event(Handle);
+
+impl Event {
+    /// Renders the event in its XML representation.
+    pub(super) fn xml(&self) -> Result<String, EvtError> {
+        render(self.0 .0, EvtRenderEventXml)
+    }
+}
+
+/// A pull subscription to the events matching a structured query, along with a bookmark of the
+/// last event read.
+pub(super) struct Subscription {
+    // The subscription is closed before its signal, as the fields are dropped in order.
+    handle: Handle,
+    _signal: Signal,
+    bookmark: Handle,
+    /// The metadata of the providers, which hold the messages of their events, or `None` for
+    /// the providers whose metadata couldn't be opened, such as the ones that were uninstalled.
+    publishers: HashMap<String, Option<Handle>>,
+}
+
+// Event log handles aren't tied to the thread that opened them.
+unsafe impl Send for Subscription {}
+
+impl Subscription {
+    /// Subscribes to the events of the query, after the event of the bookmark when there's one.
+    /// Without a bookmark, or when it's invalid, only the future events are read, unless
+    /// `read_existing_events` is set.
+    pub(super) fn new(
+        query: &str,
+        bookmark: Option<&str>,
+        read_existing_events: bool,
+    ) -> Result<Self, EvtError> {
+        let signal = unsafe { CreateEventW(ptr::null_mut(), TRUE, TRUE, ptr::null()) };
+        if signal.is_null() {
+            return Err(last_error("CreateEventW"));
+        }
+        let signal = Signal(signal);
+
+        let (bookmark, flags) = match bookmark.and_then(open_bookmark) {
+            Some(bookmark) => (bookmark, EvtSubscribeStartAfterBookmark),
+            None => {
+                let bookmark = unsafe { EvtCreateBookmark(ptr::null()) };
+                if bookmark.is_null() {
+                    return Err(last_error("EvtCreateBookmark"));
+                }
+                let flags = if read_existing_events {
+                    EvtSubscribeStartAtOldestRecord
+                } else {
+                    EvtSubscribeToFutureEvents
+                };
+                (Handle(bookmark), flags)
+            }
+        };
+
+        let query = wide(query);
+        let handle = unsafe {
+            EvtSubscribe(
+                ptr::null_mut(),
+                signal.0,
+                ptr::null(),
+                query.as_ptr(),
+                bookmark.0,
+                ptr::null_mut(),
+                None,
+                flags,
+            )
+        };
+        if handle.is_null() {
+            return Err(last_error("EvtSubscribe"));
+        }
+
+        Ok(Self {
+            handle: Handle(handle),
+            _signal: signal,
+            bookmark,
+            publishers: HashMap::new(),
+        })
+    }
+
+    /// Reads up to `count` of the available events, without waiting for new ones, and moves the
+    /// bookmark to the last of them.
+    pub(super) fn next(&mut self, count: usize) -> Result<Vec<Event>, EvtError> {
+        let mut handles: Vec<EVT_HANDLE> = vec![ptr::null_mut(); count];
+        let mut returned: DWORD = 0;
+        let result = unsafe {
+            EvtNext(
+                self.handle.0,
+                count as DWORD,
+                handles.as_mut_ptr(),
+                0,
+                0,
+                &mut returned,
+            )
+        };
+        if result == FALSE {
+            let error = last_error("EvtNext");
+            return if error.code == ERROR_NO_MORE_ITEMS {
+                Ok(Vec::new())
+            } else {
+                Err(error)
+            };
+        }
+
+        let events = handles[..returned as usize]
+            .iter()
+            .map(|handle| Event(Handle(*handle)))
+            .collect::<Vec<_>>();
+        if let Some(last) = events.last() {
+            if unsafe { EvtUpdateBookmark(self.bookmark.0, last.0 .0) } == FALSE {
+                return Err(last_error("EvtUpdateBookmark"));
+            }
+        }
+        Ok(events)
+    }
+
+    /// Renders the bookmark of the last event read in its XML representation.
+    pub(super) fn bookmark(&self) -> Result<String, EvtError> {
+        render(self.bookmark.0, EvtRenderBookmark)
+    }
+
+    /// Resolves the message of the event from the metadata of its provider, in the locale of the
+    /// system. Events whose provider or message can't be found don't have one.
+    pub(super) fn message(&mut self, event: &Event, provider: &str) -> Option<String> {
+        let publisher = self
+            .publishers
+            .entry(provider.to_owned())
+            .or_insert_with(|| {
+                let provider = wide(provider);
+                let handle = unsafe {
+                    EvtOpenPublisherMetadata(ptr::null_mut(), provider.as_ptr(), ptr::null(), 0, 0)
+                };
+                (!handle.is_null()).then(|| Handle(handle))
+            })
+            .as_ref()?;
+
+        let mut used: DWORD = 0;
+        let result = unsafe {
+            EvtFormatMessage(
+                publisher.0,
+                event.0 .0,
+                0,
+                0,
+                ptr::null_mut(),
+                EvtFormatMessageEvent,
+                0,
+                ptr::null_mut(),
+                &mut used,
+            )
+        };
+        if result == TRUE || unsafe { GetLastError() } != ERROR_INSUFFICIENT_BUFFER {
+            return None;
+        }
+
+        let mut buffer = vec![0u16; used as usize];
+        let result = unsafe {
+            EvtFormatMessage(
+                publisher.0,
+                event.0 .0,
+                0,
+                0,
+                ptr::null_mut(),
+                EvtFormatMessageEvent,
+                buffer.len() as DWORD,
+                buffer.as_mut_ptr(),
+                &mut used,
+            )
+        };
+        (result == TRUE).then(|| from_wide(&buffer))
+    }
+}
+
+fn open_bookmark(xml: &str) -> Option<Handle> {
+    let xml = wide(xml);
+    let handle = unsafe { EvtCreateBookmark(xml.as_ptr()) };
+    (!handle.is_null()).then(|| Handle(handle))
+}
+
+fn render(fragment: EVT_HANDLE, flags: DWORD) -> Result<String, EvtError> {
+    // The size of the buffer, in bytes, is first queried.
+    let mut used: DWORD = 0;
+    let mut properties: DWORD = 0;
+    let result = unsafe {
+        EvtRender(
+            ptr::null_mut(),
+            fragment,
+            flags,
+            0,
+            ptr::null_mut(),
+            &mut used,
+            &mut properties,
+        )
+    };
+    if result == TRUE {
+        return Ok(String::new());
+    }
+    let error = last_error("EvtRender");
+    if error.code != ERROR_INSUFFICIENT_BUFFER {
+        return Err(error);
+    }
+
+    let mut buffer = vec![0u16; (used as usize + 1) / 2];
+    let result = unsafe {
+        EvtRender(
+            ptr::null_mut(),
+            fragment,
+            flags,
+            (buffer.len() * 2) as DWORD,
+            buffer.as_mut_ptr().cast(),
+            &mut used,
+            &mut properties,
+        )
+    };
+    if result == FALSE {
+        return Err(last_error("EvtRender"));
+    }
+    Ok(from_wide(&buffer))
+}
+
+fn wide(value: &str) -> Vec<u16> {
+    OsStr::new(value).encode_wide().chain(Some(0)).collect()
+}
+
+/// Reads a wide string, up to its null terminator.
+fn from_wide(value: &[u16]) -> String {
+    let len = value.iter().position(|c| *c == 0).unwrap_or(value.len());
+    String::from_utf16_lossy(&value[..len])
+}
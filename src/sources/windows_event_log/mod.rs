@@ -0,0 +1,659 @@
+use std::{fmt::Write, path::PathBuf};
+#[cfg(windows)]
+use std::{io, time::Duration};
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+#[cfg(windows)]
+use futures::StreamExt;
+use roxmltree::Node;
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+#[cfg(windows)]
+use vector_core::ByteSizeOf;
+
+#[cfg(windows)]
+use super::util::finalizer::OrderedFinalizer;
+use crate::{
+    config::{
+        log_schema, AcknowledgementsConfig, DataType, GenerateConfig, Output, SourceConfig,
+        SourceContext, SourceDescription,
+    },
+    event::{LogEvent, Value},
+    serde::bool_or_struct,
+};
+#[cfg(windows)]
+use crate::{
+    event::{BatchNotifier, BatchStatus, Event},
+    internal_events::{
+        EventsReceived, StreamClosedError, WindowsEventLogCheckpointError,
+        WindowsEventLogParseError, WindowsEventLogReadError,
+    },
+    shutdown::ShutdownSignal,
+    SourceSender,
+};
+
+#[cfg(windows)]
+mod evt;
+
+#[cfg(windows)]
+const CHECKPOINT_FILENAME: &str = "bookmark.xml";
+
+/// How often the bookmark of the last delivered event is written to the checkpoint.
+#[cfg(windows)]
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Debug, Derivative, Deserialize, Serialize)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct WindowsEventLogConfig {
+    /// The channels the events are read from, such as `System`, `Application` or `Security`.
+    channels: Vec<String>,
+    /// The XPath query selecting the events of the channels, such as `*[System[Level<=3]]`.
+    #[serde(default = "default_event_query")]
+    #[derivative(Default(value = "default_event_query()"))]
+    event_query: String,
+    /// Reads the events already in the channels when there's no checkpoint, rather than only the
+    /// new ones.
+    #[serde(default)]
+    read_existing_events: bool,
+    #[serde(default = "default_batch_size")]
+    #[derivative(Default(value = "default_batch_size()"))]
+    batch_size: u32,
+    #[serde(default = "default_poll_interval_secs")]
+    #[derivative(Default(value = "default_poll_interval_secs()"))]
+    poll_interval_secs: f64,
+    /// Resolves the messages of the events from the metadata of their providers.
+    #[serde(default = "crate::serde::default_true")]
+    #[derivative(Default(value = "true"))]
+    render_message: bool,
+    data_dir: Option<PathBuf>,
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+fn default_event_query() -> String {
+    "*".to_owned()
+}
+
+const fn default_batch_size() -> u32 {
+    100
+}
+
+const fn default_poll_interval_secs() -> f64 {
+    1.0
+}
+
+inventory::submit! {
+    SourceDescription::new::<WindowsEventLogConfig>("windows_event_log")
+}
+
+impl GenerateConfig for WindowsEventLogConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"channels = ["System", "Application"]"#).unwrap()
+    }
+}
+
+#[derive(Debug, PartialEq, Snafu)]
+enum BuildError {
+    #[snafu(display("At least one channel must be configured"))]
+    NoChannels,
+    #[snafu(display("`batch_size` must be positive"))]
+    InvalidBatchSize,
+    #[snafu(display("`poll_interval_secs` must be positive"))]
+    InvalidPollInterval,
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "windows_event_log")]
+impl SourceConfig for WindowsEventLogConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let query = self.query()?;
+        self.build_source(query, cx).await
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "windows_event_log"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+impl WindowsEventLogConfig {
+    /// Builds the structured query selecting the events of every channel.
+    fn query(&self) -> Result<String, BuildError> {
+        if self.channels.is_empty() {
+            return Err(BuildError::NoChannels);
+        }
+        if self.batch_size == 0 {
+            return Err(BuildError::InvalidBatchSize);
+        }
+        if self.poll_interval_secs <= 0.0 {
+            return Err(BuildError::InvalidPollInterval);
+        }
+
+        let mut query = String::from("<QueryList>");
+        for (id, channel) in self.channels.iter().enumerate() {
+            write!(
+                query,
+                r#"<Query Id="{}"><Select Path="{}">{}</Select></Query>"#,
+                id,
+                escape(channel),
+                escape(&self.event_query)
+            )
+            .expect("writing to a string can't fail");
+        }
+        query.push_str("</QueryList>");
+        Ok(query)
+    }
+
+    #[cfg(windows)]
+    async fn build_source(&self, query: String, cx: SourceContext) -> crate::Result<super::Source> {
+        let data_dir = cx
+            .globals
+            .resolve_and_make_data_subdir(self.data_dir.as_ref(), cx.key.id())?;
+        let checkpointer = Checkpointer::new(data_dir);
+        let bookmark = checkpointer.get().await;
+        let subscription =
+            evt::Subscription::new(&query, bookmark.as_deref(), self.read_existing_events)?;
+
+        let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
+        Ok(Box::pin(self.clone().run(
+            subscription,
+            checkpointer,
+            cx.out,
+            cx.shutdown,
+            acknowledgements,
+        )))
+    }
+
+    #[cfg(not(windows))]
+    async fn build_source(&self, _: String, _: SourceContext) -> crate::Result<super::Source> {
+        Err("The `windows_event_log` source is only supported on Windows.".into())
+    }
+
+    #[cfg(windows)]
+    async fn run(
+        self,
+        mut subscription: evt::Subscription,
+        checkpointer: Checkpointer,
+        mut out: SourceSender,
+        mut shutdown: ShutdownSignal,
+        acknowledgements: bool,
+    ) -> Result<(), ()> {
+        let (finalizer, mut ack_stream) =
+            OrderedFinalizer::<String>::maybe_new(acknowledgements, shutdown.clone());
+        let mut poll_interval =
+            tokio::time::interval(Duration::from_secs_f64(self.poll_interval_secs));
+        let mut checkpoint_interval = tokio::time::interval(CHECKPOINT_INTERVAL);
+        let mut pending_checkpoint = None;
+        let batch_size = self.batch_size as usize;
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => break,
+                _ = checkpoint_interval.tick() => {
+                    if let Some(bookmark) = pending_checkpoint.take() {
+                        checkpointer.set(&bookmark).await;
+                    }
+                },
+                entry = ack_stream.next() => {
+                    if let Some((BatchStatus::Delivered, bookmark)) = entry {
+                        pending_checkpoint = Some(bookmark);
+                    }
+                },
+                _ = poll_interval.tick() => loop {
+                    // The available events are read in batches, until there are none left.
+                    let events = match subscription.next(batch_size) {
+                        Ok(events) if events.is_empty() => break,
+                        Ok(events) => events,
+                        Err(error) => {
+                            emit!(WindowsEventLogReadError {
+                                error: error.to_string()
+                            });
+                            break;
+                        }
+                    };
+                    let drained = events.len() < batch_size;
+
+                    let mut logs = Vec::with_capacity(events.len());
+                    for event in events {
+                        let xml = match event.xml() {
+                            Ok(xml) => xml,
+                            Err(error) => {
+                                emit!(WindowsEventLogReadError {
+                                    error: error.to_string()
+                                });
+                                continue;
+                            }
+                        };
+                        let mut log = match parse_event(&xml) {
+                            Ok(log) => log,
+                            Err(error) => {
+                                emit!(WindowsEventLogParseError { error });
+                                continue;
+                            }
+                        };
+                        if self.render_message {
+                            let message = log
+                                .get("provider_name")
+                                .map(|provider| provider.to_string_lossy())
+                                .and_then(|provider| subscription.message(&event, &provider));
+                            if let Some(message) = message {
+                                log.insert(log_schema().message_key(), message);
+                            }
+                        }
+                        logs.push(Event::from(log));
+                    }
+
+                    let bookmark = match subscription.bookmark() {
+                        Ok(bookmark) => Some(bookmark),
+                        Err(error) => {
+                            emit!(WindowsEventLogReadError {
+                                error: error.to_string()
+                            });
+                            None
+                        }
+                    };
+
+                    let count = logs.len();
+                    if count > 0 {
+                        emit!(EventsReceived {
+                            count,
+                            byte_size: logs.size_of(),
+                        });
+                    }
+                    let logs = match (&finalizer, bookmark) {
+                        (Some(finalizer), Some(bookmark)) => {
+                            let (batch, receiver) = BatchNotifier::new_with_receiver();
+                            finalizer.add(bookmark, receiver);
+                            logs.into_iter()
+                                .map(|log| log.with_batch_notifier(&batch))
+                                .collect()
+                        }
+                        (None, bookmark) => {
+                            pending_checkpoint = bookmark.or_else(|| pending_checkpoint.take());
+                            logs
+                        }
+                        (Some(_), None) => logs,
+                    };
+                    if let Err(error) = out.send_batch(logs).await {
+                        emit!(StreamClosedError { error, count });
+                        return Err(());
+                    }
+
+                    if drained {
+                        break;
+                    }
+                },
+            }
+        }
+
+        if let Some(bookmark) = pending_checkpoint {
+            checkpointer.set(&bookmark).await;
+        }
+        Ok(())
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Builds the event from the XML representation of a Windows event.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_event(xml: &str) -> Result<LogEvent, roxmltree::Error> {
+    let document = roxmltree::Document::parse(xml)?;
+    let mut log = LogEvent::default();
+    for element in document.root_element().children().filter(Node::is_element) {
+        match element.tag_name().name() {
+            "System" => parse_system(element, &mut log),
+            "EventData" => {
+                let data = parse_event_data(element);
+                if !data.is_empty() {
+                    log.insert("event_data", Value::Object(data));
+                }
+            }
+            "UserData" => {
+                if let Some(data) = element.children().find(Node::is_element) {
+                    log.insert("user_data", element_to_value(data));
+                }
+            }
+            _ => {}
+        }
+    }
+    log.insert(
+        log_schema().source_type_key(),
+        Bytes::from("windows_event_log"),
+    );
+    Ok(log)
+}
+
+fn parse_system(system: Node, log: &mut LogEvent) {
+    for element in system.children().filter(Node::is_element) {
+        let text = element
+            .text()
+            .map(str::trim)
+            .filter(|text| !text.is_empty());
+        match element.tag_name().name() {
+            "Provider" => {
+                insert_attribute(log, element, "Name", "provider_name");
+                insert_attribute(log, element, "Guid", "provider_guid");
+            }
+            "Correlation" => {
+                insert_attribute(log, element, "ActivityID", "activity_id");
+                insert_attribute(log, element, "RelatedActivityID", "related_activity_id");
+            }
+            "Security" => insert_attribute(log, element, "UserID", "user_id"),
+            "TimeCreated" => {
+                if let Some(timestamp) = element
+                    .attribute("SystemTime")
+                    .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+                {
+                    log.insert(log_schema().timestamp_key(), timestamp.with_timezone(&Utc));
+                }
+            }
+            "Execution" => {
+                for (attribute, field) in [("ProcessID", "process_id"), ("ThreadID", "thread_id")] {
+                    if let Some(id) = element.attribute(attribute).and_then(parse_int) {
+                        log.insert(field, id);
+                    }
+                }
+            }
+            "EventID" => {
+                if let Some(id) = text.and_then(parse_int) {
+                    log.insert("event_id", id);
+                }
+                if let Some(qualifiers) = element.attribute("Qualifiers").and_then(parse_int) {
+                    log.insert("qualifiers", qualifiers);
+                }
+            }
+            "Level" => {
+                if let Some(level) = text.and_then(parse_int) {
+                    log.insert("level", level);
+                    log.insert("level_name", level_name(level));
+                }
+            }
+            name @ ("Version" | "Task" | "Opcode" | "EventRecordID") => {
+                let field = match name {
+                    "Version" => "version",
+                    "Task" => "task",
+                    "Opcode" => "opcode",
+                    _ => "record_id",
+                };
+                if let Some(value) = text.and_then(parse_int) {
+                    log.insert(field, value);
+                }
+            }
+            name @ ("Keywords" | "Channel" | "Computer") => {
+                if let Some(text) = text {
+                    log.insert(name.to_ascii_lowercase().as_str(), text.to_owned());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn insert_attribute(log: &mut LogEvent, element: Node, attribute: &str, field: &str) {
+    if let Some(value) = element.attribute(attribute) {
+        log.insert(field, value.to_owned());
+    }
+}
+
+/// Reads the data of the event, keyed by name, or by position, as `param1`, `param2` and so on,
+/// for the unnamed ones of classic events.
+fn parse_event_data(event_data: Node) -> std::collections::BTreeMap<String, Value> {
+    event_data
+        .children()
+        .filter(|node| node.is_element())
+        .enumerate()
+        .filter_map(|(index, element)| {
+            let value = Value::from(element.text().unwrap_or_default().to_owned());
+            match element.tag_name().name() {
+                "Data" => Some((
+                    element
+                        .attribute("Name")
+                        .map(ToOwned::to_owned)
+                        .unwrap_or_else(|| format!("param{}", index + 1)),
+                    value,
+                )),
+                "Binary" => Some(("binary".to_owned(), value)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Converts an element of the user data to its text, or to an object of its child elements.
+fn element_to_value(element: Node) -> Value {
+    let mut children = element.children().filter(Node::is_element).peekable();
+    if children.peek().is_none() {
+        return Value::from(element.text().unwrap_or_default().trim().to_owned());
+    }
+    Value::Object(
+        children
+            .map(|child| (child.tag_name().name().to_owned(), element_to_value(child)))
+            .collect(),
+    )
+}
+
+fn parse_int(text: &str) -> Option<i64> {
+    text.trim().parse().ok()
+}
+
+/// The names of the standard levels, as shown by the Event Viewer.
+const fn level_name(level: i64) -> &'static str {
+    match level {
+        1 => "Critical",
+        2 => "Error",
+        3 => "Warning",
+        5 => "Verbose",
+        _ => "Information",
+    }
+}
+
+/// Keeps the bookmark of the last delivered event in the data directory.
+#[cfg(windows)]
+struct Checkpointer {
+    path: PathBuf,
+}
+
+#[cfg(windows)]
+impl Checkpointer {
+    fn new(data_dir: PathBuf) -> Self {
+        Self {
+            path: data_dir.join(CHECKPOINT_FILENAME),
+        }
+    }
+
+    async fn get(&self) -> Option<String> {
+        match tokio::fs::read_to_string(&self.path).await {
+            Ok(bookmark) => Some(bookmark).filter(|bookmark| !bookmark.trim().is_empty()),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+            Err(error) => {
+                emit!(WindowsEventLogCheckpointError {
+                    error,
+                    path: &self.path
+                });
+                None
+            }
+        }
+    }
+
+    /// Writes the bookmark to a temporary file first, so a crash can't leave a partial bookmark
+    /// behind.
+    async fn set(&self, bookmark: &str) {
+        let temp = self.path.with_extension("tmp");
+        let result = async {
+            tokio::fs::write(&temp, bookmark).await?;
+            tokio::fs::rename(&temp, &self.path).await
+        }
+        .await;
+        if let Err(error) = result {
+            emit!(WindowsEventLogCheckpointError {
+                error,
+                path: &self.path
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<WindowsEventLogConfig>();
+    }
+
+    #[test]
+    fn builds_queries() {
+        let config: WindowsEventLogConfig = toml::from_str(
+            r#"
+            channels = ["System", "Microsoft-Windows-PowerShell/Operational"]
+            event_query = "*[System[Level<=3]]"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.query().unwrap(),
+            concat!(
+                "<QueryList>",
+                r#"<Query Id="0"><Select Path="System">*[System[Level&lt;=3]]</Select></Query>"#,
+                r#"<Query Id="1"><Select Path="Microsoft-Windows-PowerShell/Operational">*[System[Level&lt;=3]]</Select></Query>"#,
+                "</QueryList>",
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_configs() {
+        let config = WindowsEventLogConfig::default();
+        assert_eq!(config.query(), Err(BuildError::NoChannels));
+
+        let config = WindowsEventLogConfig {
+            channels: vec!["System".to_owned()],
+            poll_interval_secs: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(config.query(), Err(BuildError::InvalidPollInterval));
+    }
+
+    #[test]
+    fn parses_events() {
+        let log = parse_event(
+            r#"<Event xmlns="http://schemas.microsoft.com/win/2004/08/events/event">
+                <System>
+                    <Provider Name="Microsoft-Windows-Security-Auditing" Guid="{54849625-5478-4994-a5ba-3e3b0328c30d}"/>
+                    <EventID>4624</EventID>
+                    <Version>2</Version>
+                    <Level>0</Level>
+                    <Task>12544</Task>
+                    <Opcode>0</Opcode>
+                    <Keywords>0x8020000000000000</Keywords>
+                    <TimeCreated SystemTime="2022-04-01T12:30:45.1234567Z"/>
+                    <EventRecordID>98765</EventRecordID>
+                    <Correlation ActivityID="{b8a5ae7c-4580-0000-d4ae-a5b88045d801}"/>
+                    <Execution ProcessID="680" ThreadID="724"/>
+                    <Channel>Security</Channel>
+                    <Computer>web-01.example.com</Computer>
+                    <Security/>
+                </System>
+                <EventData>
+                    <Data Name="SubjectUserSid">S-1-5-18</Data>
+                    <Data Name="LogonType">5</Data>
+                </EventData>
+            </Event>"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            log["provider_name"],
+            "Microsoft-Windows-Security-Auditing".into()
+        );
+        assert_eq!(log["event_id"], 4624.into());
+        assert_eq!(log["version"], 2.into());
+        assert_eq!(log["level"], 0.into());
+        assert_eq!(log["level_name"], "Information".into());
+        assert_eq!(log["task"], 12544.into());
+        assert_eq!(log["keywords"], "0x8020000000000000".into());
+        assert_eq!(log["record_id"], 98765.into());
+        assert_eq!(
+            log["activity_id"],
+            "{b8a5ae7c-4580-0000-d4ae-a5b88045d801}".into()
+        );
+        assert_eq!(log["process_id"], 680.into());
+        assert_eq!(log["thread_id"], 724.into());
+        assert_eq!(log["channel"], "Security".into());
+        assert_eq!(log["computer"], "web-01.example.com".into());
+        assert!(log.get("user_id").is_none());
+        assert_eq!(log["event_data.SubjectUserSid"], "S-1-5-18".into());
+        assert_eq!(log["event_data.LogonType"], "5".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.ymd(2022, 4, 1)
+                .and_hms_nano(12, 30, 45, 123_456_700)
+                .into()
+        );
+        assert_eq!(
+            log[log_schema().source_type_key()],
+            "windows_event_log".into()
+        );
+    }
+
+    #[test]
+    fn parses_classic_and_user_data() {
+        let log = parse_event(
+            r#"<Event xmlns="http://schemas.microsoft.com/win/2004/08/events/event">
+                <System>
+                    <Provider Name="Application Error"/>
+                    <EventID Qualifiers="0">1000</EventID>
+                    <Level>2</Level>
+                    <Security UserID="S-1-5-21-1004"/>
+                </System>
+                <EventData>
+                    <Data>app.exe</Data>
+                    <Data>1.0.0.0</Data>
+                    <Binary>00FF</Binary>
+                </EventData>
+                <UserData>
+                    <LogFileCleared xmlns="http://manifests.microsoft.com/win/2004/08/windows/eventlog">
+                        <SubjectUserName>admin</SubjectUserName>
+                        <SubjectDomainName>EXAMPLE</SubjectDomainName>
+                    </LogFileCleared>
+                </UserData>
+            </Event>"#,
+        )
+        .unwrap();
+
+        assert_eq!(log["qualifiers"], 0.into());
+        assert_eq!(log["level_name"], "Error".into());
+        assert_eq!(log["user_id"], "S-1-5-21-1004".into());
+        assert_eq!(log["event_data.param1"], "app.exe".into());
+        assert_eq!(log["event_data.param2"], "1.0.0.0".into());
+        assert_eq!(log["event_data.binary"], "00FF".into());
+        assert_eq!(log["user_data.SubjectUserName"], "admin".into());
+        assert_eq!(log["user_data.SubjectDomainName"], "EXAMPLE".into());
+    }
+
+    #[test]
+    fn rejects_invalid_xml() {
+        assert!(parse_event("<Event>").is_err());
+    }
+}
@@ -0,0 +1,363 @@
+use std::{collections::BTreeMap, fmt};
+
+#[cfg(windows)]
+use chrono::Utc;
+#[cfg(windows)]
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+#[cfg(windows)]
+use tokio::time;
+#[cfg(windows)]
+use tokio_stream::wrappers::IntervalStream;
+#[cfg(windows)]
+use vector_core::ByteSizeOf;
+
+use crate::{
+    config::{DataType, GenerateConfig, Output, SourceConfig, SourceContext, SourceDescription},
+    event::metric::{Metric, MetricKind, MetricValue},
+};
+#[cfg(windows)]
+use crate::{
+    internal_events::{EventsReceived, StreamClosedError, WindowsPerfCountersCollectError},
+    shutdown::ShutdownSignal,
+    SourceSender,
+};
+
+#[cfg(windows)]
+mod pdh;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct WindowsPerfCountersConfig {
+    /// The counters to collect, by their English path, such as `\Processor(*)\% Processor Time`.
+    pub counters: Vec<String>,
+
+    #[serde(default = "default_scrape_interval")]
+    pub scrape_interval_secs: f64,
+
+    #[serde(default = "default_namespace")]
+    pub namespace: Option<String>,
+}
+
+const fn default_scrape_interval() -> f64 {
+    15.0
+}
+
+fn default_namespace() -> Option<String> {
+    Some("windows".to_owned())
+}
+
+inventory::submit! {
+    SourceDescription::new::<WindowsPerfCountersConfig>("windows_perf_counters")
+}
+
+impl GenerateConfig for WindowsPerfCountersConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"counters = ['\Processor(_Total)\% Processor Time', '\Memory\Available Bytes']"#,
+        )
+        .unwrap()
+    }
+}
+
+#[derive(Debug, PartialEq, Snafu)]
+enum BuildError {
+    #[snafu(display("At least one counter must be configured"))]
+    NoCounters,
+    #[snafu(display("Invalid counter path {:?}: {}", path, reason))]
+    InvalidPath { path: String, reason: &'static str },
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "windows_perf_counters")]
+impl SourceConfig for WindowsPerfCountersConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let counters = self.counter_paths()?;
+        self.build_source(counters, cx)
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Metric)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "windows_perf_counters"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+impl WindowsPerfCountersConfig {
+    fn counter_paths(&self) -> Result<Vec<CounterPath>, BuildError> {
+        if self.counters.is_empty() {
+            return Err(BuildError::NoCounters);
+        }
+        self.counters
+            .iter()
+            .map(|path| CounterPath::parse(path))
+            .collect()
+    }
+
+    #[cfg(windows)]
+    fn build_source(
+        &self,
+        counters: Vec<CounterPath>,
+        cx: SourceContext,
+    ) -> crate::Result<super::Source> {
+        let query = pdh::Query::new(&counters)?;
+        Ok(Box::pin(self.clone().run(
+            counters,
+            query,
+            cx.out,
+            cx.shutdown,
+        )))
+    }
+
+    #[cfg(not(windows))]
+    fn build_source(&self, _: Vec<CounterPath>, _: SourceContext) -> crate::Result<super::Source> {
+        Err("The `windows_perf_counters` source is only supported on Windows.".into())
+    }
+
+    #[cfg(windows)]
+    async fn run(
+        self,
+        counters: Vec<CounterPath>,
+        mut query: pdh::Query,
+        mut out: SourceSender,
+        shutdown: ShutdownSignal,
+    ) -> Result<(), ()> {
+        let duration = time::Duration::from_secs_f64(self.scrape_interval_secs);
+        let mut interval = IntervalStream::new(time::interval(duration)).take_until(shutdown);
+        let hostname = crate::get_hostname().ok();
+
+        while interval.next().await.is_some() {
+            let samples = match query.collect() {
+                Ok(samples) => samples,
+                Err(error) => {
+                    emit!(WindowsPerfCountersCollectError {
+                        error: error.to_string()
+                    });
+                    continue;
+                }
+            };
+
+            let timestamp = Utc::now();
+            let metrics = samples
+                .into_iter()
+                .map(|sample| {
+                    let counter = &counters[sample.counter];
+                    let mut metric = counter.metric(
+                        self.namespace.clone(),
+                        sample.instance.as_deref(),
+                        sample.value,
+                    );
+                    if let Some(hostname) = &hostname {
+                        metric.insert_tag("host".into(), hostname.clone());
+                    }
+                    metric.with_timestamp(Some(timestamp))
+                })
+                .collect::<Vec<_>>();
+
+            let count = metrics.len();
+            emit!(EventsReceived {
+                count,
+                byte_size: metrics.size_of(),
+            });
+            if let Err(error) = out.send_batch(metrics).await {
+                emit!(StreamClosedError {
+                    count,
+                    error: error.clone()
+                });
+                error!(message = "Error sending performance counter metrics.", %error);
+                return Err(());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A counter path, in the `\Object(Instance)\Counter` form, the instance being optional.
+/// Instances may contain wildcards, collecting every matching instance of the object.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(not(windows), allow(dead_code))]
+pub(self) struct CounterPath {
+    path: String,
+    object: String,
+    instance: Option<String>,
+    counter: String,
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+impl CounterPath {
+    fn parse(path: &str) -> Result<Self, BuildError> {
+        let invalid = |reason| BuildError::InvalidPath {
+            path: path.to_owned(),
+            reason,
+        };
+
+        // Counters of remote computers are prefixed with `\\computer`.
+        let local = match path.strip_prefix("\\\\") {
+            Some(remote) => remote
+                .find('\\')
+                .map(|index| &remote[index..])
+                .ok_or_else(|| invalid("missing object"))?,
+            None => path,
+        };
+        let local = local
+            .strip_prefix('\\')
+            .ok_or_else(|| invalid("must start with `\\`"))?;
+        let (object, counter) = local
+            .rsplit_once('\\')
+            .ok_or_else(|| invalid("missing counter"))?;
+
+        let (object, instance) = match object.find('(') {
+            Some(start) => {
+                let instance = object[start + 1..]
+                    .strip_suffix(')')
+                    .ok_or_else(|| invalid("unterminated instance"))?;
+                (&object[..start], Some(instance.to_owned()))
+            }
+            None => (object, None),
+        };
+
+        if object.is_empty() {
+            return Err(invalid("missing object"));
+        }
+        if counter.is_empty() {
+            return Err(invalid("missing counter"));
+        }
+        if has_wildcard(object) || has_wildcard(counter) {
+            return Err(invalid("wildcards are only supported in instances"));
+        }
+
+        Ok(Self {
+            path: path.to_owned(),
+            object: object.to_owned(),
+            instance,
+            counter: counter.to_owned(),
+        })
+    }
+
+    /// Whether the path matches several instances, whose values have to be read separately.
+    fn has_wildcard_instance(&self) -> bool {
+        self.instance.as_deref().map_or(false, has_wildcard)
+    }
+
+    /// The name of the metric holding the values of the counter, derived from the names of the
+    /// object and counter, such as `processor_percent_processor_time`.
+    fn metric_name(&self) -> String {
+        let mut name = String::new();
+        for word in format!("{} {}", self.object, self.counter)
+            .replace('%', " percent ")
+            .replace('/', " per ")
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+        {
+            if !name.is_empty() {
+                name.push('_');
+            }
+            name.push_str(&word.to_ascii_lowercase());
+        }
+        name
+    }
+
+    /// Builds the metric for a value of the counter, read for the given instance when the path
+    /// contains a wildcard.
+    fn metric(&self, namespace: Option<String>, instance: Option<&str>, value: f64) -> Metric {
+        let mut tags = BTreeMap::new();
+        tags.insert("object".to_owned(), self.object.clone());
+        tags.insert("counter".to_owned(), self.counter.clone());
+        if let Some(instance) = instance.or_else(|| self.instance.as_deref()) {
+            tags.insert("instance".to_owned(), instance.to_owned());
+        }
+
+        Metric::new(
+            self.metric_name(),
+            MetricKind::Absolute,
+            MetricValue::Gauge { value },
+        )
+        .with_namespace(namespace)
+        .with_tags(Some(tags))
+    }
+}
+
+impl fmt::Display for CounterPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.path.fmt(f)
+    }
+}
+
+fn has_wildcard(name: &str) -> bool {
+    name.contains(|c| c == '*' || c == '?')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<WindowsPerfCountersConfig>();
+    }
+
+    #[test]
+    fn parses_counter_paths() {
+        let path = CounterPath::parse(r"\Processor(*)\% Processor Time").unwrap();
+        assert_eq!(path.object, "Processor");
+        assert_eq!(path.instance.as_deref(), Some("*"));
+        assert_eq!(path.counter, "% Processor Time");
+        assert!(path.has_wildcard_instance());
+
+        let path = CounterPath::parse(r"\\web-01\Memory\Available Bytes").unwrap();
+        assert_eq!(path.object, "Memory");
+        assert_eq!(path.instance, None);
+        assert_eq!(path.counter, "Available Bytes");
+        assert!(!path.has_wildcard_instance());
+
+        let path = CounterPath::parse(r"\Process(svchost#1)\IO Read Bytes/sec").unwrap();
+        assert_eq!(path.instance.as_deref(), Some("svchost#1"));
+    }
+
+    #[test]
+    fn rejects_invalid_counter_paths() {
+        for path in [
+            r"Memory\Available Bytes",
+            r"\Memory",
+            r"\Memory\",
+            r"\Processor(_Total\% Processor Time",
+            r"\Processor(*)\*",
+            r"\\web-01",
+        ] {
+            assert!(
+                matches!(
+                    CounterPath::parse(path),
+                    Err(BuildError::InvalidPath { .. })
+                ),
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn builds_metrics() {
+        let path = CounterPath::parse(r"\Network Interface(*)\Bytes Received/sec").unwrap();
+        let metric = path.metric(Some("windows".to_owned()), Some("Ethernet"), 42.0);
+
+        assert_eq!(metric.name(), "network_interface_bytes_received_per_sec");
+        assert_eq!(metric.namespace(), Some("windows"));
+        assert_eq!(metric.value(), &MetricValue::Gauge { value: 42.0 });
+        let tags = metric.tags().unwrap();
+        assert_eq!(tags["instance"], "Ethernet");
+        assert_eq!(tags["counter"], "Bytes Received/sec");
+
+        let path = CounterPath::parse(r"\Processor(_Total)\% Processor Time").unwrap();
+        let metric = path.metric(None, None, 3.5);
+        assert_eq!(metric.name(), "processor_percent_processor_time");
+        assert_eq!(metric.tags().unwrap()["instance"], "_Total");
+    }
+}
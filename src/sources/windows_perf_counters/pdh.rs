@@ -0,0 +1,212 @@
+//! A thin wrapper over the Performance Data Helper (PDH) API, reading performance counters.
+
+use std::{ffi::OsStr, fmt, os::windows::ffi::OsStrExt, ptr};
+
+use winapi::{
+    shared::minwindef::DWORD,
+    um::pdh::{
+        PdhAddEnglishCounterW, PdhCloseQuery, PdhCollectQueryData, PdhGetFormattedCounterArrayW,
+        PdhGetFormattedCounterValue, PdhOpenQueryW, PDH_FMT_COUNTERVALUE,
+        PDH_FMT_COUNTERVALUE_ITEM_W, PDH_FMT_DOUBLE, PDH_FMT_NOCAP100, PDH_HCOUNTER, PDH_HQUERY,
+        PDH_STATUS,
+    },
+};
+
+use super::CounterPath;
+
+const ERROR_SUCCESS: PDH_STATUS = 0;
+const PDH_CSTATUS_VALID_DATA: DWORD = 0x0000_0000;
+const PDH_CSTATUS_NEW_DATA: DWORD = 0x0000_0001;
+const PDH_MORE_DATA: PDH_STATUS = 0x8000_07D2_u32 as PDH_STATUS;
+
+/// Values aren't capped to 100, as the percentages of multi-core counters can exceed it.
+const FORMAT: DWORD = PDH_FMT_DOUBLE | PDH_FMT_NOCAP100;
+
+#[derive(Debug)]
+pub(super) struct PdhError {
+    call: &'static str,
+    counter: Option<String>,
+    status: PDH_STATUS,
+}
+
+impl fmt::Display for PdhError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} failed with status {:#x}", self.call, self.status)?;
+        if let Some(counter) = &self.counter {
+            write!(f, " for counter {:?}", counter)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for PdhError {}
+
+fn check(
+    call: &'static str,
+    counter: Option<&CounterPath>,
+    status: PDH_STATUS,
+) -> Result<(), PdhError> {
+    if status == ERROR_SUCCESS {
+        Ok(())
+    } else {
+        Err(PdhError {
+            call,
+            counter: counter.map(ToString::to_string),
+            status,
+        })
+    }
+}
+
+/// A value read from a counter, for one of its instances when its path contains a wildcard.
+pub(super) struct Sample {
+    /// The index of the counter the value was read from.
+    pub(super) counter: usize,
+    pub(super) instance: Option<String>,
+    pub(super) value: f64,
+}
+
+/// A query collecting the values of a set of counters at once.
+pub(super) struct Query {
+    handle: PDH_HQUERY,
+    counters: Vec<(PDH_HCOUNTER, bool)>,
+}
+
+// PDH handles aren't tied to the thread that opened them.
+unsafe impl Send for Query {}
+
+impl Query {
+    /// Opens a query for the given counters. Their paths are resolved in English, so they don't
+    /// depend on the language of the system.
+    pub(super) fn new(paths: &[CounterPath]) -> Result<Self, PdhError> {
+        let mut handle = ptr::null_mut();
+        check("PdhOpenQueryW", None, unsafe {
+            PdhOpenQueryW(ptr::null(), 0, &mut handle)
+        })?;
+        let mut query = Self {
+            handle,
+            counters: Vec::with_capacity(paths.len()),
+        };
+
+        for path in paths {
+            let wide = wide(&path.to_string());
+            let mut counter = ptr::null_mut();
+            check("PdhAddEnglishCounterW", Some(path), unsafe {
+                PdhAddEnglishCounterW(query.handle, wide.as_ptr(), 0, &mut counter)
+            })?;
+            query.counters.push((counter, path.has_wildcard_instance()));
+        }
+
+        // Rate counters are computed from two collections, so prime them with a first one.
+        check("PdhCollectQueryData", None, unsafe {
+            PdhCollectQueryData(query.handle)
+        })?;
+        Ok(query)
+    }
+
+    /// Collects the current values of the counters. Counters without a valid value, such as
+    /// instances that have just appeared, are skipped.
+    pub(super) fn collect(&mut self) -> Result<Vec<Sample>, PdhError> {
+        check("PdhCollectQueryData", None, unsafe {
+            PdhCollectQueryData(self.handle)
+        })?;
+
+        let mut samples = Vec::new();
+        for (index, (counter, wildcard)) in self.counters.iter().enumerate() {
+            if *wildcard {
+                samples.extend(
+                    read_array(*counter)?
+                        .into_iter()
+                        .map(|(instance, value)| Sample {
+                            counter: index,
+                            instance: Some(instance),
+                            value,
+                        }),
+                );
+            } else if let Some(value) = read_value(*counter) {
+                samples.push(Sample {
+                    counter: index,
+                    instance: None,
+                    value,
+                });
+            }
+        }
+        Ok(samples)
+    }
+}
+
+impl Drop for Query {
+    fn drop(&mut self) {
+        unsafe {
+            PdhCloseQuery(self.handle);
+        }
+    }
+}
+
+fn read_value(counter: PDH_HCOUNTER) -> Option<f64> {
+    let mut value: PDH_FMT_COUNTERVALUE = unsafe { std::mem::zeroed() };
+    let status =
+        unsafe { PdhGetFormattedCounterValue(counter, FORMAT, ptr::null_mut(), &mut value) };
+    (status == ERROR_SUCCESS && is_valid(value.CStatus)).then(|| unsafe { *value.u.doubleValue() })
+}
+
+fn read_array(counter: PDH_HCOUNTER) -> Result<Vec<(String, f64)>, PdhError> {
+    // The size of the buffer is first queried, and the instances can change in between.
+    loop {
+        let mut size: DWORD = 0;
+        let mut count: DWORD = 0;
+        let status = unsafe {
+            PdhGetFormattedCounterArrayW(counter, FORMAT, &mut size, &mut count, ptr::null_mut())
+        };
+        if status != PDH_MORE_DATA {
+            // There are no instances matching the path at the moment.
+            return check("PdhGetFormattedCounterArrayW", None, status).map(|_| Vec::new());
+        }
+
+        // The buffer holds the items followed by their names, so it's allocated as items to be
+        // correctly aligned.
+        let item_size = std::mem::size_of::<PDH_FMT_COUNTERVALUE_ITEM_W>();
+        let mut buffer: Vec<PDH_FMT_COUNTERVALUE_ITEM_W> =
+            Vec::with_capacity((size as usize + item_size - 1) / item_size);
+        let status = unsafe {
+            PdhGetFormattedCounterArrayW(
+                counter,
+                FORMAT,
+                &mut size,
+                &mut count,
+                buffer.as_mut_ptr(),
+            )
+        };
+        if status == PDH_MORE_DATA {
+            continue;
+        }
+        check("PdhGetFormattedCounterArrayW", None, status)?;
+
+        // Safety: PDH initialized `count` items at the start of the buffer.
+        unsafe { buffer.set_len(count as usize) };
+        return Ok(buffer
+            .iter()
+            .filter(|item| is_valid(item.FmtValue.CStatus))
+            .map(|item| unsafe { (from_wide(item.szName), *item.FmtValue.u.doubleValue()) })
+            .collect());
+    }
+}
+
+const fn is_valid(status: DWORD) -> bool {
+    status == PDH_CSTATUS_VALID_DATA || status == PDH_CSTATUS_NEW_DATA
+}
+
+fn wide(value: &str) -> Vec<u16> {
+    OsStr::new(value).encode_wide().chain(Some(0)).collect()
+}
+
+/// Reads a null-terminated wide string.
+unsafe fn from_wide(value: *const u16) -> String {
+    if value.is_null() {
+        return String::new();
+    }
+    let mut len = 0;
+    while *value.add(len) != 0 {
+        len += 1;
+    }
+    String::from_utf16_lossy(std::slice::from_raw_parts(value, len))
+}
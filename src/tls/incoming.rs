@@ -7,7 +7,10 @@ use std::{
 };
 
 use futures::{future::BoxFuture, stream, FutureExt, Stream};
-use openssl::ssl::{Ssl, SslAcceptor, SslMethod};
+use openssl::{
+    ssl::{Ssl, SslAcceptor, SslMethod},
+    x509::X509Ref,
+};
 use snafu::ResultExt;
 use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tokio::{
@@ -141,6 +144,29 @@ impl From<TcpListener> for MaybeTlsListener {
     }
 }
 
+/// The certificate a client authenticated with.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CertificateMetadata {
+    /// The attributes of the subject of the certificate, such as `C=US,O=Example,CN=client`.
+    pub subject: String,
+}
+
+impl CertificateMetadata {
+    fn from_certificate(certificate: &X509Ref) -> Self {
+        let subject = certificate
+            .subject_name()
+            .entries()
+            .filter_map(|entry| {
+                let name = entry.object().nid().short_name().ok()?;
+                let value = entry.data().as_utf8().ok()?;
+                Some(format!("{}={}", name, value))
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        Self { subject }
+    }
+}
+
 pub struct MaybeTlsIncomingStream<S> {
     state: StreamState<S>,
     // BoxFuture doesn't allow access to the inner stream, but users
@@ -182,7 +208,7 @@ impl<S> MaybeTlsIncomingStream<S> {
         }
     }
 
-    #[cfg(feature = "sources-vector")]
+    #[cfg(any(feature = "sources-opentelemetry", feature = "sources-vector"))]
     pub(crate) const fn ssl_stream(&self) -> Option<&SslStream<S>> {
         use super::MaybeTls;
 
@@ -251,6 +277,18 @@ impl MaybeTlsIncomingStream<TcpStream> {
         Ok(())
     }
 
+    /// The certificate the client authenticated with, once the handshake completed.
+    #[cfg(feature = "listenfd")]
+    pub(crate) fn peer_certificate_metadata(&self) -> Option<CertificateMetadata> {
+        match &self.state {
+            StreamState::Accepted(MaybeTlsStream::Tls(stream)) => stream
+                .ssl()
+                .peer_certificate()
+                .map(|certificate| CertificateMetadata::from_certificate(&certificate)),
+            _ => None,
+        }
+    }
+
     #[cfg(feature = "sources-utils-tcp-keepalive")]
     pub(crate) fn set_keepalive(&mut self, keepalive: TcpKeepaliveConfig) -> io::Result<()> {
         let stream = self.get_ref().ok_or_else(|| {
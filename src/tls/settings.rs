@@ -2,6 +2,7 @@ use std::{
     fmt,
     fs::File,
     io::Read,
+    net::IpAddr,
     path::{Path, PathBuf},
 };
 
@@ -10,7 +11,7 @@ use openssl::{
     pkey::{PKey, Private},
     ssl::{ConnectConfiguration, SslContextBuilder, SslVerifyMode},
     stack::Stack,
-    x509::{store::X509StoreBuilder, X509},
+    x509::{store::X509StoreBuilder, X509StoreContextRef, X509},
 };
 use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
@@ -68,6 +69,10 @@ pub struct TlsConfig {
     #[serde(alias = "key_path")]
     pub key_file: Option<PathBuf>,
     pub key_pass: Option<String>,
+    /// The subject alternative names, DNS names or IP addresses, the certificate of the peer must
+    /// have one of, which pins the peers to a subset of the certificates issued by the authorities.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subject_alt_names: Vec<String>,
 }
 
 impl TlsConfig {
@@ -89,6 +94,7 @@ pub struct TlsSettings {
     pub(super) verify_hostname: bool,
     authorities: Vec<X509>,
     pub(super) identity: Option<IdentityStore>, // openssl::pkcs12::ParsedPkcs12 doesn't impl Clone yet
+    subject_alt_names: Vec<String>,
 }
 
 #[derive(Clone)]
@@ -117,11 +123,17 @@ impl TlsSettings {
             }
         }
 
+        let verify_certificate = options.verify_certificate.unwrap_or(!for_server);
+        if !options.subject_alt_names.is_empty() && !verify_certificate {
+            return Err(TlsError::SubjectAltNamesWithoutVerification);
+        }
+
         Ok(Self {
-            verify_certificate: options.verify_certificate.unwrap_or(!for_server),
+            verify_certificate,
             verify_hostname: options.verify_hostname.unwrap_or(!for_server),
             authorities: options.load_authorities()?,
             identity: options.load_identity()?,
+            subject_alt_names: options.subject_alt_names.clone(),
         })
     }
 
@@ -138,7 +150,12 @@ impl TlsSettings {
         })
     }
 
-    #[cfg(feature = "sources-gcp_pubsub")]
+    #[cfg(any(
+        feature = "sources-gcp_pubsub",
+        feature = "sources-mqtt",
+        feature = "sinks-gcp",
+        feature = "sinks-mqtt"
+    ))]
     pub fn identity_pem(&self) -> Option<(Vec<u8>, Vec<u8>)> {
         self.identity().map(|identity| {
             let mut cert = identity.cert.to_pem().expect("Invalid stored identity");
@@ -159,7 +176,22 @@ impl TlsSettings {
         })
     }
 
-    #[cfg(feature = "sources-gcp_pubsub")]
+    /// The identity, as a DER encoded PKCS#12 archive and its password.
+    #[cfg(any(feature = "sources-amqp", feature = "sinks-amqp"))]
+    pub fn identity_pkcs12(&self) -> Option<(Vec<u8>, String)> {
+        self.identity
+            .as_ref()
+            .map(|identity| (identity.0.clone(), identity.1.clone()))
+    }
+
+    #[cfg(any(
+        feature = "sinks-amqp",
+        feature = "sources-amqp",
+        feature = "sources-gcp_pubsub",
+        feature = "sources-mqtt",
+        feature = "sinks-gcp",
+        feature = "sinks-mqtt"
+    ))]
     pub fn authorities_pem(&self) -> impl Iterator<Item = Vec<u8>> + '_ {
         self.authorities.iter().map(|authority| {
             authority
@@ -169,11 +201,19 @@ impl TlsSettings {
     }
 
     pub(super) fn apply_context(&self, context: &mut SslContextBuilder) -> Result<()> {
-        context.set_verify(if self.verify_certificate {
+        let mode = if self.verify_certificate {
             SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT
         } else {
             SslVerifyMode::NONE
-        });
+        };
+        if self.subject_alt_names.is_empty() {
+            context.set_verify(mode);
+        } else {
+            let subject_alt_names = self.subject_alt_names.clone();
+            context.set_verify_callback(mode, move |verified, store| {
+                verified && verify_subject_alt_names(store, &subject_alt_names)
+            });
+        }
         if let Some(identity) = self.identity() {
             context
                 .set_certificate(&identity.cert)
@@ -381,11 +421,49 @@ fn load_mac_certs(builder: &mut SslContextBuilder) -> Result<()> {
     Ok(())
 }
 
+/// Checks that the certificate of the peer has one of the subject alternative names, once the chain
+/// of certificates is verified. The certificates of the authorities, higher in the chain, aren't
+/// checked.
+fn verify_subject_alt_names(store: &X509StoreContextRef, subject_alt_names: &[String]) -> bool {
+    if store.error_depth() != 0 {
+        return true;
+    }
+    let names = match store
+        .current_cert()
+        .and_then(|cert| cert.subject_alt_names())
+    {
+        Some(names) => names,
+        None => return false,
+    };
+    names.iter().any(|name| {
+        if let Some(dns_name) = name.dnsname() {
+            subject_alt_names
+                .iter()
+                .any(|pinned| pinned.eq_ignore_ascii_case(dns_name))
+        } else if let Some(address) = name.ipaddress().and_then(ip_address) {
+            subject_alt_names
+                .iter()
+                .any(|pinned| pinned.parse::<IpAddr>().ok() == Some(address))
+        } else {
+            false
+        }
+    })
+}
+
+fn ip_address(bytes: &[u8]) -> Option<IpAddr> {
+    match bytes.len() {
+        4 => <[u8; 4]>::try_from(bytes).ok().map(IpAddr::from),
+        16 => <[u8; 16]>::try_from(bytes).ok().map(IpAddr::from),
+        _ => None,
+    }
+}
+
 impl fmt::Debug for TlsSettings {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("TlsSettings")
             .field("verify_certificate", &self.verify_certificate)
             .field("verify_hostname", &self.verify_hostname)
+            .field("subject_alt_names", &self.subject_alt_names)
             .finish()
     }
 }
@@ -556,6 +634,19 @@ mod test {
         assert_eq!(settings.authorities.len(), 1);
     }
 
+    #[test]
+    fn from_options_subject_alt_names() {
+        let options = TlsConfig {
+            subject_alt_names: vec!["localhost".into()],
+            ..Default::default()
+        };
+        assert!(TlsSettings::from_options(&Some(options.clone())).is_ok());
+        assert!(matches!(
+            TlsSettings::from_options_base(&Some(options), true),
+            Err(TlsError::SubjectAltNamesWithoutVerification)
+        ));
+    }
+
     #[test]
     fn from_options_inline_ca() {
         let ca =
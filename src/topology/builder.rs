@@ -6,40 +6,54 @@ use std::{
     time::Instant,
 };
 
-use futures::{stream::FuturesOrdered, FutureExt, StreamExt};
+use futures::{
+    future,
+    stream::{self, BoxStream, FuturesOrdered},
+    FutureExt, Stream, StreamExt,
+};
+use indexmap::IndexMap;
 use once_cell::sync::Lazy;
 use stream_cancel::{StreamExt as StreamCancelExt, Trigger, Tripwire};
 use tokio::{
     select,
+    sync::mpsc,
     time::{timeout, Duration},
 };
+use tokio_stream::wrappers::ReceiverStream;
 use tracing::Instrument;
 use vector_core::{
     buffers::{
-        topology::{
-            builder::TopologyBuilder,
-            channel::{BufferReceiver, BufferSender},
-        },
+        topology::{builder::TopologyBuilder, channel::BufferSender},
         BufferType, WhenFull,
     },
     internal_event::EventsSent,
     schema::Definition,
+    time::{Clock, MockClock},
     ByteSizeOf,
 };
 
 use super::{
+    dead_letter::DeadLetter,
     fanout::{self, Fanout},
     schema,
+    sequence::{SequenceChecker, Sequencer},
     task::{Task, TaskOutput},
     BuiltBuffer, ConfigDiff,
 };
 use crate::{
+    component_scope,
     config::{
-        ComponentKey, DataType, Input, Output, OutputId, ProxyConfig, SinkContext, SourceContext,
-        TransformContext, TransformOuter,
+        dropped_data_type, ComponentKey, DataType, EnrichmentTableOuter, GlobalOptions, Input,
+        Output, OutputId, ProxyConfig, Sandbox, SinkContext, SourceContext, TransformContext,
+        TransformOuter, DROPPED_OUTPUT, OVERFLOW_OUTPUT,
+    },
+    egress::EgressLimit,
+    event::{array, EventArray, EventContainer},
+    internal_events::{
+        EnrichmentTableLoaded, EnrichmentTableReloadFailed, EnrichmentTableReloaded,
+        EventsReceived, OverflowSinkEventsRouted, UnsupportedEventsDropped,
+        UnsupportedEventsRouted,
     },
-    event::{EventArray, EventContainer},
-    internal_events::EventsReceived,
     shutdown::SourceShutdownCoordinator,
     source_sender::CHUNK_SIZE,
     spawn_named,
@@ -111,6 +125,10 @@ pub(self) async fn load_enrichment_tables<'a>(
                 }
             }
 
+            emit!(EnrichmentTableLoaded {
+                table: &table_name,
+                rows: table.row_count(),
+            });
             enrichment_tables.insert(table_name, table);
         }
     }
@@ -120,6 +138,63 @@ pub(self) async fn load_enrichment_tables<'a>(
     (&ENRICHMENT_TABLES, errors)
 }
 
+/// Rebuilds any enrichment table whose underlying data has changed and swaps it into the
+/// registry in place, so that running components see the new data without a config reload.
+pub(super) async fn reload_changed_enrichment_tables(
+    tables: &IndexMap<ComponentKey, EnrichmentTableOuter>,
+    globals: &GlobalOptions,
+) {
+    for (name, table) in tables.iter() {
+        let table_name = name.to_string();
+        if !ENRICHMENT_TABLES.needs_reload(&table_name) {
+            continue;
+        }
+
+        let reloaded = match table.inner.build(globals).await {
+            Ok(table) => table,
+            Err(error) => {
+                emit!(EnrichmentTableReloadFailed {
+                    table: &table_name,
+                    error: error.to_string(),
+                });
+                continue;
+            }
+        };
+
+        // Indexes are reapplied in their original order so the handles transforms hold onto
+        // still refer to the same indexes. Indexing a large table takes a while, so it's kept
+        // off the runtime's worker threads.
+        let indexes = ENRICHMENT_TABLES.index_fields(&table_name);
+        let indexed = tokio::task::spawn_blocking(move || {
+            let mut reloaded = reloaded;
+            for (case, index) in indexes {
+                reloaded.add_index(case, &index.iter().map(|s| s.as_ref()).collect::<Vec<_>>())?;
+            }
+            Ok::<_, String>(reloaded)
+        })
+        .await
+        .unwrap_or_else(|error| Err(error.to_string()));
+        let reloaded = match indexed {
+            Ok(reloaded) => reloaded,
+            Err(error) => {
+                emit!(EnrichmentTableReloadFailed {
+                    table: &table_name,
+                    error: format!("Unable to add index: {}", error),
+                });
+                continue;
+            }
+        };
+
+        let rows = reloaded.row_count();
+        if ENRICHMENT_TABLES.replace_table(&table_name, reloaded) {
+            emit!(EnrichmentTableReloaded {
+                table: &table_name,
+                rows,
+            });
+        }
+    }
+}
+
 pub struct Pieces {
     pub(super) inputs: HashMap<ComponentKey, (BufferSender<EventArray>, Vec<OutputId>)>,
     pub(crate) outputs: HashMap<ComponentKey, HashMap<Option<String>, fanout::ControlChannel>>,
@@ -132,9 +207,24 @@ pub struct Pieces {
 
 /// Builds only the new pieces, and doesn't check their topology.
 pub async fn build_pieces(
+    config: &super::Config,
+    diff: &ConfigDiff,
+    buffers: HashMap<ComponentKey, BuiltBuffer>,
+) -> Result<Pieces, Vec<String>> {
+    build_pieces_with_clock(config, diff, buffers, Clock::default(), HashMap::new()).await
+}
+
+/// Builds only the new pieces, with transforms reading the current time from `clock`, and doesn't
+/// check their topology.
+///
+/// When `clock` is a mock clock, it is moved forward by each duration of `clock_advances` once the
+/// transform has received the given number of events, right before it gets the next ones.
+pub async fn build_pieces_with_clock(
     config: &super::Config,
     diff: &ConfigDiff,
     mut buffers: HashMap<ComponentKey, BuiltBuffer>,
+    clock: Clock,
+    mut clock_advances: HashMap<ComponentKey, Vec<(usize, Duration)>>,
 ) -> Result<Pieces, Vec<String>> {
     let mut inputs = HashMap::new();
     let mut outputs = HashMap::new();
@@ -159,14 +249,7 @@ pub async fn build_pieces(
         let typetag = source.inner.source_type();
         let source_outputs = source.inner.outputs();
 
-        let span = error_span!(
-            "source",
-            component_kind = "source",
-            component_id = %key.id(),
-            component_type = %source.inner.source_type(),
-            // maintained for compatibility
-            component_name = %key.id(),
-        );
+        let span = component_span!("source", key.id(), source.inner.source_type());
         let task_name = format!(">> {} ({}, pump) >>", source.inner.source_type(), key.id());
 
         let mut builder = SourceSender::builder().with_buffer(*SOURCE_SENDER_BUFFER_SIZE);
@@ -176,11 +259,23 @@ pub async fn build_pieces(
 
         for output in source_outputs {
             let mut rx = builder.add_output(output.clone());
+            let mut sequencer = source.sequence_numbers.then(|| {
+                Sequencer::new(
+                    OutputId {
+                        component: key.clone(),
+                        port: output.port.clone(),
+                    }
+                    .to_string(),
+                )
+            });
 
             let (mut fanout, control) = Fanout::new();
             let pump = async move {
                 debug!("Source pump starting.");
-                while let Some(array) = rx.next().await {
+                while let Some(mut array) = rx.next().await {
+                    if let Some(sequencer) = sequencer.as_mut() {
+                        sequencer.number(&mut array);
+                    }
                     fanout.send(array).await;
                 }
                 debug!("Source pump finished.");
@@ -228,7 +323,14 @@ pub async fn build_pieces(
             acknowledgements: source.sink_acknowledgements,
             schema_definitions,
         };
-        let server = match source.inner.build(context).await {
+        // Sources are built and run within their sandbox, which the clients they build capture.
+        let sandbox = Sandbox::new(&source.sandbox);
+        let server = match sandbox
+            .clone()
+            .scope(source.inner.build(context))
+            .instrument(span.clone())
+            .await
+        {
             Err(error) => {
                 errors.push(format!("Source \"{}\": {}", key, error));
                 continue;
@@ -259,7 +361,8 @@ pub async fn build_pieces(
                 Err(()) => Err(()),
             }
         };
-        let server = Task::new(key.clone(), typetag, server);
+        let server = component_scope::scope(key.clone(), server);
+        let server = Task::new(key.clone(), typetag, sandbox.scope(server));
 
         outputs.extend(controls);
         tasks.insert(key.clone(), pump);
@@ -297,11 +400,20 @@ pub async fn build_pieces(
             enrichment_tables: enrichment_tables.clone(),
             schema_definitions,
             merged_schema_definition: merged_definition.clone(),
+            clock: clock.clone(),
         };
 
         let node = TransformNode::from_parts(key.clone(), transform, &merged_definition);
 
-        let transform = match transform.inner.build(&context).await {
+        let span = component_span!("transform", key.id(), transform.inner.transform_type());
+        // Transforms are built and run within their sandbox, which the clients they build capture.
+        let sandbox = Sandbox::new(&transform.sandbox);
+        let transform = match sandbox
+            .clone()
+            .scope(transform.inner.build(&context))
+            .instrument(span)
+            .await
+        {
             Err(error) => {
                 errors.push(format!("Transform \"{}\": {}", key, error));
                 continue;
@@ -314,7 +426,16 @@ pub async fn build_pieces(
 
         inputs.insert(key.clone(), (input_tx, node.inputs.clone()));
 
-        let (transform_task, transform_outputs) = build_transform(transform, node, input_rx);
+        let input_rx = input_rx.into_stream().boxed();
+        let input_rx = match (&clock, clock_advances.remove(key)) {
+            (Clock::Mock(clock), Some(advances)) => {
+                advance_clock(input_rx, clock.clone(), advances).boxed()
+            }
+            _ => input_rx,
+        };
+
+        let (transform_task, transform_outputs) =
+            build_transform(transform, node, input_rx, sandbox);
 
         outputs.extend(transform_outputs);
         tasks.insert(key.clone(), transform_task);
@@ -333,6 +454,40 @@ pub async fn build_pieces(
 
         let typetag = sink.inner.sink_type();
         let input_type = sink.inner.input().data_type();
+        let mut sequence_checker = sink.check_sequence_numbers.then(SequenceChecker::default);
+        let span = component_span!("sink", key.id(), typetag);
+
+        // The events the sink fails to deliver are sent on through an output of its own, which the
+        // dead letter component has as an input.
+        let (dead_letter, route_dead_letters) = match sink.dead_letter {
+            Some(_) => {
+                let (dead_letter, route) = DeadLetter::new(side_output(&mut outputs, key, None));
+                (Some(dead_letter), Some(route))
+            }
+            None => (None, None),
+        };
+
+        // The events that don't fit in the buffer are sent on through another output of the sink,
+        // which the overflow sink has as an input.
+        let overflow_fanout = sink
+            .overflow_sink
+            .as_ref()
+            .map(|_| side_output(&mut outputs, key, Some(OVERFLOW_OUTPUT)));
+
+        // The events of the types the sink doesn't accept are sent on through its dropped output
+        // when components have it as an input, without holding up the sink when they lag behind.
+        let dropped_output = OutputId {
+            component: key.clone(),
+            port: Some(DROPPED_OUTPUT.to_owned()),
+        };
+        let (dropped, forward_dropped) = match dropped_data_type(input_type) {
+            Some(_) if config.is_consumed(&dropped_output) => {
+                let fanout = side_output(&mut outputs, key, Some(DROPPED_OUTPUT));
+                let (tx, rx) = mpsc::channel(TOPOLOGY_BUFFER_SIZE.get());
+                (Some(tx), Some(forward(ReceiverStream::new(rx), fanout)))
+            }
+            _ => (None, None),
+        };
 
         if config.schema.enabled {
             // At this point, we've validated that all transforms are valid, including any
@@ -343,31 +498,45 @@ pub async fn build_pieces(
             };
         }
 
-        let (tx, rx, acker) = if let Some(buffer) = buffers.remove(key) {
+        // A reused buffer comes with the receiver of the events it overflows, as the sender of
+        // these events is part of the buffer.
+        let (tx, rx, acker, overflow_rx) = if let Some(buffer) = buffers.remove(key) {
             buffer
         } else {
+            let (overflow_tx, overflow_rx) = match sink.overflow_sink {
+                Some(_) => {
+                    let (tx, rx) =
+                        TopologyBuilder::standalone_memory(TOPOLOGY_BUFFER_SIZE, WhenFull::Block)
+                            .await;
+                    (Some(tx), Some(Arc::new(Mutex::new(Some(rx.into_stream())))))
+                }
+                None => (None, None),
+            };
             let buffer_type = match sink.buffer.stages().first().expect("cant ever be empty") {
                 BufferType::Memory { .. } => "memory",
                 BufferType::DiskV1 { .. } | BufferType::DiskV2 { .. } => "disk",
             };
-            let buffer_span = error_span!(
-                "sink",
-                component_kind = "sink",
-                component_id = %key.id(),
-                component_type = typetag,
-                component_name = %key.id(),
-                buffer_type = buffer_type,
-            );
+            let buffer_span = component_span!("sink", key.id(), typetag, buffer_type = buffer_type);
             let buffer = sink
                 .buffer
-                .build(config.global.data_dir.clone(), key.to_string(), buffer_span)
+                .build(
+                    config.global.data_dir.clone(),
+                    key.to_string(),
+                    buffer_span,
+                    overflow_tx,
+                )
                 .await;
             match buffer {
                 Err(error) => {
                     errors.push(format!("Sink \"{}\": {}", key, error));
                     continue;
                 }
-                Ok((tx, rx, acker)) => (tx, Arc::new(Mutex::new(Some(rx.into_stream()))), acker),
+                Ok((tx, rx, acker)) => (
+                    tx,
+                    Arc::new(Mutex::new(Some(rx.into_stream()))),
+                    acker,
+                    overflow_rx,
+                ),
             }
         };
 
@@ -377,14 +546,50 @@ pub async fn build_pieces(
             globals: config.global.clone(),
             proxy: ProxyConfig::merge_with_env(&config.global.proxy, sink.proxy()),
             schema: config.schema,
+            input_definition: if config.schema.enabled {
+                schema::merged_definition(&sink.inputs, config, &mut definition_cache)
+            } else {
+                schema::Definition::empty()
+            },
+            secret_backends: config.secret_backends().clone(),
+            dead_letter: sink.dead_letter.is_some(),
         };
 
-        let (sink, healthcheck) = match sink.inner.build(cx).await {
+        let sandbox = Sandbox::new(&sink.sandbox);
+        let egress = EgressLimit::new(sink.egress_bytes_per_sec);
+        // The sink is built within its component, to which the services it builds belong.
+        let (sink, healthcheck) = match component_scope::scope(
+            key.clone(),
+            egress
+                .clone()
+                .scope(sandbox.clone().scope(sink.inner.build(cx))),
+        )
+        .instrument(span.clone())
+        .await
+        {
             Err(error) => {
                 errors.push(format!("Sink \"{}\": {}", key, error));
                 continue;
             }
-            Ok(built) => built,
+            // The limit is only enforced by the HTTP client, which the sink builds along with it.
+            Ok(_) if egress.is_limited() && !egress.is_enforced() => {
+                errors.push(format!(
+                    "Sink \"{}\": `egress_bytes_per_sec` is only supported by sinks sending their events over HTTP.",
+                    key
+                ));
+                continue;
+            }
+            Ok(built) => {
+                // The global limit is only enforced by the HTTP clients as well, so the bytes of
+                // the other sinks are sent regardless of it.
+                if config.global.egress_bytes_per_sec.is_some() && !egress.is_enforced() {
+                    warn!(
+                        message = "Sink doesn't send its events over HTTP, so its bytes aren't limited by the global `egress_bytes_per_sec` option.",
+                        component = %key,
+                    );
+                }
+                built
+            }
         };
 
         let (trigger, tripwire) = Tripwire::new();
@@ -403,28 +608,103 @@ pub async fn build_pieces(
                 .expect("Task started but input has been taken.");
 
             let mut rx = wrap(rx);
+            let overflow_rx = overflow_rx.map(|overflow_rx| {
+                overflow_rx
+                    .lock()
+                    .unwrap()
+                    .take()
+                    .expect("Task started but overflow has been taken.")
+            });
+
+            let route_dead_letters = async move {
+                if let Some(route) = route_dead_letters {
+                    route.await;
+                }
+            };
+
+            // The overflowed events are forwarded until the buffer is dropped, or until the sink is
+            // shut down, possibly to reuse its buffer along with the receiver of these events.
+            let overflow_tripwire = tripwire.clone();
+            let forward_overflow = async move {
+                match (overflow_rx, overflow_fanout) {
+                    (Some(mut overflow_rx), Some(mut fanout)) => {
+                        let mut overflowed = overflow_rx.by_ref().take_until_if(overflow_tripwire);
+                        while let Some(events) = overflowed.next().await {
+                            emit!(OverflowSinkEventsRouted {
+                                count: events.len()
+                            });
+                            fanout.send(events).await;
+                        }
+                        // The events that overflowed before the sink was shut down are sent on as
+                        // well, rather than lost along with the receiver when it isn't reused.
+                        while let Some(Some(events)) = overflow_rx.next().now_or_never() {
+                            emit!(OverflowSinkEventsRouted {
+                                count: events.len()
+                            });
+                            fanout.send(events).await;
+                        }
+                        Some(overflow_rx)
+                    }
+                    _ => None,
+                }
+            };
+
+            let forward_dropped = async move {
+                if let Some(forward) = forward_dropped {
+                    forward.await;
+                }
+            };
 
-            sink.run(
+            let run = sink.run(
                 rx.by_ref()
-                    .filter(|events: &EventArray| ready(filter_events_type(events, input_type)))
+                    .filter_map(move |events: EventArray| {
+                        ready(if filter_events_type(&events, input_type) {
+                            Some(events)
+                        } else {
+                            let count = events.len();
+                            match dropped.as_ref().map(|dropped| dropped.try_send(events)) {
+                                Some(Ok(())) => emit!(UnsupportedEventsRouted { count }),
+                                Some(Err(_)) => emit!(UnsupportedEventsDropped {
+                                    count,
+                                    reason: "The components consuming the dropped output are lagging behind.",
+                                }),
+                                None => emit!(UnsupportedEventsDropped {
+                                    count,
+                                    reason: "No component consumes the dropped output.",
+                                }),
+                            }
+                            None
+                        })
+                    })
                     .inspect(|events| {
                         emit!(EventsReceived {
                             count: events.len(),
                             byte_size: events.size_of(),
                         })
                     })
+                    .inspect(move |events| {
+                        if let Some(checker) = sequence_checker.as_mut() {
+                            checker.check(events);
+                        }
+                    })
+                    .map(move |events| match &dead_letter {
+                        Some(dead_letter) => dead_letter.track(events),
+                        None => events,
+                    })
                     .take_until_if(tripwire),
-            )
-            .await
-            .map(|_| {
+            );
+
+            let (result, (), overflow_rx, ()) =
+                future::join4(run, route_dead_letters, forward_overflow, forward_dropped).await;
+            result.map(|_| {
                 debug!("Finished.");
-                TaskOutput::Sink(rx, acker)
+                TaskOutput::Sink(rx, acker, overflow_rx)
             })
         };
 
-        let task = Task::new(key.clone(), typetag, sink);
+        let sink = component_scope::scope(key.clone(), egress.scope(sink));
+        let task = Task::new(key.clone(), typetag, sandbox.scope(sink));
 
-        let component_key = key.clone();
         let healthcheck_task = async move {
             if enable_healthcheck {
                 let duration = Duration::from_secs(10);
@@ -435,26 +715,11 @@ pub async fn build_pieces(
                             Ok(TaskOutput::Healthcheck)
                         }
                         Ok(Err(error)) => {
-                            error!(
-                                msg = "Healthcheck: Failed Reason.",
-                                %error,
-                                component_kind = "sink",
-                                component_type = typetag,
-                                component_id = %component_key.id(),
-                                // maintained for compatibility
-                                component_name = %component_key.id(),
-                            );
+                            error!(msg = "Healthcheck: Failed Reason.", %error);
                             Err(())
                         }
                         Err(_) => {
-                            error!(
-                                msg = "Healthcheck: timeout.",
-                                component_kind = "sink",
-                                component_type = typetag,
-                                component_id = %component_key.id(),
-                                // maintained for compatibility
-                                component_name = %component_key.id(),
-                            );
+                            error!(msg = "Healthcheck: timeout.");
                             Err(())
                         }
                     })
@@ -465,7 +730,7 @@ pub async fn build_pieces(
             }
         };
 
-        let healthcheck_task = Task::new(key.clone(), typetag, healthcheck_task);
+        let healthcheck_task = Task::new(key.clone(), typetag, healthcheck_task.instrument(span));
 
         inputs.insert(key.clone(), (tx, sink_inputs.clone()));
         healthchecks.insert(key.clone(), healthcheck_task);
@@ -502,6 +767,32 @@ pub async fn build_pieces(
     }
 }
 
+/// Adds an output of the sink, through which the sink sends on some of the events it's sent rather
+/// than delivering them, and returns its fanout.
+fn side_output(
+    outputs: &mut HashMap<OutputId, fanout::ControlChannel>,
+    key: &ComponentKey,
+    port: Option<&str>,
+) -> Fanout {
+    let (fanout, control) = Fanout::new();
+    outputs.insert(
+        OutputId {
+            component: key.clone(),
+            port: port.map(ToOwned::to_owned),
+        },
+        control,
+    );
+    fanout
+}
+
+/// Sends the events on through a side output of a sink, until they end.
+async fn forward(events: impl Stream<Item = EventArray>, mut fanout: Fanout) {
+    futures::pin_mut!(events);
+    while let Some(events) = events.next().await {
+        fanout.send(events).await;
+    }
+}
+
 const fn filter_events_type(events: &EventArray, data_type: DataType) -> bool {
     match events {
         EventArray::Logs(_) => data_type.contains(DataType::Log),
@@ -537,21 +828,59 @@ impl TransformNode {
     }
 }
 
+/// Moves the clock forward by each of `advances` once the transform has received the given number of
+/// events, right before handing it the next ones. Since the transform asks for the next events once
+/// it's done with the previous ones, it sees them at the time they were received.
+fn advance_clock(
+    input_rx: BoxStream<'static, EventArray>,
+    clock: MockClock,
+    advances: Vec<(usize, Duration)>,
+) -> impl Stream<Item = EventArray> {
+    let mut advances = advances.into_iter().peekable();
+    let mut received = 0;
+    input_rx.flat_map(move |events| {
+        // Split the events where the clock is moved forward.
+        let mut pieces = Vec::new();
+        let mut piece = (Duration::ZERO, Vec::new());
+        for event in events.into_events() {
+            while let Some((_, duration)) = advances.next_if(|(at, _)| *at <= received) {
+                if !piece.1.is_empty() {
+                    pieces.push(std::mem::replace(&mut piece, (Duration::ZERO, Vec::new())));
+                }
+                piece.0 += duration;
+            }
+            piece.1.push(event);
+            received += 1;
+        }
+        pieces.push(piece);
+
+        let clock = clock.clone();
+        stream::iter(pieces).flat_map(move |(advance, events)| {
+            if advance > Duration::ZERO {
+                clock.advance(advance);
+            }
+            stream::iter(array::events_into_arrays(events, None))
+        })
+    })
+}
+
 fn build_transform(
     transform: Transform,
     node: TransformNode,
-    input_rx: BufferReceiver<EventArray>,
+    input_rx: BoxStream<'static, EventArray>,
+    sandbox: Sandbox,
 ) -> (Task, HashMap<OutputId, fanout::ControlChannel>) {
     match transform {
         // TODO: avoid the double boxing for function transforms here
-        Transform::Function(t) => build_sync_transform(Box::new(t), node, input_rx),
-        Transform::Synchronous(t) => build_sync_transform(t, node, input_rx),
+        Transform::Function(t) => build_sync_transform(Box::new(t), node, input_rx, sandbox),
+        Transform::Synchronous(t) => build_sync_transform(t, node, input_rx, sandbox),
         Transform::Task(t) => build_task_transform(
             t,
             input_rx,
             node.input_details.data_type(),
             node.typetag,
             &node.key,
+            sandbox,
         ),
     }
 }
@@ -559,7 +888,8 @@ fn build_transform(
 fn build_sync_transform(
     t: Box<dyn SyncTransform>,
     node: TransformNode,
-    input_rx: BufferReceiver<EventArray>,
+    input_rx: BoxStream<'static, EventArray>,
+    sandbox: Sandbox,
 ) -> (Task, HashMap<OutputId, fanout::ControlChannel>) {
     let (outputs, controls) = TransformOutputs::new(node.outputs);
 
@@ -578,14 +908,15 @@ fn build_sync_transform(
         output_controls.insert(id, control);
     }
 
-    let task = Task::new(node.key.clone(), node.typetag, transform);
+    let transform = component_scope::scope(node.key.clone(), transform);
+    let task = Task::new(node.key.clone(), node.typetag, sandbox.scope(transform));
 
     (task, output_controls)
 }
 
 struct Runner {
     transform: Box<dyn SyncTransform>,
-    input_rx: Option<BufferReceiver<EventArray>>,
+    input_rx: Option<BoxStream<'static, EventArray>>,
     input_type: DataType,
     outputs: TransformOutputs,
     timer: crate::utilization::Timer,
@@ -595,7 +926,7 @@ struct Runner {
 impl Runner {
     fn new(
         transform: Box<dyn SyncTransform>,
-        input_rx: BufferReceiver<EventArray>,
+        input_rx: BoxStream<'static, EventArray>,
         input_type: DataType,
         outputs: TransformOutputs,
     ) -> Self {
@@ -637,16 +968,38 @@ impl Runner {
             .input_rx
             .take()
             .expect("can't run runner twice")
-            .into_stream()
             .filter(move |events| ready(filter_events_type(events, self.input_type)));
 
+        let mut flush_interval = self.transform.flush_period().map(tokio::time::interval);
+
         self.timer.start_wait();
-        while let Some(events) = input_rx.next().await {
-            self.on_events_received(&events);
-            self.transform.transform_all(events, &mut outputs_buf);
-            self.send_outputs(&mut outputs_buf).await;
+        loop {
+            tokio::select! {
+                biased;
+
+                events = input_rx.next() => match events {
+                    Some(events) => {
+                        self.on_events_received(&events);
+                        self.transform.transform_all(events, &mut outputs_buf);
+                        self.send_outputs(&mut outputs_buf).await;
+                    }
+                    None => break,
+                },
+
+                _ = async { flush_interval.as_mut().expect("interval exists").tick().await },
+                    if flush_interval.is_some() =>
+                {
+                    self.timer.stop_wait();
+                    self.transform.flush(&mut outputs_buf);
+                    self.send_outputs(&mut outputs_buf).await;
+                }
+            }
         }
 
+        self.timer.stop_wait();
+        self.transform.flush_all(&mut outputs_buf);
+        self.send_outputs(&mut outputs_buf).await;
+
         debug!("Finished.");
         Ok(TaskOutput::Transform)
     }
@@ -656,7 +1009,6 @@ impl Runner {
             .input_rx
             .take()
             .expect("can't run runner twice")
-            .into_stream()
             .filter(move |events| ready(filter_events_type(events, self.input_type)));
 
         let mut input_rx =
@@ -691,12 +1043,13 @@ impl Runner {
 
                             let mut t = self.transform.clone();
                             let mut outputs_buf = self.outputs.new_buf_with_capacity(len);
-                            let task = tokio::spawn(async move {
+                            let task = component_scope::in_current_scope(async move {
                                 for events in input_arrays {
                                     t.transform_all(events, &mut outputs_buf);
                                 }
                                 outputs_buf
-                            }.in_current_span());
+                            });
+                            let task = tokio::spawn(Sandbox::in_current_scope(task).in_current_span());
                             in_flight.push(task);
                         }
                         None => {
@@ -721,14 +1074,15 @@ impl Runner {
 
 fn build_task_transform(
     t: Box<dyn TaskTransform<EventArray>>,
-    input_rx: BufferReceiver<EventArray>,
+    input_rx: BoxStream<'static, EventArray>,
     input_type: DataType,
     typetag: &str,
     key: &ComponentKey,
+    sandbox: Sandbox,
 ) -> (Task, HashMap<OutputId, fanout::ControlChannel>) {
     let (mut fanout, control) = Fanout::new();
 
-    let input_rx = crate::utilization::wrap(input_rx.into_stream());
+    let input_rx = crate::utilization::wrap(input_rx);
 
     let filtered = input_rx
         .filter(move |events| ready(filter_events_type(events, input_type)))
@@ -757,7 +1111,8 @@ fn build_task_transform(
     let mut outputs = HashMap::new();
     outputs.insert(OutputId::from(key), control);
 
-    let task = Task::new(key.clone(), typetag, transform);
+    let transform = component_scope::scope(key.clone(), transform);
+    let task = Task::new(key.clone(), typetag, sandbox.scope(transform));
 
     (task, outputs)
 }
@@ -0,0 +1,256 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use futures::{
+    future::{self, join_all},
+    stream::FuturesUnordered,
+    Future, StreamExt,
+};
+use tokio::sync::mpsc;
+
+use super::fanout::Fanout;
+use crate::{
+    event::{
+        array, BatchNotifier, BatchStatus, BatchStatusReceiver, EventArray, EventContainer,
+        EventFinalizer, EventFinalizers, EventStatus,
+    },
+    internal_events::{DeadLetterEventsRouted, DeadLetterEventsUntracked},
+};
+
+/// The number of events handed to the sink whose delivery is tracked at once, including the events
+/// the sink failed to deliver which wait to be sent on to the dead letter. Beyond that, the events
+/// are handed to the sink untracked rather than holding its input back.
+const MAX_TRACKED_EVENTS: usize = 100_000;
+
+type Pending = (EventArray, Vec<BatchStatusReceiver>);
+
+/// Tracks the delivery of the events received by a sink with a dead letter, to route the events the
+/// sink fails to deliver to the dead letter component.
+///
+/// Each event handed to the sink is given a finalizer of its own, while a copy of its array holding
+/// their original finalizers is retained until the sink finalizes them. The copy shares the data of
+/// log and trace events with the array handed to the sink. The original finalizers of the events the
+/// sink delivered are updated as such, and the events it failed to deliver are sent on to the dead
+/// letter with theirs, which leaves their final status up to it.
+#[derive(Clone)]
+pub(super) struct DeadLetter {
+    pending: mpsc::UnboundedSender<Pending>,
+    tracked: Arc<AtomicUsize>,
+}
+
+impl DeadLetter {
+    /// Returns the dead letter with the task routing the events through the fanout, which completes
+    /// once the dead letter is dropped and all the events it retained are finalized.
+    pub(super) fn new(fanout: Fanout) -> (Self, impl Future<Output = ()>) {
+        let (pending, rx) = mpsc::unbounded_channel();
+        let tracked = Arc::new(AtomicUsize::new(0));
+        (
+            Self {
+                pending,
+                tracked: Arc::clone(&tracked),
+            },
+            route(rx, fanout, tracked),
+        )
+    }
+
+    /// Retains a copy of the events, and returns them with finalizers of their own to hand to the
+    /// sink. Once `MAX_TRACKED_EVENTS` events are tracked, the events are returned as they are.
+    pub(super) fn track(&self, mut array: EventArray) -> EventArray {
+        let count = array.len();
+        if self.tracked.fetch_add(count, Ordering::Relaxed) + count > MAX_TRACKED_EVENTS {
+            self.tracked.fetch_sub(count, Ordering::Relaxed);
+            emit!(DeadLetterEventsUntracked { count });
+            return array;
+        }
+
+        let retained = array.clone();
+        let mut receivers = Vec::with_capacity(count);
+        array.for_each_event(|mut event| {
+            // The retained copy keeps the original finalizers alive.
+            drop(event.metadata_mut().take_finalizers());
+            let (batch, receiver) = BatchNotifier::new_with_receiver();
+            event
+                .metadata_mut()
+                .add_finalizer(EventFinalizer::new(batch));
+            receivers.push(receiver);
+        });
+
+        // The routing task outlives the dead letter, so this can't fail.
+        let _ = self.pending.send((retained, receivers));
+        array
+    }
+}
+
+async fn route(
+    mut rx: mpsc::UnboundedReceiver<Pending>,
+    mut fanout: Fanout,
+    tracked: Arc<AtomicUsize>,
+) {
+    // The failed events are sent on by a task of their own, so that a dead letter applying
+    // backpressure doesn't hold back finalizing the events the sink delivers.
+    let (failed_tx, mut failed_rx) = mpsc::unbounded_channel::<EventArray>();
+
+    let finalize = {
+        let tracked = Arc::clone(&tracked);
+        async move {
+            let mut pending = FuturesUnordered::new();
+            loop {
+                tokio::select! {
+                    Some((array, receivers)) = rx.recv() => {
+                        pending.push(async move { (array, join_all(receivers).await) });
+                    }
+                    Some((array, statuses)) = pending.next() => {
+                        let count = array.len();
+                        if let Some(failed) = settle(array, &statuses) {
+                            tracked.fetch_sub(count - failed.len(), Ordering::Relaxed);
+                            // The sending task outlives this one.
+                            let _ = failed_tx.send(failed);
+                        } else {
+                            tracked.fetch_sub(count, Ordering::Relaxed);
+                        }
+                    }
+                    else => break,
+                }
+            }
+        }
+    };
+
+    let send = async move {
+        while let Some(array) = failed_rx.recv().await {
+            let count = array.len();
+            fanout.send(array).await;
+            tracked.fetch_sub(count, Ordering::Relaxed);
+        }
+    };
+
+    future::join(finalize, send).await;
+}
+
+/// Finalizes the events the sink delivered, and returns the events it failed to deliver, if any.
+fn settle(array: EventArray, statuses: &[BatchStatus]) -> Option<EventArray> {
+    let mut delivered = EventFinalizers::default();
+    let (mut errored, mut rejected) = (0, 0);
+    let mut failed = Vec::new();
+    for (mut event, status) in array.into_events().zip(statuses) {
+        match status {
+            BatchStatus::Delivered => {
+                delivered.merge(event.metadata_mut().take_finalizers());
+                continue;
+            }
+            BatchStatus::Errored => errored += 1,
+            BatchStatus::Rejected => rejected += 1,
+        }
+        failed.push(event);
+    }
+    delivered.update_status(EventStatus::Delivered);
+
+    for (count, status) in [(errored, "errored"), (rejected, "rejected")] {
+        if count > 0 {
+            emit!(DeadLetterEventsRouted { count, status });
+        }
+    }
+    // The events of an array are all of the same type, so they make up a single array.
+    array::events_into_arrays(failed, None).next()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use vector_buffers::{
+        topology::{builder::TopologyBuilder, channel::BufferReceiver},
+        WhenFull,
+    };
+
+    use super::*;
+    use crate::event::{Event, LogEvent};
+
+    fn track(dead_letter: &DeadLetter) -> (Event, BatchStatusReceiver) {
+        let (batch, receiver) = BatchNotifier::new_with_receiver();
+        let array = EventArray::from(LogEvent::from("message").with_batch_notifier(&batch));
+        let array = dead_letter.track(array);
+        (array.into_events().next().unwrap(), receiver)
+    }
+
+    async fn dead_letter_fanout() -> (Fanout, BufferReceiver<EventArray>) {
+        let (tx, rx) =
+            TopologyBuilder::standalone_memory(NonZeroUsize::new(4).unwrap(), WhenFull::Block)
+                .await;
+        let (mut fanout, _control) = Fanout::new();
+        fanout.add("dead_letter".into(), tx);
+        (fanout, rx)
+    }
+
+    #[tokio::test]
+    async fn routes_failed_events() {
+        let (fanout, mut rx) = dead_letter_fanout().await;
+        let (dead_letter, route) = DeadLetter::new(fanout);
+        let route = tokio::spawn(route);
+        let (delivered, mut delivered_receiver) = track(&dead_letter);
+        let (rejected, mut rejected_receiver) = track(&dead_letter);
+        drop(dead_letter);
+
+        delivered
+            .metadata()
+            .finalizers()
+            .update_status(EventStatus::Delivered);
+        rejected
+            .metadata()
+            .finalizers()
+            .update_status(EventStatus::Rejected);
+        drop((delivered, rejected));
+        route.await.unwrap();
+
+        assert_eq!(delivered_receiver.try_recv(), Ok(BatchStatus::Delivered));
+        // The status of the routed event is left to the dead letter component.
+        assert!(rejected_receiver.try_recv().is_err());
+
+        let mut routed = rx.next().await.unwrap();
+        assert_eq!(routed.len(), 1);
+        routed.for_each_event(|mut event| {
+            event
+                .metadata_mut()
+                .finalizers()
+                .update_status(EventStatus::Delivered);
+        });
+        drop(routed);
+        assert_eq!(rejected_receiver.await, BatchStatus::Delivered);
+    }
+
+    #[tokio::test]
+    async fn routes_only_the_failed_events_of_an_array() {
+        let (fanout, mut rx) = dead_letter_fanout().await;
+        let (dead_letter, route) = DeadLetter::new(fanout);
+        let route = tokio::spawn(route);
+
+        let (batch, receiver) = BatchNotifier::new_with_receiver();
+        let array = EventArray::from(vec![
+            LogEvent::from("delivered").with_batch_notifier(&batch),
+            LogEvent::from("errored").with_batch_notifier(&batch),
+        ]);
+        drop(batch);
+        let mut array = dead_letter.track(array);
+        drop(dead_letter);
+
+        let mut statuses = vec![EventStatus::Delivered, EventStatus::Errored].into_iter();
+        array.for_each_event(|mut event| {
+            event
+                .metadata_mut()
+                .take_finalizers()
+                .update_status(statuses.next().unwrap());
+        });
+        drop(array);
+        route.await.unwrap();
+
+        let routed = rx.next().await.unwrap();
+        let messages = routed
+            .into_events()
+            .map(|event| event.into_log()["message"].to_string_lossy())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, vec!["errored".to_string()]);
+        // The batch is finalized once the dead letter is done with the routed event too.
+        assert_eq!(receiver.await, BatchStatus::Delivered);
+    }
+}
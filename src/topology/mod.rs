@@ -8,10 +8,32 @@
 
 pub(super) use vector_core::fanout;
 
+/// Creates the span that every task of a component runs in.
+///
+/// All of the component's futures (building, running, healthchecks, buffers) must be
+/// instrumented with a span created by this macro, so that every internal log and metric
+/// emitted by the component carries its `component_kind`, `component_id` and
+/// `component_type` fields.
+macro_rules! component_span {
+    ($kind:literal, $id:expr, $component_type:expr $(, $($fields:tt)*)?) => {
+        error_span!(
+            $kind,
+            component_kind = $kind,
+            component_id = %$id,
+            component_type = %$component_type,
+            // maintained for compatibility
+            component_name = %$id,
+            $($($fields)*)?
+        )
+    };
+}
+
 pub mod builder;
+mod dead_letter;
 mod ready_arrays;
 mod running;
 mod schema;
+mod sequence;
 mod task;
 
 #[cfg(test)]
@@ -25,6 +47,7 @@ use std::{
 
 use futures::{Future, FutureExt};
 pub(super) use running::RunningTopology;
+pub(crate) use schema::unproduced_field_warnings;
 use tokio::sync::{mpsc, watch};
 use vector_buffers::{
     topology::channel::{BufferReceiverStream, BufferSender},
@@ -33,6 +56,7 @@ use vector_buffers::{
 
 use crate::{
     config::{ComponentKey, Config, ConfigDiff, OutputId},
+    egress,
     event::EventArray,
     topology::{
         builder::Pieces,
@@ -46,6 +70,8 @@ type BuiltBuffer = (
     BufferSender<EventArray>,
     Arc<Mutex<Option<BufferReceiverStream<EventArray>>>>,
     Acker,
+    // The receiver of the events the buffer overflows, for sinks with an overflow sink.
+    Option<Arc<Mutex<Option<BufferReceiverStream<EventArray>>>>>,
 );
 
 /// A tappable output consisting of an output ID and associated metadata
@@ -83,6 +109,9 @@ pub async fn start_validated(
 ) -> Option<(RunningTopology, mpsc::UnboundedReceiver<()>)> {
     let (abort_tx, abort_rx) = mpsc::unbounded_channel();
 
+    // The global limit is set again by the reloads changing it.
+    egress::configure(config.global.egress_bytes_per_sec);
+
     let mut running_topology = RunningTopology::new(config, abort_tx);
 
     if !running_topology
@@ -93,6 +122,7 @@ pub async fn start_validated(
     }
     running_topology.connect_diff(&diff, &mut pieces).await;
     running_topology.spawn_diff(&diff, pieces);
+    running_topology.spawn_enrichment_table_reloader();
 
     Some((running_topology, abort_rx))
 }
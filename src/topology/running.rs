@@ -16,7 +16,10 @@ use vector_buffers::topology::channel::BufferSender;
 
 use super::{TapOutput, TapResource};
 use crate::{
-    config::{ComponentKey, Config, ConfigDiff, HealthcheckOptions, OutputId, Resource},
+    config::{
+        ComponentKey, Config, ConfigDiff, GlobalOptions, HealthcheckOptions, OutputId, Resource,
+    },
+    egress,
     event::EventArray,
     shutdown::SourceShutdownCoordinator,
     spawn_named,
@@ -31,6 +34,9 @@ use crate::{
     trigger::DisabledTrigger,
 };
 
+/// How often enrichment tables are checked for changes to their underlying data.
+const ENRICHMENT_TABLE_RELOAD_INTERVAL: Duration = Duration::from_secs(10);
+
 #[allow(dead_code)]
 pub struct RunningTopology {
     inputs: HashMap<ComponentKey, BufferSender<EventArray>>,
@@ -43,6 +49,7 @@ pub struct RunningTopology {
     abort_tx: mpsc::UnboundedSender<()>,
     watch: (WatchTx, WatchRx),
     pub(crate) running: Arc<AtomicBool>,
+    enrichment_table_reloader: Option<tokio::task::JoinHandle<()>>,
 }
 
 impl RunningTopology {
@@ -58,6 +65,7 @@ impl RunningTopology {
             abort_tx,
             watch: watch::channel(TapResource::default()),
             running: Arc::new(AtomicBool::new(true)),
+            enrichment_table_reloader: None,
         }
     }
 
@@ -100,6 +108,9 @@ impl RunningTopology {
     pub fn stop(self) -> impl Future<Output = ()> {
         // Update the API's health endpoint to signal shutdown
         self.running.store(false, Ordering::Relaxed);
+        if let Some(reloader) = &self.enrichment_table_reloader {
+            reloader.abort();
+        }
         // Create handy handles collections of all tasks for the subsequent
         // operations.
         let mut wait_handles = Vec::new();
@@ -206,7 +217,12 @@ impl RunningTopology {
     pub async fn reload_config_and_respawn(&mut self, new_config: Config) -> Result<bool, ()> {
         info!("Reloading running topology with new configuration.");
 
-        if self.config.global != new_config.global {
+        // The global egress limit is the only global option reloads can change.
+        let global = GlobalOptions {
+            egress_bytes_per_sec: self.config.global.egress_bytes_per_sec,
+            ..new_config.global.clone()
+        };
+        if self.config.global != global {
             error!(
                 message =
                 "Global options can't be changed while reloading config file; reload aborted. Please restart Vector to reload the configuration file."
@@ -244,7 +260,9 @@ impl RunningTopology {
             {
                 self.connect_diff(&diff, &mut new_pieces).await;
                 self.spawn_diff(&diff, new_pieces);
+                egress::configure(new_config.global.egress_bytes_per_sec);
                 self.config = new_config;
+                self.spawn_enrichment_table_reloader();
 
                 info!("New configuration loaded successfully.");
 
@@ -277,6 +295,31 @@ impl RunningTopology {
         Err(())
     }
 
+    /// Periodically checks the enrichment tables of the current configuration for changes to
+    /// their underlying data, reloading them in place.
+    ///
+    /// Any previously spawned reloader is stopped, since it watches an outdated configuration.
+    pub(crate) fn spawn_enrichment_table_reloader(&mut self) {
+        if let Some(previous) = self.enrichment_table_reloader.take() {
+            previous.abort();
+        }
+        if self.config.enrichment_tables.is_empty() {
+            return;
+        }
+
+        let tables = self.config.enrichment_tables.clone();
+        let globals = self.config.global.clone();
+        self.enrichment_table_reloader = Some(tokio::spawn(async move {
+            let mut interval = interval(ENRICHMENT_TABLE_RELOAD_INTERVAL);
+            // The first tick completes immediately, right after the tables were loaded.
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                builder::reload_changed_enrichment_tables(&tables, &globals).await;
+            }
+        }));
+    }
+
     pub(crate) async fn run_healthchecks(
         &mut self,
         diff: &ConfigDiff,
@@ -414,13 +457,18 @@ impl RunningTopology {
             .filter(|&(existing_sink, _)| existing_sink)
             .map(|(_, key)| key.clone());
 
-        // For any sink whose buffer configuration didn't change, we can reuse their buffer.
+        // For any sink whose buffer configuration didn't change, we can reuse their buffer. A buffer
+        // which overflows is reused along with the receiver of the events it overflows, so the
+        // sink must still have an overflow sink, or still not have one.
         let reuse_buffers = diff
             .sinks
             .to_change
             .iter()
             .filter(|&key| {
-                self.config.sink(key).unwrap().buffer == new_config.sink(key).unwrap().buffer
+                let sink = self.config.sink(key).unwrap();
+                let new_sink = new_config.sink(key).unwrap();
+                sink.buffer == new_sink.buffer
+                    && sink.overflow_sink.is_some() == new_sink.overflow_sink.is_some()
             })
             .cloned()
             .collect::<HashSet<_>>();
@@ -436,6 +484,7 @@ impl RunningTopology {
         for key in &diff.sinks.to_remove {
             debug!(component = %key, "Removing sink.");
             self.remove_inputs(key, diff).await;
+            self.remove_outputs(key);
         }
 
         // After that, for any changed sinks, we temporarily detach their inputs (not remove) so
@@ -464,6 +513,7 @@ impl RunningTopology {
                 buffer_tx.insert(key.clone(), self.inputs.get(key).unwrap().clone());
             }
             self.remove_inputs(key, diff).await;
+            self.remove_outputs(key);
         }
 
         // Now that we've disconnected or temporarily detached the inputs to all changed/removed
@@ -498,12 +548,19 @@ impl RunningTopology {
                     // buffer) than it is to pass around info about which sinks are having their
                     // buffers reused and treat them differently at other stages.
                     let tx = buffer_tx.remove(key).unwrap();
-                    let (rx, acker) = match buffer {
-                        TaskOutput::Sink(rx, acker) => (rx.into_inner(), acker),
+                    let (rx, acker, overflow_rx) = match buffer {
+                        TaskOutput::Sink(rx, acker, overflow_rx) => {
+                            (rx.into_inner(), acker, overflow_rx)
+                        }
                         _ => unreachable!(),
                     };
+                    let overflow_rx =
+                        overflow_rx.map(|overflow_rx| Arc::new(Mutex::new(Some(overflow_rx))));
 
-                    buffers.insert(key.clone(), (tx, Arc::new(Mutex::new(Some(rx))), acker));
+                    buffers.insert(
+                        key.clone(),
+                        (tx, Arc::new(Mutex::new(Some(rx))), acker, overflow_rx),
+                    );
                 }
             }
         }
@@ -547,6 +604,15 @@ impl RunningTopology {
             self.setup_outputs(key, new_pieces).await;
         }
 
+        // Sinks only have outputs when they route the events they fail to deliver to a dead
+        // letter component, which may be a transform, or don't accept all the types of events.
+        for key in diff.sinks.changed_and_added() {
+            if new_pieces.outputs.contains_key(key) {
+                debug!(component = %key, "Configuring outputs for sink.");
+                self.setup_outputs(key, new_pieces).await;
+            }
+        }
+
         // Now that all possible outputs are configured, we can start wiring up inputs, starting
         // with transforms.
         for key in diff.transforms.changed_and_added() {
@@ -778,14 +844,7 @@ impl RunningTopology {
 
     fn spawn_sink(&mut self, key: &ComponentKey, new_pieces: &mut builder::Pieces) {
         let task = new_pieces.tasks.remove(key).unwrap();
-        let span = error_span!(
-            "sink",
-            component_kind = "sink",
-            component_id = %task.id(),
-            component_type = %task.typetag(),
-            // maintained for compatibility
-            component_name = %task.id(),
-        );
+        let span = component_span!("sink", task.id(), task.typetag());
         let task_name = format!(">> {} ({})", task.typetag(), task.id());
         let task = handle_errors(task, self.abort_tx.clone()).instrument(span.or_current());
         let spawned = spawn_named(task, task_name.as_ref());
@@ -796,14 +855,7 @@ impl RunningTopology {
 
     fn spawn_transform(&mut self, key: &ComponentKey, new_pieces: &mut builder::Pieces) {
         let task = new_pieces.tasks.remove(key).unwrap();
-        let span = error_span!(
-            "transform",
-            component_kind = "transform",
-            component_id = %task.id(),
-            component_type = %task.typetag(),
-            // maintained for compatibility
-            component_name = %task.id(),
-        );
+        let span = component_span!("transform", task.id(), task.typetag());
         let task_name = format!(">> {} ({}) >>", task.typetag(), task.id());
         let task = handle_errors(task, self.abort_tx.clone()).instrument(span.or_current());
         let spawned = spawn_named(task, task_name.as_ref());
@@ -814,14 +866,7 @@ impl RunningTopology {
 
     fn spawn_source(&mut self, key: &ComponentKey, new_pieces: &mut builder::Pieces) {
         let task = new_pieces.tasks.remove(key).unwrap();
-        let span = error_span!(
-            "source",
-            component_kind = "source",
-            component_id = %task.id(),
-            component_type = %task.typetag(),
-            // maintained for compatibility
-            component_name = %task.id(),
-        );
+        let span = component_span!("source", task.id(), task.typetag());
         let task_name = format!("{} ({}) >>", task.typetag(), task.id());
         let task = handle_errors(task, self.abort_tx.clone()).instrument(span.clone().or_current());
         let spawned = spawn_named(task, task_name.as_ref());
@@ -861,5 +906,14 @@ fn get_changed_outputs(diff: &ConfigDiff, output_ids: Vec<OutputId>) -> Vec<Outp
         );
     }
 
+    for sink_key in &diff.sinks.to_change {
+        changed_outputs.extend(
+            output_ids
+                .iter()
+                .filter(|id| &id.component == sink_key)
+                .cloned(),
+        );
+    }
+
     changed_outputs
 }
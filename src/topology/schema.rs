@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 pub(super) use crate::schema::Definition;
+use value::Kind;
 
 use crate::{
     config::{ComponentKey, Config, Output, OutputId, SinkOuter},
@@ -259,6 +260,42 @@ pub(super) fn validate_sink_expectations(
     Ok(())
 }
 
+/// Returns a warning for every field read by a transform that none of the transform's inputs
+/// can produce.
+///
+/// Fields can only be checked when the merged schema of the inputs is closed, i.e. it defines
+/// known fields and doesn't allow any unknown fields. Otherwise, any field could be produced.
+pub(crate) fn unproduced_field_warnings(config: &Config) -> Vec<String> {
+    let mut warnings = vec![];
+    let mut cache = HashMap::default();
+
+    for (key, transform) in config.transforms() {
+        let fields = match transform.inner.queried_fields() {
+            Some(fields) => fields,
+            None => continue,
+        };
+
+        let collection = merged_definition(&transform.inputs, config, &mut cache)
+            .collection()
+            .clone();
+        if collection.known().is_empty() || collection.unknown().is_some() {
+            continue;
+        }
+
+        let kind = Kind::object(collection);
+        for field in fields.iter().filter(|field| !field.is_root()) {
+            if matches!(kind.find_at_path(&field.to_lookup()), Ok(None)) {
+                warnings.push(format!(
+                    "Transform \"{}\" reads field \".{}\", which none of its inputs produce",
+                    key, field
+                ));
+            }
+        }
+    }
+
+    warnings
+}
+
 pub(super) trait ComponentContainer {
     fn source_outputs(&self, key: &ComponentKey) -> Option<Vec<Output>>;
 
@@ -0,0 +1,141 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    event::{EventArray, EventContainer, SequenceNumber},
+    internal_events::{SequenceGapDetected, SequenceReorderDetected},
+};
+
+/// Numbers the events sent by a source output, in the order they are sent.
+pub(super) struct Sequencer {
+    stream: Arc<str>,
+    next: u64,
+}
+
+impl Sequencer {
+    pub(super) fn new(stream: impl Into<Arc<str>>) -> Self {
+        Self {
+            stream: stream.into(),
+            next: 0,
+        }
+    }
+
+    pub(super) fn number(&mut self, array: &mut EventArray) {
+        array.for_each_event(|mut event| {
+            event
+                .metadata_mut()
+                .set_sequence_number(Some(SequenceNumber {
+                    stream: Arc::clone(&self.stream),
+                    number: self.next,
+                }));
+            self.next += 1;
+        });
+    }
+}
+
+/// Checks the sequence numbers of the events received by a sink, reporting the events that are
+/// missing or out of order.
+///
+/// Events dropped or aggregated by transforms on the way are reported as missing too.
+#[derive(Default)]
+pub(super) struct SequenceChecker {
+    /// The number expected next for each source output.
+    expected: HashMap<Arc<str>, u64>,
+}
+
+impl SequenceChecker {
+    pub(super) fn check(&mut self, array: &EventArray) {
+        for event in array.iter_events() {
+            if let Some(sequence) = event.metadata().sequence_number() {
+                self.check_number(sequence);
+            }
+        }
+    }
+
+    fn check_number(&mut self, sequence: &SequenceNumber) {
+        let number = sequence.number;
+        match self.expected.get_mut(&sequence.stream) {
+            None => {
+                self.expected
+                    .insert(Arc::clone(&sequence.stream), number + 1);
+            }
+            Some(expected) if number == *expected => *expected += 1,
+            Some(expected) if number > *expected => {
+                emit!(SequenceGapDetected {
+                    stream: &sequence.stream,
+                    missing: number - *expected,
+                });
+                *expected = number + 1;
+            }
+            // The source was restarted by a reload, and numbers its events from zero again.
+            Some(expected) if number == 0 => *expected = 1,
+            Some(_) => emit!(SequenceReorderDetected {
+                stream: &sequence.stream,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::LogEvent;
+
+    fn numbered(stream: &str, numbers: &[u64]) -> EventArray {
+        numbers
+            .iter()
+            .map(|&number| {
+                let mut log = LogEvent::from("test");
+                log.metadata_mut().set_sequence_number(Some(SequenceNumber {
+                    stream: stream.into(),
+                    number,
+                }));
+                log
+            })
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    fn numbers(array: &EventArray) -> Vec<u64> {
+        array
+            .iter_events()
+            .map(|event| event.metadata().sequence_number().as_ref().unwrap().number)
+            .collect()
+    }
+
+    fn expected(checker: &SequenceChecker, stream: &str) -> u64 {
+        checker.expected[&Arc::from(stream)]
+    }
+
+    #[test]
+    fn numbers_events_across_arrays() {
+        let mut sequencer = Sequencer::new("in");
+        let mut first = EventArray::from(vec![LogEvent::from("a"), LogEvent::from("b")]);
+        let mut second = EventArray::from(vec![LogEvent::from("c")]);
+
+        sequencer.number(&mut first);
+        sequencer.number(&mut second);
+        assert_eq!(numbers(&first), vec![0, 1]);
+        assert_eq!(numbers(&second), vec![2]);
+    }
+
+    #[test]
+    fn checks_each_stream() {
+        let mut checker = SequenceChecker::default();
+
+        // Numbers before the first one received aren't missing, as the sink may have been
+        // started after the source.
+        checker.check(&numbered("in", &[5, 6]));
+        assert_eq!(expected(&checker, "in"), 7);
+
+        checker.check(&numbered("other", &[0, 1]));
+        assert_eq!(expected(&checker, "other"), 2);
+
+        // A gap moves the expected number past it, so the late event is out of order.
+        checker.check(&numbered("in", &[9, 7]));
+        assert_eq!(expected(&checker, "in"), 10);
+
+        checker.check(&numbered("in", &[0, 1]));
+        assert_eq!(expected(&checker, "in"), 2);
+        assert_eq!(expected(&checker, "other"), 2);
+    }
+}
@@ -16,8 +16,12 @@ use crate::{config::ComponentKey, utilization::Utilization};
 pub(crate) enum TaskOutput {
     Source,
     Transform,
-    /// Buffer of sink
-    Sink(Utilization<BufferReceiverStream<EventArray>>, Acker),
+    /// Buffer of sink, along with the receiver of the events it overflows, if any
+    Sink(
+        Utilization<BufferReceiverStream<EventArray>>,
+        Acker,
+        Option<BufferReceiverStream<EventArray>>,
+    ),
     Healthcheck,
 }
 
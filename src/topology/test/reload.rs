@@ -131,6 +131,8 @@ async fn topology_reuse_old_port_sink() {
             namespace: None,
             tags: None,
         })],
+        all_metrics: false,
+        host_tag: None,
     };
 
     let mut old_config = Config::builder();
@@ -181,6 +183,8 @@ async fn topology_reuse_old_port_cross_dependency() {
             namespace: None,
             tags: None,
         })],
+        all_metrics: false,
+        host_tag: None,
     };
 
     let mut old_config = Config::builder();
@@ -244,6 +248,8 @@ async fn topology_disk_buffer_conflict() {
                 namespace: None,
                 tags: None,
             })],
+            all_metrics: false,
+            host_tag: None,
         },
     );
     old_config.add_sink(
@@ -301,6 +307,8 @@ async fn topology_reload_with_new_components() {
             namespace: None,
             tags: None,
         })],
+        all_metrics: false,
+        host_tag: None,
     };
 
     let mut old_config = Config::builder();
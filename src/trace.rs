@@ -1,5 +1,6 @@
 use std::{
     collections::HashMap,
+    fs::File,
     marker::PhantomData,
     str::FromStr,
     sync::{
@@ -54,7 +55,12 @@ fn metrics_layer_enabled() -> bool {
     !matches!(std::env::var("DISABLE_INTERNAL_METRICS_TRACING_INTEGRATION"), Ok(x) if x == "true")
 }
 
-pub fn init(color: bool, json: bool, levels: &str) {
+/// Initializes `tracing`.
+///
+/// Internal logs are written to the console, and, if `log_file` is given, also as JSON to that
+/// file. Every log emitted from within a component's span carries the span's `component_*`
+/// fields, in the list of its spans.
+pub fn init(color: bool, json: bool, levels: &str, log_file: Option<File>) {
     let _ = BUFFER.set(Mutex::new(Some(Vec::new())));
     let fmt_filter = tracing_subscriber::filter::Targets::from_str(levels).expect(
         "logging filter targets were not formatted correctly or did not specify a valid level",
@@ -63,8 +69,23 @@ pub fn init(color: bool, json: bool, levels: &str) {
     let metrics_layer = metrics_layer_enabled()
         .then(|| MetricsLayer::new().with_filter(tracing_subscriber::filter::LevelFilter::INFO));
 
+    let file_layer = log_file.map(|file| {
+        let formatter = tracing_subscriber::fmt::layer()
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            // The component fields are carried by the component's span, which isn't the current
+            // one for the events emitted within the spans nested in it.
+            .with_span_list(true)
+            .with_ansi(false)
+            .with_writer(Mutex::new(file));
+
+        RateLimitedLayer::new(formatter).with_filter(fmt_filter.clone())
+    });
+
     let subscriber = tracing_subscriber::registry()
         .with(metrics_layer)
+        .with(file_layer)
         .with(BroadcastLayer::new().with_filter(fmt_filter.clone()));
 
     #[cfg(feature = "tokio-console")]
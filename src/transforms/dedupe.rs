@@ -1,9 +1,15 @@
-use std::{future::ready, pin::Pin};
+use std::{
+    future::ready,
+    pin::Pin,
+    time::{Duration, Instant},
+};
 
+use bloom::{BloomFilter, ASMS};
 use bytes::Bytes;
 use futures::{Stream, StreamExt};
 use lru::LruCache;
 use serde::{Deserialize, Serialize};
+use vector_core::time::Clock;
 
 use crate::{
     config::{
@@ -25,10 +31,50 @@ pub enum FieldMatchConfig {
     IgnoreFields(Vec<String>),
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheMode {
+    /// Exact matching backed by an LRU cache holding up to `num_events` entries.
+    Lru,
+    /// Approximate matching backed by a pair of rotating bloom filters, each sized for
+    /// `num_events` entries. Memory usage is bounded regardless of the size of the
+    /// entries, at the cost of occasionally discarding a unique event as a duplicate.
+    Probabilistic,
+}
+
+impl Default for CacheMode {
+    fn default() -> Self {
+        Self::Lru
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct CacheConfig {
     pub num_events: usize,
+    #[serde(default)]
+    pub mode: CacheMode,
+    /// The amount of time, in seconds, after which a cached event is no longer considered
+    /// when checking for duplicates.
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
+    #[serde(default = "default_false_positive_rate")]
+    pub false_positive_rate: f32,
+}
+
+impl CacheConfig {
+    pub const fn new(num_events: usize) -> Self {
+        Self {
+            num_events,
+            mode: CacheMode::Lru,
+            ttl_secs: None,
+            false_positive_rate: default_false_positive_rate(),
+        }
+    }
+}
+
+const fn default_false_positive_rate() -> f32 {
+    0.0001
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -41,7 +87,7 @@ pub struct DedupeConfig {
 }
 
 const fn default_cache_config() -> CacheConfig {
-    CacheConfig { num_events: 5000 }
+    CacheConfig::new(5000)
 }
 
 impl DedupeConfig {
@@ -63,7 +109,8 @@ impl DedupeConfig {
 
 pub struct Dedupe {
     fields: FieldMatchConfig,
-    cache: LruCache<CacheEntry, bool>,
+    cache: Cache,
+    clock: Clock,
 }
 
 inventory::submit! {
@@ -83,8 +130,19 @@ impl GenerateConfig for DedupeConfig {
 #[async_trait::async_trait]
 #[typetag::serde(name = "dedupe")]
 impl TransformConfig for DedupeConfig {
-    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
-        Ok(Transform::event_task(Dedupe::new(self.clone())))
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        if self.cache.num_events == 0 {
+            return Err("`cache.num_events` must be greater than 0".into());
+        }
+        if self.cache.mode == CacheMode::Probabilistic
+            && !(self.cache.false_positive_rate > 0.0 && self.cache.false_positive_rate < 1.0)
+        {
+            return Err("`cache.false_positive_rate` must be between 0 and 1 (exclusive)".into());
+        }
+        Ok(Transform::event_task(Dedupe::new(
+            self.clone(),
+            context.clock.clone(),
+        )))
     }
 
     fn input(&self) -> Input {
@@ -146,19 +204,139 @@ const fn type_id_for_value(val: &Value) -> TypeId {
     }
 }
 
+/// The storage used to remember previously seen events.
+enum Cache {
+    /// Maps each entry to the time it was first seen.
+    Lru {
+        entries: LruCache<CacheEntry, Instant>,
+        ttl: Option<Duration>,
+    },
+    Probabilistic(RotatingBloomFilter),
+}
+
+impl Cache {
+    fn new(config: &CacheConfig) -> Self {
+        let ttl = config.ttl_secs.map(Duration::from_secs);
+        match config.mode {
+            CacheMode::Lru => Self::Lru {
+                entries: LruCache::new(config.num_events),
+                ttl,
+            },
+            CacheMode::Probabilistic => Self::Probabilistic(RotatingBloomFilter::new(
+                config.num_events,
+                config.false_positive_rate,
+                ttl,
+            )),
+        }
+    }
+
+    /// Records the entry as seen at `now`, returning whether it had already been seen
+    /// within the configured bounds.
+    fn check_and_insert(&mut self, entry: CacheEntry, now: Instant) -> bool {
+        match self {
+            Self::Lru { entries, ttl } => {
+                let is_live = |seen: &Instant| {
+                    ttl.map_or(true, |ttl| now.saturating_duration_since(*seen) < ttl)
+                };
+                match entries.get(&entry) {
+                    Some(seen) if is_live(seen) => true,
+                    _ => {
+                        entries.put(entry, now);
+                        false
+                    }
+                }
+            }
+            Self::Probabilistic(filter) => filter.check_and_insert(&entry, now),
+        }
+    }
+}
+
+/// A pair of bloom filters, each sized to hold `capacity` entries at the configured false
+/// positive rate. Entries are inserted into the current filter, and once it is full (or
+/// older than the TTL, if any) the previous filter is discarded and replaced by the
+/// current one. An entry is therefore remembered for at least `capacity` insertions (or
+/// the TTL) and at most twice that, while memory stays bounded to two filters.
+struct RotatingBloomFilter {
+    current: BloomFilter,
+    previous: BloomFilter,
+    capacity: usize,
+    false_positive_rate: f32,
+    ttl: Option<Duration>,
+    inserted: usize,
+    generation_start: Option<Instant>,
+}
+
+impl RotatingBloomFilter {
+    fn new(capacity: usize, false_positive_rate: f32, ttl: Option<Duration>) -> Self {
+        let new_filter = || {
+            BloomFilter::with_rate(
+                false_positive_rate,
+                u32::try_from(capacity).unwrap_or(u32::MAX),
+            )
+        };
+        Self {
+            current: new_filter(),
+            previous: new_filter(),
+            capacity,
+            false_positive_rate,
+            ttl,
+            inserted: 0,
+            generation_start: None,
+        }
+    }
+
+    fn rotate(&mut self, now: Instant) {
+        let fresh = BloomFilter::with_rate(
+            self.false_positive_rate,
+            u32::try_from(self.capacity).unwrap_or(u32::MAX),
+        );
+        self.previous = std::mem::replace(&mut self.current, fresh);
+        self.inserted = 0;
+        self.generation_start = Some(now);
+    }
+
+    fn check_and_insert(&mut self, entry: &CacheEntry, now: Instant) -> bool {
+        let generation_start = *self.generation_start.get_or_insert(now);
+        if let Some(ttl) = self.ttl {
+            if now.saturating_duration_since(generation_start) >= ttl {
+                // Both generations are stale once two TTLs have elapsed.
+                if now.saturating_duration_since(generation_start) >= ttl * 2 {
+                    self.previous.clear();
+                    self.current.clear();
+                    self.inserted = 0;
+                    self.generation_start = Some(now);
+                } else {
+                    self.rotate(now);
+                }
+            }
+        }
+
+        if self.current.contains(entry) || self.previous.contains(entry) {
+            return true;
+        }
+
+        if self.inserted >= self.capacity {
+            self.rotate(now);
+        }
+        self.current.insert(entry);
+        self.inserted += 1;
+        false
+    }
+}
+
 impl Dedupe {
-    pub fn new(config: DedupeConfig) -> Self {
-        let num_entries = config.cache.num_events;
+    pub fn new(config: DedupeConfig, clock: Clock) -> Self {
         let fields = config.fill_default_fields_match();
         Self {
             fields,
-            cache: LruCache::new(num_entries),
+            cache: Cache::new(&config.cache),
+            clock,
         }
     }
 
     fn transform_one(&mut self, event: Event) -> Option<Event> {
         let cache_entry = build_cache_entry(&event, &self.fields);
-        if self.cache.put(cache_entry, true).is_some() {
+        if self.cache.check_and_insert(cache_entry, self.clock.now()) {
             emit!(DedupeEventDiscarded { event });
             None
         } else {
@@ -220,10 +398,12 @@ impl TaskTransform<Event> for Dedupe {
 mod tests {
     use std::collections::BTreeMap;
 
+    use vector_core::time::MockClock;
+
     use super::*;
     use crate::{
         event::{Event, Value},
-        transforms::dedupe::{CacheConfig, DedupeConfig, FieldMatchConfig},
+        transforms::dedupe::{CacheConfig, CacheMode, DedupeConfig, FieldMatchConfig},
     };
 
     #[test]
@@ -232,10 +412,13 @@ mod tests {
     }
 
     fn make_match_transform(num_events: usize, fields: Vec<String>) -> Dedupe {
-        Dedupe::new(DedupeConfig {
-            cache: CacheConfig { num_events },
-            fields: Some(FieldMatchConfig::MatchFields(fields)),
-        })
+        Dedupe::new(
+            DedupeConfig {
+                cache: CacheConfig::new(num_events),
+                fields: Some(FieldMatchConfig::MatchFields(fields)),
+            },
+            Clock::default(),
+        )
     }
 
     fn make_ignore_transform(num_events: usize, given_fields: Vec<String>) -> Dedupe {
@@ -243,10 +426,13 @@ mod tests {
         let mut fields = vec!["message".into(), "timestamp".into()];
         fields.extend(given_fields);
 
-        Dedupe::new(DedupeConfig {
-            cache: CacheConfig { num_events },
-            fields: Some(FieldMatchConfig::IgnoreFields(fields)),
-        })
+        Dedupe::new(
+            DedupeConfig {
+                cache: CacheConfig::new(num_events),
+                fields: Some(FieldMatchConfig::IgnoreFields(fields)),
+            },
+            Clock::default(),
+        )
     }
 
     #[test]
@@ -485,4 +671,94 @@ mod tests {
         let new_event = transform.transform_one(event2.clone()).unwrap();
         assert_eq!(new_event, event2);
     }
+
+    fn make_ttl_transform(mode: CacheMode, ttl_secs: u64) -> (Dedupe, MockClock) {
+        let clock = MockClock::new();
+        let transform = Dedupe::new(
+            DedupeConfig {
+                cache: CacheConfig {
+                    mode,
+                    ttl_secs: Some(ttl_secs),
+                    ..CacheConfig::new(5)
+                },
+                fields: Some(FieldMatchConfig::MatchFields(vec!["matched".into()])),
+            },
+            Clock::Mock(clock.clone()),
+        );
+        (transform, clock)
+    }
+
+    fn make_probabilistic_transform(num_events: usize) -> Dedupe {
+        Dedupe::new(
+            DedupeConfig {
+                cache: CacheConfig {
+                    mode: CacheMode::Probabilistic,
+                    ..CacheConfig::new(num_events)
+                },
+                fields: Some(FieldMatchConfig::MatchFields(vec!["matched".into()])),
+            },
+            Clock::default(),
+        )
+    }
+
+    #[test]
+    fn dedupe_lru_ttl_expiry() {
+        ttl_expiry(make_ttl_transform(CacheMode::Lru, 10));
+    }
+
+    #[test]
+    fn dedupe_probabilistic_ttl_expiry() {
+        ttl_expiry(make_ttl_transform(CacheMode::Probabilistic, 10));
+    }
+
+    /// Test that a duplicate is only discarded while the original is within the TTL, and
+    /// that duplicates do not extend the lifetime of the original.
+    fn ttl_expiry((mut transform, clock): (Dedupe, MockClock)) {
+        let mut event = Event::from("message");
+        event.as_mut_log().insert("matched", "some value");
+
+        let new_event = transform.transform_one(event.clone()).unwrap();
+        assert_eq!(new_event, event);
+
+        clock.advance(Duration::from_secs(5));
+        assert_eq!(None, transform.transform_one(event.clone()));
+
+        clock.advance(Duration::from_secs(20));
+        let new_event = transform.transform_one(event.clone()).unwrap();
+        assert_eq!(new_event, event);
+    }
+
+    #[test]
+    fn dedupe_probabilistic_basic() {
+        basic(make_probabilistic_transform(5));
+    }
+
+    #[test]
+    fn dedupe_probabilistic_type_matching() {
+        type_matching(make_probabilistic_transform(5));
+    }
+
+    #[test]
+    fn dedupe_probabilistic_age_out() {
+        let mut transform = make_probabilistic_transform(1);
+
+        let events = (0..3)
+            .map(|i| {
+                let mut event = Event::from("message");
+                event.as_mut_log().insert("matched", i);
+                event
+            })
+            .collect::<Vec<_>>();
+
+        for event in &events {
+            let new_event = transform.transform_one(event.clone()).unwrap();
+            assert_eq!(&new_event, event);
+        }
+
+        // With a capacity of 1, only the last two entries are remembered.
+        assert_eq!(None, transform.transform_one(events[2].clone()));
+        assert_eq!(None, transform.transform_one(events[1].clone()));
+        let new_event = transform.transform_one(events[0].clone()).unwrap();
+        assert_eq!(new_event, events[0]);
+    }
 }
@@ -8,6 +8,7 @@ use crate::{
         DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext,
         TransformDescription,
     },
+    dropped_events,
     event::Event,
     internal_events::FilterEventDiscarded,
     schema,
@@ -91,7 +92,11 @@ impl FunctionTransform for Filter {
     fn transform(&mut self, output: &mut OutputBuffer, event: Event) {
         if self.condition.check(&event) {
             output.push(event);
-        } else if self.last_emission.elapsed() >= self.emissions_max_delay {
+            return;
+        }
+
+        dropped_events::sample(&event, "filtered");
+        if self.last_emission.elapsed() >= self.emissions_max_delay {
             emit!(FilterEventDiscarded {
                 total: self.emissions_deferred,
             });
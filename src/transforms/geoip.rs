@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     config::{
-        DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext,
+        Capability, DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext,
         TransformDescription,
     },
     event::Event,
@@ -88,6 +88,14 @@ impl TransformConfig for GeoipConfig {
     fn transform_type(&self) -> &'static str {
         "geoip"
     }
+
+    fn capabilities(&self) -> Vec<Capability> {
+        vec![Capability::path(&self.database)]
+    }
+
+    fn supports_sandbox(&self) -> bool {
+        true
+    }
 }
 
 // MaxMind GeoIP database files have a type field we can use to recognize specific
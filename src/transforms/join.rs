@@ -0,0 +1,484 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use vector_core::time::Clock;
+
+use crate::{
+    conditions::{AnyCondition, Condition},
+    config::{
+        DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext,
+        TransformDescription,
+    },
+    dropped_events,
+    event::{Event, LogEvent, Value},
+    internal_events::{JoinEventDiscarded, TemplateRenderingError},
+    schema,
+    template::Template,
+    transforms::{SyncTransform, Transform, TransformOutputsBuf},
+};
+
+const UNMATCHED: &str = "unmatched";
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct JoinConfig {
+    /// The condition identifying events from the left side of the join.
+    pub left: AnyCondition,
+    /// The condition identifying events from the right side of the join.
+    pub right: AnyCondition,
+    /// The key events from both sides are correlated on.
+    pub key_field: Template,
+    /// How long an event waits for its counterpart before it's considered unmatched.
+    #[serde(default = "default_expire_after_ms")]
+    pub expire_after_ms: u64,
+    /// How often pending events are checked for expiry.
+    #[serde(default = "default_flush_period_ms")]
+    pub flush_period_ms: u64,
+    /// The maximum number of events waiting for their counterpart. Once reached, the oldest
+    /// pending event is considered unmatched to make room for the next one.
+    #[serde(default = "default_max_pending")]
+    pub max_pending: usize,
+    /// Send unmatched events to the `unmatched` output instead of dropping them.
+    #[serde(default)]
+    pub reroute_unmatched: bool,
+}
+
+const fn default_expire_after_ms() -> u64 {
+    30000
+}
+
+const fn default_flush_period_ms() -> u64 {
+    1000
+}
+
+const fn default_max_pending() -> usize {
+    10000
+}
+
+inventory::submit! {
+    TransformDescription::new::<JoinConfig>("join")
+}
+
+impl GenerateConfig for JoinConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"left.type = "vrl"
+            left.source = '.type == "request"'
+            right.type = "vrl"
+            right.source = '.type == "response"'
+            key_field = "{{ request_id }}""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "join")]
+impl TransformConfig for JoinConfig {
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        Join::new(self, context).map(Transform::synchronous)
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        let mut outputs = vec![Output::default(DataType::Log)];
+        if self.reroute_unmatched {
+            outputs.push(Output::default(DataType::Log).with_port(UNMATCHED));
+        }
+        outputs
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "join"
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Side {
+    Left,
+    Right,
+}
+
+impl Side {
+    const fn other(self) -> Self {
+        match self {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Join {
+    left: Condition,
+    right: Condition,
+    key_field: Template,
+    expire_after: Duration,
+    flush_period: Duration,
+    max_pending: usize,
+    reroute_unmatched: bool,
+    clock: Clock,
+    /// Events waiting for their counterpart, oldest first for each key.
+    pending: HashMap<(Side, String), VecDeque<(Instant, LogEvent)>>,
+    /// The number of events in `pending`.
+    pending_count: usize,
+    /// The keys of the pending events, in arrival order, used to expire them.
+    arrivals: VecDeque<(Instant, Side, String)>,
+}
+
+impl Join {
+    pub fn new(config: &JoinConfig, context: &TransformContext) -> crate::Result<Self> {
+        if config.expire_after_ms == 0 || config.flush_period_ms == 0 || config.max_pending == 0 {
+            return Err(
+                "`expire_after_ms`, `flush_period_ms` and `max_pending` must be greater than zero"
+                    .into(),
+            );
+        }
+
+        Ok(Self {
+            left: config.left.build(&context.enrichment_tables)?,
+            right: config.right.build(&context.enrichment_tables)?,
+            key_field: config.key_field.clone(),
+            expire_after: Duration::from_millis(config.expire_after_ms),
+            flush_period: Duration::from_millis(config.flush_period_ms),
+            max_pending: config.max_pending,
+            reroute_unmatched: config.reroute_unmatched,
+            clock: context.clock.clone(),
+            pending: HashMap::new(),
+            pending_count: 0,
+            arrivals: VecDeque::new(),
+        })
+    }
+
+    fn side(&self, event: &Event) -> Option<Side> {
+        if self.left.check(event) {
+            Some(Side::Left)
+        } else if self.right.check(event) {
+            Some(Side::Right)
+        } else {
+            None
+        }
+    }
+
+    /// Emits or drops every pending event that has waited longer than `expire_after`.
+    fn expire(&mut self, now: Instant, output: &mut TransformOutputsBuf) {
+        while let Some((arrived, _, _)) = self.arrivals.front() {
+            if now.saturating_duration_since(*arrived) < self.expire_after {
+                break;
+            }
+            self.expire_oldest("Expired before a match arrived.", output);
+        }
+    }
+
+    /// Emits or drops the events of the oldest arrival, if they're still pending.
+    fn expire_oldest(&mut self, reason: &'static str, output: &mut TransformOutputsBuf) {
+        let (arrived, side, key) = match self.arrivals.pop_front() {
+            Some(arrival) => arrival,
+            None => return,
+        };
+
+        // The event may have been matched already, in which case it's no longer pending.
+        let mut expired = Vec::new();
+        if let Some(events) = self.pending.get_mut(&(side, key.clone())) {
+            while events.front().map_or(false, |(at, _)| *at <= arrived) {
+                let (_, event) = events.pop_front().expect("front exists");
+                expired.push(event);
+            }
+            if events.is_empty() {
+                self.pending.remove(&(side, key));
+            }
+        }
+        self.pending_count -= expired.len();
+
+        for event in expired {
+            self.unmatched(event.into(), reason, output);
+        }
+    }
+
+    fn unmatched(&self, event: Event, reason: &'static str, output: &mut TransformOutputsBuf) {
+        if self.reroute_unmatched {
+            output.push_named(UNMATCHED, event);
+        } else {
+            dropped_events::sample(&event, reason);
+            emit!(JoinEventDiscarded { reason });
+        }
+    }
+}
+
+/// Merges the fields of the right event into the left one, the right event's fields taking
+/// precedence.
+fn merge(mut left: LogEvent, right: LogEvent) -> LogEvent {
+    let (value, metadata) = right.into_parts();
+    if let (Some(fields), Value::Object(incoming)) = (left.as_map_mut(), value) {
+        fields.extend(incoming);
+    }
+    left.metadata_mut().merge(metadata);
+    left
+}
+
+impl SyncTransform for Join {
+    fn transform(&mut self, event: Event, output: &mut TransformOutputsBuf) {
+        let now = self.clock.now();
+        self.expire(now, output);
+
+        let side = match self.side(&event) {
+            Some(side) => side,
+            None => return self.unmatched(event, "Matched neither side.", output),
+        };
+        let key = match self.key_field.render_string(&event) {
+            Ok(key) => key,
+            Err(error) => {
+                emit!(TemplateRenderingError {
+                    error,
+                    field: Some("key_field"),
+                    drop_event: !self.reroute_unmatched,
+                });
+                return self.unmatched(event, "Failed to render the key.", output);
+            }
+        };
+
+        let counterpart = self
+            .pending
+            .get_mut(&(side.other(), key.clone()))
+            .and_then(VecDeque::pop_front);
+        match counterpart {
+            Some((_, counterpart)) => {
+                self.pending_count -= 1;
+                let event = event.into_log();
+                let merged = match side {
+                    Side::Left => merge(event, counterpart),
+                    Side::Right => merge(counterpart, event),
+                };
+                output.push(merged.into());
+            }
+            None => {
+                // Make room for the event, skipping the arrivals that were matched already.
+                while self.pending_count >= self.max_pending && !self.arrivals.is_empty() {
+                    self.expire_oldest("Too many events were pending.", output);
+                }
+                self.pending_count += 1;
+                self.pending
+                    .entry((side, key.clone()))
+                    .or_default()
+                    .push_back((now, event.into_log()));
+                self.arrivals.push_back((now, side, key));
+            }
+        }
+    }
+
+    fn flush_period(&self) -> Option<Duration> {
+        Some(self.flush_period)
+    }
+
+    fn flush(&mut self, output: &mut TransformOutputsBuf) {
+        let now = self.clock.now();
+        self.expire(now, output);
+    }
+
+    fn flush_all(&mut self, output: &mut TransformOutputsBuf) {
+        for (_, side, key) in std::mem::take(&mut self.arrivals) {
+            if let Some(events) = self.pending.remove(&(side, key)) {
+                for (_, event) in events {
+                    self.unmatched(event.into(), "Still pending on shutdown.", output);
+                }
+            }
+        }
+        self.pending_count = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_core::time::MockClock;
+
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<JoinConfig>();
+    }
+
+    fn join(reroute_unmatched: bool, clock: &MockClock) -> Join {
+        let config = toml::from_str::<JoinConfig>(&format!(
+            r#"
+            left.type = "vrl"
+            left.source = '.type == "request"'
+            right.type = "vrl"
+            right.source = '.type == "response"'
+            key_field = "{{{{ id }}}}"
+            expire_after_ms = 10000
+            max_pending = 2
+            reroute_unmatched = {}
+            "#,
+            reroute_unmatched
+        ))
+        .unwrap();
+        let context = TransformContext {
+            clock: Clock::Mock(clock.clone()),
+            ..Default::default()
+        };
+        Join::new(&config, &context).unwrap()
+    }
+
+    fn outputs(reroute_unmatched: bool) -> TransformOutputsBuf {
+        let mut outputs = vec![Output::default(DataType::Log)];
+        if reroute_unmatched {
+            outputs.push(Output::default(DataType::Log).with_port(UNMATCHED));
+        }
+        TransformOutputsBuf::new_with_capacity(outputs, 1)
+    }
+
+    fn log(value: serde_json::Value) -> Event {
+        Event::try_from(value).unwrap()
+    }
+
+    #[test]
+    fn joins_events_with_same_key() {
+        let clock = MockClock::new();
+        let mut join = join(false, &clock);
+        let mut outputs = outputs(false);
+
+        join.transform(
+            log(serde_json::json!({"type": "response", "id": "1", "status": 200})),
+            &mut outputs,
+        );
+        join.transform(
+            log(serde_json::json!({"type": "request", "id": "2", "path": "/b"})),
+            &mut outputs,
+        );
+        assert_eq!(outputs.drain().count(), 0);
+
+        join.transform(
+            log(serde_json::json!({"type": "request", "id": "1", "path": "/a"})),
+            &mut outputs,
+        );
+        let merged = outputs.drain().collect::<Vec<_>>();
+        assert_eq!(
+            merged,
+            vec![log(
+                serde_json::json!({"type": "response", "id": "1", "path": "/a", "status": 200})
+            )]
+        );
+    }
+
+    #[test]
+    fn reroutes_expired_events() {
+        let clock = MockClock::new();
+        let mut join = join(true, &clock);
+        let mut outputs = outputs(true);
+
+        let request = log(serde_json::json!({"type": "request", "id": "1"}));
+        join.transform(request.clone(), &mut outputs);
+
+        // Events matching neither side are unmatched right away.
+        let other = log(serde_json::json!({"type": "other", "id": "1"}));
+        join.transform(other.clone(), &mut outputs);
+        assert_eq!(
+            outputs.drain_named(UNMATCHED).collect::<Vec<_>>(),
+            vec![other]
+        );
+
+        clock.advance(Duration::from_secs(11));
+        let response = log(serde_json::json!({"type": "response", "id": "1"}));
+        join.transform(response, &mut outputs);
+
+        assert_eq!(outputs.drain().count(), 0);
+        assert_eq!(
+            outputs.drain_named(UNMATCHED).collect::<Vec<_>>(),
+            vec![request]
+        );
+    }
+
+    #[test]
+    fn flushes_expired_events_without_new_events() {
+        let clock = MockClock::new();
+        let mut join = join(true, &clock);
+        let mut outputs = outputs(true);
+
+        let request = log(serde_json::json!({"type": "request", "id": "1"}));
+        join.transform(request.clone(), &mut outputs);
+
+        join.flush(&mut outputs);
+        assert_eq!(outputs.drain_named(UNMATCHED).count(), 0);
+
+        clock.advance(Duration::from_secs(11));
+        join.flush(&mut outputs);
+        assert_eq!(
+            outputs.drain_named(UNMATCHED).collect::<Vec<_>>(),
+            vec![request]
+        );
+    }
+
+    #[test]
+    fn flushes_pending_events_when_input_ends() {
+        let clock = MockClock::new();
+        let mut join = join(true, &clock);
+        let mut outputs = outputs(true);
+
+        let first = log(serde_json::json!({"type": "request", "id": "1"}));
+        let second = log(serde_json::json!({"type": "response", "id": "2"}));
+        join.transform(first.clone(), &mut outputs);
+        join.transform(second.clone(), &mut outputs);
+
+        join.flush_all(&mut outputs);
+        assert_eq!(outputs.drain().count(), 0);
+        assert_eq!(
+            outputs.drain_named(UNMATCHED).collect::<Vec<_>>(),
+            vec![first, second]
+        );
+        assert!(join.pending.is_empty());
+    }
+
+    #[test]
+    fn reroutes_oldest_events_beyond_max_pending() {
+        let clock = MockClock::new();
+        let mut join = join(true, &clock);
+        let mut outputs = outputs(true);
+
+        let first = log(serde_json::json!({"type": "request", "id": "1"}));
+        let second = log(serde_json::json!({"type": "request", "id": "2"}));
+        let third = log(serde_json::json!({"type": "request", "id": "3"}));
+        join.transform(first.clone(), &mut outputs);
+        join.transform(second, &mut outputs);
+        assert_eq!(outputs.drain_named(UNMATCHED).count(), 0);
+
+        join.transform(third, &mut outputs);
+        assert_eq!(
+            outputs.drain_named(UNMATCHED).collect::<Vec<_>>(),
+            vec![first]
+        );
+
+        // Matched events make room for the next ones.
+        join.transform(
+            log(serde_json::json!({"type": "response", "id": "2"})),
+            &mut outputs,
+        );
+        assert_eq!(outputs.drain().count(), 1);
+        join.transform(
+            log(serde_json::json!({"type": "request", "id": "4"})),
+            &mut outputs,
+        );
+        assert_eq!(outputs.drain_named(UNMATCHED).count(), 0);
+        assert_eq!(join.pending_count, 2);
+    }
+
+    #[test]
+    fn invalid_expire_after() {
+        let config = toml::from_str::<JoinConfig>(
+            r#"
+            left.type = "is_log"
+            right.type = "is_log"
+            key_field = "{{ id }}"
+            expire_after_ms = 0
+            "#,
+        )
+        .unwrap();
+        assert!(Join::new(&config, &Default::default()).is_err());
+    }
+}
@@ -1,7 +1,9 @@
-use std::{collections::BTreeMap, convert::TryFrom, num::ParseFloatError};
+use std::{cmp::Ordering, collections::BTreeMap, convert::TryFrom, num::ParseFloatError};
 
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
+use vector_common::TimeZone;
+use vrl::{diagnostic::Formatter, Program, Runtime};
 
 use crate::{
     config::{
@@ -9,12 +11,12 @@ use crate::{
         TransformDescription,
     },
     event::{
-        metric::{Metric, MetricKind, MetricValue, StatisticKind},
-        Event, Value,
+        metric::{samples_to_buckets, Metric, MetricKind, MetricValue, Sample, StatisticKind},
+        Event, LogEvent, Value, VrlTarget,
     },
     internal_events::{
-        LogToMetricFieldNullError, LogToMetricParseFloatError, LogToMetricTemplateParseError,
-        ParserMissingFieldError,
+        LogToMetricDecodeError, LogToMetricFieldNullError, LogToMetricParseFloatError,
+        LogToMetricTemplateParseError, LogToMetricVrlError, ParserMissingFieldError,
     },
     schema,
     template::{Template, TemplateParseError, TemplateRenderingError},
@@ -24,7 +26,19 @@ use crate::{
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct LogToMetricConfig {
+    #[serde(default)]
     pub metrics: Vec<MetricConfig>,
+    /// Converts logs holding a metric, in the layout produced by the `metric_to_log` transform,
+    /// back into that metric, instead of deriving metrics from them as per `metrics`.
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub all_metrics: bool,
+    /// The tag the host of the log is moved back to, with `all_metrics`. This is the counterpart
+    /// of the `host_tag` option of the `metric_to_log` transform, and defaults to the same tag.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host_tag: Option<String>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -57,10 +71,31 @@ pub struct SetConfig {
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct HistogramConfig {
-    field: String,
+    field: Option<String>,
+    /// A VRL expression computing the sampled value, or an array of sampled values, from the
+    /// event, used instead of `field`.
+    value: Option<String>,
+    /// A VRL expression computing the upper limits of the histogram's buckets from the event.
+    ///
+    /// When set, the samples are counted into these buckets and emitted as an aggregated
+    /// histogram, instead of being emitted as a distribution.
+    buckets: Option<String>,
+    name: Option<String>,
+    namespace: Option<String>,
+    tags: Option<IndexMap<String, String>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct DistributionConfig {
+    field: Option<String>,
+    /// A VRL expression computing the sampled value, or an array of sampled values, from the
+    /// event, used instead of `field`.
+    value: Option<String>,
     name: Option<String>,
     namespace: Option<String>,
     tags: Option<IndexMap<String, String>>,
+    #[serde(default = "default_statistic")]
+    statistic: StatisticKind,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -76,19 +111,42 @@ pub struct SummaryConfig {
 pub enum MetricConfig {
     Counter(CounterConfig),
     Histogram(HistogramConfig),
+    Distribution(DistributionConfig),
     Gauge(GaugeConfig),
     Set(SetConfig),
     Summary(SummaryConfig),
 }
 
 impl MetricConfig {
-    fn field(&self) -> &str {
+    fn field(&self) -> Option<&str> {
+        match self {
+            MetricConfig::Counter(CounterConfig { field, .. }) => Some(field),
+            MetricConfig::Histogram(HistogramConfig { field, .. }) => field.as_deref(),
+            MetricConfig::Distribution(DistributionConfig { field, .. }) => field.as_deref(),
+            MetricConfig::Gauge(GaugeConfig { field, .. }) => Some(field),
+            MetricConfig::Set(SetConfig { field, .. }) => Some(field),
+            MetricConfig::Summary(SummaryConfig { field, .. }) => Some(field),
+        }
+    }
+
+    /// Returns the VRL sources of the `value` and `buckets` expressions of the metric.
+    fn expressions(&self) -> (Option<&str>, Option<&str>) {
+        match self {
+            MetricConfig::Histogram(HistogramConfig { value, buckets, .. }) => {
+                (value.as_deref(), buckets.as_deref())
+            }
+            MetricConfig::Distribution(DistributionConfig { value, .. }) => {
+                (value.as_deref(), None)
+            }
+            _ => (None, None),
+        }
+    }
+
+    fn name(&self) -> Option<&str> {
         match self {
-            MetricConfig::Counter(CounterConfig { field, .. }) => field,
-            MetricConfig::Histogram(HistogramConfig { field, .. }) => field,
-            MetricConfig::Gauge(GaugeConfig { field, .. }) => field,
-            MetricConfig::Set(SetConfig { field, .. }) => field,
-            MetricConfig::Summary(SummaryConfig { field, .. }) => field,
+            MetricConfig::Histogram(HistogramConfig { name, .. })
+            | MetricConfig::Distribution(DistributionConfig { name, .. }) => name.as_deref(),
+            _ => None,
         }
     }
 }
@@ -101,9 +159,21 @@ const fn default_kind() -> MetricKind {
     MetricKind::Incremental
 }
 
+const fn default_statistic() -> StatisticKind {
+    StatisticKind::Histogram
+}
+
 #[derive(Debug, Clone)]
 pub struct LogToMetric {
     config: LogToMetricConfig,
+    /// The compiled VRL expressions of each metric, in the same order as `config.metrics`.
+    programs: Vec<MetricPrograms>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct MetricPrograms {
+    value: Option<Program>,
+    buckets: Option<Program>,
 }
 
 inventory::submit! {
@@ -121,6 +191,8 @@ impl GenerateConfig for LogToMetricConfig {
                 kind: MetricKind::Incremental,
                 tags: None,
             })],
+            all_metrics: false,
+            host_tag: None,
         })
         .unwrap()
     }
@@ -130,7 +202,7 @@ impl GenerateConfig for LogToMetricConfig {
 #[typetag::serde(name = "log_to_metric")]
 impl TransformConfig for LogToMetricConfig {
     async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
-        Ok(Transform::function(LogToMetric::new(self.clone())))
+        Ok(Transform::function(LogToMetric::new(self.clone())?))
     }
 
     fn input(&self) -> Input {
@@ -151,11 +223,57 @@ impl TransformConfig for LogToMetricConfig {
 }
 
 impl LogToMetric {
-    pub const fn new(config: LogToMetricConfig) -> Self {
-        LogToMetric { config }
+    pub fn new(config: LogToMetricConfig) -> crate::Result<Self> {
+        if config.all_metrics && !config.metrics.is_empty() {
+            return Err("`metrics` can't be used together with `all_metrics`".into());
+        }
+        if !config.all_metrics && config.host_tag.is_some() {
+            return Err("`host_tag` can only be used together with `all_metrics`".into());
+        }
+
+        let programs = config
+            .metrics
+            .iter()
+            .enumerate()
+            .map(|(index, metric)| {
+                let (value, buckets) = metric.expressions();
+                if metric.field().is_some() == value.is_some() {
+                    return Err(format!(
+                        "metric {}: must provide exactly one of `field` or `value`",
+                        index
+                    )
+                    .into());
+                }
+                if value.is_some() && metric.name().is_none() {
+                    return Err(
+                        format!("metric {}: `name` is required when using `value`", index).into(),
+                    );
+                }
+
+                Ok(MetricPrograms {
+                    value: value.map(compile_vrl).transpose()?,
+                    buckets: buckets.map(compile_vrl).transpose()?,
+                })
+            })
+            .collect::<crate::Result<_>>()?;
+
+        Ok(LogToMetric { config, programs })
     }
 }
 
+fn compile_vrl(source: &str) -> crate::Result<Program> {
+    let functions = vrl_stdlib::all();
+    let (program, warnings) = vrl::compile(source, &functions)
+        .map_err(|diagnostics| Formatter::new(source, diagnostics).colored().to_string())?;
+
+    if !warnings.is_empty() {
+        let warnings = Formatter::new(source, warnings).colored().to_string();
+        warn!(message = "VRL compilation warning.", %warnings);
+    }
+
+    Ok(program)
+}
+
 enum TransformError {
     FieldNotFound {
         field: String,
@@ -169,6 +287,10 @@ enum TransformError {
         field: String,
         error: ParseFloatError,
     },
+    VrlError {
+        expression: &'static str,
+        error: String,
+    },
 }
 
 fn render_template(s: &str, event: &Event) -> Result<String, TransformError> {
@@ -210,18 +332,36 @@ fn render_tags(
     })
 }
 
-fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformError> {
-    let log = event.as_log();
-
-    let timestamp = log
-        .get(log_schema().timestamp_key())
-        .and_then(Value::as_timestamp)
-        .cloned();
-    let metadata = event.metadata().clone();
-
-    let field = config.field();
+/// Runs a VRL expression against the event, returning the number or array of numbers it
+/// evaluates to.
+fn run_vrl(
+    expression: &'static str,
+    program: &Program,
+    event: &Event,
+) -> Result<Vec<f64>, TransformError> {
+    let vrl_error = |error: String| TransformError::VrlError { expression, error };
+
+    let mut target = VrlTarget::new(event.clone(), program.info());
+    let value = Runtime::default()
+        .resolve(&mut target, program, &TimeZone::default())
+        .map_err(|error| vrl_error(error.to_string()))?;
+
+    let to_number = |value: Value| match value {
+        Value::Integer(value) => Ok(value as f64),
+        Value::Float(value) => Ok(value.into_inner()),
+        value => Err(vrl_error(format!(
+            "expected a number, got {}",
+            value.kind()
+        ))),
+    };
+    match value {
+        Value::Array(values) => values.into_iter().map(to_number).collect(),
+        value => to_number(value).map(|value| vec![value]),
+    }
+}
 
-    let value = match log.get(field) {
+fn field_value<'a>(event: &'a Event, field: &str) -> Result<&'a Value, TransformError> {
+    match event.as_log().get(field) {
         None => Err(TransformError::FieldNotFound {
             field: field.to_string(),
         }),
@@ -229,10 +369,52 @@ fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformEr
             field: field.to_string(),
         }),
         Some(value) => Ok(value),
-    }?;
+    }
+}
+
+/// Returns the samples of a histogram or distribution, read from `field` or computed by the
+/// `value` expression.
+fn samples(
+    field: Option<&str>,
+    programs: &MetricPrograms,
+    event: &Event,
+) -> Result<Vec<Sample>, TransformError> {
+    let values = match (field, &programs.value) {
+        (_, Some(program)) => run_vrl("value", program, event)?,
+        (Some(field), None) => {
+            let value = field_value(event, field)?;
+            vec![value.to_string_lossy().parse().map_err(|error| {
+                TransformError::ParseFloatError {
+                    field: field.to_string(),
+                    error,
+                }
+            })?]
+        }
+        (None, None) => unreachable!("metrics have a field or a value"),
+    };
+
+    Ok(values
+        .into_iter()
+        .map(|value| Sample { value, rate: 1 })
+        .collect())
+}
+
+fn to_metric(
+    config: &MetricConfig,
+    programs: &MetricPrograms,
+    event: &Event,
+) -> Result<Metric, TransformError> {
+    let log = event.as_log();
+
+    let timestamp = log
+        .get(log_schema().timestamp_key())
+        .and_then(Value::as_timestamp)
+        .cloned();
+    let metadata = event.metadata().clone();
 
     match config {
         MetricConfig::Counter(counter) => {
+            let value = field_value(event, &counter.field)?;
             let value = if counter.increment_by_value {
                 value.to_string_lossy().parse().map_err(|error| {
                     TransformError::ParseFloatError {
@@ -265,14 +447,32 @@ fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformEr
             .with_timestamp(timestamp))
         }
         MetricConfig::Histogram(hist) => {
-            let value = value.to_string_lossy().parse().map_err(|error| {
-                TransformError::ParseFloatError {
-                    field: field.to_string(),
-                    error,
+            let samples = samples(hist.field.as_deref(), programs, event)?;
+
+            let value = match &programs.buckets {
+                Some(program) => {
+                    let mut buckets = run_vrl("buckets", program, event)?;
+                    buckets.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+                    buckets.dedup();
+
+                    let (buckets, count, sum) = samples_to_buckets(&samples, &buckets);
+                    MetricValue::AggregatedHistogram {
+                        buckets,
+                        count,
+                        sum,
+                    }
                 }
-            })?;
+                None => MetricValue::Distribution {
+                    samples,
+                    statistic: StatisticKind::Histogram,
+                },
+            };
 
-            let name = hist.name.as_ref().unwrap_or(&hist.field);
+            let name = hist
+                .name
+                .as_ref()
+                .or(hist.field.as_ref())
+                .expect("validated");
             let name = render_template(name, event)?;
 
             let namespace = hist.namespace.as_ref();
@@ -282,12 +482,36 @@ fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformEr
 
             let tags = render_tags(&hist.tags, event)?;
 
+            Ok(
+                Metric::new_with_metadata(name, MetricKind::Incremental, value, metadata)
+                    .with_namespace(namespace)
+                    .with_tags(tags)
+                    .with_timestamp(timestamp),
+            )
+        }
+        MetricConfig::Distribution(dist) => {
+            let samples = samples(dist.field.as_deref(), programs, event)?;
+
+            let name = dist
+                .name
+                .as_ref()
+                .or(dist.field.as_ref())
+                .expect("validated");
+            let name = render_template(name, event)?;
+
+            let namespace = dist.namespace.as_ref();
+            let namespace = namespace
+                .map(|namespace| render_template(namespace, event))
+                .transpose()?;
+
+            let tags = render_tags(&dist.tags, event)?;
+
             Ok(Metric::new_with_metadata(
                 name,
                 MetricKind::Incremental,
                 MetricValue::Distribution {
-                    samples: vector_core::samples![value => 1],
-                    statistic: StatisticKind::Histogram,
+                    samples,
+                    statistic: dist.statistic,
                 },
                 metadata,
             )
@@ -296,9 +520,10 @@ fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformEr
             .with_timestamp(timestamp))
         }
         MetricConfig::Summary(summary) => {
+            let value = field_value(event, &summary.field)?;
             let value = value.to_string_lossy().parse().map_err(|error| {
                 TransformError::ParseFloatError {
-                    field: field.to_string(),
+                    field: summary.field.clone(),
                     error,
                 }
             })?;
@@ -327,9 +552,10 @@ fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformEr
             .with_timestamp(timestamp))
         }
         MetricConfig::Gauge(gauge) => {
+            let value = field_value(event, &gauge.field)?;
             let value = value.to_string_lossy().parse().map_err(|error| {
                 TransformError::ParseFloatError {
-                    field: field.to_string(),
+                    field: gauge.field.clone(),
                     error,
                 }
             })?;
@@ -355,6 +581,7 @@ fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformEr
             .with_timestamp(timestamp))
         }
         MetricConfig::Set(set) => {
+            let value = field_value(event, &set.field)?;
             let value = value.to_string_lossy();
 
             let name = set.name.as_ref().unwrap_or(&set.field);
@@ -382,10 +609,36 @@ fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformEr
     }
 }
 
+/// Decodes the metric held by a log in the layout produced by the `metric_to_log` transform,
+/// with its timestamp and host moved back to where the metric holds them, the host being moved
+/// to `host_tag`.
+fn decode_metric(mut log: LogEvent, host_tag: Option<&str>) -> Result<Metric, serde_json::Error> {
+    if let Some(timestamp) = log.remove(log_schema().timestamp_key()) {
+        log.insert("timestamp", timestamp);
+    }
+    if let Some(host) = log.remove(log_schema().host_key()) {
+        let host_tag = host_tag.unwrap_or_else(|| log_schema().host_key());
+        log.insert(format!("tags.{}", host_tag).as_str(), host);
+    }
+
+    let (value, metadata) = log.into_parts();
+    let metric = serde_json::to_value(value).and_then(serde_json::from_value::<Metric>)?;
+    let (series, data, _) = metric.into_parts();
+    Ok(Metric::from_parts(series, data, metadata))
+}
+
 impl FunctionTransform for LogToMetric {
     fn transform(&mut self, output: &mut OutputBuffer, event: Event) {
-        for config in self.config.metrics.iter() {
-            match to_metric(config, &event) {
+        if self.config.all_metrics {
+            match decode_metric(event.into_log(), self.config.host_tag.as_deref()) {
+                Ok(metric) => output.push(Event::Metric(metric)),
+                Err(error) => emit!(LogToMetricDecodeError { error }),
+            }
+            return;
+        }
+
+        for (config, programs) in self.config.metrics.iter().zip(&self.programs) {
+            match to_metric(config, programs, &event) {
                 Ok(metric) => {
                     output.push(Event::Metric(metric));
                 }
@@ -411,6 +664,9 @@ impl FunctionTransform for LogToMetric {
                 Err(TransformError::TemplateParseError(error)) => {
                     emit!(LogToMetricTemplateParseError { error })
                 }
+                Err(TransformError::VrlError { expression, error }) => {
+                    emit!(LogToMetricVrlError { expression, error })
+                }
             }
         }
     }
@@ -462,7 +718,7 @@ mod tests {
 
         let event = create_event("status", "42");
         let metadata = event.metadata().clone();
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config).unwrap();
         let metric = transform_one(&mut transform, event).unwrap();
 
         assert_eq!(
@@ -495,7 +751,7 @@ mod tests {
         event.as_mut_log().insert("code", "200");
         let metadata = event.metadata().clone();
 
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config).unwrap();
         let metric = transform_one(&mut transform, event).unwrap();
 
         assert_eq!(
@@ -533,7 +789,7 @@ mod tests {
 
         let event = create_event("backtrace", "message");
         let metadata = event.metadata().clone();
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config).unwrap();
         let metric = transform_one(&mut transform, event).unwrap();
 
         assert_eq!(
@@ -560,7 +816,7 @@ mod tests {
         );
 
         let event = create_event("success", "42");
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config).unwrap();
 
         assert_eq!(transform_one(&mut transform, event), None);
     }
@@ -579,7 +835,7 @@ mod tests {
 
         let event = create_event("amount", "33.99");
         let metadata = event.metadata().clone();
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config).unwrap();
         let metric = transform_one(&mut transform, event).unwrap();
 
         assert_eq!(
@@ -609,7 +865,7 @@ mod tests {
 
         let event = create_event("amount", "33.99");
         let metadata = event.metadata().clone();
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config).unwrap();
         let metric = transform_one(&mut transform, event).unwrap();
 
         assert_eq!(
@@ -637,7 +893,7 @@ mod tests {
 
         let event = create_event("memory_rss", "123");
         let metadata = event.metadata().clone();
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config).unwrap();
         let metric = transform_one(&mut transform, event).unwrap();
 
         assert_eq!(
@@ -665,7 +921,7 @@ mod tests {
         );
 
         let event = create_event("status", "not a number");
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config).unwrap();
 
         assert_eq!(transform_one(&mut transform, event), None);
     }
@@ -682,7 +938,7 @@ mod tests {
         );
 
         let event = create_event("not foo", "not a number");
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config).unwrap();
 
         assert_eq!(transform_one(&mut transform, event), None);
     }
@@ -699,7 +955,7 @@ mod tests {
         );
 
         let event = create_event("status", Value::Null);
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config).unwrap();
 
         assert_eq!(transform_one(&mut transform, event), None);
     }
@@ -727,7 +983,7 @@ mod tests {
         event.as_mut_log().insert("backtrace", "message");
         let metadata = event.metadata().clone();
 
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config).unwrap();
 
         let mut output = OutputBuffer::default();
         transform.transform(&mut output, event);
@@ -783,7 +1039,7 @@ mod tests {
         event.as_mut_log().insert("service", "xyz");
         let metadata = event.metadata().clone();
 
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config).unwrap();
 
         let mut output = OutputBuffer::default();
         transform.transform(&mut output, event);
@@ -827,7 +1083,7 @@ mod tests {
 
         let event = create_event("user_ip", "1.2.3.4");
         let metadata = event.metadata().clone();
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config).unwrap();
         let metric = transform_one(&mut transform, event).unwrap();
 
         assert_eq!(
@@ -856,7 +1112,7 @@ mod tests {
 
         let event = create_event("response_time", "2.5");
         let metadata = event.metadata().clone();
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config).unwrap();
         let metric = transform_one(&mut transform, event).unwrap();
 
         assert_eq!(
@@ -874,6 +1130,231 @@ mod tests {
         );
     }
 
+    #[test]
+    fn response_time_histogram_with_vrl_buckets() {
+        let config = parse_config(
+            r#"
+            [[metrics]]
+            type = "histogram"
+            name = "response_time_seconds"
+            value = "to_float!(.response_time)"
+            buckets = 'if .service == "api" { [1.0, 0.1, 0.5] } else { [1, 5, 10] }'
+            "#,
+        );
+
+        let mut event = create_event("response_time", "0.3");
+        event.as_mut_log().insert("service", "api");
+        let metadata = event.metadata().clone();
+        let mut transform = LogToMetric::new(config).unwrap();
+        let metric = transform_one(&mut transform, event).unwrap();
+
+        assert_eq!(
+            metric.into_metric(),
+            Metric::new_with_metadata(
+                "response_time_seconds",
+                MetricKind::Incremental,
+                MetricValue::AggregatedHistogram {
+                    buckets: vector_core::buckets![0.1 => 0, 0.5 => 1, 1.0 => 0],
+                    count: 1,
+                    sum: 0.3,
+                },
+                metadata
+            )
+            .with_timestamp(Some(ts()))
+        );
+    }
+
+    #[test]
+    fn distribution_with_vrl_values() {
+        let config = parse_config(
+            r#"
+            [[metrics]]
+            type = "distribution"
+            name = "response_time"
+            value = "[to_float!(.response_time), 1]"
+            statistic = "summary"
+            "#,
+        );
+
+        let event = create_event("response_time", "2.5");
+        let metadata = event.metadata().clone();
+        let mut transform = LogToMetric::new(config).unwrap();
+        let metric = transform_one(&mut transform, event).unwrap();
+
+        assert_eq!(
+            metric.into_metric(),
+            Metric::new_with_metadata(
+                "response_time",
+                MetricKind::Incremental,
+                MetricValue::Distribution {
+                    samples: vector_core::samples![2.5 => 1, 1.0 => 1],
+                    statistic: StatisticKind::Summary
+                },
+                metadata
+            )
+            .with_timestamp(Some(ts()))
+        );
+    }
+
+    #[test]
+    fn vrl_value_errors() {
+        let config = parse_config(
+            r#"
+            [[metrics]]
+            type = "distribution"
+            name = "response_time"
+            value = ".response_time"
+            "#,
+        );
+
+        let event = create_event("response_time", "not a number");
+        let mut transform = LogToMetric::new(config).unwrap();
+
+        assert_eq!(transform_one(&mut transform, event), None);
+    }
+
+    #[test]
+    fn requires_field_or_value() {
+        let missing_both = parse_config(
+            r#"
+            [[metrics]]
+            type = "histogram"
+            name = "response_time"
+            "#,
+        );
+        assert!(LogToMetric::new(missing_both).is_err());
+
+        let missing_name = parse_config(
+            r#"
+            [[metrics]]
+            type = "histogram"
+            value = "1"
+            "#,
+        );
+        assert!(LogToMetric::new(missing_name).is_err());
+    }
+
+    #[cfg(feature = "transforms-metric_to_log")]
+    #[test]
+    fn all_metrics_round_trip() {
+        use crate::transforms::metric_to_log::MetricToLog;
+
+        let mut transform = LogToMetric::new(parse_config("all_metrics = true")).unwrap();
+        let metrics = vec![
+            Metric::new(
+                "requests",
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 3.0 },
+            ),
+            Metric::new(
+                "response_time",
+                MetricKind::Absolute,
+                MetricValue::AggregatedHistogram {
+                    buckets: vector_core::buckets![0.1 => 2, 0.5 => 3, 1.0 => 0],
+                    count: 5,
+                    sum: 1.5,
+                },
+            ),
+            Metric::new(
+                "sizes",
+                MetricKind::Incremental,
+                MetricValue::Distribution {
+                    samples: vector_core::samples![10.0 => 1, 20.0 => 2],
+                    statistic: StatisticKind::Summary,
+                },
+            ),
+        ];
+
+        for metric in metrics {
+            let metric = metric
+                .with_namespace(Some("service"))
+                .with_tags(Some(
+                    vec![
+                        ("host".to_owned(), "localhost".to_owned()),
+                        ("code".to_owned(), "200".to_owned()),
+                    ]
+                    .into_iter()
+                    .collect(),
+                ))
+                .with_timestamp(Some(ts()));
+            let log = MetricToLog::new(None, Default::default())
+                .transform_one(metric.clone())
+                .unwrap();
+
+            let decoded = transform_one(&mut transform, log.into()).unwrap();
+            assert_eq!(decoded.into_metric(), metric);
+        }
+    }
+
+    #[cfg(feature = "transforms-metric_to_log")]
+    #[test]
+    fn all_metrics_round_trip_with_host_tag() {
+        use crate::transforms::metric_to_log::MetricToLog;
+
+        let mut transform = LogToMetric::new(parse_config(
+            r#"
+            all_metrics = true
+            host_tag = "hostname"
+            "#,
+        ))
+        .unwrap();
+        let metric = Metric::new(
+            "requests",
+            MetricKind::Incremental,
+            MetricValue::Counter { value: 3.0 },
+        )
+        .with_tags(Some(
+            vec![("hostname".to_owned(), "localhost".to_owned())]
+                .into_iter()
+                .collect(),
+        ))
+        .with_timestamp(Some(ts()));
+
+        let log = MetricToLog::new(Some("hostname".to_owned()), Default::default())
+            .transform_one(metric.clone())
+            .unwrap();
+        assert_eq!(log[log_schema().host_key()], "localhost".into());
+
+        let decoded = transform_one(&mut transform, log.into()).unwrap();
+        assert_eq!(decoded.into_metric(), metric);
+    }
+
+    #[test]
+    fn host_tag_requires_all_metrics() {
+        let config = parse_config(
+            r#"
+            host_tag = "hostname"
+
+            [[metrics]]
+            type = "counter"
+            field = "status"
+            "#,
+        );
+        assert!(LogToMetric::new(config).is_err());
+    }
+
+    #[test]
+    fn all_metrics_rejects_other_logs() {
+        let mut transform = LogToMetric::new(parse_config("all_metrics = true")).unwrap();
+        let event = create_event("status", "42");
+
+        assert_eq!(transform_one(&mut transform, event), None);
+    }
+
+    #[test]
+    fn all_metrics_excludes_metrics() {
+        let config = parse_config(
+            r#"
+            all_metrics = true
+
+            [[metrics]]
+            type = "counter"
+            field = "status"
+            "#,
+        );
+        assert!(LogToMetric::new(config).is_err());
+    }
+
     #[test]
     fn response_time_summary() {
         let config = parse_config(
@@ -886,7 +1367,7 @@ mod tests {
 
         let event = create_event("response_time", "2.5");
         let metadata = event.metadata().clone();
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config).unwrap();
         let metric = transform_one(&mut transform, event).unwrap();
 
         assert_eq!(
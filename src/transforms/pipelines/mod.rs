@@ -302,6 +302,7 @@ mod tests {
         let config: PipelinesConfig = config.try_into().unwrap();
         let outer = TransformOuter {
             inputs: vec!["source".to_string()],
+            sandbox: Default::default(),
             inner: Box::new(config),
         };
         let name = ComponentKey::from("foo");
@@ -9,6 +9,7 @@ use async_stream::stream;
 use futures::{stream, Stream, StreamExt};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
+use vector_core::time::Clock;
 
 use crate::{
     conditions::{AnyCondition, Condition},
@@ -57,7 +58,8 @@ impl_generate_config_from_default!(ReduceConfig);
 #[typetag::serde(name = "reduce")]
 impl TransformConfig for ReduceConfig {
     async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
-        Reduce::new(self, &context.enrichment_tables).map(Transform::event_task)
+        Reduce::new(self, &context.enrichment_tables, context.clock.clone())
+            .map(Transform::event_task)
     }
 
     fn input(&self) -> Input {
@@ -81,7 +83,7 @@ struct ReduceState {
 }
 
 impl ReduceState {
-    fn new(e: LogEvent, strategies: &IndexMap<String, MergeStrategy>) -> Self {
+    fn new(e: LogEvent, strategies: &IndexMap<String, MergeStrategy>, now: Instant) -> Self {
         let (value, metadata) = e.into_parts();
 
         let fields = if let Value::Object(fields) = value {
@@ -106,13 +108,18 @@ impl ReduceState {
         };
 
         Self {
-            stale_since: Instant::now(),
+            stale_since: now,
             fields,
             metadata,
         }
     }
 
-    fn add_event(&mut self, e: LogEvent, strategies: &IndexMap<String, MergeStrategy>) {
+    fn add_event(
+        &mut self,
+        e: LogEvent,
+        strategies: &IndexMap<String, MergeStrategy>,
+        now: Instant,
+    ) {
         let (value, metadata) = e.into_parts();
         self.metadata.merge(metadata);
 
@@ -146,7 +153,7 @@ impl ReduceState {
                 }
             }
         }
-        self.stale_since = Instant::now();
+        self.stale_since = now;
     }
 
     fn flush(mut self) -> LogEvent {
@@ -170,12 +177,14 @@ pub struct Reduce {
     reduce_merge_states: HashMap<Discriminant, ReduceState>,
     ends_when: Option<Condition>,
     starts_when: Option<Condition>,
+    clock: Clock,
 }
 
 impl Reduce {
     pub fn new(
         config: &ReduceConfig,
         enrichment_tables: &enrichment::TableRegistry,
+        clock: Clock,
     ) -> crate::Result<Self> {
         if config.ends_when.is_some() && config.starts_when.is_some() {
             return Err("only one of `ends_when` and `starts_when` can be provided".into());
@@ -201,13 +210,14 @@ impl Reduce {
             reduce_merge_states: HashMap::new(),
             ends_when,
             starts_when,
+            clock,
         })
     }
 
     fn flush_into(&mut self, output: &mut Vec<Event>) {
         let mut flush_discriminants = Vec::new();
         for (k, t) in &self.reduce_merge_states {
-            if t.stale_since.elapsed() >= self.expire_after {
+            if self.clock.elapsed(t.stale_since) >= self.expire_after {
                 flush_discriminants.push(k.clone());
             }
         }
@@ -226,12 +236,15 @@ impl Reduce {
     }
 
     fn push_or_new_reduce_state(&mut self, event: LogEvent, discriminant: Discriminant) {
+        let now = self.clock.now();
         match self.reduce_merge_states.entry(discriminant) {
             hash_map::Entry::Vacant(entry) => {
-                entry.insert(ReduceState::new(event, &self.merge_strategies));
+                entry.insert(ReduceState::new(event, &self.merge_strategies, now));
             }
             hash_map::Entry::Occupied(mut entry) => {
-                entry.get_mut().add_event(event, &self.merge_strategies);
+                entry
+                    .get_mut()
+                    .add_event(event, &self.merge_strategies, now);
             }
         }
     }
@@ -260,10 +273,10 @@ impl Reduce {
         } else if ends_here {
             output.push(match self.reduce_merge_states.remove(&discriminant) {
                 Some(mut state) => {
-                    state.add_event(event, &self.merge_strategies);
+                    state.add_event(event, &self.merge_strategies, self.clock.now());
                     state.flush().into()
                 }
-                None => ReduceState::new(event, &self.merge_strategies)
+                None => ReduceState::new(event, &self.merge_strategies, self.clock.now())
                     .flush()
                     .into(),
             })
@@ -324,6 +337,8 @@ impl TaskTransform<Event> for Reduce {
 mod test {
     use serde_json::json;
 
+    use vector_core::time::MockClock;
+
     use super::*;
     use crate::{
         config::TransformConfig,
@@ -335,6 +350,34 @@ mod test {
         crate::test_util::test_generate_config::<ReduceConfig>();
     }
 
+    #[test]
+    fn reduce_expires_stale_state_with_mock_clock() {
+        let config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+expire_after_ms = 5000
+"#,
+        )
+        .unwrap();
+        let mock = MockClock::new();
+        let mut reduce =
+            Reduce::new(&config, &Default::default(), Clock::Mock(mock.clone())).unwrap();
+
+        let mut output = Vec::new();
+        let mut e_1 = LogEvent::from("test message 1");
+        e_1.insert("request_id", "1");
+        reduce.transform_one(&mut output, e_1.into());
+
+        mock.advance(Duration::from_secs(4));
+        reduce.flush_into(&mut output);
+        assert!(output.is_empty());
+
+        mock.advance(Duration::from_secs(1));
+        reduce.flush_into(&mut output);
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].as_log()["message"], "test message 1".into());
+    }
+
     #[tokio::test]
     async fn reduce_from_condition() {
         let reduce = toml::from_str::<ReduceConfig>(
@@ -1,15 +1,17 @@
 use std::sync::Arc;
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     fs::File,
     io::{self, Read},
     path::PathBuf,
 };
 
+use lookup::LookupBuf;
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 use value::Kind;
 use vector_common::TimeZone;
+use vector_core::{internal_event::EventsSent, ByteSizeOf};
 use vector_vrl_functions::set_semantic_meaning::MeaningList;
 use vrl::{
     diagnostic::{Formatter, Note},
@@ -19,11 +21,12 @@ use vrl::{
 
 use crate::{
     config::{
-        log_schema, ComponentKey, DataType, Input, Output, TransformConfig, TransformContext,
-        TransformDescription,
+        log_schema, Capability, ComponentKey, DataType, Input, Output, TransformConfig,
+        TransformContext, TransformDescription,
     },
-    event::{Event, TargetEvents, VrlTarget},
-    internal_events::{RemapMappingAbort, RemapMappingError},
+    dropped_events,
+    event::{Event, EventArray, EventContainer, TargetEvents, VrlTarget},
+    internal_events::{EventsReceived, RemapMappingAbort, RemapMappingError},
     schema,
     transforms::{SyncTransform, Transform, TransformOutputsBuf},
     Result,
@@ -99,6 +102,21 @@ impl RemapConfig {
     }
 }
 
+impl RemapConfig {
+    fn build_remap(&self, context: &TransformContext) -> Result<(Box<dyn SyncTransform>, String)> {
+        Ok(match self.runtime {
+            VrlRuntime::Ast => {
+                let (remap, warnings) = Remap::new_ast(self.clone(), context)?;
+                (Box::new(remap) as Box<dyn SyncTransform>, warnings)
+            }
+            VrlRuntime::Vm => {
+                let (remap, warnings) = Remap::new_vm(self.clone(), context)?;
+                (Box::new(remap) as Box<dyn SyncTransform>, warnings)
+            }
+        })
+    }
+}
+
 inventory::submit! {
     TransformDescription::new::<RemapConfig>("remap")
 }
@@ -109,16 +127,7 @@ impl_generate_config_from_default!(RemapConfig);
 #[typetag::serde(name = "remap")]
 impl TransformConfig for RemapConfig {
     async fn build(&self, context: &TransformContext) -> Result<Transform> {
-        let (transform, warnings) = match self.runtime {
-            VrlRuntime::Ast => {
-                let (remap, warnings) = Remap::new_ast(self.clone(), context)?;
-                (Transform::synchronous(remap), warnings)
-            }
-            VrlRuntime::Vm => {
-                let (remap, warnings) = Remap::new_vm(self.clone(), context)?;
-                (Transform::synchronous(remap), warnings)
-            }
-        };
+        let (transform, warnings) = self.build_remap(context)?;
 
         // TODO: We could improve on this by adding support for non-fatal error
         // messages in the topology. This would make the topology responsible
@@ -128,7 +137,7 @@ impl TransformConfig for RemapConfig {
             warn!(message = "VRL compilation warning.", %warnings);
         }
 
-        Ok(transform)
+        Ok(Transform::Synchronous(transform))
     }
 
     fn input(&self) -> Input {
@@ -200,11 +209,266 @@ impl TransformConfig for RemapConfig {
         "remap"
     }
 
+    fn capabilities(&self) -> Vec<Capability> {
+        self.file.iter().map(Capability::path).collect()
+    }
+
+    fn supports_sandbox(&self) -> bool {
+        true
+    }
+
+    fn queried_fields(&self) -> Option<BTreeSet<LookupBuf>> {
+        // Programs reading the whole event can't be narrowed down to specific fields, and fields
+        // the program assigns itself don't have to be produced by its inputs.
+        self.compile_vrl_program(
+            enrichment::TableRegistry::default(),
+            schema::Definition::empty(),
+        )
+        .ok()
+        .map(|(program, _, _, _)| program.field_usage())
+        .filter(|usage| !usage.reads_root())
+        .map(|usage| usage.unassigned_reads())
+    }
+
     fn enable_concurrency(&self) -> bool {
         true
     }
 }
 
+/// A chain of `remap` transforms, each being the only consumer of the previous one, fused into a
+/// single component so events don't cross a channel between each of them.
+///
+/// Fused transforms are created when the configuration is compiled with `fuse_transforms` enabled,
+/// and take the key of the last transform in the chain.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct FusedRemapConfig {
+    sections: Vec<FusedRemapSection>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+struct FusedRemapSection {
+    component: ComponentKey,
+    remap: RemapConfig,
+}
+
+impl FusedRemapConfig {
+    /// Fuses the `downstream` transform, whose only input is `upstream`, into `upstream`.
+    ///
+    /// Returns `None` if either of them isn't a `remap` transform, or if `upstream` has more than
+    /// one output.
+    pub fn fuse(
+        upstream_key: &ComponentKey,
+        upstream: &dyn TransformConfig,
+        downstream_key: &ComponentKey,
+        downstream: &dyn TransformConfig,
+    ) -> Option<Self> {
+        let mut sections = Self::sections(upstream_key, upstream)?;
+        if sections.last()?.remap.reroute_dropped {
+            return None;
+        }
+        sections.extend(Self::sections(downstream_key, downstream)?);
+
+        Some(Self { sections })
+    }
+
+    /// Returns the `remap` transforms `transform` is made of, if it's a `remap` transform or the
+    /// result of fusing some.
+    fn sections(
+        key: &ComponentKey,
+        transform: &dyn TransformConfig,
+    ) -> Option<Vec<FusedRemapSection>> {
+        // Transform configs can't be downcast, but their serialized form tells us their type.
+        let mut value = serde_json::to_value(transform).ok()?;
+        let transform_type = value.as_object_mut()?.remove("type")?;
+
+        match transform_type.as_str()? {
+            "remap" => serde_json::from_value(value).ok().map(|remap| {
+                vec![FusedRemapSection {
+                    component: key.clone(),
+                    remap,
+                }]
+            }),
+            "remap_fused" => serde_json::from_value::<Self>(value)
+                .ok()
+                .map(|fused| fused.sections),
+            _ => None,
+        }
+    }
+
+    /// Returns each section along with the schema definition of the events it receives.
+    fn with_merged_definitions(
+        &self,
+        merged_definition: &schema::Definition,
+    ) -> Vec<(&FusedRemapSection, schema::Definition)> {
+        let mut merged_definition = merged_definition.clone();
+        self.sections
+            .iter()
+            .map(|section| {
+                let next_definition = section
+                    .remap
+                    .outputs(&merged_definition)
+                    .into_iter()
+                    .find(|output| output.port.is_none())
+                    .and_then(|output| output.log_schema_definition)
+                    .unwrap_or_else(|| merged_definition.clone());
+
+                (
+                    section,
+                    std::mem::replace(&mut merged_definition, next_definition),
+                )
+            })
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "remap_fused")]
+impl TransformConfig for FusedRemapConfig {
+    async fn build(&self, context: &TransformContext) -> Result<Transform> {
+        let mut sections = Vec::with_capacity(self.sections.len());
+        for (section, merged_definition) in
+            self.with_merged_definitions(&context.merged_schema_definition)
+        {
+            let schema_definitions = section
+                .remap
+                .outputs(&merged_definition)
+                .into_iter()
+                .map(|output| {
+                    let definition = output
+                        .log_schema_definition
+                        .unwrap_or_else(|| merged_definition.clone());
+                    (output.port, definition)
+                })
+                .collect();
+            let section_context = TransformContext {
+                key: Some(section.component.clone()),
+                globals: context.globals.clone(),
+                enrichment_tables: context.enrichment_tables.clone(),
+                schema_definitions,
+                merged_schema_definition: merged_definition,
+                clock: context.clock.clone(),
+            };
+
+            let (transform, warnings) = section.remap.build_remap(&section_context)?;
+            if !warnings.is_empty() {
+                warn!(
+                    message = "VRL compilation warning.",
+                    component_id = %section.component,
+                    %warnings
+                );
+            }
+            sections.push((section.component.clone(), transform));
+        }
+
+        Ok(Transform::synchronous(FusedRemap::new(sections)))
+    }
+
+    fn input(&self) -> Input {
+        Input::all()
+    }
+
+    fn outputs(&self, merged_definition: &schema::Definition) -> Vec<Output> {
+        self.with_merged_definitions(merged_definition)
+            .pop()
+            .map(|(section, merged_definition)| section.remap.outputs(&merged_definition))
+            .unwrap_or_default()
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "remap"
+    }
+
+    fn queried_fields(&self) -> Option<BTreeSet<LookupBuf>> {
+        self.sections
+            .first()
+            .and_then(|section| section.remap.queried_fields())
+    }
+
+    fn enable_concurrency(&self) -> bool {
+        true
+    }
+}
+
+/// Runs the programs of a chain of fused `remap` transforms one after the other.
+///
+/// Events received and sent by every transform but the last are reported under that transform's
+/// own component span, since the topology only reports them for the fused component as a whole.
+#[derive(Clone)]
+struct FusedRemap {
+    stages: Vec<FusedRemapStage>,
+    last: Box<dyn SyncTransform>,
+}
+
+#[derive(Clone)]
+struct FusedRemapStage {
+    span: tracing::Span,
+    transform: Box<dyn SyncTransform>,
+    output: TransformOutputsBuf,
+}
+
+impl FusedRemap {
+    fn new(mut sections: Vec<(ComponentKey, Box<dyn SyncTransform>)>) -> Self {
+        let (_, last) = sections.pop().expect("fused transforms have sections");
+        let stages = sections
+            .into_iter()
+            .map(|(key, transform)| FusedRemapStage {
+                span: error_span!(
+                    parent: None,
+                    "transform",
+                    component_kind = "transform",
+                    component_id = %key,
+                    component_type = "remap",
+                    component_name = %key,
+                ),
+                transform,
+                output: TransformOutputsBuf::new_with_capacity(
+                    vec![Output::default(DataType::all())],
+                    1,
+                ),
+            })
+            .collect();
+
+        Self { stages, last }
+    }
+
+    fn run(&mut self, mut events: Vec<Event>, output: &mut TransformOutputsBuf) {
+        for stage in &mut self.stages {
+            let _enter = stage.span.enter();
+            emit!(EventsReceived {
+                count: events.len(),
+                byte_size: events.size_of(),
+            });
+
+            for event in events.drain(..) {
+                stage.transform.transform(event, &mut stage.output);
+            }
+            events.extend(stage.output.drain());
+
+            emit!(EventsSent {
+                count: events.len(),
+                byte_size: events.size_of(),
+                output: None,
+            });
+        }
+
+        for event in events {
+            self.last.transform(event, output);
+        }
+    }
+}
+
+impl SyncTransform for FusedRemap {
+    fn transform(&mut self, event: Event, output: &mut TransformOutputsBuf) {
+        self.run(vec![event], output);
+    }
+
+    fn transform_all(&mut self, events: EventArray, output: &mut TransformOutputsBuf) {
+        self.run(events.into_events().collect(), output);
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Remap<Runner>
 where
@@ -481,6 +745,17 @@ where
 
                     self.annotate_dropped(&mut event, reason, error);
                     push_dropped(event, output, &self.dropped_schema_definition);
+                } else {
+                    // The original event isn't always kept when it's dropped, in which case it's
+                    // sampled as the program left it.
+                    let event = original_event.or_else(|| match target.into_events() {
+                        TargetEvents::One(event) => Some(event),
+                        TargetEvents::Logs(mut events) => events.next(),
+                        TargetEvents::Traces(mut events) => events.next(),
+                    });
+                    if let Some(event) = event {
+                        dropped_events::sample(&event, format_args!("{}: {}", reason, error));
+                    }
                 }
             }
         }
@@ -1292,6 +1567,60 @@ mod tests {
         COMPONENT_MULTIPLE_OUTPUTS_TESTS.assert(&["output"]);
     }
 
+    #[tokio::test]
+    async fn fused_remaps_run_in_order() {
+        let first = RemapConfig {
+            source: Some(".a = 1".to_owned()),
+            ..Default::default()
+        };
+        let second = RemapConfig {
+            source: Some(".b = .a".to_owned()),
+            reroute_dropped: true,
+            ..Default::default()
+        };
+        let fused = FusedRemapConfig::fuse(
+            &ComponentKey::from("first"),
+            &first,
+            &ComponentKey::from("second"),
+            &second,
+        )
+        .unwrap();
+        assert_eq!(fused.outputs(&schema::Definition::empty()).len(), 2);
+
+        let mut tform = match fused.build(&TransformContext::default()).await.unwrap() {
+            Transform::Synchronous(tform) => tform,
+            _ => panic!("expected a synchronous transform"),
+        };
+
+        let event = Event::from(LogEvent::from("fuse me"));
+        let log = transform_one_fallible(tform.as_mut(), event)
+            .unwrap()
+            .into_log();
+        assert_eq!(log["a"], 1.into());
+        assert_eq!(log["b"], 1.into());
+    }
+
+    #[test]
+    fn fuse_requires_single_output() {
+        let first = RemapConfig {
+            source: Some(".a = 1".to_owned()),
+            reroute_dropped: true,
+            ..Default::default()
+        };
+        let second = RemapConfig {
+            source: Some(".b = 2".to_owned()),
+            ..Default::default()
+        };
+
+        assert!(FusedRemapConfig::fuse(
+            &ComponentKey::from("first"),
+            &first,
+            &ComponentKey::from("second"),
+            &second,
+        )
+        .is_none());
+    }
+
     struct CollectedOuput {
         primary: OutputBuffer,
         named: HashMap<String, OutputBuffer>,
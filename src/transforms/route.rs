@@ -1,3 +1,6 @@
+use std::collections::HashSet;
+
+use enrichment::{Case, IndexHandle, TableSearch};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use vector_core::transform::SyncTransform;
@@ -8,8 +11,10 @@ use crate::{
         DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext,
         TransformDescription,
     },
-    event::Event,
+    event::{Event, Value},
+    internal_events::TemplateRenderingError,
     schema,
+    template::Template,
     transforms::Transform,
 };
 
@@ -20,6 +25,7 @@ pub(crate) const UNMATCHED_ROUTE: &str = "_unmatched";
 #[derive(Clone)]
 pub struct Route {
     conditions: Vec<(String, Condition)>,
+    dynamic: Option<DynamicRoute>,
 }
 
 impl Route {
@@ -29,7 +35,83 @@ impl Route {
             let condition = condition.build(&context.enrichment_tables)?;
             conditions.push((output_name.clone(), condition));
         }
-        Ok(Self { conditions })
+        let dynamic = config
+            .dynamic
+            .as_ref()
+            .map(|dynamic| DynamicRoute::new(dynamic, context))
+            .transpose()?;
+        Ok(Self {
+            conditions,
+            dynamic,
+        })
+    }
+}
+
+/// Routes events to the output named by the row of an enrichment table matching the event's key.
+///
+/// Lookups go through the enrichment table registry on every event, so routes change as soon as
+/// the table is reloaded, without having to rebuild the transform.
+#[derive(Clone)]
+struct DynamicRoute {
+    key: Template,
+    table: String,
+    table_key: String,
+    select: Vec<String>,
+    outputs: HashSet<String>,
+    tables: TableSearch,
+    index: IndexHandle,
+}
+
+impl DynamicRoute {
+    fn new(config: &DynamicRouteConfig, context: &TransformContext) -> crate::Result<Self> {
+        let mut registry = context.enrichment_tables.clone();
+        let index = registry.add_index(&config.table, Case::Sensitive, &[&config.table_key])?;
+        Ok(Self {
+            key: config.key.clone(),
+            table: config.table.clone(),
+            table_key: config.table_key.clone(),
+            select: vec![config.output_field.clone()],
+            outputs: config.outputs.iter().cloned().collect(),
+            tables: registry.as_readonly(),
+            index,
+        })
+    }
+
+    /// Returns the output the event should be routed to, if the table has a row for its key and
+    /// that row names one of the declared outputs.
+    fn output_for(&self, event: &Event) -> Option<&str> {
+        let key = self
+            .key
+            .render_string(event)
+            .map_err(|error| {
+                emit!(TemplateRenderingError {
+                    error,
+                    field: Some("dynamic.key"),
+                    drop_event: false,
+                })
+            })
+            .ok()?;
+        let condition = [enrichment::Condition::Equals {
+            field: &self.table_key,
+            value: Value::from(key),
+        }];
+        let row = self
+            .tables
+            .find_table_row(
+                &self.table,
+                Case::Sensitive,
+                &condition,
+                Some(self.select.as_slice()),
+                Some(self.index),
+            )
+            .ok()?;
+        match row.get(&self.select[0]) {
+            Some(Value::Bytes(output)) => self
+                .outputs
+                .get(String::from_utf8_lossy(output).as_ref())
+                .map(String::as_str),
+            _ => None,
+        }
     }
 }
 
@@ -39,15 +121,22 @@ impl SyncTransform for Route {
         event: Event,
         output: &mut vector_core::transform::TransformOutputsBuf,
     ) {
-        let mut check_failed: usize = 0;
+        let mut matched = false;
         for (output_name, condition) in &self.conditions {
             if condition.check(&event) {
                 output.push_named(output_name, event.clone());
-            } else {
-                check_failed += 1;
+                matched = true;
             }
         }
-        if check_failed == self.conditions.len() {
+        if let Some(output_name) = self
+            .dynamic
+            .as_ref()
+            .and_then(|dynamic| dynamic.output_for(&event))
+        {
+            output.push_named(output_name, event.clone());
+            matched = true;
+        }
+        if !matched {
             output.push_named(UNMATCHED_ROUTE, event);
         }
     }
@@ -59,14 +148,56 @@ impl SyncTransform for Route {
 #[serde(deny_unknown_fields)]
 pub struct RouteConfig {
     // Deprecated name
-    #[serde(alias = "lanes")]
+    #[serde(alias = "lanes", default)]
     route: IndexMap<String, AnyCondition>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dynamic: Option<DynamicRouteConfig>,
+}
+
+/// Routes events using a mapping from keys to output names stored in an enrichment table.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DynamicRouteConfig {
+    /// The enrichment table holding the routes.
+    table: String,
+    /// The template rendered for each event to find its row in the table.
+    key: Template,
+    /// The table column matched against the rendered key.
+    #[serde(default = "default_table_key")]
+    table_key: String,
+    /// The table column holding the name of the output to route to.
+    #[serde(default = "default_output_field")]
+    output_field: String,
+    /// The outputs the table may route to. As outputs have to be known when the topology is
+    /// built, rows naming any other output are treated as unmatched.
+    outputs: Vec<String>,
+}
+
+fn default_table_key() -> String {
+    "key".to_string()
+}
+
+fn default_output_field() -> String {
+    "output".to_string()
 }
 
 #[cfg(feature = "transforms-pipelines")]
 impl RouteConfig {
     pub(crate) const fn new(route: IndexMap<String, AnyCondition>) -> Self {
-        Self { route }
+        Self {
+            route,
+            dynamic: None,
+        }
+    }
+}
+
+impl RouteConfig {
+    fn output_names(&self) -> impl Iterator<Item = &String> {
+        self.route.keys().chain(
+            self.dynamic
+                .iter()
+                .flat_map(|dynamic| dynamic.outputs.iter()),
+        )
     }
 }
 
@@ -82,6 +213,7 @@ impl GenerateConfig for RouteConfig {
     fn generate_config() -> toml::Value {
         toml::Value::try_from(Self {
             route: IndexMap::new(),
+            dynamic: None,
         })
         .unwrap()
     }
@@ -100,19 +232,27 @@ impl TransformConfig for RouteConfig {
     }
 
     fn validate(&self, _: &schema::Definition) -> Result<(), Vec<String>> {
-        if self.route.contains_key(UNMATCHED_ROUTE) {
-            Err(vec![format!(
-                "cannot have a named output with reserved name: `{UNMATCHED_ROUTE}`"
-            )])
-        } else {
+        let mut errors = Vec::new();
+        let mut seen = HashSet::new();
+        for output_name in self.output_names() {
+            if output_name == UNMATCHED_ROUTE {
+                errors.push(format!(
+                    "cannot have a named output with reserved name: `{UNMATCHED_ROUTE}`"
+                ));
+            } else if !seen.insert(output_name) {
+                errors.push(format!("duplicate output name: `{output_name}`"));
+            }
+        }
+        if errors.is_empty() {
             Ok(())
+        } else {
+            Err(errors)
         }
     }
 
     fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
         let mut result: Vec<Output> = self
-            .route
-            .keys()
+            .output_names()
             .map(|output_name| Output::default(DataType::all()).with_port(output_name))
             .collect();
         result.push(Output::default(DataType::all()).with_port(UNMATCHED_ROUTE));
@@ -156,6 +296,8 @@ impl TransformConfig for RouteCompatConfig {
 
 #[cfg(test)]
 mod test {
+    use std::collections::{BTreeMap, HashMap};
+
     use indoc::indoc;
     use vector_core::transform::TransformOutputsBuf;
 
@@ -341,6 +483,150 @@ mod test {
         }
     }
 
+    /// Maps the `key` column to the `output` column.
+    #[derive(Clone)]
+    struct RoutesTable(BTreeMap<String, String>);
+
+    impl enrichment::Table for RoutesTable {
+        fn find_table_row<'a>(
+            &self,
+            _case: Case,
+            condition: &'a [enrichment::Condition<'a>],
+            _select: Option<&[String]>,
+            _index: Option<IndexHandle>,
+        ) -> Result<BTreeMap<String, Value>, String> {
+            match condition {
+                [enrichment::Condition::Equals {
+                    field: "key",
+                    value,
+                }] => self
+                    .0
+                    .get(value.to_string_lossy().as_str())
+                    .map(|output| BTreeMap::from([("output".to_string(), output.as_str().into())]))
+                    .ok_or_else(|| "no rows found".to_string()),
+                _ => Err("unexpected condition".to_string()),
+            }
+        }
+
+        fn find_table_rows<'a>(
+            &self,
+            case: Case,
+            condition: &'a [enrichment::Condition<'a>],
+            select: Option<&[String]>,
+            index: Option<IndexHandle>,
+        ) -> Result<Vec<BTreeMap<String, Value>>, String> {
+            self.find_table_row(case, condition, select, index)
+                .map(|row| vec![row])
+        }
+
+        fn add_index(&mut self, _case: Case, _fields: &[&str]) -> Result<IndexHandle, String> {
+            Ok(IndexHandle(0))
+        }
+
+        fn index_fields(&self) -> Vec<(Case, Vec<String>)> {
+            Vec::new()
+        }
+
+        fn needs_reload(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn route_dynamic_from_enrichment_table() {
+        let output_names = vec!["first", "second", UNMATCHED_ROUTE];
+        let config = toml::from_str::<RouteConfig>(
+            r#"
+            dynamic.table = "routes"
+            dynamic.key = "{{ tenant }}"
+            dynamic.outputs = ["first", "second"]
+        "#,
+        )
+        .unwrap();
+
+        let registry = enrichment::TableRegistry::default();
+        let mut tables: HashMap<String, Box<dyn enrichment::Table + Send + Sync>> = HashMap::new();
+        tables.insert(
+            "routes".to_string(),
+            Box::new(RoutesTable(BTreeMap::from([
+                ("a".to_string(), "first".to_string()),
+                ("b".to_string(), "undeclared".to_string()),
+            ]))),
+        );
+        registry.load(tables);
+        let context = TransformContext {
+            enrichment_tables: registry.clone(),
+            ..Default::default()
+        };
+        let mut transform = Route::new(&config, &context).unwrap();
+        registry.finish_load();
+
+        let mut outputs = TransformOutputsBuf::new_with_capacity(
+            output_names
+                .iter()
+                .map(|output_name| {
+                    Output::default(DataType::all()).with_port(output_name.to_owned())
+                })
+                .collect(),
+            1,
+        );
+
+        let routed = Event::try_from(serde_json::json!({"tenant": "a"})).unwrap();
+        transform.transform(routed.clone(), &mut outputs);
+        assert_eq!(
+            outputs.drain_named("first").collect::<Vec<_>>(),
+            vec![routed]
+        );
+        assert_eq!(outputs.drain_named(UNMATCHED_ROUTE).count(), 0);
+
+        // Rows naming an undeclared output, and keys without a row, are unmatched.
+        for tenant in ["b", "c"] {
+            let event = Event::try_from(serde_json::json!({ "tenant": tenant })).unwrap();
+            transform.transform(event.clone(), &mut outputs);
+            assert_eq!(
+                outputs.drain_named(UNMATCHED_ROUTE).collect::<Vec<_>>(),
+                vec![event]
+            );
+        }
+
+        // Routes change as soon as the table is replaced.
+        registry.replace_table(
+            "routes",
+            Box::new(RoutesTable(BTreeMap::from([(
+                "a".to_string(),
+                "second".to_string(),
+            )]))),
+        );
+        let event = Event::try_from(serde_json::json!({"tenant": "a"})).unwrap();
+        transform.transform(event.clone(), &mut outputs);
+        assert_eq!(
+            outputs.drain_named("second").collect::<Vec<_>>(),
+            vec![event]
+        );
+        assert_eq!(outputs.drain_named("first").count(), 0);
+    }
+
+    #[test]
+    fn route_rejects_duplicate_outputs() {
+        let config = toml::from_str::<RouteConfig>(
+            r#"
+            route.first.type = "is_log"
+            dynamic.table = "routes"
+            dynamic.key = "{{ tenant }}"
+            dynamic.outputs = ["first", "_unmatched"]
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.validate(&schema::Definition::empty()),
+            Err(vec![
+                "duplicate output name: `first`".to_string(),
+                format!("cannot have a named output with reserved name: `{UNMATCHED_ROUTE}`"),
+            ])
+        );
+    }
+
     #[tokio::test]
     async fn route_metrics_with_output_tag() {
         init_test();
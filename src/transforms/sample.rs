@@ -1,4 +1,7 @@
+use std::time::{Duration, Instant};
+
 use serde::{Deserialize, Serialize};
+use vector_core::time::Clock;
 
 use crate::{
     conditions::{AnyCondition, Condition},
@@ -17,7 +20,47 @@ use crate::{
 pub struct SampleConfig {
     pub rate: u64,
     pub key_field: Option<String>,
+    #[serde(default)]
+    pub key_sampling: KeySampling,
     pub exclude: Option<AnyCondition>,
+    pub adaptive: Option<AdaptiveConfig>,
+}
+
+/// How events with a `key_field` are selected.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeySampling {
+    /// Keeps events whose key hashes to a multiple of `rate`.
+    Modulo,
+    /// Keeps events whose key hashes into the lowest `1/rate` of the hash space, so that a key
+    /// kept at a given rate is also kept at any lower rate.
+    Consistent,
+}
+
+impl Default for KeySampling {
+    fn default() -> Self {
+        Self::Modulo
+    }
+}
+
+/// Adjusts the sampling rate over time so that the number of events forwarded per second
+/// approaches a target, using `rate` as the initial rate.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct AdaptiveConfig {
+    pub target_events_per_sec: f64,
+    #[serde(default = "default_window_secs")]
+    pub window_secs: f64,
+    #[serde(default = "default_max_rate")]
+    pub max_rate: u64,
+}
+
+const fn default_window_secs() -> f64 {
+    1.0
+}
+
+const fn default_max_rate() -> u64 {
+    10_000
 }
 
 inventory::submit! {
@@ -33,7 +76,9 @@ impl GenerateConfig for SampleConfig {
         toml::Value::try_from(Self {
             rate: 10,
             key_field: None,
+            key_sampling: KeySampling::default(),
             exclude: None::<AnyCondition>,
+            adaptive: None,
         })
         .unwrap()
     }
@@ -43,14 +88,30 @@ impl GenerateConfig for SampleConfig {
 #[typetag::serde(name = "sample")]
 impl TransformConfig for SampleConfig {
     async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
-        Ok(Transform::function(Sample::new(
+        let mut sample = Sample::new(
             self.rate,
             self.key_field.clone(),
             self.exclude
                 .as_ref()
                 .map(|condition| condition.build(&context.enrichment_tables))
                 .transpose()?,
-        )))
+        )
+        .with_key_sampling(self.key_sampling);
+
+        if let Some(adaptive) = self.adaptive {
+            if adaptive.target_events_per_sec.is_nan() || adaptive.target_events_per_sec <= 0.0 {
+                return Err("`adaptive.target_events_per_sec` must be greater than 0".into());
+            }
+            if adaptive.window_secs.is_nan() || adaptive.window_secs <= 0.0 {
+                return Err("`adaptive.window_secs` must be greater than 0".into());
+            }
+            if adaptive.max_rate == 0 {
+                return Err("`adaptive.max_rate` must be greater than 0".into());
+            }
+            sample = sample.with_adaptive(adaptive, context.clock.clone());
+        }
+
+        Ok(Transform::function(sample))
     }
 
     fn input(&self) -> Input {
@@ -94,8 +155,41 @@ impl TransformConfig for SampleCompatConfig {
 pub struct Sample {
     rate: u64,
     key_field: Option<String>,
+    key_sampling: KeySampling,
     exclude: Option<Condition>,
     count: u64,
+    adaptive: Option<AdaptiveState>,
+}
+
+#[derive(Clone)]
+struct AdaptiveState {
+    config: AdaptiveConfig,
+    clock: Clock,
+    window_start: Option<Instant>,
+    window_events: u64,
+}
+
+impl AdaptiveState {
+    /// Counts an incoming event, returning the new sampling rate if the current window has
+    /// ended.
+    fn observe(&mut self) -> Option<u64> {
+        let now = self.clock.now();
+        let window_start = *self.window_start.get_or_insert(now);
+        self.window_events += 1;
+
+        let elapsed = now.saturating_duration_since(window_start);
+        if elapsed < Duration::from_secs_f64(self.config.window_secs) {
+            return None;
+        }
+
+        let observed_events_per_sec = self.window_events as f64 / elapsed.as_secs_f64();
+        let rate = (observed_events_per_sec / self.config.target_events_per_sec).ceil() as u64;
+
+        self.window_start = Some(now);
+        self.window_events = 0;
+
+        Some(rate.clamp(1, self.config.max_rate))
+    }
 }
 
 impl Sample {
@@ -103,10 +197,27 @@ impl Sample {
         Self {
             rate,
             key_field,
+            key_sampling: KeySampling::Modulo,
             exclude,
             count: 0,
+            adaptive: None,
         }
     }
+
+    pub const fn with_key_sampling(mut self, key_sampling: KeySampling) -> Self {
+        self.key_sampling = key_sampling;
+        self
+    }
+
+    pub fn with_adaptive(mut self, config: AdaptiveConfig, clock: Clock) -> Self {
+        self.adaptive = Some(AdaptiveState {
+            config,
+            clock,
+            window_start: None,
+            window_events: 0,
+        });
+        self
+    }
 }
 
 impl FunctionTransform for Sample {
@@ -118,21 +229,32 @@ impl FunctionTransform for Sample {
             }
         }
 
+        if let Some(rate) = self.adaptive.as_mut().and_then(AdaptiveState::observe) {
+            self.rate = rate;
+            self.count %= rate;
+        }
+
         let value = self
             .key_field
             .as_ref()
             .and_then(|key_field| event.as_log().get(key_field.as_str()))
             .map(|v| v.to_string_lossy());
 
-        let num = if let Some(value) = value {
-            seahash::hash(value.as_bytes())
+        let keep = if let Some(value) = value {
+            let hash = seahash::hash(value.as_bytes());
+            match self.key_sampling {
+                KeySampling::Modulo => hash % self.rate == 0,
+                // Unlike `hash % rate`, this is consistent across rates, so all events sharing a
+                // key stay together even while the adaptive mode adjusts the rate.
+                KeySampling::Consistent => hash <= u64::MAX / self.rate,
+            }
         } else {
-            self.count
+            self.count == 0
         };
 
         self.count = (self.count + 1) % self.rate;
 
-        if num % self.rate == 0 {
+        if keep {
             event
                 .as_mut_log()
                 .insert("sample_rate", self.rate.to_string());
@@ -146,6 +268,7 @@ impl FunctionTransform for Sample {
 #[cfg(test)]
 mod tests {
     use approx::assert_relative_eq;
+    use vector_core::time::MockClock;
 
     use super::*;
     use crate::{
@@ -332,6 +455,95 @@ mod tests {
         }
     }
 
+    #[test]
+    fn hash_sampling_is_consistent_across_rates() {
+        let events = random_events(1000);
+        let key_field = Some(log_schema().message_key().into());
+
+        let mut sampler =
+            Sample::new(10, key_field.clone(), None).with_key_sampling(KeySampling::Consistent);
+        let kept_at_10 = events
+            .iter()
+            .cloned()
+            .filter_map(|event| transform_one(&mut sampler, event))
+            .map(|event| event.as_log()[log_schema().message_key()].clone())
+            .collect::<Vec<_>>();
+
+        let mut sampler =
+            Sample::new(2, key_field, None).with_key_sampling(KeySampling::Consistent);
+        let kept_at_2 = events
+            .into_iter()
+            .filter_map(|event| transform_one(&mut sampler, event))
+            .map(|event| event.as_log()[log_schema().message_key()].clone())
+            .collect::<Vec<_>>();
+
+        assert!(!kept_at_10.is_empty());
+        assert!(kept_at_10.iter().all(|value| kept_at_2.contains(value)));
+    }
+
+    #[test]
+    fn key_sampling_defaults_to_modulo() {
+        let config: SampleConfig = toml::from_str(
+            r#"
+            rate = 10
+            key_field = "message"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.key_sampling, KeySampling::Modulo);
+    }
+
+    #[test]
+    fn adaptive_adjusts_rate_to_target() {
+        let clock = MockClock::new();
+        let mut sampler = Sample::new(1, None, None).with_adaptive(
+            AdaptiveConfig {
+                target_events_per_sec: 10.0,
+                window_secs: 1.0,
+                max_rate: 1000,
+            },
+            Clock::Mock(clock.clone()),
+        );
+
+        // Send 100 events per second for 5 seconds.
+        let mut passed_last_second = 0;
+        for i in 0..500 {
+            if transform_one(&mut sampler, Event::from("message")).is_some() && i >= 400 {
+                passed_last_second += 1;
+            }
+            clock.advance(Duration::from_millis(10));
+        }
+
+        assert_eq!(sampler.rate, 10);
+        assert_eq!(passed_last_second, 10);
+    }
+
+    #[test]
+    fn adaptive_rate_is_bounded() {
+        let clock = MockClock::new();
+        let mut sampler = Sample::new(1, None, None).with_adaptive(
+            AdaptiveConfig {
+                target_events_per_sec: 1.0,
+                window_secs: 1.0,
+                max_rate: 5,
+            },
+            Clock::Mock(clock.clone()),
+        );
+
+        for _ in 0..200 {
+            transform_one(&mut sampler, Event::from("message"));
+            clock.advance(Duration::from_millis(10));
+        }
+        assert_eq!(sampler.rate, 5);
+
+        // Traffic drops well below the target.
+        clock.advance(Duration::from_secs(10));
+        transform_one(&mut sampler, Event::from("message"));
+        clock.advance(Duration::from_secs(10));
+        transform_one(&mut sampler, Event::from("message"));
+        assert_eq!(sampler.rate, 1);
+    }
+
     fn random_events(n: usize) -> Vec<Event> {
         random_lines(10).take(n).map(Event::from).collect()
     }
@@ -1,9 +1,11 @@
 use std::{
-    borrow::{Borrow, Cow},
+    borrow::Cow,
     collections::{HashMap, HashSet},
     fmt,
     future::ready,
+    mem,
     pin::Pin,
+    time::{Duration, Instant},
 };
 
 use bloom::{BloomFilter, ASMS};
@@ -36,6 +38,26 @@ pub struct TagCardinalityLimitConfig {
 
     #[serde(flatten)]
     pub mode: Mode,
+
+    /// Limits overriding `value_limit` and `limit_exceeded_action` for the metrics of the
+    /// given names, whose tags are tracked separately from those of other metrics.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub per_metric_limits: HashMap<String, PerMetricConfig>,
+
+    /// When set, values which haven't been seen for this long are forgotten, rather than
+    /// being tracked forever.
+    #[serde(default)]
+    pub window_secs: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PerMetricConfig {
+    #[serde(default = "default_value_limit")]
+    pub value_limit: u32,
+
+    #[serde(default)]
+    pub limit_exceeded_action: Option<LimitExceededAction>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -52,7 +74,7 @@ pub struct BloomFilterConfig {
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(tag = "limit_exceeded_action", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
 pub enum LimitExceededAction {
     DropTag,
     DropEvent,
@@ -62,6 +84,9 @@ pub enum LimitExceededAction {
 pub struct TagCardinalityLimit {
     config: TagCardinalityLimitConfig,
     accepted_tags: HashMap<String, TagValueSet>,
+    /// The accepted tags of the metrics with their own limits, by metric name.
+    accepted_metric_tags: HashMap<String, HashMap<String, TagValueSet>>,
+    window_started: Instant,
 }
 
 const fn default_limit_exceeded_action() -> LimitExceededAction {
@@ -86,6 +111,8 @@ impl GenerateConfig for TagCardinalityLimitConfig {
             mode: Mode::Exact,
             value_limit: default_value_limit(),
             limit_exceeded_action: default_limit_exceeded_action(),
+            per_metric_limits: HashMap::new(),
+            window_secs: None,
         })
         .unwrap()
     }
@@ -95,6 +122,9 @@ impl GenerateConfig for TagCardinalityLimitConfig {
 #[typetag::serde(name = "tag_cardinality_limit")]
 impl TransformConfig for TagCardinalityLimitConfig {
     async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        if self.window_secs == Some(0) {
+            return Err("`window_secs` must be greater than zero".into());
+        }
         Ok(Transform::event_task(TagCardinalityLimit::new(
             self.clone(),
         )))
@@ -114,10 +144,17 @@ impl TransformConfig for TagCardinalityLimitConfig {
 }
 
 /// Container for storing the set of accepted values for a given tag key.
+///
+/// When tracking is windowed, the values accepted during the previous window are kept
+/// around, so values which keep showing up remain accepted from one window to the next.
 #[derive(Debug)]
 struct TagValueSet {
     storage: TagValueSetStorage,
+    previous: Option<TagValueSetStorage>,
     num_elements: usize,
+    /// How many values of the previous window haven't shown up yet during this one. Room is
+    /// kept for them within the limit until the window ends.
+    reserved: usize,
 }
 
 enum TagValueSetStorage {
@@ -134,48 +171,78 @@ impl fmt::Debug for TagValueSetStorage {
     }
 }
 
-impl TagValueSet {
+impl TagValueSetStorage {
     fn new(value_limit: u32, mode: &Mode) -> Self {
         match &mode {
-            Mode::Exact => Self {
-                storage: TagValueSetStorage::Set(HashSet::with_capacity(value_limit as usize)),
-                num_elements: 0,
-            },
+            Mode::Exact => TagValueSetStorage::Set(HashSet::with_capacity(value_limit as usize)),
             Mode::Probabilistic(config) => {
                 let num_bits = config.cache_size_per_key / 8; // Convert bytes to bits
                 let num_hashes = bloom::optimal_num_hashes(num_bits, value_limit);
-
-                Self {
-                    storage: TagValueSetStorage::Bloom(BloomFilter::with_size(
-                        num_bits, num_hashes,
-                    )),
-                    num_elements: 0,
-                }
+                TagValueSetStorage::Bloom(BloomFilter::with_size(num_bits, num_hashes))
             }
         }
     }
 
-    fn contains(&self, value: Cow<'_, String>) -> bool {
-        match &self.storage {
-            TagValueSetStorage::Set(set) => set.contains(value.borrow() as &String),
-            TagValueSetStorage::Bloom(bloom) => bloom.contains(&value),
+    fn contains(&self, value: &Cow<'_, String>) -> bool {
+        match self {
+            TagValueSetStorage::Set(set) => set.contains(value.as_str()),
+            TagValueSetStorage::Bloom(bloom) => bloom.contains(value),
+        }
+    }
+}
+
+impl TagValueSet {
+    fn new(value_limit: u32, mode: &Mode) -> Self {
+        Self {
+            storage: TagValueSetStorage::new(value_limit, mode),
+            previous: None,
+            num_elements: 0,
+            reserved: 0,
         }
     }
 
+    fn contains(&self, value: &Cow<'_, String>) -> bool {
+        self.storage.contains(value)
+    }
+
+    fn contained_previously(&self, value: &Cow<'_, String>) -> bool {
+        self.previous
+            .as_ref()
+            .map_or(false, |previous| previous.contains(value))
+    }
+
     const fn len(&self) -> usize {
         self.num_elements
     }
 
-    fn insert(&mut self, value: Cow<'_, String>) -> bool {
+    const fn reserved(&self) -> usize {
+        self.reserved
+    }
+
+    fn insert(&mut self, value: Cow<'_, String>, previously: bool) -> bool {
         let inserted = match &mut self.storage {
             TagValueSetStorage::Set(set) => set.insert(value.into_owned()),
             TagValueSetStorage::Bloom(bloom) => bloom.insert(&value),
         };
         if inserted {
-            self.num_elements += 1
+            self.num_elements += 1;
+            if previously {
+                self.reserved = self.reserved.saturating_sub(1);
+            }
         }
         inserted
     }
+
+    /// Starts a new window, keeping the values of the one ending unless it's stale as well.
+    fn rotate(&mut self, value_limit: u32, mode: &Mode, keep_previous: bool) {
+        let ended = mem::replace(
+            &mut self.storage,
+            TagValueSetStorage::new(value_limit, mode),
+        );
+        self.previous = keep_previous.then(|| ended);
+        self.reserved = if keep_previous { self.num_elements } else { 0 };
+        self.num_elements = 0;
+    }
 }
 
 impl TagCardinalityLimit {
@@ -183,6 +250,8 @@ impl TagCardinalityLimit {
         Self {
             config,
             accepted_tags: HashMap::new(),
+            accepted_metric_tags: HashMap::new(),
+            window_started: Instant::now(),
         }
     }
 
@@ -194,26 +263,44 @@ impl TagCardinalityLimit {
     /// for the key and returns true, otherwise returns false.  A false return
     /// value indicates to the caller that the value is not accepted for this
     /// key, and the configured limit_exceeded_action should be taken.
-    fn try_accept_tag(&mut self, key: &str, value: Cow<'_, String>) -> bool {
-        if !self.accepted_tags.contains_key(key) {
-            self.accepted_tags.insert(
+    fn try_accept_tag(&mut self, metric_name: &str, key: &str, value: Cow<'_, String>) -> bool {
+        let (value_limit, accepted_tags) = match self.config.per_metric_limits.get(metric_name) {
+            Some(limits) => (
+                limits.value_limit,
+                self.accepted_metric_tags
+                    .entry(metric_name.to_string())
+                    .or_default(),
+            ),
+            None => (self.config.value_limit, &mut self.accepted_tags),
+        };
+        if !accepted_tags.contains_key(key) {
+            accepted_tags.insert(
                 key.to_string(),
-                TagValueSet::new(self.config.value_limit, &self.config.mode),
+                TagValueSet::new(value_limit, &self.config.mode),
             );
         }
-        let tag_value_set = self.accepted_tags.get_mut(key).unwrap();
+        let tag_value_set = accepted_tags.get_mut(key).unwrap();
 
-        if tag_value_set.contains(value.clone()) {
+        if tag_value_set.contains(&value) {
             // Tag value has already been accepted, nothing more to do.
             return true;
         }
 
+        // Values accepted in the previous window remain accepted, so they aren't dropped just
+        // because the window ended. Other values only get the room they leave within the limit.
+        let previously = tag_value_set.contained_previously(&value);
+        let room = if previously {
+            value_limit as usize
+        } else {
+            (value_limit as usize).saturating_sub(tag_value_set.reserved())
+        };
+
         // Tag value not yet part of the accepted set.
-        if tag_value_set.len() < self.config.value_limit as usize {
+        if tag_value_set.len() < room {
             // accept the new value
-            tag_value_set.insert(value);
+            tag_value_set.insert(value, previously);
 
-            if tag_value_set.len() == self.config.value_limit as usize {
+            if tag_value_set.len() + tag_value_set.reserved() == value_limit as usize {
                 emit!(TagCardinalityValueLimitReached { key });
             }
 
@@ -224,14 +311,52 @@ impl TagCardinalityLimit {
         }
     }
 
+    /// Starts a new window once the current one has ended. Values seen during the window
+    /// ending remain accepted for the next one, but are forgotten if they don't show up again
+    /// by its end.
+    fn rotate_window(&mut self, now: Instant) {
+        let window = match self.config.window_secs {
+            Some(window_secs) => Duration::from_secs(window_secs),
+            None => return,
+        };
+        let elapsed = now.saturating_duration_since(self.window_started);
+        if elapsed < window {
+            return;
+        }
+        // Values of the window ending are stale as well if no event came for a whole window.
+        let keep_previous = elapsed < window * 2;
+
+        let mode = &self.config.mode;
+        for set in self.accepted_tags.values_mut() {
+            set.rotate(self.config.value_limit, mode, keep_previous);
+        }
+        for (metric_name, accepted_tags) in &mut self.accepted_metric_tags {
+            let value_limit = self.config.per_metric_limits[metric_name].value_limit;
+            for set in accepted_tags.values_mut() {
+                set.rotate(value_limit, mode, keep_previous);
+            }
+        }
+        self.window_started = now;
+    }
+
     fn transform_one(&mut self, mut event: Event) -> Option<Event> {
+        self.rotate_window(Instant::now());
+
         let metric = event.as_mut_metric();
+        let metric_name = metric.name().to_string();
+        let limit_exceeded_action = self
+            .config
+            .per_metric_limits
+            .get(&metric_name)
+            .and_then(|limits| limits.limit_exceeded_action.clone())
+            .unwrap_or_else(|| self.config.limit_exceeded_action.clone());
         if let Some(tags_map) = metric.tags() {
-            match self.config.limit_exceeded_action {
+            match limit_exceeded_action {
                 LimitExceededAction::DropEvent => {
                     for (key, value) in tags_map {
-                        if !self.try_accept_tag(key, Cow::Borrowed(value)) {
+                        if !self.try_accept_tag(&metric_name, key, Cow::Borrowed(value)) {
                             emit!(TagCardinalityLimitRejectingEvent {
+                                metric_name: &metric_name,
                                 tag_key: key,
                                 tag_value: value,
                             });
@@ -242,8 +367,9 @@ impl TagCardinalityLimit {
                 LimitExceededAction::DropTag => {
                     let mut to_delete = Vec::new();
                     for (key, value) in tags_map {
-                        if !self.try_accept_tag(key, Cow::Borrowed(value)) {
+                        if !self.try_accept_tag(&metric_name, key, Cow::Borrowed(value)) {
                             emit!(TagCardinalityLimitRejectingTag {
+                                metric_name: &metric_name,
                                 tag_key: key,
                                 tag_value: value,
                             });
@@ -307,6 +433,8 @@ mod tests {
             value_limit,
             limit_exceeded_action,
             mode: Mode::Exact,
+            per_metric_limits: HashMap::new(),
+            window_secs: None,
         })
     }
 
@@ -320,6 +448,8 @@ mod tests {
             mode: Mode::Probabilistic(BloomFilterConfig {
                 cache_size_per_key: default_cache_size(),
             }),
+            per_metric_limits: HashMap::new(),
+            window_secs: None,
         })
     }
 
@@ -452,4 +582,101 @@ mod tests {
         assert_eq!(new_event2, event2);
         assert_eq!(new_event3, event3);
     }
+
+    fn make_named_metric(name: &str, tags: BTreeMap<String, String>) -> Event {
+        Event::Metric(
+            Metric::new(
+                name,
+                metric::MetricKind::Incremental,
+                metric::MetricValue::Counter { value: 1.0 },
+            )
+            .with_tags(Some(tags)),
+        )
+    }
+
+    fn tags(value: &str) -> BTreeMap<String, String> {
+        vec![("tag1".to_owned(), value.to_owned())]
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn tag_cardinality_limit_per_metric_limits() {
+        let mut transform = TagCardinalityLimit::new(
+            toml::from_str(
+                r#"
+                mode = "exact"
+                value_limit = 1
+                limit_exceeded_action = "drop_tag"
+
+                [per_metric_limits.requests]
+                value_limit = 2
+                limit_exceeded_action = "drop_event"
+                "#,
+            )
+            .unwrap(),
+        );
+
+        // The overridden metric has its own limit and action.
+        for value in ["val1", "val2"] {
+            let event = make_named_metric("requests", tags(value));
+            assert_eq!(transform.transform_one(event.clone()), Some(event));
+        }
+        assert_eq!(
+            transform.transform_one(make_named_metric("requests", tags("val3"))),
+            None
+        );
+
+        // Its tags don't count toward the limit of the other metrics.
+        let event = make_named_metric("errors", tags("val3"));
+        assert_eq!(transform.transform_one(event.clone()), Some(event));
+        let event = transform
+            .transform_one(make_named_metric("errors", tags("val4")))
+            .unwrap();
+        assert!(event.as_metric().tags().unwrap().is_empty());
+    }
+
+    #[test]
+    fn tag_cardinality_limit_window_hashset() {
+        let mut transform = make_transform_hashset(2, LimitExceededAction::DropEvent);
+        transform.config.window_secs = Some(60);
+        window(transform);
+    }
+
+    #[test]
+    fn tag_cardinality_limit_window_bloom() {
+        let mut transform = make_transform_bloom(2, LimitExceededAction::DropEvent);
+        transform.config.window_secs = Some(60);
+        window(transform);
+    }
+
+    fn window(mut transform: TagCardinalityLimit) {
+        let start = transform.window_started;
+        let mut accepts = |value| transform.transform_one(make_metric(tags(value))).is_some();
+        assert!(accepts("val1"));
+        assert!(accepts("val2"));
+        assert!(!accepts("val3"));
+
+        // Values of the previous window remain accepted, and keep their room within the limit
+        // of the new one.
+        transform.rotate_window(start + Duration::from_secs(60));
+        let mut accepts = |value| transform.transform_one(make_metric(tags(value))).is_some();
+        assert!(accepts("val1"));
+        assert!(!accepts("val3"));
+
+        // Values which weren't seen during a whole window are forgotten, and count toward the
+        // limit like new values when they show up again.
+        transform.rotate_window(start + Duration::from_secs(120));
+        let mut accepts = |value| transform.transform_one(make_metric(tags(value))).is_some();
+        assert!(accepts("val3"));
+        assert!(!accepts("val2"));
+        assert!(accepts("val1"));
+        assert!(!accepts("val2"));
+
+        transform.rotate_window(start + Duration::from_secs(300));
+        let mut accepts = |value| transform.transform_one(make_metric(tags(value))).is_some();
+        assert!(accepts("val5"));
+        assert!(accepts("val6"));
+        assert!(!accepts("val1"));
+    }
 }
@@ -3,14 +3,17 @@ use std::{num::NonZeroU32, pin::Pin, time::Duration};
 use async_stream::stream;
 use futures::{stream, Stream, StreamExt};
 use governor::{clock, Quota, RateLimiter};
+use redis::{aio::ConnectionManager, Script};
 use serde::{Deserialize, Serialize};
-use snafu::Snafu;
+use snafu::{ResultExt, Snafu};
+use vector_core::time::Clock;
 
 use crate::{
     conditions::{AnyCondition, Condition},
     config::{DataType, Input, Output, TransformConfig, TransformContext, TransformDescription},
+    dropped_events,
     event::Event,
-    internal_events::{TemplateRenderingError, ThrottleEventDiscarded},
+    internal_events::{TemplateRenderingError, ThrottleEventDiscarded, ThrottleRedisError},
     schema,
     template::Template,
     transforms::{TaskTransform, Transform},
@@ -23,6 +26,22 @@ pub struct ThrottleConfig {
     window_secs: f64,
     key_field: Option<Template>,
     exclude: Option<AnyCondition>,
+    redis: Option<RedisBackendConfig>,
+}
+
+/// Enforces the rate limit through a token bucket stored in Redis, so that every Vector
+/// instance sharing the same Redis server (and `key_prefix`) enforces a single global rate per
+/// key, instead of each instance enforcing its own rate.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RedisBackendConfig {
+    endpoint: String,
+    #[serde(default = "default_redis_key_prefix")]
+    key_prefix: String,
+}
+
+fn default_redis_key_prefix() -> String {
+    "vector_throttle".to_string()
 }
 
 inventory::submit! {
@@ -35,7 +54,11 @@ impl_generate_config_from_default!(ThrottleConfig);
 #[typetag::serde(name = "throttle")]
 impl TransformConfig for ThrottleConfig {
     async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
-        Throttle::new(self, context, clock::MonotonicClock).map(Transform::event_task)
+        let mut throttle = Throttle::new(self, context, ContextClock(context.clock.clone()))?;
+        if let Some(redis) = &self.redis {
+            throttle.redis = Some(RedisLimiter::connect(redis, &throttle.quota_params).await?);
+        }
+        Ok(Transform::event_task(throttle))
     }
 
     fn input(&self) -> Input {
@@ -51,13 +74,118 @@ impl TransformConfig for ThrottleConfig {
     }
 }
 
+/// Adapts the clock from the transform context to the clock interface of the rate limiter.
+#[derive(Clone, Debug)]
+pub struct ContextClock(Clock);
+
+impl clock::Clock for ContextClock {
+    type Instant = std::time::Instant;
+
+    fn now(&self) -> Self::Instant {
+        self.0.now()
+    }
+}
+
+/// Atomically takes a token from the bucket stored at each of `KEYS`, in order, returning an
+/// array holding 1 for every key that had a token available and 0 otherwise. A key may appear
+/// more than once, in which case a token is taken for every occurrence.
+///
+/// The bucket is tracked with the generic cell rate algorithm, which only needs to store the
+/// theoretical arrival time of the next token. Redis' own clock is used so that instances with
+/// skewed clocks still agree on the bucket state.
+const REDIS_TOKEN_BUCKET_SCRIPT: &str = r#"
+redis.replicate_commands()
+local emission_interval = tonumber(ARGV[1])
+local burst = tonumber(ARGV[2])
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000000 + tonumber(time[2])
+local results = {}
+for i, key in ipairs(KEYS) do
+    local tat = tonumber(redis.call('GET', key)) or now
+    if tat < now then
+        tat = now
+    end
+    local new_tat = tat + emission_interval
+    if new_tat - now > emission_interval * burst then
+        results[i] = 0
+    else
+        redis.call('SET', key, string.format('%.0f', new_tat), 'PX', math.ceil((new_tat - now) / 1000))
+        results[i] = 1
+    end
+end
+return results
+"#;
+
+/// The maximum number of events whose tokens are taken from Redis in a single round trip.
+const REDIS_BATCH_SIZE: usize = 1024;
+
+/// The parameters of the rate limit, shared by the local and Redis-backed limiters.
+#[derive(Clone, Copy, Debug)]
+struct QuotaParams {
+    /// The time it takes for a single token to be replenished.
+    emission_interval: Duration,
+    threshold: NonZeroU32,
+}
+
+#[derive(Clone)]
+struct RedisLimiter {
+    connection: ConnectionManager,
+    script: Script,
+    key_prefix: String,
+    params: QuotaParams,
+}
+
+impl RedisLimiter {
+    async fn connect(config: &RedisBackendConfig, params: &QuotaParams) -> crate::Result<Self> {
+        let client = redis::Client::open(config.endpoint.as_str()).context(RedisCreateSnafu)?;
+        let connection = client
+            .get_tokio_connection_manager()
+            .await
+            .context(RedisCreateSnafu)?;
+        Ok(Self {
+            connection,
+            script: Script::new(REDIS_TOKEN_BUCKET_SCRIPT),
+            key_prefix: config.key_prefix.clone(),
+            params: *params,
+        })
+    }
+
+    fn redis_key(&self, key: Option<&str>) -> String {
+        match key {
+            Some(key) => format!("{}:key:{}", self.key_prefix, key),
+            None => format!("{}:global", self.key_prefix),
+        }
+    }
+
+    /// Takes a token for each of `keys` in a single round trip, returning whether each of them
+    /// was allowed through.
+    async fn check_keys(&mut self, keys: &[Option<String>]) -> redis::RedisResult<Vec<bool>> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut invocation = self.script.prepare_invoke();
+        for key in keys {
+            invocation.key(self.redis_key(key.as_deref()));
+        }
+        let allowed: Vec<i64> = invocation
+            .arg(self.params.emission_interval.as_micros() as u64)
+            .arg(self.params.threshold.get())
+            .invoke_async(&mut self.connection)
+            .await?;
+        Ok(allowed.into_iter().map(|allowed| allowed == 1).collect())
+    }
+}
+
 #[derive(Clone)]
 pub struct Throttle<C: clock::Clock<Instant = I>, I: clock::Reference> {
     quota: Quota,
+    quota_params: QuotaParams,
     flush_keys_interval: Duration,
     key_field: Option<Template>,
     exclude: Option<Condition>,
     clock: C,
+    redis: Option<RedisLimiter>,
 }
 
 impl<C, I> Throttle<C, I>
@@ -77,9 +205,9 @@ where
             None => return Err(Box::new(ConfigError::NonZero)),
         };
 
-        let quota = match Quota::with_period(Duration::from_secs_f64(
-            config.window_secs / threshold.get() as f64,
-        )) {
+        let emission_interval =
+            Duration::from_secs_f64(config.window_secs / threshold.get() as f64);
+        let quota = match Quota::with_period(emission_interval) {
             Some(quota) => quota.allow_burst(threshold),
             None => return Err(Box::new(ConfigError::NonZero)),
         };
@@ -91,14 +219,44 @@ where
 
         Ok(Self {
             quota,
+            quota_params: QuotaParams {
+                emission_interval,
+                threshold,
+            },
             clock,
             flush_keys_interval,
             key_field: config.key_field.clone(),
             exclude,
+            redis: None,
         })
     }
 }
 
+impl<C, I> Throttle<C, I>
+where
+    C: clock::Clock<Instant = I>,
+    I: clock::Reference,
+{
+    /// Returns the key the event is throttled by, or `None` if the event is excluded from
+    /// throttling.
+    fn throttle_key(&self, event: &Event) -> Option<Option<String>> {
+        match self.exclude.as_ref() {
+            Some(condition) if condition.check(event) => None,
+            _ => Some(self.key_field.as_ref().and_then(|t| {
+                t.render_string(event)
+                    .map_err(|error| {
+                        emit!(TemplateRenderingError {
+                            error,
+                            field: Some("key_field"),
+                            drop_event: false,
+                        })
+                    })
+                    .ok()
+            })),
+        }
+    }
+}
+
 impl<C, I> TaskTransform<Event> for Throttle<C, I>
 where
     C: clock::Clock<Instant = I> + Send + 'static,
@@ -106,7 +264,7 @@ where
 {
     fn transform(
         self: Box<Self>,
-        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+        input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
     ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
     where
         Self: 'static,
@@ -116,6 +274,11 @@ where
         let mut flush_stream = tokio::time::interval(Duration::from_millis(1000));
 
         let limiter = RateLimiter::dashmap_with_clock(self.quota, &self.clock);
+        let mut redis = self.redis.clone();
+
+        // Events that are already waiting are throttled together, so that the Redis backend only
+        // takes a single round trip for all of them.
+        let mut input_rx = input_rx.ready_chunks(REDIS_BATCH_SIZE);
 
         Box::pin(
             stream! {
@@ -124,35 +287,39 @@ where
                 let done = tokio::select! {
                     biased;
 
-                    maybe_event = input_rx.next() => {
-                        match maybe_event {
+                    maybe_events = input_rx.next() => {
+                        match maybe_events {
                             None => true,
-                            Some(event) => {
-                                match self.exclude.as_ref() {
-                                  Some(condition) if condition.check(&event) => output.push(event),
-                                  _ => {
-                                        let key = self.key_field.as_ref().and_then(|t| {
-                                            t.render_string(&event)
-                                                .map_err(|error| {
-                                                    emit!(TemplateRenderingError {
-                                                        error,
-                                                        field: Some("key_field"),
-                                                        drop_event: false,
-                                                    })
-                                                })
-                                                .ok()
-                                        });
-
-                                        match limiter.check_key(&key) {
-                                            Ok(()) => {
+                            Some(events) => {
+                                let keys = events
+                                    .iter()
+                                    .map(|event| self.throttle_key(event))
+                                    .collect::<Vec<_>>();
+                                let throttled = keys.iter().flatten().cloned().collect::<Vec<_>>();
+
+                                // If Redis is unavailable, fall back to limiting the rate of this
+                                // instance only.
+                                let mut allowed = match redis.as_mut() {
+                                    Some(redis) => match redis.check_keys(&throttled).await {
+                                        Ok(allowed) => allowed,
+                                        Err(error) => {
+                                            emit!(ThrottleRedisError { error });
+                                            throttled.iter().map(|key| limiter.check_key(key).is_ok()).collect()
+                                        }
+                                    },
+                                    None => throttled.iter().map(|key| limiter.check_key(key).is_ok()).collect(),
+                                }
+                                .into_iter();
+
+                                for (event, key) in events.into_iter().zip(keys) {
+                                    match key {
+                                        None => output.push(event),
+                                        Some(key) => {
+                                            if allowed.next().unwrap_or(false) {
                                                 output.push(event);
-                                            }
-                                            _ => {
-                                                if let Some(key) = key {
-                                                  emit!(ThrottleEventDiscarded{key})
-                                                } else {
-                                                  emit!(ThrottleEventDiscarded{key: "None".to_string()})
-                                                }
+                                            } else {
+                                                dropped_events::sample(&event, "throttled");
+                                                emit!(ThrottleEventDiscarded{key: key.unwrap_or_else(|| "None".to_string())})
                                             }
                                         }
                                     }
@@ -182,6 +349,8 @@ where
 pub enum ConfigError {
     #[snafu(display("`threshold`, and `window_secs` must be non-zero"))]
     NonZero,
+    #[snafu(display("Failed to connect to Redis: {}", source))]
+    RedisCreate { source: redis::RedisError },
 }
 
 #[cfg(test)]
@@ -198,6 +367,20 @@ mod tests {
         crate::test_util::test_generate_config::<ThrottleConfig>();
     }
 
+    #[test]
+    fn redis_key_prefix_defaults() {
+        let config = toml::from_str::<ThrottleConfig>(
+            r#"
+threshold = 2
+window_secs = 5
+redis.endpoint = "redis://127.0.0.1:6379"
+"#,
+        )
+        .unwrap();
+        let redis = config.redis.unwrap();
+        assert_eq!(redis.key_prefix, "vector_throttle");
+    }
+
     #[tokio::test]
     async fn throttle_events() {
         let clock = clock::FakeRelativeClock::default();
@@ -387,3 +570,91 @@ key_field = "{{ bucket }}"
         assert_eq!(Poll::Ready(None), futures::poll!(out_stream.next()));
     }
 }
+
+#[cfg(feature = "redis-integration-tests")]
+#[cfg(test)]
+mod integration_tests {
+    use futures::SinkExt;
+    use redis::AsyncCommands;
+
+    use super::*;
+    use crate::test_util::{random_string, trace_init};
+
+    fn redis_server() -> String {
+        std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379/0".to_owned())
+    }
+
+    #[tokio::test]
+    async fn throttle_redis_buckets() {
+        trace_init();
+
+        let key_prefix = format!("test-{}", random_string(10));
+        let config = toml::from_str::<ThrottleConfig>(&format!(
+            r#"
+threshold = 2
+window_secs = 60
+key_field = "{{{{ bucket }}}}"
+exclude = "exists(.special)"
+redis.endpoint = "{}"
+redis.key_prefix = "{}"
+"#,
+            redis_server(),
+            key_prefix
+        ))
+        .unwrap();
+
+        let throttle = config
+            .build(&TransformContext::default())
+            .await
+            .unwrap()
+            .into_task();
+
+        let (mut tx, rx) = futures::channel::mpsc::channel(10);
+        let out_stream = throttle.transform_events(Box::pin(rx));
+
+        let mut events = Vec::new();
+        for (index, bucket) in ["a", "a", "a", "b"].iter().enumerate() {
+            let mut log = Event::new_empty_log();
+            log.as_mut_log().insert("bucket", *bucket);
+            log.as_mut_log().insert("index", index as i64);
+            events.push(log);
+        }
+        let mut special_log = Event::new_empty_log();
+        special_log.as_mut_log().insert("bucket", "c");
+        special_log.as_mut_log().insert("special", "true");
+        special_log.as_mut_log().insert("index", 4);
+        events.push(special_log);
+
+        for event in events {
+            tx.send(event).await.unwrap();
+        }
+        tx.disconnect();
+
+        let output = out_stream.collect::<Vec<_>>().await;
+        let indexes = output
+            .iter()
+            .map(|event| event.as_log()["index"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(indexes, vec![0.into(), 1.into(), 3.into(), 4.into()]);
+
+        let client = redis::Client::open(redis_server()).unwrap();
+        let mut conn = client.get_tokio_connection_manager().await.unwrap();
+
+        // Bucket `a` has used up both of its tokens, so the next one arrives a full window later,
+        // while bucket `b` has only used one of them.
+        let tat_a: u64 = conn.get(format!("{}:key:a", key_prefix)).await.unwrap();
+        let tat_b: u64 = conn.get(format!("{}:key:b", key_prefix)).await.unwrap();
+        assert_eq!(tat_a - tat_b, 30_000_000);
+
+        let ttl_a: i64 = conn.pttl(format!("{}:key:a", key_prefix)).await.unwrap();
+        assert!(
+            ttl_a > 30_000 && ttl_a <= 60_000,
+            "unexpected ttl {}",
+            ttl_a
+        );
+
+        // Excluded events never take a token.
+        let exists: bool = conn.exists(format!("{}:key:c", key_prefix)).await.unwrap();
+        assert!(!exists);
+    }
+}
@@ -0,0 +1,556 @@
+//! A validator for the subset of [JSON Schema][json_schema] keywords needed to describe the shape
+//! of log events.
+//!
+//! Schemas using keywords outside of that subset, such as `$ref`, are rejected when they're
+//! compiled rather than silently accepting every event.
+//!
+//! [json_schema]: https://json-schema.org
+
+use std::{collections::BTreeMap, fmt};
+
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use snafu::Snafu;
+
+#[derive(Debug, PartialEq, Snafu)]
+pub enum SchemaError {
+    #[snafu(display("{}: unsupported keyword `{}`", path, keyword))]
+    UnsupportedKeyword { path: String, keyword: String },
+    #[snafu(display("{}: invalid `{}`: {}", path, keyword, reason))]
+    InvalidKeyword {
+        path: String,
+        keyword: String,
+        reason: String,
+    },
+}
+
+/// A part of an event that doesn't conform to the schema.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Violation {
+    /// The path of the offending value within the event, such as `.user.id`.
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum JsonType {
+    Null,
+    Boolean,
+    Integer,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl JsonType {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "null" => Self::Null,
+            "boolean" => Self::Boolean,
+            "integer" => Self::Integer,
+            "number" => Self::Number,
+            "string" => Self::String,
+            "array" => Self::Array,
+            "object" => Self::Object,
+            _ => return None,
+        })
+    }
+
+    fn of(value: &Value) -> Self {
+        match value {
+            Value::Null => Self::Null,
+            Value::Bool(_) => Self::Boolean,
+            Value::Number(number) if number.is_f64() => Self::Number,
+            Value::Number(_) => Self::Integer,
+            Value::String(_) => Self::String,
+            Value::Array(_) => Self::Array,
+            Value::Object(_) => Self::Object,
+        }
+    }
+
+    fn matches(self, value: &Value) -> bool {
+        match (self, value) {
+            (Self::Integer, Value::Number(number)) => number
+                .as_f64()
+                .map_or(false, |number| number.fract() == 0.0),
+            (Self::Number, Value::Number(_)) => true,
+            (expected, value) => expected == Self::of(value),
+        }
+    }
+}
+
+impl fmt::Display for JsonType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Null => "null",
+            Self::Boolean => "boolean",
+            Self::Integer => "integer",
+            Self::Number => "number",
+            Self::String => "string",
+            Self::Array => "array",
+            Self::Object => "object",
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct JsonSchema {
+    /// Set by the `false` schema, which no value conforms to.
+    reject_all: bool,
+    types: Option<Vec<JsonType>>,
+    allowed: Option<Vec<Value>>,
+    required: Vec<String>,
+    properties: BTreeMap<String, JsonSchema>,
+    additional_properties: Option<Box<JsonSchema>>,
+    items: Option<Box<JsonSchema>>,
+    min_items: Option<u64>,
+    max_items: Option<u64>,
+    min_length: Option<u64>,
+    max_length: Option<u64>,
+    pattern: Option<Regex>,
+    minimum: Option<f64>,
+    maximum: Option<f64>,
+    exclusive_minimum: Option<f64>,
+    exclusive_maximum: Option<f64>,
+    all_of: Vec<JsonSchema>,
+    any_of: Vec<JsonSchema>,
+    not: Option<Box<JsonSchema>>,
+}
+
+impl JsonSchema {
+    pub fn new(schema: &Value) -> Result<Self, SchemaError> {
+        Self::compile(schema, "#")
+    }
+
+    fn compile(schema: &Value, path: &str) -> Result<Self, SchemaError> {
+        let keywords = match schema {
+            Value::Bool(accept) => {
+                return Ok(Self {
+                    reject_all: !accept,
+                    ..Default::default()
+                })
+            }
+            Value::Object(keywords) => keywords,
+            _ => return Err(invalid(path, "schema", "expected an object or a boolean")),
+        };
+
+        let mut schema = Self::default();
+        for (keyword, value) in keywords {
+            let keyword = keyword.as_str();
+            match keyword {
+                "type" => schema.types = Some(compile_types(value, path)?),
+                "enum" => {
+                    let values = value
+                        .as_array()
+                        .ok_or_else(|| invalid(path, keyword, "expected an array"))?;
+                    schema.allowed = Some(values.clone());
+                }
+                "const" => schema.allowed = Some(vec![value.clone()]),
+                "required" => {
+                    schema.required = value
+                        .as_array()
+                        .and_then(|fields| {
+                            fields
+                                .iter()
+                                .map(|field| field.as_str().map(ToOwned::to_owned))
+                                .collect()
+                        })
+                        .ok_or_else(|| invalid(path, keyword, "expected an array of strings"))?;
+                }
+                "properties" => {
+                    schema.properties = value
+                        .as_object()
+                        .ok_or_else(|| invalid(path, keyword, "expected an object"))?
+                        .iter()
+                        .map(|(name, property)| {
+                            let path = format!("{}/properties/{}", path, name);
+                            Self::compile(property, &path).map(|property| (name.clone(), property))
+                        })
+                        .collect::<Result<_, _>>()?;
+                }
+                "additionalProperties" => {
+                    let path = format!("{}/additionalProperties", path);
+                    schema.additional_properties = Some(Box::new(Self::compile(value, &path)?));
+                }
+                "items" => {
+                    let path = format!("{}/items", path);
+                    schema.items = Some(Box::new(Self::compile(value, &path)?));
+                }
+                "minItems" => schema.min_items = Some(compile_count(value, path, keyword)?),
+                "maxItems" => schema.max_items = Some(compile_count(value, path, keyword)?),
+                "minLength" => schema.min_length = Some(compile_count(value, path, keyword)?),
+                "maxLength" => schema.max_length = Some(compile_count(value, path, keyword)?),
+                "pattern" => {
+                    let pattern = value
+                        .as_str()
+                        .ok_or_else(|| invalid(path, keyword, "expected a string"))?;
+                    let pattern = Regex::new(pattern)
+                        .map_err(|error| invalid(path, keyword, &error.to_string()))?;
+                    schema.pattern = Some(pattern);
+                }
+                "minimum" => schema.minimum = Some(compile_number(value, path, keyword)?),
+                "maximum" => schema.maximum = Some(compile_number(value, path, keyword)?),
+                "exclusiveMinimum" => {
+                    schema.exclusive_minimum = Some(compile_number(value, path, keyword)?)
+                }
+                "exclusiveMaximum" => {
+                    schema.exclusive_maximum = Some(compile_number(value, path, keyword)?)
+                }
+                "allOf" => schema.all_of = compile_all(value, path, keyword)?,
+                "anyOf" => schema.any_of = compile_all(value, path, keyword)?,
+                "not" => {
+                    let path = format!("{}/not", path);
+                    schema.not = Some(Box::new(Self::compile(value, &path)?));
+                }
+                // Annotations, which don't affect validation.
+                "$schema" | "$id" | "$comment" | "title" | "description" | "default"
+                | "examples" | "format" | "deprecated" | "readOnly" | "writeOnly" => {}
+                _ => {
+                    return Err(SchemaError::UnsupportedKeyword {
+                        path: path.to_owned(),
+                        keyword: keyword.to_owned(),
+                    })
+                }
+            }
+        }
+
+        Ok(schema)
+    }
+
+    /// Returns every part of `value` that doesn't conform to the schema.
+    pub fn validate(&self, value: &Value) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        self.validate_at(value, ".", &mut violations);
+        violations
+    }
+
+    fn validate_at(&self, value: &Value, path: &str, violations: &mut Vec<Violation>) {
+        let mut violation = |message: String| {
+            violations.push(Violation {
+                path: path.to_owned(),
+                message,
+            })
+        };
+
+        if self.reject_all {
+            return violation("value isn't allowed".to_owned());
+        }
+
+        if let Some(types) = &self.types {
+            if !types.iter().any(|expected| expected.matches(value)) {
+                let expected = types
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" or ");
+                return violation(format!(
+                    "expected {}, found {}",
+                    expected,
+                    JsonType::of(value)
+                ));
+            }
+        }
+
+        if let Some(allowed) = &self.allowed {
+            if !allowed.contains(value) {
+                violation("value isn't one of the allowed values".to_owned());
+            }
+        }
+
+        match value {
+            Value::String(string) => self.validate_string(string, &mut violation),
+            Value::Number(number) => {
+                if let Some(number) = number.as_f64() {
+                    self.validate_number(number, &mut violation)
+                }
+            }
+            Value::Array(items) => self.validate_array(items, path, violations),
+            Value::Object(fields) => self.validate_object(fields, path, violations),
+            Value::Null | Value::Bool(_) => {}
+        }
+
+        for schema in &self.all_of {
+            schema.validate_at(value, path, violations);
+        }
+
+        if !self.any_of.is_empty()
+            && !self
+                .any_of
+                .iter()
+                .any(|schema| schema.validate(value).is_empty())
+        {
+            violations.push(Violation {
+                path: path.to_owned(),
+                message: "value doesn't match any of the allowed schemas".to_owned(),
+            });
+        }
+
+        if let Some(schema) = &self.not {
+            if schema.validate(value).is_empty() {
+                violations.push(Violation {
+                    path: path.to_owned(),
+                    message: "value matches a disallowed schema".to_owned(),
+                });
+            }
+        }
+    }
+
+    fn validate_string(&self, string: &str, violation: &mut impl FnMut(String)) {
+        let length = string.chars().count() as u64;
+        if let Some(min_length) = self.min_length {
+            if length < min_length {
+                violation(format!("expected at least {} characters", min_length));
+            }
+        }
+        if let Some(max_length) = self.max_length {
+            if length > max_length {
+                violation(format!("expected at most {} characters", max_length));
+            }
+        }
+        if let Some(pattern) = &self.pattern {
+            if !pattern.is_match(string) {
+                violation(format!("expected to match pattern `{}`", pattern));
+            }
+        }
+    }
+
+    fn validate_number(&self, number: f64, violation: &mut impl FnMut(String)) {
+        if let Some(minimum) = self.minimum {
+            if number < minimum {
+                violation(format!("expected at least {}", minimum));
+            }
+        }
+        if let Some(maximum) = self.maximum {
+            if number > maximum {
+                violation(format!("expected at most {}", maximum));
+            }
+        }
+        if let Some(minimum) = self.exclusive_minimum {
+            if number <= minimum {
+                violation(format!("expected more than {}", minimum));
+            }
+        }
+        if let Some(maximum) = self.exclusive_maximum {
+            if number >= maximum {
+                violation(format!("expected less than {}", maximum));
+            }
+        }
+    }
+
+    fn validate_array(&self, items: &[Value], path: &str, violations: &mut Vec<Violation>) {
+        let length = items.len() as u64;
+        if let Some(min_items) = self.min_items {
+            if length < min_items {
+                violations.push(Violation {
+                    path: path.to_owned(),
+                    message: format!("expected at least {} items", min_items),
+                });
+            }
+        }
+        if let Some(max_items) = self.max_items {
+            if length > max_items {
+                violations.push(Violation {
+                    path: path.to_owned(),
+                    message: format!("expected at most {} items", max_items),
+                });
+            }
+        }
+        if let Some(schema) = &self.items {
+            for (index, item) in items.iter().enumerate() {
+                schema.validate_at(item, &format!("{}[{}]", path, index), violations);
+            }
+        }
+    }
+
+    fn validate_object(
+        &self,
+        fields: &Map<String, Value>,
+        path: &str,
+        violations: &mut Vec<Violation>,
+    ) {
+        for field in &self.required {
+            if !fields.contains_key(field) {
+                violations.push(Violation {
+                    path: field_path(path, field),
+                    message: "required field is missing".to_owned(),
+                });
+            }
+        }
+
+        for (field, value) in fields {
+            let schema = match self.properties.get(field) {
+                Some(schema) => schema,
+                None => match &self.additional_properties {
+                    Some(schema) => schema,
+                    None => continue,
+                },
+            };
+            schema.validate_at(value, &field_path(path, field), violations);
+        }
+    }
+}
+
+fn field_path(parent: &str, field: &str) -> String {
+    let parent = parent.strip_suffix('.').unwrap_or(parent);
+    if !field.is_empty() && field.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        format!("{}.{}", parent, field)
+    } else {
+        format!("{}.{:?}", parent, field)
+    }
+}
+
+fn invalid(path: &str, keyword: &str, reason: &str) -> SchemaError {
+    SchemaError::InvalidKeyword {
+        path: path.to_owned(),
+        keyword: keyword.to_owned(),
+        reason: reason.to_owned(),
+    }
+}
+
+fn compile_types(value: &Value, path: &str) -> Result<Vec<JsonType>, SchemaError> {
+    let names = match value {
+        Value::String(name) => vec![name.as_str()],
+        Value::Array(names) => names
+            .iter()
+            .map(Value::as_str)
+            .collect::<Option<_>>()
+            .ok_or_else(|| invalid(path, "type", "expected an array of strings"))?,
+        _ => return Err(invalid(path, "type", "expected a string or an array")),
+    };
+
+    names
+        .into_iter()
+        .map(|name| {
+            JsonType::parse(name)
+                .ok_or_else(|| invalid(path, "type", &format!("unknown type `{}`", name)))
+        })
+        .collect()
+}
+
+fn compile_count(value: &Value, path: &str, keyword: &str) -> Result<u64, SchemaError> {
+    value
+        .as_u64()
+        .ok_or_else(|| invalid(path, keyword, "expected a non-negative integer"))
+}
+
+fn compile_number(value: &Value, path: &str, keyword: &str) -> Result<f64, SchemaError> {
+    value
+        .as_f64()
+        .ok_or_else(|| invalid(path, keyword, "expected a number"))
+}
+
+fn compile_all(value: &Value, path: &str, keyword: &str) -> Result<Vec<JsonSchema>, SchemaError> {
+    value
+        .as_array()
+        .ok_or_else(|| invalid(path, keyword, "expected an array"))?
+        .iter()
+        .enumerate()
+        .map(|(index, schema)| {
+            JsonSchema::compile(schema, &format!("{}/{}/{}", path, keyword, index))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn violations(schema: Value, value: Value) -> Vec<(String, String)> {
+        JsonSchema::new(&schema)
+            .unwrap()
+            .validate(&value)
+            .into_iter()
+            .map(|violation| (violation.path, violation.message))
+            .collect()
+    }
+
+    #[test]
+    fn validates_objects() {
+        let schema = json!({
+            "type": "object",
+            "required": ["message", "status"],
+            "properties": {
+                "message": { "type": "string", "minLength": 1 },
+                "status": { "type": "integer", "minimum": 100, "maximum": 599 },
+                "tags": { "type": "array", "items": { "enum": ["a", "b"] } },
+            },
+            "additionalProperties": false,
+        });
+
+        assert!(violations(
+            schema.clone(),
+            json!({ "message": "hello", "status": 200, "tags": ["a"] })
+        )
+        .is_empty());
+
+        assert_eq!(
+            violations(
+                schema,
+                json!({ "message": "", "status": 600.5, "tags": ["c"], "user name": "x" })
+            ),
+            vec![
+                (
+                    ".message".to_owned(),
+                    "expected at least 1 characters".to_owned()
+                ),
+                (
+                    ".status".to_owned(),
+                    "expected integer, found number".to_owned()
+                ),
+                (
+                    ".tags[0]".to_owned(),
+                    "value isn't one of the allowed values".to_owned()
+                ),
+                (
+                    ".\"user name\"".to_owned(),
+                    "value isn't allowed".to_owned()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn validates_combinators() {
+        let schema = json!({
+            "anyOf": [{ "type": "string", "pattern": "^[a-z]+$" }, { "type": "null" }],
+            "not": { "const": "forbidden" },
+        });
+
+        assert!(violations(schema.clone(), json!("allowed")).is_empty());
+        assert!(violations(schema.clone(), Value::Null).is_empty());
+        assert_eq!(
+            violations(schema.clone(), json!("forbidden")),
+            vec![(
+                ".".to_owned(),
+                "value matches a disallowed schema".to_owned()
+            )]
+        );
+        assert_eq!(
+            violations(schema, json!(42)),
+            vec![(
+                ".".to_owned(),
+                "value doesn't match any of the allowed schemas".to_owned()
+            )]
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_keywords() {
+        let error = JsonSchema::new(&json!({
+            "properties": { "user": { "$ref": "#/definitions/user" } },
+        }))
+        .unwrap_err();
+
+        assert_eq!(
+            error,
+            SchemaError::UnsupportedKeyword {
+                path: "#/properties/user".to_owned(),
+                keyword: "$ref".to_owned(),
+            }
+        );
+    }
+}
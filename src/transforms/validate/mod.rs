@@ -0,0 +1,245 @@
+use std::{fs, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+
+use crate::{
+    config::{
+        log_schema, ComponentKey, DataType, GenerateConfig, Input, Output, TransformConfig,
+        TransformContext, TransformDescription,
+    },
+    event::{Event, LogEvent},
+    internal_events::ValidateEventRejected,
+    schema,
+    transforms::{SyncTransform, Transform, TransformOutputsBuf},
+};
+
+mod json_schema;
+
+use json_schema::{JsonSchema, SchemaError, Violation};
+
+const REJECTED: &str = "rejected";
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ValidateConfig {
+    /// The JSON Schema events have to conform to.
+    pub schema: Option<serde_json::Value>,
+    /// The path of a file holding the JSON Schema events have to conform to.
+    pub schema_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("must provide exactly one of `schema` or `schema_file` configuration"))]
+    SchemaAndOrFile,
+    #[snafu(display("Could not read JSON Schema {:?}: {}", path, source))]
+    FileReadFailed {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("Could not parse JSON Schema {:?}: {}", path, source))]
+    FileParseFailed {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[snafu(display("Invalid JSON Schema: {}", source))]
+    InvalidSchema { source: SchemaError },
+}
+
+inventory::submit! {
+    TransformDescription::new::<ValidateConfig>("validate")
+}
+
+impl GenerateConfig for ValidateConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"schema.type = "object"
+            schema.required = ["message"]
+            schema.properties.message.type = "string""#,
+        )
+        .unwrap()
+    }
+}
+
+impl ValidateConfig {
+    fn json_schema(&self) -> Result<JsonSchema, BuildError> {
+        let schema = match (&self.schema, &self.schema_file) {
+            (Some(schema), None) => schema.clone(),
+            (None, Some(path)) => {
+                let schema = fs::read_to_string(path).context(FileReadFailedSnafu { path })?;
+                serde_json::from_str(&schema).context(FileParseFailedSnafu { path })?
+            }
+            _ => return Err(BuildError::SchemaAndOrFile),
+        };
+
+        JsonSchema::new(&schema).context(InvalidSchemaSnafu)
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "validate")]
+impl TransformConfig for ValidateConfig {
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        Ok(Transform::synchronous(Validate {
+            component_key: context.key.clone(),
+            schema: self.json_schema()?,
+        }))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        vec![
+            Output::default(DataType::Log),
+            Output::default(DataType::Log).with_port(REJECTED),
+        ]
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "validate"
+    }
+
+    fn enable_concurrency(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Validate {
+    component_key: Option<ComponentKey>,
+    schema: JsonSchema,
+}
+
+impl Validate {
+    fn violations(&self, log: &LogEvent) -> Vec<Violation> {
+        match serde_json::to_value(log.value()) {
+            Ok(value) => self.schema.validate(&value),
+            Err(error) => vec![Violation {
+                path: ".".to_owned(),
+                message: format!("event can't be represented as JSON: {}", error),
+            }],
+        }
+    }
+
+    fn annotate_rejected(&self, log: &mut LogEvent, violations: &[Violation]) {
+        log.insert(
+            format!("{}.rejected", log_schema().metadata_key()).as_str(),
+            serde_json::json!({
+                "violations": violations,
+                "component_id": self.component_key,
+                "component_type": "validate",
+                "component_kind": "transform",
+            }),
+        );
+    }
+}
+
+impl SyncTransform for Validate {
+    fn transform(&mut self, event: Event, output: &mut TransformOutputsBuf) {
+        let mut log = event.into_log();
+        let violations = self.violations(&log);
+
+        if violations.is_empty() {
+            output.push(log.into());
+        } else {
+            emit!(ValidateEventRejected {
+                violations: violations.len()
+            });
+            self.annotate_rejected(&mut log, &violations);
+            output.push_named(REJECTED, log.into());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::Value;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<ValidateConfig>();
+    }
+
+    fn validate(schema: &str) -> Validate {
+        let config = toml::from_str::<ValidateConfig>(schema).unwrap();
+        Validate {
+            component_key: Some(ComponentKey::from("validate")),
+            schema: config.json_schema().unwrap(),
+        }
+    }
+
+    fn outputs() -> TransformOutputsBuf {
+        TransformOutputsBuf::new_with_capacity(
+            vec![
+                Output::default(DataType::Log),
+                Output::default(DataType::Log).with_port(REJECTED),
+            ],
+            1,
+        )
+    }
+
+    #[test]
+    fn routes_events_by_validity() {
+        let mut validate = validate(
+            r#"
+            schema.type = "object"
+            schema.required = ["message"]
+            schema.properties.status.type = "integer"
+            "#,
+        );
+        let mut outputs = outputs();
+
+        let mut valid = LogEvent::from("valid");
+        valid.insert("status", 200);
+        validate.transform(valid.clone().into(), &mut outputs);
+
+        let mut invalid = LogEvent::default();
+        invalid.insert("status", "ok");
+        validate.transform(invalid.into(), &mut outputs);
+
+        assert_eq!(outputs.drain().collect::<Vec<_>>(), vec![valid.into()]);
+
+        let rejected = outputs.drain_named(REJECTED).collect::<Vec<_>>();
+        assert_eq!(rejected.len(), 1);
+        let rejected = rejected[0].as_log();
+        assert_eq!(rejected["status"], "ok".into());
+
+        let annotation = rejected
+            .get(format!("{}.rejected", log_schema().metadata_key()).as_str())
+            .unwrap();
+        assert_eq!(
+            *annotation,
+            Value::from(serde_json::json!({
+                "violations": [
+                    { "path": ".message", "message": "required field is missing" },
+                    { "path": ".status", "message": "expected integer, found string" },
+                ],
+                "component_id": "validate",
+                "component_type": "validate",
+                "component_kind": "transform",
+            }))
+        );
+    }
+
+    #[test]
+    fn requires_exactly_one_schema() {
+        let config = toml::from_str::<ValidateConfig>("").unwrap();
+        assert!(matches!(
+            config.json_schema(),
+            Err(BuildError::SchemaAndOrFile)
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_schema() {
+        let config = toml::from_str::<ValidateConfig>(r#"schema.type = "text""#).unwrap();
+        assert!(matches!(
+            config.json_schema(),
+            Err(BuildError::InvalidSchema { .. })
+        ));
+    }
+}
@@ -76,7 +76,7 @@ mod tests {
 
     // Initialize the metrics system.
     fn init_metrics() -> oneshot::Sender<()> {
-        vector::trace::init(true, true, "info");
+        vector::trace::init(true, true, "info", None);
         let _ = vector::metrics::init_test();
 
         let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
@@ -441,6 +441,27 @@ mod tests {
         assert_eq!(res.data.unwrap().meta.version_string, vector::get_version());
     }
 
+    #[tokio::test]
+    /// tests that the bundle meta reports the bundle the configuration was loaded from
+    async fn api_graphql_meta_bundle() {
+        let server = start_server();
+        let client = make_client(server.addr());
+
+        vector::config::bundle::set_loaded(Some(vector::config::bundle::BundleManifest {
+            name: "web".to_owned(),
+            version: "1.2.0".to_owned(),
+            description: None,
+            files: Default::default(),
+        }));
+        let res = client.meta_bundle().await.unwrap();
+        vector::config::bundle::set_loaded(None);
+
+        let bundle = res.data.unwrap().meta.bundle.unwrap();
+        assert_eq!(bundle.name, "web");
+        assert_eq!(bundle.version, "1.2.0");
+        assert_eq!(bundle.description, None);
+    }
+
     #[test]
     /// Tests that the heartbeat subscription returns a UTC payload every 1/2 second
     fn api_graphql_heartbeat() {
@@ -95,6 +95,72 @@ async fn bad_syntax() {
     );
 }
 
+#[cfg(all(feature = "sources-socket", feature = "sinks-datadog_traces"))]
+#[tokio::test]
+async fn datadog_traces_only_accepts_traces() {
+    let errors = load(
+        r#"
+        [sources.in]
+        type = "socket"
+        mode = "tcp"
+        address = "127.0.0.1:1235"
+
+        [sinks.out]
+        type = "datadog_traces"
+        inputs = ["in"]
+        default_api_key = "atoken"
+        "#,
+        Format::Toml,
+    )
+    .await
+    .unwrap_err();
+
+    assert_eq!(
+        errors,
+        vec!["Data type mismatch between in (Log) and out (Trace)"]
+    )
+}
+
+#[cfg(all(
+    feature = "sources-vector",
+    feature = "sinks-datadog_traces",
+    feature = "sinks-blackhole"
+))]
+#[tokio::test]
+async fn datadog_traces_dropped_output() {
+    let config = r#"
+        [sources.in]
+        type = "vector"
+        version = "2"
+        address = "127.0.0.1:1235"
+
+        [sinks.out]
+        type = "datadog_traces"
+        inputs = ["in"]
+        default_api_key = "atoken"
+
+        [sinks.dropped]
+        type = "blackhole"
+        inputs = ["out.dropped"]
+        "#;
+    load(config, Format::Toml).await.unwrap();
+
+    let errors = load(
+        &config.replace(
+            r#"type = "blackhole""#,
+            r#"type = "datadog_traces"
+            default_api_key = "atoken""#,
+        ),
+        Format::Toml,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        errors,
+        vec!["Data type mismatch between out.dropped (Log,Metric) and dropped (Trace)"]
+    );
+}
+
 #[cfg(all(feature = "sources-socket", feature = "sinks-socket"))]
 #[tokio::test]
 async fn missing_key() {
@@ -505,6 +571,52 @@ async fn warnings() {
     )
 }
 
+#[cfg(all(
+    feature = "sources-socket",
+    feature = "transforms-sample",
+    feature = "sinks-socket"
+))]
+#[tokio::test]
+async fn acknowledgement_warnings() {
+    let warnings = load(
+        r#"
+        acknowledgements = true
+
+        [sources.in1]
+        type = "socket"
+        mode = "tcp"
+        address = "127.0.0.1:1235"
+
+        [sources.in2]
+        type = "socket"
+        mode = "udp"
+        address = "127.0.0.1:1236"
+
+        [transforms.sample]
+        type = "sample"
+        inputs = ["in1", "in2"]
+        rate = 10
+
+        [sinks.out]
+        type = "socket"
+        mode = "tcp"
+        inputs = ["sample"]
+        encoding = "text"
+        address = "127.0.0.1:9999"
+        "#,
+        Format::Toml,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        warnings,
+        vec![
+            "Acknowledgements are enabled but not supported by sink \"out\", source \"in2\". Silent data loss could occur.",
+        ]
+    )
+}
+
 #[cfg(all(
     feature = "sources-socket",
     feature = "transforms-sample",
@@ -561,6 +673,159 @@ async fn cycle() {
     )
 }
 
+#[cfg(all(feature = "sources-socket", feature = "sinks-socket"))]
+#[tokio::test]
+async fn sandbox() {
+    let errors = load(
+        r#"
+        [sources.in]
+        type = "socket"
+        mode = "tcp"
+        address = "127.0.0.1:1235"
+        sandbox.hosts = ["127.0.0.1:1235"]
+
+        [sinks.out]
+        type = "socket"
+        mode = "tcp"
+        inputs = ["in"]
+        encoding = "text"
+        address = "127.0.0.1:9999"
+        sandbox.hosts = ["*.example.com"]
+        "#,
+        Format::Toml,
+    )
+    .await
+    .unwrap_err();
+
+    assert_eq!(
+        errors,
+        vec!["Sink \"out\": The sandbox doesn't allow access to host \"127.0.0.1:9999\"."]
+    )
+}
+
+#[cfg(all(feature = "sources-socket", feature = "sinks-blackhole"))]
+#[tokio::test]
+async fn sandbox_unsupported() {
+    let errors = load(
+        r#"
+        [sources.in]
+        type = "socket"
+        mode = "tcp"
+        address = "127.0.0.1:1235"
+        sandbox.hosts = ["127.0.0.1:1235"]
+
+        [sinks.out]
+        type = "blackhole"
+        inputs = ["in"]
+        sandbox.hosts = ["*.example.com"]
+        "#,
+        Format::Toml,
+    )
+    .await
+    .unwrap_err();
+
+    assert_eq!(
+        errors,
+        vec!["Sink \"out\": The `sandbox` option isn't supported by `blackhole` components, which aren't confined to it."]
+    )
+}
+
+#[cfg(all(feature = "sources-socket", feature = "sinks-socket"))]
+#[tokio::test]
+async fn dead_letter() {
+    let config = r#"
+        [sources.in]
+        type = "socket"
+        mode = "tcp"
+        address = "127.0.0.1:1235"
+
+        [sinks.out]
+        type = "socket"
+        mode = "tcp"
+        inputs = ["in"]
+        encoding = "text"
+        address = "127.0.0.1:9999"
+        dead_letter = "failed"
+
+        [sinks.failed]
+        type = "socket"
+        mode = "tcp"
+        encoding = "text"
+        address = "127.0.0.1:9998"
+        "#;
+    load(config, Format::Toml).await.unwrap();
+
+    let errors = load(
+        &config.replace(r#"dead_letter = "failed""#, r#"dead_letter = "in""#),
+        Format::Toml,
+    )
+    .await
+    .unwrap_err();
+
+    assert_eq!(
+        errors,
+        vec![
+            "Sink \"failed\" has no inputs",
+            "Dead letter \"in\" for sink \"out\" is a source, which can't receive events.",
+        ]
+    )
+}
+
+#[cfg(all(feature = "sources-socket", feature = "sinks-socket"))]
+#[tokio::test]
+async fn overflow_sink() {
+    let config = r#"
+        [sources.in]
+        type = "socket"
+        mode = "tcp"
+        address = "127.0.0.1:1235"
+
+        [sinks.out]
+        type = "socket"
+        mode = "tcp"
+        inputs = ["in"]
+        encoding = "text"
+        address = "127.0.0.1:9999"
+        overflow_sink = "archive"
+
+        [sinks.out.buffer]
+        max_events = 100
+        when_full = "overflow"
+
+        [sinks.archive]
+        type = "socket"
+        mode = "tcp"
+        encoding = "text"
+        address = "127.0.0.1:9998"
+        "#;
+    load(config, Format::Toml).await.unwrap();
+
+    let errors = load(
+        &config.replace(r#"when_full = "overflow""#, r#"when_full = "block""#),
+        Format::Toml,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        errors,
+        vec!["Sink \"out\" has an `overflow_sink`, but the last stage of its buffer isn't set to overflow."]
+    );
+
+    let errors = load(
+        &config.replace(r#"overflow_sink = "archive""#, r#"overflow_sink = "in""#),
+        Format::Toml,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        errors,
+        vec![
+            "Sink \"archive\" has no inputs",
+            "Overflow sink \"in\" for sink \"out\" isn't a sink.",
+        ]
+    )
+}
+
 #[cfg(all(feature = "sources-socket", feature = "sinks-socket"))]
 #[tokio::test]
 async fn disabled_healthcheck() {
@@ -12,5 +12,6 @@ cp /certs/postgres.crt /ssl/postgres.crt
     -c ssl=on \
     -c ssl_key_file=/ssl/postgres.key \
     -c ssl_cert_file=/ssl/postgres.crt \
-    -c ssl_ca_file=/ssl/postgres.crt
+    -c ssl_ca_file=/ssl/postgres.crt \
+    -c wal_level=logical
 
@@ -47,6 +47,60 @@ api: {
 				of the address set using the `bind` parameter.
 				"""
 		}
+		profiling: {
+			common:   false
+			required: false
+			type: bool: default: false
+			description: """
+				Whether CPU and allocation profiles of Vector are served by the API, under the
+				`/debug/pprof` endpoints, in the format read by [pprof](\(urls.pprof)). Sampling
+				allocations adds some overhead, so this is best enabled only while investigating
+				performance issues. Requires Vector to be built with the `api-profiling` feature,
+				on Unix platforms.
+				"""
+		}
+		dropped_event_samples: {
+			common:   false
+			required: false
+			description: """
+				Keep samples of the events components drop, for instance because they didn't
+				match a `filter` condition, were throttled, or failed to be processed or encoded,
+				so that they can be inspected with the `droppedEvents` GraphQL query. A bounded
+				reservoir of samples is kept for each component, representative of all the events
+				it dropped.
+				"""
+			type: object: options: {
+				enabled: {
+					common:      true
+					required:    false
+					description: "Whether the events components drop are sampled."
+					type: bool: default: false
+				}
+				size: {
+					common:      false
+					required:    false
+					description: "The number of samples kept for each component."
+					type: uint: {
+						default: 10
+						unit:    "events"
+					}
+				}
+				redact: {
+					common:   false
+					required: false
+					description: """
+						A [Vector Remap Language](\(urls.vrl_reference)) (VRL) program run on the
+						sampled events before they're kept, to remove sensitive data. Samples the
+						program fails on are discarded.
+						"""
+					type: string: {
+						default: null
+						examples: ["del(.password)"]
+						syntax: "remap_program"
+					}
+				}
+			}
+		}
 	}
 
 	endpoints: {
@@ -82,6 +136,36 @@ api: {
 				}
 			}
 		}
+		"/debug/pprof/profile": {
+			GET: {
+				description: """
+					Samples the CPU usage of Vector for the number of seconds set by the `seconds`
+					query parameter, 30 by default, and responds with the resulting profile. Only one
+					CPU profile can be collected at a time. Requires `profiling` to be enabled.
+					"""
+				responses: {
+					"200": {
+						description: "The gzipped CPU profile, in the pprof format."
+					}
+					"409": {
+						description: "Another CPU profile is being collected."
+					}
+				}
+			}
+		}
+		"/debug/pprof/heap": {
+			GET: {
+				description: """
+					Responds with a profile of the allocations sampled since the API started.
+					Requires `profiling` to be enabled.
+					"""
+				responses: {
+					"200": {
+						description: "The gzipped allocation profile, in the pprof format."
+					}
+				}
+			}
+		}
 		"/playground": {
 			GET: {
 				description: """
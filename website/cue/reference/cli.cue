@@ -85,6 +85,10 @@ cli: {
 	name: "vector"
 
 	flags: _default_flags & {
+		"bundle-allow-unsigned": {
+			description: env_vars.VECTOR_BUNDLE_ALLOW_UNSIGNED.description
+			env_var:     "VECTOR_BUNDLE_ALLOW_UNSIGNED"
+		}
 		"quiet": {
 			_short: "q"
 			description: """
@@ -149,6 +153,11 @@ cli: {
 			enum:        env_vars.VECTOR_COLOR.type.string.enum
 			env_var:     "VECTOR_COLOR"
 		}
+		"internal-log-file": {
+			description: env_vars.VECTOR_INTERNAL_LOG_FILE.description
+			type:        "string"
+			env_var:     "VECTOR_INTERNAL_LOG_FILE"
+		}
 		"log-format": {
 			description: env_vars.VECTOR_LOG_FORMAT.description
 			default:     env_vars.VECTOR_LOG_FORMAT.type.string.default
@@ -164,7 +173,18 @@ cli: {
 		}
 	}
 
-	options: _core_options
+	options: _core_options & {
+		"bundle": {
+			description: env_vars.VECTOR_BUNDLE.description
+			type:        "string"
+			env_var:     "VECTOR_BUNDLE"
+		}
+		"bundle-public-key": {
+			description: env_vars.VECTOR_BUNDLE_PUBLIC_KEY.description
+			type:        "string"
+			env_var:     "VECTOR_BUNDLE_PUBLIC_KEY"
+		}
+	}
 
 	commands: {
 		"graph": {
@@ -496,6 +516,38 @@ cli: {
 				examples: ["/mnt/host/sys"]
 			}
 		}
+		VECTOR_BUNDLE: {
+			description: """
+				Load a pipeline bundle: a gzipped tarball packaging configuration files, under `config/`,
+				along with the files they use, such as VRL programs and enrichment tables. Its
+				`manifest.toml` declares the name and version of the bundle and the SHA-256 checksum of
+				each of its files, which are verified before the bundle is unpacked. Each time it is
+				loaded, the bundle is unpacked to a new temporary directory only Vector can access, which
+				the configuration refers to through the `VECTOR_BUNDLE_DIR` variable. The bundle is
+				loaded again on each reload, and its name and version are reported by the API. Unless
+				unsigned bundles are allowed, the bundle must be signed with the key set by
+				`VECTOR_BUNDLE_PUBLIC_KEY`.
+				"""
+			type: string: {
+				default: null
+				examples: ["/etc/vector/pipeline.tar.gz"]
+			}
+		}
+		VECTOR_BUNDLE_PUBLIC_KEY: {
+			description: """
+				The Ed25519 public key, encoded in base64, that the bundle must be signed with. The
+				signature of its `manifest.toml` is expected in `manifest.toml.sig`, encoded in base64.
+				Required to load a bundle, unless unsigned bundles are allowed.
+				"""
+			type: string: {
+				default: null
+				examples: ["/etc/vector/bundle.pub"]
+			}
+		}
+		VECTOR_BUNDLE_ALLOW_UNSIGNED: {
+			description: "Load the bundle without verifying its signature, when no public key is set."
+			type: bool: default: false
+		}
 		VECTOR_COLOR: {
 			description: "Control when ANSI terminal formatting is used."
 			type: string: {
@@ -562,6 +614,17 @@ cli: {
 				examples: ["DEBUG", "INFO"]
 			}
 		}
+		VECTOR_INTERNAL_LOG_FILE: {
+			description: """
+				Additionally write Vector's logs, formatted as JSON, to the given file. Each line carries the
+				fields of the component that emitted it (`component_kind`, `component_id`, `component_type`)
+				in its `spans` list, and the file is written separately from the console output so it can be safely ingested by Vector.
+				"""
+			type: string: {
+				default: null
+				examples: ["/var/log/vector/internal.log"]
+			}
+		}
 		VECTOR_LOG_FORMAT: {
 			description: "Set the logging format"
 			type: string: {
@@ -558,6 +558,24 @@ components: {
 		classes: #Classes & {_args: kind: Kind}
 
 		configuration: {
+			_audit_log_interval_secs: {
+				common:      true
+				description: "The interval between polls of the API."
+				required:    false
+				type: uint: {
+					default: 60
+					unit:    "seconds"
+				}
+			}
+			_audit_log_lookback_secs: {
+				common:      false
+				description: "How far in the past collection starts, when there's no checkpoint to resume from."
+				required:    false
+				type: uint: {
+					default: 3600
+					unit:    "seconds"
+				}
+			}
 			_gcp_api_key: {
 				common:      false
 				description: "A [Google Cloud API key](\(urls.gcp_authentication_api_key)) used to authenticate access the pubsub project and topic. Either this or `credentials_path` must be set."
@@ -831,27 +849,78 @@ components: {
 				}
 			}
 
-			_http_basic_auth: {
+			_http_server_auth: {
 				common:      false
-				description: "Options for HTTP Basic Authentication."
+				description: "Options for authenticating the incoming requests. The requests that fail the authentication are rejected with a `401` status code."
 				required:    false
 				type: object: {
 					examples: []
 					options: {
+						strategy: {
+							common:      false
+							description: "The authentication strategy to use."
+							required:    false
+							type: string: {
+								default: "basic"
+								enum: {
+									basic:  "The [basic authentication scheme](\(urls.basic_auth)), where the `Authorization` header must hold the `username` and `password`."
+									bearer: "The [bearer authentication scheme](\(urls.bearer_auth)), where the `Authorization` header must hold the `token`."
+									hmac:   "The request body must be signed with HMAC-SHA256 and the `secret`, with the hex encoded signature in the `header` header, after the `prefix`."
+								}
+							}
+						}
 						username: {
-							description: "The basic authentication user name."
-							required:    true
+							description:   "The basic authentication user name."
+							relevant_when: "strategy = \"basic\""
+							required:      true
 							type: string: {
 								examples: ["${HTTP_USERNAME}", "username"]
 							}
 						}
 						password: {
-							description: "The basic authentication password."
-							required:    true
+							description:   "The basic authentication password."
+							relevant_when: "strategy = \"basic\""
+							required:      true
 							type: string: {
 								examples: ["${HTTP_PASSWORD}", "password"]
 							}
 						}
+						token: {
+							description:   "The bearer token."
+							relevant_when: "strategy = \"bearer\""
+							required:      true
+							type: string: {
+								examples: ["${HTTP_TOKEN}"]
+							}
+						}
+						secret: {
+							description:   "The secret key of the HMAC signatures."
+							relevant_when: "strategy = \"hmac\""
+							required:      true
+							type: string: {
+								examples: ["${WEBHOOK_SECRET}"]
+							}
+						}
+						header: {
+							common:        false
+							description:   "The header holding the HMAC signature."
+							relevant_when: "strategy = \"hmac\""
+							required:      false
+							type: string: {
+								default: "X-Signature"
+								examples: ["X-Hub-Signature-256"]
+							}
+						}
+						prefix: {
+							common:        false
+							description:   "The prefix of the HMAC signature in the header, which is stripped before verifying it."
+							relevant_when: "strategy = \"hmac\""
+							required:      false
+							type: string: {
+								default: ""
+								examples: ["sha256="]
+							}
+						}
 					}
 				}
 			}
@@ -911,6 +980,46 @@ components: {
 				}
 			}
 
+			sandbox: {
+				common:      false
+				description: """
+					Restricts the paths the component can access and the hosts it can connect to or listen on.
+					The paths and hosts the component is configured to access are checked against its sandbox when
+					the configuration is loaded, and the sandbox is enforced at runtime by the HTTP client, the
+					socket sinks, and the `file` sink, which report the accesses it denies in the
+					`sandbox_violations_total` metric. Only the components confined to their sandbox support
+					this option, which is rejected for the others: the `file` and `socket` sources, the `geoip`
+					and `remap` transforms, and the `datadog_logs`, `file`, `http`, `postgres`, `questdb`, and
+					`socket` sinks, as well as the `balance` and `failover` sinks when all their targets support
+					it. Paths are resolved, following symbolic links, before
+					they are checked. The sandbox is advisory beyond that: it doesn't restrict the system calls
+					of the process, so it doesn't replace the isolation provided by the operating system.
+					"""
+				required:    false
+				type: object: options: {
+					paths: {
+						description: "The paths the component can access, including everything under them. Any path is allowed when unset."
+						required:    false
+						type: array: {
+							default: null
+							items: type: string: examples: ["/var/log/app", "./data"]
+						}
+					}
+					hosts: {
+						description: """
+							The hosts the component can connect to or listen on, as `host` to allow any of its ports or
+							`host:port`. The host can be `*` to allow any host, or start with `*.` to allow its
+							subdomains. Any host is allowed when unset.
+							"""
+						required: false
+						type: array: {
+							default: null
+							items: type: string: examples: ["api.example.com:443", "*.datadoghq.com", "*:514", "[::1]:9000"]
+						}
+					}
+				}
+			}
+
 			"type": {
 				description: "The component type. This is a required field for all components and tells Vector which component to use."
 				required:    true
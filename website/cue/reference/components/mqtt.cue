@@ -0,0 +1,115 @@
+package metadata
+
+components: _mqtt: {
+	features: {
+		collect: from: {
+			service: services.mqtt
+			interface: {
+				socket: {
+					api: {
+						title: "MQTT 3.1.1"
+						url:   urls.mqtt_spec
+					}
+					direction: "incoming"
+					port:      1883
+					protocols: ["tcp"]
+					ssl: "optional"
+				}
+			}
+		}
+
+		send: to: {
+			service: services.mqtt
+			interface: {
+				socket: {
+					api: {
+						title: "MQTT 3.1.1"
+						url:   urls.mqtt_spec
+					}
+					direction: "outgoing"
+					protocols: ["tcp"]
+					ssl: "optional"
+				}
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		notices: [
+			"""
+				Vector speaks version 3.1.1 of the protocol, which MQTT 5 brokers accept
+				connections with as well.
+				""",
+		]
+		warnings: []
+	}
+
+	configuration: {
+		client_id: {
+			common:      false
+			description: "The ID of the client, which must be unique among the clients of the broker. A random one is generated when unset, which loses the session of the client when Vector restarts."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["vector-1"]
+			}
+		}
+		host: {
+			description: "The host of the broker."
+			required:    true
+			type: string: {
+				examples: ["127.0.0.1", "mqtt.example.com"]
+			}
+		}
+		keep_alive_secs: {
+			common:      false
+			description: "The interval at which the client pings the broker when idle, for the broker to detect its disconnection."
+			required:    false
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		password: {
+			common:      false
+			description: "The password the client authenticates with, which requires `user` to be set."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["${MQTT_PASSWORD}"]
+			}
+		}
+		port: {
+			common:      true
+			description: "The port of the broker."
+			required:    false
+			type: uint: {
+				default: 1883
+				examples: [1883, 8883]
+				unit: null
+			}
+		}
+		qos: {
+			common:      true
+			description: "The [quality of service](\(urls.mqtt_qos)) messages are delivered with."
+			required:    false
+			type: string: {
+				default: "at_least_once"
+				enum: {
+					at_most_once:  "Messages are delivered at most once, without being acknowledged (QoS 0)."
+					at_least_once: "Messages are delivered until they're acknowledged (QoS 1)."
+				}
+			}
+		}
+		user: {
+			common:      false
+			description: "The user the client authenticates as."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["vector"]
+			}
+		}
+	}
+}
@@ -74,6 +74,46 @@ components: sinks: [Name=string]: {
 			type: object: {
 				examples: []
 				options: {
+					encryption: {
+						common:      false
+						description: """
+							Encrypts the records written to the buffer on disk with AES-256-GCM.
+
+							Records written before encryption was enabled remain readable. Keys should be sourced from a
+							secrets backend with `SECRET[<backend>.<key>]` rather than written into the configuration.
+							"""
+						required:      false
+						relevant_when: "type = \"disk\""
+						type: object: {
+							examples: []
+							options: {
+								key: {
+									description: "The base64-encoded 256-bit key used to encrypt new records."
+									required:    true
+									type: string: {
+										examples: ["SECRET[backend.buffer_key]"]
+									}
+								}
+								previous_keys: {
+									common:      false
+									description: """
+										Base64-encoded 256-bit keys that records were previously encrypted with.
+
+										When rotating keys, move the old key here so that records already in the buffer can still be
+										read. It can be removed once those records have been sent. Vector drops the records encrypted
+										with a key that is no longer configured, reporting their events as corrupted.
+										"""
+									required: false
+									type: array: {
+										default: []
+										items: type: string: {
+											examples: ["SECRET[backend.previous_buffer_key]"]
+										}
+									}
+								}
+							}
+						}
+					}
 					max_events: {
 						common:        true
 						description:   "The maximum number of [events](\(urls.vector_data_model)) allowed in the buffer."
@@ -85,8 +125,12 @@ components: sinks: [Name=string]: {
 						}
 					}
 					max_size: {
-						description:   "The maximum size of the buffer on the disk."
-						required:      true
+						description: """
+							The maximum size of the buffer on the disk. Required unless the global
+							[`disk_buffer_max_size`](\(urls.vector_configuration_global)#disk_buffer_max_size)
+							is set, in which case it defaults to, and is capped by, the buffer's share of it.
+							"""
+						required:      false
 						relevant_when: "type = \"disk\""
 						type: uint: {
 							examples: [104900000]
@@ -120,6 +164,10 @@ components: sinks: [Name=string]: {
 							enum: {
 								block:       "Applies back pressure when the buffer is full. This prevents data loss, but will cause data to pile up on the edge."
 								drop_newest: "Drops new data as it's received. This data is lost. This should be used when performance is the highest priority."
+								overflow: """
+									Sends the data that doesn't fit in the buffer to the next stage of the buffer or, for its last
+									stage, to the sink set in `overflow_sink`.
+									"""
 							}
 						}
 					}
@@ -127,6 +175,86 @@ components: sinks: [Name=string]: {
 			}
 		}
 
+		check_sequence_numbers: {
+			common:      false
+			description: """
+				Whether to check the sequence numbers of the events received, for the sources with
+				`sequence_numbers` enabled. Events missing from the sequence of a source, or received out of
+				its order, are logged and counted in the `sequence_missing_events_total` and
+				`sequence_reordered_events_total` metrics. Events dropped or aggregated by transforms between
+				the source and the sink are reported as missing too.
+				"""
+			required:    false
+			type: bool: default: false
+		}
+
+		dead_letter: {
+			common:      false
+			description: """
+				The ID of a transform or sink the events this sink failed to deliver are routed to, instead
+				of being dropped. Events are routed once the sink gives up on them, after exhausting its
+				retries or being rejected with a permanent error, and their acknowledgement is then left
+				to the dead letter component. The delivery of each event is tracked, so only the events
+				the sink failed to deliver are routed. At most 100,000 events of the sink are tracked at
+				once, beyond which the sink receives its events untracked rather than waiting, and the
+				events among these it fails to deliver aren't routed. Some sinks also stop retrying the
+				requests they could only deliver later, such as throttled ones, when they have a dead
+				letter.
+				"""
+			required:    false
+			type: string: {
+				default: null
+				examples: ["failed_deliveries"]
+			}
+		}
+
+		disk_buffer_weight: {
+			common:      false
+			description: """
+				The weight of the sink in the allocation of the global
+				[`disk_buffer_max_size`](\(urls.vector_configuration_global)#disk_buffer_max_size): each sink
+				with disk buffers gets a share of it proportional to its weight. Only sinks with a disk buffer
+				support this option, and only when `disk_buffer_max_size` is set.
+				"""
+			required:    false
+			type: uint: {
+				default: 1
+				unit:    null
+			}
+		}
+
+		egress_bytes_per_sec: {
+			common:      false
+			description: """
+				The maximum number of bytes per second the sink sends over HTTP, on top of the global
+				`egress_bytes_per_sec` limit. The bytes of the request bodies are counted as they are
+				sent, and new requests are held back once the bytes sent run more than a second ahead of
+				the rate. Requests aren't timed out while held back. Only sinks sending their events over
+				HTTP support this option, and Vector refuses to start other sinks with it.
+				"""
+			required:    false
+			type: uint: {
+				default: null
+				examples: [100_000]
+				unit: "bytes"
+			}
+		}
+
+		overflow_sink: {
+			common:      false
+			description: """
+				The ID of a sink the events that don't fit in the buffer of this sink are routed to, such as
+				an archive in object storage, so that they're kept somewhere durable while this sink is down
+				for a long period. Requires the last stage of the buffer to have `when_full` set to
+				`overflow`. The acknowledgement of routed events is left to the overflow sink.
+				"""
+			required:    false
+			type: string: {
+				default: null
+				examples: ["archive"]
+			}
+		}
+
 		if features.send != _|_ {
 			if features.send.compression.enabled {
 				compression: {
@@ -331,6 +459,64 @@ components: sinks: [Name=string]: {
 									}
 								}
 							}
+							circuit_breaker: {
+								common:      false
+								description: """
+									Configure the circuit breaker, which holds back the requests to a service that keeps failing.
+									Once opened, no requests are sent until `open_secs` elapsed, and the sink stops consuming
+									events, applying backpressure to its inputs. The requests sent next are probes of the service,
+									closing the circuit again if they succeed, or opening it for another `open_secs` if one fails.
+									"""
+								required:    false
+								type: object: {
+									examples: []
+									options: {
+										enabled: {
+											common:      false
+											description: "Whether to hold back the requests while the service keeps failing."
+											required:    false
+											type: bool: default: false
+										}
+										failure_threshold: {
+											common:      false
+											description: "The number of consecutive failed requests opening the circuit. Only the failures that would be retried count, along with the requests that timed out."
+											required:    false
+											type: uint: {
+												default: 5
+												unit:    "requests"
+											}
+										}
+										half_open_requests: {
+											common:      false
+											description: "The number of probe requests sent once `open_secs` elapsed, which all have to succeed to close the circuit."
+											required:    false
+											type: uint: {
+												default: 1
+												unit:    "requests"
+											}
+										}
+										latency_threshold_secs: {
+											common:      false
+											description: "Requests taking longer than this are counted as failed, even when they succeed."
+											required:    false
+											type: float: {
+												default: null
+												examples: [2.5]
+												unit: "seconds"
+											}
+										}
+										open_secs: {
+											common:      false
+											description: "How long the circuit stays open before probing the service again."
+											required:    false
+											type: uint: {
+												default: 30
+												unit:    "seconds"
+											}
+										}
+									}
+								}
+							}
 							concurrency: {
 								common: true
 								if features.send.request.adaptive_concurrency {
@@ -607,6 +793,21 @@ components: sinks: [Name=string]: {
 								```
 								"""
 						},
+						{
+							title: "Inspecting and overriding concurrency limits"
+							body: """
+								When the API is enabled with the `api` option, the current concurrency limit of each
+								running sink can be inspected with the `concurrencyLimits` GraphQL query, and
+								overridden for live tuning with the `overrideConcurrencyLimit` mutation. An
+								overridden limit is no longer adjusted until the override is lifted, by omitting
+								its `limit`, or until the sink is reloaded.
+
+								Sinks sending the requests of each partition with their own service, such as the
+								`datadog_logs` and `datadog_traces` sinks for each API key and the `loki` sink for
+								each tenant, have a limit for each partition, which can be overridden separately.
+								The partitions of API keys are named after the last four characters of the key.
+								"""
+						},
 						{
 							title: "Rate limits"
 							body: """
@@ -664,5 +865,15 @@ components: sinks: [Name=string]: {
 		buffer_sent_events_total:             components.sources.internal_metrics.output.metrics.buffer_sent_events_total
 		buffer_sent_event_bytes_total:        components.sources.internal_metrics.output.metrics.buffer_sent_event_bytes_total
 		buffer_discarded_events_total:        components.sources.internal_metrics.output.metrics.buffer_discarded_events_total
+		circuit_breaker_opened_total:         components.sources.internal_metrics.output.metrics.circuit_breaker_opened_total
+		circuit_breaker_state:                components.sources.internal_metrics.output.metrics.circuit_breaker_state
+		dead_letter_events_total:             components.sources.internal_metrics.output.metrics.dead_letter_events_total
+		dead_letter_untracked_events_total:   components.sources.internal_metrics.output.metrics.dead_letter_untracked_events_total
+		overflow_sink_events_total:           components.sources.internal_metrics.output.metrics.overflow_sink_events_total
+		egress_rate_limited_total:            components.sources.internal_metrics.output.metrics.egress_rate_limited_total
+		egress_rate_limit_delay_seconds:      components.sources.internal_metrics.output.metrics.egress_rate_limit_delay_seconds
+		sequence_gaps_total:                  components.sources.internal_metrics.output.metrics.sequence_gaps_total
+		sequence_missing_events_total:        components.sources.internal_metrics.output.metrics.sequence_missing_events_total
+		sequence_reordered_events_total:      components.sources.internal_metrics.output.metrics.sequence_reordered_events_total
 	}
 }
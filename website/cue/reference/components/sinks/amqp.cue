@@ -0,0 +1,207 @@
+package metadata
+
+components: sinks: amqp: {
+	title: "AMQP"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: true
+		healthcheck: enabled: true
+		send: {
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: {
+					enabled: true
+					enum: ["json", "text"]
+				}
+			}
+			request: enabled: false
+			tls: {
+				enabled:                true
+				can_verify_certificate: false
+				can_verify_hostname:    false
+				enabled_default:        false
+			}
+			to: components._amqp.features.send.to
+		}
+	}
+
+	support: components._amqp.support
+
+	configuration: {
+		connection_string: components._amqp.configuration.connection_string
+		exchange: {
+			description: "The exchange messages are published to, which must already exist. The empty string is the default exchange, which routes messages to the queue their routing key names."
+			required:    true
+			type: string: {
+				examples: ["logs", "logs-{{ environment }}", ""]
+				syntax: "template"
+			}
+		}
+		properties: {
+			common:      false
+			description: "The [properties](\(urls.amqp_protocol)#class.basic) of the messages, which are rendered from each event. The properties that fail to render are left out."
+			required:    false
+			type: object: options: {
+				app_id: {
+					common:      false
+					description: "The ID of the application that published the message."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["vector"]
+						syntax: "template"
+					}
+				}
+				content_encoding: {
+					common:      false
+					description: "The encoding of the message, such as `gzip`."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["gzip"]
+						syntax: "template"
+					}
+				}
+				content_type: {
+					common:      true
+					description: "The MIME type of the message."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["application/json", "text/plain"]
+						syntax: "template"
+					}
+				}
+				correlation_id: {
+					common:      false
+					description: "The ID correlating the message to another one, such as the request it responds to."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["{{ request_id }}"]
+						syntax: "template"
+					}
+				}
+				expiration: {
+					common:      false
+					description: "The time, in milliseconds, the message can stay in a queue before being discarded."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["60000"]
+						syntax: "template"
+					}
+				}
+				headers: {
+					common:      false
+					description: "The headers of the message, as string values."
+					required:    false
+					type: object: {
+						examples: [{service: "{{ service }}"}]
+						options: {}
+					}
+				}
+				message_id: {
+					common:      false
+					description: "The ID of the message."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["{{ id }}"]
+						syntax: "template"
+					}
+				}
+				persistent: {
+					common:      true
+					description: "Whether messages are persisted by the server, which keeps them across its restarts when their queues are durable."
+					required:    false
+					type: bool: default: true
+				}
+				priority: {
+					common:      false
+					description: "The priority of the message, which queues with a maximum priority deliver the messages of higher priority first."
+					required:    false
+					type: uint: {
+						default: null
+						examples: [5]
+						unit: null
+					}
+				}
+				reply_to: {
+					common:      false
+					description: "The queue responses to the message should be sent to."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["responses"]
+						syntax: "template"
+					}
+				}
+				type: {
+					common:      false
+					description: "The type of the message."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["{{ event_type }}"]
+						syntax: "template"
+					}
+				}
+			}
+		}
+		routing_key: {
+			common:      true
+			description: "The routing key messages are published with, which the exchange routes them to queues by. Messages are published with an empty routing key when unset."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["{{ service }}", "logs.{{ level }}"]
+				syntax: "template"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	how_it_works: {
+		publisher_confirms: {
+			title: "Publisher confirms"
+			body: """
+				Messages are published on a channel in [confirm mode](\(urls.rabbitmq_confirms)), so
+				that the server confirms each of them once it's been routed to its queues, and
+				persisted by them when it's persistent. Events are only considered delivered once
+				their message is confirmed, and those whose message is negatively confirmed, or
+				fails to be published, are reported as failed to their sources. Vector reconnects
+				to the server, with an exponential backoff, when publishing fails.
+				"""
+		}
+		timestamp: {
+			title: "Timestamp"
+			body: """
+				The `timestamp` property of each message is set to the timestamp of its event, when
+				it has one.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:           components.sources.internal_metrics.output.metrics.component_errors_total
+		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+	}
+}
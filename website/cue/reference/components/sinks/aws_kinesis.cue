@@ -0,0 +1,50 @@
+package metadata
+
+components: sinks: _aws_kinesis: {
+	configuration: aggregation: {
+		category:    "Aggregation"
+		common:      false
+		description: "Aggregates the events in records of the [KPL format](\(urls.aws_kinesis_kpl_aggregation)), so that many small events fit in a single Kinesis record."
+		required:    false
+		type: object: {
+			examples: [{max_records: 1000, max_bytes: 51200}]
+			options: {
+				max_records: {
+					common:      true
+					description: "The maximum number of events aggregated in a single record."
+					required:    false
+					type: uint: {
+						default: 1000
+						unit:    "events"
+					}
+				}
+				max_bytes: {
+					common:      true
+					description: "The maximum size of the events aggregated in a single record, as measured for the batches."
+					required:    false
+					type: uint: {
+						default: 51200
+						unit:    "bytes"
+					}
+				}
+			}
+		}
+	}
+
+	how_it_works: aggregation: {
+		title: "Record aggregation"
+		body: """
+			When `aggregation` is set, the events are aggregated in records of the
+			[Kinesis Producer Library (KPL) format](\(urls.aws_kinesis_kpl_aggregation)) before
+			being batched: each record holds up to `aggregation.max_records` events, or
+			`aggregation.max_bytes` bytes of them, along with their partition keys and the MD5
+			digest of the aggregated events. This increases the throughput of the streams, whose
+			limits are per record, when the events are small.
+
+			The records must be de-aggregated by their consumers, which the consumers built with
+			the Kinesis Client Library do transparently, and the de-aggregation modules of the
+			[Kinesis Aggregation library](\(urls.aws_kinesis_aggregation_library)) support. The
+			records holding a single event are sent as they are, which these consumers pass through.
+			"""
+	}
+}
@@ -72,6 +72,7 @@ components: sinks: aws_kinesis_firehose: components._aws & {
 	}
 
 	configuration: {
+		aggregation: sinks._aws_kinesis.configuration.aggregation
 		stream_name: {
 			description: "The [stream name](\(urls.aws_cloudwatch_logs_stream_name)) of the target Kinesis Firehose delivery stream."
 			required:    true
@@ -87,6 +88,10 @@ components: sinks: aws_kinesis_firehose: components._aws & {
 		traces:  false
 	}
 
+	how_it_works: {
+		aggregation: sinks._aws_kinesis.how_it_works.aggregation
+	}
+
 	permissions: iam: [
 		{
 			platform: "aws"
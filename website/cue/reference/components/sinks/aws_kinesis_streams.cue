@@ -72,6 +72,17 @@ components: sinks: aws_kinesis_streams: components._aws & {
 	}
 
 	configuration: {
+		aggregation: sinks._aws_kinesis.configuration.aggregation
+		partition_key: {
+			common:      false
+			description: "The template rendered for each event to get the Kinesis record's partition key value. Can't be set along with `partition_key_field`."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["{{ user_id }}", "{{ host }}-{{ service }}"]
+				syntax: "template"
+			}
+		}
 		partition_key_field: {
 			common:      true
 			description: "The log field used as the Kinesis record's partition key value."
@@ -97,6 +108,23 @@ components: sinks: aws_kinesis_streams: components._aws & {
 	}
 
 	how_it_works: {
+		aggregation: {
+			title: sinks._aws_kinesis.how_it_works.aggregation.title
+			body:  sinks._aws_kinesis.how_it_works.aggregation.body
+			sub_sections: [
+				{
+					title: "Aggregation and partition keys"
+					body:  """
+						When the partition keys are set with the `partition_key_field` or
+						`partition_key` options, the events are only aggregated with the events of
+						the same partition key, so that they're still sent to the same shard. The
+						events with random partition keys are aggregated together, and sent with
+						the partition key of the first of them.
+						"""
+				},
+			]
+		}
+
 		partitioning: {
 			title: "Partitioning"
 			body:  """
@@ -108,7 +136,9 @@ components: sinks: aws_kinesis_streams: components._aws & {
 				presents an alternate field on your event to use as the partition key value instead.
 				This is useful if you have a field already on your event, and it also pairs
 				nicely with the [`remap` transform](\(urls.vector_remap_transform)), which enables you
-				to add partition-related metadata to events.
+				to add partition-related metadata to events. The `partition_key` option can be
+				used instead to render the partition key from a template, combining several fields
+				of the events for instance.
 				"""
 			sub_sections: [
 				{
@@ -118,7 +148,7 @@ components: sinks: aws_kinesis_streams: components._aws & {
 						value is blank, the event is dropped and a
 						[`warning`-level log event](\(urls.vector_monitoring)) is logged. The field
 						specified in the `partition_key_field` option should thus always contain a
-						value.
+						value, as should the fields of the `partition_key` template.
 						"""
 				},
 				{
@@ -194,6 +194,105 @@ components: sinks: aws_s3: components._aws & {
 				syntax: "template"
 			}
 		}
+		manifest: sinks._object_store.configuration.manifest
+		object_lock_legal_hold: {
+			category:    "Object Lock"
+			common:      false
+			description: "Places a [legal hold](\(urls.aws_s3_object_lock)) on the created objects, which can't be deleted until it's removed. Requires a bucket with S3 Object Lock enabled."
+			required:    false
+			type: bool: default: false
+		}
+		object_lock_mode: {
+			category:    "Object Lock"
+			common:      false
+			description: "The [S3 Object Lock](\(urls.aws_s3_object_lock)) retention mode of the created objects. Must be set along with `object_lock_retain_days`, and requires a bucket with S3 Object Lock enabled."
+			required:    false
+			type: string: {
+				default: null
+				enum: {
+					GOVERNANCE: "The objects can't be deleted or overwritten during their retention, except by users with the `s3:BypassGovernanceRetention` permission."
+					COMPLIANCE: "The objects can't be deleted or overwritten by any user during their retention, including the root user."
+				}
+			}
+		}
+		object_lock_retain_days: {
+			category:    "Object Lock"
+			common:      false
+			description: "The number of days the created objects are retained for, from the time they're written. Must be set along with `object_lock_mode`."
+			required:    false
+			type: uint: {
+				default: null
+				examples: [30, 365]
+				unit: "days"
+			}
+		}
+		parquet: {
+			common:      false
+			description: """
+				Write each object as a [Parquet](\(urls.parquet)) file, instead of encoding its events with
+				`encoding.codec`. The `compression` option doesn't apply to Parquet files, which are
+				compressed by pages, and objects are given the `parquet` extension by default.
+				"""
+			required: false
+			type: object: options: {
+				schema: {
+					common:      false
+					description: """
+						The columns of the files. When unset, the columns are derived from the schema of the
+						events the sink receives, which requires schema support to be enabled.
+						"""
+					required: false
+					type: array: {
+						default: null
+						items: type: object: options: {
+							name: {
+								description: "The name of the column, which is also the path of the field its value is read from."
+								required:    true
+								type: string: examples: ["message", "http.status"]
+							}
+							type: {
+								description: """
+									The type of the column. Missing fields, and values that don't match the type of
+									their column, are written as nulls.
+									"""
+								required: true
+								type: string: enum: {
+									string:    "UTF-8 strings. Other values are converted to strings."
+									int64:     "64-bit signed integers."
+									double:    "Double precision floats. Integers are converted to floats."
+									boolean:   "Booleans."
+									timestamp: "Timestamps, with a microsecond precision."
+									json:      "Any value, encoded as a JSON string."
+								}
+							}
+						}
+					}
+				}
+				row_group_size: {
+					common:      false
+					description: "The maximum number of events in each row group of the files."
+					required:    false
+					type: uint: {
+						default: 10000
+						unit:    "events"
+					}
+				}
+				compression: {
+					common:      false
+					description: "The compression of the pages of the files."
+					required:    false
+					type: string: {
+						default: "snappy"
+						enum: {
+							none:   "No compression."
+							snappy: "[Snappy](\(urls.snappy)) compression."
+							gzip:   "[Gzip](\(urls.gzip)) compression."
+							zstd:   "[Zstandard](\(urls.zstd)) compression."
+						}
+					}
+				}
+			}
+		}
 		server_side_encryption: {
 			category:    "Encryption"
 			common:      false
@@ -210,11 +309,12 @@ components: sinks: aws_s3: components._aws & {
 		ssekms_key_id: {
 			category:    "Encryption"
 			common:      false
-			description: "If `server_side_encryption` has the value `\"aws.kms\"`, this specifies the ID of the AWS Key Management Service (AWS KMS) symmetrical customer managed customer master key (CMK) that will used for the created objects. If not specified, Amazon S3 uses the AWS managed CMK in AWS to protect the data."
+			description: "If `server_side_encryption` has the value `\"aws.kms\"`, this specifies the ID of the AWS Key Management Service (AWS KMS) symmetrical customer managed customer master key (CMK) that will used for the created objects. If not specified, Amazon S3 uses the AWS managed CMK in AWS to protect the data. When templated, the events are partitioned by key, so that each object is encrypted with the key of its events."
 			required:    false
 			type: string: {
 				default: null
-				examples: ["abcd1234"]
+				examples: ["abcd1234", "alias/{{ tenant }}"]
+				syntax: "template"
 			}
 		}
 		storage_class: {
@@ -231,6 +331,7 @@ components: sinks: aws_s3: components._aws & {
 					STANDARD_IA:         "Amazon S3 stores the object data redundantly across multiple geographically separated Availability Zones (similar to the STANDARD storage class)."
 					ONEZONE_IA:          "Amazon S3 stores the object data in only one Availability Zone."
 					GLACIER:             "Use for archives where portions of the data might need to be retrieved in minutes."
+					GLACIER_IR:          "Use for archives that are rarely accessed, but need to be retrieved in milliseconds."
 					DEEP_ARCHIVE:        "Use for archiving data that rarely needs to be accessed."
 				}
 			}
@@ -253,6 +354,8 @@ components: sinks: aws_s3: components._aws & {
 	}
 
 	how_it_works: {
+		manifests: sinks._object_store.how_it_works.manifests
+
 		cross_account: {
 			title: "Cross account object writing"
 			body:  """
@@ -286,6 +389,25 @@ components: sinks: aws_s3: components._aws & {
 			]
 		}
 
+		object_finalization: {
+			title: "Object finalization"
+			body:  """
+				The events are grouped by partition, the rendered [`key_prefix`](#key_prefix), and
+				each partition has its own batch: an object is written once its batch reaches
+				`batch.max_events` events or `batch.max_bytes` bytes, or `batch.timeout_secs` seconds
+				after its first event was received, whichever happens first. A `key_prefix` such as
+				`date=%F/hour=%H/` with a `batch.timeout_secs` of `300` thus writes at least one
+				object per hour, and objects at most five minutes apart for each hour.
+
+				The `batch.max_bytes` limit applies to the events in memory, before they're encoded
+				and compressed, so the objects written are usually smaller.
+
+				Batch loaders waiting for complete partitions can rely on the `success` format of
+				the [manifests](#manifests), which writes a `_SUCCESS` marker to each partition once
+				no object has been added to it for a while.
+				"""
+		}
+
 		object_naming: {
 			title: "Object naming"
 			body:  """
@@ -334,6 +456,20 @@ components: sinks: aws_s3: components._aws & {
 				"""
 		}
 
+		object_lock: {
+			title: "Object Lock"
+			body:  """
+				AWS S3 can protect objects from being deleted or overwritten with
+				[S3 Object Lock](\(urls.aws_s3_object_lock)), on buckets created with it enabled.
+				Vector can set the retention of each object it writes, with the `object_lock_mode`
+				and `object_lock_retain_days` options, and place a legal hold on them with the
+				`object_lock_legal_hold` option. The retention of each object starts when it's
+				written, and the bucket's default retention applies when these options aren't set.
+
+				The manifests, when enabled, are written with the same retention as the objects.
+				"""
+		}
+
 		server_side_encryption: {
 			title: "Server-Side Encryption (SSE)"
 			body:  """
@@ -343,6 +479,10 @@ components: sinks: aws_s3: components._aws & {
 				buckets). Although, we recommend setting defaults at the bucket level when
 				possible. You can explicitly set the object level encryption via the
 				`server_side_encryption` option.
+
+				The `ssekms_key_id` option can be templated to encrypt the objects with a KMS key
+				per tenant, for instance. The events are then partitioned by KMS key as well as by
+				key prefix, and each object is encrypted with the key rendered from its events.
 				"""
 		}
 
@@ -354,6 +494,9 @@ components: sinks: aws_s3: components._aws & {
 				level. In the context of Vector only the object level is relevant (Vector does
 				not create or modify buckets). You can set the storage class via the
 				`storage_class` option.
+
+				The manifests, when enabled, are always written with the `STANDARD` storage class,
+				so that they can be read as soon as they're written.
 				"""
 		}
 	}
@@ -373,6 +516,14 @@ components: sinks: aws_s3: components._aws & {
 				{
 					_action: "PutObject"
 				},
+				{
+					_action:       "PutObjectLegalHold"
+					required_when: "[`object_lock_legal_hold`](#object_lock_legal_hold) is set"
+				},
+				{
+					_action:       "PutObjectRetention"
+					required_when: "[`object_lock_mode`](#object_lock_mode) is set"
+				},
 			]
 		},
 	]
@@ -0,0 +1,176 @@
+package metadata
+
+components: sinks: aws_sns: components._aws & {
+	title: "Amazon Simple Notification Service (SNS)"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: ["AWS"]
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    262144
+				max_events:   10
+				timeout_secs: 1.0
+			}
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: {
+					enabled: true
+					enum: ["json", "text"]
+				}
+			}
+			proxy: enabled: true
+			request: {
+				enabled:                    true
+				rate_limit_duration_secs:   1
+				rate_limit_num:             5
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    10
+				timeout_secs:               30
+				headers:                    false
+			}
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.aws_sns
+
+				interface: {
+					socket: {
+						api: {
+							title: "Amazon Simple Notification Service API"
+							url:   urls.aws_sns_api
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		topic_arn: {
+			description: "The ARN of the Amazon SNS topic to which messages are published."
+			required:    true
+			type: string: {
+				examples: ["arn:aws:sns:us-east-2:123456789012:MyTopic"]
+			}
+		}
+		message_attributes: {
+			common:      false
+			description: """
+				The [message attributes](\(urls.aws_sns_message_attributes)) set on each message, rendered from
+				the event. At most 10 attributes can be set. The attributes which can't be rendered, or are
+				rendered empty, are left out of the message.
+				"""
+			required:    false
+			type: object: {
+				examples: [{"host": "{{ host }}", "application": "{{ application }}"}]
+				options: {
+					"*": {
+						common:      false
+						description: "The template rendered as the value of the attribute, of the `String` data type."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["{{ host }}"]
+							syntax: "template"
+						}
+					}
+				}
+			}
+		}
+		message_group_id: {
+			common:      false
+			description: "The tag that specifies that a message belongs to a specific message group. Required by, and only allowed with, [FIFO topics](\(urls.aws_sns_fifo))."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["vector", "vector-%Y-%m-%d"]
+				syntax: "template"
+			}
+		}
+		message_deduplication_id: {
+			common:      false
+			description: """
+				The message deduplication ID value to allow AWS to identify duplicate messages published to FIFO
+				topics. This value is a template which should result in a unique string for each event.
+				"""
+			required:    false
+			type: string: {
+				default: null
+				examples: ["{{ transaction_id }}"]
+				syntax: "template"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	how_it_works: {
+		batching: {
+			title: "Batching"
+			body:  """
+				The messages are published with the [`PublishBatch`](\(urls.aws_sns_publish_batch)) API, in
+				batches of up to 10 messages. When some of the messages of a batch are rejected, the batch is
+				only sent again if none of its messages were accepted, and the rejections aren't caused by
+				the messages themselves, so that the messages are never published twice.
+				"""
+		}
+	}
+
+	permissions: iam: [
+		{
+			platform:  "aws"
+			_service:      "sns"
+			_url_fragment: "api"
+
+			policies: [
+				{
+					_action: "GetTopicAttributes"
+					required_for: ["healthcheck"]
+				},
+				{
+					_action: "Publish"
+				},
+			]
+		},
+	]
+
+	telemetry: metrics: {
+		component_errors_total:           components.sources.internal_metrics.output.metrics.component_errors_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		events_discarded_total:           components.sources.internal_metrics.output.metrics.events_discarded_total
+		processed_bytes_total:            components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total:           components.sources.internal_metrics.output.metrics.processed_events_total
+		processing_errors_total:          components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+}
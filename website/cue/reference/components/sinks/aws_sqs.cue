@@ -7,7 +7,7 @@ components: sinks: aws_sqs: components._aws & {
 		commonly_used: false
 		delivery:      "at_least_once"
 		development:   "beta"
-		egress_method: "stream"
+		egress_method: "batch"
 		service_providers: ["AWS"]
 		stateful: false
 	}
@@ -16,6 +16,13 @@ components: sinks: aws_sqs: components._aws & {
 		acknowledgements: true
 		healthcheck: enabled: true
 		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    262144
+				max_events:   10
+				timeout_secs: 1.0
+			}
 			compression: enabled: false
 			encoding: {
 				enabled: true
@@ -72,9 +79,33 @@ components: sinks: aws_sqs: components._aws & {
 				examples: ["https://sqs.us-east-2.amazonaws.com/123456789012/MyQueue"]
 			}
 		}
+		message_attributes: {
+			common:      false
+			description: """
+				The [message attributes](\(urls.aws_sqs_message_attributes)) set on each message, rendered from
+				the event. At most 10 attributes can be set. The attributes which can't be rendered, or are
+				rendered empty, are left out of the message.
+				"""
+			required:    false
+			type: object: {
+				examples: [{"host": "{{ host }}", "application": "{{ application }}"}]
+				options: {
+					"*": {
+						common:      false
+						description: "The template rendered as the value of the attribute, of the `String` data type."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["{{ host }}"]
+							syntax: "template"
+						}
+					}
+				}
+			}
+		}
 		message_group_id: {
 			common:      false
-			description: "The tag that specifies that a message belongs to a specific message group. Can be applied only to FIFO queues."
+			description: "The tag that specifies that a message belongs to a specific message group. Required by, and only allowed with, FIFO queues."
 			required:    false
 			type: string: {
 				default: null
@@ -124,7 +155,20 @@ components: sinks: aws_sqs: components._aws & {
 		},
 	]
 
+	how_it_works: {
+		batching: {
+			title: "Batching"
+			body:  """
+				The messages are sent with the [`SendMessageBatch`](\(urls.aws_sqs_send_message_batch)) API, in
+				batches of up to 10 messages. When some of the messages of a batch are rejected, the batch is
+				only sent again if none of its messages were accepted, and the rejections aren't caused by
+				the messages themselves, so that the messages are never sent twice.
+				"""
+		}
+	}
+
 	telemetry: metrics: {
+		component_errors_total:           components.sources.internal_metrics.output.metrics.component_errors_total
 		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
 		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
 		events_discarded_total:           components.sources.internal_metrics.output.metrics.events_discarded_total
@@ -120,6 +120,7 @@ components: sinks: azure_blob: {
 				syntax:  "strftime"
 			}
 		}
+		manifest: sinks._object_store.configuration.manifest
 	}
 
 	input: {
@@ -129,6 +130,8 @@ components: sinks: azure_blob: {
 	}
 
 	how_it_works: {
+		manifests: sinks._object_store.how_it_works.manifests
+
 		object_naming: {
 			title: "Object naming"
 			body:  """
@@ -0,0 +1,133 @@
+package metadata
+
+components: sinks: balance: {
+	title: "Balance"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: true
+		healthcheck: enabled: true
+		send: {
+			compression: enabled: false
+			encoding: enabled:    false
+			request: enabled:     false
+			tls: enabled:         false
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: [
+			"""
+				Targets are only excluded once deliveries to them fail. A target that accepts events
+				without ever finishing them still holds up the `weighted` strategy, so prefer
+				`least_outstanding` when targets are prone to stalling.
+				""",
+		]
+		notices: []
+	}
+
+	configuration: {
+		failure_threshold: {
+			common:      false
+			description: "The number of consecutive batches a target must fail to deliver before it's excluded."
+			required:    false
+			type: uint: {
+				default: 3
+				unit:    null
+			}
+		}
+		strategy: {
+			common:      true
+			description: "How the target of each batch of events is chosen."
+			required:    false
+			type: string: {
+				default: "weighted"
+				enum: {
+					weighted:          "Send batches to the targets in turn, in proportion to their `weight`."
+					least_outstanding: "Send each batch to the target with the fewest undelivered events, relative to its `weight`."
+				}
+			}
+		}
+		targets: {
+			common:      true
+			description: """
+				The sinks to distribute events across. Each target is a sink configuration, such as
+				the ones described in the [sinks documentation](\(urls.vector_sinks)), along with its
+				`weight`. Only the event types all targets accept can be sent to this sink.
+				"""
+			required: true
+			type: array: items: type: object: options: {
+				type: {
+					description: "The type of the target sink."
+					required:    true
+					type: string: examples: ["http", "elasticsearch"]
+				}
+				weight: {
+					description: "The share of events sent to the target, relative to the other targets."
+					required:    false
+					common:      true
+					type: uint: {
+						default: 1
+						unit:    null
+					}
+				}
+			}
+		}
+		unhealthy_secs: {
+			common:      false
+			description: "How long an unhealthy target is excluded before it's tried again."
+			required:    false
+			type: uint: {
+				default: 30
+				unit:    "seconds"
+			}
+		}
+	}
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+		traces: true
+	}
+
+	how_it_works: {
+		health: {
+			title: "Unhealthy targets"
+			body: """
+				A target is excluded when it fails its healthcheck, or when it fails to deliver
+				`failure_threshold` batches in a row. Rejected events, such as those refused for
+				being malformed, don't count as failures. After `unhealthy_secs`, the target is
+				tried again, and a single successful delivery brings it back. If every target is
+				unhealthy, events are still sent to all of them rather than held up.
+				"""
+		}
+		sharding: {
+			title: "Sharding across endpoints"
+			body: """
+				Events are distributed in the batches they arrive in, so each batch goes to a
+				single target. Every target keeps its own buffer and batching, as if it was
+				configured as a sink of its own.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		balance_target_unhealthy_total: components.sources.internal_metrics.output.metrics.balance_target_unhealthy_total
+	}
+}
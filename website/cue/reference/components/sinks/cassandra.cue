@@ -0,0 +1,205 @@
+package metadata
+
+components: sinks: cassandra: {
+	title: "Cassandra"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    40_000
+				max_events:   100
+				timeout_secs: 1.0
+			}
+			compression: enabled: false
+			encoding: enabled:    false
+			proxy: enabled:       false
+			request: {
+				enabled: true
+				headers: false
+			}
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    false
+				enabled_default:        false
+			}
+			to: {
+				service: services.cassandra
+
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["tcp"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		auth: {
+			common:      false
+			description: "The credentials the sink authenticates with, when the cluster requires them."
+			required:    false
+			type: object: options: {
+				password: {
+					description: "The password of the role."
+					required:    true
+					type: string: examples: ["${CASSANDRA_PASSWORD}"]
+				}
+				username: {
+					description: "The name of the role."
+					required:    true
+					type: string: examples: ["vector"]
+				}
+			}
+		}
+		columns: {
+			description: """
+				The columns the rows are written to. The values of the fields are converted to the
+				types of the columns, with objects and arrays written to maps, lists and sets. The
+				columns whose field the events are missing are left unset rather than written as
+				`null`, so that no tombstones are created for them.
+				"""
+			required: true
+			type: array: items: type: object: options: {
+				name: {
+					description: "The name of the column."
+					required:    true
+					type: string: examples: ["message", "status"]
+				}
+				field: {
+					description: "The field holding the values of the column, which defaults to its name."
+					required:    false
+					common:      true
+					type: string: {
+						default: null
+						examples: ["response.status"]
+					}
+				}
+			}
+		}
+		consistency: {
+			common:      false
+			description: "The consistency level of the writes."
+			required:    false
+			type: string: {
+				default: "local_quorum"
+				enum: {
+					any:          "The write is stored by any node, possibly as a hint."
+					one:          "The write is acknowledged by one replica."
+					two:          "The write is acknowledged by two replicas."
+					three:        "The write is acknowledged by three replicas."
+					quorum:       "The write is acknowledged by a quorum of the replicas."
+					all:          "The write is acknowledged by all the replicas."
+					local_quorum: "The write is acknowledged by a quorum of the replicas of the local data center."
+					each_quorum:  "The write is acknowledged by a quorum of the replicas of each data center."
+					local_one:    "The write is acknowledged by one replica of the local data center."
+				}
+			}
+		}
+		endpoints: {
+			description: "The contact points of the cluster, as `host:port`, from which its other nodes are discovered. The port defaults to `9042`."
+			required:    true
+			type: array: items: type: string: examples: ["cassandra-1.example.com:9042", "10.0.0.2"]
+		}
+		keyspace: {
+			description: "The keyspace of the tables."
+			required:    true
+			type: string: examples: ["logs"]
+		}
+		table: {
+			description: "The table the rows are written to, which can route the events to several tables with the same columns."
+			required:    true
+			type: string: {
+				examples: ["events", "events_{{ service }}"]
+				syntax: "template"
+			}
+		}
+		ttl_field: {
+			common:      false
+			description: "The field holding the TTL of each row, in seconds. The events whose field isn't a number of seconds are dropped, and the ones missing it are written with `ttl_secs`."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["ttl"]
+			}
+		}
+		ttl_secs: {
+			common:      false
+			description: "The TTL of the rows, after which they expire. The default TTL of the table applies when it isn't set."
+			required:    false
+			type: uint: {
+				default: null
+				examples: [604800]
+				unit: "seconds"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	how_it_works: {
+		prepared_statements: {
+			title: "Prepared statements"
+			body: """
+				The rows are inserted with an `INSERT` statement prepared once per table, whose
+				metadata tells the sink the types of the columns. The tables are prepared when the
+				first event routed to them is written, and the events of a table that doesn't exist,
+				or whose columns have types the sink doesn't support, are dropped. The supported types
+				are the text, numeric, `boolean`, `blob`, `timestamp`, `date`, `uuid`, `timeuuid`,
+				and `inet` types, along with lists, sets and maps of them.
+				"""
+		}
+		batching: {
+			title: "Token-aware batching"
+			body: """
+				The rows are batched per partition of their table, and each batch is written as an
+				[unlogged batch](\(urls.cassandra_batch)), which the driver sends to a replica
+				owning the token of the partition. The batch is then applied by the replicas of the
+				partition at once, without a coordinator having to spread it across nodes.
+				"""
+		}
+		ttl: {
+			title: "TTL"
+			body: """
+				When `ttl_secs` or `ttl_field` is set, the rows are inserted `USING TTL`, with the
+				TTL of each row bound along with its values, so that rows of the same table can
+				expire at different times.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_discarded_events_total: components.sources.internal_metrics.output.metrics.component_discarded_events_total
+		component_errors_total:           components.sources.internal_metrics.output.metrics.component_errors_total
+		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		events_out_total:                 components.sources.internal_metrics.output.metrics.events_out_total
+	}
+}
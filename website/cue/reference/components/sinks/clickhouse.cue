@@ -76,6 +76,35 @@ components: sinks: clickhouse: {
 			password_example: "${CLICKHOUSE_PASSWORD}"
 			username_example: "${CLICKHOUSE_USERNAME}"
 		}}
+		columns: {
+			common:      false
+			description: """
+				The columns of the table, in order, for the `row_binary` format. Each event is encoded as a
+				row of these columns, each column's value being read from the field of the same name. When
+				unset, the columns are read from the table when the sink starts, excluding `MATERIALIZED` and
+				`ALIAS` columns.
+				"""
+			required: false
+			type: array: {
+				default: null
+				items: type: object: options: {
+					name: {
+						description: "The name of the column, which is also the path of the field its value is read from."
+						required:    true
+						type: string: examples: ["message", "labels.env"]
+					}
+					type: {
+						description: """
+							The type of the column. The supported types are `String`, `FixedString(N)`, the
+							`UInt` and `Int` types up to 64 bits, `Float32`, `Float64`, `Bool`, `Date`, `DateTime`,
+							`DateTime64`, and `Nullable`, `LowCardinality`, `Array` and `Map` of those.
+							"""
+						required: true
+						type: string: examples: ["String", "DateTime64(3)", "Array(LowCardinality(String))"]
+					}
+				}
+			}
+		}
 		database: {
 			common:      true
 			description: "The database that contains the table that data will be inserted into."
@@ -92,6 +121,18 @@ components: sinks: clickhouse: {
 				examples: ["http://localhost:8123"]
 			}
 		}
+		format: {
+			common:      false
+			description: "The format the events are inserted in."
+			required:    false
+			type: string: {
+				default: "json_each_row"
+				enum: {
+					json_each_row: "Events are inserted as JSON objects, whose fields ClickHouse matches with the columns of the table."
+					row_binary:    "Events are encoded as rows of the columns of the table, converting their fields to the types of the columns, which takes much less CPU for ClickHouse to insert."
+				}
+			}
+		}
 		table: {
 			description: "The table that data will be inserted into."
 			required:    true
@@ -107,6 +148,24 @@ components: sinks: clickhouse: {
 		}
 	}
 
+	how_it_works: {
+		row_binary: {
+			title: "Typed inserts"
+			body: """
+				With the `row_binary` format, events are encoded as rows of the table's columns in the
+				[`RowBinary`](\(urls.clickhouse_row_binary)) format rather than as JSON, sparing ClickHouse from
+				parsing the events and converting their fields. The fields of the events are converted to the
+				types of their columns when they're encoded: strings are parsed as numbers or timestamps when
+				needed, and any value is written as its JSON representation in `String` columns.
+
+				Missing fields are written as the default value of the type of their column, such as `0` or an
+				empty string, or as `NULL` for `Nullable` columns, rather than the default expression of the column.
+				Events that can't be converted, for instance because a value is out of the range of its column,
+				are rejected.
+				"""
+		}
+	}
+
 	input: {
 		logs:    true
 		metrics: null
@@ -114,6 +173,8 @@ components: sinks: clickhouse: {
 	}
 
 	telemetry: metrics: {
+		component_discarded_events_total: components.sources.internal_metrics.output.metrics.component_discarded_events_total
+		component_errors_total:           components.sources.internal_metrics.output.metrics.component_errors_total
 		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
 		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
 		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
@@ -85,5 +85,26 @@ components: sinks: datadog_logs: {
 				If your event contains any of these fields they will be used as described by the [API reference](https://docs.datadoghq.com/api/latest/logs/#send-logs).
 				"""
 		}
+
+		quota: {
+			title: "Quota"
+			body: """
+				When the quota of your organization is exceeded, the Datadog API throttles requests
+				with a `429 Too Many Requests` response. Each throttled request is reported by the
+				`datadog_logs_throttled_requests_total` internal metric, and the
+				`datadog_logs_quota_exceeded` gauge is set to `1` until a request is accepted
+				again, so that you can alert on quota exhaustion.
+
+				Throttled requests are retried like any other failed request, unless the sink has a
+				`dead_letter`, in which case they fail right away and their events are routed to the
+				dead letter instead.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:                components.sources.internal_metrics.output.metrics.component_errors_total
+		datadog_logs_quota_exceeded:           components.sources.internal_metrics.output.metrics.datadog_logs_quota_exceeded
+		datadog_logs_throttled_requests_total: components.sources.internal_metrics.output.metrics.datadog_logs_throttled_requests_total
 	}
 }
@@ -59,11 +59,17 @@ components: sinks: datadog_traces: {
 
 	support: {
 		requirements: []
-		warnings: ["APM stats are not yet supported, so they will dropped when using Vector to send traces received from the Datadog Trace agent to Datadog."]
+		warnings: []
 		notices: []
 	}
 
 	configuration: {
+		compute_apm_stats: {
+			common:      false
+			description: "Computes APM stats from the delivered traces and sends them to Datadog, as the Datadog trace-agent does. See [APM stats](#apm-stats)."
+			required:    false
+			type: bool: default: false
+		}
 		default_api_key: sinks._datadog.configuration.api_key
 		endpoint:        sinks._datadog.configuration.endpoint
 		site:            sinks._datadog.configuration.site
@@ -75,6 +81,53 @@ components: sinks: datadog_traces: {
 		traces:  true
 	}
 
+	how_it_works: {
+		api_key_partitioning: {
+			title: "Partitioning by API key"
+			body: """
+				The traces are sent with the API key they were received with, falling back to
+				`default_api_key`. Each API key gets its own request concurrency, rate limit and
+				retries, so that the requests of an API key being throttled or rejected by Datadog
+				don't hold back the requests of the other API keys. At most 200 requests are pending at
+				once, and 50 for each API key; the requests of an API key beyond these wait for it in a
+				queue of 200 requests, rather than taking up the requests left to the other API keys.
+				"""
+		}
+
+		apm_stats: {
+			title: "APM stats"
+			body: """
+				With `compute_apm_stats` enabled, this sink computes APM stats from the traces it
+				delivers, as the Datadog trace-agent does: the number of hits and errors, and the
+				distribution of the durations of the top-level and measured spans, per service,
+				operation name, resource, type and HTTP status code. Only the traces of the requests
+				Datadog accepted are accounted for. Those stats are aggregated in 10 seconds buckets,
+				based on the end time of the spans, and sent to the stats endpoint of the Datadog
+				traces API once complete, with the same retries as the traces. The stats of the
+				buckets that are still open are sent when the sink shuts down.
+
+				The hits, errors and durations of sampled traces are weighted by the sample rate of
+				their root span, so that the stats reflect all the traces seen by the tracers.
+
+				Enable it when Vector receives traces from tracers directly, or from trace-agents
+				that don't send the stats themselves, so that they aren't counted twice.
+				"""
+		}
+
+		non_trace_events: {
+			title: "Non-trace events"
+			body: """
+				This sink only accepts traces: Vector refuses to start with a configuration where
+				one of its inputs can only produce logs or metrics. The logs and metrics its inputs
+				still send it are routed to its `dropped` output, which other components can have as
+				an input, for instance `<sink_id>.dropped`. The sink doesn't wait for these
+				components: when no component has the output as an input, or when the ones that do
+				lag behind, the events are dropped, which is reported by the `component_errors_total`
+				and `component_discarded_events_total` internal metrics.
+				"""
+		}
+	}
+
 	telemetry: metrics: {
 		component_discarded_events_total: components.sources.internal_metrics.output.metrics.component_discarded_events_total
 		component_errors_total:           components.sources.internal_metrics.output.metrics.component_errors_total
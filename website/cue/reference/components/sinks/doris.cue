@@ -0,0 +1,194 @@
+package metadata
+
+components: sinks: doris: {
+	title: "Apache Doris"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    10_000_000
+				timeout_secs: 1.0
+			}
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: enabled: false
+			}
+			proxy: enabled: true
+			request: {
+				enabled: true
+				headers: false
+			}
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.doris
+
+				interface: {
+					socket: {
+						api: {
+							title: "Stream load API"
+							url:   urls.doris_stream_load
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		auth: configuration._http_auth & {_args: {
+			password_example: "${DORIS_PASSWORD}"
+			username_example: "${DORIS_USERNAME}"
+		}}
+		columns: {
+			common:      false
+			description: """
+				The columns of the table, in order, for the `csv` format. Each event is encoded as a row of
+				these columns, each column's value being read from the field of the same name. Required for
+				the `csv` format.
+				"""
+			required:    false
+			type: array: {
+				default: null
+				items: type: string: examples: ["timestamp", "host", "message"]
+			}
+		}
+		database: {
+			description: "The database that contains the table that data will be loaded into."
+			required:    true
+			type: string: {
+				examples: ["logs"]
+			}
+		}
+		endpoint: {
+			description: "The HTTP endpoint of a frontend of the cluster, which redirects the loads to the backends."
+			required:    true
+			type: string: {
+				examples: ["http://localhost:8030"]
+			}
+		}
+		format: {
+			common:      false
+			description: "The format the events are loaded in."
+			required:    false
+			type: string: {
+				default: "json"
+				enum: {
+					json: "Events are loaded as a JSON array of objects, whose fields are matched with the columns of the table."
+					csv:  "Events are loaded as rows of the `columns`, separated by the `\\x01` character, with `\\N` for the missing fields."
+				}
+			}
+		}
+		headers: {
+			common:      false
+			description: """
+				Additional headers sent with each load, which set the properties of the
+				[stream load](\(urls.doris_stream_load)), such as `max_filter_ratio`, `timeout` or `where`.
+				"""
+			required:    false
+			type: object: {
+				examples: [{"max_filter_ratio": "0.1", "timeout": "60"}]
+				options: {
+					"*": {
+						common:      false
+						description: "The value of the header."
+						required:    false
+						type: string: default: null
+					}
+				}
+			}
+		}
+		label_prefix: {
+			common:      false
+			description: """
+				The prefix of the labels identifying the loads, followed by a random UUID. Only letters, digits,
+				`-` and `_` are allowed.
+				"""
+			required:    false
+			type: string: {
+				default: "vector"
+				examples: ["vector_logs"]
+			}
+		}
+		table: {
+			description: "The table that data will be loaded into."
+			required:    true
+			type: string: {
+				examples: ["events"]
+			}
+		}
+	}
+
+	how_it_works: {
+		labels: {
+			title: "Exactly-once loads"
+			body: """
+				Each batch of events is loaded in a transaction identified by a label, which is generated when
+				the batch is built and kept across the retries of the load. When a load succeeded but its
+				response was lost, the retry is answered with `Label Already Exists`, and the events are
+				considered delivered once the previous load is finished, rather than being loaded twice.
+				"""
+		}
+		backpressure: {
+			title: "Backpressure"
+			body: """
+				Loads failing because the backends are overloaded, such as when a tablet has too many versions
+				waiting to be compacted or a load channel exceeds its memory limit, are retried, and slow down the
+				loads through the [adaptive request concurrency](\(urls.adaptive_request_concurrency_post)). The
+				other failed loads, such as those with too many filtered rows, are not retried and their events
+				are rejected.
+				"""
+		}
+		redirects: {
+			title: "Frontends and backends"
+			body: """
+				The loads are sent to the `endpoint` of a frontend, which redirects them to one of the
+				backends. The redirects are followed with the same headers, including the credentials, and the
+				same body. This sink works with both [Apache Doris](\(urls.doris)) and
+				[StarRocks](\(urls.starrocks)), which share the stream load protocol.
+				"""
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	telemetry: metrics: {
+		component_discarded_events_total: components.sources.internal_metrics.output.metrics.component_discarded_events_total
+		component_errors_total:           components.sources.internal_metrics.output.metrics.component_errors_total
+		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		events_out_total:                 components.sources.internal_metrics.output.metrics.events_out_total
+	}
+}
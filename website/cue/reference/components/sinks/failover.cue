@@ -0,0 +1,131 @@
+package metadata
+
+components: sinks: failover: {
+	title: "Failover"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: true
+		healthcheck: enabled: true
+		send: {
+			compression: enabled: false
+			encoding: enabled:    false
+			request: enabled:     false
+			tls: enabled:         false
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		error_rate_threshold: {
+			common:      false
+			description: "The share of batches the primary must fail to deliver, within the `error_window`, for the sink to fail over to the secondary."
+			required:    false
+			type: float: {
+				default: 0.5
+				examples: [0.25]
+			}
+		}
+		error_window: {
+			common:      false
+			description: "The number of most recent batches sent to the primary that its error rate is measured over. The error rate isn't measured until the primary was sent this many batches."
+			required:    false
+			type: uint: {
+				default: 10
+				unit:    null
+			}
+		}
+		primary: {
+			common:      true
+			description: """
+				The sink events are sent to while it's healthy. This is a sink configuration, such as
+				the ones described in the [sinks documentation](\(urls.vector_sinks)).
+				"""
+			required: true
+			type: object: options: {
+				type: {
+					description: "The type of the primary sink."
+					required:    true
+					type: string: examples: ["elasticsearch"]
+				}
+			}
+		}
+		probation_secs: {
+			common:      false
+			description: "How long events are sent to the secondary before switching back to the primary."
+			required:    false
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		secondary: {
+			common:      true
+			description: """
+				The sink events are sent to while the primary is unhealthy. This is a sink
+				configuration, such as the ones described in the
+				[sinks documentation](\(urls.vector_sinks)).
+				"""
+			required: true
+			type: object: options: {
+				type: {
+					description: "The type of the secondary sink."
+					required:    true
+					type: string: examples: ["aws_s3"]
+				}
+			}
+		}
+	}
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+		traces: true
+	}
+
+	how_it_works: {
+		switching: {
+			title: "Switching targets"
+			body: """
+				Events are sent to the primary until it fails its healthcheck, or until the share
+				of batches it fails to deliver reaches `error_rate_threshold`. Rejected events,
+				such as those refused for being malformed, don't count as failures. Events are then
+				sent to the secondary for `probation_secs`, after which the primary is tried again.
+				Only the event types both targets accept can be sent to this sink.
+				"""
+		}
+		retries: {
+			title: "Retrying on the secondary"
+			body: """
+				Batches of events the primary fails to deliver are sent to the secondary, whether or
+				not the sink failed over. A copy of each batch sent to the primary is kept until the
+				primary delivered it. Rejected events aren't sent to the secondary.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		failover_active_target:  components.sources.internal_metrics.output.metrics.failover_active_target
+		failover_switches_total: components.sources.internal_metrics.output.metrics.failover_switches_total
+	}
+}
@@ -0,0 +1,183 @@
+package metadata
+
+components: sinks: gcp_bigquery: {
+	title: "GCP BigQuery"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: ["GCP"]
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    9_000_000
+				max_events:   1000
+				timeout_secs: 1.0
+			}
+			compression: enabled: false
+			encoding: enabled:    false
+			proxy: enabled:       false
+			request: {
+				enabled: true
+				headers: false
+			}
+			tls: {
+				enabled:                true
+				can_verify_certificate: false
+				can_verify_hostname:    false
+				enabled_default:        false
+			}
+			to: {
+				service: services.gcp_bigquery
+
+				interface: {
+					socket: {
+						api: {
+							title: "BigQuery Storage Write API"
+							url:   urls.gcp_bigquery_storage_write_api
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		credentials_path: configuration._gcp_credentials_path
+		dataset: {
+			description: "The dataset of the tables the events are appended to."
+			required:    true
+			type: string: {
+				examples: ["vector_logs"]
+			}
+		}
+		endpoint: {
+			common:      false
+			description: "The endpoint of the BigQuery Storage API to which to send data."
+			required:    false
+			type: string: {
+				default: "https://bigquerystorage.googleapis.com"
+				examples: ["http://localhost:9060"]
+			}
+		}
+		project: {
+			description: "The project of the dataset."
+			required:    true
+			type: string: {
+				examples: ["vector-123456"]
+			}
+		}
+		schema: {
+			description: """
+				The columns of the rows the events are written as. The type of each column must match
+				the type of the column of the table, and the values of the fields that can't be
+				converted to it are written as `NULL`, as are the fields the events are missing.
+				"""
+			required: true
+			type: array: items: type: object: options: {
+				name: {
+					description: "The name of the column."
+					required:    true
+					type: string: examples: ["message", "status"]
+				}
+				field: {
+					description: "The field holding the values of the column, which defaults to its name."
+					required:    false
+					common:      true
+					type: string: {
+						default: null
+						examples: ["response.status"]
+					}
+				}
+				type: {
+					description: "The type of the column, as described in the [data type conversions](\(urls.gcp_bigquery_write_api_types))."
+					required:    true
+					type: string: enum: {
+						string:    "A `STRING` column. Values of other types are written as text."
+						int64:     "An `INT64` column."
+						float64:   "A `FLOAT64` column, which integers are also written to."
+						bool:      "A `BOOL` column."
+						timestamp: "A `TIMESTAMP` column, which RFC 3339 strings are also written to."
+						json:      "A `JSON` column, which values of any type are written to."
+					}
+				}
+			}
+		}
+		skip_authentication: {
+			common:      false
+			description: "Disables authentication, to send data to an emulator."
+			required:    false
+			type: bool: default: false
+		}
+		table: {
+			description: "The table the events are appended to. All the tables the events are routed to must share the schema."
+			required:    true
+			type: string: {
+				examples: ["logs", "logs_{{ service }}"]
+				syntax: "template"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	how_it_works: {
+		storage_write_api: {
+			title: "Storage Write API"
+			body: """
+				Events are serialized as protobuf messages following the configured schema, and
+				appended to the default stream of their table with the
+				[Storage Write API](\(urls.gcp_bigquery_storage_write_api)), where the rows are
+				committed as soon as they are appended. The rows of a batch are rejected together if
+				any of them is malformed, in which case they aren't retried.
+				"""
+		}
+	}
+
+	permissions: iam: [
+		{
+			platform: "gcp"
+			_service: "bigquery"
+
+			policies: [
+				{
+					_action: "datasets.get"
+					required_for: ["healthcheck"]
+				},
+				{
+					_action: "tables.updateData"
+					required_for: ["operation"]
+				},
+			]
+		},
+	]
+
+	telemetry: metrics: {
+		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		events_out_total:                 components.sources.internal_metrics.output.metrics.events_out_total
+	}
+}
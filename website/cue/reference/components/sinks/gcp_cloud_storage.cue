@@ -143,6 +143,7 @@ components: sinks: gcp_cloud_storage: {
 				syntax: "template"
 			}
 		}
+		manifest: sinks._object_store.configuration.manifest
 		metadata: {
 			common:      false
 			description: "The set of metadata `key:value` pairs for the created objects. See the [GCS custom metadata](\(urls.gcs_custom_metadata)) documentation for more details."
@@ -176,6 +177,8 @@ components: sinks: gcp_cloud_storage: {
 	}
 
 	how_it_works: {
+		manifests: sinks._object_store.how_it_works.manifests
+
 		object_access_control_list: {
 			title: "Object access control list (ACL)"
 			body:  """
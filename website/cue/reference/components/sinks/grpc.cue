@@ -0,0 +1,181 @@
+package metadata
+
+components: sinks: grpc: {
+	title: "gRPC"
+
+	description: """
+		Calls a [gRPC](\(urls.grpc)) method described by a user-provided file descriptor set,
+		encoding the log events as its request messages.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: true
+		healthcheck: enabled: false
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    10_000_000
+				timeout_secs: 1.0
+			}
+			compression: enabled: false
+			encoding: enabled:    false
+			proxy: enabled:       true
+			request: {
+				enabled: true
+				headers: false
+			}
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.grpc
+
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	configuration: {
+		auth: configuration._http_auth & {_args: {
+			password_example: "${GRPC_PASSWORD}"
+			username_example: "${GRPC_USERNAME}"
+		}}
+		compression: {
+			common:      true
+			description: "Compress the request messages with gzip."
+			required:    false
+			type: bool: default: false
+		}
+		deadline_secs: {
+			common:      false
+			description: """
+				The deadline of each call, sent to the server with the `grpc-timeout` header so that it can
+				give up on the calls Vector stopped waiting for. Calls exceeding it are retried.
+				"""
+			required:    false
+			type: float: {
+				default: null
+				examples: [5.0]
+				unit: "seconds"
+			}
+		}
+		descriptor_set: {
+			description: """
+				The path of the file descriptor set describing the method, its service and its
+				messages, as generated by `protoc --include_imports --descriptor_set_out`.
+				"""
+			required: true
+			type: string: {
+				examples: ["/etc/vector/logs.desc"]
+			}
+		}
+		endpoint: {
+			description: "The URL of the gRPC server."
+			required:    true
+			type: string: {
+				examples: ["http://localhost:50051", "https://logs.example.com"]
+			}
+		}
+		fields: {
+			common:      false
+			description: """
+				The templates of the fields of the request messages, by field name. When set, only these
+				fields are set, to their rendered value, which must be a scalar. Otherwise, the fields of the
+				events are mapped onto the fields of the messages with the same names.
+				"""
+			required:    false
+			type: object: {
+				examples: [{"message": "{{ message }}", "severity": "{{ level }}"}]
+				options: {
+					"*": {
+						common:      false
+						description: "The template of the field."
+						required:    false
+						type: string: {
+							default: null
+							syntax:  "template"
+						}
+					}
+				}
+			}
+		}
+		method: {
+			description: """
+				The method to call, as in `package.Service/Method`. The method may stream its
+				requests, but not its responses.
+				"""
+			required: true
+			type: string: {
+				examples: ["logs.v1.LogService/Push"]
+			}
+		}
+	}
+
+	how_it_works: {
+		encoding: {
+			title: "Encoding"
+			body: """
+				The fields of the events are converted to the types of the fields of the messages, following
+				the [JSON mapping of Protobuf](\(urls.protobuf_json_mapping)): strings are parsed as numbers,
+				booleans and enum values, enum values are set by name or number, and `google.protobuf.Timestamp`
+				fields are set from timestamps or RFC 3339 strings. Objects are encoded as nested messages or
+				maps, and arrays as repeated fields. The fields of the events the messages don't have are left
+				out. Events with a value that can't be converted are rejected.
+				"""
+		}
+		streaming: {
+			title: "Unary and streaming methods"
+			body: """
+				Unary methods are called once for each event. Methods streaming their requests are called
+				once for each batch, streaming a message for each of its events.
+				"""
+		}
+		retries: {
+			title: "Retries"
+			body: """
+				Calls failing with the `UNKNOWN`, `CANCELLED`, `DEADLINE_EXCEEDED`, `RESOURCE_EXHAUSTED`,
+				`ABORTED`, `INTERNAL` and `UNAVAILABLE` status codes, which includes the connection errors, are
+				retried with backoff. The events of the calls failing with the other status codes are rejected.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_discarded_events_total: components.sources.internal_metrics.output.metrics.component_discarded_events_total
+		component_errors_total:           components.sources.internal_metrics.output.metrics.component_errors_total
+		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+	}
+}
@@ -99,6 +99,18 @@ components: sinks: http: {
 				examples: ["https://10.22.212.22:9000/health"]
 			}
 		}
+		propagate_trace_context: {
+			common: false
+			description: """
+				Whether to add a [W3C `traceparent`](\(urls.w3c_trace_context)) header to each request. Events
+				received by the `http` source with a valid `traceparent` header continue that trace, otherwise a
+				new trace is started, whose IDs are derived from the first event of the request, so that retried
+				requests keep their IDs. The trace and span IDs of each request are recorded on Vector's own
+				`http` span, so that its logs can be correlated with the downstream service.
+				"""
+			required: false
+			type: bool: default: false
+		}
 	}
 
 	input: {
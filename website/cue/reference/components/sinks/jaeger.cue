@@ -0,0 +1,131 @@
+package metadata
+
+components: sinks: jaeger: {
+	title: "Jaeger"
+
+	description: """
+		Exports traces to the [Jaeger collector](\(urls.jaeger_collector)), with its gRPC
+		`PostSpans` service or as Thrift batches posted to its `/api/traces` endpoint.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: true
+		healthcheck: enabled: false
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_events:   1000
+				timeout_secs: 1.0
+			}
+			compression: enabled: false
+			encoding: enabled:    false
+			proxy: enabled:       true
+			request: {
+				enabled: true
+				headers: false
+			}
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.jaeger
+
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	input: {
+		logs:    false
+		metrics: null
+		traces:  true
+	}
+
+	configuration: {
+		endpoint: {
+			description: """
+				The URL of the Jaeger collector. With the `thrift_http` protocol, Vector appends
+				`/api/traces` to it.
+				"""
+			required: true
+			type: string: {
+				examples: ["http://localhost:14250", "http://jaeger-collector:14268"]
+			}
+		}
+		protocol: {
+			common:      true
+			description: "The API of the collector the spans are posted to."
+			required:    false
+			type: string: {
+				default: "grpc"
+				enum: {
+					grpc:        "Post the spans with the protobuf encoded `PostSpans` gRPC service, usually on port `14250`."
+					thrift_http: "Post the spans as Thrift batches over HTTP, usually on port `14268`."
+				}
+			}
+		}
+		auth: configuration._http_auth & {_args: {
+			password_example: "${JAEGER_PASSWORD}"
+			username_example: "${JAEGER_USERNAME}"
+		}}
+	}
+
+	how_it_works: {
+		mapping: {
+			title: "Mapping to Jaeger spans"
+			body: """
+				Traces received by the [`opentelemetry` source](\(urls.vector_sources)/opentelemetry/)
+				hold one span each, whose `service.name` resource names the service of the span and
+				whose other resources are the tags of its process. Traces received by the
+				[`datadog_agent` source](\(urls.vector_sources)/datadog_agent/) hold all the spans in
+				their `spans` field, with their `resource`, `meta` and `metrics` written as tags.
+
+				The kind and status of the spans are written as the `span.kind`, `error` and
+				`otel.status_description` tags, their events as logs, and their links as
+				`FOLLOWS_FROM` references. Traces missing the IDs or timestamps of a span are
+				dropped.
+				"""
+		}
+		processes: {
+			title: "Processes"
+			body: """
+				The spans of a Thrift batch share their process, so the spans of each request are
+				split into a batch per process with the `thrift_http` protocol, each posted on its
+				own. With the `grpc` protocol, each span carries its own process instead.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_discarded_events_total: components.sources.internal_metrics.output.metrics.component_discarded_events_total
+		component_errors_total:           components.sources.internal_metrics.output.metrics.component_errors_total
+		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+	}
+}
@@ -46,6 +46,15 @@ components: sinks: kafka: {
 
 	configuration: {
 		bootstrap_servers: components._kafka.configuration.bootstrap_servers
+		idempotence: {
+			common:      false
+			description: """
+				Produce with the idempotent producer, which keeps the retries of the producer from duplicating or
+				reordering messages. Transactional producers are always idempotent.
+				"""
+			required:    false
+			type: bool: default: false
+		}
 		key_field: {
 			common:      true
 			description: "The log field name or tags key to use for the topic key. If the field does not exist in the log or in tags, a blank value will be used. If unspecified, the key is not sent. Kafka uses a hash of the key to choose the partition or uses round-robin if the record has no key."
@@ -118,6 +127,69 @@ components: sinks: kafka: {
 				syntax: "template"
 			}
 		}
+		transaction: {
+			common:      false
+			description: """
+				Produce the events of each batch in a transaction. See the
+				[Transactions](#transactions) section for more info.
+				"""
+			required:    false
+			type: object: options: {
+				batch: {
+					common:      false
+					description: "Configures the batches of events produced in each transaction."
+					required:    false
+					type: object: options: {
+						max_bytes: {
+							common:      false
+							description: "The maximum size of a batch, in bytes, before its transaction is started."
+							required:    false
+							type: uint: {
+								default: null
+								unit:    "bytes"
+							}
+						}
+						max_events: {
+							common:      false
+							description: "The maximum size of a batch, in events, before its transaction is started."
+							required:    false
+							type: uint: {
+								default: 1000
+								unit:    "events"
+							}
+						}
+						timeout_secs: {
+							common:      false
+							description: "The maximum age of a batch before its transaction is started."
+							required:    false
+							type: float: {
+								default: 1.0
+								unit:    "seconds"
+							}
+						}
+					}
+				}
+				timeout_ms: {
+					common:      false
+					description: "The maximum time a transaction can remain open before the brokers abort it."
+					required:    false
+					type: uint: {
+						default: 60000
+						examples: [60000]
+						unit: "milliseconds"
+					}
+				}
+				transactional_id: {
+					description: """
+						Identifies the producer across restarts, so that the transactions it left open when it
+						stopped are aborted once it's back. It has to be unique to each sink, as producers with the
+						same identifier fence each other off.
+						"""
+					required:    true
+					type: string: examples: ["vector-kafka-0"]
+				}
+			}
+		}
 		headers_key: {
 			common:      false
 			description: "The log field name to use for the Kafka headers. If omitted, no headers will be written."
@@ -142,7 +214,26 @@ components: sinks: kafka: {
 		traces: false
 	}
 
-	how_it_works: components._kafka.how_it_works
+	how_it_works: components._kafka.how_it_works & {
+		transactions: {
+			title: "Transactions"
+			body: """
+				With `transaction` set, the events of each batch are produced in a Kafka transaction, which is
+				committed once they're all produced, before they're acknowledged. When producing any of them fails,
+				the transaction is aborted, so that consumers with `isolation.level` set to `read_committed` don't
+				see the messages of the aborted attempts. On a retriable error, such as a timeout or an unavailable
+				broker, all of them are then produced again in a new transaction, after a backoff growing up to a
+				minute. On a permanent error, such as a message being too large or the producer not being
+				authorized to write to the topic, the events of the batch are rejected instead. Paired
+				with the end-to-end acknowledgements of the `kafka` source, this keeps Kafka-to-Kafka pipelines
+				from duplicating messages when they're retried.
+
+				The transactions of a producer can't overlap, so they're produced one at a time. The brokers have
+				to support transactions, which requires at least as many brokers as the replication factor of the
+				transaction state log.
+				"""
+		}
+	}
 
 	telemetry: metrics: {
 		component_sent_events_total:         components.sources.internal_metrics.output.metrics.component_sent_events_total
@@ -117,7 +117,7 @@ components: sinks: loki: {
 				service can't accept a stream of such events prior version 2.4.0. Vector sorts events before sending
 				them to Loki, however some late events might arrive after a batch has been sent. This option specifies
 				what Vector should do with those events. If you are using Loki 2.4.0 and newer, you should set this
-				option to "accept"
+				option to "accept". `clamp_to_latest` is accepted as an alias of "rewrite_timestamp".
 				"""
 			required: false
 			type: string: {
@@ -125,6 +125,7 @@ components: sinks: loki: {
 				enum: {
 					"drop":              "Drop the event."
 					"rewrite_timestamp": "Rewrite timestamp of the event to the latest timestamp that was pushed."
+					"restream":          "Send the event to the first sibling stream it is in order for, labelled with `restream_label` set to `1`, `2` and so on, up to `10`. The event is dropped if it is out of order for all of them."
 					"accept":            "Don't do anything, send events into Loki normally (needs Loki 2.4.0 and newer)"
 				}
 			}
@@ -136,12 +137,57 @@ components: sinks: loki: {
 			type: bool: default: false
 		}
 
+		remove_structured_metadata_fields: {
+			common:      false
+			description: "If this is set to `true` then when structured metadata is collected from events those fields will also get removed from the event."
+			required:    false
+			type: bool: default: false
+		}
 		remove_timestamp: {
 			common:      false
 			description: "If this is set to `true` then the timestamp will be removed from the event payload. Note the event timestamp will still be sent as metadata to Loki for indexing."
 			required:    false
 			type: bool: default: true
 		}
+		restream_label: {
+			common:      false
+			description: "The name of the label distinguishing the sibling streams of out-of-order events, when `out_of_order_action` is set to `restream`."
+			required:    false
+			type: string: {
+				default: "out_of_order"
+				examples: ["late"]
+			}
+		}
+		structured_metadata: {
+			common:      false
+			description: """
+				A set of [structured metadata](\(urls.loki_structured_metadata)) attached to each event. Unlike the
+				labels, the structured metadata isn't indexed, so it can hold values of high cardinality such as
+				trace ids. Both keys and values are templatable, and the keys ending with `*` are prefixes for the
+				keys of the objects rendered by their values, as with the labels. Requires Loki 2.9 or later.
+				"""
+			required: false
+			type: object: {
+				examples: [
+					{
+						"trace_id": "{{ trace_id }}"
+						"pod_*":    "{{ kubernetes.pod_labels }}"
+					},
+				]
+				options: {
+					"*": {
+						common:      false
+						description: "Any structured metadata, templatable"
+						required:    false
+						type: string: {
+							default: null
+							examples: ["{{ trace_id }}"]
+							syntax: "template"
+						}
+					}
+				}
+			}
+		}
 		tenant_id: {
 			common:      false
 			description: """
@@ -149,6 +195,9 @@ components: sinks: loki: {
 				this header. When running Loki locally a tenant id is not required either.
 
 				You can read more about tenant id's [here](\(urls.loki_multi_tenancy)).
+
+				The events are batched per tenant, and the requests of each tenant are sent, and retried, independently
+				of the other tenants.
 				"""
 			required:    false
 			type: string: {
@@ -179,6 +228,16 @@ components: sinks: loki: {
 				"""
 		}
 
+		multi_tenancy: {
+			title: "Multi-tenancy"
+			body: """
+				When `tenant_id` is rendered from the events, the batches are partitioned by tenant, and each
+				tenant has its own request concurrency, rate limit and retries. A tenant whose requests are
+				throttled by Loki, with `429` responses, is retried without delaying the requests of the other
+				tenants.
+				"""
+		}
+
 		event_ordering: {
 			title: "Event Ordering"
 			body: """
@@ -192,13 +251,16 @@ components: sinks: loki: {
 	}
 
 	telemetry: metrics: {
-		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
-		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
-		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
-		events_discarded_total:           components.sources.internal_metrics.output.metrics.events_discarded_total
-		events_out_total:                 components.sources.internal_metrics.output.metrics.events_out_total
-		processed_bytes_total:            components.sources.internal_metrics.output.metrics.processed_bytes_total
-		processing_errors_total:          components.sources.internal_metrics.output.metrics.processing_errors_total
-		streams_total:                    components.sources.internal_metrics.output.metrics.streams_total
+		component_sent_bytes_total:         components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_events_total:        components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total:   components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		events_discarded_total:             components.sources.internal_metrics.output.metrics.events_discarded_total
+		events_out_total:                   components.sources.internal_metrics.output.metrics.events_out_total
+		out_of_order_accepted_events_total: components.sources.internal_metrics.output.metrics.out_of_order_accepted_events_total
+		processed_bytes_total:              components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processing_errors_total:            components.sources.internal_metrics.output.metrics.processing_errors_total
+		restreamed_events_total:            components.sources.internal_metrics.output.metrics.restreamed_events_total
+		rewritten_timestamp_events_total:   components.sources.internal_metrics.output.metrics.rewritten_timestamp_events_total
+		streams_total:                      components.sources.internal_metrics.output.metrics.streams_total
 	}
 }
@@ -0,0 +1,81 @@
+package metadata
+
+components: sinks: mqtt: {
+	title: "MQTT"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: true
+		healthcheck: enabled: true
+		send: {
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: {
+					enabled: true
+					enum: ["json", "text"]
+				}
+			}
+			request: enabled: false
+			tls: {
+				enabled:                true
+				can_verify_certificate: false
+				can_verify_hostname:    false
+				enabled_default:        false
+			}
+			to: components._mqtt.features.send.to
+		}
+	}
+
+	support: components._mqtt.support
+
+	configuration: components._mqtt.configuration & {
+		retain: {
+			common:      false
+			description: "Whether the broker [retains](\(urls.mqtt_retained)) the last message of each topic, to send it to the clients subscribing to the topic later."
+			required:    false
+			type: bool: default: false
+		}
+		topic: {
+			description: "The topic messages are published to."
+			required:    true
+			type: string: {
+				examples: ["vector", "devices/{{ host }}/logs"]
+				syntax: "template"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	how_it_works: {
+		delivery: {
+			title: "Delivery"
+			body: """
+				Events are considered delivered once the broker acknowledges their message, when
+				they're published with a QoS of 1, and once their message is sent otherwise. The
+				messages that weren't acknowledged when the connection is lost are sent again once
+				Vector reconnects to the broker, with an exponential backoff.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:           components.sources.internal_metrics.output.metrics.component_errors_total
+		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+	}
+}
@@ -0,0 +1,114 @@
+package metadata
+
+components: sinks: _object_store: {
+	configuration: manifest: {
+		category:    "Manifest"
+		common:      false
+		description: "Records the objects written in manifests, once they are complete, so that batch loaders can discover them without listing the objects of each partition."
+		required:    false
+		type: object: {
+			examples: [{format: "json"}]
+			options: {
+				format: {
+					common:      true
+					description: "The format of the manifests."
+					required:    false
+					type: string: {
+						default: "json"
+						enum: {
+							json:    "A manifest object per partition, listing all of the objects written to it."
+							marker:  "A marker object per object, written next to it."
+							success: "A success marker per partition, written once no object has been added to it for `success_after_secs`."
+						}
+					}
+				}
+				name: {
+					common:        false
+					description:   "The name of the manifest object of each partition."
+					required:      false
+					relevant_when: "format = \"json\""
+					type: string: {
+						default: "manifest.json"
+					}
+				}
+				marker_suffix: {
+					common:        false
+					description:   "The suffix appended to the key of each object to get the key of its marker."
+					required:      false
+					relevant_when: "format = \"marker\""
+					type: string: {
+						default: ".manifest.json"
+					}
+				}
+				success_name: {
+					common:        false
+					description:   "The name of the success marker of each partition."
+					required:      false
+					relevant_when: "format = \"success\""
+					type: string: {
+						default: "_SUCCESS"
+					}
+				}
+				success_after_secs: {
+					common:        false
+					description:   "The time after which a partition no object has been added to is complete, and its success marker is written."
+					required:      false
+					relevant_when: "format = \"success\""
+					type: uint: {
+						default: 300
+						unit:    "seconds"
+					}
+				}
+			}
+		}
+	}
+
+	how_it_works: manifests: {
+		title: "Manifests"
+		body: """
+			When `manifest` is set, each object is recorded in a manifest once it has been
+			written, which lets batch loaders discover the complete objects of a partition
+			without picking up objects that are still being written. The partition of an object
+			is the "directory" it's written to, its key up to the last `/`, which is the rendered
+			key prefix when it ends with a `/`.
+
+			With the `json` format, a manifest object is written to each partition, and rewritten
+			each time an object is added to it:
+
+			```json
+			{
+			  "partition": "date=2022-06-01/",
+			  "records": 1500,
+			  "bytes": 104857,
+			  "objects": [
+			    {"key": "date=2022-06-01/1654041600-a8e1c1c0.log.gz", "records": 1500, "bytes": 104857}
+			  ]
+			}
+			```
+
+			The manifest already written to a partition is read before Vector adds the first
+			object to it, so that it keeps listing the objects written before Vector restarted.
+			Until it can be read, the objects are only recorded, and the manifest isn't
+			rewritten. The name of the manifest should still be made unique to each Vector
+			instance writing to the same partitions, as they would otherwise overwrite each
+			other's updates.
+
+			With the `marker` format, a marker object is written next to each object, with the
+			`marker_suffix` appended to its key, describing the object like the entries of the
+			`objects` list above.
+
+			With the `success` format, a success marker is written to each partition once no
+			object has been added to it for `success_after_secs`, following the `_SUCCESS`
+			convention of Hadoop and Spark. It describes the objects of the partition like the
+			`json` manifest, and is only written once: the objects added to the partition after it
+			get their own success marker, once the partition is idle again. `success_after_secs`
+			should be longer than the `batch.timeout_secs`, so that the objects of a partition are
+			all written before it's considered complete. The partitions still pending when Vector
+			stops don't get a success marker.
+
+			The manifests are written with the same options as the objects, except for their
+			content type and encoding, and a failure to write them doesn't fail the delivery of
+			the events.
+			"""
+	}
+}
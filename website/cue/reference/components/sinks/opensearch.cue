@@ -0,0 +1,137 @@
+package metadata
+
+components: sinks: opensearch: {
+	title: "OpenSearch"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: ["AWS"]
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    10_000_000
+				timeout_secs: 1.0
+			}
+			compression: {
+				enabled: true
+				default: "none"
+				algorithms: ["none", "gzip"]
+				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+			}
+			encoding: {
+				enabled: true
+				codec: enabled: false
+			}
+			proxy: enabled: true
+			request: {
+				enabled: true
+				headers: true
+			}
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.opensearch
+
+				interface: {
+					socket: {
+						api: {
+							title: "OpenSearch bulk API"
+							url:   urls.opensearch_bulk
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		auth:        components.sinks.elasticsearch.configuration.auth
+		aws:         components.sinks.elasticsearch.configuration.aws
+		bulk:        components.sinks.elasticsearch.configuration.bulk
+		data_stream: components.sinks.elasticsearch.configuration.data_stream
+		endpoint: {
+			description: "The OpenSearch endpoint to send logs to. This should be the full URL as shown in the example."
+			required:    true
+			type: string: {
+				examples: ["http://10.24.32.122:9200", "https://search-example.us-east-1.es.amazonaws.com", "https://example.us-east-1.aoss.amazonaws.com"]
+			}
+		}
+		id:       components.sinks.elasticsearch.configuration.id
+		id_key:   components.sinks.elasticsearch.configuration.id_key
+		metrics:  components.sinks.elasticsearch.configuration.metrics
+		mode:     components.sinks.elasticsearch.configuration.mode
+		pipeline: components.sinks.elasticsearch.configuration.pipeline
+		query:    components.sinks.elasticsearch.configuration.query
+		routing:  components.sinks.elasticsearch.configuration.routing
+		serverless: {
+			common:      false
+			description: "Whether the endpoint is an [Amazon OpenSearch Serverless](\(urls.aws_opensearch_serverless)) collection. Serverless collections require the `aws` authentication strategy."
+			required:    false
+			type: bool: default: false
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	how_it_works: {
+		aws_signing: {
+			title: "AWS request signing"
+			body:  """
+				With the `aws` authentication strategy, the requests are signed with AWS Signature Version 4,
+				for the `es` service of the Amazon OpenSearch Service domains, or for the `aoss` service of the
+				[Amazon OpenSearch Serverless](\(urls.aws_opensearch_serverless)) collections when `serverless`
+				is enabled. Since serverless collections don't expose the cluster APIs, their healthcheck lists
+				the indices instead of querying the health of the cluster.
+				"""
+		}
+
+		document_types: {
+			title: "Document types"
+			body:  """
+				OpenSearch 2 removed the mapping types, so the documents are always sent without a `_type`,
+				which all the versions of OpenSearch accept.
+				"""
+		}
+
+		partial_failures: {
+			title: "Partial failures"
+			body:  """
+				The bulk requests are retried when OpenSearch rejects all their documents because it is
+				overloaded, with a `429` status, or failing, with a `5xx` status. They are not retried if some
+				documents were written, so that no document is written twice, nor if a document is invalid,
+				such as with mapping errors. The errors are reported with the number of documents that failed.
+				"""
+		}
+
+		aws_authentication: components._aws.how_it_works.aws_authentication
+	}
+
+	telemetry: components.sinks.elasticsearch.telemetry
+}
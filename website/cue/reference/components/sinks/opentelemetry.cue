@@ -0,0 +1,143 @@
+package metadata
+
+components: sinks: opentelemetry: {
+	title: "OpenTelemetry"
+
+	description: """
+		Exports logs, metrics and traces with the [OpenTelemetry protocol](\(urls.opentelemetry_protocol))
+		(OTLP) over gRPC or HTTP, to any OTLP compatible backend such as the OpenTelemetry Collector.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: true
+		healthcheck: enabled: false
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_events:   1000
+				timeout_secs: 1.0
+			}
+			compression: enabled: false
+			encoding: enabled:    false
+			proxy: enabled:       true
+			request: {
+				enabled: true
+				headers: false
+			}
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.opentelemetry
+
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: false
+			gauge:        true
+			histogram:    true
+			summary:      true
+			set:          true
+		}
+		traces: true
+	}
+
+	configuration: {
+		endpoint: {
+			description: """
+				The URL of the OTLP receiver. With the `http` protocol, Vector appends `/v1/logs`, `/v1/metrics` or
+				`/v1/traces` to it depending on the exported events.
+				"""
+			required: true
+			type: string: {
+				examples: ["http://localhost:4317", "https://otlp.example.com:4318"]
+			}
+		}
+		protocol: {
+			common:      true
+			description: "The transport of the export requests."
+			required:    false
+			type: string: {
+				default: "grpc"
+				enum: {
+					grpc: "Export with OTLP/gRPC."
+					http: "Export with OTLP/HTTP, as protobuf encoded requests."
+				}
+			}
+		}
+		compression: {
+			common:      true
+			description: "Compress the export requests with gzip."
+			required:    false
+			type: bool: default: false
+		}
+		auth: configuration._http_auth & {_args: {
+			password_example: "${OTLP_PASSWORD}"
+			username_example: "${OTLP_USERNAME}"
+		}}
+	}
+
+	how_it_works: {
+		mapping: {
+			title: "Mapping to OTLP"
+			body: """
+				Logs and traces are mapped the same way the [`opentelemetry` source](\(urls.vector_sources)/opentelemetry/)
+				receives them: fields without an OTLP counterpart are exported as attributes, and the resource and
+				instrumentation scope the source kept in the metadata of the events are exported along with them.
+				For metrics, the tags prefixed with `resource.` and `scope.` describe the resource and
+				instrumentation scope.
+
+				Counters are exported as monotonic sums and incremental gauges as non-monotonic sums, both with delta
+				temporality when incremental and cumulative otherwise. Sets are exported as gauges of their size.
+				Distributions and sketches have no OTLP counterpart; they are rejected, which is reported to the sources
+				with end-to-end acknowledgements enabled, and counted as discarded events.
+				"""
+		}
+		retries: {
+			title: "Retries"
+			body: """
+				Requests that fail with a status the OTLP specification considers transient are retried: the
+				`CANCELLED`, `DEADLINE_EXCEEDED`, `ABORTED`, `OUT_OF_RANGE`, `UNAVAILABLE` and `DATA_LOSS` gRPC
+				status codes, and the `429`, `502`, `503` and `504` HTTP status codes.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:           components.sources.internal_metrics.output.metrics.component_errors_total
+		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+	}
+}
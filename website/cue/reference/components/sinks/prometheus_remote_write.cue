@@ -128,6 +128,88 @@ components: sinks: prometheus_remote_write: {
 				syntax: "template"
 			}
 		}
+		tenant_headers: {
+			common:      false
+			description: "The additional headers of the requests of each tenant, keyed by the `tenant_id` they are sent with."
+			required:    false
+			type: object: {
+				examples: [{"tenant-a": {"X-Tenant-Region": "us-west-1"}}]
+				options: {
+					"*": {
+						common:      false
+						description: "The headers of the requests of a tenant."
+						required:    false
+						type: object: {
+							examples: [{"X-Tenant-Region": "us-west-1"}]
+							options: {}
+						}
+					}
+				}
+			}
+		}
+		protocol: {
+			common:      false
+			description: "The version of the [remote write protocol](\(urls.prometheus_remote_write_spec_v2)) to send the metrics with."
+			required:    false
+			type: string: {
+				default: "v1"
+				enum: {
+					v1: "The 1.0 version of the protocol, with the metadata of the metric families sent separately."
+					v2: "The 2.0 version of the protocol, whose strings are interned, and whose series hold their metadata."
+				}
+			}
+		}
+		max_samples_per_request: {
+			common:      false
+			description: """
+				The maximum number of samples, native histograms and exemplars of a request. A batch exceeding it is
+				sent with several requests, in order, each holding whole series. By default, a batch is sent with a
+				single request.
+				"""
+			required:    false
+			type: uint: {
+				default: null
+				examples: [2000]
+				unit:    null
+			}
+		}
+		native_histogram_schema: {
+			common:      false
+			description: """
+				If set, the histogram [distributions](\(urls.vector_metric)/#distribution) are sent as native
+				histograms of this schema, instead of the `buckets` of classic histograms. The schema is an integer
+				between -4 and 8, and the buckets of a schema `n` are bounded by the powers of `2^(2^-n)`.
+				"""
+			required:    false
+			type: float: {
+				default: null
+				examples: [3.0, -1.0]
+			}
+		}
+		exemplar_labels: {
+			common:      false
+			description: """
+				If set, the gauges named after a series with the `_exemplar` suffix, such as the exemplars received by
+				the `prometheus_remote_write` source, are sent as the exemplars of this series. Their tags with these
+				names are the labels of the exemplars, while their other tags are the labels of the series.
+				"""
+			required:    false
+			type: array: {
+				default: null
+				items: type: string: examples: ["trace_id", "span_id"]
+			}
+		}
+	}
+
+	how_it_works: {
+		out_of_order_samples: {
+			title: "Out-of-order samples"
+			body: """
+				The samples of each series are sorted by timestamp within a request, as the metrics of a batch may be
+				received out of order. The timestamps of the metrics are kept, so their samples may be older than the
+				ones already sent, which the backends ingesting out-of-order samples accept.
+				"""
+		}
 	}
 
 	input: {
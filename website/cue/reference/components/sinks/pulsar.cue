@@ -21,7 +21,7 @@ components: sinks: pulsar: {
 				enabled: true
 				codec: {
 					enabled: true
-					enum: ["text", "json"]
+					enum: ["text", "json", "avro"]
 				}
 			}
 			request: enabled: false
@@ -76,6 +76,56 @@ components: sinks: pulsar: {
 							examples: ["${PULSAR_TOKEN}", "123456789"]
 						}
 					}
+					oauth2: {
+						common:      false
+						description: "Authenticates with the OAuth 2.0 client credentials flow, rather than with a `name` and a `token`."
+						required:    false
+						type: object: options: {
+							issuer_url: {
+								description: "The URL of the OAuth 2.0 issuer."
+								required:    true
+								type: string: examples: ["https://oauth2.example.com"]
+							}
+							credentials_url: {
+								description: "The URL of the JSON file holding the credentials of the client."
+								required:    true
+								type: string: examples: ["file:///etc/pulsar/credentials.json"]
+							}
+							audience: {
+								common:      false
+								description: "The audience the access token is requested for."
+								required:    false
+								type: string: {
+									default: null
+									examples: ["urn:sn:pulsar:my-org:my-instance"]
+								}
+							}
+							scope: {
+								common:      false
+								description: "The scope the access token is requested for."
+								required:    false
+								type: string: {
+									default: null
+									examples: ["pulsar"]
+								}
+							}
+						}
+					}
+				}
+			}
+		}
+		compression: {
+			common:      false
+			description: "The compression applied to the batches of messages by the producer."
+			required:    false
+			type: string: {
+				default: "none"
+				enum: {
+					none:   "No compression."
+					lz4:    "LZ4 compression."
+					zlib:   "Zlib compression."
+					zstd:   "Zstandard compression."
+					snappy: "Snappy compression."
 				}
 			}
 		}
@@ -86,6 +136,16 @@ components: sinks: pulsar: {
 				examples: ["pulsar://127.0.0.1:6650"]
 			}
 		}
+		partition_key: {
+			common:      false
+			description: "The key of the messages, which routes them to the partitions of the topic and identifies them for compaction. Messages are sent without a key when it fails to render."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["{{ host }}", "{{ user_id }}"]
+				syntax: "template"
+			}
+		}
 		topic: {
 			description: "The Pulsar topic name to write events to."
 			required:    true
@@ -101,6 +161,18 @@ components: sinks: pulsar: {
 		traces:  false
 	}
 
+	how_it_works: {
+		schema: {
+			title: "Schema registration"
+			body: """
+				When `encoding.schema` is set, the schema is registered on the topic, as an Avro
+				schema with the `avro` codec or a JSON schema with the `json` codec, so that the
+				broker checks that its consumers are compatible with it. The schema of a JSON schema
+				is also written as an Avro schema definition.
+				"""
+		}
+	}
+
 	telemetry: metrics: {
 		encode_errors_total: components.sources.internal_metrics.output.metrics.encode_errors_total
 	}
@@ -0,0 +1,165 @@
+package metadata
+
+components: sinks: questdb: {
+	title: "QuestDB"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    1_000_000
+				max_events:   1000
+				timeout_secs: 1.0
+			}
+			compression: enabled: false
+			encoding: enabled:    false
+			keepalive: enabled:   true
+			proxy: enabled:       false
+			request: enabled:     false
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.questdb
+
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["tcp"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		address: {
+			description: "The address of the InfluxDB line protocol listener of the server, which must include the port."
+			required:    true
+			type: string: {
+				examples: ["localhost:9009", "questdb.example.com:9009"]
+			}
+		}
+		auth: {
+			common:      false
+			description: "The key connections are [authenticated](\(urls.questdb_ilp_auth)) with. Connections aren't authenticated when unset."
+			required:    false
+			type: object: options: {
+				key_id: {
+					description: "The ID of the key, which is the name of the user it's configured for on the server."
+					required:    true
+					type: string: examples: ["vector"]
+				}
+				private_key: {
+					description: "The private ECDSA P-256 key, as the base64url encoded `d` parameter of its JSON Web Key."
+					required:    true
+					type: string: examples: ["${QUESTDB_PRIVATE_KEY}"]
+				}
+			}
+		}
+		default_namespace: {
+			common:      true
+			description: "The namespace of the metrics that don't have one, which prefixes the names of their tables."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["service"]
+			}
+		}
+		quantiles: {
+			common:      false
+			description: "The quantiles written for [distribution](\(urls.vector_data_model)/metric#distribution) metrics with a summary statistic."
+			required:    false
+			type: array: {
+				default: [0.5, 0.75, 0.9, 0.95, 0.99]
+				items: type: float: examples: [0.5, 0.75, 0.9, 0.95, 0.99]
+			}
+		}
+		table: {
+			common:      true
+			description: """
+				The table each metric is written to, which QuestDB creates when it doesn't exist.
+				Defaults to the name of the metric, prefixed with its namespace and an underscore.
+				"""
+			required: false
+			type: string: {
+				default: null
+				examples: ["metrics", "metrics_{{ namespace }}", "{{ name }}"]
+				syntax: "template"
+			}
+		}
+		tags: {
+			common:      false
+			description: "Additional tags written with each metric, as symbol columns."
+			required:    false
+			type: object: {
+				examples: [{region: "us-west-1"}]
+			}
+		}
+	}
+
+	input: {
+		logs: false
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+		traces: false
+	}
+
+	how_it_works: {
+		line_protocol: {
+			title: "InfluxDB line protocol"
+			body: """
+				Metrics are written with the [InfluxDB line protocol](\(urls.questdb_ilp)), the same
+				way as by the `influxdb_metrics` sink: their tags and a `metric_type` tag are written
+				as symbol columns, and their values as one or more fields, such as `value` for
+				counters and gauges. The characters QuestDB doesn't allow in the names of columns,
+				such as the dots of the `bucket_2.5` fields of histograms, are replaced with
+				underscores.
+				"""
+		}
+		delivery: {
+			title: "Delivery"
+			body: """
+				Batches are written over a persistent TCP connection, which is reopened, and the
+				batch written again, when writing fails. QuestDB doesn't acknowledge the rows it
+				receives, so the events are considered delivered once their batch has been written
+				to the connection, and rows the server rejects are only reported in its logs.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		connection_errors_total:          components.sources.internal_metrics.output.metrics.connection_errors_total
+	}
+}
@@ -63,6 +63,12 @@ components: sinks: redis: {
 				examples: ["redis://127.0.0.1:6379/0"]
 			}
 		}
+		cluster: {
+			common:      false
+			description: "Whether `url` is a node of a Redis Cluster, whose other nodes are discovered from it. The commands are sent to the nodes serving the slots of their keys."
+			required:    false
+			type: bool: default: false
+		}
 		key: {
 			description: "The Redis key to publish messages to."
 			required:    true
@@ -73,13 +79,14 @@ components: sinks: redis: {
 		}
 		data_type: {
 			common:      false
-			description: "The Redis data type (`list` or `channel`) to use."
+			description: "The Redis data type (`list`, `channel` or `stream`) to use."
 			required:    false
 			type: string: {
 				default: "list"
 				enum: {
 					list:    "Use the Redis `list` data type."
 					channel: "Use the Redis `channel` data type."
+					stream:  "Use the Redis `stream` data type."
 				}
 			}
 		}
@@ -105,6 +112,40 @@ components: sinks: redis: {
 				}
 			}
 		}
+		stream: {
+			common:      false
+			description: "Options for the Redis `stream` data type."
+			required:    false
+			type: object: {
+				examples: []
+				options: {
+					approximate: {
+						common:      false
+						description: "Whether the streams are trimmed to about `maxlen` entries, which Redis does more efficiently, rather than exactly."
+						required:    false
+						type: bool: default: true
+					}
+					field: {
+						common:      false
+						description: "The field of the stream entries the encoded events are set to."
+						required:    false
+						type: string: {
+							default: "message"
+						}
+					}
+					maxlen: {
+						common:      false
+						description: "The length the streams are trimmed to when entries are added. The streams are not trimmed by default."
+						required:    false
+						type: uint: {
+							default: null
+							examples: [10000]
+							unit: "events"
+						}
+					}
+				}
+			}
+		}
 	}
 
 	input: {
@@ -122,6 +163,30 @@ components: sinks: redis: {
 				API.
 				"""
 		}
+		streams: {
+			title: "Streams"
+			body:  """
+				When `data_type` is `stream`, each event is added to the stream with `XADD`, as an entry with an
+				auto-generated ID whose `stream.field` field is the encoded event. When `stream.maxlen` is set, the
+				stream is trimmed to that many entries as they are added.
+				"""
+		}
+		pipelining: {
+			title: "Pipelining"
+			body:  """
+				The commands of a batch are sent together in a pipeline, saving the round trips between them. Against a
+				single server, the pipeline is run as a transaction, so a batch is either entirely written or retried.
+				"""
+		}
+		cluster: {
+			title: "Redis Cluster"
+			body:  """
+				When `cluster` is enabled, the slots served by each node are fetched from `url` with `CLUSTER SLOTS`,
+				and the commands of a batch are pipelined to the nodes serving the slots of their keys. Since the keys
+				may be in different slots, the batches are not run as transactions. When a node fails, the slots are
+				fetched again before the batch is retried.
+				"""
+		}
 	}
 
 	telemetry: metrics: {
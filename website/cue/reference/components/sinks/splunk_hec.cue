@@ -40,6 +40,33 @@ components: sinks: _splunk_hec: {
 							unit:    null
 						}
 					}
+					query_timeout: {
+						common:      false
+						description: "The amount of time to wait for a response to a query to the Splunk HEC indexer acknowledgement endpoint. Timed out queries count as attempts and are retried. Minimum of `1`."
+						required:    false
+						type: uint: {
+							default: 10
+							unit:    "seconds"
+						}
+					}
+					query_concurrency: {
+						common:      false
+						description: "The maximum number of channels whose ack ids are queried at once. Minimum of `1`."
+						required:    false
+						type: uint: {
+							default: 1
+							unit:    null
+						}
+					}
+					channels: {
+						common:      false
+						description: "The number of Splunk channels the requests are spread over. The ack ids of a request are always queried on its channel. Minimum of `1`."
+						required:    false
+						type: uint: {
+							default: 1
+							unit:    null
+						}
+					}
 				}
 			}
 		}
@@ -50,6 +77,39 @@ components: sinks: _splunk_hec: {
 				examples: ["${SPLUNK_HEC_TOKEN}", "A94A8FE5CCB19BA61C4C08"]
 			}
 		}
+		token_rotation: {
+			common:      false
+			description: "Refreshes the default token from a [secret backend](\(urls.vector_configuration)#secrets-management) while the sink is running, so that it can be rotated without restarting Vector."
+			required:    false
+			type: object: {
+				examples: [{backend: "vault", key: "splunk_hec_token", refresh_interval_secs: 300}]
+				options: {
+					backend: {
+						description: "The name of the secret backend, as used in `SECRET[<backend>.<key>]`."
+						required:    true
+						type: string: {
+							examples: ["vault"]
+						}
+					}
+					key: {
+						description: "The key of the token in the secret backend."
+						required:    true
+						type: string: {
+							examples: ["splunk_hec_token"]
+						}
+					}
+					refresh_interval_secs: {
+						common:      false
+						description: "The interval at which the token is refreshed. Minimum of `1`."
+						required:    false
+						type: uint: {
+							default: 300
+							unit:    "seconds"
+						}
+					}
+				}
+			}
+		}
 	}
 	how_it_works: {
 		indexer_acknowledgements: {
@@ -74,6 +134,20 @@ components: sinks: _splunk_hec: {
 				Splunk requires [a channel value](\(urls.splunk_hec_channel_header)) when using indexer acknowledgements, but also accepts
 				channel values when indexer acknowledgements is disabled. Thus, this channel value is included regardless of indexer
 				acknowledgement settings.
+
+				With `acknowledgements.channels` greater than `1`, the requests are spread over as many channels, and the ack ids of a
+				request are always queried on the channel it was sent on, as ack ids are only unique within a channel. When Splunk HEC is
+				behind a load balancer, it must route the requests of a channel to the same indexer, for instance with sticky sessions
+				based on the `X-Splunk-Request-Channel` header.
+				"""
+		}
+		token_rotation: {
+			title: "Token Rotation"
+			body:  """
+				The `default_token` is usually set from a secret backend with `SECRET[<backend>.<key>]`, which is only retrieved when
+				the configuration is loaded. With the `token_rotation` option, the sink also retrieves the token from the secret backend
+				every `refresh_interval_secs` and uses the new token for the following requests, so that it can be rotated without
+				restarting Vector. The current token is kept if it cannot be retrieved.
 				"""
 		}
 	}
@@ -78,17 +78,81 @@ components: sinks: vector: {
 
 	configuration: {
 		address: {
-			description: "The downstream Vector address to connect to. The address _must_ include a port."
-			required:    true
+			description: "The downstream Vector address to connect to. The address _must_ include a port. Exactly one of `address` and `addresses` must be set."
+			common:      true
+			required:    false
 			type: string: {
+				default: null
 				examples: ["92.12.333.224:\(_port)"]
 			}
 		}
+		addresses: {
+			common:      false
+			description: """
+				The addresses of several downstream Vector instances the events are balanced across, in turn.
+				Each address _must_ include a port. Exactly one of `address` and `addresses` must be set.
+				Only supported by version 2 of the sink.
+				"""
+			required: false
+			type: array: {
+				default: null
+				items: type: string: examples: ["10.0.0.1:\(_port)", "10.0.0.2:\(_port)"]
+			}
+		}
+		client_metadata: {
+			common:      false
+			description: """
+				Metadata identifying this Vector instance, such as its agent ID or site, sent with every request so
+				that the `vector` source can attach it to the events with its `client_metadata_key` option. Names
+				can only contain lowercase letters, digits and underscores, and values printable ASCII characters.
+				Only supported by version 2 of the sink.
+				"""
+			required: false
+			type: object: {
+				examples: [{agent_id: "agent-1", site: "paris"}]
+				options: {}
+			}
+		}
 		compression: {
-			description: "Enable gRPC compression with gzip."
-			common:      true
+			description: """
+				The compression of the events. For backward compatibility, `true` and `false` are accepted as
+				`gzip` and `none`.
+				"""
+			common:   true
+			required: false
+			type: string: {
+				default: "none"
+				enum: {
+					none: "No compression."
+					gzip: "gRPC compression with gzip."
+					zstd: """
+						Compression with zstd, once the source advertised it accepts it, and gzip until then. Only
+						supported by version 2 of the sink.
+						"""
+				}
+			}
+		}
+		tls: type: object: options: subject_alt_names: {
+			common:      false
+			description: """
+				The DNS names or IP addresses, one of which the certificate of the downstream Vector instance
+				must have as a subject alternative name, on top of being verified. Requires
+				`tls.verify_certificate` to be enabled.
+				"""
+			required: false
+			type: array: {
+				default: []
+				items: type: string: examples: ["aggregator.example.com", "10.0.0.1"]
+			}
+		}
+		unhealthy_secs: {
+			common:      false
+			description: "How long one of the `addresses` which failed isn't sent events, before being tried again."
 			required:    false
-			type: bool: default: false
+			type: uint: {
+				default: 30
+				unit:    "seconds"
+			}
 		}
 		version: {
 			description: "Sink API version. Specifying this version ensures that Vector does not break backward compatibility."
@@ -105,7 +169,16 @@ components: sinks: vector: {
 		}
 	}
 
-	how_it_works: components.sources.vector.how_it_works
+	how_it_works: components.sources.vector.how_it_works & {
+		load_balancing: {
+			title: "Load balancing"
+			body: """
+				When `addresses` is set, the events are balanced across the downstream Vector instances, in turn.
+				An instance which can't be reached, or fails the health check, isn't sent events for
+				`unhealthy_secs`, and the requests which failed are retried against the other instances.
+				"""
+		}
+	}
 
 	telemetry: metrics: {
 		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
@@ -114,5 +187,6 @@ components: sinks: vector: {
 		processed_bytes_total:            components.sources.internal_metrics.output.metrics.processed_bytes_total
 		processed_events_total:           components.sources.internal_metrics.output.metrics.processed_events_total
 		protobuf_decode_errors_total:     components.sources.internal_metrics.output.metrics.protobuf_decode_errors_total
+		vector_endpoint_unhealthy_total:  components.sources.internal_metrics.output.metrics.vector_endpoint_unhealthy_total
 	}
 }
@@ -84,16 +84,74 @@ components: sinks: websocket: {
 			}
 		}
 		ping_timeout: {
-			common:        true
-			description:   "Try to reconnect to the WebSocket server if pong not received for this number of seconds."
-			relevant_when: "ping_interval is set"
-			required:      false
-			warnings: ["This parameter is not taken into account if ping_interval is not set"]
+			common:      true
+			description: """
+				Try to reconnect to the WebSocket server if a ping isn't answered with a pong within this number
+				of seconds. Without `ping_interval`, only the ping sent after connecting is checked.
+				"""
+			required:    false
+			warnings: []
 			type: uint: {
 				default: null
 				unit:    "seconds"
 			}
 		}
+		reconnect_initial_backoff_ms: {
+			common:      false
+			description: "The delay before the first attempt to reconnect, doubled after each failed attempt."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 500
+				unit:    "milliseconds"
+			}
+		}
+		reconnect_max_backoff_secs: {
+			common:      false
+			description: "The maximum delay between the attempts to reconnect."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		replay_buffer_max_events: {
+			common:      false
+			description: """
+				The maximum number of events kept in memory to be sent after reconnecting: the events accepted
+				while disconnected, and the events sent which the server isn't known to have received yet.
+				"""
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 1000
+				unit:    "events"
+			}
+		}
+	}
+
+	how_it_works: {
+		reconnection: {
+			title: "Reconnection"
+			body: """
+				When the connection fails, or a ping isn't answered within `ping_timeout`, Vector reconnects with
+				an exponential backoff, between `reconnect_initial_backoff_ms` and `reconnect_max_backoff_secs`.
+				In the meantime, events keep being accepted into the replay buffer until it holds
+				`replay_buffer_max_events` events, and are sent once reconnected.
+				"""
+		}
+		replay: {
+			title: "Replay"
+			body: """
+				The pings sent each `ping_interval` tell the position of the last event sent, so that their pongs
+				tell which events the server received. The events sent since the last answered ping are sent again
+				after reconnecting, which may duplicate some of them. Events are acknowledged once the server is
+				known to have received them. When the replay buffer is full, its oldest sent event is dropped, with
+				an error, to make room. Without `ping_interval`, the events are assumed to have been received once
+				sent.
+				"""
+		}
 	}
 
 	input: {
@@ -104,6 +162,8 @@ components: sinks: websocket: {
 
 	telemetry: metrics: {
 		open_connections:                 components.sources.internal_metrics.output.metrics.open_connections
+		component_discarded_events_total: components.sources.internal_metrics.output.metrics.component_discarded_events_total
+		component_errors_total:           components.sources.internal_metrics.output.metrics.component_errors_total
 		connection_established_total:     components.sources.internal_metrics.output.metrics.connection_established_total
 		connection_failed_total:          components.sources.internal_metrics.output.metrics.connection_failed_total
 		connection_shutdown_total:        components.sources.internal_metrics.output.metrics.connection_shutdown_total
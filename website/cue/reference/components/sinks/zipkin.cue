@@ -0,0 +1,118 @@
+package metadata
+
+components: sinks: zipkin: {
+	title: "Zipkin"
+
+	description: """
+		Exports traces with the [Zipkin v2 JSON API](\(urls.zipkin_api)), to Zipkin or any
+		tracing backend accepting it.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_events:   1000
+				timeout_secs: 1.0
+			}
+			compression: enabled: false
+			encoding: enabled:    false
+			proxy: enabled:       true
+			request: {
+				enabled: true
+				headers: false
+			}
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.zipkin
+
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	input: {
+		logs:    false
+		metrics: null
+		traces:  true
+	}
+
+	configuration: {
+		endpoint: {
+			description: """
+				The base URL of the Zipkin API. Vector posts the spans to `/api/v2/spans` and checks
+				the health of the server with `/health`.
+				"""
+			required: true
+			type: string: {
+				examples: ["http://localhost:9411", "https://tempo.example.com:9411"]
+			}
+		}
+		compression: {
+			common:      true
+			description: "Compress the requests with gzip."
+			required:    false
+			type: bool: default: false
+		}
+		auth: configuration._http_auth & {_args: {
+			password_example: "${ZIPKIN_PASSWORD}"
+			username_example: "${ZIPKIN_USERNAME}"
+		}}
+	}
+
+	how_it_works: {
+		mapping: {
+			title: "Mapping to Zipkin spans"
+			body: """
+				Traces received by the [`opentelemetry` source](\(urls.vector_sources)/opentelemetry/)
+				hold one span each, whose `service.name` resource names its local endpoint. Traces
+				received by the [`datadog_agent` source](\(urls.vector_sources)/datadog_agent/) hold
+				all the spans in their `spans` field, with their `resource`, `meta` and `metrics`
+				written as tags.
+
+				As Zipkin spans only have string tags, the other resources and the attributes of the
+				spans are written as strings, and failed spans have an `error` tag holding their
+				status message. The events of the spans are written as annotations, and their links,
+				which have no Zipkin counterpart, aren't sent. Traces missing the IDs or timestamps of
+				a span are dropped.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_discarded_events_total: components.sources.internal_metrics.output.metrics.component_discarded_events_total
+		component_errors_total:           components.sources.internal_metrics.output.metrics.component_errors_total
+		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+	}
+}
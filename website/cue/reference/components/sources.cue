@@ -26,17 +26,52 @@ components: sources: [Name=string]: {
 				description: "Multiline parsing configuration. If not specified, multiline parsing is disabled."
 				required:    false
 				type: object: options: {
+					condition: {
+						common:      false
+						description: "Condition [VRL](\(urls.vrl_reference)) expression to look for, instead of `condition_pattern`. It's checked against an event whose `message` is the line, and whose `previous` field is the preceding line of the message being aggregated. Exact behavior is configured via `mode`."
+						required:    false
+						sort:        3
+						type: string: {
+							default: null
+							examples: [#"starts_with(string!(.message), " ") || ends_with(string(.previous) ?? "", ",")"#]
+							syntax: "remap_program"
+						}
+					}
 					condition_pattern: {
-						description: "Condition regex pattern to look for. Exact behavior is configured via `mode`."
-						required:    true
+						common:      true
+						description: "Condition regex pattern to look for. Exact behavior is configured via `mode`. Exactly one of `condition_pattern` or `condition` must be set."
+						required:    false
 						sort:        3
 						type: string: {
+							default: null
 							examples: ["^[\\s]+", "\\\\$", "^(INFO|ERROR) ", ";$"]
 							syntax: "regex"
 						}
 					}
+					max_bytes: {
+						common:      false
+						description: "The maximum size of an aggregated message. Once reached, the message is flushed, and the following lines are handled as new ones."
+						required:    false
+						sort:        5
+						type: uint: {
+							default: null
+							examples: [65_536]
+							unit: "bytes"
+						}
+					}
+					max_lines: {
+						common:      false
+						description: "The maximum number of lines of an aggregated message. Once reached, the message is flushed, and the following lines are handled as new ones."
+						required:    false
+						sort:        5
+						type: uint: {
+							default: null
+							examples: [500]
+							unit: "lines"
+						}
+					}
 					mode: {
-						description: "Mode of operation, specifies how the `condition_pattern` is interpreted."
+						description: "Mode of operation, specifies how the `condition_pattern`, or the `condition`, is interpreted."
 						required:    true
 						sort:        2
 						type: string: {
@@ -48,11 +83,24 @@ components: sources: [Name=string]: {
 							}
 						}
 					}
+					start_condition: {
+						common:      false
+						description: "Start [VRL](\(urls.vrl_reference)) condition to look for as a beginning of the message, instead of `start_pattern`. It's checked against an event whose `message` is the line."
+						required:    false
+						sort:        1
+						type: string: {
+							default: null
+							examples: [#"!starts_with(string!(.message), " ")"#]
+							syntax: "remap_program"
+						}
+					}
 					start_pattern: {
-						description: "Start regex pattern to look for as a beginning of the message."
-						required:    true
+						common:      true
+						description: "Start regex pattern to look for as a beginning of the message. Exactly one of `start_pattern` or `start_condition` must be set."
+						required:    false
 						sort:        1
 						type: string: {
+							default: null
 							examples: ["^[^\\s]", "\\\\$", "^(INFO|ERROR) ", "[^;]$"]
 							syntax: "regex"
 						}
@@ -171,6 +219,8 @@ components: sources: [Name=string]: {
 									syslog:      "Events being parsed from a Syslog message."
 									native:      "Events being parsed from Vector's [native protobuf format](\(urls.native_proto_schema)) ([EXPERIMENTAL](/highlights/2022-03-31-native-event-codecs))."
 									native_json: "Events being parsed from Vector's [native JSON format](\(urls.native_json_schema)) ([EXPERIMENTAL](/highlights/2022-03-31-native-event-codecs))."
+									aws_cloudtrail:    "Events being parsed from the records of an [AWS CloudTrail log file](\(urls.aws_cloudtrail_log_files)), or from an [AWS CloudTrail digest file](\(urls.aws_cloudtrail_digest_files))."
+									aws_s3_access_log: "Events being parsed from a record of an [AWS S3 server access log](\(urls.aws_s3_server_access_logs))."
 								}
 							}
 						}
@@ -200,6 +250,19 @@ components: sources: [Name=string]: {
 			}
 		}
 
+		sequence_numbers: {
+			common:      false
+			description: """
+				Whether to number the events sent by each output of the source, in the order they are sent.
+				Sinks with `check_sequence_numbers` enabled report the events missing from the sequence, or
+				received out of order, which helps find where events were lost. The numbers are kept in the
+				event metadata, so they aren't part of the event data and don't survive disk buffers, and
+				they start from zero again when the source is restarted.
+				"""
+			required:    false
+			type: bool: default: false
+		}
+
 		if features.collect != _|_ {
 			if features.collect.proxy != _|_ {
 				if features.collect.proxy.enabled {
@@ -348,3 +411,26 @@ components: sources: [Name=string]: {
 		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
 	}
 }
+
+components: _audit_log_how_it_works: {
+	polling: {
+		title: "Polling"
+		body: """
+			The API is polled on an interval for the records published since the last poll,
+			fetching page after page until it's caught up. The position reached is
+			checkpointed in the data directory once the records of a page have been sent, so
+			collection resumes from there after a restart. Without a checkpoint, collection
+			starts `lookback_secs` in the past.
+			"""
+	}
+	rate_limits: {
+		title: "Rate limits"
+		body: """
+			Requests the API rejects for exceeding its rate limits are retried after the delay
+			it asks for, with the `Retry-After` or `X-Rate-Limit-Reset` headers, and with an
+			exponential backoff otherwise. Requests failing on the side of the API are retried
+			in the same way. A poll which still fails is abandoned, to be resumed by the next
+			one.
+			"""
+	}
+}
@@ -0,0 +1,130 @@
+package metadata
+
+components: sources: amqp: {
+	title: "AMQP"
+
+	features: {
+		acknowledgements: true
+		collect: {
+			checkpoint: enabled: false
+			tls: {
+				enabled:                true
+				can_verify_certificate: false
+				enabled_default:        false
+			}
+			from: components._amqp.features.collect.from
+		}
+		multiline: enabled: false
+		codecs: {
+			enabled:         true
+			default_framing: "bytes"
+		}
+	}
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	support: components._amqp.support
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements:  configuration._source_acknowledgements
+		connection_string: components._amqp.configuration.connection_string
+		consumer: {
+			common:      false
+			description: "The tag identifying the consumer on the channel."
+			required:    false
+			type: string: {
+				default: "vector"
+				examples: ["vector", "logs-archiver"]
+			}
+		}
+		prefetch_count: {
+			common:      true
+			description: "The number of messages the server [sends](\(urls.rabbitmq_prefetch)) without waiting for them to be acknowledged, which is unlimited when unset."
+			required:    false
+			type: uint: {
+				default: null
+				examples: [100, 1000]
+				unit: null
+			}
+		}
+		queue: {
+			description: "The queue the messages are consumed from, which must already exist."
+			required:    true
+			type: string: {
+				examples: ["logs"]
+			}
+		}
+	}
+
+	output: logs: record: {
+		description: "An individual AMQP message"
+		fields: {
+			exchange: {
+				description: "The exchange the message was published to, which is empty for the default exchange."
+				required:    true
+				type: string: {
+					examples: ["logs", ""]
+				}
+			}
+			message: {
+				description: "The raw line from the AMQP message."
+				required:    true
+				type: string: {
+					examples: ["53.126.150.246 - - [01/Oct/2020:11:25:58 -0400] \"GET /disintermediate HTTP/2.0\" 401 20308"]
+				}
+			}
+			routing_key: {
+				description: "The routing key the message was published with."
+				required:    true
+				type: string: {
+					examples: ["logs.nginx"]
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The `timestamp` property of the message, or the time it was received when it has none."
+			}
+		}
+	}
+
+	how_it_works: {
+		acknowledgements: {
+			title: "Acknowledgements"
+			body: """
+				Each message is acknowledged once its events are delivered, when acknowledgements are
+				enabled, and as soon as its events are sent downstream otherwise. Messages whose
+				events fail to be delivered are requeued, while the ones whose events are rejected
+				are rejected without being requeued, which sends them to the
+				[dead letter exchange](\(urls.rabbitmq_dlx)) of the queue when it has one. The
+				`prefetch_count` bounds the number of messages waiting for their events to be
+				delivered.
+				"""
+		}
+		reconnection: {
+			title: "Reconnection"
+			body: """
+				Vector reconnects to the server, with an exponential backoff, whenever its connection
+				is lost. The messages that weren't acknowledged yet are then redelivered by the
+				server.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_in_total:                      components.sources.internal_metrics.output.metrics.events_in_total
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+	}
+}
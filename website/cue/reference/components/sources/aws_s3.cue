@@ -70,6 +70,31 @@ components: sources: aws_s3: components._aws & {
 				}
 			}
 		}
+		decoding: {
+			common:      false
+			description: """
+				Configures in which way objects are decoded into events. Metrics and traces, as decoded by the
+				`native` and `native_json` codecs, are forwarded as is, without the `bucket`, `object`, and
+				`region` fields added to logs.
+				"""
+			required: false
+			type: object: options: {
+				codec: {
+					description: "The decoding method."
+					required:    false
+					common:      true
+					type: string: {
+						default: "bytes"
+						enum: {
+							bytes:             "Events containing a line of the object as-is."
+							json:              "Events being parsed from a line of the object holding JSON."
+							aws_cloudtrail:    "Events being parsed from the records of an [AWS CloudTrail log file](\(urls.aws_cloudtrail_log_files)), or from an [AWS CloudTrail digest file](\(urls.aws_cloudtrail_digest_files)). The object is decoded as a whole."
+							aws_s3_access_log: "Events being parsed from a line of an [AWS S3 server access log](\(urls.aws_s3_server_access_logs))."
+						}
+					}
+				}
+			}
+		}
 		sqs: {
 			common:      true
 			description: "SQS strategy options. Required if strategy=`sqs`."
@@ -125,7 +150,7 @@ components: sources: aws_s3: components._aws & {
 				}
 			}
 			timestamp: fields._current_timestamp & {
-				description: "The Last-Modified time of the object. Defaults the current timestamp if this information is missing."
+				description: "The time decoded from the event, such as the `eventTime` of a CloudTrail record. Otherwise the Last-Modified time of the object, or the current timestamp if this information is missing."
 			}
 			bucket: {
 				description: "The bucket of the object the line came from."
@@ -152,6 +177,27 @@ components: sources: aws_s3: components._aws & {
 	}
 
 	how_it_works: {
+		decoding_presets: {
+			title: "Decoding AWS logs"
+			body:  """
+				Logs that AWS services deliver to S3 can be parsed into typed
+				events by setting `decoding.codec`, without having to write a
+				transform for them:
+
+				* `aws_cloudtrail` decodes each object as a whole, and outputs an
+				  event for every record of a CloudTrail log file, timestamped by
+				  its `eventTime`. A digest file is output as a single event,
+				  timestamped by its `digestEndTime`.
+				* `aws_s3_access_log` outputs an event for every line of a server
+				  access log, with a field for each of its values, named after the
+				  [log format](\(urls.aws_s3_server_access_logs)), such as
+				  `bucket`, `operation` or `http_status`. Status codes, sizes and
+				  times are integers, the time of the request becomes the
+				  timestamp, and values logged as `-` are left out.
+
+				Lines and objects which can't be decoded are logged and skipped.
+				"""
+		}
 		events: {
 			title: "Handling events from the `aws_s3` source"
 			body:  """
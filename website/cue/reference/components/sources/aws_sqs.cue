@@ -78,6 +78,23 @@ components: sources: aws_sqs: components._aws & {
 				unit:    "seconds"
 			}
 		}
+		extended_payloads: {
+			common:      false
+			description: """
+				Fetches the payloads stored in S3 by the [SQS Extended Client Library](\(urls.aws_sqs_extended_client_library)),
+				in place of the pointers sent in their messages. The payloads are fetched with the
+				authentication, region and endpoint of the queue.
+				"""
+			required:    false
+			type: object: options: {
+				delete_payloads: {
+					common:      false
+					description: "Whether to delete the payloads from S3 along with their messages."
+					required:    false
+					type: bool: default: false
+				}
+			}
+		}
 		delete_message: {
 			common:      true
 			description: "Whether to delete the message once Vector processes it. It can be useful to set this to `false` to debug or during initial Vector setup."
@@ -123,6 +140,7 @@ components: sources: aws_sqs: components._aws & {
 	}
 
 	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
 		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
 		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
 		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
@@ -141,5 +159,19 @@ components: sources: aws_sqs: components._aws & {
 				`acknowledgements` setting.
 				"""
 		}
+		fifo_queues: {
+			title: "FIFO queues"
+			body: """
+				The messages of the [FIFO queues](\(urls.aws_sqs_fifo)), whose URL ends with `.fifo`,
+				are processed in the order of their message group: the messages of each group received
+				together are sent in order, as a batch of their own, while the groups are processed in
+				parallel. SQS doesn't deliver the following messages of a group until the messages
+				received are deleted, so that the groups are processed serially across all the clients.
+
+				When the payload of a message can't be fetched from S3, the message is left in the
+				queue along with the following messages of its group, to be received again once their
+				visibility timeout expires.
+				"""
+		}
 	}
 }
@@ -0,0 +1,238 @@
+package metadata
+
+components: sources: azure_event_hubs: {
+	title: "Azure Event Hubs"
+
+	features: {
+		acknowledgements: true
+		collect: {
+			checkpoint: enabled: true
+			from: {
+				service: services.azure_event_hubs
+				interface: {
+					socket: {
+						api: {
+							title: "AMQP 1.0"
+							url:   urls.azure_event_hubs_amqp
+						}
+						direction: "outgoing"
+						port:      5671
+						protocols: ["tcp"]
+						ssl: "required"
+					}
+				}
+			}
+		}
+		multiline: enabled: false
+		codecs: {
+			enabled:         true
+			default_framing: "bytes"
+		}
+	}
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: ["Azure"]
+		stateful: false
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._source_acknowledgements
+		checkpoint_store: {
+			description: "The Azure Blob Storage container the checkpoints and the ownership of the partitions are stored in."
+			required:    true
+			type: object: options: {
+				connection_string: {
+					common:      true
+					description: "The connection string of the storage account, which is authenticated with through Azure AD when unset."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["DefaultEndpointsProtocol=https;AccountName=mylogstorage;AccountKey=storageaccountkeybase64encoded;EndpointSuffix=core.windows.net"]
+					}
+				}
+				container_name: {
+					description: "The name of the container, which must already exist."
+					required:    true
+					type: string: examples: ["checkpoints"]
+				}
+				storage_account: {
+					common:      true
+					description: "The name of the storage account, which is authenticated with through Azure AD, when there is no connection string."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["mylogstorage"]
+					}
+				}
+			}
+		}
+		connection_string: {
+			common:      true
+			description: "A connection string of the namespace, or of the event hub, which authenticates with a shared access key."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["Endpoint=sb://example.servicebus.windows.net/;SharedAccessKeyName=vector;SharedAccessKey=c2VjcmV0;EntityPath=logs"]
+			}
+		}
+		consumer_group: {
+			common:      true
+			description: "The consumer group the event hub is read by, whose partitions are shared by the instances of Vector using it."
+			required:    false
+			type: string: {
+				default: "$Default"
+				examples: ["vector"]
+			}
+		}
+		event_hub_name: {
+			common:      true
+			description: "The name of the event hub, which is read from the `EntityPath` of the connection string when unset."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["logs"]
+			}
+		}
+		load_balancing_interval_secs: {
+			common:      false
+			description: "How often the checkpoints are written, and the ownership of the partitions renewed and balanced among the instances of Vector."
+			required:    false
+			type: uint: {
+				default: 10
+				unit:    "seconds"
+			}
+		}
+		namespace: {
+			common:      true
+			description: "The fully qualified name of the namespace, which is authenticated with through [Azure AD](\(urls.azure_identity_default_credential)), such as with a managed identity, when there is no connection string."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["example.servicebus.windows.net"]
+			}
+		}
+		ownership_expiration_secs: {
+			common:      false
+			description: "How long the ownership of a partition lasts without being renewed, after which the other instances of Vector take the partition over."
+			required:    false
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		start_position: {
+			common:      false
+			description: "Where the partitions without a checkpoint are read from."
+			required:    false
+			type: string: {
+				default: "latest"
+				enum: {
+					earliest: "The oldest events retained by the event hub."
+					latest:   "The events enqueued once the partition is read."
+				}
+			}
+		}
+	}
+
+	output: logs: record: {
+		description: "An individual Event Hubs event"
+		fields: {
+			message: {
+				description: "The raw line from the body of the event."
+				required:    true
+				type: string: {
+					examples: ["53.126.150.246 - - [01/Oct/2020:11:25:58 -0400] \"GET /disintermediate HTTP/2.0\" 401 20308"]
+				}
+			}
+			offset: {
+				description: "The offset of the event in its partition."
+				required:    true
+				type: string: {
+					examples: ["4096"]
+				}
+			}
+			partition_id: {
+				description: "The partition the event was read from."
+				required:    true
+				type: string: {
+					examples: ["0"]
+				}
+			}
+			partition_key: {
+				description: "The key the event was sent with, if any."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["api"]
+				}
+			}
+			sequence_number: {
+				description: "The sequence number of the event in its partition."
+				required:    true
+				type: uint: {
+					examples: [42]
+					unit: null
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The time the event was enqueued at."
+			}
+		}
+	}
+
+	how_it_works: {
+		checkpointing: {
+			title: "Checkpointing"
+			body: """
+				The offset of the latest event read from each partition is written to a blob of the
+				checkpoint store on every load balancing cycle, once the event is delivered when
+				acknowledgements are enabled, and once it's sent downstream otherwise. A partition
+				is read from its checkpoint when its reading starts, such as after a restart. The
+				blobs follow the layout of the checkpoint stores of the Azure SDKs, so that the
+				applications built with them and Vector can take over each other's consumer group.
+				"""
+		}
+		load_balancing: {
+			title: "Load balancing"
+			body: """
+				The instances of Vector sharing a consumer group and a checkpoint store share the
+				partitions of the event hub. Each instance claims the ownership of the partitions
+				it reads in the checkpoint store, and renews it on every load balancing cycle, during
+				which it claims one more partition while it owns less than its share of them. The
+				partitions whose ownership expires, such as when their instance stops, are claimed
+				by the other instances.
+				"""
+		}
+		authentication: {
+			title: "Authentication"
+			body: """
+				The event hub is authenticated with through a shared access key when it's set by the
+				connection string, and through Azure AD otherwise, with the credentials of the
+				environment, of a managed identity, or of the Azure CLI, in that order.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_in_total:                      components.sources.internal_metrics.output.metrics.events_in_total
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+	}
+}
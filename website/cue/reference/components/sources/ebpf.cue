@@ -0,0 +1,199 @@
+package metadata
+
+components: sources: ebpf: {
+	title: "eBPF"
+
+	description: """
+		Captures process and network telemetry from the Linux kernel with
+		[eBPF](\(urls.ebpf)) programs: the processes executed and exited, and the
+		TCP connections opened and accepted on the host, as structured logs.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["daemon"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: false
+		collect: {
+			checkpoint: enabled: false
+			from: service:       services.host
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  false
+			"armv7-unknown-linux-musleabihf": false
+			"x86_64-apple-darwin":            false
+			"x86_64-pc-windows-msv":          false
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+
+		requirements: [
+			"""
+				Linux 5.8 or later, built with `CONFIG_DEBUG_INFO_BTF`, so that
+				`/sys/kernel/btf/vmlinux` exists. Vector checks this when the source starts.
+				""",
+			"""
+				Vector must run as root, or with the `CAP_BPF` and `CAP_PERFMON` capabilities.
+				When running in a container, it must be privileged and share the PID namespace
+				of the host.
+				""",
+		]
+		warnings: [
+			"""
+				This source is experimental, and isn't part of the default builds. Build Vector
+				with the `sources-ebpf` feature, which requires `clang` to compile the eBPF
+				programs.
+				""",
+		]
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		probes: {
+			common:      true
+			description: "The probes to attach to the kernel."
+			required:    false
+			type: array: {
+				default: ["process_exec", "process_exit", "tcp_connect", "tcp_accept"]
+				items: type: string: {
+					enum: {
+						process_exec: "The processes executed, with the `sched_process_exec` tracepoint."
+						process_exit: "The processes exited, with the `sched_process_exit` tracepoint."
+						tcp_connect:  "The TCP connections opened, with kprobes on `tcp_v4_connect` and `tcp_v6_connect`."
+						tcp_accept:   "The TCP connections accepted, with a kprobe on `inet_csk_accept`."
+					}
+				}
+			}
+		}
+	}
+
+	output: logs: event: {
+		description: "A process or a network event."
+		fields: {
+			comm: {
+				description: "The name of the command of the process, truncated to 15 characters by the kernel."
+				required:    true
+				type: string: examples: ["curl"]
+			}
+			destination_address: {
+				description: "The remote address of the connection, for the TCP events."
+				required:    false
+				type: string: examples: ["93.184.216.34", "2001:db8::1"]
+			}
+			destination_port: {
+				description: "The remote port of the connection, for the TCP events."
+				required:    false
+				type: uint: {
+					examples: [443]
+					unit: null
+				}
+			}
+			event: {
+				description: "The kind of the event."
+				required:    true
+				type: string: enum: {
+					process_exec: "A process executed a file."
+					process_exit: "A process exited."
+					tcp_connect:  "A process opened a TCP connection."
+					tcp_accept:   "A process accepted a TCP connection."
+				}
+			}
+			exit_code: {
+				description: "The exit code of the process, for the `process_exit` events."
+				required:    false
+				type: int: examples: [0, 1]
+			}
+			filename: {
+				description: "The file executed, for the `process_exec` events, truncated to 127 bytes."
+				required:    false
+				type: string: examples: ["/usr/bin/curl"]
+			}
+			host: fields._local_host
+			pid: {
+				description: "The ID of the process."
+				required:    true
+				type: uint: {
+					examples: [1234]
+					unit: null
+				}
+			}
+			ppid: {
+				description: "The ID of the parent of the process."
+				required:    true
+				type: uint: {
+					examples: [1]
+					unit: null
+				}
+			}
+			source_address: {
+				description: "The local address of the connection, for the TCP events."
+				required:    false
+				type: string: examples: ["10.0.0.1", "2001:db8::2"]
+			}
+			source_port: {
+				description: "The local port of the connection, for the TCP events."
+				required:    false
+				type: uint: {
+					examples: [54321]
+					unit: null
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: examples: ["ebpf"]
+			}
+			timestamp: fields._current_timestamp
+			uid: {
+				description: "The ID of the user running the process."
+				required:    true
+				type: uint: {
+					examples: [1000]
+					unit: null
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		co_re: {
+			title: "Portability"
+			body: """
+				The eBPF programs are compiled along with Vector, and are relocated against
+				the BTF of the running kernel when the source starts ([CO-RE](\(urls.ebpf_core))),
+				so the same Vector binary runs on every supported kernel without kernel headers.
+				"""
+		}
+		ring_buffer: {
+			title: "Ring buffer"
+			body: """
+				The programs send the events through a ring buffer of 256 KiB. When Vector
+				doesn't read the events as fast as they are produced, the ring buffer fills
+				up and the new events are dropped by the kernel.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+	}
+}
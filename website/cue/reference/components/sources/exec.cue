@@ -13,7 +13,7 @@ components: sources: exec: {
 	}
 
 	features: {
-		acknowledgements: false
+		acknowledgements: true
 		multiline: enabled: false
 		codecs: {
 			enabled:         true
@@ -39,6 +39,7 @@ components: sources: exec: {
 	}
 
 	configuration: {
+		acknowledgements: configuration._source_acknowledgements
 		mode: {
 			description: "The type of exec mechanism."
 			required:    true
@@ -180,6 +181,15 @@ components: sources: exec: {
 	]
 
 	how_it_works: {
+		acknowledgements: {
+			title: "Acknowledgements"
+			body: """
+				Commands can't be told which of their lines were delivered, so with acknowledgements
+				enabled, each run of the command waits for the events it produced to be delivered
+				before finishing, holding off the next scheduled run or the respawn of a streaming
+				command, and logs an error if any of them were not delivered.
+				"""
+		}
 		line_delimiters: {
 			title: "Line Delimiters"
 			body: """
@@ -170,6 +170,16 @@ components: sources: file: {
 				examples: ["\r\n"]
 			}
 		}
+		max_checkpoints: {
+			common:      false
+			description: "The maximum number of checkpoints kept in the data directory. Once reached, the checkpoints of removed files are discarded first, then the least recently updated ones, whose files are read again from `read_from` if Vector restarts. If not specified, the checkpoints are only discarded once their files are removed."
+			required:    false
+			type: uint: {
+				default: null
+				examples: [10_000]
+				unit: null
+			}
+		}
 		max_line_bytes: {
 			common:      false
 			description: "The maximum number of a bytes a line can contain before being discarded. This protects against malformed lines or tailing incorrect files."
@@ -400,6 +410,19 @@ components: sources: file: {
 				"""
 		}
 
+		checkpoint_expiration: {
+			title: "Checkpoint Expiration"
+			body: """
+				The checkpoints of files that are no longer found, because they were
+				removed or rotated away, are discarded 60 seconds later, unless their
+				files are found again. This includes the checkpoints of the files that
+				were removed while Vector wasn't running, which are discarded 60 seconds
+				after Vector starts. In environments where files churn faster than that,
+				such as hosts running many short-lived containers, the `max_checkpoints`
+				option caps the number of checkpoints kept.
+				"""
+		}
+
 		line_delimiters: {
 			title: "Line Delimiters"
 			body: """
@@ -529,6 +552,46 @@ components: sources: file: {
 							a timestamp sequence.
 						"""#
 				},
+				{
+					title: "Example 4: Conditions"
+					body: #"""
+						When whether a line continues a message depends on its context,
+						such as the preceding line, the patterns can be replaced with
+						[VRL](\#(urls.vrl_reference)) conditions. They are checked against
+						an event whose `message` is the line, and whose `previous` field is
+						the preceding line of the message being aggregated:
+
+						```text
+						Traceback (most recent call last):
+						  File "app.py", line 3, in <module>
+						    main(config,
+						args)
+						ValueError: invalid config
+						```
+
+						To consume these lines as a single event, use the following Vector
+						configuration:
+
+						```toml
+						[sources.my_file_source]
+						type = "file"
+						# ...
+
+						[sources.my_file_source.multiline]
+						start_pattern = '^Traceback'
+						mode = "continue_through"
+						condition = 'starts_with(string!(.message), " ") || ends_with(string(.previous) ?? "", ",") || contains(string!(.message), "Error: ")'
+						timeout_ms = 1000
+						max_lines = 500
+						```
+
+						* `condition` tells Vector to continue aggregating lines if they
+							are indented, follow a line ending with a comma, or are the
+							error closing the traceback.
+						* `max_lines`, set to `500`, tells Vector to flush the event once
+							it reaches 500 lines, even if the traceback isn't complete.
+						"""#
+				},
 			]
 		}
 
@@ -593,7 +656,10 @@ components: sources: file: {
 		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
 		events_in_total:                      components.sources.internal_metrics.output.metrics.events_in_total
 		checkpoint_write_errors_total:        components.sources.internal_metrics.output.metrics.checkpoint_write_errors_total
+		checkpoint_entries:                   components.sources.internal_metrics.output.metrics.checkpoint_entries
+		checkpoint_file_size_bytes:           components.sources.internal_metrics.output.metrics.checkpoint_file_size_bytes
 		checkpoints_total:                    components.sources.internal_metrics.output.metrics.checkpoints_total
+		checkpoints_removed_total:            components.sources.internal_metrics.output.metrics.checkpoints_removed_total
 		checksum_errors_total:                components.sources.internal_metrics.output.metrics.checksum_errors_total
 		file_delete_errors_total:             components.sources.internal_metrics.output.metrics.file_delete_errors_total
 		file_watch_errors_total:              components.sources.internal_metrics.output.metrics.file_watch_errors_total
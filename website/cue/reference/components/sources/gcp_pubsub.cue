@@ -79,6 +79,46 @@ components: sources: gcp_pubsub: {
 				examples: ["https://us-central1-pubsub.googleapis.com"]
 			}
 		}
+		keepalive_secs: {
+			common:      false
+			description: "How often an empty request is sent on the stream, which keeps it from being closed while there is nothing to acknowledge."
+			required:    false
+			type: float: {
+				default: 60.0
+				examples: [30.0]
+				unit: "seconds"
+			}
+		}
+		max_lease_duration_secs: {
+			common:      false
+			description: "How long the deadline of the messages waiting for their events to be acknowledged is extended for, after which the server redelivers them."
+			required:    false
+			type: uint: {
+				default: 3600
+				examples: [600, 3600]
+				unit: "seconds"
+			}
+		}
+		max_outstanding_bytes: {
+			common:      false
+			description: "The maximum size of the messages delivered to Vector while their events wait to be acknowledged, beyond which the server stops delivering messages."
+			required:    false
+			type: uint: {
+				default: 104857600
+				examples: [104857600]
+				unit: "bytes"
+			}
+		}
+		max_outstanding_messages: {
+			common:      false
+			description: "The maximum number of messages delivered to Vector while their events wait to be acknowledged, beyond which the server stops delivering messages."
+			required:    false
+			type: uint: {
+				default: 1000
+				examples: [1000]
+				unit: null
+			}
+		}
 		project: {
 			description: "The project name from which to pull logs."
 			required:    true
@@ -154,5 +194,27 @@ components: sources: gcp_pubsub: {
 				have the `acknowledgements` setting enabled.
 				"""
 		}
+		flow_control: {
+			title: "Flow control and lease extension"
+			body: """
+				The server stops delivering messages on the stream once `max_outstanding_messages`
+				messages, or `max_outstanding_bytes` bytes of them, are waiting for their events to
+				be acknowledged. The deadline of these messages is extended halfway through the
+				`ack_deadline_seconds`, so that slow pipelines don't have them redelivered, until
+				`max_lease_duration_secs` elapses. Messages whose events fail to be delivered are
+				redelivered right away, while the ones whose events are rejected are left to expire,
+				and redelivered after their deadline, or with the backoff of the retry policy of the
+				subscription.
+				"""
+		}
+		exactly_once_delivery: {
+			title: "Exactly-once delivery"
+			body: """
+				When exactly-once delivery is enabled on the subscription, the acknowledgements are
+				confirmed by the server, and the ones not confirmed in time are sent again. The
+				acknowledgements the server rejects, such as the ones of messages whose deadline
+				expired, are reported, and their messages are redelivered.
+				"""
+		}
 	}
 }
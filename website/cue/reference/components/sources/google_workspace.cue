@@ -0,0 +1,132 @@
+package metadata
+
+components: sources: google_workspace: {
+	title: "Google Workspace"
+
+	description: """
+		Collects the activities of a Google Workspace application, such as logins or admin
+		console changes, from the [Reports API](\(urls.google_workspace_reports)) of the Admin
+		SDK.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: false
+		collect: {
+			checkpoint: enabled: true
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			proxy: enabled: true
+			from: service:  services.google_workspace
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		requirements: [
+			"""
+				The source requires a service account granted [domain-wide
+				delegation](\(urls.google_workspace_delegation)) of the
+				`https://www.googleapis.com/auth/admin.reports.audit.readonly` scope, and an
+				administrator allowed to view reports for it to act on behalf of.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		application: {
+			common:      true
+			description: "The application whose activities are collected."
+			required:    false
+			type: string: {
+				default: "login"
+				examples: ["admin", "drive", "login", "token"]
+			}
+		}
+		credentials_path: {
+			common:      true
+			description: "The filename of the key of the service account, in JSON. If this is unset, Vector checks the `GOOGLE_APPLICATION_CREDENTIALS` environment variable for a filename."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["/path/to/credentials.json"]
+			}
+		}
+		delay_secs: {
+			common:      false
+			description: "Activities can take a while to be reported, so each poll collects the activities up to this long ago."
+			required:    false
+			type: uint: {
+				default: 180
+				unit:    "seconds"
+			}
+		}
+		subject: {
+			description: "The email address of the administrator the service account acts on behalf of."
+			required:    true
+			type: string: {
+				examples: ["admin@example.com"]
+			}
+		}
+		interval_secs: configuration._audit_log_interval_secs
+		lookback_secs: configuration._audit_log_lookback_secs
+	}
+
+	how_it_works: {
+		polling:     components._audit_log_how_it_works.polling
+		rate_limits: components._audit_log_how_it_works.rate_limits
+	}
+
+	output: logs: activity: {
+		description: "An activity of the application, holding the fields of its [activity resource](\(urls.google_workspace_reports))."
+		fields: {
+			actor: {
+				description: "The user who performed the activity."
+				required:    true
+				type: object: {
+					examples: [{"email": "user@example.com", "profileId": "1234567890"}]
+				}
+			}
+			events: {
+				description: "The events of the activity."
+				required:    true
+				type: array: items: type: object: {}
+			}
+			id: {
+				description: "The identifier of the activity, including the time it happened."
+				required:    true
+				type: object: {
+					examples: [{"time": "2022-06-01T12:30:15.000Z", "uniqueQualifier": "-1234", "applicationName": "login"}]
+				}
+			}
+			timestamp: {
+				description: "The time the activity happened, or when it was collected if that's unknown."
+				required:    true
+				type: timestamp: {}
+			}
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:          components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_events_total: components.sources.internal_metrics.output.metrics.component_received_events_total
+	}
+}
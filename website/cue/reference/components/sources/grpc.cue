@@ -0,0 +1,129 @@
+package metadata
+
+components: sources: grpc: {
+	_port: 50051
+
+	title: "gRPC"
+
+	description: """
+		Receives the messages sent to a [gRPC](\(urls.grpc)) method described by a user-provided
+		file descriptor set, and decodes them to structured log events.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator", "sidecar"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: true
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: services.grpc
+
+				interface: socket: {
+					direction: "incoming"
+					port:      _port
+					protocols: ["http"]
+					ssl: "optional"
+				}
+			}
+
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				enabled_default:        false
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._source_acknowledgements
+		address: {
+			description: "The address to listen for gRPC connections on. It _must_ include a port."
+			required:    true
+			type: string: {
+				examples: ["0.0.0.0:\(_port)"]
+			}
+		}
+		descriptor_set: {
+			description: """
+				The path of the file descriptor set describing the method, its service and its
+				messages, as generated by `protoc --include_imports --descriptor_set_out`.
+				"""
+			required: true
+			type: string: {
+				examples: ["/etc/vector/logs.desc"]
+			}
+		}
+		method: {
+			description: """
+				The method to expose, as in `package.Service/Method`. The method may stream its
+				requests, but not its responses.
+				"""
+			required: true
+			type: string: {
+				examples: ["logs.v1.LogService/Push"]
+			}
+		}
+	}
+
+	output: logs: message: {
+		description: "A message received by the method."
+		fields: {
+			"*": {
+				description: """
+					The fields set in the message, following the [JSON mapping of Protobuf](\(urls.protobuf_json_mapping)),
+					which omits the fields set to their default value.
+					"""
+				required: true
+				type: "*": {}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["grpc"]
+				}
+			}
+			timestamp: fields._current_timestamp
+		}
+	}
+
+	how_it_works: {
+		responses: {
+			title: "Responses"
+			body: """
+				The method responds with an empty message of its output type once the events of the
+				request are accepted, or delivered by the sinks when acknowledgements are enabled.
+				Requests with messages that can't be decoded fail with the `INVALID_ARGUMENT` status,
+				and requests whose events fail to be delivered with the `INTERNAL` or `DATA_LOSS`
+				statuses, so that clients can retry them. The calls to other methods fail with the
+				`UNIMPLEMENTED` status.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		events_in_total:                      components.sources.internal_metrics.output.metrics.events_in_total
+	}
+}
@@ -87,7 +87,7 @@ components: sources: http: {
 				}
 			}
 		}
-		auth: configuration._http_basic_auth
+		auth: configuration._http_server_auth
 		query_parameters: {
 			common:      false
 			description: "A list of URL query parameters to include in the log event. These will override any values included in the body with conflicting names."
@@ -128,6 +128,78 @@ components: sources: http: {
 				examples: ["vector_http_path"]
 			}
 		}
+		metadata_headers: {
+			common:      false
+			description: "A list of HTTP headers to keep in the metadata of the events, rather than in the events themselves. They can be read with the `get_metadata_field(\"http_headers\")` VRL function, as an object keyed by the lowercase header names."
+			required:    false
+			type: array: {
+				default: null
+				items: type: string: {
+					examples: ["X-Request-Id", "User-Agent"]
+				}
+			}
+		}
+		response: {
+			common:      false
+			description: "The response to the requests whose events are accepted."
+			required:    false
+			type: object: {
+				examples: []
+				options: {
+					code: {
+						common:      false
+						description: "The status code of the response, which must be a `2xx` status code."
+						required:    false
+						type: uint: {
+							default: 200
+							examples: [202, 204]
+							unit: null
+						}
+					}
+					body: {
+						common:      false
+						description: "The body of the response."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["{\"status\":\"accepted\"}"]
+						}
+					}
+					content_type: {
+						common:      false
+						description: "The `Content-Type` header of the response."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["application/json"]
+						}
+					}
+				}
+			}
+		}
+		routes: {
+			common:      false
+			description: """
+				The URL paths whose events are sent to named outputs, by output name. The events of the
+				requests on a route are sent to the `<component_id>.<output_name>` output, and the events
+				of the other requests to the default output. The paths must be under `path`, and with
+				`strict_path` set to `true`, the requests on other paths than `path` and the routes are
+				rejected.
+				"""
+			required: false
+			type: object: {
+				examples: [{"errors": "/logs/errors", "audit": "/logs/audit"}]
+				options: {
+					"*": {
+						description: "The URL path of the route."
+						required:    true
+						type: string: {
+							examples: ["/logs/errors"]
+						}
+					}
+				}
+			}
+		}
 		method: {
 			common:      false
 			description: "Specifies the action of the HTTP request."
@@ -91,6 +91,12 @@ components: sources: internal_metrics: {
 
 	output: metrics: {
 		// Default internal metrics tags
+		_sequence_tags: _component_tags & {
+			stream: {
+				description: "The source output that numbered the events, such as `my_source` or `my_source.port`."
+				required:    true
+			}
+		}
 		_internal_metrics_tags: {
 			pid: {
 				description: "The process ID of the Vector instance."
@@ -103,6 +109,12 @@ components: sources: internal_metrics: {
 				examples: [_values.local_host]
 			}
 		}
+		_enrichment_table_tags: _internal_metrics_tags & {
+			table: {
+				description: "The name of the enrichment table."
+				required:    true
+			}
+		}
 
 		// Instance-level "process" metrics
 		aggregate_events_recorded_total: {
@@ -234,6 +246,29 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		balance_target_unhealthy_total: {
+			description:       "The total number of times a target of the `balance` sink was excluded for being unhealthy."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				target: {
+					description: "The index of the target in the `targets` list."
+					required:    true
+				}
+			}
+		}
+		checkpoint_entries: {
+			description:       "The number of checkpoints kept, as of the last time they were written."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags
+		}
+		checkpoint_file_size_bytes: {
+			description:       "The size of the checkpoints file, as of the last time it was written."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags
+		}
 		checkpoint_write_errors_total: {
 			description:       "The total number of errors writing checkpoints. This metric is deprecated in favor of `component_errors_total`."
 			type:              "counter"
@@ -246,6 +281,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		checkpoints_removed_total: {
+			description:       "The total number of checkpoints discarded, because their files were removed or because there were more than `max_checkpoints`."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags
+		}
 		checksum_errors_total: {
 			description:       "The total number of errors identifying files via checksum."
 			type:              "counter"
@@ -411,12 +452,117 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		netflow_missing_templates_total: {
+			description:       "The total number of data sets discarded as the template describing their records wasn't received yet."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		snmp_unauthorized_traps_total: {
+			description:       "The total number of traps discarded as their community or user isn't configured."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		encode_errors_total: {
 			description:       "The total number of errors encountered when encoding an event."
 			type:              "counter"
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		enrichment_table_reload_errors_total: {
+			description:       "The total number of times an enrichment table failed to reload after its data changed."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _enrichment_table_tags
+		}
+		enrichment_table_reloads_total: {
+			description:       "The total number of times an enrichment table was reloaded after its data changed."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _enrichment_table_tags
+		}
+		enrichment_table_rows: {
+			description:       "The number of rows loaded into an enrichment table. Only reported for tables that hold their rows in memory."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _enrichment_table_tags
+		}
+		circuit_breaker_opened_total: {
+			description:       "The total number of times the circuit breaker of a sink opened, holding back its requests."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		circuit_breaker_state: {
+			description:       "The state of the circuit breaker of a sink: 0 when closed, 1 when half open and 2 when open."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		dead_letter_events_total: {
+			description:       "The total number of events a sink failed to deliver, and routed to its dead letter component."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				status: {
+					description: "The delivery status of the event, either `errored` or `rejected`."
+					required:    true
+				}
+			}
+		}
+		dead_letter_untracked_events_total: {
+			description:       "The total number of events handed to a sink without tracking their delivery, as too many of its events were tracked for its dead letter. The events among these the sink fails to deliver aren't routed to its dead letter."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		overflow_sink_events_total: {
+			description:       "The total number of events that didn't fit in the buffer of a sink, and were routed to its overflow sink."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		egress_rate_limited_total: {
+			description:       "The total number of requests a sink delayed to stay within an egress rate limit."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				scope: {
+					description: "The limit the request was delayed by, either the `component` one or the `global` one."
+					required:    true
+				}
+			}
+		}
+		egress_rate_limit_delay_seconds: {
+			description:       "The time requests were delayed by to stay within an egress rate limit."
+			type:              "histogram"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				scope: {
+					description: "The limit the request was delayed by, either the `component` one or the `global` one."
+					required:    true
+				}
+			}
+		}
+		sequence_gaps_total: {
+			description:       "The total number of gaps found by a sink in the sequence numbers of the events of a source."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _sequence_tags
+		}
+		sequence_missing_events_total: {
+			description:       "The total number of events missing from the gaps found by a sink in the sequence numbers of the events of a source."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _sequence_tags
+		}
+		sequence_reordered_events_total: {
+			description:       "The total number of events received by a sink out of the order they were sent by their source."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _sequence_tags
+		}
 		events_discarded_total: {
 			description:       "The total number of events discarded by this component."
 			type:              "counter"
@@ -638,12 +784,30 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		datadog_logs_quota_exceeded: {
+			description:       "Whether the Datadog API throttled the last request of the `datadog_logs` sink because the quota is exceeded, `1` if it did and `0` otherwise."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		datadog_logs_throttled_requests_total: {
+			description:       "The total number of requests of the `datadog_logs` sink the Datadog API throttled because the quota is exceeded."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		datadog_metrics_received_in_total: {
 			description:       "Number of Datadog metrics received."
 			type:              "counter"
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		dropped_event_redaction_errors_total: {
+			description:       "The total number of samples of the events the component dropped that the `api.dropped_event_samples.redact` program failed on."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		kafka_queue_messages: {
 			description:       "Current number of messages in producer queues."
 			type:              "gauge"
@@ -668,6 +832,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		kafka_revoked_partitions_drain_timeouts_total: {
+			description:       "Total number of rebalances whose revoked partitions weren't drained before the drain timeout elapsed."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		kafka_responses_total: {
 			description:       "Total number of responses received from Kafka brokers."
 			type:              "counter"
@@ -704,6 +874,28 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		failover_active_target: {
+			description:       "Whether the target of the `failover` sink is the one events are sent to, `1` if it is and `0` otherwise."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				target: {
+					description: "The target, either `primary` or `secondary`."
+					required:    true
+				}
+			}
+		}
+		failover_switches_total: {
+			description:       "The total number of times the `failover` sink switched the target events are sent to."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				target: {
+					description: "The target switched to, either `primary` or `secondary`."
+					required:    true
+				}
+			}
+		}
 		file_delete_errors_total: {
 			description:       "The total number of failures to delete a file. This metric is deprecated in favor of `component_errors_total`."
 			type:              "counter"
@@ -859,6 +1051,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		out_of_order_accepted_events_total: {
+			description:       "The total number of out-of-order events sent as they are."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		parse_errors_total: {
 			description:       "The total number of errors parsing metrics for this component."
 			type:              "counter"
@@ -956,6 +1154,33 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		restreamed_events_total: {
+			description:       "The total number of out-of-order events sent to a sibling stream."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		rewritten_timestamp_events_total: {
+			description:       "The total number of out-of-order events whose timestamp was rewritten."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		sandbox_violations_total: {
+			description:       "The total number of accesses to paths or hosts the sandbox of the component denied."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				capability: {
+					description: "The kind of access that was denied."
+					required:    true
+					enum: {
+						path: "Access to a path."
+						host: "Access to a host."
+					}
+				}
+			}
+		}
 		send_errors_total: {
 			description:       "The total number of errors sending messages."
 			type:              "counter"
@@ -1050,7 +1275,12 @@ components: sources: internal_metrics: {
 				"""
 			type:              "counter"
 			default_namespace: "vector"
-			tags:              _component_tags
+			tags:              _component_tags & {
+				tag_key: {
+					description: "The key of the rejected tag."
+					required:    true
+				}
+			}
 		}
 		timestamp_parse_errors_total: {
 			description:       "The total number of errors encountered parsing [RFC 3339](\(urls.rfc_3339)) timestamps."
@@ -1093,6 +1323,17 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		vector_endpoint_unhealthy_total: {
+			description:       "The total number of times a `vector` sink stopped sending events to one of its `addresses` after a failure."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				endpoint: {
+					description: "The address of the downstream Vector instance."
+					required:    true
+				}
+			}
+		}
 
 		// Windows metrics
 		windows_service_does_not_exist_total: {
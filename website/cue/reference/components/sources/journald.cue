@@ -62,10 +62,38 @@ components: sources: journald: {
 		}
 		since_now: {
 			common:      true
-			description: "Include only future entries."
+			description: "Include only future entries. Equivalent to setting `seek` to `\"tail\"`, and can't be used together with it."
 			required:    false
 			type: bool: default: false
 		}
+		seek: {
+			common:      false
+			description: "Where to start reading the journal from when Vector has no checkpoint for it, such as on its first start. Either `\"head\"`, the oldest entry, `\"tail\"`, the entries written after Vector started, or one of the options below."
+			required:    false
+			type: object: {
+				examples: [
+					{cursor: "s=0d1a9c5ad5e84a0f8d8b8f0f9bf4c5d1;i=1a2b;b=5f7e;m=1f2e3d;t=5b6c7d;x=8e9f"},
+					{timestamp: "2022-01-01T00:00:00Z"},
+				]
+				options: {
+					cursor: {
+						common:      false
+						description: "Start from the entry with this cursor, as output by `journalctl --show-cursor`."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["s=0d1a9c5ad5e84a0f8d8b8f0f9bf4c5d1;i=1a2b;b=5f7e;m=1f2e3d;t=5b6c7d;x=8e9f"]
+						}
+					}
+					timestamp: {
+						common:      false
+						description: "Start from the entries written at, or after, this timestamp."
+						required:    false
+						type: timestamp: {}
+					}
+				}
+			}
+		}
 		exclude_units: {
 			common:      true
 			description: "The list of unit names to exclude from monitoring. Unit names lacking a `\".\"` will have `\".service\"` appended to make them a valid service unit name."
@@ -151,11 +179,44 @@ components: sources: journald: {
 		}
 		journal_directory: {
 			common:      false
-			description: "The full path of the journal directory. If not set, `journalctl` will use the default system journal paths"
+			description: "The full path of the journal directory. If not set, `journalctl` will use the default system journal paths. This can be the directory of the journal files received from other hosts by `systemd-journal-remote`."
 			required:    false
 			type: string: {
 				default: null
-				examples: ["/run/log/journal"]
+				examples: ["/run/log/journal", "/var/log/journal/remote"]
+			}
+		}
+		journal_files: {
+			common:      false
+			description: "The journal files to read, instead of the default system journal paths. Glob patterns are expanded by `journalctl`. Can't be used together with `journal_directory`."
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["/var/log/journal/remote/*.journal"]
+				}
+			}
+		}
+		include_fields: {
+			common:      false
+			description: "The journal fields to keep in the events. If empty or not present, all the fields are kept. The `MESSAGE` field and the timestamp fields are always kept, unless they are excluded."
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["_SYSTEMD_UNIT", "PRIORITY"]
+				}
+			}
+		}
+		exclude_fields: {
+			common:      false
+			description: "The journal fields to remove from the events. Fields are matched by `include_matches` and `exclude_matches` before being removed."
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["_CMDLINE", "_BOOT_ID"]
+				}
 			}
 		}
 	}
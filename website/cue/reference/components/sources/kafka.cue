@@ -58,6 +58,19 @@ components: sources: kafka: {
 				unit: "milliseconds"
 			}
 		}
+		drain_timeout_ms: {
+			common:      false
+			description: """
+				The maximum time to wait, when partitions are revoked during a rebalance, for the events
+				read from them to be acknowledged before they're handed over to other members of the group.
+				"""
+			required:    false
+			type: uint: {
+				default: 2500
+				examples: [2500, 10000]
+				unit: "milliseconds"
+			}
+		}
 		fetch_wait_max_ms: {
 			common:      false
 			description: "Maximum time the broker may wait to fill the response."
@@ -75,6 +88,19 @@ components: sources: kafka: {
 				examples: ["consumer-group-name"]
 			}
 		}
+		group_instance_id: {
+			common:      false
+			description: """
+				The identifier of the consumer as a static member of the group. A static member keeps its
+				partitions while it restarts, as long as it rejoins the group before the session times out.
+				It must be unique within the group.
+				"""
+			required:    false
+			type: string: {
+				default: null
+				examples: ["vector-0"]
+			}
+		}
 		key_field: {
 			common:      true
 			description: "The log field name to use for the Kafka message key."
@@ -93,6 +119,19 @@ components: sources: kafka: {
 				examples: ["topic"]
 			}
 		}
+		partition_assignment_strategy: {
+			common:      false
+			description: """
+				The strategies assigning the partitions to the members of the group, in order of
+				preference. See the [librdkafka documentation](\(urls.librdkafka_config)) for the
+				`partition.assignment.strategy` option for further clarification.
+				"""
+			required:    false
+			type: string: {
+				default: "range,roundrobin"
+				examples: ["cooperative-sticky", "range,roundrobin"]
+			}
+		}
 		partition_key: {
 			common:      false
 			description: "The log field name to use for the Kafka partition name."
@@ -164,6 +203,30 @@ components: sources: kafka: {
 				}
 			}
 		}
+		schema_registry: {
+			common:      false
+			description: """
+				Decode the messages written in the wire format of the [Confluent Schema Registry](\(urls.schema_registry)),
+				with their schemas resolved against it. The `framing` and `decoding` options are ignored when it's set.
+				"""
+			required:    false
+			type: object: options: {
+				auth: configuration._http_auth & {_args: {
+					password_example: "${SCHEMA_REGISTRY_PASSWORD}"
+					username_example: "${SCHEMA_REGISTRY_USERNAME}"
+				}}
+				tls: configuration._tls_connect & {_args: {
+					can_verify_certificate: true
+					can_verify_hostname:    true
+					enabled_default:        false
+				}}
+				url: {
+					description: "The URL of the Schema Registry."
+					required:    true
+					type: string: examples: ["http://localhost:8081"]
+				}
+			}
+		}
 		session_timeout_ms: {
 			common:      false
 			description: "The Kafka session timeout in milliseconds."
@@ -230,6 +293,7 @@ components: sources: kafka: {
 		kafka_queue_messages_bytes:           components.sources.internal_metrics.output.metrics.kafka_queue_messages_bytes
 		kafka_requests_total:                 components.sources.internal_metrics.output.metrics.kafka_requests_total
 		kafka_requests_bytes_total:           components.sources.internal_metrics.output.metrics.kafka_requests_bytes_total
+		kafka_revoked_partitions_drain_timeouts_total: components.sources.internal_metrics.output.metrics.kafka_revoked_partitions_drain_timeouts_total
 		kafka_responses_total:                components.sources.internal_metrics.output.metrics.kafka_responses_total
 		kafka_responses_bytes_total:          components.sources.internal_metrics.output.metrics.kafka_responses_bytes_total
 		kafka_produced_messages_total:        components.sources.internal_metrics.output.metrics.kafka_produced_messages_total
@@ -245,5 +309,37 @@ components: sources: kafka: {
 		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
 	}
 
-	how_it_works: components._kafka.how_it_works
+	how_it_works: components._kafka.how_it_works & {
+		schema_registry: {
+			title: "Schema Registry"
+			body: """
+				With `schema_registry` set, the messages are expected in the
+				[wire format](\(urls.schema_registry_wire_format)) of the Confluent Schema Registry: a magic byte and
+				the identifier of the schema they were written with, followed by the record. The schemas are fetched
+				from the Schema Registry the first time their identifier is seen and cached from then on, as an
+				identifier always refers to the same schema.
+
+				Avro, Protobuf, and JSON schemas are supported. The records are decoded into the fields of the log
+				events, with the fields of Protobuf records that are set to their default value omitted, as in the
+				JSON mapping of Protobuf. The files imported by Protobuf schemas are resolved through the references
+				of the schemas, except for the well-known types of Protobuf, which aren't supported. The messages
+				whose schema can't be resolved or that can't be decoded with it are dropped.
+				"""
+		}
+		rebalancing: {
+			title: "Rebalancing"
+			body: """
+				Before partitions are revoked from Vector during a rebalance of the consumer group, Vector waits,
+				for up to `drain_timeout_ms`, for the events read from them to be acknowledged, so that their
+				offsets are committed before other members of the group take over. This keeps the partitions from
+				being consumed again from their last committed offsets, which otherwise duplicates the events read
+				since the last commit.
+
+				With the `cooperative-sticky` partition assignment strategy, only the partitions moving to other
+				members are revoked, rather than all of them, so that the other partitions are consumed throughout
+				the rebalance. Setting `group_instance_id` makes Vector a static member of the group, whose restarts
+				don't trigger a rebalance as long as it rejoins before `session_timeout_ms` elapses.
+				"""
+		}
+	}
 }
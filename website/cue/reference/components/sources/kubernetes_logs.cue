@@ -260,6 +260,120 @@ components: sources: kubernetes_logs: {
 				examples: ["my_custom_label!=my_value", "my_custom_label!=my_value,my_other_custom_label=my_value"]
 			}
 		}
+		include_pod_labels: {
+			common:      false
+			description: "Specifies the labels the `Pod`s must all have to be included. These are added to the label selector, see `extra_label_selector`."
+			required:    false
+			type: object: {
+				examples: [{"app": "web"}]
+				options: {
+					"*": {
+						common:      false
+						description: "The value the label must have."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["web"]
+						}
+					}
+				}
+			}
+		}
+		exclude_pod_labels: {
+			common:      false
+			description: "Specifies the labels that exclude the `Pod`s having any of them. These are added to the label selector, see `extra_label_selector`."
+			required:    false
+			type: object: {
+				examples: [{"tier": "debug"}]
+				options: {
+					"*": {
+						common:      false
+						description: "The value the label must have."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["debug"]
+						}
+					}
+				}
+			}
+		}
+		include_namespace_labels: {
+			common:      false
+			description: "Specifies the labels the `Namespace`s must all have to be included. These are added to the namespace label selector, see `extra_namespace_label_selector`."
+			required:    false
+			type: object: {
+				examples: [{"environment": "production"}]
+				options: {
+					"*": {
+						common:      false
+						description: "The value the label must have."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["production"]
+						}
+					}
+				}
+			}
+		}
+		exclude_namespace_labels: {
+			common:      false
+			description: "Specifies the labels that exclude the `Namespace`s having any of them. These are added to the namespace label selector, see `extra_namespace_label_selector`."
+			required:    false
+			type: object: {
+				examples: [{"environment": "sandbox"}]
+				options: {
+					"*": {
+						common:      false
+						description: "The value the label must have."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["sandbox"]
+						}
+					}
+				}
+			}
+		}
+		include_pod_annotations: {
+			common:      false
+			description: "Specifies the annotations the `Pod`s must all have for their log files to be read. Unlike labels, annotations can't be selected on by the Kubernetes API, so this filter is applied by Vector."
+			required:    false
+			type: object: {
+				examples: [{"logging.example.com/collect": "true"}]
+				options: {
+					"*": {
+						common:      false
+						description: "The value the annotation must have."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["true"]
+						}
+					}
+				}
+			}
+		}
+		exclude_pod_annotations: {
+			common:      false
+			description: "Specifies the annotations that prevent the log files of the `Pod`s having any of them from being read. Unlike labels, annotations can't be selected on by the Kubernetes API, so this filter is applied by Vector."
+			required:    false
+			type: object: {
+				examples: [{"logging.example.com/collect": "false"}]
+				options: {
+					"*": {
+						common:      false
+						description: "The value the annotation must have."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["false"]
+						}
+					}
+				}
+			}
+		}
 		max_read_bytes: {
 			category:    "Reading"
 			common:      false
@@ -318,6 +432,18 @@ components: sources: kubernetes_logs: {
 				unit:    "milliseconds"
 			}
 		}
+		legacy_pod_log_directories: {
+			common:      false
+			description: "Also read the log files in the legacy `/var/log/pods/<pod_uid>` layout, used by older `kubelet`s, such as the ones of OpenShift 3 running CRI-O. See [the log file layouts](#log-file-layouts)."
+			required:    false
+			type: bool: default: false
+		}
+		metadata_cache: {
+			common:      false
+			description: "Cache the Kubernetes metadata in the data directory, to enrich the events before the Kubernetes API is reached after a restart. See [the metadata cache](#metadata-cache)."
+			required:    false
+			type: bool: default: false
+		}
 		timezone: configuration._timezone
 	}
 
@@ -521,6 +647,38 @@ components: sources: kubernetes_logs: {
 				* The `extra_label_selector` option specifies the label selector to
 				  filter `Pod`s with, to be used in addition to the [built-in
 				  `vector.dev/exclude` filter](#pod-exclusion).
+				* The `include_pod_labels`, `exclude_pod_labels`,
+				  `include_namespace_labels`, and `exclude_namespace_labels` options
+				  specify labels to add to the label selectors.
+				* The `include_pod_annotations` and `exclude_pod_annotations`
+				  options filter `Pod`s by their annotations. The Kubernetes API
+				  can't select on annotations, so Vector still watches these `Pod`s,
+				  but doesn't read their log files.
+				"""
+		}
+
+		log_file_layouts: {
+			title: "Log file layouts"
+			body: """
+				Vector reads the log files `kubelet` writes at
+				`/var/log/pods/<namespace>_<pod_name>_<pod_uid>/<container_name>/`,
+				whichever container runtime is used, such as Docker, containerd, or CRI-O.
+
+				`kubelet`s prior to 1.14, such as the ones of OpenShift 3 running CRI-O,
+				write them at `/var/log/pods/<pod_uid>/<container_name>/` instead. Set the
+				`legacy_pod_log_directories` option to also read these; Vector then looks
+				up the `Pod` by its uid to enrich the events.
+				"""
+		}
+
+		metadata_cache: {
+			title: "Metadata cache"
+			body: """
+				When the `metadata_cache` option is set, Vector writes the `Pod`, `Namespace`
+				and `Node` metadata to its data directory every 30 seconds and on shutdown.
+				After a restart, the cached metadata is used to enrich the events until the
+				Kubernetes API is reached. The `Pod`s deleted in the meantime are then kept
+				for `delay_deletion_ms`, so that their remaining log lines are still enriched.
 				"""
 		}
 
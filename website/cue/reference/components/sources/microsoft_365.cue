@@ -0,0 +1,140 @@
+package metadata
+
+components: sources: microsoft_365: {
+	title: "Microsoft 365"
+
+	description: """
+		Collects the audit logs of a Microsoft 365 tenant, such as Azure Active Directory
+		sign-ins or Exchange and SharePoint activity, from the [Office 365 Management Activity
+		API](\(urls.microsoft_365_management_activity)).
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: false
+		collect: {
+			checkpoint: enabled: true
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			proxy: enabled: true
+			from: service:  services.microsoft_365
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		requirements: [
+			"""
+				The source requires an application registered in Azure Active Directory, granted
+				the `ActivityFeed.Read` application permission of the Office 365 Management APIs,
+				and `ActivityFeed.ReadDlp` to collect `DLP.All` content.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		client_id: {
+			description: "The ID of the application."
+			required:    true
+			type: string: {
+				examples: ["00000000-0000-0000-0000-000000000000"]
+			}
+		}
+		client_secret: {
+			description: "A client secret of the application."
+			required:    true
+			type: string: {
+				examples: ["${MICROSOFT_365_CLIENT_SECRET}"]
+			}
+		}
+		content_types: {
+			common:      true
+			description: "The types of content to collect. Their subscriptions are started by the source if needed."
+			required:    false
+			type: array: {
+				default: ["Audit.AzureActiveDirectory", "Audit.Exchange", "Audit.SharePoint", "Audit.General"]
+				items: type: string: {
+					examples: ["Audit.Exchange", "DLP.All"]
+				}
+			}
+		}
+		tenant_id: {
+			description: "The ID of the Azure Active Directory tenant of the organization."
+			required:    true
+			type: string: {
+				examples: ["00000000-0000-0000-0000-000000000000"]
+			}
+		}
+		interval_secs: configuration._audit_log_interval_secs
+		lookback_secs: configuration._audit_log_lookback_secs
+	}
+
+	how_it_works: {
+		polling:     components._audit_log_how_it_works.polling
+		rate_limits: components._audit_log_how_it_works.rate_limits
+		content: {
+			title: "Content"
+			body: """
+				Records are published in blobs of content, listed by the time they became
+				available rather than the time of the audited activity, which can be earlier.
+				Listings span at most a day and only go back a week, so a source catching up
+				collects a day at a time, and starts at most a week in the past.
+				"""
+		}
+	}
+
+	output: logs: record: {
+		description: "An audit log record, holding the fields of its [schema](\(urls.microsoft_365_management_activity))."
+		fields: {
+			CreationTime: {
+				description: "The time of the audited activity, in UTC."
+				required:    true
+				type: string: {
+					examples: ["2022-06-01T12:30:15"]
+				}
+			}
+			Operation: {
+				description: "The name of the audited operation."
+				required:    true
+				type: string: {
+					examples: ["UserLoggedIn", "FileAccessed"]
+				}
+			}
+			Workload: {
+				description: "The service the operation was performed in."
+				required:    true
+				type: string: {
+					examples: ["AzureActiveDirectory", "Exchange", "SharePoint"]
+				}
+			}
+			timestamp: {
+				description: "The time of the audited activity, or when it was collected if that's unknown."
+				required:    true
+				type: timestamp: {}
+			}
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:          components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_events_total: components.sources.internal_metrics.output.metrics.component_received_events_total
+	}
+}
@@ -0,0 +1,188 @@
+package metadata
+
+components: sources: mongodb_change_stream: {
+	title: "MongoDB Change Stream"
+
+	description: """
+		Tails the [change streams](\(urls.mongodb_change_streams)) of a [MongoDB](\(urls.mongodb))
+		replica set or sharded cluster, emitting an event for each change to the documents of a
+		collection, a database, or the whole deployment.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: true
+		collect: {
+			checkpoint: enabled: true
+			from: {
+				service: services.mongodb
+
+				interface: {
+					socket: {
+						api: {
+							title: "MongoDB change streams"
+							url:   urls.mongodb_change_streams
+						}
+						direction: "outgoing"
+						protocols: ["tcp"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		requirements: [
+			"""
+				Change streams are only available on replica sets and sharded clusters running
+				MongoDB 4.2 or later, and the user must have the `changeStream` and `find` actions on
+				the watched collections.
+				""",
+		]
+		warnings: [
+			"""
+				Changes can only be resumed while they're in the oplog, so the changes made while
+				Vector is stopped for longer than the oplog window are lost.
+				""",
+		]
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._source_acknowledgements
+		collection: {
+			common:      true
+			description: "The collection of the database whose changes are consumed. The changes to all the collections of the database are consumed when unset."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["users"]
+			}
+		}
+		database: {
+			common:      true
+			description: "The database whose changes are consumed. The changes to all the databases of the deployment are consumed when unset."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["app"]
+			}
+		}
+		endpoint: {
+			description: "The MongoDB [connection string](\(urls.mongodb_connection_string_uri_format)) of the replica set or sharded cluster."
+			required:    true
+			type: string: examples: ["mongodb://localhost:27017/?replicaSet=rs0"]
+		}
+		full_document: {
+			common:      false
+			description: "What the events of updates hold."
+			required:    false
+			type: string: {
+				default: "delta"
+				enum: {
+					delta:         "The changed and removed fields only."
+					update_lookup: "The changed and removed fields, along with the current version of the updated document. The document is looked up when the change is read, so it may hold later changes, and is missing when the document was deleted since."
+				}
+			}
+		}
+	}
+
+	output: logs: record: {
+		description: "A change to a document, a collection, or a database."
+		fields: {
+			collection: {
+				description: "The collection of the change."
+				required:    false
+				type: string: examples: ["users"]
+			}
+			database: {
+				description: "The database of the change."
+				required:    false
+				type: string: examples: ["app"]
+			}
+			document_key: {
+				description: "The `_id` of the changed document, along with its shard key in sharded collections."
+				required:    false
+				type: object: {}
+			}
+			full_document: {
+				description: "The inserted or replacing document, or the current version of the updated document when `full_document` is `update_lookup`."
+				required:    false
+				type: object: {}
+			}
+			operation: {
+				description: "The kind of change."
+				required:    true
+				type: string: examples: ["insert", "update", "replace", "delete", "drop", "rename", "dropDatabase", "invalidate"]
+			}
+			removed_fields: {
+				description: "The fields removed by the update."
+				required:    false
+				type: array: items: type: string: examples: ["age"]
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: examples: ["mongodb_change_stream"]
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The time of the change, to the second before MongoDB 6.0."
+			}
+			to: {
+				description: "The new namespace of a renamed collection."
+				required:    false
+				type: object: {}
+			}
+			updated_fields: {
+				description: "The fields changed by the update, along with their new values."
+				required:    false
+				type: object: {}
+			}
+		}
+	}
+
+	how_it_works: {
+		resuming: {
+			title: "Resuming"
+			body: """
+				The resume token of the last delivered change, when acknowledgements are enabled, or
+				of the last change sent downstream otherwise, is written to a checkpoint in the data
+				directory once a second. When Vector restarts, the change stream is resumed after
+				that change, so the changes being delivered may be emitted again.
+
+				The stream is reopened after the change that invalidated it, such as the drop of the
+				watched collection, so that the changes to a collection recreated with the same name
+				are consumed as well.
+				"""
+		}
+		types: {
+			title: "BSON types"
+			body: """
+				Strings, booleans, integers, doubles, arrays and documents are converted to the
+				matching types, dates to timestamps, object IDs to their hexadecimal representation,
+				and decimals to strings. The other BSON types are converted to their relaxed
+				extended JSON representation.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+	}
+}
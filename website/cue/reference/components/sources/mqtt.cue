@@ -0,0 +1,112 @@
+package metadata
+
+components: sources: mqtt: {
+	title: "MQTT"
+
+	features: {
+		acknowledgements: true
+		collect: {
+			checkpoint: enabled: false
+			tls: {
+				enabled:                true
+				can_verify_certificate: false
+				enabled_default:        false
+			}
+			from: components._mqtt.features.collect.from
+		}
+		multiline: enabled: false
+		codecs: {
+			enabled:         true
+			default_framing: "bytes"
+		}
+	}
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	support: components._mqtt.support
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: components._mqtt.configuration & {
+		acknowledgements: configuration._source_acknowledgements
+		clean_session: {
+			common:      false
+			description: "Whether the broker discards the session of the client when it disconnects. The broker otherwise keeps its subscriptions, and queues the messages it misses, until it reconnects with the same `client_id`."
+			required:    false
+			type: bool: default: true
+		}
+		topic_key: {
+			common:      false
+			description: "The field the topic of each message is inserted into."
+			required:    false
+			type: string: {
+				default: "topic"
+				examples: ["topic", "mqtt_topic"]
+			}
+		}
+		topics: {
+			description: "The topic filters subscribed to, which can contain the `+` and `#` [wildcards](\(urls.mqtt_wildcards)) to match several topics."
+			required:    true
+			type: array: items: type: string: examples: ["sensors/+/logs", "devices/#"]
+		}
+	}
+
+	output: logs: record: {
+		description: "An individual MQTT message"
+		fields: {
+			message: {
+				description: "The raw line from the MQTT message."
+				required:    true
+				type: string: {
+					examples: ["temperature=21.5"]
+				}
+			}
+			timestamp: fields._current_timestamp
+			topic: {
+				description: "The topic the message was published to, which the filters with wildcards match several of. It's inserted into the field named by `topic_key`."
+				required:    true
+				type: string: {
+					examples: ["sensors/kitchen/logs"]
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		acknowledgements: {
+			title: "Acknowledgements"
+			body: """
+				Messages delivered with a QoS of 1 are acknowledged once their events are delivered,
+				when acknowledgements are enabled, and as soon as their events are sent downstream
+				otherwise. MQTT has no negative acknowledgements: the messages whose events are
+				rejected are acknowledged, while the ones whose events fail to be delivered are left
+				unacknowledged, for the broker to redeliver them once the client reconnects with
+				`clean_session` disabled.
+				"""
+		}
+		reconnection: {
+			title: "Reconnection"
+			body: """
+				Vector reconnects to the broker, with an exponential backoff, whenever its connection
+				is lost, and subscribes to the `topics` again.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_in_total:                      components.sources.internal_metrics.output.metrics.events_in_total
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+	}
+}
@@ -4,7 +4,7 @@ components: sources: nats: {
 	title: "NATS"
 
 	features: {
-		acknowledgements: false
+		acknowledgements: true
 		collect: {
 			checkpoint: enabled: false
 			from: components._nats.features.collect.from
@@ -32,6 +32,46 @@ components: sources: nats: {
 	}
 
 	configuration: components._nats.configuration & {
+		acknowledgements: configuration._source_acknowledgements
+		jetstream: {
+			common:      false
+			description: "Consumes the subject from a JetStream stream with a durable pull consumer, rather than subscribing to it. The server keeps track of the messages acknowledged by the consumer, so that consuming resumes where it stopped when Vector restarts, and the instances of Vector sharing the consumer share its messages."
+			required:    false
+			type: object: options: {
+				ack_wait_secs: {
+					common:      false
+					description: "How long the server waits for a message to be acknowledged before redelivering it."
+					required:    false
+					type: uint: {
+						default: 30
+						unit:    "seconds"
+					}
+				}
+				batch_size: {
+					common:      false
+					description: "The maximum number of messages requested from the server at once."
+					required:    false
+					type: uint: {
+						default: 100
+						unit:    null
+					}
+				}
+				durable_name: {
+					common:      false
+					description: "The name of the durable consumer, which is created when it doesn't exist yet."
+					required:    false
+					type: string: {
+						default: "vector"
+						examples: ["vector", "logs-archiver"]
+					}
+				}
+				stream: {
+					description: "The stream the subject is stored by."
+					required:    true
+					type: string: examples: ["LOGS"]
+				}
+			}
+		}
 		queue: {
 			common:      false
 			description: "NATS Queue Group to join"
@@ -67,5 +107,22 @@ components: sources: nats: {
 		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
 	}
 
-	how_it_works: components._nats.how_it_works
+	how_it_works: components._nats.how_it_works & {
+		jetstream: {
+			title: "JetStream"
+			body: """
+				When `jetstream` is set, the messages of the subject are pulled in batches from a
+				durable consumer of the stream, which is created with an explicit acknowledgement
+				policy when it doesn't exist yet. Changing the options of an existing consumer
+				requires deleting it first.
+
+				Each message is acknowledged once its events are delivered, when acknowledgements
+				are enabled, and as soon as its events are sent downstream otherwise. Messages whose
+				events fail to be delivered are negatively acknowledged, so that the server
+				redelivers them, while the ones whose events are rejected are terminated, so that it
+				doesn't. The position of the consumer is kept by the server, so that consuming
+				resumes from the first unacknowledged message across restarts.
+				"""
+		}
+	}
 }
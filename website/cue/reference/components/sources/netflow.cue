@@ -0,0 +1,194 @@
+package metadata
+
+components: sources: netflow: {
+	_port: 2055
+
+	title: "NetFlow"
+
+	description: """
+		Collects the flow records of [NetFlow v5](\(urls.netflow_v5)), [NetFlow v9](\(urls.netflow_v9))
+		and [IPFIX](\(urls.ipfix)) exporters, emitting an event per record.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: false
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: services.netflow
+				interface: socket: {
+					direction: "incoming"
+					port:      _port
+					protocols: ["udp"]
+					ssl: "disabled"
+				}
+			}
+			receive_buffer_bytes: enabled: true
+			tls: enabled:                  false
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		address: {
+			description: "The address to listen on for the messages of the exporters. It _must_ include a port."
+			required:    true
+			type: string: {
+				examples: ["0.0.0.0:\(_port)", "0.0.0.0:4739"]
+			}
+		}
+	}
+
+	output: logs: record: {
+		description: "A flow record, or a record of an options template describing the exporter."
+		fields: {
+			engine_id: {
+				description: "The slot number of the flow switching engine, for NetFlow v5 messages."
+				required:    false
+				type: uint: {
+					examples: [0]
+					unit: null
+				}
+			}
+			engine_type: {
+				description: "The type of the flow switching engine, for NetFlow v5 messages."
+				required:    false
+				type: uint: {
+					examples: [0]
+					unit: null
+				}
+			}
+			host: {
+				description: "The IP address of the exporter."
+				required:    true
+				type: string: examples: ["192.0.2.1"]
+			}
+			observation_domain_id: {
+				description: "The observation domain of the exporter, for IPFIX messages."
+				required:    false
+				type: uint: {
+					examples: [1]
+					unit: null
+				}
+			}
+			record_type: {
+				description: "The kind of the record."
+				required:    true
+				type: string: enum: {
+					flow:    "A flow record."
+					options: "A record of an options template, such as the sampling configuration of the exporter."
+				}
+			}
+			sampling_interval: {
+				description: "The sampling interval of the exporter, for NetFlow v5 messages."
+				required:    false
+				type: uint: {
+					examples: [100]
+					unit: null
+				}
+			}
+			sequence_number: {
+				description: "The sequence number of the message, which exporters increment by message or by record."
+				required:    true
+				type: uint: {
+					examples: [1024]
+					unit: null
+				}
+			}
+			source_id: {
+				description: "The observation domain of the exporter, for NetFlow v9 messages."
+				required:    false
+				type: uint: {
+					examples: [1]
+					unit: null
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: examples: ["netflow"]
+			}
+			sys_uptime: {
+				description: "The uptime of the exporter, in milliseconds, for NetFlow v5 and v9 messages."
+				required:    false
+				type: uint: {
+					examples: [360000]
+					unit: "milliseconds"
+				}
+			}
+			template_id: {
+				description: "The template describing the record, for NetFlow v9 and IPFIX messages."
+				required:    false
+				type: uint: {
+					examples: [256]
+					unit: null
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The time the message was exported."
+			}
+			version: {
+				description: "The version of the protocol."
+				required:    true
+				type: uint: {
+					examples: [5, 9, 10]
+					unit: null
+				}
+			}
+			"*": {
+				description: "The fields of the record, named after their information elements, such as `source_ipv4_address`, `destination_transport_port` or `octet_delta_count`."
+				required:    true
+				type: "*": {}
+			}
+		}
+	}
+
+	how_it_works: {
+		fields: {
+			title: "Fields"
+			body: """
+				The fields of the records are named after their [information elements](\(urls.ipfix_information_elements)),
+				in snake case, so that NetFlow v5, NetFlow v9 and IPFIX records share them. Addresses
+				and timestamps are decoded, while the fields of the information elements that aren't
+				known are named `ie_<id>`, or `ie_<enterprise>_<id>` for the ones of enterprises,
+				and are decoded as integers, or as hexadecimal when they're longer than eight bytes.
+				"""
+		}
+		templates: {
+			title: "Templates"
+			body: """
+				The records of NetFlow v9 and IPFIX messages are described by templates that
+				exporters send periodically. The templates are cached by exporter and observation
+				domain, and the records of the templates that weren't received yet, such as the ones
+				received right after Vector starts, are discarded. The templates aren't persisted, so
+				exporters should be configured to send them often.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		netflow_missing_templates_total:      components.sources.internal_metrics.output.metrics.netflow_missing_templates_total
+	}
+}
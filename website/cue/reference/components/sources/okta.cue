@@ -0,0 +1,116 @@
+package metadata
+
+components: sources: okta: {
+	title: "Okta"
+
+	description: """
+		Collects the events of the [System Log](\(urls.okta_system_log)) of an Okta
+		organization, polling it for the events published since the last poll.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: false
+		collect: {
+			checkpoint: enabled: true
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			proxy: enabled: true
+			from: service:  services.okta
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		requirements: [
+			"""
+				The source requires an [API token](\(urls.okta_api_token)) of an administrator
+				allowed to read the System Log.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		domain: {
+			description: """
+				The domain of the Okta organization. It can be prefixed with a scheme, such as
+				`http://`, to reach a server other than Okta.
+				"""
+			required: true
+			type: string: {
+				examples: ["example.okta.com", "example.oktapreview.com"]
+			}
+		}
+		token: {
+			description: "The API token used to authenticate with Okta."
+			required:    true
+			type: string: {
+				examples: ["${OKTA_API_TOKEN}"]
+			}
+		}
+		filter: {
+			common:      false
+			description: "An expression filtering the collected events, in the filter syntax of the System Log API."
+			required:    false
+			type: string: {
+				default: null
+				examples: [#"eventType eq "user.session.start""#]
+			}
+		}
+		interval_secs: configuration._audit_log_interval_secs
+		lookback_secs: configuration._audit_log_lookback_secs
+	}
+
+	how_it_works: {
+		polling:     components._audit_log_how_it_works.polling
+		rate_limits: components._audit_log_how_it_works.rate_limits
+	}
+
+	output: logs: event: {
+		description: "An event of the System Log, holding the fields of its [LogEvent object](\(urls.okta_system_log))."
+		fields: {
+			eventType: {
+				description: "The type of the event."
+				required:    true
+				type: string: {
+					examples: ["user.session.start"]
+				}
+			}
+			published: {
+				description: "The time the event was published."
+				required:    true
+				type: string: {
+					examples: ["2022-06-01T12:30:15.250Z"]
+				}
+			}
+			timestamp: {
+				description: "The time the event was published, or when it was collected if that's unknown."
+				required:    true
+				type: timestamp: {}
+			}
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:          components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_events_total: components.sources.internal_metrics.output.metrics.component_received_events_total
+	}
+}
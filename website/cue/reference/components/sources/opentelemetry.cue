@@ -0,0 +1,330 @@
+package metadata
+
+components: sources: opentelemetry: {
+	_grpc_port: 4317
+	_http_port: 4318
+
+	title: "OpenTelemetry"
+
+	description: """
+		Receives logs, metrics and traces sent with the [OpenTelemetry protocol](\(urls.opentelemetry_protocol))
+		(OTLP) over gRPC or HTTP.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator", "sidecar"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: true
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: services.opentelemetry
+
+				interface: socket: {
+					direction: "incoming"
+					port:      _grpc_port
+					protocols: ["http"]
+					ssl: "optional"
+				}
+			}
+			// TLS is configured for each listener, see `grpc.tls` and `http.tls`.
+			tls: enabled: false
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._source_acknowledgements
+		grpc: {
+			common:      true
+			description: "Accepts OTLP over gRPC. At least one of `grpc` or `http` must be configured."
+			required:    false
+			type: object: options: {
+				address: {
+					description: "The address to listen for gRPC connections on. It _must_ include a port."
+					required:    true
+					type: string: {
+						examples: ["0.0.0.0:\(_grpc_port)"]
+					}
+				}
+				tls: configuration._tls_accept & {_args: {
+					can_verify_certificate: true
+					enabled_default:        false
+				}}
+			}
+		}
+		http: {
+			common:      true
+			description: """
+				Accepts OTLP over HTTP, with protobuf encoded requests posted to `/v1/logs`,
+				`/v1/metrics` and `/v1/traces`. At least one of `grpc` or `http` must be configured.
+				"""
+			required:    false
+			type: object: options: {
+				address: {
+					description: "The address to listen for HTTP connections on. It _must_ include a port."
+					required:    true
+					type: string: {
+						examples: ["0.0.0.0:\(_http_port)"]
+					}
+				}
+				tls: configuration._tls_accept & {_args: {
+					can_verify_certificate: true
+					enabled_default:        false
+				}}
+			}
+		}
+	}
+
+	outputs: [
+		{
+			name: "logs"
+			description: """
+				Received log records. Use `<component_id>.logs` as an input to downstream transforms and sinks.
+				"""
+		},
+		{
+			name: "metrics"
+			description: """
+				Received metric data points. Use `<component_id>.metrics` as an input to downstream transforms and sinks.
+				"""
+		},
+		{
+			name: "traces"
+			description: """
+				Received spans. Use `<component_id>.traces` as an input to downstream transforms and sinks.
+				"""
+		},
+	]
+
+	output: {
+		logs: record: {
+			description: "A log record received in an OTLP export request."
+			fields: {
+				message: {
+					description: "The body of the log record."
+					required:    true
+					type: "*": {}
+				}
+				timestamp: {
+					description: "The time of the log record, or the time it was observed if unknown."
+					required:    true
+					type: timestamp: {}
+				}
+				observed_timestamp: {
+					description: "The time the log record was observed by the collection system."
+					required:    false
+					common:      false
+					type: timestamp: {}
+				}
+				attributes: {
+					description: "The attributes of the log record."
+					required:    true
+					type: object: {}
+				}
+				severity_text: {
+					description: "The severity of the log record as known to its source."
+					required:    false
+					common:      true
+					type: string: {
+						examples: ["INFO"]
+					}
+				}
+				severity_number: {
+					description: "The normalized severity of the log record, from 1 (`TRACE`) to 24 (`FATAL4`)."
+					required:    false
+					common:      true
+					type: uint: {
+						examples: [9]
+						unit: null
+					}
+				}
+				trace_id: {
+					description: "The hex encoded ID of the trace the log record is part of."
+					required:    false
+					common:      true
+					type: string: {
+						examples: ["4bf92f3577b34da6a3ce929d0e0e4736"]
+					}
+				}
+				span_id: {
+					description: "The hex encoded ID of the span the log record is part of."
+					required:    false
+					common:      true
+					type: string: {
+						examples: ["00f067aa0ba902b7"]
+					}
+				}
+				source_type: {
+					description: "The name of the source type."
+					required:    true
+					type: string: {
+						examples: ["opentelemetry"]
+					}
+				}
+			}
+		}
+		metrics: {
+			_extra_tags: {
+				"resource.*": {
+					description: "The attributes of the resource that produced the metric."
+					required:    false
+					examples: ["checkout"]
+				}
+				"scope.name": {
+					description: "The name of the instrumentation scope that produced the metric."
+					required:    false
+					examples: ["io.opentelemetry.http"]
+				}
+				"scope.version": {
+					description: "The version of the instrumentation scope that produced the metric."
+					required:    false
+					examples: ["1.0.0"]
+				}
+			}
+			counter: output._passthrough_counter & {
+				tags: _extra_tags
+			}
+			gauge: output._passthrough_gauge & {
+				tags: _extra_tags
+			}
+			histogram: output._passthrough_histogram & {
+				tags: _extra_tags
+			}
+			summary: output._passthrough_summary & {
+				tags: _extra_tags
+			}
+		}
+		traces: {
+			description: "A span received in an OTLP export request."
+			fields: {
+				trace_id: {
+					description: "The hex encoded ID of the trace the span is part of."
+					required:    true
+					type: string: {
+						examples: ["4bf92f3577b34da6a3ce929d0e0e4736"]
+					}
+				}
+				span_id: {
+					description: "The hex encoded ID of the span."
+					required:    true
+					type: string: {
+						examples: ["00f067aa0ba902b7"]
+					}
+				}
+				parent_span_id: {
+					description: "The hex encoded ID of the parent span, absent for root spans."
+					required:    false
+					common:      true
+					type: string: {
+						examples: ["53995c3f42cd8ad8"]
+					}
+				}
+				name: {
+					description: "The name of the operation the span represents."
+					required:    true
+					type: string: {
+						examples: ["GET /orders"]
+					}
+				}
+				kind: {
+					description: "The kind of the span."
+					required:    true
+					type: string: {
+						enum: {
+							unspecified: "The kind of the span is unknown."
+							internal:    "An internal operation of an application."
+							server:      "The server side of a synchronous request."
+							client:      "The client side of a synchronous request."
+							producer:    "The sending side of an asynchronous request."
+							consumer:    "The receiving side of an asynchronous request."
+						}
+					}
+				}
+				start_timestamp: {
+					description: "The time the span started."
+					required:    true
+					type: timestamp: {}
+				}
+				end_timestamp: {
+					description: "The time the span ended."
+					required:    true
+					type: timestamp: {}
+				}
+				attributes: {
+					description: "The attributes of the span."
+					required:    true
+					type: object: {}
+				}
+				events: {
+					description: "The timestamped events of the span, with their `name`, `timestamp` and `attributes`."
+					required:    true
+					type: array: items: type: object: options: {}
+				}
+				links: {
+					description: "The links of the span to other spans, with their `trace_id`, `span_id`, `trace_state` and `attributes`."
+					required:    true
+					type: array: items: type: object: options: {}
+				}
+				status: {
+					description: "The status of the span, with its `code` (`unset`, `ok` or `error`) and `message`."
+					required:    true
+					type: object: {}
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		resources: {
+			title: "Resources and instrumentation scopes"
+			body: """
+				The attributes of the resource, and the name, version and attributes of the instrumentation scope,
+				that produced logs and traces are kept in the metadata of the events rather than in their fields,
+				so that they aren't copied into every event. The `opentelemetry` sink exports them as they were
+				received. For metrics, they're tags prefixed with `resource.` and `scope.`, since they tell apart
+				the series of different resources.
+				"""
+		}
+		metric_types: {
+			title: "Metric types"
+			body: """
+				Gauges are received as absolute gauges. Monotonic sums are received as counters and other sums as
+				gauges, both incremental for delta temporality and absolute otherwise. Histograms, including
+				exponential histograms, are received as aggregated histograms and summaries as aggregated summaries.
+				"""
+		}
+		http_encoding: {
+			title: "HTTP encoding"
+			body: """
+				Only protobuf encoded requests, with the `application/x-protobuf` content type, are accepted over
+				HTTP. Requests may be compressed with `gzip`, `deflate` or `snappy`.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_discarded_events_total:     components.sources.internal_metrics.output.metrics.component_discarded_events_total
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		events_in_total:                      components.sources.internal_metrics.output.metrics.events_in_total
+	}
+}
@@ -57,12 +57,13 @@ components: sources: prometheus_remote_write: {
 				examples: ["0.0.0.0:9090"]
 			}
 		}
-		auth: configuration._http_basic_auth
+		auth: configuration._http_server_auth
 	}
 
 	output: metrics: {
-		counter: output._passthrough_counter
-		gauge:   output._passthrough_gauge
+		counter:   output._passthrough_counter
+		gauge:     output._passthrough_gauge
+		histogram: output._passthrough_histogram
 	}
 
 	how_it_works: {
@@ -81,6 +82,28 @@ components: sources: prometheus_remote_write: {
 				are emitted as gauges.
 				"""
 		}
+		native_histograms: {
+			title: "Native histograms"
+			body: """
+				Native (sparse) histograms are emitted as aggregated
+				histograms, with a bucket for each populated bucket of
+				their exponential schema, bounded by the powers of its
+				base, along with a bucket bounded by the zero threshold
+				for the zero bucket. Float histograms are converted in the
+				same way, with their counts truncated to integers.
+				"""
+		}
+		exemplars: {
+			title: "Exemplars"
+			body: """
+				The exemplars of a time series are emitted as gauges,
+				named after the time series with a suffix of `_exemplar`,
+				whose values are the values of the exemplars. Their tags
+				are the tags of the time series along with the labels of
+				the exemplars, such as their `trace_id`, and their
+				timestamps are the timestamps of the exemplars.
+				"""
+		}
 	}
 
 	telemetry: metrics: {
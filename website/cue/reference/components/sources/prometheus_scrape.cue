@@ -53,15 +53,185 @@ components: sources: prometheus_scrape: {
 
 	configuration: {
 		endpoints: {
-			description: "Endpoints to scrape metrics from."
-			required:    true
+			common:      true
+			description: "Endpoints to scrape metrics from. Required unless `kubernetes_sd` or `http_sd` is set."
+			required:    false
 			warnings: ["You must explicitly add the path to your endpoints. Vector will _not_ automatically add `/metrics`."]
 			type: array: {
+				default: []
 				items: type: string: {
 					examples: ["http://localhost:9090/metrics"]
 				}
 			}
 		}
+		http_sd: {
+			common:      false
+			description: """
+				Discovers the targets to scrape from an HTTP endpoint, returning them in the format of the
+				[HTTP service discovery](\(urls.prometheus_http_sd)) of Prometheus. The discovered targets are
+				labeled with `__meta_url`, the URL they were discovered from.
+				"""
+			required: false
+			type: object: options: {
+				url: {
+					description: "The endpoint listing the targets to scrape."
+					required:    true
+					type: string: {
+						examples: ["http://localhost:8080/targets"]
+					}
+				}
+				refresh_interval_secs: {
+					common:      false
+					description: "The interval between refreshes of the discovered targets, in seconds."
+					required:    false
+					type: uint: {
+						default: 60
+						unit:    "seconds"
+					}
+				}
+				auth: configuration._http_auth & {_args: {
+					password_example: "${HTTP_SD_PASSWORD}"
+					username_example: "${HTTP_SD_USERNAME}"
+				}}
+			}
+		}
+		kubernetes_sd: {
+			common:      false
+			description: """
+				Discovers the ports of the running pods of a Kubernetes cluster as the targets to scrape, as the
+				`pod` role of the [Kubernetes service discovery](\(urls.prometheus_kubernetes_sd)) of Prometheus
+				does. A target is discovered for each declared TCP port of the containers of a pod, or for the pod
+				itself if it declares no ports.
+				"""
+			required: false
+			type: object: options: {
+				namespaces: {
+					common:      false
+					description: "The namespaces of the pods to discover, all of them if empty."
+					required:    false
+					type: array: {
+						default: []
+						items: type: string: {
+							examples: ["default", "monitoring"]
+						}
+					}
+				}
+				label_selector: {
+					common:      false
+					description: "The [label selector](\(urls.kubernetes_label_selector)) of the pods to discover."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["app.kubernetes.io/component=web"]
+					}
+				}
+				field_selector: {
+					common:      false
+					description: "The [field selector](\(urls.kubernetes_field_selector)) of the pods to discover."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["spec.nodeName=node-1"]
+					}
+				}
+				kube_config_file: {
+					common:      false
+					description: """
+						The path of the Kubeconfig file to connect to the cluster with. By default, the local
+						Kubeconfig is used, followed by the in-cluster configuration.
+						"""
+					required: false
+					type: string: {
+						default: null
+						examples: ["/path/to/.kube/config"]
+					}
+				}
+				refresh_interval_secs: {
+					common:      false
+					description: "The interval between refreshes of the discovered targets, in seconds."
+					required:    false
+					type: uint: {
+						default: 30
+						unit:    "seconds"
+					}
+				}
+			}
+		}
+		relabel_configs: {
+			common:      false
+			description: """
+				The rules relabeling the discovered targets, applied in order, as the
+				[`relabel_configs`](\(urls.prometheus_relabel_config)) of Prometheus. They select the targets to
+				scrape, and the labels to add to their metrics as tags. They don't apply to the `endpoints`.
+				"""
+			required: false
+			type: array: {
+				default: []
+				items: type: object: options: {
+					action: {
+						common:      true
+						description: "The action of the rule."
+						required:    false
+						type: string: {
+							default: "replace"
+							enum: {
+								replace:   "Sets `target_label` to the `replacement`, if `regex` matches the source labels."
+								keep:      "Drops the targets whose source labels don't match `regex`."
+								drop:      "Drops the targets whose source labels match `regex`."
+								labelmap:  "Copies the labels whose names match `regex` to the labels named by the `replacement`."
+								labeldrop: "Removes the labels whose names match `regex`."
+								labelkeep: "Removes the labels whose names don't match `regex`."
+							}
+						}
+					}
+					source_labels: {
+						common:      true
+						description: "The labels whose values, joined with the `separator`, are matched against `regex`."
+						required:    false
+						type: array: {
+							default: []
+							items: type: string: {
+								examples: ["__meta_kubernetes_namespace"]
+							}
+						}
+					}
+					separator: {
+						common:      false
+						description: "The separator of the values of the source labels."
+						required:    false
+						type: string: {
+							default: ";"
+						}
+					}
+					regex: {
+						common:      true
+						description: "The regular expression matched against the whole value, or label names."
+						required:    false
+						type: string: {
+							default: "(.*)"
+							examples: ["kube-.*"]
+						}
+					}
+					target_label: {
+						common:      true
+						description: "The label set by the `replace` action. It can refer to the groups of `regex`."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["pod"]
+						}
+					}
+					replacement: {
+						common:      false
+						description: "The value of the `replace` and `labelmap` actions. It can refer to the groups of `regex`."
+						required:    false
+						type: string: {
+							default: "$1"
+						}
+					}
+				}
+			}
+		}
 		scrape_interval_secs: {
 			common:      true
 			description: "The interval between scrapes, in seconds."
@@ -149,6 +319,52 @@ components: sources: prometheus_scrape: {
 		}}
 	}
 
+	how_it_works: {
+		service_discovery: {
+			title: "Service discovery"
+			body: """
+				In addition to its static `endpoints`, this source can discover its targets with `kubernetes_sd`
+				or `http_sd`. A discovered target is described by labels: its `__address__`, and the
+				`__scheme__` (`http` by default), `__metrics_path__` (`/metrics` by default) and `__param_<name>`
+				query parameters of its URL, along with the `__meta_*` labels of the discovery mechanism.
+
+				The `relabel_configs` rules select the targets to scrape and rewrite their labels. Once they are
+				applied, the labels prefixed with `__` are removed, and the others are added as tags to the
+				metrics of the target, following `honor_labels`. For example, the following rules scrape the pods
+				annotated with `prometheus.io/scrape: "true"`, on the port of their `prometheus.io/port`
+				annotation, tagging their metrics with their namespace and pod name:
+
+				```toml
+				[sources.pods]
+				type = "prometheus_scrape"
+				kubernetes_sd = {}
+
+				[[sources.pods.relabel_configs]]
+				source_labels = ["__meta_kubernetes_pod_annotation_prometheus_io_scrape"]
+				action = "keep"
+				regex = "true"
+
+				[[sources.pods.relabel_configs]]
+				source_labels = ["__address__", "__meta_kubernetes_pod_annotation_prometheus_io_port"]
+				regex = "([^:]+)(?::\\\\d+)?;(\\\\d+)"
+				replacement = "$1:$2"
+				target_label = "__address__"
+
+				[[sources.pods.relabel_configs]]
+				source_labels = ["__meta_kubernetes_namespace"]
+				target_label = "namespace"
+
+				[[sources.pods.relabel_configs]]
+				source_labels = ["__meta_kubernetes_pod_name"]
+				target_label = "pod"
+				```
+
+				The targets are refreshed every `refresh_interval_secs`, and the previous targets are kept if
+				they cannot be refreshed.
+				"""
+		}
+	}
+
 	output: metrics: {
 		_extra_tags: {
 			"instance": {
@@ -0,0 +1,199 @@
+package metadata
+
+components: sources: pulsar: {
+	title: "Apache Pulsar"
+
+	features: {
+		acknowledgements: true
+		collect: {
+			checkpoint: enabled: false
+			tls: enabled:        false
+			from: {
+				service: services.pulsar
+				interface: {
+					socket: {
+						api: {
+							title: "Pulsar protocol"
+							url:   urls.pulsar_protocol
+						}
+						direction: "incoming"
+						port:      6650
+						protocols: ["tcp"]
+						ssl: "disabled"
+					}
+				}
+			}
+		}
+		multiline: enabled: false
+		codecs: {
+			enabled:         true
+			default_framing: "bytes"
+		}
+	}
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._source_acknowledgements
+		auth:             components.sinks.pulsar.configuration.auth
+		batch_size: {
+			common:      false
+			description: "The number of messages the consumer asks the broker for at a time."
+			required:    false
+			type: uint: {
+				default: 1000
+				examples: [100, 1000]
+				unit: null
+			}
+		}
+		consumer_name: {
+			common:      false
+			description: "The name of the consumer, which is generated by the client when unset."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["vector-1"]
+			}
+		}
+		dead_letter_queue_policy: {
+			common:      false
+			description: "Sends the messages redelivered too many times to a dead letter topic, rather than redelivering them again."
+			required:    false
+			type: object: options: {
+				max_redeliver_count: {
+					description: "The number of times a message is redelivered before being sent to the dead letter topic."
+					required:    true
+					type: uint: {
+						examples: [3]
+						unit: null
+					}
+				}
+				dead_letter_topic: {
+					description: "The topic the messages are sent to."
+					required:    true
+					type: string: examples: ["logs-dlq"]
+				}
+			}
+		}
+		endpoint: {
+			description: "The endpoint of the Pulsar cluster."
+			required:    true
+			type: string: {
+				examples: ["pulsar://127.0.0.1:6650"]
+			}
+		}
+		subscription_name: {
+			common:      true
+			description: "The name of the subscription the messages are consumed through, which is shared by the instances of Vector consuming them together."
+			required:    false
+			type: string: {
+				default: "vector"
+				examples: ["vector", "logs-archiver"]
+			}
+		}
+		subscription_type: {
+			common:      false
+			description: "How the messages of the topics are distributed among the consumers of the subscription."
+			required:    false
+			type: string: {
+				default: "shared"
+				enum: {
+					exclusive:  "Only one consumer can subscribe."
+					shared:     "The messages are distributed among the consumers in a round-robin fashion."
+					failover:   "The messages are only sent to one of the consumers, which another one takes over from when it disconnects."
+					key_shared: "The messages sharing a key are all sent to the same consumer."
+				}
+			}
+		}
+		topics: {
+			description: "The topics the messages are consumed from."
+			required:    true
+			type: array: items: type: string: examples: ["logs", "persistent://public/default/logs"]
+		}
+	}
+
+	output: logs: record: {
+		description: "An individual Pulsar message"
+		fields: {
+			message: {
+				description: "The raw line from the Pulsar message."
+				required:    true
+				type: string: {
+					examples: ["53.126.150.246 - - [01/Oct/2020:11:25:58 -0400] \"GET /disintermediate HTTP/2.0\" 401 20308"]
+				}
+			}
+			message_key: {
+				description: "The key of the message, if it has one."
+				required:    false
+				common:      true
+				type: string: {
+					default: null
+					examples: ["host-1"]
+				}
+			}
+			producer_name: {
+				description: "The name of the producer that published the message."
+				required:    true
+				type: string: {
+					examples: ["standalone-0-1"]
+				}
+			}
+			properties: {
+				description: "The properties of the message."
+				required:    true
+				type: object: {
+					examples: [{service: "api"}]
+					options: {}
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The time the message was published."
+			}
+			topic: {
+				description: "The topic that the message came from."
+				required:    true
+				type: string: {
+					examples: ["persistent://public/default/logs"]
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		acknowledgements: {
+			title: "Acknowledgements"
+			body: """
+				Each message is acknowledged once its events are delivered, when acknowledgements are
+				enabled, and as soon as its events are sent downstream otherwise. Messages whose
+				events fail to be delivered are negatively acknowledged, so that the broker
+				redelivers them, until they're sent to the dead letter topic of the
+				`dead_letter_queue_policy`.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_in_total:                      components.sources.internal_metrics.output.metrics.events_in_total
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+	}
+}
@@ -0,0 +1,430 @@
+package metadata
+
+components: sources: snmp: {
+	_port: 162
+
+	title: "SNMP"
+
+	description: """
+		Receives the traps and informs of [SNMP](\(urls.snmp)) agents, and polls their objects
+		periodically, emitting the traps as logs and the polled objects as metrics or logs.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["aggregator", "daemon"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: false
+		collect: {
+			checkpoint: enabled: false
+			from: {
+				service: services.snmp
+				interface: socket: {
+					direction: "outgoing"
+					port:      161
+					protocols: ["udp"]
+					ssl: "disabled"
+				}
+			}
+		}
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: services.snmp
+				interface: socket: {
+					direction: "incoming"
+					port:      _port
+					protocols: ["udp"]
+					ssl: "disabled"
+				}
+			}
+			receive_buffer_bytes: enabled: true
+			tls: enabled:                  false
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		_user: {
+			auth_password: {
+				common:      true
+				description: "The authentication password of the user, at least eight characters long."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["${SNMP_AUTH_PASSWORD}"]
+				}
+			}
+			auth_protocol: {
+				common:      true
+				description: "The authentication protocol of the user, which is unauthenticated when unset."
+				required:    false
+				type: string: {
+					default: null
+					enum: {
+						md5:    "HMAC-MD5-96."
+						sha:    "HMAC-SHA-96."
+						sha224: "HMAC-SHA-224-128."
+						sha256: "HMAC-SHA-256-192."
+						sha384: "HMAC-SHA-384-256."
+						sha512: "HMAC-SHA-512-384."
+					}
+				}
+			}
+			name: {
+				description: "The name of the user."
+				required:    true
+				type: string: examples: ["vector"]
+			}
+			privacy_password: {
+				common:      true
+				description: "The privacy password of the user, at least eight characters long."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["${SNMP_PRIVACY_PASSWORD}"]
+				}
+			}
+			privacy_protocol: {
+				common:      true
+				description: "The privacy protocol of the user, which requires an authentication protocol, or unencrypted when unset."
+				required:    false
+				type: string: {
+					default: null
+					enum: {
+						des: "DES-CBC."
+						aes: "AES-128-CFB."
+					}
+				}
+			}
+		}
+
+		mib_paths: {
+			common:      false
+			description: "The MIB modules, or the directories of MIB modules, resolving the names of objects in addition to the ones of the common modules, such as `SNMPv2-MIB` and `IF-MIB`."
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: examples: ["/usr/share/snmp/mibs"]
+			}
+		}
+		poll: {
+			common:      true
+			description: "Polls the objects of agents periodically. At least one of `traps` or `poll` must be configured."
+			required:    false
+			type: object: options: {
+				community: {
+					common:      true
+					description: "The community of the requests, for SNMPv2c."
+					required:    false
+					type: string: {
+						default: "public"
+						examples: ["monitoring"]
+					}
+				}
+				emit: {
+					common:      true
+					description: "How the polled objects are emitted."
+					required:    false
+					type: string: {
+						default: "metrics"
+						enum: {
+							metrics: "A metric per numeric variable, named after its object, with the index of its instance as a tag."
+							logs:    "A log per agent, with the variables it returned."
+						}
+					}
+				}
+				get: {
+					common:      true
+					description: "The variables to get, by name, such as `sysUpTime.0` or `IF-MIB::ifNumber.0`, or by object identifier."
+					required:    false
+					type: array: {
+						default: []
+						items: type: string: examples: ["sysUpTime.0", "1.3.6.1.2.1.2.1.0"]
+					}
+				}
+				interval_secs: {
+					common:      true
+					description: "The interval between the polls."
+					required:    false
+					type: uint: {
+						default: 60
+						unit:    "seconds"
+					}
+				}
+				max_repetitions: {
+					common:      false
+					description: "The number of variables requested at once while walking subtrees."
+					required:    false
+					type: uint: {
+						default: 10
+						unit:    null
+					}
+				}
+				namespace: {
+					common:      false
+					description: "The namespace of the metrics. Disabled if empty."
+					required:    false
+					type: string: default: "snmp"
+				}
+				retries: {
+					common:      false
+					description: "The number of times the requests are sent again when they time out."
+					required:    false
+					type: uint: {
+						default: 1
+						unit:    null
+					}
+				}
+				targets: {
+					description: "The agents to poll, on port 161 unless specified."
+					required:    true
+					type: array: items: type: string: examples: ["192.0.2.1", "switch.example.com:1161"]
+				}
+				timeout_secs: {
+					common:      false
+					description: "How long to wait for the responses of the agents."
+					required:    false
+					type: float: {
+						default: 5.0
+						examples: [1.0, 10.0]
+					}
+				}
+				user: {
+					common:      true
+					description: "The user of the requests, required for SNMPv3."
+					required:    false
+					type: object: options: _user
+				}
+				version: {
+					common:      true
+					description: "The version of the protocol of the requests."
+					required:    false
+					type: string: {
+						default: "2c"
+						enum: {
+							"2c": "SNMPv2c, authenticated with a community."
+							"3":  "SNMPv3, authenticated and encrypted with the [user-based security model](\(urls.snmp_usm))."
+						}
+					}
+				}
+				walk: {
+					common:      true
+					description: "The subtrees to walk, such as tables, by name or by object identifier."
+					required:    false
+					type: array: {
+						default: []
+						items: type: string: examples: ["IF-MIB::ifTable", "1.3.6.1.2.1.31.1.1"]
+					}
+				}
+			}
+		}
+		traps: {
+			common:      true
+			description: "Receives the traps and informs of agents. At least one of `traps` or `poll` must be configured."
+			required:    false
+			type: object: options: {
+				address: {
+					common:      true
+					description: "The address to listen on for the traps of the agents. It _must_ include a port."
+					required:    false
+					type: string: {
+						default: "0.0.0.0:\(_port)"
+						examples: ["0.0.0.0:1162"]
+					}
+				}
+				communities: {
+					common:      true
+					description: "The communities of the SNMPv2c traps accepted, or any when empty."
+					required:    false
+					type: array: {
+						default: []
+						items: type: string: examples: ["public"]
+					}
+				}
+				users: {
+					common:      true
+					description: "The users of the SNMPv3 traps accepted."
+					required:    false
+					type: array: {
+						default: []
+						items: type: object: options: _user
+					}
+				}
+			}
+		}
+	}
+
+	output: logs: {
+		trap: {
+			description: "A trap or an inform received from an agent."
+			fields: {
+				community: {
+					description: "The community of the trap, for SNMPv2c."
+					required:    false
+					type: string: examples: ["public"]
+				}
+				host: {
+					description: "The IP address of the agent."
+					required:    true
+					type: string: examples: ["192.0.2.1"]
+				}
+				pdu_type: {
+					description: "The kind of notification."
+					required:    true
+					type: string: enum: {
+						trap:   "A trap, which isn't acknowledged."
+						inform: "An inform, which is acknowledged for SNMPv2c."
+					}
+				}
+				request_id: {
+					description: "The identifier of the notification."
+					required:    true
+					type: int: examples: [1234]
+				}
+				source_type: {
+					description: "The name of the source type."
+					required:    true
+					type: string: examples: ["snmp"]
+				}
+				timestamp: fields._current_timestamp
+				trap_oid: {
+					description: "The notification, `snmpTrapOID.0`, by name when its object is known."
+					required:    false
+					type: string: examples: ["linkDown", "1.3.6.1.4.1.9.9.41.2.0.1"]
+				}
+				uptime: {
+					description: "The uptime of the agent, `sysUpTime.0`, in hundredths of seconds."
+					required:    false
+					type: uint: {
+						examples: [123456]
+						unit: null
+					}
+				}
+				user: {
+					description: "The user of the trap, for SNMPv3."
+					required:    false
+					type: string: examples: ["vector"]
+				}
+				variables: {
+					description: "The other variables of the notification, keyed by the names of their instances, such as `ifIndex.2`."
+					required:    true
+					type: object: examples: [{"ifIndex.2": 2, "ifDescr.2": "eth0"}]
+				}
+				version: {
+					description: "The version of the protocol."
+					required:    true
+					type: string: examples: ["2c", "3"]
+				}
+			}
+		}
+		poll: {
+			description: "The variables returned by an agent, when `poll.emit` is `logs`."
+			fields: {
+				host: {
+					description: "The agent, as configured in `poll.targets`."
+					required:    true
+					type: string: examples: ["192.0.2.1"]
+				}
+				source_type: {
+					description: "The name of the source type."
+					required:    true
+					type: string: examples: ["snmp"]
+				}
+				timestamp: fields._current_timestamp
+				variables: {
+					description: "The variables, keyed by the names of their instances."
+					required:    true
+					type: object: examples: [{"sysUpTime.0": 123456, "ifDescr.1": "lo"}]
+				}
+				version: {
+					description: "The version of the protocol."
+					required:    true
+					type: string: examples: ["2c", "3"]
+				}
+			}
+		}
+	}
+
+	output: metrics: {
+		_snmp_tags: {
+			host: {
+				description: "The agent, as configured in `poll.targets`."
+				required:    true
+				examples: ["192.0.2.1"]
+			}
+			index: {
+				description: "The index of the instance, such as the one of an interface."
+				required:    false
+				examples: ["2"]
+			}
+			oid: {
+				description: "The object identifier of the variable."
+				required:    true
+				examples: ["1.3.6.1.2.1.2.2.1.10.2"]
+			}
+		}
+
+		counter: {
+			description:       "A `Counter32` or `Counter64` variable, named after its object, such as `ifInOctets`."
+			type:              "counter"
+			default_namespace: "snmp"
+			tags:              _snmp_tags
+		}
+		gauge: {
+			description:       "An `INTEGER`, `Gauge32` or `TimeTicks` variable, named after its object, such as `ifOperStatus`."
+			type:              "gauge"
+			default_namespace: "snmp"
+			tags:              _snmp_tags
+		}
+	}
+
+	how_it_works: {
+		mibs: {
+			title: "MIB modules"
+			body: """
+				The names of the objects are resolved with the objects of the common MIB modules,
+				such as `SNMPv2-MIB`, `IF-MIB` and `HOST-RESOURCES-MIB`, and of the modules of
+				`mib_paths`. Only the object identifiers assigned by the modules are read from them,
+				so the objects of modules that import unknown modules are still resolved as long as
+				their parents are. Variables whose object isn't known are named by their object
+				identifier.
+				"""
+		}
+		snmpv3: {
+			title: "SNMPv3"
+			body: """
+				SNMPv3 requests are authenticated and encrypted with the
+				[user-based security model](\(urls.snmp_usm)), after discovering the engine of each
+				agent. SNMPv3 traps are authenticated with the keys of their user localized to the
+				engine of the agent. SNMPv3 informs, which require Vector to be their authoritative
+				engine, aren't supported, nor are SNMPv1 traps.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		collect_completed_total:              components.sources.internal_metrics.output.metrics.collect_completed_total
+		collect_duration_seconds:             components.sources.internal_metrics.output.metrics.collect_duration_seconds
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		snmp_unauthorized_traps_total:        components.sources.internal_metrics.output.metrics.snmp_unauthorized_traps_total
+	}
+}
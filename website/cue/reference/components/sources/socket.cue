@@ -15,7 +15,7 @@ components: sources: socket: {
 	}
 
 	features: {
-		acknowledgements: false
+		acknowledgements: true
 		multiline: enabled: false
 		codecs: {
 			enabled:         true
@@ -55,6 +55,9 @@ components: sources: socket: {
 	}
 
 	configuration: {
+		acknowledgements: configuration._source_acknowledgements & {
+			relevant_when: "mode = `tcp`"
+		}
 		address: {
 			description:   "The address to listen for connections on, or `systemd#N` to use the Nth socket passed by systemd socket activation. If an address is used it _must_ include a port."
 			relevant_when: "mode = `tcp` or `udp`"
@@ -13,7 +13,7 @@ components: sources: stdin: {
 	}
 
 	features: {
-		acknowledgements: false
+		acknowledgements: true
 		multiline: enabled: false
 		codecs: {
 			enabled:         true
@@ -40,6 +40,7 @@ components: sources: stdin: {
 	}
 
 	configuration: {
+		acknowledgements: configuration._source_acknowledgements
 		host_key: {
 			category:    "Context"
 			common:      false
@@ -89,6 +90,15 @@ components: sources: stdin: {
 	]
 
 	how_it_works: {
+		acknowledgements: {
+			title: "Acknowledgements"
+			body: """
+				STDIN can't acknowledge the lines read, so with acknowledgements enabled, the source
+				waits for the events it read to be delivered before finishing once STDIN is closed,
+				and fails if any of them were not delivered, so that Vector doesn't exit with events
+				in flight.
+				"""
+		}
 		line_delimiters: {
 			title: "Line Delimiters"
 			body: """
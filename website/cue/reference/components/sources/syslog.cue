@@ -69,6 +69,20 @@ components: sources: syslog: {
 				default: "host"
 			}
 		}
+		framing: {
+			common:        false
+			description:   "How the messages of the connections are framed."
+			relevant_when: "mode = `tcp` or `unix`"
+			required:      false
+			type: string: {
+				default: "auto"
+				enum: {
+					auto:              "Detected from the first message of each connection: octet counted when it starts with a digit, newline delimited otherwise."
+					octet_counting:    "Each message is prefixed with its length, as required by [RFC 5425](\(urls.syslog_5425))."
+					newline_delimited: "Each message is terminated by a newline."
+				}
+			}
+		}
 		max_length: {
 			common:      true
 			description: "The maximum buffer size of incoming messages. Messages larger than this are truncated."
@@ -98,6 +112,16 @@ components: sources: syslog: {
 			}
 		}
 		socket_file_mode: sources.socket.configuration.socket_file_mode
+		tls_client_subject_key: {
+			common:        false
+			description:   "The key the subject of the certificate of the client is added at, such as `C=US,O=Example,CN=client`, when it authenticated with one."
+			relevant_when: "mode = `tcp`"
+			required:      false
+			type: string: {
+				default: null
+				examples: ["tls_client_subject"]
+			}
+		}
 		connection_limit: {
 			common:        false
 			description:   "The max number of TCP connections that will be processed."
@@ -233,7 +257,23 @@ components: sources: syslog: {
 		line_delimiters: {
 			title: "Line Delimiters"
 			body: """
-				Each line is read until a new line delimiter, the `0xA` byte, is found.
+				Over TCP and Unix sockets, messages are either newline delimited, read until a new
+				line delimiter, the `0xA` byte, is found, or octet counted, prefixed with their
+				length as described in [RFC 6587](\(urls.syslog_6587)). The framing is detected from
+				the first message of each connection, so that newline delimited messages starting
+				with a digit aren't mistaken for octet counted ones, unless it's set with `framing`.
+				Over UDP, each datagram is a message.
+				"""
+		}
+
+		tls: {
+			title: "TLS"
+			body: """
+				Syslog over TLS, as described in [RFC 5425](\(urls.syslog_5425)), is received by
+				enabling `tls` in `tcp` mode, usually on port 6514. Clients are required to
+				authenticate with a certificate signed by the authorities of `tls.ca_file` when
+				`tls.verify_certificate` is enabled, and the subject of their certificate is added to
+				the events at `tls_client_subject_key` when it's set.
 				"""
 		}
 
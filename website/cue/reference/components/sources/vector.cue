@@ -63,6 +63,21 @@ components: sources: vector: {
 				examples: ["0.0.0.0:\(_port)"]
 			}
 		}
+		client_metadata_key: {
+			common:      false
+			description: """
+				The field the metadata clients identify themselves with is attached to their events at, such as
+				their agent ID or site, which the `vector` sink sends with its `client_metadata` option. The metadata
+				is attached to logs and traces as an object at this field, and to metrics as tags named after the
+				field and the metadata, such as `client_agent_id`. The metadata isn't attached when unset.
+				Only supported by version 2 of the source.
+				"""
+			required: false
+			type: string: {
+				default: null
+				examples: ["client"]
+			}
+		}
 		shutdown_timeout_secs: {
 			common:      false
 			description: "The timeout before a connection is forcefully closed during shutdown."
@@ -72,6 +87,19 @@ components: sources: vector: {
 				unit:    "seconds"
 			}
 		}
+		tls: type: object: options: subject_alt_names: {
+			common:      false
+			description: """
+				The DNS names or IP addresses, one of which the certificates of the clients must have as a
+				subject alternative name, on top of being verified. Requires `tls.verify_certificate` to be
+				enabled.
+				"""
+			required: false
+			type: array: {
+				default: []
+				items: type: string: examples: ["agent.example.com", "10.0.0.1"]
+			}
+		}
 		version: {
 			description: "Source API version. Specifying this version ensures that Vector does not break backward compatibility."
 			common:      true
@@ -87,6 +115,27 @@ components: sources: vector: {
 		}
 	}
 
+	how_it_works: {
+		client_metadata: {
+			title: "Client metadata"
+			body: """
+				In aggregator topologies, the `vector` sinks of the agents can identify themselves with their
+				`client_metadata`, such as their agent ID or the site they run at, which is sent with every
+				request. When `client_metadata_key` is set, the `vector` source attaches this metadata to all the
+				events of each client, so that they can be routed, or metrics computed, per agent downstream.
+				"""
+		}
+		compression: {
+			title: "Compression"
+			body: """
+				Version 2 of the source accepts events compressed with gzip, and with zstd, which it advertises in
+				its responses. The `vector` sinks configured with the `zstd` compression send events compressed
+				with gzip until they learn that the source accepts zstd, so that they can be upgraded before the
+				sources. Once decompressed, the events sent in a single request can't exceed 64 MiB.
+				"""
+		}
+	}
+
 	output: {
 		logs: event: {
 			description: "A Vector event"
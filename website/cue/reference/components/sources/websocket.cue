@@ -0,0 +1,181 @@
+package metadata
+
+components: sources: websocket: {
+	title: "WebSocket"
+
+	description: """
+		Connects to a [WebSocket](\(urls.websocket)) server, such as the streaming API of an exchange,
+		and ingests each message it sends as an event.
+		"""
+
+	features: {
+		acknowledgements: false
+		collect: {
+			checkpoint: enabled: false
+			tls: {
+				enabled:                true
+				can_verify_certificate: true
+				enabled_default:        false
+			}
+			from: {
+				service: services.websocket
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["tcp"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+		multiline: enabled: false
+		codecs: {
+			enabled:         true
+			default_framing: "bytes"
+		}
+	}
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		auth: configuration._http_auth & {_args: {
+			password_example: "${WEBSOCKET_PASSWORD}"
+			username_example: "${WEBSOCKET_USERNAME}"
+		}}
+		headers: {
+			common:      false
+			description: "Additional headers sent with the handshake requests, such as API keys."
+			required:    false
+			type: object: {
+				examples: [{"X-Api-Key": "${API_KEY}"}]
+				options: {
+					"*": {
+						common:      false
+						description: "The value of the header."
+						required:    false
+						type: string: default: null
+					}
+				}
+			}
+		}
+		ping_interval: {
+			common:      false
+			description: "Send WebSocket pings each this number of seconds."
+			required:    false
+			type: uint: {
+				default: null
+				unit:    "seconds"
+			}
+		}
+		ping_timeout: {
+			common:        false
+			description:   "Reconnect to the WebSocket server if a ping isn't answered with a pong within this number of seconds."
+			relevant_when: "ping_interval is set"
+			required:      false
+			type: uint: {
+				default: null
+				unit:    "seconds"
+			}
+		}
+		reconnect_initial_backoff_ms: {
+			common:      false
+			description: "The delay before the first attempt to reconnect, doubled after each failed attempt."
+			required:    false
+			type: uint: {
+				default: 500
+				unit:    "milliseconds"
+			}
+		}
+		reconnect_max_backoff_secs: {
+			common:      false
+			description: "The maximum delay between the attempts to reconnect."
+			required:    false
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		subprotocols: {
+			common:      false
+			description: "The subprotocols offered to the server, in order of preference, with the `Sec-WebSocket-Protocol` header."
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: examples: ["graphql-transport-ws", "v2.stream.example.com"]
+			}
+		}
+		subscribe_messages: {
+			common:      true
+			description: """
+				The text messages sent to the server after each connection, such as the subscription
+				requests of streaming APIs.
+				"""
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: examples: [#"{"op": "subscribe", "channel": "trades"}"#]
+			}
+		}
+		uri: {
+			description: "The WebSocket URI to connect to, with the `ws` or `wss` scheme."
+			required:    true
+			type: string: {
+				examples: ["wss://stream.example.com/ws"]
+			}
+		}
+	}
+
+	output: logs: record: {
+		description: "An individual WebSocket message"
+		fields: {
+			message: {
+				description: "The text or binary message, when it's not decoded."
+				required:    true
+				type: string: {
+					examples: [#"{"channel": "trades", "price": 42.1}"#]
+				}
+			}
+			timestamp: fields._current_timestamp
+		}
+	}
+
+	how_it_works: {
+		reconnection: {
+			title: "Reconnection"
+			body: """
+				Vector reconnects to the server, with an exponential backoff, whenever its connection
+				fails, is closed, or a ping isn't answered within `ping_timeout`. The
+				`subscribe_messages` are sent again on every connection, as the subscriptions don't
+				outlive it. The messages sent by the server while disconnected are lost.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		connection_established_total:         components.sources.internal_metrics.output.metrics.connection_established_total
+		connection_shutdown_total:            components.sources.internal_metrics.output.metrics.connection_shutdown_total
+		events_in_total:                      components.sources.internal_metrics.output.metrics.events_in_total
+		open_connections:                     components.sources.internal_metrics.output.metrics.open_connections
+	}
+}
@@ -0,0 +1,259 @@
+package metadata
+
+components: sources: windows_event_log: {
+	title: "Windows Event Log"
+
+	description: """
+		Collects the events of the [Windows Event Log](\(urls.windows_event_log)) channels of the
+		local system, such as `System`, `Application` and `Security`.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["daemon"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: true
+		collect: {
+			checkpoint: enabled: true
+			from: service:       services.host
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		requirements: [
+			"""
+				This source is only supported on Windows. Reading the `Security` channel requires
+				running Vector as an administrator, or as a member of the `Event Log Readers` group.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._source_acknowledgements
+		batch_size: {
+			common:      false
+			description: "The maximum number of events read at once."
+			required:    false
+			type: uint: {
+				default: 100
+				unit:    "events"
+			}
+		}
+		channels: {
+			description: "The channels the events are read from."
+			required:    true
+			type: array: items: type: string: {
+				examples: ["System", "Application", "Security", "Microsoft-Windows-PowerShell/Operational"]
+			}
+		}
+		event_query: {
+			common:      true
+			description: "The [XPath query](\(urls.windows_event_log_queries)) selecting the events of the channels."
+			required:    false
+			type: string: {
+				default: "*"
+				examples: ["*[System[Level<=3]]", "*[System[(EventID=4624 or EventID=4625)]]"]
+			}
+		}
+		poll_interval_secs: {
+			common:      false
+			description: "The interval between reads of the new events."
+			required:    false
+			type: float: {
+				default: 1.0
+				examples: [0.5, 1.0]
+			}
+		}
+		read_existing_events: {
+			common:      false
+			description: "Reads the events already in the channels when there's no checkpoint, rather than only the new ones."
+			required:    false
+			type: bool: default: false
+		}
+		render_message: {
+			common:      false
+			description: "Resolves the messages of the events, as shown by the Event Viewer, from the metadata of their providers."
+			required:    false
+			type: bool: default: true
+		}
+	}
+
+	output: logs: event: {
+		description: "An event of a channel."
+		fields: {
+			activity_id: {
+				description: "The identifier of the activity the event is part of."
+				required:    false
+				type: string: examples: ["{b8a5ae7c-4580-0000-d4ae-a5b88045d801}"]
+			}
+			channel: {
+				description: "The channel of the event."
+				required:    true
+				type: string: examples: ["Security"]
+			}
+			computer: {
+				description: "The name of the computer the event was logged on."
+				required:    true
+				type: string: examples: ["web-01.example.com"]
+			}
+			event_data: {
+				description: "The data of the event, keyed by name, or as `param1`, `param2` and so on for the unnamed data of classic events."
+				required:    false
+				type: object: {}
+			}
+			event_id: {
+				description: "The identifier of the event, specific to its provider."
+				required:    true
+				type: uint: {
+					examples: [4624]
+					unit: null
+				}
+			}
+			keywords: {
+				description: "The keywords of the event, as a hexadecimal bitmask."
+				required:    false
+				type: string: examples: ["0x8020000000000000"]
+			}
+			level: {
+				description: "The level of the event."
+				required:    false
+				type: uint: {
+					examples: [2]
+					unit: null
+				}
+			}
+			level_name: {
+				description: "The name of the level of the event."
+				required:    false
+				type: string: enum: {
+					Critical:    "Level 1."
+					Error:       "Level 2."
+					Warning:     "Level 3."
+					Information: "Level 0 or 4."
+					Verbose:     "Level 5."
+				}
+			}
+			message: {
+				description: "The message of the event, when `render_message` is set and its provider has one."
+				required:    false
+				type: string: examples: ["An account was successfully logged on."]
+			}
+			opcode: {
+				description: "The opcode of the event."
+				required:    false
+				type: uint: {
+					examples: [0]
+					unit: null
+				}
+			}
+			process_id: {
+				description: "The identifier of the process that logged the event."
+				required:    false
+				type: uint: {
+					examples: [680]
+					unit: null
+				}
+			}
+			provider_guid: {
+				description: "The GUID of the provider of the event."
+				required:    false
+				type: string: examples: ["{54849625-5478-4994-a5ba-3e3b0328c30d}"]
+			}
+			provider_name: {
+				description: "The name of the provider of the event."
+				required:    true
+				type: string: examples: ["Microsoft-Windows-Security-Auditing"]
+			}
+			qualifiers: {
+				description: "The qualifiers of the identifier of classic events."
+				required:    false
+				type: uint: {
+					examples: [16384]
+					unit: null
+				}
+			}
+			record_id: {
+				description: "The number of the event in its channel."
+				required:    true
+				type: uint: {
+					examples: [98765]
+					unit: null
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: examples: ["windows_event_log"]
+			}
+			task: {
+				description: "The task of the event."
+				required:    false
+				type: uint: {
+					examples: [12544]
+					unit: null
+				}
+			}
+			thread_id: {
+				description: "The identifier of the thread that logged the event."
+				required:    false
+				type: uint: {
+					examples: [724]
+					unit: null
+				}
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The time the event was logged."
+			}
+			user_data: {
+				description: "The user data of the event, for the events that have it rather than event data."
+				required:    false
+				type: object: {}
+			}
+			user_id: {
+				description: "The SID of the user the event was logged for."
+				required:    false
+				type: string: examples: ["S-1-5-18"]
+			}
+			version: {
+				description: "The version of the event schema."
+				required:    false
+				type: uint: {
+					examples: [2]
+					unit: null
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		checkpointing: {
+			title: "Checkpointing"
+			body: """
+				The bookmark of the last delivered event, when acknowledgements are enabled, or of
+				the last event sent downstream otherwise, is written to a checkpoint in the data
+				directory once a second. When Vector restarts, reading resumes after that event, so
+				the events being delivered may be emitted again. Without a checkpoint, only the
+				new events are read, unless `read_existing_events` is set.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+	}
+}
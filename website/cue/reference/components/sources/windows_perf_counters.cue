@@ -0,0 +1,131 @@
+package metadata
+
+components: sources: windows_perf_counters: {
+	title: "Windows Performance Counters"
+
+	description: """
+		Collects [performance counters](\(urls.windows_performance_counters)) of the local
+		or remote Windows systems as metrics, on an interval.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["daemon"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: false
+		collect: {
+			checkpoint: enabled: false
+			from: service:       services.host
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		counters: {
+			description: """
+				The paths of the counters to collect, in the `\\Object(Instance)\\Counter` form,
+				prefixed with `\\\\computer` for counters of a remote system. The instance can
+				contain `*` and `?` wildcards to collect every matching instance, but the object
+				and counter names can't. Paths are given in English, whatever the language of the
+				system.
+				"""
+			required: true
+			type: array: items: type: string: {
+				examples: [
+					#"\Processor(*)\% Processor Time"#,
+					#"\Memory\Available Bytes"#,
+					#"\\web-01\Network Interface(*)\Bytes Received/sec"#,
+				]
+			}
+		}
+		scrape_interval_secs: {
+			description: "The interval between collections."
+			common:      true
+			required:    false
+			type: float: {
+				default: 15.0
+				unit:    "seconds"
+			}
+		}
+		namespace: {
+			description: "The namespace of metrics. Disabled if empty."
+			common:      false
+			required:    false
+			type: string: {
+				default: "windows"
+			}
+		}
+	}
+
+	how_it_works: {
+		metric_names: {
+			title: "Metric names"
+			body: """
+				Each counter is collected as a gauge named after its object and counter names,
+				lowercased and joined by underscores, with `%` written as `percent` and `/` as
+				`per`. For example, `\\Network Interface(*)\\Bytes Received/sec` is collected as
+				`network_interface_bytes_received_per_sec`, tagged with the name of each instance.
+				"""
+		}
+		rate_counters: {
+			title: "Rate counters"
+			body: """
+				Counters measuring rates, such as `% Processor Time`, are computed over the
+				interval between two collections. They're first collected when the source starts,
+				so their values are available from the first interval on. Instances appearing
+				between collections are only reported from the next one.
+				"""
+		}
+	}
+
+	output: metrics: {
+		counter_value: {
+			description: "The value of a performance counter. The name of the metric is derived from the counter path."
+			type:        "gauge"
+			default_namespace: "windows"
+			tags: {
+				counter: {
+					description: "The name of the counter."
+					required:    true
+					examples: ["% Processor Time"]
+				}
+				host: {
+					description: "The hostname of the system Vector runs on."
+					required:    true
+					examples: [_values.local_host]
+				}
+				instance: {
+					description: "The instance of the object the value was read for."
+					required:    false
+					examples: ["_Total", "0"]
+				}
+				object: {
+					description: "The name of the object the counter belongs to."
+					required:    true
+					examples: ["Processor"]
+				}
+			}
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:          components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_events_total: components.sources.internal_metrics.output.metrics.component_received_events_total
+	}
+}
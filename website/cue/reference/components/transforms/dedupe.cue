@@ -31,6 +31,24 @@ components: transforms: dedupe: {
 			required:    false
 			type: object: {
 				options: {
+					false_positive_rate: {
+						common:      false
+						description: "The target false positive rate of the bloom filters used when `cache.mode` is `probabilistic`. A false positive causes a unique Event to be discarded as a duplicate."
+						required:    false
+						type: float: default: 0.0001
+					}
+					mode: {
+						common:      false
+						description: "The storage used to remember recent Events."
+						required:    false
+						type: string: {
+							default: "lru"
+							enum: {
+								lru:           "Exact matching, backed by an LRU cache of `cache.num_events` entries."
+								probabilistic: "Approximate matching, backed by a pair of rotating bloom filters each sized for `cache.num_events` entries. Memory usage is bounded regardless of Event size."
+							}
+						}
+					}
 					num_events: {
 						common:      true
 						description: "The number of recent Events to cache and compare new incoming Events against."
@@ -40,6 +58,15 @@ components: transforms: dedupe: {
 							unit:    null
 						}
 					}
+					ttl_secs: {
+						common:      false
+						description: "The amount of time after which a cached Event is no longer considered when checking for duplicates. Seeing a duplicate does not extend the lifetime of the cached Event."
+						required:    false
+						type: uint: {
+							default: null
+							unit:    "seconds"
+						}
+					}
 				}
 			}
 		}
@@ -93,6 +120,17 @@ components: transforms: dedupe: {
 				already in the cache that will put that event back to the head of
 				the cache and reset its place in line, making it once again last
 				entry in line to be evicted.
+
+				If `cache.ttl_secs` is set, cached Events are additionally only
+				considered for `cache.ttl_secs` seconds after they were first seen.
+
+				When `cache.mode` is set to `probabilistic`, Events are instead
+				remembered by two bloom filters. New Events are added to the current
+				filter until it holds `cache.num_events` Events (or is older than
+				`cache.ttl_secs`), at which point the older filter is discarded. This
+				bounds memory usage for steady, high-cardinality streams at the cost
+				of occasionally discarding a unique Event, at the rate configured by
+				`cache.false_positive_rate`.
 				"""
 		}
 
@@ -0,0 +1,169 @@
+package metadata
+
+components: transforms: join: {
+	title: "Join"
+
+	description: """
+		Correlates pairs of log events, such as requests and their responses, that share a key and arrive within a
+		time window of each other, and merges each pair into a single log event.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		reduce: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		expire_after_ms: {
+			common:      true
+			description: "How long an event waits for an event from the other side with the same key."
+			required:    false
+			type: uint: {
+				default: 30000
+				unit:    "milliseconds"
+			}
+		}
+		flush_period_ms: {
+			common:      false
+			description: "Controls the frequency that Vector checks for (and flushes) expired events."
+			required:    false
+			type: uint: {
+				default: 1000
+				unit:    "milliseconds"
+			}
+		}
+		key_field: {
+			description: "The template rendered for each event to find the events it should be joined with."
+			required:    true
+			type: string: {
+				examples: ["{{ request_id }}", "{{ host }}-{{ trace_id }}"]
+				syntax: "template"
+			}
+		}
+		left: {
+			description: """
+				The condition identifying events from the left side of the join. Events matching both `left` and `right`
+				are considered to be on the left side.
+				"""
+			required: true
+			type: condition: {}
+		}
+		max_pending: {
+			common: false
+			description: """
+				The maximum number of events waiting to be joined. Once reached, the oldest waiting event is unmatched to
+				make room for the next one.
+				"""
+			required: false
+			type: uint: {
+				default: 10000
+				unit:    "events"
+			}
+		}
+		reroute_unmatched: {
+			common: false
+			description: """
+				Sends events that couldn't be joined to the `<transform_name>.unmatched` output instead of discarding
+				them.
+				"""
+			required: false
+			type: bool: default: false
+		}
+		right: {
+			description: "The condition identifying events from the right side of the join."
+			required:    true
+			type: condition: {}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	telemetry: metrics: {
+		component_discarded_events_total: components.sources.internal_metrics.output.metrics.component_discarded_events_total
+		events_discarded_total:           components.sources.internal_metrics.output.metrics.events_discarded_total
+	}
+
+	examples: [
+		{
+			title: "Join requests and responses"
+			input: [
+				{
+					log: {
+						type:       "request"
+						request_id: "a1b2"
+						path:       "/login"
+					}
+				},
+				{
+					log: {
+						type:       "response"
+						request_id: "a1b2"
+						status:     200
+					}
+				},
+			]
+
+			configuration: {
+				left:      #".type == "request""#
+				right:     #".type == "response""#
+				key_field: "{{ request_id }}"
+			}
+
+			output: [
+				{
+					log: {
+						type:       "response"
+						request_id: "a1b2"
+						path:       "/login"
+						status:     200
+					}
+				},
+			]
+		},
+	]
+
+	outputs: [
+		{
+			name:        "unmatched"
+			description: "Events that couldn't be joined, when `reroute_unmatched` is enabled."
+		},
+	]
+
+	how_it_works: {
+		joining: {
+			title: "Joining"
+			body: """
+				Each event is assigned to the left or right side of the join using the `left` and `right` conditions, and
+				kept until an event from the other side with the same rendered `key_field` arrives. The two events are
+				then merged into one, the fields of the right event overriding those of the left event, and sent to the
+				default output. Events are paired in the order they arrive.
+				"""
+		}
+		unmatched_events: {
+			title: "Unmatched Events"
+			body: """
+				Events that match neither condition, whose `key_field` can't be rendered, or that aren't joined within
+				`expire_after_ms` are unmatched, as well as the oldest waiting event once `max_pending` events are
+				waiting. They are sent to the `unmatched` output when `reroute_unmatched` is enabled, and discarded
+				otherwise, which is logged and reported by the `component_discarded_events_total` internal metric.
+				Events still waiting to be joined when Vector shuts down are unmatched as well.
+				"""
+		}
+	}
+}
@@ -25,16 +25,61 @@ components: transforms: log_to_metric: {
 	}
 
 	configuration: {
+		all_metrics: {
+			description: """
+				Converts logs holding a metric, as produced by the
+				[`metric_to_log`](\(urls.vector_metric_to_log_transform)) transform, back into that
+				metric. The `timestamp` and `host` fields are moved back to the timestamp and the
+				`host_tag` tag of the metric. Logs that don't hold a metric are dropped. Can't be used together
+				with `metrics`.
+				"""
+			required: false
+			common:   false
+			type: bool: default: false
+		}
+		host_tag: {
+			description: """
+				The tag of the metric the `host` field is moved back to, when `all_metrics` is set. Set this to the
+				same value as the `host_tag` of the `metric_to_log` transform that produced the logs.
+				"""
+			required: false
+			common:   false
+			type: string: {
+				default: "host"
+				examples: ["hostname"]
+			}
+		}
 		metrics: {
-			description: "A table of key/value pairs representing the keys to be added to the event."
-			required:    true
+			description: "A table of key/value pairs representing the keys to be added to the event. Required unless `all_metrics` is set."
+			required:    false
+			common:      true
 			type: array: items: type: object: {
 				examples: []
 				options: {
+					buckets: {
+						description: """
+							A [VRL](\(urls.vrl_reference)) expression evaluated against each event, returning the
+							upper bounds of the histogram buckets. When set, an aggregated histogram is emitted
+							instead of a distribution, allowing the buckets to vary per event.
+							"""
+						required:      false
+						common:        false
+						relevant_when: #"type = "histogram""#
+						type: string: {
+							default: null
+							examples: [#"if .service == "api" { [0.1, 0.5, 1.0] } else { [1.0, 5.0, 10.0] }"#]
+							syntax: "remap_program"
+						}
+					}
 					field: {
-						description: "The log field to use as the metric."
-						required:    true
+						description: """
+							The log field to use as the metric. Exactly one of `field` or `value` must be set,
+							and `value` is only available for the `histogram` and `distribution` types.
+							"""
+						required:    false
+						common:      true
 						type: string: {
+							default: null
 							examples: ["duration", "parent.child"]
 						}
 					}
@@ -85,6 +130,19 @@ components: transforms: log_to_metric: {
 							syntax: "template"
 						}
 					}
+					statistic: {
+						description: "The statistic of the emitted distribution."
+						required:      false
+						common:        false
+						relevant_when: #"type = "distribution""#
+						type: string: {
+							enum: {
+								histogram: "The samples are aggregated into a histogram."
+								summary:   "The samples are aggregated into a summary."
+							}
+							default: "histogram"
+						}
+					}
 					tags: {
 						description: "Key/value pairs representing [metric tags](\(urls.vector_metric)#tags)."
 						required:    false
@@ -114,14 +172,29 @@ components: transforms: log_to_metric: {
 						required:    true
 						type: string: {
 							enum: {
-								counter:   "A [counter metric type](\(urls.vector_metric)#counter)."
-								gauge:     "A [gauge metric type](\(urls.vector_metric)#gauge)."
-								histogram: "A [distribution metric type](\(urls.vector_metric)#histogram) with histogram statistic."
-								set:       "A [set metric type](\(urls.vector_metric)#set)."
-								summary:   "A [distribution metric type](\(urls.vector_metric)#distribution) with summary statistic."
+								counter:      "A [counter metric type](\(urls.vector_metric)#counter)."
+								distribution: "A [distribution metric type](\(urls.vector_metric)#distribution) with the configured statistic."
+								gauge:        "A [gauge metric type](\(urls.vector_metric)#gauge)."
+								histogram:    "A [distribution metric type](\(urls.vector_metric)#histogram) with histogram statistic."
+								set:          "A [set metric type](\(urls.vector_metric)#set)."
+								summary:      "A [distribution metric type](\(urls.vector_metric)#distribution) with summary statistic."
 							}
 						}
 					}
+					value: {
+						description: """
+							A [VRL](\(urls.vrl_reference)) expression evaluated against each event, returning the
+							sample value, or an array of sample values, to record. Requires `name` to be set.
+							"""
+						required:      false
+						common:        false
+						relevant_when: #"type = "histogram" or type = "distribution""#
+						type: string: {
+							default: null
+							examples: ["to_float!(.duration_ms) / 1000"]
+							syntax: "remap_program"
+						}
+					}
 				}
 			}
 		}
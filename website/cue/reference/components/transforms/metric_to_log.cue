@@ -28,7 +28,10 @@ components: transforms: metric_to_log: {
 	configuration: {
 		host_tag: {
 			common:      true
-			description: "Tag key that identifies the source host."
+			description: """
+				Tag key that identifies the source host. The tag is moved to the `host` field of the log, and can be
+				moved back by the `host_tag` option of the `log_to_metric` transform.
+				"""
 			required:    false
 			type: string: {
 				default: "hostname"
@@ -102,7 +105,19 @@ components: transforms: metric_to_log: {
 		},
 	]
 
-	how_it_works: {}
+	how_it_works: {
+		round_trip: {
+			title: "Converting logs back into metrics"
+			body: """
+				The structure of the metric, such as the buckets of histograms or the samples of
+				distributions, is kept in the fields of the log, so the metric can be restored
+				without loss by the [`log_to_metric`](\(urls.vector_log_to_metric_transform))
+				transform with `all_metrics` set. This allows metrics to be sent through sinks that
+				only accept logs. The `host_tag` of both transforms must be the same, so that the
+				host is moved back to the tag it was taken from.
+				"""
+		}
+	}
 
 	telemetry: metrics: {
 		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
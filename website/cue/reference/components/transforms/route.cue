@@ -28,7 +28,63 @@ components: transforms: route: {
 	}
 
 	configuration: {
+		dynamic: {
+			common: false
+			description: """
+				Routes events using a mapping from keys to output names stored in an
+				[enrichment table](\(urls.enrichment_tables_concept)), so routes can be added or changed by updating the
+				table instead of the configuration. The table is looked up for every event, so routes change as soon as
+				the table is reloaded. Events whose key has no row in the table, or whose row names an output not listed
+				in `outputs`, are sent to the `<transform_name>._unmatched` output unless they match a static route.
+				"""
+			required: false
+			type: object: options: {
+				table: {
+					description: "The name of the enrichment table holding the routes."
+					required:    true
+					type: string: {
+						examples: ["tenant_routes"]
+					}
+				}
+				key: {
+					description: "The template rendered for each event to find its row in the table."
+					required:    true
+					type: string: {
+						examples: ["{{ tenant }}"]
+						syntax: "template"
+					}
+				}
+				table_key: {
+					common:      false
+					description: "The column of the table matched against the rendered `key`."
+					required:    false
+					type: string: {
+						default: "key"
+					}
+				}
+				output_field: {
+					common:      false
+					description: "The column of the table holding the name of the output to route the event to."
+					required:    false
+					type: string: {
+						default: "output"
+					}
+				}
+				outputs: {
+					description: """
+						The outputs the table may route events to. Outputs have to be known when Vector starts, so each
+						of them can be referenced as an input by other components with the name
+						`<transform_name>.<output>`.
+						"""
+					required: true
+					type: array: items: type: string: {
+						examples: ["tenant_a", "tenant_b"]
+					}
+				}
+			}
+		}
 		route: {
+			common: true
 			description: """
 				A table of route identifiers to logical conditions representing the filter of the route. Each route can
 				then be referenced as an input by other components with the name `<transform_name>.<route_id>`. If an
@@ -36,7 +92,7 @@ components: transforms: route: {
 				`_unmatched` is a reserved output name and cannot be used as a route name. `_default` is also reserved
 				for future use.
 				"""
-			required: true
+			required: false
 			type: object: {
 				options: {
 					"*": {
@@ -25,12 +25,44 @@ components: transforms: sample: {
 	}
 
 	configuration: {
+		adaptive: {
+			common: false
+			description: """
+				Adjusts the sampling rate over time so that the number of events forwarded per second approaches
+				a target. `rate` is used as the initial rate. When `key_field` is set and `key_sampling` is
+				`consistent`, events sharing a key are still kept or dropped together as the rate changes.
+				"""
+			required: false
+			type: object: options: {
+				max_rate: {
+					common:      false
+					description: "The highest sampling rate the adaptive mode may select."
+					required:    false
+					type: uint: {
+						default: 10000
+						unit:    null
+					}
+				}
+				target_events_per_sec: {
+					description: "The number of events per second that should be forwarded."
+					required:    true
+					type: float: examples: [100.0]
+				}
+				window_secs: {
+					common:      false
+					description: "The interval over which the incoming event rate is measured before the sampling rate is adjusted."
+					required:    false
+					type: float: default: 1.0
+				}
+			}
+		}
 		key_field: {
 			common: false
 			description: """
 				The name of the log field whose value will be hashed to determine if the event should be passed.
 
-				Consistently samples the same events. Actual rate of sampling may differ from the configured one if
+				Consistently samples the same events, so all events sharing a value (for example a trace or user ID)
+				are kept or dropped together. Actual rate of sampling may differ from the configured one if
 				values in the field are not uniformly distributed. If left unspecified, or if the event doesn't have
 				`key_field`, events will be count rated.
 				"""
@@ -40,6 +72,18 @@ components: transforms: sample: {
 				examples: ["message"]
 			}
 		}
+		key_sampling: {
+			common:      false
+			description: "How events are selected using the hash of their `key_field` value."
+			required:    false
+			type: string: {
+				default: "modulo"
+				enum: {
+					modulo:     "Keep events whose hash is a multiple of `rate`."
+					consistent: "Keep events whose hash falls in the lowest `1/rate` of the hash space. A value kept at a given rate is also kept at any lower rate, across instances and restarts, so events sharing a value stay together when the rate changes, for example in adaptive mode."
+				}
+			}
+		}
 		exclude: {
 			common: true
 			description: """
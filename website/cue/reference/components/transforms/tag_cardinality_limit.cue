@@ -59,6 +59,47 @@ components: transforms: tag_cardinality_limit: {
 				}
 			}
 		}
+		per_metric_limits: {
+			common:      false
+			description: """
+				Limits overriding `value_limit` and `limit_exceeded_action` for the metrics of the
+				given names. The tags of these metrics are tracked separately from those of other
+				metrics.
+				"""
+			required:    false
+			type: object: {
+				examples: [{"http_requests_total": {"value_limit": 100, "limit_exceeded_action": "drop_event"}}]
+				options: {
+					"*": {
+						description: "The limits of the metric named by the key."
+						required:    true
+						type: object: options: {
+							limit_exceeded_action: {
+								common:      true
+								description: "The action taken for tags of the metric exceeding its limit. Defaults to the `limit_exceeded_action` of the transform."
+								required:    false
+								type: string: {
+									default: null
+									enum: {
+										drop_tag:   "Remove tags that would exceed the limit from the incoming metric"
+										drop_event: "Drop any metric events that contain tags that would exceed the limit"
+									}
+								}
+							}
+							value_limit: {
+								common:      true
+								description: "How many distinct values to accept for any given key of the metric."
+								required:    false
+								type: uint: {
+									default: 500
+									unit:    null
+								}
+							}
+						}
+					}
+				}
+			}
+		}
 		value_limit: {
 			common:      true
 			description: "How many distinct values to accept for any given key."
@@ -68,6 +109,20 @@ components: transforms: tag_cardinality_limit: {
 				unit:    null
 			}
 		}
+		window_secs: {
+			common:      false
+			description: """
+				When set, the values of tags are tracked in windows of this length, and values which
+				weren't seen during a whole window are forgotten, making room for new ones. By
+				default, values are tracked for as long as Vector runs.
+				"""
+			required:    false
+			type: uint: {
+				default: null
+				examples: [3600, 86400]
+				unit: "seconds"
+			}
+		}
 	}
 
 	input: {
@@ -203,6 +258,21 @@ components: transforms: tag_cardinality_limit: {
 				"""
 		}
 
+		windows: {
+			title: "Windows"
+			body: """
+				Without `window_secs`, a value accepted for a key takes up room towards its limit
+				for as long as Vector runs, even if it's never seen again. With it, tracking starts
+				over at the end of each window, the values seen during the window ending remaining
+				accepted for the next one. Values which keep showing up stay accepted, while the
+				others are forgotten after one to two windows, making room for new values.
+
+				Values carried over from the previous window are accepted even if the limit has
+				been reached by new values in the meantime, so at most twice the limit of distinct
+				values can be accepted for a key over the span of two windows.
+				"""
+		}
+
 		restarts: {
 			title: "Restarts"
 			body: """
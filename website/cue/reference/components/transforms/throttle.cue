@@ -48,6 +48,32 @@ components: transforms: throttle: {
 				syntax: "template"
 			}
 		}
+		redis: {
+			common: false
+			description: """
+				Stores the rate limiters in Redis so that all Vector instances configured with the same Redis server and
+				`key_prefix` share one rate limit per bucket. If Redis can't be reached, each instance falls back to
+				rate limiting locally until Redis is available again.
+				"""
+			required: false
+			type: object: options: {
+				endpoint: {
+					description: "The Redis URL to connect to."
+					required:    true
+					type: string: {
+						examples: ["redis://127.0.0.1:6379/0"]
+					}
+				}
+				key_prefix: {
+					common:      false
+					description: "The prefix of the Redis keys that hold the state of each bucket."
+					required:    false
+					type: string: {
+						default: "vector_throttle"
+					}
+				}
+			}
+		}
 		threshold: {
 			description: """
 				The number of events allowed for a given bucket per configured `window_secs`.
@@ -79,6 +105,7 @@ components: transforms: throttle: {
 	}
 
 	telemetry: metrics: {
+		component_errors_total: components.sources.internal_metrics.output.metrics.component_errors_total
 		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
 	}
 
@@ -156,6 +183,15 @@ components: transforms: throttle: {
 						by the bucket's `key`.
 						"""
 				},
+				{
+					title: "Distributed Rate Limiting"
+					body: """
+						When `redis` is configured, each bucket's state is kept in Redis and updated atomically, using the
+						Redis server's clock, so that the `threshold` applies across every Vector instance sharing the
+						Redis server rather than to each instance separately. Events that arrive together are checked in a
+						single round trip to Redis.
+						"""
+				},
 			]
 		}
 	}
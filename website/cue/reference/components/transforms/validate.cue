@@ -0,0 +1,112 @@
+package metadata
+
+components: transforms: validate: {
+	title: "Validate"
+
+	description: """
+		Validates log events against a [JSON Schema](\(urls.json_schema)), sending events that don't conform to it
+		to a separate output along with the list of violations, so that log contracts can be enforced before events
+		reach sinks.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		filter: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		schema: {
+			common: true
+			description: """
+				The JSON Schema events have to conform to, written as a table. Exactly one of `schema` or
+				`schema_file` must be set.
+				"""
+			required: false
+			type: object: {
+				examples: [
+					{
+						type: "object"
+						required: ["message"]
+						properties: message: type: "string"
+					},
+				]
+				options: {}
+			}
+		}
+		schema_file: {
+			common:      false
+			description: "The path of a JSON file holding the JSON Schema events have to conform to."
+			required:    false
+			type: string: {
+				examples: ["./schemas/access_log.json"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	telemetry: metrics: {
+		component_errors_total: components.sources.internal_metrics.output.metrics.component_errors_total
+	}
+
+	examples: [
+		{
+			title: "Reject events missing a field"
+
+			configuration: {
+				schema: {
+					type: "object"
+					required: ["status"]
+					properties: status: type: "integer"
+				}
+			}
+
+			input: log: {
+				message: "GET /index.html"
+			}
+			output: null
+		},
+	]
+
+	outputs: [
+		{
+			name: "rejected"
+			description: """
+				Events that don't conform to the schema. Each event is annotated with the violations found in it, under
+				`metadata.rejected.violations`, as a list of objects holding the `path` of the offending field and a
+				`message` describing the violation.
+				"""
+		},
+	]
+
+	how_it_works: {
+		supported_keywords: {
+			title: "Supported Keywords"
+			body: """
+				The following JSON Schema keywords are supported: `type`, `enum`, `const`, `required`, `properties`,
+				`additionalProperties`, `items`, `minItems`, `maxItems`, `minLength`, `maxLength`, `pattern`,
+				`minimum`, `maximum`, `exclusiveMinimum`, `exclusiveMaximum`, `allOf`, `anyOf` and `not`. Annotations
+				such as `title`, `description` and `format` are accepted but don't affect validation.
+
+				Schemas using any other keyword, such as `$ref`, are rejected when Vector loads the configuration,
+				rather than accepting every event.
+				"""
+		}
+	}
+}
@@ -41,8 +41,13 @@ configuration: {
 			common:      false
 			description: """
 				Configuration options for an [enrichment table](\(urls.enrichment_tables_concept)) to be used in a
-				[`remap`](\(urls.vector_remap_transform)) transform. Currently, only [CSV](\(urls.csv)) files are
-				supported.
+				[`remap`](\(urls.vector_remap_transform)) transform. Enrichment tables can be loaded from
+				[CSV](\(urls.csv)) files, or from [MaxMind](\(urls.maxmind)) databases. Tables are reloaded in the
+				background when their file is modified, and swapped in once their indexes are rebuilt, without
+				reloading the configuration. If the modified file can't be loaded, the previously loaded data keeps
+				being used. Data that changes too frequently to be reloaded can be searched live in
+				[Redis](\(urls.redis)), with the rows found being cached for a while, and datasets too large to be
+				held in memory can be searched in a [SQLite](\(urls.sqlite)) database.
 
 				For the lookup in the enrichment tables to be as performant as possible, the data is indexed according
 				to the fields that are used in the search. Note that indices can only be created for fields for which an
@@ -53,9 +58,20 @@ configuration: {
 				"""
 			required:    false
 			type: object: options: {
-				file: {
+				type: {
+					description: "The type of the enrichment table."
 					required:    true
-					description: "Configuration options for the file that provides the enrichment table."
+					type: string: enum: {
+						file:   "A table loaded from a file."
+						geoip:  "A table looking up the IP addresses of a [MaxMind](\(urls.maxmind)) database."
+						redis:  "A table whose rows are searched in [Redis](\(urls.redis)) by their key."
+						sqlite: "A table whose rows are searched in a [SQLite](\(urls.sqlite)) database. Only available in builds with the `enrichment-tables-sqlite` feature."
+					}
+				}
+				file: {
+					required:      true
+					relevant_when: "type = `file`"
+					description:   "Configuration options for the file that provides the enrichment table."
 					type: object: options: {
 						path: {
 							description: """
@@ -133,9 +149,216 @@ configuration: {
 						}
 					}
 				}
+				path: {
+					description: """
+						For `geoip` tables, the path of the [MaxMind GeoIP2](\(urls.maxmind_geoip2)) or [GeoLite2](\(urls.maxmind_geolite2_city))
+						database. [City](\(urls.maxmind_geoip2_city)), [ISP](\(urls.maxmind_geoip2_isp)) and
+						[ASN](\(urls.maxmind_geolite2_asn)) databases are supported.
+
+						The table is searched with an exact match of the `ip` field. Looking up an address of a
+						City database finds the `city_name`, `continent_code`, `country_code`, `country_name`,
+						`region_code`, `region_name`, `postal_code`, `metro_code`, `timezone`, `latitude` and
+						`longitude` fields, while ISP and ASN databases find the `autonomous_system_number`,
+						`autonomous_system_organization`, `isp` and `organization` fields. Fields that are unknown
+						for the address are null.
+
+						For `sqlite` tables, the path of the SQLite database file. The database is searched live, and
+						opened again when the file is modified. Searches read the file on the thread of the transform
+						searching the table, with a connection per CPU, so the database should be on a local disk.
+						"""
+					required:      true
+					relevant_when: "type = `geoip` or `sqlite`"
+					type: string: {
+						examples: ["/path/to/GeoLite2-City.mmdb", "/path/to/GeoLite2-ASN.mmdb", "/path/to/data.db"]
+					}
+				}
+				locale: {
+					description: """
+						The locale of the names found in City databases. Valid locales are: "de", "en", "es",
+						"fr", "ja", "pt-BR", "ru", and "zh-CN".
+						"""
+					required:      false
+					common:        false
+					relevant_when: "type = `geoip`"
+					type: string: {
+						default: "en"
+						examples: ["de", "ja"]
+					}
+				}
+				url: {
+					description:   "The URL of the Redis server."
+					required:      true
+					relevant_when: "type = `redis`"
+					type: string: {
+						examples: ["redis://127.0.0.1:6379/0"]
+					}
+				}
+				key_prefix: {
+					description: """
+						A prefix added to the searched key to find the Redis key of a row. The table is searched
+						with an exact match of the `key` field, so searching `{ "key": "42" }` with the `user:`
+						prefix finds the row stored at `user:42`.
+						"""
+					required:      false
+					common:        true
+					relevant_when: "type = `redis`"
+					type: string: {
+						default: ""
+						examples: ["user:"]
+					}
+				}
+				data_type: {
+					description:   "How rows are stored in Redis."
+					required:      false
+					common:        true
+					relevant_when: "type = `redis`"
+					type: string: {
+						default: "hash"
+						enum: {
+							hash: "Rows are hashes, whose fields are the fields of the row."
+							json: "Rows are strings holding a JSON object."
+						}
+					}
+				}
+				cache_ttl_secs: {
+					description: """
+						How long the rows found in Redis are cached. Keys without a row are cached too, so that
+						searching them doesn't query Redis again. Rows cached for longer are still found while
+						they're fetched again in the background.
+						"""
+					required:      false
+					common:        true
+					relevant_when: "type = `redis`"
+					type: uint: {
+						default: 60
+						unit:    "seconds"
+					}
+				}
+				cache_max_entries: {
+					description:   "The maximum number of keys cached, beyond which the least recently searched keys are evicted."
+					required:      false
+					common:        false
+					relevant_when: "type = `redis`"
+					type: uint: {
+						default: 10_000
+						unit:    null
+					}
+				}
+				timeout_ms: {
+					description: """
+						The timeout for connecting to Redis and for each query. Rows are fetched in the
+						background, so searching never waits for Redis: searching a key that isn't cached yet
+						fails until its row is fetched.
+						"""
+					required:      false
+					common:        false
+					relevant_when: "type = `redis`"
+					type: uint: {
+						default: 100
+						unit:    "milliseconds"
+					}
+				}
+				table: {
+					description: """
+						The table, or view, holding the rows of the enrichment table. Its columns are the fields
+						of the rows, and searches are translated into queries of the table, so only the rows
+						found are read into memory. Case insensitive searches use SQLite's `NOCASE` collation,
+						which only folds ASCII characters, so that non-ASCII letters only match in the same case.
+						Date range searches expect dates stored as
+						ISO 8601 text. Exactly one of `table` and `query` must be set.
+						"""
+					required:      false
+					common:        true
+					relevant_when: "type = `sqlite`"
+					type: string: {
+						default: null
+						examples: ["users"]
+					}
+				}
+				query: {
+					description: """
+						A `SELECT` statement whose results are the rows of the enrichment table, used instead of
+						a `table`. Searches filter its results, which SQLite can still satisfy with the indexes of
+						the queried tables.
+						"""
+					required:      false
+					common:        false
+					relevant_when: "type = `sqlite`"
+					type: string: {
+						default: null
+						examples: ["SELECT id, name, team FROM users WHERE active"]
+					}
+				}
+				create_indexes: {
+					description: """
+						Whether to create an index in the database for the fields each search matches exactly,
+						so that multi-gigabyte tables don't need to be scanned. This requires write access to the
+						database, and creating the index of a large table delays the start of Vector the first
+						time. Without it, the searched columns should be indexed beforehand.
+						"""
+					required:      false
+					common:        false
+					relevant_when: "type = `sqlite`"
+					type: bool: default: false
+				}
+			}
+		}
+
+		disk_buffer_max_size: {
+			common: false
+			description: """
+				The maximum number of bytes taken by the disk buffers of all the sinks together, so that their
+				sizes can't over-commit the volume of the data directory. Each sink with disk buffers is
+				allocated a share of it proportional to its `disk_buffer_weight`, split evenly between its disk
+				buffers, and disk buffers with a `buffer.max_size` of their own are capped to their share. Once
+				a disk buffer fills its share, its `buffer.when_full` applies: `block` applies back pressure and
+				`drop_newest` drops new data. As a disk buffer takes at least 128 MiB, smaller shares are
+				rejected. The shares are fixed when the configuration is loaded: the space a sink leaves unused
+				isn't lent to the others, and a full disk buffer doesn't take space from the others.
+				"""
+			required: false
+			type: uint: {
+				default: null
+				examples: [10_737_418_240]
+				unit: "bytes"
+			}
+		}
+
+		egress_bytes_per_sec: {
+			common: false
+			description: """
+				The maximum number of bytes per second sent over HTTP by all the sinks together, for instance
+				on metered links. Requests are delayed once the bytes sent run more than a second ahead of the
+				rate. Each sink can also be limited on its own, with its `egress_bytes_per_sec` option. Only
+				the request bodies are counted, as they are sent, and sinks that don't send their events over
+				HTTP aren't limited, which Vector warns about when it builds them. Unlike the other global
+				options, this one can be changed by reloading the configuration.
+				"""
+			required: false
+			type: uint: {
+				default: null
+				examples: [1_000_000]
+				unit: "bytes"
 			}
 		}
 
+		fuse_transforms: {
+			common: false
+			description: """
+				Fuses chains of [`remap`](\(urls.vector_remap_transform)) transforms, where each transform is the only
+				consumer of the previous one and doesn't reroute dropped events, into a single component when the
+				configuration is loaded. Events then run through all the programs of the chain without crossing a
+				channel between each transform.
+
+				Each fused transform keeps reporting its own received and sent events and errors. The fused component
+				takes the ID of the last transform of the chain, so the other transforms can no longer be tapped, and
+				the received events of the last transform count the events entering the chain. Transforms aren't fused
+				when running unit tests.
+				"""
+			required: false
+			type: bool: default: false
+		}
+
 		log_schema: {
 			common: false
 			description: """
@@ -13,7 +13,8 @@ remap: errors: "630": {
 
 	resolution: """
 		Make the expression passed to the function infallible, potentially by aborting on error using `!`, coalescing
-		the error using `??`, or via some other method.
+		the error using `??`, or via some other method. Alternatively, propagate the error to the function call using
+		`?`, which makes the function call fallible instead.
 		"""
 
 	examples: [
@@ -0,0 +1,30 @@
+package metadata
+
+remap: errors: "631": {
+	title: "Unnecessary error propagation"
+	description: """
+		You've propagated the error of a function argument using `?`, but the argument is infallible.
+		"""
+
+	rationale: """
+		The `?` operator makes a function call fail when its argument fails. An infallible argument never fails, so
+		propagating its error has no effect and would incorrectly convey that the function call might fail.
+		"""
+
+	resolution: """
+		Remove the `?` after the argument.
+		"""
+
+	examples: [
+		{
+			"title": "\(title)"
+			source: #"""
+				upcase("foo"?)
+				"""#
+			diff: #"""
+				-	upcase("foo"?)
+				+	upcase("foo")
+				"""#
+		},
+	]
+}
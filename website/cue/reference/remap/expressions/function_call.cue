@@ -93,6 +93,20 @@ remap: expressions: function_call: {
 							See the [errors reference](\(urls.vrl_errors_reference)) for more info.
 							"""
 					}
+					error_propagation: {
+						title:       "Error propagation"
+						description: """
+							Function arguments must be infallible. Instead of handling the error of a fallible argument
+							beforehand, a trailing `?` propagates it to the function call, which then fails along with
+							the argument:
+
+							```coffee
+							upcase(to_string(.message)?) ?? "default"
+							```
+
+							The function call becomes fallible, and its error must be handled like any other.
+							"""
+					}
 				}
 			}
 			closure: {
@@ -76,5 +76,14 @@ remap: functions: get_enrichment_table_record: {
 				"""#
 			return: {"id": 1, "firstname": "Bob", "surname": "Smith"}
 		},
+		{
+			title: "GeoIP lookup"
+			source: #"""
+				get_enrichment_table_record!("geoip",
+				  { "ip": "2.125.160.216" },
+				  select: ["city_name", "country_code"])
+				"""#
+			return: {"city_name": "Boxford", "country_code": "GB"}
+		},
 	]
 }
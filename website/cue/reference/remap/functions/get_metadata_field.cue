@@ -24,13 +24,18 @@ remap: functions: get_metadata_field: {
 
 					This exists if the `store_hec_token` setting is true in the `splunk_hec` source.
 					"""
+				http_headers: """
+					The request headers, as an object keyed by the lowercase header names.
+
+					This exists if the `metadata_headers` setting is set in the `http` source.
+					"""
 			}
 			type: ["string"]
 		},
 	]
 	internal_failure_reasons: [
 	]
-	return: types: ["string"]
+	return: types: ["string", "object"]
 
 	examples: [
 		{
@@ -0,0 +1,41 @@
+package metadata
+
+remap: functions: type: {
+	category: "Type"
+	description: """
+		Returns the name of the type of a `value`.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: #"The value to get the type of."#
+			required:    true
+			type: ["any"]
+		},
+	]
+	internal_failure_reasons: []
+	return: {
+		types: ["string"]
+		rules: [
+			#"Returns one of `string`, `integer`, `float`, `boolean`, `timestamp`, `regex`, `object`, `array` or `null`."#,
+		]
+	}
+
+	examples: [
+		{
+			title: "String"
+			source: """
+				type("a string")
+				"""
+			return: "string"
+		},
+		{
+			title: "Object"
+			source: """
+				type({"foo": [1, 2]})
+				"""
+			return: "object"
+		},
+	]
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: aws_sns: {
+	name:     "AWS Simple Notification Service"
+	thing:    "an \(name) topic"
+	url:      urls.aws_sns
+	versions: null
+
+	description: "[Amazon Simple Notification Service (SNS)](\(urls.aws_sns)) is a fully managed messaging service for both application-to-application (A2A) and application-to-person (A2P) communication."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: cassandra: {
+	name:     "Apache Cassandra"
+	thing:    "an \(name) or ScyllaDB cluster"
+	url:      urls.cassandra
+	versions: ">= 3.0"
+
+	description: "[Apache Cassandra](\(urls.cassandra)) is an open-source, distributed, wide-column database built for high write throughput across data centers. [ScyllaDB](\(urls.scylladb)) is a compatible reimplementation of it, speaking the same CQL protocol."
+}
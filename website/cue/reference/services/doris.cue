@@ -0,0 +1,10 @@
+package metadata
+
+services: doris: {
+	name:     "Apache Doris"
+	thing:    "an \(name) or StarRocks table"
+	url:      urls.doris
+	versions: null
+
+	description: "[Apache Doris](\(urls.doris)) and its fork [StarRocks](\(urls.starrocks)) are real-time analytical databases based on an MPP architecture, which load data in transactions through the frontends and backends of the cluster."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: gcp_bigquery: {
+	name:     "GCP BigQuery"
+	thing:    "a \(name) table"
+	url:      urls.gcp_bigquery
+	versions: null
+
+	description: "[Google BigQuery](\(urls.gcp_bigquery)) is a fully-managed, serverless data warehouse on Google Cloud Platform that enables scalable analysis over petabytes of data with SQL."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: google_workspace: {
+	name:     "Google Workspace"
+	thing:    "a \(name) domain"
+	url:      urls.google_workspace
+	versions: null
+
+	description: "[Google Workspace](\(urls.google_workspace)) is Google's suite of productivity and collaboration applications, whose activity is reported by the Reports API of the Admin SDK."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: jaeger: {
+	name:     "Jaeger"
+	thing:    "a \(name) collector"
+	url:      urls.jaeger
+	versions: ">= 1.8"
+
+	description: "[Jaeger](\(urls.jaeger)) is an open-source, end-to-end distributed tracing system, whose collector receives spans over gRPC or as Thrift batches over HTTP."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: microsoft_365: {
+	name:     "Microsoft 365"
+	thing:    "a \(name) tenant"
+	url:      urls.microsoft_365
+	versions: null
+
+	description: "[Microsoft 365](\(urls.microsoft_365)) is Microsoft's suite of productivity applications, whose audit logs are published through the Office 365 Management Activity API."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: netflow: {
+	name:     "NetFlow/IPFIX exporter"
+	thing:    "a \(name)"
+	url:      urls.ipfix
+	versions: null
+
+	description: "NetFlow and [IPFIX](\(urls.ipfix)) exporters, such as routers, switches, firewalls and probes, report the flows of the traffic going through them to collectors."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: okta: {
+	name:     "Okta"
+	thing:    "an \(name) organization"
+	url:      urls.okta
+	versions: null
+
+	description: "[Okta](\(urls.okta)) is an identity and access management service, recording the activity of its organizations in its System Log."
+}
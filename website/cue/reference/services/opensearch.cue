@@ -0,0 +1,10 @@
+package metadata
+
+services: opensearch: {
+	name:     "OpenSearch"
+	thing:    "an \(name) cluster"
+	url:      urls.opensearch
+	versions: null
+
+	description: "[OpenSearch](\(urls.opensearch)) is a community-driven search and analytics suite forked from Elasticsearch 7.10. It is available as the Amazon OpenSearch Service, with managed domains and serverless collections, and is commonly used to store and analyze log data."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: opentelemetry: {
+	name:     "OpenTelemetry"
+	thing:    "an \(name) collector or SDK"
+	url:      urls.opentelemetry
+	versions: null
+
+	description: "[OpenTelemetry](\(urls.opentelemetry)) is a collection of APIs, SDKs and tools to instrument, generate, collect and export telemetry data, which it sends with the OpenTelemetry protocol (OTLP)."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: postgresql: {
+	name:     "PostgreSQL"
+	thing:    "a \(name) database"
+	url:      urls.postgresql
+	versions: null
+
+	description: "[PostgreSQL](\(urls.postgresql)) is an open-source object-relational database system, which extensions such as [TimescaleDB](\(urls.timescaledb)) turn into a time-series database, making it a simple storage for logs in small self-hosted analytics setups."
+}
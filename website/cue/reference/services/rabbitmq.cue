@@ -0,0 +1,10 @@
+package metadata
+
+services: rabbitmq: {
+	name:     "RabbitMQ"
+	thing:    "a \(name) server"
+	url:      urls.rabbitmq
+	versions: null
+
+	description: "[RabbitMQ](\(urls.rabbitmq)) is an open source message broker implementing the AMQP 0.9.1 protocol, which routes the messages published to its exchanges to queues its consumers read from."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: snmp: {
+	name:     "SNMP agent"
+	thing:    "an \(name)"
+	url:      urls.snmp
+	versions: null
+
+	description: "[SNMP](\(urls.snmp)) agents, such as the ones of routers, switches, printers and servers, expose their state as objects described by MIB modules, and send traps as it changes."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: zipkin: {
+	name:     "Zipkin"
+	thing:    "a \(name) server"
+	url:      urls.zipkin
+	versions: null
+
+	description: "[Zipkin](\(urls.zipkin)) is an open-source distributed tracing system, whose v2 JSON API is also accepted by other tracing backends such as Grafana Tempo."
+}
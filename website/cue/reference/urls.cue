@@ -5,6 +5,7 @@ urls: {
 	affine_type_system:                                       "\(wikipedia)/wiki/Substructural_type_system#Affine_type_systems"
 	adaptive_request_concurrency_post:                        "/blog/adaptive-request-concurrency/"
 	amazon_linux:                                             "https://aws.amazon.com/amazon-linux-ami/"
+	amqp_protocol:                                            "https://www.rabbitmq.com/amqp-0-9-1-reference.html"
 	ansi_escape_codes:                                        "\(wikipedia)/wiki/ANSI_escape_code"
 	apache:                                                   "https://httpd.apache.org"
 	apache_common:                                            "\(apache)/docs/current/logs.html#common"
@@ -20,6 +21,8 @@ urls: {
 	aws_athena:                                               "https://aws.amazon.com/athena/"
 	aws_athena_console:                                       "https://console.aws.amazon.com/athena/home"
 	aws_canonical_user_id:                                    "\(aws_docs)/general/latest/gr/acct-identifiers.html#FindingCanonicalId"
+	aws_cloudtrail_digest_files:                              "\(aws_docs)/awscloudtrail/latest/userguide/cloudtrail-log-file-validation-digest-file-structure.html"
+	aws_cloudtrail_log_files:                                 "\(aws_docs)/awscloudtrail/latest/userguide/cloudtrail-log-file-examples.html"
 	aws_cloudwatch:                                           "https://aws.amazon.com/cloudwatch/"
 	aws_cloudwatch_logs:                                      "\(aws_docs)/AmazonCloudWatch/latest/logs/WhatIsCloudWatchLogs.html"
 	aws_cloudwatch_logs_api:                                  "\(aws_docs)/AmazonCloudWatchLogs/latest/APIReference/Welcome.html"
@@ -50,17 +53,20 @@ urls: {
 	aws_iam:                                                  "\(aws_docs)/IAM/latest/UserGuide/introduction.html"
 	aws_iam_role:                                             "\(aws_docs)/IAM/latest/UserGuide/id_roles.html"
 	aws_imds_v1_security_problems:                            "https://aws.amazon.com/blogs/security/defense-in-depth-open-firewalls-reverse-proxies-ssrf-vulnerabilities-ec2-instance-metadata-service/"
+	aws_kinesis_aggregation_library:                          "https://github.com/awslabs/kinesis-aggregation"
 	aws_kinesis_firehose:                                     "https://aws.amazon.com/kinesis/data-firehose/"
 	aws_kinesis_firehose_http_protocol:                       "\(aws_docs)/firehose/latest/dev/create-destination.html#create-destination-http"
 	aws_firehose_http_request_spec:                           "\(aws_docs)/firehose/latest/dev/httpdeliveryrequestresponse.html"
 	aws_kinesis_firehose_api:                                 "\(aws_docs)/firehose/latest/APIReference/API_PutRecordBatch.html"
 	aws_kinesis_firehose_service_limits:                      "\(aws_docs)/firehose/latest/dev/limits.html"
 	aws_kinesis_firehose_http_setup:                          "https://aws.amazon.com/blogs/big-data/stream-data-to-an-http-endpoint-with-amazon-kinesis-data-firehose/"
+	aws_kinesis_kpl_aggregation:                              "\(aws_docs)/streams/latest/dev/kinesis-kpl-concepts.html"
 	aws_kinesis_partition_key:                                "\(aws_docs)/kinesis/latest/APIReference/API_PutRecordsRequestEntry.html#Streams-Type-PutRecordsRequestEntry-PartitionKey"
 	aws_kinesis_streams:                                      "https://aws.amazon.com/kinesis/data-streams/"
 	aws_kinesis_streams_api:                                  "\(aws_docs)/kinesis/latest/APIReference/API_PutRecords.html"
 	aws_kinesis_streams_service_limits:                       "\(aws_docs)/streams/latest/dev/service-sizes-and-limits.html"
 	aws_kinesis_split_shards:                                 "\(aws_docs)/streams/latest/dev/kinesis-using-sdk-java-resharding-split.html"
+	aws_opensearch_serverless:                                "\(aws_docs)/opensearch-service/latest/developerguide/serverless.html"
 	aws_regions:                                              "\(aws_docs)/AmazonRDS/latest/UserGuide/Concepts.RegionsAndAvailabilityZones.html"
 	aws_s3:                                                   "https://aws.amazon.com/s3/"
 	aws_s3_acl:                                               "\(aws_docs)/AmazonS3/latest/dev/acl-overview.html"
@@ -70,19 +76,32 @@ urls: {
 	aws_s3_endpoints:                                         "\(aws_docs)/general/latest/gr/rande.html#s3_endpoint"
 	aws_s3_grantee:                                           "\(aws_docs)/AmazonS3/latest/dev/acl-overview.html#specifying-grantee"
 	aws_s3_metadata:                                          "\(aws_docs)/AmazonS3/latest/dev/UsingMetadata.html#object-metadata"
+	aws_s3_object_lock:                                       "\(aws_docs)/AmazonS3/latest/userguide/object-lock.html"
 	aws_s3_regions:                                           "\(aws_docs)/general/latest/gr/rande.html#s3_region"
 	aws_s3_server_access_logs:                                "\(aws_docs)/AmazonS3/latest/dev/ServerLogs.html"
 	aws_s3_service_limits:                                    "\(aws_docs)/streams/latest/dev/service-sizes-and-limits.html"
 	aws_s3_sse:                                               "\(aws_docs)/AmazonS3/latest/dev/UsingServerSideEncryption.html"
 	aws_s3_storage_classes:                                   "https://aws.amazon.com/s3/storage-classes/"
 	aws_s3_tags:                                              "\(aws_docs)/AmazonS3/latest/user-guide/add-object-tags.html"
+	aws_sns:                                                  "https://aws.amazon.com/sns/"
+	aws_sns_api:                                              "\(aws_docs)/sns/latest/api/welcome.html"
+	aws_sns_fifo:                                             "\(aws_docs)/sns/latest/dg/sns-fifo-topics.html"
+	aws_sns_message_attributes:                               "\(aws_docs)/sns/latest/dg/sns-message-attributes.html"
+	aws_sns_publish_batch:                                    "\(aws_docs)/sns/latest/api/API_PublishBatch.html"
 	aws_sqs:                                                  "https://aws.amazon.com/sqs/"
 	aws_sqs_api:                                              "\(aws_docs)/AWSSimpleQueueService/latest/APIReference/Welcome.html"
 	aws_sqs_create:                                           "\(aws_docs)/AWSSimpleQueueService/latest/SQSDeveloperGuide/sqs-configure-create-queue.html"
+	aws_sqs_extended_client_library:                          "https://github.com/awslabs/amazon-sqs-java-extended-client-lib"
+	aws_sqs_fifo:                                             "\(aws_docs)/AWSSimpleQueueService/latest/SQSDeveloperGuide/FIFO-queues.html"
+	aws_sqs_message_attributes:                               "\(aws_docs)/AWSSimpleQueueService/latest/SQSDeveloperGuide/sqs-message-metadata.html#sqs-message-attributes"
 	aws_sqs_message_deduplication_id:                         "\(aws_docs)/AWSSimpleQueueService/latest/SQSDeveloperGuide/using-messagededuplicationid-property.html"
+	aws_sqs_send_message_batch:                               "\(aws_docs)/AWSSimpleQueueService/latest/APIReference/API_SendMessageBatch.html"
 	aws_vpc_flow_logs:                                        "\(aws_docs)/vpc/latest/userguide/flow-logs.html"
 	azure_blob:                                               "https://azure.microsoft.com/en-us/services/storage/blobs/"
 	azure_blob_endpoints:                                     "https://docs.microsoft.com/en-us/rest/api/storageservices/blob-service-rest-api"
+	azure_event_hubs:                                         "https://azure.microsoft.com/en-us/services/event-hubs/"
+	azure_event_hubs_amqp:                                    "https://docs.microsoft.com/en-us/azure/event-hubs/event-hubs-amqp-troubleshoot"
+	azure_identity_default_credential:                        "https://docs.microsoft.com/en-us/azure/developer/intro/passwordless-overview"
 	azure_monitor:                                            "https://azure.microsoft.com/en-us/services/monitor/"
 	azure_monitor_logs_endpoints:                             "https://docs.microsoft.com/en-us/rest/api/monitor/"
 	base64:                                                   "\(wikipedia)/wiki/Base64"
@@ -90,15 +109,19 @@ urls: {
 	base64_standard:                                          "https://tools.ietf.org/html/rfc4648#section-4"
 	base64_url_safe:                                          "\(wikipedia)/wiki/Base64#URL_applications"
 	basic_auth:                                               "\(wikipedia)/wiki/Basic_access_authentication"
+	bearer_auth:                                              "https://datatracker.ietf.org/doc/html/rfc6750"
 	big_query_streaming:                                      "https://cloud.google.com/bigquery/streaming-data-into-bigquery"
 	bind_dnstap:                                              "https://kb.isc.org/docs/aa-01342"
 	b_tree_map:                                               "https://doc.rust-lang.org/std/collections/struct.BTreeMap.html"
 	cargo_audit:                                              "\(github)/RustSec/cargo-audit"
+	cassandra:                                                "https://cassandra.apache.org/"
+	cassandra_batch:                                          "https://cassandra.apache.org/doc/latest/cassandra/cql/dml.html#batch_statement"
 	centos:                                                   "https://www.centos.org/"
 	chrono_time_formats:                                      "https://docs.rs/chrono/latest/chrono/format/strftime/index.html#specifiers"
 	cgroups_limit_resources:                                  "https://the.binbashtheory.com/control-resources-cgroups/"
 	clickhouse:                                               "https://clickhouse.yandex/"
 	clickhouse_http:                                          "https://clickhouse.yandex/docs/en/interfaces/http/"
+	clickhouse_row_binary:                                    "https://clickhouse.com/docs/en/interfaces/formats/#rowbinary"
 	cloudsmith:                                               "https://cloudsmith.io/~timber/repos/vector/packages/"
 	cloudsmith_apt:                                           "https://cloudsmith.io/~timber/repos/vector/setup/#formats-deb"
 	cloudsmith_yum:                                           "https://cloudsmith.io/~timber/repos/vector/setup/#formats-rpm"
@@ -157,17 +180,27 @@ urls: {
 	docker_setup:                                             "\(docker_docs)/get-docker/"
 	dockerfile:                                               "\(vector_repo)/blob/master/Dockerfile"
 	dogstatsd:                                                "\(datadog_docs)/developers/dogstatsd/?tab=hostagent"
+	doris:                                                    "https://doris.apache.org/"
+	doris_stream_load:                                        "https://doris.apache.org/docs/data-operate/import/import-way/stream-load-manual"
 	dot_format:                                               "https://graphviz.org/doc/info/lang.html"
 	dpkg:                                                     "https://wiki.debian.org/dpkg"
 	dry_code:                                                 "\(wikipedia)/wiki/Don%27t_repeat_yourself"
 	cidr:                                                     "\(wikipedia)/wiki/Classless_Inter-Domain_Routing"
+	ebpf:                                                     "https://ebpf.io/"
+	ebpf_core:                                                "https://nakryiko.com/posts/bpf-portability-and-co-re/"
 	elastic_beats:                                            "https://www.elastic.co/beats/"
 	elasticsearch:                                            "https://www.elastic.co/products/elasticsearch"
 	elasticsearch_bulk:                                       "https://www.elastic.co/guide/en/elasticsearch/reference/current/docs-bulk.html"
+	elasticsearch_component_templates:                        "https://www.elastic.co/guide/en/elasticsearch/reference/current/indices-component-template.html"
 	elasticsearch_data_streams:                               "https://www.elastic.co/guide/en/elasticsearch/reference/current/data-streams.html"
 	elasticsearch_id_field:                                   "https://www.elastic.co/guide/en/elasticsearch/reference/current/mapping-id-field.html"
 	elasticsearch_id_performance:                             "https://www.elastic.co/guide/en/elasticsearch/reference/master/tune-for-indexing-speed.html#_use_auto_generated_ids"
 	elasticsearch_ignore_malformed:                           "https://www.elastic.co/guide/en/elasticsearch/reference/current/ignore-malformed.html"
+	elasticsearch_ilm:                                        "https://www.elastic.co/guide/en/elasticsearch/reference/current/index-lifecycle-management.html"
+	elasticsearch_index_templates:                            "https://www.elastic.co/guide/en/elasticsearch/reference/current/index-templates.html"
+	elasticsearch_removal_of_types:                           "https://www.elastic.co/guide/en/elasticsearch/reference/7.17/removal-of-types.html"
+	elasticsearch_routing_field:                              "https://www.elastic.co/guide/en/elasticsearch/reference/current/mapping-routing-field.html"
+	elasticsearch_upserts:                                    "https://www.elastic.co/guide/en/elasticsearch/reference/current/docs-update.html#doc_as_upsert"
 	encoding_charset_labels:                                  "https://encoding.spec.whatwg.org/#concept-encoding-get"
 	encoding_standard:                                        "https://encoding.spec.whatwg.org/"
 	endler_dev:                                               "https://endler.dev/"
@@ -192,6 +225,9 @@ urls: {
 	gcp_authentication_api_key:                               "\(gcp)/docs/authentication/api-keys"
 	gcp_authentication_server_to_server:                      "\(gcp)/docs/authentication/production"
 	gcp_authentication_service_account:                       "\(gcp)/docs/authentication/production#obtaining_and_providing_service_account_credentials_manually"
+	gcp_bigquery:                                             "\(gcp)/bigquery/"
+	gcp_bigquery_storage_write_api:                           "\(gcp)/bigquery/docs/write-api"
+	gcp_bigquery_write_api_types:                             "\(gcp)/bigquery/docs/write-api#data_type_conversions"
 	gcp_cloud_storage:                                        "\(gcp)/storage"
 	gcp_folders:                                              "\(gcp)/resource-manager/docs/creating-managing-folders"
 	gcp_pubsub:                                               "\(gcp)/pubsub/"
@@ -216,12 +252,16 @@ urls: {
 	github_sign_commits:                                      "https://help.github.com/en/github/authenticating-to-github/signing-commits"
 	globbing:                                                 "\(wikipedia)/wiki/Glob_(programming)"
 	glog:                                                     "\(github)/google/glog"
+	google_workspace:                                         "https://workspace.google.com/"
+	google_workspace_delegation:                              "https://developers.google.com/admin-sdk/reports/v1/guides/delegation"
+	google_workspace_reports:                                 "https://developers.google.com/admin-sdk/reports/reference/rest/v1/activities/list"
 	graphql:                                                  "https://graphql.org"
 	graphql_playground:                                       "\(github)/graphql/graphql-playground"
 	graphviz:                                                 "https://graphviz.org/"
 	grok:                                                     "https://grokdebug.herokuapp.com/"
 	grok_debugger:                                            "https://grokdebug.herokuapp.com/"
 	grok_patterns:                                            "\(github)/daschl/grok/tree/master/patterns"
+	grpc:                                                     "https://grpc.io/"
 	gzip:                                                     "https://www.gzip.org/"
 	haproxy:                                                  "https://www.haproxy.org/"
 	helm:                                                     "https://helm.sh/"
@@ -256,12 +296,17 @@ urls: {
 	ip_ntoa:                                                  "https://linux.die.net/man/3/inet_ntoa"
 	ip_ntop:                                                  "https://linux.die.net/man/3/inet_ntop"
 	ip_pton:                                                  "https://linux.die.net/man/3/inet_pton"
+	ipfix:                                                    "https://datatracker.ietf.org/doc/html/rfc7011"
+	ipfix_information_elements:                               "https://www.iana.org/assignments/ipfix/ipfix.xhtml"
 	iso_8601:                                                 "\(wikipedia)/wiki/ISO_8601"
 	iso3166_2:                                                "\(wikipedia)/wiki/ISO_3166-2"
 	issue_1694:                                               "\(vector_repo)/issues/1694"
+	jaeger:                                                   "https://www.jaegertracing.io/"
+	jaeger_collector:                                         "https://www.jaegertracing.io/docs/latest/apis/#span-reporting-apis"
 	journalctl:                                               "https://www.freedesktop.org/software/systemd/man/journalctl.html"
 	journald:                                                 "https://www.freedesktop.org/software/systemd/man/systemd-journald.service.html"
 	json:                                                     "\(wikipedia)/wiki/JSON"
+	json_schema:                                              "https://json-schema.org"
 	json_types:                                               "\(wikipedia)/wiki/JSON#Data_types_and_syntax"
 	jsonnet:                                                  "https://jsonnet.org/"
 	kafka:                                                    "https://kafka.apache.org/"
@@ -277,6 +322,8 @@ urls: {
 	kubernetes_authorization:                                 "\(kubernetes)/docs/reference/access-authn-authz/authorization/"
 	kubernetes_daemonset:                                     "\(kubernetes)/docs/concepts/workloads/controllers/daemonset/"
 	kubernetes_example_daemonset:                             "\(vector_repo)/blob/master/config/kubernetes/vector-daemonset.yaml"
+	kubernetes_field_selector:                                "\(kubernetes)/docs/concepts/overview/working-with-objects/field-selectors/"
+	kubernetes_label_selector:                                "\(kubernetes)/docs/concepts/overview/working-with-objects/labels/#label-selectors"
 	kubernetes_limit_resources:                               "\(kubernetes)/docs/tasks/configure-pod-container/assign-cpu-resource/"
 	kubernetes_logging_architecture:                          "\(kubernetes)/docs/concepts/cluster-administration/logging/"
 	kubernetes_rbac:                                          "\(kubernetes)/docs/reference/access-authn-authz/rbac/"
@@ -295,6 +342,7 @@ urls: {
 	logstash_protocol:                                        "https://github.com/elastic/logstash-forwarder/blob/master/PROTOCOL.md"
 	loki:                                                     "https://grafana.com/oss/loki/"
 	loki_multi_tenancy:                                       "\(github)/grafana/loki/blob/master/docs/operations/multi-tenancy.md"
+	loki_structured_metadata:                                 "https://grafana.com/docs/loki/latest/get-started/labels/structured-metadata/"
 	log_event_source:                                         "\(vector_repo)/blob/master/src/event/"
 	logplex:                                                  "https://devcenter.heroku.com/articles/logplex"
 	logplex_protocol:                                         "\(github)/heroku/logplex/blob/master/doc/README.http_drains.md"
@@ -331,15 +379,25 @@ urls: {
 	memory_safety:                                            "\(wikipedia)/wiki/Memory_safety"
 	memory_safety_bugs:                                       "https://thenewstack.io/microsoft-rust-is-the-industrys-best-chance-at-safe-systems-programming/"
 	metric_event_source:                                      "\(vector_repo)/blob/master/src/event/metric.rs"
+	microsoft_365:                                            "https://www.microsoft.com/microsoft-365"
+	microsoft_365_management_activity:                        "https://docs.microsoft.com/en-us/office/office-365-management-api/office-365-management-activity-api-reference"
 	mlua:                                                     "\(github)/khvzak/mlua"
 	mongodb:                                                  "https://www.mongodb.com"
+	mongodb_change_streams:                                   "https://www.mongodb.com/docs/manual/changeStreams/"
 	mongodb_command_server_status:                            "https://docs.mongodb.com/manual/reference/command/serverStatus/"
 	mongodb_connection_string_uri_format:                     "https://docs.mongodb.com/manual/reference/connection-string/"
+	mqtt:                                                     "https://mqtt.org/"
+	mqtt_qos:                                                 "https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718099"
+	mqtt_retained:                                            "https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718038"
+	mqtt_spec:                                                "https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html"
+	mqtt_wildcards:                                           "https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718106"
 	musl_builder_docker_image:                                "\(vector_repo)/blob/master/scripts/ci-docker-images/builder-x86_64-unknown-linux-musl/Dockerfile"
 	native_proto_schema:                                      "\(vector_repo)/blob/master/lib/vector-core/proto/event.proto"
 	native_json_schema:                                       "\(vector_repo)/blob/master/lib/codecs/tests/data/native_encoding/schema.cue"
 	nats:                                                     "https://nats.io/"
 	nats_rs:                                                  "\(github)/nats-io/nats.rs"
+	netflow_v5:                                               "https://www.cisco.com/c/en/us/td/docs/net_mgmt/netflow_collection_engine/3-6/user/guide/format.html#wp1006108"
+	netflow_v9:                                               "https://datatracker.ietf.org/doc/html/rfc3954"
 	new_bug_report:                                           "\(vector_repo)/issues/new?labels=type%3A+bug"
 	new_feature_request:                                      "\(vector_repo)/issues/new?labels=type%3A+new+feature"
 	new_relic:                                                "https://newrelic.com/"
@@ -357,8 +415,16 @@ urls: {
 	nix:                                                      "https://nixos.org/nix/"
 	nixos:                                                    "https://nixos.org/"
 	nixpkgs_9682:                                             "\(github)/NixOS/nixpkgs/issues/9682"
+	okta:                                                     "https://www.okta.com/"
+	okta_api_token:                                           "https://developer.okta.com/docs/guides/create-an-api-token/"
+	okta_system_log:                                          "https://developer.okta.com/docs/reference/api/system-log/"
+	opensearch:                                               "https://opensearch.org/"
+	opensearch_bulk:                                          "https://opensearch.org/docs/latest/api-reference/document-apis/bulk/"
 	openssl:                                                  "https://www.openssl.org/"
+	opentelemetry:                                            "https://opentelemetry.io/"
+	opentelemetry_protocol:                                   "https://opentelemetry.io/docs/reference/specification/protocol/otlp/"
 	order_of_ops:                                             "\(wikipedia)/wiki/Order_of_operations"
+	parquet:                                                  "https://parquet.apache.org/"
 	papertrail:                                               "https://www.papertrail.com/"
 	papertrail_syslog:                                        "https://help.papertrailapp.com/kb/how-it-works/http-api/#submitting-log-messages"
 	perl_windows:                                             "https://www.perl.org/get.html#win32"
@@ -372,9 +438,13 @@ urls: {
 	percent_encoding_component:                               "https://url.spec.whatwg.org/#component-percent-encode-set"
 	percent_encoding_www_form_urlencoded:                     "https://url.spec.whatwg.org/#application-x-www-form-urlencoded-percent-encode-set"
 	posix_acls:                                               "https://www.usenix.org/legacy/publications/library/proceedings/usenix03/tech/freenix03/full_papers/gruenbacher/gruenbacher_html/main.html"
+	pprof:                                                    "https://github.com/google/pprof"
 	postgresql:                                               "https://www.postgresql.org/"
+	postgresql_copy:                                          "https://www.postgresql.org/docs/current/sql-copy.html"
 	postgresql_csvlog:                                        "https://www.postgresql.org/docs/current/runtime-config-logging.html#RUNTIME-CONFIG-LOGGING-CSVLOG"
+	postgresql_logical_replication:                           "https://www.postgresql.org/docs/current/logical-replication.html"
 	postgresql_matching:                                      "https://www.postgresql.org/docs/current/functions-matching.html#FUNCTIONS-POSIX-REGEXP"
+	postgresql_upsert:                                        "https://www.postgresql.org/docs/current/sql-insert.html#SQL-ON-CONFLICT"
 	procfs:                                                   "https://en.wikipedia.org/wiki/Procfs#:~:text=The%20proc%20filesystem%20(procfs)%20is,in%20the%20kernel%20than%20traditional"
 	prometheus:                                               "https://prometheus.io/"
 	prometheus_client:                                        "https://prometheus.io/docs/instrumenting/clientlibs/"
@@ -384,15 +454,27 @@ urls: {
 	prometheus_high_cardinality:                              "https://prometheus.io/docs/practices/naming/#labels"
 	prometheus_histogram:                                     "https://prometheus.io/docs/concepts/metric_types/#histogram"
 	prometheus_histograms_guide:                              "https://prometheus.io/docs/practices/histograms/"
+	prometheus_http_sd:                                       "https://prometheus.io/docs/prometheus/latest/http_sd/"
+	prometheus_kubernetes_sd:                                 "https://prometheus.io/docs/prometheus/latest/configuration/configuration/#kubernetes_sd_config"
 	prometheus_summary:                                       "https://prometheus.io/docs/concepts/metric_types/#summary"
 	prometheus_text_based_exposition_format:                  "\(github)/prometheus/docs/blob/master/content/docs/instrumenting/exposition_formats.md#text-based-format"
 	prometheus_metric_naming:                                 "https://prometheus.io/docs/practices/naming/#metric-names"
+	prometheus_relabel_config:                                "https://prometheus.io/docs/prometheus/latest/configuration/configuration/#relabel_config"
 	prometheus_remote_integrations:                           "https://prometheus.io/docs/operating/integrations/#remote-endpoints-and-storage"
 	prometheus_remote_write:                                  "https://prometheus.io/docs/prometheus/latest/configuration/configuration/#remote_write"
 	prometheus_remote_write_protocol:                         "https://docs.google.com/document/d/1LPhVRSFkGNSuU1fBd81ulhsCPR4hkSZyyBj1SZ8fWOM/edit#heading=h.n0d0vphea3fe"
+	prometheus_remote_write_spec_v2:                          "https://prometheus.io/docs/specs/prw/remote_write_spec_2_0/"
 	protobuf:                                                 "https://developers.google.com/protocol-buffers"
+	protobuf_json_mapping:                                    "https://developers.google.com/protocol-buffers/docs/proto3#json"
 	pulsar:                                                   "https://pulsar.apache.org/"
 	pulsar_protocol:                                          "https://pulsar.apache.org/docs/en/develop-binary-protocol/"
+	questdb:                                                  "https://questdb.io/"
+	questdb_ilp:                                              "https://questdb.io/docs/reference/api/ilp/overview/"
+	questdb_ilp_auth:                                         "https://questdb.io/docs/reference/api/ilp/authenticate/"
+	rabbitmq:                                                 "https://www.rabbitmq.com/"
+	rabbitmq_confirms:                                        "https://www.rabbitmq.com/confirms.html#publisher-confirms"
+	rabbitmq_dlx:                                             "https://www.rabbitmq.com/dlx.html"
+	rabbitmq_prefetch:                                        "https://www.rabbitmq.com/consumer-prefetch.html"
 	raspbian:                                                 "https://www.raspbian.org/"
 	rdkafka:                                                  "\(github)/edenhill/librdkafka"
 	regex:                                                    "\(wikipedia)/wiki/Regular_expression"
@@ -427,6 +509,9 @@ urls: {
 	rustup:                                                   "https://rustup.rs"
 	redis:                                                    "https://redis.io"
 	redis_rs:                                                 "https://github.com/mitsuhiko/redis-rs"
+	schema_registry:                                          "https://docs.confluent.io/platform/current/schema-registry/index.html"
+	schema_registry_wire_format:                              "https://docs.confluent.io/platform/current/schema-registry/serdes-develop/index.html#wire-format"
+	scylladb:                                                 "https://www.scylladb.com/"
 	sematext:                                                 "https://sematext.com"
 	sematext_create_logs_app:                                 "https://apps.sematext.com/ui/integrations"
 	sematext_es:                                              "https://sematext.com/docs/logs/index-events-via-elasticsearch-api/"
@@ -440,6 +525,8 @@ urls: {
 	signal:                                                   "\(wikipedia)/wiki/Signal_(IPC)"
 	snake_case:                                               "\(wikipedia)/wiki/Snake_case"
 	snappy:                                                   "https://google.github.io/snappy/"
+	snmp:                                                     "https://datatracker.ietf.org/doc/html/rfc3416"
+	snmp_usm:                                                 "https://datatracker.ietf.org/doc/html/rfc3414"
 	socket:                                                   "\(wikipedia)/wiki/Network_socket"
 	splunk:                                                   "https://www.splunk.com"
 	splunk_hec:                                               "https://dev.splunk.com/enterprise/docs/dataapps/httpeventcollector/"
@@ -451,7 +538,10 @@ urls: {
 	splunk_hec_raw_endpoint:                                  "https://docs.splunk.com/Documentation/Splunk/8.0.0/RESTREF/RESTinput#services.2Fcollector.2Fraw"
 	splunk_hec_setup:                                         "https://docs.splunk.com/Documentation/Splunk/latest/Data/UsetheHTTPEventCollector"
 	specs_instrumentation:                                    "\(vector_repo)/blob/master/docs/specs/instrumentation.md)"
+	sqlite:                                                   "https://www.sqlite.org"
 	standard_streams:                                         "\(wikipedia)/wiki/Standard_streams"
+	starrocks:                                                "https://www.starrocks.io/"
+	starrocks_stream_load:                                    "https://docs.starrocks.io/en-us/latest/loading/StreamLoad"
 	statsd:                                                   "\(github)/statsd/statsd"
 	statsd_multi:                                             "\(github)/statsd/statsd/blob/master/docs/metric_types.md#multi-metric-packets"
 	statsd_set:                                               "\(github)/statsd/statsd/blob/master/docs/metric_types.md#sets"
@@ -466,6 +556,7 @@ urls: {
 	syslog:                                                   "\(wikipedia)/wiki/Syslog"
 	syslog_3164:                                              "https://tools.ietf.org/html/rfc3164"
 	syslog_5424:                                              "https://tools.ietf.org/html/rfc5424"
+	syslog_5425:                                              "https://tools.ietf.org/html/rfc5425"
 	syslog_6587:                                              "https://tools.ietf.org/html/rfc6587"
 	syslog_facility:                                          "\(wikipedia)/wiki/Syslog#Facility"
 	syslog_levels:                                            "\(wikipedia)/wiki/Syslog#Severity_level"
@@ -475,6 +566,7 @@ urls: {
 	tcp:                                                      "\(wikipedia)/wiki/Transmission_Control_Protocol"
 	team:                                                     "/community#team"
 	timber:                                                   "https://timber.io"
+	timescaledb:                                              "https://www.timescale.com/"
 	toml:                                                     "\(github)/toml-lang/toml"
 	toml_array:                                               "\(github)/toml-lang/toml#array"
 	toml_table:                                               "\(github)/toml-lang/toml#table"
@@ -518,6 +610,7 @@ urls: {
 	vector_download_nightly:                                  "/releases/nightly/download/"
 	vector_encoding_config_improve_error_message:             "\(vector_repo)/issues/12162"
 	vector_enriching_transforms:                              "/components/?functions%5B%5D=enrich"
+	vector_end_to_end_acknowledgements:                       "/docs/about/under-the-hood/architecture/end-to-end-acknowledgements/"
 	vector_file_source:                                       "/docs/reference/configuration/sources/file/"
 	vector_exec_source:                                       "/docs/reference/configuration/sources/exec"
 	vector_file_source:                                       "/docs/reference/configuration/sources/file/"
@@ -541,8 +634,10 @@ urls: {
 	vector_log_data_types:                                    "/docs/about/under-the-hood/architecture/data-model/log/#types"
 	vector_lua_rfc:                                           "\(vector_repo)/blob/master/rfcs/2020-03-06-1999-api-extensions-for-lua-transform.md"
 	vector_log_schema:                                        "/docs/reference/configuration/global-options/#log_schema"
+	vector_log_to_metric_transform:                           "/docs/reference/configuration/transforms/log_to_metric/"
 	vector_lua_transform:                                     "/docs/reference/configuration/transforms/lua/"
 	vector_metric:                                            "/docs/about/under-the-hood/architecture/data-model/metric"
+	vector_metric_to_log_transform:                           "/docs/reference/configuration/transforms/metric_to_log/"
 	vector_monitoring:                                        "/docs/administration/monitoring"
 	vector_msi_source_files:                                  "\(vector_repo)/tree/master/distribution/msi"
 	vector_new_relic_sink:                                    "/docs/reference/configuration/sinks/new_relic/"
@@ -617,17 +712,23 @@ urls: {
 	vrl_safety:                                               "\(vrl_reference)#safety"
 	vrl_type_safety:                                          "\(vrl_reference)#type-safety"
 	vote_feature:                                             "\(vector_repo)/issues?q=is%3Aissue+is%3Aopen+sort%3Areactions-%2B1-desc+label%3A%22Type%3A+New+Feature%22"
+	w3c_trace_context:                                        "https://www.w3.org/TR/trace-context/"
 	wasm:                                                     "https://webassembly.org/"
 	wasm_languages:                                           "\(github)/appcypher/awesome-wasm-langs"
 	websocket:                                                "\(wikipedia)/wiki/WebSocket"
 	wikipedia:                                                "https://en.wikipedia.org"
 	windows:                                                  "https://www.microsoft.com/en-us/windows"
+	windows_event_log:                                        "https://docs.microsoft.com/en-us/windows/win32/wes/windows-event-log"
+	windows_event_log_queries:                                "https://docs.microsoft.com/en-us/windows/win32/wes/consuming-events#xpath-10-limitations"
 	windows_installer:                                        "\(wikipedia)/wiki/Windows_Installer"
+	windows_performance_counters:                             "https://docs.microsoft.com/en-us/windows/win32/perfctrs/about-performance-counters"
 	windows_service:                                          "https://docs.microsoft.com/en-us/powershell/module/microsoft.powershell.management/new-service"
 	woothee:                                                  "https://github.com/woothee/woothee"
 	yaml:                                                     "https://yaml.org/"
 	ytt:                                                      "https://carvel.dev/ytt/"
 	yum:                                                      "\(wikipedia)/wiki/Yum_(software)"
+	zipkin:                                                   "https://zipkin.io/"
+	zipkin_api:                                               "https://zipkin.io/zipkin-api/"
 	zlib:                                                     "https://www.zlib.net"
 	zstd:                                                     "https://zstd.net"
 }